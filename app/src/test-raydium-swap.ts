@@ -124,7 +124,7 @@ async function main() {
 
   try {
     const swapTx = await program.methods
-      .executeTrade(new anchor.BN(1000000)) // 1 USDT
+      .executeTrade(new anchor.BN(1000000), false, null) // 1 USDT, no keeper nonce
       .accounts({
         userAuthority: provider.wallet.publicKey,
         traderConfig: traderConfigPDA,
@@ -0,0 +1,238 @@
+//! Replays a recorded Pyth price series through the checks `execute_trade`
+//! runs on-chain (cooldown, drawdown halt, price threshold or condition
+//! program, trade fee and slippage floor) using the shared `vectai_math`
+//! crate.
+
+use std::fmt;
+
+use vectai_math::condition::{self, ConditionError, EvalContext};
+use vectai_math::{
+    bps_of, drawdown_bps, dynamic_slippage_bps, minimum_amount_out, threshold_met, value_in_source_units, MathError,
+};
+
+/// Minimum seconds between executions, as enforced by `execute_trade`
+pub const TRADE_COOLDOWN: i64 = 60;
+
+/// Highest slippage `execute_trade` allows, fixed or dynamic
+pub const MAX_SLIPPAGE_BPS: u64 = 1_000;
+
+/// One recorded oracle update
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct PriceTick {
+    pub publish_time: i64,
+    pub price: i64,
+    pub conf: u64,
+    pub expo: i32,
+}
+
+/// Parse `publish_time,price,conf,expo` rows, as exported from Pyth
+/// Benchmarks. Blank lines, `#` comments and a header row are skipped.
+pub fn parse_price_csv(input: &str) -> Result<Vec<PriceTick>, String> {
+    let mut ticks = Vec::new();
+    for (index, line) in input.lines().enumerate() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') || line.starts_with("publish_time") {
+            continue;
+        }
+        let fields: Vec<&str> = line.split(',').map(str::trim).collect();
+        if fields.len() != 4 {
+            return Err(format!("line {}: expected 4 fields, found {}", index + 1, fields.len()));
+        }
+        let error = |name: &str| format!("line {}: invalid {}", index + 1, name);
+        ticks.push(PriceTick {
+            publish_time: fields[0].parse().map_err(|_| error("publish_time"))?,
+            price: fields[1].parse().map_err(|_| error("price"))?,
+            conf: fields[2].parse().map_err(|_| error("conf"))?,
+            expo: fields[3].parse().map_err(|_| error("expo"))?,
+        });
+    }
+    ticks.sort_by_key(|t| t.publish_time);
+    Ok(ticks)
+}
+
+/// Strategy parameters as stored in `TraderConfig`, plus the starting
+/// balance and protocol fee being simulated
+#[derive(Clone, Debug)]
+pub struct StrategyParams {
+    pub price_threshold: i64,
+    pub condition: Vec<u8>, // Replaces price_threshold when non-empty
+    pub swap_amount: u64,
+    pub slippage_tolerance: u64, // Basis points; the base of dynamic slippage
+    pub conf_factor_bps: i64, // Dynamic slippage factor on conf/price; 0 = fixed
+    pub max_drawdown_bps: u16, // 0 = no limit
+    pub trade_fee_bps: u64,
+    pub source_is_usdc: bool,
+    pub starting_balance: u64, // Source-token base units
+}
+
+/// A simulated execution. Fills are taken at the slippage floor, the worst
+/// price the on-chain minimum output allows.
+#[derive(Clone, Copy, Debug)]
+pub struct Trade {
+    pub publish_time: i64,
+    pub price: i64,
+    pub amount_in: u64,
+    pub trade_fee: u64,
+    pub expected_out: u64,
+    pub minimum_out: u64,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum SimulationError {
+    Math(MathError),
+    Condition(ConditionError),
+}
+
+impl From<MathError> for SimulationError {
+    fn from(error: MathError) -> Self {
+        SimulationError::Math(error)
+    }
+}
+
+impl From<ConditionError> for SimulationError {
+    fn from(error: ConditionError) -> Self {
+        SimulationError::Condition(error)
+    }
+}
+
+#[derive(Debug, Default)]
+pub struct Report {
+    pub ticks: usize,
+    pub trades: Vec<Trade>,
+    pub skipped_cooldown: usize,
+    pub skipped_trigger: usize,
+    pub skipped_balance: usize,
+    pub halted_at: Option<i64>,
+    pub fees_paid: u64,
+    pub source_balance: u64,
+    pub destination_balance: u64,
+    pub final_equity: u64, // Source units at the last price
+    pub max_drawdown_bps: u16,
+}
+
+impl fmt::Display for Report {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "ticks replayed:       {}", self.ticks)?;
+        writeln!(f, "trades executed:      {}", self.trades.len())?;
+        writeln!(f, "skipped (cooldown):   {}", self.skipped_cooldown)?;
+        writeln!(f, "skipped (trigger):    {}", self.skipped_trigger)?;
+        writeln!(f, "skipped (balance):    {}", self.skipped_balance)?;
+        match self.halted_at {
+            Some(time) => writeln!(f, "drawdown halt at:     {}", time)?,
+            None => writeln!(f, "drawdown halt at:     never")?,
+        }
+        writeln!(f, "trade fees paid:      {}", self.fees_paid)?;
+        writeln!(f, "source balance:       {}", self.source_balance)?;
+        writeln!(f, "destination balance:  {}", self.destination_balance)?;
+        writeln!(f, "final equity:         {}", self.final_equity)?;
+        write!(f, "max drawdown:         {} bps", self.max_drawdown_bps)
+    }
+}
+
+/// Run `params` over `ticks` in order
+pub fn simulate(params: &StrategyParams, ticks: &[PriceTick]) -> Result<Report, SimulationError> {
+    let mut report = Report {
+        ticks: ticks.len(),
+        source_balance: params.starting_balance,
+        ..Report::default()
+    };
+    let mut last_swap_time = 0i64;
+    let mut peak_equity = 0u64;
+
+    for tick in ticks {
+        if report.halted_at.is_some() {
+            break;
+        }
+        if tick.publish_time - last_swap_time < TRADE_COOLDOWN {
+            report.skipped_cooldown += 1;
+            continue;
+        }
+
+        // Drawdown is marked before the threshold check, as on-chain
+        let equity = report
+            .source_balance
+            .checked_add(value_in_source_units(
+                report.destination_balance,
+                params.source_is_usdc,
+                tick.price,
+                tick.expo,
+            )?)
+            .ok_or(MathError::Overflow)?;
+        let drawdown = drawdown_bps(peak_equity, equity);
+        peak_equity = peak_equity.max(equity);
+        report.max_drawdown_bps = report.max_drawdown_bps.max(drawdown);
+        if params.max_drawdown_bps > 0 && drawdown > params.max_drawdown_bps {
+            report.halted_at = Some(tick.publish_time);
+            break;
+        }
+
+        let triggered = if params.condition.is_empty() {
+            threshold_met(tick.price, params.price_threshold)
+        } else {
+            condition::evaluate(
+                &params.condition,
+                &EvalContext {
+                    price: tick.price,
+                    now: tick.publish_time,
+                    last_execution: last_swap_time,
+                },
+            )?
+        };
+        if !triggered {
+            report.skipped_trigger += 1;
+            continue;
+        }
+        if report.source_balance < params.swap_amount {
+            report.skipped_balance += 1;
+            continue;
+        }
+
+        let trade_fee = bps_of(params.swap_amount, params.trade_fee_bps);
+        let amount_in = params.swap_amount - trade_fee;
+        // Source -> destination conversion is the inverse valuation
+        let expected_out =
+            value_in_source_units(amount_in, !params.source_is_usdc, tick.price, tick.expo)?;
+        let slippage_bps = if params.conf_factor_bps == 0 {
+            params.slippage_tolerance
+        } else {
+            dynamic_slippage_bps(
+                params.slippage_tolerance,
+                params.conf_factor_bps,
+                tick.price,
+                tick.conf,
+                MAX_SLIPPAGE_BPS,
+            )?
+        };
+        let minimum_out = minimum_amount_out(expected_out, slippage_bps)?;
+
+        report.source_balance -= params.swap_amount;
+        report.destination_balance = report
+            .destination_balance
+            .checked_add(minimum_out)
+            .ok_or(MathError::Overflow)?;
+        report.fees_paid += trade_fee;
+        last_swap_time = tick.publish_time;
+        report.trades.push(Trade {
+            publish_time: tick.publish_time,
+            price: tick.price,
+            amount_in,
+            trade_fee,
+            expected_out,
+            minimum_out,
+        });
+    }
+
+    report.final_equity = match ticks.last() {
+        Some(last) => report
+            .source_balance
+            .checked_add(value_in_source_units(
+                report.destination_balance,
+                params.source_is_usdc,
+                last.price,
+                last.expo,
+            )?)
+            .ok_or(MathError::Overflow)?,
+        None => report.source_balance,
+    };
+    Ok(report)
+}
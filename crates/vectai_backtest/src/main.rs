@@ -0,0 +1,116 @@
+//! vectai_backtest <prices.csv> --threshold <price> --amount <units> --slippage-bps <bps>
+//!     [--balance <units>] [--source usdc|sol] [--max-drawdown-bps <bps>]
+//!     [--trade-fee-bps <bps>] [--conf-factor-bps <bps>] [--condition <hex>] [--trades]
+
+use std::{env, fs, process};
+
+use vectai_backtest::{parse_price_csv, simulate, StrategyParams, MAX_SLIPPAGE_BPS};
+
+const USAGE: &str = "usage: vectai_backtest <prices.csv> --threshold <price> --amount <units> \
+--slippage-bps <bps> [--balance <units>] [--source usdc|sol] [--max-drawdown-bps <bps>] \
+[--trade-fee-bps <bps>] [--conf-factor-bps <bps>] [--condition <hex>] [--trades]";
+
+fn main() {
+    if let Err(error) = run() {
+        eprintln!("error: {}", error);
+        eprintln!("{}", USAGE);
+        process::exit(1);
+    }
+}
+
+fn run() -> Result<(), String> {
+    let mut args = env::args().skip(1);
+    let path = args.next().ok_or("missing price file")?;
+
+    let mut threshold = None;
+    let mut amount = None;
+    let mut slippage = None;
+    let mut balance = None;
+    let mut source_is_usdc = true;
+    let mut max_drawdown_bps = 0u16;
+    let mut trade_fee_bps = 0u64;
+    let mut conf_factor_bps = 0i64;
+    let mut condition = Vec::new();
+    let mut print_trades = false;
+    while let Some(flag) = args.next() {
+        if flag == "--trades" {
+            print_trades = true;
+            continue;
+        }
+        let value = args.next().ok_or_else(|| format!("{} needs a value", flag))?;
+        let invalid = || format!("invalid value for {}: {}", flag, value);
+        match flag.as_str() {
+            "--threshold" => threshold = Some(value.parse::<i64>().map_err(|_| invalid())?),
+            "--amount" => amount = Some(value.parse::<u64>().map_err(|_| invalid())?),
+            "--slippage-bps" => slippage = Some(value.parse::<u64>().map_err(|_| invalid())?),
+            "--balance" => balance = Some(value.parse::<u64>().map_err(|_| invalid())?),
+            "--max-drawdown-bps" => max_drawdown_bps = value.parse().map_err(|_| invalid())?,
+            "--trade-fee-bps" => trade_fee_bps = value.parse().map_err(|_| invalid())?,
+            "--conf-factor-bps" => conf_factor_bps = value.parse::<i32>().map_err(|_| invalid())? as i64,
+            "--condition" => condition = parse_hex(&value).ok_or_else(invalid)?,
+            "--source" => {
+                source_is_usdc = match value.as_str() {
+                    "usdc" => true,
+                    "sol" => false,
+                    _ => return Err(format!("--source must be usdc or sol, got {}", value)),
+                }
+            }
+            _ => return Err(format!("unknown flag {}", flag)),
+        }
+    }
+
+    let swap_amount = amount.ok_or("--amount is required")?;
+    let params = StrategyParams {
+        price_threshold: threshold.ok_or("--threshold is required")?,
+        condition,
+        swap_amount,
+        slippage_tolerance: slippage.ok_or("--slippage-bps is required")?,
+        conf_factor_bps,
+        max_drawdown_bps,
+        trade_fee_bps,
+        source_is_usdc,
+        starting_balance: balance.unwrap_or(swap_amount),
+    };
+    if params.price_threshold <= 0 || params.swap_amount == 0 {
+        return Err("threshold and amount must be positive".to_string());
+    }
+    if !params.condition.is_empty() {
+        vectai_math::condition::validate(&params.condition)
+            .map_err(|e| format!("invalid condition: {:?}", e))?;
+    }
+    if params.slippage_tolerance > MAX_SLIPPAGE_BPS {
+        return Err(format!("slippage above the on-chain maximum of {} bps", MAX_SLIPPAGE_BPS));
+    }
+
+    let input = fs::read_to_string(&path).map_err(|e| format!("{}: {}", path, e))?;
+    let ticks = parse_price_csv(&input)?;
+    let report = simulate(&params, &ticks).map_err(|e| format!("simulation failed: {:?}", e))?;
+
+    if print_trades {
+        for trade in &report.trades {
+            println!(
+                "{} price {} in {} fee {} expected {} min out {}",
+                trade.publish_time,
+                trade.price,
+                trade.amount_in,
+                trade.trade_fee,
+                trade.expected_out,
+                trade.minimum_out
+            );
+        }
+    }
+    println!("{}", report);
+    Ok(())
+}
+
+/// Decode a condition program given as hex, e.g. from a client encoder
+fn parse_hex(hex: &str) -> Option<Vec<u8>> {
+    let hex = hex.trim_start_matches("0x");
+    if !hex.len().is_multiple_of(2) {
+        return None;
+    }
+    (0..hex.len())
+        .step_by(2)
+        .map(|i| u8::from_str_radix(hex.get(i..i + 2)?, 16).ok())
+        .collect()
+}
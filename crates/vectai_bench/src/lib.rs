@@ -0,0 +1,286 @@
+//! Compute units of the hot instructions (`execute_trade`, `swap`,
+//! `mint_to`) run under `vectai_program_test`, compared against the
+//! committed `baseline.txt`.
+//!
+//! The harness meters syscalls only, so the figures are a floor dominated
+//! by logging and CPI, which is where these handlers spend their budget.
+//! A change that adds more than [`MAX_REGRESSION_PERCENT`] fails the
+//! `baseline` test; `cargo run -p vectai_bench -- --update` accepts it.
+
+use std::{collections::BTreeMap, fmt, fs, io, path::PathBuf};
+
+use anchor_lang::{
+    solana_program::{system_program, sysvar},
+    InstructionData, ToAccountMetas,
+};
+use solana_sdk::{
+    instruction::Instruction,
+    native_token::LAMPORTS_PER_SOL,
+    pubkey::Pubkey,
+    signature::{Keypair, Signer},
+};
+use vectai_program_test::{
+    trader::{new_trader_config, trader_address},
+    ProgramTest,
+};
+use vectai_protocol::FeeConfig;
+
+/// Growth over the baseline a benchmark may show before it fails
+pub const MAX_REGRESSION_PERCENT: u64 = 10;
+
+const SWAP_AMOUNT: u64 = 1_000_000;
+const SLIPPAGE_BPS: u64 = 200;
+const POOL_RESERVE: u64 = 1_000_000_000_000;
+
+/// Compute units one benchmark consumed
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Measurement {
+    pub name: &'static str,
+    pub compute_units: u64,
+}
+
+/// Run every benchmark, in baseline order
+pub fn measure() -> Vec<Measurement> {
+    vec![
+        Measurement {
+            name: "execute_trade",
+            compute_units: execute_trade(),
+        },
+        Measurement {
+            name: "mint_to",
+            compute_units: mint_to(),
+        },
+        Measurement {
+            name: "swap",
+            compute_units: swap(),
+        },
+    ]
+}
+
+fn funded(test: &mut ProgramTest) -> Keypair {
+    let keypair = Keypair::new();
+    test.airdrop(&keypair.pubkey(), LAMPORTS_PER_SOL);
+    keypair
+}
+
+/// USDC -> WSOL through the mock AMM, no optional accounts
+fn execute_trade() -> u64 {
+    let mut test = ProgramTest::new();
+    let network = test.create_trading_network(POOL_RESERVE);
+    let authority = funded(&mut test);
+    test.set_trader(&new_trader_config(authority.pubkey(), 40_000, SWAP_AMOUNT, SLIPPAGE_BPS));
+    let (source, destination) = (Pubkey::new_unique(), Pubkey::new_unique());
+    test.create_token_account(source, &network.usdc, &authority.pubkey(), SWAP_AMOUNT);
+    test.create_token_account(destination, &network.wsol, &authority.pubkey(), 0);
+
+    let ix = Instruction {
+        program_id: vectai_trader::ID,
+        accounts: network
+            .execute_trade_accounts(&authority.pubkey(), source, destination)
+            .to_account_metas(None),
+        data: vectai_trader::instruction::ExecuteTrade { amount: SWAP_AMOUNT, dry_run: false, nonce: None }.data(),
+    };
+    test.process_instruction(ix, &[&authority]).expect("execute_trade");
+    assert_eq!(test.anchor_account::<vectai_trader::TraderConfig>(&trader_address(&authority.pubkey())).unwrap().total_swaps, 1);
+    test.compute_units_consumed()
+}
+
+/// USDC -> WSOL through `raydium_swapper` and the whitelisted pool
+fn swap() -> u64 {
+    let mut test = ProgramTest::new();
+    let protocol_status = test.set_protocol_status(false, FeeConfig::INITIAL);
+    let (usdc, wsol) = (Pubkey::new_unique(), Pubkey::new_unique());
+    let mint_authority = Pubkey::new_unique();
+    test.create_mint(usdc, &mint_authority, 6);
+    test.create_mint(wsol, &mint_authority, 9);
+    let network_config = test.set_network_config(wsol, usdc);
+    let protocol_stats = test.set_protocol_stats();
+    let pool = test.create_whitelisted_pool(&usdc, &wsol, POOL_RESERVE, POOL_RESERVE);
+    let authority = funded(&mut test);
+    let (source, destination) = (Pubkey::new_unique(), Pubkey::new_unique());
+    test.create_token_account(source, &usdc, &authority.pubkey(), SWAP_AMOUNT);
+    test.create_token_account(destination, &wsol, &authority.pubkey(), 0);
+
+    let ix = Instruction {
+        program_id: raydium_swapper::ID,
+        accounts: raydium_swapper::accounts::SwapAccounts {
+            user_authority: authority.pubkey(),
+            user_source_token: source,
+            user_destination_token: destination,
+            raydium_amm_program: pool.amm_program,
+            amm: pool.amm,
+            amm_authority: pool.amm_authority,
+            amm_open_orders: pool.amm_open_orders,
+            amm_target_orders: pool.amm_target_orders,
+            pool_coin_token_account: pool.pool_coin_token_account,
+            pool_pc_token_account: pool.pool_pc_token_account,
+            serum_program: pool.serum_program,
+            serum_market: pool.serum_market,
+            serum_bids: pool.serum_bids,
+            serum_asks: pool.serum_asks,
+            serum_event_queue: pool.serum_event_queue,
+            serum_coin_vault: pool.serum_coin_vault,
+            serum_pc_vault: pool.serum_pc_vault,
+            serum_vault_signer: pool.serum_vault_signer,
+            token_program: spl_token::ID,
+            protocol_status,
+            network_config,
+            protocol_stats,
+            stats_reporter: vectai_protocol::stats_reporter(&raydium_swapper::ID),
+            protocol_program: vectai_protocol::ID,
+            fee_account: None,
+        }
+        .to_account_metas(None),
+        data: raydium_swapper::instruction::Swap {
+            amount_in: SWAP_AMOUNT,
+            min_amount_out: SWAP_AMOUNT * (10_000 - SLIPPAGE_BPS) / 10_000,
+        }
+        .data(),
+    };
+    test.process_instruction(ix, &[&authority]).expect("swap");
+    assert_eq!(test.token_balance(&source), Some(0));
+    test.compute_units_consumed()
+}
+
+/// Mint under the supply cap with the recorded mint authority signing
+fn mint_to() -> u64 {
+    let mut test = ProgramTest::new();
+    let protocol_status = test.set_protocol_status(false, FeeConfig::INITIAL);
+    let protocol_stats = test.set_protocol_stats();
+    let authority = funded(&mut test);
+    let mint = Keypair::new();
+    let pda = |seed: &[u8]| Pubkey::find_program_address(&[seed, mint.pubkey().as_ref()], &vectai_token::ID).0;
+    let (token_info, mint_signer) = (pda(b"token-info"), pda(b"mint-authority"));
+    let payer = test.payer().pubkey();
+    let initialize = Instruction {
+        program_id: vectai_token::ID,
+        accounts: vectai_token::accounts::InitializeToken {
+            mint: mint.pubkey(),
+            token_info,
+            mint_authority: authority.pubkey(),
+            payer,
+            rent: sysvar::rent::ID,
+            token_program: spl_token::ID,
+            system_program: system_program::ID,
+        }
+        .to_account_metas(None),
+        data: vectai_token::instruction::InitializeToken {
+            max_supply: vectai_token::MAX_SUPPLY_CEILING,
+            decimals: 6,
+        }
+        .data(),
+    };
+    test.process_instruction(initialize, &[&mint, &authority]).expect("initialize_token");
+    let to = Pubkey::new_unique();
+    test.create_token_account(to, &mint.pubkey(), &authority.pubkey(), 0);
+
+    let ix = Instruction {
+        program_id: vectai_token::ID,
+        accounts: vectai_token::accounts::MintTokens {
+            mint: mint.pubkey(),
+            to,
+            token_info,
+            mint_signer,
+            mint_authority: authority.pubkey(),
+            token_program: spl_token::ID,
+            protocol_status,
+            protocol_stats,
+            stats_reporter: vectai_protocol::stats_reporter(&vectai_token::ID),
+            protocol_program: vectai_protocol::ID,
+            role_registry: None,
+        }
+        .to_account_metas(None),
+        data: vectai_token::instruction::MintTo { amount: SWAP_AMOUNT }.data(),
+    };
+    test.process_instruction(ix, &[&authority]).expect("mint_to");
+    assert_eq!(test.token_balance(&to), Some(SWAP_AMOUNT));
+    test.compute_units_consumed()
+}
+
+/// Accepted compute units per benchmark, one `name units` pair per line
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Baseline(BTreeMap<String, u64>);
+
+impl Baseline {
+    pub fn path() -> PathBuf {
+        PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("baseline.txt")
+    }
+
+    pub fn load() -> io::Result<Self> {
+        let input = fs::read_to_string(Self::path())?;
+        Self::parse(&input).map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))
+    }
+
+    /// Blank lines and `#` comments are skipped
+    pub fn parse(input: &str) -> Result<Self, String> {
+        let mut entries = BTreeMap::new();
+        for (index, line) in input.lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            let (name, units) = line
+                .split_once(char::is_whitespace)
+                .ok_or_else(|| format!("line {}: expected `name units`", index + 1))?;
+            let units = units
+                .trim()
+                .parse()
+                .map_err(|_| format!("line {}: invalid compute units", index + 1))?;
+            entries.insert(name.to_string(), units);
+        }
+        Ok(Baseline(entries))
+    }
+
+    pub fn save(measurements: &[Measurement]) -> io::Result<()> {
+        let mut output = String::from("# Syscall compute units per benchmark; `cargo run -p vectai_bench -- --update`\n");
+        for measurement in measurements {
+            output.push_str(&format!("{} {}\n", measurement.name, measurement.compute_units));
+        }
+        fs::write(Self::path(), output)
+    }
+
+    pub fn compare(&self, measurements: &[Measurement]) -> Vec<Comparison> {
+        measurements
+            .iter()
+            .map(|measurement| Comparison {
+                name: measurement.name,
+                baseline: self.0.get(measurement.name).copied(),
+                measured: measurement.compute_units,
+            })
+            .collect()
+    }
+}
+
+/// One benchmark against its baseline
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Comparison {
+    pub name: &'static str,
+    pub baseline: Option<u64>, // None for a benchmark not yet in the baseline
+    pub measured: u64,
+}
+
+impl Comparison {
+    /// Missing from the baseline, or more than `MAX_REGRESSION_PERCENT` over it
+    pub fn regressed(&self) -> bool {
+        match self.baseline {
+            Some(baseline) => self.measured as u128 * 100 > baseline as u128 * (100 + MAX_REGRESSION_PERCENT) as u128,
+            None => true,
+        }
+    }
+}
+
+impl fmt::Display for Comparison {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self.baseline {
+            Some(baseline) => {
+                let change = (self.measured as f64 - baseline as f64) * 100.0 / baseline.max(1) as f64;
+                write!(f, "{:<16} {:>8} CU  (baseline {}, {:+.1}%)", self.name, self.measured, baseline, change)?;
+            }
+            None => write!(f, "{:<16} {:>8} CU  (no baseline)", self.name, self.measured)?,
+        }
+        if self.regressed() {
+            write!(f, "  REGRESSED")?;
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,53 @@
+//! vectai_bench [--update]
+//!
+//! Prints each benchmark's compute units against `baseline.txt` and exits
+//! non-zero on a regression; `--update` writes the measurements as the new
+//! baseline instead.
+
+use std::{env, process};
+
+use vectai_bench::{measure, Baseline, MAX_REGRESSION_PERCENT};
+
+const USAGE: &str = "usage: vectai_bench [--update]";
+
+fn main() {
+    if let Err(error) = run() {
+        eprintln!("error: {}", error);
+        eprintln!("{}", USAGE);
+        process::exit(1);
+    }
+}
+
+fn run() -> Result<(), String> {
+    let mut update = false;
+    for flag in env::args().skip(1) {
+        match flag.as_str() {
+            "--update" => update = true,
+            _ => return Err(format!("unknown flag {}", flag)),
+        }
+    }
+
+    let measurements = measure();
+    if update {
+        Baseline::save(&measurements).map_err(|e| e.to_string())?;
+        for measurement in &measurements {
+            println!("{:<16} {:>8} CU", measurement.name, measurement.compute_units);
+        }
+        println!("Wrote {}", Baseline::path().display());
+        return Ok(());
+    }
+
+    let baseline = Baseline::load().map_err(|e| format!("{}: {}", Baseline::path().display(), e))?;
+    let comparisons = baseline.compare(&measurements);
+    for comparison in &comparisons {
+        println!("{}", comparison);
+    }
+    if comparisons.iter().any(|c| c.regressed()) {
+        eprintln!(
+            "compute units regressed by more than {}%; rerun with --update to accept",
+            MAX_REGRESSION_PERCENT
+        );
+        process::exit(1);
+    }
+    Ok(())
+}
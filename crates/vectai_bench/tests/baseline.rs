@@ -0,0 +1,33 @@
+//! Fails the workspace tests when a hot path's compute units outgrow the
+//! committed baseline.
+
+use vectai_bench::{measure, Baseline, Comparison, Measurement, MAX_REGRESSION_PERCENT};
+
+#[test]
+fn compute_units_within_baseline() {
+    let baseline = Baseline::load().expect("read baseline.txt");
+    let regressions: Vec<String> = baseline
+        .compare(&measure())
+        .iter()
+        .filter(|c| c.regressed())
+        .map(Comparison::to_string)
+        .collect();
+    assert!(
+        regressions.is_empty(),
+        "compute units regressed by more than {}%; run `cargo run -p vectai_bench -- --update` to accept:\n{}",
+        MAX_REGRESSION_PERCENT,
+        regressions.join("\n")
+    );
+}
+
+#[test]
+fn regression_threshold_is_exclusive() {
+    let baseline = Baseline::parse("# comment\nexecute_trade 1000\n").unwrap();
+    let regressed = |name, compute_units| baseline.compare(&[Measurement { name, compute_units }])[0].regressed();
+
+    assert!(!regressed("execute_trade", 1_100));
+    assert!(regressed("execute_trade", 1_101));
+    // A benchmark has to be accepted into the baseline before it passes
+    assert!(regressed("swap", 1));
+    assert!(Baseline::parse("execute_trade many").is_err());
+}
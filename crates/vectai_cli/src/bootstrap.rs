@@ -0,0 +1,416 @@
+use std::fmt;
+
+use anchor_lang::{system_program, InstructionData, ToAccountMetas};
+use solana_rpc_client::rpc_client::RpcClient;
+use solana_sdk::{
+    instruction::{Instruction, InstructionError},
+    native_token::LAMPORTS_PER_SOL,
+    program_pack::Pack,
+    pubkey::Pubkey,
+    signature::{Keypair, Signature},
+    signer::Signer,
+    system_instruction, sysvar,
+    transaction::TransactionError,
+};
+use spl_associated_token_account::{
+    get_associated_token_address, instruction::create_associated_token_account_idempotent,
+};
+use vectai_client::{fetch_account, ClientError, TransactionBuilder};
+use vectai_protocol::{account_bundle, NetworkConfig, ProtocolError, RaydiumPoolAccounts};
+
+use crate::{CliError, Profile};
+
+// Test USDC mirrors devnet USDC
+pub const TEST_USDC_DECIMALS: u8 = 6;
+
+pub const VECTAI_DECIMALS: u8 = 6;
+pub const VECTAI_MAX_SUPPLY: u64 = 1_000_000_000 * 10u64.pow(VECTAI_DECIMALS as u32);
+
+/// What `bootstrap-devnet` sets up; every amount is in base units
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct BootstrapParams {
+    pub sol_lamports: u64, // Balance to airdrop up to
+    pub test_usdc_amount: u64, // Minted to the keypair while it holds none
+    pub price_threshold: i64, // Trader config, at the oracle's expo -8
+    pub swap_amount: u64,
+    pub slippage_bps: u64,
+}
+
+impl Default for BootstrapParams {
+    /// 2 SOL, 1,000 test USDC, and a trader swapping 10 USDC below $145
+    /// with 1% slippage
+    fn default() -> Self {
+        BootstrapParams {
+            sol_lamports: 2 * LAMPORTS_PER_SOL,
+            test_usdc_amount: 1_000 * 10u64.pow(TEST_USDC_DECIMALS as u32),
+            price_threshold: 14_500_000_000,
+            swap_amount: 10_000_000,
+            slippage_bps: 100,
+        }
+    }
+}
+
+/// One bootstrap step and how it went
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Step {
+    pub name: &'static str,
+    pub status: StepStatus,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum StepStatus {
+    Done(String),
+    AlreadyDone(String),
+    Skipped(String), // Cannot be done from this keypair or cluster
+}
+
+impl fmt::Display for Step {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match &self.status {
+            StepStatus::Done(detail) => write!(f, "✅ {}: {}", self.name, detail),
+            StepStatus::AlreadyDone(detail) => write!(f, "✔  {}: already done, {}", self.name, detail),
+            StepStatus::Skipped(reason) => write!(f, "⏭  {}: skipped, {}", self.name, reason),
+        }
+    }
+}
+
+/// Sets up a devnet keypair to run a strategy: SOL, a test USDC mint and
+/// balance, the VECTAI token, token accounts, a trader config and the
+/// pinned pool's account bundle. Steps whose result is already on the
+/// cluster are not repeated.
+pub struct DevnetBootstrap<'a> {
+    rpc: &'a RpcClient,
+    payer: &'a Keypair,
+    params: BootstrapParams,
+}
+
+impl<'a> DevnetBootstrap<'a> {
+    pub fn new(rpc: &'a RpcClient, payer: &'a Keypair, params: BootstrapParams) -> Self {
+        DevnetBootstrap { rpc, payer, params }
+    }
+
+    /// Run every step in order, recording created mints in `profile`.
+    /// Stops at the first failing step; the mints created before it are
+    /// already recorded, so the caller should save `profile` either way.
+    pub fn run(&self, profile: &mut Profile, report: &mut dyn FnMut(&Step)) -> Result<Vec<Step>, CliError> {
+        if !profile.is_devnet() {
+            return Err(CliError::NotDevnet(profile.url.clone()));
+        }
+        let mut steps = Vec::new();
+        let mut record = |step: Step| {
+            report(&step);
+            steps.push(step);
+        };
+        record(self.airdrop()?);
+        record(self.test_usdc(profile)?);
+        record(self.vectai_token(profile)?);
+        record(self.token_accounts(profile)?);
+        record(self.trader()?);
+        record(self.register_pool()?);
+        Ok(steps)
+    }
+
+    fn airdrop(&self) -> Result<Step, CliError> {
+        let name = "airdrop";
+        let balance = self.rpc.get_balance(&self.payer.pubkey()).map_err(Box::new)?;
+        if balance >= self.params.sol_lamports {
+            return Ok(already(name, format!("{} SOL", sol(balance))));
+        }
+        let lamports = self.params.sol_lamports - balance;
+        let signature = self
+            .rpc
+            .request_airdrop(&self.payer.pubkey(), lamports)
+            .map_err(Box::new)?;
+        self.rpc.poll_for_signature(&signature).map_err(Box::new)?;
+        Ok(done(name, format!("{} SOL", sol(lamports))))
+    }
+
+    fn test_usdc(&self, profile: &mut Profile) -> Result<Step, CliError> {
+        let name = "test USDC mint";
+        if let Some(mint) = profile.test_usdc_mint {
+            if self.exists(&mint)? {
+                return Ok(already(name, mint.to_string()));
+            }
+        }
+        let mint = Keypair::new();
+        let rent = self
+            .rpc
+            .get_minimum_balance_for_rent_exemption(spl_token::state::Mint::LEN)
+            .map_err(Box::new)?;
+        let instructions = [
+            system_instruction::create_account(
+                &self.payer.pubkey(),
+                &mint.pubkey(),
+                rent,
+                spl_token::state::Mint::LEN as u64,
+                &spl_token::ID,
+            ),
+            spl_token::instruction::initialize_mint2(
+                &spl_token::ID,
+                &mint.pubkey(),
+                &self.payer.pubkey(),
+                None,
+                TEST_USDC_DECIMALS,
+            )
+            .map_err(|e| CliError::InvalidAccount(e.to_string()))?,
+        ];
+        self.send(&instructions, &[&mint])?;
+        profile.test_usdc_mint = Some(mint.pubkey());
+        Ok(done(name, mint.pubkey().to_string()))
+    }
+
+    fn vectai_token(&self, profile: &mut Profile) -> Result<Step, CliError> {
+        let name = "VECTAI token";
+        if let Some(mint) = profile.vectai_mint {
+            if self.exists(&token_info(&mint))? {
+                return Ok(already(name, mint.to_string()));
+            }
+        }
+        let mint = Keypair::new();
+        let instruction = initialize_token_instruction(&self.payer.pubkey(), &mint.pubkey());
+        self.send(&[instruction], &[&mint])?;
+        profile.vectai_mint = Some(mint.pubkey());
+        Ok(done(name, mint.pubkey().to_string()))
+    }
+
+    /// Associated token accounts for both mints and, once the protocol's
+    /// network config exists, for the pool's WSOL and USDC; then the test
+    /// USDC balance
+    fn token_accounts(&self, profile: &Profile) -> Result<Step, CliError> {
+        let name = "token accounts";
+        let authority = self.payer.pubkey();
+        let mut mints: Vec<Pubkey> = profile.test_usdc_mint.into_iter().chain(profile.vectai_mint).collect();
+        if let Some(network) = self.network_config()? {
+            mints.extend([network.wsol_mint, network.usdc_mint]);
+        }
+
+        let mut instructions = Vec::new();
+        for mint in &mints {
+            if !self.exists(&get_associated_token_address(&authority, mint))? {
+                instructions.push(create_associated_token_account_idempotent(
+                    &authority,
+                    &authority,
+                    mint,
+                    &spl_token::ID,
+                ));
+            }
+        }
+        let opened = instructions.len();
+        let mut minted = 0;
+        if let Some(usdc) = profile.test_usdc_mint {
+            let account = get_associated_token_address(&authority, &usdc);
+            if !self.exists(&account)? || self.token_balance(&account)? == 0 {
+                minted = self.params.test_usdc_amount;
+                instructions.push(
+                    spl_token::instruction::mint_to(&spl_token::ID, &usdc, &account, &authority, &[], minted)
+                        .map_err(|e| CliError::InvalidAccount(e.to_string()))?,
+                );
+            }
+        }
+
+        if instructions.is_empty() {
+            return Ok(already(name, format!("{} accounts", mints.len())));
+        }
+        self.send(&instructions, &[])?;
+        Ok(done(name, format!("{} opened, {} test USDC minted", opened, minted)))
+    }
+
+    fn trader(&self) -> Result<Step, CliError> {
+        let name = "trader config";
+        let config = trader_config(&self.payer.pubkey());
+        if self.exists(&config)? {
+            return Ok(already(name, config.to_string()));
+        }
+        let instruction = initialize_trader_instruction(&self.payer.pubkey(), &self.params);
+        self.send(&[instruction], &[])?;
+        Ok(done(name, config.to_string()))
+    }
+
+    /// Register the swapper's pinned pool as the WSOL/USDC account bundle.
+    /// Only the protocol admin may; any other keypair skips the step.
+    fn register_pool(&self) -> Result<Step, CliError> {
+        let name = "pool account bundle";
+        let Some(network) = self.network_config()? else {
+            return Ok(skipped(name, "the protocol's network config is not set".to_string()));
+        };
+        let bundle = account_bundle(&network.wsol_mint, &network.usdc_mint);
+        if self.exists(&bundle)? {
+            return Ok(already(name, bundle.to_string()));
+        }
+        if !self.exists(&raydium_swapper::pool_config::AMM_ID)? {
+            return Ok(skipped(name, "the pinned pool is not on this cluster".to_string()));
+        }
+
+        let pool = pinned_pool_accounts(network.raydium_amm_program);
+        let instruction = register_pool_instruction(&self.payer.pubkey(), &network, pool);
+        match self.send(&[instruction], &[]) {
+            Ok(_) => Ok(done(name, bundle.to_string())),
+            Err(CliError::Client(ClientError::Simulation { err, .. })) if is_unauthorized_admin(&err) => {
+                Ok(skipped(name, "registering needs the protocol admin".to_string()))
+            }
+            Err(error) => Err(error),
+        }
+    }
+
+    fn network_config(&self) -> Result<Option<NetworkConfig>, CliError> {
+        let address = protocol_pda(b"network-config");
+        if !self.exists(&address)? {
+            return Ok(None);
+        }
+        Ok(Some(fetch_account(self.rpc, &address)?))
+    }
+
+    fn exists(&self, address: &Pubkey) -> Result<bool, CliError> {
+        let account = self
+            .rpc
+            .get_account_with_commitment(address, self.rpc.commitment())
+            .map_err(Box::new)?;
+        Ok(account.value.is_some())
+    }
+
+    fn token_balance(&self, account: &Pubkey) -> Result<u64, CliError> {
+        let data = self.rpc.get_account_data(account).map_err(Box::new)?;
+        let account = spl_token::state::Account::unpack(&data)
+            .map_err(|_| CliError::InvalidAccount(format!("{} is not a token account", account)))?;
+        Ok(account.amount)
+    }
+
+    fn send(&self, instructions: &[Instruction], signers: &[&Keypair]) -> Result<Signature, CliError> {
+        let mut all_signers: Vec<&dyn Signer> = vec![self.payer];
+        all_signers.extend(signers.iter().map(|signer| *signer as &dyn Signer));
+        let transaction = TransactionBuilder::new(self.payer.pubkey())
+            .instructions(instructions.iter().cloned())
+            .build(self.rpc, &all_signers)?;
+        Ok(self.rpc.send_and_confirm_transaction(&transaction).map_err(Box::new)?)
+    }
+}
+
+/// `vectai_token::initialize_token` for a new `mint`, with `authority`
+/// as mint authority and payer
+pub fn initialize_token_instruction(authority: &Pubkey, mint: &Pubkey) -> Instruction {
+    Instruction {
+        program_id: vectai_token::ID,
+        accounts: vectai_token::accounts::InitializeToken {
+            mint: *mint,
+            token_info: token_info(mint),
+            mint_authority: *authority,
+            payer: *authority,
+            rent: sysvar::rent::ID,
+            token_program: spl_token::ID,
+            system_program: system_program::ID,
+        }
+        .to_account_metas(None),
+        data: vectai_token::instruction::InitializeToken {
+            max_supply: VECTAI_MAX_SUPPLY,
+            decimals: VECTAI_DECIMALS,
+        }
+        .data(),
+    }
+}
+
+/// `vectai_trader::initialize_trader` for `authority`, without a referrer
+pub fn initialize_trader_instruction(authority: &Pubkey, params: &BootstrapParams) -> Instruction {
+    Instruction {
+        program_id: vectai_trader::ID,
+        accounts: vectai_trader::accounts::InitializeTrader {
+            trader_config: trader_config(authority),
+            referral_code: None,
+            authority: *authority,
+            system_program: system_program::ID,
+        }
+        .to_account_metas(None),
+        data: vectai_trader::instruction::InitializeTrader {
+            price_threshold: params.price_threshold,
+            swap_amount: params.swap_amount,
+            slippage_tolerance: params.slippage_bps,
+        }
+        .data(),
+    }
+}
+
+/// `vectai_protocol::initialize_account_bundle` registering `pool` for
+/// the network's WSOL/USDC pair
+pub fn register_pool_instruction(admin: &Pubkey, network: &NetworkConfig, pool: RaydiumPoolAccounts) -> Instruction {
+    Instruction {
+        program_id: vectai_protocol::ID,
+        accounts: vectai_protocol::accounts::InitializeAccountBundle {
+            bundle: account_bundle(&network.wsol_mint, &network.usdc_mint),
+            admin: *admin,
+            system_program: system_program::ID,
+        }
+        .to_account_metas(None),
+        data: vectai_protocol::instruction::InitializeAccountBundle {
+            base_mint: network.wsol_mint,
+            quote_mint: network.usdc_mint,
+            pool,
+        }
+        .data(),
+    }
+}
+
+/// The swapper's whitelisted pool and market, behind `amm_program`
+pub fn pinned_pool_accounts(amm_program: Pubkey) -> RaydiumPoolAccounts {
+    use raydium_swapper::pool_config::*;
+
+    RaydiumPoolAccounts {
+        raydium_amm_program: amm_program,
+        amm: AMM_ID,
+        amm_authority: AMM_AUTHORITY,
+        amm_open_orders: AMM_OPEN_ORDERS,
+        amm_target_orders: AMM_TARGET_ORDERS,
+        pool_coin_token_account: POOL_COIN_TOKEN_ACCOUNT,
+        pool_pc_token_account: POOL_PC_TOKEN_ACCOUNT,
+        serum_program: SERUM_PROGRAM,
+        serum_market: SERUM_MARKET,
+        serum_bids: SERUM_BIDS,
+        serum_asks: SERUM_ASKS,
+        serum_event_queue: SERUM_EVENT_QUEUE,
+        serum_coin_vault: SERUM_COIN_VAULT,
+        serum_pc_vault: SERUM_PC_VAULT,
+        serum_vault_signer: SERUM_VAULT_SIGNER,
+    }
+}
+
+/// Address of `mint`'s `vectai_token::TokenInfo`
+pub fn token_info(mint: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(&[b"token-info", mint.as_ref()], &vectai_token::ID).0
+}
+
+/// Address of `authority`'s `vectai_trader::TraderConfig`
+pub fn trader_config(authority: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(&[b"trader", authority.as_ref()], &vectai_trader::ID).0
+}
+
+fn protocol_pda(seed: &[u8]) -> Pubkey {
+    Pubkey::find_program_address(&[seed], &vectai_protocol::ID).0
+}
+
+fn is_unauthorized_admin(err: &TransactionError) -> bool {
+    let code = u32::from(ProtocolError::UnauthorizedAdmin);
+    matches!(err, TransactionError::InstructionError(_, InstructionError::Custom(c)) if *c == code)
+}
+
+fn sol(lamports: u64) -> f64 {
+    lamports as f64 / LAMPORTS_PER_SOL as f64
+}
+
+fn done(name: &'static str, detail: String) -> Step {
+    Step {
+        name,
+        status: StepStatus::Done(detail),
+    }
+}
+
+fn already(name: &'static str, detail: String) -> Step {
+    Step {
+        name,
+        status: StepStatus::AlreadyDone(detail),
+    }
+}
+
+fn skipped(name: &'static str, reason: String) -> Step {
+    Step {
+        name,
+        status: StepStatus::Skipped(reason),
+    }
+}
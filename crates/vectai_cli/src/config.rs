@@ -0,0 +1,159 @@
+use std::{
+    collections::BTreeMap,
+    env, fs,
+    path::{Path, PathBuf},
+};
+
+use serde::{Deserialize, Serialize};
+use solana_sdk::{
+    pubkey::Pubkey,
+    signature::{read_keypair_file, Keypair},
+};
+
+use crate::CliError;
+
+pub const DEVNET_URL: &str = "https://api.devnet.solana.com";
+pub const LOCALNET_URL: &str = "http://127.0.0.1:8899";
+
+/// Profile used when neither `--profile` nor the config names one
+pub const DEFAULT_PROFILE: &str = "devnet";
+
+/// Environment variable overriding the config file location
+pub const CONFIG_ENV: &str = "VECTAI_CLI_CONFIG";
+
+/// Named profiles and which one commands use by default
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CliConfig {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub default_profile: Option<String>,
+    #[serde(default)]
+    pub profiles: BTreeMap<String, Profile>,
+}
+
+impl CliConfig {
+    /// `$VECTAI_CLI_CONFIG`, else `~/.config/vectai/cli.toml`
+    pub fn default_path() -> PathBuf {
+        match env::var_os(CONFIG_ENV) {
+            Some(path) => PathBuf::from(path),
+            None => home_dir().join(".config").join("vectai").join("cli.toml"),
+        }
+    }
+
+    /// The config at `path`; empty when there is no file yet
+    pub fn load(path: &Path) -> Result<Self, CliError> {
+        if !path.exists() {
+            return Ok(CliConfig::default());
+        }
+        toml::from_str(&fs::read_to_string(path)?).map_err(|e| CliError::Config(e.to_string()))
+    }
+
+    pub fn save(&self, path: &Path) -> Result<(), CliError> {
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir)?;
+        }
+        let contents = toml::to_string_pretty(self).map_err(|e| CliError::Config(e.to_string()))?;
+        Ok(fs::write(path, contents)?)
+    }
+
+    /// Name of the profile `requested`, the config's default or
+    /// `DEFAULT_PROFILE`, in that order
+    pub fn profile_name(&self, requested: Option<&str>) -> String {
+        requested
+            .or(self.default_profile.as_deref())
+            .unwrap_or(DEFAULT_PROFILE)
+            .to_string()
+    }
+
+    /// The configured profile `name`, else the built-in one
+    pub fn profile(&self, name: &str) -> Result<Profile, CliError> {
+        self.profiles
+            .get(name)
+            .cloned()
+            .or_else(|| Profile::built_in(name))
+            .ok_or_else(|| CliError::UnknownProfile(name.to_string()))
+    }
+
+    pub fn set_profile(&mut self, name: &str, profile: Profile) {
+        self.profiles.insert(name.to_string(), profile);
+    }
+}
+
+/// One cluster and the keypair used on it
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Profile {
+    pub url: String,
+    pub keypair: PathBuf, // A leading `~` is the home directory
+    #[serde(default, skip_serializing_if = "Option::is_none", with = "pubkey_string")]
+    pub test_usdc_mint: Option<Pubkey>, // Created by `bootstrap-devnet`
+    #[serde(default, skip_serializing_if = "Option::is_none", with = "pubkey_string")]
+    pub vectai_mint: Option<Pubkey>, // Created by `bootstrap-devnet`
+}
+
+impl Profile {
+    pub fn new(url: impl Into<String>, keypair: impl Into<PathBuf>) -> Self {
+        Profile {
+            url: url.into(),
+            keypair: keypair.into(),
+            test_usdc_mint: None,
+            vectai_mint: None,
+        }
+    }
+
+    /// `devnet` and `localnet`, on the Solana CLI's default keypair
+    pub fn built_in(name: &str) -> Option<Self> {
+        let url = match name {
+            "devnet" => DEVNET_URL,
+            "localnet" => LOCALNET_URL,
+            _ => return None,
+        };
+        Some(Profile::new(url, "~/.config/solana/id.json"))
+    }
+
+    /// Keypair path with `~` expanded
+    pub fn keypair_path(&self) -> PathBuf {
+        match self.keypair.strip_prefix("~") {
+            Ok(rest) => home_dir().join(rest),
+            Err(_) => self.keypair.clone(),
+        }
+    }
+
+    pub fn read_keypair(&self) -> Result<Keypair, CliError> {
+        let path = self.keypair_path();
+        read_keypair_file(&path).map_err(|e| CliError::Keypair {
+            path,
+            reason: e.to_string(),
+        })
+    }
+
+    /// Whether the cluster hands out airdrops: devnet, testnet or a local
+    /// validator
+    pub fn is_devnet(&self) -> bool {
+        ["devnet", "testnet", "localhost", "127.0.0.1"]
+            .iter()
+            .any(|cluster| self.url.contains(cluster))
+    }
+}
+
+fn home_dir() -> PathBuf {
+    env::var_os("HOME")
+        .or_else(|| env::var_os("USERPROFILE"))
+        .map_or_else(|| PathBuf::from("."), PathBuf::from)
+}
+
+// Base58 strings rather than byte arrays in the config file
+mod pubkey_string {
+    use serde::{Deserialize, Deserializer, Serializer};
+    use solana_sdk::pubkey::Pubkey;
+
+    pub fn serialize<S: Serializer>(key: &Option<Pubkey>, serializer: S) -> Result<S::Ok, S::Error> {
+        match key {
+            Some(key) => serializer.serialize_str(&key.to_string()),
+            None => serializer.serialize_none(),
+        }
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Option<Pubkey>, D::Error> {
+        let key = String::deserialize(deserializer)?;
+        key.parse().map(Some).map_err(serde::de::Error::custom)
+    }
+}
@@ -0,0 +1,75 @@
+//! Command-line client for VECT.AI.
+//!
+//! [`CliConfig`] keeps named profiles in `~/.config/vectai/cli.toml`: the
+//! RPC URL and keypair a cluster is used with, plus the mints
+//! `bootstrap-devnet` created there. `devnet` and `localnet` profiles are
+//! built in, on the Solana CLI's default keypair.
+//!
+//! [`DevnetBootstrap`] takes a keypair from a fresh clone to a working
+//! devnet strategy in one run: it airdrops SOL, creates a test USDC mint
+//! and the VECTAI token, opens the keypair's token accounts, initializes
+//! its trader config and registers the swapper's pinned pool in the
+//! protocol's account bundle. Every step checks the cluster first, so a
+//! rerun only does what is still missing.
+
+use std::{fmt, io, path::PathBuf};
+
+use solana_rpc_client_api::client_error::Error as RpcError;
+use vectai_client::ClientError;
+
+mod bootstrap;
+pub use bootstrap::*;
+mod config;
+pub use config::*;
+
+#[derive(Debug)]
+pub enum CliError {
+    Io(io::Error),
+    Rpc(Box<RpcError>),
+    Client(ClientError),
+    /// The config file does not parse or serialize
+    Config(String),
+    /// No profile of this name is configured or built in
+    UnknownProfile(String),
+    /// The keypair file is missing or malformed
+    Keypair { path: PathBuf, reason: String },
+    /// Airdrops only exist on devnet, testnet and localnet
+    NotDevnet(String),
+    /// A cluster account does not hold what was expected of it
+    InvalidAccount(String),
+}
+
+impl fmt::Display for CliError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            CliError::Io(e) => write!(f, "io: {}", e),
+            CliError::Rpc(e) => write!(f, "rpc: {}", e),
+            CliError::Client(e) => write!(f, "{}", e),
+            CliError::Config(reason) => write!(f, "config: {}", reason),
+            CliError::UnknownProfile(name) => write!(f, "unknown profile {}", name),
+            CliError::Keypair { path, reason } => write!(f, "keypair {}: {}", path.display(), reason),
+            CliError::NotDevnet(url) => write!(f, "{} is not a devnet, testnet or local cluster", url),
+            CliError::InvalidAccount(reason) => write!(f, "invalid account: {}", reason),
+        }
+    }
+}
+
+impl std::error::Error for CliError {}
+
+impl From<io::Error> for CliError {
+    fn from(e: io::Error) -> Self {
+        CliError::Io(e)
+    }
+}
+
+impl From<Box<RpcError>> for CliError {
+    fn from(e: Box<RpcError>) -> Self {
+        CliError::Rpc(e)
+    }
+}
+
+impl From<ClientError> for CliError {
+    fn from(e: ClientError) -> Self {
+        CliError::Client(e)
+    }
+}
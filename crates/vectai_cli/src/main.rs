@@ -0,0 +1,118 @@
+//! vectai-cli [--config <path>] [--profile <name>] [--url <rpc>] [--keypair <path>] <command>
+//!
+//!   config                                  Show the config file and the selected profile
+//!   profile <name> [--default]              Save the selected URL and keypair as profile <name>
+//!   bootstrap-devnet [--sol <sol>] [--usdc <amount>] [--threshold <price>]
+//!                    [--amount <amount>] [--slippage-bps <bps>]
+//!                                           Set the profile's keypair up on devnet
+//!
+//! `--url` and `--keypair` override the selected profile for one command;
+//! `bootstrap-devnet` saves the mints it creates back to the profile.
+
+use std::{env, path::PathBuf, process, str::FromStr};
+
+use solana_sdk::{native_token::sol_to_lamports, signer::Signer};
+use vectai_cli::{BootstrapParams, CliConfig, DevnetBootstrap};
+use vectai_client::rpc_client;
+
+const USAGE: &str = "usage: vectai-cli [--config <path>] [--profile <name>] [--url <rpc>] [--keypair <path>] \
+                     <config | profile <name> [--default] | bootstrap-devnet [--sol <sol>] [--usdc <amount>] \
+                     [--threshold <price>] [--amount <amount>] [--slippage-bps <bps>]>";
+
+fn main() {
+    if let Err(error) = run() {
+        eprintln!("error: {}", error);
+        eprintln!("{}", USAGE);
+        process::exit(1);
+    }
+}
+
+fn run() -> Result<(), String> {
+    let mut args = env::args().skip(1).peekable();
+    let mut config_path = CliConfig::default_path();
+    let mut profile_name = None;
+    let mut url = None;
+    let mut keypair = None;
+    while let Some(flag) = args.next_if(|arg| arg.starts_with("--")) {
+        let value = args.next().ok_or_else(|| format!("{} needs a value", flag))?;
+        match flag.as_str() {
+            "--config" => config_path = PathBuf::from(value),
+            "--profile" => profile_name = Some(value),
+            "--url" => url = Some(value),
+            "--keypair" => keypair = Some(PathBuf::from(value)),
+            _ => return Err(format!("unknown flag {}", flag)),
+        }
+    }
+
+    let mut config = CliConfig::load(&config_path).map_err(|e| e.to_string())?;
+    let name = config.profile_name(profile_name.as_deref());
+    let mut profile = config.profile(&name).map_err(|e| e.to_string())?;
+    if let Some(url) = url {
+        profile.url = url;
+    }
+    if let Some(keypair) = keypair {
+        profile.keypair = keypair;
+    }
+
+    match args.next().as_deref() {
+        Some("config") => {
+            println!("Config {}", config_path.display());
+            println!("Profile {}: {} with {}", name, profile.url, profile.keypair.display());
+            if let Some(mint) = profile.test_usdc_mint {
+                println!("  test USDC mint {}", mint);
+            }
+            if let Some(mint) = profile.vectai_mint {
+                println!("  VECTAI mint {}", mint);
+            }
+            Ok(())
+        }
+        Some("profile") => {
+            let new_name = args.next().ok_or("profile needs a name")?;
+            let mut default = false;
+            for flag in args {
+                match flag.as_str() {
+                    "--default" => default = true,
+                    _ => return Err(format!("unknown flag {}", flag)),
+                }
+            }
+            config.set_profile(&new_name, profile);
+            if default {
+                config.default_profile = Some(new_name.clone());
+            }
+            config.save(&config_path).map_err(|e| e.to_string())?;
+            println!("Saved profile {} to {}", new_name, config_path.display());
+            Ok(())
+        }
+        Some("bootstrap-devnet") => {
+            let mut params = BootstrapParams::default();
+            while let Some(flag) = args.next() {
+                let value = args.next().ok_or_else(|| format!("{} needs a value", flag))?;
+                match flag.as_str() {
+                    "--sol" => params.sol_lamports = sol_to_lamports(parse(&flag, &value)?),
+                    "--usdc" => params.test_usdc_amount = parse(&flag, &value)?,
+                    "--threshold" => params.price_threshold = parse(&flag, &value)?,
+                    "--amount" => params.swap_amount = parse(&flag, &value)?,
+                    "--slippage-bps" => params.slippage_bps = parse(&flag, &value)?,
+                    _ => return Err(format!("unknown flag {}", flag)),
+                }
+            }
+
+            let payer = profile.read_keypair().map_err(|e| e.to_string())?;
+            let rpc = rpc_client(&profile.url);
+            println!("Bootstrapping {} on {} (profile {})", payer.pubkey(), profile.url, name);
+            let result = DevnetBootstrap::new(&rpc, &payer, params).run(&mut profile, &mut |step| println!("{}", step));
+            // Keep the mints created before any failing step
+            config.set_profile(&name, profile);
+            config.save(&config_path).map_err(|e| e.to_string())?;
+            result.map_err(|e| e.to_string())?;
+            println!("Saved profile {} to {}", name, config_path.display());
+            Ok(())
+        }
+        Some(command) => Err(format!("unknown command {}", command)),
+        None => Err("missing command".to_string()),
+    }
+}
+
+fn parse<T: FromStr>(flag: &str, value: &str) -> Result<T, String> {
+    value.parse().map_err(|_| format!("invalid value for {}: {}", flag, value))
+}
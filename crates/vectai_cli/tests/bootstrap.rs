@@ -0,0 +1,90 @@
+//! The bootstrap's instructions, run in-process.
+
+use anchor_lang::prelude::Pubkey;
+use solana_rpc_client::rpc_client::RpcClient;
+use solana_sdk::{native_token::LAMPORTS_PER_SOL, signature::Keypair, signer::Signer};
+use vectai_cli::{
+    initialize_token_instruction, initialize_trader_instruction, pinned_pool_accounts, register_pool_instruction,
+    token_info, trader_config, BootstrapParams, CliError, DevnetBootstrap, Profile, VECTAI_DECIMALS,
+    VECTAI_MAX_SUPPLY,
+};
+use vectai_program_test::{anchor_error, ProgramTest};
+use vectai_protocol::{account_bundle, NetworkConfig, ProtocolError};
+use vectai_token::TokenInfo;
+use vectai_trader::TraderConfig;
+
+fn funded(test: &mut ProgramTest) -> Keypair {
+    let keypair = Keypair::new();
+    test.airdrop(&keypair.pubkey(), 10 * LAMPORTS_PER_SOL);
+    keypair
+}
+
+#[test]
+fn initializes_the_vectai_token() {
+    let mut test = ProgramTest::new();
+    let authority = funded(&mut test);
+    let mint = Keypair::new();
+    let ix = initialize_token_instruction(&authority.pubkey(), &mint.pubkey());
+    test.process_instruction(ix, &[&authority, &mint]).unwrap();
+
+    let info: TokenInfo = test.anchor_account(&token_info(&mint.pubkey())).unwrap();
+    assert_eq!(info.mint, mint.pubkey());
+    assert_eq!(info.mint_authority, authority.pubkey());
+    assert_eq!((info.max_supply, info.decimals), (VECTAI_MAX_SUPPLY, VECTAI_DECIMALS));
+}
+
+#[test]
+fn initializes_a_trader_config() {
+    let mut test = ProgramTest::new();
+    let authority = funded(&mut test);
+    let params = BootstrapParams::default();
+    let ix = initialize_trader_instruction(&authority.pubkey(), &params);
+    test.process_instruction(ix, &[&authority]).unwrap();
+
+    let config: TraderConfig = test.anchor_account(&trader_config(&authority.pubkey())).unwrap();
+    assert_eq!(config.authority, authority.pubkey());
+    assert_eq!(config.price_threshold, params.price_threshold);
+    assert_eq!(config.swap_amount, params.swap_amount);
+    assert_eq!(config.slippage_tolerance, params.slippage_bps);
+}
+
+#[test]
+fn pool_registration_is_complete_and_admin_only() {
+    let amm_program = Pubkey::new_unique();
+    let pool = pinned_pool_accounts(amm_program);
+    assert!(pool.is_complete());
+    assert_eq!(pool.raydium_amm_program, amm_program);
+    assert_eq!(pool.amm, raydium_swapper::pool_config::AMM_ID);
+
+    // Any keypair but the protocol admin is refused, which the bootstrap
+    // reports as a skipped step
+    let mut test = ProgramTest::new();
+    let impostor = funded(&mut test);
+    let network = NetworkConfig {
+        raydium_amm_program: amm_program,
+        wsol_mint: Pubkey::new_unique(),
+        usdc_mint: Pubkey::new_unique(),
+        oracle_program: Pubkey::new_unique(),
+        treasury_program: Pubkey::new_unique(),
+        bump: 255,
+        version: 1,
+        upgrade_attestations: Vec::new(),
+    };
+    let ix = register_pool_instruction(&impostor.pubkey(), &network, pool);
+    assert_eq!(
+        test.process_instruction(ix, &[&impostor]).unwrap_err(),
+        anchor_error(ProtocolError::UnauthorizedAdmin)
+    );
+    assert!(test.account(&account_bundle(&network.wsol_mint, &network.usdc_mint)).is_none());
+}
+
+#[test]
+fn refuses_mainnet() {
+    let rpc = RpcClient::new_mock("succeeds".to_string());
+    let payer = Keypair::new();
+    let mut profile = Profile::new("https://api.mainnet-beta.solana.com", "~/.config/solana/id.json");
+    let bootstrap = DevnetBootstrap::new(&rpc, &payer, BootstrapParams::default());
+    let result = bootstrap.run(&mut profile, &mut |_| panic!("no step runs on mainnet"));
+    assert!(matches!(result, Err(CliError::NotDevnet(url)) if url == profile.url));
+    assert_eq!((profile.test_usdc_mint, profile.vectai_mint), (None, None));
+}
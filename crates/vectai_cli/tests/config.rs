@@ -0,0 +1,105 @@
+//! Config file round trips and profile resolution.
+
+use std::{env, fs, path::PathBuf};
+
+use solana_sdk::{
+    pubkey::Pubkey,
+    signature::{write_keypair_file, Keypair},
+    signer::Signer,
+};
+use vectai_cli::{CliConfig, CliError, Profile, CONFIG_ENV, DEVNET_URL, LOCALNET_URL};
+
+fn scratch_dir(name: &str) -> PathBuf {
+    let dir = env::temp_dir().join(format!("vectai-cli-{}-{}", name, std::process::id()));
+    let _ = fs::remove_dir_all(&dir);
+    dir
+}
+
+#[test]
+fn config_round_trips_through_toml() {
+    let dir = scratch_dir("round-trip");
+    let path = dir.join("nested").join("cli.toml");
+
+    let mut config = CliConfig::default();
+    let mut profile = Profile::new("https://rpc.example.com", "~/keys/devnet.json");
+    profile.test_usdc_mint = Some(Pubkey::new_unique());
+    profile.vectai_mint = Some(Pubkey::new_unique());
+    config.set_profile("team", profile.clone());
+    config.set_profile("local", Profile::new(LOCALNET_URL, "/tmp/local.json"));
+    config.default_profile = Some("team".to_string());
+    config.save(&path).unwrap();
+
+    // Mints are stored as base58 strings
+    let contents = fs::read_to_string(&path).unwrap();
+    assert!(contents.contains(&profile.vectai_mint.unwrap().to_string()));
+    assert_eq!(CliConfig::load(&path).unwrap(), config);
+    fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn missing_config_is_empty() {
+    let path = scratch_dir("missing").join("cli.toml");
+    assert_eq!(CliConfig::load(&path).unwrap(), CliConfig::default());
+}
+
+#[test]
+fn malformed_config_is_rejected() {
+    let dir = scratch_dir("malformed");
+    fs::create_dir_all(&dir).unwrap();
+    let path = dir.join("cli.toml");
+    fs::write(&path, "[profiles.devnet]\nurl = \"x\"\nkeypair = \"k\"\nvectai_mint = \"not-a-key\"\n").unwrap();
+    assert!(matches!(CliConfig::load(&path), Err(CliError::Config(_))));
+    fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn profiles_fall_back_to_built_ins() {
+    let mut config = CliConfig::default();
+    assert_eq!(config.profile_name(None), "devnet");
+    assert_eq!(config.profile("devnet").unwrap().url, DEVNET_URL);
+    assert_eq!(config.profile("localnet").unwrap().url, LOCALNET_URL);
+    assert!(matches!(config.profile("mainnet"), Err(CliError::UnknownProfile(name)) if name == "mainnet"));
+
+    // A configured profile shadows the built-in one, and the default
+    // profile yields to an explicit one
+    config.set_profile("devnet", Profile::new("https://rpc.example.com", "/keys/id.json"));
+    config.default_profile = Some("localnet".to_string());
+    assert_eq!(config.profile("devnet").unwrap().url, "https://rpc.example.com");
+    assert_eq!(config.profile_name(None), "localnet");
+    assert_eq!(config.profile_name(Some("devnet")), "devnet");
+}
+
+#[test]
+fn config_path_follows_the_environment() {
+    env::set_var(CONFIG_ENV, "/etc/vectai/cli.toml");
+    assert_eq!(CliConfig::default_path(), PathBuf::from("/etc/vectai/cli.toml"));
+    env::remove_var(CONFIG_ENV);
+    assert!(CliConfig::default_path().ends_with(".config/vectai/cli.toml"));
+}
+
+#[test]
+fn keypairs_load_from_expanded_paths() {
+    let home_relative = Profile::new(DEVNET_URL, "~/.config/solana/id.json").keypair_path();
+    assert!(!home_relative.starts_with("~"));
+    assert!(home_relative.ends_with(".config/solana/id.json"));
+
+    let dir = scratch_dir("keypair");
+    fs::create_dir_all(&dir).unwrap();
+    let path = dir.join("id.json");
+    let keypair = Keypair::new();
+    write_keypair_file(&keypair, &path).unwrap();
+    let profile = Profile::new(DEVNET_URL, &path);
+    assert_eq!(profile.read_keypair().unwrap().pubkey(), keypair.pubkey());
+
+    let missing = Profile::new(DEVNET_URL, dir.join("missing.json"));
+    assert!(matches!(missing.read_keypair(), Err(CliError::Keypair { .. })));
+    fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn only_test_clusters_are_devnet() {
+    assert!(Profile::built_in("devnet").unwrap().is_devnet());
+    assert!(Profile::built_in("localnet").unwrap().is_devnet());
+    assert!(Profile::new("https://api.testnet.solana.com", "k").is_devnet());
+    assert!(!Profile::new("https://api.mainnet-beta.solana.com", "k").is_devnet());
+}
@@ -0,0 +1,109 @@
+use anchor_lang::AccountDeserialize;
+use solana_rpc_client::rpc_client::RpcClient;
+use solana_sdk::{pubkey::Pubkey, sysvar};
+use vectai_protocol::{account_bundle, AccountBundle};
+
+use crate::{fetch_account, ClientError};
+
+/// Decode the `AccountBundle` account `key` holds
+pub fn decode_account_bundle(key: Pubkey, data: &[u8]) -> Result<AccountBundle, ClientError> {
+    AccountBundle::try_deserialize(&mut &data[..]).map_err(|_| ClientError::InvalidAccount(key))
+}
+
+/// The account bundle registered in `vectai_protocol` for
+/// `base_mint`/`quote_mint`
+pub fn fetch_account_bundle(rpc: &RpcClient, base_mint: &Pubkey, quote_mint: &Pubkey) -> Result<AccountBundle, ClientError> {
+    fetch_account(rpc, &account_bundle(base_mint, quote_mint))
+}
+
+fn protocol_pda(seed: &[u8]) -> Pubkey {
+    Pubkey::find_program_address(&[seed], &vectai_protocol::ID).0
+}
+
+/// `execute_trade` accounts for `authority`'s trader swapping `source`
+/// into `destination` through the pool in `bundle`, priced by
+/// `price_feed`, with no optional accounts
+pub fn execute_trade_accounts(
+    bundle: &AccountBundle,
+    authority: &Pubkey,
+    source: Pubkey,
+    destination: Pubkey,
+    price_feed: Pubkey,
+) -> vectai_trader::accounts::ExecuteTrade {
+    let pool = &bundle.pool;
+    vectai_trader::accounts::ExecuteTrade {
+        user_authority: *authority,
+        trader_config: Pubkey::find_program_address(&[b"trader", authority.as_ref()], &vectai_trader::ID).0,
+        user_source_token_account: source,
+        user_destination_token_account: destination,
+        raydium_amm_program: pool.raydium_amm_program,
+        amm: pool.amm,
+        amm_authority: pool.amm_authority,
+        amm_open_orders: pool.amm_open_orders,
+        amm_target_orders: pool.amm_target_orders,
+        pool_coin_token_account: pool.pool_coin_token_account,
+        pool_pc_token_account: pool.pool_pc_token_account,
+        serum_program: pool.serum_program,
+        serum_market: pool.serum_market,
+        serum_bids: pool.serum_bids,
+        serum_asks: pool.serum_asks,
+        serum_event_queue: pool.serum_event_queue,
+        serum_coin_vault_account: pool.serum_coin_vault,
+        serum_pc_vault_account: pool.serum_pc_vault,
+        serum_vault_signer: pool.serum_vault_signer,
+        vectai_oracle_program: vectai_oracle::ID,
+        price_feed,
+        points_ledger: None,
+        points_config: None,
+        trade_history: None,
+        trade_history_tree: None,
+        compression_program: None,
+        noop_program: None,
+        protocol_fee_account: None,
+        token_program: spl_token::ID,
+        protocol_status: protocol_pda(b"protocol-status"),
+        network_config: protocol_pda(b"network-config"),
+        protocol_stats: protocol_pda(b"protocol-stats"),
+        stats_reporter: vectai_protocol::stats_reporter(&vectai_trader::ID),
+        protocol_program: vectai_protocol::ID,
+        instructions: sysvar::instructions::ID,
+    }
+}
+
+/// `raydium_swapper::swap` accounts for `authority` swapping `source` into
+/// `destination` through the pool in `bundle`, with no fee account
+pub fn swap_accounts(
+    bundle: &AccountBundle,
+    authority: &Pubkey,
+    source: Pubkey,
+    destination: Pubkey,
+) -> raydium_swapper::accounts::SwapAccounts {
+    let pool = &bundle.pool;
+    raydium_swapper::accounts::SwapAccounts {
+        user_authority: *authority,
+        user_source_token: source,
+        user_destination_token: destination,
+        raydium_amm_program: pool.raydium_amm_program,
+        amm: pool.amm,
+        amm_authority: pool.amm_authority,
+        amm_open_orders: pool.amm_open_orders,
+        amm_target_orders: pool.amm_target_orders,
+        pool_coin_token_account: pool.pool_coin_token_account,
+        pool_pc_token_account: pool.pool_pc_token_account,
+        serum_program: pool.serum_program,
+        serum_market: pool.serum_market,
+        serum_bids: pool.serum_bids,
+        serum_asks: pool.serum_asks,
+        serum_event_queue: pool.serum_event_queue,
+        serum_coin_vault: pool.serum_coin_vault,
+        serum_pc_vault: pool.serum_pc_vault,
+        serum_vault_signer: pool.serum_vault_signer,
+        token_program: spl_token::ID,
+        protocol_status: protocol_pda(b"protocol-status"),
+        network_config: protocol_pda(b"network-config"),
+        protocol_stats: protocol_pda(b"protocol-stats"),
+        stats_reporter: vectai_protocol::stats_reporter(&raydium_swapper::ID),
+        protocol_program: vectai_protocol::ID,
+        fee_account: None,
+    }
+}
@@ -0,0 +1,117 @@
+use anchor_lang::Discriminator;
+use solana_sdk::{
+    compute_budget::{self, ComputeBudgetInstruction},
+    instruction::Instruction,
+    pubkey::Pubkey,
+};
+
+/// Most compute units one transaction may request
+pub const MAX_COMPUTE_UNIT_LIMIT: u32 = 1_400_000;
+
+/// Units the runtime grants an instruction when no limit is requested, and
+/// the estimate for instructions [`instruction_compute_units`] does not know
+pub const DEFAULT_INSTRUCTION_COMPUTE_UNITS: u32 = 200_000;
+
+/// Headroom added to simulated compute units by default, in basis points
+pub const DEFAULT_COMPUTE_MARGIN_BPS: u16 = 1_000;
+
+// Consumed by each `ComputeBudget` instruction itself
+const COMPUTE_BUDGET_INSTRUCTION_UNITS: u32 = 150;
+
+/// Upper estimates of the hot instructions, including their CPIs: oracle
+/// read and Raydium/Serum swap for executions, Drift for hedges
+const ESTIMATES: &[(Pubkey, [u8; 8], u32)] = &[
+    (vectai_trader::ID, vectai_trader::instruction::ExecuteTrade::DISCRIMINATOR, 150_000),
+    (vectai_trader::ID, vectai_trader::instruction::CopyTrade::DISCRIMINATOR, 150_000),
+    (vectai_trader::ID, vectai_trader::instruction::RebalanceHedge::DISCRIMINATOR, 250_000),
+    (vectai_trader::ID, vectai_trader::instruction::TipJito::DISCRIMINATOR, 15_000),
+    (raydium_swapper::ID, raydium_swapper::instruction::Swap::DISCRIMINATOR, 120_000),
+    (vectai_token::ID, vectai_token::instruction::MintTo::DISCRIMINATOR, 40_000),
+    (vectai_token::ID, vectai_token::instruction::MintEpoch::DISCRIMINATOR, 40_000),
+    (vectai_token::ID, vectai_token::instruction::BuyFromAuction::DISCRIMINATOR, 50_000),
+];
+
+/// Compute unit limit and price for one transaction
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct ComputeBudget {
+    pub unit_limit: u32,
+    pub unit_price: u64, // Micro-lamports per compute unit
+}
+
+impl ComputeBudget {
+    /// `SetComputeUnitLimit`, then `SetComputeUnitPrice` unless the price is 0
+    pub fn instructions(&self) -> Vec<Instruction> {
+        let mut instructions = vec![ComputeBudgetInstruction::set_compute_unit_limit(self.unit_limit)];
+        if self.unit_price > 0 {
+            instructions.push(ComputeBudgetInstruction::set_compute_unit_price(self.unit_price));
+        }
+        instructions
+    }
+
+    /// `instructions` behind this budget, dropping any `ComputeBudget`
+    /// instructions they already carry
+    pub fn apply(&self, instructions: &[Instruction]) -> Vec<Instruction> {
+        let mut budgeted = self.instructions();
+        budgeted.extend(
+            instructions
+                .iter()
+                .filter(|instruction| !compute_budget::check_id(&instruction.program_id))
+                .cloned(),
+        );
+        budgeted
+    }
+
+    /// Lamports paid for priority on top of the signature fees, charged on
+    /// the requested limit rather than the units consumed
+    pub fn priority_fee(&self) -> u64 {
+        (self.unit_limit as u128 * self.unit_price as u128).div_ceil(1_000_000) as u64
+    }
+}
+
+/// Estimated compute units of one instruction, without simulating it
+pub fn instruction_compute_units(instruction: &Instruction) -> u32 {
+    if compute_budget::check_id(&instruction.program_id) {
+        return COMPUTE_BUDGET_INSTRUCTION_UNITS;
+    }
+    ESTIMATES
+        .iter()
+        .find(|(program_id, discriminator, _)| {
+            instruction.program_id == *program_id && instruction.data.starts_with(discriminator)
+        })
+        .map_or(DEFAULT_INSTRUCTION_COMPUTE_UNITS, |(_, _, units)| *units)
+}
+
+/// Estimated unit limit for `instructions` and the budget instructions
+/// prepended to them
+pub fn estimate_compute_units(instructions: &[Instruction]) -> u32 {
+    let units: u64 = instructions
+        .iter()
+        .filter(|instruction| !compute_budget::check_id(&instruction.program_id))
+        .map(|instruction| instruction_compute_units(instruction) as u64)
+        .sum();
+    with_budget_overhead(units)
+}
+
+/// Unit limit for a transaction that consumed `units` in simulation, with
+/// `margin_bps` of headroom and the budget instructions prepended to it
+pub fn compute_unit_limit(units: u64, margin_bps: u16) -> u32 {
+    let units = units as u128 * (10_000 + margin_bps as u128) / 10_000;
+    with_budget_overhead(units.min(u64::MAX as u128) as u64)
+}
+
+fn with_budget_overhead(units: u64) -> u32 {
+    let units = units.saturating_add(2 * COMPUTE_BUDGET_INSTRUCTION_UNITS as u64);
+    units.min(MAX_COMPUTE_UNIT_LIMIT as u64) as u32
+}
+
+/// The `percentile` (0-100) of recent per-slot prioritization fees, by
+/// nearest rank; 0 without samples
+pub fn fee_percentile(fees: &[u64], percentile: u8) -> u64 {
+    if fees.is_empty() {
+        return 0;
+    }
+    let mut fees = fees.to_vec();
+    fees.sort_unstable();
+    let rank = (fees.len() * percentile.min(100) as usize).div_ceil(100);
+    fees[rank.saturating_sub(1)]
+}
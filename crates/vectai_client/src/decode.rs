@@ -0,0 +1,33 @@
+use anchor_lang::{AccountDeserialize, Discriminator, Owner};
+use solana_rpc_client::rpc_client::RpcClient;
+use solana_sdk::{account::Account, pubkey::Pubkey};
+
+use crate::ClientError;
+
+/// Decode `account`, as read from `key`, into the program account `T`.
+///
+/// The account must be owned by `T`'s program and start with `T`'s
+/// discriminator, so a lookalike of the same layout — another program's
+/// account, or another account type of the same program — is rejected
+/// instead of misread.
+pub fn try_decode<T>(key: Pubkey, account: &Account) -> Result<T, ClientError>
+where
+    T: AccountDeserialize + Discriminator + Owner,
+{
+    if account.owner != T::owner() {
+        return Err(ClientError::AccountOwner { key, owner: account.owner });
+    }
+    if account.data.get(..8) != Some(&T::DISCRIMINATOR[..]) {
+        return Err(ClientError::AccountDiscriminator(key));
+    }
+    T::try_deserialize(&mut account.data.as_slice()).map_err(|_| ClientError::InvalidAccount(key))
+}
+
+/// Fetch the account at `key` and [`try_decode`] it as a `T`
+pub fn fetch_account<T>(rpc: &RpcClient, key: &Pubkey) -> Result<T, ClientError>
+where
+    T: AccountDeserialize + Discriminator + Owner,
+{
+    let account = rpc.get_account(key).map_err(Box::new)?;
+    try_decode(*key, &account)
+}
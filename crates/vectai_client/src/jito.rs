@@ -0,0 +1,73 @@
+use anchor_lang::{InstructionData, ToAccountMetas};
+use base64::{engine::general_purpose::STANDARD, Engine};
+use serde_json::json;
+use solana_rpc_client::rpc_client::RpcClient;
+use solana_rpc_client_api::request::RpcRequest;
+use solana_sdk::{
+    instruction::Instruction,
+    pubkey::Pubkey,
+    system_program,
+    sysvar::instructions,
+    transaction::VersionedTransaction,
+};
+use vectai_trader::JITO_TIP_ACCOUNTS;
+
+use crate::ClientError;
+
+/// Mainnet block engine's JSON-RPC endpoint for bundles
+pub const JITO_MAINNET_BLOCK_ENGINE: &str = "https://mainnet.block-engine.jito.wtf/api/v1/bundles";
+
+/// Transactions the block engine accepts in one bundle
+pub const MAX_BUNDLE_TRANSACTIONS: usize = 5;
+
+/// One of the Jito tip accounts, picked by `seed`; spreading tips over
+/// them keeps bundles from contending on a single account
+pub fn jito_tip_account(seed: u64) -> Pubkey {
+    JITO_TIP_ACCOUNTS[(seed % JITO_TIP_ACCOUNTS.len() as u64) as usize]
+}
+
+/// `tip_jito` paying `lamports` from `authority` to `tip_account`; it
+/// only succeeds in a transaction that also executes `authority`'s
+/// strategy, within its configured tip cap
+pub fn tip_jito_instruction(authority: &Pubkey, tip_account: Pubkey, lamports: u64) -> Instruction {
+    Instruction {
+        program_id: vectai_trader::ID,
+        accounts: vectai_trader::accounts::TipJito {
+            user_authority: *authority,
+            trader_config: Pubkey::find_program_address(&[b"trader", authority.as_ref()], &vectai_trader::ID).0,
+            tip_account,
+            instructions: instructions::ID,
+            system_program: system_program::ID,
+        }
+        .to_account_metas(None),
+        data: vectai_trader::instruction::TipJito { lamports }.data(),
+    }
+}
+
+/// Signed transactions as the base64 strings `sendBundle` takes
+pub fn encode_bundle(transactions: &[VersionedTransaction]) -> Result<Vec<String>, ClientError> {
+    if transactions.is_empty() || transactions.len() > MAX_BUNDLE_TRANSACTIONS {
+        return Err(ClientError::InvalidBundle(transactions.len()));
+    }
+    transactions
+        .iter()
+        .map(|transaction| {
+            let bytes = bincode::serialize(transaction).map_err(|_| ClientError::InvalidBundle(transactions.len()))?;
+            Ok(STANDARD.encode(bytes))
+        })
+        .collect()
+}
+
+/// Send `transactions` as one bundle through `block_engine` (an
+/// [`RpcClient`] on e.g. [`JITO_MAINNET_BLOCK_ENGINE`]), which lands them
+/// in order and all together or not at all; returns the bundle id
+pub fn send_bundle(block_engine: &RpcClient, transactions: &[VersionedTransaction]) -> Result<String, ClientError> {
+    let encoded = encode_bundle(transactions)?;
+    let bundle_id = block_engine
+        .send(
+            RpcRequest::Custom { method: "sendBundle" },
+            json!([encoded, { "encoding": "base64" }]),
+        )
+        .map_err(Box::new)?;
+    Ok(bundle_id)
+}
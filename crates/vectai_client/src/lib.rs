@@ -0,0 +1,111 @@
+//! Client-side transaction building for VECT.AI programs.
+//!
+//! Raydium swap transactions routinely fail on congested slots when sent
+//! with the default compute budget: they land without priority or run out
+//! of units inside the AMM CPI. [`TransactionBuilder`] sizes the compute
+//! unit limit by simulating the instructions (or from the static
+//! [`instruction_compute_units`] estimates), prices each unit at a
+//! percentile of the fees recently paid for the accounts it writes, and
+//! prepends the matching `ComputeBudget` instructions.
+//!
+//! Behind the registered address lookup table (see [`registered_lookup_table`])
+//! the same instructions compile to a v0 message that references the pool
+//! and protocol accounts by one-byte index, leaving room for more
+//! instructions per transaction.
+//!
+//! [`try_decode`] and [`fetch_account`] read any program account after
+//! checking its owner and discriminator.
+//!
+//! [`fetch_account_bundle`] reads a pair's registered Raydium/Serum
+//! accounts in one call, from which [`execute_trade_accounts`] and
+//! [`swap_accounts`] fill in the rest of the account list.
+//!
+//! For MEV-sensitive executions, [`tip_jito_instruction`] adds a tip to a
+//! Jito tip account (capped on-chain per strategy) and [`send_bundle`]
+//! sends the signed transactions to a Jito block engine as one bundle.
+
+use std::fmt;
+
+use solana_rpc_client::rpc_client::RpcClient;
+use solana_rpc_client_api::client_error::Error as RpcError;
+use solana_sdk::{
+    commitment_config::CommitmentConfig, message::CompileError, pubkey::Pubkey, signer::SignerError,
+    transaction::TransactionError,
+};
+
+mod bundle;
+pub use bundle::*;
+mod compute_budget;
+pub use compute_budget::*;
+mod decode;
+pub use decode::*;
+mod jito;
+pub use jito::*;
+mod lookup_table;
+pub use lookup_table::*;
+mod transaction;
+pub use transaction::*;
+
+#[derive(Debug)]
+pub enum ClientError {
+    Rpc(Box<RpcError>),
+    /// The simulated transaction failed; it would fail on-chain as well
+    Simulation { err: TransactionError, logs: Vec<String> },
+    /// The simulation reported no compute units consumed
+    MissingUnitsConsumed,
+    Signing(SignerError),
+    /// The instructions do not fit a v0 message over the lookup tables
+    Compile(CompileError),
+    /// The account does not hold what was expected of it
+    InvalidAccount(Pubkey),
+    /// The account is owned by another program than the expected type's
+    AccountOwner { key: Pubkey, owner: Pubkey },
+    /// The account holds another account type than the expected one
+    AccountDiscriminator(Pubkey),
+    /// The simulated view instruction set no (or malformed) return data
+    MissingReturnData,
+    /// A bundle of this many transactions (or one that would not encode)
+    InvalidBundle(usize),
+}
+
+impl fmt::Display for ClientError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ClientError::Rpc(e) => write!(f, "rpc: {}", e),
+            ClientError::Simulation { err, .. } => write!(f, "simulation failed: {}", err),
+            ClientError::MissingUnitsConsumed => write!(f, "simulation did not report compute units"),
+            ClientError::Signing(e) => write!(f, "signing: {}", e),
+            ClientError::Compile(e) => write!(f, "compile: {}", e),
+            ClientError::InvalidAccount(key) => write!(f, "invalid account {}", key),
+            ClientError::AccountOwner { key, owner } => write!(f, "account {} is owned by {}", key, owner),
+            ClientError::AccountDiscriminator(key) => write!(f, "account {} has another type", key),
+            ClientError::MissingReturnData => write!(f, "simulation did not return data"),
+            ClientError::InvalidBundle(len) => write!(f, "invalid bundle of {} transactions", len),
+        }
+    }
+}
+
+impl std::error::Error for ClientError {}
+
+impl From<Box<RpcError>> for ClientError {
+    fn from(e: Box<RpcError>) -> Self {
+        ClientError::Rpc(e)
+    }
+}
+
+impl From<SignerError> for ClientError {
+    fn from(e: SignerError) -> Self {
+        ClientError::Signing(e)
+    }
+}
+
+impl From<CompileError> for ClientError {
+    fn from(e: CompileError) -> Self {
+        ClientError::Compile(e)
+    }
+}
+
+/// RPC client for `url` at confirmed commitment
+pub fn rpc_client(url: &str) -> RpcClient {
+    RpcClient::new_with_commitment(url.to_string(), CommitmentConfig::confirmed())
+}
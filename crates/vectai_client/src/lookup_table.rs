@@ -0,0 +1,104 @@
+use solana_rpc_client::rpc_client::RpcClient;
+use solana_sdk::{
+    address_lookup_table::{
+        instruction::{create_lookup_table as create_table, extend_lookup_table},
+        state::AddressLookupTable,
+        AddressLookupTableAccount,
+    },
+    clock::Slot,
+    instruction::Instruction,
+    pubkey::Pubkey,
+    sysvar,
+};
+use vectai_protocol::{lookup_table_registry, LookupTableRegistry, NetworkConfig};
+
+use crate::{fetch_account, ClientError};
+
+/// Addresses each extend instruction adds, keeping every transaction of
+/// [`create_lookup_table`] well under the packet size
+pub const LOOKUP_TABLE_CHUNK: usize = 20;
+
+/// The static accounts of an execution or swap on the whitelisted pool:
+/// the Raydium AMM and Serum market accounts, the network's programs,
+/// mints and fee vaults, and the protocol state every execution reads
+pub fn raydium_lookup_addresses(network: &NetworkConfig) -> Vec<Pubkey> {
+    use raydium_swapper::pool_config::*;
+
+    let protocol_pda = |seed: &[u8]| Pubkey::find_program_address(&[seed], &vectai_protocol::ID).0;
+    vec![
+        network.raydium_amm_program,
+        AMM_ID,
+        AMM_AUTHORITY,
+        AMM_OPEN_ORDERS,
+        AMM_TARGET_ORDERS,
+        AMM_LP_MINT,
+        POOL_COIN_TOKEN_ACCOUNT,
+        POOL_PC_TOKEN_ACCOUNT,
+        SERUM_PROGRAM,
+        SERUM_MARKET,
+        SERUM_BIDS,
+        SERUM_ASKS,
+        SERUM_EVENT_QUEUE,
+        SERUM_COIN_VAULT,
+        SERUM_PC_VAULT,
+        SERUM_VAULT_SIGNER,
+        network.wsol_mint,
+        network.usdc_mint,
+        network.oracle_program,
+        network.treasury_vault(&network.wsol_mint),
+        network.treasury_vault(&network.usdc_mint),
+        protocol_pda(b"protocol-status"),
+        protocol_pda(b"network-config"),
+        protocol_pda(b"protocol-stats"),
+        vectai_protocol::stats_reporter(&vectai_trader::ID),
+        vectai_protocol::stats_reporter(&raydium_swapper::ID),
+        vectai_protocol::ID,
+        vectai_trader::ID,
+        raydium_swapper::ID,
+        spl_token::ID,
+        sysvar::instructions::ID,
+    ]
+}
+
+/// A new lookup table at the returned address holding `addresses`, as
+/// one instruction list per transaction: the create with the first
+/// chunk, then one extend per further chunk, to be sent in order.
+/// `recent_slot` must be a recent finalized slot.
+pub fn create_lookup_table(
+    authority: Pubkey,
+    payer: Pubkey,
+    recent_slot: Slot,
+    addresses: &[Pubkey],
+) -> (Pubkey, Vec<Vec<Instruction>>) {
+    let (create, address) = create_table(authority, payer, recent_slot);
+    let mut transactions = vec![vec![create]];
+    for (index, chunk) in addresses.chunks(LOOKUP_TABLE_CHUNK).enumerate() {
+        let extend = extend_lookup_table(address, authority, Some(payer), chunk.to_vec());
+        if index == 0 {
+            transactions[0].push(extend);
+        } else {
+            transactions.push(vec![extend]);
+        }
+    }
+    (address, transactions)
+}
+
+/// Decode the lookup table account `key` holds
+pub fn decode_lookup_table(key: Pubkey, data: &[u8]) -> Result<AddressLookupTableAccount, ClientError> {
+    let table = AddressLookupTable::deserialize(data).map_err(|_| ClientError::InvalidAccount(key))?;
+    Ok(AddressLookupTableAccount {
+        key,
+        addresses: table.addresses.to_vec(),
+    })
+}
+
+pub fn fetch_lookup_table(rpc: &RpcClient, address: &Pubkey) -> Result<AddressLookupTableAccount, ClientError> {
+    let account = rpc.get_account(address).map_err(Box::new)?;
+    decode_lookup_table(*address, &account.data)
+}
+
+/// The lookup table registered in `vectai_protocol`
+pub fn registered_lookup_table(rpc: &RpcClient) -> Result<AddressLookupTableAccount, ClientError> {
+    let registry: LookupTableRegistry = fetch_account(rpc, &lookup_table_registry())?;
+    fetch_lookup_table(rpc, &registry.lookup_table)
+}
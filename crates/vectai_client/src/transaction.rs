@@ -0,0 +1,194 @@
+use solana_rpc_client::rpc_client::RpcClient;
+use solana_rpc_client_api::config::RpcSimulateTransactionConfig;
+use solana_sdk::{
+    address_lookup_table::AddressLookupTableAccount,
+    hash::Hash,
+    instruction::Instruction,
+    message::{v0, Message, VersionedMessage},
+    pubkey::Pubkey,
+    signature::Signature,
+    signer::Signer,
+    transaction::{Transaction, VersionedTransaction},
+};
+
+use crate::{
+    compute_unit_limit, estimate_compute_units, fee_percentile, ClientError, ComputeBudget,
+    DEFAULT_COMPUTE_MARGIN_BPS, MAX_COMPUTE_UNIT_LIMIT,
+};
+
+/// Fee percentile paid by default; high enough to land a swap in a busy
+/// slot without bidding against the outliers
+pub const DEFAULT_FEE_PERCENTILE: u8 = 75;
+
+// `getRecentPrioritizationFees` accepts at most this many accounts
+const MAX_FEE_ACCOUNTS: usize = 128;
+
+/// Legacy or v0 transaction with a compute budget sized for its instructions
+#[derive(Clone, Debug)]
+pub struct TransactionBuilder {
+    payer: Pubkey,
+    instructions: Vec<Instruction>,
+    fee_percentile: u8,
+    max_unit_price: Option<u64>,
+    compute_margin_bps: u16,
+    simulate: bool,
+    lookup_tables: Vec<AddressLookupTableAccount>,
+}
+
+impl TransactionBuilder {
+    /// Simulated unit limit with 10% headroom, priced at the 75th fee
+    /// percentile and uncapped
+    pub fn new(payer: Pubkey) -> Self {
+        TransactionBuilder {
+            payer,
+            instructions: Vec::new(),
+            fee_percentile: DEFAULT_FEE_PERCENTILE,
+            max_unit_price: None,
+            compute_margin_bps: DEFAULT_COMPUTE_MARGIN_BPS,
+            simulate: true,
+            lookup_tables: Vec::new(),
+        }
+    }
+
+    pub fn instruction(mut self, instruction: Instruction) -> Self {
+        self.instructions.push(instruction);
+        self
+    }
+
+    pub fn instructions(mut self, instructions: impl IntoIterator<Item = Instruction>) -> Self {
+        self.instructions.extend(instructions);
+        self
+    }
+
+    /// Price units at this percentile (0-100) of recent fees
+    pub fn fee_percentile(mut self, percentile: u8) -> Self {
+        self.fee_percentile = percentile.min(100);
+        self
+    }
+
+    /// Never pay more than `micro_lamports` per unit, whatever recent fees were
+    pub fn max_unit_price(mut self, micro_lamports: u64) -> Self {
+        self.max_unit_price = Some(micro_lamports);
+        self
+    }
+
+    /// Headroom over simulated units, in basis points
+    pub fn compute_margin_bps(mut self, margin_bps: u16) -> Self {
+        self.compute_margin_bps = margin_bps;
+        self
+    }
+
+    /// Size the unit limit from [`estimate_compute_units`] instead of a
+    /// simulation, saving the RPC round trip
+    pub fn without_simulation(mut self) -> Self {
+        self.simulate = false;
+        self
+    }
+
+    /// Compile [`build_versioned`](Self::build_versioned) and simulations
+    /// to a v0 message that looks accounts up in `tables`
+    pub fn lookup_tables(mut self, tables: impl IntoIterator<Item = AddressLookupTableAccount>) -> Self {
+        self.lookup_tables.extend(tables);
+        self
+    }
+
+    /// Unit limit and price for the instructions as they stand
+    pub fn compute_budget(&self, rpc: &RpcClient) -> Result<ComputeBudget, ClientError> {
+        let unit_limit = if self.simulate {
+            compute_unit_limit(self.simulate_units(rpc)?, self.compute_margin_bps)
+        } else {
+            estimate_compute_units(&self.instructions)
+        };
+        Ok(ComputeBudget {
+            unit_limit,
+            unit_price: self.unit_price(rpc)?,
+        })
+    }
+
+    /// Signed legacy transaction with the compute budget prepended, on the
+    /// latest blockhash; lookup tables are ignored
+    pub fn build(&self, rpc: &RpcClient, signers: &[&dyn Signer]) -> Result<Transaction, ClientError> {
+        let budget = self.compute_budget(rpc)?;
+        let message = Message::new(&budget.apply(&self.instructions), Some(&self.payer));
+        let blockhash = rpc.get_latest_blockhash().map_err(Box::new)?;
+        let mut transaction = Transaction::new_unsigned(message);
+        transaction.try_sign(signers, blockhash)?;
+        Ok(transaction)
+    }
+
+    /// Signed transaction with the compute budget prepended, on the latest
+    /// blockhash, as a v0 message when lookup tables are set
+    pub fn build_versioned(
+        &self,
+        rpc: &RpcClient,
+        signers: &[&dyn Signer],
+    ) -> Result<VersionedTransaction, ClientError> {
+        let budget = self.compute_budget(rpc)?;
+        let blockhash = rpc.get_latest_blockhash().map_err(Box::new)?;
+        let message = self.message(&budget, blockhash)?;
+        Ok(VersionedTransaction::try_new(message, signers)?)
+    }
+
+    fn message(&self, budget: &ComputeBudget, blockhash: Hash) -> Result<VersionedMessage, ClientError> {
+        let instructions = budget.apply(&self.instructions);
+        if self.lookup_tables.is_empty() {
+            return Ok(VersionedMessage::Legacy(Message::new_with_blockhash(
+                &instructions,
+                Some(&self.payer),
+                &blockhash,
+            )));
+        }
+        let message = v0::Message::try_compile(&self.payer, &instructions, &self.lookup_tables, blockhash)?;
+        Ok(VersionedMessage::V0(message))
+    }
+
+    /// Units the instructions consume in simulation, run at the maximum
+    /// limit so the simulation itself cannot run out
+    fn simulate_units(&self, rpc: &RpcClient) -> Result<u64, ClientError> {
+        let budget = ComputeBudget {
+            unit_limit: MAX_COMPUTE_UNIT_LIMIT,
+            unit_price: 0,
+        };
+        let message = self.message(&budget, Hash::default())?;
+        let transaction = VersionedTransaction {
+            signatures: vec![Signature::default(); message.header().num_required_signatures as usize],
+            message,
+        };
+        let config = RpcSimulateTransactionConfig {
+            sig_verify: false,
+            replace_recent_blockhash: true,
+            ..RpcSimulateTransactionConfig::default()
+        };
+        let result = rpc
+            .simulate_transaction_with_config(&transaction, config)
+            .map_err(Box::new)?
+            .value;
+        if let Some(err) = result.err {
+            return Err(ClientError::Simulation {
+                err,
+                logs: result.logs.unwrap_or_default(),
+            });
+        }
+        result.units_consumed.ok_or(ClientError::MissingUnitsConsumed)
+    }
+
+    /// Recent fee percentile over the accounts the instructions write,
+    /// which is where their slots are contended
+    fn unit_price(&self, rpc: &RpcClient) -> Result<u64, ClientError> {
+        let mut writable: Vec<Pubkey> = Vec::new();
+        for meta in self.instructions.iter().flat_map(|instruction| &instruction.accounts) {
+            if meta.is_writable && !writable.contains(&meta.pubkey) {
+                writable.push(meta.pubkey);
+            }
+        }
+        writable.truncate(MAX_FEE_ACCOUNTS);
+        let fees: Vec<u64> = rpc
+            .get_recent_prioritization_fees(&writable)
+            .map_err(Box::new)?
+            .into_iter()
+            .map(|fee| fee.prioritization_fee)
+            .collect();
+        let price = fee_percentile(&fees, self.fee_percentile);
+        Ok(self.max_unit_price.map_or(price, |max| price.min(max)))
+    }
+}
@@ -0,0 +1,87 @@
+//! Account bundles and the account lists built from them.
+
+use anchor_lang::{AccountSerialize, ToAccountMetas};
+use solana_sdk::pubkey::Pubkey;
+use vectai_client::{decode_account_bundle, execute_trade_accounts, swap_accounts, ClientError};
+use vectai_protocol::{AccountBundle, LookupTableRegistry, RaydiumPoolAccounts};
+
+fn bundle() -> AccountBundle {
+    let mut keys = (0..15).map(|_| Pubkey::new_unique());
+    let mut next = || keys.next().unwrap();
+    AccountBundle {
+        base_mint: Pubkey::new_unique(),
+        quote_mint: Pubkey::new_unique(),
+        pool: RaydiumPoolAccounts {
+            raydium_amm_program: next(),
+            amm: next(),
+            amm_authority: next(),
+            amm_open_orders: next(),
+            amm_target_orders: next(),
+            pool_coin_token_account: next(),
+            pool_pc_token_account: next(),
+            serum_program: next(),
+            serum_market: next(),
+            serum_bids: next(),
+            serum_asks: next(),
+            serum_event_queue: next(),
+            serum_coin_vault: next(),
+            serum_pc_vault: next(),
+            serum_vault_signer: next(),
+        },
+        updated_at: 1_700_000_000,
+        bump: 254,
+    }
+}
+
+#[test]
+fn decode_account_bundle_round_trips() {
+    let bundle = bundle();
+    let mut data = Vec::new();
+    bundle.try_serialize(&mut data).unwrap();
+    let key = Pubkey::new_unique();
+    let decoded = decode_account_bundle(key, &data).unwrap();
+    assert_eq!(decoded.pool, bundle.pool);
+    assert_eq!((decoded.base_mint, decoded.quote_mint), (bundle.base_mint, bundle.quote_mint));
+
+    // Another protocol account is not a bundle
+    let mut other = Vec::new();
+    LookupTableRegistry {
+        lookup_table: Pubkey::new_unique(),
+        updated_at: 0,
+        bump: 255,
+    }
+    .try_serialize(&mut other)
+    .unwrap();
+    assert!(matches!(decode_account_bundle(key, &other), Err(ClientError::InvalidAccount(k)) if k == key));
+}
+
+#[test]
+fn execute_trade_accounts_take_the_pool_from_the_bundle() {
+    let bundle = bundle();
+    let authority = Pubkey::new_unique();
+    let (source, destination, price_feed) = (Pubkey::new_unique(), Pubkey::new_unique(), Pubkey::new_unique());
+    let accounts = execute_trade_accounts(&bundle, &authority, source, destination, price_feed);
+    assert_eq!(
+        accounts.trader_config,
+        Pubkey::find_program_address(&[b"trader", authority.as_ref()], &vectai_trader::ID).0
+    );
+    assert_eq!(accounts.price_feed, price_feed);
+
+    let metas: Vec<Pubkey> = accounts.to_account_metas(None).iter().map(|meta| meta.pubkey).collect();
+    assert_eq!(metas[..4], [authority, accounts.trader_config, source, destination]);
+    assert_eq!(metas[4..19], bundle.pool.ordered());
+}
+
+#[test]
+fn swap_accounts_take_the_pool_from_the_bundle() {
+    let bundle = bundle();
+    let authority = Pubkey::new_unique();
+    let (source, destination) = (Pubkey::new_unique(), Pubkey::new_unique());
+    let metas: Vec<Pubkey> = swap_accounts(&bundle, &authority, source, destination)
+        .to_account_metas(None)
+        .iter()
+        .map(|meta| meta.pubkey)
+        .collect();
+    assert_eq!(metas[..3], [authority, source, destination]);
+    assert_eq!(metas[3..18], bundle.pool.ordered());
+}
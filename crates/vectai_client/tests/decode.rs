@@ -0,0 +1,63 @@
+//! Program accounts decoded from raw RPC data after their owner and
+//! discriminator checks.
+
+use anchor_lang::AccountSerialize;
+use solana_sdk::{account::Account, pubkey::Pubkey};
+use vectai_client::{try_decode, ClientError};
+use vectai_protocol::{LookupTableRegistry, NetworkConfig};
+
+fn account(owner: Pubkey, data: Vec<u8>) -> Account {
+    Account {
+        lamports: 1_000_000,
+        data,
+        owner,
+        executable: false,
+        rent_epoch: 0,
+    }
+}
+
+fn registry_data(registry: &LookupTableRegistry) -> Vec<u8> {
+    let mut data = Vec::new();
+    registry.try_serialize(&mut data).unwrap();
+    data
+}
+
+#[test]
+fn try_decode_reads_owned_accounts_of_the_type() {
+    let registry = LookupTableRegistry {
+        lookup_table: Pubkey::new_unique(),
+        updated_at: 1_700_000_000,
+        bump: 253,
+    };
+    let key = Pubkey::new_unique();
+    let decoded: LookupTableRegistry = try_decode(key, &account(vectai_protocol::ID, registry_data(&registry))).unwrap();
+    assert_eq!(decoded.lookup_table, registry.lookup_table);
+    assert_eq!((decoded.updated_at, decoded.bump), (registry.updated_at, registry.bump));
+}
+
+#[test]
+fn try_decode_rejects_lookalikes() {
+    let data = registry_data(&LookupTableRegistry {
+        lookup_table: Pubkey::new_unique(),
+        updated_at: 0,
+        bump: 255,
+    });
+    let key = Pubkey::new_unique();
+
+    // The same bytes under another program
+    let result = try_decode::<LookupTableRegistry>(key, &account(vectai_trader::ID, data.clone()));
+    assert!(matches!(
+        result,
+        Err(ClientError::AccountOwner { key: k, owner }) if k == key && owner == vectai_trader::ID
+    ));
+
+    // Another account type of the same program
+    let result = try_decode::<NetworkConfig>(key, &account(vectai_protocol::ID, data.clone()));
+    assert!(matches!(result, Err(ClientError::AccountDiscriminator(k)) if k == key));
+    let result = try_decode::<LookupTableRegistry>(key, &account(vectai_protocol::ID, vec![1, 2, 3]));
+    assert!(matches!(result, Err(ClientError::AccountDiscriminator(k)) if k == key));
+
+    // The right type, cut short
+    let result = try_decode::<LookupTableRegistry>(key, &account(vectai_protocol::ID, data[..20].to_vec()));
+    assert!(matches!(result, Err(ClientError::InvalidAccount(k)) if k == key));
+}
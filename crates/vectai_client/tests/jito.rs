@@ -0,0 +1,71 @@
+//! Jito tips and bundles, against the RPC client's mock sender.
+
+use std::collections::HashMap;
+
+use base64::{engine::general_purpose::STANDARD, Engine};
+use serde_json::json;
+use solana_rpc_client::rpc_client::RpcClient;
+use solana_rpc_client_api::request::RpcRequest;
+use solana_sdk::{
+    hash::Hash,
+    message::{Message, VersionedMessage},
+    pubkey::Pubkey,
+    signature::{Keypair, Signer},
+    system_instruction,
+    transaction::VersionedTransaction,
+};
+use vectai_client::{
+    encode_bundle, instruction_compute_units, jito_tip_account, send_bundle, tip_jito_instruction, ClientError,
+    MAX_BUNDLE_TRANSACTIONS,
+};
+use vectai_trader::JITO_TIP_ACCOUNTS;
+
+fn transfer(payer: &Keypair) -> VersionedTransaction {
+    let instruction = system_instruction::transfer(&payer.pubkey(), &Pubkey::new_unique(), 1);
+    let message = Message::new_with_blockhash(&[instruction], Some(&payer.pubkey()), &Hash::new_unique());
+    VersionedTransaction::try_new(VersionedMessage::Legacy(message), &[payer]).unwrap()
+}
+
+#[test]
+fn tip_accounts_are_spread_by_seed() {
+    assert_eq!(jito_tip_account(0), JITO_TIP_ACCOUNTS[0]);
+    assert_eq!(jito_tip_account(3), JITO_TIP_ACCOUNTS[3]);
+    assert_eq!(jito_tip_account(JITO_TIP_ACCOUNTS.len() as u64 + 1), JITO_TIP_ACCOUNTS[1]);
+}
+
+#[test]
+fn tip_instruction_pays_from_the_authority() {
+    let authority = Pubkey::new_unique();
+    let tip_account = jito_tip_account(7);
+    let instruction = tip_jito_instruction(&authority, tip_account, 100_000);
+    assert_eq!(instruction.program_id, vectai_trader::ID);
+
+    let trader_config = Pubkey::find_program_address(&[b"trader", authority.as_ref()], &vectai_trader::ID).0;
+    let accounts: Vec<_> = instruction.accounts.iter().map(|meta| (meta.pubkey, meta.is_signer, meta.is_writable)).collect();
+    assert_eq!(accounts[..3], [(authority, true, true), (trader_config, false, false), (tip_account, false, true)]);
+    assert_eq!(instruction_compute_units(&instruction), 15_000);
+}
+
+#[test]
+fn bundles_encode_each_transaction_in_order() {
+    let payer = Keypair::new();
+    let transactions = [transfer(&payer), transfer(&payer)];
+    let encoded = encode_bundle(&transactions).unwrap();
+    assert_eq!(encoded.len(), 2);
+    for (encoded, transaction) in encoded.iter().zip(&transactions) {
+        let decoded: VersionedTransaction = bincode::deserialize(&STANDARD.decode(encoded).unwrap()).unwrap();
+        assert_eq!(decoded, *transaction);
+    }
+
+    let oversized = vec![transfer(&payer); MAX_BUNDLE_TRANSACTIONS + 1];
+    assert!(matches!(encode_bundle(&oversized), Err(ClientError::InvalidBundle(6))));
+    assert!(matches!(encode_bundle(&[]), Err(ClientError::InvalidBundle(0))));
+}
+
+#[test]
+fn send_bundle_returns_the_bundle_id() {
+    let mocks = HashMap::from([(RpcRequest::Custom { method: "sendBundle" }, json!("bundle-id"))]);
+    let block_engine = RpcClient::new_mock_with_mocks("succeeds".to_string(), mocks);
+    let payer = Keypair::new();
+    assert_eq!(send_bundle(&block_engine, &[transfer(&payer)]).unwrap(), "bundle-id");
+}
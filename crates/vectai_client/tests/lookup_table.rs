@@ -0,0 +1,164 @@
+//! Lookup table contents, creation and v0 transactions compiled over it.
+
+use std::collections::{HashMap, HashSet};
+
+use anchor_lang::InstructionData;
+use serde_json::json;
+use solana_rpc_client::rpc_client::RpcClient;
+use solana_rpc_client_api::request::RpcRequest;
+use solana_sdk::{
+    address_lookup_table::{
+        instruction::derive_lookup_table_address,
+        program,
+        state::{AddressLookupTable, LookupTableMeta},
+        AddressLookupTableAccount,
+    },
+    instruction::{AccountMeta, Instruction},
+    message::VersionedMessage,
+    pubkey::Pubkey,
+    signature::{Keypair, Signer},
+};
+use vectai_client::{
+    create_lookup_table, decode_lookup_table, raydium_lookup_addresses, ClientError, TransactionBuilder,
+    LOOKUP_TABLE_CHUNK,
+};
+use vectai_protocol::NetworkConfig;
+
+fn network() -> NetworkConfig {
+    NetworkConfig {
+        raydium_amm_program: Pubkey::new_unique(),
+        wsol_mint: Pubkey::new_unique(),
+        usdc_mint: Pubkey::new_unique(),
+        oracle_program: Pubkey::new_unique(),
+        treasury_program: Pubkey::new_unique(),
+        bump: 255,
+        version: 1,
+        upgrade_attestations: Vec::new(),
+    }
+}
+
+fn rpc() -> RpcClient {
+    let simulation = json!({
+        "context": { "slot": 1 },
+        "value": { "err": null, "logs": [], "unitsConsumed": 80_000 },
+    });
+    let mocks = HashMap::from([
+        (RpcRequest::GetRecentPrioritizationFees, json!([{ "slot": 1, "prioritizationFee": 1_000 }])),
+        (RpcRequest::SimulateTransaction, simulation),
+    ]);
+    RpcClient::new_mock_with_mocks("succeeds".to_string(), mocks)
+}
+
+#[test]
+fn lookup_addresses_cover_pool_and_network() {
+    let network = network();
+    let addresses = raydium_lookup_addresses(&network);
+    let unique: HashSet<&Pubkey> = addresses.iter().collect();
+    assert_eq!(unique.len(), addresses.len());
+    for address in [
+        raydium_swapper::pool_config::AMM_ID,
+        raydium_swapper::pool_config::SERUM_MARKET,
+        network.raydium_amm_program,
+        network.wsol_mint,
+        network.usdc_mint,
+        network.treasury_vault(&network.usdc_mint),
+        vectai_protocol::stats_reporter(&vectai_trader::ID),
+    ] {
+        assert!(addresses.contains(&address), "missing {}", address);
+    }
+}
+
+#[test]
+fn create_lookup_table_chunks_extends() {
+    let authority = Pubkey::new_unique();
+    let payer = Pubkey::new_unique();
+    let addresses: Vec<Pubkey> = (0..LOOKUP_TABLE_CHUNK * 2 + 1).map(|_| Pubkey::new_unique()).collect();
+    let (address, transactions) = create_lookup_table(authority, payer, 42, &addresses);
+
+    assert_eq!(address, derive_lookup_table_address(&authority, 42).0);
+    // Create and the first extend, then one extend per remaining chunk
+    assert_eq!(
+        transactions.iter().map(Vec::len).collect::<Vec<_>>(),
+        vec![2, 1, 1]
+    );
+    assert!(transactions.iter().flatten().all(|instruction| instruction.program_id == program::ID));
+    assert!(transactions.iter().flatten().all(|instruction| instruction.accounts[0].pubkey == address));
+
+    let (_, transactions) = create_lookup_table(authority, payer, 42, &[]);
+    assert_eq!(transactions.len(), 1);
+    assert_eq!(transactions[0].len(), 1);
+}
+
+#[test]
+fn decode_lookup_table_round_trips() {
+    let key = Pubkey::new_unique();
+    let addresses = raydium_lookup_addresses(&network());
+    let data = AddressLookupTable {
+        meta: LookupTableMeta::new(Pubkey::new_unique()),
+        addresses: addresses.clone().into(),
+    }
+    .serialize_for_tests()
+    .unwrap();
+
+    let table = decode_lookup_table(key, &data).unwrap();
+    assert_eq!(table.key, key);
+    assert_eq!(table.addresses, addresses);
+    assert!(matches!(
+        decode_lookup_table(key, &[1, 2, 3]),
+        Err(ClientError::InvalidAccount(invalid)) if invalid == key
+    ));
+}
+
+#[test]
+fn build_versioned_looks_up_table_accounts() {
+    let table = AddressLookupTableAccount {
+        key: Pubkey::new_unique(),
+        addresses: raydium_lookup_addresses(&network()),
+    };
+    let amm = raydium_swapper::pool_config::AMM_ID;
+    let payer = Keypair::new();
+    let trade = Instruction {
+        program_id: vectai_trader::ID,
+        accounts: vec![
+            AccountMeta::new(payer.pubkey(), true),
+            AccountMeta::new(amm, false),
+            AccountMeta::new_readonly(vectai_protocol::ID, false),
+        ],
+        data: vectai_trader::instruction::ExecuteTrade {
+            amount: 1_000_000,
+            dry_run: false,
+            nonce: None,
+        }
+        .data(),
+    };
+    let builder = TransactionBuilder::new(payer.pubkey()).instruction(trade);
+
+    // Without tables the versioned transaction is still a legacy message
+    let legacy = builder.build_versioned(&rpc(), &[&payer]).unwrap();
+    assert!(matches!(legacy.message, VersionedMessage::Legacy(_)));
+
+    let transaction = builder
+        .lookup_tables([table.clone()])
+        .build_versioned(&rpc(), &[&payer])
+        .unwrap();
+    assert!(transaction.verify_with_results().into_iter().all(|verified| verified));
+    let VersionedMessage::V0(message) = &transaction.message else {
+        panic!("expected a v0 message");
+    };
+    assert!(!message.account_keys.contains(&amm));
+    assert_eq!(message.address_table_lookups.len(), 1);
+    let lookup = &message.address_table_lookups[0];
+    assert_eq!(lookup.account_key, table.key);
+    let writable: Vec<Pubkey> = lookup
+        .writable_indexes
+        .iter()
+        .map(|index| table.addresses[*index as usize])
+        .collect();
+    assert_eq!(writable, vec![amm]);
+    let readonly: Vec<Pubkey> = lookup
+        .readonly_indexes
+        .iter()
+        .map(|index| table.addresses[*index as usize])
+        .collect();
+    assert_eq!(readonly, vec![vectai_protocol::ID]);
+}
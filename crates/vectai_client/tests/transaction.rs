@@ -0,0 +1,169 @@
+//! Compute budgets and priority fees, against the RPC client's mock sender.
+
+use std::collections::HashMap;
+
+use anchor_lang::InstructionData;
+use serde_json::{json, Value};
+use solana_rpc_client::rpc_client::RpcClient;
+use solana_rpc_client_api::request::RpcRequest;
+use solana_sdk::{
+    compute_budget::{self, ComputeBudgetInstruction},
+    instruction::{AccountMeta, Instruction},
+    pubkey::Pubkey,
+    signature::{Keypair, Signer},
+    transaction::TransactionError,
+};
+use vectai_client::{
+    compute_unit_limit, estimate_compute_units, fee_percentile, instruction_compute_units, ClientError,
+    ComputeBudget, TransactionBuilder, DEFAULT_INSTRUCTION_COMPUTE_UNITS, MAX_COMPUTE_UNIT_LIMIT,
+};
+
+fn execute_trade() -> Instruction {
+    Instruction {
+        program_id: vectai_trader::ID,
+        accounts: vec![AccountMeta::new(Pubkey::new_unique(), false)],
+        data: vectai_trader::instruction::ExecuteTrade {
+            amount: 1_000_000,
+            dry_run: false,
+            nonce: None,
+        }
+        .data(),
+    }
+}
+
+fn unknown() -> Instruction {
+    Instruction::new_with_bytes(Pubkey::new_unique(), &[1, 2, 3], vec![])
+}
+
+/// Mock RPC answering one fee lookup and one simulation with these fee
+/// samples and simulated units
+fn rpc(fees: &[u64], units_consumed: u64, err: Option<TransactionError>) -> RpcClient {
+    let fees: Vec<Value> = fees
+        .iter()
+        .enumerate()
+        .map(|(slot, fee)| json!({ "slot": slot, "prioritizationFee": fee }))
+        .collect();
+    let simulation = json!({
+        "context": { "slot": 1 },
+        "value": { "err": err, "logs": ["Program log: simulated"], "unitsConsumed": units_consumed },
+    });
+    let mocks = HashMap::from([
+        (RpcRequest::GetRecentPrioritizationFees, Value::Array(fees)),
+        (RpcRequest::SimulateTransaction, simulation),
+    ]);
+    RpcClient::new_mock_with_mocks("succeeds".to_string(), mocks)
+}
+
+#[test]
+fn fee_percentile_uses_nearest_rank() {
+    let fees = [70, 10, 20, 30, 40, 50, 60, 80, 90, 100];
+    assert_eq!(fee_percentile(&fees, 0), 10);
+    assert_eq!(fee_percentile(&fees, 50), 50);
+    assert_eq!(fee_percentile(&fees, 75), 80);
+    assert_eq!(fee_percentile(&fees, 100), 100);
+    assert_eq!(fee_percentile(&fees, 200), 100);
+    assert_eq!(fee_percentile(&[], 75), 0);
+}
+
+#[test]
+fn compute_units_are_estimated_per_instruction() {
+    assert_eq!(instruction_compute_units(&execute_trade()), 150_000);
+    assert_eq!(instruction_compute_units(&unknown()), DEFAULT_INSTRUCTION_COMPUTE_UNITS);
+    // Same discriminator under another program
+    let mut foreign = execute_trade();
+    foreign.program_id = Pubkey::new_unique();
+    assert_eq!(instruction_compute_units(&foreign), DEFAULT_INSTRUCTION_COMPUTE_UNITS);
+
+    // Existing budget instructions are replaced, so not counted
+    let instructions = [ComputeBudgetInstruction::set_compute_unit_limit(1), execute_trade(), unknown()];
+    assert_eq!(estimate_compute_units(&instructions), 150_000 + DEFAULT_INSTRUCTION_COMPUTE_UNITS + 300);
+    assert_eq!(estimate_compute_units(&vec![unknown(); 10]), MAX_COMPUTE_UNIT_LIMIT);
+
+    assert_eq!(compute_unit_limit(100_000, 1_000), 110_300);
+    assert_eq!(compute_unit_limit(u64::MAX, 1_000), MAX_COMPUTE_UNIT_LIMIT);
+}
+
+#[test]
+fn compute_budget_replaces_existing_budget_instructions() {
+    let budget = ComputeBudget {
+        unit_limit: 110_300,
+        unit_price: 5_001,
+    };
+    let trade = execute_trade();
+    let instructions = [ComputeBudgetInstruction::set_compute_unit_price(1), trade.clone()];
+    let applied = budget.apply(&instructions);
+    assert_eq!(
+        applied,
+        vec![
+            ComputeBudgetInstruction::set_compute_unit_limit(110_300),
+            ComputeBudgetInstruction::set_compute_unit_price(5_001),
+            trade,
+        ]
+    );
+    // Charged on the limit, rounded up to the lamport
+    assert_eq!(budget.priority_fee(), 552);
+
+    let free = ComputeBudget { unit_price: 0, ..budget };
+    assert_eq!(free.instructions(), vec![ComputeBudgetInstruction::set_compute_unit_limit(110_300)]);
+    assert_eq!(free.priority_fee(), 0);
+}
+
+#[test]
+fn builder_sizes_budget_from_simulation_and_recent_fees() {
+    let fees = [0, 1_000, 5_000, 20_000];
+    let builder = TransactionBuilder::new(Pubkey::new_unique()).instruction(execute_trade());
+
+    assert_eq!(
+        builder.compute_budget(&rpc(&fees, 100_000, None)).unwrap(),
+        ComputeBudget {
+            unit_limit: 110_300,
+            unit_price: 5_000,
+        }
+    );
+    let capped = builder.clone().fee_percentile(100).max_unit_price(8_000);
+    assert_eq!(capped.compute_budget(&rpc(&fees, 100_000, None)).unwrap().unit_price, 8_000);
+    let estimated = builder.without_simulation();
+    assert_eq!(
+        estimated.compute_budget(&rpc(&fees, 100_000, None)).unwrap().unit_limit,
+        estimate_compute_units(&[execute_trade()])
+    );
+}
+
+#[test]
+fn builder_prepends_budget_and_signs() {
+    let rpc = rpc(&[2_000], 50_000, None);
+    let payer = Keypair::new();
+    let transaction = TransactionBuilder::new(payer.pubkey())
+        .instructions([ComputeBudgetInstruction::set_compute_unit_limit(1), execute_trade()])
+        .compute_margin_bps(0)
+        .build(&rpc, &[&payer])
+        .unwrap();
+
+    transaction.verify().unwrap();
+    let message = &transaction.message;
+    assert_eq!(message.account_keys[0], payer.pubkey());
+    let programs: Vec<Pubkey> = message
+        .instructions
+        .iter()
+        .map(|instruction| message.account_keys[instruction.program_id_index as usize])
+        .collect();
+    assert_eq!(programs, vec![compute_budget::ID, compute_budget::ID, vectai_trader::ID]);
+    assert_eq!(
+        message.instructions[0].data,
+        ComputeBudgetInstruction::set_compute_unit_limit(50_300).data
+    );
+    assert_eq!(message.instructions[1].data, ComputeBudgetInstruction::set_compute_unit_price(2_000).data);
+}
+
+#[test]
+fn builder_surfaces_failed_simulation() {
+    let rpc = rpc(&[], 1_000, Some(TransactionError::AccountNotFound));
+    let builder = TransactionBuilder::new(Pubkey::new_unique()).instruction(execute_trade());
+    match builder.compute_budget(&rpc) {
+        Err(ClientError::Simulation { err, logs }) => {
+            assert_eq!(err, TransactionError::AccountNotFound);
+            assert_eq!(logs, vec!["Program log: simulated".to_string()]);
+        }
+        other => panic!("expected a simulation error, got {:?}", other),
+    }
+}
@@ -0,0 +1,28 @@
+//! The protocol admin and the SPL Governance realm that can stand in for
+//! it, shared by every program's admin-gated instructions.
+
+use anchor_lang::prelude::*;
+
+/// Admin key for setup and emergency instructions
+pub const ADMIN_AUTHORITY: Pubkey = anchor_lang::solana_program::pubkey!("11111111111111111111111111111111"); // Replace with actual admin
+
+/// SPL Governance program; proposals executed by [`PROTOCOL_GOVERNANCE`]
+/// sign with its native treasury PDA, which is accepted wherever the admin is
+pub const SPL_GOVERNANCE_PROGRAM: Pubkey = anchor_lang::solana_program::pubkey!("GovER5Lthms3bLBqWub97yVrMmEogzX7xNjdXpPPCVZw");
+
+/// The protocol's governance realm
+pub const PROTOCOL_GOVERNANCE: Pubkey = anchor_lang::solana_program::pubkey!("11111111111111111111111111111111"); // Replace with actual governance
+
+/// Admin key, or the protocol governance's native treasury
+pub fn is_admin(key: &Pubkey) -> bool {
+    *key == ADMIN_AUTHORITY || *key == governance_native_treasury()
+}
+
+/// Native treasury PDA of [`PROTOCOL_GOVERNANCE`]
+pub fn governance_native_treasury() -> Pubkey {
+    Pubkey::find_program_address(
+        &[b"native-treasury", PROTOCOL_GOVERNANCE.as_ref()],
+        &SPL_GOVERNANCE_PROGRAM,
+    )
+    .0
+}
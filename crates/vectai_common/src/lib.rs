@@ -0,0 +1,120 @@
+//! Math and error categories shared by the VECT.AI programs.
+//!
+//! The pure integer math lives in `vectai_math`, which off-chain tooling
+//! shares; this crate re-exports it and wraps the fallible parts in Anchor
+//! results with one set of error codes, so each program stops keeping its
+//! own copy of the basis-point denominator and the `u128` arithmetic.
+//! [`versioned`] holds the shared account migration path, [`governance`]
+//! the admin key and governance realm every program accepts, and [`risk`]
+//! the drawdown halt of strategies and vaults.
+
+use anchor_lang::prelude::*;
+
+pub mod governance;
+pub mod risk;
+pub mod versioned;
+
+pub use governance::{governance_native_treasury, is_admin};
+pub use risk::{DrawdownTracker, MAX_DRAWDOWN_LIMIT_BPS};
+pub use versioned::{migrate, Versioned};
+
+pub use vectai_math::rewards::REWARD_PRECISION;
+pub use vectai_math::shares::{VIRTUAL_ASSETS, VIRTUAL_SHARES};
+pub use vectai_math::{bps_of, drawdown_bps, volatility_bps, MathError, PoolPricing, BPS_DENOMINATOR};
+
+/// Failures of the shared helpers. Codes start at 9000, clear of every
+/// program's own errors, so a code means the same whichever program
+/// returned it.
+#[error_code(offset = 9000)]
+pub enum CommonError {
+    #[msg("Math overflow")]
+    MathOverflow,
+
+    #[msg("Basis points above 100%, or a non-positive or unusable price")]
+    InvalidInput,
+
+    #[msg("Division by zero")]
+    DivisionByZero,
+
+    #[msg("Account layout version is not supported by this program")]
+    UnsupportedVersion,
+}
+
+impl From<MathError> for CommonError {
+    fn from(error: MathError) -> Self {
+        match error {
+            MathError::InvalidInput => CommonError::InvalidInput,
+            MathError::Overflow => CommonError::MathOverflow,
+        }
+    }
+}
+
+/// `expected_amount` less `slippage_bps`, the floor passed to the AMM
+pub fn minimum_amount_out(expected_amount: u64, slippage_bps: u64) -> Result<u64> {
+    vectai_math::minimum_amount_out(expected_amount, slippage_bps).map_err(|e| CommonError::from(e).into())
+}
+
+/// Slippage of `base_bps` plus `conf_factor_bps` of the oracle's
+/// confidence-to-price ratio, clamped to `0..=max_bps`
+pub fn dynamic_slippage_bps(base_bps: u64, conf_factor_bps: i64, price: i64, conf: u64, max_bps: u64) -> Result<u64> {
+    vectai_math::dynamic_slippage_bps(base_bps, conf_factor_bps, price, conf, max_bps)
+        .map_err(|e| CommonError::from(e).into())
+}
+
+/// Value of `output` (destination-token units) in source-token units at an
+/// oracle `price` with exponent `expo`, for the USDC/SOL pair
+pub fn value_in_source_units(output: u64, source_is_usdc: bool, price: i64, expo: i32) -> Result<u64> {
+    vectai_math::value_in_source_units(output, source_is_usdc, price, expo).map_err(|e| CommonError::from(e).into())
+}
+
+/// Output of swapping `amount_in` on a venue priced by `pricing`, after
+/// its `fee_bps` on the input
+pub fn expected_amount_out(amount_in: u64, source_is_usdc: bool, pricing: PoolPricing, fee_bps: u64) -> Result<u64> {
+    vectai_math::expected_amount_out(amount_in, source_is_usdc, pricing, fee_bps).map_err(|e| CommonError::from(e).into())
+}
+
+/// `value_out - value_in` as a signed amount: the realized PnL of an
+/// execution whose two legs are marked in the same unit
+pub fn realized_pnl(value_in: u64, value_out: u64) -> Result<i64> {
+    i64::try_from(value_out as i128 - value_in as i128).map_err(|_| CommonError::MathOverflow.into())
+}
+
+/// `amount * numerator / denominator`, rounded down, without intermediate
+/// overflow
+pub fn mul_div(amount: u64, numerator: u64, denominator: u64) -> Result<u64> {
+    require!(denominator > 0, CommonError::DivisionByZero);
+    let value = amount as u128 * numerator as u128 / denominator as u128;
+    u64::try_from(value).map_err(|_| CommonError::MathOverflow.into())
+}
+
+/// Shares minted for `amount` deposited into a fund worth `nav`, counting
+/// the virtual shares and assets in both
+pub fn shares_for_deposit(amount: u64, supply: u64, nav: u64) -> Result<u64> {
+    vectai_math::shares::shares_for_deposit(amount, supply, nav).map_err(|e| CommonError::from(e).into())
+}
+
+/// Slice of `balance` paid for burning `shares` of `supply`, counting the
+/// virtual shares and `virtual_assets`; never above the pro-rata slice
+pub fn redemption_amount(balance: u64, virtual_assets: u64, shares: u64, supply: u64) -> Result<u64> {
+    vectai_math::shares::redemption_amount(balance, virtual_assets, shares, supply)
+        .map_err(|e| CommonError::from(e).into())
+}
+
+/// Accumulator increase from distributing `reward` across `total_weight`
+pub fn reward_per_share_delta(reward: u128, total_weight: u64) -> Result<u128> {
+    vectai_math::rewards::reward_per_share_delta(reward, total_weight).map_err(|e| CommonError::from(e).into())
+}
+
+/// `weight`'s reward debt when checkpointed at `acc_reward_per_share`
+pub fn reward_debt(weight: u64, acc_reward_per_share: u128) -> Result<u128> {
+    vectai_math::rewards::reward_debt(weight, acc_reward_per_share).map_err(|e| CommonError::from(e).into())
+}
+
+/// Add what `weight` earned since its checkpoint at `reward_debt` to
+/// `pending_rewards`
+pub fn settle_rewards(weight: u64, acc_reward_per_share: u128, reward_debt: u128, pending_rewards: &mut u64) -> Result<()> {
+    let earned = vectai_math::rewards::earned_rewards(weight, acc_reward_per_share, reward_debt)
+        .map_err(CommonError::from)?;
+    *pending_rewards = pending_rewards.checked_add(earned).ok_or(CommonError::MathOverflow)?;
+    Ok(())
+}
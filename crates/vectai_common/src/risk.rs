@@ -0,0 +1,84 @@
+//! Max-drawdown halt shared by the trader's strategies and the vault.
+
+use anchor_lang::prelude::*;
+
+/// Highest configurable drawdown limit (90%)
+pub const MAX_DRAWDOWN_LIMIT_BPS: u16 = 9_000;
+
+/// Peak-to-trough drawdown of an equity mark (a strategy's holdings or a
+/// vault's share price), updated at each trade. Once the limit is exceeded
+/// trading stays blocked until the owner or manager resets.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Default)]
+pub struct DrawdownTracker {
+    pub max_drawdown_bps: u16, // 0 = no limit
+    pub peak_equity: u64, // 0 = re-baseline at the next mark
+    pub last_drawdown_bps: u16,
+    pub halted: bool,
+}
+
+impl DrawdownTracker {
+    pub const LEN: usize = 2 + // max_drawdown_bps
+        8 +  // peak_equity
+        2 +  // last_drawdown_bps
+        1;   // halted
+
+    /// Record `equity`; returns true if this mark breached the limit
+    pub fn mark(&mut self, equity: u64) -> bool {
+        if equity >= self.peak_equity {
+            self.peak_equity = equity;
+            self.last_drawdown_bps = 0;
+            return false;
+        }
+
+        self.last_drawdown_bps = crate::drawdown_bps(self.peak_equity, equity);
+        if self.max_drawdown_bps > 0 && self.last_drawdown_bps > self.max_drawdown_bps {
+            self.halted = true;
+        }
+        self.halted
+    }
+
+    /// Scale the peak by the deposits and withdrawals since the last mark,
+    /// so only trading losses count: `expected` is what the holdings last
+    /// marked are worth now and `equity` what is actually held. With
+    /// nothing marked before, the peak re-baselines at the next mark.
+    pub fn rebase(&mut self, expected: u64, equity: u64) {
+        if expected == equity {
+            return;
+        }
+        self.peak_equity = match expected {
+            0 => 0,
+            _ => (self.peak_equity as u128 * equity as u128 / expected as u128).min(u64::MAX as u128) as u64,
+        };
+    }
+
+    /// Clear the halt and re-baseline the peak at the next mark
+    pub fn reset(&mut self) {
+        self.peak_equity = 0;
+        self.last_drawdown_bps = 0;
+        self.halted = false;
+    }
+}
+
+/// Define the `DrawdownLimitHit` and `DrawdownReset` events in the calling
+/// program, which needs `anchor_lang::prelude::*` in scope. They are
+/// expanded there rather than defined here so they land in that program's
+/// IDL.
+#[macro_export]
+macro_rules! drawdown_events {
+    () => {
+        #[event]
+        pub struct DrawdownLimitHit {
+            pub account: Pubkey, // TraderConfig or Vault
+            pub peak_equity: u64,
+            pub equity: u64,
+            pub drawdown_bps: u16,
+            pub max_drawdown_bps: u16,
+        }
+
+        #[event]
+        pub struct DrawdownReset {
+            pub account: Pubkey,
+            pub max_drawdown_bps: u16,
+        }
+    };
+}
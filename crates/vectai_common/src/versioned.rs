@@ -0,0 +1,91 @@
+//! Versioned account layouts and their one migration path.
+//!
+//! A versioned account ends in a `version: u8` written by each `init`, and
+//! new fields are only ever appended after it. An account written by an
+//! older build is then a prefix of the current layout: [`migrate`] grows it
+//! to the current size with zeroed bytes, so the appended fields (and the
+//! `version` of accounts that predate it) read as zero, and lets
+//! [`Versioned::upgrade`] fill them in before the version is bumped.
+
+use anchor_lang::{
+    prelude::*,
+    system_program::{self, Transfer},
+    Discriminator,
+};
+
+use crate::CommonError;
+
+/// An account whose layout carries a version
+pub trait Versioned: AccountSerialize + AccountDeserialize + Discriminator + Owner {
+    /// Layout written by this build
+    const VERSION: u8;
+
+    /// Account size of that layout, discriminator included
+    const SPACE: usize;
+
+    fn version(&self) -> u8;
+
+    fn set_version(&mut self, version: u8);
+
+    /// Set the fields appended since layout `from`, which read as zero
+    fn upgrade(&mut self, from: u8) -> Result<()> {
+        let _ = from;
+        Ok(())
+    }
+}
+
+/// Bring `account` up to `T::VERSION` in place, paying any extra rent from
+/// `payer`, and return the version it was at. Accounts already current are
+/// left untouched; accounts written by a newer build are rejected.
+pub fn migrate<'info, T: Versioned>(
+    account: &AccountInfo<'info>,
+    payer: &AccountInfo<'info>,
+    system_program: &AccountInfo<'info>,
+) -> Result<u8> {
+    require_keys_eq!(*account.owner, T::owner(), ErrorCode::AccountOwnedByWrongProgram);
+    {
+        // Never resize an account of another type
+        let data = account.try_borrow_data()?;
+        require!(data.len() >= 8, ErrorCode::AccountDiscriminatorNotFound);
+        require!(data[..8] == T::DISCRIMINATOR, ErrorCode::AccountDiscriminatorMismatch);
+    }
+
+    grow(account, payer, system_program, T::SPACE)?;
+    let mut state = T::try_deserialize(&mut &account.try_borrow_data()?[..])?;
+    let from = state.version();
+    require!(from <= T::VERSION, CommonError::UnsupportedVersion);
+    if from < T::VERSION {
+        state.upgrade(from)?;
+        state.set_version(T::VERSION);
+        state.try_serialize(&mut &mut account.try_borrow_mut_data()?[..])?;
+    }
+    Ok(from)
+}
+
+/// Resize `account` to at least `space` bytes, zeroing the new ones and
+/// topping it up to rent exemption from `payer`
+pub fn grow<'info>(
+    account: &AccountInfo<'info>,
+    payer: &AccountInfo<'info>,
+    system_program: &AccountInfo<'info>,
+    space: usize,
+) -> Result<()> {
+    if account.data_len() >= space {
+        return Ok(());
+    }
+    let shortfall = Rent::get()?.minimum_balance(space).saturating_sub(account.lamports());
+    if shortfall > 0 {
+        system_program::transfer(
+            CpiContext::new(
+                system_program.clone(),
+                Transfer {
+                    from: payer.clone(),
+                    to: account.clone(),
+                },
+            ),
+            shortfall,
+        )?;
+    }
+    account.realloc(space, true)?;
+    Ok(())
+}
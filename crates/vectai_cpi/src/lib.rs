@@ -0,0 +1,48 @@
+//! CPI interface to the VECT.AI programs for downstream Anchor programs.
+//!
+//! Each program's `cpi::accounts` structs, instruction clients, account,
+//! event and argument types are generated from its IDL in `idls/`, so an
+//! integrator depends on this crate alone instead of copying account
+//! definitions or building the programs themselves. [`trader`],
+//! [`swapper`], [`token`] and [`oracle`] add typed clients for the view
+//! instructions, decoding their return data into `Result`s rather than
+//! the generated `Return::get`, which panics without it, plus the PDAs
+//! callers pass.
+
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::program::get_return_data;
+
+// Strategies, executions and their views
+declare_program!(vectai_trader);
+// Whitelisted pool swaps and quotes
+declare_program!(raydium_swapper);
+// VECTAI mint, staking and supply views
+declare_program!(vectai_token);
+// Pyth, Lazer and aggregated prices
+declare_program!(vectai_oracle);
+
+pub mod oracle;
+pub mod swapper;
+pub mod token;
+pub mod trader;
+
+/// Failures of the typed clients. Codes start at 9100, clear of the
+/// programs' own errors and the shared ones in `vectai_common`.
+#[error_code(offset = 9100)]
+pub enum CpiError {
+    #[msg("The invoked program set no return data")]
+    MissingReturnData,
+
+    #[msg("Return data did not decode as the view's type")]
+    InvalidReturnData,
+}
+
+/// Return data the view just invoked on `program_id` set, decoded as `T`
+fn returned<T: AnchorDeserialize>(program_id: &Pubkey) -> Result<T> {
+    match get_return_data() {
+        Some((program, data)) if program == *program_id => {
+            T::deserialize(&mut data.as_slice()).map_err(|_| error!(CpiError::InvalidReturnData))
+        }
+        _ => err!(CpiError::MissingReturnData),
+    }
+}
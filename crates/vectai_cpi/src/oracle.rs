@@ -0,0 +1,57 @@
+//! Typed clients for `vectai_oracle` views.
+
+use anchor_lang::prelude::*;
+
+use crate::vectai_oracle::{
+    cpi,
+    types::{AggregatedPrice, LazerPrice, PolicyPrice, PriceData},
+};
+
+pub fn get_price<'info>(ctx: CpiContext<'_, '_, '_, 'info, cpi::accounts::GetPrice<'info>>) -> Result<PriceData> {
+    let program_id = ctx.program.key();
+    cpi::get_price(ctx)?;
+    crate::returned(&program_id)
+}
+
+pub fn get_inverse_price<'info>(
+    ctx: CpiContext<'_, '_, '_, 'info, cpi::accounts::GetInversePrice<'info>>,
+) -> Result<PriceData> {
+    let program_id = ctx.program.key();
+    cpi::get_inverse_price(ctx)?;
+    crate::returned(&program_id)
+}
+
+pub fn get_aggregated_price<'info>(
+    ctx: CpiContext<'_, '_, '_, 'info, cpi::accounts::GetAggregatedPrice<'info>>,
+) -> Result<AggregatedPrice> {
+    let program_id = ctx.program.key();
+    cpi::get_aggregated_price(ctx)?;
+    crate::returned(&program_id)
+}
+
+pub fn get_price_with_policy<'info>(
+    ctx: CpiContext<'_, '_, '_, 'info, cpi::accounts::GetPriceWithPolicy<'info>>,
+) -> Result<PolicyPrice> {
+    let program_id = ctx.program.key();
+    cpi::get_price_with_policy(ctx)?;
+    crate::returned(&program_id)
+}
+
+/// Price of `feed_id` in a signed Lazer `update` at most `max_age_us` old,
+/// whose Ed25519 check must precede the caller's instruction
+pub fn get_lazer_price<'info>(
+    ctx: CpiContext<'_, '_, '_, 'info, cpi::accounts::GetLazerPrice<'info>>,
+    update: Vec<u8>,
+    feed_id: u32,
+    max_age_us: u64,
+) -> Result<LazerPrice> {
+    let program_id = ctx.program.key();
+    cpi::get_lazer_price(ctx, update, feed_id, max_age_us)?;
+    crate::returned(&program_id)
+}
+
+pub fn monitor_price<'info>(ctx: CpiContext<'_, '_, '_, 'info, cpi::accounts::MonitorPrice<'info>>) -> Result<PriceData> {
+    let program_id = ctx.program.key();
+    cpi::monitor_price(ctx)?;
+    crate::returned(&program_id)
+}
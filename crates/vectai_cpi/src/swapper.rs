@@ -0,0 +1,29 @@
+//! Typed clients for `raydium_swapper` views.
+
+use anchor_lang::prelude::*;
+
+use crate::raydium_swapper::{
+    cpi,
+    types::{PoolEntry, QuotePair, SwapQuote},
+};
+
+/// Addresses, mints and reserves of the whitelisted pool
+pub fn get_pool_entry<'info>(
+    ctx: CpiContext<'_, '_, '_, 'info, cpi::accounts::GetPoolEntry<'info>>,
+) -> Result<PoolEntry> {
+    let program_id = ctx.program.key();
+    cpi::get_pool_entry(ctx)?;
+    crate::returned(&program_id)
+}
+
+/// Expected output, fees and price impact of swapping `amount_in` of
+/// `pair.input_mint` at the pool's live reserves
+pub fn get_quote<'info>(
+    ctx: CpiContext<'_, '_, '_, 'info, cpi::accounts::GetQuote<'info>>,
+    pair: QuotePair,
+    amount_in: u64,
+) -> Result<SwapQuote> {
+    let program_id = ctx.program.key();
+    cpi::get_quote(ctx, pair, amount_in)?;
+    crate::returned(&program_id)
+}
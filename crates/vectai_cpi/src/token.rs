@@ -0,0 +1,27 @@
+//! Typed clients for `vectai_token` views.
+
+use anchor_lang::prelude::*;
+
+use crate::vectai_token::{accounts::TokenInfo, cpi, types::CirculatingSupply};
+
+/// Address of the `TokenInfo` of `mint`
+pub fn token_info(mint: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(&[b"token-info", mint.as_ref()], &crate::vectai_token::ID).0
+}
+
+pub fn get_token_info<'info>(
+    ctx: CpiContext<'_, '_, '_, 'info, cpi::accounts::GetTokenInfo<'info>>,
+) -> Result<TokenInfo> {
+    let program_id = ctx.program.key();
+    cpi::get_token_info(ctx)?;
+    crate::returned(&program_id)
+}
+
+/// Supply net of burns and the registered vesting and escrow locks
+pub fn get_circulating_supply<'info>(
+    ctx: CpiContext<'_, '_, '_, 'info, cpi::accounts::GetCirculatingSupply<'info>>,
+) -> Result<CirculatingSupply> {
+    let program_id = ctx.program.key();
+    cpi::get_circulating_supply(ctx)?;
+    crate::returned(&program_id)
+}
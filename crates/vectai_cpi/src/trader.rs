@@ -0,0 +1,19 @@
+//! Typed clients for `vectai_trader` views.
+
+use anchor_lang::prelude::*;
+
+use crate::vectai_trader::{accounts::TraderConfig, cpi};
+
+/// Address of `authority`'s `TraderConfig`; for a strategy created with
+/// `create_program_strategy`, `authority` is the caller's signing PDA
+pub fn trader_config(authority: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(&[b"trader", authority.as_ref()], &crate::vectai_trader::ID).0
+}
+
+pub fn get_trader_config<'info>(
+    ctx: CpiContext<'_, '_, '_, 'info, cpi::accounts::GetTraderConfig<'info>>,
+) -> Result<TraderConfig> {
+    let program_id = ctx.program.key();
+    cpi::get_trader_config(ctx)?;
+    crate::returned(&program_id)
+}
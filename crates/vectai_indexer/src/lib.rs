@@ -0,0 +1,431 @@
+//! Typed decoding of VECT.AI program events.
+//!
+//! Anchor's `emit!` writes each event as a `Program data: <base64>` log
+//! line: an 8-byte discriminator followed by the Borsh-encoded struct.
+//! [`parse_logs`] walks a transaction's logs, tracks which program is
+//! executing (events emitted inside a CPI belong to the callee) and
+//! decodes every event it recognises into [`VectaiEvent`]. [`subscribe`]
+//! streams them live from an RPC websocket.
+//!
+//! Every decoder checks the discriminator before deserializing; the `try_`
+//! forms and [`decode_event`] report why a payload was rejected.
+
+use std::fmt;
+
+use anchor_lang::prelude::Pubkey;
+use anchor_lang::{AnchorDeserialize, Discriminator};
+use base64::{engine::general_purpose::STANDARD, Engine};
+use solana_transaction_status::EncodedConfirmedTransactionWithStatusMeta;
+
+mod stream;
+pub use stream::*;
+
+const PROGRAM_DATA: &str = "Program data: ";
+
+/// Why a `Program data` payload did not decode
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum DecodeError {
+    /// Shorter than the 8-byte discriminator
+    TooShort,
+    /// Not emitted by a VECT.AI program
+    UnknownProgram(Pubkey),
+    /// No expected event has this discriminator
+    UnknownDiscriminator([u8; 8]),
+    /// The discriminator of the named event, with a body that does not
+    /// deserialize as it
+    Malformed(&'static str),
+}
+
+impl fmt::Display for DecodeError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            DecodeError::TooShort => write!(f, "event data shorter than its discriminator"),
+            DecodeError::UnknownProgram(program_id) => write!(f, "{} is not a VECT.AI program", program_id),
+            DecodeError::UnknownDiscriminator(discriminator) => {
+                write!(f, "unknown event discriminator {:?}", discriminator)
+            }
+            DecodeError::Malformed(name) => write!(f, "malformed {} event", name),
+        }
+    }
+}
+
+impl std::error::Error for DecodeError {}
+
+fn split_discriminator(data: &[u8]) -> Result<([u8; 8], &[u8]), DecodeError> {
+    if data.len() < 8 {
+        return Err(DecodeError::TooShort);
+    }
+    let (discriminator, body) = data.split_at(8);
+    Ok((discriminator.try_into().unwrap(), body))
+}
+
+/// Decode one event of type `T`, e.g. `decode_event::<TradeExecuted>(..)`,
+/// rejecting payloads with another event's discriminator
+pub fn decode_event<T: AnchorDeserialize + Discriminator>(data: &[u8]) -> Result<T, DecodeError> {
+    let (discriminator, body) = split_discriminator(data)?;
+    if discriminator != T::DISCRIMINATOR {
+        return Err(DecodeError::UnknownDiscriminator(discriminator));
+    }
+    let name = std::any::type_name::<T>().rsplit("::").next().unwrap_or_default();
+    T::try_from_slice(body).map_err(|_| DecodeError::Malformed(name))
+}
+
+/// Declares a per-program event enum and its discriminator lookup
+macro_rules! program_events {
+    ($(#[$doc:meta])* $name:ident, $program:ident { $($event:ident),* $(,)? }) => {
+        $(#[$doc])*
+        pub enum $name {
+            $($event($program::$event),)*
+        }
+
+        impl $name {
+            fn try_decode(discriminator: [u8; 8], body: &[u8]) -> Result<Self, DecodeError> {
+                $(
+                    if discriminator == <$program::$event as Discriminator>::DISCRIMINATOR {
+                        return $program::$event::try_from_slice(body)
+                            .map($name::$event)
+                            .map_err(|_| DecodeError::Malformed(stringify!($event)));
+                    }
+                )*
+                Err(DecodeError::UnknownDiscriminator(discriminator))
+            }
+
+            /// Event struct name, e.g. for metrics labels
+            pub fn name(&self) -> &'static str {
+                match self {
+                    $($name::$event(_) => stringify!($event),)*
+                }
+            }
+        }
+    };
+}
+
+program_events!(
+    /// Strategy executions and their PnL, copy trading, subscriptions, risk,
+    /// points and audit log entries
+    TraderEvent, vectai_trader {
+        TradeExecuted,
+        TradePreview,
+        TradeCompounded,
+        TakeProfitFilled,
+        TradeCopied,
+        SubscriptionPaid,
+        DrawdownLimitHit,
+        DrawdownReset,
+        VolatilityLimitHit,
+        HedgeRebalanced,
+        PointsCredited,
+        PointsRedeemed,
+        AuditEntryAppended,
+        JitoTipPaid,
+        PnlRealized,
+        StrategyCompleted,
+        ProgramStrategyCreated,
+    }
+);
+
+program_events!(
+    /// Raydium swaps routed through the swapper
+    SwapperEvent, raydium_swapper {
+        SwapExecuted,
+        SwapCommitted,
+    }
+);
+
+program_events!(
+    /// Epoch mints, auctions, burns and compliance actions on the VECTAI token
+    TokenEvent, vectai_token {
+        EpochMinted,
+        RewardsDistributed,
+        InterestRateUpdated,
+        AccountFlagged,
+        TokensClawedBack,
+        ClawbackRenounced,
+        AuctionCreated,
+        AuctionPurchase,
+        BurnRateUpdated,
+        TransferBurned,
+        MaxSupplyRaised,
+        StakeLocked,
+        StakeUnlocked,
+        SupplyLockRegistered,
+        SupplyLockDeregistered,
+    }
+);
+
+program_events!(
+    /// Pauses, protocol-wide configuration, exposure caps and role changes
+    ProtocolEvent, vectai_protocol {
+        ProtocolPauseChanged,
+        NetworkConfigUpdated,
+        FeeConfigUpdated,
+        UpgradeAuthorityAttested,
+        LookupTableUpdated,
+        PairVenuesUpdated,
+        AccountBundleUpdated,
+        ExposureCapUpdated,
+        RoleGranted,
+        RoleRevoked,
+    }
+);
+
+program_events!(
+    /// Pooled vault deposits, withdrawals, trade PnL, fees and lending
+    VaultEvent, vectai_vault {
+        Deposited,
+        Withdrawn,
+        WithdrawalRequested,
+        WithdrawalsProcessed,
+        VaultFeesUpdated,
+        FeesAccrued,
+        IdleFundsSupplied,
+        IdleFundsRecalled,
+        DrawdownLimitHit,
+        DrawdownReset,
+        DepositCapsUpdated,
+        PnlRealized,
+        StrategyRegistered,
+        AllocationsUpdated,
+        AllocationsRebalanced,
+    }
+);
+
+program_events!(
+    /// Index token mints, redemptions and rebalances
+    IndexEvent, vectai_index {
+        IndexMinted,
+        IndexRedeemed,
+        IndexRebalanced,
+    }
+);
+
+program_events!(
+    /// Conditional orders and the keeper registry
+    OrderbookEvent, vectai_orderbook {
+        OrderPlaced,
+        OrderFilled,
+        OrdersLinked,
+        LinkedOrderCancelled,
+        OrderCancelled,
+        OrdersForceCancelled,
+        KeeperBonded,
+        KeeperUnbonding,
+        KeeperSlashed,
+    }
+);
+
+program_events!(
+    /// Treasury spends, payment streams and protocol-owned liquidity
+    TreasuryEvent, vectai_treasury {
+        SpendProposed,
+        SpendApproved,
+        SpendExecuted,
+        StreamCreated,
+        StreamWithdrawn,
+        StreamCancelled,
+        LiquidityDeployed,
+        LiquidityWithdrawn,
+        StableConverted,
+    }
+);
+
+program_events!(
+    /// Liquidity mining
+    FarmEvent, vectai_farm {
+        EmissionsUpdated,
+        RewardsClaimed,
+    }
+);
+
+program_events!(
+    /// Referral payouts
+    ReferralEvent, vectai_referral {
+        ReferralFeesClaimed,
+    }
+);
+
+program_events!(
+    /// Timelocked operations
+    TimelockEvent, vectai_timelock {
+        OperationQueued,
+        OperationCancelled,
+        OperationExecuted,
+        BatchQueued,
+        BatchCancelled,
+        BatchExecuted,
+    }
+);
+
+program_events!(
+    /// Price monitoring and policy changes
+    OracleEvent, vectai_oracle {
+        AbnormalPriceMove,
+        PricePolicyUpdated,
+    }
+);
+
+program_events!(
+    /// Token-2022 compliance rules
+    TransferHookEvent, vectai_transfer_hook {
+        TransferLimitsUpdated,
+        OwnerRuleSet,
+        OwnerRuleCleared,
+    }
+);
+
+/// Any decoded VECT.AI event, grouped by emitting program
+pub enum VectaiEvent {
+    Trader(TraderEvent),
+    Swapper(SwapperEvent),
+    Token(TokenEvent),
+    Protocol(ProtocolEvent),
+    Vault(VaultEvent),
+    Index(IndexEvent),
+    Orderbook(OrderbookEvent),
+    Treasury(TreasuryEvent),
+    Farm(FarmEvent),
+    Referral(ReferralEvent),
+    Timelock(TimelockEvent),
+    Oracle(OracleEvent),
+    TransferHook(TransferHookEvent),
+}
+
+impl VectaiEvent {
+    /// Decode one `Program data` payload emitted by `program_id`. Returns
+    /// `None` for other programs and unknown or malformed events.
+    pub fn decode(program_id: &Pubkey, data: &[u8]) -> Option<Self> {
+        Self::try_decode(program_id, data).ok()
+    }
+
+    /// As [`decode`](Self::decode), with the reason a payload was rejected
+    pub fn try_decode(program_id: &Pubkey, data: &[u8]) -> Result<Self, DecodeError> {
+        let (discriminator, body) = split_discriminator(data)?;
+        let program_id = *program_id;
+        if program_id == vectai_trader::ID {
+            TraderEvent::try_decode(discriminator, body).map(VectaiEvent::Trader)
+        } else if program_id == raydium_swapper::ID {
+            SwapperEvent::try_decode(discriminator, body).map(VectaiEvent::Swapper)
+        } else if program_id == vectai_token::ID {
+            TokenEvent::try_decode(discriminator, body).map(VectaiEvent::Token)
+        } else if program_id == vectai_protocol::ID {
+            ProtocolEvent::try_decode(discriminator, body).map(VectaiEvent::Protocol)
+        } else if program_id == vectai_vault::ID {
+            VaultEvent::try_decode(discriminator, body).map(VectaiEvent::Vault)
+        } else if program_id == vectai_index::ID {
+            IndexEvent::try_decode(discriminator, body).map(VectaiEvent::Index)
+        } else if program_id == vectai_orderbook::ID {
+            OrderbookEvent::try_decode(discriminator, body).map(VectaiEvent::Orderbook)
+        } else if program_id == vectai_treasury::ID {
+            TreasuryEvent::try_decode(discriminator, body).map(VectaiEvent::Treasury)
+        } else if program_id == vectai_farm::ID {
+            FarmEvent::try_decode(discriminator, body).map(VectaiEvent::Farm)
+        } else if program_id == vectai_referral::ID {
+            ReferralEvent::try_decode(discriminator, body).map(VectaiEvent::Referral)
+        } else if program_id == vectai_timelock::ID {
+            TimelockEvent::try_decode(discriminator, body).map(VectaiEvent::Timelock)
+        } else if program_id == vectai_oracle::ID {
+            OracleEvent::try_decode(discriminator, body).map(VectaiEvent::Oracle)
+        } else if program_id == vectai_transfer_hook::ID {
+            TransferHookEvent::try_decode(discriminator, body).map(VectaiEvent::TransferHook)
+        } else {
+            Err(DecodeError::UnknownProgram(program_id))
+        }
+    }
+
+    /// Event struct name, e.g. `"TradeExecuted"`
+    pub fn name(&self) -> &'static str {
+        match self {
+            VectaiEvent::Trader(event) => event.name(),
+            VectaiEvent::Swapper(event) => event.name(),
+            VectaiEvent::Token(event) => event.name(),
+            VectaiEvent::Protocol(event) => event.name(),
+            VectaiEvent::Vault(event) => event.name(),
+            VectaiEvent::Index(event) => event.name(),
+            VectaiEvent::Orderbook(event) => event.name(),
+            VectaiEvent::Treasury(event) => event.name(),
+            VectaiEvent::Farm(event) => event.name(),
+            VectaiEvent::Referral(event) => event.name(),
+            VectaiEvent::Timelock(event) => event.name(),
+            VectaiEvent::Oracle(event) => event.name(),
+            VectaiEvent::TransferHook(event) => event.name(),
+        }
+    }
+}
+
+/// A decoded event and the program that emitted it
+pub struct DecodedEvent {
+    pub program_id: Pubkey,
+    pub event: VectaiEvent,
+}
+
+/// Decode every VECT.AI event in a transaction's log messages, in order
+pub fn parse_logs<S: AsRef<str>>(logs: &[S]) -> Vec<DecodedEvent> {
+    program_data(logs)
+        .into_iter()
+        .filter_map(|(program_id, data)| {
+            let event = VectaiEvent::decode(&program_id, &data)?;
+            Some(DecodedEvent { program_id, event })
+        })
+        .collect()
+}
+
+/// Events of type `T` that `program_id` emitted in a transaction's log
+/// messages, in order
+pub fn parse_events<T, S>(logs: &[S], program_id: &Pubkey) -> Vec<T>
+where
+    T: AnchorDeserialize + Discriminator,
+    S: AsRef<str>,
+{
+    program_data(logs)
+        .into_iter()
+        .filter(|(emitter, _)| emitter == program_id)
+        .filter_map(|(_, data)| decode_event(&data).ok())
+        .collect()
+}
+
+/// Events of a transaction fetched with `getTransaction`, or `None` when
+/// the response carries no log messages. A failed transaction has none,
+/// since its events were rolled back.
+pub fn transaction_events(transaction: &EncodedConfirmedTransactionWithStatusMeta) -> Option<Vec<DecodedEvent>> {
+    let meta = transaction.transaction.meta.as_ref()?;
+    let logs: Option<Vec<String>> = meta.log_messages.clone().into();
+    let logs = logs?;
+    if meta.err.is_some() {
+        return Some(Vec::new());
+    }
+    Some(parse_logs(&logs))
+}
+
+/// Every `Program data` payload with the program executing when it was
+/// logged
+fn program_data<S: AsRef<str>>(logs: &[S]) -> Vec<(Pubkey, Vec<u8>)> {
+    let mut invoked: Vec<Pubkey> = Vec::new();
+    let mut payloads = Vec::new();
+    for line in logs {
+        let line = line.as_ref();
+        if let Some(data) = line.strip_prefix(PROGRAM_DATA) {
+            let (Some(program_id), Ok(bytes)) = (invoked.last(), STANDARD.decode(data)) else {
+                continue;
+            };
+            payloads.push((*program_id, bytes));
+        } else if let Some(rest) = line.strip_prefix("Program ") {
+            // "Program <id> invoke [n]" / "Program <id> success" / "Program <id> failed: .."
+            let mut words = rest.split_whitespace();
+            let (Some(id), Some(status)) = (words.next(), words.next()) else {
+                continue;
+            };
+            match status {
+                "invoke" => {
+                    if let Ok(program_id) = id.parse() {
+                        invoked.push(program_id);
+                    }
+                }
+                "success" | "failed:" => {
+                    invoked.pop();
+                }
+                _ => {}
+            }
+        }
+    }
+    payloads
+}
@@ -0,0 +1,87 @@
+use solana_pubsub_client::pubsub_client::{LogsSubscription, PubsubClient, PubsubClientError};
+use solana_rpc_client_api::config::{RpcTransactionLogsConfig, RpcTransactionLogsFilter};
+use solana_sdk::commitment_config::CommitmentConfig;
+
+use crate::{parse_logs, DecodedEvent, Pubkey};
+
+/// Events decoded from one successful transaction
+pub struct TransactionEvents {
+    pub signature: String,
+    pub slot: u64,
+    pub events: Vec<DecodedEvent>,
+}
+
+/// Live log subscription for one program. Iterating blocks until the next
+/// transaction with at least one decoded event; it ends when the socket
+/// closes. Failed transactions are skipped since their events were rolled
+/// back.
+pub struct EventSubscription {
+    inner: LogsSubscription,
+}
+
+/// Subscribe to transactions mentioning `program_id` over the RPC
+/// websocket at `ws_url`
+pub fn subscribe(
+    ws_url: &str,
+    program_id: &Pubkey,
+    commitment: CommitmentConfig,
+) -> Result<EventSubscription, Box<PubsubClientError>> {
+    let inner = PubsubClient::logs_subscribe(
+        ws_url,
+        RpcTransactionLogsFilter::Mentions(vec![program_id.to_string()]),
+        RpcTransactionLogsConfig {
+            commitment: Some(commitment),
+        },
+    )
+    .map_err(Box::new)?;
+    Ok(EventSubscription { inner })
+}
+
+/// Subscribe and call `on_events` for every transaction with decoded
+/// events until the socket closes
+pub fn for_each_event<F>(
+    ws_url: &str,
+    program_id: &Pubkey,
+    commitment: CommitmentConfig,
+    mut on_events: F,
+) -> Result<(), Box<PubsubClientError>>
+where
+    F: FnMut(TransactionEvents),
+{
+    let mut subscription = subscribe(ws_url, program_id, commitment)?;
+    for events in subscription.by_ref() {
+        on_events(events);
+    }
+    subscription.unsubscribe()
+}
+
+impl EventSubscription {
+    /// Stop the subscription and close the socket
+    pub fn unsubscribe(&mut self) -> Result<(), Box<PubsubClientError>> {
+        self.inner.0.send_unsubscribe().map_err(Box::new)?;
+        let _ = self.inner.0.shutdown();
+        Ok(())
+    }
+}
+
+impl Iterator for EventSubscription {
+    type Item = TransactionEvents;
+
+    fn next(&mut self) -> Option<TransactionEvents> {
+        loop {
+            let response = self.inner.1.recv().ok()?;
+            if response.value.err.is_some() {
+                continue;
+            }
+            let events = parse_logs(&response.value.logs);
+            if events.is_empty() {
+                continue;
+            }
+            return Some(TransactionEvents {
+                signature: response.value.signature,
+                slot: response.context.slot,
+                events,
+            });
+        }
+    }
+}
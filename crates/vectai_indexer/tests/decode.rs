@@ -0,0 +1,142 @@
+//! Event decoding from real program logs, produced by `vectai_program_test`.
+
+use anchor_lang::{Discriminator, Event, InstructionData, ToAccountMetas};
+use solana_sdk::{
+    instruction::Instruction,
+    native_token::LAMPORTS_PER_SOL,
+    pubkey::Pubkey,
+    signature::{Keypair, Signer},
+    transaction::TransactionError,
+};
+use solana_transaction_status::{
+    EncodedConfirmedTransactionWithStatusMeta, EncodedTransaction, EncodedTransactionWithStatusMeta,
+    TransactionStatusMeta,
+};
+use vectai_indexer::{
+    decode_event, parse_events, parse_logs, transaction_events, DecodeError, TraderEvent, VectaiEvent,
+};
+use vectai_program_test::{trader::new_trader_config, ProgramTest};
+use vectai_trader::TradeExecuted;
+
+const SWAP_AMOUNT: u64 = 1_000_000;
+
+/// Logs of one successful `execute_trade` of `SWAP_AMOUNT`
+fn execute_trade_logs() -> Vec<String> {
+    let mut test = ProgramTest::new();
+    let network = test.create_trading_network(1_000_000_000_000);
+    let authority = Keypair::new();
+    test.airdrop(&authority.pubkey(), LAMPORTS_PER_SOL);
+    test.set_trader(&new_trader_config(authority.pubkey(), 40_000, SWAP_AMOUNT, 200));
+    let (source, destination) = (Pubkey::new_unique(), Pubkey::new_unique());
+    test.create_token_account(source, &network.usdc, &authority.pubkey(), SWAP_AMOUNT);
+    test.create_token_account(destination, &network.wsol, &authority.pubkey(), 0);
+    let ix = Instruction {
+        program_id: vectai_trader::ID,
+        accounts: network
+            .execute_trade_accounts(&authority.pubkey(), source, destination)
+            .to_account_metas(None),
+        data: vectai_trader::instruction::ExecuteTrade { amount: SWAP_AMOUNT, dry_run: false, nonce: None }.data(),
+    };
+    test.process_instruction(ix, &[&authority]).unwrap();
+    test.logs().to_vec()
+}
+
+fn trade_executed() -> TradeExecuted {
+    TradeExecuted {
+        trader: Pubkey::new_unique(),
+        source_mint: Pubkey::new_unique(),
+        destination_mint: Pubkey::new_unique(),
+        amount_in: SWAP_AMOUNT,
+        trade_fee: 0,
+        expected_out: SWAP_AMOUNT,
+        oracle_price: 45_000,
+        timestamp: 1_700_000_000,
+    }
+}
+
+fn confirmed(logs: Option<Vec<String>>, err: Option<TransactionError>) -> EncodedConfirmedTransactionWithStatusMeta {
+    let meta = TransactionStatusMeta {
+        status: err.map_or(Ok(()), Err),
+        log_messages: logs,
+        ..TransactionStatusMeta::default()
+    };
+    EncodedConfirmedTransactionWithStatusMeta {
+        slot: 1,
+        transaction: EncodedTransactionWithStatusMeta {
+            transaction: EncodedTransaction::LegacyBinary(String::new()),
+            meta: Some(meta.into()),
+            version: None,
+        },
+        block_time: None,
+    }
+}
+
+#[test]
+fn parse_logs_decodes_execute_trade() {
+    let logs = execute_trade_logs();
+
+    let events = parse_logs(&logs);
+    let executed: Vec<&TradeExecuted> = events
+        .iter()
+        .filter_map(|decoded| match &decoded.event {
+            VectaiEvent::Trader(TraderEvent::TradeExecuted(event)) => Some(event),
+            _ => None,
+        })
+        .collect();
+    assert_eq!(executed.len(), 1);
+    assert_eq!(executed[0].amount_in, SWAP_AMOUNT);
+    assert!(events.iter().all(|decoded| decoded.program_id == vectai_trader::ID));
+
+    let typed: Vec<TradeExecuted> = parse_events(&logs, &vectai_trader::ID);
+    assert_eq!(typed.len(), 1);
+    assert_eq!(typed[0].trader, executed[0].trader);
+    // Attributed to the emitting program only
+    assert!(parse_events::<TradeExecuted, _>(&logs, &raydium_swapper::ID).is_empty());
+}
+
+#[test]
+fn decoders_validate_discriminators() {
+    let event = trade_executed();
+    let data = event.data();
+
+    let decoded = decode_event::<TradeExecuted>(&data).unwrap();
+    assert_eq!((decoded.trader, decoded.amount_in), (event.trader, event.amount_in));
+    assert_eq!(
+        decode_event::<raydium_swapper::SwapExecuted>(&data).err(),
+        Some(DecodeError::UnknownDiscriminator(TradeExecuted::DISCRIMINATOR))
+    );
+    assert_eq!(decode_event::<TradeExecuted>(&data[..7]).err(), Some(DecodeError::TooShort));
+    assert_eq!(
+        decode_event::<TradeExecuted>(&data[..40]).err(),
+        Some(DecodeError::Malformed("TradeExecuted"))
+    );
+
+    assert_eq!(VectaiEvent::try_decode(&vectai_trader::ID, &data).unwrap().name(), "TradeExecuted");
+    assert_eq!(
+        VectaiEvent::try_decode(&vectai_trader::ID, &data[..40]).err(),
+        Some(DecodeError::Malformed("TradeExecuted"))
+    );
+    assert_eq!(
+        VectaiEvent::try_decode(&raydium_swapper::ID, &data).err(),
+        Some(DecodeError::UnknownDiscriminator(TradeExecuted::DISCRIMINATOR))
+    );
+    let stranger = Pubkey::new_unique();
+    assert_eq!(
+        VectaiEvent::try_decode(&stranger, &data).err(),
+        Some(DecodeError::UnknownProgram(stranger))
+    );
+}
+
+#[test]
+fn transaction_events_reads_confirmed_transactions() {
+    let logs = execute_trade_logs();
+
+    let events = transaction_events(&confirmed(Some(logs.clone()), None)).unwrap();
+    assert_eq!(events.len(), parse_logs(&logs).len());
+    assert!(events.iter().any(|decoded| decoded.event.name() == "TradeExecuted"));
+
+    // Rolled back with the transaction
+    let failed = confirmed(Some(logs), Some(TransactionError::AccountInUse));
+    assert_eq!(transaction_events(&failed).unwrap().len(), 0);
+    assert!(transaction_events(&confirmed(None, None)).is_none());
+}
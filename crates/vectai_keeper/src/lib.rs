@@ -0,0 +1,294 @@
+//! Keeper for VECT.AI strategy executions.
+//!
+//! [`Keeper`] sends `execute_trade` for a strategy's authority, with the
+//! pool accounts read from the pair's registered account bundle, through
+//! `vectai_client`'s priority-fee-aware [`TransactionBuilder`], reads the
+//! fill back from the confirmed transaction and records how the execution
+//! went in [`Metrics`]: outcome counts, latency to confirmation, realized
+//! slippage against the program's quote and the age of the oracle price.
+//! [`serve`] exposes them to Prometheus so operators can alert on degraded
+//! execution quality.
+//!
+//! RPC failures are retried under a [`RetryPolicy`], failing over to the
+//! next of the keeper's RPC endpoints each time. Every execution attempt
+//! is rebuilt on a fresh blockhash and simulated against the endpoint it
+//! is sent through first, so a trigger does not miss its window because
+//! one node stalled or a blockhash expired, while a program rejection
+//! still ends it at once.
+
+use std::{
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc,
+    },
+    thread,
+    time::{Duration, Instant, SystemTime, UNIX_EPOCH},
+};
+
+use anchor_lang::{AnchorDeserialize, InstructionData, ToAccountMetas};
+use base64::{engine::general_purpose::STANDARD, Engine};
+use solana_rpc_client::rpc_client::RpcClient;
+use solana_rpc_client_api::config::{RpcSimulateTransactionConfig, RpcTransactionConfig};
+use solana_sdk::{
+    commitment_config::CommitmentConfig,
+    instruction::Instruction,
+    message::Message,
+    pubkey::Pubkey,
+    signature::{Keypair, Signature, Signer},
+    transaction::Transaction,
+};
+use solana_transaction_status::{
+    option_serializer::OptionSerializer, EncodedConfirmedTransactionWithStatusMeta, UiTransactionEncoding,
+    UiTransactionTokenBalance,
+};
+use vectai_client::{fetch_account_bundle, ClientError, TransactionBuilder};
+use vectai_indexer::{transaction_events, TraderEvent, VectaiEvent};
+use vectai_oracle::PriceData;
+
+mod metrics;
+pub use metrics::*;
+mod retry;
+pub use retry::*;
+
+/// A confirmed execution and its fill
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Execution {
+    pub signature: Signature,
+    pub latency: Duration,
+    pub fill: Option<Fill>, // `None` when the transaction could not be read back
+}
+
+/// Quoted and received output of one execution
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Fill {
+    pub expected_out: u64, // From `TradeExecuted`
+    pub actual_out: u64,   // Destination token balance change
+}
+
+impl Fill {
+    pub fn slippage_bps(&self) -> i64 {
+        realized_slippage_bps(self.expected_out, self.actual_out)
+    }
+}
+
+/// Shortfall of `actual_out` against `expected_out` in basis points;
+/// negative when the fill beat the quote
+pub fn realized_slippage_bps(expected_out: u64, actual_out: u64) -> i64 {
+    if expected_out == 0 {
+        return 0;
+    }
+    ((expected_out as i128 - actual_out as i128) * 10_000 / expected_out as i128) as i64
+}
+
+/// Fill of the `execute_trade` in `transaction` that paid into `destination`
+pub fn execution_fill(transaction: &EncodedConfirmedTransactionWithStatusMeta, destination: &Pubkey) -> Option<Fill> {
+    let expected_out = transaction_events(transaction)?
+        .into_iter()
+        .find_map(|decoded| match decoded.event {
+            VectaiEvent::Trader(TraderEvent::TradeExecuted(event)) => Some(event.expected_out),
+            _ => None,
+        })?;
+
+    let meta = transaction.transaction.meta.as_ref()?;
+    let mut account_keys = transaction.transaction.transaction.decode()?.message.static_account_keys().to_vec();
+    if let OptionSerializer::Some(loaded) = &meta.loaded_addresses {
+        for address in loaded.writable.iter().chain(&loaded.readonly) {
+            account_keys.push(address.parse().ok()?);
+        }
+    }
+    let index = account_keys.iter().position(|key| key == destination)?;
+    let balance = |balances: &OptionSerializer<Vec<UiTransactionTokenBalance>>| {
+        let balances: Option<Vec<_>> = balances.clone().into();
+        balances?
+            .into_iter()
+            .find(|balance| balance.account_index as usize == index)
+            .and_then(|balance| balance.ui_token_amount.amount.parse::<u64>().ok())
+    };
+    // A destination created in the same transaction has no pre balance
+    let before = balance(&meta.pre_token_balances).unwrap_or_default();
+    let after = balance(&meta.post_token_balances)?;
+    Some(Fill {
+        expected_out,
+        actual_out: after.saturating_sub(before),
+    })
+}
+
+/// Sends executions for one strategy authority and records their quality
+pub struct Keeper {
+    rpcs: Vec<RpcClient>, // Primary first, then the fallbacks
+    active: AtomicUsize,  // Index into `rpcs` calls start from
+    retry: RetryPolicy,
+    authority: Keypair,
+    metrics: Arc<Metrics>,
+}
+
+impl Keeper {
+    /// Keeper on `rpc` alone, with the default [`RetryPolicy`]
+    pub fn new(rpc: RpcClient, authority: Keypair, metrics: Arc<Metrics>) -> Self {
+        Keeper {
+            rpcs: vec![rpc],
+            active: AtomicUsize::new(0),
+            retry: RetryPolicy::default(),
+            authority,
+            metrics,
+        }
+    }
+
+    /// Fail over to `rpcs`, in order, when the active endpoint errors
+    pub fn fallback_rpcs(mut self, rpcs: impl IntoIterator<Item = RpcClient>) -> Self {
+        self.rpcs.extend(rpcs);
+        self
+    }
+
+    pub fn retry_policy(mut self, retry: RetryPolicy) -> Self {
+        self.retry = retry;
+        self
+    }
+
+    pub fn metrics(&self) -> &Arc<Metrics> {
+        &self.metrics
+    }
+
+    /// Price `vectai_oracle::get_price` returns for `price_feed`, read by
+    /// simulation; records its age against the local clock
+    pub fn oracle_price(&self, price_feed: &Pubkey) -> Result<PriceData, ClientError> {
+        let instruction = Instruction {
+            program_id: vectai_oracle::ID,
+            accounts: vectai_oracle::accounts::GetPrice { price_feed: *price_feed }.to_account_metas(None),
+            data: vectai_oracle::instruction::GetPrice {}.data(),
+        };
+        let message = Message::new(&[instruction], Some(&self.authority.pubkey()));
+        let config = RpcSimulateTransactionConfig {
+            sig_verify: false,
+            replace_recent_blockhash: true,
+            ..RpcSimulateTransactionConfig::default()
+        };
+        let transaction = Transaction::new_unsigned(message);
+        let result = self
+            .with_retry(|rpc| Ok(rpc.simulate_transaction_with_config(&transaction, config.clone()).map_err(Box::new)?))?
+            .value;
+        if let Some(err) = result.err {
+            return Err(ClientError::Simulation {
+                err,
+                logs: result.logs.unwrap_or_default(),
+            });
+        }
+        let return_data = result.return_data.ok_or(ClientError::MissingReturnData)?;
+        let data = STANDARD.decode(&return_data.data.0).map_err(|_| ClientError::MissingReturnData)?;
+        let price = PriceData::try_from_slice(&data).map_err(|_| ClientError::MissingReturnData)?;
+
+        let now = SystemTime::now().duration_since(UNIX_EPOCH).map_or(0, |now| now.as_secs() as i64);
+        self.metrics.record_oracle_staleness(*price_feed, now - price.publish_time);
+        Ok(price)
+    }
+
+    /// `execute_trade` accounts for the keeper's authority swapping
+    /// `source` into `destination`, with the pool accounts read from the
+    /// account bundle registered for `base_mint`/`quote_mint`
+    pub fn execute_trade_accounts(
+        &self,
+        base_mint: &Pubkey,
+        quote_mint: &Pubkey,
+        source: Pubkey,
+        destination: Pubkey,
+        price_feed: Pubkey,
+    ) -> Result<vectai_trader::accounts::ExecuteTrade, ClientError> {
+        let bundle = self.with_retry(|rpc| fetch_account_bundle(rpc, base_mint, quote_mint))?;
+        Ok(vectai_client::execute_trade_accounts(
+            &bundle,
+            &self.authority.pubkey(),
+            source,
+            destination,
+            price_feed,
+        ))
+    }
+
+    /// Send `execute_trade` and wait for confirmation, retrying under the
+    /// keeper's policy. Before resending, the previous attempt's signature
+    /// is looked up in case it landed after all; pass a `nonce` so an
+    /// attempt landing later still cannot execute the trigger twice.
+    /// Failures are counted by outcome; a confirmed execution records its
+    /// latency and, once the transaction is read back, its realized
+    /// slippage.
+    pub fn execute_trade(
+        &self,
+        accounts: vectai_trader::accounts::ExecuteTrade,
+        amount: u64,
+        nonce: Option<u64>,
+    ) -> Result<Execution, ClientError> {
+        let destination = accounts.user_destination_token_account;
+        let instruction = Instruction {
+            program_id: vectai_trader::ID,
+            accounts: accounts.to_account_metas(None),
+            data: vectai_trader::instruction::ExecuteTrade {
+                amount,
+                dry_run: false,
+                nonce,
+            }
+            .data(),
+        };
+
+        let started = Instant::now();
+        let builder = TransactionBuilder::new(self.authority.pubkey()).instruction(instruction);
+        // Signature of the last attempt that was sent; it may land late
+        let mut pending: Option<Signature> = None;
+        let sent = self.with_retry(|rpc| {
+            if let Some(signature) = pending.take() {
+                if let Ok(Some(Ok(()))) = rpc.get_signature_status(&signature) {
+                    return Ok(signature);
+                }
+            }
+            let transaction = builder.build(rpc, &[&self.authority])?;
+            pending = Some(transaction.signatures[0]);
+            Ok(rpc.send_and_confirm_transaction(&transaction).map_err(Box::new)?)
+        });
+        let signature = match sent {
+            Ok(signature) => signature,
+            Err(err) => {
+                self.metrics.record_failure(&err);
+                return Err(err);
+            }
+        };
+        let latency = started.elapsed();
+
+        let fill = self
+            .fetch_transaction(&signature)
+            .and_then(|transaction| execution_fill(&transaction, &destination));
+        self.metrics.record_success(latency, fill.map(|fill| fill.slippage_bps()));
+        Ok(Execution {
+            signature,
+            latency,
+            fill,
+        })
+    }
+
+    fn fetch_transaction(&self, signature: &Signature) -> Option<EncodedConfirmedTransactionWithStatusMeta> {
+        let config = RpcTransactionConfig {
+            encoding: Some(UiTransactionEncoding::Base64),
+            commitment: Some(CommitmentConfig::confirmed()),
+            max_supported_transaction_version: Some(0),
+        };
+        self.with_retry(|rpc| Ok(rpc.get_transaction_with_config(signature, config).map_err(Box::new)?))
+            .ok()
+    }
+
+    /// Run `op` against the active RPC. While its errors are retryable and
+    /// attempts remain, the next RPC becomes active and `op` runs again
+    /// after the policy's backoff.
+    fn with_retry<T>(&self, mut op: impl FnMut(&RpcClient) -> Result<T, ClientError>) -> Result<T, ClientError> {
+        let mut attempt = 0;
+        loop {
+            let index = self.active.load(Ordering::Relaxed);
+            match op(&self.rpcs[index]) {
+                Ok(value) => return Ok(value),
+                Err(err) if attempt + 1 < self.retry.max_attempts && is_retryable(&err) => {
+                    self.active.store((index + 1) % self.rpcs.len(), Ordering::Relaxed);
+                    self.metrics.record_retry();
+                    thread::sleep(self.retry.backoff(attempt));
+                    attempt += 1;
+                }
+                Err(err) => return Err(err),
+            }
+        }
+    }
+}
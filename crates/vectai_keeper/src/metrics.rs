@@ -0,0 +1,227 @@
+use std::{
+    collections::BTreeMap,
+    fmt::Write as _,
+    io::{self, BufRead, BufReader, Write},
+    net::{SocketAddr, TcpListener, TcpStream, ToSocketAddrs},
+    sync::{Arc, Mutex},
+    thread::{self, JoinHandle},
+    time::Duration,
+};
+
+use solana_sdk::pubkey::Pubkey;
+use vectai_client::ClientError;
+
+/// Upper bounds of the execution latency buckets, in seconds
+pub const LATENCY_BUCKETS: &[f64] = &[0.5, 1.0, 2.0, 5.0, 10.0, 20.0, 30.0, 60.0];
+
+/// Upper bounds of the realized slippage buckets, in basis points; fills
+/// at or better than the quote land in the first
+pub const SLIPPAGE_BUCKETS: &[f64] = &[0.0, 10.0, 25.0, 50.0, 100.0, 200.0, 500.0, 1_000.0];
+
+/// How one execution ended
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Outcome {
+    /// Confirmed on-chain
+    Success,
+    /// Failed simulation: the program would reject it (cooldown, threshold,
+    /// slippage, pause)
+    Rejected,
+    /// Never confirmed: RPC, signing or confirmation failure
+    Error,
+}
+
+impl Outcome {
+    pub const ALL: [Outcome; 3] = [Outcome::Success, Outcome::Rejected, Outcome::Error];
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            Outcome::Success => "success",
+            Outcome::Rejected => "rejected",
+            Outcome::Error => "error",
+        }
+    }
+
+    pub fn of(error: &ClientError) -> Self {
+        match error {
+            ClientError::Simulation { .. } => Outcome::Rejected,
+            _ => Outcome::Error,
+        }
+    }
+}
+
+/// Cumulative-bucket histogram in the Prometheus layout
+#[derive(Clone, Debug)]
+struct Histogram {
+    bounds: &'static [f64],
+    counts: Vec<u64>, // Per bucket, not cumulative; the last is +Inf
+    sum: f64,
+    count: u64,
+}
+
+impl Histogram {
+    fn new(bounds: &'static [f64]) -> Self {
+        Histogram {
+            bounds,
+            counts: vec![0; bounds.len() + 1],
+            sum: 0.0,
+            count: 0,
+        }
+    }
+
+    fn observe(&mut self, value: f64) {
+        let bucket = self.bounds.iter().position(|bound| value <= *bound).unwrap_or(self.bounds.len());
+        self.counts[bucket] += 1;
+        self.sum += value;
+        self.count += 1;
+    }
+
+    fn render(&self, out: &mut String, name: &str) {
+        let mut cumulative = 0;
+        for (bound, count) in self.bounds.iter().zip(&self.counts) {
+            cumulative += count;
+            let _ = writeln!(out, "{}_bucket{{le=\"{}\"}} {}", name, bound, cumulative);
+        }
+        let _ = writeln!(out, "{}_bucket{{le=\"+Inf\"}} {}", name, self.count);
+        let _ = writeln!(out, "{}_sum {}", name, self.sum);
+        let _ = writeln!(out, "{}_count {}", name, self.count);
+    }
+}
+
+#[derive(Debug)]
+struct State {
+    executions: BTreeMap<Outcome, u64>,
+    retries: u64,
+    latency: Histogram,
+    slippage: Histogram,
+    oracle_staleness: BTreeMap<Pubkey, i64>,
+}
+
+/// Execution quality counters, shared between the keeper and the exporter
+#[derive(Debug)]
+pub struct Metrics {
+    state: Mutex<State>,
+}
+
+impl Default for Metrics {
+    fn default() -> Self {
+        Metrics {
+            state: Mutex::new(State {
+                executions: Outcome::ALL.into_iter().map(|outcome| (outcome, 0)).collect(),
+                retries: 0,
+                latency: Histogram::new(LATENCY_BUCKETS),
+                slippage: Histogram::new(SLIPPAGE_BUCKETS),
+                oracle_staleness: BTreeMap::new(),
+            }),
+        }
+    }
+}
+
+impl Metrics {
+    pub fn new() -> Arc<Self> {
+        Arc::new(Metrics::default())
+    }
+
+    /// A confirmed execution, `latency` after it started building, and its
+    /// slippage when the fill could be read back
+    pub fn record_success(&self, latency: Duration, slippage_bps: Option<i64>) {
+        let mut state = self.state.lock().unwrap();
+        *state.executions.entry(Outcome::Success).or_default() += 1;
+        state.latency.observe(latency.as_secs_f64());
+        if let Some(slippage_bps) = slippage_bps {
+            state.slippage.observe(slippage_bps as f64);
+        }
+    }
+
+    pub fn record_failure(&self, error: &ClientError) {
+        let mut state = self.state.lock().unwrap();
+        *state.executions.entry(Outcome::of(error)).or_default() += 1;
+    }
+
+    /// An RPC call or execution attempt that failed and was retried
+    pub fn record_retry(&self) {
+        self.state.lock().unwrap().retries += 1;
+    }
+
+    /// Seconds between `price_feed`'s publish time and when it was read
+    pub fn record_oracle_staleness(&self, price_feed: Pubkey, staleness_secs: i64) {
+        self.state.lock().unwrap().oracle_staleness.insert(price_feed, staleness_secs);
+    }
+
+    pub fn executions(&self, outcome: Outcome) -> u64 {
+        self.state.lock().unwrap().executions.get(&outcome).copied().unwrap_or_default()
+    }
+
+    pub fn retries(&self) -> u64 {
+        self.state.lock().unwrap().retries
+    }
+
+    /// Every metric in the Prometheus text exposition format
+    pub fn render(&self) -> String {
+        let state = self.state.lock().unwrap();
+        let mut out = String::new();
+
+        out.push_str("# HELP vectai_keeper_executions_total Trade executions by outcome\n");
+        out.push_str("# TYPE vectai_keeper_executions_total counter\n");
+        for (outcome, count) in &state.executions {
+            let _ = writeln!(out, "vectai_keeper_executions_total{{outcome=\"{}\"}} {}", outcome.label(), count);
+        }
+
+        out.push_str("# HELP vectai_keeper_retries_total RPC calls and execution attempts retried after a transient failure\n");
+        out.push_str("# TYPE vectai_keeper_retries_total counter\n");
+        let _ = writeln!(out, "vectai_keeper_retries_total {}", state.retries);
+
+        out.push_str("# HELP vectai_keeper_execution_latency_seconds From building an execution to its confirmation\n");
+        out.push_str("# TYPE vectai_keeper_execution_latency_seconds histogram\n");
+        state.latency.render(&mut out, "vectai_keeper_execution_latency_seconds");
+
+        out.push_str("# HELP vectai_keeper_realized_slippage_bps Shortfall of the filled amount against the quoted output\n");
+        out.push_str("# TYPE vectai_keeper_realized_slippage_bps histogram\n");
+        state.slippage.render(&mut out, "vectai_keeper_realized_slippage_bps");
+
+        out.push_str("# HELP vectai_keeper_oracle_staleness_seconds Age of the last price read from each feed\n");
+        out.push_str("# TYPE vectai_keeper_oracle_staleness_seconds gauge\n");
+        for (price_feed, staleness) in &state.oracle_staleness {
+            let _ = writeln!(out, "vectai_keeper_oracle_staleness_seconds{{price_feed=\"{}\"}} {}", price_feed, staleness);
+        }
+        out
+    }
+}
+
+/// Serve `GET /metrics` on `addr` from a background thread, returning the
+/// bound address (useful with port 0)
+pub fn serve(metrics: Arc<Metrics>, addr: impl ToSocketAddrs) -> io::Result<(SocketAddr, JoinHandle<()>)> {
+    let listener = TcpListener::bind(addr)?;
+    let local_addr = listener.local_addr()?;
+    let handle = thread::spawn(move || {
+        for stream in listener.incoming().flatten() {
+            // A client that hangs up mid-request only loses its own scrape
+            let _ = respond(&metrics, stream);
+        }
+    });
+    Ok((local_addr, handle))
+}
+
+fn respond(metrics: &Metrics, mut stream: TcpStream) -> io::Result<()> {
+    stream.set_read_timeout(Some(Duration::from_secs(5)))?;
+    let mut request_line = String::new();
+    let mut reader = BufReader::new(&stream);
+    reader.read_line(&mut request_line)?;
+    // Drain the headers up to the blank line
+    let mut header = String::new();
+    while reader.read_line(&mut header)? > 2 {
+        header.clear();
+    }
+
+    let (status, content_type, body) = match request_line.split_whitespace().take(2).collect::<Vec<_>>()[..] {
+        ["GET", "/metrics"] => ("200 OK", "text/plain; version=0.0.4", metrics.render()),
+        _ => ("404 Not Found", "text/plain", "not found\n".to_string()),
+    };
+    write!(
+        stream,
+        "HTTP/1.1 {}\r\nContent-Type: {}\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+        status,
+        content_type,
+        body.len(),
+        body
+    )
+}
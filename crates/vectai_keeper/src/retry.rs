@@ -0,0 +1,57 @@
+use std::time::Duration;
+
+use solana_sdk::transaction::TransactionError;
+use vectai_client::ClientError;
+
+/// How often, and how far apart, the keeper retries an RPC call or an
+/// execution that failed for reasons other than the program rejecting it
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct RetryPolicy {
+    pub max_attempts: u32, // Including the first; 1 = no retries
+    pub initial_backoff: Duration,
+    pub max_backoff: Duration, // Backoff doubles per retry up to this
+}
+
+impl Default for RetryPolicy {
+    /// Four attempts, 250ms apart doubling to at most 2s: about 2s in all,
+    /// well inside a blockhash's lifetime
+    fn default() -> Self {
+        RetryPolicy {
+            max_attempts: 4,
+            initial_backoff: Duration::from_millis(250),
+            max_backoff: Duration::from_secs(2),
+        }
+    }
+}
+
+impl RetryPolicy {
+    /// Fail on the first error
+    pub fn none() -> Self {
+        RetryPolicy {
+            max_attempts: 1,
+            ..RetryPolicy::default()
+        }
+    }
+
+    /// Wait before the retry following attempt `attempt` (0-based)
+    pub fn backoff(&self, attempt: u32) -> Duration {
+        self.initial_backoff
+            .checked_mul(1 << attempt.min(16))
+            .map_or(self.max_backoff, |backoff| backoff.min(self.max_backoff))
+    }
+}
+
+/// Whether another attempt, on a fresh blockhash and possibly another RPC,
+/// could succeed where this one failed. Transaction errors the program or
+/// runtime would raise again are final; an expired or unknown blockhash
+/// and RPC transport failures are not.
+pub fn is_retryable(error: &ClientError) -> bool {
+    match error {
+        ClientError::Rpc(error) => error
+            .get_transaction_error()
+            .is_none_or(|err| err == TransactionError::BlockhashNotFound),
+        ClientError::Simulation { err, .. } => *err == TransactionError::BlockhashNotFound,
+        ClientError::MissingUnitsConsumed => true,
+        _ => false,
+    }
+}
@@ -0,0 +1,332 @@
+//! Executions, retries and oracle reads against the RPC client's mock
+//! sender.
+
+use std::{collections::HashMap, time::Duration};
+
+use anchor_lang::{AnchorSerialize, Event};
+use base64::{engine::general_purpose::STANDARD, Engine};
+use serde_json::{json, Value};
+use solana_rpc_client::rpc_client::RpcClient;
+use solana_rpc_client_api::{client_error::ErrorKind as ClientErrorKind, request::RpcRequest};
+use solana_sdk::{
+    instruction::{AccountMeta, Instruction},
+    message::Message,
+    pubkey::Pubkey,
+    signature::{Keypair, Signer},
+    transaction::{Transaction, TransactionError},
+};
+use solana_transaction_status::{
+    Encodable, EncodedConfirmedTransactionWithStatusMeta, EncodedTransactionWithStatusMeta, TransactionStatusMeta,
+    UiTransactionEncoding,
+};
+use vectai_client::ClientError;
+use vectai_keeper::{execution_fill, is_retryable, realized_slippage_bps, Fill, Keeper, Metrics, Outcome, RetryPolicy};
+use vectai_oracle::PriceData;
+use vectai_trader::TradeExecuted;
+
+const EXPECTED_OUT: u64 = 1_000_000;
+
+fn execute_trade_accounts(authority: Pubkey, destination: Pubkey) -> vectai_trader::accounts::ExecuteTrade {
+    vectai_trader::accounts::ExecuteTrade {
+        user_authority: authority,
+        trader_config: Pubkey::new_unique(),
+        user_source_token_account: Pubkey::new_unique(),
+        user_destination_token_account: destination,
+        raydium_amm_program: Pubkey::new_unique(),
+        amm: Pubkey::new_unique(),
+        amm_authority: Pubkey::new_unique(),
+        amm_open_orders: Pubkey::new_unique(),
+        amm_target_orders: Pubkey::new_unique(),
+        pool_coin_token_account: Pubkey::new_unique(),
+        pool_pc_token_account: Pubkey::new_unique(),
+        serum_program: Pubkey::new_unique(),
+        serum_market: Pubkey::new_unique(),
+        serum_bids: Pubkey::new_unique(),
+        serum_asks: Pubkey::new_unique(),
+        serum_event_queue: Pubkey::new_unique(),
+        serum_coin_vault_account: Pubkey::new_unique(),
+        serum_pc_vault_account: Pubkey::new_unique(),
+        serum_vault_signer: Pubkey::new_unique(),
+        vectai_oracle_program: vectai_oracle::ID,
+        price_feed: Pubkey::new_unique(),
+        points_ledger: None,
+        points_config: None,
+        trade_history: None,
+        trade_history_tree: None,
+        compression_program: None,
+        noop_program: None,
+        protocol_fee_account: None,
+        token_program: Pubkey::new_unique(),
+        protocol_status: Pubkey::new_unique(),
+        network_config: Pubkey::new_unique(),
+        protocol_stats: Pubkey::new_unique(),
+        stats_reporter: Pubkey::new_unique(),
+        protocol_program: Pubkey::new_unique(),
+        instructions: Pubkey::new_unique(),
+    }
+}
+
+fn token_balance(account_index: u8, amount: u64) -> Value {
+    json!({
+        "accountIndex": account_index,
+        "mint": Pubkey::new_unique().to_string(),
+        "uiTokenAmount": {
+            "uiAmount": null,
+            "decimals": 9,
+            "amount": amount.to_string(),
+            "uiAmountString": amount.to_string(),
+        },
+    })
+}
+
+/// `getTransaction` response of an `execute_trade` quoted at `EXPECTED_OUT`
+/// that paid `received` into `destination`
+fn confirmed_execution(destination: Pubkey, received: u64) -> Value {
+    let payer = Pubkey::new_unique();
+    let instruction = Instruction {
+        program_id: vectai_trader::ID,
+        accounts: vec![AccountMeta::new(destination, false)],
+        data: Vec::new(),
+    };
+    let transaction = Transaction::new_unsigned(Message::new(&[instruction], Some(&payer)));
+    let event = TradeExecuted {
+        trader: Pubkey::new_unique(),
+        source_mint: Pubkey::new_unique(),
+        destination_mint: Pubkey::new_unique(),
+        amount_in: EXPECTED_OUT,
+        trade_fee: 0,
+        expected_out: EXPECTED_OUT,
+        oracle_price: 45_000,
+        timestamp: 1_700_000_000,
+    };
+    let logs = vec![
+        format!("Program {} invoke [1]", vectai_trader::ID),
+        format!("Program data: {}", STANDARD.encode(event.data())),
+        format!("Program {} success", vectai_trader::ID),
+    ];
+    let meta = TransactionStatusMeta {
+        log_messages: Some(logs),
+        ..TransactionStatusMeta::default()
+    };
+    let confirmed = EncodedConfirmedTransactionWithStatusMeta {
+        slot: 1,
+        transaction: EncodedTransactionWithStatusMeta {
+            transaction: transaction.encode(UiTransactionEncoding::Base64),
+            meta: Some(meta.into()),
+            version: None,
+        },
+        block_time: None,
+    };
+    // The destination is account 1, after the payer
+    let mut value = serde_json::to_value(confirmed).unwrap();
+    value["meta"]["preTokenBalances"] = json!([token_balance(1, 500)]);
+    value["meta"]["postTokenBalances"] = json!([token_balance(1, 500 + received)]);
+    value
+}
+
+fn rpc(mocks: impl IntoIterator<Item = (RpcRequest, Value)>) -> RpcClient {
+    RpcClient::new_mock_with_mocks("succeeds".to_string(), mocks.into_iter().collect::<HashMap<_, _>>())
+}
+
+fn simulation(err: Option<TransactionError>, return_data: Option<Value>) -> (RpcRequest, Value) {
+    let value = json!({
+        "context": { "slot": 1 },
+        "value": { "err": err, "logs": [], "unitsConsumed": 80_000, "returnData": return_data },
+    });
+    (RpcRequest::SimulateTransaction, value)
+}
+
+#[test]
+fn realized_slippage_is_the_shortfall_against_the_quote() {
+    assert_eq!(realized_slippage_bps(1_000_000, 990_000), 100);
+    assert_eq!(realized_slippage_bps(1_000_000, 1_000_000), 0);
+    assert_eq!(realized_slippage_bps(1_000_000, 1_002_000), -20);
+    assert_eq!(realized_slippage_bps(0, 5), 0);
+}
+
+#[test]
+fn execution_fill_reads_quote_and_balance_change() {
+    let destination = Pubkey::new_unique();
+    let confirmed: EncodedConfirmedTransactionWithStatusMeta =
+        serde_json::from_value(confirmed_execution(destination, 995_000)).unwrap();
+
+    let fill = execution_fill(&confirmed, &destination).unwrap();
+    assert_eq!(
+        fill,
+        Fill {
+            expected_out: EXPECTED_OUT,
+            actual_out: 995_000,
+        }
+    );
+    assert_eq!(fill.slippage_bps(), 50);
+    assert!(execution_fill(&confirmed, &Pubkey::new_unique()).is_none());
+}
+
+#[test]
+fn execute_trade_records_latency_and_slippage() {
+    let authority = Keypair::new();
+    let destination = Pubkey::new_unique();
+    let rpc = rpc([
+        simulation(None, None),
+        (RpcRequest::GetTransaction, confirmed_execution(destination, 990_000)),
+    ]);
+    let keeper = Keeper::new(rpc, authority.insecure_clone(), Metrics::new());
+
+    let execution = keeper
+        .execute_trade(execute_trade_accounts(authority.pubkey(), destination), EXPECTED_OUT, Some(7))
+        .unwrap();
+    assert_eq!(execution.fill.unwrap().slippage_bps(), 100);
+    let metrics = keeper.metrics();
+    assert_eq!(metrics.executions(Outcome::Success), 1);
+    let rendered = metrics.render();
+    assert!(rendered.contains("vectai_keeper_execution_latency_seconds_count 1\n"));
+    assert!(rendered.contains("vectai_keeper_realized_slippage_bps_sum 100\n"));
+}
+
+#[test]
+fn execute_trade_counts_rejections() {
+    let authority = Keypair::new();
+    let rpc = rpc([simulation(Some(TransactionError::AccountNotFound), None)]);
+    let keeper = Keeper::new(rpc, authority.insecure_clone(), Metrics::new());
+
+    let result = keeper.execute_trade(execute_trade_accounts(authority.pubkey(), Pubkey::new_unique()), 1, None);
+    assert!(matches!(result, Err(ClientError::Simulation { .. })));
+    assert_eq!(keeper.metrics().executions(Outcome::Rejected), 1);
+    assert_eq!(keeper.metrics().executions(Outcome::Success), 0);
+    assert!(keeper
+        .metrics()
+        .render()
+        .contains("vectai_keeper_execution_latency_seconds_count 0\n"));
+}
+
+#[test]
+fn oracle_price_records_staleness() {
+    let price = PriceData {
+        price: 45_000,
+        conf: 100,
+        expo: 0,
+        publish_time: 0,
+        slot: 0,
+    };
+    let return_data = json!({
+        "programId": vectai_oracle::ID.to_string(),
+        "data": [STANDARD.encode(price.try_to_vec().unwrap()), "base64"],
+    });
+    let keeper = Keeper::new(rpc([simulation(None, Some(return_data))]), Keypair::new(), Metrics::new());
+    let price_feed = Pubkey::new_unique();
+
+    let read = keeper.oracle_price(&price_feed).unwrap();
+    assert_eq!((read.price, read.conf, read.publish_time), (45_000, 100, 0));
+    let rendered = keeper.metrics().render();
+    let gauge = format!("vectai_keeper_oracle_staleness_seconds{{price_feed=\"{}\"}} ", price_feed);
+    let staleness: i64 = rendered
+        .lines()
+        .find_map(|line| line.strip_prefix(&gauge))
+        .unwrap()
+        .parse()
+        .unwrap();
+    // Published at the epoch, so as old as the local clock
+    assert!(staleness > 1_700_000_000);
+
+    let keeper = Keeper::new(rpc([simulation(None, None)]), Keypair::new(), Metrics::new());
+    assert!(matches!(keeper.oracle_price(&price_feed), Err(ClientError::MissingReturnData)));
+}
+
+/// Retries without waiting between attempts
+const NO_BACKOFF: RetryPolicy = RetryPolicy {
+    max_attempts: 3,
+    initial_backoff: Duration::ZERO,
+    max_backoff: Duration::ZERO,
+};
+
+#[test]
+fn retry_policy_backs_off_exponentially_up_to_its_cap() {
+    let policy = RetryPolicy::default();
+    let backoffs: Vec<_> = (0..5).map(|attempt| policy.backoff(attempt).as_millis()).collect();
+    assert_eq!(backoffs, [250, 500, 1_000, 2_000, 2_000]);
+    assert_eq!(policy.backoff(u32::MAX), policy.max_backoff);
+    assert_eq!(RetryPolicy::none().max_attempts, 1);
+}
+
+#[test]
+fn only_transient_failures_are_retryable() {
+    let simulation = |err| ClientError::Simulation { err, logs: Vec::new() };
+    assert!(is_retryable(&simulation(TransactionError::BlockhashNotFound)));
+    assert!(!is_retryable(&simulation(TransactionError::AccountNotFound)));
+    assert!(is_retryable(&ClientError::MissingUnitsConsumed));
+    assert!(!is_retryable(&ClientError::MissingReturnData));
+
+    let rpc_error = |kind: ClientErrorKind| ClientError::Rpc(Box::new(kind.into()));
+    assert!(is_retryable(&rpc_error(ClientErrorKind::Custom("connection reset".to_string()))));
+    assert!(is_retryable(&rpc_error(TransactionError::BlockhashNotFound.into())));
+    assert!(!is_retryable(&rpc_error(TransactionError::InsufficientFundsForFee.into())));
+}
+
+#[test]
+fn execute_trade_fails_over_to_the_next_rpc() {
+    let authority = Keypair::new();
+    let destination = Pubkey::new_unique();
+    // Every call to the primary comes back malformed
+    let primary = RpcClient::new_mock("fails".to_string());
+    let fallback = rpc([
+        simulation(None, None),
+        (RpcRequest::GetTransaction, confirmed_execution(destination, 1_000_000)),
+    ]);
+    let keeper = Keeper::new(primary, authority.insecure_clone(), Metrics::new())
+        .fallback_rpcs([fallback])
+        .retry_policy(NO_BACKOFF);
+
+    let execution = keeper
+        .execute_trade(execute_trade_accounts(authority.pubkey(), destination), EXPECTED_OUT, Some(7))
+        .unwrap();
+    // The fallback stays active for the read back
+    assert_eq!(execution.fill.unwrap().slippage_bps(), 0);
+    let metrics = keeper.metrics();
+    assert_eq!((metrics.executions(Outcome::Success), metrics.retries()), (1, 1));
+    assert!(metrics.render().contains("vectai_keeper_retries_total 1\n"));
+}
+
+#[test]
+fn execute_trade_does_not_resend_an_attempt_that_landed() {
+    let authority = Keypair::new();
+    let destination = Pubkey::new_unique();
+    // The send errors once, but the transaction reports as confirmed
+    let rpc = rpc([
+        simulation(None, None),
+        (RpcRequest::SendTransaction, Value::Null),
+        (RpcRequest::GetTransaction, confirmed_execution(destination, 990_000)),
+    ]);
+    let keeper = Keeper::new(rpc, authority.insecure_clone(), Metrics::new()).retry_policy(NO_BACKOFF);
+
+    let execution = keeper
+        .execute_trade(execute_trade_accounts(authority.pubkey(), destination), EXPECTED_OUT, Some(7))
+        .unwrap();
+    assert_eq!(execution.fill.unwrap().slippage_bps(), 100);
+    assert_eq!((keeper.metrics().executions(Outcome::Success), keeper.metrics().retries()), (1, 1));
+}
+
+#[test]
+fn execute_trade_gives_up_after_the_last_attempt() {
+    let authority = Keypair::new();
+    let rpcs = (0..2).map(|_| RpcClient::new_mock("fails".to_string()));
+    let keeper = Keeper::new(RpcClient::new_mock("fails".to_string()), authority.insecure_clone(), Metrics::new())
+        .fallback_rpcs(rpcs)
+        .retry_policy(NO_BACKOFF);
+
+    let result = keeper.execute_trade(execute_trade_accounts(authority.pubkey(), Pubkey::new_unique()), 1, None);
+    assert!(matches!(result, Err(ClientError::Rpc(_))));
+    let metrics = keeper.metrics();
+    assert_eq!((metrics.executions(Outcome::Error), metrics.retries()), (1, 2));
+}
+
+#[test]
+fn execute_trade_does_not_retry_a_failed_transaction() {
+    let authority = Keypair::new();
+    // Passes simulation and lands, but the program fails it
+    let rpc = RpcClient::new_mock_with_mocks("instruction_error".to_string(), [simulation(None, None)].into());
+    let keeper = Keeper::new(rpc, authority.insecure_clone(), Metrics::new()).retry_policy(NO_BACKOFF);
+
+    let result = keeper.execute_trade(execute_trade_accounts(authority.pubkey(), Pubkey::new_unique()), 1, None);
+    assert!(matches!(result, Err(ClientError::Rpc(_))));
+    assert_eq!((keeper.metrics().executions(Outcome::Error), keeper.metrics().retries()), (1, 0));
+}
@@ -0,0 +1,88 @@
+//! Prometheus exposition of the keeper's execution metrics.
+
+use std::{
+    io::{Read, Write},
+    net::TcpStream,
+    time::Duration,
+};
+
+use solana_sdk::{pubkey::Pubkey, transaction::TransactionError};
+use vectai_client::ClientError;
+use vectai_keeper::{serve, Metrics, Outcome};
+
+fn simulation_failed() -> ClientError {
+    ClientError::Simulation {
+        err: TransactionError::AccountNotFound,
+        logs: Vec::new(),
+    }
+}
+
+#[test]
+fn render_exposes_every_metric() {
+    let metrics = Metrics::new();
+    let rendered = metrics.render();
+    // Outcomes are exported at zero before the first execution
+    for outcome in Outcome::ALL {
+        assert!(rendered.contains(&format!("vectai_keeper_executions_total{{outcome=\"{}\"}} 0\n", outcome.label())));
+    }
+    assert!(rendered.contains("vectai_keeper_retries_total 0\n"));
+    assert!(rendered.contains("# TYPE vectai_keeper_execution_latency_seconds histogram\n"));
+    assert!(rendered.contains("# TYPE vectai_keeper_realized_slippage_bps histogram\n"));
+    assert!(rendered.contains("# TYPE vectai_keeper_oracle_staleness_seconds gauge\n"));
+
+    metrics.record_success(Duration::from_millis(1_500), Some(30));
+    metrics.record_success(Duration::from_millis(400), Some(-5));
+    metrics.record_success(Duration::from_secs(90), None);
+    metrics.record_failure(&simulation_failed());
+    metrics.record_failure(&ClientError::MissingUnitsConsumed);
+    let price_feed = Pubkey::new_unique();
+    metrics.record_oracle_staleness(price_feed, 12);
+
+    assert_eq!(metrics.executions(Outcome::Success), 3);
+    assert_eq!(metrics.executions(Outcome::Rejected), 1);
+    assert_eq!(metrics.executions(Outcome::Error), 1);
+    let rendered = metrics.render();
+    for line in [
+        "vectai_keeper_executions_total{outcome=\"success\"} 3",
+        "vectai_keeper_executions_total{outcome=\"rejected\"} 1",
+        "vectai_keeper_executions_total{outcome=\"error\"} 1",
+        // Buckets are cumulative; 90s only lands in +Inf
+        "vectai_keeper_execution_latency_seconds_bucket{le=\"0.5\"} 1",
+        "vectai_keeper_execution_latency_seconds_bucket{le=\"2\"} 2",
+        "vectai_keeper_execution_latency_seconds_bucket{le=\"60\"} 2",
+        "vectai_keeper_execution_latency_seconds_bucket{le=\"+Inf\"} 3",
+        "vectai_keeper_execution_latency_seconds_count 3",
+        // Only the fills that were read back, price improvement at or below 0
+        "vectai_keeper_realized_slippage_bps_bucket{le=\"0\"} 1",
+        "vectai_keeper_realized_slippage_bps_bucket{le=\"25\"} 1",
+        "vectai_keeper_realized_slippage_bps_bucket{le=\"50\"} 2",
+        "vectai_keeper_realized_slippage_bps_sum 25",
+        "vectai_keeper_realized_slippage_bps_count 2",
+    ] {
+        assert!(rendered.contains(&format!("{}\n", line)), "missing {}", line);
+    }
+    assert!(rendered.contains(&format!("vectai_keeper_oracle_staleness_seconds{{price_feed=\"{}\"}} 12\n", price_feed)));
+}
+
+fn get(addr: std::net::SocketAddr, path: &str) -> String {
+    let mut stream = TcpStream::connect(addr).unwrap();
+    write!(stream, "GET {} HTTP/1.1\r\nHost: localhost\r\n\r\n", path).unwrap();
+    let mut response = String::new();
+    stream.read_to_string(&mut response).unwrap();
+    response
+}
+
+#[test]
+fn serve_answers_scrapes() {
+    let metrics = Metrics::new();
+    let (addr, _) = serve(metrics.clone(), "127.0.0.1:0").unwrap();
+
+    metrics.record_success(Duration::from_secs(1), Some(0));
+    let response = get(addr, "/metrics");
+    assert!(response.starts_with("HTTP/1.1 200 OK\r\n"));
+    assert!(response.contains("Content-Type: text/plain; version=0.0.4\r\n"));
+    assert!(response.ends_with(&metrics.render()));
+    assert!(response.contains("vectai_keeper_executions_total{outcome=\"success\"} 1\n"));
+
+    assert!(get(addr, "/").starts_with("HTTP/1.1 404 Not Found\r\n"));
+}
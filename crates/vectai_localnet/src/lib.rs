@@ -0,0 +1,69 @@
+//! Localnet fixtures for exercising VECT.AI programs end to end.
+//!
+//! [`RaydiumFixture::snapshot`] clones the whitelisted Raydium AMM pool, its
+//! Serum market and both programs from a live cluster into
+//! `fixtures/raydium`. [`LocalnetBuilder`] loads those snapshots, the built
+//! VECT.AI programs and any synthetic accounts (token balances, protocol
+//! state) into a fresh `solana-test-validator`, so the swap CPI path runs
+//! against real pool state without network access.
+
+use std::{fmt, io};
+
+use solana_rpc_client::rpc_client::RpcClient;
+use solana_rpc_client_api::client_error::Error as ClientError;
+use solana_sdk::commitment_config::CommitmentConfig;
+
+mod raydium;
+pub use raydium::*;
+mod snapshot;
+pub use snapshot::*;
+mod validator;
+pub use validator::*;
+
+#[derive(Debug)]
+pub enum LocalnetError {
+    Io(io::Error),
+    Json(serde_json::Error),
+    Rpc(Box<ClientError>),
+    /// Malformed snapshot, fixture or account state
+    InvalidSnapshot(String),
+    /// The validator failed to start or exited
+    Validator(String),
+}
+
+impl fmt::Display for LocalnetError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            LocalnetError::Io(e) => write!(f, "io: {}", e),
+            LocalnetError::Json(e) => write!(f, "json: {}", e),
+            LocalnetError::Rpc(e) => write!(f, "rpc: {}", e),
+            LocalnetError::InvalidSnapshot(reason) => write!(f, "invalid snapshot: {}", reason),
+            LocalnetError::Validator(reason) => write!(f, "validator: {}", reason),
+        }
+    }
+}
+
+impl std::error::Error for LocalnetError {}
+
+impl From<io::Error> for LocalnetError {
+    fn from(e: io::Error) -> Self {
+        LocalnetError::Io(e)
+    }
+}
+
+impl From<serde_json::Error> for LocalnetError {
+    fn from(e: serde_json::Error) -> Self {
+        LocalnetError::Json(e)
+    }
+}
+
+impl From<Box<ClientError>> for LocalnetError {
+    fn from(e: Box<ClientError>) -> Self {
+        LocalnetError::Rpc(e)
+    }
+}
+
+/// RPC client for `url` at confirmed commitment
+pub fn rpc_client(url: &str) -> RpcClient {
+    RpcClient::new_with_commitment(url.to_string(), CommitmentConfig::confirmed())
+}
@@ -0,0 +1,57 @@
+//! vectai_localnet snapshot [--url <rpc>] [--amm <address>] [--out <dir>]
+//!
+//! Clones a Raydium pool (the swapper's whitelisted pool by default) into a
+//! fixture directory for `LocalnetBuilder::raydium`.
+
+use std::{env, path::PathBuf, process};
+
+use solana_sdk::pubkey::Pubkey;
+use vectai_localnet::{rpc_client, RaydiumFixture};
+
+const USAGE: &str = "usage: vectai_localnet snapshot [--url <rpc>] [--amm <address>] [--out <dir>]";
+
+const DEVNET_URL: &str = "https://api.devnet.solana.com";
+
+fn main() {
+    if let Err(error) = run() {
+        eprintln!("error: {}", error);
+        eprintln!("{}", USAGE);
+        process::exit(1);
+    }
+}
+
+fn run() -> Result<(), String> {
+    let mut args = env::args().skip(1);
+    match args.next().as_deref() {
+        Some("snapshot") => {}
+        Some(command) => return Err(format!("unknown command {}", command)),
+        None => return Err("missing command".to_string()),
+    }
+
+    let mut url = DEVNET_URL.to_string();
+    let mut amm = raydium_swapper::pool_config::AMM_ID;
+    let mut out = RaydiumFixture::default_dir();
+    while let Some(flag) = args.next() {
+        let value = args.next().ok_or_else(|| format!("{} needs a value", flag))?;
+        match flag.as_str() {
+            "--url" => url = value,
+            "--amm" => {
+                amm = value
+                    .parse::<Pubkey>()
+                    .map_err(|_| format!("invalid value for --amm: {}", value))?
+            }
+            "--out" => out = PathBuf::from(value),
+            _ => return Err(format!("unknown flag {}", flag)),
+        }
+    }
+
+    let rpc = rpc_client(&url);
+    let pool = RaydiumFixture::snapshot(&rpc, &amm, &out).map_err(|e| e.to_string())?;
+    println!("Snapshotted pool {} from {} into {}", pool.amm, url, out.display());
+    println!("  AMM program {}, market program {}", pool.amm_program, pool.serum_program);
+    println!("  coin mint {}, pc mint {}", pool.coin_mint, pool.pc_mint);
+    if !pool.is_whitelisted() {
+        println!("  warning: raydium_swapper::pool_config does not match this pool; swaps will fail");
+    }
+    Ok(())
+}
@@ -0,0 +1,285 @@
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
+
+use serde::{Deserialize, Serialize};
+use solana_rpc_client::rpc_client::RpcClient;
+use solana_sdk::{
+    account::Account,
+    bpf_loader_upgradeable::{self, UpgradeableLoaderState},
+    pubkey::Pubkey,
+};
+
+use crate::{AccountSnapshot, LocalnetError};
+
+// Raydium AMM v4 `AmmInfo` offsets
+const AMM_NONCE: usize = 8;
+const AMM_COIN_VAULT: usize = 336;
+const AMM_PC_VAULT: usize = 368;
+const AMM_COIN_MINT: usize = 400;
+const AMM_PC_MINT: usize = 432;
+const AMM_OPEN_ORDERS: usize = 496;
+const AMM_MARKET: usize = 528;
+const AMM_MARKET_PROGRAM: usize = 560;
+const AMM_TARGET_ORDERS: usize = 592;
+const AMM_AUTHORITY_SEED: &[u8] = b"amm authority";
+
+// Serum/OpenBook v3 `MarketState` offsets, after the 5-byte "serum" head
+const MARKET_VAULT_SIGNER_NONCE: usize = 45;
+const MARKET_COIN_VAULT: usize = 117;
+const MARKET_PC_VAULT: usize = 165;
+const MARKET_EVENT_QUEUE: usize = 253;
+const MARKET_BIDS: usize = 285;
+const MARKET_ASKS: usize = 317;
+
+/// Every account `raydium_swapper::swap` passes to the AMM, plus the
+/// pool's mints. Field names follow `raydium_swapper::SwapAccounts`.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RaydiumPool {
+    #[serde(with = "base58")]
+    pub amm_program: Pubkey,
+    #[serde(with = "base58")]
+    pub amm: Pubkey,
+    #[serde(with = "base58")]
+    pub amm_authority: Pubkey,
+    #[serde(with = "base58")]
+    pub amm_open_orders: Pubkey,
+    #[serde(with = "base58")]
+    pub amm_target_orders: Pubkey,
+    #[serde(with = "base58")]
+    pub pool_coin_token_account: Pubkey,
+    #[serde(with = "base58")]
+    pub pool_pc_token_account: Pubkey,
+    #[serde(with = "base58")]
+    pub serum_program: Pubkey,
+    #[serde(with = "base58")]
+    pub serum_market: Pubkey,
+    #[serde(with = "base58")]
+    pub serum_bids: Pubkey,
+    #[serde(with = "base58")]
+    pub serum_asks: Pubkey,
+    #[serde(with = "base58")]
+    pub serum_event_queue: Pubkey,
+    #[serde(with = "base58")]
+    pub serum_coin_vault: Pubkey,
+    #[serde(with = "base58")]
+    pub serum_pc_vault: Pubkey,
+    #[serde(with = "base58")]
+    pub serum_vault_signer: Pubkey,
+    #[serde(with = "base58")]
+    pub coin_mint: Pubkey,
+    #[serde(with = "base58")]
+    pub pc_mint: Pubkey,
+}
+
+impl RaydiumPool {
+    /// Resolve a pool from its AMM account and the market account it
+    /// points to
+    pub fn from_state(
+        amm: Pubkey,
+        amm_account: &Account,
+        market_data: &[u8],
+    ) -> Result<Self, LocalnetError> {
+        let amm_program = amm_account.owner;
+        let data = &amm_account.data;
+        let nonce = read_u64(data, AMM_NONCE)?;
+        let amm_authority = Pubkey::create_program_address(
+            &[AMM_AUTHORITY_SEED, &[nonce as u8]],
+            &amm_program,
+        )
+        .map_err(|_| invalid("AMM authority nonce"))?;
+
+        let serum_market = read_pubkey(data, AMM_MARKET)?;
+        let serum_program = read_pubkey(data, AMM_MARKET_PROGRAM)?;
+        let signer_nonce = read_u64(market_data, MARKET_VAULT_SIGNER_NONCE)?;
+        let serum_vault_signer = Pubkey::create_program_address(
+            &[serum_market.as_ref(), &signer_nonce.to_le_bytes()],
+            &serum_program,
+        )
+        .map_err(|_| invalid("market vault signer nonce"))?;
+
+        Ok(RaydiumPool {
+            amm_program,
+            amm,
+            amm_authority,
+            amm_open_orders: read_pubkey(data, AMM_OPEN_ORDERS)?,
+            amm_target_orders: read_pubkey(data, AMM_TARGET_ORDERS)?,
+            pool_coin_token_account: read_pubkey(data, AMM_COIN_VAULT)?,
+            pool_pc_token_account: read_pubkey(data, AMM_PC_VAULT)?,
+            serum_program,
+            serum_market,
+            serum_bids: read_pubkey(market_data, MARKET_BIDS)?,
+            serum_asks: read_pubkey(market_data, MARKET_ASKS)?,
+            serum_event_queue: read_pubkey(market_data, MARKET_EVENT_QUEUE)?,
+            serum_coin_vault: read_pubkey(market_data, MARKET_COIN_VAULT)?,
+            serum_pc_vault: read_pubkey(market_data, MARKET_PC_VAULT)?,
+            serum_vault_signer,
+            coin_mint: read_pubkey(data, AMM_COIN_MINT)?,
+            pc_mint: read_pubkey(data, AMM_PC_MINT)?,
+        })
+    }
+
+    /// Data accounts the swap reads or writes, in `SwapAccounts` order,
+    /// followed by the mints
+    pub fn accounts(&self) -> Vec<Pubkey> {
+        vec![
+            self.amm,
+            self.amm_open_orders,
+            self.amm_target_orders,
+            self.pool_coin_token_account,
+            self.pool_pc_token_account,
+            self.serum_market,
+            self.serum_bids,
+            self.serum_asks,
+            self.serum_event_queue,
+            self.serum_coin_vault,
+            self.serum_pc_vault,
+            self.coin_mint,
+            self.pc_mint,
+        ]
+    }
+
+    /// Whether `raydium_swapper` will accept this pool
+    pub fn is_whitelisted(&self) -> bool {
+        use raydium_swapper::pool_config::*;
+        self.amm == AMM_ID
+            && self.amm_authority == AMM_AUTHORITY
+            && self.amm_open_orders == AMM_OPEN_ORDERS
+            && self.amm_target_orders == AMM_TARGET_ORDERS
+            && self.pool_coin_token_account == POOL_COIN_TOKEN_ACCOUNT
+            && self.pool_pc_token_account == POOL_PC_TOKEN_ACCOUNT
+            && self.serum_program == SERUM_PROGRAM
+            && self.serum_market == SERUM_MARKET
+            && self.serum_bids == SERUM_BIDS
+            && self.serum_asks == SERUM_ASKS
+            && self.serum_event_queue == SERUM_EVENT_QUEUE
+            && self.serum_coin_vault == SERUM_COIN_VAULT
+            && self.serum_pc_vault == SERUM_PC_VAULT
+            && self.serum_vault_signer == SERUM_VAULT_SIGNER
+    }
+}
+
+/// A snapshotted pool on disk:
+///
+/// ```text
+/// <dir>/pool.json                 RaydiumPool
+/// <dir>/accounts/<pubkey>.json    AccountSnapshot per pool account
+/// <dir>/programs/<program>.so     AMM and market program binaries
+/// ```
+pub struct RaydiumFixture {
+    pub pool: RaydiumPool,
+    pub accounts: Vec<AccountSnapshot>,
+    pub programs: Vec<(Pubkey, PathBuf)>,
+}
+
+impl RaydiumFixture {
+    /// Checked-in snapshot of the whitelisted pool
+    pub fn default_dir() -> PathBuf {
+        Path::new(env!("CARGO_MANIFEST_DIR")).join("fixtures/raydium")
+    }
+
+    pub fn exists(dir: &Path) -> bool {
+        dir.join("pool.json").is_file()
+    }
+
+    pub fn load(dir: &Path) -> Result<Self, LocalnetError> {
+        let pool: RaydiumPool = serde_json::from_slice(&fs::read(dir.join("pool.json"))?)?;
+        let accounts = pool
+            .accounts()
+            .iter()
+            .map(|address| AccountSnapshot::read(&account_path(dir, address)))
+            .collect::<Result<Vec<_>, _>>()?;
+        let programs = [pool.amm_program, pool.serum_program]
+            .into_iter()
+            .map(|program| {
+                let path = program_path(dir, &program);
+                if path.is_file() {
+                    Ok((program, path))
+                } else {
+                    Err(invalid(&format!("missing {}", path.display())))
+                }
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(RaydiumFixture { pool, accounts, programs })
+    }
+
+    /// Clone the pool at `amm` from the cluster behind `rpc` into `dir`
+    pub fn snapshot(rpc: &RpcClient, amm: &Pubkey, dir: &Path) -> Result<RaydiumPool, LocalnetError> {
+        let amm_account = rpc.get_account(amm).map_err(Box::new)?;
+        let market = read_pubkey(&amm_account.data, AMM_MARKET)?;
+        let market_account = rpc.get_account(&market).map_err(Box::new)?;
+        let pool = RaydiumPool::from_state(*amm, &amm_account, &market_account.data)?;
+
+        fs::create_dir_all(dir.join("accounts"))?;
+        fs::create_dir_all(dir.join("programs"))?;
+        let addresses = pool.accounts();
+        let accounts = rpc.get_multiple_accounts(&addresses).map_err(Box::new)?;
+        for (address, account) in addresses.iter().zip(accounts) {
+            let account = account.ok_or_else(|| invalid(&format!("account {} not found", address)))?;
+            AccountSnapshot::new(address, &account).write(&account_path(dir, address))?;
+        }
+        for program in [pool.amm_program, pool.serum_program] {
+            fs::write(program_path(dir, &program), program_binary(rpc, &program)?)?;
+        }
+        fs::write(dir.join("pool.json"), serde_json::to_vec_pretty(&pool)?)?;
+        Ok(pool)
+    }
+}
+
+/// ELF of a deployed program, unwrapped from its program data account
+/// when it is upgradeable
+fn program_binary(rpc: &RpcClient, program: &Pubkey) -> Result<Vec<u8>, LocalnetError> {
+    let account = rpc.get_account(program).map_err(Box::new)?;
+    if account.owner != bpf_loader_upgradeable::id() {
+        return Ok(account.data);
+    }
+    // UpgradeableLoaderState::Program { programdata_address } after a u32 tag
+    let programdata = read_pubkey(&account.data, 4)?;
+    let data = rpc.get_account_data(&programdata).map_err(Box::new)?;
+    let metadata = UpgradeableLoaderState::size_of_programdata_metadata();
+    data.get(metadata..)
+        .map(<[u8]>::to_vec)
+        .ok_or_else(|| invalid(&format!("program data of {} is empty", program)))
+}
+
+fn account_path(dir: &Path, address: &Pubkey) -> PathBuf {
+    dir.join("accounts").join(format!("{}.json", address))
+}
+
+fn program_path(dir: &Path, program: &Pubkey) -> PathBuf {
+    dir.join("programs").join(format!("{}.so", program))
+}
+
+fn read_pubkey(data: &[u8], offset: usize) -> Result<Pubkey, LocalnetError> {
+    data.get(offset..offset + 32)
+        .and_then(|bytes| <[u8; 32]>::try_from(bytes).ok())
+        .map(Pubkey::new_from_array)
+        .ok_or_else(|| invalid("account data too short"))
+}
+
+fn read_u64(data: &[u8], offset: usize) -> Result<u64, LocalnetError> {
+    data.get(offset..offset + 8)
+        .and_then(|bytes| <[u8; 8]>::try_from(bytes).ok())
+        .map(u64::from_le_bytes)
+        .ok_or_else(|| invalid("account data too short"))
+}
+
+fn invalid(reason: &str) -> LocalnetError {
+    LocalnetError::InvalidSnapshot(reason.to_string())
+}
+
+/// Pubkeys as base58 strings, so `pool.json` stays readable
+mod base58 {
+    use serde::{de::Error, Deserialize, Deserializer, Serializer};
+    use solana_sdk::pubkey::Pubkey;
+
+    pub fn serialize<S: Serializer>(pubkey: &Pubkey, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(pubkey)
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Pubkey, D::Error> {
+        let s = String::deserialize(deserializer)?;
+        s.parse().map_err(D::Error::custom)
+    }
+}
@@ -0,0 +1,78 @@
+use std::{fs, path::Path, str::FromStr};
+
+use base64::{engine::general_purpose::STANDARD, Engine};
+use serde::{Deserialize, Serialize};
+use solana_sdk::{account::Account, pubkey::Pubkey};
+
+use crate::LocalnetError;
+
+/// An account in the JSON shape of `solana account --output json`, which
+/// `solana-test-validator --account` loads at genesis
+#[derive(Serialize, Deserialize)]
+pub struct AccountSnapshot {
+    pub pubkey: String,
+    pub account: SnapshotAccount,
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SnapshotAccount {
+    pub lamports: u64,
+    pub data: (String, String), // (payload, encoding); always base64 here
+    pub owner: String,
+    pub executable: bool,
+    pub rent_epoch: u64,
+    pub space: u64,
+}
+
+impl AccountSnapshot {
+    pub fn new(pubkey: &Pubkey, account: &Account) -> Self {
+        AccountSnapshot {
+            pubkey: pubkey.to_string(),
+            account: SnapshotAccount {
+                lamports: account.lamports,
+                data: (STANDARD.encode(&account.data), "base64".to_string()),
+                owner: account.owner.to_string(),
+                executable: account.executable,
+                rent_epoch: account.rent_epoch,
+                space: account.data.len() as u64,
+            },
+        }
+    }
+
+    pub fn read(path: &Path) -> Result<Self, LocalnetError> {
+        Ok(serde_json::from_slice(&fs::read(path)?)?)
+    }
+
+    pub fn write(&self, path: &Path) -> Result<(), LocalnetError> {
+        fs::write(path, serde_json::to_vec_pretty(self)?)?;
+        Ok(())
+    }
+
+    pub fn address(&self) -> Result<Pubkey, LocalnetError> {
+        parse_pubkey(&self.pubkey)
+    }
+
+    pub fn to_account(&self) -> Result<Account, LocalnetError> {
+        let (payload, encoding) = &self.account.data;
+        if encoding != "base64" {
+            return Err(LocalnetError::InvalidSnapshot(format!(
+                "{}: unsupported encoding {}",
+                self.pubkey, encoding
+            )));
+        }
+        Ok(Account {
+            lamports: self.account.lamports,
+            data: STANDARD
+                .decode(payload)
+                .map_err(|e| LocalnetError::InvalidSnapshot(format!("{}: {}", self.pubkey, e)))?,
+            owner: parse_pubkey(&self.account.owner)?,
+            executable: self.account.executable,
+            rent_epoch: self.account.rent_epoch,
+        })
+    }
+}
+
+pub(crate) fn parse_pubkey(s: &str) -> Result<Pubkey, LocalnetError> {
+    Pubkey::from_str(s).map_err(|_| LocalnetError::InvalidSnapshot(format!("invalid pubkey {}", s)))
+}
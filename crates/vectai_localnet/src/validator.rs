@@ -0,0 +1,302 @@
+use std::{
+    env, fs,
+    path::{Path, PathBuf},
+    process::{Child, Command, Stdio},
+    thread,
+    time::{Duration, Instant},
+};
+
+use anchor_lang::{AccountSerialize, Owner};
+use solana_rpc_client::rpc_client::RpcClient;
+use solana_sdk::{
+    account::Account,
+    instruction::Instruction,
+    program_pack::Pack,
+    pubkey::Pubkey,
+    rent::Rent,
+    signature::{Keypair, Signature, Signer},
+    transaction::Transaction,
+};
+use vectai_common::Versioned;
+use vectai_protocol::{NetworkConfig, ProtocolStats, ProtocolStatus};
+
+use crate::{rpc_client, AccountSnapshot, LocalnetError, RaydiumFixture};
+
+/// Validator binary, looked up on `PATH`
+pub const VALIDATOR_BIN: &str = "solana-test-validator";
+
+pub const DEFAULT_RPC_PORT: u16 = 8899;
+
+/// How long to wait for the validator's RPC to report healthy
+const STARTUP_TIMEOUT: Duration = Duration::from_secs(60);
+
+/// VECT.AI programs by `target/deploy` name
+pub fn vectai_programs() -> Vec<(&'static str, Pubkey)> {
+    vec![
+        ("vectai_protocol", vectai_protocol::ID),
+        ("raydium_swapper", raydium_swapper::ID),
+        ("vectai_trader", vectai_trader::ID),
+        ("vectai_oracle", vectai_oracle::ID),
+        ("vectai_vault", vectai_vault::ID),
+        ("vectai_index", vectai_index::ID),
+        ("vectai_orderbook", vectai_orderbook::ID),
+        ("vectai_treasury", vectai_treasury::ID),
+        ("vectai_referral", vectai_referral::ID),
+        ("vectai_timelock", vectai_timelock::ID),
+    ]
+}
+
+/// Whether `solana-test-validator` can be run
+pub fn validator_available() -> bool {
+    Command::new(VALIDATOR_BIN)
+        .arg("--version")
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .status()
+        .is_ok_and(|status| status.success())
+}
+
+/// Genesis programs and accounts for a test validator
+pub struct LocalnetBuilder {
+    deploy_dir: PathBuf,
+    programs: Vec<(Pubkey, PathBuf)>,
+    accounts: Vec<(Pubkey, Account)>,
+    payer: Keypair,
+    rpc_port: u16,
+}
+
+impl Default for LocalnetBuilder {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl LocalnetBuilder {
+    pub fn new() -> Self {
+        LocalnetBuilder {
+            deploy_dir: Path::new(env!("CARGO_MANIFEST_DIR")).join("../../target/deploy"),
+            programs: Vec::new(),
+            accounts: Vec::new(),
+            payer: Keypair::new(),
+            rpc_port: DEFAULT_RPC_PORT,
+        }
+    }
+
+    /// Directory holding the built `<name>.so` files; `target/deploy` by default
+    pub fn deploy_dir(mut self, dir: impl Into<PathBuf>) -> Self {
+        self.deploy_dir = dir.into();
+        self
+    }
+
+    /// RPC port; the websocket listens on the next one
+    pub fn rpc_port(mut self, port: u16) -> Self {
+        self.rpc_port = port;
+        self
+    }
+
+    /// Path of a built VECT.AI program, if `anchor build` produced it
+    pub fn built_program(&self, name: &str) -> Option<PathBuf> {
+        let path = self.deploy_dir.join(format!("{}.so", name));
+        path.is_file().then_some(path)
+    }
+
+    pub fn program(mut self, id: Pubkey, path: impl Into<PathBuf>) -> Self {
+        self.programs.push((id, path.into()));
+        self
+    }
+
+    /// Deploy every VECT.AI program found in the deploy directory
+    pub fn vectai_programs(mut self) -> Self {
+        for (name, id) in vectai_programs() {
+            if let Some(path) = self.built_program(name) {
+                self.programs.push((id, path));
+            }
+        }
+        self
+    }
+
+    pub fn account(mut self, address: Pubkey, account: Account) -> Self {
+        self.accounts.push((address, account));
+        self
+    }
+
+    /// Clone the pool's accounts and programs from a snapshot
+    pub fn raydium(mut self, fixture: &RaydiumFixture) -> Result<Self, LocalnetError> {
+        for snapshot in &fixture.accounts {
+            self.accounts.push((snapshot.address()?, snapshot.to_account()?));
+        }
+        self.programs.extend(fixture.programs.iter().cloned());
+        Ok(self)
+    }
+
+    /// An initialized SPL token account holding `amount`, whatever the
+    /// mint's authority; the way to fund wallets in cloned mints
+    pub fn token_account(self, address: Pubkey, mint: Pubkey, owner: Pubkey, amount: u64) -> Self {
+        let state = spl_token::state::Account {
+            mint,
+            owner,
+            amount,
+            state: spl_token::state::AccountState::Initialized,
+            ..Default::default()
+        };
+        let mut data = vec![0u8; spl_token::state::Account::LEN];
+        spl_token::state::Account::pack(state, &mut data).expect("buffer is Account::LEN");
+        let account = rent_exempt(data, spl_token::ID);
+        self.account(address, account)
+    }
+
+    /// An Anchor account of any program, with its discriminator
+    pub fn anchor_account<T: AccountSerialize + Owner>(
+        self,
+        address: Pubkey,
+        state: &T,
+    ) -> Result<Self, LocalnetError> {
+        let mut data = Vec::new();
+        state
+            .try_serialize(&mut data)
+            .map_err(|e| LocalnetError::InvalidSnapshot(e.to_string()))?;
+        Ok(self.account(address, rent_exempt(data, T::owner())))
+    }
+
+    /// vectai_protocol's pause registry, network config and empty activity
+    /// counters at their PDAs, skipping the admin-signed initialization
+    pub fn protocol(self, status: &ProtocolStatus, network: &NetworkConfig) -> Result<Self, LocalnetError> {
+        let (status_address, _) = Pubkey::find_program_address(&[b"protocol-status"], &vectai_protocol::ID);
+        let (network_address, _) = Pubkey::find_program_address(&[b"network-config"], &vectai_protocol::ID);
+        let (stats_address, stats_bump) = Pubkey::find_program_address(&[b"protocol-stats"], &vectai_protocol::ID);
+        let stats = ProtocolStats {
+            executions: 0,
+            swaps: 0,
+            mints: 0,
+            volumes: Vec::new(),
+            bump: stats_bump,
+            version: ProtocolStats::VERSION,
+            exposures: Vec::new(),
+        };
+        let mut stats_data = Vec::new();
+        stats
+            .try_serialize(&mut stats_data)
+            .map_err(|e| LocalnetError::InvalidSnapshot(e.to_string()))?;
+        stats_data.resize(ProtocolStats::LEN, 0);
+        Ok(self
+            .anchor_account(status_address, status)?
+            .anchor_account(network_address, network)?
+            .account(stats_address, rent_exempt(stats_data, vectai_protocol::ID)))
+    }
+
+    /// Launch the validator and wait until its RPC is healthy
+    pub fn start(self) -> Result<Localnet, LocalnetError> {
+        let ledger = env::temp_dir().join(format!("vectai-localnet-{}", self.rpc_port));
+        let _ = fs::remove_dir_all(&ledger);
+        let accounts_dir = ledger.join("genesis-accounts");
+        fs::create_dir_all(&accounts_dir)?;
+
+        let mut command = Command::new(VALIDATOR_BIN);
+        command
+            .arg("--reset")
+            .arg("--quiet")
+            .arg("--ledger")
+            .arg(ledger.join("ledger"))
+            .arg("--rpc-port")
+            .arg(self.rpc_port.to_string())
+            .arg("--faucet-port")
+            .arg((self.rpc_port + 1001).to_string())
+            .arg("--mint")
+            .arg(self.payer.pubkey().to_string());
+        for (id, path) in &self.programs {
+            command.arg("--bpf-program").arg(id.to_string()).arg(path);
+        }
+        for (address, account) in &self.accounts {
+            let path = accounts_dir.join(format!("{}.json", address));
+            AccountSnapshot::new(address, account).write(&path)?;
+            command.arg("--account").arg(address.to_string()).arg(path);
+        }
+
+        let child = command
+            .stdout(Stdio::null())
+            .stderr(Stdio::inherit())
+            .spawn()
+            .map_err(|e| LocalnetError::Validator(format!("{}: {}", VALIDATOR_BIN, e)))?;
+        let mut localnet = Localnet {
+            child,
+            ledger,
+            rpc: rpc_client(&format!("http://127.0.0.1:{}", self.rpc_port)),
+            payer: self.payer,
+        };
+        localnet.wait_healthy()?;
+        Ok(localnet)
+    }
+}
+
+/// A running test validator; stopped and its ledger removed on drop
+pub struct Localnet {
+    child: Child,
+    ledger: PathBuf,
+    rpc: RpcClient,
+    payer: Keypair,
+}
+
+impl Localnet {
+    pub fn rpc(&self) -> &RpcClient {
+        &self.rpc
+    }
+
+    /// Genesis mint account, funded with the validator's initial supply
+    pub fn payer(&self) -> &Keypair {
+        &self.payer
+    }
+
+    /// Sign with the payer and `signers`, send and confirm
+    pub fn send(&self, instructions: &[Instruction], signers: &[&Keypair]) -> Result<Signature, LocalnetError> {
+        let blockhash = self.rpc.get_latest_blockhash().map_err(Box::new)?;
+        let mut all_signers = vec![&self.payer];
+        all_signers.extend_from_slice(signers);
+        let transaction = Transaction::new_signed_with_payer(
+            instructions,
+            Some(&self.payer.pubkey()),
+            &all_signers,
+            blockhash,
+        );
+        Ok(self.rpc.send_and_confirm_transaction(&transaction).map_err(Box::new)?)
+    }
+
+    /// Token balance of an SPL token account
+    pub fn token_balance(&self, address: &Pubkey) -> Result<u64, LocalnetError> {
+        let data = self.rpc.get_account_data(address).map_err(Box::new)?;
+        let account = spl_token::state::Account::unpack(&data)
+            .map_err(|e| LocalnetError::InvalidSnapshot(e.to_string()))?;
+        Ok(account.amount)
+    }
+
+    fn wait_healthy(&mut self) -> Result<(), LocalnetError> {
+        let started = Instant::now();
+        while started.elapsed() < STARTUP_TIMEOUT {
+            if let Some(status) = self.child.try_wait()? {
+                return Err(LocalnetError::Validator(format!("exited with {}", status)));
+            }
+            if self.rpc.get_health().is_ok() {
+                return Ok(());
+            }
+            thread::sleep(Duration::from_millis(250));
+        }
+        Err(LocalnetError::Validator("RPC not healthy before timeout".to_string()))
+    }
+}
+
+impl Drop for Localnet {
+    fn drop(&mut self) {
+        let _ = self.child.kill();
+        let _ = self.child.wait();
+        let _ = fs::remove_dir_all(&self.ledger);
+    }
+}
+
+fn rent_exempt(data: Vec<u8>, owner: Pubkey) -> Account {
+    Account {
+        lamports: Rent::default().minimum_balance(data.len()),
+        data,
+        owner,
+        executable: false,
+        rent_epoch: 0,
+    }
+}
@@ -0,0 +1,119 @@
+//! End-to-end `raydium_swapper::swap` against the snapshotted pool.
+//!
+//! Needs `solana-test-validator` on PATH, `anchor build` output in
+//! `target/deploy` and a snapshot in `fixtures/raydium`
+//! (`cargo run -p vectai_localnet -- snapshot`); skipped otherwise.
+
+use anchor_lang::{InstructionData, ToAccountMetas};
+use solana_sdk::{
+    instruction::Instruction,
+    pubkey::Pubkey,
+    signature::{Keypair, Signer},
+};
+use vectai_localnet::{validator_available, LocalnetBuilder, RaydiumFixture};
+use vectai_protocol::{FeeConfig, NetworkConfig, ProtocolStatus};
+
+const AMOUNT_IN: u64 = 1_000_000; // 1 USDC
+
+#[test]
+fn swap_through_cloned_pool() {
+    let dir = RaydiumFixture::default_dir();
+    let builder = LocalnetBuilder::new();
+    if !RaydiumFixture::exists(&dir) || !validator_available() {
+        eprintln!("skipping: no pool snapshot in {} or no solana-test-validator", dir.display());
+        return;
+    }
+    let (Some(protocol), Some(swapper)) =
+        (builder.built_program("vectai_protocol"), builder.built_program("raydium_swapper"))
+    else {
+        eprintln!("skipping: run `anchor build` first");
+        return;
+    };
+
+    let fixture = RaydiumFixture::load(&dir).expect("load fixture");
+    let pool = &fixture.pool;
+    assert!(pool.is_whitelisted(), "snapshot does not match raydium_swapper::pool_config");
+    let (usdc_mint, wsol_mint) = if pool.pc_mint == spl_token::native_mint::ID {
+        (pool.coin_mint, pool.pc_mint)
+    } else {
+        (pool.pc_mint, pool.coin_mint)
+    };
+
+    let user = Keypair::new();
+    let user_usdc = Pubkey::new_unique();
+    let user_wsol = Pubkey::new_unique();
+    let status = ProtocolStatus {
+        guardian: Pubkey::default(),
+        paused: false,
+        updated_at: 0,
+        fee_config: FeeConfig {
+            trade_fee_bps: 0,
+            swap_fee_bps: 0,
+            performance_fee_bps: 0,
+            referral_share_bps: 0,
+        },
+        bump: Pubkey::find_program_address(&[b"protocol-status"], &vectai_protocol::ID).1,
+    };
+    let network = NetworkConfig {
+        raydium_amm_program: pool.amm_program,
+        wsol_mint,
+        usdc_mint,
+        oracle_program: vectai_oracle::ID,
+        treasury_program: vectai_treasury::ID,
+        bump: Pubkey::find_program_address(&[b"network-config"], &vectai_protocol::ID).1,
+        version: 1,
+        upgrade_attestations: Vec::new(),
+    };
+
+    let localnet = builder
+        .program(vectai_protocol::ID, protocol)
+        .program(raydium_swapper::ID, swapper)
+        .raydium(&fixture)
+        .and_then(|b| b.protocol(&status, &network))
+        .expect("genesis accounts")
+        .token_account(user_usdc, usdc_mint, user.pubkey(), AMOUNT_IN)
+        .token_account(user_wsol, wsol_mint, user.pubkey(), 0)
+        .start()
+        .expect("start validator");
+
+    let accounts = raydium_swapper::accounts::SwapAccounts {
+        user_authority: user.pubkey(),
+        user_source_token: user_usdc,
+        user_destination_token: user_wsol,
+        raydium_amm_program: pool.amm_program,
+        amm: pool.amm,
+        amm_authority: pool.amm_authority,
+        amm_open_orders: pool.amm_open_orders,
+        amm_target_orders: pool.amm_target_orders,
+        pool_coin_token_account: pool.pool_coin_token_account,
+        pool_pc_token_account: pool.pool_pc_token_account,
+        serum_program: pool.serum_program,
+        serum_market: pool.serum_market,
+        serum_bids: pool.serum_bids,
+        serum_asks: pool.serum_asks,
+        serum_event_queue: pool.serum_event_queue,
+        serum_coin_vault: pool.serum_coin_vault,
+        serum_pc_vault: pool.serum_pc_vault,
+        serum_vault_signer: pool.serum_vault_signer,
+        token_program: spl_token::ID,
+        protocol_status: Pubkey::find_program_address(&[b"protocol-status"], &vectai_protocol::ID).0,
+        network_config: Pubkey::find_program_address(&[b"network-config"], &vectai_protocol::ID).0,
+        protocol_stats: Pubkey::find_program_address(&[b"protocol-stats"], &vectai_protocol::ID).0,
+        stats_reporter: vectai_protocol::stats_reporter(&raydium_swapper::ID),
+        protocol_program: vectai_protocol::ID,
+        fee_account: None,
+    };
+    let swap = Instruction {
+        program_id: raydium_swapper::ID,
+        accounts: accounts.to_account_metas(None),
+        data: raydium_swapper::instruction::Swap {
+            amount_in: AMOUNT_IN,
+            min_amount_out: 1,
+        }
+        .data(),
+    };
+    localnet.send(&[swap], &[&user]).expect("swap");
+
+    assert_eq!(localnet.token_balance(&user_usdc).unwrap(), 0);
+    assert!(localnet.token_balance(&user_wsol).unwrap() > 0);
+}
@@ -0,0 +1,134 @@
+//! Bytecode for strategy trigger conditions, stored in `TraderConfig` and
+//! evaluated by `execute_trade` so new strategy shapes need no upgrade.
+//!
+//! A program is a sequence of opcodes run on a stack of booleans; it must
+//! leave exactly one value, the result. Operands are little-endian.
+//!
+//! | Opcode | Operands            | Pushes                                   |
+//! |--------|---------------------|------------------------------------------|
+//! | 0x01   | i64 price           | oracle price > operand                   |
+//! | 0x02   | i64 price           | oracle price < operand                   |
+//! | 0x03   |                     | pop b, a; a AND b                        |
+//! | 0x04   |                     | pop b, a; a OR b                         |
+//! | 0x05   |                     | pop a; NOT a                             |
+//! | 0x06   | u32 start, u32 end  | UTC second-of-day in [start, end), wraps |
+//! | 0x07   | u32 seconds         | seconds since last execution >= operand  |
+
+pub const OP_PRICE_ABOVE: u8 = 0x01;
+pub const OP_PRICE_BELOW: u8 = 0x02;
+pub const OP_AND: u8 = 0x03;
+pub const OP_OR: u8 = 0x04;
+pub const OP_NOT: u8 = 0x05;
+pub const OP_TIME_WINDOW: u8 = 0x06;
+pub const OP_COOLDOWN: u8 = 0x07;
+
+/// Longest program a strategy can store
+pub const MAX_CONDITION_LEN: usize = 64;
+
+/// Deepest the boolean stack may grow
+pub const MAX_STACK_DEPTH: usize = 8;
+
+pub const SECONDS_PER_DAY: i64 = 86_400;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ConditionError {
+    TooLong,
+    UnknownOpcode,
+    Truncated, // Operand runs past the end of the program
+    InvalidOperand, // Time window bound outside the day
+    StackUnderflow,
+    StackOverflow,
+    NotSingleResult,
+}
+
+/// Inputs a condition can read
+#[derive(Clone, Copy, Debug, Default)]
+pub struct EvalContext {
+    pub price: i64, // Oracle price, in price feed units
+    pub now: i64, // Unix timestamp
+    pub last_execution: i64, // Unix timestamp, 0 if never
+}
+
+/// Check a program is well formed: known opcodes, complete operands, valid
+/// time windows and a single result
+pub fn validate(program: &[u8]) -> Result<(), ConditionError> {
+    evaluate(program, &EvalContext::default()).map(|_| ())
+}
+
+/// Run `program` against `ctx`
+pub fn evaluate(program: &[u8], ctx: &EvalContext) -> Result<bool, ConditionError> {
+    if program.len() > MAX_CONDITION_LEN {
+        return Err(ConditionError::TooLong);
+    }
+
+    let mut stack = [false; MAX_STACK_DEPTH];
+    let mut depth = 0usize;
+    let mut pc = 0usize;
+    while pc < program.len() {
+        let opcode = program[pc];
+        pc += 1;
+        let value = match opcode {
+            OP_PRICE_ABOVE => ctx.price > read_i64(program, &mut pc)?,
+            OP_PRICE_BELOW => ctx.price < read_i64(program, &mut pc)?,
+            OP_AND | OP_OR => {
+                if depth < 2 {
+                    return Err(ConditionError::StackUnderflow);
+                }
+                depth -= 2;
+                let (a, b) = (stack[depth], stack[depth + 1]);
+                if opcode == OP_AND { a && b } else { a || b }
+            }
+            OP_NOT => {
+                if depth < 1 {
+                    return Err(ConditionError::StackUnderflow);
+                }
+                depth -= 1;
+                !stack[depth]
+            }
+            OP_TIME_WINDOW => {
+                let start = read_u32(program, &mut pc)? as i64;
+                let end = read_u32(program, &mut pc)? as i64;
+                if start >= SECONDS_PER_DAY || end > SECONDS_PER_DAY {
+                    return Err(ConditionError::InvalidOperand);
+                }
+                let second = ctx.now.rem_euclid(SECONDS_PER_DAY);
+                if start <= end {
+                    second >= start && second < end
+                } else {
+                    second >= start || second < end
+                }
+            }
+            OP_COOLDOWN => {
+                let seconds = read_u32(program, &mut pc)? as i64;
+                ctx.now.saturating_sub(ctx.last_execution) >= seconds
+            }
+            _ => return Err(ConditionError::UnknownOpcode),
+        };
+        if depth == MAX_STACK_DEPTH {
+            return Err(ConditionError::StackOverflow);
+        }
+        stack[depth] = value;
+        depth += 1;
+    }
+
+    if depth != 1 {
+        return Err(ConditionError::NotSingleResult);
+    }
+    Ok(stack[0])
+}
+
+fn read_i64(program: &[u8], pc: &mut usize) -> Result<i64, ConditionError> {
+    let bytes = program.get(*pc..*pc + 8).ok_or(ConditionError::Truncated)?;
+    *pc += 8;
+    let mut buf = [0u8; 8];
+    buf.copy_from_slice(bytes);
+    Ok(i64::from_le_bytes(buf))
+}
+
+fn read_u32(program: &[u8], pc: &mut usize) -> Result<u32, ConditionError> {
+    let bytes = program.get(*pc..*pc + 4).ok_or(ConditionError::Truncated)?;
+    *pc += 4;
+    let mut buf = [0u8; 4];
+    buf.copy_from_slice(bytes);
+    Ok(u32::from_le_bytes(buf))
+}
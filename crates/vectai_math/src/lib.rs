@@ -0,0 +1,284 @@
+//! Strategy math shared by `vectai_trader` and the off-chain backtester.
+//!
+//! Everything here is pure integer arithmetic with no Solana or std
+//! dependency, so a backtest replays exactly what `execute_trade` computes,
+//! including strategy conditions written in the `condition` bytecode.
+//! [`shares`] holds the share accounting of the pooled funds and
+//! [`rewards`] the reward-per-share accounting of the staking pools.
+
+#![no_std]
+
+pub mod condition;
+pub mod rewards;
+pub mod shares;
+
+// Basis-point denominator (10000 = 100%)
+pub const BPS_DENOMINATOR: u64 = 10_000;
+
+// Decimals of the supported pair
+pub const WSOL_DECIMALS: u8 = 9;
+pub const USDC_DECIMALS: u8 = 6;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum MathError {
+    /// Slippage above 100%, or a non-positive or unusable price
+    InvalidInput,
+    Overflow,
+}
+
+/// Trigger condition of a strategy: execute only while the oracle price is
+/// strictly above the threshold
+pub fn threshold_met(price: i64, price_threshold: i64) -> bool {
+    price > price_threshold
+}
+
+/// `expected_amount` less `slippage_bps`, the floor passed to the AMM
+pub fn minimum_amount_out(expected_amount: u64, slippage_bps: u64) -> Result<u64, MathError> {
+    // expected_amount * (10000 - slippage_bps) / 10000, never above expected_amount
+    let multiplier = BPS_DENOMINATOR
+        .checked_sub(slippage_bps)
+        .ok_or(MathError::InvalidInput)?;
+
+    Ok(bps_of(expected_amount, multiplier))
+}
+
+/// `bps` of `amount`, rounded down; saturates when `bps` is above 100%
+pub fn bps_of(amount: u64, bps: u64) -> u64 {
+    let value = (amount as u128) * bps as u128 / BPS_DENOMINATOR as u128;
+    u64::try_from(value).unwrap_or(u64::MAX)
+}
+
+/// Value of `output` (destination-token units) in source-token units at an
+/// oracle `price` with exponent `expo`, for the USDC/SOL pair
+pub fn value_in_source_units(
+    output: u64,
+    source_is_usdc: bool,
+    price: i64,
+    expo: i32,
+) -> Result<u64, MathError> {
+    if price <= 0 {
+        return Err(MathError::InvalidInput);
+    }
+
+    // USDC -> SOL: output is lamports, value = output * price / 10^(WSOL - USDC decimals)
+    // SOL -> USDC: output is USDC, value = output * 10^(WSOL - USDC decimals) / price
+    let decimals_shift = (WSOL_DECIMALS - USDC_DECIMALS) as i32;
+    let exponent = u32::try_from(decimals_shift as i64 - expo as i64)
+        .map_err(|_| MathError::InvalidInput)?;
+    let scale = 10u128.checked_pow(exponent).ok_or(MathError::Overflow)?;
+    let (numerator, denominator) = if source_is_usdc {
+        (price as u128, scale)
+    } else {
+        (scale, price as u128)
+    };
+    let value = (output as u128)
+        .checked_mul(numerator)
+        .ok_or(MathError::Overflow)?
+        / denominator;
+    u64::try_from(value).map_err(|_| MathError::Overflow)
+}
+
+/// How a venue prices a USDC/SOL swap, for the expected output a minimum
+/// out is taken from
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum PoolPricing {
+    /// x * y = k over the pool's reserves (Raydium AMM v4)
+    ConstantProduct { reserve_in: u64, reserve_out: u64 },
+    /// Lifinity's proactive market maker: x * y = k centred on the oracle
+    /// `price` instead of the reserve ratio, over virtual reserves
+    /// `concentration` times the real output reserve, never paying out
+    /// more than that reserve
+    OracleAnchored {
+        price: i64,
+        expo: i32,
+        reserve_out: u64,
+        concentration: u64,
+    },
+    /// Curve's stable-swap invariant for two coins of the same decimals
+    /// (Saber, Raydium stable pools), with amplification `A` as in the
+    /// whitepaper: A * n^n * sum(x) + D = A * D * n^n + D^(n+1) / (n^n * prod(x))
+    StableSwap {
+        amplification: u64,
+        reserve_in: u64,
+        reserve_out: u64,
+    },
+}
+
+/// Output of swapping `amount_in` on a venue priced by `pricing`, after
+/// its `fee_bps` on the input
+pub fn expected_amount_out(
+    amount_in: u64,
+    source_is_usdc: bool,
+    pricing: PoolPricing,
+    fee_bps: u64,
+) -> Result<u64, MathError> {
+    if fee_bps > BPS_DENOMINATOR {
+        return Err(MathError::InvalidInput);
+    }
+    let amount_in = (amount_in - bps_of(amount_in, fee_bps)) as u128;
+
+    let out = match pricing {
+        PoolPricing::ConstantProduct { reserve_in, reserve_out } => {
+            let denominator = reserve_in as u128 + amount_in;
+            if denominator == 0 {
+                return Ok(0);
+            }
+            reserve_out as u128 * amount_in / denominator
+        }
+        PoolPricing::OracleAnchored {
+            price,
+            expo,
+            reserve_out,
+            concentration,
+        } => {
+            // Output at the oracle price, then the curve's price impact:
+            // with virtual reserves X and Y = X * price, Y * dx / (X + dx)
+            // is fair * depth / (depth + fair)
+            let fair = value_in_source_units(amount_in as u64, !source_is_usdc, price, expo)? as u128;
+            let depth = reserve_out as u128 * concentration as u128;
+            if depth == 0 {
+                return Ok(0);
+            }
+            let out = fair.checked_mul(depth).ok_or(MathError::Overflow)? / (depth + fair);
+            out.min(reserve_out as u128)
+        }
+        PoolPricing::StableSwap {
+            amplification,
+            reserve_in,
+            reserve_out,
+        } => stable_swap_amount_out(amplification, amount_in, reserve_in as u128, reserve_out as u128)?,
+    };
+    // Every branch is at most an output reserve
+    Ok(out as u64)
+}
+
+/// Output of a two-coin stable-swap pool: the invariant `D` of the
+/// current reserves, the output reserve that keeps it after the input,
+/// less one unit so rounding favours the pool
+fn stable_swap_amount_out(
+    amplification: u64,
+    amount_in: u128,
+    reserve_in: u128,
+    reserve_out: u128,
+) -> Result<u128, MathError> {
+    if amplification == 0 || amount_in == 0 || reserve_in == 0 || reserve_out == 0 {
+        return Ok(0);
+    }
+    let ann = amplification as u128 * 4; // A * n^n
+    let d = stable_swap_d(ann, reserve_in, reserve_out)?;
+    let y = stable_swap_y(ann, reserve_in.checked_add(amount_in).ok_or(MathError::Overflow)?, d)?;
+    Ok(reserve_out.saturating_sub(y).saturating_sub(1))
+}
+
+/// Newton's method for the invariant `D` of reserves `x` and `y`
+fn stable_swap_d(ann: u128, x: u128, y: u128) -> Result<u128, MathError> {
+    let sum = x + y;
+    let mut d = sum;
+    for _ in 0..255 {
+        // D^(n+1) / (n^n * prod(x)), one reserve at a time
+        let mut d_p = d;
+        for reserve in [x, y] {
+            d_p = d_p.checked_mul(d).ok_or(MathError::Overflow)? / (reserve * 2);
+        }
+        let previous = d;
+        let numerator = ann
+            .checked_mul(sum)
+            .and_then(|v| v.checked_add(d_p * 2))
+            .and_then(|v| v.checked_mul(d))
+            .ok_or(MathError::Overflow)?;
+        let denominator = (ann - 1)
+            .checked_mul(d)
+            .and_then(|v| v.checked_add(d_p.checked_mul(3)?))
+            .ok_or(MathError::Overflow)?;
+        d = numerator / denominator;
+        if d.abs_diff(previous) <= 1 {
+            return Ok(d);
+        }
+    }
+    Err(MathError::InvalidInput)
+}
+
+/// Newton's method for the reserve `y` that keeps invariant `d` when the
+/// other reserve is `x`
+fn stable_swap_y(ann: u128, x: u128, d: u128) -> Result<u128, MathError> {
+    let c = d.checked_mul(d).ok_or(MathError::Overflow)? / (x * 2);
+    let c = c.checked_mul(d).ok_or(MathError::Overflow)? / (ann * 2);
+    let b = x + d / ann;
+    let mut y = d;
+    for _ in 0..255 {
+        let previous = y;
+        let numerator = y.checked_mul(y).and_then(|v| v.checked_add(c)).ok_or(MathError::Overflow)?;
+        let denominator = (y * 2 + b).checked_sub(d).filter(|v| *v > 0).ok_or(MathError::InvalidInput)?;
+        y = numerator / denominator;
+        if y.abs_diff(previous) <= 1 {
+            return Ok(y);
+        }
+    }
+    Err(MathError::InvalidInput)
+}
+
+/// Peak-to-trough drawdown of `equity` from `peak_equity`, in basis points
+pub fn drawdown_bps(peak_equity: u64, equity: u64) -> u16 {
+    if equity >= peak_equity {
+        return 0;
+    }
+    ((peak_equity - equity) as u128 * BPS_DENOMINATOR as u128 / peak_equity as u128) as u16
+}
+
+/// Slippage that scales with oracle uncertainty: `base_bps` plus
+/// `conf_factor_bps` (10_000 = 1x) of the confidence interval as a share of
+/// the price, clamped to `0..=max_bps`. A negative factor tightens fills as
+/// the confidence widens.
+pub fn dynamic_slippage_bps(
+    base_bps: u64,
+    conf_factor_bps: i64,
+    price: i64,
+    conf: u64,
+    max_bps: u64,
+) -> Result<u64, MathError> {
+    if price <= 0 {
+        return Err(MathError::InvalidInput);
+    }
+
+    let conf_bps = conf as u128 * BPS_DENOMINATOR as u128 / price as u128;
+    let adjustment = (conf_bps as i128).saturating_mul(conf_factor_bps as i128) / BPS_DENOMINATOR as i128;
+    let slippage = (base_bps as i128).saturating_add(adjustment).clamp(0, max_bps as i128);
+    Ok(slippage as u64)
+}
+
+/// Standard deviation of `prices` in basis points of their mean, the
+/// short-term volatility a strategy's filter bounds. `None` for fewer than
+/// two prices or a non-positive mean.
+pub fn volatility_bps(prices: &[i64]) -> Option<u64> {
+    if prices.len() < 2 {
+        return None;
+    }
+    let count = prices.len() as i128;
+    let mean = prices.iter().map(|&price| price as i128).sum::<i128>() / count;
+    if mean <= 0 {
+        return None;
+    }
+    let variance = prices
+        .iter()
+        .map(|&price| (price as i128 - mean).unsigned_abs().saturating_pow(2))
+        .fold(0u128, u128::saturating_add)
+        / count as u128;
+    let stddev_bps = isqrt(variance).saturating_mul(BPS_DENOMINATOR as u128) / mean as u128;
+    Some(u64::try_from(stddev_bps).unwrap_or(u64::MAX))
+}
+
+/// Integer square root, rounded down
+fn isqrt(value: u128) -> u128 {
+    if value < 2 {
+        return value;
+    }
+    // Newton's method from an estimate at or above the root
+    let mut root = 1u128 << (128 - value.leading_zeros()).div_ceil(2);
+    loop {
+        let next = (root + value / root) / 2;
+        if next >= root {
+            return root;
+        }
+        root = next;
+    }
+}
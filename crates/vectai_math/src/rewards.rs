@@ -0,0 +1,32 @@
+//! Reward-per-share accounting of the staking pools, `vectai_farm` and
+//! `vectai_token`'s flexible and locked stake.
+//!
+//! A pool grows its accumulator by each distribution over its total
+//! weight. A position's reward debt is its weight times the accumulator
+//! when last checkpointed, so what it earned since is the difference.
+
+use crate::MathError;
+
+/// Fixed-point scale of `acc_reward_per_share`
+pub const REWARD_PRECISION: u128 = 1_000_000_000_000;
+
+/// Accumulator increase from distributing `reward` across `total_weight`
+pub fn reward_per_share_delta(reward: u128, total_weight: u64) -> Result<u128, MathError> {
+    if total_weight == 0 {
+        return Err(MathError::InvalidInput);
+    }
+    Ok(reward.checked_mul(REWARD_PRECISION).ok_or(MathError::Overflow)? / total_weight as u128)
+}
+
+/// `weight`'s share of the accumulator: its reward debt when checkpointed
+/// at `acc_reward_per_share`
+pub fn reward_debt(weight: u64, acc_reward_per_share: u128) -> Result<u128, MathError> {
+    Ok((weight as u128).checked_mul(acc_reward_per_share).ok_or(MathError::Overflow)? / REWARD_PRECISION)
+}
+
+/// Rewards `weight` earned since it was checkpointed at `reward_debt`
+pub fn earned_rewards(weight: u64, acc_reward_per_share: u128, reward_debt: u128) -> Result<u64, MathError> {
+    let accrued = self::reward_debt(weight, acc_reward_per_share)?;
+    let earned = accrued.checked_sub(reward_debt).ok_or(MathError::Overflow)?;
+    u64::try_from(earned).map_err(|_| MathError::Overflow)
+}
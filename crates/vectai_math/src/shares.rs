@@ -0,0 +1,38 @@
+//! Share accounting of pooled funds, `vectai_vault` and `vectai_index`.
+//!
+//! Deposits and redemptions are priced against virtual shares backed by as
+//! many virtual assets, so a donation cannot inflate the share price of an
+//! empty fund and round the next depositor's shares down to nothing.
+
+use crate::MathError;
+
+/// Virtual shares counted in every fund's supply; equal to
+/// `VIRTUAL_ASSETS` so the first deposit still mints 1:1
+pub const VIRTUAL_SHARES: u64 = 1_000;
+
+/// Virtual asset units counted in every fund's value
+pub const VIRTUAL_ASSETS: u64 = 1_000;
+
+/// Shares minted for `amount` deposited into a fund worth `nav` with
+/// `supply` shares out, counting the virtual shares and assets in both
+pub fn shares_for_deposit(amount: u64, supply: u64, nav: u64) -> Result<u64, MathError> {
+    let shares = (amount as u128)
+        .checked_mul(supply as u128 + VIRTUAL_SHARES as u128)
+        .ok_or(MathError::Overflow)?
+        / (nav as u128 + VIRTUAL_ASSETS as u128);
+    u64::try_from(shares).map_err(|_| MathError::Overflow)
+}
+
+/// Slice of `balance` paid for burning `shares` of `supply`: the virtual
+/// shares count in the supply and `virtual_assets` in the balance, as in
+/// `shares_for_deposit`, and the payout never exceeds the plain pro-rata
+/// slice
+pub fn redemption_amount(balance: u64, virtual_assets: u64, shares: u64, supply: u64) -> Result<u64, MathError> {
+    if supply == 0 {
+        return Err(MathError::InvalidInput);
+    }
+    let assets = balance.checked_add(virtual_assets).ok_or(MathError::Overflow)?;
+    let with_virtual = assets as u128 * shares as u128 / (supply as u128 + VIRTUAL_SHARES as u128);
+    let pro_rata = balance as u128 * shares as u128 / supply as u128;
+    u64::try_from(with_virtual.min(pro_rata)).map_err(|_| MathError::Overflow)
+}
@@ -0,0 +1,423 @@
+//! Properties of the slippage floor, fee, valuation, venue quote,
+//! drawdown, fund share and staking reward math over the whole
+//! u64/i64/i32 input space.
+//!
+//! `calculate_minimum_amount_out` in `vectai_trader` and the backtester's
+//! fee and expected-output quoting call straight into these functions.
+
+use proptest::prelude::*;
+use vectai_math::rewards::{earned_rewards, reward_debt, reward_per_share_delta};
+use vectai_math::shares::{redemption_amount, shares_for_deposit, VIRTUAL_ASSETS};
+use vectai_math::{
+    bps_of, drawdown_bps, dynamic_slippage_bps, expected_amount_out, minimum_amount_out, threshold_met,
+    value_in_source_units, volatility_bps, MathError, PoolPricing, BPS_DENOMINATOR,
+};
+
+fn bps() -> impl Strategy<Value = u64> {
+    0..=BPS_DENOMINATOR
+}
+
+/// Exponents a price feed plausibly publishes, where valuations can succeed
+fn expo() -> impl Strategy<Value = i32> {
+    -12..=3i32
+}
+
+proptest! {
+    // ===== SLIPPAGE FLOOR =====
+
+    #[test]
+    fn minimum_amount_out_is_exact(expected in any::<u64>(), slippage in bps()) {
+        let exact = expected as u128 * (BPS_DENOMINATOR - slippage) as u128 / BPS_DENOMINATOR as u128;
+        prop_assert_eq!(minimum_amount_out(expected, slippage), Ok(exact as u64));
+    }
+
+    #[test]
+    fn minimum_amount_out_never_exceeds_expected(expected in any::<u64>(), slippage in bps()) {
+        let floor = minimum_amount_out(expected, slippage).unwrap();
+        prop_assert!(floor <= expected);
+        // Rounding costs at most one unit beyond the tolerance
+        prop_assert!(expected - floor <= bps_of(expected, slippage) + 1);
+    }
+
+    #[test]
+    fn minimum_amount_out_is_monotonic(a in any::<u64>(), b in any::<u64>(), s in bps(), t in bps()) {
+        let (low, high) = (a.min(b), a.max(b));
+        prop_assert!(minimum_amount_out(low, s).unwrap() <= minimum_amount_out(high, s).unwrap());
+        let (tight, loose) = (s.min(t), s.max(t));
+        prop_assert!(minimum_amount_out(a, loose).unwrap() <= minimum_amount_out(a, tight).unwrap());
+    }
+
+    #[test]
+    fn minimum_amount_out_rejects_slippage_above_100_percent(expected in any::<u64>(), slippage in BPS_DENOMINATOR + 1..) {
+        prop_assert_eq!(minimum_amount_out(expected, slippage), Err(MathError::InvalidInput));
+    }
+
+    #[test]
+    fn dynamic_slippage_stays_within_cap(
+        base in bps(), factor in any::<i64>(), price in 1..=i64::MAX, conf in any::<u64>(), max in bps()
+    ) {
+        let slippage = dynamic_slippage_bps(base, factor, price, conf, max).unwrap();
+        prop_assert!(slippage <= max);
+        prop_assert_eq!(dynamic_slippage_bps(base, factor, price, 0, max), Ok(base.min(max)));
+    }
+
+    #[test]
+    fn dynamic_slippage_follows_factor_sign(
+        base in bps(), factor in 0..=i64::MAX, price in 1..=i64::MAX, conf in any::<u64>(), max in bps()
+    ) {
+        let capped = base.min(max);
+        prop_assert!(dynamic_slippage_bps(base, factor, price, conf, max).unwrap() >= capped);
+        prop_assert!(dynamic_slippage_bps(base, -factor, price, conf, max).unwrap() <= capped);
+    }
+
+    #[test]
+    fn dynamic_slippage_rejects_non_positive_price(base in bps(), factor in any::<i64>(), price in ..=0i64) {
+        prop_assert_eq!(dynamic_slippage_bps(base, factor, price, 1, BPS_DENOMINATOR), Err(MathError::InvalidInput));
+    }
+
+    // ===== VOLATILITY =====
+
+    #[test]
+    fn volatility_of_flat_prices_is_zero(price in 1..=i64::MAX, count in 2..=16usize) {
+        prop_assert_eq!(volatility_bps(&vec![price; count]), Some(0));
+    }
+
+    #[test]
+    fn volatility_is_scale_invariant(
+        prices in prop::collection::vec(1_000_000..=10_000_000i64, 2..=8), scale in 1..=1_000i64
+    ) {
+        let scaled: Vec<i64> = prices.iter().map(|price| price * scale).collect();
+        let (base, scaled) = (volatility_bps(&prices).unwrap(), volatility_bps(&scaled).unwrap());
+        // At these magnitudes flooring the mean and root costs under a bp
+        prop_assert!(base.abs_diff(scaled) <= 1, "{} vs {}", base, scaled);
+    }
+
+    #[test]
+    fn volatility_never_panics(prices in prop::collection::vec(any::<i64>(), 0..=8)) {
+        let volatility = volatility_bps(&prices);
+        prop_assert!(prices.len() >= 2 || volatility.is_none());
+    }
+
+    // ===== FEES =====
+
+    #[test]
+    fn bps_of_is_bounded_by_amount(amount in any::<u64>(), bps in bps()) {
+        prop_assert!(bps_of(amount, bps) <= amount);
+        prop_assert_eq!(bps_of(amount, 0), 0);
+        prop_assert_eq!(bps_of(amount, BPS_DENOMINATOR), amount);
+    }
+
+    #[test]
+    fn bps_of_split_loses_at_most_one_unit(amount in any::<u64>(), bps in bps()) {
+        // A fee and the remainder it leaves never exceed the amount
+        let split = bps_of(amount, bps) as u128 + bps_of(amount, BPS_DENOMINATOR - bps) as u128;
+        prop_assert!(split <= amount as u128);
+        prop_assert!(split + 1 >= amount as u128);
+    }
+
+    #[test]
+    fn bps_of_is_monotonic(a in any::<u64>(), b in any::<u64>(), s in any::<u64>(), t in any::<u64>()) {
+        prop_assert!(bps_of(a.min(b), s) <= bps_of(a.max(b), s));
+        prop_assert!(bps_of(a, s.min(t)) <= bps_of(a, s.max(t)));
+    }
+
+    #[test]
+    fn bps_of_saturates(amount in any::<u64>(), bps in any::<u64>()) {
+        let exact = amount as u128 * bps as u128 / BPS_DENOMINATOR as u128;
+        prop_assert_eq!(bps_of(amount, bps) as u128, exact.min(u64::MAX as u128));
+    }
+
+    // ===== VALUATION =====
+
+    #[test]
+    fn value_in_source_units_never_panics(output in any::<u64>(), usdc in any::<bool>(), price in any::<i64>(), expo in any::<i32>()) {
+        let value = value_in_source_units(output, usdc, price, expo);
+        if price <= 0 {
+            prop_assert_eq!(value, Err(MathError::InvalidInput));
+        }
+    }
+
+    #[test]
+    fn value_in_source_units_is_monotonic_in_output(
+        a in any::<u64>(),
+        b in any::<u64>(),
+        usdc in any::<bool>(),
+        price in 1..=i64::MAX,
+        expo in expo(),
+    ) {
+        let (low, high) = (a.min(b), a.max(b));
+        if let Ok(high_value) = value_in_source_units(high, usdc, price, expo) {
+            prop_assert!(value_in_source_units(low, usdc, price, expo).unwrap() <= high_value);
+        }
+    }
+
+    #[test]
+    fn value_in_source_units_follows_price(
+        output in any::<u64>(),
+        a in 1..=i64::MAX,
+        b in 1..=i64::MAX,
+        expo in expo(),
+    ) {
+        let (low, high) = (a.min(b), a.max(b));
+        // Lamports are worth more USDC at a higher price; USDC buys fewer lamports
+        if let Ok(high_value) = value_in_source_units(output, true, high, expo) {
+            prop_assert!(value_in_source_units(output, true, low, expo).unwrap() <= high_value);
+        }
+        if let Ok(low_value) = value_in_source_units(output, false, low, expo) {
+            prop_assert!(value_in_source_units(output, false, high, expo).unwrap() <= low_value);
+        }
+    }
+
+    #[test]
+    fn value_in_source_units_normalizes_exponent(
+        output in any::<u64>(),
+        usdc in any::<bool>(),
+        price in 1..=i64::MAX / 10,
+        expo in expo(),
+    ) {
+        // The same price published with one more decimal
+        let value = value_in_source_units(output, usdc, price, expo);
+        let rescaled = value_in_source_units(output, usdc, price * 10, expo - 1);
+        if let (Ok(value), Ok(rescaled)) = (value, rescaled) {
+            prop_assert_eq!(value, rescaled);
+        }
+    }
+
+    #[test]
+    fn value_in_source_units_round_trip_never_gains(
+        amount in any::<u64>(),
+        usdc in any::<bool>(),
+        price in 1..=i64::MAX,
+        expo in expo(),
+    ) {
+        // Quoting a trade and valuing the quote rounds down both ways
+        if let Ok(quote) = value_in_source_units(amount, !usdc, price, expo) {
+            if let Ok(value) = value_in_source_units(quote, usdc, price, expo) {
+                prop_assert!(value <= amount);
+            }
+        }
+    }
+
+    // ===== VENUE QUOTES =====
+
+    #[test]
+    fn constant_product_never_drains_the_pool(
+        amount in any::<u64>(),
+        reserve_in in any::<u64>(),
+        reserve_out in any::<u64>(),
+        fee in bps(),
+    ) {
+        let pricing = PoolPricing::ConstantProduct { reserve_in, reserve_out };
+        let out = expected_amount_out(amount, true, pricing, fee).unwrap();
+        prop_assert!(out <= reserve_out);
+        if reserve_in > 0 && reserve_out > 0 {
+            prop_assert!(out < reserve_out);
+        }
+    }
+
+    #[test]
+    fn oracle_anchored_never_beats_the_oracle(
+        amount in any::<u64>(),
+        usdc in any::<bool>(),
+        price in 1..=i64::MAX,
+        expo in expo(),
+        reserve_out in any::<u64>(),
+        concentration in 0..=1_000u64,
+        fee in bps(),
+    ) {
+        let pricing = PoolPricing::OracleAnchored { price, expo, reserve_out, concentration };
+        if let Ok(out) = expected_amount_out(amount, usdc, pricing, fee) {
+            prop_assert!(out <= reserve_out);
+            let fair = value_in_source_units(amount - bps_of(amount, fee), !usdc, price, expo).unwrap();
+            prop_assert!(out <= fair);
+        }
+    }
+
+    #[test]
+    fn expected_amount_out_is_monotonic_in_amount(
+        a in any::<u64>(),
+        b in any::<u64>(),
+        price in 1..=1_000_000_000_000i64,
+        reserve_out in any::<u64>(),
+        concentration in 1..=1_000u64,
+    ) {
+        let pricing = PoolPricing::OracleAnchored { price, expo: -8, reserve_out, concentration };
+        let quote = |amount| expected_amount_out(amount, true, pricing, 30);
+        if let (Ok(low), Ok(high)) = (quote(a.min(b)), quote(a.max(b))) {
+            prop_assert!(low <= high);
+        }
+    }
+
+    #[test]
+    fn stable_swap_never_drains_the_pool(
+        amount in 0..=1u64 << 48,
+        reserve_in in 0..=1u64 << 48,
+        reserve_out in 0..=1u64 << 48,
+        amplification in 1..=10_000u64,
+    ) {
+        let pricing = PoolPricing::StableSwap { amplification, reserve_in, reserve_out };
+        let out = expected_amount_out(amount, true, pricing, 0).unwrap();
+        prop_assert!(out < reserve_out.max(1));
+    }
+
+    #[test]
+    fn stable_swap_beats_constant_product_on_a_balanced_pool(
+        amount in 1..=1u64 << 40,
+        reserve in 1u64 << 20..=1u64 << 48,
+        amplification in 1..=10_000u64,
+    ) {
+        let stable = PoolPricing::StableSwap { amplification, reserve_in: reserve, reserve_out: reserve };
+        let constant_product = PoolPricing::ConstantProduct { reserve_in: reserve, reserve_out: reserve };
+        let out = expected_amount_out(amount, true, stable, 0).unwrap();
+        // Never better than 1:1 from balance, never worse than x * y = k
+        prop_assert!(out <= amount);
+        prop_assert!(out + 1 >= expected_amount_out(amount, true, constant_product, 0).unwrap());
+    }
+
+    #[test]
+    fn expected_amount_out_rejects_fee_above_100_percent(amount in any::<u64>(), fee in BPS_DENOMINATOR + 1..) {
+        let pricing = PoolPricing::ConstantProduct { reserve_in: 1, reserve_out: 1 };
+        prop_assert_eq!(expected_amount_out(amount, true, pricing, fee), Err(MathError::InvalidInput));
+    }
+
+    // ===== THRESHOLD AND DRAWDOWN =====
+
+    #[test]
+    fn threshold_is_strict(price in any::<i64>(), threshold in any::<i64>()) {
+        prop_assert_eq!(threshold_met(price, threshold), price > threshold);
+        prop_assert!(!threshold_met(price, price));
+    }
+
+    #[test]
+    fn drawdown_bps_is_bounded(peak in any::<u64>(), equity in any::<u64>()) {
+        let drawdown = drawdown_bps(peak, equity);
+        prop_assert!(drawdown as u64 <= BPS_DENOMINATOR);
+        if equity >= peak {
+            prop_assert_eq!(drawdown, 0);
+        }
+    }
+
+    #[test]
+    fn drawdown_bps_grows_as_equity_falls(peak in any::<u64>(), a in any::<u64>(), b in any::<u64>()) {
+        prop_assert!(drawdown_bps(peak, a.max(b)) <= drawdown_bps(peak, a.min(b)));
+    }
+
+    // ===== FUND SHARES =====
+
+    #[test]
+    fn first_deposit_mints_one_to_one(amount in any::<u64>()) {
+        prop_assert_eq!(shares_for_deposit(amount, 0, 0), Ok(amount));
+    }
+
+    #[test]
+    fn deposit_and_redeem_never_profits(
+        amount in 1..=u64::MAX >> 24,
+        supply in 0..=u64::MAX >> 24,
+        nav in 0..=u64::MAX >> 24,
+        virtual_assets in prop_oneof![Just(0), Just(VIRTUAL_ASSETS)],
+    ) {
+        let shares = shares_for_deposit(amount, supply, nav).unwrap();
+        prop_assume!(shares > 0);
+        let redeemed = redemption_amount(nav + amount, virtual_assets, shares, supply + shares).unwrap();
+        prop_assert!(redeemed <= amount);
+    }
+
+    #[test]
+    fn redemption_is_at_most_pro_rata(
+        balance in any::<u64>(),
+        virtual_assets in any::<u64>(),
+        supply in 1..=u64::MAX,
+        shares in any::<u64>(),
+    ) {
+        let shares = shares % supply + 1;
+        let pro_rata = balance as u128 * shares as u128 / supply as u128;
+        match redemption_amount(balance, virtual_assets, shares, supply) {
+            Ok(redeemed) => prop_assert!(redeemed as u128 <= pro_rata),
+            Err(error) => prop_assert!(error == MathError::Overflow && balance.checked_add(virtual_assets).is_none()),
+        }
+    }
+
+    #[test]
+    fn donations_never_pay_the_donor(first in 1..=1_000_000u64, donation in any::<u32>(), deposit in 1..=u32::MAX as u64) {
+        // The donor deposits `first`, donates, then a victim deposits
+        let held = shares_for_deposit(first, 0, 0).unwrap();
+        let nav = first + donation as u64;
+        let minted = shares_for_deposit(deposit, held, nav).unwrap();
+        let supply = held + minted;
+        let donor_out = redemption_amount(nav + deposit, VIRTUAL_ASSETS, held, supply).unwrap();
+        prop_assert!(donor_out <= first + donation as u64);
+    }
+
+    // ===== STAKING REWARDS =====
+
+    #[test]
+    fn nothing_is_earned_at_the_checkpoint(weight in 0..=u64::MAX >> 24, acc in 0..=u64::MAX as u128 * 1_000_000) {
+        let debt = reward_debt(weight, acc).unwrap();
+        prop_assert_eq!(earned_rewards(weight, acc, debt), Ok(0));
+    }
+
+    #[test]
+    fn stakers_never_earn_more_than_is_distributed(
+        weights in prop::collection::vec(1..=u64::MAX >> 24, 1..8),
+        rewards in prop::collection::vec(0..=u64::MAX >> 24, 1..8),
+    ) {
+        let total_weight = weights.iter().sum::<u64>();
+        let debts: Vec<u128> = weights.iter().map(|weight| reward_debt(*weight, 0).unwrap()).collect();
+        let mut acc = 0u128;
+        for reward in &rewards {
+            acc += reward_per_share_delta(*reward as u128, total_weight).unwrap();
+        }
+        let earned = weights
+            .iter()
+            .zip(&debts)
+            .map(|(weight, debt)| earned_rewards(*weight, acc, *debt).unwrap() as u128)
+            .sum::<u128>();
+        prop_assert!(earned <= rewards.iter().map(|reward| *reward as u128).sum::<u128>());
+    }
+}
+
+#[test]
+fn donation_to_an_empty_fund_does_not_zero_the_next_deposit() {
+    // One unit in, a million donated: without the virtual shares the next
+    // million deposited would mint 1_000_000 * 1 / 1_000_001 = 0 shares
+    let minted = shares_for_deposit(1_000_000, 1, 1_000_001).unwrap();
+    assert_eq!(minted, 999);
+    let redeemed = redemption_amount(2_000_001, VIRTUAL_ASSETS, minted, 1 + minted).unwrap();
+    assert!(redeemed > 999_000);
+    assert_eq!(redemption_amount(1, 0, 1, 0), Err(MathError::InvalidInput));
+}
+
+#[test]
+fn distribution_needs_stake() {
+    assert_eq!(reward_per_share_delta(1, 0), Err(MathError::InvalidInput));
+    // A debt above what the position accrued is corrupt, not a payout
+    assert_eq!(earned_rewards(1, 0, 1), Err(MathError::Overflow));
+}
+
+#[test]
+fn value_in_source_units_rejects_extreme_exponents() {
+    assert_eq!(value_in_source_units(1, true, 1, i32::MIN), Err(MathError::Overflow));
+    assert_eq!(value_in_source_units(1, true, 1, i32::MAX), Err(MathError::InvalidInput));
+    assert_eq!(value_in_source_units(1, true, 1, 4), Err(MathError::InvalidInput));
+    assert_eq!(value_in_source_units(1, true, 1, 3), Ok(1));
+}
+
+#[test]
+fn volatility_bps_of_a_wick() {
+    // Population standard deviation of 40, 50 and 45 is 4.08, 907 bps of 45
+    assert_eq!(volatility_bps(&[40_000, 50_000, 45_000]), Some(907));
+    assert_eq!(volatility_bps(&[45_000]), None);
+    assert_eq!(volatility_bps(&[-5, 5]), None);
+}
+
+#[test]
+fn stable_swap_of_a_balanced_pool_is_near_par() {
+    // 1M USDT into 1B/1B at A = 100 loses well under a basis point
+    let pricing = PoolPricing::StableSwap {
+        amplification: 100,
+        reserve_in: 1_000_000_000_000_000,
+        reserve_out: 1_000_000_000_000_000,
+    };
+    let out = expected_amount_out(1_000_000_000_000, true, pricing, 0).unwrap();
+    assert!(out < 1_000_000_000_000 && out > 999_990_000_000);
+}
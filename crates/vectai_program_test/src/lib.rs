@@ -0,0 +1,601 @@
+//! In-process runtime for instruction-level tests of the VECT.AI programs.
+//!
+//! Programs are linked natively (`no-entrypoint`) and called through their
+//! Anchor `entry`, with the system, SPL Token and Token-2022 programs, the
+//! Ed25519 precompile, CPI, PDA signing, sysvars, return data and program
+//! logs provided natively, so tests encode real instructions and read real
+//! account state without a validator or an SBF build:
+//!
+//! ```ignore
+//! let mut test = ProgramTest::new();
+//! let ix = Instruction {
+//!     program_id: vectai_trader::ID,
+//!     accounts: vectai_trader::accounts::PauseTrader { .. }.to_account_metas(None),
+//!     data: vectai_trader::instruction::PauseTrader {}.data(),
+//! };
+//! test.process_instruction(ix, &[&authority])?;
+//! let config: TraderConfig = test.anchor_account(&trader_config).unwrap();
+//! ```
+//!
+//! Only syscalls (logs, events, CPI, sysvars, return data) are charged
+//! compute units, at the default budget's costs, so
+//! [`ProgramTest::compute_units_consumed`] is a floor that tracks changes in
+//! logging and CPI rather than a validator's figure. The SBF memory model is
+//! not enforced; paths that depend on it, or on exact compute units, belong
+//! in `vectai_localnet`.
+
+pub mod mock_amm;
+pub mod mock_dlmm;
+pub mod mock_drift;
+pub mod mock_jupiter;
+pub mod mock_lifinity;
+pub mod mock_phoenix;
+pub mod mock_solend;
+pub mod mock_stable;
+mod runtime;
+pub mod trader;
+
+use std::collections::HashMap;
+
+use anchor_lang::{
+    solana_program::{
+        account_info::AccountInfo,
+        bpf_loader,
+        clock::Clock,
+        ed25519_program,
+        entrypoint::ProgramResult,
+        instruction::Instruction,
+        program_error::ProgramError,
+        program_pack::Pack,
+        pubkey::Pubkey,
+        rent::Rent,
+        system_program,
+        sysvar::{self, instructions::BorrowedAccountMeta},
+    },
+    AccountDeserialize, AccountSerialize, Event, Owner,
+};
+use base64::{engine::general_purpose::STANDARD, Engine};
+use anchor_spl::token_2022::spl_token_2022::{
+    self,
+    extension::{
+        transfer_hook::TransferHookAccount, BaseStateWithExtensions, BaseStateWithExtensionsMut, ExtensionType,
+        StateWithExtensions, StateWithExtensionsMut,
+    },
+};
+use solana_sdk::{
+    account::Account,
+    native_token::LAMPORTS_PER_SOL,
+    signature::{Keypair, Signer},
+};
+use vectai_common::Versioned;
+use vectai_protocol::{FeeConfig, NetworkConfig, ProtocolStats, ProtocolStatus, Role, RoleRegistry};
+
+use crate::runtime::{InvokeContext, Slot};
+
+pub use crate::runtime::shorten;
+
+/// A native program's entrypoint
+pub type ProcessInstruction = fn(&Pubkey, &[AccountInfo], &[u8]) -> ProgramResult;
+
+/// Wrap an Anchor program's `entry` as a [`ProcessInstruction`]
+#[macro_export]
+macro_rules! anchor_processor {
+    ($program:path) => {{
+        fn process(
+            program_id: &$crate::__private::Pubkey,
+            accounts: &[$crate::__private::AccountInfo],
+            data: &[u8],
+        ) -> $crate::__private::ProgramResult {
+            // SAFETY: Anchor handlers only hold accounts for the instruction
+            let accounts = unsafe { $crate::shorten(accounts) };
+            $program(program_id, accounts, data)
+        }
+        process as $crate::ProcessInstruction
+    }};
+}
+
+#[doc(hidden)]
+pub mod __private {
+    pub use anchor_lang::solana_program::{
+        account_info::AccountInfo, entrypoint::ProgramResult, pubkey::Pubkey,
+    };
+}
+
+/// 2023-11-14, so timestamp math sees a realistic clock
+const GENESIS_TIMESTAMP: i64 = 1_700_000_000;
+
+/// Accounts, programs and the clock of one test
+pub struct ProgramTest {
+    accounts: HashMap<Pubkey, Account>,
+    programs: HashMap<Pubkey, ProcessInstruction>,
+    clock: Clock,
+    payer: Keypair,
+    logs: Vec<String>,
+    return_data: Option<(Pubkey, Vec<u8>)>,
+    compute_units_consumed: u64,
+}
+
+impl Default for ProgramTest {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl ProgramTest {
+    /// Every VECT.AI program plus system, SPL Token, Token-2022, Ed25519,
+    /// the [`mock_amm`], [`mock_dlmm`], [`mock_phoenix`], [`mock_lifinity`],
+    /// [`mock_stable`], [`mock_jupiter`], [`mock_drift`] and [`mock_solend`],
+    /// and a payer holding 100 SOL
+    pub fn new() -> Self {
+        let mut test = ProgramTest {
+            accounts: HashMap::new(),
+            programs: HashMap::new(),
+            clock: Clock {
+                slot: 1,
+                epoch_start_timestamp: GENESIS_TIMESTAMP,
+                epoch: 0,
+                leader_schedule_epoch: 1,
+                unix_timestamp: GENESIS_TIMESTAMP,
+            },
+            payer: Keypair::new(),
+            logs: Vec::new(),
+            return_data: None,
+            compute_units_consumed: 0,
+        };
+        test.add_program(system_program::ID, runtime::process_system);
+        test.add_program(ed25519_program::ID, runtime::process_ed25519);
+        test.add_program(spl_token::ID, spl_token::processor::Processor::process);
+        test.add_program(spl_token_2022::ID, spl_token_2022::processor::Processor::process);
+        test.add_program(vectai_token::ID, anchor_processor!(vectai_token::entry));
+        test.add_program(vectai_oracle::ID, anchor_processor!(vectai_oracle::entry));
+        test.add_program(vectai_trader::ID, anchor_processor!(vectai_trader::entry));
+        test.add_program(raydium_swapper::ID, anchor_processor!(raydium_swapper::entry));
+        test.add_program(vectai_protocol::ID, anchor_processor!(vectai_protocol::entry));
+        test.add_program(vectai_vault::ID, anchor_processor!(vectai_vault::entry));
+        test.add_program(vectai_index::ID, anchor_processor!(vectai_index::entry));
+        test.add_program(vectai_orderbook::ID, anchor_processor!(vectai_orderbook::entry));
+        test.add_program(vectai_treasury::ID, anchor_processor!(vectai_treasury::entry));
+        test.add_program(vectai_farm::ID, anchor_processor!(vectai_farm::entry));
+        test.add_program(vectai_referral::ID, anchor_processor!(vectai_referral::entry));
+        test.add_program(vectai_timelock::ID, anchor_processor!(vectai_timelock::entry));
+        test.add_program(vectai_transfer_hook::ID, anchor_processor!(vectai_transfer_hook::entry));
+        test.add_program(mock_amm::ID, mock_amm::process);
+        test.add_program(mock_dlmm::ID, mock_dlmm::process);
+        test.add_program(mock_phoenix::ID, mock_phoenix::process);
+        test.add_program(mock_lifinity::ID, mock_lifinity::process);
+        test.add_program(mock_stable::ID, mock_stable::process);
+        test.add_program(mock_jupiter::ID, mock_jupiter::process);
+        test.add_program(mock_drift::ID, mock_drift::process);
+        test.add_program(mock_solend::ID, mock_solend::process);
+        let payer = test.payer.pubkey();
+        test.airdrop(&payer, 100 * LAMPORTS_PER_SOL);
+        test
+    }
+
+    /// Register `processor` as the executable program at `program_id`
+    pub fn add_program(&mut self, program_id: Pubkey, processor: ProcessInstruction) {
+        self.programs.insert(program_id, processor);
+        self.accounts.insert(
+            program_id,
+            Account {
+                lamports: 1,
+                data: Vec::new(),
+                owner: bpf_loader::ID,
+                executable: true,
+                rent_epoch: 0,
+            },
+        );
+    }
+
+    pub fn payer(&self) -> &Keypair {
+        &self.payer
+    }
+
+    pub fn account(&self, address: &Pubkey) -> Option<Account> {
+        self.accounts.get(address).cloned()
+    }
+
+    pub fn set_account(&mut self, address: Pubkey, account: Account) {
+        self.accounts.insert(address, account);
+    }
+
+    pub fn lamports(&self, address: &Pubkey) -> u64 {
+        self.accounts.get(address).map_or(0, |account| account.lamports)
+    }
+
+    /// Credit `lamports` to `address`, creating a system account if needed
+    pub fn airdrop(&mut self, address: &Pubkey, lamports: u64) {
+        self.accounts
+            .entry(*address)
+            .or_insert_with(|| Account::new(0, 0, &system_program::ID))
+            .lamports += lamports;
+    }
+
+    /// Write a rent-exempt Anchor account, discriminator included
+    pub fn set_anchor_account<T: AccountSerialize + Owner>(&mut self, address: Pubkey, state: &T) {
+        let mut data = Vec::new();
+        state.try_serialize(&mut data).expect("serialize account");
+        self.set_account(address, rent_exempt(data, T::owner()));
+    }
+
+    /// Decode an Anchor account; `None` when missing or of another type
+    pub fn anchor_account<T: AccountDeserialize>(&self, address: &Pubkey) -> Option<T> {
+        let account = self.accounts.get(address)?;
+        T::try_deserialize(&mut account.data.as_slice()).ok()
+    }
+
+    /// Write an initialized SPL Token mint
+    pub fn create_mint(&mut self, address: Pubkey, authority: &Pubkey, decimals: u8) {
+        let mint = spl_token::state::Mint {
+            mint_authority: Some(*authority).into(),
+            supply: 0,
+            decimals,
+            is_initialized: true,
+            freeze_authority: None.into(),
+        };
+        let mut data = vec![0; spl_token::state::Mint::LEN];
+        mint.pack_into_slice(&mut data);
+        self.set_account(address, rent_exempt(data, spl_token::ID));
+    }
+
+    /// Write an initialized token account holding `amount`, owned by the
+    /// mint's token program (SPL Token or Token-2022), raising the mint's
+    /// supply to match. Mints that don't exist default to SPL Token.
+    /// Token-2022 accounts get the extensions their mint requires, such as
+    /// `TransferHookAccount`.
+    pub fn create_token_account(&mut self, address: Pubkey, mint: &Pubkey, owner: &Pubkey, amount: u64) {
+        let mut token_program = spl_token::ID;
+        let mut extensions = Vec::new();
+        let mint_account = self
+            .accounts
+            .get_mut(mint)
+            .filter(|account| account.owner == spl_token::ID || account.owner == spl_token_2022::ID);
+        if let Some(mint_account) = mint_account {
+            token_program = mint_account.owner;
+            // `supply` follows the mint authority in both layouts
+            let supply = &mut mint_account.data[36..44];
+            let total = u64::from_le_bytes(supply.try_into().unwrap()) + amount;
+            supply.copy_from_slice(&total.to_le_bytes());
+            if let Ok(state) = StateWithExtensions::<spl_token_2022::state::Mint>::unpack(&mint_account.data) {
+                let mint_extensions = state.get_extension_types().expect("mint extensions");
+                extensions = ExtensionType::get_required_init_account_extensions(&mint_extensions);
+            }
+        }
+        let account = spl_token::state::Account {
+            mint: *mint,
+            owner: *owner,
+            amount,
+            delegate: None.into(),
+            state: spl_token::state::AccountState::Initialized,
+            is_native: None.into(),
+            delegated_amount: 0,
+            close_authority: None.into(),
+        };
+        if extensions.is_empty() {
+            let mut data = vec![0; spl_token::state::Account::LEN];
+            account.pack_into_slice(&mut data);
+            self.set_account(address, rent_exempt(data, token_program));
+            return;
+        }
+        let len = ExtensionType::try_calculate_account_len::<spl_token_2022::state::Account>(&extensions).unwrap();
+        let mut data = vec![0; len];
+        let mut state = StateWithExtensionsMut::<spl_token_2022::state::Account>::unpack_uninitialized(&mut data).unwrap();
+        for extension in extensions {
+            match extension {
+                ExtensionType::TransferHookAccount => {
+                    state.init_extension::<TransferHookAccount>(true).unwrap();
+                }
+                other => panic!("unsupported account extension {:?}", other),
+            }
+        }
+        state.base = spl_token_2022::state::Account {
+            mint: *mint,
+            owner: *owner,
+            amount,
+            state: spl_token_2022::state::AccountState::Initialized,
+            ..Default::default()
+        };
+        state.pack_base();
+        state.init_account_type().unwrap();
+        self.set_account(address, rent_exempt(data, token_program));
+    }
+
+    /// Write `vectai_protocol`'s status PDA, which most instructions
+    /// check for the global pause
+    pub fn set_protocol_status(&mut self, paused: bool, fee_config: FeeConfig) -> Pubkey {
+        let (address, bump) = Pubkey::find_program_address(&[b"protocol-status"], &vectai_protocol::ID);
+        let status = ProtocolStatus {
+            guardian: Pubkey::default(),
+            paused,
+            updated_at: self.clock.unix_timestamp,
+            fee_config,
+            bump,
+        };
+        self.set_anchor_account(address, &status);
+        address
+    }
+
+    /// Write `vectai_protocol`'s activity counters, empty and sized for
+    /// every tracked mint as `initialize_protocol_stats` leaves them
+    pub fn set_protocol_stats(&mut self) -> Pubkey {
+        let (address, bump) = Pubkey::find_program_address(&[b"protocol-stats"], &vectai_protocol::ID);
+        let stats = ProtocolStats {
+            executions: 0,
+            swaps: 0,
+            mints: 0,
+            volumes: Vec::new(),
+            bump,
+            version: ProtocolStats::VERSION,
+            exposures: Vec::new(),
+        };
+        let mut data = Vec::new();
+        stats.try_serialize(&mut data).expect("serialize account");
+        data.resize(ProtocolStats::LEN, 0);
+        self.set_account(address, rent_exempt(data, vectai_protocol::ID));
+        address
+    }
+
+    /// Write `vectai_protocol`'s network config PDA, routing swaps to the
+    /// [`mock_amm`] and prices to `vectai_oracle`
+    pub fn set_network_config(&mut self, wsol_mint: Pubkey, usdc_mint: Pubkey) -> Pubkey {
+        let (address, bump) = Pubkey::find_program_address(&[b"network-config"], &vectai_protocol::ID);
+        let config = NetworkConfig {
+            raydium_amm_program: mock_amm::ID,
+            wsol_mint,
+            usdc_mint,
+            oracle_program: vectai_oracle::ID,
+            treasury_program: vectai_treasury::ID,
+            bump,
+            version: NetworkConfig::VERSION,
+            upgrade_attestations: Vec::new(),
+        };
+        let mut data = Vec::new();
+        config.try_serialize(&mut data).expect("serialize account");
+        data.resize(NetworkConfig::LEN, 0);
+        self.set_account(address, rent_exempt(data, vectai_protocol::ID));
+        address
+    }
+
+    /// Write `vectai_protocol`'s role registry with each `(holder, role)`
+    /// granted, as `grant_role` would leave it
+    pub fn set_role_registry(&mut self, grants: &[(Pubkey, Role)]) -> Pubkey {
+        let (address, bump) = Pubkey::find_program_address(&[b"role-registry"], &vectai_protocol::ID);
+        let mut registry = RoleRegistry {
+            holders: Default::default(),
+            updated_at: self.clock.unix_timestamp,
+            bump,
+        };
+        for (holder, role) in grants {
+            registry.grant(*holder, *role).expect("grant role");
+        }
+        self.set_anchor_account(address, &registry);
+        address
+    }
+
+    /// Balance of an SPL Token or Token-2022 account
+    pub fn token_balance(&self, address: &Pubkey) -> Option<u64> {
+        // `amount` sits after mint and owner in both layouts
+        let data = self.accounts.get(address)?.data.get(64..72)?;
+        Some(u64::from_le_bytes(data.try_into().ok()?))
+    }
+
+    /// Supply of an SPL Token or Token-2022 mint
+    pub fn token_supply(&self, mint: &Pubkey) -> Option<u64> {
+        let data = self.accounts.get(mint)?.data.get(36..44)?;
+        Some(u64::from_le_bytes(data.try_into().ok()?))
+    }
+
+    pub fn clock(&self) -> &Clock {
+        &self.clock
+    }
+
+    pub fn set_clock(&mut self, clock: Clock) {
+        self.clock = clock;
+    }
+
+    /// Move the clock to `unix_timestamp`, advancing the slot alongside
+    pub fn warp_to_timestamp(&mut self, unix_timestamp: i64) {
+        let elapsed = unix_timestamp.saturating_sub(self.clock.unix_timestamp).max(0);
+        // 400ms slots
+        self.clock.slot += (elapsed as u64 * 5).div_ceil(2);
+        self.clock.unix_timestamp = unix_timestamp;
+    }
+
+    /// Advance the clock by `seconds`
+    pub fn warp_forward(&mut self, seconds: i64) {
+        self.warp_to_timestamp(self.clock.unix_timestamp + seconds);
+    }
+
+    /// Program log lines of the last transaction, as a validator reports
+    /// them (`Program <id> invoke [n]`, `Program log: ..`, `Program data: ..`)
+    pub fn logs(&self) -> &[String] {
+        &self.logs
+    }
+
+    /// Events the last transaction emitted of type `T`, in order
+    pub fn events<T: Event>(&self) -> Vec<T> {
+        self.logs
+            .iter()
+            .filter_map(|line| line.strip_prefix("Program data: "))
+            .filter_map(|data| STANDARD.decode(data).ok())
+            .filter(|data| data.starts_with(&T::DISCRIMINATOR))
+            .filter_map(|data| T::deserialize(&mut &data[8..]).ok())
+            .collect()
+    }
+
+    /// Syscall compute units the last transaction consumed, failed or not
+    pub fn compute_units_consumed(&self) -> u64 {
+        self.compute_units_consumed
+    }
+
+    /// Return data left by the last transaction, with the program that set it
+    pub fn return_data(&self) -> Option<&(Pubkey, Vec<u8>)> {
+        self.return_data.as_ref()
+    }
+
+    pub fn process_instruction(&mut self, instruction: Instruction, signers: &[&Keypair]) -> Result<(), ProgramError> {
+        self.process_transaction(&[instruction], signers)
+    }
+
+    /// Run `instructions` atomically: state is written back only when all
+    /// succeed. The payer always signs.
+    pub fn process_transaction(&mut self, instructions: &[Instruction], signers: &[&Keypair]) -> Result<(), ProgramError> {
+        self.write_sysvars();
+
+        // Transaction-wide account list with merged privileges
+        let mut keys: Vec<(Pubkey, bool, bool)> = Vec::new();
+        for meta in instructions.iter().flat_map(|ix| &ix.accounts) {
+            match keys.iter_mut().find(|(key, _, _)| *key == meta.pubkey) {
+                Some((_, is_signer, is_writable)) => {
+                    *is_signer |= meta.is_signer;
+                    *is_writable |= meta.is_writable;
+                }
+                None => keys.push((meta.pubkey, meta.is_signer, meta.is_writable)),
+            }
+        }
+        let payer = self.payer.pubkey();
+        for (key, is_signer, _) in &keys {
+            if *is_signer && *key != payer && !signers.iter().any(|signer| signer.pubkey() == *key) {
+                return Err(ProgramError::MissingRequiredSignature);
+            }
+        }
+
+        if keys.iter().any(|(key, _, _)| *key == sysvar::instructions::ID) {
+            let data = instructions_sysvar(instructions);
+            self.set_account(sysvar::instructions::ID, rent_exempt(data, sysvar::ID));
+        }
+
+        let mut slots: Vec<Slot> = keys
+            .iter()
+            .map(|(key, _, _)| {
+                let account = self
+                    .accounts
+                    .get(key)
+                    .cloned()
+                    .unwrap_or_else(|| Account::new(0, 0, &system_program::ID));
+                Slot::new(*key, &account)
+            })
+            .collect();
+        let infos: Vec<AccountInfo> = slots.iter_mut().map(Slot::info).collect();
+
+        runtime::enter(InvokeContext {
+            programs: self.programs.clone(),
+            clock: self.clock.clone(),
+            stack: Vec::new(),
+            return_data: None,
+            logs: Vec::new(),
+            compute_units_consumed: 0,
+        });
+        let mut result = Ok(());
+        for (index, instruction) in instructions.iter().enumerate() {
+            if let Some(sysvar) = infos.iter().find(|info| *info.key == sysvar::instructions::ID) {
+                sysvar::instructions::store_current_index(&mut sysvar.data.borrow_mut(), index as u16);
+            }
+            let Some(processor) = self.programs.get(&instruction.program_id).copied() else {
+                result = Err(ProgramError::IncorrectProgramId);
+                break;
+            };
+            let accounts: Vec<AccountInfo> = instruction
+                .accounts
+                .iter()
+                .map(|meta| {
+                    let mut info = infos
+                        .iter()
+                        .find(|info| *info.key == meta.pubkey)
+                        .expect("account collected above")
+                        .clone();
+                    info.is_signer = meta.is_signer;
+                    info.is_writable = meta.is_writable;
+                    info
+                })
+                .collect();
+            runtime::with_context(|c| c.return_data = None);
+            if let Err(e) = runtime::run(&instruction.program_id, processor, &accounts, &instruction.data) {
+                result = Err(e);
+                break;
+            }
+        }
+        let context = runtime::exit();
+        self.logs = context.logs;
+        self.return_data = context.return_data;
+        self.compute_units_consumed = context.compute_units_consumed;
+
+        let updated: Vec<(Pubkey, Account)> = if result.is_ok() {
+            infos
+                .iter()
+                .zip(&keys)
+                .filter(|(_, (_, _, is_writable))| *is_writable)
+                .map(|(info, _)| {
+                    (
+                        *info.key,
+                        Account {
+                            lamports: info.lamports(),
+                            data: info.data.borrow().to_vec(),
+                            owner: *info.owner,
+                            executable: info.executable,
+                            rent_epoch: info.rent_epoch,
+                        },
+                    )
+                })
+                .collect()
+        } else {
+            Vec::new()
+        };
+        drop(infos);
+        drop(slots);
+        result?;
+
+        for (address, account) in updated {
+            if account.lamports == 0 {
+                self.accounts.remove(&address);
+            } else {
+                self.accounts.insert(address, account);
+            }
+        }
+        Ok(())
+    }
+
+    fn write_sysvars(&mut self) {
+        let clock = bincode::serialize(&self.clock).expect("serialize clock");
+        self.set_account(sysvar::clock::ID, rent_exempt(clock, sysvar::ID));
+        let rent = bincode::serialize(&Rent::default()).expect("serialize rent");
+        self.set_account(sysvar::rent::ID, rent_exempt(rent, sysvar::ID));
+    }
+}
+
+/// The Anchor error an instruction fails with, as the runtime reports it
+pub fn anchor_error(error: impl Into<anchor_lang::error::Error>) -> ProgramError {
+    ProgramError::from(error.into())
+}
+
+fn rent_exempt(data: Vec<u8>, owner: Pubkey) -> Account {
+    Account {
+        lamports: Rent::default().minimum_balance(data.len()),
+        data,
+        owner,
+        executable: false,
+        rent_epoch: 0,
+    }
+}
+
+fn instructions_sysvar(instructions: &[Instruction]) -> Vec<u8> {
+    use sysvar::instructions::{construct_instructions_data, BorrowedInstruction};
+
+    let borrowed: Vec<BorrowedInstruction> = instructions
+        .iter()
+        .map(|ix| BorrowedInstruction {
+            program_id: &ix.program_id,
+            accounts: ix
+                .accounts
+                .iter()
+                .map(|meta| BorrowedAccountMeta {
+                    pubkey: &meta.pubkey,
+                    is_signer: meta.is_signer,
+                    is_writable: meta.is_writable,
+                })
+                .collect(),
+            data: &ix.data,
+        })
+        .collect();
+    construct_instructions_data(&borrowed)
+}
@@ -0,0 +1,241 @@
+//! Constant-product stand-in for the Raydium AMM v4 swap instruction, so
+//! trades routed through `raydium_swap` settle real token balances.
+//!
+//! Only instructions 9 (`SwapBaseIn`) and 16 (`SwapBaseInV2`, sent by
+//! `no-serum` builds) are understood. The Serum accounts are accepted and
+//! ignored; the pool is just its two vaults, owned by the AMM authority PDA. The pool's `amm` account may hold an [`AmmBehavior`] for
+//! negative-path tests: short payouts past the minimum out, or failures.
+
+use anchor_lang::solana_program::{
+    account_info::AccountInfo,
+    entrypoint::ProgramResult,
+    program::{invoke, invoke_signed},
+    program_error::ProgramError,
+    program_pack::Pack,
+    pubkey::Pubkey,
+};
+
+use crate::ProgramTest;
+
+/// Raydium AMM v4, where `NetworkConfig` points the trader by default
+pub const ID: Pubkey = solana_sdk::pubkey!("675kPX9MHTjS2zt1qfr1NYHuzeLXfQM9H24wFSUt1Mp8");
+
+const SWAP_BASE_IN: u8 = 9;
+const SWAP_BASE_IN_V2: u8 = 16;
+const AMM_AUTHORITY_SEED: &[u8] = b"amm authority";
+
+/// Raydium's `AmmError::ExceededSlippage`
+pub const EXCEEDED_SLIPPAGE: u32 = 30;
+
+/// How the mock settles swaps on one pool, kept in its `amm` account
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum AmmBehavior {
+    /// x * y = k, failing with `EXCEEDED_SLIPPAGE` below the minimum out
+    #[default]
+    ConstantProduct,
+    /// Pay `bps` of the x * y = k output without checking the minimum out,
+    /// like a pool misreporting its reserves
+    ShortPay { bps: u16 },
+    /// Fail every swap with `ProgramError::Custom(code)`
+    Fail { code: u32 },
+}
+
+impl AmmBehavior {
+    fn pack(self) -> Vec<u8> {
+        match self {
+            AmmBehavior::ConstantProduct => Vec::new(),
+            AmmBehavior::ShortPay { bps } => [&[1][..], &bps.to_le_bytes()].concat(),
+            AmmBehavior::Fail { code } => [&[2][..], &code.to_le_bytes()].concat(),
+        }
+    }
+
+    fn unpack(data: &[u8]) -> Result<Self, ProgramError> {
+        match data {
+            [] => Ok(AmmBehavior::ConstantProduct),
+            [1, bps @ ..] => Ok(AmmBehavior::ShortPay {
+                bps: u16::from_le_bytes(bps.try_into().map_err(|_| ProgramError::InvalidAccountData)?),
+            }),
+            [2, code @ ..] => Ok(AmmBehavior::Fail {
+                code: u32::from_le_bytes(code.try_into().map_err(|_| ProgramError::InvalidAccountData)?),
+            }),
+            _ => Err(ProgramError::InvalidAccountData),
+        }
+    }
+}
+
+/// Signer of the pool vaults
+pub fn amm_authority() -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[AMM_AUTHORITY_SEED], &ID)
+}
+
+/// Every account the trader passes to the AMM, plus the pool's mints.
+/// Field names follow `vectai_trader::ExecuteTrade`.
+#[derive(Clone, Debug)]
+pub struct Pool {
+    pub amm_program: Pubkey,
+    pub amm: Pubkey,
+    pub amm_authority: Pubkey,
+    pub amm_open_orders: Pubkey,
+    pub amm_target_orders: Pubkey,
+    pub pool_coin_token_account: Pubkey,
+    pub pool_pc_token_account: Pubkey,
+    pub serum_program: Pubkey,
+    pub serum_market: Pubkey,
+    pub serum_bids: Pubkey,
+    pub serum_asks: Pubkey,
+    pub serum_event_queue: Pubkey,
+    pub serum_coin_vault: Pubkey,
+    pub serum_pc_vault: Pubkey,
+    pub serum_vault_signer: Pubkey,
+    pub coin_mint: Pubkey,
+    pub pc_mint: Pubkey,
+}
+
+impl ProgramTest {
+    /// Create a pool of `coin_reserve` `coin_mint` against `pc_reserve`
+    /// `pc_mint`, priced by x * y = k
+    pub fn create_amm_pool(
+        &mut self,
+        coin_mint: &Pubkey,
+        pc_mint: &Pubkey,
+        coin_reserve: u64,
+        pc_reserve: u64,
+    ) -> Pool {
+        let (amm_authority, _) = amm_authority();
+        let pool = Pool {
+            amm_program: ID,
+            amm: Pubkey::new_unique(),
+            amm_authority,
+            amm_open_orders: Pubkey::new_unique(),
+            amm_target_orders: Pubkey::new_unique(),
+            pool_coin_token_account: Pubkey::new_unique(),
+            pool_pc_token_account: Pubkey::new_unique(),
+            serum_program: Pubkey::new_unique(),
+            serum_market: Pubkey::new_unique(),
+            serum_bids: Pubkey::new_unique(),
+            serum_asks: Pubkey::new_unique(),
+            serum_event_queue: Pubkey::new_unique(),
+            serum_coin_vault: Pubkey::new_unique(),
+            serum_pc_vault: Pubkey::new_unique(),
+            serum_vault_signer: Pubkey::new_unique(),
+            coin_mint: *coin_mint,
+            pc_mint: *pc_mint,
+        };
+        self.fund_amm_pool(&pool, coin_reserve, pc_reserve);
+        pool
+    }
+
+    /// As [`create_amm_pool`](Self::create_amm_pool), at the addresses of
+    /// the pool `raydium_swapper` whitelists. The mock's authority PDA is
+    /// Raydium's, so the whitelist check passes unchanged.
+    pub fn create_whitelisted_pool(
+        &mut self,
+        coin_mint: &Pubkey,
+        pc_mint: &Pubkey,
+        coin_reserve: u64,
+        pc_reserve: u64,
+    ) -> Pool {
+        use raydium_swapper::pool_config::*;
+
+        let pool = Pool {
+            amm_program: ID,
+            amm: AMM_ID,
+            amm_authority: AMM_AUTHORITY,
+            amm_open_orders: AMM_OPEN_ORDERS,
+            amm_target_orders: AMM_TARGET_ORDERS,
+            pool_coin_token_account: POOL_COIN_TOKEN_ACCOUNT,
+            pool_pc_token_account: POOL_PC_TOKEN_ACCOUNT,
+            serum_program: SERUM_PROGRAM,
+            serum_market: SERUM_MARKET,
+            serum_bids: SERUM_BIDS,
+            serum_asks: SERUM_ASKS,
+            serum_event_queue: SERUM_EVENT_QUEUE,
+            serum_coin_vault: SERUM_COIN_VAULT,
+            serum_pc_vault: SERUM_PC_VAULT,
+            serum_vault_signer: SERUM_VAULT_SIGNER,
+            coin_mint: *coin_mint,
+            pc_mint: *pc_mint,
+        };
+        assert_eq!(pool.amm_authority, amm_authority().0);
+        self.fund_amm_pool(&pool, coin_reserve, pc_reserve);
+        pool
+    }
+
+    /// Settle later swaps on `pool` as `behavior` says
+    pub fn set_amm_behavior(&mut self, pool: &Pool, behavior: AmmBehavior) {
+        self.set_account(pool.amm, crate::rent_exempt(behavior.pack(), ID));
+    }
+
+    fn fund_amm_pool(&mut self, pool: &Pool, coin_reserve: u64, pc_reserve: u64) {
+        self.create_token_account(pool.pool_coin_token_account, &pool.coin_mint, &pool.amm_authority, coin_reserve);
+        self.create_token_account(pool.pool_pc_token_account, &pool.pc_mint, &pool.amm_authority, pc_reserve);
+    }
+}
+
+/// Output of swapping `amount_in` into a pool of `reserve_in`/`reserve_out`
+pub fn quote(amount_in: u64, reserve_in: u64, reserve_out: u64) -> u64 {
+    let out = reserve_out as u128 * amount_in as u128 / (reserve_in as u128 + amount_in as u128);
+    out as u64
+}
+
+/// Accounts in `raydium_swap` order: token program, AMM (5), pool vaults
+/// (2), Serum (8), user source, user destination, user owner. `SwapBaseInV2`
+/// drops the open and target orders and the Serum accounts.
+pub(crate) fn process(_program_id: &Pubkey, accounts: &[AccountInfo], data: &[u8]) -> ProgramResult {
+    let (&tag, args) = data.split_first().ok_or(ProgramError::InvalidInstructionData)?;
+    if args.len() != 16 {
+        return Err(ProgramError::InvalidInstructionData);
+    }
+    let amount_in = u64::from_le_bytes(args[..8].try_into().unwrap());
+    let minimum_out = u64::from_le_bytes(args[8..].try_into().unwrap());
+
+    let (token_program, amm, authority, pool_coin, pool_pc, user_source, user_destination, user_owner) =
+        match (tag, accounts) {
+            (
+                SWAP_BASE_IN,
+                [token_program, amm, authority, _, _, pool_coin, pool_pc, .., user_source, user_destination, user_owner],
+            )
+            | (
+                SWAP_BASE_IN_V2,
+                [token_program, amm, authority, pool_coin, pool_pc, user_source, user_destination, user_owner],
+            ) => (token_program, amm, authority, pool_coin, pool_pc, user_source, user_destination, user_owner),
+            (SWAP_BASE_IN | SWAP_BASE_IN_V2, _) => return Err(ProgramError::NotEnoughAccountKeys),
+            _ => return Err(ProgramError::InvalidInstructionData),
+        };
+    let (authority_key, bump) = amm_authority();
+    if *authority.key != authority_key {
+        return Err(ProgramError::InvalidSeeds);
+    }
+
+    let source_mint = token_account(user_source)?.mint;
+    let (vault_in, vault_out) = if source_mint == token_account(pool_coin)?.mint {
+        (pool_coin, pool_pc)
+    } else if source_mint == token_account(pool_pc)?.mint {
+        (pool_pc, pool_coin)
+    } else {
+        return Err(ProgramError::InvalidArgument);
+    };
+    let amount_out = quote(amount_in, token_account(vault_in)?.amount, token_account(vault_out)?.amount);
+    let amount_out = match AmmBehavior::unpack(&amm.data.borrow())? {
+        AmmBehavior::ConstantProduct if amount_out < minimum_out => {
+            return Err(ProgramError::Custom(EXCEEDED_SLIPPAGE));
+        }
+        AmmBehavior::ConstantProduct => amount_out,
+        AmmBehavior::ShortPay { bps } => (amount_out as u128 * bps as u128 / 10_000) as u64,
+        AmmBehavior::Fail { code } => return Err(ProgramError::Custom(code)),
+    };
+
+    invoke(
+        &spl_token::instruction::transfer(token_program.key, user_source.key, vault_in.key, user_owner.key, &[], amount_in)?,
+        &[user_source.clone(), vault_in.clone(), user_owner.clone()],
+    )?;
+    invoke_signed(
+        &spl_token::instruction::transfer(token_program.key, vault_out.key, user_destination.key, authority.key, &[], amount_out)?,
+        &[vault_out.clone(), user_destination.clone(), authority.clone()],
+        &[&[AMM_AUTHORITY_SEED, &[bump]]],
+    )
+}
+
+fn token_account(info: &AccountInfo) -> Result<spl_token::state::Account, ProgramError> {
+    spl_token::state::Account::unpack(&info.data.borrow())
+}
@@ -0,0 +1,118 @@
+//! Stand-in for Meteora DLMM's `swap` instruction, so swaps routed through
+//! `raydium_swapper::swap_meteora_dlmm` settle real token balances.
+//!
+//! Bins are not modelled: the pair is priced by x * y = k over its two
+//! reserves, like the [`mock_amm`](crate::mock_amm). The reserves are owned
+//! by the pair account, a PDA of the two mints, which signs for them.
+
+use anchor_lang::solana_program::{
+    account_info::AccountInfo,
+    entrypoint::ProgramResult,
+    hash::hash,
+    program::{invoke, invoke_signed},
+    program_error::ProgramError,
+    program_pack::Pack,
+    pubkey::Pubkey,
+};
+use solana_sdk::account::Account;
+
+use crate::{mock_amm::quote, ProgramTest};
+
+/// Meteora DLMM, where `swap_meteora_dlmm` requires the pair to live
+pub const ID: Pubkey = raydium_swapper::METEORA_DLMM_PROGRAM;
+
+/// DLMM's `LBError::ExceededAmountSlippageTolerance`
+pub const EXCEEDED_AMOUNT_SLIPPAGE_TOLERANCE: u32 = 6003;
+
+/// Every account `swap_meteora_dlmm` passes to DLMM besides the user's
+#[derive(Clone, Debug)]
+pub struct DlmmPair {
+    pub lb_pair: Pubkey,
+    pub reserve_x: Pubkey,
+    pub reserve_y: Pubkey,
+    pub token_x_mint: Pubkey,
+    pub token_y_mint: Pubkey,
+    pub oracle: Pubkey,
+    pub event_authority: Pubkey,
+    pub bin_array: Pubkey,
+}
+
+fn lb_pair(token_x_mint: &Pubkey, token_y_mint: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[token_x_mint.as_ref(), token_y_mint.as_ref()], &ID)
+}
+
+impl ProgramTest {
+    /// Create a pair of `reserve_x` `token_x_mint` against `reserve_y`
+    /// `token_y_mint`
+    pub fn create_dlmm_pair(
+        &mut self,
+        token_x_mint: &Pubkey,
+        token_y_mint: &Pubkey,
+        reserve_x: u64,
+        reserve_y: u64,
+    ) -> DlmmPair {
+        let pair = DlmmPair {
+            lb_pair: lb_pair(token_x_mint, token_y_mint).0,
+            reserve_x: Pubkey::new_unique(),
+            reserve_y: Pubkey::new_unique(),
+            token_x_mint: *token_x_mint,
+            token_y_mint: *token_y_mint,
+            oracle: Pubkey::new_unique(),
+            event_authority: Pubkey::find_program_address(&[b"__event_authority"], &ID).0,
+            bin_array: Pubkey::new_unique(),
+        };
+        self.set_account(pair.lb_pair, Account::new(1, 8, &ID));
+        self.create_token_account(pair.reserve_x, token_x_mint, &pair.lb_pair, reserve_x);
+        self.create_token_account(pair.reserve_y, token_y_mint, &pair.lb_pair, reserve_y);
+        pair
+    }
+}
+
+/// Accounts in DLMM `swap` order: pair, bitmap extension, reserves (2),
+/// user in, user out, mints (2), oracle, host fee, user, token programs
+/// (2), event authority, program, then the bin arrays
+pub(crate) fn process(_program_id: &Pubkey, accounts: &[AccountInfo], data: &[u8]) -> ProgramResult {
+    let (discriminator, args) = data.split_at_checked(8).ok_or(ProgramError::InvalidInstructionData)?;
+    if discriminator != &hash(b"global:swap").to_bytes()[..8] || args.len() != 16 {
+        return Err(ProgramError::InvalidInstructionData);
+    }
+    let amount_in = u64::from_le_bytes(args[..8].try_into().unwrap());
+    let min_amount_out = u64::from_le_bytes(args[8..].try_into().unwrap());
+
+    let [pair, _bitmap, reserve_x, reserve_y, user_in, user_out, x_mint, y_mint, _oracle, _host_fee, user, token_program, _token_y_program, _event_authority, _program, bin_arrays @ ..] =
+        accounts
+    else {
+        return Err(ProgramError::NotEnoughAccountKeys);
+    };
+    let (pair_key, bump) = lb_pair(x_mint.key, y_mint.key);
+    if *pair.key != pair_key || bin_arrays.is_empty() {
+        return Err(ProgramError::InvalidSeeds);
+    }
+
+    let source_mint = token_account(user_in)?.mint;
+    let (reserve_in, reserve_out) = if source_mint == *x_mint.key {
+        (reserve_x, reserve_y)
+    } else if source_mint == *y_mint.key {
+        (reserve_y, reserve_x)
+    } else {
+        return Err(ProgramError::InvalidArgument);
+    };
+    let amount_out = quote(amount_in, token_account(reserve_in)?.amount, token_account(reserve_out)?.amount);
+    if amount_out < min_amount_out {
+        return Err(ProgramError::Custom(EXCEEDED_AMOUNT_SLIPPAGE_TOLERANCE));
+    }
+
+    invoke(
+        &spl_token::instruction::transfer(token_program.key, user_in.key, reserve_in.key, user.key, &[], amount_in)?,
+        &[user_in.clone(), reserve_in.clone(), user.clone()],
+    )?;
+    invoke_signed(
+        &spl_token::instruction::transfer(token_program.key, reserve_out.key, user_out.key, pair.key, &[], amount_out)?,
+        &[reserve_out.clone(), user_out.clone(), pair.clone()],
+        &[&[x_mint.key.as_ref(), y_mint.key.as_ref(), &[bump]]],
+    )
+}
+
+fn token_account(info: &AccountInfo) -> Result<spl_token::state::Account, ProgramError> {
+    spl_token::state::Account::unpack(&info.data.borrow())
+}
@@ -0,0 +1,137 @@
+//! Stand-in for Drift v2's `withdraw` instruction, so hedge collateral
+//! withdrawals settle real token balances, plus `User` accounts laid out
+//! the way Drift serializes them.
+//!
+//! The spot market vault is owned by the mock's signer PDA and holds the
+//! user's free collateral: like Drift, a withdrawal is capped at it rather
+//! than failing. Every other Drift instruction is refused.
+
+use anchor_lang::solana_program::{
+    account_info::AccountInfo,
+    entrypoint::ProgramResult,
+    hash::hash,
+    native_token::LAMPORTS_PER_SOL,
+    program::invoke_signed,
+    program_error::ProgramError,
+    program_pack::Pack,
+    pubkey::Pubkey,
+};
+use solana_sdk::account::Account;
+
+use crate::ProgramTest;
+
+/// Drift v2, where the hedge instructions require Drift to live
+pub const ID: Pubkey = vectai_trader::DRIFT_PROGRAM;
+
+/// Size of a Drift `User`: discriminator, authority, delegate, name, 8
+/// spot positions of 40 bytes, 8 perp positions of 96 bytes, 32 orders of
+/// 96 bytes, then 112 bytes of counters and flags
+pub const USER_LEN: usize = 8 + 32 + 32 + 32 + 8 * 40 + 8 * PERP_POSITION_LEN + 32 * 96 + 112;
+
+const PERP_POSITION_LEN: usize = 96;
+
+/// The perp fields of a Drift `PerpPosition` the hedge reads
+#[derive(Clone, Copy, Debug, Default)]
+pub struct PerpPosition {
+    pub market_index: u16,
+    pub base_asset_amount: i64, // Negative = short
+    pub open_orders: u8,
+}
+
+/// Drift's vault signer PDA
+pub fn drift_signer() -> Pubkey {
+    Pubkey::find_program_address(&[b"drift_signer"], &ID).0
+}
+
+/// Drift `User` data owned by `authority` holding `positions` in its first
+/// perp slots. The fields the hedge doesn't read are filled in too, as a
+/// live account's would be, so a misread offset picks them up.
+pub fn user_data(authority: &Pubkey, positions: &[PerpPosition]) -> Vec<u8> {
+    assert!(positions.len() <= 8);
+    let mut data = hash(b"account:User").to_bytes()[..8].to_vec();
+    data.extend_from_slice(authority.as_ref());
+    data.extend_from_slice(authority.as_ref()); // delegate
+    data.extend_from_slice(&[b' '; 32]); // name
+    for market_index in 0..8u16 {
+        // SpotPosition: scaled_balance, open_bids, open_asks,
+        // cumulative_deposits, market_index, balance_type, open_orders,
+        // padding
+        data.extend_from_slice(&1_000_000u64.to_le_bytes());
+        data.extend_from_slice(&(-7i64).to_le_bytes());
+        data.extend_from_slice(&7i64.to_le_bytes());
+        data.extend_from_slice(&1_000_000i64.to_le_bytes());
+        data.extend_from_slice(&market_index.to_le_bytes());
+        data.extend_from_slice(&[0, 1, 0, 0, 0, 0]);
+    }
+    for slot in 0..8 {
+        let start = data.len();
+        let Some(position) = positions.get(slot) else {
+            data.resize(start + PERP_POSITION_LEN, 0);
+            continue;
+        };
+        let quote = -position.base_asset_amount.saturating_mul(150);
+        data.extend_from_slice(&12i64.to_le_bytes()); // last_cumulative_funding_rate
+        data.extend_from_slice(&position.base_asset_amount.to_le_bytes());
+        for field in [quote, quote, quote, -3, 3, 0] {
+            // quote_asset_amount, quote_break_even_amount,
+            // quote_entry_amount, open_bids, open_asks, settled_pnl
+            data.extend_from_slice(&field.to_le_bytes());
+        }
+        data.extend_from_slice(&[0; 8 * 3 + 4]); // lp_shares, last_*_per_lp, remainder_base_asset_amount
+        data.extend_from_slice(&position.market_index.to_le_bytes());
+        data.push(position.open_orders);
+        data.push(0); // per_lp_base
+        assert_eq!(data.len() - start, PERP_POSITION_LEN);
+    }
+    data.resize(USER_LEN, 0); // orders and the trailing counters
+    data
+}
+
+impl ProgramTest {
+    /// Write a Drift `User` at `address` holding `positions`
+    pub fn set_drift_user(&mut self, address: Pubkey, authority: &Pubkey, positions: &[PerpPosition]) {
+        let mut account = Account::new(LAMPORTS_PER_SOL, USER_LEN, &ID);
+        account.data = user_data(authority, positions);
+        self.set_account(address, account);
+    }
+
+    /// Create a Drift spot market vault of `mint` holding `free_collateral`,
+    /// all of it withdrawable
+    pub fn create_drift_vault(&mut self, address: Pubkey, mint: &Pubkey, free_collateral: u64) {
+        self.create_token_account(address, mint, &drift_signer(), free_collateral);
+    }
+}
+
+/// Accounts in Drift `withdraw` order: state, user, user stats, authority,
+/// spot market vault, drift signer, user token account, token program
+pub(crate) fn process(_program_id: &Pubkey, accounts: &[AccountInfo], data: &[u8]) -> ProgramResult {
+    if data.len() != 8 + 2 + 8 + 1 || data[..8] != hash(b"global:withdraw").to_bytes()[..8] {
+        return Err(ProgramError::InvalidInstructionData);
+    }
+    let amount = u64::from_le_bytes(data[10..18].try_into().unwrap());
+
+    let [_state, user, _user_stats, authority, vault, signer, user_token, token_program, ..] = accounts else {
+        return Err(ProgramError::NotEnoughAccountKeys);
+    };
+    if *user.owner != ID || !authority.is_signer || user.data.borrow()[8..40] != authority.key.to_bytes() {
+        return Err(ProgramError::IllegalOwner);
+    }
+    let (signer_key, bump) = Pubkey::find_program_address(&[b"drift_signer"], &ID);
+    if *signer.key != signer_key {
+        return Err(ProgramError::InvalidSeeds);
+    }
+
+    let free_collateral = spl_token::state::Account::unpack(&vault.data.borrow())?.amount;
+    invoke_signed(
+        &spl_token::instruction::transfer(
+            token_program.key,
+            vault.key,
+            user_token.key,
+            signer.key,
+            &[],
+            amount.min(free_collateral),
+        )?,
+        &[vault.clone(), user_token.clone(), signer.clone()],
+        &[&[b"drift_signer", &[bump]]],
+    )
+}
@@ -0,0 +1,151 @@
+//! Stand-in for Jupiter v6's `route` instruction, so routes passed to
+//! `vectai_trader::execute_jupiter_route` settle real token balances.
+//!
+//! The route plan is opaque to the mock: every route is one
+//! constant-product hop through a pool of the mock's own, whose accounts
+//! follow Jupiter's nine fixed ones. The pool's vaults are owned by its
+//! authority PDA, which signs for them.
+
+use anchor_lang::solana_program::{
+    account_info::AccountInfo,
+    entrypoint::ProgramResult,
+    hash::hash,
+    instruction::AccountMeta,
+    program::{invoke, invoke_signed},
+    program_error::ProgramError,
+    program_pack::Pack,
+    pubkey::Pubkey,
+};
+use solana_sdk::account::Account;
+
+use crate::{mock_amm, ProgramTest};
+
+/// Jupiter v6, where `execute_jupiter_route` requires routes to go
+pub const ID: Pubkey = vectai_trader::JUPITER_V6_PROGRAM;
+
+/// Jupiter's `SlippageToleranceExceeded`
+pub const SLIPPAGE_TOLERANCE_EXCEEDED: u32 = 6001;
+
+/// One pool a route hops through, trading `mint_a` against `mint_b`
+#[derive(Clone, Debug)]
+pub struct JupiterPool {
+    pub pool: Pubkey,
+    pub authority: Pubkey,
+    pub vault_a: Pubkey,
+    pub vault_b: Pubkey,
+    pub mint_a: Pubkey,
+    pub mint_b: Pubkey,
+}
+
+impl JupiterPool {
+    /// Accounts of a `route` from `source` into `destination` (of
+    /// `destination_mint`) signed by `authority`: Jupiter's fixed accounts,
+    /// then the hop's
+    pub fn route_accounts(
+        &self,
+        authority: &Pubkey,
+        source: Pubkey,
+        destination: Pubkey,
+        destination_mint: &Pubkey,
+    ) -> Vec<AccountMeta> {
+        let (vault_in, vault_out) = if *destination_mint == self.mint_b {
+            (self.vault_a, self.vault_b)
+        } else {
+            (self.vault_b, self.vault_a)
+        };
+        vec![
+            AccountMeta::new_readonly(spl_token::ID, false),
+            AccountMeta::new_readonly(*authority, true),
+            AccountMeta::new(source, false),
+            AccountMeta::new(destination, false),
+            AccountMeta::new_readonly(ID, false), // No separate destination
+            AccountMeta::new_readonly(*destination_mint, false),
+            AccountMeta::new_readonly(ID, false), // No platform fee account
+            AccountMeta::new_readonly(Pubkey::new_unique(), false), // Event authority
+            AccountMeta::new_readonly(ID, false),
+            AccountMeta::new_readonly(self.pool, false),
+            AccountMeta::new_readonly(self.authority, false),
+            AccountMeta::new(vault_in, false),
+            AccountMeta::new(vault_out, false),
+        ]
+    }
+}
+
+/// Jupiter `route` data: an opaque one-step route plan, then the amounts
+pub fn route_data(in_amount: u64, quoted_out_amount: u64, slippage_bps: u16, platform_fee_bps: u8) -> Vec<u8> {
+    let mut data = hash(b"global:route").to_bytes()[..8].to_vec();
+    data.extend_from_slice(&1u32.to_le_bytes()); // Route plan length
+    data.extend_from_slice(&[0, 100, 0, 1]); // Swap variant, percent, input and output index
+    data.extend_from_slice(&in_amount.to_le_bytes());
+    data.extend_from_slice(&quoted_out_amount.to_le_bytes());
+    data.extend_from_slice(&slippage_bps.to_le_bytes());
+    data.push(platform_fee_bps);
+    data
+}
+
+fn pool_authority(pool: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[pool.as_ref()], &ID)
+}
+
+impl ProgramTest {
+    /// Create a route pool of `reserve_a` `mint_a` against `reserve_b` `mint_b`
+    pub fn create_jupiter_pool(&mut self, mint_a: &Pubkey, mint_b: &Pubkey, reserve_a: u64, reserve_b: u64) -> JupiterPool {
+        let pool = Pubkey::new_unique();
+        let jupiter_pool = JupiterPool {
+            pool,
+            authority: pool_authority(&pool).0,
+            vault_a: Pubkey::new_unique(),
+            vault_b: Pubkey::new_unique(),
+            mint_a: *mint_a,
+            mint_b: *mint_b,
+        };
+        self.set_account(pool, Account::new(1, 0, &ID));
+        self.create_token_account(jupiter_pool.vault_a, mint_a, &jupiter_pool.authority, reserve_a);
+        self.create_token_account(jupiter_pool.vault_b, mint_b, &jupiter_pool.authority, reserve_b);
+        jupiter_pool
+    }
+}
+
+/// Accounts in `route` order: token program, user transfer authority, user
+/// source, user destination, destination, destination mint, platform fee,
+/// event authority, program, then the hop's pool, authority and vaults
+pub(crate) fn process(_program_id: &Pubkey, accounts: &[AccountInfo], data: &[u8]) -> ProgramResult {
+    let (discriminator, rest) = data.split_at_checked(8).ok_or(ProgramError::InvalidInstructionData)?;
+    if discriminator != &hash(b"global:route").to_bytes()[..8] || rest.len() < 19 {
+        return Err(ProgramError::InvalidInstructionData);
+    }
+    let args = &rest[rest.len() - 19..];
+    let in_amount = u64::from_le_bytes(args[..8].try_into().unwrap());
+    let quoted_out_amount = u64::from_le_bytes(args[8..16].try_into().unwrap());
+    let slippage_bps = u16::from_le_bytes(args[16..18].try_into().unwrap());
+
+    let [token_program, user, user_in, user_out, _destination, _destination_mint, _platform_fee, _event_authority, _program, pool, authority, pool_in, pool_out] =
+        accounts
+    else {
+        return Err(ProgramError::NotEnoughAccountKeys);
+    };
+    let (authority_key, bump) = pool_authority(pool.key);
+    if *authority.key != authority_key || *pool.owner != ID {
+        return Err(ProgramError::InvalidSeeds);
+    }
+
+    let amount_out = mock_amm::quote(in_amount, token_account(pool_in)?.amount, token_account(pool_out)?.amount);
+    let minimum_out = quoted_out_amount as u128 * (10_000 - slippage_bps as u128) / 10_000;
+    if (amount_out as u128) < minimum_out {
+        return Err(ProgramError::Custom(SLIPPAGE_TOLERANCE_EXCEEDED));
+    }
+
+    invoke(
+        &spl_token::instruction::transfer(token_program.key, user_in.key, pool_in.key, user.key, &[], in_amount)?,
+        &[user_in.clone(), pool_in.clone(), user.clone()],
+    )?;
+    invoke_signed(
+        &spl_token::instruction::transfer(token_program.key, pool_out.key, user_out.key, authority.key, &[], amount_out)?,
+        &[pool_out.clone(), user_out.clone(), authority.clone()],
+        &[&[pool.key.as_ref(), &[bump]]],
+    )
+}
+
+fn token_account(info: &AccountInfo) -> Result<spl_token::state::Account, ProgramError> {
+    spl_token::state::Account::unpack(&info.data.borrow())
+}
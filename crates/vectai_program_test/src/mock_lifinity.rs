@@ -0,0 +1,170 @@
+//! Stand-in for Lifinity v2's `swap` instruction, so swaps routed through
+//! `raydium_swapper::swap_lifinity` settle real token balances.
+//!
+//! The pool is priced the way `vectai_math::PoolPricing::OracleAnchored`
+//! quotes it: around the price in its main oracle account, not its
+//! reserve ratio. The pool and oracle layouts are the mock's own; the
+//! vaults are owned by the pool's authority PDA, which signs for them.
+
+use anchor_lang::solana_program::{
+    account_info::AccountInfo,
+    entrypoint::ProgramResult,
+    hash::hash,
+    program::{invoke, invoke_signed},
+    program_error::ProgramError,
+    program_pack::Pack,
+    pubkey::Pubkey,
+};
+use solana_sdk::account::Account;
+use vectai_math::{expected_amount_out, PoolPricing};
+
+use crate::ProgramTest;
+
+/// Lifinity v2, where `swap_lifinity` requires the pool to live
+pub const ID: Pubkey = raydium_swapper::LIFINITY_V2_PROGRAM;
+
+/// Returned when the output is below the minimum (the mock's own code,
+/// not Lifinity's)
+pub const EXCEEDED_SLIPPAGE: u32 = 1;
+
+/// Every account `swap_lifinity` passes to Lifinity besides the user's.
+/// The pool trades `base_mint` (WSOL) against `quote_mint` (USDC).
+#[derive(Clone, Debug)]
+pub struct LifinityPool {
+    pub amm: Pubkey,
+    pub amm_authority: Pubkey,
+    pub base_vault: Pubkey,
+    pub quote_vault: Pubkey,
+    pub pool_mint: Pubkey,
+    pub pool_fee_account: Pubkey,
+    pub oracle_main_account: Pubkey,
+    pub oracle_sub_account: Pubkey,
+    pub oracle_pc_account: Pubkey,
+    pub base_mint: Pubkey,
+    pub quote_mint: Pubkey,
+}
+
+impl LifinityPool {
+    /// The pool's (source, destination) vaults for a swap out of `source_mint`
+    pub fn vaults(&self, source_mint: &Pubkey) -> (Pubkey, Pubkey) {
+        if *source_mint == self.quote_mint {
+            (self.quote_vault, self.base_vault)
+        } else {
+            (self.base_vault, self.quote_vault)
+        }
+    }
+}
+
+fn amm_authority(amm: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[amm.as_ref()], &ID)
+}
+
+impl ProgramTest {
+    /// Create a pool of `base_reserve` `base_mint` against `quote_reserve`
+    /// `quote_mint` with curve depth `concentration` and a `fee_bps` input
+    /// fee, priced at `price` x 10^`expo` quote per base
+    #[allow(clippy::too_many_arguments)]
+    pub fn create_lifinity_pool(
+        &mut self,
+        base_mint: &Pubkey,
+        quote_mint: &Pubkey,
+        base_reserve: u64,
+        quote_reserve: u64,
+        concentration: u64,
+        fee_bps: u64,
+        price: i64,
+        expo: i32,
+    ) -> LifinityPool {
+        let amm = Pubkey::new_unique();
+        let pool = LifinityPool {
+            amm,
+            amm_authority: amm_authority(&amm).0,
+            base_vault: Pubkey::new_unique(),
+            quote_vault: Pubkey::new_unique(),
+            pool_mint: Pubkey::new_unique(),
+            pool_fee_account: Pubkey::new_unique(),
+            oracle_main_account: Pubkey::new_unique(),
+            oracle_sub_account: Pubkey::new_unique(),
+            oracle_pc_account: Pubkey::new_unique(),
+            base_mint: *base_mint,
+            quote_mint: *quote_mint,
+        };
+        let mut account = Account::new(1, 32 + 32 + 8 + 8, &ID);
+        account.data[..32].copy_from_slice(base_mint.as_ref());
+        account.data[32..64].copy_from_slice(quote_mint.as_ref());
+        account.data[64..72].copy_from_slice(&concentration.to_le_bytes());
+        account.data[72..80].copy_from_slice(&fee_bps.to_le_bytes());
+        self.set_account(amm, account);
+        self.create_token_account(pool.base_vault, base_mint, &pool.amm_authority, base_reserve);
+        self.create_token_account(pool.quote_vault, quote_mint, &pool.amm_authority, quote_reserve);
+        self.set_lifinity_price(&pool, price, expo);
+        pool
+    }
+
+    /// Move the price in `pool`'s main oracle account
+    pub fn set_lifinity_price(&mut self, pool: &LifinityPool, price: i64, expo: i32) {
+        let mut account = Account::new(1, 8 + 4, &ID);
+        account.data[..8].copy_from_slice(&price.to_le_bytes());
+        account.data[8..].copy_from_slice(&expo.to_le_bytes());
+        self.set_account(pool.oracle_main_account, account);
+    }
+}
+
+/// Accounts in Lifinity `swap` order: authority, amm, user, user source,
+/// user destination, pool source, pool destination, LP mint, fee account,
+/// token program, then the main, sub and quote oracles
+pub(crate) fn process(_program_id: &Pubkey, accounts: &[AccountInfo], data: &[u8]) -> ProgramResult {
+    let (discriminator, args) = data.split_at_checked(8).ok_or(ProgramError::InvalidInstructionData)?;
+    if discriminator != &hash(b"global:swap").to_bytes()[..8] || args.len() != 16 {
+        return Err(ProgramError::InvalidInstructionData);
+    }
+    let amount_in = u64::from_le_bytes(args[..8].try_into().unwrap());
+    let min_amount_out = u64::from_le_bytes(args[8..].try_into().unwrap());
+
+    let [authority, amm, user, user_in, user_out, pool_in, pool_out, _pool_mint, _fee_account, token_program, oracle, _oracle_sub, _oracle_pc] =
+        accounts
+    else {
+        return Err(ProgramError::NotEnoughAccountKeys);
+    };
+    let (authority_key, bump) = amm_authority(amm.key);
+    if *authority.key != authority_key || *amm.owner != ID {
+        return Err(ProgramError::InvalidSeeds);
+    }
+
+    let (quote_mint, concentration, fee_bps) = {
+        let pool = amm.data.borrow();
+        let field = |at: usize| u64::from_le_bytes(pool[at..at + 8].try_into().unwrap());
+        (Pubkey::try_from(&pool[32..64]).unwrap(), field(64), field(72))
+    };
+    let (price, expo) = {
+        let oracle = oracle.data.borrow();
+        let price = i64::from_le_bytes(oracle[..8].try_into().unwrap());
+        (price, i32::from_le_bytes(oracle[8..12].try_into().unwrap()))
+    };
+    let source_is_usdc = token_account(user_in)?.mint == quote_mint;
+    let pricing = PoolPricing::OracleAnchored {
+        price,
+        expo,
+        reserve_out: token_account(pool_out)?.amount,
+        concentration,
+    };
+    let amount_out = expected_amount_out(amount_in, source_is_usdc, pricing, fee_bps)
+        .map_err(|_| ProgramError::InvalidAccountData)?;
+    if amount_out < min_amount_out {
+        return Err(ProgramError::Custom(EXCEEDED_SLIPPAGE));
+    }
+
+    invoke(
+        &spl_token::instruction::transfer(token_program.key, user_in.key, pool_in.key, user.key, &[], amount_in)?,
+        &[user_in.clone(), pool_in.clone(), user.clone()],
+    )?;
+    invoke_signed(
+        &spl_token::instruction::transfer(token_program.key, pool_out.key, user_out.key, authority.key, &[], amount_out)?,
+        &[pool_out.clone(), user_out.clone(), authority.clone()],
+        &[&[amm.key.as_ref(), &[bump]]],
+    )
+}
+
+fn token_account(info: &AccountInfo) -> Result<spl_token::state::Account, ProgramError> {
+    spl_token::state::Account::unpack(&info.data.borrow())
+}
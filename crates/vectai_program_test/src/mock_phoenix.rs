@@ -0,0 +1,168 @@
+//! Stand-in for Phoenix's `Swap` instruction with an immediate-or-cancel
+//! order packet, so swaps routed through `raydium_swapper::swap_phoenix_ioc`
+//! settle real token balances.
+//!
+//! The book is not modelled: orders fill by x * y = k over the two vaults,
+//! like the [`mock_amm`](crate::mock_amm), rounded down to whole lots. Each
+//! vault is a PDA of the market and its mint, as on Phoenix, and owns
+//! itself.
+
+use anchor_lang::solana_program::{
+    account_info::AccountInfo,
+    entrypoint::ProgramResult,
+    program::{invoke, invoke_signed},
+    program_error::ProgramError,
+    program_pack::Pack,
+    pubkey::Pubkey,
+};
+use raydium_swapper::{PhoenixMarket, SIDE_ASK, SIDE_BID};
+use solana_sdk::account::Account;
+
+use crate::{mock_amm::quote, ProgramTest};
+
+/// Phoenix, where `swap_phoenix_ioc` requires the market to live
+pub const ID: Pubkey = raydium_swapper::PHOENIX_PROGRAM;
+
+/// Returned when an order cannot fill its minimum lots (the mock's own
+/// code, not Phoenix's)
+pub const MIN_FILL_NOT_MET: u32 = 1;
+
+/// Every account `swap_phoenix_ioc` passes to Phoenix besides the user's
+#[derive(Clone, Debug)]
+pub struct PhoenixBook {
+    pub market: Pubkey,
+    pub log_authority: Pubkey,
+    pub header: PhoenixMarket,
+}
+
+fn vault(market: &Pubkey, mint: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[b"vault", market.as_ref(), mint.as_ref()], &ID)
+}
+
+impl ProgramTest {
+    /// Create a market of `base_mint` against `quote_mint` trading in lots
+    /// of `base_lot_size` and `quote_lot_size`, with `base_reserve` and
+    /// `quote_reserve` in its vaults
+    pub fn create_phoenix_market(
+        &mut self,
+        base_mint: &Pubkey,
+        quote_mint: &Pubkey,
+        base_lot_size: u64,
+        quote_lot_size: u64,
+        base_reserve: u64,
+        quote_reserve: u64,
+    ) -> PhoenixBook {
+        let market = Pubkey::new_unique();
+        let header = PhoenixMarket {
+            base_mint: *base_mint,
+            base_vault: vault(&market, base_mint).0,
+            base_lot_size,
+            quote_mint: *quote_mint,
+            quote_vault: vault(&market, quote_mint).0,
+            quote_lot_size,
+        };
+        let mut account = Account::new(1, 256, &ID);
+        let data = &mut account.data;
+        data[48..80].copy_from_slice(base_mint.as_ref());
+        data[80..112].copy_from_slice(header.base_vault.as_ref());
+        data[112..120].copy_from_slice(&base_lot_size.to_le_bytes());
+        data[128..160].copy_from_slice(quote_mint.as_ref());
+        data[160..192].copy_from_slice(header.quote_vault.as_ref());
+        data[192..200].copy_from_slice(&quote_lot_size.to_le_bytes());
+        self.set_account(market, account);
+        self.create_token_account(header.base_vault, base_mint, &header.base_vault, base_reserve);
+        self.create_token_account(header.quote_vault, quote_mint, &header.quote_vault, quote_reserve);
+        PhoenixBook {
+            market,
+            log_authority: Pubkey::find_program_address(&[b"log"], &ID).0,
+            header,
+        }
+    }
+}
+
+/// Accounts in Phoenix `Swap` order: program, log authority, market,
+/// trader, base account, quote account, base vault, quote vault, token
+/// program
+pub(crate) fn process(_program_id: &Pubkey, accounts: &[AccountInfo], data: &[u8]) -> ProgramResult {
+    let order = IocPacket::parse(data).ok_or(ProgramError::InvalidInstructionData)?;
+    let [_program, _log_authority, market, trader, base_account, quote_account, base_vault, quote_vault, token_program] =
+        accounts
+    else {
+        return Err(ProgramError::NotEnoughAccountKeys);
+    };
+    let header = PhoenixMarket::parse(&market.data.borrow()).ok_or(ProgramError::InvalidAccountData)?;
+    if *base_vault.key != header.base_vault || *quote_vault.key != header.quote_vault {
+        return Err(ProgramError::InvalidSeeds);
+    }
+
+    let base_reserve = token_account(base_vault)?.amount;
+    let quote_reserve = token_account(quote_vault)?.amount;
+    let (user_in, vault_in, vault_out, user_out, amount_in, amount_out, out_mint) = match order.side {
+        SIDE_BID => {
+            let quote_in = order.num_quote_lots * header.quote_lot_size;
+            let base_lots = quote(quote_in, quote_reserve, base_reserve) / header.base_lot_size;
+            if base_lots == 0 || base_lots < order.min_base_lots_to_fill {
+                return Err(ProgramError::Custom(MIN_FILL_NOT_MET));
+            }
+            let base_out = base_lots * header.base_lot_size;
+            (quote_account, quote_vault, base_vault, base_account, quote_in, base_out, header.base_mint)
+        }
+        SIDE_ASK => {
+            let base_in = order.num_base_lots * header.base_lot_size;
+            let quote_lots = quote(base_in, base_reserve, quote_reserve) / header.quote_lot_size;
+            if quote_lots == 0 || quote_lots < order.min_quote_lots_to_fill {
+                return Err(ProgramError::Custom(MIN_FILL_NOT_MET));
+            }
+            let quote_out = quote_lots * header.quote_lot_size;
+            (base_account, base_vault, quote_vault, quote_account, base_in, quote_out, header.quote_mint)
+        }
+        _ => return Err(ProgramError::InvalidInstructionData),
+    };
+
+    invoke(
+        &spl_token::instruction::transfer(token_program.key, user_in.key, vault_in.key, trader.key, &[], amount_in)?,
+        &[user_in.clone(), vault_in.clone(), trader.clone()],
+    )?;
+    let (_, bump) = vault(market.key, &out_mint);
+    invoke_signed(
+        &spl_token::instruction::transfer(token_program.key, vault_out.key, user_out.key, vault_out.key, &[], amount_out)?,
+        &[vault_out.clone(), user_out.clone()],
+        &[&[b"vault", market.key.as_ref(), out_mint.as_ref(), &[bump]]],
+    )
+}
+
+/// The fields of a `Swap` with an `ImmediateOrCancel` packet the mock
+/// fills by; the rest must be at their market-order defaults
+struct IocPacket {
+    side: u8,
+    num_base_lots: u64,
+    num_quote_lots: u64,
+    min_base_lots_to_fill: u64,
+    min_quote_lots_to_fill: u64,
+}
+
+impl IocPacket {
+    fn parse(data: &[u8]) -> Option<Self> {
+        let [0, 2, side, 0, rest @ ..] = data else {
+            return None;
+        };
+        let (lots, rest) = rest.split_at_checked(32)?;
+        let lot = |i: usize| u64::from_le_bytes(lots[i * 8..i * 8 + 8].try_into().unwrap());
+        // Self-trade behavior, no match limit, client order id, not only
+        // deposited funds, no expiry
+        if rest.len() != 21 || rest.iter().any(|byte| *byte != 0) {
+            return None;
+        }
+        Some(IocPacket {
+            side: *side,
+            num_base_lots: lot(0),
+            num_quote_lots: lot(1),
+            min_base_lots_to_fill: lot(2),
+            min_quote_lots_to_fill: lot(3),
+        })
+    }
+}
+
+fn token_account(info: &AccountInfo) -> Result<spl_token::state::Account, ProgramError> {
+    spl_token::state::Account::unpack(&info.data.borrow())
+}
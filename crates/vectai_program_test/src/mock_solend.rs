@@ -0,0 +1,225 @@
+//! Stand-in for Solend's `deposit_reserve_liquidity` and
+//! `redeem_reserve_collateral`, so vault lending settles real token
+//! balances, plus `Reserve` accounts laid out the way Solend packs them.
+//!
+//! Collateral is minted and redeemed at the reserve's exchange rate, total
+//! liquidity over collateral supply, as Solend computes it. The lending
+//! market authority PDA owns the liquidity supply and the collateral mint.
+//! Reserves never go stale, and every other Solend instruction is refused.
+
+use anchor_lang::solana_program::{
+    account_info::AccountInfo,
+    entrypoint::ProgramResult,
+    native_token::LAMPORTS_PER_SOL,
+    program::{invoke, invoke_signed},
+    program_error::ProgramError,
+    program_pack::Pack,
+    pubkey::Pubkey,
+};
+use solana_sdk::account::Account;
+
+use crate::ProgramTest;
+
+/// Solend, where vault lending requires the reserve to live
+pub const ID: Pubkey = vectai_vault::SOLEND_PROGRAM;
+
+/// Size of a Solend `Reserve`
+pub const RESERVE_LEN: usize = 619;
+
+const WAD: u128 = 1_000_000_000_000_000_000;
+
+// Byte offsets into a `Reserve` the mock reads or writes
+const LENDING_MARKET: usize = 10;
+const LIQUIDITY_MINT: usize = 42;
+const LIQUIDITY_SUPPLY: usize = 75;
+const AVAILABLE_AMOUNT: usize = 171;
+const BORROWED_AMOUNT_WADS: usize = 179;
+const CUMULATIVE_BORROW_RATE_WADS: usize = 195;
+const COLLATERAL_MINT: usize = 227;
+const COLLATERAL_SUPPLY: usize = 259;
+const PROTOCOL_FEES_WADS: usize = 373;
+
+/// Every account of a reserve the vault passes to Solend besides its own
+#[derive(Clone, Debug)]
+pub struct Reserve {
+    pub reserve: Pubkey,
+    pub liquidity_supply: Pubkey,
+    pub collateral_mint: Pubkey,
+    pub lending_market: Pubkey,
+    pub lending_market_authority: Pubkey,
+}
+
+fn lending_market_authority(lending_market: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[lending_market.as_ref()], &ID)
+}
+
+fn u64_at(data: &[u8], offset: usize) -> u64 {
+    u64::from_le_bytes(data[offset..offset + 8].try_into().unwrap())
+}
+
+fn u128_at(data: &[u8], offset: usize) -> u128 {
+    u128::from_le_bytes(data[offset..offset + 16].try_into().unwrap())
+}
+
+/// (total liquidity, collateral supply), the way Solend values collateral
+fn exchange_rate(data: &[u8]) -> (u128, u64) {
+    let total_wads = u64_at(data, AVAILABLE_AMOUNT) as u128 * WAD + u128_at(data, BORROWED_AMOUNT_WADS)
+        - u128_at(data, PROTOCOL_FEES_WADS);
+    (total_wads / WAD, u64_at(data, COLLATERAL_SUPPLY))
+}
+
+impl ProgramTest {
+    /// Create an empty reserve lending `liquidity_mint`, with a collateral
+    /// mint of `decimals` decimals
+    pub fn create_solend_reserve(&mut self, liquidity_mint: &Pubkey, decimals: u8) -> Reserve {
+        let lending_market = Pubkey::new_unique();
+        let reserve = Reserve {
+            reserve: Pubkey::new_unique(),
+            liquidity_supply: Pubkey::new_unique(),
+            collateral_mint: Pubkey::new_unique(),
+            lending_market,
+            lending_market_authority: lending_market_authority(&lending_market).0,
+        };
+        self.create_token_account(reserve.liquidity_supply, liquidity_mint, &reserve.lending_market_authority, 0);
+        self.create_mint(reserve.collateral_mint, &reserve.lending_market_authority, decimals);
+
+        let mut account = Account::new(LAMPORTS_PER_SOL, RESERVE_LEN, &ID);
+        let data = &mut account.data;
+        data[0] = 1; // version
+        data[LENDING_MARKET..LENDING_MARKET + 32].copy_from_slice(lending_market.as_ref());
+        data[LIQUIDITY_MINT..LIQUIDITY_MINT + 32].copy_from_slice(liquidity_mint.as_ref());
+        data[LIQUIDITY_MINT + 32] = decimals;
+        data[LIQUIDITY_SUPPLY..LIQUIDITY_SUPPLY + 32].copy_from_slice(reserve.liquidity_supply.as_ref());
+        data[CUMULATIVE_BORROW_RATE_WADS..CUMULATIVE_BORROW_RATE_WADS + 16].copy_from_slice(&WAD.to_le_bytes());
+        data[COLLATERAL_MINT..COLLATERAL_MINT + 32].copy_from_slice(reserve.collateral_mint.as_ref());
+        self.set_account(reserve.reserve, account);
+        reserve
+    }
+
+    /// Pay `interest` into the reserve, as borrowers repaying would, raising
+    /// what its collateral redeems for
+    pub fn pay_solend_interest(&mut self, reserve: &Reserve, interest: u64) {
+        let mut account = self.account(&reserve.reserve).unwrap();
+        let available = u64_at(&account.data, AVAILABLE_AMOUNT) + interest;
+        account.data[AVAILABLE_AMOUNT..AVAILABLE_AMOUNT + 8].copy_from_slice(&available.to_le_bytes());
+        self.set_account(reserve.reserve, account);
+
+        let mint = self.account(&reserve.liquidity_supply).unwrap().data[..32].try_into().unwrap();
+        let balance = self.token_balance(&reserve.liquidity_supply).unwrap();
+        self.create_token_account(
+            reserve.liquidity_supply,
+            &Pubkey::new_from_array(mint),
+            &reserve.lending_market_authority,
+            balance + interest,
+        );
+    }
+}
+
+/// Accounts in Solend order. Deposit: source liquidity, destination
+/// collateral, reserve, liquidity supply, collateral mint, lending market,
+/// market authority, transfer authority, token program. Redeem: source
+/// collateral, destination liquidity, reserve, collateral mint, liquidity
+/// supply, then as deposit.
+pub(crate) fn process(_program_id: &Pubkey, accounts: &[AccountInfo], data: &[u8]) -> ProgramResult {
+    let (deposit, amount) = match data {
+        [4, amount @ ..] if amount.len() == 8 => (true, u64::from_le_bytes(amount.try_into().unwrap())),
+        [5, amount @ ..] if amount.len() == 8 => (false, u64::from_le_bytes(amount.try_into().unwrap())),
+        _ => return Err(ProgramError::InvalidInstructionData),
+    };
+    let [source, destination, reserve, first, second, lending_market, authority, transfer_authority, token_program] =
+        accounts
+    else {
+        return Err(ProgramError::NotEnoughAccountKeys);
+    };
+    let (liquidity_supply, collateral_mint) = if deposit { (first, second) } else { (second, first) };
+    if *reserve.owner != ID {
+        return Err(ProgramError::IllegalOwner);
+    }
+    {
+        let data = reserve.data.borrow();
+        if data[LENDING_MARKET..LENDING_MARKET + 32] != lending_market.key.to_bytes()
+            || data[LIQUIDITY_SUPPLY..LIQUIDITY_SUPPLY + 32] != liquidity_supply.key.to_bytes()
+            || data[COLLATERAL_MINT..COLLATERAL_MINT + 32] != collateral_mint.key.to_bytes()
+        {
+            return Err(ProgramError::InvalidAccountData);
+        }
+    }
+    let (authority_key, bump) = lending_market_authority(lending_market.key);
+    if *authority.key != authority_key {
+        return Err(ProgramError::InvalidSeeds);
+    }
+    let seeds: &[&[u8]] = &[lending_market.key.as_ref(), &[bump]];
+
+    let (total_liquidity, collateral_supply) = exchange_rate(&reserve.data.borrow());
+    let (liquidity, collateral) = if deposit {
+        let collateral = match collateral_supply {
+            0 => amount,
+            supply => (amount as u128 * supply as u128 / total_liquidity) as u64,
+        };
+        invoke(
+            &spl_token::instruction::transfer(
+                token_program.key,
+                source.key,
+                liquidity_supply.key,
+                transfer_authority.key,
+                &[],
+                amount,
+            )?,
+            &[source.clone(), liquidity_supply.clone(), transfer_authority.clone()],
+        )?;
+        invoke_signed(
+            &spl_token::instruction::mint_to(
+                token_program.key,
+                collateral_mint.key,
+                destination.key,
+                authority.key,
+                &[],
+                collateral,
+            )?,
+            &[collateral_mint.clone(), destination.clone(), authority.clone()],
+            &[seeds],
+        )?;
+        (amount, collateral)
+    } else {
+        let liquidity = (amount as u128 * total_liquidity / collateral_supply as u128) as u64;
+        let supply_balance = spl_token::state::Account::unpack(&liquidity_supply.data.borrow())?.amount;
+        if liquidity > supply_balance {
+            return Err(ProgramError::InsufficientFunds);
+        }
+        invoke(
+            &spl_token::instruction::burn(
+                token_program.key,
+                source.key,
+                collateral_mint.key,
+                transfer_authority.key,
+                &[],
+                amount,
+            )?,
+            &[source.clone(), collateral_mint.clone(), transfer_authority.clone()],
+        )?;
+        invoke_signed(
+            &spl_token::instruction::transfer(
+                token_program.key,
+                liquidity_supply.key,
+                destination.key,
+                authority.key,
+                &[],
+                liquidity,
+            )?,
+            &[liquidity_supply.clone(), destination.clone(), authority.clone()],
+            &[seeds],
+        )?;
+        (liquidity, amount)
+    };
+
+    let mut data = reserve.data.borrow_mut();
+    let (available, supply) = (u64_at(&data, AVAILABLE_AMOUNT), u64_at(&data, COLLATERAL_SUPPLY));
+    let (available, supply) = if deposit {
+        (available + liquidity, supply + collateral)
+    } else {
+        (available - liquidity, supply - collateral)
+    };
+    data[AVAILABLE_AMOUNT..AVAILABLE_AMOUNT + 8].copy_from_slice(&available.to_le_bytes());
+    data[COLLATERAL_SUPPLY..COLLATERAL_SUPPLY + 8].copy_from_slice(&supply.to_le_bytes());
+    Ok(())
+}
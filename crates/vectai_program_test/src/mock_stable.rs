@@ -0,0 +1,143 @@
+//! Stand-in for the StableSwap `swap` instruction, so swaps routed through
+//! `raydium_swapper::swap_stable` settle real token balances.
+//!
+//! The pool is priced the way `vectai_math::PoolPricing::StableSwap`
+//! quotes it, from its amplification and vault balances. The pool layout
+//! is the mock's own; the vaults are owned by the pool's authority PDA,
+//! which signs for them, and no admin fee is taken.
+
+use anchor_lang::solana_program::{
+    account_info::AccountInfo,
+    entrypoint::ProgramResult,
+    program::{invoke, invoke_signed},
+    program_error::ProgramError,
+    program_pack::Pack,
+    pubkey::Pubkey,
+};
+use solana_sdk::account::Account;
+use vectai_math::{expected_amount_out, PoolPricing};
+
+use crate::ProgramTest;
+
+/// StableSwap, where `swap_stable` requires the pool to live
+pub const ID: Pubkey = raydium_swapper::STABLE_SWAP_PROGRAM;
+
+/// Returned when the output is below the minimum (the mock's own code,
+/// not StableSwap's)
+pub const EXCEEDED_SLIPPAGE: u32 = 1;
+
+/// Every account `swap_stable` passes to StableSwap besides the user's
+#[derive(Clone, Debug)]
+pub struct StablePool {
+    pub pool: Pubkey,
+    pub pool_authority: Pubkey,
+    pub vault_a: Pubkey,
+    pub vault_b: Pubkey,
+    pub admin_fee_a: Pubkey,
+    pub admin_fee_b: Pubkey,
+    pub mint_a: Pubkey,
+    pub mint_b: Pubkey,
+}
+
+impl StablePool {
+    /// The pool's (source, destination, destination admin fee) accounts
+    /// for a swap out of `source_mint`
+    pub fn accounts(&self, source_mint: &Pubkey) -> (Pubkey, Pubkey, Pubkey) {
+        if *source_mint == self.mint_a {
+            (self.vault_a, self.vault_b, self.admin_fee_b)
+        } else {
+            (self.vault_b, self.vault_a, self.admin_fee_a)
+        }
+    }
+}
+
+fn pool_authority(pool: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[pool.as_ref()], &ID)
+}
+
+impl ProgramTest {
+    /// Create a pool of `reserve_a` `mint_a` against `reserve_b` `mint_b`
+    /// with amplification `amplification` and a `fee_bps` input fee
+    pub fn create_stable_pool(
+        &mut self,
+        mint_a: &Pubkey,
+        mint_b: &Pubkey,
+        reserve_a: u64,
+        reserve_b: u64,
+        amplification: u64,
+        fee_bps: u64,
+    ) -> StablePool {
+        let pool = Pubkey::new_unique();
+        let stable = StablePool {
+            pool,
+            pool_authority: pool_authority(&pool).0,
+            vault_a: Pubkey::new_unique(),
+            vault_b: Pubkey::new_unique(),
+            admin_fee_a: Pubkey::new_unique(),
+            admin_fee_b: Pubkey::new_unique(),
+            mint_a: *mint_a,
+            mint_b: *mint_b,
+        };
+        let mut account = Account::new(1, 8 + 8, &ID);
+        account.data[..8].copy_from_slice(&amplification.to_le_bytes());
+        account.data[8..].copy_from_slice(&fee_bps.to_le_bytes());
+        self.set_account(pool, account);
+        self.create_token_account(stable.vault_a, mint_a, &stable.pool_authority, reserve_a);
+        self.create_token_account(stable.vault_b, mint_b, &stable.pool_authority, reserve_b);
+        self.create_token_account(stable.admin_fee_a, mint_a, &stable.pool_authority, 0);
+        self.create_token_account(stable.admin_fee_b, mint_b, &stable.pool_authority, 0);
+        stable
+    }
+}
+
+/// Accounts in StableSwap `swap` order: pool, pool authority, user, user
+/// source, pool source, pool destination, user destination, admin fee
+/// destination, token program
+pub(crate) fn process(_program_id: &Pubkey, accounts: &[AccountInfo], data: &[u8]) -> ProgramResult {
+    let [1, args @ ..] = data else {
+        return Err(ProgramError::InvalidInstructionData);
+    };
+    if args.len() != 16 {
+        return Err(ProgramError::InvalidInstructionData);
+    }
+    let amount_in = u64::from_le_bytes(args[..8].try_into().unwrap());
+    let min_amount_out = u64::from_le_bytes(args[8..].try_into().unwrap());
+
+    let [pool, authority, user, user_in, pool_in, pool_out, user_out, _admin_fee, token_program] = accounts else {
+        return Err(ProgramError::NotEnoughAccountKeys);
+    };
+    let (authority_key, bump) = pool_authority(pool.key);
+    if *authority.key != authority_key || *pool.owner != ID {
+        return Err(ProgramError::InvalidSeeds);
+    }
+
+    let (amplification, fee_bps) = {
+        let pool = pool.data.borrow();
+        let field = |at: usize| u64::from_le_bytes(pool[at..at + 8].try_into().unwrap());
+        (field(0), field(8))
+    };
+    let pricing = PoolPricing::StableSwap {
+        amplification,
+        reserve_in: token_account(pool_in)?.amount,
+        reserve_out: token_account(pool_out)?.amount,
+    };
+    let amount_out =
+        expected_amount_out(amount_in, false, pricing, fee_bps).map_err(|_| ProgramError::InvalidAccountData)?;
+    if amount_out < min_amount_out {
+        return Err(ProgramError::Custom(EXCEEDED_SLIPPAGE));
+    }
+
+    invoke(
+        &spl_token::instruction::transfer(token_program.key, user_in.key, pool_in.key, user.key, &[], amount_in)?,
+        &[user_in.clone(), pool_in.clone(), user.clone()],
+    )?;
+    invoke_signed(
+        &spl_token::instruction::transfer(token_program.key, pool_out.key, user_out.key, authority.key, &[], amount_out)?,
+        &[pool_out.clone(), user_out.clone(), authority.clone()],
+        &[&[pool.key.as_ref(), &[bump]]],
+    )
+}
+
+fn token_account(info: &AccountInfo) -> Result<spl_token::state::Account, ProgramError> {
+    spl_token::state::Account::unpack(&info.data.borrow())
+}
@@ -0,0 +1,378 @@
+//! Native stand-ins for the parts of the runtime programs touch: syscalls,
+//! cross-program invocation, the system program and the Ed25519 precompile.
+
+use std::{cell::RefCell, collections::HashMap, slice, sync::Once};
+
+use anchor_lang::solana_program::{
+    account_info::AccountInfo,
+    clock::Clock,
+    entrypoint::{ProgramResult, MAX_PERMITTED_DATA_INCREASE, SUCCESS},
+    epoch_schedule::EpochSchedule,
+    instruction::Instruction,
+    program_error::{ProgramError, UNSUPPORTED_SYSVAR},
+    program_stubs::{set_syscall_stubs, SyscallStubs},
+    pubkey::Pubkey,
+    rent::Rent,
+    system_instruction::{SystemError, SystemInstruction},
+    system_program,
+};
+use base64::{engine::general_purpose::STANDARD, Engine};
+use solana_sdk::{account::Account, ed25519_instruction, feature_set::FeatureSet};
+
+use crate::ProcessInstruction;
+
+/// Transaction-wide limit `sol_remaining_compute_units` counts down from
+const COMPUTE_BUDGET: u64 = 1_400_000;
+
+// Syscall costs of the default compute budget. Only syscalls are metered:
+// SBF instructions, PDA derivation and hashing run natively for free.
+const SYSCALL_BASE_COST: u64 = 100;
+const INVOKE_UNITS: u64 = 1_000;
+const CPI_BYTES_PER_UNIT: u64 = 250;
+const SYSVAR_BASE_COST: u64 = 100;
+
+/// Deepest CPI the runtime allows, counting the top-level instruction
+const MAX_STACK_HEIGHT: usize = 5;
+
+/// State the syscalls of the transaction being processed read and write
+pub(crate) struct InvokeContext {
+    pub programs: HashMap<Pubkey, ProcessInstruction>,
+    pub clock: Clock,
+    pub stack: Vec<Pubkey>,
+    pub return_data: Option<(Pubkey, Vec<u8>)>,
+    pub logs: Vec<String>,
+    pub compute_units_consumed: u64,
+}
+
+thread_local! {
+    static CONTEXT: RefCell<Option<InvokeContext>> = const { RefCell::new(None) };
+}
+
+pub(crate) fn enter(context: InvokeContext) {
+    static INSTALL: Once = Once::new();
+    INSTALL.call_once(|| {
+        set_syscall_stubs(Box::new(NativeStubs));
+    });
+    CONTEXT.with(|c| *c.borrow_mut() = Some(context));
+}
+
+pub(crate) fn exit() -> InvokeContext {
+    CONTEXT
+        .with(|c| c.borrow_mut().take())
+        .expect("exit without enter")
+}
+
+pub(crate) fn with_context<R>(f: impl FnOnce(&mut InvokeContext) -> R) -> Option<R> {
+    CONTEXT.with(|c| c.borrow_mut().as_mut().map(f))
+}
+
+fn consume(units: u64) {
+    with_context(|c| c.compute_units_consumed = c.compute_units_consumed.saturating_add(units));
+}
+
+/// Run `processor` as `program_id`, logging like the runtime does
+pub(crate) fn run(
+    program_id: &Pubkey,
+    processor: ProcessInstruction,
+    accounts: &[AccountInfo],
+    data: &[u8],
+) -> ProgramResult {
+    let height = with_context(|c| {
+        c.stack.push(*program_id);
+        c.logs.push(format!("Program {} invoke [{}]", program_id, c.stack.len()));
+        c.stack.len()
+    })
+    .expect("run outside a transaction");
+    let result = if height > MAX_STACK_HEIGHT {
+        Err(ProgramError::InvalidInstructionData)
+    } else {
+        processor(program_id, accounts, data)
+    };
+    with_context(|c| {
+        c.stack.pop();
+        c.logs.push(match &result {
+            Ok(()) => format!("Program {} success", program_id),
+            Err(e) => format!("Program {} failed: {}", program_id, e),
+        });
+    });
+    result
+}
+
+/// Reborrow accounts at the single lifetime Anchor's `entry` expects.
+///
+/// # Safety
+///
+/// The callee must not store references into the accounts that outlive
+/// the call; programs only ever hold them for the instruction.
+pub unsafe fn shorten<'a, 'b>(accounts: &'b [AccountInfo<'a>]) -> &'b [AccountInfo<'b>] {
+    std::mem::transmute(accounts)
+}
+
+fn invoke(instruction: &Instruction, account_infos: &[AccountInfo], signers_seeds: &[&[&[u8]]]) -> ProgramResult {
+    let (caller, processor) = with_context(|c| {
+        (
+            *c.stack.last().expect("CPI outside an instruction"),
+            c.programs.get(&instruction.program_id).copied(),
+        )
+    })
+    .expect("CPI outside a transaction");
+    let processor = processor.ok_or(ProgramError::IncorrectProgramId)?;
+    let pda_signers = signers_seeds
+        .iter()
+        .map(|seeds| Pubkey::create_program_address(seeds, &caller))
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|_| ProgramError::InvalidSeeds)?;
+
+    let mut accounts = Vec::with_capacity(instruction.accounts.len());
+    for meta in &instruction.accounts {
+        let info = account_infos
+            .iter()
+            .find(|info| *info.key == meta.pubkey)
+            .ok_or(ProgramError::NotEnoughAccountKeys)?;
+        if meta.is_signer && !info.is_signer && !pda_signers.contains(&meta.pubkey) {
+            return Err(ProgramError::MissingRequiredSignature);
+        }
+        if meta.is_writable && !info.is_writable {
+            return Err(ProgramError::InvalidArgument);
+        }
+        let mut info = info.clone();
+        info.is_signer = meta.is_signer;
+        info.is_writable = meta.is_writable;
+        accounts.push(info);
+    }
+    run(&instruction.program_id, processor, &accounts, &instruction.data)
+}
+
+struct NativeStubs;
+
+impl SyscallStubs for NativeStubs {
+    fn sol_log(&self, message: &str) {
+        consume(SYSCALL_BASE_COST.max(message.len() as u64));
+        with_context(|c| c.logs.push(format!("Program log: {}", message)));
+    }
+
+    fn sol_log_data(&self, fields: &[&[u8]]) {
+        let bytes: usize = fields.iter().map(|field| field.len()).sum();
+        consume(SYSCALL_BASE_COST * (1 + fields.len() as u64) + bytes as u64);
+        let encoded: Vec<String> = fields.iter().map(|field| STANDARD.encode(field)).collect();
+        with_context(|c| c.logs.push(format!("Program data: {}", encoded.join(" "))));
+    }
+
+    fn sol_log_compute_units(&self) {
+        let remaining = self.sol_remaining_compute_units();
+        with_context(|c| c.logs.push(format!("Program consumption: {} units remaining", remaining)));
+    }
+
+    fn sol_remaining_compute_units(&self) -> u64 {
+        consume(SYSCALL_BASE_COST);
+        with_context(|c| COMPUTE_BUDGET.saturating_sub(c.compute_units_consumed)).unwrap_or(COMPUTE_BUDGET)
+    }
+
+    fn sol_invoke_signed(
+        &self,
+        instruction: &Instruction,
+        account_infos: &[AccountInfo],
+        signers_seeds: &[&[&[u8]]],
+    ) -> ProgramResult {
+        // Instruction data and every account's data are copied across
+        let account_bytes: usize = account_infos.iter().map(AccountInfo::data_len).sum();
+        consume(INVOKE_UNITS + (instruction.data.len() + account_bytes) as u64 / CPI_BYTES_PER_UNIT);
+        invoke(instruction, account_infos, signers_seeds)
+    }
+
+    fn sol_get_clock_sysvar(&self, var_addr: *mut u8) -> u64 {
+        write_sysvar(var_addr, with_context(|c| c.clock.clone()))
+    }
+
+    fn sol_get_rent_sysvar(&self, var_addr: *mut u8) -> u64 {
+        write_sysvar(var_addr, Some(Rent::default()))
+    }
+
+    fn sol_get_epoch_schedule_sysvar(&self, var_addr: *mut u8) -> u64 {
+        write_sysvar(var_addr, Some(EpochSchedule::default()))
+    }
+
+    fn sol_get_return_data(&self) -> Option<(Pubkey, Vec<u8>)> {
+        let return_data = with_context(|c| c.return_data.clone()).flatten();
+        let len = return_data.as_ref().map_or(0, |(_, data)| data.len());
+        consume(SYSCALL_BASE_COST + len as u64 / CPI_BYTES_PER_UNIT);
+        return_data
+    }
+
+    fn sol_set_return_data(&self, data: &[u8]) {
+        consume(SYSCALL_BASE_COST + data.len() as u64 / CPI_BYTES_PER_UNIT);
+        with_context(|c| {
+            let program = *c.stack.last().expect("return data outside an instruction");
+            c.return_data = (!data.is_empty()).then(|| (program, data.to_vec()));
+        });
+    }
+
+    fn sol_get_stack_height(&self) -> u64 {
+        with_context(|c| c.stack.len() as u64).unwrap_or(0)
+    }
+}
+
+fn write_sysvar<T>(var_addr: *mut u8, value: Option<T>) -> u64 {
+    consume(SYSVAR_BASE_COST + std::mem::size_of::<T>() as u64);
+    match value {
+        Some(value) => {
+            // SAFETY: the sysvar getters pass a pointer to an initialized T
+            unsafe { std::ptr::write(var_addr as *mut T, value) };
+            SUCCESS
+        }
+        None => UNSUPPORTED_SYSVAR,
+    }
+}
+
+/// System program instructions Anchor and SPL issue
+pub(crate) fn process_system(_program_id: &Pubkey, accounts: &[AccountInfo], data: &[u8]) -> ProgramResult {
+    let instruction: SystemInstruction =
+        bincode::deserialize(data).map_err(|_| ProgramError::InvalidInstructionData)?;
+    match instruction {
+        SystemInstruction::CreateAccount { lamports, space, owner } => {
+            let [from, to, ..] = accounts else {
+                return Err(ProgramError::NotEnoughAccountKeys);
+            };
+            require_signer(from)?;
+            require_signer(to)?;
+            if to.lamports() > 0 || !to.data_is_empty() || *to.owner != system_program::ID {
+                return Err(ProgramError::Custom(SystemError::AccountAlreadyInUse as u32));
+            }
+            move_lamports(from, to, lamports)?;
+            to.realloc(space as usize, true)?;
+            to.assign(&owner);
+        }
+        SystemInstruction::Assign { owner } => {
+            let [account, ..] = accounts else {
+                return Err(ProgramError::NotEnoughAccountKeys);
+            };
+            require_signer(account)?;
+            require_system_owned(account)?;
+            account.assign(&owner);
+        }
+        SystemInstruction::Transfer { lamports } => {
+            let [from, to, ..] = accounts else {
+                return Err(ProgramError::NotEnoughAccountKeys);
+            };
+            require_signer(from)?;
+            require_system_owned(from)?;
+            if !from.data_is_empty() {
+                return Err(ProgramError::InvalidArgument);
+            }
+            move_lamports(from, to, lamports)?;
+        }
+        SystemInstruction::Allocate { space } => {
+            let [account, ..] = accounts else {
+                return Err(ProgramError::NotEnoughAccountKeys);
+            };
+            require_signer(account)?;
+            require_system_owned(account)?;
+            if !account.data_is_empty() {
+                return Err(ProgramError::Custom(SystemError::AccountAlreadyInUse as u32));
+            }
+            account.realloc(space as usize, true)?;
+        }
+        _ => return Err(ProgramError::InvalidInstructionData),
+    }
+    Ok(())
+}
+
+/// Ed25519 signature verification; only signatures whose key, signature
+/// and message all sit in the precompile instruction itself are supported
+pub(crate) fn process_ed25519(_program_id: &Pubkey, _accounts: &[AccountInfo], data: &[u8]) -> ProgramResult {
+    ed25519_instruction::verify(data, &[data], &FeatureSet::all_enabled())
+        .map_err(|err| ProgramError::Custom(err as u32))
+}
+
+fn require_signer(account: &AccountInfo) -> ProgramResult {
+    if account.is_signer {
+        Ok(())
+    } else {
+        Err(ProgramError::MissingRequiredSignature)
+    }
+}
+
+fn require_system_owned(account: &AccountInfo) -> ProgramResult {
+    if *account.owner == system_program::ID {
+        Ok(())
+    } else {
+        Err(ProgramError::InvalidAccountOwner)
+    }
+}
+
+fn move_lamports(from: &AccountInfo, to: &AccountInfo, lamports: u64) -> ProgramResult {
+    let mut from_lamports = from.try_borrow_mut_lamports()?;
+    **from_lamports = from_lamports
+        .checked_sub(lamports)
+        .ok_or(ProgramError::Custom(SystemError::ResultWithNegativeLamports as u32))?;
+    drop(from_lamports);
+    let mut to_lamports = to.try_borrow_mut_lamports()?;
+    **to_lamports = to_lamports.checked_add(lamports).ok_or(ProgramError::ArithmeticOverflow)?;
+    Ok(())
+}
+
+/// Key laid out as the runtime serializes it: the account's original data
+/// length sits just before, where `AccountInfo::realloc` reads it
+#[repr(C)]
+struct KeyCell {
+    original_data_len: u32,
+    key: Pubkey,
+}
+
+/// One account of a transaction, with its data in a runtime-shaped buffer:
+/// an 8-aligned length header, the data, then `MAX_PERMITTED_DATA_INCREASE`
+/// bytes of headroom so `realloc` works natively
+pub(crate) struct Slot {
+    key: Box<KeyCell>,
+    owner: Box<Pubkey>,
+    lamports: Box<u64>,
+    buffer: Vec<u64>,
+    len: usize,
+    executable: bool,
+    rent_epoch: u64,
+}
+
+impl Slot {
+    pub fn new(key: Pubkey, account: &Account) -> Self {
+        let len = account.data.len();
+        let words = (8 + len + MAX_PERMITTED_DATA_INCREASE).div_ceil(8);
+        let mut slot = Slot {
+            key: Box::new(KeyCell {
+                original_data_len: len as u32,
+                key,
+            }),
+            owner: Box::new(account.owner),
+            lamports: Box::new(account.lamports),
+            buffer: vec![0u64; words],
+            len,
+            executable: account.executable,
+            rent_epoch: account.rent_epoch,
+        };
+        slot.buffer[0] = len as u64;
+        slot.bytes()[8..8 + len].copy_from_slice(&account.data);
+        slot
+    }
+
+    fn bytes(&mut self) -> &mut [u8] {
+        // SAFETY: u64 storage reinterpreted as bytes, same allocation
+        unsafe { slice::from_raw_parts_mut(self.buffer.as_mut_ptr() as *mut u8, self.buffer.len() * 8) }
+    }
+
+    pub fn info(&mut self) -> AccountInfo<'_> {
+        let len = self.len;
+        // SAFETY: as in `bytes`; the header word stays outside the slice
+        let data = unsafe {
+            slice::from_raw_parts_mut((self.buffer.as_mut_ptr() as *mut u8).add(8), len)
+        };
+        AccountInfo::new(
+            &self.key.key,
+            false,
+            false,
+            &mut self.lamports,
+            data,
+            &self.owner,
+            self.executable,
+            self.rent_epoch,
+        )
+    }
+}
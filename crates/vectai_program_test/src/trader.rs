@@ -0,0 +1,192 @@
+//! Seeded `vectai_trader` state, so a test of one instruction's logic
+//! (cooldowns, caps, threshold math) writes the accounts it reads directly
+//! instead of running the instructions that would create them.
+
+use anchor_lang::solana_program::{pubkey::Pubkey, sysvar};
+use vectai_common::Versioned;
+use vectai_protocol::FeeConfig;
+use vectai_trader::{
+    accounts, DailyTradeCap, DrawdownTracker, DynamicSlippage, TakeProfitLadder, TraderConfig, TradingSchedule,
+    VolatilityFilter,
+};
+
+use crate::{mock_amm::Pool, ProgramTest};
+
+/// Seconds `execute_trade` enforces between executions
+pub const TRADE_COOLDOWN: i64 = 60;
+
+/// Trader PDA of `authority`
+pub fn trader_address(authority: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(&[b"trader", authority.as_ref()], &vectai_trader::ID).0
+}
+
+/// Trader PDA `subscribe` instantiates for `subscriber` from `listing`
+pub fn subscription_trader_address(subscriber: &Pubkey, listing: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(&[b"trader", subscriber.as_ref(), listing.as_ref()], &vectai_trader::ID).0
+}
+
+/// Config as `initialize_trader` writes it
+pub fn new_trader_config(authority: Pubkey, price_threshold: i64, swap_amount: u64, slippage_tolerance: u64) -> TraderConfig {
+    TraderConfig {
+        authority,
+        price_threshold,
+        swap_amount,
+        slippage_tolerance,
+        total_swaps: 0,
+        last_swap_time: 0,
+        is_active: true,
+        subscription_expires_at: 0,
+        auto_compound: false,
+        total_compounded: 0,
+        risk: DrawdownTracker::default(),
+        referrer: Pubkey::default(),
+        condition: Vec::new(),
+        version: TraderConfig::VERSION,
+        last_nonce: 0,
+        schedule: TradingSchedule::default(),
+        dynamic_slippage: DynamicSlippage::default(),
+        volatility: VolatilityFilter::default(),
+        take_profit: TakeProfitLadder::default(),
+        trade_cap: DailyTradeCap::default(),
+        max_jito_tip: 0,
+        realized_pnl_usd: 0,
+        profit_target_usd: 0,
+        last_swap_slot: 0,
+        cooldown_slots: 0,
+        max_price_age_slots: 0,
+        owner_program: Pubkey::default(),
+        listing: Pubkey::default(),
+        marked_source_balance: 0,
+        marked_destination_balance: 0,
+        price_aggregator: Pubkey::default(),
+        max_source_deviation_bps: 0,
+    }
+}
+
+/// The USDC/WSOL network `execute_trade` routes through: protocol status
+/// and stats, network config, both mints, the whitelisted pool on the
+/// mock AMM and a SOL/USD price feed
+#[derive(Clone, Debug)]
+pub struct TradingNetwork {
+    pub protocol_status: Pubkey,
+    pub protocol_stats: Pubkey,
+    pub network_config: Pubkey,
+    pub usdc: Pubkey,
+    pub wsol: Pubkey,
+    pub pool: Pool,
+    pub price_feed: Pubkey, // The mock oracle prices any feed at 45_000
+}
+
+impl ProgramTest {
+    /// Unpaused protocol with the initial fees, and a 1:1 WSOL/USDC pool
+    /// holding `pool_reserve` of each at the whitelisted addresses
+    pub fn create_trading_network(&mut self, pool_reserve: u64) -> TradingNetwork {
+        let protocol_status = self.set_protocol_status(false, FeeConfig::INITIAL);
+        let protocol_stats = self.set_protocol_stats();
+        let (usdc, wsol) = (Pubkey::new_unique(), Pubkey::new_unique());
+        let mint_authority = Pubkey::new_unique();
+        self.create_mint(usdc, &mint_authority, vectai_math::USDC_DECIMALS);
+        self.create_mint(wsol, &mint_authority, vectai_math::WSOL_DECIMALS);
+        let network_config = self.set_network_config(wsol, usdc);
+        let pool = self.create_whitelisted_pool(&wsol, &usdc, pool_reserve, pool_reserve);
+        TradingNetwork {
+            protocol_status,
+            protocol_stats,
+            network_config,
+            usdc,
+            wsol,
+            pool,
+            price_feed: Pubkey::new_unique(),
+        }
+    }
+
+    /// Write `config` at its authority's trader PDA
+    pub fn set_trader(&mut self, config: &TraderConfig) -> Pubkey {
+        let address = trader_address(&config.authority);
+        self.set_anchor_account(address, config);
+        address
+    }
+}
+
+impl TradingNetwork {
+    /// `execute_trade` accounts for `authority`'s trader swapping `source`
+    /// into `destination` through the pool, with no optional accounts
+    pub fn execute_trade_accounts(&self, authority: &Pubkey, source: Pubkey, destination: Pubkey) -> accounts::ExecuteTrade {
+        let pool = &self.pool;
+        accounts::ExecuteTrade {
+            user_authority: *authority,
+            trader_config: trader_address(authority),
+            user_source_token_account: source,
+            user_destination_token_account: destination,
+            raydium_amm_program: pool.amm_program,
+            amm: pool.amm,
+            amm_authority: pool.amm_authority,
+            amm_open_orders: pool.amm_open_orders,
+            amm_target_orders: pool.amm_target_orders,
+            pool_coin_token_account: pool.pool_coin_token_account,
+            pool_pc_token_account: pool.pool_pc_token_account,
+            serum_program: pool.serum_program,
+            serum_market: pool.serum_market,
+            serum_bids: pool.serum_bids,
+            serum_asks: pool.serum_asks,
+            serum_event_queue: pool.serum_event_queue,
+            serum_coin_vault_account: pool.serum_coin_vault,
+            serum_pc_vault_account: pool.serum_pc_vault,
+            serum_vault_signer: pool.serum_vault_signer,
+            vectai_oracle_program: vectai_oracle::ID,
+            price_feed: self.price_feed,
+            points_ledger: None,
+            points_config: None,
+            trade_history: None,
+            trade_history_tree: None,
+            compression_program: None,
+            noop_program: None,
+            protocol_fee_account: None,
+            token_program: spl_token::ID,
+            protocol_status: self.protocol_status,
+            network_config: self.network_config,
+            protocol_stats: self.protocol_stats,
+            stats_reporter: vectai_protocol::stats_reporter(&vectai_trader::ID),
+            protocol_program: vectai_protocol::ID,
+            instructions: sysvar::instructions::ID,
+        }
+    }
+
+    /// `raydium_swapper::swap` accounts for `authority` swapping `source`
+    /// into `destination` through the pool, with no fee account
+    pub fn swap_accounts(
+        &self,
+        authority: &Pubkey,
+        source: Pubkey,
+        destination: Pubkey,
+    ) -> raydium_swapper::accounts::SwapAccounts {
+        let pool = &self.pool;
+        raydium_swapper::accounts::SwapAccounts {
+            user_authority: *authority,
+            user_source_token: source,
+            user_destination_token: destination,
+            raydium_amm_program: pool.amm_program,
+            amm: pool.amm,
+            amm_authority: pool.amm_authority,
+            amm_open_orders: pool.amm_open_orders,
+            amm_target_orders: pool.amm_target_orders,
+            pool_coin_token_account: pool.pool_coin_token_account,
+            pool_pc_token_account: pool.pool_pc_token_account,
+            serum_program: pool.serum_program,
+            serum_market: pool.serum_market,
+            serum_bids: pool.serum_bids,
+            serum_asks: pool.serum_asks,
+            serum_event_queue: pool.serum_event_queue,
+            serum_coin_vault: pool.serum_coin_vault,
+            serum_pc_vault: pool.serum_pc_vault,
+            serum_vault_signer: pool.serum_vault_signer,
+            token_program: spl_token::ID,
+            protocol_status: self.protocol_status,
+            network_config: self.network_config,
+            protocol_stats: self.protocol_stats,
+            stats_reporter: vectai_protocol::stats_reporter(&raydium_swapper::ID),
+            protocol_program: vectai_protocol::ID,
+            fee_account: None,
+        }
+    }
+}
@@ -0,0 +1,151 @@
+//! Downstream programs integrating through `vectai_cpi`: a consumer
+//! program reads the swapper's quote and a strategy over CPI with only the
+//! crate's generated accounts and typed clients, and hands the decoded
+//! values back through its own return data.
+
+use anchor_lang::{
+    solana_program::{account_info::AccountInfo, entrypoint::ProgramResult, program::set_return_data},
+    AnchorDeserialize, AnchorSerialize,
+};
+use solana_sdk::{
+    instruction::{AccountMeta, Instruction},
+    pubkey::Pubkey,
+};
+use vectai_cpi::{
+    raydium_swapper::{cpi::accounts::GetQuote, types::QuotePair},
+    swapper,
+    trader,
+    vectai_trader::cpi::accounts::GetTraderConfig,
+    CpiError,
+};
+use vectai_program_test::{anchor_error, trader::new_trader_config, ProgramTest};
+use vectai_protocol::FeeConfig;
+use vectai_trader::TraderConfig;
+
+const CONSUMER: Pubkey = Pubkey::new_from_array([0xc0; 32]);
+// Program that succeeds without setting return data
+const SILENT: Pubkey = Pubkey::new_from_array([0x51; 32]);
+
+const QUOTE: u8 = 0;
+const TRADER_CONFIG: u8 = 1;
+
+/// Consumer program: the first data byte selects the view, the rest are
+/// its arguments; the first account is the program to invoke
+fn process_consumer(_program_id: &Pubkey, accounts: &[AccountInfo], data: &[u8]) -> ProgramResult {
+    let program = accounts[0].clone();
+    let returned = match data[0] {
+        QUOTE => {
+            let (pair, amount_in) = <(QuotePair, u64)>::deserialize(&mut &data[1..])?;
+            let ctx = anchor_lang::context::CpiContext::new(
+                program,
+                GetQuote {
+                    pool_coin_token_account: accounts[1].clone(),
+                    pool_pc_token_account: accounts[2].clone(),
+                    protocol_status: accounts[3].clone(),
+                },
+            );
+            swapper::get_quote(ctx, pair, amount_in)?.try_to_vec()?
+        }
+        TRADER_CONFIG => {
+            let ctx = anchor_lang::context::CpiContext::new(
+                program,
+                GetTraderConfig {
+                    trader_config: accounts[1].clone(),
+                },
+            );
+            trader::get_trader_config(ctx)?.try_to_vec()?
+        }
+        _ => unreachable!(),
+    };
+    set_return_data(&returned);
+    Ok(())
+}
+
+fn process_silent(_program_id: &Pubkey, _accounts: &[AccountInfo], _data: &[u8]) -> ProgramResult {
+    Ok(())
+}
+
+fn consume(test: &mut ProgramTest, view: u8, args: impl AnchorSerialize, accounts: &[Pubkey]) -> Vec<u8> {
+    let mut data = vec![view];
+    args.serialize(&mut data).unwrap();
+    let ix = Instruction {
+        program_id: CONSUMER,
+        accounts: accounts.iter().map(|&account| AccountMeta::new_readonly(account, false)).collect(),
+        data,
+    };
+    test.process_instruction(ix, &[]).unwrap();
+    let (program, data) = test.return_data().expect("return data");
+    assert_eq!(*program, CONSUMER);
+    data.clone()
+}
+
+fn program_test() -> ProgramTest {
+    let mut test = ProgramTest::new();
+    test.add_program(CONSUMER, process_consumer);
+    test.add_program(SILENT, process_silent);
+    test
+}
+
+#[test]
+fn consumer_quotes_the_whitelisted_pool() {
+    let mut test = program_test();
+    let (usdc, wsol) = (Pubkey::new_unique(), Pubkey::new_unique());
+    let mint_authority = Pubkey::new_unique();
+    test.create_mint(usdc, &mint_authority, 6);
+    test.create_mint(wsol, &mint_authority, 9);
+    let pool = test.create_whitelisted_pool(&usdc, &wsol, 5_000_000, 9_000_000);
+    let protocol_status = test.set_protocol_status(false, FeeConfig::INITIAL);
+    let pair = QuotePair {
+        input_mint: usdc,
+        output_mint: wsol,
+    };
+    let accounts = [
+        raydium_swapper::ID,
+        pool.pool_coin_token_account,
+        pool.pool_pc_token_account,
+        protocol_status,
+    ];
+
+    let data = consume(&mut test, QUOTE, (pair, 1_000_000u64), &accounts);
+    let quote = raydium_swapper::SwapQuote::deserialize(&mut data.as_slice()).unwrap();
+    // As the swapper computes it for a direct caller
+    let direct = raydium_swapper::SwapQuote::constant_product(1_000_000, 5_000_000, 9_000_000, FeeConfig::INITIAL.swap_fee_bps as u64).unwrap();
+    assert_eq!(
+        (quote.expected_out, quote.fee, quote.price_impact_bps),
+        (direct.expected_out, direct.fee, direct.price_impact_bps)
+    );
+}
+
+#[test]
+fn consumer_reads_a_strategy() {
+    let mut test = program_test();
+    let authority = Pubkey::new_unique();
+    let mut config = new_trader_config(authority, 40_000, 1_000_000, 200);
+    config.total_swaps = 3;
+    let address = test.set_trader(&config);
+    assert_eq!(trader::trader_config(&authority), address);
+
+    let data = consume(&mut test, TRADER_CONFIG, (), &[vectai_trader::ID, address]);
+    // The IDL's layout decodes as the program's own
+    let returned = TraderConfig::deserialize(&mut data.as_slice()).unwrap();
+    assert_eq!(returned.authority, authority);
+    assert_eq!(returned.total_swaps, 3);
+    assert_eq!(returned.version, config.version);
+}
+
+#[test]
+fn typed_clients_require_return_data() {
+    let mut test = program_test();
+    let ix = Instruction {
+        program_id: CONSUMER,
+        accounts: [SILENT, Pubkey::new_unique()]
+            .iter()
+            .map(|&account| AccountMeta::new_readonly(account, false))
+            .collect(),
+        data: vec![TRADER_CONFIG],
+    };
+    assert_eq!(
+        test.process_instruction(ix, &[]).unwrap_err(),
+        anchor_error(CpiError::MissingReturnData)
+    );
+}
@@ -0,0 +1,288 @@
+//! `vectai_index` share pricing, basket gating and rebalancing.
+
+use anchor_lang::{
+    solana_program::{system_program, sysvar},
+    InstructionData, ToAccountMetas,
+};
+use solana_sdk::{
+    instruction::{AccountMeta, Instruction},
+    program_error::ProgramError,
+    native_token::LAMPORTS_PER_SOL,
+    pubkey::Pubkey,
+    signature::{Keypair, Signer},
+};
+use vectai_index::{
+    accounts, component_drifts, instruction, is_largest_drift_pair, redemption_amount, shares_for_value, IndexError,
+    VIRTUAL_SHARES,
+};
+use vectai_program_test::{anchor_error, ProgramTest};
+use vectai_protocol::{FeeConfig, PairVenues, VenueRegistry};
+
+const INDEX_ID: u64 = 1;
+const REBALANCE_INTERVAL: i64 = 3_600;
+
+/// An empty index with WSOL and USDC set in the network config; every
+/// component is priced at the mock oracle's 45_000
+struct Fixture {
+    test: ProgramTest,
+    manager: Keypair,
+    index: Pubkey,
+    index_mint: Pubkey,
+    wsol: Pubkey,
+    usdc: Pubkey,
+    network_config: Pubkey,
+}
+
+impl Fixture {
+    fn new() -> Self {
+        let mut test = ProgramTest::new();
+        let (wsol, usdc) = (Pubkey::new_unique(), Pubkey::new_unique());
+        test.create_mint(wsol, &Pubkey::new_unique(), 6);
+        test.create_mint(usdc, &Pubkey::new_unique(), 6);
+        let network_config = test.set_network_config(wsol, usdc);
+
+        let manager = Keypair::new();
+        test.airdrop(&manager.pubkey(), 10 * LAMPORTS_PER_SOL);
+        let (index, _) = Pubkey::find_program_address(
+            &[b"index", manager.pubkey().as_ref(), &INDEX_ID.to_le_bytes()],
+            &vectai_index::ID,
+        );
+        let (index_mint, _) = Pubkey::find_program_address(&[b"index-mint", index.as_ref()], &vectai_index::ID);
+        let initialize = Instruction {
+            program_id: vectai_index::ID,
+            accounts: accounts::InitializeIndex {
+                index,
+                index_mint,
+                manager: manager.pubkey(),
+                rent: sysvar::rent::ID,
+                token_program: spl_token::ID,
+                system_program: system_program::ID,
+            }
+            .to_account_metas(None),
+            data: instruction::InitializeIndex {
+                index_id: INDEX_ID,
+                rebalance_interval: REBALANCE_INTERVAL,
+            }
+            .data(),
+        };
+        test.process_instruction(initialize, &[&manager]).unwrap();
+        Fixture {
+            test,
+            manager,
+            index,
+            index_mint,
+            wsol,
+            usdc,
+            network_config,
+        }
+    }
+
+    fn vault(&self, mint: &Pubkey) -> Pubkey {
+        Pubkey::find_program_address(&[b"component-vault", self.index.as_ref(), mint.as_ref()], &vectai_index::ID).0
+    }
+
+    /// A new 6-decimal mint
+    fn mint(&mut self) -> Pubkey {
+        let mint = Pubkey::new_unique();
+        self.test.create_mint(mint, &Pubkey::new_unique(), 6);
+        mint
+    }
+
+    /// The venue registry of `base`/`quote`, listing a stable pool
+    fn list_stable_pool(&mut self, base: Pubkey, quote: Pubkey) -> Pubkey {
+        let (registry, bump) =
+            Pubkey::find_program_address(&[b"venue-registry", base.as_ref(), quote.as_ref()], &vectai_protocol::ID);
+        self.test.set_anchor_account(
+            registry,
+            &VenueRegistry {
+                base_mint: base,
+                quote_mint: quote,
+                venues: PairVenues {
+                    stable_swap_pool: Pubkey::new_unique(),
+                    ..PairVenues::default()
+                },
+                updated_at: 0,
+                bump,
+            },
+        );
+        registry
+    }
+
+    /// `add_component` of `mint`, priced by its own feed, with `registries`
+    /// as remaining accounts
+    fn add_component(&mut self, mint: Pubkey, weight_bps: u16, registries: &[Pubkey]) -> Result<(), ProgramError> {
+        let mut accounts = accounts::AddComponent {
+            index: self.index,
+            index_mint: self.index_mint,
+            component_mint: mint,
+            component_vault: self.vault(&mint),
+            price_feed: price_feed(&mint),
+            network_config: self.network_config,
+            manager: self.manager.pubkey(),
+            rent: sysvar::rent::ID,
+            token_program: spl_token::ID,
+            system_program: system_program::ID,
+        }
+        .to_account_metas(None);
+        accounts.extend(registries.iter().map(|registry| AccountMeta::new_readonly(*registry, false)));
+        let ix = Instruction {
+            program_id: vectai_index::ID,
+            accounts,
+            data: instruction::AddComponent { weight_bps }.data(),
+        };
+        self.test.process_instruction(ix, &[&self.manager])
+    }
+
+    /// `rebalance_stable` from component `from` into `to` of a basket of
+    /// `mints`; the stable pool accounts are placeholders, as the plan is
+    /// checked before the swap
+    fn rebalance_stable(&mut self, mints: &[Pubkey], from: u8, to: u8) -> Result<(), ProgramError> {
+        let (source, destination) = (mints[from as usize], mints[to as usize]);
+        let keeper = Keypair::new();
+        self.test.airdrop(&keeper.pubkey(), LAMPORTS_PER_SOL);
+        let protocol_status = self.test.set_protocol_status(false, FeeConfig::INITIAL);
+        let protocol_stats = self.test.set_protocol_stats();
+        let mut accounts = accounts::RebalanceStable {
+            index: self.index,
+            source_vault: self.vault(&source),
+            destination_vault: self.vault(&destination),
+            keeper: keeper.pubkey(),
+            vectai_oracle_program: vectai_oracle::ID,
+            swapper_program: raydium_swapper::ID,
+            stable_swap_program: Pubkey::new_unique(),
+            pool: Pubkey::new_unique(),
+            pool_authority: Pubkey::new_unique(),
+            pool_source: Pubkey::new_unique(),
+            pool_destination: Pubkey::new_unique(),
+            admin_fee_destination: Pubkey::new_unique(),
+            token_program: spl_token::ID,
+            venue_registry: vectai_protocol::venue_registry(&source, &destination),
+            protocol_status,
+            network_config: self.network_config,
+            protocol_stats,
+            swapper_stats_reporter: Pubkey::new_unique(),
+            protocol_program: vectai_protocol::ID,
+            swap_fee_account: None,
+        }
+        .to_account_metas(None);
+        for mint in mints {
+            accounts.push(AccountMeta::new_readonly(self.vault(mint), false));
+            accounts.push(AccountMeta::new_readonly(price_feed(mint), false));
+        }
+        let ix = Instruction {
+            program_id: vectai_index::ID,
+            accounts,
+            data: instruction::RebalanceStable {
+                from_component: from,
+                to_component: to,
+            }
+            .data(),
+        };
+        self.test.process_instruction(ix, &[&keeper])
+    }
+}
+
+/// Each component's price feed, derived from its mint so tests can find it
+fn price_feed(mint: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(&[b"feed", mint.as_ref()], &vectai_oracle::ID).0
+}
+
+#[test]
+fn first_deposit_mints_index_tokens_one_to_one() {
+    assert_eq!(shares_for_value(1_000_000, 0, 0).unwrap(), 1_000_000);
+    // Value donated before the first deposit is priced in, not ignored
+    assert!(shares_for_value(1_000_000, 0, 5_000_000).unwrap() < 1_000_000);
+}
+
+#[test]
+fn worthless_index_with_holders_takes_no_deposits() {
+    assert_eq!(
+        shares_for_value(1_000_000, 1_000, 0).unwrap_err(),
+        IndexError::WorthlessIndex.into()
+    );
+}
+
+#[test]
+fn donation_cannot_round_the_next_deposit_to_nothing() {
+    // One token minted, then a million donated to the components
+    let minted = shares_for_value(1_000_000, 1, 1_000_001).unwrap();
+    assert!(minted > 0);
+    // The virtual shares keep their slice of every component
+    assert_eq!(redemption_amount(10_000, 1_000, 1_000).unwrap(), 10_000 * 1_000 / (1_000 + VIRTUAL_SHARES));
+}
+
+#[test]
+fn basket_takes_only_mints_it_can_swap() {
+    let mut fixture = Fixture::new();
+    let (wsol, usdc) = (fixture.wsol, fixture.usdc);
+    fixture.add_component(wsol, 5_000, &[]).unwrap();
+    fixture.add_component(usdc, 3_000, &[]).unwrap();
+
+    // No pool trades it against WSOL or USDC
+    let unlisted = fixture.mint();
+    assert_eq!(
+        fixture.add_component(unlisted, 2_000, &[]).unwrap_err(),
+        anchor_error(IndexError::UnswappableComponent)
+    );
+
+    // A stable pool against USDC alone leaves it stranded from WSOL
+    let usdt = fixture.mint();
+    let usdt_usdc = fixture.list_stable_pool(usdt, usdc);
+    assert_eq!(
+        fixture.add_component(usdt, 2_000, &[usdt_usdc, usdt_usdc]).unwrap_err(),
+        anchor_error(IndexError::UnswappableComponent)
+    );
+
+    let usdt_wsol = fixture.list_stable_pool(wsol, usdt);
+    fixture.add_component(usdt, 2_000, &[usdt_wsol, usdt_usdc]).unwrap();
+    let index: vectai_index::IndexFund = fixture.test.anchor_account(&fixture.index).unwrap();
+    assert_eq!(index.components.len(), 3);
+}
+
+#[test]
+fn drifts_measure_each_component_against_its_target() {
+    // Targets of 40/30/30 on a NAV of 100
+    let drifts = component_drifts(&[30, 45, 25], &[4_000, 3_000, 3_000], 100).unwrap();
+    assert_eq!(drifts, vec![(0, 10), (15, 0), (0, 5)]);
+
+    assert!(is_largest_drift_pair(&drifts, 1, 0));
+    // USDT is underweight too, but less so than USDC
+    assert!(!is_largest_drift_pair(&drifts, 1, 2));
+    assert!(!is_largest_drift_pair(&drifts, 0, 1));
+    assert!(!is_largest_drift_pair(&drifts, 1, 3));
+
+    // Components tied for the largest drift qualify alike
+    let tied = component_drifts(&[30, 40, 30], &[4_000, 2_000, 4_000], 100).unwrap();
+    assert!(is_largest_drift_pair(&tied, 1, 0));
+    assert!(is_largest_drift_pair(&tied, 1, 2));
+}
+
+#[test]
+fn rebalance_rejects_a_smaller_drift_pair() {
+    let mut fixture = Fixture::new();
+    let (wsol, usdc) = (fixture.wsol, fixture.usdc);
+    let usdt = fixture.mint();
+    let usdt_wsol = fixture.list_stable_pool(usdt, wsol);
+    let usdt_usdc = fixture.list_stable_pool(usdt, usdc);
+    fixture.add_component(usdc, 4_000, &[]).unwrap();
+    fixture.add_component(wsol, 3_000, &[]).unwrap();
+    fixture.add_component(usdt, 3_000, &[usdt_usdc, usdt_wsol]).unwrap();
+
+    // 30/45/25 against targets of 40/30/30: WSOL is 15 over, USDC 10 under
+    // and USDT 5 under
+    let mints = [usdc, wsol, usdt];
+    for (mint, amount) in mints.iter().zip([30_000_000, 45_000_000, 25_000_000]) {
+        let vault = fixture.vault(mint);
+        fixture.test.create_token_account(vault, mint, &fixture.index, amount);
+    }
+
+    // USDT is over the 2% threshold, but USDC is further under
+    assert_eq!(
+        fixture.rebalance_stable(&mints, 1, 2).unwrap_err(),
+        anchor_error(IndexError::NotLargestDrift)
+    );
+    assert_eq!(
+        fixture.rebalance_stable(&mints, 0, 2).unwrap_err(),
+        anchor_error(IndexError::NotLargestDrift)
+    );
+}
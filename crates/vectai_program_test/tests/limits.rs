@@ -0,0 +1,379 @@
+//! Boundaries of `execute_trade`'s cooldowns, caps and threshold math.
+//!
+//! Each test seeds the trader state it needs and runs a single instruction,
+//! so the edge (one second, one unit, one basis point) is the only variable.
+
+use anchor_lang::{solana_program::system_program, InstructionData, ToAccountMetas};
+use solana_sdk::{
+    instruction::Instruction,
+    native_token::LAMPORTS_PER_SOL,
+    program_error::ProgramError,
+    pubkey::Pubkey,
+    signature::{Keypair, Signer},
+};
+use vectai_math::condition::{OP_COOLDOWN, OP_PRICE_ABOVE, OP_PRICE_BELOW};
+use vectai_program_test::{
+    anchor_error, mock_amm,
+    trader::{new_trader_config, trader_address, TradingNetwork, TRADE_COOLDOWN},
+    ProgramTest,
+};
+use vectai_protocol::FeeConfig;
+use vectai_trader::{accounts, instruction, PointsConfig, PointsLedger, TraderConfig, TraderError};
+
+const ORACLE_PRICE: i64 = 45_000;
+const SWAP_AMOUNT: u64 = 1_000_000;
+const SLIPPAGE_BPS: u64 = 200;
+const SOURCE_BALANCE: u64 = 100_000_000;
+const POOL_RESERVE: u64 = 1_000_000_000_000;
+const MAX_SWAP_AMOUNT: u64 = 1_000_000_000_000;
+const DAY: i64 = 86_400;
+const HOUR: i64 = 3_600;
+
+/// A trader seeded directly, holding `SOURCE_BALANCE` USDC
+struct Seeded {
+    test: ProgramTest,
+    network: TradingNetwork,
+    authority: Keypair,
+    source: Pubkey,
+    destination: Pubkey,
+}
+
+impl Seeded {
+    fn new(configure: impl FnOnce(&mut TraderConfig, i64)) -> Self {
+        Self::with_pool(POOL_RESERVE, SOURCE_BALANCE, configure)
+    }
+
+    fn with_pool(pool_reserve: u64, source_balance: u64, configure: impl FnOnce(&mut TraderConfig, i64)) -> Self {
+        let mut test = ProgramTest::new();
+        let network = test.create_trading_network(pool_reserve);
+        let authority = Keypair::new();
+        test.airdrop(&authority.pubkey(), LAMPORTS_PER_SOL);
+        let mut config = new_trader_config(authority.pubkey(), ORACLE_PRICE - 1, SWAP_AMOUNT, SLIPPAGE_BPS);
+        configure(&mut config, test.clock().unix_timestamp);
+        test.set_trader(&config);
+
+        let (source, destination) = (Pubkey::new_unique(), Pubkey::new_unique());
+        test.create_token_account(source, &network.usdc, &authority.pubkey(), source_balance);
+        test.create_token_account(destination, &network.wsol, &authority.pubkey(), 0);
+        Seeded {
+            test,
+            network,
+            authority,
+            source,
+            destination,
+        }
+    }
+
+    fn trader(&self) -> TraderConfig {
+        self.test.anchor_account(&trader_address(&self.authority.pubkey())).unwrap()
+    }
+
+    fn update_trader(&mut self, update: impl FnOnce(&mut TraderConfig)) {
+        let mut config = self.trader();
+        update(&mut config);
+        self.test.set_trader(&config);
+    }
+
+    fn trade_with(&mut self, accounts: accounts::ExecuteTrade, amount: u64) -> Result<(), ProgramError> {
+        let ix = Instruction {
+            program_id: vectai_trader::ID,
+            accounts: accounts.to_account_metas(None),
+            data: instruction::ExecuteTrade { amount, dry_run: false, nonce: None }.data(),
+        };
+        let authority = self.authority.insecure_clone();
+        self.test.process_instruction(ix, &[&authority])
+    }
+
+    fn trade(&mut self, amount: u64) -> Result<(), ProgramError> {
+        let accounts = self.accounts();
+        self.trade_with(accounts, amount)
+    }
+
+    fn trade_cap_window(&self) -> u32 {
+        self.trader().trade_cap.trades_in_window(self.test.clock().unix_timestamp)
+    }
+
+    fn accounts(&self) -> accounts::ExecuteTrade {
+        self.network
+            .execute_trade_accounts(&self.authority.pubkey(), self.source, self.destination)
+    }
+}
+
+fn price_op(opcode: u8, price: i64) -> Vec<u8> {
+    let mut program = vec![opcode];
+    program.extend_from_slice(&price.to_le_bytes());
+    program
+}
+
+// ===== COOLDOWNS =====
+
+#[test]
+fn cooldown_opens_at_sixty_seconds() {
+    let mut seeded = Seeded::new(|config, now| config.last_swap_time = now - (TRADE_COOLDOWN - 1));
+
+    assert_eq!(seeded.trade(SWAP_AMOUNT).unwrap_err(), anchor_error(TraderError::RateLimited));
+    seeded.test.warp_forward(1);
+    seeded.trade(SWAP_AMOUNT).unwrap();
+    assert_eq!(seeded.trader().last_swap_time, seeded.test.clock().unix_timestamp);
+}
+
+#[test]
+fn slot_cooldown_counts_slots_not_seconds() {
+    let mut seeded = Seeded::new(|config, now| {
+        config.cooldown_slots = 150;
+        config.last_swap_time = now - DAY;
+    });
+    seeded.test.warp_forward(HOUR);
+    let slot = seeded.test.clock().slot;
+    seeded.update_trader(|config| config.last_swap_slot = slot - 149);
+
+    // Over a day has passed on the unix clock, one slot short of the cooldown
+    assert_eq!(seeded.trade(SWAP_AMOUNT).unwrap_err(), anchor_error(TraderError::RateLimited));
+    let mut clock = seeded.test.clock().clone();
+    clock.slot += 1;
+    seeded.test.set_clock(clock);
+    seeded.trade(SWAP_AMOUNT).unwrap();
+    let trader = seeded.trader();
+    assert_eq!(trader.last_swap_slot, slot + 1);
+    assert_eq!(trader.last_swap_time, seeded.test.clock().unix_timestamp);
+}
+
+#[test]
+fn condition_cooldown_counts_from_last_execution() {
+    let mut program = vec![OP_COOLDOWN];
+    program.extend_from_slice(&3_600u32.to_le_bytes());
+    let mut seeded = Seeded::new(|config, now| {
+        config.condition = program;
+        config.last_swap_time = now - 3_599;
+    });
+
+    assert_eq!(seeded.trade(SWAP_AMOUNT).unwrap_err(), anchor_error(TraderError::ConditionNotMet));
+    seeded.test.warp_forward(1);
+    seeded.trade(SWAP_AMOUNT).unwrap();
+}
+
+#[test]
+fn subscription_expires_at_its_timestamp() {
+    let mut seeded = Seeded::new(|config, now| config.subscription_expires_at = now + 1);
+    seeded.trade(SWAP_AMOUNT).unwrap();
+
+    seeded.update_trader(|config| config.last_swap_time = 0);
+    seeded.test.warp_forward(1);
+    assert_eq!(seeded.trade(SWAP_AMOUNT).unwrap_err(), anchor_error(TraderError::SubscriptionExpired));
+}
+
+#[test]
+fn daily_points_cap_resets_at_utc_midnight() {
+    let mut seeded = Seeded::new(|_, _| {});
+    let authority = seeded.authority.pubkey();
+    let (config, config_bump) = Pubkey::find_program_address(&[b"points-config"], &vectai_trader::ID);
+    let (ledger, _) = Pubkey::find_program_address(&[b"points", authority.as_ref()], &vectai_trader::ID);
+    // $1 earns exactly one day's cap
+    seeded.test.set_anchor_account(
+        config,
+        &PointsConfig {
+            points_per_dollar: 10,
+            redemption_rate: 0,
+            daily_pair_cap: 10,
+            rewards_vault: Pubkey::new_unique(),
+            total_points_issued: 0,
+            total_points_redeemed: 0,
+            bump: config_bump,
+        },
+    );
+    let ix = Instruction {
+        program_id: vectai_trader::ID,
+        accounts: accounts::OpenPointsLedger {
+            points_ledger: ledger,
+            authority,
+            system_program: system_program::ID,
+        }
+        .to_account_metas(None),
+        data: instruction::OpenPointsLedger {}.data(),
+    };
+    let signer = seeded.authority.insecure_clone();
+    seeded.test.process_instruction(ix, &[&signer]).unwrap();
+    let trade = |seeded: &mut Seeded| {
+        let mut accounts = seeded.accounts();
+        accounts.points_ledger = Some(ledger);
+        accounts.points_config = Some(config);
+        seeded.trade_with(accounts, SWAP_AMOUNT).unwrap();
+        seeded.test.anchor_account::<PointsLedger>(&ledger).unwrap().balance
+    };
+
+    let midnight = (seeded.test.clock().unix_timestamp / DAY + 1) * DAY;
+    seeded.test.warp_to_timestamp(midnight - 2 * TRADE_COOLDOWN);
+    assert_eq!(trade(&mut seeded), 10);
+    seeded.test.warp_to_timestamp(midnight - TRADE_COOLDOWN);
+    assert_eq!(trade(&mut seeded), 10);
+    seeded.test.warp_to_timestamp(midnight);
+    assert_eq!(trade(&mut seeded), 20);
+}
+
+// ===== CAPS =====
+
+#[test]
+fn initialize_trader_bounds_are_inclusive() {
+    let initialize = |price_threshold, swap_amount, slippage_tolerance| {
+        let mut test = ProgramTest::new();
+        let authority = Keypair::new();
+        test.airdrop(&authority.pubkey(), LAMPORTS_PER_SOL);
+        let ix = Instruction {
+            program_id: vectai_trader::ID,
+            accounts: accounts::InitializeTrader {
+                trader_config: trader_address(&authority.pubkey()),
+                referral_code: None,
+                authority: authority.pubkey(),
+                system_program: system_program::ID,
+            }
+            .to_account_metas(None),
+            data: instruction::InitializeTrader {
+                price_threshold,
+                swap_amount,
+                slippage_tolerance,
+            }
+            .data(),
+        };
+        test.process_instruction(ix, &[&authority])
+    };
+    let invalid = Err(anchor_error(TraderError::InvalidInput));
+
+    assert_eq!(initialize(999_999_999_999, MAX_SWAP_AMOUNT, 1_000), Ok(()));
+    assert_eq!(initialize(1_000_000_000_000, SWAP_AMOUNT, SLIPPAGE_BPS), invalid);
+    assert_eq!(initialize(ORACLE_PRICE, MAX_SWAP_AMOUNT + 1, SLIPPAGE_BPS), invalid);
+    assert_eq!(initialize(ORACLE_PRICE, SWAP_AMOUNT, 1_001), invalid);
+}
+
+#[test]
+fn daily_trade_cap_counts_rolling_24_hours() {
+    let mut seeded = Seeded::new(|config, _| config.trade_cap.max_trades = 3);
+    let start = seeded.test.clock().unix_timestamp;
+    for _ in 0..3 {
+        seeded.trade(SWAP_AMOUNT).unwrap();
+        seeded.test.warp_forward(HOUR);
+    }
+    let capped = anchor_error(TraderError::DailyTradeCapReached);
+    assert_eq!(seeded.trade(SWAP_AMOUNT).unwrap_err(), capped);
+
+    // The first trade leaves the window with the hour 24 hours after it
+    let first_hour = start - start.rem_euclid(HOUR);
+    seeded.test.warp_to_timestamp(first_hour + DAY - 1);
+    assert_eq!(seeded.trade(SWAP_AMOUNT).unwrap_err(), capped);
+    seeded.test.warp_forward(1);
+    seeded.trade(SWAP_AMOUNT).unwrap();
+    assert_eq!(seeded.trade_cap_window(), 3);
+    assert_eq!(seeded.trader().total_swaps, 4);
+}
+
+#[test]
+fn auto_compound_caps_swap_amount() {
+    let amount = MAX_SWAP_AMOUNT / 2;
+    let reserve = 10_000 * MAX_SWAP_AMOUNT;
+    let mut seeded = Seeded::with_pool(reserve, amount, |config, _| {
+        config.swap_amount = amount;
+        config.auto_compound = true;
+    });
+
+    seeded.trade(amount).unwrap();
+
+    let out = mock_amm::quote(amount, reserve, reserve);
+    let gain = vectai_math::value_in_source_units(out, true, ORACLE_PRICE, 0).unwrap() - amount;
+    assert!(amount + gain > MAX_SWAP_AMOUNT);
+    let trader = seeded.trader();
+    assert_eq!(trader.swap_amount, MAX_SWAP_AMOUNT);
+    assert_eq!(trader.total_compounded, gain);
+}
+
+#[test]
+fn drawdown_limit_is_exclusive() {
+    // Exactly 10% below the peak
+    let mut seeded = Seeded::new(|config, _| {
+        config.risk.max_drawdown_bps = 1_000;
+        config.risk.peak_equity = SOURCE_BALANCE * 10 / 9 + 1;
+        config.marked_source_balance = SOURCE_BALANCE;
+    });
+    assert_eq!(vectai_math::drawdown_bps(SOURCE_BALANCE * 10 / 9 + 1, SOURCE_BALANCE), 1_000);
+    seeded.trade(SWAP_AMOUNT).unwrap();
+    let trader = seeded.trader();
+    assert!(!trader.risk.halted);
+    assert_eq!(trader.total_swaps, 1);
+
+    // One basis point further halts
+    let mut seeded = Seeded::new(|config, _| {
+        config.risk.max_drawdown_bps = 1_000;
+        config.risk.peak_equity = SOURCE_BALANCE * 10_000 / 8_999 + 1;
+        config.marked_source_balance = SOURCE_BALANCE;
+    });
+    seeded.trade(SWAP_AMOUNT).unwrap();
+    let trader = seeded.trader();
+    assert!(trader.risk.halted);
+    assert_eq!(trader.risk.last_drawdown_bps, 1_001);
+    assert_eq!(trader.total_swaps, 0);
+}
+
+#[test]
+fn trade_fee_rounds_down() {
+    let mut seeded = Seeded::new(|_, _| {});
+    let fee_config = FeeConfig {
+        trade_fee_bps: 100,
+        ..FeeConfig::INITIAL
+    };
+    seeded.test.set_protocol_status(false, fee_config);
+
+    // 1% of 99 rounds to nothing, so no fee account is needed
+    seeded.trade(99).unwrap();
+    seeded.test.warp_forward(TRADE_COOLDOWN);
+    assert_eq!(seeded.trade(100).unwrap_err(), anchor_error(TraderError::FeeAccountRequired));
+}
+
+// ===== THRESHOLD MATH =====
+
+#[test]
+fn price_threshold_must_be_exceeded() {
+    let mut seeded = Seeded::new(|config, _| config.price_threshold = ORACLE_PRICE);
+    assert_eq!(seeded.trade(SWAP_AMOUNT).unwrap_err(), anchor_error(TraderError::ThresholdNotMet));
+
+    seeded.update_trader(|config| config.price_threshold = ORACLE_PRICE - 1);
+    seeded.trade(SWAP_AMOUNT).unwrap();
+}
+
+#[test]
+fn condition_price_bounds_are_strict() {
+    let not_met = Err(anchor_error(TraderError::ConditionNotMet));
+    for (program, expected) in [
+        (price_op(OP_PRICE_ABOVE, ORACLE_PRICE), not_met.clone()),
+        (price_op(OP_PRICE_ABOVE, ORACLE_PRICE - 1), Ok(())),
+        (price_op(OP_PRICE_BELOW, ORACLE_PRICE), not_met.clone()),
+        (price_op(OP_PRICE_BELOW, ORACLE_PRICE + 1), Ok(())),
+    ] {
+        // The condition replaces a threshold that would otherwise block
+        let mut seeded = Seeded::new(|config, _| {
+            config.price_threshold = ORACLE_PRICE;
+            config.condition = program;
+        });
+        assert_eq!(seeded.trade(SWAP_AMOUNT), expected);
+    }
+}
+
+#[test]
+fn slippage_floor_is_inclusive() {
+    // A pool paying out exactly the 2% floor on the 1:1 estimate
+    let floor = SWAP_AMOUNT * (10_000 - SLIPPAGE_BPS) / 10_000;
+    let reserve_in = SWAP_AMOUNT * floor / (SWAP_AMOUNT - floor);
+    let mut seeded = Seeded::new(|_, _| {});
+    let pool = &seeded.network.pool;
+    let (coin, pc) = (pool.pool_coin_token_account, pool.pool_pc_token_account);
+    let (usdc, wsol, authority) = (seeded.network.usdc, seeded.network.wsol, pool.amm_authority);
+    seeded.test.create_token_account(coin, &wsol, &authority, reserve_in);
+    seeded.test.create_token_account(pc, &usdc, &authority, reserve_in);
+    assert_eq!(mock_amm::quote(SWAP_AMOUNT, reserve_in, reserve_in), floor);
+    seeded.trade(SWAP_AMOUNT).unwrap();
+    assert_eq!(seeded.test.token_balance(&seeded.destination), Some(floor));
+
+    // One more unit of input moves the price past the floor
+    seeded.test.warp_forward(TRADE_COOLDOWN);
+    assert_eq!(
+        seeded.trade(SWAP_AMOUNT).unwrap_err(),
+        ProgramError::Custom(mock_amm::EXCEEDED_SLIPPAGE)
+    );
+}
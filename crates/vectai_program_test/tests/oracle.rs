@@ -0,0 +1,828 @@
+//! `vectai_oracle` instructions.
+
+use anchor_lang::{solana_program::system_program, AnchorDeserialize, InstructionData, ToAccountMetas};
+use solana_sdk::{
+    instruction::{AccountMeta, Instruction},
+    native_token::LAMPORTS_PER_SOL,
+    pubkey::Pubkey,
+    signature::{Keypair, Signer},
+};
+use vectai_oracle::{
+    aggregate_prices, lazer_signers, price_aggregator, price_monitor, price_policy, AbnormalPriceMove, AggregatedPrice,
+    LazerFeed, LazerPayload, LazerPrice, LazerSigner, LazerSigners, LazerUpdate, OracleError, PolicyPrice,
+    PriceAggregator, PriceData, PriceMonitor, PricePolicy, PriceSource, StalePricePolicy, LAZER_PAYLOAD_FORMAT_MAGIC,
+    LAZER_SOLANA_FORMAT_MAGIC,
+};
+use vectai_program_test::{anchor_error, ProgramTest};
+
+fn get_price(price_feed: Pubkey) -> Instruction {
+    Instruction {
+        program_id: vectai_oracle::ID,
+        accounts: vectai_oracle::accounts::GetPrice { price_feed }.to_account_metas(None),
+        data: vectai_oracle::instruction::GetPrice {}.data(),
+    }
+}
+
+#[test]
+fn get_price_returns_mock_price_at_current_time() {
+    let mut test = ProgramTest::new();
+    test.warp_forward(3_600);
+
+    test.process_instruction(get_price(Pubkey::new_unique()), &[]).unwrap();
+
+    let (program, data) = test.return_data().expect("return data");
+    assert_eq!(*program, vectai_oracle::ID);
+    let price = PriceData::deserialize(&mut data.as_slice()).unwrap();
+    assert_eq!(price.price, 45_000);
+    assert_eq!(price.conf, 100);
+    assert_eq!(price.expo, 0);
+    assert_eq!(price.publish_time, test.clock().unix_timestamp);
+    assert_eq!(price.slot, test.clock().slot);
+}
+
+#[test]
+fn get_inverse_price_inverts_mock_price() {
+    let mut test = ProgramTest::new();
+    let mut ix = get_price(Pubkey::new_unique());
+    ix.data = vectai_oracle::instruction::GetInversePrice {}.data();
+
+    test.process_instruction(ix, &[]).unwrap();
+
+    let (_, data) = test.return_data().expect("return data");
+    let inverse = PriceData::deserialize(&mut data.as_slice()).unwrap();
+    // 1 / 45_000 = 0.0000222222222..
+    assert_eq!((inverse.price, inverse.expo), (222_222_222, -13));
+    // 100 / 45_000^2, rounded up
+    assert_eq!(inverse.conf, 493_828);
+    assert_eq!(inverse.publish_time, test.clock().unix_timestamp);
+    assert!(test.logs().iter().any(|line| line == "Program log: 📊 Inverse price: 222222222 x 10^-13"));
+}
+
+#[test]
+fn inverse_price_rescales_exponent_and_confidence() {
+    // SOL/USD $150.00000000 +- $0.1
+    let sol_usd = PriceData {
+        price: 15_000_000_000,
+        conf: 10_000_000,
+        expo: -8,
+        publish_time: 7,
+        slot: 17,
+    };
+    let usd_sol = sol_usd.inverse().unwrap();
+    // 1 / 150 = 0.00666666666 SOL per USD
+    assert_eq!((usd_sol.price, usd_sol.expo, usd_sol.publish_time, usd_sol.slot), (666_666_666, -11, 7, 17));
+    // 0.1 / 150^2 = 0.00000444.. with the same relative width
+    assert_eq!(usd_sol.conf, 444_445);
+
+    // Inverting twice returns the price to nine significant digits
+    let back = usd_sol.inverse().unwrap();
+    assert_eq!((back.price, back.expo), (150_000_000, -6));
+
+    for price in [0, -1] {
+        assert!(PriceData { price, ..sol_usd.clone() }.inverse().is_none());
+    }
+    // The largest mantissa still fits
+    let max = PriceData { price: i64::MAX, conf: u64::MAX, expo: 0, publish_time: 0, slot: 0 }.inverse().unwrap();
+    assert_eq!((max.price, max.expo), (108_420_217, -27));
+}
+
+#[test]
+fn slot_age_bounds_freshness_in_slots() {
+    let price = PriceData {
+        price: 45_000,
+        conf: 100,
+        expo: 0,
+        publish_time: 0,
+        slot: 1_000,
+    };
+    assert_eq!(price.slot_age(1_010), 10);
+    assert!(price.observed_within(1_010, 10));
+    assert!(!price.observed_within(1_011, 10));
+    // Clocks behind the observation count as fresh
+    assert_eq!(price.slot_age(990), 0);
+    assert!(price.observed_within(990, 0));
+}
+
+#[test]
+fn get_price_logs_mock_warning() {
+    let mut test = ProgramTest::new();
+    test.process_instruction(get_price(Pubkey::new_unique()), &[]).unwrap();
+
+    let logs = test.logs();
+    assert_eq!(logs.first().unwrap(), &format!("Program {} invoke [1]", vectai_oracle::ID));
+    assert!(logs.iter().any(|line| line.contains("Using mock price data")));
+    assert!(logs.iter().any(|line| line == "Program log: 📊 Mock price: $45000"));
+    assert_eq!(logs.last().unwrap(), &format!("Program {} success", vectai_oracle::ID));
+}
+
+#[test]
+fn get_price_requires_price_feed_account() {
+    let mut test = ProgramTest::new();
+    let mut ix = get_price(Pubkey::new_unique());
+    ix.accounts.clear();
+
+    let err = test.process_instruction(ix, &[]).unwrap_err();
+    assert_eq!(err, vectai_program_test::anchor_error(anchor_lang::error::ErrorCode::AccountNotEnoughKeys));
+}
+
+fn initialize_price_monitor(price_feed: Pubkey, authority: Pubkey, max_move_bps: u16) -> Instruction {
+    Instruction {
+        program_id: vectai_oracle::ID,
+        accounts: vectai_oracle::accounts::InitializePriceMonitor {
+            price_monitor: price_monitor(&price_feed, &authority),
+            price_feed,
+            authority,
+            system_program: system_program::ID,
+        }
+        .to_account_metas(None),
+        data: vectai_oracle::instruction::InitializePriceMonitor { max_move_bps }.data(),
+    }
+}
+
+fn monitor_price(price_feed: Pubkey, authority: Pubkey) -> Instruction {
+    Instruction {
+        program_id: vectai_oracle::ID,
+        accounts: vectai_oracle::accounts::MonitorPrice {
+            price_monitor: price_monitor(&price_feed, &authority),
+            price_feed,
+        }
+        .to_account_metas(None),
+        data: vectai_oracle::instruction::MonitorPrice {}.data(),
+    }
+}
+
+/// A funded authority with a monitor on a fresh feed
+fn monitored_feed(test: &mut ProgramTest, max_move_bps: u16) -> (Pubkey, Keypair) {
+    let authority = Keypair::new();
+    test.airdrop(&authority.pubkey(), LAMPORTS_PER_SOL);
+    let price_feed = Pubkey::new_unique();
+    test.process_instruction(initialize_price_monitor(price_feed, authority.pubkey(), max_move_bps), &[&authority])
+        .unwrap();
+    (price_feed, authority)
+}
+
+/// Rewind the monitor's last read to `last_price`
+fn set_last_price(test: &mut ProgramTest, address: Pubkey, last_price: i64) {
+    let mut monitor: PriceMonitor = test.anchor_account(&address).unwrap();
+    monitor.last_price = last_price;
+    test.set_anchor_account(address, &monitor);
+}
+
+#[test]
+fn monitor_price_flags_moves_past_the_threshold() {
+    let mut test = ProgramTest::new();
+    let (price_feed, authority) = monitored_feed(&mut test, 500);
+    let address = price_monitor(&price_feed, &authority.pubkey());
+
+    // The first read has nothing to compare with
+    test.process_instruction(monitor_price(price_feed, authority.pubkey()), &[]).unwrap();
+    assert!(test.events::<AbnormalPriceMove>().is_empty());
+    let (_, data) = test.return_data().expect("return data");
+    assert_eq!(PriceData::deserialize(&mut data.as_slice()).unwrap().price, 45_000);
+    let monitor: PriceMonitor = test.anchor_account(&address).unwrap();
+    assert_eq!((monitor.last_price, monitor.last_publish_time), (45_000, test.clock().unix_timestamp));
+
+    // 40_000 -> 45_000 is 1_250 bps
+    set_last_price(&mut test, address, 40_000);
+    test.warp_forward(60);
+    test.process_instruction(monitor_price(price_feed, authority.pubkey()), &[]).unwrap();
+    let events = test.events::<AbnormalPriceMove>();
+    assert_eq!(events.len(), 1);
+    let event = &events[0];
+    assert_eq!((event.price_feed, event.monitor), (price_feed, address));
+    assert_eq!((event.previous_price, event.price, event.move_bps), (40_000, 45_000, 1_250));
+    assert_eq!(event.publish_time - event.previous_publish_time, 60);
+
+    // 43_000 -> 45_000 is 465 bps, within the threshold
+    set_last_price(&mut test, address, 43_000);
+    test.process_instruction(monitor_price(price_feed, authority.pubkey()), &[]).unwrap();
+    assert!(test.events::<AbnormalPriceMove>().is_empty());
+}
+
+#[test]
+fn price_monitor_threshold_belongs_to_its_authority() {
+    let mut test = ProgramTest::new();
+    let (price_feed, authority) = monitored_feed(&mut test, 500);
+    let address = price_monitor(&price_feed, &authority.pubkey());
+    let set_max_price_move = |authority: Pubkey, max_move_bps: u16| Instruction {
+        program_id: vectai_oracle::ID,
+        accounts: vectai_oracle::accounts::SetMaxPriceMove {
+            price_monitor: address,
+            authority,
+        }
+        .to_account_metas(None),
+        data: vectai_oracle::instruction::SetMaxPriceMove { max_move_bps }.data(),
+    };
+
+    let impostor = Keypair::new();
+    test.airdrop(&impostor.pubkey(), LAMPORTS_PER_SOL);
+    assert_eq!(
+        test.process_instruction(set_max_price_move(impostor.pubkey(), 2_000), &[&impostor]).unwrap_err(),
+        anchor_error(anchor_lang::error::ErrorCode::ConstraintHasOne)
+    );
+    assert_eq!(
+        test.process_instruction(set_max_price_move(authority.pubkey(), 0), &[&authority]).unwrap_err(),
+        anchor_error(OracleError::InvalidMaxPriceMove)
+    );
+    test.process_instruction(set_max_price_move(authority.pubkey(), 2_000), &[&authority]).unwrap();
+
+    // A 1_250 bps move is now within the threshold
+    test.process_instruction(monitor_price(price_feed, authority.pubkey()), &[]).unwrap();
+    set_last_price(&mut test, address, 40_000);
+    test.process_instruction(monitor_price(price_feed, authority.pubkey()), &[]).unwrap();
+    assert!(test.events::<AbnormalPriceMove>().is_empty());
+
+    // Another operator monitors the same feed with its own threshold
+    let other = Keypair::new();
+    test.airdrop(&other.pubkey(), LAMPORTS_PER_SOL);
+    test.process_instruction(initialize_price_monitor(price_feed, other.pubkey(), 100), &[&other]).unwrap();
+    let monitor: PriceMonitor = test.anchor_account(&price_monitor(&price_feed, &other.pubkey())).unwrap();
+    assert_eq!((monitor.authority, monitor.max_move_bps), (other.pubkey(), 100));
+    assert_eq!(
+        test.process_instruction(initialize_price_monitor(Pubkey::new_unique(), other.pubkey(), 0), &[&other])
+            .unwrap_err(),
+        anchor_error(OracleError::InvalidMaxPriceMove)
+    );
+}
+
+#[test]
+fn monitor_price_requires_the_monitored_feed() {
+    let mut test = ProgramTest::new();
+    let (price_feed, authority) = monitored_feed(&mut test, 500);
+    let mut ix = monitor_price(price_feed, authority.pubkey());
+    ix.accounts[1].pubkey = Pubkey::new_unique();
+    assert_eq!(
+        test.process_instruction(ix, &[]).unwrap_err(),
+        anchor_error(anchor_lang::error::ErrorCode::ConstraintSeeds)
+    );
+}
+
+fn initialize_price_aggregator(authority: Pubkey, aggregator_id: u64, sources: Vec<PriceSource>) -> Instruction {
+    Instruction {
+        program_id: vectai_oracle::ID,
+        accounts: vectai_oracle::accounts::InitializePriceAggregator {
+            price_aggregator: price_aggregator(&authority, aggregator_id),
+            authority,
+            system_program: system_program::ID,
+        }
+        .to_account_metas(None),
+        data: vectai_oracle::instruction::InitializePriceAggregator { aggregator_id, sources }.data(),
+    }
+}
+
+fn get_aggregated_price(aggregator: Pubkey, price_feeds: &[Pubkey]) -> Instruction {
+    let mut accounts = vectai_oracle::accounts::GetAggregatedPrice {
+        price_aggregator: aggregator,
+    }
+    .to_account_metas(None);
+    accounts.extend(price_feeds.iter().map(|price_feed| AccountMeta::new_readonly(*price_feed, false)));
+    Instruction {
+        program_id: vectai_oracle::ID,
+        accounts,
+        data: vectai_oracle::instruction::GetAggregatedPrice {}.data(),
+    }
+}
+
+fn source(price_feed: Pubkey, weight_bps: u16) -> PriceSource {
+    PriceSource { price_feed, weight_bps }
+}
+
+#[test]
+fn get_aggregated_price_averages_the_sources() {
+    let mut test = ProgramTest::new();
+    let authority = Keypair::new();
+    test.airdrop(&authority.pubkey(), LAMPORTS_PER_SOL);
+    let (pyth, switchboard) = (Pubkey::new_unique(), Pubkey::new_unique());
+    let sources = vec![source(pyth, 7_000), source(switchboard, 3_000)];
+    test.process_instruction(initialize_price_aggregator(authority.pubkey(), 1, sources.clone()), &[&authority])
+        .unwrap();
+    let address = price_aggregator(&authority.pubkey(), 1);
+    let aggregator: PriceAggregator = test.anchor_account(&address).unwrap();
+    assert_eq!((aggregator.authority, aggregator.aggregator_id), (authority.pubkey(), 1));
+    assert_eq!(aggregator.sources, sources);
+
+    test.process_instruction(get_aggregated_price(address, &[pyth, switchboard]), &[]).unwrap();
+    let (_, data) = test.return_data().expect("return data");
+    let aggregated = AggregatedPrice::deserialize(&mut data.as_slice()).unwrap();
+    // Every mocked feed reads the same price
+    assert_eq!((aggregated.price, aggregated.conf, aggregated.expo), (45_000, 100, 0));
+    assert_eq!((aggregated.max_deviation_bps, aggregated.publish_time), (0, test.clock().unix_timestamp));
+
+    // Feeds out of order or missing do not match the sources
+    for price_feeds in [&[switchboard, pyth][..], &[pyth][..]] {
+        assert_eq!(
+            test.process_instruction(get_aggregated_price(address, price_feeds), &[]).unwrap_err(),
+            anchor_error(OracleError::PriceSourceMismatch)
+        );
+    }
+}
+
+#[test]
+fn price_sources_must_be_distinct_and_weighted_to_10000_bps() {
+    let mut test = ProgramTest::new();
+    let authority = Keypair::new();
+    test.airdrop(&authority.pubkey(), LAMPORTS_PER_SOL);
+    let feed = Pubkey::new_unique();
+    let invalid = [
+        vec![],
+        vec![source(feed, 7_000), source(Pubkey::new_unique(), 2_000)],
+        vec![source(feed, 10_000), source(Pubkey::new_unique(), 0)],
+        vec![source(feed, 5_000), source(feed, 5_000)],
+        (0..5).map(|_| source(Pubkey::new_unique(), 2_000)).collect(),
+    ];
+    for sources in invalid {
+        assert_eq!(
+            test.process_instruction(initialize_price_aggregator(authority.pubkey(), 1, sources), &[&authority])
+                .unwrap_err(),
+            anchor_error(OracleError::InvalidPriceSources)
+        );
+    }
+    let sources = vec![source(feed, 10_000)];
+    test.process_instruction(initialize_price_aggregator(authority.pubkey(), 1, sources), &[&authority]).unwrap();
+
+    let address = price_aggregator(&authority.pubkey(), 1);
+    let set_price_sources = |authority: Pubkey, sources: Vec<PriceSource>| Instruction {
+        program_id: vectai_oracle::ID,
+        accounts: vectai_oracle::accounts::SetPriceSources {
+            price_aggregator: address,
+            authority,
+        }
+        .to_account_metas(None),
+        data: vectai_oracle::instruction::SetPriceSources { sources }.data(),
+    };
+    let sources = vec![source(feed, 6_000), source(Pubkey::new_unique(), 4_000)];
+    let impostor = Keypair::new();
+    test.airdrop(&impostor.pubkey(), LAMPORTS_PER_SOL);
+    assert_eq!(
+        test.process_instruction(set_price_sources(impostor.pubkey(), sources.clone()), &[&impostor]).unwrap_err(),
+        anchor_error(anchor_lang::error::ErrorCode::ConstraintHasOne)
+    );
+    assert_eq!(
+        test.process_instruction(set_price_sources(authority.pubkey(), vec![source(feed, 9_999)]), &[&authority])
+            .unwrap_err(),
+        anchor_error(OracleError::InvalidPriceSources)
+    );
+    test.process_instruction(set_price_sources(authority.pubkey(), sources.clone()), &[&authority]).unwrap();
+    let aggregator: PriceAggregator = test.anchor_account(&address).unwrap();
+    assert_eq!(aggregator.sources, sources);
+}
+
+#[test]
+fn aggregate_prices_weights_sources_at_the_finest_exponent() {
+    // 70% Pyth at $150.00 +- $1.00, 30% Switchboard at $151.000 +- $0.200
+    let pyth = PriceData {
+        price: 15_000,
+        conf: 100,
+        expo: -2,
+        publish_time: 1_000,
+        slot: 2_500,
+    };
+    let switchboard = PriceData {
+        price: 151_000,
+        conf: 200,
+        expo: -3,
+        publish_time: 990,
+        slot: 2_475,
+    };
+
+    let aggregated = aggregate_prices(&[(pyth.clone(), 7_000), (switchboard.clone(), 3_000)]).unwrap();
+    // 150_000 * 0.7 + 151_000 * 0.3
+    assert_eq!((aggregated.price, aggregated.expo), (150_300, -3));
+    // 1_000 * 0.7 + 200 * 0.3
+    assert_eq!(aggregated.conf, 760);
+    assert_eq!((aggregated.publish_time, aggregated.slot), (990, 2_475));
+    // 1_000 / 150_300
+    assert_eq!(aggregated.max_deviation_bps, 66);
+
+    let single = aggregate_prices(&[(pyth.clone(), 10_000)]).unwrap();
+    assert_eq!((single.price_data().price, single.max_deviation_bps), (15_000, 0));
+
+    assert!(aggregate_prices(&[]).is_none());
+    let negative = PriceData { price: -1, ..switchboard };
+    assert!(aggregate_prices(&[(pyth, 5_000), (negative, 5_000)]).is_none());
+}
+
+fn initialize_price_policy(
+    price_feed: Pubkey,
+    authority: Pubkey,
+    max_age_secs: u32,
+    stale_price_policy: StalePricePolicy,
+) -> Instruction {
+    Instruction {
+        program_id: vectai_oracle::ID,
+        accounts: vectai_oracle::accounts::InitializePricePolicy {
+            price_policy: price_policy(&price_feed, &authority),
+            price_feed,
+            authority,
+            system_program: system_program::ID,
+        }
+        .to_account_metas(None),
+        data: vectai_oracle::instruction::InitializePricePolicy {
+            max_age_secs,
+            stale_price_policy,
+        }
+        .data(),
+    }
+}
+
+#[test]
+fn get_price_with_policy_records_fresh_reads() {
+    let mut test = ProgramTest::new();
+    let authority = Keypair::new();
+    test.airdrop(&authority.pubkey(), LAMPORTS_PER_SOL);
+    let price_feed = Pubkey::new_unique();
+    test.process_instruction(
+        initialize_price_policy(price_feed, authority.pubkey(), 60, StalePricePolicy::LastGoodPrice),
+        &[&authority],
+    )
+    .unwrap();
+    let address = price_policy(&price_feed, &authority.pubkey());
+
+    let ix = Instruction {
+        program_id: vectai_oracle::ID,
+        accounts: vectai_oracle::accounts::GetPriceWithPolicy {
+            price_policy: address,
+            price_feed,
+        }
+        .to_account_metas(None),
+        data: vectai_oracle::instruction::GetPriceWithPolicy {}.data(),
+    };
+    test.process_instruction(ix.clone(), &[]).unwrap();
+    let (_, data) = test.return_data().expect("return data");
+    let price = PolicyPrice::deserialize(&mut data.as_slice()).unwrap();
+    assert_eq!((price.price, price.conf, price.stale), (45_000, 100, false));
+    let policy: PricePolicy = test.anchor_account(&address).unwrap();
+    assert_eq!((policy.last_good_price, policy.last_good_publish_time), (45_000, test.clock().unix_timestamp));
+
+    // The policy is bound to its feed
+    let mut ix = ix;
+    ix.accounts[1].pubkey = Pubkey::new_unique();
+    assert_eq!(
+        test.process_instruction(ix, &[]).unwrap_err(),
+        anchor_error(anchor_lang::error::ErrorCode::ConstraintSeeds)
+    );
+}
+
+#[test]
+fn price_policy_belongs_to_its_authority() {
+    let mut test = ProgramTest::new();
+    let authority = Keypair::new();
+    test.airdrop(&authority.pubkey(), LAMPORTS_PER_SOL);
+    let price_feed = Pubkey::new_unique();
+    for (max_age_secs, stale_price_policy) in
+        [(0, StalePricePolicy::Reject), (60, StalePricePolicy::InflateConfidence { conf_bps_per_sec: 0 })]
+    {
+        assert_eq!(
+            test.process_instruction(
+                initialize_price_policy(price_feed, authority.pubkey(), max_age_secs, stale_price_policy),
+                &[&authority]
+            )
+            .unwrap_err(),
+            anchor_error(OracleError::InvalidPricePolicy)
+        );
+    }
+    test.process_instruction(
+        initialize_price_policy(price_feed, authority.pubkey(), 60, StalePricePolicy::Reject),
+        &[&authority],
+    )
+    .unwrap();
+
+    let address = price_policy(&price_feed, &authority.pubkey());
+    let set_price_policy = |authority: Pubkey| Instruction {
+        program_id: vectai_oracle::ID,
+        accounts: vectai_oracle::accounts::SetPricePolicy {
+            price_policy: address,
+            authority,
+        }
+        .to_account_metas(None),
+        data: vectai_oracle::instruction::SetPricePolicy {
+            max_age_secs: 30,
+            stale_price_policy: StalePricePolicy::InflateConfidence { conf_bps_per_sec: 5 },
+        }
+        .data(),
+    };
+    let impostor = Keypair::new();
+    test.airdrop(&impostor.pubkey(), LAMPORTS_PER_SOL);
+    assert_eq!(
+        test.process_instruction(set_price_policy(impostor.pubkey()), &[&impostor]).unwrap_err(),
+        anchor_error(anchor_lang::error::ErrorCode::ConstraintHasOne)
+    );
+    test.process_instruction(set_price_policy(authority.pubkey()), &[&authority]).unwrap();
+    let policy: PricePolicy = test.anchor_account(&address).unwrap();
+    assert_eq!(policy.max_age_secs, 30);
+    assert_eq!(policy.stale_price_policy, StalePricePolicy::InflateConfidence { conf_bps_per_sec: 5 });
+}
+
+#[test]
+fn stale_price_policies() {
+    let mut policy = PricePolicy {
+        authority: Pubkey::new_unique(),
+        price_feed: Pubkey::new_unique(),
+        max_age_secs: 60,
+        stale_price_policy: StalePricePolicy::LastGoodPrice,
+        last_good_price: 0,
+        last_good_conf: 0,
+        last_good_expo: 0,
+        last_good_publish_time: 0,
+        bump: 255,
+    };
+    let read = |price: i64, publish_time: i64| PriceData {
+        price,
+        conf: 100,
+        expo: -2,
+        publish_time,
+        slot: 0,
+    };
+
+    // No good price yet to fall back on
+    assert_eq!(policy.apply(read(15_000, 1_000), 1_061), None);
+
+    // Exactly max_age_secs old is still fresh
+    let fresh = policy.apply(read(15_000, 1_000), 1_060).unwrap();
+    assert_eq!((fresh.price, fresh.stale), (15_000, false));
+    assert_eq!((policy.last_good_price, policy.last_good_publish_time), (15_000, 1_000));
+
+    let last_good = policy.apply(read(14_000, 1_100), 1_200).unwrap();
+    assert_eq!((last_good.price, last_good.publish_time, last_good.stale), (15_000, 1_000, true));
+    assert_eq!(policy.last_good_price, 15_000);
+
+    // 10 bps of 14_000 for each of the 40 seconds past the max age
+    policy.stale_price_policy = StalePricePolicy::InflateConfidence { conf_bps_per_sec: 10 };
+    let inflated = policy.apply(read(14_000, 1_100), 1_200).unwrap();
+    assert_eq!((inflated.price, inflated.conf, inflated.stale), (14_000, 100 + 560, true));
+
+    policy.stale_price_policy = StalePricePolicy::Reject;
+    assert_eq!(policy.apply(read(14_000, 1_100), 1_200), None);
+    assert!(!policy.apply(read(14_000, 1_100), 1_150).unwrap().stale);
+}
+
+/// A Lazer payload at `timestamp_us` with BTC (feed 1) at 45_000.12345678
+/// (bid/ask 45_000.10/45_000.15, 12 publishers) and an ETH (feed 2) price
+/// without an exponent
+fn lazer_payload(timestamp_us: u64) -> Vec<u8> {
+    let mut payload = LAZER_PAYLOAD_FORMAT_MAGIC.to_le_bytes().to_vec();
+    payload.extend_from_slice(&timestamp_us.to_le_bytes());
+    payload.extend_from_slice(&[1, 2]); // Channel, feed count
+
+    payload.extend_from_slice(&1u32.to_le_bytes());
+    payload.push(5);
+    payload.push(0);
+    payload.extend_from_slice(&4_500_012_345_678i64.to_le_bytes());
+    payload.push(1);
+    payload.extend_from_slice(&4_500_010_000_000i64.to_le_bytes());
+    payload.push(2);
+    payload.extend_from_slice(&4_500_015_000_000i64.to_le_bytes());
+    payload.push(3);
+    payload.extend_from_slice(&12u16.to_le_bytes());
+    payload.push(4);
+    payload.extend_from_slice(&(-8i16).to_le_bytes());
+
+    payload.extend_from_slice(&2u32.to_le_bytes());
+    payload.push(1);
+    payload.push(0);
+    payload.extend_from_slice(&250_000_000_000i64.to_le_bytes());
+    payload
+}
+
+/// `payload` signed by `signer` in the Solana update format
+fn lazer_update(signer: &Keypair, payload: &[u8]) -> Vec<u8> {
+    let mut update = LAZER_SOLANA_FORMAT_MAGIC.to_le_bytes().to_vec();
+    update.extend_from_slice(signer.sign_message(payload).as_ref());
+    update.extend_from_slice(signer.pubkey().as_ref());
+    update.extend_from_slice(&(payload.len() as u16).to_le_bytes());
+    update.extend_from_slice(payload);
+    update
+}
+
+fn get_lazer_price(update: Vec<u8>, feed_id: u32) -> Instruction {
+    get_lazer_price_within(update, feed_id, 2_000_000)
+}
+
+fn get_lazer_price_within(update: Vec<u8>, feed_id: u32, max_age_us: u64) -> Instruction {
+    Instruction {
+        program_id: vectai_oracle::ID,
+        accounts: vectai_oracle::accounts::GetLazerPrice {
+            lazer_signers: lazer_signers(),
+            instructions: anchor_lang::solana_program::sysvar::instructions::ID,
+        }
+        .to_account_metas(None),
+        data: vectai_oracle::instruction::GetLazerPrice {
+            update,
+            feed_id,
+            max_age_us,
+        }
+        .data(),
+    }
+}
+
+/// Trust `signer` for an hour, standing in for the timelocked setup
+fn trust_lazer_signer(test: &mut ProgramTest, signer: Pubkey) {
+    let expires_at = test.clock().unix_timestamp + 3_600;
+    let (address, bump) = Pubkey::find_program_address(&[b"lazer-signers"], &vectai_oracle::ID);
+    let lazer_signers = LazerSigners {
+        signers: vec![LazerSigner {
+            pubkey: signer,
+            expires_at,
+        }],
+        bump,
+    };
+    test.set_anchor_account(address, &lazer_signers);
+}
+
+#[test]
+fn get_lazer_price_verifies_signed_updates() {
+    let mut test = ProgramTest::new();
+    let signer = Keypair::new();
+    trust_lazer_signer(&mut test, signer.pubkey());
+    let timestamp_us = test.clock().unix_timestamp as u64 * 1_000_000 + 250_000;
+    let update = lazer_update(&signer, &lazer_payload(timestamp_us));
+    let verify = LazerUpdate::parse(&update).unwrap().ed25519_instruction();
+
+    test.process_transaction(&[verify.clone(), get_lazer_price(update.clone(), 1)], &[]).unwrap();
+    let (_, data) = test.return_data().expect("return data");
+    let price = LazerPrice::deserialize(&mut data.as_slice()).unwrap();
+    assert_eq!(
+        price,
+        LazerPrice {
+            feed_id: 1,
+            price: 4_500_012_345_678,
+            conf: 2_500_000,
+            expo: -8,
+            timestamp_us,
+        }
+    );
+    let price_data = price.price_data(test.clock());
+    assert_eq!((price_data.publish_time, price_data.slot), (test.clock().unix_timestamp, test.clock().slot));
+
+    // ETH has no exponent; feed 3 is not in the update
+    for feed_id in [2, 3] {
+        assert_eq!(
+            test.process_transaction(&[verify.clone(), get_lazer_price(update.clone(), feed_id)], &[])
+                .unwrap_err(),
+            anchor_error(OracleError::LazerFeedNotFound)
+        );
+    }
+}
+
+#[test]
+fn get_lazer_price_rejects_stale_and_future_updates() {
+    let mut test = ProgramTest::new();
+    let signer = Keypair::new();
+    trust_lazer_signer(&mut test, signer.pubkey());
+    let now = test.clock().unix_timestamp as u64;
+    let update = lazer_update(&signer, &lazer_payload(now * 1_000_000 + 250_000));
+    let verify = LazerUpdate::parse(&update).unwrap().ed25519_instruction();
+
+    // 2.75s old by the clock's second
+    test.warp_forward(3);
+    assert_eq!(
+        test.process_transaction(&[verify.clone(), get_lazer_price_within(update.clone(), 1, 2_000_000)], &[])
+            .unwrap_err(),
+        anchor_error(OracleError::StaleLazerUpdate)
+    );
+    test.process_transaction(&[verify, get_lazer_price_within(update, 1, 2_750_000)], &[]).unwrap();
+    let (_, data) = test.return_data().expect("return data");
+    let price = LazerPrice::deserialize(&mut data.as_slice()).unwrap();
+
+    // Dated 6 slots back rather than at the verification slot
+    let price_data = price.price_data(test.clock());
+    assert_eq!(price_data.slot, test.clock().slot - 6);
+    assert!(!price_data.observed_within(test.clock().slot, 5));
+
+    let now = test.clock().unix_timestamp as u64;
+    let update = lazer_update(&signer, &lazer_payload((now + 1) * 1_000_000));
+    let verify = LazerUpdate::parse(&update).unwrap().ed25519_instruction();
+    assert_eq!(
+        test.process_transaction(&[verify, get_lazer_price(update, 1)], &[]).unwrap_err(),
+        anchor_error(OracleError::LazerUpdateFromFuture)
+    );
+}
+
+#[test]
+fn get_lazer_price_rejects_unverified_updates() {
+    let mut test = ProgramTest::new();
+    let signer = Keypair::new();
+    trust_lazer_signer(&mut test, signer.pubkey());
+    let payload = lazer_payload(1_700_000_000_000_000);
+    let update = lazer_update(&signer, &payload);
+    let verify = LazerUpdate::parse(&update).unwrap().ed25519_instruction();
+
+    // Without the Ed25519 instruction, or with it elsewhere in the transaction
+    assert_eq!(
+        test.process_instruction(get_lazer_price(update.clone(), 1), &[]).unwrap_err(),
+        anchor_error(OracleError::LazerSignatureNotVerified)
+    );
+    assert_eq!(
+        test.process_transaction(&[get_lazer_price(update.clone(), 1), verify.clone()], &[]).unwrap_err(),
+        anchor_error(OracleError::LazerSignatureNotVerified)
+    );
+
+    // A payload edited after signing: verifying the original proves nothing,
+    // verifying the edit fails in the precompile
+    let mut forged = update.clone();
+    *forged.last_mut().unwrap() ^= 1;
+    assert_eq!(
+        test.process_transaction(&[verify.clone(), get_lazer_price(forged.clone(), 1)], &[]).unwrap_err(),
+        anchor_error(OracleError::LazerSignatureNotVerified)
+    );
+    let forged_verify = LazerUpdate::parse(&forged).unwrap().ed25519_instruction();
+    assert!(test.process_transaction(&[forged_verify, get_lazer_price(forged, 1)], &[]).is_err());
+
+    // Correctly signed by a key that is not trusted
+    let stranger = Keypair::new();
+    let update = lazer_update(&stranger, &payload);
+    let verify = LazerUpdate::parse(&update).unwrap().ed25519_instruction();
+    assert_eq!(
+        test.process_transaction(&[verify, get_lazer_price(update, 1)], &[]).unwrap_err(),
+        anchor_error(OracleError::UntrustedLazerSigner)
+    );
+
+    // Trust lapses at the expiry
+    let update = lazer_update(&signer, &payload);
+    let verify = LazerUpdate::parse(&update).unwrap().ed25519_instruction();
+    test.warp_forward(3_600);
+    assert_eq!(
+        test.process_transaction(&[verify, get_lazer_price(update, 1)], &[]).unwrap_err(),
+        anchor_error(OracleError::UntrustedLazerSigner)
+    );
+
+    assert_eq!(
+        test.process_instruction(get_lazer_price(payload, 1), &[]).unwrap_err(),
+        anchor_error(OracleError::InvalidLazerUpdate)
+    );
+}
+
+#[test]
+fn lazer_signers_are_admin_managed() {
+    let mut test = ProgramTest::new();
+    let impostor = Keypair::new();
+    test.airdrop(&impostor.pubkey(), LAMPORTS_PER_SOL);
+
+    let initialize = Instruction {
+        program_id: vectai_oracle::ID,
+        accounts: vectai_oracle::accounts::InitializeLazerSigners {
+            lazer_signers: lazer_signers(),
+            admin: impostor.pubkey(),
+            system_program: system_program::ID,
+        }
+        .to_account_metas(None),
+        data: vectai_oracle::instruction::InitializeLazerSigners {}.data(),
+    };
+    assert_eq!(
+        test.process_instruction(initialize, &[&impostor]).unwrap_err(),
+        anchor_error(OracleError::UnauthorizedAdmin)
+    );
+
+    trust_lazer_signer(&mut test, Pubkey::new_unique());
+    let set_lazer_signer = Instruction {
+        program_id: vectai_oracle::ID,
+        accounts: vectai_oracle::accounts::SetLazerSigner {
+            lazer_signers: lazer_signers(),
+            admin: impostor.pubkey(),
+        }
+        .to_account_metas(None),
+        data: vectai_oracle::instruction::SetLazerSigner {
+            signer: impostor.pubkey(),
+            expires_at: i64::MAX,
+        }
+        .data(),
+    };
+    assert_eq!(
+        test.process_instruction(set_lazer_signer, &[&impostor]).unwrap_err(),
+        anchor_error(OracleError::UnauthorizedAdmin)
+    );
+}
+
+#[test]
+fn lazer_payload_parses_requested_properties() {
+    let payload = LazerPayload::parse(&lazer_payload(42)).unwrap();
+    assert_eq!((payload.timestamp_us, payload.channel), (42, 1));
+    assert_eq!(
+        payload.feeds,
+        vec![
+            LazerFeed {
+                feed_id: 1,
+                price: Some(4_500_012_345_678),
+                best_bid_price: Some(4_500_010_000_000),
+                best_ask_price: Some(4_500_015_000_000),
+                publisher_count: Some(12),
+                exponent: Some(-8),
+            },
+            LazerFeed {
+                feed_id: 2,
+                price: Some(250_000_000_000),
+                ..LazerFeed::default()
+            },
+        ]
+    );
+
+    // Trailing bytes, an unknown property, a truncated feed
+    let mut trailing = lazer_payload(42);
+    trailing.push(0);
+    assert_eq!(LazerPayload::parse(&trailing), None);
+    let mut unknown = lazer_payload(42);
+    let len = unknown.len();
+    unknown[len - 9] = 9;
+    assert_eq!(LazerPayload::parse(&unknown), None);
+    assert_eq!(LazerPayload::parse(&lazer_payload(42)[..40]), None);
+}
@@ -0,0 +1,528 @@
+//! `vectai_orderbook` orders and the protocol exposure their escrows open.
+//!
+//! Orders sell USDC for WSOL on the trading network, triggered by the mock
+//! oracle's 45000. Exposure caps take the timelock, which cannot sign
+//! here, so capped entries are written into the protocol stats directly.
+//! Force-cancels are signed by the governance native treasury, through a
+//! mock of SPL Governance executing a proposal.
+
+use anchor_lang::{
+    solana_program::{
+        account_info::AccountInfo, entrypoint::ProgramResult, program::invoke_signed, system_program, sysvar,
+    },
+    InstructionData, ToAccountMetas,
+};
+use solana_sdk::{
+    instruction::{AccountMeta, Instruction},
+    native_token::LAMPORTS_PER_SOL,
+    program_error::ProgramError,
+    pubkey::Pubkey,
+    signature::{Keypair, Signer},
+};
+use vectai_common::governance_native_treasury;
+use vectai_orderbook::{
+    accounts, instruction, ConditionalOrder, Keeper, KeeperRegistry, OrderCancelled, OrderError, OrderKind,
+    OrderSide, OrdersForceCancelled,
+};
+use vectai_program_test::{
+    anchor_error,
+    trader::{new_trader_config, trader_address, TradingNetwork},
+    ProgramTest,
+};
+use vectai_protocol::{MintExposure, ProtocolError, ProtocolStats};
+use vectai_trader::TraderConfig;
+
+const POOL_RESERVE: u64 = 1_000_000_000_000;
+const OWNER_BALANCE: u64 = 10_000_000;
+const TRIGGER_PRICE: i64 = 40_000;
+const ORDER_DURATION: i64 = 3_600;
+const KEEPER_BOND: u64 = 1_000;
+
+fn order_address(owner: &Pubkey, order_id: u64) -> Pubkey {
+    Pubkey::find_program_address(&[b"order", owner.as_ref(), &order_id.to_le_bytes()], &vectai_orderbook::ID).0
+}
+
+fn escrow_address(order: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(&[b"order-escrow", order.as_ref()], &vectai_orderbook::ID).0
+}
+
+/// SPL Governance, whose proposals sign with the native treasury of the
+/// protocol governance (still the placeholder default address)
+const SPL_GOVERNANCE: Pubkey = solana_sdk::pubkey!("GovER5Lthms3bLBqWub97yVrMmEogzX7xNjdXpPPCVZw");
+
+/// Executes a proposal: forwards its instruction data to the program in its
+/// first account, signing as the native treasury
+fn process_governance(program_id: &Pubkey, accounts: &[AccountInfo], data: &[u8]) -> ProgramResult {
+    let governance = Pubkey::default();
+    let (treasury, bump) = Pubkey::find_program_address(&[b"native-treasury", governance.as_ref()], program_id);
+    let ix = Instruction {
+        program_id: *accounts[0].key,
+        accounts: accounts[1..]
+            .iter()
+            .map(|account| AccountMeta {
+                pubkey: *account.key,
+                is_signer: account.is_signer || *account.key == treasury,
+                is_writable: account.is_writable,
+            })
+            .collect(),
+        data: data.to_vec(),
+    };
+    invoke_signed(&ix, accounts, &[&[b"native-treasury", governance.as_ref(), &[bump]]])
+}
+
+/// `ix` wrapped in an executed governance proposal
+fn through_governance(ix: Instruction) -> Instruction {
+    let mut accounts = vec![AccountMeta::new_readonly(ix.program_id, false)];
+    accounts.extend(ix.accounts.into_iter().map(|meta| AccountMeta {
+        is_signer: meta.is_signer && meta.pubkey != governance_native_treasury(),
+        ..meta
+    }));
+    Instruction {
+        program_id: SPL_GOVERNANCE,
+        accounts,
+        data: ix.data,
+    }
+}
+
+/// An owner holding USDC and an empty WSOL account on the trading network,
+/// and a bonded keeper
+struct Fixture {
+    test: ProgramTest,
+    network: TradingNetwork,
+    owner: Keypair,
+    source: Pubkey,
+    destination: Pubkey,
+    price_feed: Pubkey,
+    keeper: Keypair,
+}
+
+impl Fixture {
+    fn new() -> Self {
+        let mut test = ProgramTest::new();
+        let network = test.create_trading_network(POOL_RESERVE);
+        let owner = Keypair::new();
+        test.airdrop(&owner.pubkey(), LAMPORTS_PER_SOL);
+        let (source, destination) = (Pubkey::new_unique(), Pubkey::new_unique());
+        test.create_token_account(source, &network.usdc, &owner.pubkey(), OWNER_BALANCE);
+        test.create_token_account(destination, &network.wsol, &owner.pubkey(), 0);
+
+        let keeper = Keypair::new();
+        test.airdrop(&keeper.pubkey(), LAMPORTS_PER_SOL);
+        let (registry, bump) = Pubkey::find_program_address(&[b"keeper-registry"], &vectai_orderbook::ID);
+        test.set_anchor_account(
+            registry,
+            &KeeperRegistry {
+                min_bond: KEEPER_BOND,
+                full_tip_bond: KEEPER_BOND,
+                bond_vault: Pubkey::new_unique(),
+                total_bonded: KEEPER_BOND,
+                total_slashed: 0,
+                keeper_count: 1,
+                bump,
+            },
+        );
+        let (keeper_account, bump) =
+            Pubkey::find_program_address(&[b"keeper", keeper.pubkey().as_ref()], &vectai_orderbook::ID);
+        test.set_anchor_account(
+            keeper_account,
+            &Keeper {
+                authority: keeper.pubkey(),
+                bonded: KEEPER_BOND,
+                unbonding: 0,
+                unbonds_at: 0,
+                fills: 0,
+                tips_earned: 0,
+                slashed: 0,
+                registered_at: 0,
+                bump,
+            },
+        );
+
+        Fixture {
+            test,
+            network,
+            owner,
+            source,
+            destination,
+            price_feed: Pubkey::new_unique(),
+            keeper,
+        }
+    }
+
+    fn stats(&self) -> ProtocolStats {
+        self.test.anchor_account(&self.network.protocol_stats).unwrap()
+    }
+
+    /// Open exposure to USDC, `None` when untracked
+    fn exposure(&self) -> Option<MintExposure> {
+        self.stats().exposure(&self.network.usdc).copied()
+    }
+
+    /// Cap the open exposure to USDC, as `set_exposure_cap` would
+    fn cap_exposure(&mut self, cap: u64) {
+        let mut stats = self.stats();
+        stats.set_exposure_cap(self.network.usdc, cap).unwrap();
+        self.test.set_anchor_account(self.network.protocol_stats, &stats);
+    }
+
+    fn place(&mut self, order_id: u64, amount_in: u64) -> Result<Pubkey, ProgramError> {
+        let owner = self.owner.pubkey();
+        let order = order_address(&owner, order_id);
+        let ix = Instruction {
+            program_id: vectai_orderbook::ID,
+            accounts: accounts::PlaceOrder {
+                order,
+                input_mint: self.network.usdc,
+                escrow: escrow_address(&order),
+                owner_source_token: self.source,
+                owner_destination_token: self.destination,
+                price_feed: self.price_feed,
+                owner,
+                rent: sysvar::rent::ID,
+                token_program: spl_token::ID,
+                system_program: system_program::ID,
+                protocol_stats: self.network.protocol_stats,
+                stats_reporter: vectai_protocol::stats_reporter(&vectai_orderbook::ID),
+                protocol_program: vectai_protocol::ID,
+            }
+            .to_account_metas(None),
+            data: instruction::PlaceOrder {
+                order_id,
+                side: OrderSide::Sell,
+                kind: OrderKind::Limit,
+                trigger_price: TRIGGER_PRICE,
+                amount_in,
+                min_amount_out: 1,
+                expires_at: self.test.clock().unix_timestamp + ORDER_DURATION,
+                keeper_tip: 0,
+            }
+            .data(),
+        };
+        let signer = self.owner.insecure_clone();
+        self.test.process_instruction(ix, &[&signer]).map(|_| order)
+    }
+
+    /// Cancel `order`, unlinking its OCO sibling `linked` when passed
+    fn cancel(&mut self, order: Pubkey, linked: Option<Pubkey>) -> Result<(), ProgramError> {
+        let owner = self.owner.pubkey();
+        let ix = Instruction {
+            program_id: vectai_orderbook::ID,
+            accounts: accounts::CancelOrder {
+                order,
+                escrow: escrow_address(&order),
+                owner_source_token: self.source,
+                owner,
+                authority: owner,
+                linked_order: linked,
+                token_program: spl_token::ID,
+                protocol_stats: self.network.protocol_stats,
+                stats_reporter: vectai_protocol::stats_reporter(&vectai_orderbook::ID),
+                protocol_program: vectai_protocol::ID,
+            }
+            .to_account_metas(None),
+            data: instruction::CancelOrder {}.data(),
+        };
+        let signer = self.owner.insecure_clone();
+        self.test.process_instruction(ix, &[&signer])
+    }
+
+    fn link(&mut self, order_a: Pubkey, order_b: Pubkey) {
+        let ix = Instruction {
+            program_id: vectai_orderbook::ID,
+            accounts: accounts::LinkOrders {
+                order_a,
+                order_b,
+                owner: self.owner.pubkey(),
+            }
+            .to_account_metas(None),
+            data: instruction::LinkOrders {}.data(),
+        };
+        let signer = self.owner.insecure_clone();
+        self.test.process_instruction(ix, &[&signer]).unwrap();
+    }
+
+    /// Fill `order`, refunding its OCO sibling `linked` when passed
+    fn fill(&mut self, order: Pubkey, linked: Option<Pubkey>) -> Result<(), ProgramError> {
+        let pool = &self.network.pool;
+        let keeper = self.keeper.pubkey();
+        let mut metas = accounts::FillOrder {
+            order,
+            escrow: escrow_address(&order),
+            owner_destination_token: self.destination,
+            owner: self.owner.pubkey(),
+            price_feed: self.price_feed,
+            keeper,
+            keeper_registry: Pubkey::find_program_address(&[b"keeper-registry"], &vectai_orderbook::ID).0,
+            keeper_account: Pubkey::find_program_address(&[b"keeper", keeper.as_ref()], &vectai_orderbook::ID).0,
+            vectai_oracle_program: vectai_oracle::ID,
+            swapper_program: raydium_swapper::ID,
+            raydium_amm_program: pool.amm_program,
+            amm: pool.amm,
+            amm_authority: pool.amm_authority,
+            amm_open_orders: pool.amm_open_orders,
+            amm_target_orders: pool.amm_target_orders,
+            pool_coin_token_account: pool.pool_coin_token_account,
+            pool_pc_token_account: pool.pool_pc_token_account,
+            serum_program: pool.serum_program,
+            serum_market: pool.serum_market,
+            serum_bids: pool.serum_bids,
+            serum_asks: pool.serum_asks,
+            serum_event_queue: pool.serum_event_queue,
+            serum_coin_vault: pool.serum_coin_vault,
+            serum_pc_vault: pool.serum_pc_vault,
+            serum_vault_signer: pool.serum_vault_signer,
+            token_program: spl_token::ID,
+            protocol_status: self.network.protocol_status,
+            network_config: self.network.network_config,
+            protocol_stats: self.network.protocol_stats,
+            swapper_stats_reporter: vectai_protocol::stats_reporter(&raydium_swapper::ID),
+            stats_reporter: vectai_protocol::stats_reporter(&vectai_orderbook::ID),
+            protocol_program: vectai_protocol::ID,
+            swap_fee_account: None,
+        }
+        .to_account_metas(None);
+        if let Some(linked) = linked {
+            metas.push(AccountMeta::new(linked, false));
+            metas.push(AccountMeta::new(escrow_address(&linked), false));
+            metas.push(AccountMeta::new(self.source, false));
+        }
+        let ix = Instruction {
+            program_id: vectai_orderbook::ID,
+            accounts: metas,
+            data: instruction::FillOrder {}.data(),
+        };
+        let signer = self.keeper.insecure_clone();
+        self.test.process_instruction(ix, &[&signer])
+    }
+
+    /// Give the owner a strategy, paused or active
+    fn set_strategy(&mut self, is_active: bool) {
+        let owner = self.owner.pubkey();
+        self.test.set_anchor_account(
+            trader_address(&owner),
+            &TraderConfig {
+                is_active,
+                ..new_trader_config(owner, TRIGGER_PRICE, 1_000_000, 100)
+            },
+        );
+    }
+
+    fn force_cancel_ix(&self, orders: &[Pubkey], admin: Pubkey) -> Instruction {
+        let owner = self.owner.pubkey();
+        let mut metas = accounts::ForceCancelOrders {
+            trader_config: trader_address(&owner),
+            owner,
+            admin,
+            token_program: spl_token::ID,
+            protocol_stats: self.network.protocol_stats,
+            stats_reporter: vectai_protocol::stats_reporter(&vectai_orderbook::ID),
+            protocol_program: vectai_protocol::ID,
+        }
+        .to_account_metas(None);
+        for order in orders {
+            metas.push(AccountMeta::new(*order, false));
+            metas.push(AccountMeta::new(escrow_address(order), false));
+            metas.push(AccountMeta::new(self.source, false));
+        }
+        Instruction {
+            program_id: vectai_orderbook::ID,
+            accounts: metas,
+            data: instruction::ForceCancelOrders {}.data(),
+        }
+    }
+
+    /// Force-cancel `orders` through a governance proposal
+    fn force_cancel(&mut self, orders: &[Pubkey]) -> Result<(), ProgramError> {
+        self.test.add_program(SPL_GOVERNANCE, process_governance);
+        let ix = through_governance(self.force_cancel_ix(orders, governance_native_treasury()));
+        self.test.process_instruction(ix, &[])
+    }
+}
+
+#[test]
+fn escrowed_orders_open_exposure_until_cancelled() {
+    let mut fixture = Fixture::new();
+    assert!(fixture.exposure().is_none());
+
+    let first = fixture.place(0, 1_000_000).unwrap();
+    let second = fixture.place(1, 250_000).unwrap();
+    assert_eq!(fixture.exposure().unwrap().open, 1_250_000);
+    assert_eq!(fixture.test.token_balance(&escrow_address(&first)), Some(1_000_000));
+
+    fixture.cancel(first, None).unwrap();
+    assert_eq!(fixture.exposure().unwrap().open, 250_000);
+    // Uncapped mints give up their entry once nothing is open
+    fixture.cancel(second, None).unwrap();
+    assert!(fixture.exposure().is_none());
+    assert_eq!(fixture.test.token_balance(&fixture.source), Some(OWNER_BALANCE));
+}
+
+#[test]
+fn exposure_cap_bounds_open_orders() {
+    let mut fixture = Fixture::new();
+    fixture.cap_exposure(1_500_000);
+
+    let first = fixture.place(0, 1_000_000).unwrap();
+    assert_eq!(
+        fixture.place(1, 500_001).unwrap_err(),
+        anchor_error(ProtocolError::ExposureCapExceeded)
+    );
+    assert!(fixture.test.account(&order_address(&fixture.owner.pubkey(), 1)).is_none());
+    // Up to the cap exactly
+    fixture.place(1, 500_000).unwrap();
+    assert_eq!(
+        fixture.exposure(),
+        Some(MintExposure {
+            mint: fixture.network.usdc,
+            open: 1_500_000,
+            cap: 1_500_000,
+        })
+    );
+
+    // Refunds make room again; the capped entry stays when nothing is open
+    fixture.cancel(first, None).unwrap();
+    fixture.place(2, 1_000_000).unwrap();
+    assert_eq!(fixture.exposure().unwrap().open, 1_500_000);
+}
+
+#[test]
+fn fills_close_exposure_of_both_oco_orders() {
+    let mut fixture = Fixture::new();
+    let take_profit = fixture.place(0, 1_000_000).unwrap();
+    let stop_loss = fixture.place(1, 1_000_000).unwrap();
+    fixture.link(take_profit, stop_loss);
+    assert_eq!(fixture.exposure().unwrap().open, 2_000_000);
+
+    fixture.fill(take_profit, Some(stop_loss)).unwrap();
+    assert!(fixture.test.account(&take_profit).is_none());
+    assert!(fixture.test.account(&stop_loss).is_none());
+    assert!(fixture.test.token_balance(&fixture.destination).unwrap() > 0);
+    assert!(fixture.exposure().is_none());
+    // The fill itself is still counted as a swap
+    let stats = fixture.stats();
+    assert_eq!(stats.swaps, 1);
+    assert_eq!(stats.volume(&fixture.network.usdc).unwrap().swapped, 1_000_000);
+}
+
+#[test]
+fn tokens_sent_to_an_escrow_do_not_block_closing_it() {
+    let mut fixture = Fixture::new();
+    let take_profit = fixture.place(0, 1_000_000).unwrap();
+    let stop_loss = fixture.place(1, 1_000_000).unwrap();
+    fixture.link(take_profit, stop_loss);
+    let limit = fixture.place(2, 500_000).unwrap();
+    let balance = OWNER_BALANCE - 2_500_000;
+    // Anyone may transfer into an escrow, e.g. a single token to grief it
+    let usdc = fixture.network.usdc;
+    for (order, escrowed) in [(take_profit, 1_000_000), (stop_loss, 1_000_000), (limit, 500_000)] {
+        fixture.test.create_token_account(escrow_address(&order), &usdc, &order, escrowed + 1);
+    }
+
+    fixture.cancel(limit, None).unwrap();
+    assert_eq!(fixture.test.token_balance(&fixture.source), Some(balance + 500_001));
+    fixture.fill(take_profit, Some(stop_loss)).unwrap();
+    assert!(fixture.test.account(&escrow_address(&take_profit)).is_none());
+    assert!(fixture.test.account(&escrow_address(&stop_loss)).is_none());
+    assert_eq!(fixture.test.token_balance(&fixture.source), Some(balance + 500_001 + 1_000_001));
+    assert_eq!(fixture.stats().volume(&usdc).unwrap().swapped, 1_000_001);
+}
+
+#[test]
+fn cancelling_one_oco_half_unlinks_the_other() {
+    let mut fixture = Fixture::new();
+    let take_profit = fixture.place(0, 1_000_000).unwrap();
+    let stop_loss = fixture.place(1, 1_000_000).unwrap();
+    fixture.link(take_profit, stop_loss);
+
+    let invalid = anchor_error(OrderError::InvalidLinkedOrder);
+    assert_eq!(fixture.cancel(take_profit, None).unwrap_err(), invalid);
+    let limit = fixture.place(2, 500_000).unwrap();
+    assert_eq!(fixture.cancel(take_profit, Some(limit)).unwrap_err(), invalid);
+
+    fixture.cancel(take_profit, Some(stop_loss)).unwrap();
+    assert!(fixture.test.account(&take_profit).is_none());
+    let sibling: ConditionalOrder = fixture.test.anchor_account(&stop_loss).unwrap();
+    assert_eq!(sibling.linked_order, Pubkey::default());
+
+    // The survivor fills on its own and no longer needs the closed order
+    fixture.fill(stop_loss, None).unwrap();
+    assert!(fixture.test.account(&stop_loss).is_none());
+}
+
+#[test]
+fn force_cancel_refunds_every_order_of_a_paused_strategy() {
+    let mut fixture = Fixture::new();
+    let take_profit = fixture.place(0, 1_000_000).unwrap();
+    let stop_loss = fixture.place(1, 1_000_000).unwrap();
+    fixture.link(take_profit, stop_loss);
+    let limit = fixture.place(2, 500_000).unwrap();
+    fixture.set_strategy(false);
+    let owner_lamports = fixture.test.lamports(&fixture.owner.pubkey());
+
+    let orders = [take_profit, stop_loss, limit];
+    fixture.force_cancel(&orders).unwrap();
+    for order in orders {
+        assert!(fixture.test.account(&order).is_none());
+        assert!(fixture.test.account(&escrow_address(&order)).is_none());
+    }
+    assert_eq!(fixture.test.token_balance(&fixture.source), Some(OWNER_BALANCE));
+    assert!(fixture.exposure().is_none());
+    // Order and escrow rents go back to the owner
+    assert!(fixture.test.lamports(&fixture.owner.pubkey()) > owner_lamports);
+
+    assert_eq!(fixture.test.events::<OrderCancelled>().len(), 3);
+    let [cancelled] = fixture.test.events::<OrdersForceCancelled>().try_into().ok().unwrap();
+    assert_eq!(
+        (cancelled.trader, cancelled.owner, cancelled.admin),
+        (trader_address(&fixture.owner.pubkey()), fixture.owner.pubkey(), governance_native_treasury())
+    );
+    assert_eq!((cancelled.orders, cancelled.refunded), (3, 2_500_000));
+}
+
+#[test]
+fn force_cancel_requires_admin_and_a_paused_strategy() {
+    let mut fixture = Fixture::new();
+    let order = fixture.place(0, 1_000_000).unwrap();
+
+    fixture.set_strategy(true);
+    assert_eq!(
+        fixture.force_cancel(&[order]).unwrap_err(),
+        anchor_error(OrderError::StrategyNotFlagged)
+    );
+
+    fixture.set_strategy(false);
+    let ix = fixture.force_cancel_ix(&[order], fixture.owner.pubkey());
+    let owner = fixture.owner.insecure_clone();
+    assert_eq!(
+        fixture.test.process_instruction(ix, &[&owner]).unwrap_err(),
+        anchor_error(OrderError::Unauthorized)
+    );
+
+    // Another owner's order cannot ride along
+    let mut other = Fixture::new();
+    let stranger = other.place(0, 1_000_000).unwrap();
+    for address in [stranger, escrow_address(&stranger)] {
+        fixture.test.set_account(address, other.test.account(&address).unwrap());
+    }
+    assert_eq!(
+        fixture.force_cancel(&[order, stranger]).unwrap_err(),
+        anchor_error(OrderError::InvalidOrderAccounts)
+    );
+    assert!(fixture.test.account(&order).is_some());
+    assert_eq!(fixture.exposure().unwrap().open, 1_000_000);
+}
+
+#[test]
+fn force_cancel_takes_both_oco_halves() {
+    let mut fixture = Fixture::new();
+    let take_profit = fixture.place(0, 1_000_000).unwrap();
+    let stop_loss = fixture.place(1, 1_000_000).unwrap();
+    fixture.link(take_profit, stop_loss);
+    fixture.set_strategy(false);
+
+    assert_eq!(
+        fixture.force_cancel(&[take_profit]).unwrap_err(),
+        anchor_error(OrderError::InvalidLinkedOrder)
+    );
+    assert!(fixture.test.account(&take_profit).is_some());
+}
@@ -0,0 +1,786 @@
+//! `vectai_protocol`'s activity counters, fed by `execute_trade`, `swap`
+//! and the token mints, its exposure caps, its lookup table, venue
+//! registries and account bundles, its role registry and its upgrade
+//! authority attestations.
+
+use anchor_lang::{
+    prelude::ProgramData,
+    solana_program::{address_lookup_table, bpf_loader_upgradeable::{self, UpgradeableLoaderState}, system_program},
+    AccountSerialize, InstructionData, ToAccountMetas,
+};
+use solana_sdk::{
+    account::Account,
+    instruction::Instruction,
+    native_token::LAMPORTS_PER_SOL,
+    pubkey::Pubkey,
+    signature::{Keypair, Signer},
+};
+use vectai_common::Versioned;
+use vectai_program_test::{anchor_error, ProgramTest};
+use vectai_protocol::{
+    account_bundle, accounts, instruction, lookup_table_registry, role_registry, venue_registry, AccountBundle,
+    ActivityKind, FeeConfig, LookupTableRegistry, MintExposure, NetworkConfig, PairVenues, ProtocolError,
+    ProtocolStats, ProtocolStatus, RaydiumPoolAccounts, Role, RoleHolder, RoleRegistry, UpgradeAttestation,
+    UpgradeAuthorityAttested, MAX_ROLE_HOLDERS, MAX_TRACKED_MINTS,
+};
+
+const SWAP_AMOUNT: u64 = 1_000_000;
+const POOL_RESERVE: u64 = 1_000_000_000_000;
+
+fn stats_address() -> Pubkey {
+    Pubkey::find_program_address(&[b"protocol-stats"], &vectai_protocol::ID).0
+}
+
+fn funded(test: &mut ProgramTest) -> Keypair {
+    let keypair = Keypair::new();
+    test.airdrop(&keypair.pubkey(), LAMPORTS_PER_SOL);
+    keypair
+}
+
+/// An empty account owned by the lookup table program
+fn lookup_table(test: &mut ProgramTest) -> Pubkey {
+    let address = Pubkey::new_unique();
+    test.set_account(address, Account::new(LAMPORTS_PER_SOL, 56, &address_lookup_table::program::ID));
+    address
+}
+
+#[test]
+fn initialize_protocol_stats_requires_admin() {
+    let mut test = ProgramTest::new();
+    let impostor = funded(&mut test);
+    let ix = Instruction {
+        program_id: vectai_protocol::ID,
+        accounts: accounts::InitializeProtocolStats {
+            protocol_stats: stats_address(),
+            admin: impostor.pubkey(),
+            system_program: system_program::ID,
+        }
+        .to_account_metas(None),
+        data: instruction::InitializeProtocolStats {}.data(),
+    };
+    assert_eq!(
+        test.process_instruction(ix, &[&impostor]).unwrap_err(),
+        anchor_error(ProtocolError::UnauthorizedAdmin)
+    );
+    assert!(test.account(&stats_address()).is_none());
+}
+
+#[test]
+fn record_activity_requires_the_kinds_reporter() {
+    let mut test = ProgramTest::new();
+    let protocol_stats = test.set_protocol_stats();
+    let impostor = funded(&mut test);
+    for kind in [ActivityKind::Execution, ActivityKind::Swap, ActivityKind::Mint] {
+        let ix = Instruction {
+            program_id: vectai_protocol::ID,
+            accounts: accounts::RecordActivity {
+                protocol_stats,
+                reporter: impostor.pubkey(),
+            }
+            .to_account_metas(None),
+            data: instruction::RecordActivity {
+                kind,
+                mint: Pubkey::new_unique(),
+                amount: SWAP_AMOUNT,
+            }
+            .data(),
+        };
+        assert_eq!(
+            test.process_instruction(ix, &[&impostor]).unwrap_err(),
+            anchor_error(ProtocolError::UnauthorizedReporter)
+        );
+    }
+    let stats: ProtocolStats = test.anchor_account(&protocol_stats).unwrap();
+    assert_eq!((stats.executions, stats.swaps, stats.mints), (0, 0, 0));
+}
+
+#[test]
+fn swap_records_activity() {
+    let mut test = ProgramTest::new();
+    let protocol_status = test.set_protocol_status(false, FeeConfig::INITIAL);
+    let protocol_stats = test.set_protocol_stats();
+    let (usdc, wsol) = (Pubkey::new_unique(), Pubkey::new_unique());
+    let mint_authority = Pubkey::new_unique();
+    test.create_mint(usdc, &mint_authority, 6);
+    test.create_mint(wsol, &mint_authority, 9);
+    let network_config = test.set_network_config(wsol, usdc);
+    let pool = test.create_whitelisted_pool(&usdc, &wsol, POOL_RESERVE, POOL_RESERVE);
+    let authority = funded(&mut test);
+    let (source, destination) = (Pubkey::new_unique(), Pubkey::new_unique());
+    test.create_token_account(source, &usdc, &authority.pubkey(), 2 * SWAP_AMOUNT);
+    test.create_token_account(destination, &wsol, &authority.pubkey(), 0);
+
+    let ix = Instruction {
+        program_id: raydium_swapper::ID,
+        accounts: raydium_swapper::accounts::SwapAccounts {
+            user_authority: authority.pubkey(),
+            user_source_token: source,
+            user_destination_token: destination,
+            raydium_amm_program: pool.amm_program,
+            amm: pool.amm,
+            amm_authority: pool.amm_authority,
+            amm_open_orders: pool.amm_open_orders,
+            amm_target_orders: pool.amm_target_orders,
+            pool_coin_token_account: pool.pool_coin_token_account,
+            pool_pc_token_account: pool.pool_pc_token_account,
+            serum_program: pool.serum_program,
+            serum_market: pool.serum_market,
+            serum_bids: pool.serum_bids,
+            serum_asks: pool.serum_asks,
+            serum_event_queue: pool.serum_event_queue,
+            serum_coin_vault: pool.serum_coin_vault,
+            serum_pc_vault: pool.serum_pc_vault,
+            serum_vault_signer: pool.serum_vault_signer,
+            token_program: spl_token::ID,
+            protocol_status,
+            network_config,
+            protocol_stats,
+            stats_reporter: vectai_protocol::stats_reporter(&raydium_swapper::ID),
+            protocol_program: vectai_protocol::ID,
+            fee_account: None,
+        }
+        .to_account_metas(None),
+        data: raydium_swapper::instruction::Swap {
+            amount_in: SWAP_AMOUNT,
+            min_amount_out: 1,
+        }
+        .data(),
+    };
+    test.process_instruction(ix.clone(), &[&authority]).unwrap();
+    test.process_instruction(ix, &[&authority]).unwrap();
+    assert_eq!(test.token_balance(&source), Some(0));
+
+    let stats: ProtocolStats = test.anchor_account(&protocol_stats).unwrap();
+    assert_eq!((stats.executions, stats.swaps, stats.mints), (0, 2, 0));
+    assert_eq!(stats.volume(&usdc).unwrap().swapped, 2 * SWAP_AMOUNT);
+    assert!(stats.volume(&wsol).is_none());
+}
+
+#[test]
+fn protocol_stats_track_volumes_for_a_bounded_set_of_mints() {
+    let mut stats = ProtocolStats {
+        executions: 0,
+        swaps: 0,
+        mints: 0,
+        volumes: Vec::new(),
+        bump: 255,
+        version: ProtocolStats::VERSION,
+        exposures: Vec::new(),
+    };
+    let mints: Vec<Pubkey> = (0..=MAX_TRACKED_MINTS).map(|_| Pubkey::new_unique()).collect();
+    for mint in &mints {
+        stats.record(ActivityKind::Mint, *mint, 10);
+    }
+    stats.record(ActivityKind::Execution, mints[0], u64::MAX);
+    stats.record(ActivityKind::Execution, mints[0], 1);
+
+    assert_eq!(stats.mints, MAX_TRACKED_MINTS as u64 + 1);
+    assert_eq!(stats.executions, 2);
+    assert_eq!(stats.volumes.len(), MAX_TRACKED_MINTS);
+    // The mint past the cap is counted but has no volume
+    assert!(stats.volume(&mints[MAX_TRACKED_MINTS]).is_none());
+    let first = stats.volume(&mints[0]).unwrap();
+    assert_eq!((first.minted, first.executed), (10, u64::MAX));
+}
+
+#[test]
+fn initialize_lookup_table_registry_requires_admin() {
+    let mut test = ProgramTest::new();
+    let impostor = funded(&mut test);
+    let table = lookup_table(&mut test);
+    let ix = Instruction {
+        program_id: vectai_protocol::ID,
+        accounts: accounts::InitializeLookupTableRegistry {
+            registry: lookup_table_registry(),
+            lookup_table: table,
+            admin: impostor.pubkey(),
+            system_program: system_program::ID,
+        }
+        .to_account_metas(None),
+        data: instruction::InitializeLookupTableRegistry {}.data(),
+    };
+    assert_eq!(
+        test.process_instruction(ix, &[&impostor]).unwrap_err(),
+        anchor_error(ProtocolError::UnauthorizedAdmin)
+    );
+    assert!(test.account(&lookup_table_registry()).is_none());
+}
+
+#[test]
+fn initialize_venue_registry_requires_admin() {
+    let mut test = ProgramTest::new();
+    let impostor = funded(&mut test);
+    let (base_mint, quote_mint) = (Pubkey::new_unique(), Pubkey::new_unique());
+    let ix = Instruction {
+        program_id: vectai_protocol::ID,
+        accounts: accounts::InitializeVenueRegistry {
+            registry: venue_registry(&base_mint, &quote_mint),
+            admin: impostor.pubkey(),
+            system_program: system_program::ID,
+        }
+        .to_account_metas(None),
+        data: instruction::InitializeVenueRegistry {
+            base_mint,
+            quote_mint,
+            venues: PairVenues::default(),
+        }
+        .data(),
+    };
+    assert_eq!(
+        test.process_instruction(ix, &[&impostor]).unwrap_err(),
+        anchor_error(ProtocolError::UnauthorizedAdmin)
+    );
+    assert!(test.account(&venue_registry(&base_mint, &quote_mint)).is_none());
+}
+
+fn pool_accounts() -> RaydiumPoolAccounts {
+    RaydiumPoolAccounts {
+        raydium_amm_program: Pubkey::new_unique(),
+        amm: Pubkey::new_unique(),
+        amm_authority: Pubkey::new_unique(),
+        amm_open_orders: Pubkey::new_unique(),
+        amm_target_orders: Pubkey::new_unique(),
+        pool_coin_token_account: Pubkey::new_unique(),
+        pool_pc_token_account: Pubkey::new_unique(),
+        serum_program: Pubkey::new_unique(),
+        serum_market: Pubkey::new_unique(),
+        serum_bids: Pubkey::new_unique(),
+        serum_asks: Pubkey::new_unique(),
+        serum_event_queue: Pubkey::new_unique(),
+        serum_coin_vault: Pubkey::new_unique(),
+        serum_pc_vault: Pubkey::new_unique(),
+        serum_vault_signer: Pubkey::new_unique(),
+    }
+}
+
+#[test]
+fn initialize_account_bundle_requires_admin() {
+    let mut test = ProgramTest::new();
+    let impostor = funded(&mut test);
+    let (base_mint, quote_mint) = (Pubkey::new_unique(), Pubkey::new_unique());
+    let ix = Instruction {
+        program_id: vectai_protocol::ID,
+        accounts: accounts::InitializeAccountBundle {
+            bundle: account_bundle(&base_mint, &quote_mint),
+            admin: impostor.pubkey(),
+            system_program: system_program::ID,
+        }
+        .to_account_metas(None),
+        data: instruction::InitializeAccountBundle {
+            base_mint,
+            quote_mint,
+            pool: pool_accounts(),
+        }
+        .data(),
+    };
+    assert_eq!(
+        test.process_instruction(ix, &[&impostor]).unwrap_err(),
+        anchor_error(ProtocolError::UnauthorizedAdmin)
+    );
+    assert!(test.account(&account_bundle(&base_mint, &quote_mint)).is_none());
+}
+
+#[test]
+fn set_account_bundle_requires_timelock() {
+    let mut test = ProgramTest::new();
+    let impostor = funded(&mut test);
+    let (base_mint, quote_mint) = (Pubkey::new_unique(), Pubkey::new_unique());
+    let (_, bump) = Pubkey::find_program_address(
+        &[b"account-bundle", base_mint.as_ref(), quote_mint.as_ref()],
+        &vectai_protocol::ID,
+    );
+    let current = pool_accounts();
+    let address = account_bundle(&base_mint, &quote_mint);
+    test.set_anchor_account(
+        address,
+        &AccountBundle {
+            base_mint,
+            quote_mint,
+            pool: current,
+            updated_at: 0,
+            bump,
+        },
+    );
+
+    let ix = Instruction {
+        program_id: vectai_protocol::ID,
+        accounts: accounts::SetAccountBundle {
+            bundle: address,
+            admin: impostor.pubkey(),
+        }
+        .to_account_metas(None),
+        data: instruction::SetAccountBundle { pool: pool_accounts() }.data(),
+    };
+    assert_eq!(
+        test.process_instruction(ix, &[&impostor]).unwrap_err(),
+        anchor_error(ProtocolError::UnauthorizedAdmin)
+    );
+    assert_eq!(test.anchor_account::<AccountBundle>(&address).unwrap().pool, current);
+}
+
+#[test]
+fn pool_accounts_are_ordered_as_execute_trade_takes_them() {
+    let pool = pool_accounts();
+    assert!(pool.is_complete());
+    assert!(!RaydiumPoolAccounts { amm: Pubkey::default(), ..pool }.is_complete());
+
+    let network = ProgramTest::new().create_trading_network(POOL_RESERVE);
+    let authority = Pubkey::new_unique();
+    let execute = network.execute_trade_accounts(&authority, Pubkey::new_unique(), Pubkey::new_unique());
+    let metas: Vec<Pubkey> = execute.to_account_metas(None).iter().map(|meta| meta.pubkey).collect();
+    let bundled = RaydiumPoolAccounts {
+        raydium_amm_program: execute.raydium_amm_program,
+        amm: execute.amm,
+        amm_authority: execute.amm_authority,
+        amm_open_orders: execute.amm_open_orders,
+        amm_target_orders: execute.amm_target_orders,
+        pool_coin_token_account: execute.pool_coin_token_account,
+        pool_pc_token_account: execute.pool_pc_token_account,
+        serum_program: execute.serum_program,
+        serum_market: execute.serum_market,
+        serum_bids: execute.serum_bids,
+        serum_asks: execute.serum_asks,
+        serum_event_queue: execute.serum_event_queue,
+        serum_coin_vault: execute.serum_coin_vault_account,
+        serum_pc_vault: execute.serum_pc_vault_account,
+        serum_vault_signer: execute.serum_vault_signer,
+    };
+    // After the authority, trader config and the user's two token accounts
+    assert_eq!(metas[4..4 + bundled.ordered().len()], bundled.ordered());
+}
+
+#[test]
+fn set_lookup_table_requires_timelock_and_a_lookup_table() {
+    let mut test = ProgramTest::new();
+    let current = lookup_table(&mut test);
+    let (_, bump) = Pubkey::find_program_address(&[b"lookup-table-registry"], &vectai_protocol::ID);
+    test.set_anchor_account(
+        lookup_table_registry(),
+        &LookupTableRegistry {
+            lookup_table: current,
+            updated_at: 0,
+            bump,
+        },
+    );
+    let impostor = funded(&mut test);
+    let set_lookup_table = |lookup_table: Pubkey| Instruction {
+        program_id: vectai_protocol::ID,
+        accounts: accounts::SetLookupTable {
+            registry: lookup_table_registry(),
+            lookup_table,
+            admin: impostor.pubkey(),
+        }
+        .to_account_metas(None),
+        data: instruction::SetLookupTable {}.data(),
+    };
+
+    let replacement = lookup_table(&mut test);
+    assert_eq!(
+        test.process_instruction(set_lookup_table(replacement), &[&impostor]).unwrap_err(),
+        anchor_error(ProtocolError::UnauthorizedAdmin)
+    );
+    // Checked before the signer: a system account is never a lookup table
+    let not_a_table = Pubkey::new_unique();
+    test.airdrop(&not_a_table, LAMPORTS_PER_SOL);
+    assert_eq!(
+        test.process_instruction(set_lookup_table(not_a_table), &[&impostor]).unwrap_err(),
+        anchor_error(ProtocolError::InvalidLookupTable)
+    );
+    let registry: LookupTableRegistry = test.anchor_account(&lookup_table_registry()).unwrap();
+    assert_eq!(registry.lookup_table, current);
+}
+
+#[test]
+fn open_exposure_requires_the_orderbook_reporter() {
+    let mut test = ProgramTest::new();
+    let protocol_stats = test.set_protocol_stats();
+    let impostor = funded(&mut test);
+    let ix = Instruction {
+        program_id: vectai_protocol::ID,
+        accounts: accounts::ReportExposure {
+            protocol_stats,
+            reporter: impostor.pubkey(),
+        }
+        .to_account_metas(None),
+        data: instruction::OpenExposure {
+            mint: Pubkey::new_unique(),
+            amount: SWAP_AMOUNT,
+        }
+        .data(),
+    };
+    assert_eq!(
+        test.process_instruction(ix, &[&impostor]).unwrap_err(),
+        anchor_error(ProtocolError::UnauthorizedReporter)
+    );
+    let stats: ProtocolStats = test.anchor_account(&protocol_stats).unwrap();
+    assert!(stats.exposures.is_empty());
+}
+
+#[test]
+fn set_exposure_cap_requires_timelock() {
+    let mut test = ProgramTest::new();
+    let protocol_stats = test.set_protocol_stats();
+    let impostor = funded(&mut test);
+    let ix = Instruction {
+        program_id: vectai_protocol::ID,
+        accounts: accounts::SetExposureCap {
+            protocol_stats,
+            admin: impostor.pubkey(),
+        }
+        .to_account_metas(None),
+        data: instruction::SetExposureCap {
+            mint: Pubkey::new_unique(),
+            cap: SWAP_AMOUNT,
+        }
+        .data(),
+    };
+    assert_eq!(
+        test.process_instruction(ix, &[&impostor]).unwrap_err(),
+        anchor_error(ProtocolError::UnauthorizedAdmin)
+    );
+    let stats: ProtocolStats = test.anchor_account(&protocol_stats).unwrap();
+    assert!(stats.exposures.is_empty());
+}
+
+#[test]
+fn capped_mints_keep_their_exposure_entry() {
+    let mut stats = ProtocolStats {
+        executions: 0,
+        swaps: 0,
+        mints: 0,
+        volumes: Vec::new(),
+        bump: 255,
+        version: ProtocolStats::VERSION,
+        exposures: Vec::new(),
+    };
+    let capped = Pubkey::new_unique();
+    stats.set_exposure_cap(capped, 1_000).unwrap();
+    stats.open_exposure(capped, 600).unwrap();
+    assert_eq!(
+        stats.open_exposure(capped, 401).unwrap_err(),
+        ProtocolError::ExposureCapExceeded.into()
+    );
+    stats.open_exposure(capped, 400).unwrap();
+
+    // Uncapped mints fill the other entries; the one with the least open
+    // exposure makes room for a new cap
+    let mints: Vec<Pubkey> = (1..MAX_TRACKED_MINTS).map(|_| Pubkey::new_unique()).collect();
+    for (i, mint) in mints.iter().enumerate() {
+        stats.open_exposure(*mint, 10 + i as u64).unwrap();
+    }
+    let untracked = Pubkey::new_unique();
+    stats.open_exposure(untracked, u64::MAX).unwrap();
+    assert!(stats.exposure(&untracked).is_none());
+
+    let newly_capped = Pubkey::new_unique();
+    stats.set_exposure_cap(newly_capped, 50).unwrap();
+    assert_eq!(stats.exposures.len(), MAX_TRACKED_MINTS);
+    assert!(stats.exposure(&mints[0]).is_none());
+    assert_eq!(
+        stats.exposure(&newly_capped),
+        Some(&MintExposure {
+            mint: newly_capped,
+            open: 0,
+            cap: 50,
+        })
+    );
+
+    // Closing saturates, and frees entries that hold neither exposure nor a cap
+    stats.close_exposure(mints[1], u64::MAX);
+    assert!(stats.exposure(&mints[1]).is_none());
+    stats.close_exposure(capped, 1_000);
+    assert_eq!(stats.exposure(&capped).unwrap().open, 0);
+    stats.set_exposure_cap(capped, 0).unwrap();
+    assert!(stats.exposure(&capped).is_none());
+}
+
+#[test]
+fn initialize_role_registry_requires_admin() {
+    let mut test = ProgramTest::new();
+    let impostor = funded(&mut test);
+    let ix = Instruction {
+        program_id: vectai_protocol::ID,
+        accounts: accounts::InitializeRoleRegistry {
+            registry: role_registry(),
+            admin: impostor.pubkey(),
+            system_program: system_program::ID,
+        }
+        .to_account_metas(None),
+        data: instruction::InitializeRoleRegistry {}.data(),
+    };
+    assert_eq!(
+        test.process_instruction(ix, &[&impostor]).unwrap_err(),
+        anchor_error(ProtocolError::UnauthorizedAdmin)
+    );
+    assert!(test.account(&role_registry()).is_none());
+}
+
+#[test]
+fn grant_and_revoke_role_require_timelock() {
+    let mut test = ProgramTest::new();
+    let registry = test.set_role_registry(&[]);
+    let impostor = funded(&mut test);
+    let holder = Pubkey::new_unique();
+    let grant = Instruction {
+        program_id: vectai_protocol::ID,
+        accounts: accounts::UpdateRole {
+            registry,
+            admin: impostor.pubkey(),
+        }
+        .to_account_metas(None),
+        data: instruction::GrantRole { role: Role::Pauser, holder }.data(),
+    };
+    assert_eq!(
+        test.process_instruction(grant, &[&impostor]).unwrap_err(),
+        anchor_error(ProtocolError::UnauthorizedAdmin)
+    );
+
+    // A role holder cannot revoke another's role either
+    test.set_role_registry(&[(holder, Role::Pauser), (impostor.pubkey(), Role::Pauser)]);
+    let revoke = Instruction {
+        program_id: vectai_protocol::ID,
+        accounts: accounts::UpdateRole {
+            registry,
+            admin: impostor.pubkey(),
+        }
+        .to_account_metas(None),
+        data: instruction::RevokeRole { role: Role::Pauser, holder }.data(),
+    };
+    assert_eq!(
+        test.process_instruction(revoke, &[&impostor]).unwrap_err(),
+        anchor_error(ProtocolError::UnauthorizedAdmin)
+    );
+    let registry: RoleRegistry = test.anchor_account(&registry).unwrap();
+    assert!(registry.has_role(&holder, Role::Pauser));
+}
+
+#[test]
+fn role_registry_keeps_one_slot_per_holder() {
+    let mut registry = RoleRegistry {
+        holders: [RoleHolder::default(); MAX_ROLE_HOLDERS],
+        updated_at: 0,
+        bump: 255,
+    };
+    let (ops, treasury) = (Pubkey::new_unique(), Pubkey::new_unique());
+    registry.grant(ops, Role::Pauser).unwrap();
+    registry.grant(ops, Role::Minter).unwrap();
+    registry.grant(treasury, Role::FeeManager).unwrap();
+    assert_eq!(registry.holders.iter().filter(|holder| holder.holder != Pubkey::default()).count(), 2);
+    assert!(registry.has_role(&ops, Role::Pauser) && registry.has_role(&ops, Role::Minter));
+    assert!(!registry.has_role(&ops, Role::FeeManager));
+    assert!(registry.has_role(&treasury, Role::FeeManager));
+    assert!(!registry.has_role(&Pubkey::default(), Role::Pauser));
+
+    assert_eq!(registry.grant(ops, Role::Pauser), Err(ProtocolError::RoleAlreadyHeld.into()));
+    assert_eq!(registry.grant(Pubkey::default(), Role::Pauser), Err(ProtocolError::InvalidRoleHolder.into()));
+    assert_eq!(registry.revoke(treasury, Role::Minter), Err(ProtocolError::RoleNotHeld.into()));
+
+    // Revoking a holder's last role frees its slot for another
+    registry.revoke(ops, Role::Pauser).unwrap();
+    assert!(registry.has_role(&ops, Role::Minter));
+    registry.revoke(ops, Role::Minter).unwrap();
+    assert!(registry.holders.iter().all(|holder| holder.holder != ops));
+    for _ in 1..MAX_ROLE_HOLDERS {
+        registry.grant(Pubkey::new_unique(), Role::Pauser).unwrap();
+    }
+    assert_eq!(registry.grant(ops, Role::Pauser), Err(ProtocolError::RoleHoldersFull.into()));
+    // A current holder can still take on another role
+    registry.grant(treasury, Role::Pauser).unwrap();
+}
+
+fn set_protocol_paused(authority: &Keypair, role_registry: Option<Pubkey>, paused: bool) -> Instruction {
+    Instruction {
+        program_id: vectai_protocol::ID,
+        accounts: accounts::SetProtocolPaused {
+            protocol_status: Pubkey::find_program_address(&[b"protocol-status"], &vectai_protocol::ID).0,
+            authority: authority.pubkey(),
+            role_registry,
+        }
+        .to_account_metas(None),
+        data: instruction::SetProtocolPaused { paused }.data(),
+    }
+}
+
+#[test]
+fn pauser_pauses_the_protocol_but_cannot_unpause() {
+    let mut test = ProgramTest::new();
+    let protocol_status = test.set_protocol_status(false, FeeConfig::INITIAL);
+    let (pauser, fee_manager) = (funded(&mut test), funded(&mut test));
+    let registry = test.set_role_registry(&[(pauser.pubkey(), Role::Pauser), (fee_manager.pubkey(), Role::FeeManager)]);
+
+    // Only with the registry, and only as a pauser
+    let unauthorized = anchor_error(ProtocolError::Unauthorized);
+    assert_eq!(test.process_instruction(set_protocol_paused(&pauser, None, true), &[&pauser]).unwrap_err(), unauthorized);
+    assert_eq!(
+        test.process_instruction(set_protocol_paused(&fee_manager, Some(registry), true), &[&fee_manager])
+            .unwrap_err(),
+        unauthorized
+    );
+
+    test.process_instruction(set_protocol_paused(&pauser, Some(registry), true), &[&pauser]).unwrap();
+    let status: ProtocolStatus = test.anchor_account(&protocol_status).unwrap();
+    assert!(status.paused);
+    assert_eq!(
+        test.process_instruction(set_protocol_paused(&pauser, Some(registry), false), &[&pauser]).unwrap_err(),
+        unauthorized
+    );
+}
+
+#[test]
+fn fee_manager_sets_fees_within_the_ceilings() {
+    let mut test = ProgramTest::new();
+    let protocol_status = test.set_protocol_status(false, FeeConfig::INITIAL);
+    let (fee_manager, pauser) = (funded(&mut test), funded(&mut test));
+    let registry = test.set_role_registry(&[(fee_manager.pubkey(), Role::FeeManager), (pauser.pubkey(), Role::Pauser)]);
+    let set_fee_config = |admin: &Keypair, fee_config: FeeConfig| Instruction {
+        program_id: vectai_protocol::ID,
+        accounts: accounts::SetFeeConfig {
+            protocol_status,
+            admin: admin.pubkey(),
+            role_registry: Some(registry),
+        }
+        .to_account_metas(None),
+        data: instruction::SetFeeConfig { fee_config }.data(),
+    };
+    let fees = FeeConfig {
+        trade_fee_bps: 20,
+        ..FeeConfig::INITIAL
+    };
+
+    assert_eq!(
+        test.process_instruction(set_fee_config(&pauser, fees), &[&pauser]).unwrap_err(),
+        anchor_error(ProtocolError::UnauthorizedAdmin)
+    );
+    let over_ceiling = FeeConfig {
+        trade_fee_bps: 101,
+        ..fees
+    };
+    assert_eq!(
+        test.process_instruction(set_fee_config(&fee_manager, over_ceiling), &[&fee_manager]).unwrap_err(),
+        anchor_error(ProtocolError::InvalidFeeConfig)
+    );
+
+    test.process_instruction(set_fee_config(&fee_manager, fees), &[&fee_manager]).unwrap();
+    let status: ProtocolStatus = test.anchor_account(&protocol_status).unwrap();
+    assert_eq!(status.fee_config.trade_fee_bps, 20);
+}
+
+/// An executable program and its programdata, last deployed at `slot`
+fn upgradeable_program(test: &mut ProgramTest, slot: u64, upgrade_authority: Option<Pubkey>) -> (Pubkey, Pubkey) {
+    let program = Pubkey::new_unique();
+    let program_data = Pubkey::find_program_address(&[program.as_ref()], &bpf_loader_upgradeable::ID).0;
+    let mut account = Account::new(LAMPORTS_PER_SOL, 0, &bpf_loader_upgradeable::ID);
+    account.executable = true;
+    test.set_account(program, account);
+    set_upgrade_authority(test, program_data, slot, upgrade_authority);
+    (program, program_data)
+}
+
+fn set_upgrade_authority(test: &mut ProgramTest, program_data: Pubkey, slot: u64, upgrade_authority: Option<Pubkey>) {
+    let state = UpgradeableLoaderState::ProgramData {
+        slot,
+        upgrade_authority_address: upgrade_authority,
+    };
+    let mut account = Account::new(LAMPORTS_PER_SOL, 0, &bpf_loader_upgradeable::ID);
+    account.data = bincode::serialize(&state).unwrap();
+    test.set_account(program_data, account);
+}
+
+fn attest_upgrade_authority(program: Pubkey, program_data: Pubkey, network_config: Pubkey, authority: &Keypair) -> Instruction {
+    Instruction {
+        program_id: vectai_protocol::ID,
+        accounts: accounts::AttestUpgradeAuthority {
+            program,
+            program_data,
+            network_config,
+            authority: authority.pubkey(),
+        }
+        .to_account_metas(None),
+        data: instruction::AttestUpgradeAuthority {}.data(),
+    }
+}
+
+/// Network config with `program` already attested, as the admin would
+/// have left it
+fn network_config_attesting(test: &mut ProgramTest, program: Pubkey) -> Pubkey {
+    let address = test.set_network_config(Pubkey::new_unique(), Pubkey::new_unique());
+    let mut config: NetworkConfig = test.anchor_account(&address).unwrap();
+    config.upgrade_attestations.push(UpgradeAttestation {
+        program,
+        ..UpgradeAttestation::default()
+    });
+    let mut data = Vec::new();
+    config.try_serialize(&mut data).unwrap();
+    data.resize(NetworkConfig::LEN, 0);
+    let mut account = test.account(&address).unwrap();
+    account.data = data;
+    test.set_account(address, account);
+    address
+}
+
+#[test]
+fn attest_upgrade_authority_adds_programs_for_the_admin_only() {
+    let mut test = ProgramTest::new();
+    let cranker = funded(&mut test);
+    let network_config = test.set_network_config(Pubkey::new_unique(), Pubkey::new_unique());
+    let (program, program_data) = upgradeable_program(&mut test, 10, None);
+
+    assert_eq!(
+        test.process_instruction(attest_upgrade_authority(program, program_data, network_config, &cranker), &[&cranker])
+            .unwrap_err(),
+        anchor_error(ProtocolError::UnauthorizedAdmin)
+    );
+    let config: NetworkConfig = test.anchor_account(&network_config).unwrap();
+    assert!(config.upgrade_attestation(&program).is_none());
+}
+
+#[test]
+fn attestation_is_revoked_when_the_upgrade_authority_moves_back() {
+    let mut test = ProgramTest::new();
+    let cranker = funded(&mut test);
+    let timelock = vectai_timelock::timelock_authority();
+    let (program, program_data) = upgradeable_program(&mut test, 10, Some(timelock));
+    let network_config = network_config_attesting(&mut test, program);
+
+    test.process_instruction(attest_upgrade_authority(program, program_data, network_config, &cranker), &[&cranker])
+        .unwrap();
+    let config: NetworkConfig = test.anchor_account(&network_config).unwrap();
+    let attestation = *config.upgrade_attestation(&program).unwrap();
+    assert!(attestation.is_decentralized);
+    assert_eq!(attestation.upgrade_authority, timelock);
+    let live: ProgramData = test.anchor_account(&program_data).unwrap();
+    assert!(attestation.holds_for(&live));
+
+    // A SetAuthority back to a single key leaves the programdata slot alone
+    let deployer = Pubkey::new_unique();
+    set_upgrade_authority(&mut test, program_data, 10, Some(deployer));
+    let live: ProgramData = test.anchor_account(&program_data).unwrap();
+    assert!(!attestation.holds_for(&live));
+
+    // Recranking records the revocation instead of reverting
+    test.process_instruction(attest_upgrade_authority(program, program_data, network_config, &cranker), &[&cranker])
+        .unwrap();
+    let config: NetworkConfig = test.anchor_account(&network_config).unwrap();
+    let attestation = config.upgrade_attestation(&program).unwrap();
+    assert!(!attestation.is_decentralized);
+    assert_eq!(attestation.upgrade_authority, deployer);
+    assert_eq!(config.upgrade_attestations.len(), 1);
+    let event = test.events::<UpgradeAuthorityAttested>().pop().unwrap();
+    assert!(!event.is_decentralized);
+}
+
+#[test]
+fn immutable_programs_attest_as_decentralized() {
+    let mut test = ProgramTest::new();
+    let cranker = funded(&mut test);
+    let (program, program_data) = upgradeable_program(&mut test, 10, None);
+    let network_config = network_config_attesting(&mut test, program);
+
+    test.process_instruction(attest_upgrade_authority(program, program_data, network_config, &cranker), &[&cranker])
+        .unwrap();
+    let config: NetworkConfig = test.anchor_account(&network_config).unwrap();
+    let attestation = config.upgrade_attestation(&program).unwrap();
+    assert!(attestation.is_immutable && attestation.is_decentralized);
+    let live: ProgramData = test.anchor_account(&program_data).unwrap();
+    assert!(attestation.holds_for(&live));
+}
@@ -0,0 +1,540 @@
+//! `raydium_swapper`'s commit-reveal swaps and alternative venues.
+
+use anchor_lang::{solana_program::system_program, InstructionData, ToAccountMetas};
+use raydium_swapper::{accounts, instruction, swap_commitment, swap_commitment_hash, SwapCommitment, SwapError};
+use solana_sdk::{
+    instruction::{AccountMeta, Instruction},
+    native_token::LAMPORTS_PER_SOL,
+    program_error::ProgramError,
+    pubkey::Pubkey,
+    signature::{Keypair, Signer},
+};
+use vectai_program_test::{
+    anchor_error, mock_dlmm, mock_dlmm::DlmmPair, mock_lifinity, mock_lifinity::LifinityPool, mock_phoenix,
+    mock_phoenix::PhoenixBook, mock_stable, mock_stable::StablePool, trader::TradingNetwork, ProgramTest,
+};
+use vectai_math::{expected_amount_out, minimum_amount_out, PoolPricing};
+use vectai_protocol::{best_venue, venue_registry, PairVenues, Venue, VenueRegistry};
+
+const SWAP_AMOUNT: u64 = 1_000_000;
+const POOL_RESERVE: u64 = 1_000_000_000_000;
+const SALT: [u8; 32] = [7; 32];
+const BASE_LOT: u64 = 1_000;
+const QUOTE_LOT: u64 = 300_000;
+const LIFINITY_CONCENTRATION: u64 = 10;
+const LIFINITY_FEE_BPS: u64 = 30;
+const STABLE_AMPLIFICATION: u64 = 100;
+const STABLE_FEE_BPS: u64 = 4;
+
+fn ix(accounts: impl ToAccountMetas, data: impl InstructionData) -> Instruction {
+    Instruction {
+        program_id: raydium_swapper::ID,
+        accounts: accounts.to_account_metas(None),
+        data: data.data(),
+    }
+}
+
+/// A user holding USDC and an empty WSOL account on the trading network
+struct Fixture {
+    test: ProgramTest,
+    network: TradingNetwork,
+    user: Keypair,
+    source: Pubkey,
+    destination: Pubkey,
+}
+
+impl Fixture {
+    fn new() -> Self {
+        let mut test = ProgramTest::new();
+        let network = test.create_trading_network(POOL_RESERVE);
+        let user = Keypair::new();
+        test.airdrop(&user.pubkey(), LAMPORTS_PER_SOL);
+        let (source, destination) = (Pubkey::new_unique(), Pubkey::new_unique());
+        test.create_token_account(source, &network.usdc, &user.pubkey(), SWAP_AMOUNT);
+        test.create_token_account(destination, &network.wsol, &user.pubkey(), 0);
+        Fixture {
+            test,
+            network,
+            user,
+            source,
+            destination,
+        }
+    }
+
+    fn commit(&mut self, hash: [u8; 32]) -> Result<(), ProgramError> {
+        let ix = ix(
+            accounts::CommitSwap {
+                commitment: swap_commitment(&self.user.pubkey()),
+                user_authority: self.user.pubkey(),
+                system_program: system_program::ID,
+            },
+            instruction::CommitSwap { hash },
+        );
+        let user = self.user.insecure_clone();
+        self.test.process_instruction(ix, &[&user])
+    }
+
+    fn reveal(&mut self, amount_in: u64, min_amount_out: u64) -> Result<(), ProgramError> {
+        let ix = ix(
+            accounts::RevealAndExecute {
+                swap: self.network.swap_accounts(&self.user.pubkey(), self.source, self.destination),
+                commitment: swap_commitment(&self.user.pubkey()),
+            },
+            instruction::RevealAndExecute {
+                amount_in,
+                min_amount_out,
+                salt: SALT,
+            },
+        );
+        let user = self.user.insecure_clone();
+        self.test.process_instruction(ix, &[&user])
+    }
+
+    fn commitment(&self) -> SwapCommitment {
+        self.test.anchor_account(&swap_commitment(&self.user.pubkey())).unwrap()
+    }
+
+    fn swap_dlmm(&mut self, pair: &DlmmPair, min_amount_out: u64) -> Result<(), ProgramError> {
+        let swap = self.network.swap_accounts(&self.user.pubkey(), self.source, self.destination);
+        let mut ix = ix(
+            accounts::MeteoraSwapAccounts {
+                user_authority: swap.user_authority,
+                user_source_token: swap.user_source_token,
+                user_destination_token: swap.user_destination_token,
+                dlmm_program: mock_dlmm::ID,
+                lb_pair: pair.lb_pair,
+                reserve_x: pair.reserve_x,
+                reserve_y: pair.reserve_y,
+                token_x_mint: pair.token_x_mint,
+                token_y_mint: pair.token_y_mint,
+                oracle: pair.oracle,
+                event_authority: pair.event_authority,
+                token_program: swap.token_program,
+                protocol_status: swap.protocol_status,
+                network_config: swap.network_config,
+                protocol_stats: swap.protocol_stats,
+                stats_reporter: swap.stats_reporter,
+                protocol_program: swap.protocol_program,
+                fee_account: None,
+            },
+            instruction::SwapMeteoraDlmm {
+                amount_in: SWAP_AMOUNT,
+                min_amount_out,
+            },
+        );
+        ix.accounts.push(AccountMeta::new(pair.bin_array, false));
+        let user = self.user.insecure_clone();
+        self.test.process_instruction(ix, &[&user])
+    }
+
+    /// Write the WSOL/USDC venue registry with `phoenix_market` listed
+    fn list_phoenix_market(&mut self, phoenix_market: Pubkey) {
+        let (wsol, usdc) = (self.network.wsol, self.network.usdc);
+        let (_, bump) =
+            Pubkey::find_program_address(&[b"venue-registry", wsol.as_ref(), usdc.as_ref()], &vectai_protocol::ID);
+        self.test.set_anchor_account(
+            venue_registry(&wsol, &usdc),
+            &VenueRegistry {
+                base_mint: wsol,
+                quote_mint: usdc,
+                venues: PairVenues {
+                    raydium_amm: self.network.pool.amm,
+                    phoenix_market,
+                    ..PairVenues::default()
+                },
+                updated_at: 0,
+                bump,
+            },
+        );
+    }
+
+    fn swap_phoenix(&mut self, book: &PhoenixBook, min_amount_out: u64) -> Result<(), ProgramError> {
+        let swap = self.network.swap_accounts(&self.user.pubkey(), self.source, self.destination);
+        let ix = ix(
+            accounts::PhoenixSwapAccounts {
+                user_authority: swap.user_authority,
+                user_source_token: swap.user_source_token,
+                user_destination_token: swap.user_destination_token,
+                phoenix_program: mock_phoenix::ID,
+                log_authority: book.log_authority,
+                market: book.market,
+                base_vault: book.header.base_vault,
+                quote_vault: book.header.quote_vault,
+                token_program: swap.token_program,
+                venue_registry: venue_registry(&self.network.wsol, &self.network.usdc),
+                protocol_status: swap.protocol_status,
+                network_config: swap.network_config,
+                protocol_stats: swap.protocol_stats,
+                stats_reporter: swap.stats_reporter,
+                protocol_program: swap.protocol_program,
+                fee_account: None,
+            },
+            instruction::SwapPhoenixIoc {
+                amount_in: SWAP_AMOUNT,
+                min_amount_out,
+            },
+        );
+        let user = self.user.insecure_clone();
+        self.test.process_instruction(ix, &[&user])
+    }
+
+    fn swap_lifinity(&mut self, pool: &LifinityPool, min_amount_out: u64) -> Result<(), ProgramError> {
+        let swap = self.network.swap_accounts(&self.user.pubkey(), self.source, self.destination);
+        let (swap_source, swap_destination) = pool.vaults(&self.network.usdc);
+        let ix = ix(
+            accounts::LifinitySwapAccounts {
+                user_authority: swap.user_authority,
+                user_source_token: swap.user_source_token,
+                user_destination_token: swap.user_destination_token,
+                lifinity_program: mock_lifinity::ID,
+                amm: pool.amm,
+                amm_authority: pool.amm_authority,
+                swap_source,
+                swap_destination,
+                pool_mint: pool.pool_mint,
+                pool_fee_account: pool.pool_fee_account,
+                oracle_main_account: pool.oracle_main_account,
+                oracle_sub_account: pool.oracle_sub_account,
+                oracle_pc_account: pool.oracle_pc_account,
+                token_program: swap.token_program,
+                protocol_status: swap.protocol_status,
+                network_config: swap.network_config,
+                protocol_stats: swap.protocol_stats,
+                stats_reporter: swap.stats_reporter,
+                protocol_program: swap.protocol_program,
+                fee_account: None,
+            },
+            instruction::SwapLifinity {
+                amount_in: SWAP_AMOUNT,
+                min_amount_out,
+            },
+        );
+        let user = self.user.insecure_clone();
+        self.test.process_instruction(ix, &[&user])
+    }
+
+    /// A USDT mint, a USDT/USDC stable pool listed in the pair's venue
+    /// registry, and an empty USDT account of the user's
+    fn create_stable_pool(&mut self) -> (StablePool, Pubkey) {
+        let (usdt, usdc) = (Pubkey::new_unique(), self.network.usdc);
+        self.test.create_mint(usdt, &Pubkey::new_unique(), vectai_math::USDC_DECIMALS);
+        let pool =
+            self.test
+                .create_stable_pool(&usdt, &usdc, POOL_RESERVE, POOL_RESERVE, STABLE_AMPLIFICATION, STABLE_FEE_BPS);
+        let (_, bump) =
+            Pubkey::find_program_address(&[b"venue-registry", usdt.as_ref(), usdc.as_ref()], &vectai_protocol::ID);
+        self.test.set_anchor_account(
+            venue_registry(&usdt, &usdc),
+            &VenueRegistry {
+                base_mint: usdt,
+                quote_mint: usdc,
+                venues: PairVenues {
+                    stable_swap_pool: pool.pool,
+                    ..PairVenues::default()
+                },
+                updated_at: 0,
+                bump,
+            },
+        );
+        let usdt_account = Pubkey::new_unique();
+        self.test.create_token_account(usdt_account, &usdt, &self.user.pubkey(), 0);
+        (pool, usdt_account)
+    }
+
+    /// Swap the user's USDC into `destination` on `pool`
+    fn swap_stable(&mut self, pool: &StablePool, destination: Pubkey, min_amount_out: u64) -> Result<(), ProgramError> {
+        let swap = self.network.swap_accounts(&self.user.pubkey(), self.source, destination);
+        let (pool_source, pool_destination, admin_fee_destination) = pool.accounts(&self.network.usdc);
+        let ix = ix(
+            accounts::StableSwapAccounts {
+                user_authority: swap.user_authority,
+                user_source_token: swap.user_source_token,
+                user_destination_token: swap.user_destination_token,
+                stable_swap_program: mock_stable::ID,
+                pool: pool.pool,
+                pool_authority: pool.pool_authority,
+                pool_source,
+                pool_destination,
+                admin_fee_destination,
+                token_program: swap.token_program,
+                venue_registry: venue_registry(&pool.mint_a, &pool.mint_b),
+                protocol_status: swap.protocol_status,
+                network_config: swap.network_config,
+                protocol_stats: swap.protocol_stats,
+                stats_reporter: swap.stats_reporter,
+                protocol_program: swap.protocol_program,
+                fee_account: None,
+            },
+            instruction::SwapStable {
+                amount_in: SWAP_AMOUNT,
+                min_amount_out,
+            },
+        );
+        let user = self.user.insecure_clone();
+        self.test.process_instruction(ix, &[&user])
+    }
+
+    fn create_phoenix_market(&mut self) -> PhoenixBook {
+        let (wsol, usdc) = (self.network.wsol, self.network.usdc);
+        self.test.create_phoenix_market(&wsol, &usdc, BASE_LOT, QUOTE_LOT, POOL_RESERVE, POOL_RESERVE)
+    }
+}
+
+#[test]
+fn reveal_executes_committed_swap_in_a_later_slot() {
+    let mut fixture = Fixture::new();
+    fixture.commit(swap_commitment_hash(SWAP_AMOUNT, 1, &SALT)).unwrap();
+    let commitment = fixture.commitment();
+    assert_eq!(commitment.user, fixture.user.pubkey());
+    assert_eq!(commitment.slot, fixture.test.clock().slot);
+
+    let err = fixture.reveal(SWAP_AMOUNT, 1).unwrap_err();
+    assert_eq!(err, anchor_error(SwapError::RevealTooEarly));
+
+    fixture.test.warp_forward(1);
+    let err = fixture.reveal(SWAP_AMOUNT, 2).unwrap_err();
+    assert_eq!(err, anchor_error(SwapError::CommitmentMismatch));
+    fixture.reveal(SWAP_AMOUNT, 1).unwrap();
+    assert_eq!(fixture.test.token_balance(&fixture.source), Some(0));
+    assert!(fixture.test.token_balance(&fixture.destination).unwrap() > 0);
+    assert!(!fixture.commitment().is_pending());
+
+    // Each commitment is good for one swap
+    fixture.test.create_token_account(fixture.source, &fixture.network.usdc, &fixture.user.pubkey(), SWAP_AMOUNT);
+    let err = fixture.reveal(SWAP_AMOUNT, 1).unwrap_err();
+    assert_eq!(err, anchor_error(SwapError::NoPendingCommitment));
+}
+
+#[test]
+fn commit_swap_replaces_pending_commitment() {
+    let mut fixture = Fixture::new();
+    let err = fixture.commit([0; 32]).unwrap_err();
+    assert_eq!(err, anchor_error(SwapError::InvalidCommitment));
+
+    fixture.commit(swap_commitment_hash(SWAP_AMOUNT, 1, &SALT)).unwrap();
+    fixture.commit(swap_commitment_hash(SWAP_AMOUNT / 2, 1, &SALT)).unwrap();
+    fixture.test.warp_forward(1);
+    let err = fixture.reveal(SWAP_AMOUNT, 1).unwrap_err();
+    assert_eq!(err, anchor_error(SwapError::CommitmentMismatch));
+    fixture.reveal(SWAP_AMOUNT / 2, 1).unwrap();
+    assert_eq!(fixture.test.token_balance(&fixture.source), Some(SWAP_AMOUNT / 2));
+}
+
+#[test]
+fn swap_meteora_dlmm_settles_through_the_pair() {
+    let mut fixture = Fixture::new();
+    let (wsol, usdc) = (fixture.network.wsol, fixture.network.usdc);
+    let pair = fixture.test.create_dlmm_pair(&wsol, &usdc, POOL_RESERVE, POOL_RESERVE);
+    let expected = vectai_program_test::mock_amm::quote(SWAP_AMOUNT, POOL_RESERVE, POOL_RESERVE);
+
+    let err = fixture.swap_dlmm(&pair, expected + 1).unwrap_err();
+    assert_eq!(err, ProgramError::Custom(mock_dlmm::EXCEEDED_AMOUNT_SLIPPAGE_TOLERANCE));
+    fixture.swap_dlmm(&pair, expected).unwrap();
+    assert_eq!(fixture.test.token_balance(&fixture.source), Some(0));
+    assert_eq!(fixture.test.token_balance(&fixture.destination), Some(expected));
+    assert_eq!(fixture.test.token_balance(&pair.reserve_y), Some(POOL_RESERVE + SWAP_AMOUNT));
+}
+
+#[test]
+fn swap_meteora_dlmm_requires_a_dlmm_pair() {
+    let mut fixture = Fixture::new();
+    let (wsol, usdc) = (fixture.network.wsol, fixture.network.usdc);
+    let mut pair = fixture.test.create_dlmm_pair(&wsol, &usdc, POOL_RESERVE, POOL_RESERVE);
+    pair.lb_pair = fixture.network.pool.amm;
+
+    let err = fixture.swap_dlmm(&pair, 1).unwrap_err();
+    assert_eq!(err, anchor_error(SwapError::InvalidPool));
+    assert_eq!(fixture.test.token_balance(&fixture.source), Some(SWAP_AMOUNT));
+}
+
+#[test]
+fn swap_phoenix_ioc_fills_whole_lots_on_the_listed_market() {
+    let mut fixture = Fixture::new();
+    let book = fixture.create_phoenix_market();
+    fixture.list_phoenix_market(book.market);
+    // Three quote lots fit in the input; the base received rounds down to
+    // whole lots
+    let spent = SWAP_AMOUNT / QUOTE_LOT * QUOTE_LOT;
+    let expected = vectai_program_test::mock_amm::quote(spent, POOL_RESERVE, POOL_RESERVE) / BASE_LOT * BASE_LOT;
+
+    let err = fixture.swap_phoenix(&book, expected + 1).unwrap_err();
+    assert_eq!(err, ProgramError::Custom(mock_phoenix::MIN_FILL_NOT_MET));
+    fixture.swap_phoenix(&book, expected).unwrap();
+    assert_eq!(fixture.test.token_balance(&fixture.source), Some(SWAP_AMOUNT - spent));
+    assert_eq!(fixture.test.token_balance(&fixture.destination), Some(expected));
+    assert_eq!(fixture.test.token_balance(&book.header.quote_vault), Some(POOL_RESERVE + spent));
+}
+
+#[test]
+fn swap_phoenix_ioc_requires_the_registered_market() {
+    let mut fixture = Fixture::new();
+    let book = fixture.create_phoenix_market();
+    let listed = fixture.create_phoenix_market();
+    fixture.list_phoenix_market(listed.market);
+
+    let err = fixture.swap_phoenix(&book, 1).unwrap_err();
+    assert_eq!(err, anchor_error(SwapError::InvalidPool));
+    assert_eq!(fixture.test.token_balance(&fixture.source), Some(SWAP_AMOUNT));
+}
+
+#[test]
+fn best_venue_picks_the_highest_quote() {
+    assert_eq!(best_venue(&[]), None);
+    assert_eq!(
+        best_venue(&[(Venue::RaydiumAmm, 90), (Venue::Phoenix, 95), (Venue::MeteoraDlmm, 93)]),
+        Some(Venue::Phoenix)
+    );
+    // Ties keep the earlier venue
+    assert_eq!(
+        best_venue(&[(Venue::RaydiumAmm, 95), (Venue::Phoenix, 95)]),
+        Some(Venue::RaydiumAmm)
+    );
+    let venues = PairVenues {
+        phoenix_market: Pubkey::new_unique(),
+        ..PairVenues::default()
+    };
+    assert_eq!(venues.market(Venue::Phoenix), Some(venues.phoenix_market));
+    assert_eq!(venues.market(Venue::MeteoraDlmm), None);
+}
+
+#[test]
+fn swap_lifinity_fills_at_the_oracle_anchored_quote() {
+    let mut fixture = Fixture::new();
+    let (wsol, usdc) = (fixture.network.wsol, fixture.network.usdc);
+    // Reserves at 1000 USDC per SOL, oracle at 150
+    let (price, expo) = (15_000_000_000, -8);
+    let pool = fixture.test.create_lifinity_pool(
+        &wsol,
+        &usdc,
+        POOL_RESERVE,
+        POOL_RESERVE,
+        LIFINITY_CONCENTRATION,
+        LIFINITY_FEE_BPS,
+        price,
+        expo,
+    );
+    let pricing = PoolPricing::OracleAnchored {
+        price,
+        expo,
+        reserve_out: POOL_RESERVE,
+        concentration: LIFINITY_CONCENTRATION,
+    };
+    let expected = expected_amount_out(SWAP_AMOUNT, true, pricing, LIFINITY_FEE_BPS).unwrap();
+    // 1 USDC buys about 1/150 SOL, far more than the reserve ratio says
+    let reserve_ratio = PoolPricing::ConstantProduct {
+        reserve_in: POOL_RESERVE,
+        reserve_out: POOL_RESERVE,
+    };
+    assert!(expected > expected_amount_out(SWAP_AMOUNT, true, reserve_ratio, LIFINITY_FEE_BPS).unwrap() * 6);
+    assert_eq!(expected / 1_000_000, 6);
+
+    let err = fixture.swap_lifinity(&pool, expected + 1).unwrap_err();
+    assert_eq!(err, ProgramError::Custom(mock_lifinity::EXCEEDED_SLIPPAGE));
+    fixture.swap_lifinity(&pool, minimum_amount_out(expected, 50).unwrap()).unwrap();
+    assert_eq!(fixture.test.token_balance(&fixture.source), Some(0));
+    assert_eq!(fixture.test.token_balance(&fixture.destination), Some(expected));
+}
+
+#[test]
+fn swap_lifinity_quote_follows_the_oracle() {
+    let mut fixture = Fixture::new();
+    let (wsol, usdc) = (fixture.network.wsol, fixture.network.usdc);
+    let pool = fixture.test.create_lifinity_pool(
+        &wsol,
+        &usdc,
+        POOL_RESERVE,
+        POOL_RESERVE,
+        LIFINITY_CONCENTRATION,
+        LIFINITY_FEE_BPS,
+        15_000_000_000,
+        -8,
+    );
+    let quote_at = |price: i64| {
+        let pricing = PoolPricing::OracleAnchored {
+            price,
+            expo: -8,
+            reserve_out: POOL_RESERVE,
+            concentration: LIFINITY_CONCENTRATION,
+        };
+        expected_amount_out(SWAP_AMOUNT, true, pricing, LIFINITY_FEE_BPS).unwrap()
+    };
+    let stale = quote_at(15_000_000_000);
+
+    // SOL rallies to 200: the same USDC buys less, and a floor quoted at
+    // the old price no longer fills
+    fixture.test.set_lifinity_price(&pool, 20_000_000_000, -8);
+    let err = fixture.swap_lifinity(&pool, stale).unwrap_err();
+    assert_eq!(err, ProgramError::Custom(mock_lifinity::EXCEEDED_SLIPPAGE));
+    fixture.swap_lifinity(&pool, quote_at(20_000_000_000)).unwrap();
+    assert_eq!(fixture.test.token_balance(&fixture.destination), Some(quote_at(20_000_000_000)));
+}
+
+#[test]
+fn swap_lifinity_requires_a_lifinity_pool() {
+    let mut fixture = Fixture::new();
+    let (wsol, usdc) = (fixture.network.wsol, fixture.network.usdc);
+    let mut pool = fixture.test.create_lifinity_pool(
+        &wsol,
+        &usdc,
+        POOL_RESERVE,
+        POOL_RESERVE,
+        LIFINITY_CONCENTRATION,
+        LIFINITY_FEE_BPS,
+        15_000_000_000,
+        -8,
+    );
+    pool.amm = fixture.network.pool.amm;
+
+    let err = fixture.swap_lifinity(&pool, 1).unwrap_err();
+    assert_eq!(err, anchor_error(SwapError::InvalidPool));
+    assert_eq!(fixture.test.token_balance(&fixture.source), Some(SWAP_AMOUNT));
+}
+
+#[test]
+fn swap_stable_fills_at_the_invariant_quote() {
+    let mut fixture = Fixture::new();
+    let (pool, usdt_account) = fixture.create_stable_pool();
+    let pricing = PoolPricing::StableSwap {
+        amplification: STABLE_AMPLIFICATION,
+        reserve_in: POOL_RESERVE,
+        reserve_out: POOL_RESERVE,
+    };
+    let expected = expected_amount_out(SWAP_AMOUNT, true, pricing, STABLE_FEE_BPS).unwrap();
+    // Near par: only the fee and rounding come off
+    assert!(expected >= SWAP_AMOUNT - vectai_common::bps_of(SWAP_AMOUNT, STABLE_FEE_BPS) - 1);
+
+    let err = fixture.swap_stable(&pool, usdt_account, expected + 1).unwrap_err();
+    assert_eq!(err, ProgramError::Custom(mock_stable::EXCEEDED_SLIPPAGE));
+    fixture.swap_stable(&pool, usdt_account, expected).unwrap();
+    assert_eq!(fixture.test.token_balance(&fixture.source), Some(0));
+    assert_eq!(fixture.test.token_balance(&usdt_account), Some(expected));
+}
+
+#[test]
+fn swap_stable_requires_the_registered_pool() {
+    let mut fixture = Fixture::new();
+    let (pool, usdt_account) = fixture.create_stable_pool();
+    let unlisted = fixture.test.create_stable_pool(
+        &pool.mint_a,
+        &pool.mint_b,
+        POOL_RESERVE,
+        POOL_RESERVE,
+        STABLE_AMPLIFICATION,
+        STABLE_FEE_BPS,
+    );
+
+    let err = fixture.swap_stable(&unlisted, usdt_account, 1).unwrap_err();
+    assert_eq!(err, anchor_error(SwapError::InvalidPool));
+    assert_eq!(fixture.test.token_balance(&fixture.source), Some(SWAP_AMOUNT));
+}
+
+#[test]
+fn swap_stable_only_swaps_the_registered_pair() {
+    let mut fixture = Fixture::new();
+    let (pool, _) = fixture.create_stable_pool();
+    let destination = fixture.destination;
+
+    // WSOL is not one of the pool's mints
+    let err = fixture.swap_stable(&pool, destination, 1).unwrap_err();
+    assert_eq!(err, anchor_error(SwapError::InvalidTokenPair));
+}
@@ -0,0 +1,341 @@
+//! `vectai_timelock` batches: a governance proposal's changes to the fee
+//! config, venue registries, protocol price policies and roles, applied
+//! together by one `execute_batch`.
+//!
+//! Queueing takes the admin or governance, neither of which can sign here,
+//! so batches are written as `queue_batch` leaves them.
+
+use anchor_lang::{solana_program::system_program, InstructionData, ToAccountMetas};
+use solana_sdk::{
+    instruction::{AccountMeta, Instruction},
+    native_token::LAMPORTS_PER_SOL,
+    program_error::ProgramError,
+    pubkey::Pubkey,
+    signature::{Keypair, Signer},
+};
+use vectai_oracle::{price_policy, PricePolicy, PricePolicyUpdated, StalePricePolicy};
+use vectai_program_test::{anchor_error, ProgramTest};
+use vectai_protocol::{
+    venue_registry, FeeConfig, FeeConfigUpdated, PairVenues, PairVenuesUpdated, ProtocolError, ProtocolStatus, Role,
+    RoleGranted, RoleRegistry, RoleRevoked, VenueRegistry,
+};
+use vectai_timelock::{
+    accounts, batch_operation, instruction, timelock_authority, BatchExecuted, BatchOperation, OperationAccount,
+    OperationInstruction, Timelock, TimelockError, MIN_DELAY_FLOOR,
+};
+
+const ETA_DELAY: i64 = MIN_DELAY_FLOOR;
+
+/// A timelock, the protocol status, one pair's venue registry and the
+/// protocol's price policy for one feed
+struct Fixture {
+    test: ProgramTest,
+    proposer: Keypair,
+    protocol_status: Pubkey,
+    base_mint: Pubkey,
+    quote_mint: Pubkey,
+    price_feed: Pubkey,
+}
+
+impl Fixture {
+    fn new() -> Self {
+        let mut test = ProgramTest::new();
+        let protocol_status = test.set_protocol_status(false, FeeConfig::INITIAL);
+        let proposer = Keypair::new();
+        test.airdrop(&proposer.pubkey(), LAMPORTS_PER_SOL);
+
+        let (timelock, bump) = Pubkey::find_program_address(&[b"timelock"], &vectai_timelock::ID);
+        test.set_anchor_account(
+            timelock,
+            &Timelock {
+                min_delay: MIN_DELAY_FLOOR,
+                operation_count: 0,
+                bump,
+            },
+        );
+
+        let (base_mint, quote_mint) = (Pubkey::new_unique(), Pubkey::new_unique());
+        let (registry, bump) = Pubkey::find_program_address(
+            &[b"venue-registry", base_mint.as_ref(), quote_mint.as_ref()],
+            &vectai_protocol::ID,
+        );
+        test.set_anchor_account(
+            registry,
+            &VenueRegistry {
+                base_mint,
+                quote_mint,
+                venues: PairVenues::default(),
+                updated_at: 0,
+                bump,
+            },
+        );
+
+        let price_feed = Pubkey::new_unique();
+        let (policy, bump) = Pubkey::find_program_address(
+            &[b"price-policy", price_feed.as_ref(), timelock_authority().as_ref()],
+            &vectai_oracle::ID,
+        );
+        test.set_anchor_account(
+            policy,
+            &PricePolicy {
+                authority: timelock_authority(),
+                price_feed,
+                max_age_secs: 60,
+                stale_price_policy: StalePricePolicy::Reject,
+                last_good_price: 0,
+                last_good_conf: 0,
+                last_good_expo: 0,
+                last_good_publish_time: 0,
+                bump,
+            },
+        );
+
+        Fixture {
+            test,
+            proposer,
+            protocol_status,
+            base_mint,
+            quote_mint,
+            price_feed,
+        }
+    }
+
+    /// Batch 0 of `instructions`, due `ETA_DELAY` from now
+    fn queue(&mut self, instructions: Vec<OperationInstruction>) -> Pubkey {
+        let (address, bump) = Pubkey::find_program_address(&[b"batch", 0u64.to_le_bytes().as_ref()], &vectai_timelock::ID);
+        let now = self.test.clock().unix_timestamp;
+        self.test.set_anchor_account(
+            address,
+            &BatchOperation {
+                id: 0,
+                proposer: self.proposer.pubkey(),
+                instructions,
+                queued_at: now,
+                eta: now + ETA_DELAY,
+                bump,
+            },
+        );
+        address
+    }
+
+    fn execute(&mut self, instructions: &[OperationInstruction]) -> Result<(), ProgramError> {
+        let mut metas = accounts::ExecuteBatch {
+            timelock: Pubkey::find_program_address(&[b"timelock"], &vectai_timelock::ID).0,
+            batch: batch_operation(0),
+            proposer: self.proposer.pubkey(),
+        }
+        .to_account_metas(None);
+        for operation in instructions {
+            metas.push(AccountMeta::new_readonly(operation.program_id, false));
+            for account in &operation.accounts {
+                // The timelock PDA is appended by the program itself
+                if account.pubkey != timelock_authority() {
+                    metas.push(AccountMeta {
+                        pubkey: account.pubkey,
+                        is_signer: false,
+                        is_writable: account.is_writable,
+                    });
+                }
+            }
+        }
+        let ix = Instruction {
+            program_id: vectai_timelock::ID,
+            accounts: metas,
+            data: instruction::ExecuteBatch {}.data(),
+        };
+        self.test.process_instruction(ix, &[])
+    }
+
+    fn fee_config(&self) -> FeeConfig {
+        let status: ProtocolStatus = self.test.anchor_account(&self.protocol_status).unwrap();
+        status.fee_config
+    }
+}
+
+/// `ix` as queued, with the timelock PDA signing wherever it is passed
+fn operation(ix: impl ToAccountMetas, program_id: Pubkey, data: impl InstructionData) -> OperationInstruction {
+    OperationInstruction {
+        program_id,
+        accounts: ix
+            .to_account_metas(None)
+            .into_iter()
+            .map(|meta| OperationAccount {
+                pubkey: meta.pubkey,
+                is_signer: meta.pubkey == timelock_authority(),
+                is_writable: meta.is_writable,
+            })
+            .collect(),
+        data: data.data(),
+    }
+}
+
+fn set_fee_config(fixture: &Fixture, fee_config: FeeConfig) -> OperationInstruction {
+    operation(
+        vectai_protocol::accounts::SetFeeConfig {
+            protocol_status: fixture.protocol_status,
+            admin: timelock_authority(),
+            role_registry: None,
+        },
+        vectai_protocol::ID,
+        vectai_protocol::instruction::SetFeeConfig { fee_config },
+    )
+}
+
+fn update_role(registry: Pubkey, data: impl InstructionData) -> OperationInstruction {
+    operation(
+        vectai_protocol::accounts::UpdateRole {
+            registry,
+            admin: timelock_authority(),
+        },
+        vectai_protocol::ID,
+        data,
+    )
+}
+
+fn set_pair_venues(fixture: &Fixture, venues: PairVenues) -> OperationInstruction {
+    operation(
+        vectai_protocol::accounts::SetPairVenues {
+            registry: venue_registry(&fixture.base_mint, &fixture.quote_mint),
+            admin: timelock_authority(),
+        },
+        vectai_protocol::ID,
+        vectai_protocol::instruction::SetPairVenues { venues },
+    )
+}
+
+fn set_price_policy(fixture: &Fixture, max_age_secs: u32) -> OperationInstruction {
+    operation(
+        vectai_oracle::accounts::SetPricePolicy {
+            price_policy: price_policy(&fixture.price_feed, &timelock_authority()),
+            authority: timelock_authority(),
+        },
+        vectai_oracle::ID,
+        vectai_oracle::instruction::SetPricePolicy {
+            max_age_secs,
+            stale_price_policy: StalePricePolicy::LastGoodPrice,
+        },
+    )
+}
+
+const NEW_FEES: FeeConfig = FeeConfig {
+    trade_fee_bps: 10,
+    swap_fee_bps: 5,
+    performance_fee_bps: 1_000,
+    referral_share_bps: 2_500,
+};
+
+#[test]
+fn batch_applies_every_change_with_its_event() {
+    let mut fixture = Fixture::new();
+    let venues = PairVenues {
+        raydium_amm: Pubkey::new_unique(),
+        ..PairVenues::default()
+    };
+    let instructions = vec![
+        set_fee_config(&fixture, NEW_FEES),
+        set_pair_venues(&fixture, venues),
+        set_price_policy(&fixture, 30),
+    ];
+    let batch = fixture.queue(instructions.clone());
+
+    let err = fixture.execute(&instructions).unwrap_err();
+    assert_eq!(err, anchor_error(TimelockError::OperationNotReady));
+
+    fixture.test.warp_forward(ETA_DELAY);
+    fixture.execute(&instructions).unwrap();
+    assert!(fixture.test.account(&batch).is_none());
+
+    let fee_config = fixture.fee_config();
+    assert_eq!((fee_config.trade_fee_bps, fee_config.referral_share_bps), (10, 2_500));
+    let registry: VenueRegistry =
+        fixture.test.anchor_account(&venue_registry(&fixture.base_mint, &fixture.quote_mint)).unwrap();
+    assert_eq!(registry.venues, venues);
+    let policy: PricePolicy =
+        fixture.test.anchor_account(&price_policy(&fixture.price_feed, &timelock_authority())).unwrap();
+    assert_eq!((policy.max_age_secs, policy.stale_price_policy), (30, StalePricePolicy::LastGoodPrice));
+
+    let [fees] = fixture.test.events::<FeeConfigUpdated>().try_into().ok().unwrap();
+    assert_eq!(fees.fee_config.swap_fee_bps, 5);
+    let [pair] = fixture.test.events::<PairVenuesUpdated>().try_into().ok().unwrap();
+    assert_eq!((pair.base_mint, pair.venues), (fixture.base_mint, venues));
+    let [age] = fixture.test.events::<PricePolicyUpdated>().try_into().ok().unwrap();
+    assert_eq!((age.price_feed, age.authority, age.max_age_secs), (fixture.price_feed, timelock_authority(), 30));
+    let [executed] = fixture.test.events::<BatchExecuted>().try_into().ok().unwrap();
+    assert_eq!((executed.batch, executed.instruction_count), (batch, 3));
+}
+
+#[test]
+fn failing_instruction_reverts_the_whole_batch() {
+    let mut fixture = Fixture::new();
+    let invalid = FeeConfig {
+        trade_fee_bps: 10_000,
+        ..NEW_FEES
+    };
+    let instructions = vec![set_price_policy(&fixture, 30), set_fee_config(&fixture, invalid)];
+    let batch = fixture.queue(instructions.clone());
+    fixture.test.warp_forward(ETA_DELAY);
+
+    let err = fixture.execute(&instructions).unwrap_err();
+    assert_eq!(err, anchor_error(ProtocolError::InvalidFeeConfig));
+    let policy: PricePolicy =
+        fixture.test.anchor_account(&price_policy(&fixture.price_feed, &timelock_authority())).unwrap();
+    assert_eq!(policy.max_age_secs, 60);
+    assert_eq!(fixture.fee_config().trade_fee_bps, 0);
+    assert!(fixture.test.account(&batch).is_some());
+}
+
+#[test]
+fn queue_batch_requires_admin() {
+    let mut fixture = Fixture::new();
+    let ix = Instruction {
+        program_id: vectai_timelock::ID,
+        accounts: accounts::QueueBatch {
+            timelock: Pubkey::find_program_address(&[b"timelock"], &vectai_timelock::ID).0,
+            batch: batch_operation(0),
+            proposer: fixture.proposer.pubkey(),
+            system_program: system_program::ID,
+        }
+        .to_account_metas(None),
+        data: instruction::QueueBatch {
+            instructions: vec![set_fee_config(&fixture, NEW_FEES)],
+            delay: ETA_DELAY,
+        }
+        .data(),
+    };
+    let proposer = fixture.proposer.insecure_clone();
+    let err = fixture.test.process_instruction(ix, &[&proposer]).unwrap_err();
+    assert_eq!(err, anchor_error(TimelockError::UnauthorizedAdmin));
+    assert!(fixture.test.account(&batch_operation(0)).is_none());
+}
+
+#[test]
+fn roles_are_granted_and_revoked_through_a_batch() {
+    let mut fixture = Fixture::new();
+    let registry = fixture.test.set_role_registry(&[]);
+    let holder = Pubkey::new_unique();
+    let grants = vec![
+        update_role(registry, vectai_protocol::instruction::GrantRole { role: Role::Pauser, holder }),
+        update_role(registry, vectai_protocol::instruction::GrantRole { role: Role::FeeManager, holder }),
+    ];
+    fixture.queue(grants.clone());
+    fixture.test.warp_forward(ETA_DELAY);
+    fixture.execute(&grants).unwrap();
+
+    let roles: RoleRegistry = fixture.test.anchor_account(&registry).unwrap();
+    assert!(roles.has_role(&holder, Role::Pauser) && roles.has_role(&holder, Role::FeeManager));
+    assert!(!roles.has_role(&holder, Role::Minter));
+    let granted = fixture.test.events::<RoleGranted>();
+    assert_eq!(granted.len(), 2);
+    assert!(granted.iter().all(|event| event.holder == holder && event.granted_by == timelock_authority()));
+    assert_eq!((granted[0].role, granted[1].role), (Role::Pauser, Role::FeeManager));
+
+    let revoke = vec![update_role(registry, vectai_protocol::instruction::RevokeRole { role: Role::Pauser, holder })];
+    fixture.queue(revoke.clone());
+    fixture.test.warp_forward(ETA_DELAY);
+    fixture.execute(&revoke).unwrap();
+
+    let roles: RoleRegistry = fixture.test.anchor_account(&registry).unwrap();
+    assert!(!roles.has_role(&holder, Role::Pauser) && roles.has_role(&holder, Role::FeeManager));
+    let [revoked] = fixture.test.events::<RoleRevoked>().try_into().ok().unwrap();
+    assert_eq!((revoked.role, revoked.holder, revoked.revoked_by), (Role::Pauser, holder, timelock_authority()));
+}
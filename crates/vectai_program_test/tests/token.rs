@@ -0,0 +1,1606 @@
+//! `vectai_token` instructions.
+
+use anchor_lang::{
+    solana_program::{program_option::COption, program_pack::Pack, system_program, sysvar},
+    AnchorDeserialize, InstructionData, ToAccountMetas,
+};
+use anchor_spl::token_2022::spl_token_2022::{
+    self,
+    extension::{interest_bearing_mint::InterestBearingConfig, BaseStateWithExtensions, StateWithExtensions},
+};
+use solana_sdk::{
+    instruction::{AccountMeta, Instruction},
+    native_token::LAMPORTS_PER_SOL,
+    program_error::ProgramError,
+    pubkey::Pubkey,
+    signature::{Keypair, Signer},
+};
+use vectai_program_test::{anchor_error, ProgramTest};
+use vectai_protocol::{FeeConfig, ProtocolStats, Role};
+use vectai_token::{
+    accounts, instruction, supply_locks, Auction, CirculatingSupply, EmissionSchedule, FlaggedAccount, LockTiers,
+    LockedStake, StakePool, StakePosition, StakeUnlocked, SupplyLock, SupplyLockKind, SupplyLocks, TierTotals,
+    TokenError, TokenInfo, TransferBurned, LOCK_TIERS, MAX_SUPPLY_CEILING, REWARD_PRECISION,
+};
+
+const DECIMALS: u8 = 6;
+const DAY: i64 = 86_400;
+
+fn pda(seeds: &[&[u8]]) -> Pubkey {
+    Pubkey::find_program_address(seeds, &vectai_token::ID).0
+}
+
+fn ix(accounts: impl ToAccountMetas, data: impl InstructionData) -> Instruction {
+    Instruction {
+        program_id: vectai_token::ID,
+        accounts: accounts.to_account_metas(None),
+        data: data.data(),
+    }
+}
+
+/// A VECTAI mint created through `initialize_token`
+struct Fixture {
+    test: ProgramTest,
+    authority: Keypair,
+    mint: Pubkey,
+    token_info: Pubkey,
+    protocol_status: Pubkey,
+    protocol_stats: Pubkey,
+}
+
+impl Fixture {
+    fn new(max_supply: u64) -> Self {
+        let mut test = ProgramTest::new();
+        let protocol_status = test.set_protocol_status(false, FeeConfig::INITIAL);
+        let protocol_stats = test.set_protocol_stats();
+        let authority = funded(&mut test);
+        let mint = Keypair::new();
+        initialize_token(&mut test, &authority, &mint, max_supply).unwrap();
+        Fixture {
+            test,
+            authority,
+            mint: mint.pubkey(),
+            token_info: pda(&[b"token-info", mint.pubkey().as_ref()]),
+            protocol_status,
+            protocol_stats,
+        }
+    }
+
+    fn token_info(&self) -> TokenInfo {
+        self.test.anchor_account(&self.token_info).unwrap()
+    }
+
+    fn mint_state(&self) -> spl_token::state::Mint {
+        spl_token::state::Mint::unpack(&self.test.account(&self.mint).unwrap().data).unwrap()
+    }
+
+    fn mint_signer(&self) -> Pubkey {
+        pda(&[b"mint-authority", self.mint.as_ref()])
+    }
+
+    fn token_account(&mut self, owner: &Pubkey) -> Pubkey {
+        let address = Pubkey::new_unique();
+        self.test.create_token_account(address, &self.mint, owner, 0);
+        address
+    }
+
+    fn mint_to_ix(&self, to: Pubkey, authority: Pubkey, amount: u64) -> Instruction {
+        ix(
+            accounts::MintTokens {
+                mint: self.mint,
+                to,
+                token_info: self.token_info,
+                mint_signer: self.mint_signer(),
+                mint_authority: authority,
+                token_program: spl_token::ID,
+                protocol_status: self.protocol_status,
+                protocol_stats: self.protocol_stats,
+                stats_reporter: vectai_protocol::stats_reporter(&vectai_token::ID),
+                protocol_program: vectai_protocol::ID,
+                role_registry: None,
+            },
+            instruction::MintTo { amount },
+        )
+    }
+
+    fn mint_to(&mut self, to: Pubkey, amount: u64) -> Result<(), ProgramError> {
+        let ix = self.mint_to_ix(to, self.authority.pubkey(), amount);
+        let authority = self.authority.insecure_clone();
+        self.test.process_instruction(ix, &[&authority])
+    }
+
+    fn transfer(&mut self, from: Pubkey, to: Pubkey, authority: &Keypair, amount: u64) -> Result<(), ProgramError> {
+        let ix = ix(
+            accounts::TransferTokens {
+                mint: self.mint,
+                token_info: self.token_info,
+                from,
+                to,
+                authority: authority.pubkey(),
+                token_program: spl_token::ID,
+                protocol_status: self.protocol_status,
+            },
+            instruction::Transfer { amount },
+        );
+        self.test.process_instruction(ix, &[authority])
+    }
+}
+
+fn initialize_token(test: &mut ProgramTest, authority: &Keypair, mint: &Keypair, max_supply: u64) -> Result<(), ProgramError> {
+    let payer = test.payer().pubkey();
+    let ix = ix(
+        accounts::InitializeToken {
+            mint: mint.pubkey(),
+            token_info: pda(&[b"token-info", mint.pubkey().as_ref()]),
+            mint_authority: authority.pubkey(),
+            payer,
+            rent: sysvar::rent::ID,
+            token_program: spl_token::ID,
+            system_program: system_program::ID,
+        },
+        instruction::InitializeToken { max_supply, decimals: DECIMALS },
+    );
+    test.process_instruction(ix, &[mint, authority])
+}
+
+fn funded(test: &mut ProgramTest) -> Keypair {
+    let keypair = Keypair::new();
+    test.airdrop(&keypair.pubkey(), 10 * LAMPORTS_PER_SOL);
+    keypair
+}
+
+// ===== CORE TOKEN =====
+
+#[test]
+fn initialize_token_records_supply_cap() {
+    let fixture = Fixture::new(1_000_000);
+
+    let info = fixture.token_info();
+    assert_eq!(info.mint, fixture.mint);
+    assert_eq!(info.mint_authority, fixture.authority.pubkey());
+    assert_eq!(info.max_supply, 1_000_000);
+    assert_eq!(info.minted, 0);
+    assert_eq!(info.decimals, DECIMALS);
+    assert!(!info.is_paused);
+    assert!(!info.clawback_enabled);
+    assert_eq!(info.compliance_authority, Pubkey::default());
+
+    let mint = fixture.mint_state();
+    assert_eq!(mint.decimals, DECIMALS);
+    assert_eq!(mint.mint_authority, COption::Some(fixture.authority.pubkey()));
+    assert_eq!(mint.supply, 0);
+}
+
+#[test]
+fn initialize_token_caps_supply_at_ceiling() {
+    let mut test = ProgramTest::new();
+    let authority = funded(&mut test);
+    let err = initialize_token(&mut test, &authority, &Keypair::new(), MAX_SUPPLY_CEILING + 1).unwrap_err();
+    assert_eq!(err, anchor_error(TokenError::InvalidMaxSupply));
+    initialize_token(&mut test, &authority, &Keypair::new(), MAX_SUPPLY_CEILING).unwrap();
+}
+
+#[test]
+fn raise_max_supply_requires_timelock() {
+    let mut fixture = Fixture::new(1_000);
+    let authority = fixture.authority.insecure_clone();
+    let ix = ix(
+        accounts::RaiseMaxSupply {
+            token_info: fixture.token_info,
+            admin: authority.pubkey(),
+        },
+        instruction::RaiseMaxSupply { max_supply: 2_000 },
+    );
+    let err = fixture.test.process_instruction(ix, &[&authority]).unwrap_err();
+    assert_eq!(err, anchor_error(TokenError::UnauthorizedAdmin));
+    assert_eq!(fixture.token_info().max_supply, 1_000);
+}
+
+#[test]
+fn mint_to_enforces_supply_cap() {
+    let mut fixture = Fixture::new(1_000);
+    let owner = Pubkey::new_unique();
+    let to = fixture.token_account(&owner);
+
+    fixture.mint_to(to, 600).unwrap();
+    assert_eq!(fixture.test.token_balance(&to), Some(600));
+    assert_eq!(fixture.token_info().minted, 600);
+
+    let err = fixture.mint_to(to, 401).unwrap_err();
+    assert_eq!(err, anchor_error(TokenError::ExceedsMaxSupply));
+    fixture.mint_to(to, 400).unwrap();
+    assert_eq!(fixture.test.token_supply(&fixture.mint), Some(1_000));
+    assert_eq!(fixture.token_info().minted, 1_000);
+
+    let stats: ProtocolStats = fixture.test.anchor_account(&fixture.protocol_stats).unwrap();
+    assert_eq!(stats.mints, 2);
+    assert_eq!(stats.volume(&fixture.mint).unwrap().minted, 1_000);
+}
+
+#[test]
+fn mint_to_rejects_zero_and_unauthorized() {
+    let mut fixture = Fixture::new(1_000);
+    let to = fixture.token_account(&Pubkey::new_unique());
+
+    let err = fixture.mint_to(to, 0).unwrap_err();
+    assert_eq!(err, anchor_error(TokenError::InvalidAmount));
+
+    let impostor = Keypair::new();
+    let ix = fixture.mint_to_ix(to, impostor.pubkey(), 100);
+    let err = fixture.test.process_instruction(ix, &[&impostor]).unwrap_err();
+    assert_eq!(err, anchor_error(TokenError::UnauthorizedMintAuthority));
+    assert_eq!(fixture.token_info().minted, 0);
+}
+
+#[test]
+fn mint_to_rejected_while_paused() {
+    let mut fixture = Fixture::new(1_000);
+    let to = fixture.token_account(&Pubkey::new_unique());
+
+    let mut info = fixture.token_info();
+    info.is_paused = true;
+    fixture.test.set_anchor_account(fixture.token_info, &info);
+    let err = fixture.mint_to(to, 100).unwrap_err();
+    assert_eq!(err, anchor_error(TokenError::TokenPaused));
+
+    info.is_paused = false;
+    fixture.test.set_anchor_account(fixture.token_info, &info);
+    fixture.test.set_protocol_status(true, FeeConfig::INITIAL);
+    let err = fixture.mint_to(to, 100).unwrap_err();
+    assert_eq!(err, anchor_error(TokenError::ProtocolPaused));
+    assert_eq!(fixture.test.token_balance(&to), Some(0));
+}
+
+#[test]
+fn transfer_moves_tokens() {
+    let mut fixture = Fixture::new(1_000);
+    let alice = Keypair::new();
+    let bob = Keypair::new();
+    let alice_account = fixture.token_account(&alice.pubkey());
+    let bob_account = fixture.token_account(&bob.pubkey());
+    fixture.mint_to(alice_account, 500).unwrap();
+
+    fixture.transfer(alice_account, bob_account, &alice, 200).unwrap();
+    assert_eq!(fixture.test.token_balance(&alice_account), Some(300));
+    assert_eq!(fixture.test.token_balance(&bob_account), Some(200));
+}
+
+#[test]
+fn transfer_rejects_invalid_requests() {
+    let mut fixture = Fixture::new(1_000);
+    let alice = Keypair::new();
+    let bob = Keypair::new();
+    let alice_account = fixture.token_account(&alice.pubkey());
+    let bob_account = fixture.token_account(&bob.pubkey());
+    fixture.mint_to(alice_account, 500).unwrap();
+
+    let err = fixture.transfer(alice_account, bob_account, &alice, 0).unwrap_err();
+    assert_eq!(err, anchor_error(TokenError::InvalidAmount));
+    let err = fixture.transfer(alice_account, bob_account, &bob, 100).unwrap_err();
+    assert_eq!(err, anchor_error(TokenError::InvalidTokenAccount));
+    let err = fixture.transfer(alice_account, bob_account, &alice, 501).unwrap_err();
+    assert_eq!(err, anchor_error(TokenError::InsufficientBalance));
+
+    fixture.test.set_protocol_status(true, FeeConfig::INITIAL);
+    let err = fixture.transfer(alice_account, bob_account, &alice, 100).unwrap_err();
+    assert_eq!(err, anchor_error(TokenError::ProtocolPaused));
+    assert_eq!(fixture.test.token_balance(&alice_account), Some(500));
+}
+
+#[test]
+fn transfer_burns_at_burn_rate() {
+    let mut fixture = Fixture::new(1_000);
+    let alice = Keypair::new();
+    let bob = Keypair::new();
+    let alice_account = fixture.token_account(&alice.pubkey());
+    let bob_account = fixture.token_account(&bob.pubkey());
+    fixture.mint_to(alice_account, 500).unwrap();
+    let mut info = fixture.token_info();
+    info.burn_rate_bps = 250;
+    fixture.test.set_anchor_account(fixture.token_info, &info);
+
+    fixture.transfer(alice_account, bob_account, &alice, 200).unwrap();
+    assert_eq!(fixture.test.token_balance(&alice_account), Some(300));
+    assert_eq!(fixture.test.token_balance(&bob_account), Some(195));
+    assert_eq!(fixture.test.token_supply(&fixture.mint), Some(495));
+    assert_eq!(fixture.token_info().burned, 5);
+    let events = fixture.test.events::<TransferBurned>();
+    assert_eq!((events[0].amount, events[0].burned), (200, 5));
+
+    // Rounds down, so small transfers burn nothing
+    fixture.transfer(alice_account, bob_account, &alice, 39).unwrap();
+    assert_eq!(fixture.test.token_balance(&bob_account), Some(234));
+    assert_eq!(fixture.token_info().burned, 5);
+    assert!(fixture.test.events::<TransferBurned>().is_empty());
+}
+
+#[test]
+fn set_burn_rate_requires_timelock() {
+    let mut fixture = Fixture::new(1_000);
+    let authority = fixture.authority.insecure_clone();
+    let ix = ix(
+        accounts::SetBurnRate {
+            token_info: fixture.token_info,
+            admin: authority.pubkey(),
+        },
+        instruction::SetBurnRate { burn_rate_bps: 100 },
+    );
+    let err = fixture.test.process_instruction(ix, &[&authority]).unwrap_err();
+    assert_eq!(err, anchor_error(TokenError::UnauthorizedAdmin));
+    assert_eq!(fixture.token_info().burn_rate_bps, 0);
+}
+
+#[test]
+fn pause_and_unpause_require_admin() {
+    let mut fixture = Fixture::new(1_000);
+    let impostor = Keypair::new();
+    let accounts = || accounts::PauseToken {
+        token_info: fixture.token_info,
+        admin: impostor.pubkey(),
+        role_registry: None,
+    };
+
+    let err = fixture
+        .test
+        .process_instruction(ix(accounts(), instruction::PauseToken {}), &[&impostor])
+        .unwrap_err();
+    assert_eq!(err, anchor_error(TokenError::UnauthorizedAdmin));
+    let err = fixture
+        .test
+        .process_instruction(ix(accounts(), instruction::UnpauseToken {}), &[&impostor])
+        .unwrap_err();
+    assert_eq!(err, anchor_error(TokenError::UnauthorizedAdmin));
+    assert!(!fixture.token_info().is_paused);
+}
+
+#[test]
+fn pauser_pauses_but_cannot_unpause() {
+    let mut fixture = Fixture::new(1_000);
+    let (pauser, minter) = (Keypair::new(), Keypair::new());
+    let role_registry = fixture
+        .test
+        .set_role_registry(&[(pauser.pubkey(), Role::Pauser), (minter.pubkey(), Role::Minter)]);
+    let accounts = |admin: &Keypair| accounts::PauseToken {
+        token_info: fixture.token_info,
+        admin: admin.pubkey(),
+        role_registry: Some(role_registry),
+    };
+
+    let err = fixture
+        .test
+        .process_instruction(ix(accounts(&minter), instruction::PauseToken {}), &[&minter])
+        .unwrap_err();
+    assert_eq!(err, anchor_error(TokenError::UnauthorizedAdmin));
+
+    fixture
+        .test
+        .process_instruction(ix(accounts(&pauser), instruction::PauseToken {}), &[&pauser])
+        .unwrap();
+    assert!(fixture.token_info().is_paused);
+    let err = fixture
+        .test
+        .process_instruction(ix(accounts(&pauser), instruction::UnpauseToken {}), &[&pauser])
+        .unwrap_err();
+    assert_eq!(err, anchor_error(TokenError::UnauthorizedAdmin));
+    assert!(fixture.token_info().is_paused);
+}
+
+// ===== STAKING =====
+
+struct Pool {
+    address: Pubkey,
+    reward_mint: Pubkey,
+    stake_vault: Pubkey,
+    reward_vault: Pubkey,
+    fee_vault: Pubkey,
+}
+
+/// Stake pool as `initialize_stake_pool` leaves it; the instruction itself
+/// is admin-only
+fn stake_pool(fixture: &mut Fixture) -> Pool {
+    let (address, bump) = Pubkey::find_program_address(&[b"stake-pool", fixture.mint.as_ref()], &vectai_token::ID);
+    let reward_mint = Pubkey::new_unique();
+    fixture.test.create_mint(reward_mint, &fixture.authority.pubkey(), DECIMALS);
+    let stake_vault = pda(&[b"stake-vault", address.as_ref()]);
+    let reward_vault = pda(&[b"reward-vault", address.as_ref()]);
+    let fee_vault = pda(&[b"fee-vault", address.as_ref()]);
+    fixture.test.create_token_account(stake_vault, &fixture.mint, &address, 0);
+    fixture.test.create_token_account(reward_vault, &reward_mint, &address, 0);
+    fixture.test.create_token_account(fee_vault, &reward_mint, &address, 0);
+    fixture.test.set_anchor_account(
+        address,
+        &StakePool {
+            mint: fixture.mint,
+            reward_mint,
+            stake_vault,
+            reward_vault,
+            fee_vault,
+            total_staked: 0,
+            acc_reward_per_share: 0,
+            total_distributed: 0,
+            bump,
+        },
+    );
+    Pool {
+        address,
+        reward_mint,
+        stake_vault,
+        reward_vault,
+        fee_vault,
+    }
+}
+
+fn position_address(pool: &Pool, owner: &Pubkey) -> Pubkey {
+    pda(&[b"stake", pool.address.as_ref(), owner.as_ref()])
+}
+
+fn stake(fixture: &mut Fixture, pool: &Pool, owner: &Keypair, from: Pubkey, amount: u64) -> Result<(), ProgramError> {
+    let ix = ix(
+        accounts::Stake {
+            token_info: fixture.token_info,
+            stake_pool: pool.address,
+            position: position_address(pool, &owner.pubkey()),
+            owner_token_account: from,
+            stake_vault: pool.stake_vault,
+            owner: owner.pubkey(),
+            token_program: spl_token::ID,
+            system_program: system_program::ID,
+            protocol_status: fixture.protocol_status,
+        },
+        instruction::Stake { amount },
+    );
+    fixture.test.process_instruction(ix, &[owner])
+}
+
+fn unstake(fixture: &mut Fixture, pool: &Pool, owner: &Keypair, to: Pubkey, amount: u64) -> Result<(), ProgramError> {
+    let ix = ix(
+        accounts::Unstake {
+            stake_pool: pool.address,
+            position: position_address(pool, &owner.pubkey()),
+            owner_token_account: to,
+            stake_vault: pool.stake_vault,
+            owner: owner.pubkey(),
+            token_program: spl_token::ID,
+            protocol_status: fixture.protocol_status,
+        },
+        instruction::Unstake { amount },
+    );
+    fixture.test.process_instruction(ix, &[owner])
+}
+
+fn claim(fixture: &mut Fixture, pool: &Pool, owner: &Keypair, to: Pubkey) -> Result<(), ProgramError> {
+    let ix = ix(
+        accounts::ClaimRewards {
+            stake_pool: pool.address,
+            position: position_address(pool, &owner.pubkey()),
+            owner_reward_account: to,
+            reward_vault: pool.reward_vault,
+            owner: owner.pubkey(),
+            token_program: spl_token::ID,
+            protocol_status: fixture.protocol_status,
+        },
+        instruction::ClaimRewards {},
+    );
+    fixture.test.process_instruction(ix, &[owner])
+}
+
+fn distribute(fixture: &mut Fixture, pool: &Pool) -> Result<(), ProgramError> {
+    let ix = ix(
+        accounts::DistributeRewards {
+            stake_pool: pool.address,
+            fee_vault: pool.fee_vault,
+            reward_vault: pool.reward_vault,
+            token_program: spl_token::ID,
+            protocol_status: fixture.protocol_status,
+        },
+        instruction::DistributeRewards {},
+    );
+    fixture.test.process_instruction(ix, &[])
+}
+
+#[test]
+fn initialize_stake_pool_requires_admin() {
+    let mut fixture = Fixture::new(1_000);
+    let reward_mint = Pubkey::new_unique();
+    fixture.test.create_mint(reward_mint, &fixture.authority.pubkey(), DECIMALS);
+    let impostor = funded(&mut fixture.test);
+    let pool = pda(&[b"stake-pool", fixture.mint.as_ref()]);
+
+    let ix = ix(
+        accounts::InitializeStakePool {
+            token_info: fixture.token_info,
+            mint: fixture.mint,
+            reward_mint,
+            stake_pool: pool,
+            stake_vault: pda(&[b"stake-vault", pool.as_ref()]),
+            reward_vault: pda(&[b"reward-vault", pool.as_ref()]),
+            fee_vault: pda(&[b"fee-vault", pool.as_ref()]),
+            admin: impostor.pubkey(),
+            rent: sysvar::rent::ID,
+            token_program: spl_token::ID,
+            system_program: system_program::ID,
+        },
+        instruction::InitializeStakePool {},
+    );
+    let err = fixture.test.process_instruction(ix, &[&impostor]).unwrap_err();
+    assert_eq!(err, anchor_error(TokenError::UnauthorizedAdmin));
+    assert!(fixture.test.account(&pool).is_none());
+}
+
+#[test]
+fn stakers_earn_fees_pro_rata() {
+    let mut fixture = Fixture::new(10_000);
+    let pool = stake_pool(&mut fixture);
+    let alice = funded(&mut fixture.test);
+    let bob = funded(&mut fixture.test);
+    let alice_tokens = fixture.token_account(&alice.pubkey());
+    let bob_tokens = fixture.token_account(&bob.pubkey());
+    fixture.mint_to(alice_tokens, 300).unwrap();
+    fixture.mint_to(bob_tokens, 100).unwrap();
+
+    stake(&mut fixture, &pool, &alice, alice_tokens, 300).unwrap();
+    stake(&mut fixture, &pool, &bob, bob_tokens, 100).unwrap();
+    assert_eq!(fixture.test.token_balance(&pool.stake_vault), Some(400));
+    let position: StakePosition = fixture.test.anchor_account(&position_address(&pool, &alice.pubkey())).unwrap();
+    assert_eq!(position.owner, alice.pubkey());
+    assert_eq!(position.pool, pool.address);
+    assert_eq!(position.amount, 300);
+
+    fixture.test.create_token_account(pool.fee_vault, &pool.reward_mint, &pool.address, 400);
+    distribute(&mut fixture, &pool).unwrap();
+    let state: StakePool = fixture.test.anchor_account(&pool.address).unwrap();
+    assert_eq!(state.total_staked, 400);
+    assert_eq!(state.total_distributed, 400);
+    assert_eq!(state.acc_reward_per_share, REWARD_PRECISION);
+    assert_eq!(fixture.test.token_balance(&pool.fee_vault), Some(0));
+    assert_eq!(fixture.test.token_balance(&pool.reward_vault), Some(400));
+
+    let alice_rewards = Pubkey::new_unique();
+    fixture.test.create_token_account(alice_rewards, &pool.reward_mint, &alice.pubkey(), 0);
+    claim(&mut fixture, &pool, &alice, alice_rewards).unwrap();
+    assert_eq!(fixture.test.token_balance(&alice_rewards), Some(300));
+    let err = claim(&mut fixture, &pool, &alice, alice_rewards).unwrap_err();
+    assert_eq!(err, anchor_error(TokenError::NothingToClaim));
+
+    let bob_rewards = Pubkey::new_unique();
+    fixture.test.create_token_account(bob_rewards, &pool.reward_mint, &bob.pubkey(), 0);
+    claim(&mut fixture, &pool, &bob, bob_rewards).unwrap();
+    assert_eq!(fixture.test.token_balance(&bob_rewards), Some(100));
+}
+
+#[test]
+fn unstake_returns_stake_and_keeps_rewards() {
+    let mut fixture = Fixture::new(10_000);
+    let pool = stake_pool(&mut fixture);
+    let alice = funded(&mut fixture.test);
+    let alice_tokens = fixture.token_account(&alice.pubkey());
+    fixture.mint_to(alice_tokens, 500).unwrap();
+    stake(&mut fixture, &pool, &alice, alice_tokens, 500).unwrap();
+    fixture.test.create_token_account(pool.fee_vault, &pool.reward_mint, &pool.address, 50);
+    distribute(&mut fixture, &pool).unwrap();
+
+    let err = unstake(&mut fixture, &pool, &alice, alice_tokens, 501).unwrap_err();
+    assert_eq!(err, anchor_error(TokenError::InsufficientBalance));
+    unstake(&mut fixture, &pool, &alice, alice_tokens, 500).unwrap();
+    assert_eq!(fixture.test.token_balance(&alice_tokens), Some(500));
+    let position: StakePosition = fixture.test.anchor_account(&position_address(&pool, &alice.pubkey())).unwrap();
+    assert_eq!(position.amount, 0);
+    assert_eq!(position.pending_rewards, 50);
+    let state: StakePool = fixture.test.anchor_account(&pool.address).unwrap();
+    assert_eq!(state.total_staked, 0);
+}
+
+#[test]
+fn close_stake_position_requires_unstake_and_claim() {
+    let mut fixture = Fixture::new(10_000);
+    let pool = stake_pool(&mut fixture);
+    let alice = funded(&mut fixture.test);
+    let alice_tokens = fixture.token_account(&alice.pubkey());
+    fixture.mint_to(alice_tokens, 500).unwrap();
+    stake(&mut fixture, &pool, &alice, alice_tokens, 500).unwrap();
+    fixture.test.create_token_account(pool.fee_vault, &pool.reward_mint, &pool.address, 50);
+    distribute(&mut fixture, &pool).unwrap();
+    let position = position_address(&pool, &alice.pubkey());
+    let close = ix(
+        accounts::CloseStakePosition {
+            position,
+            owner: alice.pubkey(),
+        },
+        instruction::CloseStakePosition {},
+    );
+
+    let err = fixture.test.process_instruction(close.clone(), &[&alice]).unwrap_err();
+    assert_eq!(err, anchor_error(TokenError::PositionNotEmpty));
+    unstake(&mut fixture, &pool, &alice, alice_tokens, 500).unwrap();
+    let err = fixture.test.process_instruction(close.clone(), &[&alice]).unwrap_err();
+    assert_eq!(err, anchor_error(TokenError::PositionNotEmpty));
+
+    let alice_rewards = Pubkey::new_unique();
+    fixture.test.create_token_account(alice_rewards, &pool.reward_mint, &alice.pubkey(), 0);
+    claim(&mut fixture, &pool, &alice, alice_rewards).unwrap();
+    fixture.test.process_instruction(close, &[&alice]).unwrap();
+    assert!(fixture.test.account(&position).is_none());
+}
+
+#[test]
+fn staking_rejects_invalid_requests() {
+    let mut fixture = Fixture::new(10_000);
+    let pool = stake_pool(&mut fixture);
+    let alice = funded(&mut fixture.test);
+    let alice_tokens = fixture.token_account(&alice.pubkey());
+    fixture.mint_to(alice_tokens, 100).unwrap();
+
+    let err = stake(&mut fixture, &pool, &alice, alice_tokens, 0).unwrap_err();
+    assert_eq!(err, anchor_error(TokenError::InvalidAmount));
+    let err = stake(&mut fixture, &pool, &alice, alice_tokens, 101).unwrap_err();
+    assert_eq!(err, anchor_error(TokenError::InsufficientBalance));
+    let err = distribute(&mut fixture, &pool).unwrap_err();
+    assert_eq!(err, anchor_error(TokenError::NothingToDistribute));
+
+    // Fees with nobody staked cannot be credited to anyone
+    fixture.test.create_token_account(pool.fee_vault, &pool.reward_mint, &pool.address, 10);
+    let err = distribute(&mut fixture, &pool).unwrap_err();
+    assert_eq!(err, anchor_error(TokenError::NoStakers));
+}
+
+// ===== LOCK TIERS =====
+
+/// Lock tiers as `initialize_lock_tiers` leaves them; the instruction
+/// itself is admin-only
+fn lock_tiers(fixture: &mut Fixture, pool: &Pool) -> Pubkey {
+    let (address, bump) = Pubkey::find_program_address(&[b"lock-tiers", pool.address.as_ref()], &vectai_token::ID);
+    fixture.test.set_anchor_account(
+        address,
+        &LockTiers {
+            pool: pool.address,
+            tiers: Default::default(),
+            bump,
+        },
+    );
+    address
+}
+
+fn lock(fixture: &mut Fixture, pool: &Pool, owner: &Keypair, from: Pubkey, tier: u8, amount: u64) -> Result<(), ProgramError> {
+    let ix = ix(
+        accounts::LockStake {
+            token_info: fixture.token_info,
+            stake_pool: pool.address,
+            lock_tiers: pda(&[b"lock-tiers", pool.address.as_ref()]),
+            position: vectai_token::locked_stake(&pool.address, &owner.pubkey(), tier),
+            owner_token_account: from,
+            stake_vault: pool.stake_vault,
+            owner: owner.pubkey(),
+            token_program: spl_token::ID,
+            system_program: system_program::ID,
+            protocol_status: fixture.protocol_status,
+        },
+        instruction::LockStake { tier, amount },
+    );
+    fixture.test.process_instruction(ix, &[owner])
+}
+
+fn unlock(
+    fixture: &mut Fixture,
+    pool: &Pool,
+    owner: &Keypair,
+    tier: u8,
+    to: Pubkey,
+    rewards_to: Pubkey,
+) -> Result<(), ProgramError> {
+    let ix = ix(
+        accounts::UnlockStake {
+            stake_pool: pool.address,
+            lock_tiers: pda(&[b"lock-tiers", pool.address.as_ref()]),
+            position: vectai_token::locked_stake(&pool.address, &owner.pubkey(), tier),
+            owner_token_account: to,
+            owner_reward_account: rewards_to,
+            stake_vault: pool.stake_vault,
+            reward_vault: pool.reward_vault,
+            fee_vault: pool.fee_vault,
+            owner: owner.pubkey(),
+            token_program: spl_token::ID,
+            protocol_status: fixture.protocol_status,
+        },
+        instruction::UnlockStake {},
+    );
+    fixture.test.process_instruction(ix, &[owner])
+}
+
+#[test]
+fn initialize_lock_tiers_requires_admin() {
+    let mut fixture = Fixture::new(1_000);
+    let pool = stake_pool(&mut fixture);
+    let impostor = funded(&mut fixture.test);
+    let address = pda(&[b"lock-tiers", pool.address.as_ref()]);
+
+    let ix = ix(
+        accounts::InitializeLockTiers {
+            stake_pool: pool.address,
+            lock_tiers: address,
+            admin: impostor.pubkey(),
+            system_program: system_program::ID,
+        },
+        instruction::InitializeLockTiers {},
+    );
+    let err = fixture.test.process_instruction(ix, &[&impostor]).unwrap_err();
+    assert_eq!(err, anchor_error(TokenError::UnauthorizedAdmin));
+    assert!(fixture.test.account(&address).is_none());
+}
+
+#[test]
+fn locked_stake_earns_at_its_tier_multiplier() {
+    let mut fixture = Fixture::new(10_000);
+    let pool = stake_pool(&mut fixture);
+    let tiers = lock_tiers(&mut fixture, &pool);
+    let alice = funded(&mut fixture.test);
+    let bob = funded(&mut fixture.test);
+    let alice_tokens = fixture.token_account(&alice.pubkey());
+    let bob_tokens = fixture.token_account(&bob.pubkey());
+    fixture.mint_to(alice_tokens, 300).unwrap();
+    fixture.mint_to(bob_tokens, 100).unwrap();
+
+    // 365 days at 2x: bob's 100 weigh as much as 200 staked
+    stake(&mut fixture, &pool, &alice, alice_tokens, 300).unwrap();
+    lock(&mut fixture, &pool, &bob, bob_tokens, 3, 100).unwrap();
+    let position: LockedStake = fixture.test.anchor_account(&vectai_token::locked_stake(&pool.address, &bob.pubkey(), 3)).unwrap();
+    assert_eq!((position.tier, position.amount, position.weight), (3, 100, 200));
+    assert_eq!(position.unlock_at, fixture.test.clock().unix_timestamp + LOCK_TIERS[3].duration);
+    let state: LockTiers = fixture.test.anchor_account(&tiers).unwrap();
+    assert_eq!(state.tiers[3], TierTotals { locked: 100, weight: 200, positions: 1, penalties: 0 });
+    assert_eq!(state.tiers[0], TierTotals::default());
+    assert_eq!(fixture.test.token_balance(&pool.stake_vault), Some(400));
+
+    fixture.test.create_token_account(pool.fee_vault, &pool.reward_mint, &pool.address, 500);
+    distribute(&mut fixture, &pool).unwrap();
+    let state: StakePool = fixture.test.anchor_account(&pool.address).unwrap();
+    assert_eq!(state.total_staked, 500);
+
+    let alice_rewards = Pubkey::new_unique();
+    fixture.test.create_token_account(alice_rewards, &pool.reward_mint, &alice.pubkey(), 0);
+    claim(&mut fixture, &pool, &alice, alice_rewards).unwrap();
+    assert_eq!(fixture.test.token_balance(&alice_rewards), Some(300));
+
+    // Past the lock: principal and every reward, no penalty
+    fixture.test.warp_forward(365 * DAY);
+    let bob_rewards = Pubkey::new_unique();
+    fixture.test.create_token_account(bob_rewards, &pool.reward_mint, &bob.pubkey(), 0);
+    unlock(&mut fixture, &pool, &bob, 3, bob_tokens, bob_rewards).unwrap();
+    assert_eq!(fixture.test.token_balance(&bob_tokens), Some(100));
+    assert_eq!(fixture.test.token_balance(&bob_rewards), Some(200));
+    assert_eq!(fixture.test.token_balance(&pool.fee_vault), Some(0));
+    let [event] = fixture.test.events::<StakeUnlocked>().try_into().ok().unwrap();
+    assert_eq!((event.tier, event.amount, event.rewards, event.penalty), (3, 100, 200, 0));
+    assert!(fixture.test.account(&vectai_token::locked_stake(&pool.address, &bob.pubkey(), 3)).is_none());
+    let state: LockTiers = fixture.test.anchor_account(&tiers).unwrap();
+    assert_eq!(state.tiers[3], TierTotals::default());
+    let state: StakePool = fixture.test.anchor_account(&pool.address).unwrap();
+    assert_eq!(state.total_staked, 300);
+}
+
+#[test]
+fn early_unlock_returns_penalty_to_the_reward_pool() {
+    let mut fixture = Fixture::new(10_000);
+    let pool = stake_pool(&mut fixture);
+    let tiers = lock_tiers(&mut fixture, &pool);
+    let alice = funded(&mut fixture.test);
+    let bob = funded(&mut fixture.test);
+    let alice_tokens = fixture.token_account(&alice.pubkey());
+    let bob_tokens = fixture.token_account(&bob.pubkey());
+    fixture.mint_to(alice_tokens, 100).unwrap();
+    fixture.mint_to(bob_tokens, 100).unwrap();
+
+    // 30 days at 1.1x: bob earns 110 to alice's 100
+    stake(&mut fixture, &pool, &alice, alice_tokens, 100).unwrap();
+    lock(&mut fixture, &pool, &bob, bob_tokens, 0, 100).unwrap();
+    fixture.test.create_token_account(pool.fee_vault, &pool.reward_mint, &pool.address, 210);
+    distribute(&mut fixture, &pool).unwrap();
+
+    // A day short of the lock: 25% of the 110 earned is forfeited
+    fixture.test.warp_forward(29 * DAY);
+    let bob_rewards = Pubkey::new_unique();
+    fixture.test.create_token_account(bob_rewards, &pool.reward_mint, &bob.pubkey(), 0);
+    unlock(&mut fixture, &pool, &bob, 0, bob_tokens, bob_rewards).unwrap();
+    assert_eq!(fixture.test.token_balance(&bob_tokens), Some(100));
+    assert_eq!(fixture.test.token_balance(&bob_rewards), Some(83));
+    assert_eq!(fixture.test.token_balance(&pool.fee_vault), Some(27));
+    let state: LockTiers = fixture.test.anchor_account(&tiers).unwrap();
+    assert_eq!(state.tiers[0], TierTotals { locked: 0, weight: 0, positions: 0, penalties: 27 });
+
+    // The next distribution pays it to the stakers who remain
+    distribute(&mut fixture, &pool).unwrap();
+    let alice_rewards = Pubkey::new_unique();
+    fixture.test.create_token_account(alice_rewards, &pool.reward_mint, &alice.pubkey(), 0);
+    claim(&mut fixture, &pool, &alice, alice_rewards).unwrap();
+    assert_eq!(fixture.test.token_balance(&alice_rewards), Some(127));
+}
+
+#[test]
+fn adding_to_a_lock_restarts_it() {
+    let mut fixture = Fixture::new(10_000);
+    let pool = stake_pool(&mut fixture);
+    let tiers = lock_tiers(&mut fixture, &pool);
+    let alice = funded(&mut fixture.test);
+    let alice_tokens = fixture.token_account(&alice.pubkey());
+    fixture.mint_to(alice_tokens, 300).unwrap();
+    let position = vectai_token::locked_stake(&pool.address, &alice.pubkey(), 1);
+
+    lock(&mut fixture, &pool, &alice, alice_tokens, 1, 100).unwrap();
+    fixture.test.warp_forward(60 * DAY);
+    lock(&mut fixture, &pool, &alice, alice_tokens, 1, 100).unwrap();
+    let state: LockedStake = fixture.test.anchor_account(&position).unwrap();
+    assert_eq!((state.amount, state.weight), (200, 250));
+    assert_eq!(state.unlock_at, fixture.test.clock().unix_timestamp + 90 * DAY);
+    let state: LockTiers = fixture.test.anchor_account(&tiers).unwrap();
+    assert_eq!(state.tiers[1], TierTotals { locked: 200, weight: 250, positions: 1, penalties: 0 });
+
+    let err = lock(&mut fixture, &pool, &alice, alice_tokens, LOCK_TIERS.len() as u8, 100).unwrap_err();
+    assert_eq!(err, anchor_error(TokenError::InvalidLockTier));
+    let err = lock(&mut fixture, &pool, &alice, alice_tokens, 1, 0).unwrap_err();
+    assert_eq!(err, anchor_error(TokenError::InvalidAmount));
+    let err = lock(&mut fixture, &pool, &alice, alice_tokens, 1, 101).unwrap_err();
+    assert_eq!(err, anchor_error(TokenError::InsufficientBalance));
+}
+
+// ===== EMISSIONS =====
+
+fn emission_accounts(fixture: &Fixture, rewards_vault: Pubkey) -> accounts::InitializeEmissionSchedule {
+    accounts::InitializeEmissionSchedule {
+        token_info: fixture.token_info,
+        mint: fixture.mint,
+        emission_schedule: pda(&[b"emission", fixture.mint.as_ref()]),
+        rewards_vault,
+        mint_signer: fixture.mint_signer(),
+        mint_authority: fixture.authority.pubkey(),
+        token_program: spl_token::ID,
+        system_program: system_program::ID,
+    }
+}
+
+fn mint_epoch(fixture: &mut Fixture, rewards_vault: Pubkey) -> Result<(), ProgramError> {
+    let ix = ix(
+        accounts::MintEpoch {
+            token_info: fixture.token_info,
+            mint: fixture.mint,
+            emission_schedule: pda(&[b"emission", fixture.mint.as_ref()]),
+            rewards_vault,
+            mint_signer: fixture.mint_signer(),
+            token_program: spl_token::ID,
+            protocol_status: fixture.protocol_status,
+            protocol_stats: fixture.protocol_stats,
+            stats_reporter: vectai_protocol::stats_reporter(&vectai_token::ID),
+            protocol_program: vectai_protocol::ID,
+        },
+        instruction::MintEpoch {},
+    );
+    fixture.test.process_instruction(ix, &[])
+}
+
+#[test]
+fn emissions_mint_decaying_epochs_up_to_cap() {
+    let mut fixture = Fixture::new(1_500);
+    let vault = fixture.token_account(&Pubkey::new_unique());
+    let start = fixture.test.clock().unix_timestamp;
+    let ix = ix(
+        emission_accounts(&fixture, vault),
+        instruction::InitializeEmissionSchedule {
+            epoch_duration: DAY,
+            initial_epoch_amount: 1_000,
+            decay_bps: 1_000,
+        },
+    );
+    let authority = fixture.authority.insecure_clone();
+    fixture.test.process_instruction(ix, &[&authority]).unwrap();
+    assert_eq!(fixture.mint_state().mint_authority, COption::Some(fixture.mint_signer()));
+
+    let err = mint_epoch(&mut fixture, vault).unwrap_err();
+    assert_eq!(err, anchor_error(TokenError::EpochNotReady));
+
+    fixture.test.warp_forward(DAY);
+    mint_epoch(&mut fixture, vault).unwrap();
+    assert_eq!(fixture.test.token_balance(&vault), Some(1_000));
+    let schedule: EmissionSchedule = fixture.test.anchor_account(&pda(&[b"emission", fixture.mint.as_ref()])).unwrap();
+    assert_eq!(schedule.epochs_minted, 1);
+    assert_eq!(schedule.epoch_amount, 900);
+    assert_eq!(schedule.next_epoch_time, start + 2 * DAY);
+
+    let err = mint_epoch(&mut fixture, vault).unwrap_err();
+    assert_eq!(err, anchor_error(TokenError::EpochNotReady));
+
+    // Second epoch is clipped to the 500 left under max_supply
+    fixture.test.warp_forward(DAY);
+    mint_epoch(&mut fixture, vault).unwrap();
+    assert_eq!(fixture.test.token_balance(&vault), Some(1_500));
+    assert_eq!(fixture.token_info().minted, 1_500);
+
+    fixture.test.warp_forward(DAY);
+    let err = mint_epoch(&mut fixture, vault).unwrap_err();
+    assert_eq!(err, anchor_error(TokenError::ExceedsMaxSupply));
+}
+
+#[test]
+fn mint_to_signs_with_pda_after_emission_schedule() {
+    let mut fixture = Fixture::new(10_000);
+    let vault = fixture.token_account(&Pubkey::new_unique());
+    let ix = ix(
+        emission_accounts(&fixture, vault),
+        instruction::InitializeEmissionSchedule {
+            epoch_duration: DAY,
+            initial_epoch_amount: 1_000,
+            decay_bps: 0,
+        },
+    );
+    let authority = fixture.authority.insecure_clone();
+    fixture.test.process_instruction(ix, &[&authority]).unwrap();
+
+    let to = fixture.token_account(&Pubkey::new_unique());
+    fixture.mint_to(to, 250).unwrap();
+    assert_eq!(fixture.test.token_balance(&to), Some(250));
+}
+
+#[test]
+fn minter_mints_once_the_pda_holds_the_mint_authority() {
+    let mut fixture = Fixture::new(10_000);
+    let (minter, pauser) = (Keypair::new(), Keypair::new());
+    let role_registry = fixture
+        .test
+        .set_role_registry(&[(minter.pubkey(), Role::Minter), (pauser.pubkey(), Role::Pauser)]);
+    let to = fixture.token_account(&Pubkey::new_unique());
+    let minter_ix = |fixture: &Fixture, holder: &Keypair| {
+        let mut ix = fixture.mint_to_ix(to, holder.pubkey(), 100);
+        ix.accounts.last_mut().unwrap().pubkey = role_registry;
+        ix
+    };
+
+    // While the recorded authority still holds the SPL mint authority,
+    // only it can sign the mint
+    let err = fixture.test.process_instruction(minter_ix(&fixture, &minter), &[&minter]).unwrap_err();
+    assert_eq!(err, anchor_error(TokenError::UnauthorizedMintAuthority));
+
+    let vault = fixture.token_account(&Pubkey::new_unique());
+    let schedule = ix(
+        emission_accounts(&fixture, vault),
+        instruction::InitializeEmissionSchedule {
+            epoch_duration: DAY,
+            initial_epoch_amount: 1_000,
+            decay_bps: 0,
+        },
+    );
+    let authority = fixture.authority.insecure_clone();
+    fixture.test.process_instruction(schedule, &[&authority]).unwrap();
+
+    fixture.test.process_instruction(minter_ix(&fixture, &minter), &[&minter]).unwrap();
+    assert_eq!(fixture.test.token_balance(&to), Some(100));
+    assert_eq!(fixture.token_info().minted, 100);
+
+    // Another role does not mint
+    let err = fixture.test.process_instruction(minter_ix(&fixture, &pauser), &[&pauser]).unwrap_err();
+    assert_eq!(err, anchor_error(TokenError::UnauthorizedMintAuthority));
+}
+
+#[test]
+fn emission_schedule_rejects_invalid_parameters() {
+    let mut fixture = Fixture::new(1_000);
+    let vault = fixture.token_account(&Pubkey::new_unique());
+    let authority = fixture.authority.insecure_clone();
+    for (epoch_duration, initial_epoch_amount, decay_bps, expected) in [
+        (0, 1_000, 0, TokenError::InvalidEmissionSchedule),
+        (DAY, 0, 0, TokenError::InvalidAmount),
+        (DAY, 1_000, 10_000, TokenError::InvalidEmissionSchedule),
+    ] {
+        let ix = ix(
+            emission_accounts(&fixture, vault),
+            instruction::InitializeEmissionSchedule {
+                epoch_duration,
+                initial_epoch_amount,
+                decay_bps,
+            },
+        );
+        let err = fixture.test.process_instruction(ix, &[&authority]).unwrap_err();
+        assert_eq!(err, anchor_error(expected));
+    }
+
+    let impostor = funded(&mut fixture.test);
+    let mut accounts = emission_accounts(&fixture, vault);
+    accounts.mint_authority = impostor.pubkey();
+    let ix = ix(
+        accounts,
+        instruction::InitializeEmissionSchedule {
+            epoch_duration: DAY,
+            initial_epoch_amount: 1_000,
+            decay_bps: 0,
+        },
+    );
+    let err = fixture.test.process_instruction(ix, &[&impostor]).unwrap_err();
+    assert_eq!(err, anchor_error(TokenError::UnauthorizedMintAuthority));
+}
+
+// ===== CIRCULATING SUPPLY =====
+
+/// `get_circulating_supply` with `locks` passed as the registered accounts
+fn circulating_supply(fixture: &mut Fixture, locks: &[Pubkey]) -> Result<CirculatingSupply, ProgramError> {
+    let mut ix = ix(
+        accounts::GetCirculatingSupply {
+            token_info: fixture.token_info,
+            supply_locks: fixture.test.account(&supply_locks(&fixture.mint)).map(|_| supply_locks(&fixture.mint)),
+        },
+        instruction::GetCirculatingSupply {},
+    );
+    ix.accounts.extend(locks.iter().map(|lock| AccountMeta::new_readonly(*lock, false)));
+    fixture.test.process_instruction(ix, &[])?;
+    let (_, data) = fixture.test.return_data().expect("return data");
+    Ok(CirculatingSupply::deserialize(&mut data.as_slice()).unwrap())
+}
+
+#[test]
+fn circulating_supply_nets_burns_and_registered_locks() {
+    let mut fixture = Fixture::new(1_000_000);
+    let (vesting, escrow, holder) = (
+        fixture.token_account(&Pubkey::new_unique()),
+        fixture.token_account(&Pubkey::new_unique()),
+        fixture.token_account(&Pubkey::new_unique()),
+    );
+    for (account, amount) in [(vesting, 300_000), (escrow, 50_000), (holder, 100_000)] {
+        fixture.mint_to(account, amount).unwrap();
+    }
+    let mut token_info = fixture.token_info();
+    token_info.burned = 20_000;
+    fixture.test.set_anchor_account(fixture.token_info, &token_info);
+
+    // Nothing registered yet: only burns come off
+    let supply = circulating_supply(&mut fixture, &[]).unwrap();
+    assert_eq!((supply.minted, supply.circulating), (450_000, 430_000));
+
+    // Registration is admin-only, so the registry is written directly
+    let (address, bump) = Pubkey::find_program_address(&[b"supply-locks", fixture.mint.as_ref()], &vectai_token::ID);
+    let mut registry = SupplyLocks {
+        mint: fixture.mint,
+        locks: Vec::new(),
+        bump,
+    };
+    registry.register(vesting, SupplyLockKind::Vesting).unwrap();
+    registry.register(escrow, SupplyLockKind::Escrow).unwrap();
+    fixture.test.set_anchor_account(address, &registry);
+
+    assert_eq!(
+        circulating_supply(&mut fixture, &[vesting, escrow]).unwrap(),
+        CirculatingSupply {
+            minted: 450_000,
+            burned: 20_000,
+            vesting_locked: 300_000,
+            escrow_locked: 50_000,
+            circulating: 80_000,
+        }
+    );
+    // Every registered account, in registry order
+    for locks in [&[vesting][..], &[escrow, vesting], &[vesting, holder]] {
+        assert_eq!(
+            circulating_supply(&mut fixture, locks).unwrap_err(),
+            anchor_error(TokenError::SupplyLockMismatch)
+        );
+    }
+}
+
+#[test]
+fn supply_lock_registry_keeps_one_entry_per_account() {
+    let mut registry = SupplyLocks {
+        mint: Pubkey::new_unique(),
+        locks: Vec::new(),
+        bump: 0,
+    };
+    let account = Pubkey::new_unique();
+    registry.register(account, SupplyLockKind::Vesting).unwrap();
+    registry.register(account, SupplyLockKind::Escrow).unwrap();
+    assert_eq!(
+        registry.locks,
+        vec![SupplyLock {
+            token_account: account,
+            kind: SupplyLockKind::Escrow,
+        }]
+    );
+
+    while registry.locks.len() < vectai_token::MAX_SUPPLY_LOCKS {
+        registry.register(Pubkey::new_unique(), SupplyLockKind::Escrow).unwrap();
+    }
+    assert!(registry.register(Pubkey::new_unique(), SupplyLockKind::Escrow).is_err());
+}
+
+#[test]
+fn register_supply_lock_requires_admin() {
+    let mut fixture = Fixture::new(1_000);
+    let impostor = funded(&mut fixture.test);
+    let token_account = fixture.token_account(&impostor.pubkey());
+
+    let ix = ix(
+        accounts::RegisterSupplyLock {
+            token_info: fixture.token_info,
+            supply_locks: supply_locks(&fixture.mint),
+            token_account,
+            admin: impostor.pubkey(),
+            system_program: system_program::ID,
+        },
+        instruction::RegisterSupplyLock {
+            kind: SupplyLockKind::Vesting,
+        },
+    );
+    let err = fixture.test.process_instruction(ix, &[&impostor]).unwrap_err();
+    assert_eq!(err, anchor_error(TokenError::UnauthorizedAdmin));
+    assert!(fixture.test.account(&supply_locks(&fixture.mint)).is_none());
+}
+
+// ===== COMPLIANCE CLAWBACK =====
+
+/// A Token-2022 mint created through `initialize_clawback_token`
+struct Clawback {
+    fixture: Fixture,
+    compliance: Keypair,
+    permanent_delegate: Pubkey,
+}
+
+impl Clawback {
+    fn new() -> Self {
+        let mut test = ProgramTest::new();
+        let protocol_status = test.set_protocol_status(false, FeeConfig::INITIAL);
+        let protocol_stats = test.set_protocol_stats();
+        let authority = funded(&mut test);
+        let compliance = funded(&mut test);
+        let mint = Keypair::new();
+        let token_info = pda(&[b"token-info", mint.pubkey().as_ref()]);
+        let permanent_delegate = pda(&[b"permanent-delegate", mint.pubkey().as_ref()]);
+        let payer = test.payer().pubkey();
+        test.process_instruction(
+            ix(
+                accounts::InitializeClawbackToken {
+                    mint: mint.pubkey(),
+                    token_info,
+                    permanent_delegate,
+                    mint_authority: authority.pubkey(),
+                    payer,
+                    token_program: spl_token_2022::ID,
+                    system_program: system_program::ID,
+                },
+                instruction::InitializeClawbackToken {
+                    max_supply: 1_000,
+                    decimals: DECIMALS,
+                    compliance_authority: compliance.pubkey(),
+                },
+            ),
+            &[&mint, &authority],
+        )
+        .unwrap();
+        Clawback {
+            fixture: Fixture {
+                test,
+                authority,
+                mint: mint.pubkey(),
+                token_info,
+                protocol_status,
+                protocol_stats,
+            },
+            compliance,
+            permanent_delegate,
+        }
+    }
+
+    /// Token-2022 account funded through `mint_to`
+    fn funded_account(&mut self, amount: u64) -> Pubkey {
+        let account = self.fixture.token_account(&Pubkey::new_unique());
+        let mut ix = self.fixture.mint_to_ix(account, self.fixture.authority.pubkey(), amount);
+        ix.accounts[5].pubkey = spl_token_2022::ID; // token_program
+        let authority = self.fixture.authority.insecure_clone();
+        self.fixture.test.process_instruction(ix, &[&authority]).unwrap();
+        account
+    }
+
+    fn flag_address(&self, account: &Pubkey) -> Pubkey {
+        pda(&[b"flagged", self.fixture.mint.as_ref(), account.as_ref()])
+    }
+
+    fn flag(&mut self, account: Pubkey, authority: &Keypair, reason_code: u16) -> Result<(), ProgramError> {
+        let ix = ix(
+            accounts::FlagAccount {
+                token_info: self.fixture.token_info,
+                flagged_token_account: account,
+                flag: self.flag_address(&account),
+                compliance_authority: authority.pubkey(),
+                system_program: system_program::ID,
+            },
+            instruction::FlagAccount { reason_code },
+        );
+        self.fixture.test.process_instruction(ix, &[authority])
+    }
+
+    fn clawback(&mut self, from: Pubkey, to: Pubkey, authority: &Keypair, amount: u64, reason_code: u16) -> Result<(), ProgramError> {
+        let ix = ix(
+            accounts::Clawback {
+                token_info: self.fixture.token_info,
+                mint: self.fixture.mint,
+                flag: self.flag_address(&from),
+                from,
+                to,
+                permanent_delegate: self.permanent_delegate,
+                compliance_authority: authority.pubkey(),
+                token_program: spl_token_2022::ID,
+            },
+            instruction::Clawback { amount, reason_code },
+        );
+        self.fixture.test.process_instruction(ix, &[authority])
+    }
+}
+
+#[test]
+fn clawback_token_installs_permanent_delegate() {
+    let clawback = Clawback::new();
+    let info = clawback.fixture.token_info();
+    assert!(info.clawback_enabled);
+    assert_eq!(info.compliance_authority, clawback.compliance.pubkey());
+
+    let mint = clawback.fixture.test.account(&clawback.fixture.mint).unwrap();
+    assert_eq!(mint.owner, spl_token_2022::ID);
+    let state = StateWithExtensions::<spl_token_2022::state::Mint>::unpack(&mint.data).unwrap();
+    assert_eq!(state.base.decimals, DECIMALS);
+    let delegate = state
+        .get_extension::<spl_token_2022::extension::permanent_delegate::PermanentDelegate>()
+        .unwrap();
+    assert_eq!(Option::<Pubkey>::from(delegate.delegate), Some(clawback.permanent_delegate));
+}
+
+#[test]
+fn clawback_token_requires_compliance_authority() {
+    let mut test = ProgramTest::new();
+    let authority = funded(&mut test);
+    let mint = Keypair::new();
+    let payer = test.payer().pubkey();
+    let ix = ix(
+        accounts::InitializeClawbackToken {
+            mint: mint.pubkey(),
+            token_info: pda(&[b"token-info", mint.pubkey().as_ref()]),
+            permanent_delegate: pda(&[b"permanent-delegate", mint.pubkey().as_ref()]),
+            mint_authority: authority.pubkey(),
+            payer,
+            token_program: spl_token_2022::ID,
+            system_program: system_program::ID,
+        },
+        instruction::InitializeClawbackToken {
+            max_supply: 1_000,
+            decimals: DECIMALS,
+            compliance_authority: Pubkey::default(),
+        },
+    );
+    let err = test.process_instruction(ix, &[&mint, &authority]).unwrap_err();
+    assert_eq!(err, anchor_error(TokenError::InvalidComplianceAuthority));
+}
+
+#[test]
+fn clawback_recovers_tokens_from_flagged_account() {
+    let mut clawback = Clawback::new();
+    let compliance = clawback.compliance.insecure_clone();
+    let from = clawback.funded_account(400);
+    let to = clawback.fixture.token_account(&compliance.pubkey());
+
+    // Unflagged accounts cannot be touched
+    let err = clawback.clawback(from, to, &compliance, 100, 7).unwrap_err();
+    assert_eq!(err, anchor_error(anchor_lang::error::ErrorCode::AccountNotInitialized));
+
+    clawback.flag(from, &compliance, 7).unwrap();
+    let flag: FlaggedAccount = clawback.fixture.test.anchor_account(&clawback.flag_address(&from)).unwrap();
+    assert_eq!(flag.token_account, from);
+    assert_eq!(flag.reason_code, 7);
+    assert_eq!(flag.flagged_at, clawback.fixture.test.clock().unix_timestamp);
+
+    clawback.clawback(from, to, &compliance, 150, 7).unwrap();
+    assert_eq!(clawback.fixture.test.token_balance(&from), Some(250));
+    assert_eq!(clawback.fixture.test.token_balance(&to), Some(150));
+
+    let err = clawback.clawback(from, to, &compliance, 251, 7).unwrap_err();
+    assert_eq!(err, anchor_error(TokenError::InsufficientBalance));
+    let err = clawback.clawback(from, to, &compliance, 100, 0).unwrap_err();
+    assert_eq!(err, anchor_error(TokenError::MissingReasonCode));
+    let impostor = funded(&mut clawback.fixture.test);
+    let err = clawback.clawback(from, to, &impostor, 100, 7).unwrap_err();
+    assert_eq!(err, anchor_error(TokenError::UnauthorizedComplianceAuthority));
+}
+
+#[test]
+fn flag_requires_compliance_authority_and_reason() {
+    let mut clawback = Clawback::new();
+    let compliance = clawback.compliance.insecure_clone();
+    let account = clawback.funded_account(10);
+
+    let err = clawback.flag(account, &compliance, 0).unwrap_err();
+    assert_eq!(err, anchor_error(TokenError::MissingReasonCode));
+    let impostor = funded(&mut clawback.fixture.test);
+    let err = clawback.flag(account, &impostor, 1).unwrap_err();
+    assert_eq!(err, anchor_error(TokenError::UnauthorizedComplianceAuthority));
+    assert!(clawback.fixture.test.account(&clawback.flag_address(&account)).is_none());
+}
+
+#[test]
+fn unflag_closes_flag_account() {
+    let mut clawback = Clawback::new();
+    let compliance = clawback.compliance.insecure_clone();
+    let account = clawback.funded_account(10);
+    clawback.flag(account, &compliance, 3).unwrap();
+    let flag = clawback.flag_address(&account);
+    let rent = clawback.fixture.test.lamports(&flag);
+    let before = clawback.fixture.test.lamports(&compliance.pubkey());
+
+    let ix = ix(
+        accounts::UnflagAccount {
+            token_info: clawback.fixture.token_info,
+            flag,
+            compliance_authority: compliance.pubkey(),
+        },
+        instruction::UnflagAccount {},
+    );
+    clawback.fixture.test.process_instruction(ix, &[&compliance]).unwrap();
+    assert!(clawback.fixture.test.account(&flag).is_none());
+    assert_eq!(clawback.fixture.test.lamports(&compliance.pubkey()), before + rent);
+}
+
+#[test]
+fn renounce_clawback_disables_it_for_good() {
+    let mut clawback = Clawback::new();
+    let compliance = clawback.compliance.insecure_clone();
+    let from = clawback.funded_account(100);
+    let to = clawback.fixture.token_account(&compliance.pubkey());
+    clawback.flag(from, &compliance, 1).unwrap();
+
+    let ix = ix(
+        accounts::RenounceClawback {
+            token_info: clawback.fixture.token_info,
+            mint: clawback.fixture.mint,
+            permanent_delegate: clawback.permanent_delegate,
+            compliance_authority: compliance.pubkey(),
+            token_program: spl_token_2022::ID,
+        },
+        instruction::RenounceClawback {},
+    );
+    clawback.fixture.test.process_instruction(ix, &[&compliance]).unwrap();
+
+    let info = clawback.fixture.token_info();
+    assert!(!info.clawback_enabled);
+    assert_eq!(info.compliance_authority, Pubkey::default());
+    let mint = clawback.fixture.test.account(&clawback.fixture.mint).unwrap();
+    let state = StateWithExtensions::<spl_token_2022::state::Mint>::unpack(&mint.data).unwrap();
+    let delegate = state
+        .get_extension::<spl_token_2022::extension::permanent_delegate::PermanentDelegate>()
+        .unwrap();
+    assert_eq!(Option::<Pubkey>::from(delegate.delegate), None);
+
+    let err = clawback.clawback(from, to, &compliance, 10, 1).unwrap_err();
+    assert_eq!(err, anchor_error(TokenError::ClawbackDisabled));
+}
+
+// ===== INTEREST-BEARING =====
+
+fn initialize_interest_bearing(test: &mut ProgramTest, authority: &Keypair, mint: &Keypair, rate_bps: i16) -> Result<(), ProgramError> {
+    let payer = test.payer().pubkey();
+    let ix = ix(
+        accounts::InitializeInterestBearingToken {
+            mint: mint.pubkey(),
+            token_info: pda(&[b"token-info", mint.pubkey().as_ref()]),
+            rate_authority: pda(&[b"rate-authority", mint.pubkey().as_ref()]),
+            mint_authority: authority.pubkey(),
+            payer,
+            token_program: spl_token_2022::ID,
+            system_program: system_program::ID,
+        },
+        instruction::InitializeInterestBearingToken {
+            max_supply: 1_000,
+            decimals: DECIMALS,
+            rate_bps,
+        },
+    );
+    test.process_instruction(ix, &[mint, authority])
+}
+
+#[test]
+fn interest_bearing_token_sets_rate() {
+    let mut test = ProgramTest::new();
+    let authority = funded(&mut test);
+    let mint = Keypair::new();
+    initialize_interest_bearing(&mut test, &authority, &mint, 500).unwrap();
+
+    let info: TokenInfo = test.anchor_account(&pda(&[b"token-info", mint.pubkey().as_ref()])).unwrap();
+    assert_eq!(info.mint, mint.pubkey());
+    assert_eq!(info.max_supply, 1_000);
+    let account = test.account(&mint.pubkey()).unwrap();
+    let state = StateWithExtensions::<spl_token_2022::state::Mint>::unpack(&account.data).unwrap();
+    let config = state.get_extension::<InterestBearingConfig>().unwrap();
+    assert_eq!(i16::from(config.current_rate), 500);
+    assert_eq!(
+        Option::<Pubkey>::from(config.rate_authority),
+        Some(pda(&[b"rate-authority", mint.pubkey().as_ref()]))
+    );
+}
+
+#[test]
+fn interest_rate_is_bounded_and_timelocked() {
+    let mut test = ProgramTest::new();
+    let authority = funded(&mut test);
+    for rate_bps in [-1, vectai_token::MAX_INTEREST_RATE_BPS + 1] {
+        let err = initialize_interest_bearing(&mut test, &authority, &Keypair::new(), rate_bps).unwrap_err();
+        assert_eq!(err, anchor_error(TokenError::InvalidInterestRate));
+    }
+
+    let mint = Keypair::new();
+    initialize_interest_bearing(&mut test, &authority, &mint, 500).unwrap();
+    let ix = ix(
+        accounts::SetInterestRate {
+            token_info: pda(&[b"token-info", mint.pubkey().as_ref()]),
+            mint: mint.pubkey(),
+            rate_authority: pda(&[b"rate-authority", mint.pubkey().as_ref()]),
+            admin: authority.pubkey(),
+            token_program: spl_token_2022::ID,
+        },
+        instruction::SetInterestRate { rate_bps: 100 },
+    );
+    let err = test.process_instruction(ix, &[&authority]).unwrap_err();
+    assert_eq!(err, anchor_error(TokenError::UnauthorizedAdmin));
+}
+
+// ===== DUTCH AUCTION =====
+
+const AUCTION_ID: u64 = 1;
+const START_PRICE: u64 = 2_000_000; // 2 USDC per token
+const FLOOR_PRICE: u64 = 1_000_000;
+const DURATION: i64 = 1_000;
+
+struct Sale {
+    fixture: Fixture,
+    auction: Pubkey,
+    quote_mint: Pubkey,
+    proceeds: Pubkey,
+    start_time: i64,
+}
+
+impl Sale {
+    fn create(supply: u64) -> Result<Self, ProgramError> {
+        let mut fixture = Fixture::new(100_000_000);
+        let quote_mint = Pubkey::new_unique();
+        fixture.test.create_mint(quote_mint, &Pubkey::new_unique(), 6);
+        let proceeds = vectai_treasury::vault_address(&quote_mint);
+        fixture.test.create_token_account(proceeds, &quote_mint, &Pubkey::new_unique(), 0);
+        let start_time = fixture.test.clock().unix_timestamp + 60;
+        let auction = pda(&[b"auction", fixture.mint.as_ref(), &AUCTION_ID.to_le_bytes()]);
+        let ix = ix(
+            accounts::CreateAuction {
+                token_info: fixture.token_info,
+                mint: fixture.mint,
+                quote_mint,
+                auction,
+                mint_signer: fixture.mint_signer(),
+                mint_authority: fixture.authority.pubkey(),
+                token_program: spl_token::ID,
+                system_program: system_program::ID,
+            },
+            instruction::CreateAuction {
+                auction_id: AUCTION_ID,
+                start_price: START_PRICE,
+                floor_price: FLOOR_PRICE,
+                start_time,
+                duration: DURATION,
+                supply,
+            },
+        );
+        let authority = fixture.authority.insecure_clone();
+        fixture.test.process_instruction(ix, &[&authority])?;
+        Ok(Sale {
+            fixture,
+            auction,
+            quote_mint,
+            proceeds,
+            start_time,
+        })
+    }
+
+    fn buyer(&mut self, quote: u64) -> (Keypair, Pubkey, Pubkey) {
+        let buyer = funded(&mut self.fixture.test);
+        let quote_account = Pubkey::new_unique();
+        self.fixture.test.create_token_account(quote_account, &self.quote_mint, &buyer.pubkey(), quote);
+        let token_account = self.fixture.token_account(&buyer.pubkey());
+        (buyer, quote_account, token_account)
+    }
+
+    fn buy(&mut self, buyer: &(Keypair, Pubkey, Pubkey), amount: u64, max_price: u64) -> Result<(), ProgramError> {
+        let ix = ix(
+            accounts::BuyFromAuction {
+                token_info: self.fixture.token_info,
+                mint: self.fixture.mint,
+                auction: self.auction,
+                buyer_quote_account: buyer.1,
+                buyer_token_account: buyer.2,
+                proceeds_account: self.proceeds,
+                mint_signer: self.fixture.mint_signer(),
+                buyer: buyer.0.pubkey(),
+                token_program: spl_token::ID,
+                protocol_status: self.fixture.protocol_status,
+                protocol_stats: self.fixture.protocol_stats,
+                stats_reporter: vectai_protocol::stats_reporter(&vectai_token::ID),
+                protocol_program: vectai_protocol::ID,
+            },
+            instruction::BuyFromAuction { amount, max_price },
+        );
+        self.fixture.test.process_instruction(ix, &[&buyer.0])
+    }
+}
+
+#[test]
+fn create_auction_takes_mint_authority() {
+    let sale = Sale::create(10_000_000).unwrap();
+    let auction: Auction = sale.fixture.test.anchor_account(&sale.auction).unwrap();
+    assert_eq!(auction.mint, sale.fixture.mint);
+    assert_eq!(auction.quote_mint, sale.quote_mint);
+    assert_eq!(auction.start_time, sale.start_time);
+    assert_eq!(auction.end_time, sale.start_time + DURATION);
+    assert_eq!(auction.supply, 10_000_000);
+    assert_eq!(auction.sold, 0);
+    assert_eq!(sale.fixture.mint_state().mint_authority, COption::Some(sale.fixture.mint_signer()));
+}
+
+#[test]
+fn create_auction_rejects_invalid_parameters() {
+    let err = Sale::create(0).err().unwrap();
+    assert_eq!(err, anchor_error(TokenError::InvalidAmount));
+    let err = Sale::create(100_000_001).err().unwrap();
+    assert_eq!(err, anchor_error(TokenError::ExceedsMaxSupply));
+}
+
+#[test]
+fn buy_from_auction_pays_current_price() {
+    let mut sale = Sale::create(10_000_000).unwrap();
+    let buyer = sale.buyer(10_000_000);
+
+    let err = sale.buy(&buyer, 1_000_000, START_PRICE).unwrap_err();
+    assert_eq!(err, anchor_error(TokenError::AuctionNotLive));
+
+    // Halfway through, the price is halfway to the floor
+    sale.fixture.test.warp_to_timestamp(sale.start_time + DURATION / 2);
+    let err = sale.buy(&buyer, 2_000_000, 1_499_999).unwrap_err();
+    assert_eq!(err, anchor_error(TokenError::AuctionPriceAboveLimit));
+    sale.buy(&buyer, 2_000_000, 1_500_000).unwrap();
+
+    assert_eq!(sale.fixture.test.token_balance(&buyer.1), Some(7_000_000));
+    assert_eq!(sale.fixture.test.token_balance(&buyer.2), Some(2_000_000));
+    assert_eq!(sale.fixture.test.token_balance(&sale.proceeds), Some(3_000_000));
+    let auction: Auction = sale.fixture.test.anchor_account(&sale.auction).unwrap();
+    assert_eq!(auction.sold, 2_000_000);
+    assert_eq!(auction.proceeds, 3_000_000);
+    assert_eq!(sale.fixture.token_info().minted, 2_000_000);
+
+    let err = sale.buy(&buyer, 8_000_001, START_PRICE).unwrap_err();
+    assert_eq!(err, anchor_error(TokenError::AuctionSoldOut));
+    let err = sale.buy(&buyer, 5_000_000, START_PRICE).unwrap_err();
+    assert_eq!(err, anchor_error(TokenError::InsufficientBalance));
+
+    sale.fixture.test.warp_to_timestamp(sale.start_time + DURATION);
+    let err = sale.buy(&buyer, 1_000_000, START_PRICE).unwrap_err();
+    assert_eq!(err, anchor_error(TokenError::AuctionNotLive));
+}
+
+#[test]
+fn end_auction_closes_it() {
+    let mut sale = Sale::create(10_000_000).unwrap();
+    let authority = sale.fixture.authority.insecure_clone();
+    let accounts = |mint_authority: Pubkey| accounts::EndAuction {
+        token_info: sale.fixture.token_info,
+        auction: sale.auction,
+        mint_authority,
+    };
+
+    let impostor = funded(&mut sale.fixture.test);
+    let forged = accounts(impostor.pubkey());
+    let err = sale
+        .fixture
+        .test
+        .process_instruction(ix(forged, instruction::EndAuction {}), &[&impostor])
+        .unwrap_err();
+    assert_eq!(err, anchor_error(TokenError::UnauthorizedMintAuthority));
+
+    let accounts = accounts(authority.pubkey());
+    sale.fixture
+        .test
+        .process_instruction(ix(accounts, instruction::EndAuction {}), &[&authority])
+        .unwrap();
+    assert!(sale.fixture.test.account(&sale.auction).is_none());
+}
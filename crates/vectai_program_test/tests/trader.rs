@@ -0,0 +1,3134 @@
+//! `vectai_trader` instructions.
+//!
+//! Swaps settle against the harness's constant-product mock of the Raydium
+//! AMM, or its mock of Jupiter's `route`, and prices come from the mock
+//! oracle (45000, expo 0). Paths that CPI into Drift or account
+//! compression are covered up to the CPI.
+
+use anchor_lang::{
+    solana_program::{
+        account_info::AccountInfo, clock::Clock, entrypoint::ProgramResult, program::invoke_signed, system_program,
+        sysvar,
+    },
+    InstructionData, ToAccountMetas,
+};
+use solana_sdk::{
+    instruction::{AccountMeta, Instruction},
+    native_token::LAMPORTS_PER_SOL,
+    program_error::ProgramError,
+    pubkey::Pubkey,
+    signature::{Keypair, Signer},
+};
+use vectai_math::condition::{OP_AND, OP_PRICE_ABOVE, OP_PRICE_BELOW};
+use vectai_oracle::PriceSource;
+use vectai_program_test::{
+    anchor_error,
+    mock_amm::{self, AmmBehavior, Pool},
+    mock_drift::{self, PerpPosition},
+    mock_jupiter::{self, JupiterPool},
+    trader::{subscription_trader_address, trader_address, TradingNetwork, TRADE_COOLDOWN},
+    ProgramTest,
+};
+use vectai_protocol::{FeeConfig, ProtocolStats, Role};
+use vectai_referral::ReferralCode;
+use vectai_trader::{
+    accounts, instruction, AuditAction, AuditEntry, AuditEntryAppended, AuditLog, DrawdownLimitHit, DynamicSlippage,
+    FollowerConfig,
+    HedgeConfig, PnlRealized, PointsConfig, ProgramStrategyCreated, PointsLedger, StrategyCompleted, StrategyListing, Subscription, TradeCompounded, TradeExecuted,
+    TakeProfitFilled, TakeProfitLevel, TradeHistory, TradePreview, TradeRecord, TraderConfig, TraderError, TradingSchedule,
+    VolatilityLimitHit, JitoTipPaid,
+    drift_perp_short, ACCOUNT_COMPRESSION_PROGRAM, DRIFT_PROGRAM, JITO_TIP_ACCOUNTS, MAX_PRICE_AGE_SLOTS, NOOP_PROGRAM,
+    VOLATILITY_SAMPLE_SECS,
+};
+
+const ORACLE_PRICE: i64 = 45_000;
+const PRICE_THRESHOLD: i64 = 40_000;
+const SWAP_AMOUNT: u64 = 1_000_000;
+const SLIPPAGE_BPS: u64 = 200;
+const SOURCE_BALANCE: u64 = 100_000_000;
+const POOL_RESERVE: u64 = 1_000_000_000_000;
+const DAY: i64 = 86_400;
+
+/// `vectai_trader`'s VECTAI mint placeholder (the system program id); no
+/// mint account exists there, so VECTAI token accounts are written directly
+const VECTAI_MINT: Pubkey = system_program::ID;
+
+fn pda(seeds: &[&[u8]]) -> Pubkey {
+    Pubkey::find_program_address(seeds, &vectai_trader::ID).0
+}
+
+fn ix(accounts: impl ToAccountMetas, data: impl InstructionData) -> Instruction {
+    Instruction {
+        program_id: vectai_trader::ID,
+        accounts: accounts.to_account_metas(None),
+        data: data.data(),
+    }
+}
+
+fn funded(test: &mut ProgramTest) -> Keypair {
+    let keypair = Keypair::new();
+    test.airdrop(&keypair.pubkey(), 10 * LAMPORTS_PER_SOL);
+    keypair
+}
+
+fn initialize_trader(
+    test: &mut ProgramTest,
+    authority: &Keypair,
+    referral_code: Option<Pubkey>,
+    price_threshold: i64,
+    swap_amount: u64,
+    slippage_tolerance: u64,
+) -> Result<(), ProgramError> {
+    let ix = ix(
+        accounts::InitializeTrader {
+            trader_config: trader_address(&authority.pubkey()),
+            referral_code,
+            authority: authority.pubkey(),
+            system_program: system_program::ID,
+        },
+        instruction::InitializeTrader {
+            price_threshold,
+            swap_amount,
+            slippage_tolerance,
+        },
+    );
+    test.process_instruction(ix, &[authority])
+}
+
+/// A USDC/WSOL network with a deep 1:1 pool, and a trader with an audit
+/// log whose authority holds `SOURCE_BALANCE` USDC
+struct Fixture {
+    test: ProgramTest,
+    network: TradingNetwork,
+    authority: Keypair,
+    trader_config: Pubkey,
+    audit_log: Pubkey,
+    source: Pubkey,
+    destination: Pubkey,
+}
+
+impl Fixture {
+    fn new() -> Self {
+        let mut test = ProgramTest::new();
+        let network = test.create_trading_network(POOL_RESERVE);
+        let authority = funded(&mut test);
+        initialize_trader(&mut test, &authority, None, PRICE_THRESHOLD, SWAP_AMOUNT, SLIPPAGE_BPS).unwrap();
+        let trader_config = trader_address(&authority.pubkey());
+        let audit_log = pda(&[b"audit-log", trader_config.as_ref()]);
+        test.process_instruction(
+            ix(
+                accounts::InitializeAuditLog {
+                    trader_config,
+                    audit_log,
+                    authority: authority.pubkey(),
+                    system_program: system_program::ID,
+                },
+                instruction::InitializeAuditLog {},
+            ),
+            &[&authority],
+        )
+        .unwrap();
+
+        let owner = authority.pubkey();
+        let source = Pubkey::new_unique();
+        test.create_token_account(source, &network.usdc, &owner, SOURCE_BALANCE);
+        let destination = Pubkey::new_unique();
+        test.create_token_account(destination, &network.wsol, &owner, 0);
+        Fixture {
+            test,
+            network,
+            authority,
+            trader_config,
+            audit_log,
+            source,
+            destination,
+        }
+    }
+
+    fn token_account(&mut self, mint: &Pubkey, owner: &Pubkey, amount: u64) -> Pubkey {
+        let address = Pubkey::new_unique();
+        self.test.create_token_account(address, mint, owner, amount);
+        address
+    }
+
+    fn balance(&self, address: &Pubkey) -> u64 {
+        self.test.token_balance(address).unwrap()
+    }
+
+    fn trader(&self) -> TraderConfig {
+        self.test.anchor_account(&self.trader_config).unwrap()
+    }
+
+    fn update_trader(&mut self, update: impl FnOnce(&mut TraderConfig)) {
+        let mut trader = self.trader();
+        update(&mut trader);
+        self.test.set_anchor_account(self.trader_config, &trader);
+    }
+
+    fn audit_log(&self) -> AuditLog {
+        self.test.anchor_account(&self.audit_log).unwrap()
+    }
+
+    fn trade_accounts(&self, authority: &Pubkey, source: Pubkey, destination: Pubkey) -> accounts::ExecuteTrade {
+        self.network.execute_trade_accounts(authority, source, destination)
+    }
+
+    fn execute_trade_with(&mut self, accounts: accounts::ExecuteTrade, amount: u64, signer: &Keypair) -> Result<(), ProgramError> {
+        self.test
+            .process_instruction(ix(accounts, instruction::ExecuteTrade { amount, dry_run: false, nonce: None }), &[signer])
+    }
+
+    fn execute_trade(&mut self, amount: u64) -> Result<(), ProgramError> {
+        let accounts = self.trade_accounts(&self.authority.pubkey(), self.source, self.destination);
+        let authority = self.authority.insecure_clone();
+        self.execute_trade_with(accounts, amount, &authority)
+    }
+
+    fn execute_trade_nonce(&mut self, nonce: Option<u64>, dry_run: bool) -> Result<(), ProgramError> {
+        let accounts = self.trade_accounts(&self.authority.pubkey(), self.source, self.destination);
+        let authority = self.authority.insecure_clone();
+        let data = instruction::ExecuteTrade { amount: SWAP_AMOUNT, dry_run, nonce };
+        self.test.process_instruction(ix(accounts, data), &[&authority])
+    }
+
+    fn dry_run(&mut self, amount: u64) -> Result<(), ProgramError> {
+        let accounts = self.trade_accounts(&self.authority.pubkey(), self.source, self.destination);
+        let authority = self.authority.insecure_clone();
+        self.test
+            .process_instruction(ix(accounts, instruction::ExecuteTrade { amount, dry_run: true, nonce: None }), &[&authority])
+    }
+
+    fn configure_with(&mut self, data: impl InstructionData, signer: &Keypair) -> Result<(), ProgramError> {
+        let ix = ix(
+            accounts::ConfigureTrader {
+                trader_config: trader_address(&signer.pubkey()),
+                audit_log: self.audit_log,
+                authority: signer.pubkey(),
+            },
+            data,
+        );
+        self.test.process_instruction(ix, &[signer])
+    }
+
+    fn configure(&mut self, data: impl InstructionData) -> Result<(), ProgramError> {
+        let authority = self.authority.insecure_clone();
+        self.configure_with(data, &authority)
+    }
+
+    /// Leave `source` holding only `amount` and `destination` empty, as
+    /// losing trades rather than a withdrawal would: the last drawdown
+    /// mark sees the same balances
+    fn lose_all_but(&mut self, amount: u64) {
+        let owner = self.authority.pubkey();
+        self.test.create_token_account(self.source, &self.network.usdc, &owner, amount);
+        self.test.create_token_account(self.destination, &self.network.wsol, &owner, 0);
+        self.update_trader(|trader| {
+            trader.marked_source_balance = amount;
+            trader.marked_destination_balance = 0;
+        });
+    }
+}
+
+// ===== CORE TRADER =====
+
+#[test]
+fn initialize_trader_records_config() {
+    let fixture = Fixture::new();
+
+    let trader = fixture.trader();
+    assert_eq!(trader.authority, fixture.authority.pubkey());
+    assert_eq!(trader.price_threshold, PRICE_THRESHOLD);
+    assert_eq!(trader.swap_amount, SWAP_AMOUNT);
+    assert_eq!(trader.slippage_tolerance, SLIPPAGE_BPS);
+    assert_eq!(trader.total_swaps, 0);
+    assert!(trader.is_active);
+    assert!(!trader.auto_compound);
+    assert_eq!(trader.subscription_expires_at, 0);
+    assert_eq!(trader.referrer, Pubkey::default());
+    assert!(trader.condition.is_empty());
+
+    let audit_log = fixture.audit_log();
+    assert_eq!(audit_log.trader, fixture.trader_config);
+    assert_eq!(audit_log.head, [0u8; 32]);
+    assert_eq!(audit_log.entry_count, 0);
+}
+
+#[test]
+fn initialize_trader_rejects_invalid_parameters() {
+    let mut test = ProgramTest::new();
+    let authority = funded(&mut test);
+    let invalid = anchor_error(TraderError::InvalidInput);
+
+    for (price_threshold, swap_amount, slippage) in [
+        (0, SWAP_AMOUNT, SLIPPAGE_BPS),
+        (1_000_000_000_000, SWAP_AMOUNT, SLIPPAGE_BPS),
+        (PRICE_THRESHOLD, 0, SLIPPAGE_BPS),
+        (PRICE_THRESHOLD, 1_000_000_000_001, SLIPPAGE_BPS),
+        (PRICE_THRESHOLD, SWAP_AMOUNT, 1_001),
+    ] {
+        let err = initialize_trader(&mut test, &authority, None, price_threshold, swap_amount, slippage).unwrap_err();
+        assert_eq!(err, invalid);
+    }
+    assert!(test.account(&trader_address(&authority.pubkey())).is_none());
+}
+
+#[test]
+fn initialize_trader_records_referrer() {
+    let mut test = ProgramTest::new();
+    let authority = funded(&mut test);
+    let code = Pubkey::new_unique();
+    let referral = |owner| ReferralCode {
+        owner,
+        code: "VECT".to_string(),
+        created_at: 0,
+        bump: 255,
+    };
+
+    // No self-referral
+    test.set_anchor_account(code, &referral(authority.pubkey()));
+    let err = initialize_trader(&mut test, &authority, Some(code), PRICE_THRESHOLD, SWAP_AMOUNT, SLIPPAGE_BPS)
+        .unwrap_err();
+    assert_eq!(err, anchor_error(TraderError::InvalidReferral));
+
+    test.set_anchor_account(code, &referral(Pubkey::new_unique()));
+    initialize_trader(&mut test, &authority, Some(code), PRICE_THRESHOLD, SWAP_AMOUNT, SLIPPAGE_BPS).unwrap();
+    let trader: TraderConfig = test.anchor_account(&trader_address(&authority.pubkey())).unwrap();
+    assert_eq!(trader.referrer, code);
+}
+
+#[test]
+fn execute_trade_swaps_through_amm() {
+    let mut fixture = Fixture::new();
+    let now = fixture.test.clock().unix_timestamp;
+
+    fixture.execute_trade(SWAP_AMOUNT).unwrap();
+
+    let out = mock_amm::quote(SWAP_AMOUNT, POOL_RESERVE, POOL_RESERVE);
+    assert_eq!(fixture.balance(&fixture.source), SOURCE_BALANCE - SWAP_AMOUNT);
+    assert_eq!(fixture.balance(&fixture.destination), out);
+    assert_eq!(fixture.balance(&fixture.network.pool.pool_pc_token_account), POOL_RESERVE + SWAP_AMOUNT);
+    assert_eq!(fixture.balance(&fixture.network.pool.pool_coin_token_account), POOL_RESERVE - out);
+
+    let trader = fixture.trader();
+    assert_eq!(trader.total_swaps, 1);
+    assert_eq!(trader.last_swap_time, now);
+
+    let [event] = fixture.test.events::<TradeExecuted>().try_into().ok().unwrap();
+    assert_eq!(event.trader, fixture.trader_config);
+    assert_eq!(event.source_mint, fixture.network.usdc);
+    assert_eq!(event.destination_mint, fixture.network.wsol);
+    assert_eq!(event.amount_in, SWAP_AMOUNT);
+    assert_eq!(event.trade_fee, 0);
+    assert_eq!(event.oracle_price, ORACLE_PRICE);
+    assert_eq!(event.timestamp, now);
+}
+
+#[test]
+fn execute_trade_enforces_cooldown() {
+    let mut fixture = Fixture::new();
+    fixture.execute_trade(SWAP_AMOUNT).unwrap();
+
+    let rate_limited = anchor_error(TraderError::RateLimited);
+    assert_eq!(fixture.execute_trade(SWAP_AMOUNT).unwrap_err(), rate_limited);
+    fixture.test.warp_forward(TRADE_COOLDOWN - 1);
+    assert_eq!(fixture.execute_trade(SWAP_AMOUNT).unwrap_err(), rate_limited);
+
+    fixture.test.warp_forward(1);
+    fixture.execute_trade(SWAP_AMOUNT).unwrap();
+    assert_eq!(fixture.trader().total_swaps, 2);
+}
+
+#[test]
+fn execute_trade_nonce_executes_each_trigger_once() {
+    let mut fixture = Fixture::new();
+    fixture.execute_trade_nonce(Some(5), false).unwrap();
+    assert_eq!(fixture.trader().last_nonce, 5);
+
+    // A racing keeper with the same trigger, or an older one, is rejected
+    // ahead of the cooldown, even once it has passed
+    let already_used = anchor_error(TraderError::NonceAlreadyUsed);
+    assert_eq!(fixture.execute_trade_nonce(Some(5), false).unwrap_err(), already_used);
+    fixture.test.warp_forward(TRADE_COOLDOWN);
+    assert_eq!(fixture.execute_trade_nonce(Some(5), false).unwrap_err(), already_used);
+    assert_eq!(fixture.execute_trade_nonce(Some(4), true).unwrap_err(), already_used);
+
+    // Dry runs check the nonce without using it; trades without one keep it
+    fixture.execute_trade_nonce(Some(6), true).unwrap();
+    assert_eq!(fixture.trader().last_nonce, 5);
+    fixture.execute_trade_nonce(None, false).unwrap();
+    assert_eq!(fixture.trader().last_nonce, 5);
+    fixture.test.warp_forward(TRADE_COOLDOWN);
+    fixture.execute_trade_nonce(Some(6), false).unwrap();
+    let trader = fixture.trader();
+    assert_eq!((trader.total_swaps, trader.last_nonce), (3, 6));
+}
+
+#[test]
+fn execute_trade_rejects_invalid_requests() {
+    let mut fixture = Fixture::new();
+
+    assert_eq!(fixture.execute_trade(0).unwrap_err(), anchor_error(TraderError::InvalidSwapAmount));
+    assert_eq!(
+        fixture.execute_trade(SOURCE_BALANCE + 1).unwrap_err(),
+        anchor_error(TraderError::InsufficientBalance)
+    );
+
+    // Another signer against this trader
+    let other = funded(&mut fixture.test);
+    let mut accounts = fixture.trade_accounts(&other.pubkey(), fixture.source, fixture.destination);
+    accounts.trader_config = fixture.trader_config;
+    assert_eq!(
+        fixture.execute_trade_with(accounts, SWAP_AMOUNT, &other).unwrap_err(),
+        anchor_error(TraderError::Unauthorized)
+    );
+
+    // Source account of someone else
+    let foreign = fixture.token_account(&fixture.network.usdc.clone(), &other.pubkey(), SOURCE_BALANCE);
+    let accounts = fixture.trade_accounts(&fixture.authority.pubkey(), foreign, fixture.destination);
+    let authority = fixture.authority.insecure_clone();
+    assert_eq!(
+        fixture.execute_trade_with(accounts, SWAP_AMOUNT, &authority).unwrap_err(),
+        anchor_error(TraderError::InvalidTokenAccount)
+    );
+
+    fixture.update_trader(|trader| trader.price_threshold = ORACLE_PRICE);
+    assert_eq!(fixture.execute_trade(SWAP_AMOUNT).unwrap_err(), anchor_error(TraderError::ThresholdNotMet));
+    assert_eq!(fixture.trader().total_swaps, 0);
+}
+
+#[test]
+fn execute_trade_validates_route() {
+    let mut fixture = Fixture::new();
+    let authority = fixture.authority.insecure_clone();
+
+    let mut accounts = fixture.trade_accounts(&authority.pubkey(), fixture.source, fixture.destination);
+    accounts.raydium_amm_program = vectai_oracle::ID;
+    assert_eq!(
+        fixture.execute_trade_with(accounts, SWAP_AMOUNT, &authority).unwrap_err(),
+        anchor_error(TraderError::InvalidRaydiumProgram)
+    );
+
+    let other_mint = Pubkey::new_unique();
+    fixture.test.create_mint(other_mint, &Pubkey::new_unique(), 6);
+    let unsupported = fixture.token_account(&other_mint, &authority.pubkey(), 0);
+    let accounts = fixture.trade_accounts(&authority.pubkey(), fixture.source, unsupported);
+    assert_eq!(
+        fixture.execute_trade_with(accounts, SWAP_AMOUNT, &authority).unwrap_err(),
+        anchor_error(TraderError::InvalidTokenPair)
+    );
+
+    // A look-alike pool off the whitelist, substituted account by account
+    let decoy = fixture.test.create_amm_pool(&fixture.network.wsol, &fixture.network.usdc, POOL_RESERVE, POOL_RESERVE);
+    let substitutions: [fn(&mut accounts::ExecuteTrade, &Pool); 3] = [
+        |accounts, decoy| accounts.amm = decoy.amm,
+        |accounts, decoy| accounts.pool_pc_token_account = decoy.pool_pc_token_account,
+        |accounts, decoy| accounts.serum_vault_signer = decoy.serum_vault_signer,
+    ];
+    for substitute in substitutions {
+        let mut accounts = fixture.trade_accounts(&authority.pubkey(), fixture.source, fixture.destination);
+        substitute(&mut accounts, &decoy);
+        assert_eq!(
+            fixture.execute_trade_with(accounts, SWAP_AMOUNT, &authority).unwrap_err(),
+            anchor_error(TraderError::InvalidPool)
+        );
+    }
+    let follower = follower(&mut fixture);
+    fixture.network.pool = decoy;
+    assert_eq!(
+        fixture.test.process_instruction(copy_trade_ix(&fixture, &follower), &[]).unwrap_err(),
+        anchor_error(TraderError::InvalidPool)
+    );
+    assert_eq!(fixture.balance(&fixture.source), SOURCE_BALANCE);
+}
+
+#[test]
+fn execute_trade_rejects_untrusted_programs_in_the_transaction() {
+    let mut fixture = Fixture::new();
+    let authority = fixture.authority.insecure_clone();
+    let execute = ix(
+        fixture.trade_accounts(&authority.pubkey(), fixture.source, fixture.destination),
+        instruction::ExecuteTrade { amount: SWAP_AMOUNT, dry_run: false, nonce: None },
+    );
+    // Moving the pool right before the execution, through another program
+    let push_price = Instruction {
+        program_id: raydium_swapper::ID,
+        accounts: fixture
+            .network
+            .swap_accounts(&authority.pubkey(), fixture.source, fixture.destination)
+            .to_account_metas(None),
+        data: raydium_swapper::instruction::Swap { amount_in: SWAP_AMOUNT, min_amount_out: 1 }.data(),
+    };
+    assert_eq!(
+        fixture.test.process_transaction(&[push_price, execute.clone()], &[&authority]).unwrap_err(),
+        anchor_error(TraderError::UntrustedInstruction)
+    );
+    assert_eq!(fixture.balance(&fixture.source), SOURCE_BALANCE);
+
+    // This program's own instructions may share the transaction
+    let preview = ix(
+        fixture.trade_accounts(&authority.pubkey(), fixture.source, fixture.destination),
+        instruction::ExecuteTrade { amount: SWAP_AMOUNT, dry_run: true, nonce: None },
+    );
+    fixture.test.process_transaction(&[preview, execute], &[&authority]).unwrap();
+    assert_eq!(fixture.trader().total_swaps, 1);
+}
+
+#[test]
+fn execute_trade_enforces_slippage() {
+    let mut fixture = Fixture::new();
+    // Shallow WSOL side: the pool pays out far less than the 1:1 estimate
+    fixture.network.pool = fixture.test.create_whitelisted_pool(&fixture.network.wsol, &fixture.network.usdc, SWAP_AMOUNT, POOL_RESERVE);
+
+    assert_eq!(
+        fixture.execute_trade(SWAP_AMOUNT).unwrap_err(),
+        ProgramError::Custom(mock_amm::EXCEEDED_SLIPPAGE)
+    );
+    assert_eq!(fixture.balance(&fixture.source), SOURCE_BALANCE);
+    assert_eq!(fixture.trader().total_swaps, 0);
+}
+
+fn dynamic_slippage(base_bps: u16, conf_factor_bps: i32) -> instruction::SetDynamicSlippage {
+    instruction::SetDynamicSlippage {
+        dynamic_slippage: DynamicSlippage {
+            base_bps,
+            conf_factor_bps,
+        },
+    }
+}
+
+#[test]
+fn execute_trade_scales_slippage_with_confidence() {
+    let mut fixture = Fixture::new();
+    let minimum_out = |fixture: &mut Fixture| {
+        fixture.dry_run(SWAP_AMOUNT).unwrap();
+        let [preview] = fixture.test.events::<TradePreview>().try_into().ok().unwrap();
+        preview.minimum_out
+    };
+    let floor = |slippage_bps: u64| SWAP_AMOUNT * (10_000 - slippage_bps) / 10_000;
+    // The mock feed's confidence is 100 / 45_000, 22 bps of the price
+    let conf_bps = 22;
+
+    fixture.configure(dynamic_slippage(50, 20_000)).unwrap();
+    let [appended] = fixture.test.events::<AuditEntryAppended>().try_into().ok().unwrap();
+    assert!(matches!(
+        appended.entry.action,
+        AuditAction::DynamicSlippageSet { dynamic_slippage } if dynamic_slippage.base_bps == 50
+    ));
+    assert_eq!(minimum_out(&mut fixture), floor(50 + 2 * conf_bps));
+
+    // A negative factor tightens fills as the confidence widens
+    fixture.configure(dynamic_slippage(50, -10_000)).unwrap();
+    assert_eq!(minimum_out(&mut fixture), floor(50 - conf_bps));
+
+    // Never beyond the 10% cap fixed tolerances are held to
+    fixture.configure(dynamic_slippage(900, 100_000)).unwrap();
+    assert_eq!(minimum_out(&mut fixture), floor(1_000));
+
+    // A zero factor returns to the fixed tolerance
+    fixture.configure(dynamic_slippage(900, 0)).unwrap();
+    assert_eq!(minimum_out(&mut fixture), floor(SLIPPAGE_BPS));
+
+    let err = fixture.configure(dynamic_slippage(1_001, 10_000)).unwrap_err();
+    assert_eq!(err, anchor_error(TraderError::InvalidInput));
+    assert_eq!(fixture.trader().dynamic_slippage.base_bps, 900);
+}
+
+#[test]
+fn execute_trade_rejected_while_paused_or_inactive() {
+    let mut fixture = Fixture::new();
+
+    fixture.test.set_protocol_status(true, FeeConfig::INITIAL);
+    assert_eq!(fixture.execute_trade(SWAP_AMOUNT).unwrap_err(), anchor_error(TraderError::ProtocolPaused));
+
+    fixture.test.set_protocol_status(false, FeeConfig::INITIAL);
+    fixture.update_trader(|trader| trader.is_active = false);
+    assert_eq!(fixture.execute_trade(SWAP_AMOUNT).unwrap_err(), anchor_error(TraderError::TraderInactive));
+}
+
+#[test]
+fn execute_trade_charges_trade_fee() {
+    let mut fixture = Fixture::new();
+    let fee_config = FeeConfig {
+        trade_fee_bps: 50,
+        ..FeeConfig::INITIAL
+    };
+    fixture.test.set_protocol_status(false, fee_config);
+
+    assert_eq!(fixture.execute_trade(SWAP_AMOUNT).unwrap_err(), anchor_error(TraderError::FeeAccountRequired));
+
+    let vault = vectai_treasury::vault_address(&fixture.network.usdc);
+    fixture.test.create_token_account(vault, &fixture.network.usdc, &vault, 0);
+    let mut accounts = fixture.trade_accounts(&fixture.authority.pubkey(), fixture.source, fixture.destination);
+    accounts.protocol_fee_account = Some(vault);
+    let authority = fixture.authority.insecure_clone();
+    fixture.execute_trade_with(accounts, SWAP_AMOUNT, &authority).unwrap();
+
+    let fee = SWAP_AMOUNT * 50 / 10_000;
+    assert_eq!(fixture.balance(&vault), fee);
+    assert_eq!(fixture.balance(&fixture.source), SOURCE_BALANCE - SWAP_AMOUNT);
+    assert_eq!(fixture.balance(&fixture.network.pool.pool_pc_token_account), POOL_RESERVE + SWAP_AMOUNT - fee);
+    let [event] = fixture.test.events::<TradeExecuted>().try_into().ok().unwrap();
+    assert_eq!(event.amount_in, SWAP_AMOUNT);
+    assert_eq!(event.trade_fee, fee);
+
+    let stats: ProtocolStats = fixture.test.anchor_account(&fixture.network.protocol_stats).unwrap();
+    assert_eq!(stats.executions, 1);
+    assert_eq!(stats.volume(&fixture.network.usdc).unwrap().executed, SWAP_AMOUNT - fee);
+}
+
+#[test]
+fn execute_trade_dry_run_quotes_without_trading() {
+    let mut fixture = Fixture::new();
+    let now = fixture.test.clock().unix_timestamp;
+    let pool_wsol = fixture.balance(&fixture.network.pool.pool_coin_token_account);
+
+    fixture.dry_run(SWAP_AMOUNT).unwrap();
+
+    let [preview] = fixture.test.events::<TradePreview>().try_into().ok().unwrap();
+    assert_eq!(preview.trader, fixture.trader_config);
+    assert_eq!(preview.source_mint, fixture.network.usdc);
+    assert_eq!(preview.destination_mint, fixture.network.wsol);
+    assert_eq!(preview.amount_in, SWAP_AMOUNT);
+    assert_eq!(preview.trade_fee, 0);
+    assert_eq!(preview.expected_out, SWAP_AMOUNT);
+    assert_eq!(preview.minimum_out, SWAP_AMOUNT * (10_000 - SLIPPAGE_BPS) / 10_000);
+    assert_eq!(preview.oracle_price, ORACLE_PRICE);
+    assert_eq!(preview.timestamp, now);
+    assert!(fixture.test.events::<TradeExecuted>().is_empty());
+    assert_eq!(fixture.balance(&fixture.source), SOURCE_BALANCE);
+    assert_eq!(fixture.balance(&fixture.network.pool.pool_coin_token_account), pool_wsol);
+    let trader = fixture.trader();
+    assert_eq!((trader.total_swaps, trader.last_swap_time), (0, 0));
+    let stats: ProtocolStats = fixture.test.anchor_account(&fixture.network.protocol_stats).unwrap();
+    assert_eq!(stats.executions, 0);
+
+    // The same checks as a real trade, which the dry run did not start the cooldown for
+    fixture.execute_trade(SWAP_AMOUNT).unwrap();
+    assert_eq!(fixture.dry_run(SWAP_AMOUNT).unwrap_err(), anchor_error(TraderError::RateLimited));
+    fixture.test.warp_forward(TRADE_COOLDOWN);
+    fixture.update_trader(|trader| trader.price_threshold = ORACLE_PRICE);
+    assert_eq!(fixture.dry_run(SWAP_AMOUNT).unwrap_err(), anchor_error(TraderError::ThresholdNotMet));
+    fixture.update_trader(|trader| trader.price_threshold = PRICE_THRESHOLD);
+    fixture.test.set_protocol_status(false, FeeConfig { trade_fee_bps: 50, ..FeeConfig::INITIAL });
+    assert_eq!(fixture.dry_run(SWAP_AMOUNT).unwrap_err(), anchor_error(TraderError::FeeAccountRequired));
+}
+
+// ===== STRATEGY CONDITIONS =====
+
+fn price_condition(opcode: u8, price: i64) -> Vec<u8> {
+    let mut program = vec![opcode];
+    program.extend_from_slice(&price.to_le_bytes());
+    program
+}
+
+#[test]
+fn set_strategy_condition_validates_program() {
+    let mut fixture = Fixture::new();
+    let invalid = anchor_error(TraderError::InvalidCondition);
+
+    let truncated = vec![OP_PRICE_ABOVE, 0];
+    assert_eq!(fixture.configure(instruction::SetStrategyCondition { program: truncated }).unwrap_err(), invalid);
+    let mut unbalanced = price_condition(OP_PRICE_ABOVE, 1);
+    unbalanced.extend(price_condition(OP_PRICE_BELOW, 2));
+    assert_eq!(fixture.configure(instruction::SetStrategyCondition { program: unbalanced }).unwrap_err(), invalid);
+    assert_eq!(fixture.configure(instruction::SetStrategyCondition { program: vec![0; 65] }).unwrap_err(), invalid);
+
+    let program = price_condition(OP_PRICE_ABOVE, PRICE_THRESHOLD);
+    fixture.configure(instruction::SetStrategyCondition { program: program.clone() }).unwrap();
+    assert_eq!(fixture.trader().condition, program);
+    assert_eq!(fixture.audit_log().entry_count, 1);
+
+    // Clearing falls back to the price threshold
+    fixture.configure(instruction::SetStrategyCondition { program: Vec::new() }).unwrap();
+    assert!(fixture.trader().condition.is_empty());
+}
+
+#[test]
+fn execute_trade_evaluates_condition() {
+    let mut fixture = Fixture::new();
+
+    // 40000 < price < 44000: not met at 45000
+    let mut program = price_condition(OP_PRICE_ABOVE, PRICE_THRESHOLD);
+    program.extend(price_condition(OP_PRICE_BELOW, 44_000));
+    program.push(OP_AND);
+    fixture.configure(instruction::SetStrategyCondition { program }).unwrap();
+    assert_eq!(fixture.execute_trade(SWAP_AMOUNT).unwrap_err(), anchor_error(TraderError::ConditionNotMet));
+
+    // The condition replaces the threshold, which alone would block
+    fixture.update_trader(|trader| trader.price_threshold = 50_000);
+    let program = price_condition(OP_PRICE_BELOW, 50_000);
+    fixture.configure(instruction::SetStrategyCondition { program }).unwrap();
+    fixture.execute_trade(SWAP_AMOUNT).unwrap();
+    assert_eq!(fixture.trader().total_swaps, 1);
+}
+
+#[test]
+fn configuration_requires_trader_authority() {
+    let mut fixture = Fixture::new();
+    let other = funded(&mut fixture.test);
+
+    // The trader PDA is derived from the signer, so another signer can't
+    // address this trader
+    let err = fixture
+        .configure_with(instruction::SetAutoCompound { enabled: true }, &other)
+        .unwrap_err();
+    assert_eq!(err, anchor_error(anchor_lang::error::ErrorCode::AccountNotInitialized));
+    assert!(!fixture.trader().auto_compound);
+}
+
+#[test]
+fn audit_log_chains_configuration_changes() {
+    let mut fixture = Fixture::new();
+    let authority = fixture.authority.pubkey();
+    let mut entries = Vec::new();
+
+    fixture.configure(instruction::SetAutoCompound { enabled: true }).unwrap();
+    entries.extend(fixture.test.events::<AuditEntryAppended>());
+    fixture.configure(instruction::SetMaxDrawdown { max_drawdown_bps: 1_000 }).unwrap();
+    entries.extend(fixture.test.events::<AuditEntryAppended>());
+
+    assert_eq!(entries.len(), 2);
+    let mut head = [0u8; 32];
+    for (sequence, appended) in entries.iter().enumerate() {
+        assert_eq!(appended.trader, fixture.trader_config);
+        assert_eq!(appended.entry.sequence, sequence as u64);
+        assert_eq!(appended.entry.actor, authority);
+        assert_eq!(appended.prev_hash, head);
+        head = appended.entry.chain(&head).unwrap();
+        assert_eq!(appended.hash, head);
+    }
+    assert!(matches!(entries[0].entry.action, AuditAction::AutoCompoundSet { enabled: true }));
+    assert!(matches!(entries[1].entry.action, AuditAction::MaxDrawdownSet { max_drawdown_bps: 1_000 }));
+
+    let audit_log = fixture.audit_log();
+    assert_eq!(audit_log.entry_count, 2);
+    assert_eq!(audit_log.head, head);
+
+    // A replay with an altered entry no longer reaches the head
+    let forged = AuditEntry {
+        action: AuditAction::AutoCompoundSet { enabled: false },
+        ..entries[0].entry.clone()
+    };
+    let forged_head = entries[1].entry.chain(&forged.chain(&[0u8; 32]).unwrap()).unwrap();
+    assert_ne!(forged_head, audit_log.head);
+}
+
+// ===== TRADING HOURS =====
+
+/// Midnight UTC starting the genesis day, a Tuesday
+const GENESIS_DAY: i64 = 1_699_920_000;
+const HOUR: i64 = 3_600;
+
+fn window(days: u8, start_minute: u16, end_minute: u16) -> TradingSchedule {
+    TradingSchedule {
+        days,
+        start_minute,
+        end_minute,
+    }
+}
+
+#[test]
+fn set_trading_schedule_validates_window() {
+    let mut fixture = Fixture::new();
+    let invalid = [
+        window(0, 540, 1_020),
+        window(0x80, 540, 1_020),
+        window(0x1f, 540, 540),
+        window(0x1f, 1_440, 60),
+        window(0x1f, 540, 1_441),
+    ];
+    for schedule in invalid {
+        let err = fixture.configure(instruction::SetTradingSchedule { schedule }).unwrap_err();
+        assert_eq!(err, anchor_error(TraderError::InvalidInput), "{:?}", schedule);
+    }
+
+    let schedule = window(0x1f, 540, 1_440);
+    fixture.configure(instruction::SetTradingSchedule { schedule }).unwrap();
+    assert_eq!(fixture.trader().schedule, schedule);
+    let [appended] = fixture.test.events::<AuditEntryAppended>().try_into().ok().unwrap();
+    assert!(matches!(appended.entry.action, AuditAction::TradingScheduleSet { schedule: set } if set == schedule));
+}
+
+#[test]
+fn execute_trade_only_within_trading_hours() {
+    let mut fixture = Fixture::new();
+    // Weekdays 09:00 to 17:00; genesis is Tuesday 22:13
+    let schedule = window(0x1f, 540, 1_020);
+    fixture.configure(instruction::SetTradingSchedule { schedule }).unwrap();
+
+    let closed = anchor_error(TraderError::OutsideTradingHours);
+    assert_eq!(fixture.execute_trade(SWAP_AMOUNT).unwrap_err(), closed);
+    fixture.test.warp_to_timestamp(GENESIS_DAY + DAY + 9 * HOUR - 1);
+    assert_eq!(fixture.execute_trade(SWAP_AMOUNT).unwrap_err(), closed);
+
+    fixture.test.warp_forward(1);
+    fixture.execute_trade(SWAP_AMOUNT).unwrap();
+
+    // Saturday is not a trading day
+    fixture.test.warp_to_timestamp(GENESIS_DAY + 4 * DAY + 12 * HOUR);
+    assert_eq!(fixture.execute_trade(SWAP_AMOUNT).unwrap_err(), closed);
+
+    // An all-zero schedule lifts the restriction
+    fixture
+        .configure(instruction::SetTradingSchedule {
+            schedule: TradingSchedule::default(),
+        })
+        .unwrap();
+    fixture.execute_trade(SWAP_AMOUNT).unwrap();
+    assert_eq!(fixture.trader().total_swaps, 2);
+}
+
+#[test]
+fn trading_schedule_window_crosses_midnight() {
+    // Friday 22:00 to Saturday 02:00
+    let schedule = window(1 << 4, 1_320, 120);
+    let friday = GENESIS_DAY + 3 * DAY;
+    assert!(schedule.is_valid());
+    assert!(!schedule.is_open(friday + 22 * HOUR - 1));
+    assert!(schedule.is_open(friday + 22 * HOUR));
+    assert!(schedule.is_open(friday + DAY + HOUR));
+    assert!(!schedule.is_open(friday + DAY + 2 * HOUR));
+    // Thursday's window never opened
+    assert!(!schedule.is_open(friday + HOUR));
+    assert!(!schedule.is_open(friday + DAY + 23 * HOUR));
+    assert!(TradingSchedule::default().is_open(friday));
+}
+
+// ===== AUTO-COMPOUND =====
+
+#[test]
+fn auto_compound_grows_swap_amount_by_gain() {
+    let mut fixture = Fixture::new();
+    fixture.configure(instruction::SetAutoCompound { enabled: true }).unwrap();
+    assert!(fixture.trader().auto_compound);
+
+    // Only the configured amount may trade while compounding
+    assert_eq!(
+        fixture.execute_trade(SWAP_AMOUNT / 2).unwrap_err(),
+        anchor_error(TraderError::InvalidSwapAmount)
+    );
+
+    fixture.execute_trade(SWAP_AMOUNT).unwrap();
+
+    let out = mock_amm::quote(SWAP_AMOUNT, POOL_RESERVE, POOL_RESERVE);
+    let gain = vectai_math::value_in_source_units(out, true, ORACLE_PRICE, 0).unwrap() - SWAP_AMOUNT;
+    let trader = fixture.trader();
+    assert_eq!(trader.swap_amount, SWAP_AMOUNT + gain);
+    assert_eq!(trader.total_compounded, gain);
+    let [event] = fixture.test.events::<TradeCompounded>().try_into().ok().unwrap();
+    assert_eq!(event.gain, gain);
+    assert_eq!(event.swap_amount, SWAP_AMOUNT + gain);
+}
+
+#[test]
+fn executions_realize_pnl_marked_to_usd() {
+    let mut fixture = Fixture::new();
+    let fee_config = FeeConfig {
+        trade_fee_bps: 50,
+        ..FeeConfig::INITIAL
+    };
+    fixture.test.set_protocol_status(false, fee_config);
+    let vault = vectai_treasury::vault_address(&fixture.network.usdc);
+    fixture.test.create_token_account(vault, &fixture.network.usdc, &vault, 0);
+    let trade = |fixture: &mut Fixture| {
+        let mut accounts = fixture.trade_accounts(&fixture.authority.pubkey(), fixture.source, fixture.destination);
+        accounts.protocol_fee_account = Some(vault);
+        let authority = fixture.authority.insecure_clone();
+        fixture.execute_trade_with(accounts, SWAP_AMOUNT, &authority)
+    };
+    trade(&mut fixture).unwrap();
+
+    // USDC in at par, fee included; WSOL out at the oracle
+    let fee = SWAP_AMOUNT * 50 / 10_000;
+    let out = mock_amm::quote(SWAP_AMOUNT - fee, POOL_RESERVE, POOL_RESERVE);
+    let value_out = vectai_math::value_in_source_units(out, true, ORACLE_PRICE, 0).unwrap();
+    let pnl = value_out as i64 - SWAP_AMOUNT as i64;
+    assert_eq!(fixture.trader().realized_pnl_usd, pnl);
+    let [event] = fixture.test.events::<PnlRealized>().try_into().ok().unwrap();
+    assert_eq!(event.account, fixture.trader_config);
+    assert_eq!((event.value_in_usd, event.value_out_usd), (SWAP_AMOUNT, value_out));
+    assert_eq!((event.pnl_usd, event.realized_pnl_usd), (pnl, pnl));
+
+    // Executions accumulate on the strategy
+    fixture.test.warp_forward(TRADE_COOLDOWN);
+    trade(&mut fixture).unwrap();
+    let [second] = fixture.test.events::<PnlRealized>().try_into().ok().unwrap();
+    assert_eq!(second.realized_pnl_usd, pnl + second.pnl_usd);
+    assert_eq!(fixture.trader().realized_pnl_usd, second.realized_pnl_usd);
+}
+
+#[test]
+fn profit_target_completes_strategy_and_sells_position() {
+    let mut fixture = Fixture::new();
+    // Far above one trade's PnL: the strategy keeps running
+    fixture.configure(instruction::SetProfitTarget { profit_target_usd: u64::MAX }).unwrap();
+    let [appended] = fixture.test.events::<AuditEntryAppended>().try_into().ok().unwrap();
+    assert!(matches!(appended.entry.action, AuditAction::ProfitTargetSet { profit_target_usd: u64::MAX }));
+    fixture.execute_trade(SWAP_AMOUNT).unwrap();
+    assert!(fixture.trader().is_active);
+    assert!(fixture.test.events::<StrategyCompleted>().is_empty());
+    let pnl = fixture.trader().realized_pnl_usd;
+
+    fixture.configure(instruction::SetProfitTarget { profit_target_usd: pnl as u64 }).unwrap();
+    fixture.test.warp_forward(TRADE_COOLDOWN);
+    fixture.execute_trade(SWAP_AMOUNT).unwrap();
+
+    // The whole WSOL position goes back into USDC through the same pool
+    let first = mock_amm::quote(SWAP_AMOUNT, POOL_RESERVE, POOL_RESERVE);
+    let second = mock_amm::quote(SWAP_AMOUNT, POOL_RESERVE + SWAP_AMOUNT, POOL_RESERVE - first);
+    let (position, pc_reserve, coin_reserve) =
+        (first + second, POOL_RESERVE + 2 * SWAP_AMOUNT, POOL_RESERVE - first - second);
+    let proceeds = mock_amm::quote(position, coin_reserve, pc_reserve);
+    assert_eq!(fixture.balance(&fixture.destination), 0);
+    assert_eq!(fixture.balance(&fixture.source), SOURCE_BALANCE - 2 * SWAP_AMOUNT + proceeds);
+
+    let trader = fixture.trader();
+    assert!(!trader.is_active);
+    let [completed] = fixture.test.events::<StrategyCompleted>().try_into().ok().unwrap();
+    assert_eq!(completed.trader, fixture.trader_config);
+    assert_eq!((completed.residual_sold, completed.proceeds), (position, proceeds));
+    assert_eq!(completed.profit_target_usd, pnl as u64);
+    assert_eq!(completed.realized_pnl_usd, trader.realized_pnl_usd);
+    // The sale is marked like any execution: the trade's PnL, then the sale's
+    let [trade, sale] = fixture.test.events::<PnlRealized>().try_into().ok().unwrap();
+    assert_eq!((sale.value_in_usd, sale.value_out_usd), (
+        vectai_math::value_in_source_units(position, true, ORACLE_PRICE, 0).unwrap(),
+        proceeds
+    ));
+    assert_eq!(sale.realized_pnl_usd, pnl + trade.pnl_usd + sale.pnl_usd);
+
+    fixture.test.warp_forward(TRADE_COOLDOWN);
+    assert_eq!(fixture.execute_trade(SWAP_AMOUNT).unwrap_err(), anchor_error(TraderError::TraderInactive));
+}
+
+#[test]
+fn max_price_age_bounds_oracle_slots() {
+    let mut fixture = Fixture::new();
+    assert_eq!(
+        fixture.configure(instruction::SetMaxPriceAgeSlots { max_price_age_slots: MAX_PRICE_AGE_SLOTS + 1 })
+            .unwrap_err(),
+        anchor_error(TraderError::InvalidInput)
+    );
+
+    fixture.configure(instruction::SetMaxPriceAgeSlots { max_price_age_slots: 25 }).unwrap();
+    let [appended] = fixture.test.events::<AuditEntryAppended>().try_into().ok().unwrap();
+    assert!(matches!(appended.entry.action, AuditAction::MaxPriceAgeSlotsSet { max_price_age_slots: 25 }));
+    // The mock oracle observes its price in the executing slot
+    fixture.execute_trade(SWAP_AMOUNT).unwrap();
+
+    let mut trader = fixture.trader();
+    let clock = Clock { slot: 1_025, ..Clock::default() };
+    assert!(trader.price_fresh(1_000, &clock));
+    assert!(!trader.price_fresh(999, &clock));
+    trader.max_price_age_slots = 0;
+    assert!(trader.price_fresh(0, &clock));
+}
+
+// ===== PRICE AGGREGATOR =====
+
+/// Create the authority's aggregator of two feeds, returning it and them
+fn create_price_aggregator(fixture: &mut Fixture) -> (Pubkey, [Pubkey; 2]) {
+    let authority = fixture.authority.insecure_clone();
+    let price_feeds = [Pubkey::new_unique(), Pubkey::new_unique()];
+    let aggregator = vectai_oracle::price_aggregator(&authority.pubkey(), 1);
+    let sources = vec![
+        PriceSource { price_feed: price_feeds[0], weight_bps: 7_000 },
+        PriceSource { price_feed: price_feeds[1], weight_bps: 3_000 },
+    ];
+    let initialize = Instruction {
+        program_id: vectai_oracle::ID,
+        accounts: vectai_oracle::accounts::InitializePriceAggregator {
+            price_aggregator: aggregator,
+            authority: authority.pubkey(),
+            system_program: system_program::ID,
+        }
+        .to_account_metas(None),
+        data: vectai_oracle::instruction::InitializePriceAggregator { aggregator_id: 1, sources }.data(),
+    };
+    fixture.test.process_instruction(initialize, &[&authority]).unwrap();
+    (aggregator, price_feeds)
+}
+
+#[test]
+fn set_price_aggregator_bounds_the_source_deviation() {
+    let mut fixture = Fixture::new();
+    let aggregator = Pubkey::new_unique();
+    assert_eq!(
+        fixture
+            .configure(instruction::SetPriceAggregator { price_aggregator: aggregator, max_source_deviation_bps: 10_001 })
+            .unwrap_err(),
+        anchor_error(TraderError::InvalidInput)
+    );
+
+    fixture
+        .configure(instruction::SetPriceAggregator { price_aggregator: aggregator, max_source_deviation_bps: 50 })
+        .unwrap();
+    let [appended] = fixture.test.events::<AuditEntryAppended>().try_into().ok().unwrap();
+    assert!(matches!(
+        appended.entry.action,
+        AuditAction::PriceAggregatorSet { price_aggregator, max_source_deviation_bps: 50 } if price_aggregator == aggregator
+    ));
+    let mut trader = fixture.trader();
+    assert_eq!((trader.price_aggregator, trader.max_source_deviation_bps), (aggregator, 50));
+    assert!(trader.sources_agree(50));
+    assert!(!trader.sources_agree(51));
+    trader.price_aggregator = Pubkey::default();
+    assert!(trader.sources_agree(u64::MAX));
+}
+
+#[test]
+fn execute_trade_prices_from_the_strategys_aggregator() {
+    let mut fixture = Fixture::new();
+    let (aggregator, price_feeds) = create_price_aggregator(&mut fixture);
+    fixture
+        .configure(instruction::SetPriceAggregator { price_aggregator: aggregator, max_source_deviation_bps: 0 })
+        .unwrap();
+    assert_eq!(fixture.execute_trade(SWAP_AMOUNT).unwrap_err(), anchor_error(TraderError::InvalidPriceFeed));
+
+    let authority = fixture.authority.insecure_clone();
+    let mut accounts = fixture.trade_accounts(&authority.pubkey(), fixture.source, fixture.destination);
+    accounts.price_feed = aggregator;
+    let mut execute = ix(accounts, instruction::ExecuteTrade { amount: SWAP_AMOUNT, dry_run: false, nonce: None });
+    assert_eq!(
+        fixture.test.process_instruction(execute.clone(), &[&authority]).unwrap_err(),
+        anchor_error(TraderError::InvalidPriceFeed)
+    );
+
+    // The mock oracle reads the same price from every source
+    execute.accounts.extend(price_feeds.iter().map(|price_feed| AccountMeta::new_readonly(*price_feed, false)));
+    fixture.test.process_instruction(execute, &[&authority]).unwrap();
+    assert!(fixture.test.logs().iter().any(|log| log.contains("Aggregated price: 45000")));
+    assert_eq!(fixture.balance(&fixture.source), SOURCE_BALANCE - SWAP_AMOUNT);
+}
+
+// ===== VOLATILITY FILTER =====
+
+/// Seed the filter's window with `prices`, last sampled a minute ago
+fn seed_volatility(fixture: &mut Fixture, prices: &[i64]) {
+    let now = fixture.test.clock().unix_timestamp;
+    fixture.update_trader(|trader| {
+        trader.volatility.samples[..prices.len()].copy_from_slice(prices);
+        trader.volatility.sample_count = prices.len() as u8;
+        trader.volatility.next_sample = prices.len() as u8;
+        trader.volatility.last_sample_time = now - VOLATILITY_SAMPLE_SECS;
+    });
+}
+
+#[test]
+fn set_volatility_filter_restarts_window() {
+    let mut fixture = Fixture::new();
+    let err = fixture
+        .configure(instruction::SetVolatilityFilter { max_volatility_bps: 10_001 })
+        .unwrap_err();
+    assert_eq!(err, anchor_error(TraderError::InvalidInput));
+
+    seed_volatility(&mut fixture, &[40_000, 50_000]);
+    fixture.configure(instruction::SetVolatilityFilter { max_volatility_bps: 100 }).unwrap();
+    let [appended] = fixture.test.events::<AuditEntryAppended>().try_into().ok().unwrap();
+    assert!(matches!(appended.entry.action, AuditAction::VolatilityFilterSet { max_volatility_bps: 100 }));
+    let volatility = fixture.trader().volatility;
+    assert_eq!((volatility.max_volatility_bps, volatility.sample_count), (100, 0));
+
+    // The first observations cannot exceed any bound
+    fixture.execute_trade(SWAP_AMOUNT).unwrap();
+    let volatility = fixture.trader().volatility;
+    assert_eq!((volatility.samples[0], volatility.sample_count), (ORACLE_PRICE, 1));
+}
+
+#[test]
+fn volatile_prices_skip_execution() {
+    let mut fixture = Fixture::new();
+    let now = fixture.test.clock().unix_timestamp;
+    fixture.configure(instruction::SetVolatilityFilter { max_volatility_bps: 100 }).unwrap();
+    // A wick to 40_000 and 50_000 around the current 45_000: 907 bps
+    seed_volatility(&mut fixture, &[40_000, 50_000]);
+
+    assert_eq!(fixture.dry_run(SWAP_AMOUNT).unwrap_err(), anchor_error(TraderError::VolatilityTooHigh));
+    fixture.execute_trade(SWAP_AMOUNT).unwrap();
+
+    let [event] = fixture.test.events::<VolatilityLimitHit>().try_into().ok().unwrap();
+    assert_eq!(event.trader, fixture.trader_config);
+    assert_eq!((event.volatility_bps, event.max_volatility_bps), (907, 100));
+    assert_eq!((event.oracle_price, event.timestamp), (ORACLE_PRICE, now));
+    assert!(fixture.test.events::<TradeExecuted>().is_empty());
+    assert_eq!(fixture.balance(&fixture.source), SOURCE_BALANCE);
+    // Skipped, not failed: the observation is kept and no cooldown starts
+    let trader = fixture.trader();
+    assert_eq!((trader.total_swaps, trader.volatility.sample_count), (0, 3));
+
+    // Within the sampling interval the window is unchanged
+    fixture.test.warp_forward(VOLATILITY_SAMPLE_SECS - 1);
+    fixture.execute_trade(SWAP_AMOUNT).unwrap();
+    assert_eq!(fixture.trader().volatility.sample_count, 3);
+
+    // Once the price settles the strategy trades again
+    seed_volatility(&mut fixture, &[ORACLE_PRICE; 7]);
+    fixture.execute_trade(SWAP_AMOUNT).unwrap();
+    assert!(fixture.test.events::<VolatilityLimitHit>().is_empty());
+    assert_eq!(fixture.trader().total_swaps, 1);
+}
+
+// ===== TAKE-PROFIT LADDER =====
+
+fn level(price: i64, fraction_bps: u16) -> TakeProfitLevel {
+    TakeProfitLevel { price, fraction_bps }
+}
+
+impl Fixture {
+    /// Sell out of `position` (WSOL) into the strategy's USDC account
+    fn take_profit(&mut self, position: Pubkey) -> Result<(), ProgramError> {
+        let accounts = self.trade_accounts(&self.authority.pubkey(), position, self.source);
+        let authority = self.authority.insecure_clone();
+        self.test
+            .process_instruction(ix(accounts, instruction::ExecuteTakeProfit {}), &[&authority])
+    }
+}
+
+#[test]
+fn set_take_profit_ladder_validates_levels() {
+    let mut fixture = Fixture::new();
+    let invalid = [
+        vec![level(50_000, 2_500), level(50_000, 2_500)],
+        vec![level(50_000, 2_500), level(40_000, 2_500)],
+        vec![level(40_000, 6_000), level(50_000, 5_000)],
+        vec![level(40_000, 0)],
+        vec![level(0, 1_000)],
+        vec![level(1, 1), level(2, 1), level(3, 1), level(4, 1), level(5, 1)],
+    ];
+    for levels in invalid {
+        let err = fixture.configure(instruction::SetTakeProfitLadder { levels }).unwrap_err();
+        assert_eq!(err, anchor_error(TraderError::InvalidTakeProfit));
+    }
+
+    let levels = vec![level(50_000, 5_000), level(60_000, 5_000)];
+    fixture
+        .configure(instruction::SetTakeProfitLadder { levels: levels.clone() })
+        .unwrap();
+    let [appended] = fixture.test.events::<AuditEntryAppended>().try_into().ok().unwrap();
+    assert!(matches!(&appended.entry.action, AuditAction::TakeProfitSet { levels: set } if *set == levels));
+    let ladder = fixture.trader().take_profit;
+    assert_eq!(ladder.rungs().iter().map(|rung| rung.level).collect::<Vec<_>>(), levels);
+    assert!(ladder.rungs().iter().all(|rung| !rung.filled));
+}
+
+#[test]
+fn take_profit_scales_out_by_rung() {
+    let mut fixture = Fixture::new();
+    let position = fixture.token_account(&fixture.network.wsol.clone(), &fixture.authority.pubkey(), SWAP_AMOUNT);
+    // The oracle sits at 45_000: the first two rungs are reached
+    let levels = vec![level(40_000, 2_500), level(44_000, 2_500), level(50_000, 5_000)];
+    fixture.configure(instruction::SetTakeProfitLadder { levels }).unwrap();
+
+    fixture.take_profit(position).unwrap();
+
+    let sold = SWAP_AMOUNT / 2;
+    let out = mock_amm::quote(sold, POOL_RESERVE, POOL_RESERVE);
+    assert_eq!(fixture.balance(&position), SWAP_AMOUNT - sold);
+    assert_eq!(fixture.balance(&fixture.source), SOURCE_BALANCE + out);
+    let [event] = fixture.test.events::<TakeProfitFilled>().try_into().ok().unwrap();
+    assert_eq!((event.trader, event.rungs_filled), (fixture.trader_config, 2));
+    assert_eq!((event.amount_in, event.trade_fee, event.oracle_price), (sold, 0, ORACLE_PRICE));
+    let ladder = fixture.trader().take_profit;
+    assert_eq!(ladder.base_position, SWAP_AMOUNT);
+    assert_eq!(ladder.rungs().iter().map(|rung| rung.filled).collect::<Vec<_>>(), [true, true, false]);
+
+    // Filled rungs don't fire again
+    let not_reached = anchor_error(TraderError::TakeProfitNotReached);
+    assert_eq!(fixture.take_profit(position).unwrap_err(), not_reached);
+
+    // The last rung sells its share of the position as it was at the first
+    // fill, which is all that is left
+    fixture.update_trader(|trader| trader.take_profit.rungs[2].level.price = ORACLE_PRICE);
+    fixture.take_profit(position).unwrap();
+    assert_eq!(fixture.balance(&position), 0);
+    assert!(fixture.trader().take_profit.rungs().iter().all(|rung| rung.filled));
+}
+
+#[test]
+fn take_profit_requires_trader_authority() {
+    let mut fixture = Fixture::new();
+    let position = fixture.token_account(&fixture.network.wsol.clone(), &fixture.authority.pubkey(), SWAP_AMOUNT);
+    assert_eq!(
+        fixture.take_profit(position).unwrap_err(),
+        anchor_error(TraderError::TakeProfitNotReached)
+    );
+    fixture
+        .configure(instruction::SetTakeProfitLadder { levels: vec![level(40_000, 10_000)] })
+        .unwrap();
+
+    // Another signer against this trader
+    let other = funded(&mut fixture.test);
+    let mut accounts = fixture.trade_accounts(&other.pubkey(), position, fixture.source);
+    accounts.trader_config = fixture.trader_config;
+    let err = fixture
+        .test
+        .process_instruction(ix(accounts, instruction::ExecuteTakeProfit {}), &[&other])
+        .unwrap_err();
+    assert_eq!(err, anchor_error(TraderError::Unauthorized));
+    assert_eq!(fixture.balance(&position), SWAP_AMOUNT);
+}
+
+// ===== JUPITER ROUTES =====
+
+impl Fixture {
+    fn jupiter_route_accounts(&self, signer: &Pubkey) -> accounts::ExecuteJupiterRoute {
+        let trade = self.trade_accounts(signer, self.source, self.destination);
+        accounts::ExecuteJupiterRoute {
+            user_authority: *signer,
+            trader_config: trade.trader_config,
+            user_source_token_account: self.source,
+            user_destination_token_account: self.destination,
+            jupiter_program: mock_jupiter::ID,
+            vectai_oracle_program: trade.vectai_oracle_program,
+            price_feed: trade.price_feed,
+            protocol_fee_account: None,
+            token_program: spl_token::ID,
+            protocol_status: trade.protocol_status,
+            network_config: trade.network_config,
+            protocol_stats: trade.protocol_stats,
+            stats_reporter: trade.stats_reporter,
+            protocol_program: trade.protocol_program,
+            instructions: trade.instructions,
+        }
+    }
+
+    fn jupiter_route_ix(
+        accounts: accounts::ExecuteJupiterRoute,
+        amount: u64,
+        route_accounts: Vec<AccountMeta>,
+        route_data: Vec<u8>,
+    ) -> Instruction {
+        let mut ix = ix(accounts, instruction::ExecuteJupiterRoute { amount, route_data });
+        ix.accounts.extend(route_accounts);
+        ix
+    }
+
+    /// Execute `route_data` through `route_accounts` from the strategy's
+    /// USDC into its WSOL
+    fn execute_jupiter_route(
+        &mut self,
+        amount: u64,
+        route_accounts: Vec<AccountMeta>,
+        route_data: Vec<u8>,
+    ) -> Result<(), ProgramError> {
+        let accounts = self.jupiter_route_accounts(&self.authority.pubkey());
+        let ix = Self::jupiter_route_ix(accounts, amount, route_accounts, route_data);
+        let authority = self.authority.insecure_clone();
+        self.test.process_instruction(ix, &[&authority])
+    }
+
+    fn jupiter_pool(&mut self) -> JupiterPool {
+        let (usdc, wsol) = (self.network.usdc, self.network.wsol);
+        self.test.create_jupiter_pool(&usdc, &wsol, POOL_RESERVE, POOL_RESERVE)
+    }
+
+    fn route_accounts(&self, pool: &JupiterPool) -> Vec<AccountMeta> {
+        pool.route_accounts(&self.authority.pubkey(), self.source, self.destination, &self.network.wsol)
+    }
+}
+
+#[test]
+fn jupiter_route_swaps_between_strategy_accounts() {
+    let mut fixture = Fixture::new();
+    let pool = fixture.jupiter_pool();
+    let quoted = mock_amm::quote(SWAP_AMOUNT, POOL_RESERVE, POOL_RESERVE);
+    let route_accounts = fixture.route_accounts(&pool);
+
+    fixture
+        .execute_jupiter_route(SWAP_AMOUNT, route_accounts, mock_jupiter::route_data(SWAP_AMOUNT, quoted, 50, 0))
+        .unwrap();
+
+    assert_eq!(fixture.balance(&fixture.source), SOURCE_BALANCE - SWAP_AMOUNT);
+    assert_eq!(fixture.balance(&fixture.destination), quoted);
+    assert_eq!(fixture.balance(&pool.vault_a), POOL_RESERVE + SWAP_AMOUNT);
+    // The Raydium pool is untouched
+    assert_eq!(fixture.balance(&fixture.network.pool.pool_pc_token_account), POOL_RESERVE);
+    assert_eq!(fixture.trader().total_swaps, 1);
+    let [event] = fixture.test.events::<TradeExecuted>().try_into().ok().unwrap();
+    assert_eq!((event.trader, event.amount_in), (fixture.trader_config, SWAP_AMOUNT));
+    assert_eq!((event.expected_out, event.oracle_price), (quoted, ORACLE_PRICE));
+
+    // The cooldown is shared with execute_trade
+    assert_eq!(fixture.execute_trade(SWAP_AMOUNT).unwrap_err(), anchor_error(TraderError::RateLimited));
+}
+
+#[test]
+fn jupiter_route_must_use_strategy_accounts() {
+    let mut fixture = Fixture::new();
+    let pool = fixture.jupiter_pool();
+    let quoted = mock_amm::quote(SWAP_AMOUNT, POOL_RESERVE, POOL_RESERVE);
+    let data = || mock_jupiter::route_data(SWAP_AMOUNT, quoted, 50, 0);
+    let invalid = anchor_error(TraderError::InvalidJupiterRoute);
+
+    // Paying into an account the strategy does not hold
+    let other = Pubkey::new_unique();
+    fixture.test.create_token_account(other, &fixture.network.wsol, &Pubkey::new_unique(), 0);
+    let route_accounts = pool.route_accounts(&fixture.authority.pubkey(), fixture.source, other, &fixture.network.wsol);
+    assert_eq!(fixture.execute_jupiter_route(SWAP_AMOUNT, route_accounts, data()).unwrap_err(), invalid);
+
+    // Spending more than the strategy traded, or skimming a platform fee
+    let route_accounts = fixture.route_accounts(&pool);
+    let oversized = mock_jupiter::route_data(2 * SWAP_AMOUNT, quoted, 50, 0);
+    assert_eq!(fixture.execute_jupiter_route(SWAP_AMOUNT, route_accounts.clone(), oversized).unwrap_err(), invalid);
+    let with_fee = mock_jupiter::route_data(SWAP_AMOUNT, quoted, 50, 10);
+    assert_eq!(fixture.execute_jupiter_route(SWAP_AMOUNT, route_accounts.clone(), with_fee).unwrap_err(), invalid);
+
+    // Not a route at all
+    assert_eq!(fixture.execute_jupiter_route(SWAP_AMOUNT, route_accounts, vec![0; 8]).unwrap_err(), invalid);
+    assert_eq!(fixture.balance(&fixture.source), SOURCE_BALANCE);
+    assert_eq!(fixture.trader().total_swaps, 0);
+}
+
+#[test]
+fn jupiter_route_pays_only_the_strategy_owner() {
+    let mut fixture = Fixture::new();
+    let pool = fixture.jupiter_pool();
+    let quoted = mock_amm::quote(SWAP_AMOUNT, POOL_RESERVE, POOL_RESERVE);
+
+    // The route and the destination agree, but the account isn't the owner's
+    let other = Pubkey::new_unique();
+    fixture.test.create_token_account(other, &fixture.network.wsol, &Pubkey::new_unique(), 0);
+    let accounts = accounts::ExecuteJupiterRoute {
+        user_destination_token_account: other,
+        ..fixture.jupiter_route_accounts(&fixture.authority.pubkey())
+    };
+    let route_accounts = pool.route_accounts(&fixture.authority.pubkey(), fixture.source, other, &fixture.network.wsol);
+    let route_data = mock_jupiter::route_data(SWAP_AMOUNT, quoted, 50, 0);
+    let ix = Fixture::jupiter_route_ix(accounts, SWAP_AMOUNT, route_accounts, route_data);
+    let authority = fixture.authority.insecure_clone();
+    assert_eq!(
+        fixture.test.process_instruction(ix, &[&authority]).unwrap_err(),
+        anchor_error(TraderError::InvalidTokenAccount)
+    );
+    assert_eq!(fixture.balance(&other), 0);
+}
+
+#[test]
+fn jupiter_route_rejects_untrusted_programs_in_the_transaction() {
+    let mut fixture = Fixture::new();
+    let pool = fixture.jupiter_pool();
+    let authority = fixture.authority.insecure_clone();
+    let quoted = mock_amm::quote(SWAP_AMOUNT, POOL_RESERVE, POOL_RESERVE);
+    let route = Fixture::jupiter_route_ix(
+        fixture.jupiter_route_accounts(&authority.pubkey()),
+        SWAP_AMOUNT,
+        fixture.route_accounts(&pool),
+        mock_jupiter::route_data(SWAP_AMOUNT, quoted, 50, 0),
+    );
+    // Moving a pool right before the route, through another program
+    let push_price = Instruction {
+        program_id: raydium_swapper::ID,
+        accounts: fixture
+            .network
+            .swap_accounts(&authority.pubkey(), fixture.source, fixture.destination)
+            .to_account_metas(None),
+        data: raydium_swapper::instruction::Swap { amount_in: SWAP_AMOUNT, min_amount_out: 1 }.data(),
+    };
+    assert_eq!(
+        fixture.test.process_transaction(&[push_price, route.clone()], &[&authority]).unwrap_err(),
+        anchor_error(TraderError::UntrustedInstruction)
+    );
+    assert_eq!(fixture.balance(&fixture.source), SOURCE_BALANCE);
+
+    // Jupiter's own instructions may share the transaction
+    let jupiter = Instruction {
+        program_id: mock_jupiter::ID,
+        accounts: fixture.route_accounts(&pool),
+        data: mock_jupiter::route_data(1_000, mock_amm::quote(1_000, POOL_RESERVE, POOL_RESERVE), 50, 0),
+    };
+    fixture.test.process_transaction(&[jupiter, route], &[&authority]).unwrap();
+    assert_eq!(fixture.balance(&fixture.source), SOURCE_BALANCE - 1_000 - SWAP_AMOUNT);
+    assert_eq!(fixture.trader().total_swaps, 1);
+}
+
+#[test]
+fn jupiter_route_enforces_strategy_slippage() {
+    let mut fixture = Fixture::new();
+    let pool = fixture.jupiter_pool();
+    let quoted = mock_amm::quote(SWAP_AMOUNT, POOL_RESERVE, POOL_RESERVE);
+    let route_accounts = fixture.route_accounts(&pool);
+
+    // Looser than the strategy's 200 bps
+    let loose = mock_jupiter::route_data(SWAP_AMOUNT, quoted, SLIPPAGE_BPS as u16 + 1, 0);
+    assert_eq!(
+        fixture.execute_jupiter_route(SWAP_AMOUNT, route_accounts.clone(), loose).unwrap_err(),
+        anchor_error(TraderError::RouteSlippageTooWide)
+    );
+
+    // An inflated quote the pool cannot fill fails inside Jupiter
+    let inflated = mock_jupiter::route_data(SWAP_AMOUNT, quoted * 2, SLIPPAGE_BPS as u16, 0);
+    assert_eq!(
+        fixture.execute_jupiter_route(SWAP_AMOUNT, route_accounts, inflated).unwrap_err(),
+        ProgramError::Custom(mock_jupiter::SLIPPAGE_TOLERANCE_EXCEEDED)
+    );
+    assert_eq!(fixture.balance(&fixture.destination), 0);
+}
+
+#[test]
+fn jupiter_route_requires_jupiter_program() {
+    let mut fixture = Fixture::new();
+    let pool = fixture.jupiter_pool();
+    let quoted = mock_amm::quote(SWAP_AMOUNT, POOL_RESERVE, POOL_RESERVE);
+    let mut accounts = fixture.jupiter_route_accounts(&fixture.authority.pubkey());
+    accounts.jupiter_program = mock_amm::ID;
+    let mut ix = ix(
+        accounts,
+        instruction::ExecuteJupiterRoute {
+            amount: SWAP_AMOUNT,
+            route_data: mock_jupiter::route_data(SWAP_AMOUNT, quoted, 50, 0),
+        },
+    );
+    ix.accounts.extend(fixture.route_accounts(&pool));
+    let authority = fixture.authority.insecure_clone();
+    assert_eq!(
+        fixture.test.process_instruction(ix, &[&authority]).unwrap_err(),
+        anchor_error(TraderError::InvalidJupiterRoute)
+    );
+}
+
+// ===== JITO TIPS =====
+
+const TIP: u64 = 100_000;
+
+impl Fixture {
+    fn tip_jito_ix(&self, tip_account: Pubkey, lamports: u64) -> Instruction {
+        ix(
+            accounts::TipJito {
+                user_authority: self.authority.pubkey(),
+                trader_config: self.trader_config,
+                tip_account,
+                instructions: sysvar::instructions::ID,
+                system_program: system_program::ID,
+            },
+            instruction::TipJito { lamports },
+        )
+    }
+
+    /// `execute_trade`, then a tip of `lamports` in the same transaction
+    fn execute_with_tip(&mut self, tip_account: Pubkey, lamports: u64) -> Result<(), ProgramError> {
+        let accounts = self.trade_accounts(&self.authority.pubkey(), self.source, self.destination);
+        let execute = ix(accounts, instruction::ExecuteTrade { amount: SWAP_AMOUNT, dry_run: false, nonce: None });
+        let tip = self.tip_jito_ix(tip_account, lamports);
+        let authority = self.authority.insecure_clone();
+        self.test.process_transaction(&[execute, tip], &[&authority])
+    }
+}
+
+#[test]
+fn set_jito_tip_cap_is_audited() {
+    let mut fixture = Fixture::new();
+    assert_eq!(fixture.trader().max_jito_tip, 0);
+
+    fixture.configure(instruction::SetJitoTipCap { max_tip_lamports: TIP }).unwrap();
+    assert_eq!(fixture.trader().max_jito_tip, TIP);
+    let [appended] = fixture.test.events::<AuditEntryAppended>().try_into().ok().unwrap();
+    assert!(matches!(appended.entry.action, AuditAction::JitoTipCapSet { max_tip_lamports: TIP }));
+}
+
+#[test]
+fn jito_tip_pays_alongside_an_execution() {
+    let mut fixture = Fixture::new();
+    fixture.configure(instruction::SetJitoTipCap { max_tip_lamports: TIP }).unwrap();
+    let tip_account = JITO_TIP_ACCOUNTS[3];
+    fixture.test.airdrop(&tip_account, LAMPORTS_PER_SOL);
+
+    fixture.execute_with_tip(tip_account, TIP).unwrap();
+
+    assert_eq!(fixture.test.lamports(&tip_account), LAMPORTS_PER_SOL + TIP);
+    assert_eq!(fixture.trader().total_swaps, 1);
+    let [event] = fixture.test.events::<JitoTipPaid>().try_into().ok().unwrap();
+    assert_eq!((event.trader, event.tip_account, event.lamports), (fixture.trader_config, tip_account, TIP));
+}
+
+#[test]
+fn jito_tip_is_capped_and_bound_to_executions() {
+    let mut fixture = Fixture::new();
+    let tip_account = JITO_TIP_ACCOUNTS[0];
+    fixture.test.airdrop(&tip_account, LAMPORTS_PER_SOL);
+
+    // Tips are off until the authority sets a cap
+    let too_high = anchor_error(TraderError::JitoTipTooHigh);
+    assert_eq!(fixture.execute_with_tip(tip_account, 1).unwrap_err(), too_high);
+    fixture.configure(instruction::SetJitoTipCap { max_tip_lamports: TIP }).unwrap();
+    assert_eq!(fixture.execute_with_tip(tip_account, TIP + 1).unwrap_err(), too_high);
+
+    // Only Jito's tip accounts
+    assert_eq!(
+        fixture.execute_with_tip(Pubkey::new_unique(), TIP).unwrap_err(),
+        anchor_error(TraderError::InvalidJitoTipAccount)
+    );
+
+    // Never on its own
+    let tip = fixture.tip_jito_ix(tip_account, TIP);
+    let authority = fixture.authority.insecure_clone();
+    assert_eq!(
+        fixture.test.process_instruction(tip, &[&authority]).unwrap_err(),
+        anchor_error(TraderError::TipWithoutExecution)
+    );
+
+    // A failed execution takes the tip down with it
+    fixture.execute_with_tip(tip_account, TIP).unwrap();
+    assert_eq!(
+        fixture.execute_with_tip(tip_account, TIP).unwrap_err(),
+        anchor_error(TraderError::RateLimited)
+    );
+    assert_eq!(fixture.test.lamports(&tip_account), LAMPORTS_PER_SOL + TIP);
+}
+
+// ===== DAILY TRADE CAP =====
+
+#[test]
+fn daily_trade_cap_blocks_after_max_trades() {
+    let mut fixture = Fixture::new();
+    fixture.configure(instruction::SetDailyTradeCap { max_trades: 1 }).unwrap();
+    let [appended] = fixture.test.events::<AuditEntryAppended>().try_into().ok().unwrap();
+    assert!(matches!(appended.entry.action, AuditAction::DailyTradeCapSet { max_trades: 1 }));
+
+    fixture.execute_trade(SWAP_AMOUNT).unwrap();
+    fixture.test.warp_forward(TRADE_COOLDOWN);
+    let capped = anchor_error(TraderError::DailyTradeCapReached);
+    assert_eq!(fixture.execute_trade(SWAP_AMOUNT).unwrap_err(), capped);
+    assert_eq!(fixture.dry_run(SWAP_AMOUNT).unwrap_err(), capped);
+
+    // Lifting the cap keeps the count, so lowering it later bites at once
+    fixture.configure(instruction::SetDailyTradeCap { max_trades: 0 }).unwrap();
+    fixture.execute_trade(SWAP_AMOUNT).unwrap();
+    let now = fixture.test.clock().unix_timestamp;
+    assert_eq!(fixture.trader().trade_cap.trades_in_window(now), 2);
+}
+
+// ===== DRAWDOWN =====
+
+#[test]
+fn drawdown_breach_halts_until_reset() {
+    let mut fixture = Fixture::new();
+    assert_eq!(
+        fixture.configure(instruction::SetMaxDrawdown { max_drawdown_bps: 9_001 }).unwrap_err(),
+        anchor_error(TraderError::InvalidInput)
+    );
+    fixture.configure(instruction::SetMaxDrawdown { max_drawdown_bps: 1_000 }).unwrap();
+
+    // First execution marks the peak at the USDC balance
+    fixture.execute_trade(SWAP_AMOUNT).unwrap();
+    assert_eq!(fixture.trader().risk.peak_equity, SOURCE_BALANCE);
+
+    // Equity collapses to 1% of the peak through the strategy's own trades
+    fixture.lose_all_but(SWAP_AMOUNT);
+    fixture.test.warp_forward(TRADE_COOLDOWN);
+
+    // The halt persists instead of failing the transaction
+    fixture.execute_trade(SWAP_AMOUNT).unwrap();
+    let trader = fixture.trader();
+    assert!(trader.risk.halted);
+    assert_eq!(trader.risk.last_drawdown_bps, 9_900);
+    assert_eq!(trader.total_swaps, 1);
+    assert_eq!(fixture.balance(&fixture.source), SWAP_AMOUNT);
+    let [event] = fixture.test.events::<DrawdownLimitHit>().try_into().ok().unwrap();
+    assert_eq!(event.account, fixture.trader_config);
+    assert_eq!(event.peak_equity, SOURCE_BALANCE);
+    assert_eq!(event.equity, SWAP_AMOUNT);
+    assert_eq!(event.max_drawdown_bps, 1_000);
+
+    fixture.test.warp_forward(TRADE_COOLDOWN);
+    assert_eq!(
+        fixture.execute_trade(SWAP_AMOUNT).unwrap_err(),
+        anchor_error(TraderError::DrawdownLimitExceeded)
+    );
+
+    fixture.configure(instruction::ResetDrawdown {}).unwrap();
+    let risk = fixture.trader().risk;
+    assert!(!risk.halted);
+    assert_eq!(risk.peak_equity, 0);
+    assert_eq!(risk.max_drawdown_bps, 1_000);
+
+    // The peak re-baselines at the reduced equity
+    fixture.execute_trade(SWAP_AMOUNT).unwrap();
+    let trader = fixture.trader();
+    assert_eq!(trader.total_swaps, 2);
+    assert_eq!(trader.risk.peak_equity, SWAP_AMOUNT);
+}
+
+#[test]
+fn withdrawals_rebase_the_drawdown_peak() {
+    let mut fixture = Fixture::new();
+    fixture.configure(instruction::SetMaxDrawdown { max_drawdown_bps: 1_000 }).unwrap();
+    fixture.execute_trade(SWAP_AMOUNT).unwrap();
+    let marked = fixture.trader();
+    assert_eq!(marked.marked_source_balance, SOURCE_BALANCE - SWAP_AMOUNT);
+    assert_eq!(marked.marked_destination_balance, fixture.balance(&fixture.destination));
+
+    // The owner withdraws all but 1% of both balances between executions
+    let owner = fixture.authority.pubkey();
+    let destination_balance = fixture.balance(&fixture.destination);
+    fixture.test.create_token_account(fixture.source, &fixture.network.usdc, &owner, SWAP_AMOUNT);
+    fixture.test.create_token_account(fixture.destination, &fixture.network.wsol, &owner, destination_balance / 100);
+    fixture.test.warp_forward(TRADE_COOLDOWN);
+
+    fixture.execute_trade(SWAP_AMOUNT).unwrap();
+    let trader = fixture.trader();
+    assert!(!trader.risk.halted);
+    assert_eq!(trader.total_swaps, 2);
+    assert!(trader.risk.peak_equity < marked.risk.peak_equity / 50);
+    assert!(fixture.test.events::<DrawdownLimitHit>().is_empty());
+}
+
+// ===== ADMIN =====
+
+#[test]
+fn pause_and_unpause_require_admin() {
+    let mut fixture = Fixture::new();
+    let authority = fixture.authority.insecure_clone();
+    let pause_accounts = || accounts::PauseTrader {
+        trader_config: fixture.trader_config,
+        audit_log: Some(fixture.audit_log),
+        admin: authority.pubkey(),
+        role_registry: None,
+    };
+
+    let pause = ix(pause_accounts(), instruction::PauseTrader {});
+    let unpause = ix(pause_accounts(), instruction::UnpauseTrader {});
+    let unauthorized = anchor_error(TraderError::UnauthorizedAdmin);
+    assert_eq!(fixture.test.process_instruction(pause, &[&authority]).unwrap_err(), unauthorized);
+    assert_eq!(fixture.test.process_instruction(unpause, &[&authority]).unwrap_err(), unauthorized);
+    assert!(fixture.trader().is_active);
+    assert_eq!(fixture.audit_log().entry_count, 0);
+}
+
+#[test]
+fn pauser_pauses_but_cannot_unpause() {
+    let mut fixture = Fixture::new();
+    let pauser = Keypair::new();
+    let role_registry = fixture.test.set_role_registry(&[(pauser.pubkey(), Role::Pauser)]);
+    let pause_accounts = || accounts::PauseTrader {
+        trader_config: fixture.trader_config,
+        audit_log: Some(fixture.audit_log),
+        admin: pauser.pubkey(),
+        role_registry: Some(role_registry),
+    };
+
+    let pause = ix(pause_accounts(), instruction::PauseTrader {});
+    let unpause = ix(pause_accounts(), instruction::UnpauseTrader {});
+    fixture.test.process_instruction(pause, &[&pauser]).unwrap();
+    assert!(!fixture.trader().is_active);
+    let [appended] = fixture.test.events::<AuditEntryAppended>().try_into().ok().unwrap();
+    assert_eq!(appended.entry.actor, pauser.pubkey());
+    assert!(matches!(appended.entry.action, AuditAction::Paused));
+
+    let err = fixture.test.process_instruction(unpause, &[&pauser]).unwrap_err();
+    assert_eq!(err, anchor_error(TraderError::UnauthorizedAdmin));
+    assert!(!fixture.trader().is_active);
+}
+
+#[test]
+fn pauser_pauses_strategies_without_an_audit_log() {
+    let mut fixture = Fixture::new();
+    let pauser = Keypair::new();
+    let role_registry = fixture.test.set_role_registry(&[(pauser.pubkey(), Role::Pauser)]);
+    let owner = funded(&mut fixture.test);
+    initialize_trader(&mut fixture.test, &owner, None, PRICE_THRESHOLD, SWAP_AMOUNT, 50).unwrap();
+    let trader_config = trader_address(&owner.pubkey());
+
+    let pause = ix(
+        accounts::PauseTrader {
+            trader_config,
+            audit_log: None,
+            admin: pauser.pubkey(),
+            role_registry: Some(role_registry),
+        },
+        instruction::PauseTrader {},
+    );
+    fixture.test.process_instruction(pause, &[&pauser]).unwrap();
+    let trader: TraderConfig = fixture.test.anchor_account(&trader_config).unwrap();
+    assert!(!trader.is_active);
+    assert!(fixture.test.events::<AuditEntryAppended>().is_empty());
+}
+
+// ===== COPY TRADING =====
+
+/// A follower of the fixture's trader at 50%, granting the lead 1%
+struct Follower {
+    keypair: Keypair,
+    config: Pubkey,
+    source: Pubkey,
+    destination: Pubkey,
+    lead_fee_account: Pubkey,
+}
+
+const FOLLOW_RATIO_BPS: u16 = 5_000;
+const LEAD_FEE_BPS: u16 = 100;
+
+fn follow_ix(fixture: &Fixture, follower: &Pubkey, ratio_bps: u16, lead_fee_bps: u16) -> Instruction {
+    ix(
+        accounts::FollowTrader {
+            lead_config: fixture.trader_config,
+            follower_config: pda(&[b"follower", follower.as_ref(), fixture.trader_config.as_ref()]),
+            follower: *follower,
+            system_program: system_program::ID,
+        },
+        instruction::FollowTrader {
+            ratio_bps,
+            max_trade_amount: 10 * SWAP_AMOUNT,
+            slippage_tolerance: SLIPPAGE_BPS,
+            lead_fee_bps,
+        },
+    )
+}
+
+fn follower(fixture: &mut Fixture) -> Follower {
+    let keypair = funded(&mut fixture.test);
+    let ix = follow_ix(fixture, &keypair.pubkey(), FOLLOW_RATIO_BPS, LEAD_FEE_BPS);
+    fixture.test.process_instruction(ix, &[&keypair]).unwrap();
+    let (usdc, wsol, lead) = (fixture.network.usdc, fixture.network.wsol, fixture.authority.pubkey());
+    Follower {
+        config: pda(&[b"follower", keypair.pubkey().as_ref(), fixture.trader_config.as_ref()]),
+        source: fixture.token_account(&usdc, &keypair.pubkey(), SOURCE_BALANCE),
+        destination: fixture.token_account(&wsol, &keypair.pubkey(), 0),
+        lead_fee_account: fixture.token_account(&usdc, &lead, 0),
+        keypair,
+    }
+}
+
+/// Approve the follower config as delegate on the follower's source account
+fn approve(follower: &Follower, test: &mut ProgramTest, amount: u64) {
+    let ix = spl_token::instruction::approve(
+        &spl_token::ID,
+        &follower.source,
+        &follower.config,
+        &follower.keypair.pubkey(),
+        &[],
+        amount,
+    )
+    .unwrap();
+    test.process_instruction(ix, &[&follower.keypair]).unwrap();
+}
+
+fn copy_trade_accounts(fixture: &Fixture, follower: &Follower) -> accounts::CopyTrade {
+    let pool = &fixture.network.pool;
+    accounts::CopyTrade {
+        keeper: fixture.test.payer().pubkey(),
+        lead_config: fixture.trader_config,
+        follower_config: follower.config,
+        follower_source_token_account: follower.source,
+        follower_destination_token_account: follower.destination,
+        lead_fee_account: follower.lead_fee_account,
+        raydium_amm_program: pool.amm_program,
+        amm: pool.amm,
+        amm_authority: pool.amm_authority,
+        amm_open_orders: pool.amm_open_orders,
+        amm_target_orders: pool.amm_target_orders,
+        pool_coin_token_account: pool.pool_coin_token_account,
+        pool_pc_token_account: pool.pool_pc_token_account,
+        serum_program: pool.serum_program,
+        serum_market: pool.serum_market,
+        serum_bids: pool.serum_bids,
+        serum_asks: pool.serum_asks,
+        serum_event_queue: pool.serum_event_queue,
+        serum_coin_vault_account: pool.serum_coin_vault,
+        serum_pc_vault_account: pool.serum_pc_vault,
+        serum_vault_signer: pool.serum_vault_signer,
+        vectai_oracle_program: vectai_oracle::ID,
+        price_feed: fixture.network.price_feed,
+        instructions: sysvar::instructions::ID,
+        protocol_fee_account: None,
+        token_program: spl_token::ID,
+        protocol_status: fixture.network.protocol_status,
+        network_config: fixture.network.network_config,
+        protocol_stats: fixture.network.protocol_stats,
+        stats_reporter: vectai_protocol::stats_reporter(&vectai_trader::ID),
+        protocol_program: vectai_protocol::ID,
+    }
+}
+
+fn copy_trade_ix(fixture: &Fixture, follower: &Follower) -> Instruction {
+    ix(copy_trade_accounts(fixture, follower), instruction::CopyTrade {})
+}
+
+fn lead_trade_ix(fixture: &Fixture) -> Instruction {
+    ix(
+        fixture.trade_accounts(&fixture.authority.pubkey(), fixture.source, fixture.destination),
+        instruction::ExecuteTrade { amount: SWAP_AMOUNT, dry_run: false, nonce: None },
+    )
+}
+
+#[test]
+fn follow_trader_records_config() {
+    let mut fixture = Fixture::new();
+    fixture.execute_trade(SWAP_AMOUNT).unwrap();
+    let follower = follower(&mut fixture);
+
+    let config: FollowerConfig = fixture.test.anchor_account(&follower.config).unwrap();
+    assert_eq!(config.follower, follower.keypair.pubkey());
+    assert_eq!(config.lead, fixture.trader_config);
+    assert_eq!(config.ratio_bps, FOLLOW_RATIO_BPS);
+    assert_eq!(config.max_trade_amount, 10 * SWAP_AMOUNT);
+    assert_eq!(config.lead_fee_bps, LEAD_FEE_BPS);
+    // Only trades after following are copied
+    assert_eq!(config.last_copied_swap, 1);
+    assert_eq!(config.total_copied, 0);
+}
+
+#[test]
+fn follow_trader_rejects_invalid_parameters() {
+    let mut fixture = Fixture::new();
+    let keypair = funded(&mut fixture.test);
+    let invalid = anchor_error(TraderError::InvalidInput);
+
+    for (ratio_bps, lead_fee_bps) in [(0, 0), (10_001, 0), (FOLLOW_RATIO_BPS, 2_001)] {
+        let ix = follow_ix(&fixture, &keypair.pubkey(), ratio_bps, lead_fee_bps);
+        assert_eq!(fixture.test.process_instruction(ix, &[&keypair]).unwrap_err(), invalid);
+    }
+
+    // No following yourself
+    let authority = fixture.authority.insecure_clone();
+    let ix = follow_ix(&fixture, &authority.pubkey(), FOLLOW_RATIO_BPS, 0);
+    assert_eq!(fixture.test.process_instruction(ix, &[&authority]).unwrap_err(), invalid);
+}
+
+#[test]
+fn unfollow_trader_closes_config() {
+    let mut fixture = Fixture::new();
+    let follower = follower(&mut fixture);
+    let lamports = fixture.test.lamports(&follower.keypair.pubkey());
+    let rent = fixture.test.lamports(&follower.config);
+
+    let ix = ix(
+        accounts::UnfollowTrader {
+            follower_config: follower.config,
+            follower: follower.keypair.pubkey(),
+        },
+        instruction::UnfollowTrader {},
+    );
+    fixture.test.process_instruction(ix, &[&follower.keypair]).unwrap();
+
+    assert!(fixture.test.account(&follower.config).is_none());
+    assert_eq!(fixture.test.lamports(&follower.keypair.pubkey()), lamports + rent);
+}
+
+#[test]
+fn copy_trade_mirrors_lead_trade() {
+    let mut fixture = Fixture::new();
+    let follower = follower(&mut fixture);
+    approve(&follower, &mut fixture.test, SWAP_AMOUNT);
+    let pool_wsol = fixture.balance(&fixture.network.pool.pool_coin_token_account);
+
+    let instructions = [lead_trade_ix(&fixture), copy_trade_ix(&fixture, &follower)];
+    let authority = fixture.authority.insecure_clone();
+    fixture.test.process_transaction(&instructions, &[&authority]).unwrap();
+
+    let amount = SWAP_AMOUNT * FOLLOW_RATIO_BPS as u64 / 10_000;
+    let lead_fee = amount * LEAD_FEE_BPS as u64 / 10_000;
+    assert_eq!(fixture.balance(&follower.source), SOURCE_BALANCE - amount);
+    assert_eq!(fixture.balance(&follower.lead_fee_account), lead_fee);
+    let lead_out = fixture.balance(&fixture.destination);
+    let follower_out = fixture.balance(&follower.destination);
+    assert!(follower_out >= (amount - lead_fee) * (10_000 - SLIPPAGE_BPS) / 10_000);
+    assert_eq!(lead_out + follower_out, pool_wsol - fixture.balance(&fixture.network.pool.pool_coin_token_account));
+
+    let config: FollowerConfig = fixture.test.anchor_account(&follower.config).unwrap();
+    assert_eq!(config.last_copied_swap, 1);
+    assert_eq!(config.total_copied, 1);
+
+    // Both the lead and the copied trade count, each after its fee
+    let stats: ProtocolStats = fixture.test.anchor_account(&fixture.network.protocol_stats).unwrap();
+    assert_eq!(stats.executions, 2);
+    assert_eq!(stats.volume(&fixture.network.usdc).unwrap().executed, SWAP_AMOUNT + amount - lead_fee);
+}
+
+#[test]
+fn copy_trade_requires_fresh_lead_trade_and_delegation() {
+    let mut fixture = Fixture::new();
+    let follower = follower(&mut fixture);
+    let authority = fixture.authority.insecure_clone();
+
+    // No lead trade in the transaction
+    let copy = copy_trade_ix(&fixture, &follower);
+    assert_eq!(
+        fixture.test.process_instruction(copy, &[]).unwrap_err(),
+        anchor_error(TraderError::LeadTradeNotFound)
+    );
+
+    // Follower hasn't approved its config as delegate
+    let instructions = [lead_trade_ix(&fixture), copy_trade_ix(&fixture, &follower)];
+    assert_eq!(
+        fixture.test.process_transaction(&instructions, &[&authority]).unwrap_err(),
+        anchor_error(TraderError::DelegationRequired)
+    );
+
+    // A lead trade is copied once
+    approve(&follower, &mut fixture.test, 10 * SWAP_AMOUNT);
+    let mut dry_run = lead_trade_ix(&fixture);
+    dry_run.data = instruction::ExecuteTrade { amount: SWAP_AMOUNT, dry_run: true, nonce: None }.data();
+    let instructions = [dry_run, copy_trade_ix(&fixture, &follower)];
+    assert_eq!(
+        fixture.test.process_transaction(&instructions, &[&authority]).unwrap_err(),
+        anchor_error(TraderError::LeadTradeNotFound)
+    );
+    let instructions = [
+        lead_trade_ix(&fixture),
+        copy_trade_ix(&fixture, &follower),
+        copy_trade_ix(&fixture, &follower),
+    ];
+    assert_eq!(
+        fixture.test.process_transaction(&instructions, &[&authority]).unwrap_err(),
+        anchor_error(TraderError::AlreadyCopied)
+    );
+    assert_eq!(fixture.trader().total_swaps, 0);
+}
+
+#[test]
+fn copy_trade_prices_from_the_lead_trades_feed() {
+    let mut fixture = Fixture::new();
+    let follower = follower(&mut fixture);
+    approve(&follower, &mut fixture.test, SWAP_AMOUNT);
+    let authority = fixture.authority.insecure_clone();
+
+    let accounts = accounts::CopyTrade { price_feed: Pubkey::new_unique(), ..copy_trade_accounts(&fixture, &follower) };
+    let copy = ix(accounts, instruction::CopyTrade {});
+    assert_eq!(
+        fixture.test.process_transaction(&[lead_trade_ix(&fixture), copy], &[&authority]).unwrap_err(),
+        anchor_error(TraderError::LeadPriceFeedMismatch)
+    );
+    assert_eq!(fixture.balance(&follower.source), SOURCE_BALANCE);
+}
+
+#[test]
+fn copy_trade_holds_the_follower_to_the_oracle_price() {
+    let mut fixture = Fixture::new();
+    // A follower selling WSOL: the oracle values each lamport at 45 USDC
+    // units, which the 1:1 pool can't pay out
+    let keypair = funded(&mut fixture.test);
+    let ix = follow_ix(&fixture, &keypair.pubkey(), FOLLOW_RATIO_BPS, LEAD_FEE_BPS);
+    fixture.test.process_instruction(ix, &[&keypair]).unwrap();
+    let (usdc, wsol, lead) = (fixture.network.usdc, fixture.network.wsol, fixture.authority.pubkey());
+    let follower = Follower {
+        config: pda(&[b"follower", keypair.pubkey().as_ref(), fixture.trader_config.as_ref()]),
+        source: fixture.token_account(&wsol, &keypair.pubkey(), SOURCE_BALANCE),
+        destination: fixture.token_account(&usdc, &keypair.pubkey(), 0),
+        lead_fee_account: fixture.token_account(&wsol, &lead, 0),
+        keypair,
+    };
+    approve(&follower, &mut fixture.test, SWAP_AMOUNT);
+
+    let instructions = [lead_trade_ix(&fixture), copy_trade_ix(&fixture, &follower)];
+    let authority = fixture.authority.insecure_clone();
+    assert_eq!(
+        fixture.test.process_transaction(&instructions, &[&authority]).unwrap_err(),
+        ProgramError::Custom(mock_amm::EXCEEDED_SLIPPAGE)
+    );
+    assert_eq!(fixture.balance(&follower.source), SOURCE_BALANCE);
+}
+
+// ===== MARKETPLACE =====
+
+const LISTING_ID: u64 = 7;
+const LISTING_FEE: u64 = 1_000;
+const PERIOD: i64 = 30 * DAY;
+
+/// A listing of the fixture's network and a subscriber holding VECTAI
+struct Market {
+    creator: Keypair,
+    listing: Pubkey,
+    creator_fee_account: Pubkey,
+    protocol_fee_account: Pubkey,
+    subscriber: Keypair,
+    subscriber_vectai_account: Pubkey,
+    subscription: Pubkey,
+}
+
+fn create_listing_ix(creator: &Pubkey, creator_fee_account: Pubkey, fee_amount: u64, period_duration: i64) -> Instruction {
+    ix(
+        accounts::CreateListing {
+            listing: pda(&[b"listing", creator.as_ref(), &LISTING_ID.to_le_bytes()]),
+            creator_fee_account,
+            creator: *creator,
+            system_program: system_program::ID,
+        },
+        instruction::CreateListing {
+            listing_id: LISTING_ID,
+            price_threshold: PRICE_THRESHOLD,
+            swap_amount: SWAP_AMOUNT,
+            slippage_tolerance: SLIPPAGE_BPS,
+            fee_amount,
+            period_duration,
+        },
+    )
+}
+
+fn market(fixture: &mut Fixture) -> Market {
+    let creator = funded(&mut fixture.test);
+    let creator_fee_account = fixture.token_account(&VECTAI_MINT, &creator.pubkey(), 0);
+    let ix = create_listing_ix(&creator.pubkey(), creator_fee_account, LISTING_FEE, PERIOD);
+    fixture.test.process_instruction(ix, &[&creator]).unwrap();
+
+    let listing = pda(&[b"listing", creator.pubkey().as_ref(), &LISTING_ID.to_le_bytes()]);
+    let protocol_fee_account = vectai_treasury::vault_address(&VECTAI_MINT);
+    fixture.test.create_token_account(protocol_fee_account, &VECTAI_MINT, &protocol_fee_account, 0);
+    let subscriber = funded(&mut fixture.test);
+    Market {
+        creator,
+        listing,
+        creator_fee_account,
+        protocol_fee_account,
+        subscriber_vectai_account: fixture.token_account(&VECTAI_MINT, &subscriber.pubkey(), 10 * LISTING_FEE),
+        subscription: pda(&[b"subscription", listing.as_ref(), subscriber.pubkey().as_ref()]),
+        subscriber,
+    }
+}
+
+impl Market {
+    fn manage(&self, test: &mut ProgramTest, data: impl InstructionData, signer: &Keypair) -> Result<(), ProgramError> {
+        let ix = ix(
+            accounts::ManageListing {
+                listing: self.listing,
+                creator: signer.pubkey(),
+            },
+            data,
+        );
+        test.process_instruction(ix, &[signer])
+    }
+
+    fn subscribe_accounts(&self, fixture: &Fixture) -> accounts::Subscribe {
+        accounts::Subscribe {
+            listing: self.listing,
+            subscription: self.subscription,
+            trader_config: self.trader_config(),
+            subscriber_vectai_account: self.subscriber_vectai_account,
+            creator_fee_account: self.creator_fee_account,
+            protocol_fee_account: self.protocol_fee_account,
+            referral_code: None,
+            referral_escrow: None,
+            membership_token: None,
+            membership_metadata: None,
+            subscriber: self.subscriber.pubkey(),
+            token_program: spl_token::ID,
+            system_program: system_program::ID,
+            protocol_status: fixture.network.protocol_status,
+        }
+    }
+
+    fn subscribe_with(&self, fixture: &mut Fixture, accounts: accounts::Subscribe) -> Result<(), ProgramError> {
+        fixture
+            .test
+            .process_instruction(ix(accounts, instruction::Subscribe {}), &[&self.subscriber])
+    }
+
+    fn subscribe(&self, fixture: &mut Fixture) -> Result<(), ProgramError> {
+        let accounts = self.subscribe_accounts(fixture);
+        self.subscribe_with(fixture, accounts)
+    }
+
+    fn renew(&self, fixture: &mut Fixture) -> Result<(), ProgramError> {
+        let ix = ix(
+            accounts::RenewSubscription {
+                listing: self.listing,
+                subscription: self.subscription,
+                trader_config: self.trader_config(),
+                subscriber_vectai_account: self.subscriber_vectai_account,
+                creator_fee_account: self.creator_fee_account,
+                protocol_fee_account: self.protocol_fee_account,
+                referral_escrow: None,
+                membership_token: None,
+                membership_metadata: None,
+                subscriber: self.subscriber.pubkey(),
+                token_program: spl_token::ID,
+                protocol_status: fixture.network.protocol_status,
+            },
+            instruction::RenewSubscription {},
+        );
+        fixture.test.process_instruction(ix, &[&self.subscriber])
+    }
+
+    fn trader_config(&self) -> Pubkey {
+        subscription_trader_address(&self.subscriber.pubkey(), &self.listing)
+    }
+
+    fn paid_until(&self, test: &ProgramTest) -> i64 {
+        test.anchor_account::<Subscription>(&self.subscription).unwrap().paid_until
+    }
+}
+
+#[test]
+fn create_listing_records_terms() {
+    let mut fixture = Fixture::new();
+    let market = market(&mut fixture);
+
+    let listing: StrategyListing = fixture.test.anchor_account(&market.listing).unwrap();
+    assert_eq!(listing.creator, market.creator.pubkey());
+    assert_eq!(listing.listing_id, LISTING_ID);
+    assert_eq!(listing.price_threshold, PRICE_THRESHOLD);
+    assert_eq!(listing.swap_amount, SWAP_AMOUNT);
+    assert_eq!(listing.fee_amount, LISTING_FEE);
+    assert_eq!(listing.period_duration, PERIOD);
+    assert_eq!(listing.creator_fee_account, market.creator_fee_account);
+    assert!(listing.is_active);
+    assert_eq!(listing.required_collection, Pubkey::default());
+}
+
+#[test]
+fn create_listing_rejects_invalid_terms() {
+    let mut fixture = Fixture::new();
+    let creator = funded(&mut fixture.test);
+    let fee_account = fixture.token_account(&VECTAI_MINT, &creator.pubkey(), 0);
+
+    for (fee_amount, period) in [(0, PERIOD), (LISTING_FEE, 0)] {
+        let ix = create_listing_ix(&creator.pubkey(), fee_account, fee_amount, period);
+        assert_eq!(
+            fixture.test.process_instruction(ix, &[&creator]).unwrap_err(),
+            anchor_error(TraderError::InvalidInput)
+        );
+    }
+
+    // Fees must be paid out in VECTAI
+    let usdc = fixture.network.usdc;
+    let usdc_account = fixture.token_account(&usdc, &creator.pubkey(), 0);
+    let ix = create_listing_ix(&creator.pubkey(), usdc_account, LISTING_FEE, PERIOD);
+    assert_eq!(
+        fixture.test.process_instruction(ix, &[&creator]).unwrap_err(),
+        anchor_error(TraderError::InvalidTokenAccount)
+    );
+}
+
+#[test]
+fn manage_listing_requires_creator() {
+    let mut fixture = Fixture::new();
+    let market = market(&mut fixture);
+    let collection = Pubkey::new_unique();
+
+    let subscriber = market.subscriber.insecure_clone();
+    assert_eq!(
+        market
+            .manage(&mut fixture.test, instruction::SetListingActive { is_active: false }, &subscriber)
+            .unwrap_err(),
+        anchor_error(TraderError::Unauthorized)
+    );
+
+    let creator = market.creator.insecure_clone();
+    market.manage(&mut fixture.test, instruction::SetListingActive { is_active: false }, &creator).unwrap();
+    market.manage(&mut fixture.test, instruction::SetListingCollection { collection }, &creator).unwrap();
+    let listing: StrategyListing = fixture.test.anchor_account(&market.listing).unwrap();
+    assert!(!listing.is_active);
+    assert_eq!(listing.required_collection, collection);
+}
+
+#[test]
+fn subscribe_splits_fee_and_instantiates_trader() {
+    let mut fixture = Fixture::new();
+    let market = market(&mut fixture);
+    let now = fixture.test.clock().unix_timestamp;
+
+    market.subscribe(&mut fixture).unwrap();
+
+    let protocol_amount = LISTING_FEE / 10;
+    assert_eq!(fixture.balance(&market.creator_fee_account), LISTING_FEE - protocol_amount);
+    assert_eq!(fixture.balance(&market.protocol_fee_account), protocol_amount);
+    assert_eq!(fixture.balance(&market.subscriber_vectai_account), 9 * LISTING_FEE);
+
+    let trader: TraderConfig = fixture
+        .test
+        .anchor_account(&market.trader_config())
+        .unwrap();
+    assert_eq!(trader.authority, market.subscriber.pubkey());
+    assert_eq!(trader.listing, market.listing);
+    assert_eq!(trader.price_threshold, PRICE_THRESHOLD);
+    assert_eq!(trader.swap_amount, SWAP_AMOUNT);
+    assert_eq!(trader.subscription_expires_at, now + PERIOD);
+
+    let subscription: Subscription = fixture.test.anchor_account(&market.subscription).unwrap();
+    assert_eq!(subscription.listing, market.listing);
+    assert_eq!(subscription.trader_config, market.trader_config());
+    assert_eq!(subscription.paid_until, now + PERIOD);
+
+    let listing: StrategyListing = fixture.test.anchor_account(&market.listing).unwrap();
+    assert_eq!(listing.subscribers, 1);
+    assert_eq!(listing.total_revenue, LISTING_FEE);
+}
+
+#[test]
+fn subscribe_keeps_the_subscribers_own_strategy() {
+    let mut fixture = Fixture::new();
+    let market = market(&mut fixture);
+    initialize_trader(&mut fixture.test, &market.subscriber, None, 2 * PRICE_THRESHOLD, 2 * SWAP_AMOUNT, 50).unwrap();
+
+    market.subscribe(&mut fixture).unwrap();
+
+    let own: TraderConfig = fixture.test.anchor_account(&trader_address(&market.subscriber.pubkey())).unwrap();
+    assert_eq!((own.swap_amount, own.listing), (2 * SWAP_AMOUNT, Pubkey::default()));
+    let subscribed: TraderConfig = fixture.test.anchor_account(&market.trader_config()).unwrap();
+    assert_eq!((subscribed.swap_amount, subscribed.listing), (SWAP_AMOUNT, market.listing));
+}
+
+#[test]
+fn subscribe_rejects_closed_or_gated_listing() {
+    let mut fixture = Fixture::new();
+    let market = market(&mut fixture);
+    let creator = market.creator.insecure_clone();
+
+    market.manage(&mut fixture.test, instruction::SetListingActive { is_active: false }, &creator).unwrap();
+    assert_eq!(market.subscribe(&mut fixture).unwrap_err(), anchor_error(TraderError::ListingInactive));
+
+    market.manage(&mut fixture.test, instruction::SetListingActive { is_active: true }, &creator).unwrap();
+    let collection = Pubkey::new_unique();
+    market.manage(&mut fixture.test, instruction::SetListingCollection { collection }, &creator).unwrap();
+    assert_eq!(market.subscribe(&mut fixture).unwrap_err(), anchor_error(TraderError::MembershipRequired));
+    assert_eq!(fixture.balance(&market.subscriber_vectai_account), 10 * LISTING_FEE);
+}
+
+#[test]
+fn subscribe_pays_referral_share() {
+    let mut fixture = Fixture::new();
+    let market = market(&mut fixture);
+    let code = Pubkey::new_unique();
+    fixture.test.set_anchor_account(
+        code,
+        &ReferralCode {
+            owner: Pubkey::new_unique(),
+            code: "VECT".to_string(),
+            created_at: 0,
+            bump: 255,
+        },
+    );
+    let escrow = vectai_referral::escrow_address(&code, &VECTAI_MINT);
+    fixture.test.create_token_account(escrow, &VECTAI_MINT, &escrow, 0);
+
+    let mut accounts = market.subscribe_accounts(&fixture);
+    accounts.referral_code = Some(code);
+    assert_eq!(
+        market.subscribe_with(&mut fixture, accounts).unwrap_err(),
+        anchor_error(TraderError::InvalidReferral)
+    );
+
+    let mut accounts = market.subscribe_accounts(&fixture);
+    accounts.referral_code = Some(code);
+    accounts.referral_escrow = Some(escrow);
+    market.subscribe_with(&mut fixture, accounts).unwrap();
+
+    let protocol_amount = LISTING_FEE / 10;
+    let referral_amount =
+        vectai_referral::referral_share(protocol_amount, FeeConfig::INITIAL.referral_share_bps);
+    assert_eq!(fixture.balance(&escrow), referral_amount);
+    assert_eq!(fixture.balance(&market.protocol_fee_account), protocol_amount - referral_amount);
+}
+
+#[test]
+fn renew_subscription_extends_paid_period() {
+    let mut fixture = Fixture::new();
+    let market = market(&mut fixture);
+    market.subscribe(&mut fixture).unwrap();
+    let paid_until = market.paid_until(&fixture.test);
+
+    // Early renewal extends from expiry
+    fixture.test.warp_forward(DAY);
+    market.renew(&mut fixture).unwrap();
+    assert_eq!(market.paid_until(&fixture.test), paid_until + PERIOD);
+
+    // A lapsed subscription restarts from now
+    fixture.test.warp_forward(3 * PERIOD);
+    let now = fixture.test.clock().unix_timestamp;
+    market.renew(&mut fixture).unwrap();
+    assert_eq!(market.paid_until(&fixture.test), now + PERIOD);
+    let trader: TraderConfig = fixture
+        .test
+        .anchor_account(&market.trader_config())
+        .unwrap();
+    assert_eq!(trader.subscription_expires_at, now + PERIOD);
+    assert_eq!(fixture.balance(&market.subscriber_vectai_account), 7 * LISTING_FEE);
+}
+
+#[test]
+fn expired_subscription_blocks_trades() {
+    let mut fixture = Fixture::new();
+    let market = market(&mut fixture);
+    market.subscribe(&mut fixture).unwrap();
+    let subscriber = market.subscriber.insecure_clone();
+    let (usdc, wsol) = (fixture.network.usdc, fixture.network.wsol);
+    let source = fixture.token_account(&usdc, &subscriber.pubkey(), SOURCE_BALANCE);
+    let destination = fixture.token_account(&wsol, &subscriber.pubkey(), 0);
+
+    let trade_accounts = |fixture: &Fixture| accounts::ExecuteTrade {
+        trader_config: market.trader_config(),
+        ..fixture.trade_accounts(&subscriber.pubkey(), source, destination)
+    };
+    let accounts = trade_accounts(&fixture);
+    fixture.execute_trade_with(accounts, SWAP_AMOUNT, &subscriber).unwrap();
+
+    fixture.test.warp_forward(PERIOD);
+    let accounts = trade_accounts(&fixture);
+    assert_eq!(
+        fixture.execute_trade_with(accounts, SWAP_AMOUNT, &subscriber).unwrap_err(),
+        anchor_error(TraderError::SubscriptionExpired)
+    );
+}
+
+#[test]
+fn close_listing_and_subscription_require_them_to_end() {
+    let mut fixture = Fixture::new();
+    let market = market(&mut fixture);
+    market.subscribe(&mut fixture).unwrap();
+    let (creator, subscriber) = (market.creator.insecure_clone(), market.subscriber.insecure_clone());
+    let close_listing = ix(
+        accounts::CloseListing {
+            listing: market.listing,
+            creator: creator.pubkey(),
+        },
+        instruction::CloseListing {},
+    );
+    let close_subscription = ix(
+        accounts::CloseSubscription {
+            subscription: market.subscription,
+            subscriber: subscriber.pubkey(),
+        },
+        instruction::CloseSubscription {},
+    );
+    let in_use = anchor_error(TraderError::AccountInUse);
+
+    assert_eq!(fixture.test.process_instruction(close_listing.clone(), &[&creator]).unwrap_err(), in_use);
+    market.manage(&mut fixture.test, instruction::SetListingActive { is_active: false }, &creator).unwrap();
+    fixture.test.process_instruction(close_listing, &[&creator]).unwrap();
+    assert!(fixture.test.account(&market.listing).is_none());
+
+    // Outlives its listing until the paid period ends
+    assert_eq!(fixture.test.process_instruction(close_subscription.clone(), &[&subscriber]).unwrap_err(), in_use);
+    fixture.test.warp_forward(PERIOD);
+    let lamports = fixture.test.lamports(&subscriber.pubkey());
+    let rent = fixture.test.lamports(&market.subscription);
+    fixture.test.process_instruction(close_subscription, &[&subscriber]).unwrap();
+    assert!(fixture.test.account(&market.subscription).is_none());
+    assert_eq!(fixture.test.lamports(&subscriber.pubkey()), lamports + rent);
+}
+
+// ===== DRIFT HEDGE =====
+
+const HEDGE_MARKET: u16 = 1;
+
+/// Hedge config written directly, with a flat Drift user; creating it
+/// needs Drift
+fn hedge(fixture: &mut Fixture, min_position: u64) -> (Pubkey, HedgeConfig) {
+    let (address, bump) = Pubkey::find_program_address(
+        &[b"hedge", fixture.trader_config.as_ref()],
+        &vectai_trader::ID,
+    );
+    let config = HedgeConfig {
+        trader: fixture.trader_config,
+        drift_user: Pubkey::new_unique(),
+        spot_mint: fixture.network.wsol,
+        perp_market_index: HEDGE_MARKET,
+        hedge_ratio_bps: 5_000,
+        min_position,
+        hedged_amount: 0,
+        spot_position: fixture.destination,
+        collateral: 0,
+        bump,
+    };
+    fixture.test.set_anchor_account(address, &config);
+    set_drift_position(fixture, &config.drift_user, 0, 0);
+    (address, config)
+}
+
+/// The hedge's Drift `User`, whose second perp slot holds `base` in
+/// `HEDGE_MARKET` (negative = short) with `open_orders` unfilled, behind a
+/// long in another market
+fn set_drift_position(fixture: &mut Fixture, drift_user: &Pubkey, base: i64, open_orders: u8) {
+    let positions = [
+        PerpPosition { market_index: 0, base_asset_amount: LAMPORTS_PER_SOL as i64, open_orders: 2 },
+        PerpPosition { market_index: HEDGE_MARKET, base_asset_amount: base, open_orders },
+    ];
+    let authority = hedge_authority(fixture);
+    fixture.test.set_drift_user(*drift_user, &authority, &positions);
+}
+
+fn hedge_authority(fixture: &Fixture) -> Pubkey {
+    pda(&[b"hedge-authority", fixture.trader_config.as_ref()])
+}
+
+#[test]
+fn initialize_hedge_rejects_invalid_ratio() {
+    let mut fixture = Fixture::new();
+    let authority = fixture.authority.insecure_clone();
+
+    for hedge_ratio_bps in [0, 10_001] {
+        let ix = ix(
+            accounts::InitializeHedge {
+                trader_config: fixture.trader_config,
+                hedge_config: pda(&[b"hedge", fixture.trader_config.as_ref()]),
+                hedge_authority: hedge_authority(&fixture),
+                spot_position: fixture.destination,
+                drift_user: Pubkey::new_unique(),
+                drift_user_stats: Pubkey::new_unique(),
+                drift_state: Pubkey::new_unique(),
+                drift_program: DRIFT_PROGRAM,
+                authority: authority.pubkey(),
+                rent: sysvar::rent::ID,
+                system_program: system_program::ID,
+                network_config: fixture.network.network_config,
+            },
+            instruction::InitializeHedge {
+                perp_market_index: 0,
+                hedge_ratio_bps,
+                min_position: 0,
+            },
+        );
+        assert_eq!(
+            fixture.test.process_instruction(ix, &[&authority]).unwrap_err(),
+            anchor_error(TraderError::InvalidInput)
+        );
+    }
+}
+
+#[test]
+fn configure_hedge_updates_ratio() {
+    let mut fixture = Fixture::new();
+    let (hedge_config, _) = hedge(&mut fixture, 0);
+    let authority = fixture.authority.insecure_clone();
+    let configure = |hedge_ratio_bps| {
+        ix(
+            accounts::ConfigureHedge {
+                trader_config: fixture.trader_config,
+                hedge_config,
+                authority: authority.pubkey(),
+            },
+            instruction::ConfigureHedge {
+                hedge_ratio_bps,
+                min_position: 5 * LAMPORTS_PER_SOL,
+            },
+        )
+    };
+
+    let (too_high, valid) = (configure(10_001), configure(2_500));
+    assert_eq!(
+        fixture.test.process_instruction(too_high, &[&authority]).unwrap_err(),
+        anchor_error(TraderError::InvalidInput)
+    );
+    fixture.test.process_instruction(valid, &[&authority]).unwrap();
+    let config: HedgeConfig = fixture.test.anchor_account(&hedge_config).unwrap();
+    assert_eq!(config.hedge_ratio_bps, 2_500);
+    assert_eq!(config.min_position, 5 * LAMPORTS_PER_SOL);
+}
+
+#[test]
+fn fund_hedge_rejects_zero_amount() {
+    let mut fixture = Fixture::new();
+    let (hedge_config, config) = hedge(&mut fixture, 0);
+    let authority = fixture.authority.insecure_clone();
+    let hedge_authority = hedge_authority(&fixture);
+    let usdc = fixture.network.usdc;
+    let hedge_collateral = fixture.token_account(&usdc, &hedge_authority, 0);
+
+    let ix = ix(
+        accounts::FundHedge {
+            trader_config: fixture.trader_config,
+            hedge_config,
+            hedge_authority,
+            hedge_collateral,
+            authority_collateral: fixture.source,
+            drift_user: config.drift_user,
+            drift_user_stats: Pubkey::new_unique(),
+            drift_state: Pubkey::new_unique(),
+            drift_spot_market_vault: Pubkey::new_unique(),
+            drift_program: DRIFT_PROGRAM,
+            authority: authority.pubkey(),
+            token_program: spl_token::ID,
+            protocol_status: fixture.network.protocol_status,
+        },
+        instruction::FundHedge {
+            spot_market_index: 0,
+            amount: 0,
+        },
+    );
+    assert_eq!(
+        fixture.test.process_instruction(ix, &[&authority]).unwrap_err(),
+        anchor_error(TraderError::InvalidSwapAmount)
+    );
+}
+
+#[test]
+fn withdraw_hedge_collateral_rejects_zero_amount() {
+    let mut fixture = Fixture::new();
+    let (hedge_config, config) = hedge(&mut fixture, 0);
+    let authority = fixture.authority.insecure_clone();
+    let hedge_authority = hedge_authority(&fixture);
+    let usdc = fixture.network.usdc;
+    let hedge_collateral = fixture.token_account(&usdc, &hedge_authority, 0);
+
+    let ix = ix(
+        accounts::WithdrawHedgeCollateral {
+            trader_config: fixture.trader_config,
+            hedge_config,
+            hedge_authority,
+            hedge_collateral,
+            authority_collateral: fixture.source,
+            drift_user: config.drift_user,
+            drift_user_stats: Pubkey::new_unique(),
+            drift_state: Pubkey::new_unique(),
+            drift_spot_market_vault: Pubkey::new_unique(),
+            drift_signer: Pubkey::new_unique(),
+            drift_program: DRIFT_PROGRAM,
+            authority: authority.pubkey(),
+            token_program: spl_token::ID,
+        },
+        instruction::WithdrawHedgeCollateral {
+            spot_market_index: 0,
+            amount: 0,
+        },
+    );
+    assert_eq!(
+        fixture.test.process_instruction(ix, &[&authority]).unwrap_err(),
+        anchor_error(TraderError::InvalidSwapAmount)
+    );
+}
+
+#[test]
+fn withdraw_hedge_collateral_counts_only_what_drift_releases() {
+    let mut fixture = Fixture::new();
+    let (hedge_config, mut config) = hedge(&mut fixture, 0);
+    config.collateral = 1_000;
+    fixture.test.set_anchor_account(hedge_config, &config);
+    let authority = fixture.authority.insecure_clone();
+    let hedge_authority = hedge_authority(&fixture);
+    let usdc = fixture.network.usdc;
+    let hedge_collateral = fixture.token_account(&usdc, &hedge_authority, 0);
+    // 600 of the 1_000 still back the hedge
+    let drift_vault = Pubkey::new_unique();
+    fixture.test.create_drift_vault(drift_vault, &usdc, 400);
+
+    let withdraw = ix(
+        accounts::WithdrawHedgeCollateral {
+            trader_config: fixture.trader_config,
+            hedge_config,
+            hedge_authority,
+            hedge_collateral,
+            authority_collateral: fixture.source,
+            drift_user: config.drift_user,
+            drift_user_stats: Pubkey::new_unique(),
+            drift_state: Pubkey::new_unique(),
+            drift_spot_market_vault: drift_vault,
+            drift_signer: mock_drift::drift_signer(),
+            drift_program: DRIFT_PROGRAM,
+            authority: authority.pubkey(),
+            token_program: spl_token::ID,
+        },
+        instruction::WithdrawHedgeCollateral {
+            spot_market_index: 0,
+            amount: u64::MAX,
+        },
+    );
+    fixture.test.process_instruction(withdraw, &[&authority]).unwrap();
+    assert_eq!(fixture.balance(&fixture.source), SOURCE_BALANCE + 400);
+    let withdrawn: HedgeConfig = fixture.test.anchor_account(&hedge_config).unwrap();
+    assert_eq!(withdrawn.collateral, 600);
+
+    let close_hedge = ix(
+        accounts::CloseHedge {
+            trader_config: fixture.trader_config,
+            hedge_config,
+            drift_user: config.drift_user,
+            authority: authority.pubkey(),
+        },
+        instruction::CloseHedge {},
+    );
+    assert_eq!(
+        fixture.test.process_instruction(close_hedge, &[&authority]).unwrap_err(),
+        anchor_error(TraderError::AccountInUse)
+    );
+}
+
+fn rebalance_hedge_ix(fixture: &Fixture, hedge_config: Pubkey, config: &HedgeConfig) -> Instruction {
+    ix(
+        accounts::RebalanceHedge {
+            keeper: fixture.test.payer().pubkey(),
+            trader_config: fixture.trader_config,
+            hedge_config,
+            spot_position: config.spot_position,
+            hedge_authority: hedge_authority(fixture),
+            drift_user: config.drift_user,
+            drift_state: Pubkey::new_unique(),
+            drift_program: DRIFT_PROGRAM,
+            protocol_status: fixture.network.protocol_status,
+        },
+        instruction::RebalanceHedge {},
+    )
+}
+
+#[test]
+fn rebalance_hedge_skips_balanced_position() {
+    let mut fixture = Fixture::new();
+    // Spot position below the trigger size: target hedge is 0, as is the current one
+    let (hedge_config, config) = hedge(&mut fixture, LAMPORTS_PER_SOL);
+
+    let ix = rebalance_hedge_ix(&fixture, hedge_config, &config);
+    assert_eq!(
+        fixture.test.process_instruction(ix, &[]).unwrap_err(),
+        anchor_error(TraderError::HedgeBalanced)
+    );
+}
+
+#[test]
+fn rebalance_hedge_reconciles_fills_from_drift() {
+    let mut fixture = Fixture::new();
+    let (hedge_config, mut config) = hedge(&mut fixture, LAMPORTS_PER_SOL);
+    // A reduce order placed for the last rebalance has since filled
+    config.hedged_amount = LAMPORTS_PER_SOL / 2;
+    fixture.test.set_anchor_account(hedge_config, &config);
+
+    set_drift_position(&mut fixture, &config.drift_user, 0, 1);
+    let ix = rebalance_hedge_ix(&fixture, hedge_config, &config);
+    assert_eq!(
+        fixture.test.process_instruction(ix.clone(), &[]).unwrap_err(),
+        anchor_error(TraderError::HedgeOrderPending)
+    );
+
+    set_drift_position(&mut fixture, &config.drift_user, 0, 0);
+    fixture.test.process_instruction(ix.clone(), &[]).unwrap();
+    let reconciled: HedgeConfig = fixture.test.anchor_account(&hedge_config).unwrap();
+    assert_eq!(reconciled.hedged_amount, 0);
+    assert_eq!(
+        fixture.test.process_instruction(ix, &[]).unwrap_err(),
+        anchor_error(TraderError::HedgeBalanced)
+    );
+}
+
+#[test]
+fn rebalance_hedge_reads_only_the_pinned_spot_position() {
+    let mut fixture = Fixture::new();
+    let (hedge_config, mut config) = hedge(&mut fixture, LAMPORTS_PER_SOL);
+    let (wsol, authority) = (fixture.network.wsol, fixture.authority.pubkey());
+    config.spot_position = fixture.token_account(&wsol, &authority, 10 * LAMPORTS_PER_SOL);
+
+    let ix = rebalance_hedge_ix(&fixture, hedge_config, &config);
+    assert_eq!(
+        fixture.test.process_instruction(ix, &[]).unwrap_err(),
+        anchor_error(TraderError::InvalidTokenAccount)
+    );
+}
+
+#[test]
+fn drift_perp_short_reads_every_slot_of_a_drift_user() {
+    let drift_user = Pubkey::new_unique();
+    let position = |market_index, base_asset_amount, open_orders| PerpPosition {
+        market_index,
+        base_asset_amount,
+        open_orders,
+    };
+    for slot in 0..8 {
+        // Other markets, long and short, ahead of the hedged one
+        let mut positions: Vec<_> = (0..slot).map(|i| position(10 + i as u16, 5 - i as i64 * 3, 1)).collect();
+        positions.push(position(HEDGE_MARKET, -42, 0));
+        let mut data = mock_drift::user_data(&Pubkey::new_unique(), &positions);
+        assert_eq!(data.len(), mock_drift::USER_LEN);
+        let mut lamports = LAMPORTS_PER_SOL;
+        let account = AccountInfo::new(&drift_user, false, false, &mut lamports, &mut data, &DRIFT_PROGRAM, false, 0);
+        assert_eq!(drift_perp_short(&account, HEDGE_MARKET).unwrap(), (42, false), "slot {slot}");
+        if slot > 0 {
+            // Long, with orders open
+            assert_eq!(drift_perp_short(&account, 10).unwrap(), (0, true), "slot {slot}");
+        }
+        assert_eq!(drift_perp_short(&account, 99).unwrap(), (0, false), "slot {slot}");
+    }
+}
+
+#[test]
+fn drift_user_must_be_owned_by_drift() {
+    let mut fixture = Fixture::new();
+    let (hedge_config, config) = hedge(&mut fixture, LAMPORTS_PER_SOL);
+    let mut account = fixture.test.account(&config.drift_user).unwrap();
+    account.owner = Pubkey::new_unique();
+    fixture.test.set_account(config.drift_user, account);
+
+    let ix = rebalance_hedge_ix(&fixture, hedge_config, &config);
+    assert_eq!(
+        fixture.test.process_instruction(ix, &[]).unwrap_err(),
+        anchor_error(TraderError::InvalidDriftUser)
+    );
+}
+
+#[test]
+fn close_trader_requires_flat_hedge_closed_first() {
+    let mut fixture = Fixture::new();
+    let (hedge_config, config) = hedge(&mut fixture, LAMPORTS_PER_SOL);
+    let authority = fixture.authority.insecure_clone();
+    let close_hedge = ix(
+        accounts::CloseHedge {
+            trader_config: fixture.trader_config,
+            hedge_config,
+            drift_user: config.drift_user,
+            authority: authority.pubkey(),
+        },
+        instruction::CloseHedge {},
+    );
+    let close_trader = ix(
+        accounts::CloseTrader {
+            trader_config: fixture.trader_config,
+            audit_log: Some(fixture.audit_log),
+            trade_history: None,
+            hedge_config,
+            authority: authority.pubkey(),
+        },
+        instruction::CloseTrader {},
+    );
+    let in_use = anchor_error(TraderError::AccountInUse);
+
+    assert_eq!(fixture.test.process_instruction(close_trader.clone(), &[&authority]).unwrap_err(), in_use);
+    // Still short on Drift, whatever the last rebalance recorded
+    set_drift_position(&mut fixture, &config.drift_user, -1, 0);
+    assert_eq!(fixture.test.process_instruction(close_hedge.clone(), &[&authority]).unwrap_err(), in_use);
+    set_drift_position(&mut fixture, &config.drift_user, 0, 0);
+    // Collateral not yet withdrawn
+    let mut funded = config.clone();
+    funded.collateral = 1;
+    fixture.test.set_anchor_account(hedge_config, &funded);
+    assert_eq!(fixture.test.process_instruction(close_hedge.clone(), &[&authority]).unwrap_err(), in_use);
+    fixture.test.set_anchor_account(hedge_config, &config);
+    fixture.test.process_instruction(close_hedge, &[&authority]).unwrap();
+    assert!(fixture.test.account(&hedge_config).is_none());
+
+    let lamports = fixture.test.lamports(&authority.pubkey());
+    let rent = fixture.test.lamports(&fixture.trader_config) + fixture.test.lamports(&fixture.audit_log);
+    fixture.test.process_instruction(close_trader, &[&authority]).unwrap();
+    assert!(fixture.test.account(&fixture.trader_config).is_none());
+    assert!(fixture.test.account(&fixture.audit_log).is_none());
+    assert_eq!(fixture.test.lamports(&authority.pubkey()), lamports + rent);
+}
+
+// ===== POINTS =====
+
+const POINTS_PER_DOLLAR: u64 = 10;
+const REDEMPTION_RATE: u64 = 1_000;
+const DAILY_PAIR_CAP: u64 = 15;
+
+/// Points config written directly: `initialize_points` needs the deployed
+/// VECTAI mint and the admin key
+struct Points {
+    config: Pubkey,
+    ledger: Pubkey,
+    rewards_vault: Pubkey,
+}
+
+fn points(fixture: &mut Fixture) -> Points {
+    let (config, bump) = Pubkey::find_program_address(&[b"points-config"], &vectai_trader::ID);
+    let rewards_vault = pda(&[b"rewards-vault"]);
+    fixture.test.create_token_account(rewards_vault, &VECTAI_MINT, &config, 1_000_000);
+    fixture.test.set_anchor_account(
+        config,
+        &PointsConfig {
+            points_per_dollar: POINTS_PER_DOLLAR,
+            redemption_rate: REDEMPTION_RATE,
+            daily_pair_cap: DAILY_PAIR_CAP,
+            rewards_vault,
+            total_points_issued: 0,
+            total_points_redeemed: 0,
+            bump,
+        },
+    );
+
+    let authority = fixture.authority.insecure_clone();
+    let ledger = pda(&[b"points", authority.pubkey().as_ref()]);
+    let ix = ix(
+        accounts::OpenPointsLedger {
+            points_ledger: ledger,
+            authority: authority.pubkey(),
+            system_program: system_program::ID,
+        },
+        instruction::OpenPointsLedger {},
+    );
+    fixture.test.process_instruction(ix, &[&authority]).unwrap();
+    Points {
+        config,
+        ledger,
+        rewards_vault,
+    }
+}
+
+fn trade_for_points(fixture: &mut Fixture, points: &Points) -> Result<(), ProgramError> {
+    let mut accounts = fixture.trade_accounts(&fixture.authority.pubkey(), fixture.source, fixture.destination);
+    accounts.points_ledger = Some(points.ledger);
+    accounts.points_config = Some(points.config);
+    let authority = fixture.authority.insecure_clone();
+    fixture.execute_trade_with(accounts, SWAP_AMOUNT, &authority)
+}
+
+#[test]
+fn set_points_rates_requires_timelock() {
+    let mut fixture = Fixture::new();
+    let points = points(&mut fixture);
+    let authority = fixture.authority.insecure_clone();
+
+    let ix = ix(
+        accounts::SetPointsRates {
+            points_config: points.config,
+            admin: authority.pubkey(),
+        },
+        instruction::SetPointsRates {
+            points_per_dollar: 1,
+            redemption_rate: 1,
+            daily_pair_cap: 1,
+        },
+    );
+    assert_eq!(
+        fixture.test.process_instruction(ix, &[&authority]).unwrap_err(),
+        anchor_error(TraderError::UnauthorizedAdmin)
+    );
+}
+
+#[test]
+fn trades_earn_points_up_to_daily_pair_cap() {
+    let mut fixture = Fixture::new();
+    let points = points(&mut fixture);
+    let ledger = |fixture: &Fixture| fixture.test.anchor_account::<PointsLedger>(&points.ledger).unwrap();
+    assert_eq!(ledger(&fixture).authority, fixture.authority.pubkey());
+    assert_eq!(ledger(&fixture).balance, 0);
+
+    // $1 of volume earns 10 points, then the cap of 15 bites
+    let earned = SWAP_AMOUNT * POINTS_PER_DOLLAR / 1_000_000;
+    trade_for_points(&mut fixture, &points).unwrap();
+    assert_eq!(ledger(&fixture).balance, earned);
+    fixture.test.warp_forward(TRADE_COOLDOWN);
+    trade_for_points(&mut fixture, &points).unwrap();
+    assert_eq!(ledger(&fixture).balance, DAILY_PAIR_CAP);
+    fixture.test.warp_forward(TRADE_COOLDOWN);
+    trade_for_points(&mut fixture, &points).unwrap();
+    assert_eq!(ledger(&fixture).balance, DAILY_PAIR_CAP);
+
+    // The cap resets the next day
+    fixture.test.warp_forward(DAY);
+    trade_for_points(&mut fixture, &points).unwrap();
+    let ledger = ledger(&fixture);
+    assert_eq!(ledger.balance, DAILY_PAIR_CAP + earned);
+    assert_eq!(ledger.lifetime_points, DAILY_PAIR_CAP + earned);
+    let config: PointsConfig = fixture.test.anchor_account(&points.config).unwrap();
+    assert_eq!(config.total_points_issued, DAILY_PAIR_CAP + earned);
+}
+
+#[test]
+fn redeem_points_pays_vectai() {
+    let mut fixture = Fixture::new();
+    let points = points(&mut fixture);
+    trade_for_points(&mut fixture, &points).unwrap();
+    let authority = fixture.authority.insecure_clone();
+    let vectai_account = fixture.token_account(&VECTAI_MINT, &authority.pubkey(), 0);
+    let redeem = |points_to_redeem| {
+        ix(
+            accounts::RedeemPoints {
+                points_ledger: points.ledger,
+                points_config: points.config,
+                rewards_vault: points.rewards_vault,
+                authority_vectai_account: vectai_account,
+                authority: authority.pubkey(),
+                token_program: spl_token::ID,
+                protocol_status: fixture.network.protocol_status,
+            },
+            instruction::RedeemPoints { points: points_to_redeem },
+        )
+    };
+
+    let (zero, too_many, valid) = (redeem(0), redeem(11), redeem(4));
+    assert_eq!(
+        fixture.test.process_instruction(zero, &[&authority]).unwrap_err(),
+        anchor_error(TraderError::InvalidInput)
+    );
+    assert_eq!(
+        fixture.test.process_instruction(too_many, &[&authority]).unwrap_err(),
+        anchor_error(TraderError::InsufficientPoints)
+    );
+    fixture.test.process_instruction(valid, &[&authority]).unwrap();
+
+    assert_eq!(fixture.balance(&vectai_account), 4 * REDEMPTION_RATE);
+    assert_eq!(fixture.balance(&points.rewards_vault), 1_000_000 - 4 * REDEMPTION_RATE);
+    let ledger: PointsLedger = fixture.test.anchor_account(&points.ledger).unwrap();
+    assert_eq!(ledger.balance, 6);
+    assert_eq!(ledger.total_redeemed, 4);
+    let config: PointsConfig = fixture.test.anchor_account(&points.config).unwrap();
+    assert_eq!(config.total_points_redeemed, 4);
+}
+
+#[test]
+fn close_points_ledger_requires_zero_balance() {
+    let mut fixture = Fixture::new();
+    let points = points(&mut fixture);
+    trade_for_points(&mut fixture, &points).unwrap();
+    let authority = fixture.authority.insecure_clone();
+    let close = ix(
+        accounts::ClosePointsLedger {
+            points_ledger: points.ledger,
+            authority: authority.pubkey(),
+        },
+        instruction::ClosePointsLedger {},
+    );
+
+    assert_eq!(
+        fixture.test.process_instruction(close.clone(), &[&authority]).unwrap_err(),
+        anchor_error(TraderError::AccountInUse)
+    );
+    let mut ledger: PointsLedger = fixture.test.anchor_account(&points.ledger).unwrap();
+    ledger.balance = 0;
+    fixture.test.set_anchor_account(points.ledger, &ledger);
+    fixture.test.process_instruction(close, &[&authority]).unwrap();
+    assert!(fixture.test.account(&points.ledger).is_none());
+}
+
+// ===== TRADE HISTORY =====
+
+/// Trade history written directly; creating it needs account compression
+fn trade_history(fixture: &mut Fixture) -> (Pubkey, TradeHistory) {
+    let (address, bump) = Pubkey::find_program_address(
+        &[b"trade-history", fixture.trader_config.as_ref()],
+        &vectai_trader::ID,
+    );
+    let history = TradeHistory {
+        trader: fixture.trader_config,
+        merkle_tree: Pubkey::new_unique(),
+        max_depth: 14,
+        max_buffer_size: 64,
+        record_count: 0,
+        bump,
+    };
+    fixture.test.set_anchor_account(address, &history);
+    (address, history)
+}
+
+#[test]
+fn initialize_trade_history_rejects_invalid_depth() {
+    let mut fixture = Fixture::new();
+    let authority = fixture.authority.insecure_clone();
+
+    for max_depth in [0, 31] {
+        let ix = ix(
+            accounts::InitializeTradeHistory {
+                trader_config: fixture.trader_config,
+                trade_history: pda(&[b"trade-history", fixture.trader_config.as_ref()]),
+                merkle_tree: Pubkey::new_unique(),
+                compression_program: ACCOUNT_COMPRESSION_PROGRAM,
+                noop_program: NOOP_PROGRAM,
+                authority: authority.pubkey(),
+                system_program: system_program::ID,
+            },
+            instruction::InitializeTradeHistory {
+                max_depth,
+                max_buffer_size: 64,
+            },
+        );
+        assert_eq!(
+            fixture.test.process_instruction(ix, &[&authority]).unwrap_err(),
+            anchor_error(TraderError::InvalidInput)
+        );
+    }
+}
+
+#[test]
+fn verify_trade_record_rejects_other_trader() {
+    let mut fixture = Fixture::new();
+    let (address, history) = trade_history(&mut fixture);
+
+    let ix = ix(
+        accounts::VerifyTradeRecord {
+            trade_history: address,
+            merkle_tree: history.merkle_tree,
+            compression_program: ACCOUNT_COMPRESSION_PROGRAM,
+        },
+        instruction::VerifyTradeRecord {
+            root: [0; 32],
+            record: TradeRecord {
+                trader: Pubkey::new_unique(),
+                index: 0,
+                source_mint: fixture.network.usdc,
+                destination_mint: fixture.network.wsol,
+                amount_in: SWAP_AMOUNT,
+                trade_fee: 0,
+                expected_out: SWAP_AMOUNT,
+                oracle_price: ORACLE_PRICE,
+                timestamp: fixture.test.clock().unix_timestamp,
+            },
+        },
+    );
+    assert_eq!(
+        fixture.test.process_instruction(ix, &[]).unwrap_err(),
+        anchor_error(TraderError::InvalidTradeHistory)
+    );
+}
+
+#[test]
+fn execute_trade_requires_history_tree_when_recording() {
+    let mut fixture = Fixture::new();
+    let (address, _) = trade_history(&mut fixture);
+
+    let mut accounts = fixture.trade_accounts(&fixture.authority.pubkey(), fixture.source, fixture.destination);
+    accounts.trade_history = Some(address);
+    let authority = fixture.authority.insecure_clone();
+    assert_eq!(
+        fixture.execute_trade_with(accounts, SWAP_AMOUNT, &authority).unwrap_err(),
+        anchor_error(TraderError::InvalidTradeHistory)
+    );
+    // Rolled back with the swap
+    assert_eq!(fixture.balance(&fixture.source), SOURCE_BALANCE);
+    assert_eq!(fixture.trader().total_swaps, 0);
+}
+
+// ===== PROGRAM-OWNED STRATEGIES =====
+
+/// Third-party program forwarding its instruction data to the program in its
+/// first account, signing as its `integrator` PDA
+const INTEGRATOR: Pubkey = Pubkey::new_from_array([0x1e; 32]);
+
+fn integrator_authority() -> Pubkey {
+    Pubkey::find_program_address(&[b"integrator"], &INTEGRATOR).0
+}
+
+fn process_integrator(program_id: &Pubkey, accounts: &[AccountInfo], data: &[u8]) -> ProgramResult {
+    let (authority, bump) = Pubkey::find_program_address(&[b"integrator"], program_id);
+    let ix = Instruction {
+        program_id: *accounts[0].key,
+        accounts: accounts[1..]
+            .iter()
+            .map(|account| AccountMeta {
+                pubkey: *account.key,
+                is_signer: account.is_signer || *account.key == authority,
+                is_writable: account.is_writable,
+            })
+            .collect(),
+        data: data.to_vec(),
+    };
+    invoke_signed(&ix, accounts, &[&[b"integrator", &[bump]]])
+}
+
+/// `ix` wrapped in an instruction of the integrator
+fn through_integrator(ix: Instruction) -> Instruction {
+    let mut accounts = vec![AccountMeta::new_readonly(ix.program_id, false)];
+    accounts.extend(ix.accounts.into_iter().map(|meta| AccountMeta {
+        is_signer: meta.is_signer && meta.pubkey != integrator_authority(),
+        ..meta
+    }));
+    Instruction {
+        program_id: INTEGRATOR,
+        accounts,
+        data: ix.data,
+    }
+}
+
+fn create_program_strategy(fixture: &Fixture, authority: Pubkey) -> Instruction {
+    ix(
+        accounts::CreateProgramStrategy {
+            trader_config: trader_address(&authority),
+            authority,
+            payer: fixture.authority.pubkey(),
+            instructions: sysvar::instructions::ID,
+            system_program: system_program::ID,
+        },
+        instruction::CreateProgramStrategy {
+            price_threshold: PRICE_THRESHOLD,
+            swap_amount: SWAP_AMOUNT,
+            slippage_tolerance: SLIPPAGE_BPS,
+        },
+    )
+}
+
+#[test]
+fn program_strategy_requires_a_cpi() {
+    let mut fixture = Fixture::new();
+    let wallet = funded(&mut fixture.test);
+
+    let ix = create_program_strategy(&fixture, wallet.pubkey());
+    let payer = fixture.authority.insecure_clone();
+    assert_eq!(
+        fixture.test.process_instruction(ix, &[&payer, &wallet]).unwrap_err(),
+        anchor_error(TraderError::NotCalledByProgram)
+    );
+    assert!(fixture.test.account(&trader_address(&wallet.pubkey())).is_none());
+    // Wallet strategies are not program-owned
+    assert!(!fixture.trader().is_program_owned());
+}
+
+#[test]
+fn program_strategy_executes_through_its_owner() {
+    let mut fixture = Fixture::new();
+    fixture.test.add_program(INTEGRATOR, process_integrator);
+    let authority = integrator_authority();
+
+    let create = through_integrator(create_program_strategy(&fixture, authority));
+    let payer = fixture.authority.insecure_clone();
+    fixture.test.process_instruction(create, &[&payer]).unwrap();
+    let address = trader_address(&authority);
+    let trader: TraderConfig = fixture.test.anchor_account(&address).unwrap();
+    assert_eq!((trader.authority, trader.owner_program), (authority, INTEGRATOR));
+    assert!(trader.is_program_owned());
+    let [created] = fixture.test.events::<ProgramStrategyCreated>().try_into().ok().unwrap();
+    assert_eq!((created.trader, created.authority, created.owner_program), (address, authority, INTEGRATOR));
+
+    // The owner's instruction is trusted alongside the strategy's executions
+    let (usdc, wsol) = (fixture.network.usdc, fixture.network.wsol);
+    let source = fixture.token_account(&usdc, &authority, SOURCE_BALANCE);
+    let destination = fixture.token_account(&wsol, &authority, 0);
+    let trade = ix(
+        fixture.trade_accounts(&authority, source, destination),
+        instruction::ExecuteTrade { amount: SWAP_AMOUNT, dry_run: false, nonce: None },
+    );
+    fixture.test.process_instruction(through_integrator(trade), &[]).unwrap();
+    assert_eq!(fixture.balance(&source), SOURCE_BALANCE - SWAP_AMOUNT);
+    let trader: TraderConfig = fixture.test.anchor_account(&address).unwrap();
+    assert_eq!(trader.total_swaps, 1);
+}
+
+// ===== POOL FAILURES =====
+
+#[test]
+fn short_paying_pool_trips_the_slippage_floor() {
+    let mut fixture = Fixture::new();
+    // Half the quote, past the AMM's own minimum-out check
+    let pool = fixture.network.pool.clone();
+    fixture.test.set_amm_behavior(&pool, AmmBehavior::ShortPay { bps: 5_000 });
+
+    assert_eq!(
+        fixture.execute_trade(SWAP_AMOUNT).unwrap_err(),
+        anchor_error(TraderError::SlippageExceeded)
+    );
+    assert_eq!(fixture.balance(&fixture.source), SOURCE_BALANCE);
+    assert_eq!(fixture.balance(&fixture.destination), 0);
+    assert_eq!(fixture.trader().total_swaps, 0);
+
+    // A shortfall inside the tolerance executes
+    fixture.test.set_amm_behavior(&pool, AmmBehavior::ShortPay { bps: 9_900 });
+    fixture.execute_trade(SWAP_AMOUNT).unwrap();
+    let paid = mock_amm::quote(SWAP_AMOUNT, POOL_RESERVE, POOL_RESERVE) * 9_900 / 10_000;
+    assert_eq!(fixture.balance(&fixture.destination), paid);
+    assert_eq!(fixture.trader().total_swaps, 1);
+}
+
+#[test]
+fn failing_pool_spends_no_execution() {
+    let mut fixture = Fixture::new();
+    fixture.configure(instruction::SetDailyTradeCap { max_trades: 1 }).unwrap();
+    let pool = fixture.network.pool.clone();
+    fixture.test.set_amm_behavior(&pool, AmmBehavior::Fail { code: 42 });
+
+    assert_eq!(fixture.execute_trade(SWAP_AMOUNT).unwrap_err(), ProgramError::Custom(42));
+    let trader = fixture.trader();
+    assert_eq!((trader.total_swaps, trader.last_swap_time), (0, 0));
+    assert_eq!(trader.trade_cap.trades_in_window(fixture.test.clock().unix_timestamp), 0);
+    assert_eq!(fixture.balance(&fixture.source), SOURCE_BALANCE);
+
+    // Neither the cooldown nor the cap was spent on the failure
+    fixture.test.set_amm_behavior(&pool, AmmBehavior::ConstantProduct);
+    fixture.execute_trade(SWAP_AMOUNT).unwrap();
+    assert_eq!(fixture.trader().total_swaps, 1);
+}
+
+#[test]
+fn drawdown_halt_stops_executions_before_the_pool() {
+    let mut fixture = Fixture::new();
+    fixture.configure(instruction::SetMaxDrawdown { max_drawdown_bps: 1_000 }).unwrap();
+    fixture.execute_trade(SWAP_AMOUNT).unwrap();
+
+    // Equity collapses to 1% of the peak while the pool starts failing
+    fixture.lose_all_but(SWAP_AMOUNT);
+    let pool = fixture.network.pool.clone();
+    fixture.test.set_amm_behavior(&pool, AmmBehavior::Fail { code: 42 });
+    fixture.test.warp_forward(TRADE_COOLDOWN);
+
+    // The breach is persisted without reaching the pool, and holds after
+    fixture.execute_trade(SWAP_AMOUNT).unwrap();
+    assert!(fixture.trader().risk.halted);
+    fixture.test.warp_forward(TRADE_COOLDOWN);
+    assert_eq!(
+        fixture.execute_trade(SWAP_AMOUNT).unwrap_err(),
+        anchor_error(TraderError::DrawdownLimitExceeded)
+    );
+    assert_eq!(fixture.trader().total_swaps, 1);
+    assert_eq!(fixture.balance(&fixture.source), SWAP_AMOUNT);
+}
@@ -0,0 +1,387 @@
+//! `vectai_transfer_hook`, run by Token-2022 on every transfer of a mint
+//! created through `vectai_token::initialize_transfer_hook_token`.
+
+use anchor_lang::{solana_program::system_program, InstructionData, ToAccountMetas};
+use anchor_spl::token_2022::spl_token_2022::{
+    self,
+    extension::{transfer_hook::TransferHook, BaseStateWithExtensions, StateWithExtensions},
+};
+use solana_sdk::{
+    instruction::{AccountMeta, Instruction},
+    native_token::LAMPORTS_PER_SOL,
+    program_error::ProgramError,
+    pubkey::Pubkey,
+    signature::{Keypair, Signer},
+};
+use vectai_program_test::{anchor_error, ProgramTest};
+use vectai_protocol::FeeConfig;
+use vectai_token::TokenInfo;
+use vectai_transfer_hook::{
+    accounts, compliance_rules, instruction, owner_rule, ComplianceRules, OwnerRuleSet, OwnerStatus,
+    TransferHookError,
+};
+
+const DECIMALS: u8 = 6;
+
+fn ix(accounts: impl ToAccountMetas, data: impl InstructionData) -> Instruction {
+    Instruction {
+        program_id: vectai_transfer_hook::ID,
+        accounts: accounts.to_account_metas(None),
+        data: data.data(),
+    }
+}
+
+fn funded(test: &mut ProgramTest) -> Keypair {
+    let keypair = Keypair::new();
+    test.airdrop(&keypair.pubkey(), 10 * LAMPORTS_PER_SOL);
+    keypair
+}
+
+/// A transfer-hook VECTAI mint with its compliance rules, uncapped
+struct Fixture {
+    test: ProgramTest,
+    mint_authority: Keypair,
+    compliance: Keypair,
+    mint: Pubkey,
+    protocol_status: Pubkey,
+}
+
+impl Fixture {
+    fn new() -> Self {
+        let mut fixture = Fixture::without_rules();
+        fixture.initialize_rules(&fixture.mint_authority.insecure_clone()).unwrap();
+        fixture
+    }
+
+    /// The mint, before `initialize_compliance_rules`
+    fn without_rules() -> Self {
+        let mut test = ProgramTest::new();
+        let protocol_status = test.set_protocol_status(false, FeeConfig::INITIAL);
+        let mint_authority = funded(&mut test);
+        let compliance = funded(&mut test);
+        let mint = Keypair::new();
+        let payer = test.payer().pubkey();
+        let init = Instruction {
+            program_id: vectai_token::ID,
+            accounts: vectai_token::accounts::InitializeTransferHookToken {
+                mint: mint.pubkey(),
+                token_info: token_info(&mint.pubkey()),
+                mint_authority: mint_authority.pubkey(),
+                payer,
+                token_program: spl_token_2022::ID,
+                system_program: system_program::ID,
+            }
+            .to_account_metas(None),
+            data: vectai_token::instruction::InitializeTransferHookToken {
+                max_supply: 1_000_000,
+                decimals: DECIMALS,
+                compliance_authority: compliance.pubkey(),
+            }
+            .data(),
+        };
+        test.process_instruction(init, &[&mint, &mint_authority]).unwrap();
+        Fixture {
+            test,
+            mint_authority,
+            compliance,
+            mint: mint.pubkey(),
+            protocol_status,
+        }
+    }
+
+    fn initialize_rules(&mut self, mint_authority: &Keypair) -> Result<(), ProgramError> {
+        let payer = self.test.payer().pubkey();
+        let ix = ix(
+            accounts::InitializeComplianceRules {
+                mint: self.mint,
+                rules: compliance_rules(&self.mint),
+                extra_account_metas: extra_account_metas(&self.mint),
+                mint_authority: mint_authority.pubkey(),
+                payer,
+                system_program: system_program::ID,
+            },
+            instruction::InitializeComplianceRules {
+                compliance_authority: self.compliance.pubkey(),
+                max_transfer_amount: 0,
+                max_wallet_balance: 0,
+            },
+        );
+        self.test.process_instruction(ix, &[mint_authority])
+    }
+
+    fn rules(&self) -> ComplianceRules {
+        self.test.anchor_account(&compliance_rules(&self.mint)).unwrap()
+    }
+
+    /// A funded owner with a Token-2022 account holding `amount`
+    fn holder(&mut self, amount: u64) -> (Keypair, Pubkey) {
+        let owner = funded(&mut self.test);
+        let account = Pubkey::new_unique();
+        self.test.create_token_account(account, &self.mint, &owner.pubkey(), amount);
+        (owner, account)
+    }
+
+    /// The accounts Token-2022 needs to run the hook, in the order the
+    /// extra account metas resolve to
+    fn hook_accounts(&self, from_owner: &Pubkey, to_owner: &Pubkey) -> Vec<AccountMeta> {
+        vec![
+            AccountMeta::new_readonly(compliance_rules(&self.mint), false),
+            AccountMeta::new_readonly(owner_rule(&self.mint, from_owner), false),
+            AccountMeta::new_readonly(owner_rule(&self.mint, to_owner), false),
+            AccountMeta::new_readonly(vectai_transfer_hook::ID, false),
+            AccountMeta::new_readonly(extra_account_metas(&self.mint), false),
+        ]
+    }
+
+    /// `transfer_checked` sent straight to Token-2022, as any wallet or
+    /// program would
+    fn transfer(&mut self, owner: &Keypair, from: Pubkey, to: Pubkey, to_owner: &Pubkey, amount: u64) -> Result<(), ProgramError> {
+        let mut ix = spl_token_2022::instruction::transfer_checked(
+            &spl_token_2022::ID,
+            &from,
+            &self.mint,
+            &to,
+            &owner.pubkey(),
+            &[],
+            amount,
+            DECIMALS,
+        )
+        .unwrap();
+        ix.accounts.extend(self.hook_accounts(&owner.pubkey(), to_owner));
+        self.test.process_instruction(ix, &[owner])
+    }
+
+    /// The same transfer through `vectai_token::transfer`
+    fn token_transfer(&mut self, owner: &Keypair, from: Pubkey, to: Pubkey, to_owner: &Pubkey, amount: u64) -> Result<(), ProgramError> {
+        let mut accounts = vectai_token::accounts::TransferTokens {
+            mint: self.mint,
+            token_info: token_info(&self.mint),
+            from,
+            to,
+            authority: owner.pubkey(),
+            token_program: spl_token_2022::ID,
+            protocol_status: self.protocol_status,
+        }
+        .to_account_metas(None);
+        accounts.extend(self.hook_accounts(&owner.pubkey(), to_owner));
+        let ix = Instruction {
+            program_id: vectai_token::ID,
+            accounts,
+            data: vectai_token::instruction::Transfer { amount }.data(),
+        };
+        self.test.process_instruction(ix, &[owner])
+    }
+
+    fn set_owner_rule_with(
+        &mut self,
+        signer: &Keypair,
+        owner: Pubkey,
+        status: OwnerStatus,
+        reason_code: u16,
+    ) -> Result<(), ProgramError> {
+        let ix = ix(
+            accounts::SetOwnerRule {
+                rules: compliance_rules(&self.mint),
+                owner_rule: owner_rule(&self.mint, &owner),
+                authority: signer.pubkey(),
+                system_program: system_program::ID,
+            },
+            instruction::SetOwnerRule {
+                owner,
+                status,
+                reason_code,
+            },
+        );
+        self.test.process_instruction(ix, &[signer])
+    }
+
+    fn set_owner_rule(&mut self, owner: Pubkey, status: OwnerStatus, reason_code: u16) -> Result<(), ProgramError> {
+        let compliance = self.compliance.insecure_clone();
+        self.set_owner_rule_with(&compliance, owner, status, reason_code)
+    }
+
+    fn clear_owner_rule(&mut self, owner: Pubkey) -> Result<(), ProgramError> {
+        let ix = ix(
+            accounts::ClearOwnerRule {
+                rules: compliance_rules(&self.mint),
+                owner_rule: owner_rule(&self.mint, &owner),
+                authority: self.compliance.pubkey(),
+            },
+            instruction::ClearOwnerRule {},
+        );
+        let compliance = self.compliance.insecure_clone();
+        self.test.process_instruction(ix, &[&compliance])
+    }
+
+    fn set_transfer_limits_with(
+        &mut self,
+        signer: &Keypair,
+        max_transfer_amount: u64,
+        max_wallet_balance: u64,
+    ) -> Result<(), ProgramError> {
+        let ix = ix(
+            accounts::ManageRules {
+                rules: compliance_rules(&self.mint),
+                authority: signer.pubkey(),
+            },
+            instruction::SetTransferLimits {
+                max_transfer_amount,
+                max_wallet_balance,
+            },
+        );
+        self.test.process_instruction(ix, &[signer])
+    }
+
+    fn set_transfer_limits(&mut self, max_transfer_amount: u64, max_wallet_balance: u64) -> Result<(), ProgramError> {
+        let compliance = self.compliance.insecure_clone();
+        self.set_transfer_limits_with(&compliance, max_transfer_amount, max_wallet_balance)
+    }
+}
+
+fn token_info(mint: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(&[b"token-info", mint.as_ref()], &vectai_token::ID).0
+}
+
+fn extra_account_metas(mint: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(&[b"extra-account-metas", mint.as_ref()], &vectai_transfer_hook::ID).0
+}
+
+#[test]
+fn transfer_hook_token_points_at_the_hook() {
+    let fixture = Fixture::new();
+    let mint = fixture.test.account(&fixture.mint).unwrap();
+    assert_eq!(mint.owner, spl_token_2022::ID);
+    let state = StateWithExtensions::<spl_token_2022::state::Mint>::unpack(&mint.data).unwrap();
+    assert_eq!(state.base.decimals, DECIMALS);
+    let hook = state.get_extension::<TransferHook>().unwrap();
+    assert_eq!(Option::<Pubkey>::from(hook.program_id), Some(vectai_transfer_hook::ID));
+    // Nobody can repoint it
+    assert_eq!(Option::<Pubkey>::from(hook.authority), None);
+
+    let info: TokenInfo = fixture.test.anchor_account(&token_info(&fixture.mint)).unwrap();
+    assert_eq!(info.compliance_authority, fixture.compliance.pubkey());
+    assert!(!info.clawback_enabled);
+
+    let rules = fixture.rules();
+    assert_eq!((rules.mint, rules.authority), (fixture.mint, fixture.compliance.pubkey()));
+    assert_eq!((rules.max_transfer_amount, rules.max_wallet_balance), (0, 0));
+}
+
+#[test]
+fn compliance_rules_require_the_mint_authority() {
+    let mut fixture = Fixture::without_rules();
+    let (sender, from) = fixture.holder(1_000);
+    let (recipient, to) = fixture.holder(0);
+    // Transfers fail closed until the rules exist
+    assert_eq!(
+        fixture.transfer(&sender, from, to, &recipient.pubkey(), 100).unwrap_err(),
+        ProgramError::InvalidAccountData
+    );
+
+    let impostor = funded(&mut fixture.test);
+    assert_eq!(fixture.initialize_rules(&impostor).unwrap_err(), anchor_error(TransferHookError::Unauthorized));
+    let mint_authority = fixture.mint_authority.insecure_clone();
+    fixture.initialize_rules(&mint_authority).unwrap();
+    fixture.transfer(&sender, from, to, &recipient.pubkey(), 100).unwrap();
+    assert_eq!(fixture.test.token_balance(&to), Some(100));
+
+    // Only the compliance authority manages them afterwards
+    assert_eq!(
+        fixture.set_transfer_limits_with(&mint_authority, 1, 1).unwrap_err(),
+        anchor_error(TransferHookError::Unauthorized)
+    );
+    assert_eq!(
+        fixture.set_owner_rule_with(&mint_authority, sender.pubkey(), OwnerStatus::Denied, 1).unwrap_err(),
+        anchor_error(TransferHookError::Unauthorized)
+    );
+}
+
+#[test]
+fn denied_owners_cannot_send_or_receive() {
+    let mut fixture = Fixture::new();
+    let (sender, from) = fixture.holder(1_000);
+    let (recipient, to) = fixture.holder(0);
+
+    assert_eq!(
+        fixture.set_owner_rule(sender.pubkey(), OwnerStatus::Denied, 0).unwrap_err(),
+        anchor_error(TransferHookError::MissingReasonCode)
+    );
+    fixture.set_owner_rule(sender.pubkey(), OwnerStatus::Denied, 7).unwrap();
+    let [event] = fixture.test.events::<OwnerRuleSet>().try_into().ok().unwrap();
+    assert_eq!((event.owner, event.status, event.reason_code), (sender.pubkey(), OwnerStatus::Denied, 7));
+
+    // Sent straight to Token-2022, bypassing vectai_token
+    assert_eq!(
+        fixture.transfer(&sender, from, to, &recipient.pubkey(), 100).unwrap_err(),
+        anchor_error(TransferHookError::SenderDenied)
+    );
+    // Every account the owner holds is covered
+    let other = Pubkey::new_unique();
+    fixture.test.create_token_account(other, &fixture.mint, &sender.pubkey(), 1_000);
+    assert_eq!(
+        fixture.transfer(&sender, other, to, &recipient.pubkey(), 100).unwrap_err(),
+        anchor_error(TransferHookError::SenderDenied)
+    );
+
+    fixture.clear_owner_rule(sender.pubkey()).unwrap();
+    fixture.transfer(&sender, from, to, &recipient.pubkey(), 100).unwrap();
+
+    fixture.set_owner_rule(recipient.pubkey(), OwnerStatus::Denied, 3).unwrap();
+    assert_eq!(
+        fixture.transfer(&sender, from, to, &recipient.pubkey(), 100).unwrap_err(),
+        anchor_error(TransferHookError::RecipientDenied)
+    );
+    assert_eq!(fixture.test.token_balance(&to), Some(100));
+}
+
+#[test]
+fn transfer_limits_bound_amount_and_wallet_unless_exempt() {
+    let mut fixture = Fixture::new();
+    let (sender, from) = fixture.holder(10_000);
+    let (recipient, to) = fixture.holder(0);
+    fixture.set_transfer_limits(1_000, 1_500).unwrap();
+
+    assert_eq!(
+        fixture.transfer(&sender, from, to, &recipient.pubkey(), 1_001).unwrap_err(),
+        anchor_error(TransferHookError::TransferTooLarge)
+    );
+    fixture.transfer(&sender, from, to, &recipient.pubkey(), 1_000).unwrap();
+    // The recipient's balance after the transfer counts
+    assert_eq!(
+        fixture.transfer(&sender, from, to, &recipient.pubkey(), 501).unwrap_err(),
+        anchor_error(TransferHookError::WalletBalanceTooLarge)
+    );
+    fixture.transfer(&sender, from, to, &recipient.pubkey(), 500).unwrap();
+
+    // Exempt senders (a pool paying out) skip the transfer cap and exempt
+    // recipients the wallet cap
+    fixture.set_owner_rule(sender.pubkey(), OwnerStatus::Exempt, 0).unwrap();
+    let (whale, whale_account) = fixture.holder(0);
+    assert_eq!(
+        fixture.transfer(&sender, from, whale_account, &whale.pubkey(), 2_000).unwrap_err(),
+        anchor_error(TransferHookError::WalletBalanceTooLarge)
+    );
+    fixture.set_owner_rule(whale.pubkey(), OwnerStatus::Exempt, 0).unwrap();
+    fixture.transfer(&sender, from, whale_account, &whale.pubkey(), 2_000).unwrap();
+    assert_eq!(fixture.test.token_balance(&whale_account), Some(2_000));
+
+    fixture.set_transfer_limits(0, 0).unwrap();
+    fixture.transfer(&recipient, to, from, &sender.pubkey(), 1_500).unwrap();
+}
+
+#[test]
+fn vectai_token_transfer_runs_the_hook() {
+    let mut fixture = Fixture::new();
+    let (sender, from) = fixture.holder(1_000);
+    let (recipient, to) = fixture.holder(0);
+
+    fixture.token_transfer(&sender, from, to, &recipient.pubkey(), 400).unwrap();
+    assert_eq!(fixture.test.token_balance(&to), Some(400));
+
+    fixture.set_owner_rule(sender.pubkey(), OwnerStatus::Denied, 7).unwrap();
+    assert_eq!(
+        fixture.token_transfer(&sender, from, to, &recipient.pubkey(), 100).unwrap_err(),
+        anchor_error(TransferHookError::SenderDenied)
+    );
+    assert_eq!(fixture.test.token_balance(&from), Some(600));
+}
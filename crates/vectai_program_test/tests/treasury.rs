@@ -0,0 +1,350 @@
+//! `vectai_treasury` instructions.
+
+use anchor_lang::{
+    solana_program::{program_pack::Pack, system_program, sysvar},
+    InstructionData, ToAccountMetas,
+};
+use solana_sdk::{
+    instruction::{AccountMeta, Instruction},
+    native_token::LAMPORTS_PER_SOL,
+    pubkey::Pubkey,
+    signature::{Keypair, Signer},
+};
+use vectai_math::{expected_amount_out, PoolPricing};
+use vectai_program_test::{anchor_error, mock_stable, mock_stable::StablePool, ProgramTest};
+use vectai_protocol::{venue_registry, FeeConfig, PairVenues, VenueRegistry};
+use vectai_treasury::{
+    accounts, instruction, vault_address, StableConverted, Stream, StreamCancelled, Treasury, TreasuryError,
+};
+
+const STABLE_RESERVE: u64 = 1_000_000_000_000;
+const STABLE_AMPLIFICATION: u64 = 100;
+const STABLE_FEE_BPS: u64 = 4;
+const CONVERT_AMOUNT: u64 = 5_000_000;
+
+/// Treasury as `initialize_treasury` leaves it, on an unpaused protocol;
+/// the instruction itself is admin-only
+struct Fixture {
+    test: ProgramTest,
+    treasury: Pubkey,
+    governance: Keypair,
+    wsol: Pubkey,
+    usdc: Pubkey,
+    network_config: Pubkey,
+    protocol_status: Pubkey,
+    protocol_stats: Pubkey,
+}
+
+impl Fixture {
+    fn new() -> Self {
+        let mut test = ProgramTest::new();
+        let (wsol, usdc) = (Pubkey::new_unique(), Pubkey::new_unique());
+        let mint_authority = Pubkey::new_unique();
+        test.create_mint(wsol, &mint_authority, 9);
+        test.create_mint(usdc, &mint_authority, 6);
+        let network_config = test.set_network_config(wsol, usdc);
+        let protocol_status = test.set_protocol_status(false, FeeConfig::INITIAL);
+        let protocol_stats = test.set_protocol_stats();
+
+        let governance = Keypair::new();
+        test.airdrop(&governance.pubkey(), LAMPORTS_PER_SOL);
+        let (treasury, bump) = Pubkey::find_program_address(&[b"treasury"], &vectai_treasury::ID);
+        test.set_anchor_account(
+            treasury,
+            &Treasury {
+                signers: vec![Pubkey::new_unique()],
+                threshold: 1,
+                governance: governance.pubkey(),
+                proposal_count: 0,
+                bump,
+            },
+        );
+        Fixture {
+            test,
+            treasury,
+            governance,
+            wsol,
+            usdc,
+            network_config,
+            protocol_status,
+            protocol_stats,
+        }
+    }
+
+    /// `initialize_fee_vaults` with `extra_mints` as remaining accounts
+    fn initialize_fee_vaults(&mut self, signer: &Keypair, extra_mints: &[Pubkey]) -> Instruction {
+        let mut accounts = accounts::InitializeFeeVaults {
+            treasury: self.treasury,
+            network_config: self.network_config,
+            wsol_mint: self.wsol,
+            usdc_mint: self.usdc,
+            wsol_vault: vault_address(&self.wsol),
+            usdc_vault: vault_address(&self.usdc),
+            governance: signer.pubkey(),
+            payer: self.test.payer().pubkey(),
+            rent: sysvar::rent::ID,
+            token_program: spl_token::ID,
+            system_program: system_program::ID,
+        }
+        .to_account_metas(None);
+        for mint in extra_mints {
+            accounts.push(AccountMeta::new_readonly(*mint, false));
+            accounts.push(AccountMeta::new(vault_address(mint), false));
+        }
+        Instruction {
+            program_id: vectai_treasury::ID,
+            accounts,
+            data: instruction::InitializeFeeVaults {}.data(),
+        }
+    }
+
+    /// A USDT/USDC stable pool listed in the pair's venue registry, with
+    /// `usdt_held` USDT in the treasury's USDT vault and an empty USDC vault
+    fn list_stable_pool(&mut self, usdt_held: u64) -> StablePool {
+        let (usdt, usdc) = (Pubkey::new_unique(), self.usdc);
+        self.test.create_mint(usdt, &Pubkey::new_unique(), 6);
+        let pool = self.test.create_stable_pool(
+            &usdt,
+            &usdc,
+            STABLE_RESERVE,
+            STABLE_RESERVE,
+            STABLE_AMPLIFICATION,
+            STABLE_FEE_BPS,
+        );
+        let (_, bump) =
+            Pubkey::find_program_address(&[b"venue-registry", usdt.as_ref(), usdc.as_ref()], &vectai_protocol::ID);
+        self.test.set_anchor_account(
+            venue_registry(&usdt, &usdc),
+            &VenueRegistry {
+                base_mint: usdt,
+                quote_mint: usdc,
+                venues: PairVenues {
+                    stable_swap_pool: pool.pool,
+                    ..PairVenues::default()
+                },
+                updated_at: 0,
+                bump,
+            },
+        );
+        self.test.create_token_account(vault_address(&usdt), &usdt, &self.treasury, usdt_held);
+        self.test.create_token_account(vault_address(&usdc), &usdc, &self.treasury, 0);
+        pool
+    }
+
+    /// `convert_stable` of the treasury's USDT into USDC on `pool`
+    fn convert_stable(&self, signer: &Keypair, pool: &StablePool, min_amount_out: u64) -> Instruction {
+        let (pool_source, pool_destination, admin_fee_destination) = pool.accounts(&pool.mint_a);
+        Instruction {
+            program_id: vectai_treasury::ID,
+            accounts: accounts::ConvertStable {
+                treasury: self.treasury,
+                source_vault: vault_address(&pool.mint_a),
+                destination_vault: vault_address(&pool.mint_b),
+                governance: signer.pubkey(),
+                swapper_program: raydium_swapper::ID,
+                stable_swap_program: mock_stable::ID,
+                pool: pool.pool,
+                pool_authority: pool.pool_authority,
+                pool_source,
+                pool_destination,
+                admin_fee_destination,
+                token_program: spl_token::ID,
+                venue_registry: venue_registry(&pool.mint_a, &pool.mint_b),
+                protocol_status: self.protocol_status,
+                network_config: self.network_config,
+                protocol_stats: self.protocol_stats,
+                swapper_stats_reporter: vectai_protocol::stats_reporter(&raydium_swapper::ID),
+                protocol_program: vectai_protocol::ID,
+                swap_fee_account: None,
+            }
+            .to_account_metas(None),
+            data: instruction::ConvertStable {
+                amount_in: CONVERT_AMOUNT,
+                min_amount_out,
+            }
+            .data(),
+        }
+    }
+
+    fn vault(&self, mint: &Pubkey) -> Option<spl_token::state::Account> {
+        let account = self.test.account(&vault_address(mint))?;
+        Some(spl_token::state::Account::unpack(&account.data).unwrap())
+    }
+}
+
+#[test]
+fn initialize_fee_vaults_opens_every_fee_vault() {
+    let mut fixture = Fixture::new();
+    let vectai = Pubkey::new_unique();
+    fixture.test.create_mint(vectai, &Pubkey::new_unique(), 6);
+    let governance = fixture.governance.insecure_clone();
+
+    let ix = fixture.initialize_fee_vaults(&governance, &[vectai]);
+    fixture.test.process_instruction(ix.clone(), &[&governance]).unwrap();
+
+    for mint in [fixture.wsol, fixture.usdc, vectai] {
+        let vault = fixture.vault(&mint).expect("fee vault");
+        assert_eq!(vault.mint, mint);
+        assert_eq!(vault.owner, fixture.treasury);
+        assert_eq!(vault.amount, 0);
+    }
+
+    // Existing vaults are kept, so the call can be rerun with a new mint
+    fixture.test.create_token_account(vault_address(&vectai), &vectai, &fixture.treasury, 7);
+    let share_mint = Pubkey::new_unique();
+    fixture.test.create_mint(share_mint, &Pubkey::new_unique(), 6);
+    let ix = fixture.initialize_fee_vaults(&governance, &[vectai, share_mint]);
+    fixture.test.process_instruction(ix, &[&governance]).unwrap();
+    assert_eq!(fixture.test.token_balance(&vault_address(&vectai)), Some(7));
+    assert_eq!(fixture.vault(&share_mint).unwrap().owner, fixture.treasury);
+}
+
+#[test]
+fn initialize_fee_vaults_requires_governance() {
+    let mut fixture = Fixture::new();
+    let impostor = Keypair::new();
+
+    let ix = fixture.initialize_fee_vaults(&impostor, &[]);
+    assert_eq!(
+        fixture.test.process_instruction(ix, &[&impostor]).unwrap_err(),
+        anchor_error(TreasuryError::Unauthorized)
+    );
+    assert!(fixture.vault(&fixture.wsol).is_none());
+    assert!(fixture.vault(&fixture.usdc).is_none());
+}
+
+#[test]
+fn initialize_fee_vaults_rejects_mismatched_vaults() {
+    let mut fixture = Fixture::new();
+    let vectai = Pubkey::new_unique();
+    fixture.test.create_mint(vectai, &Pubkey::new_unique(), 6);
+    let governance = fixture.governance.insecure_clone();
+
+    // A vault without its mint, and a vault at another mint's address
+    let mut unpaired = fixture.initialize_fee_vaults(&governance, &[vectai]);
+    unpaired.accounts.pop();
+    let mut misplaced = fixture.initialize_fee_vaults(&governance, &[vectai]);
+    misplaced.accounts.last_mut().unwrap().pubkey = Pubkey::new_unique();
+    for ix in [unpaired, misplaced] {
+        assert_eq!(
+            fixture.test.process_instruction(ix, &[&governance]).unwrap_err(),
+            anchor_error(TreasuryError::InvalidTokenAccount)
+        );
+    }
+    // Rolled back with the transaction
+    assert!(fixture.vault(&fixture.wsol).is_none());
+}
+
+#[test]
+fn convert_stable_swaps_treasury_usdt_into_usdc() {
+    let mut fixture = Fixture::new();
+    let pool = fixture.list_stable_pool(CONVERT_AMOUNT);
+    let governance = fixture.governance.insecure_clone();
+    let pricing = PoolPricing::StableSwap {
+        amplification: STABLE_AMPLIFICATION,
+        reserve_in: STABLE_RESERVE,
+        reserve_out: STABLE_RESERVE,
+    };
+    let expected = expected_amount_out(CONVERT_AMOUNT, false, pricing, STABLE_FEE_BPS).unwrap();
+
+    let ix = fixture.convert_stable(&governance, &pool, expected);
+    fixture.test.process_instruction(ix, &[&governance]).unwrap();
+    assert_eq!(fixture.test.token_balance(&vault_address(&pool.mint_a)), Some(0));
+    assert_eq!(fixture.test.token_balance(&vault_address(&pool.mint_b)), Some(expected));
+    let event = &fixture.test.events::<StableConverted>()[0];
+    assert_eq!((event.source_mint, event.destination_mint), (pool.mint_a, pool.mint_b));
+    assert_eq!((event.amount_in, event.amount_out), (CONVERT_AMOUNT, expected));
+}
+
+#[test]
+fn convert_stable_requires_governance() {
+    let mut fixture = Fixture::new();
+    let pool = fixture.list_stable_pool(CONVERT_AMOUNT);
+    let impostor = Keypair::new();
+
+    let ix = fixture.convert_stable(&impostor, &pool, 1);
+    assert_eq!(
+        fixture.test.process_instruction(ix, &[&impostor]).unwrap_err(),
+        anchor_error(TreasuryError::Unauthorized)
+    );
+    assert_eq!(fixture.test.token_balance(&vault_address(&pool.mint_a)), Some(CONVERT_AMOUNT));
+}
+
+// ===== STREAMS =====
+
+const STREAM_AMOUNT: u64 = 1_000_000;
+
+/// USDC stream written directly, a quarter vested and 100_000 already
+/// withdrawn; funding one needs an approved proposal
+fn stream(fixture: &mut Fixture, payee: &Keypair) -> (Stream, Pubkey) {
+    let (address, bump) = Pubkey::find_program_address(&[b"stream", &0u64.to_le_bytes()], &vectai_treasury::ID);
+    let escrow = Pubkey::new_unique();
+    let recipient = Pubkey::new_unique();
+    let usdc = fixture.usdc;
+    fixture.test.create_token_account(escrow, &usdc, &address, STREAM_AMOUNT - 100_000);
+    fixture.test.create_token_account(recipient, &usdc, &payee.pubkey(), 100_000);
+    fixture.test.create_token_account(vault_address(&usdc), &usdc, &fixture.treasury, 0);
+    let now = fixture.test.clock().unix_timestamp;
+    let stream = Stream {
+        id: 0,
+        mint: usdc,
+        recipient,
+        escrow,
+        total_amount: STREAM_AMOUNT,
+        withdrawn: 100_000,
+        start_time: now - 250,
+        end_time: now + 750,
+        cancelled: false,
+        bump,
+    };
+    fixture.test.set_anchor_account(address, &stream);
+    (stream, address)
+}
+
+#[test]
+fn cancel_stream_returns_the_unvested_rest_and_leaves_the_accrued_claimable() {
+    let mut fixture = Fixture::new();
+    let payee = Keypair::new();
+    let (stream, address) = stream(&mut fixture, &payee);
+    let governance = fixture.governance.insecure_clone();
+
+    let cancel = Instruction {
+        program_id: vectai_treasury::ID,
+        accounts: accounts::CancelStream {
+            treasury: fixture.treasury,
+            stream: address,
+            escrow: stream.escrow,
+            treasury_vault: vault_address(&fixture.usdc),
+            authority: governance.pubkey(),
+            token_program: spl_token::ID,
+        }
+        .to_account_metas(None),
+        data: instruction::CancelStream {}.data(),
+    };
+    fixture.test.process_instruction(cancel, &[&governance]).unwrap();
+    assert_eq!(fixture.test.token_balance(&vault_address(&fixture.usdc)), Some(750_000));
+    assert_eq!(fixture.test.token_balance(&stream.escrow), Some(150_000));
+    assert_eq!(fixture.test.token_balance(&stream.recipient), Some(100_000));
+    let [cancelled] = fixture.test.events::<StreamCancelled>().try_into().ok().unwrap();
+    assert_eq!((cancelled.still_claimable, cancelled.returned_to_treasury), (150_000, 750_000));
+
+    // Nothing vests after the cancellation
+    fixture.test.warp_forward(1_000);
+    let withdraw = Instruction {
+        program_id: vectai_treasury::ID,
+        accounts: accounts::WithdrawStream {
+            stream: address,
+            escrow: stream.escrow,
+            recipient: stream.recipient,
+            payee: payee.pubkey(),
+            token_program: spl_token::ID,
+        }
+        .to_account_metas(None),
+        data: instruction::WithdrawStream {}.data(),
+    };
+    fixture.test.process_instruction(withdraw, &[&payee]).unwrap();
+    assert_eq!(fixture.test.token_balance(&stream.recipient), Some(250_000));
+    assert_eq!(fixture.test.token_balance(&stream.escrow), Some(0));
+    let settled: Stream = fixture.test.anchor_account(&address).unwrap();
+    assert_eq!((settled.withdrawn, settled.total_amount), (250_000, 250_000));
+}
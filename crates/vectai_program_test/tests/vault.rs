@@ -0,0 +1,977 @@
+//! `vectai_vault` instructions.
+//!
+//! Vaults take USDC deposits against WSOL as the quote mint, valued at the
+//! mock oracle's 45000 (expo 0). Their quote vault stays empty unless a
+//! test fills it, so NAV is the base balance.
+
+use anchor_lang::{
+    solana_program::{system_program, sysvar},
+    AnchorDeserialize, InstructionData, ToAccountMetas,
+};
+use solana_sdk::{
+    instruction::{AccountMeta, Instruction},
+    native_token::LAMPORTS_PER_SOL,
+    program_error::ProgramError,
+    pubkey::Pubkey,
+    signature::{Keypair, Signer},
+};
+use vectai_program_test::{
+    anchor_error, mock_amm,
+    mock_solend::{self, Reserve},
+    trader::TradingNetwork,
+    ProgramTest,
+};
+use vectai_protocol::FeeConfig;
+use vectai_timelock::{timelock_authority, BatchOperation, OperationAccount, OperationInstruction};
+use vectai_vault::{
+    accounts, instruction, redemption_amount, strategy_escrow, vault_allocations, AllocationsRebalanced,
+    AllocationsUpdated, DepositCapsUpdated, DepositorPosition, FeesAccrued, IdleFundsRecalled, PnlRealized,
+    SharePrice, StrategyRegistered, Vault, VaultAllocations, VaultError, MAX_STRATEGIES, SECONDS_PER_YEAR,
+    SHARE_PRICE_PRECISION, VIRTUAL_ASSETS,
+};
+
+const DEPOSITOR_BALANCE: u64 = 10_000_000;
+const ORACLE_PRICE: i64 = 45_000;
+
+fn pda(seeds: &[&[u8]]) -> Pubkey {
+    Pubkey::find_program_address(seeds, &vectai_vault::ID).0
+}
+
+fn ix(accounts: impl ToAccountMetas, data: impl InstructionData) -> Instruction {
+    Instruction {
+        program_id: vectai_vault::ID,
+        accounts: accounts.to_account_metas(None),
+        data: data.data(),
+    }
+}
+
+/// A vault whose manager is funded, and a protocol status to pass its gates
+struct Fixture {
+    test: ProgramTest,
+    manager: Keypair,
+    vault: Pubkey,
+    base_mint: Pubkey,
+    quote_mint: Pubkey,
+    price_feed: Pubkey,
+    protocol_status: Pubkey,
+    lending: Vec<AccountMeta>, // Remaining accounts of instructions valuing the vault
+}
+
+impl Fixture {
+    fn new() -> Self {
+        let mut test = ProgramTest::new();
+        let protocol_status = test.set_protocol_status(false, FeeConfig::INITIAL);
+        let (base_mint, quote_mint) = (Pubkey::new_unique(), Pubkey::new_unique());
+        let mint_authority = Pubkey::new_unique();
+        test.create_mint(base_mint, &mint_authority, 6);
+        test.create_mint(quote_mint, &mint_authority, 9);
+        Self::with_mints(test, protocol_status, base_mint, quote_mint)
+    }
+
+    /// A vault of `base_mint` against `quote_mint`, both already created
+    fn with_mints(mut test: ProgramTest, protocol_status: Pubkey, base_mint: Pubkey, quote_mint: Pubkey) -> Self {
+        let manager = Keypair::new();
+        test.airdrop(&manager.pubkey(), 10 * LAMPORTS_PER_SOL);
+
+        let vault = pda(&[b"vault", manager.pubkey().as_ref(), base_mint.as_ref()]);
+        let price_feed = Pubkey::new_unique();
+        let init = ix(
+            accounts::InitializeVault {
+                vault,
+                base_mint,
+                quote_mint,
+                base_vault: pda(&[b"base-vault", vault.as_ref()]),
+                quote_vault: pda(&[b"quote-vault", vault.as_ref()]),
+                share_mint: pda(&[b"shares", vault.as_ref()]),
+                price_feed,
+                referral_code: None,
+                manager: manager.pubkey(),
+                rent: sysvar::rent::ID,
+                token_program: spl_token::ID,
+                system_program: system_program::ID,
+            },
+            instruction::InitializeVault {},
+        );
+        test.process_instruction(init, &[&manager]).unwrap();
+        Fixture {
+            test,
+            manager,
+            vault,
+            base_mint,
+            quote_mint,
+            price_feed,
+            protocol_status,
+            lending: Vec::new(),
+        }
+    }
+
+    fn vault(&self) -> Vault {
+        self.test.anchor_account(&self.vault).unwrap()
+    }
+
+    fn position(&self, depositor: &Depositor) -> DepositorPosition {
+        let address = vectai_vault::depositor_position(&self.vault, &depositor.keypair.pubkey());
+        self.test.anchor_account(&address).unwrap()
+    }
+
+    /// A funded depositor with `DEPOSITOR_BALANCE` base tokens and an empty
+    /// share account
+    fn depositor(&mut self) -> Depositor {
+        let keypair = Keypair::new();
+        self.test.airdrop(&keypair.pubkey(), LAMPORTS_PER_SOL);
+        let owner = keypair.pubkey();
+        let (base, quote, shares) = (Pubkey::new_unique(), Pubkey::new_unique(), Pubkey::new_unique());
+        self.test.create_token_account(base, &self.base_mint, &owner, DEPOSITOR_BALANCE);
+        self.test.create_token_account(quote, &self.quote_mint, &owner, 0);
+        let share_mint = self.vault().share_mint;
+        self.test.create_token_account(shares, &share_mint, &owner, 0);
+        Depositor {
+            keypair,
+            base,
+            quote,
+            shares,
+        }
+    }
+
+    fn deposit(&mut self, depositor: &Depositor, amount: u64) -> Result<(), ProgramError> {
+        let vault = self.vault();
+        let user = depositor.keypair.pubkey();
+        let mut ix = ix(
+            accounts::Deposit {
+                vault: self.vault,
+                base_mint: vault.base_mint,
+                quote_mint: vault.quote_mint,
+                base_vault: vault.base_vault,
+                quote_vault: vault.quote_vault,
+                share_mint: vault.share_mint,
+                fee_recipient: fee_recipient(&vault),
+                referral_escrow: None,
+                user_base_account: depositor.base,
+                user_share_account: depositor.shares,
+                depositor_position: vectai_vault::depositor_position(&self.vault, &user),
+                user,
+                vectai_oracle_program: vectai_oracle::ID,
+                price_feed: self.price_feed,
+                token_program: spl_token::ID,
+                system_program: system_program::ID,
+                protocol_status: self.protocol_status,
+            },
+            instruction::Deposit { amount },
+        );
+        ix.accounts.extend(self.lending.clone());
+        self.test.process_instruction(ix, &[&depositor.keypair])
+    }
+
+    fn withdraw(&mut self, depositor: &Depositor, shares: u64, release: bool) -> Result<(), ProgramError> {
+        let price_feed = self.price_feed;
+        self.withdraw_with(depositor, shares, release, Some(price_feed))
+    }
+
+    /// `withdraw`, passing `price_feed` or, for an oracle outage, none
+    fn withdraw_with(
+        &mut self,
+        depositor: &Depositor,
+        shares: u64,
+        release: bool,
+        price_feed: Option<Pubkey>,
+    ) -> Result<(), ProgramError> {
+        let vault = self.vault();
+        let user = depositor.keypair.pubkey();
+        let mut ix = ix(
+            accounts::Withdraw {
+                vault: self.vault,
+                base_mint: vault.base_mint,
+                quote_mint: vault.quote_mint,
+                base_vault: vault.base_vault,
+                quote_vault: vault.quote_vault,
+                share_mint: vault.share_mint,
+                fee_recipient: fee_recipient(&vault),
+                referral_escrow: None,
+                user_share_account: depositor.shares,
+                user_base_account: depositor.base,
+                user_quote_account: depositor.quote,
+                depositor_position: release.then(|| vectai_vault::depositor_position(&self.vault, &user)),
+                user,
+                vectai_oracle_program: vectai_oracle::ID,
+                price_feed,
+                token_program: spl_token::ID,
+                protocol_status: self.protocol_status,
+            },
+            instruction::Withdraw { shares },
+        );
+        ix.accounts.extend(self.lending.clone());
+        self.test.process_instruction(ix, &[&depositor.keypair])
+    }
+
+    fn set_deposit_caps_with(
+        &mut self,
+        signer: &Keypair,
+        max_total_value: u64,
+        max_per_depositor: u64,
+    ) -> Result<(), ProgramError> {
+        let ix = ix(
+            accounts::ManageVault {
+                vault: self.vault,
+                manager: signer.pubkey(),
+            },
+            instruction::SetDepositCaps {
+                max_total_value,
+                max_per_depositor,
+            },
+        );
+        self.test.process_instruction(ix, &[signer])
+    }
+
+    fn set_deposit_caps(&mut self, max_total_value: u64, max_per_depositor: u64) -> Result<(), ProgramError> {
+        let manager = self.manager.insecure_clone();
+        self.set_deposit_caps_with(&manager, max_total_value, max_per_depositor)
+    }
+}
+
+/// The vault's fee share account, while it charges fees
+fn fee_recipient(vault: &Vault) -> Option<Pubkey> {
+    (vault.fee_recipient != Pubkey::default()).then_some(vault.fee_recipient)
+}
+
+struct Depositor {
+    keypair: Keypair,
+    base: Pubkey,
+    quote: Pubkey,
+    shares: Pubkey,
+}
+
+// ===== DEPOSIT CAPS =====
+
+#[test]
+fn deposits_are_uncapped_by_default() {
+    let mut fixture = Fixture::new();
+    assert_eq!(fixture.vault().caps, Default::default());
+
+    let depositor = fixture.depositor();
+    fixture.deposit(&depositor, DEPOSITOR_BALANCE).unwrap();
+    assert_eq!(fixture.test.token_balance(&depositor.shares), Some(DEPOSITOR_BALANCE));
+    let position = fixture.position(&depositor);
+    assert_eq!((position.vault, position.depositor), (fixture.vault, depositor.keypair.pubkey()));
+    assert_eq!(position.shares, DEPOSITOR_BALANCE);
+}
+
+#[test]
+fn set_deposit_caps_requires_manager() {
+    let mut fixture = Fixture::new();
+    fixture.set_deposit_caps(5_000_000, 1_000_000).unwrap();
+    let [event] = fixture.test.events::<DepositCapsUpdated>().try_into().ok().unwrap();
+    assert_eq!((event.vault, event.max_total_value, event.max_per_depositor), (fixture.vault, 5_000_000, 1_000_000));
+    let caps = fixture.vault().caps;
+    assert_eq!((caps.max_total_value, caps.max_per_depositor), (5_000_000, 1_000_000));
+
+    let other = Keypair::new();
+    fixture.test.airdrop(&other.pubkey(), LAMPORTS_PER_SOL);
+    assert_eq!(
+        fixture.set_deposit_caps_with(&other, 0, 0).unwrap_err(),
+        anchor_error(VaultError::Unauthorized)
+    );
+}
+
+#[test]
+fn total_cap_bounds_vault_value() {
+    let mut fixture = Fixture::new();
+    fixture.set_deposit_caps(3_000_000, 0).unwrap();
+    let (first, second) = (fixture.depositor(), fixture.depositor());
+
+    fixture.deposit(&first, 2_000_000).unwrap();
+    assert_eq!(
+        fixture.deposit(&second, 1_000_001).unwrap_err(),
+        anchor_error(VaultError::VaultCapExceeded)
+    );
+    // Up to the cap exactly
+    fixture.deposit(&second, 1_000_000).unwrap();
+    assert_eq!(fixture.test.token_balance(&fixture.vault().base_vault), Some(3_000_000));
+
+    // Withdrawals make room again
+    fixture.withdraw(&first, 500_000, false).unwrap();
+    fixture.deposit(&second, 500_000).unwrap();
+}
+
+#[test]
+fn per_depositor_cap_bounds_each_position() {
+    let mut fixture = Fixture::new();
+    fixture.set_deposit_caps(0, 2_000_000).unwrap();
+    let (first, second) = (fixture.depositor(), fixture.depositor());
+    let over = anchor_error(VaultError::DepositorCapExceeded);
+
+    assert_eq!(fixture.deposit(&first, 2_000_001).unwrap_err(), over);
+    fixture.deposit(&first, 1_500_000).unwrap();
+    assert_eq!(fixture.deposit(&first, 500_001).unwrap_err(), over);
+    fixture.deposit(&first, 500_000).unwrap();
+
+    // Others have their own room
+    fixture.deposit(&second, 2_000_000).unwrap();
+
+    // Withdrawing without the position keeps the room used; passing it
+    // releases the burned shares
+    fixture.withdraw(&first, 1_000_000, false).unwrap();
+    assert_eq!(fixture.deposit(&first, 1).unwrap_err(), over);
+    fixture.withdraw(&first, 500_000, true).unwrap();
+    assert_eq!(fixture.position(&first).shares, 1_500_000);
+    fixture.deposit(&first, 500_000).unwrap();
+    assert_eq!(fixture.position(&first).shares, 2_000_000);
+}
+
+// ===== SHARE PRICE =====
+
+impl Fixture {
+    fn share_price(&mut self) -> SharePrice {
+        let vault = self.vault();
+        let mut ix = ix(
+            accounts::GetSharePrice {
+                vault: self.vault,
+                base_mint: vault.base_mint,
+                quote_mint: vault.quote_mint,
+                base_vault: vault.base_vault,
+                quote_vault: vault.quote_vault,
+                share_mint: vault.share_mint,
+                vectai_oracle_program: vectai_oracle::ID,
+                price_feed: self.price_feed,
+            },
+            instruction::GetSharePrice {},
+        );
+        ix.accounts.extend(self.lending.clone());
+        self.test.process_instruction(ix, &[]).unwrap();
+        let (program, data) = self.test.return_data().expect("return data");
+        assert_eq!(*program, vectai_vault::ID);
+        SharePrice::deserialize(&mut data.as_slice()).unwrap()
+    }
+}
+
+#[test]
+fn share_price_values_both_balances_at_the_oracle() {
+    let mut fixture = Fixture::new();
+    let now = fixture.test.clock().unix_timestamp;
+    // 1.0 before any deposit
+    let empty = fixture.share_price();
+    assert_eq!((empty.nav, empty.share_supply, empty.share_price), (0, 0, SHARE_PRICE_PRECISION));
+    assert_eq!(empty.timestamp, now);
+
+    let depositor = fixture.depositor();
+    fixture.deposit(&depositor, 4_500_000).unwrap();
+    let price = fixture.share_price();
+    assert_eq!((price.nav, price.share_supply, price.share_price), (4_500_000, 4_500_000, SHARE_PRICE_PRECISION));
+
+    // The pool buys 0.0001 WSOL (9 decimals) worth 4.5 USDC (6 decimals) at
+    // the mock oracle's 45_000: NAV doubles over the same shares
+    let vault = fixture.vault();
+    fixture.test.create_token_account(vault.quote_vault, &vault.quote_mint, &fixture.vault, 100_000);
+    let price = fixture.share_price();
+    assert_eq!((price.nav, price.share_supply), (9_000_000, 4_500_000));
+    assert_eq!(price.share_price, 2 * SHARE_PRICE_PRECISION);
+}
+
+#[test]
+fn donations_cannot_round_deposits_to_zero_shares() {
+    let mut fixture = Fixture::new();
+    let (attacker, victim) = (fixture.depositor(), fixture.depositor());
+
+    // One share for one base unit, then 4 USDC donated straight to the
+    // base vault to inflate it
+    fixture.deposit(&attacker, 1).unwrap();
+    let vault = fixture.vault();
+    fixture.test.create_token_account(vault.base_vault, &vault.base_mint, &fixture.vault, 4_000_001);
+
+    // The virtual shares keep the victim's 1 USDC from rounding to zero
+    // shares: 1_000_000 * (1 + 1_000) / (4_000_001 + 1_000)
+    fixture.deposit(&victim, 1_000_000).unwrap();
+    assert_eq!(fixture.test.token_balance(&victim.shares), Some(250));
+
+    // and most of the donation stays behind the virtual shares
+    fixture.withdraw(&attacker, 1, true).unwrap();
+    assert_eq!(fixture.test.token_balance(&attacker.base), Some(DEPOSITOR_BALANCE - 1 + 3_997));
+    fixture.withdraw(&victim, 250, true).unwrap();
+    assert_eq!(fixture.test.token_balance(&victim.base), Some(DEPOSITOR_BALANCE - 600));
+}
+
+// ===== FEES =====
+
+impl Fixture {
+    /// `set_vault_fees` through governance, opening the treasury's fee share
+    /// account first
+    fn set_vault_fees(&mut self, management_fee_bps: u16) -> Result<(), ProgramError> {
+        let vault = self.vault();
+        let fee_recipient = vectai_treasury::vault_address(&vault.share_mint);
+        if self.test.account(&fee_recipient).is_none() {
+            self.test.create_token_account(fee_recipient, &vault.share_mint, &Pubkey::new_unique(), 0);
+        }
+        let update = ix(
+            accounts::SetVaultFees {
+                vault: self.vault,
+                base_mint: vault.base_mint,
+                quote_mint: vault.quote_mint,
+                base_vault: vault.base_vault,
+                quote_vault: vault.quote_vault,
+                share_mint: vault.share_mint,
+                fee_recipient,
+                referral_escrow: None,
+                admin: timelock_authority(),
+                vectai_oracle_program: vectai_oracle::ID,
+                price_feed: self.price_feed,
+                token_program: spl_token::ID,
+                protocol_status: self.protocol_status,
+            },
+            instruction::SetVaultFees { management_fee_bps },
+        );
+        self.govern(update)
+    }
+
+    fn accrue_fees(&mut self) -> Result<(), ProgramError> {
+        let vault = self.vault();
+        let ix = ix(
+            accounts::AccrueFees {
+                vault: self.vault,
+                base_mint: vault.base_mint,
+                quote_mint: vault.quote_mint,
+                base_vault: vault.base_vault,
+                quote_vault: vault.quote_vault,
+                share_mint: vault.share_mint,
+                fee_recipient: fee_recipient(&vault),
+                referral_escrow: None,
+                vectai_oracle_program: vectai_oracle::ID,
+                price_feed: self.price_feed,
+                token_program: spl_token::ID,
+                protocol_status: self.protocol_status,
+            },
+            instruction::AccrueFees {},
+        );
+        self.test.process_instruction(ix, &[])
+    }
+
+    fn fee_shares(&self) -> u64 {
+        let recipient = vectai_treasury::vault_address(&self.vault().share_mint);
+        self.test.token_balance(&recipient).unwrap_or(0)
+    }
+}
+
+#[test]
+fn accrue_fees_runs_with_fees_off() {
+    let mut fixture = Fixture::new();
+    let depositor = fixture.depositor();
+    fixture.deposit(&depositor, DEPOSITOR_BALANCE).unwrap();
+
+    // No fee recipient: the crank only moves the accrual clock
+    fixture.test.warp_forward(SECONDS_PER_YEAR);
+    fixture.accrue_fees().unwrap();
+    assert_eq!(fixture.vault().last_fee_accrual, fixture.test.clock().unix_timestamp);
+    assert!(fixture.test.events::<FeesAccrued>().is_empty());
+    assert_eq!(
+        fixture.accrue_fees().unwrap_err(),
+        anchor_error(VaultError::NothingToAccrue)
+    );
+}
+
+#[test]
+fn fee_changes_settle_at_the_old_rates() {
+    let mut fixture = Fixture::new();
+    let depositor = fixture.depositor();
+    fixture.deposit(&depositor, DEPOSITOR_BALANCE).unwrap();
+
+    // A year with fees off is not charged once they are turned on
+    fixture.test.warp_forward(SECONDS_PER_YEAR);
+    fixture.set_vault_fees(500).unwrap();
+    assert_eq!(fixture.fee_shares(), 0);
+
+    // A year at 5% is charged when the fee is turned off again:
+    // 500_000 of fees diluting 10_000_000 shares worth 10 USDC
+    fixture.test.warp_forward(SECONDS_PER_YEAR);
+    fixture.set_vault_fees(0).unwrap();
+    assert_eq!(fixture.fee_shares(), 526_315);
+    let [event] = fixture.test.events::<FeesAccrued>().try_into().ok().unwrap();
+    assert_eq!((event.management_fee, event.fee_shares), (500_000, 526_315));
+
+    fixture.test.warp_forward(SECONDS_PER_YEAR);
+    fixture.accrue_fees().unwrap();
+    assert_eq!(fixture.fee_shares(), 526_315);
+}
+
+#[test]
+fn deposits_and_withdrawals_settle_fees_first() {
+    let mut fixture = Fixture::new();
+    let (first, second) = (fixture.depositor(), fixture.depositor());
+    fixture.deposit(&first, DEPOSITOR_BALANCE).unwrap();
+    fixture.set_vault_fees(500).unwrap();
+
+    // The first depositor's year is settled before the second deposit is
+    // priced, so the second buys in after the fee
+    fixture.test.warp_forward(SECONDS_PER_YEAR);
+    fixture.deposit(&second, DEPOSITOR_BALANCE).unwrap();
+    assert_eq!(fixture.fee_shares(), 526_315);
+    assert_eq!(fixture.test.token_balance(&second.shares), Some(10_526_262));
+
+    // Half a year on both deposits is settled before the first redeems
+    fixture.test.warp_forward(SECONDS_PER_YEAR / 2);
+    fixture.withdraw(&first, DEPOSITOR_BALANCE, true).unwrap();
+    assert_eq!(fixture.fee_shares(), 526_315 + 539_809);
+    assert_eq!(fixture.test.token_balance(&first.base), Some(9_262_524));
+    assert_eq!(fixture.vault().last_fee_accrual, fixture.test.clock().unix_timestamp);
+}
+
+#[test]
+fn withdrawals_settle_fees_at_the_high_water_mark_without_a_price() {
+    let mut fixture = Fixture::new();
+    let fee_config = FeeConfig {
+        performance_fee_bps: 2_000,
+        ..FeeConfig::INITIAL
+    };
+    fixture.test.set_protocol_status(false, fee_config);
+    let (first, second) = (fixture.depositor(), fixture.depositor());
+    fixture.deposit(&first, DEPOSITOR_BALANCE).unwrap();
+    fixture.deposit(&second, DEPOSITOR_BALANCE).unwrap();
+    fixture.set_vault_fees(500).unwrap();
+    let high_water_mark = fixture.vault().high_water_mark;
+
+    // The vault gains 10%, but with the oracle down the first depositor
+    // exits on management fees alone, at the high-water mark
+    let base_vault = fixture.vault().base_vault;
+    let base_mint = fixture.base_mint;
+    fixture
+        .test
+        .create_token_account(base_vault, &base_mint, &fixture.vault, 2 * DEPOSITOR_BALANCE * 11 / 10);
+    fixture.test.warp_forward(SECONDS_PER_YEAR);
+    fixture.withdraw_with(&first, DEPOSITOR_BALANCE, true, None).unwrap();
+    let [event] = fixture.test.events::<FeesAccrued>().try_into().ok().unwrap();
+    assert_eq!(event.management_fee, 2 * DEPOSITOR_BALANCE * 500 / 10_000);
+    assert_eq!((event.performance_fee, event.high_water_mark), (0, high_water_mark));
+    assert_eq!(fixture.vault().high_water_mark, high_water_mark);
+
+    // The gain is charged at the next priced settlement
+    fixture.test.warp_forward(1);
+    fixture.accrue_fees().unwrap();
+    let [event] = fixture.test.events::<FeesAccrued>().try_into().ok().unwrap();
+    assert!(event.performance_fee > 0);
+    assert!(fixture.vault().high_water_mark > high_water_mark);
+}
+
+// ===== TRADING =====
+
+const POOL_RESERVE: u64 = 1_000_000_000_000;
+
+/// A vault of the trading network's USDC against its WSOL, and the network
+fn trading_fixture() -> (Fixture, TradingNetwork) {
+    let mut test = ProgramTest::new();
+    let network = test.create_trading_network(POOL_RESERVE);
+    let fixture = Fixture::with_mints(test, network.protocol_status, network.usdc, network.wsol);
+    (fixture, network)
+}
+
+impl Fixture {
+    fn trade(&mut self, network: &TradingNetwork, amount_in: u64, base_to_quote: bool) -> Result<(), ProgramError> {
+        let vault = self.vault();
+        let pool = &network.pool;
+        let ix = ix(
+            accounts::VaultTrade {
+                vault: self.vault,
+                base_vault: vault.base_vault,
+                quote_vault: vault.quote_vault,
+                base_mint: vault.base_mint,
+                quote_mint: vault.quote_mint,
+                share_mint: vault.share_mint,
+                manager: self.manager.pubkey(),
+                vectai_oracle_program: vectai_oracle::ID,
+                price_feed: self.price_feed,
+                swapper_program: raydium_swapper::ID,
+                raydium_amm_program: pool.amm_program,
+                amm: pool.amm,
+                amm_authority: pool.amm_authority,
+                amm_open_orders: pool.amm_open_orders,
+                amm_target_orders: pool.amm_target_orders,
+                pool_coin_token_account: pool.pool_coin_token_account,
+                pool_pc_token_account: pool.pool_pc_token_account,
+                serum_program: pool.serum_program,
+                serum_market: pool.serum_market,
+                serum_bids: pool.serum_bids,
+                serum_asks: pool.serum_asks,
+                serum_event_queue: pool.serum_event_queue,
+                serum_coin_vault: pool.serum_coin_vault,
+                serum_pc_vault: pool.serum_pc_vault,
+                serum_vault_signer: pool.serum_vault_signer,
+                token_program: spl_token::ID,
+                protocol_status: network.protocol_status,
+                network_config: network.network_config,
+                protocol_stats: network.protocol_stats,
+                swapper_stats_reporter: vectai_protocol::stats_reporter(&raydium_swapper::ID),
+                protocol_program: vectai_protocol::ID,
+                swap_fee_account: None,
+            },
+            instruction::Trade {
+                amount_in,
+                min_amount_out: 1,
+                base_to_quote,
+            },
+        );
+        let manager = self.manager.insecure_clone();
+        self.test.process_instruction(ix, &[&manager])
+    }
+}
+
+#[test]
+fn trades_realize_pnl_marked_at_the_oracle() {
+    let (mut fixture, network) = trading_fixture();
+    let depositor = fixture.depositor();
+    fixture.deposit(&depositor, 4_500_000).unwrap();
+
+    // 1 USDC for WSOL at the pool's 1:1, worth 45 USDC at the oracle
+    fixture.trade(&network, 1_000_000, true).unwrap();
+    let bought = mock_amm::quote(1_000_000, POOL_RESERVE, POOL_RESERVE);
+    let value_out = vectai_vault::quote_value_in_base(bought, ORACLE_PRICE, 0, 6, 9).unwrap();
+    let pnl = value_out as i64 - 1_000_000;
+    assert_eq!(fixture.vault().realized_pnl, pnl);
+    let [event] = fixture.test.events::<PnlRealized>().try_into().ok().unwrap();
+    assert_eq!(event.vault, fixture.vault);
+    assert_eq!((event.value_in, event.value_out, event.pnl), (1_000_000, value_out, pnl));
+
+    // Selling the WSOL back realizes the loss and accumulates on the vault
+    fixture.trade(&network, bought, false).unwrap();
+    let value_in = vectai_vault::quote_value_in_base(bought, ORACLE_PRICE, 0, 6, 9).unwrap();
+    let [event] = fixture.test.events::<PnlRealized>().try_into().ok().unwrap();
+    assert_eq!(event.value_in, value_in);
+    assert!(event.pnl < 0);
+    assert_eq!(event.realized_pnl, pnl + event.pnl);
+    assert_eq!(fixture.vault().realized_pnl, event.realized_pnl);
+}
+
+// ===== ALLOCATIONS =====
+
+impl Fixture {
+    fn register_strategy_with(&mut self, signer: &Keypair, strategy: Pubkey) -> Result<(), ProgramError> {
+        let ix = ix(
+            accounts::RegisterStrategy {
+                vault: self.vault,
+                allocations: vault_allocations(&self.vault),
+                base_mint: self.base_mint,
+                strategy_escrow: strategy_escrow(&self.vault, &strategy),
+                manager: signer.pubkey(),
+                rent: sysvar::rent::ID,
+                token_program: spl_token::ID,
+                system_program: system_program::ID,
+            },
+            instruction::RegisterStrategy { strategy },
+        );
+        self.test.process_instruction(ix, &[signer])
+    }
+
+    /// Register a fresh strategy, returning its escrow
+    fn register_strategy(&mut self) -> Pubkey {
+        let (manager, strategy) = (self.manager.insecure_clone(), Pubkey::new_unique());
+        self.register_strategy_with(&manager, strategy).unwrap();
+        strategy_escrow(&self.vault, &strategy)
+    }
+
+    fn allocations(&self) -> VaultAllocations {
+        self.test.anchor_account(&vault_allocations(&self.vault)).unwrap()
+    }
+
+    fn update_allocations_ix(&self, admin: Pubkey, weights: Vec<u16>) -> Instruction {
+        ix(
+            accounts::UpdateAllocations {
+                vault: self.vault,
+                allocations: vault_allocations(&self.vault),
+                admin,
+            },
+            instruction::UpdateAllocations { weights },
+        )
+    }
+
+    fn govern_allocations(&mut self, weights: Vec<u16>) -> Result<(), ProgramError> {
+        let update = self.update_allocations_ix(timelock_authority(), weights);
+        self.govern(update)
+    }
+
+    /// `update` as governance applies it: queued as a timelock batch,
+    /// written here since queueing takes the admin, then executed once due
+    fn govern(&mut self, update: Instruction) -> Result<(), ProgramError> {
+        let timelock = Pubkey::find_program_address(&[b"timelock"], &vectai_timelock::ID);
+        self.test.set_anchor_account(
+            timelock.0,
+            &vectai_timelock::Timelock {
+                min_delay: vectai_timelock::MIN_DELAY_FLOOR,
+                operation_count: 0,
+                bump: timelock.1,
+            },
+        );
+        let operation = OperationInstruction {
+            program_id: update.program_id,
+            accounts: update
+                .accounts
+                .iter()
+                .map(|meta| OperationAccount {
+                    pubkey: meta.pubkey,
+                    is_signer: meta.pubkey == timelock_authority(),
+                    is_writable: meta.is_writable,
+                })
+                .collect(),
+            data: update.data,
+        };
+        let (batch, bump) = Pubkey::find_program_address(&[b"batch", 0u64.to_le_bytes().as_ref()], &vectai_timelock::ID);
+        let now = self.test.clock().unix_timestamp;
+        self.test.set_anchor_account(
+            batch,
+            &BatchOperation {
+                id: 0,
+                proposer: self.manager.pubkey(),
+                instructions: vec![operation],
+                queued_at: now,
+                eta: now,
+                bump,
+            },
+        );
+
+        let mut metas = vectai_timelock::accounts::ExecuteBatch {
+            timelock: timelock.0,
+            batch,
+            proposer: self.manager.pubkey(),
+        }
+        .to_account_metas(None);
+        metas.push(AccountMeta::new_readonly(vectai_vault::ID, false));
+        // The timelock PDA is appended by the timelock itself
+        metas.extend(update.accounts.into_iter().filter(|meta| meta.pubkey != timelock_authority()));
+        let execute = Instruction {
+            program_id: vectai_timelock::ID,
+            accounts: metas,
+            data: vectai_timelock::instruction::ExecuteBatch {}.data(),
+        };
+        self.test.process_instruction(execute, &[])
+    }
+
+    fn rebalance(&mut self, escrows: &[Pubkey]) -> Result<(), ProgramError> {
+        let mut ix = ix(
+            accounts::RebalanceAllocations {
+                vault: self.vault,
+                allocations: vault_allocations(&self.vault),
+                base_vault: self.vault().base_vault,
+                token_program: spl_token::ID,
+                protocol_status: self.protocol_status,
+            },
+            instruction::RebalanceAllocations {},
+        );
+        ix.accounts.extend(escrows.iter().map(|escrow| AccountMeta::new(*escrow, false)));
+        self.test.process_instruction(ix, &[])
+    }
+}
+
+#[test]
+fn register_strategy_requires_manager_up_to_the_limit() {
+    let mut fixture = Fixture::new();
+    let escrow = fixture.register_strategy();
+    let [event] = fixture.test.events::<StrategyRegistered>().try_into().ok().unwrap();
+    assert_eq!((event.vault, event.escrow), (fixture.vault, escrow));
+    let allocations = fixture.allocations();
+    assert_eq!((allocations.vault, allocations.strategy_count), (fixture.vault, 1));
+    let [registered] = allocations.registered() else { panic!("one strategy") };
+    assert_eq!((registered.strategy, registered.escrow), (event.strategy, escrow));
+    assert_eq!((registered.weight_bps, registered.allocated), (0, 0));
+    assert_eq!(fixture.test.token_balance(&escrow), Some(0));
+
+    let other = Keypair::new();
+    fixture.test.airdrop(&other.pubkey(), LAMPORTS_PER_SOL);
+    assert_eq!(
+        fixture.register_strategy_with(&other, Pubkey::new_unique()).unwrap_err(),
+        anchor_error(VaultError::Unauthorized)
+    );
+
+    for _ in 1..MAX_STRATEGIES {
+        fixture.register_strategy();
+    }
+    let manager = fixture.manager.insecure_clone();
+    assert_eq!(
+        fixture.register_strategy_with(&manager, Pubkey::new_unique()).unwrap_err(),
+        anchor_error(VaultError::TooManyStrategies)
+    );
+}
+
+#[test]
+fn update_allocations_takes_the_timelock_and_valid_weights() {
+    let mut fixture = Fixture::new();
+    fixture.register_strategy();
+    fixture.register_strategy();
+
+    let update = fixture.update_allocations_ix(fixture.manager.pubkey(), vec![5_000, 5_000]);
+    let manager = fixture.manager.insecure_clone();
+    let err = fixture.test.process_instruction(update, &[&manager]).unwrap_err();
+    assert_eq!(err, anchor_error(VaultError::UnauthorizedAdmin));
+
+    for weights in [vec![5_000], vec![6_000, 4_001]] {
+        let err = fixture.govern_allocations(weights).unwrap_err();
+        assert_eq!(err, anchor_error(VaultError::InvalidAllocations));
+    }
+
+    fixture.govern_allocations(vec![6_000, 2_500]).unwrap();
+    let weights: Vec<u16> = fixture.allocations().registered().iter().map(|s| s.weight_bps).collect();
+    assert_eq!(weights, [6_000, 2_500]);
+    let [event] = fixture.test.events::<AllocationsUpdated>().try_into().ok().unwrap();
+    assert_eq!((event.vault, event.weights), (fixture.vault, vec![6_000, 2_500]));
+}
+
+#[test]
+fn rebalance_moves_base_tokens_to_match_the_weights() {
+    let mut fixture = Fixture::new();
+    let depositor = fixture.depositor();
+    fixture.deposit(&depositor, DEPOSITOR_BALANCE).unwrap();
+    let escrows = [fixture.register_strategy(), fixture.register_strategy()];
+    let base_vault = fixture.vault().base_vault;
+
+    fixture.govern_allocations(vec![6_000, 2_500]).unwrap();
+    fixture.rebalance(&escrows).unwrap();
+    assert_eq!(fixture.test.token_balance(&escrows[0]), Some(6_000_000));
+    assert_eq!(fixture.test.token_balance(&escrows[1]), Some(2_500_000));
+    assert_eq!(fixture.test.token_balance(&base_vault), Some(1_500_000));
+    assert_eq!(fixture.vault().allocated, 8_500_000);
+    let allocated: Vec<u64> = fixture.allocations().registered().iter().map(|s| s.allocated).collect();
+    assert_eq!(allocated, [6_000_000, 2_500_000]);
+    let [event] = fixture.test.events::<AllocationsRebalanced>().try_into().ok().unwrap();
+    assert_eq!((event.total, event.moved, event.allocated), (DEPOSITOR_BALANCE, 8_500_000, 8_500_000));
+
+    // Escrowed tokens still count towards NAV
+    let price = fixture.share_price();
+    assert_eq!((price.nav, price.share_price), (DEPOSITOR_BALANCE, SHARE_PRICE_PRECISION));
+
+    // Shifting weight from the first strategy to the second pays the first
+    // back before topping up the second
+    fixture.govern_allocations(vec![1_000, 5_000]).unwrap();
+    fixture.rebalance(&escrows).unwrap();
+    assert_eq!(fixture.test.token_balance(&escrows[0]), Some(1_000_000));
+    assert_eq!(fixture.test.token_balance(&escrows[1]), Some(5_000_000));
+    assert_eq!(fixture.test.token_balance(&base_vault), Some(4_000_000));
+    let [event] = fixture.test.events::<AllocationsRebalanced>().try_into().ok().unwrap();
+    assert_eq!((event.moved, event.allocated), (7_500_000, 6_000_000));
+
+    // Withdrawals are paid from what the weights leave in the base vault
+    assert_eq!(
+        fixture.withdraw(&depositor, DEPOSITOR_BALANCE, true).unwrap_err(),
+        anchor_error(VaultError::InsufficientLiquidity)
+    );
+    fixture.withdraw(&depositor, DEPOSITOR_BALANCE / 4, true).unwrap();
+    assert_eq!(fixture.test.token_balance(&depositor.base), Some(DEPOSITOR_BALANCE / 4));
+}
+
+#[test]
+fn rebalance_requires_every_escrow_in_order() {
+    let mut fixture = Fixture::new();
+    let depositor = fixture.depositor();
+    fixture.deposit(&depositor, DEPOSITOR_BALANCE).unwrap();
+    let escrows = [fixture.register_strategy(), fixture.register_strategy()];
+    fixture.govern_allocations(vec![5_000, 5_000]).unwrap();
+
+    for passed in [&escrows[..1], &[escrows[1], escrows[0]]] {
+        let err = fixture.rebalance(passed).unwrap_err();
+        assert_eq!(err, anchor_error(VaultError::InvalidAllocationAccounts));
+    }
+    assert_eq!(fixture.vault().allocated, 0);
+    assert_eq!(fixture.test.token_balance(&escrows[0]), Some(0));
+}
+
+// ===== LENDING =====
+
+impl Fixture {
+    /// Enable lending to a fresh reserve and pass its accounts from now on
+    fn enable_lending(&mut self) -> Reserve {
+        let reserve = self.test.create_solend_reserve(&self.base_mint, 6);
+        let collateral_vault = pda(&[b"collateral-vault", self.vault.as_ref()]);
+        let ix = ix(
+            accounts::EnableLending {
+                vault: self.vault,
+                reserve: reserve.reserve,
+                reserve_collateral_mint: reserve.collateral_mint,
+                collateral_vault,
+                manager: self.manager.pubkey(),
+                rent: sysvar::rent::ID,
+                token_program: spl_token::ID,
+                system_program: system_program::ID,
+            },
+            instruction::EnableLending {},
+        );
+        let manager = self.manager.insecure_clone();
+        self.test.process_instruction(ix, &[&manager]).unwrap();
+        self.lending = vec![
+            AccountMeta::new(collateral_vault, false),
+            AccountMeta::new(reserve.reserve, false),
+            AccountMeta::new(reserve.liquidity_supply, false),
+            AccountMeta::new(reserve.collateral_mint, false),
+            AccountMeta::new_readonly(reserve.lending_market, false),
+            AccountMeta::new_readonly(reserve.lending_market_authority, false),
+            AccountMeta::new_readonly(mock_solend::ID, false),
+        ];
+        reserve
+    }
+
+    fn supply_idle(&mut self, reserve: &Reserve, amount: u64) {
+        let vault = self.vault();
+        let ix = ix(
+            accounts::ManageIdleFunds {
+                vault: self.vault,
+                base_vault: vault.base_vault,
+                collateral_vault: vault.collateral_vault,
+                reserve: reserve.reserve,
+                reserve_liquidity_supply: reserve.liquidity_supply,
+                reserve_collateral_mint: reserve.collateral_mint,
+                lending_market: reserve.lending_market,
+                lending_market_authority: reserve.lending_market_authority,
+                lending_program: mock_solend::ID,
+                manager: self.manager.pubkey(),
+                token_program: spl_token::ID,
+                protocol_status: self.protocol_status,
+            },
+            instruction::SupplyIdle { amount },
+        );
+        let manager = self.manager.insecure_clone();
+        self.test.process_instruction(ix, &[&manager]).unwrap();
+    }
+}
+
+#[test]
+fn withdrawals_recall_lent_funds() {
+    let mut fixture = Fixture::new();
+    let depositor = fixture.depositor();
+    fixture.deposit(&depositor, 1_000_000).unwrap();
+    let reserve = fixture.enable_lending();
+    fixture.supply_idle(&reserve, 900_000);
+
+    // Lent funds can't be valued, or recalled, without the lending accounts
+    let lending = std::mem::take(&mut fixture.lending);
+    let err = fixture.withdraw(&depositor, 500_000, true).unwrap_err();
+    assert_eq!(err, anchor_error(VaultError::InvalidLendingAccounts));
+    fixture.lending = lending;
+
+    // Half the shares take 500_000 of the vault's 100_000 idle: the other
+    // 400_000 come back from lending, and no more
+    fixture.withdraw(&depositor, 500_000, true).unwrap();
+    let vault = fixture.vault();
+    assert_eq!(
+        fixture.test.token_balance(&depositor.base),
+        Some(DEPOSITOR_BALANCE - 1_000_000 + 500_000)
+    );
+    assert_eq!(fixture.test.token_balance(&vault.base_vault), Some(0));
+    assert_eq!(fixture.test.token_balance(&vault.collateral_vault), Some(500_000));
+    assert_eq!(vault.lent_principal, 500_000);
+    let recalled = fixture.test.events::<IdleFundsRecalled>();
+    assert_eq!(recalled.len(), 1);
+    assert_eq!((recalled[0].collateral_amount, recalled[0].liquidity_received), (400_000, 400_000));
+}
+
+#[test]
+fn nav_counts_lending_interest() {
+    let mut fixture = Fixture::new();
+    let depositor = fixture.depositor();
+    fixture.deposit(&depositor, 1_000_000).unwrap();
+    let reserve = fixture.enable_lending();
+    fixture.supply_idle(&reserve, 900_000);
+    assert_eq!(fixture.share_price().nav, 1_000_000);
+
+    fixture.test.pay_solend_interest(&reserve, 90_000);
+    assert_eq!(fixture.share_price().nav, 1_090_000);
+
+    // The interest is paid out on withdrawal
+    fixture.withdraw(&depositor, 1_000_000, true).unwrap();
+    let payout = redemption_amount(1_090_000, VIRTUAL_ASSETS, 1_000_000, 1_000_000).unwrap();
+    assert!(payout > 1_080_000);
+    assert_eq!(
+        fixture.test.token_balance(&depositor.base),
+        Some(DEPOSITOR_BALANCE - 1_000_000 + payout)
+    );
+}
@@ -0,0 +1,300 @@
+//! Account layout migrations through `vectai_common::migrate`, applied to
+//! `TraderConfig`, `TokenInfo` and `ProtocolStats`.
+
+use anchor_lang::{
+    error::ErrorCode,
+    solana_program::{rent::Rent, system_program},
+    Discriminator, InstructionData, ToAccountMetas,
+};
+use solana_sdk::{
+    instruction::Instruction,
+    native_token::LAMPORTS_PER_SOL,
+    program_error::ProgramError,
+    pubkey::Pubkey,
+    signature::{Keypair, Signer},
+};
+use vectai_common::{CommonError, Versioned};
+use vectai_math::condition::MAX_CONDITION_LEN;
+use vectai_program_test::{
+    anchor_error,
+    trader::{new_trader_config, trader_address},
+    ProgramTest,
+};
+use vectai_protocol::{ActivityKind, MintExposure, ProtocolStats, MAX_TRACKED_MINTS};
+use vectai_token::TokenInfo;
+use vectai_trader::{
+    DailyTradeCap, DynamicSlippage, FollowerConfig, TakeProfitLadder, TraderConfig, TradingSchedule, VolatilityFilter,
+};
+
+fn funded(test: &mut ProgramTest) -> Keypair {
+    let keypair = Keypair::new();
+    test.airdrop(&keypair.pubkey(), LAMPORTS_PER_SOL);
+    keypair
+}
+
+/// Bytes `TraderConfig` layouts v0 (no `version`) to v15 lack
+const TRADER_V0_MISSING: usize = 1 + TRADER_V1_MISSING; // version, ...
+const TRADER_V1_MISSING: usize = 8 + TRADER_V2_MISSING; // last_nonce, ...
+const TRADER_V2_MISSING: usize = TradingSchedule::LEN + TRADER_V3_MISSING; // schedule, ...
+const TRADER_V3_MISSING: usize = DynamicSlippage::LEN + TRADER_V4_MISSING; // dynamic_slippage, ...
+const TRADER_V4_MISSING: usize = VolatilityFilter::LEN + TRADER_V5_MISSING; // volatility, ...
+const TRADER_V5_MISSING: usize = TakeProfitLadder::LEN + TRADER_V6_MISSING; // take_profit, ...
+const TRADER_V6_MISSING: usize = DailyTradeCap::LEN + TRADER_V7_MISSING; // trade_cap, ...
+const TRADER_V7_MISSING: usize = 8 + TRADER_V8_MISSING; // max_jito_tip, ...
+const TRADER_V8_MISSING: usize = 8 + TRADER_V9_MISSING; // realized_pnl_usd, ...
+const TRADER_V9_MISSING: usize = 8 + TRADER_V10_MISSING; // profit_target_usd, ...
+const TRADER_V10_MISSING: usize = 8 + 8 + TRADER_V11_MISSING; // last_swap_slot, cooldown_slots, ...
+const TRADER_V11_MISSING: usize = 8 + TRADER_V12_MISSING; // max_price_age_slots, ...
+const TRADER_V12_MISSING: usize = 32 + TRADER_V13_MISSING; // owner_program, ...
+const TRADER_V13_MISSING: usize = 32 + TRADER_V14_MISSING; // listing, ...
+const TRADER_V14_MISSING: usize = 8 + 8 + TRADER_V15_MISSING; // marked_source_balance, marked_destination_balance, ...
+const TRADER_V15_MISSING: usize = 32 + 2; // price_aggregator, max_source_deviation_bps
+
+/// Bytes `TokenInfo` layouts v0 (no `version`) and v1 lack
+const TOKEN_V0_MISSING: usize = 1 + TOKEN_V1_MISSING; // version, ...
+const TOKEN_V1_MISSING: usize = 2 + 8; // burn_rate_bps, burned
+
+/// Bytes `ProtocolStats` layout v0 (no `version`) lacks with every volume
+/// entry taken
+const STATS_V0_MISSING: usize = 1 + 4 + MAX_TRACKED_MINTS * MintExposure::LEN; // version, exposures
+
+/// Rewrite `address` as an older layout: the same bytes without the last
+/// `missing` ones, rent-exempt at that size
+fn make_legacy(test: &mut ProgramTest, address: &Pubkey, missing: usize) {
+    let mut account = test.account(address).unwrap();
+    account.data.truncate(account.data.len() - missing);
+    account.lamports = Rent::default().minimum_balance(account.data.len());
+    test.set_account(*address, account);
+}
+
+fn migrate_trader_config(test: &mut ProgramTest, authority: &Keypair) -> Result<(), ProgramError> {
+    let ix = Instruction {
+        program_id: vectai_trader::ID,
+        accounts: vectai_trader::accounts::MigrateTraderConfig {
+            trader_config: trader_address(&authority.pubkey()),
+            authority: authority.pubkey(),
+            system_program: system_program::ID,
+        }
+        .to_account_metas(None),
+        data: vectai_trader::instruction::MigrateTraderConfig {}.data(),
+    };
+    test.process_instruction(ix, &[authority])
+}
+
+/// A full-length condition, so a legacy account ends exactly where the
+/// fields its layout lacks now start
+fn seed_trader(test: &mut ProgramTest, authority: &Keypair, version: u8) -> Pubkey {
+    let mut config = new_trader_config(authority.pubkey(), 40_000, 1_000_000, 200);
+    config.condition = vec![0; MAX_CONDITION_LEN];
+    config.version = version;
+    let address = trader_address(&authority.pubkey());
+    test.set_anchor_account(address, &config);
+    address
+}
+
+#[test]
+fn migrate_trader_config_grows_legacy_account() {
+    let mut test = ProgramTest::new();
+    let authority = funded(&mut test);
+    let address = seed_trader(&mut test, &authority, 0);
+    make_legacy(&mut test, &address, TRADER_V0_MISSING);
+    assert_eq!(test.account(&address).unwrap().data.len(), TraderConfig::SPACE - TRADER_V0_MISSING);
+    // Unreadable by the current build until migrated
+    assert!(test.anchor_account::<TraderConfig>(&address).is_none());
+
+    let balance = test.lamports(&authority.pubkey());
+    migrate_trader_config(&mut test, &authority).unwrap();
+
+    let account = test.account(&address).unwrap();
+    assert_eq!(account.data.len(), TraderConfig::SPACE);
+    let rent = Rent::default().minimum_balance(TraderConfig::SPACE);
+    assert_eq!(account.lamports, rent);
+    assert_eq!(
+        test.lamports(&authority.pubkey()),
+        balance - (rent - Rent::default().minimum_balance(TraderConfig::SPACE - TRADER_V0_MISSING))
+    );
+    let config: TraderConfig = test.anchor_account(&address).unwrap();
+    assert_eq!(config.version, TraderConfig::VERSION);
+    assert_eq!(config.authority, authority.pubkey());
+    assert_eq!(config.condition.len(), MAX_CONDITION_LEN);
+    assert_eq!(config.last_nonce, 0);
+    let log = format!("migrated from v0 to v{}", TraderConfig::VERSION);
+    assert!(test.logs().iter().any(|line| line.contains(&log)));
+
+    // Current accounts are left as they are
+    migrate_trader_config(&mut test, &authority).unwrap();
+    assert_eq!(test.account(&address).unwrap(), account);
+}
+
+/// Whether the fields a migration appended hold their defaults
+type HasDefaults = fn(&TraderConfig) -> bool;
+
+#[test]
+fn migrate_trader_config_appends_each_layouts_fields() {
+    // Every layout since `version` was added, the bytes it lacks and the
+    // defaults of the fields it gains
+    let layouts: [(u8, usize, HasDefaults); 15] = [
+        (1, TRADER_V1_MISSING, |config| config.last_nonce == 0),
+        (2, TRADER_V2_MISSING, |config| config.schedule.is_unrestricted()),
+        (3, TRADER_V3_MISSING, |config| !config.dynamic_slippage.is_enabled()),
+        (4, TRADER_V4_MISSING, |config| !config.volatility.is_enabled()),
+        (5, TRADER_V5_MISSING, |config| config.take_profit.rungs().is_empty()),
+        (6, TRADER_V6_MISSING, |config| config.trade_cap == DailyTradeCap::default()),
+        (7, TRADER_V7_MISSING, |config| config.max_jito_tip == 0),
+        (8, TRADER_V8_MISSING, |config| config.realized_pnl_usd == 0),
+        (9, TRADER_V9_MISSING, |config| config.profit_target_usd == 0),
+        (10, TRADER_V10_MISSING, |config| (config.last_swap_slot, config.cooldown_slots) == (0, 0)),
+        (11, TRADER_V11_MISSING, |config| config.max_price_age_slots == 0),
+        (12, TRADER_V12_MISSING, |config| !config.is_program_owned()),
+        (13, TRADER_V13_MISSING, |config| config.listing == Pubkey::default()),
+        (14, TRADER_V14_MISSING, |config| {
+            (config.marked_source_balance, config.marked_destination_balance) == (0, 0)
+        }),
+        (15, TRADER_V15_MISSING, |config| {
+            (config.price_aggregator, config.max_source_deviation_bps) == (Pubkey::default(), 0)
+        }),
+    ];
+    // A new layout needs its row
+    assert_eq!(
+        layouts.iter().map(|(version, ..)| *version).collect::<Vec<_>>(),
+        (1..TraderConfig::VERSION).collect::<Vec<_>>()
+    );
+
+    for (version, missing, has_defaults) in layouts {
+        let mut test = ProgramTest::new();
+        let authority = funded(&mut test);
+        let address = seed_trader(&mut test, &authority, version);
+        make_legacy(&mut test, &address, missing);
+        assert!(test.anchor_account::<TraderConfig>(&address).is_none(), "v{version}");
+
+        migrate_trader_config(&mut test, &authority).unwrap();
+
+        assert_eq!(test.account(&address).unwrap().data.len(), TraderConfig::SPACE, "v{version}");
+        let config: TraderConfig = test.anchor_account(&address).unwrap();
+        assert_eq!(config.version, TraderConfig::VERSION);
+        assert!(has_defaults(&config), "v{version}");
+        let log = format!("migrated from v{version} to v{}", TraderConfig::VERSION);
+        assert!(test.logs().iter().any(|line| line.contains(&log)), "v{version}");
+    }
+}
+
+#[test]
+fn migrate_rejects_other_types_and_newer_layouts() {
+    let mut test = ProgramTest::new();
+    let authority = funded(&mut test);
+
+    let address = seed_trader(&mut test, &authority, TraderConfig::VERSION + 1);
+    assert_eq!(
+        migrate_trader_config(&mut test, &authority).unwrap_err(),
+        anchor_error(CommonError::UnsupportedVersion)
+    );
+
+    // Same program, another account type at the address
+    let mut account = test.account(&address).unwrap();
+    account.data[..8].copy_from_slice(&FollowerConfig::DISCRIMINATOR);
+    test.set_account(address, account.clone());
+    assert_eq!(
+        migrate_trader_config(&mut test, &authority).unwrap_err(),
+        anchor_error(ErrorCode::AccountDiscriminatorMismatch)
+    );
+
+    account.owner = vectai_token::ID;
+    test.set_account(address, account);
+    assert_eq!(
+        migrate_trader_config(&mut test, &authority).unwrap_err(),
+        anchor_error(ErrorCode::AccountOwnedByWrongProgram)
+    );
+}
+
+/// Migrate a `TokenInfo` seeded as layout `version`, which lacks the last
+/// `missing` bytes
+fn migrate_legacy_token_info(version: u8, missing: usize) -> (ProgramTest, TokenInfo) {
+    let mut test = ProgramTest::new();
+    let mint = Pubkey::new_unique();
+    let address = Pubkey::find_program_address(&[b"token-info", mint.as_ref()], &vectai_token::ID).0;
+    let token_info = TokenInfo {
+        mint_authority: Pubkey::new_unique(),
+        mint,
+        max_supply: 1_000_000,
+        minted: 0,
+        decimals: 6,
+        is_paused: false,
+        compliance_authority: Pubkey::default(),
+        clawback_enabled: false,
+        version,
+        burn_rate_bps: 0,
+        burned: 0,
+    };
+    test.set_anchor_account(address, &token_info);
+    make_legacy(&mut test, &address, missing);
+    assert!(test.anchor_account::<TokenInfo>(&address).is_none());
+
+    let payer = funded(&mut test);
+    let ix = Instruction {
+        program_id: vectai_token::ID,
+        accounts: vectai_token::accounts::MigrateTokenInfo {
+            mint,
+            token_info: address,
+            payer: payer.pubkey(),
+            system_program: system_program::ID,
+        }
+        .to_account_metas(None),
+        data: vectai_token::instruction::MigrateTokenInfo {}.data(),
+    };
+    test.process_instruction(ix, &[&payer]).unwrap();
+
+    assert_eq!(test.account(&address).unwrap().data.len(), TokenInfo::SPACE);
+    let migrated: TokenInfo = test.anchor_account(&address).unwrap();
+    assert_eq!(migrated.version, TokenInfo::VERSION);
+    assert_eq!((migrated.mint, migrated.max_supply), (mint, 1_000_000));
+    (test, migrated)
+}
+
+#[test]
+fn migrate_token_info_is_permissionless() {
+    migrate_legacy_token_info(0, TOKEN_V0_MISSING);
+}
+
+#[test]
+fn migrate_token_info_appends_burn_rate_to_v1() {
+    let (test, migrated) = migrate_legacy_token_info(1, TOKEN_V1_MISSING);
+    assert_eq!((migrated.burn_rate_bps, migrated.burned), (0, 0));
+    assert!(test.logs().iter().any(|log| log.contains("migrated from v1 to v2")));
+}
+
+#[test]
+fn migrate_protocol_stats_appends_exposures_to_v0() {
+    let mut test = ProgramTest::new();
+    let address = test.set_protocol_stats();
+    let mut stats: ProtocolStats = test.anchor_account(&address).unwrap();
+    for _ in 0..MAX_TRACKED_MINTS {
+        stats.record(ActivityKind::Swap, Pubkey::new_unique(), 1_000);
+    }
+    stats.version = 0;
+    test.set_anchor_account(address, &stats);
+    let mut account = test.account(&address).unwrap();
+    account.data.resize(ProtocolStats::SPACE, 0);
+    test.set_account(address, account);
+    make_legacy(&mut test, &address, STATS_V0_MISSING);
+    assert!(test.anchor_account::<ProtocolStats>(&address).is_none());
+
+    let payer = funded(&mut test);
+    let ix = Instruction {
+        program_id: vectai_protocol::ID,
+        accounts: vectai_protocol::accounts::MigrateProtocolStats {
+            protocol_stats: address,
+            payer: payer.pubkey(),
+            system_program: system_program::ID,
+        }
+        .to_account_metas(None),
+        data: vectai_protocol::instruction::MigrateProtocolStats {}.data(),
+    };
+    test.process_instruction(ix, &[&payer]).unwrap();
+
+    assert_eq!(test.account(&address).unwrap().data.len(), ProtocolStats::SPACE);
+    let migrated: ProtocolStats = test.anchor_account(&address).unwrap();
+    assert_eq!(migrated.version, ProtocolStats::VERSION);
+    assert_eq!((migrated.swaps, migrated.volumes), (stats.swaps, stats.volumes));
+    assert!(migrated.exposures.is_empty());
+    assert!(test.logs().iter().any(|log| log.contains("migrated from v0 to v1")));
+}
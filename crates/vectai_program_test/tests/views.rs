@@ -0,0 +1,167 @@
+//! View instructions that hand account state to CPI callers through
+//! return data.
+
+use anchor_lang::{error::ErrorCode, AnchorDeserialize, InstructionData, ToAccountMetas};
+use solana_sdk::{instruction::Instruction, pubkey::Pubkey};
+use vectai_program_test::{anchor_error, mock_amm, trader::new_trader_config, ProgramTest};
+use vectai_protocol::FeeConfig;
+use vectai_token::TokenInfo;
+use vectai_trader::TraderConfig;
+
+/// Run `ix` and decode its return data as `T`, checking the program set it
+fn view<T: AnchorDeserialize>(test: &mut ProgramTest, ix: Instruction) -> T {
+    let program_id = ix.program_id;
+    test.process_instruction(ix, &[]).unwrap();
+    let (program, data) = test.return_data().expect("return data");
+    assert_eq!(*program, program_id);
+    T::deserialize(&mut data.as_slice()).unwrap()
+}
+
+fn get_trader_config(trader_config: Pubkey) -> Instruction {
+    Instruction {
+        program_id: vectai_trader::ID,
+        accounts: vectai_trader::accounts::GetTraderConfig { trader_config }.to_account_metas(None),
+        data: vectai_trader::instruction::GetTraderConfig {}.data(),
+    }
+}
+
+#[test]
+fn get_trader_config_returns_account_state() {
+    let mut test = ProgramTest::new();
+    let mut config = new_trader_config(Pubkey::new_unique(), 40_000, 1_000_000, 200);
+    config.total_swaps = 7;
+    config.condition = vec![1, 2, 3];
+    let address = test.set_trader(&config);
+
+    let returned: TraderConfig = view(&mut test, get_trader_config(address));
+    assert_eq!(returned.authority, config.authority);
+    assert_eq!(returned.price_threshold, 40_000);
+    assert_eq!(returned.total_swaps, 7);
+    assert_eq!(returned.condition, vec![1, 2, 3]);
+    assert_eq!(returned.version, config.version);
+
+    // Only the authority's canonical PDA
+    let copy = Pubkey::new_unique();
+    test.set_anchor_account(copy, &config);
+    assert_eq!(
+        test.process_instruction(get_trader_config(copy), &[]).unwrap_err(),
+        anchor_error(ErrorCode::ConstraintSeeds)
+    );
+}
+
+#[test]
+fn get_token_info_returns_account_state() {
+    let mut test = ProgramTest::new();
+    let mint = Pubkey::new_unique();
+    let token_info = Pubkey::find_program_address(&[b"token-info", mint.as_ref()], &vectai_token::ID).0;
+    let state = TokenInfo {
+        mint_authority: Pubkey::new_unique(),
+        mint,
+        max_supply: 1_000_000,
+        minted: 250_000,
+        decimals: 6,
+        is_paused: true,
+        compliance_authority: Pubkey::default(),
+        clawback_enabled: false,
+        version: 2,
+        burn_rate_bps: 250,
+        burned: 0,
+    };
+    test.set_anchor_account(token_info, &state);
+
+    let ix = Instruction {
+        program_id: vectai_token::ID,
+        accounts: vectai_token::accounts::GetTokenInfo { token_info }.to_account_metas(None),
+        data: vectai_token::instruction::GetTokenInfo {}.data(),
+    };
+    let returned: TokenInfo = view(&mut test, ix);
+    assert_eq!((returned.mint, returned.mint_authority), (mint, state.mint_authority));
+    assert_eq!((returned.max_supply, returned.minted), (1_000_000, 250_000));
+    assert!(returned.is_paused);
+}
+
+#[test]
+fn get_pool_entry_returns_whitelisted_pool() {
+    let mut test = ProgramTest::new();
+    let (usdc, wsol) = (Pubkey::new_unique(), Pubkey::new_unique());
+    let mint_authority = Pubkey::new_unique();
+    test.create_mint(usdc, &mint_authority, 6);
+    test.create_mint(wsol, &mint_authority, 9);
+    let pool = test.create_whitelisted_pool(&usdc, &wsol, 5_000_000, 9_000_000);
+    let ix = |pool_coin_token_account, pool_pc_token_account| Instruction {
+        program_id: raydium_swapper::ID,
+        accounts: raydium_swapper::accounts::GetPoolEntry {
+            pool_coin_token_account,
+            pool_pc_token_account,
+        }
+        .to_account_metas(None),
+        data: raydium_swapper::instruction::GetPoolEntry {}.data(),
+    };
+
+    let entry: raydium_swapper::PoolEntry = view(&mut test, ix(pool.pool_coin_token_account, pool.pool_pc_token_account));
+    assert_eq!((entry.amm, entry.amm_authority), (pool.amm, pool.amm_authority));
+    assert_eq!(entry.serum_market, pool.serum_market);
+    assert_eq!((entry.coin_mint, entry.pc_mint), (usdc, wsol));
+    assert_eq!((entry.coin_reserve, entry.pc_reserve), (5_000_000, 9_000_000));
+
+    let other = Pubkey::new_unique();
+    test.create_token_account(other, &usdc, &Pubkey::new_unique(), 0);
+    assert_eq!(
+        test.process_instruction(ix(other, pool.pool_pc_token_account), &[]).unwrap_err(),
+        anchor_error(raydium_swapper::SwapError::InvalidPool)
+    );
+}
+
+#[test]
+fn get_quote_prices_against_live_reserves() {
+    let mut test = ProgramTest::new();
+    let (usdc, wsol) = (Pubkey::new_unique(), Pubkey::new_unique());
+    let mint_authority = Pubkey::new_unique();
+    test.create_mint(usdc, &mint_authority, 6);
+    test.create_mint(wsol, &mint_authority, 9);
+    let pool = test.create_whitelisted_pool(&usdc, &wsol, 5_000_000, 9_000_000);
+    let protocol_status = test.set_protocol_status(
+        false,
+        FeeConfig {
+            swap_fee_bps: 10,
+            ..FeeConfig::INITIAL
+        },
+    );
+    let ix = |input_mint, output_mint, amount_in| Instruction {
+        program_id: raydium_swapper::ID,
+        accounts: raydium_swapper::accounts::GetQuote {
+            pool_coin_token_account: pool.pool_coin_token_account,
+            pool_pc_token_account: pool.pool_pc_token_account,
+            protocol_status,
+        }
+        .to_account_metas(None),
+        data: raydium_swapper::instruction::GetQuote {
+            pair: raydium_swapper::QuotePair { input_mint, output_mint },
+            amount_in,
+        }
+        .data(),
+    };
+
+    // 1_000 protocol fee, then 0.25% of the rest to the pool
+    let quote: raydium_swapper::SwapQuote = view(&mut test, ix(usdc, wsol, 1_000_000));
+    assert_eq!(quote.fee, 1_000 + 2_497);
+    let net_in = 1_000_000 - quote.fee;
+    assert_eq!(quote.expected_out, mock_amm::quote(net_in, 5_000_000, 9_000_000));
+    // net_in / (reserve_in + net_in): about a fifth of the reserve costs a sixth
+    assert_eq!(quote.price_impact_bps, 1_661);
+
+    let reverse: raydium_swapper::SwapQuote = view(&mut test, ix(wsol, usdc, 1_000_000));
+    assert_eq!(reverse.expected_out, mock_amm::quote(1_000_000 - reverse.fee, 9_000_000, 5_000_000));
+    assert!(reverse.price_impact_bps < quote.price_impact_bps);
+
+    for (input_mint, output_mint) in [(usdc, usdc), (usdc, Pubkey::new_unique())] {
+        assert_eq!(
+            test.process_instruction(ix(input_mint, output_mint, 1_000_000), &[]).unwrap_err(),
+            anchor_error(raydium_swapper::SwapError::InvalidTokenPair)
+        );
+    }
+    assert_eq!(
+        test.process_instruction(ix(usdc, wsol, 0), &[]).unwrap_err(),
+        anchor_error(raydium_swapper::SwapError::InvalidAmount)
+    );
+}
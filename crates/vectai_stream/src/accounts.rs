@@ -0,0 +1,130 @@
+use anchor_lang::{AccountDeserialize, Discriminator};
+use solana_sdk::pubkey::Pubkey;
+use vectai_indexer::DecodeError;
+
+/// Declares a per-program account enum and its discriminator lookup.
+/// Accounts are boxed: they run to hundreds of bytes and cross a channel.
+macro_rules! program_accounts {
+    ($(#[$doc:meta])* $name:ident, $program:ident { $($account:ident),* $(,)? }) => {
+        $(#[$doc])*
+        pub enum $name {
+            $($account(Box<$program::$account>),)*
+        }
+
+        impl $name {
+            fn try_decode(data: &[u8]) -> Result<Self, DecodeError> {
+                let discriminator: [u8; 8] = data.get(..8).ok_or(DecodeError::TooShort)?.try_into().unwrap();
+                $(
+                    if discriminator == <$program::$account as Discriminator>::DISCRIMINATOR {
+                        return $program::$account::try_deserialize(&mut &data[..])
+                            .map(|account| $name::$account(Box::new(account)))
+                            .map_err(|_| DecodeError::Malformed(stringify!($account)));
+                    }
+                )*
+                Err(DecodeError::UnknownDiscriminator(discriminator))
+            }
+
+            /// Account struct name, e.g. `"TraderConfig"`
+            pub fn name(&self) -> &'static str {
+                match self {
+                    $($name::$account(_) => stringify!($account),)*
+                }
+            }
+        }
+    };
+}
+
+program_accounts!(
+    /// Strategies, copy-trading followers, hedges and the marketplace
+    TraderAccount, vectai_trader {
+        TraderConfig,
+        FollowerConfig,
+        HedgeConfig,
+        StrategyListing,
+        Subscription,
+    }
+);
+
+program_accounts!(
+    /// Pause state, protocol-wide configuration and role holders
+    ProtocolAccount, vectai_protocol {
+        ProtocolStatus,
+        NetworkConfig,
+        ProtocolStats,
+        LookupTableRegistry,
+        RoleRegistry,
+    }
+);
+
+program_accounts!(
+    /// Conditional orders and the keeper registry
+    OrderbookAccount, vectai_orderbook {
+        ConditionalOrder,
+        KeeperRegistry,
+        Keeper,
+    }
+);
+
+program_accounts!(
+    /// Pooled vaults, their withdrawal queues, depositor positions and
+    /// strategy allocations
+    VaultAccount, vectai_vault {
+        Vault,
+        WithdrawalQueue,
+        WithdrawalRequest,
+        DepositorPosition,
+        VaultAllocations,
+    }
+);
+
+program_accounts!(
+    /// Price monitors, multi-feed aggregators, staleness policies and
+    /// trusted Pyth Lazer signers
+    OracleAccount, vectai_oracle {
+        PriceMonitor,
+        PriceAggregator,
+        PricePolicy,
+        LazerSigners,
+    }
+);
+
+/// Any decoded VECT.AI account, grouped by owning program
+pub enum VectaiAccount {
+    Trader(TraderAccount),
+    Protocol(ProtocolAccount),
+    Orderbook(OrderbookAccount),
+    Vault(VaultAccount),
+    Oracle(OracleAccount),
+}
+
+impl VectaiAccount {
+    /// Decode the data of an account owned by `owner`, checking the owner
+    /// and the discriminator before deserializing
+    pub fn try_decode(owner: &Pubkey, data: &[u8]) -> Result<Self, DecodeError> {
+        let owner = *owner;
+        if owner == vectai_trader::ID {
+            TraderAccount::try_decode(data).map(VectaiAccount::Trader)
+        } else if owner == vectai_protocol::ID {
+            ProtocolAccount::try_decode(data).map(VectaiAccount::Protocol)
+        } else if owner == vectai_orderbook::ID {
+            OrderbookAccount::try_decode(data).map(VectaiAccount::Orderbook)
+        } else if owner == vectai_vault::ID {
+            VaultAccount::try_decode(data).map(VectaiAccount::Vault)
+        } else if owner == vectai_oracle::ID {
+            OracleAccount::try_decode(data).map(VectaiAccount::Oracle)
+        } else {
+            Err(DecodeError::UnknownProgram(owner))
+        }
+    }
+
+    /// Account struct name, e.g. `"TraderConfig"`
+    pub fn name(&self) -> &'static str {
+        match self {
+            VectaiAccount::Trader(account) => account.name(),
+            VectaiAccount::Protocol(account) => account.name(),
+            VectaiAccount::Orderbook(account) => account.name(),
+            VectaiAccount::Vault(account) => account.name(),
+            VectaiAccount::Oracle(account) => account.name(),
+        }
+    }
+}
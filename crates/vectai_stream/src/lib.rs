@@ -0,0 +1,308 @@
+//! Real-time VECT.AI events and account updates over a channel.
+//!
+//! [`subscribe`] opens a `logsSubscribe` and a `programSubscribe` websocket
+//! subscription per program, decodes what they deliver — events with
+//! `vectai_indexer`, accounts with [`VectaiAccount`] — and republishes it
+//! as [`StreamMessage`]s on one [`TradeStream`], a plain channel receiver
+//! bots and UIs iterate or poll.
+//!
+//! The websocket is only one producer: [`channel`] hands out a
+//! [`Publisher`] that a Geyser plugin (or any other feed of raw account
+//! writes and transaction logs) uses to push into the same stream, with
+//! the same decoding.
+
+use std::{
+    fmt,
+    str::FromStr,
+    sync::mpsc::{self, Receiver, Sender},
+    thread::{self, JoinHandle},
+};
+
+use solana_account_decoder::UiAccountEncoding;
+use solana_pubsub_client::pubsub_client::{
+    PubsubClient, PubsubClientError, PubsubLogsClientSubscription, PubsubProgramClientSubscription,
+};
+use solana_rpc_client_api::config::{
+    RpcAccountInfoConfig, RpcProgramAccountsConfig, RpcTransactionLogsConfig, RpcTransactionLogsFilter,
+};
+use solana_sdk::{account::Account, commitment_config::CommitmentConfig, pubkey::Pubkey};
+use vectai_indexer::{parse_logs, DecodeError, TransactionEvents};
+
+mod accounts;
+pub use accounts::*;
+
+/// Programs [`StreamConfig::new`] subscribes to
+pub const PROGRAMS: [Pubkey; 6] = [
+    vectai_trader::ID,
+    raydium_swapper::ID,
+    vectai_protocol::ID,
+    vectai_orderbook::ID,
+    vectai_vault::ID,
+    vectai_oracle::ID,
+];
+
+/// One decoded item on the stream
+pub enum StreamMessage {
+    /// Events of one successful transaction
+    Events(TransactionEvents),
+    /// New state of a program account
+    Account(AccountUpdate),
+}
+
+/// A program account as written at `slot`
+pub struct AccountUpdate {
+    pub pubkey: Pubkey,
+    pub slot: u64,
+    pub lamports: u64,
+    pub account: VectaiAccount,
+}
+
+/// Why an update was not published
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum PublishError {
+    /// The account data is not a VECT.AI account this crate decodes
+    Decode(DecodeError),
+    /// The [`TradeStream`] was dropped; the producer should stop
+    Closed,
+}
+
+impl fmt::Display for PublishError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            PublishError::Decode(e) => write!(f, "decode: {}", e),
+            PublishError::Closed => write!(f, "stream closed"),
+        }
+    }
+}
+
+impl std::error::Error for PublishError {}
+
+/// Producer side of a [`TradeStream`]; cheap to clone, one per feed
+#[derive(Clone)]
+pub struct Publisher {
+    sender: Sender<StreamMessage>,
+}
+
+impl Publisher {
+    /// Decode the events in one successful transaction's logs and publish
+    /// them; transactions without VECT.AI events publish nothing
+    pub fn publish_logs<S: AsRef<str>>(&self, signature: String, slot: u64, logs: &[S]) -> Result<(), PublishError> {
+        let events = parse_logs(logs);
+        if events.is_empty() {
+            return Ok(());
+        }
+        self.send(StreamMessage::Events(TransactionEvents { signature, slot, events }))
+    }
+
+    /// Decode and publish a write to the account `pubkey`, owned by `owner`
+    pub fn publish_account(
+        &self,
+        pubkey: Pubkey,
+        owner: &Pubkey,
+        lamports: u64,
+        data: &[u8],
+        slot: u64,
+    ) -> Result<(), PublishError> {
+        let account = VectaiAccount::try_decode(owner, data).map_err(PublishError::Decode)?;
+        self.send(StreamMessage::Account(AccountUpdate {
+            pubkey,
+            slot,
+            lamports,
+            account,
+        }))
+    }
+
+    fn send(&self, message: StreamMessage) -> Result<(), PublishError> {
+        self.sender.send(message).map_err(|_| PublishError::Closed)
+    }
+}
+
+/// A stream fed only through the returned [`Publisher`]
+pub fn channel() -> (Publisher, TradeStream) {
+    let (sender, receiver) = mpsc::channel();
+    let stream = TradeStream {
+        receiver,
+        subscriptions: Vec::new(),
+        threads: Vec::new(),
+    };
+    (Publisher { sender }, stream)
+}
+
+/// What [`subscribe`] listens to
+#[derive(Clone, Debug)]
+pub struct StreamConfig {
+    ws_url: String,
+    programs: Vec<Pubkey>,
+    commitment: CommitmentConfig,
+    events: bool,
+    accounts: bool,
+}
+
+impl StreamConfig {
+    /// Events and account updates of every program in [`PROGRAMS`], at
+    /// confirmed commitment
+    pub fn new(ws_url: &str) -> Self {
+        StreamConfig {
+            ws_url: ws_url.to_string(),
+            programs: PROGRAMS.to_vec(),
+            commitment: CommitmentConfig::confirmed(),
+            events: true,
+            accounts: true,
+        }
+    }
+
+    pub fn programs(mut self, programs: impl IntoIterator<Item = Pubkey>) -> Self {
+        self.programs = programs.into_iter().collect();
+        self
+    }
+
+    pub fn commitment(mut self, commitment: CommitmentConfig) -> Self {
+        self.commitment = commitment;
+        self
+    }
+
+    pub fn without_events(mut self) -> Self {
+        self.events = false;
+        self
+    }
+
+    pub fn without_accounts(mut self) -> Self {
+        self.accounts = false;
+        self
+    }
+}
+
+enum Subscription {
+    Logs(PubsubLogsClientSubscription),
+    Program(PubsubProgramClientSubscription),
+}
+
+/// Consumer side: iterating blocks until the next message and ends once
+/// every producer is gone. [`receiver`](Self::receiver) gives the
+/// `try_recv`/`recv_timeout` forms for polling loops.
+pub struct TradeStream {
+    receiver: Receiver<StreamMessage>,
+    subscriptions: Vec<Subscription>,
+    threads: Vec<JoinHandle<()>>,
+}
+
+/// Subscribe to `config`'s programs over the websocket. Events are
+/// attributed to the program subscribed to, so a transaction touching
+/// several of them is published once per emitting program; account writes
+/// that do not decode are skipped.
+pub fn subscribe(config: &StreamConfig) -> Result<TradeStream, Box<PubsubClientError>> {
+    let (publisher, mut stream) = channel();
+    for program_id in &config.programs {
+        if config.events {
+            let (subscription, responses) = PubsubClient::logs_subscribe(
+                &config.ws_url,
+                RpcTransactionLogsFilter::Mentions(vec![program_id.to_string()]),
+                RpcTransactionLogsConfig {
+                    commitment: Some(config.commitment),
+                },
+            )
+            .map_err(Box::new)?;
+            stream.subscriptions.push(Subscription::Logs(subscription));
+            let (publisher, program_id) = (publisher.clone(), *program_id);
+            stream.threads.push(thread::spawn(move || {
+                for response in responses {
+                    // Failed transactions' events were rolled back
+                    if response.value.err.is_some() {
+                        continue;
+                    }
+                    let mut events = parse_logs(&response.value.logs);
+                    events.retain(|decoded| decoded.program_id == program_id);
+                    if events.is_empty() {
+                        continue;
+                    }
+                    let events = TransactionEvents {
+                        signature: response.value.signature,
+                        slot: response.context.slot,
+                        events,
+                    };
+                    if publisher.send(StreamMessage::Events(events)).is_err() {
+                        break;
+                    }
+                }
+            }));
+        }
+
+        if config.accounts {
+            let (subscription, responses) = PubsubClient::program_subscribe(
+                &config.ws_url,
+                program_id,
+                Some(RpcProgramAccountsConfig {
+                    account_config: RpcAccountInfoConfig {
+                        encoding: Some(UiAccountEncoding::Base64),
+                        commitment: Some(config.commitment),
+                        ..RpcAccountInfoConfig::default()
+                    },
+                    ..RpcProgramAccountsConfig::default()
+                }),
+            )
+            .map_err(Box::new)?;
+            stream.subscriptions.push(Subscription::Program(subscription));
+            let publisher = publisher.clone();
+            stream.threads.push(thread::spawn(move || {
+                for response in responses {
+                    let keyed = response.value;
+                    let (Ok(pubkey), Some(account)) = (Pubkey::from_str(&keyed.pubkey), keyed.account.decode::<Account>())
+                    else {
+                        continue;
+                    };
+                    let published = publisher.publish_account(
+                        pubkey,
+                        &account.owner,
+                        account.lamports,
+                        &account.data,
+                        response.context.slot,
+                    );
+                    if published == Err(PublishError::Closed) {
+                        break;
+                    }
+                }
+            }));
+        }
+    }
+    Ok(stream)
+}
+
+impl TradeStream {
+    pub fn receiver(&self) -> &Receiver<StreamMessage> {
+        &self.receiver
+    }
+
+    /// Unsubscribe and close every websocket; messages already received
+    /// stay readable
+    pub fn close(&mut self) {
+        for subscription in self.subscriptions.drain(..) {
+            match subscription {
+                Subscription::Logs(mut subscription) => {
+                    let _ = subscription.send_unsubscribe();
+                    let _ = subscription.shutdown();
+                }
+                Subscription::Program(mut subscription) => {
+                    let _ = subscription.send_unsubscribe();
+                    let _ = subscription.shutdown();
+                }
+            }
+        }
+        for thread in self.threads.drain(..) {
+            let _ = thread.join();
+        }
+    }
+}
+
+impl Iterator for TradeStream {
+    type Item = StreamMessage;
+
+    fn next(&mut self) -> Option<StreamMessage> {
+        self.receiver.recv().ok()
+    }
+}
+
+impl Drop for TradeStream {
+    fn drop(&mut self) {
+        self.close();
+    }
+}
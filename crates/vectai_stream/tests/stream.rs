@@ -0,0 +1,106 @@
+//! Publishing raw logs and account writes into a stream, as the websocket
+//! threads and a Geyser feed do.
+
+use anchor_lang::{AccountSerialize, InstructionData, ToAccountMetas};
+use solana_sdk::{
+    instruction::Instruction,
+    native_token::LAMPORTS_PER_SOL,
+    pubkey::Pubkey,
+    signature::{Keypair, Signer},
+};
+use vectai_indexer::{DecodeError, TraderEvent, VectaiEvent};
+use vectai_program_test::{trader::new_trader_config, ProgramTest};
+use vectai_stream::{channel, PublishError, StreamMessage, TraderAccount, VectaiAccount};
+
+const SWAP_AMOUNT: u64 = 1_000_000;
+
+/// Logs of one successful `execute_trade` of `SWAP_AMOUNT`
+fn execute_trade_logs() -> Vec<String> {
+    let mut test = ProgramTest::new();
+    let network = test.create_trading_network(1_000_000_000_000);
+    let authority = Keypair::new();
+    test.airdrop(&authority.pubkey(), LAMPORTS_PER_SOL);
+    test.set_trader(&new_trader_config(authority.pubkey(), 40_000, SWAP_AMOUNT, 200));
+    let (source, destination) = (Pubkey::new_unique(), Pubkey::new_unique());
+    test.create_token_account(source, &network.usdc, &authority.pubkey(), SWAP_AMOUNT);
+    test.create_token_account(destination, &network.wsol, &authority.pubkey(), 0);
+    let ix = Instruction {
+        program_id: vectai_trader::ID,
+        accounts: network
+            .execute_trade_accounts(&authority.pubkey(), source, destination)
+            .to_account_metas(None),
+        data: vectai_trader::instruction::ExecuteTrade { amount: SWAP_AMOUNT, dry_run: false, nonce: None }.data(),
+    };
+    test.process_instruction(ix, &[&authority]).unwrap();
+    test.logs().to_vec()
+}
+
+fn trader_config_data(authority: Pubkey) -> Vec<u8> {
+    let mut data = Vec::new();
+    new_trader_config(authority, 40_000, SWAP_AMOUNT, 200).try_serialize(&mut data).unwrap();
+    data
+}
+
+#[test]
+fn publish_logs_streams_decoded_events() {
+    let (publisher, mut stream) = channel();
+    publisher.publish_logs("sig".to_string(), 7, &execute_trade_logs()).unwrap();
+    // Nothing to publish without VECT.AI events
+    publisher.publish_logs("other".to_string(), 8, &["Program log: hello"]).unwrap();
+    drop(publisher);
+
+    let Some(StreamMessage::Events(transaction)) = stream.next() else {
+        panic!("expected events");
+    };
+    assert_eq!((transaction.signature.as_str(), transaction.slot), ("sig", 7));
+    assert!(transaction.events.iter().any(|decoded| matches!(
+        &decoded.event,
+        VectaiEvent::Trader(TraderEvent::TradeExecuted(event)) if event.amount_in == SWAP_AMOUNT
+    )));
+    // Every producer is gone
+    assert!(stream.next().is_none());
+}
+
+#[test]
+fn publish_account_checks_owner_and_discriminator() {
+    let (publisher, stream) = channel();
+    let (address, authority) = (Pubkey::new_unique(), Pubkey::new_unique());
+    let data = trader_config_data(authority);
+
+    publisher.publish_account(address, &vectai_trader::ID, 1_000, &data, 9).unwrap();
+    let Ok(StreamMessage::Account(update)) = stream.receiver().try_recv() else {
+        panic!("expected an account update");
+    };
+    assert_eq!((update.pubkey, update.slot, update.lamports), (address, 9, 1_000));
+    assert_eq!(update.account.name(), "TraderConfig");
+    let VectaiAccount::Trader(TraderAccount::TraderConfig(config)) = update.account else {
+        panic!("expected a TraderConfig");
+    };
+    assert_eq!(config.authority, authority);
+
+    // The same bytes under another owner are a lookalike, not a strategy
+    let foreign = Pubkey::new_unique();
+    assert_eq!(
+        publisher.publish_account(address, &foreign, 1_000, &data, 9),
+        Err(PublishError::Decode(DecodeError::UnknownProgram(foreign)))
+    );
+    assert!(matches!(
+        publisher.publish_account(address, &vectai_protocol::ID, 1_000, &data, 9),
+        Err(PublishError::Decode(DecodeError::UnknownDiscriminator(_)))
+    ));
+    assert_eq!(
+        publisher.publish_account(address, &vectai_trader::ID, 1_000, &data[..40], 9),
+        Err(PublishError::Decode(DecodeError::Malformed("TraderConfig")))
+    );
+    assert_eq!(
+        publisher.publish_account(address, &vectai_trader::ID, 1_000, &data[..4], 9),
+        Err(PublishError::Decode(DecodeError::TooShort))
+    );
+    assert!(stream.receiver().try_recv().is_err());
+
+    drop(stream);
+    assert_eq!(
+        publisher.publish_account(address, &vectai_trader::ID, 1_000, &data, 9),
+        Err(PublishError::Closed)
+    );
+}
@@ -0,0 +1,58 @@
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::hash::hashv;
+
+#[derive(Accounts)]
+pub struct CommitSwap<'info> {
+    #[account(
+        init_if_needed,
+        payer = user_authority,
+        space = SwapCommitment::LEN,
+        seeds = [b"swap-commitment", user_authority.key().as_ref()],
+        bump
+    )]
+    pub commitment: Account<'info, SwapCommitment>,
+
+    #[account(mut)]
+    pub user_authority: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+/// A user's pending commit-reveal swap; kept between swaps and reused by
+/// the next `commit_swap`
+#[account]
+pub struct SwapCommitment {
+    pub user: Pubkey,
+    pub hash: [u8; 32], // All zeros once revealed
+    pub slot: u64, // Slot of the commit; the reveal must land later
+    pub bump: u8,
+}
+
+impl SwapCommitment {
+    pub const LEN: usize = 8 + // discriminator
+        32 + // user
+        32 + // hash
+        8 +  // slot
+        1;   // bump
+
+    pub fn is_pending(&self) -> bool {
+        self.hash != [0; 32]
+    }
+}
+
+/// What `commit_swap` takes for a later `reveal_and_execute(amount_in,
+/// min_amount_out, salt)`
+pub fn swap_commitment_hash(amount_in: u64, min_amount_out: u64, salt: &[u8; 32]) -> [u8; 32] {
+    hashv(&[&amount_in.to_le_bytes(), &min_amount_out.to_le_bytes(), salt]).to_bytes()
+}
+
+/// Address of `user`'s `SwapCommitment`
+pub fn swap_commitment(user: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(&[b"swap-commitment", user.as_ref()], &crate::ID).0
+}
+
+#[event]
+pub struct SwapCommitted {
+    pub user: Pubkey,
+    pub slot: u64,
+}
@@ -1,25 +1,19 @@
 use anchor_lang::prelude::*;
 use anchor_lang::solana_program::{instruction::Instruction, program::invoke};
-use anchor_spl::token::{Token, TokenAccount};
+use anchor_spl::token::{self, Token, TokenAccount, Transfer};
+use vectai_protocol::{program::VectaiProtocol, ActivityKind, NetworkConfig, ProtocolStatus};
 
 // Program ID - update after first build with: solana address -k target/deploy/raydium_swapper-keypair.json
 declare_id!("Fg6PaFpoGXkYsidMpWTK6W2BeZ7FEfcYkg476zPFsLnS");
 
-// ===== RAYDIUM AMM PROGRAM =====
-// Raydium AMM V4 program (same address on devnet and mainnet)
-const RAYDIUM_AMM_PROGRAM: Pubkey = solana_program::pubkey!("675kPX9MHTjS2zt1qfr1NYHuzeLXfQM9H24wFSUt1Mp8");
-
-// ===== TOKEN MINTS =====
-// Wrapped SOL (same on all networks)
-const WSOL_MINT: Pubkey = solana_program::pubkey!("So11111111111111111111111111111111111111112");
-
-// USDC Devnet mint (we use USDC instead of USDT on devnet)
-const USDC_DEVNET: Pubkey = solana_program::pubkey!("4zMMC9srt5Ri5X14GAgXhaHii3GnPAEERYPJgZJDncDU");
+// Raydium program and WSOL/USDC mints come from the shared NetworkConfig
+// in vectai_protocol
 
 // ===== RAYDIUM USDC/SOL POOL (DEVNET) =====
 // These are REAL addresses from a Raydium USDC/SOL pool on Devnet
 // Note: Pool addresses can change. Verify current pools at https://raydium.io or via API
-mod pool_config {
+/// Whitelisted pool; `swap` and the liquidity instructions reject any other
+pub mod pool_config {
     use super::*;
     
     // AMM Pool ID (the main pool state account)
@@ -38,6 +32,10 @@ mod pool_config {
     pub const AMM_TARGET_ORDERS: Pubkey = 
         solana_program::pubkey!("CZza3Ej4Mc58MnxWA385itCC9jCo3L1D7zc3LKy1bZMR");
     
+    // AMM LP Mint (minted on deposit, burned on withdraw)
+    pub const AMM_LP_MINT: Pubkey = 
+        solana_program::pubkey!("8HoQnePLqPj4M7PUDzfw8e3Ymdwgc7NLGnaTUapubyvu");
+    
     // Pool Token Accounts (the pool's token vaults)
     // Coin account (USDC)
     pub const POOL_COIN_TOKEN_ACCOUNT: Pubkey = 
@@ -81,8 +79,41 @@ mod pool_config {
         solana_program::pubkey!("F8Vyqk3unwxkXukZFQeYyGmFfTG3CAX4v24iyrjEYBJV");
 }
 
-// Raydium AMM swap instruction discriminator
+// Raydium AMM swap instruction discriminator: `SwapBaseIn`, or under
+// `no-serum` the OpenBook-free `SwapBaseInV2`
+#[cfg(not(feature = "no-serum"))]
 const RAYDIUM_SWAP_INSTRUCTION: u8 = 9;
+#[cfg(feature = "no-serum")]
+const RAYDIUM_SWAP_INSTRUCTION: u8 = 16;
+
+// Add/remove liquidity on the whitelisted pool. AMM v4 deposits and
+// withdrawals always take the OpenBook market, `no-serum` or not.
+mod liquidity;
+pub use liquidity::*;
+
+// Commit-reveal swaps
+mod commit;
+pub use commit::*;
+
+// Meteora DLMM swaps
+mod meteora;
+pub use meteora::*;
+
+// Phoenix order-book swaps
+mod phoenix;
+pub use phoenix::*;
+
+// Lifinity oracle-anchored pool swaps
+mod lifinity;
+pub use lifinity::*;
+
+// Stable-swap pools for pegged pairs
+mod stable;
+pub use stable::*;
+
+// Live quotes on the whitelisted pool for CPI callers
+mod quote;
+pub use quote::*;
 
 #[program]
 pub mod raydium_swapper {
@@ -106,157 +137,747 @@ pub mod raydium_swapper {
         amount_in: u64,
         min_amount_out: u64,
     ) -> Result<()> {
-        msg!("🔄 Starting Raydium swap");
-        msg!("   Input: {} tokens", amount_in);
-        msg!("   Min output: {} tokens", min_amount_out);
+        process_swap(ctx.accounts, ctx.bumps.stats_reporter, amount_in, min_amount_out)
+    }
 
-        // ===== STEP 1: VALIDATE RAYDIUM PROGRAM =====
-        require!(
-            ctx.accounts.raydium_amm_program.key() == RAYDIUM_AMM_PROGRAM,
-            SwapError::InvalidRaydiumProgram
-        );
+    /// First half of a commit-reveal swap: record
+    /// `swap_commitment_hash(amount_in, min_amount_out, salt)` so the size
+    /// and limit stay private until the reveal. Replaces any pending
+    /// commitment.
+    pub fn commit_swap(ctx: Context<CommitSwap>, hash: [u8; 32]) -> Result<()> {
+        require!(hash != [0; 32], SwapError::InvalidCommitment);
 
-        // ===== STEP 2: VALIDATE TOKEN PAIR =====
-        // Only allow USDC <-> SOL swaps
-        let source_mint = ctx.accounts.user_source_token.mint;
-        let dest_mint = ctx.accounts.user_destination_token.mint;
-        
-        let is_usdc_to_sol = source_mint == USDC_DEVNET && dest_mint == WSOL_MINT;
-        let is_sol_to_usdc = source_mint == WSOL_MINT && dest_mint == USDC_DEVNET;
-        
-        require!(
-            is_usdc_to_sol || is_sol_to_usdc,
-            SwapError::InvalidTokenPair
-        );
-        
-        msg!("   Token pair: {} -> {}", 
-            if is_usdc_to_sol { "USDC" } else { "SOL" },
-            if is_usdc_to_sol { "SOL" } else { "USDC" }
-        );
+        let slot = Clock::get()?.slot;
+        let commitment = &mut ctx.accounts.commitment;
+        commitment.user = ctx.accounts.user_authority.key();
+        commitment.hash = hash;
+        commitment.slot = slot;
+        commitment.bump = ctx.bumps.commitment;
+
+        emit!(SwapCommitted {
+            user: commitment.user,
+            slot,
+        });
+        msg!("🔒 Swap committed at slot {}", slot);
+        Ok(())
+    }
 
-        // ===== STEP 3: VALIDATE USER OWNERSHIP =====
+    /// Second half of a commit-reveal swap, in a later slot than the commit:
+    /// check the revealed parameters against the commitment and swap
+    pub fn reveal_and_execute(
+        ctx: Context<RevealAndExecute>,
+        amount_in: u64,
+        min_amount_out: u64,
+        salt: [u8; 32],
+    ) -> Result<()> {
+        let commitment = &mut ctx.accounts.commitment;
+        require!(commitment.is_pending(), SwapError::NoPendingCommitment);
+        require!(Clock::get()?.slot > commitment.slot, SwapError::RevealTooEarly);
         require!(
-            ctx.accounts.user_source_token.owner == ctx.accounts.user_authority.key(),
-            SwapError::InvalidOwner
+            swap_commitment_hash(amount_in, min_amount_out, &salt) == commitment.hash,
+            SwapError::CommitmentMismatch
         );
+        commitment.hash = [0; 32];
+
+        process_swap(&ctx.accounts.swap, ctx.bumps.swap.stats_reporter, amount_in, min_amount_out)
+    }
+
+    /// Swap USDC <-> SOL on a Meteora DLMM pair instead of the Raydium
+    /// pool, for pairs whose deepest liquidity sits in DLMM bins. The bin
+    /// arrays the swap crosses are passed as remaining accounts.
+    pub fn swap_meteora_dlmm<'info>(
+        ctx: Context<'_, '_, 'info, 'info, MeteoraSwapAccounts<'info>>,
+        amount_in: u64,
+        min_amount_out: u64,
+    ) -> Result<()> {
+        msg!("🔄 Starting Meteora DLMM swap");
+        let accounts = &ctx.accounts;
+        let source_mint = accounts.user_source_token.mint;
+        let dest_mint = accounts.user_destination_token.mint;
+        check_swap_request(
+            &accounts.network_config,
+            &accounts.user_source_token,
+            &accounts.user_destination_token,
+            &accounts.user_authority.key(),
+            amount_in,
+        )?;
+        require!(!ctx.remaining_accounts.is_empty(), SwapError::InvalidPool);
+
+        let swap_fee = take_swap_fee(
+            &accounts.token_program,
+            &accounts.user_source_token,
+            &accounts.user_authority,
+            accounts.fee_account.as_ref(),
+            &accounts.protocol_status,
+            &accounts.network_config,
+            amount_in,
+        )?;
+        let amount_in = amount_in - swap_fee;
+
+        msg!("📞 Calling Meteora DLMM program...");
+        invoke_dlmm_swap(accounts, ctx.remaining_accounts, amount_in, min_amount_out)?;
+
+        vectai_protocol::report(
+            accounts.protocol_program.to_account_info(),
+            accounts.protocol_stats.to_account_info(),
+            accounts.stats_reporter.to_account_info(),
+            ctx.bumps.stats_reporter,
+            ActivityKind::Swap,
+            source_mint,
+            amount_in,
+        )?;
+
+        emit!(SwapExecuted {
+            user: accounts.user_authority.key(),
+            source_mint,
+            destination_mint: dest_mint,
+            amount_in,
+            swap_fee,
+            min_amount_out,
+        });
+        msg!("✅ Meteora DLMM swap completed");
+        Ok(())
+    }
+
+    /// Swap USDC <-> SOL with an immediate-or-cancel order on the Phoenix
+    /// market the venue registry lists for the pair, for when the book
+    /// quotes better than the AMMs. Only whole lots trade; the remainder
+    /// of `amount_in` stays in the source account.
+    pub fn swap_phoenix_ioc(
+        ctx: Context<PhoenixSwapAccounts>,
+        amount_in: u64,
+        min_amount_out: u64,
+    ) -> Result<()> {
+        msg!("🔄 Starting Phoenix IOC swap");
+        let accounts = &ctx.accounts;
+        let source_mint = accounts.user_source_token.mint;
+        let dest_mint = accounts.user_destination_token.mint;
+        check_swap_request(
+            &accounts.network_config,
+            &accounts.user_source_token,
+            &accounts.user_destination_token,
+            &accounts.user_authority.key(),
+            amount_in,
+        )?;
 
-        // ===== STEP 4: VALIDATE BALANCES =====
+        let market = PhoenixMarket::parse(&accounts.market.try_borrow_data()?).ok_or(SwapError::InvalidPool)?;
+        require!(accounts.base_vault.key() == market.base_vault, SwapError::InvalidPool);
+        require!(accounts.quote_vault.key() == market.quote_vault, SwapError::InvalidPool);
+
+        let swap_fee = take_swap_fee(
+            &accounts.token_program,
+            &accounts.user_source_token,
+            &accounts.user_authority,
+            accounts.fee_account.as_ref(),
+            &accounts.protocol_status,
+            &accounts.network_config,
+            amount_in,
+        )?;
+        let order = IocOrder::new(&market, &source_mint, amount_in - swap_fee, min_amount_out)
+            .ok_or(SwapError::InvalidPool)?;
+        let amount_in = order.amount_in(&market);
         require!(amount_in > 0, SwapError::InvalidAmount);
+
+        msg!("📞 Calling Phoenix program...");
+        invoke_phoenix_swap(accounts, &order)?;
+
+        vectai_protocol::report(
+            accounts.protocol_program.to_account_info(),
+            accounts.protocol_stats.to_account_info(),
+            accounts.stats_reporter.to_account_info(),
+            ctx.bumps.stats_reporter,
+            ActivityKind::Swap,
+            source_mint,
+            amount_in,
+        )?;
+
+        emit!(SwapExecuted {
+            user: accounts.user_authority.key(),
+            source_mint,
+            destination_mint: dest_mint,
+            amount_in,
+            swap_fee,
+            min_amount_out,
+        });
+        msg!("✅ Phoenix IOC swap completed");
+        Ok(())
+    }
+
+    /// Swap USDC <-> SOL on a Lifinity v2 pool. Lifinity prices around its
+    /// oracle rather than its reserve ratio, so `min_amount_out` should
+    /// come from `expected_amount_out` with `PoolPricing::OracleAnchored`;
+    /// a constant-product quote of the same reserves can be far off.
+    pub fn swap_lifinity(
+        ctx: Context<LifinitySwapAccounts>,
+        amount_in: u64,
+        min_amount_out: u64,
+    ) -> Result<()> {
+        msg!("🔄 Starting Lifinity swap");
+        let accounts = &ctx.accounts;
+        let source_mint = accounts.user_source_token.mint;
+        let dest_mint = accounts.user_destination_token.mint;
+        check_swap_request(
+            &accounts.network_config,
+            &accounts.user_source_token,
+            &accounts.user_destination_token,
+            &accounts.user_authority.key(),
+            amount_in,
+        )?;
+
+        let swap_fee = take_swap_fee(
+            &accounts.token_program,
+            &accounts.user_source_token,
+            &accounts.user_authority,
+            accounts.fee_account.as_ref(),
+            &accounts.protocol_status,
+            &accounts.network_config,
+            amount_in,
+        )?;
+        let amount_in = amount_in - swap_fee;
+
+        msg!("📞 Calling Lifinity program...");
+        invoke_lifinity_swap(accounts, amount_in, min_amount_out)?;
+
+        vectai_protocol::report(
+            accounts.protocol_program.to_account_info(),
+            accounts.protocol_stats.to_account_info(),
+            accounts.stats_reporter.to_account_info(),
+            ctx.bumps.stats_reporter,
+            ActivityKind::Swap,
+            source_mint,
+            amount_in,
+        )?;
+
+        emit!(SwapExecuted {
+            user: accounts.user_authority.key(),
+            source_mint,
+            destination_mint: dest_mint,
+            amount_in,
+            swap_fee,
+            min_amount_out,
+        });
+        msg!("✅ Lifinity swap completed");
+        Ok(())
+    }
+
+    /// Swap between two pegged assets (USDT <-> USDC) on the stable-swap
+    /// pool listed for the pair in the venue registry, priced by the
+    /// stable-swap invariant rather than x * y = k; quote the minimum out
+    /// with `PoolPricing::StableSwap`
+    pub fn swap_stable(
+        ctx: Context<StableSwapAccounts>,
+        amount_in: u64,
+        min_amount_out: u64,
+    ) -> Result<()> {
+        msg!("🔄 Starting stable swap");
+        let accounts = &ctx.accounts;
+        let source_mint = accounts.user_source_token.mint;
+        let dest_mint = accounts.user_destination_token.mint;
+        check_stable_swap_request(
+            &accounts.venue_registry,
+            &accounts.user_source_token,
+            &accounts.user_destination_token,
+            &accounts.user_authority.key(),
+            amount_in,
+        )?;
+
+        let swap_fee = take_swap_fee(
+            &accounts.token_program,
+            &accounts.user_source_token,
+            &accounts.user_authority,
+            accounts.fee_account.as_ref(),
+            &accounts.protocol_status,
+            &accounts.network_config,
+            amount_in,
+        )?;
+        let amount_in = amount_in - swap_fee;
+
+        msg!("📞 Calling StableSwap program...");
+        invoke_stable_swap(accounts, amount_in, min_amount_out)?;
+
+        vectai_protocol::report(
+            accounts.protocol_program.to_account_info(),
+            accounts.protocol_stats.to_account_info(),
+            accounts.stats_reporter.to_account_info(),
+            ctx.bumps.stats_reporter,
+            ActivityKind::Swap,
+            source_mint,
+            amount_in,
+        )?;
+
+        emit!(SwapExecuted {
+            user: accounts.user_authority.key(),
+            source_mint,
+            destination_mint: dest_mint,
+            amount_in,
+            swap_fee,
+            min_amount_out,
+        });
+        msg!("✅ Stable swap completed");
+        Ok(())
+    }
+
+    /// Deposit into the whitelisted Raydium pool
+    ///
+    /// # Arguments
+    /// * `max_coin_amount` - Most coin tokens the deposit may take
+    /// * `max_pc_amount` - Most PC tokens the deposit may take
+    /// * `base_side` - 0 to size the deposit by coin, 1 by PC
+    pub fn add_liquidity(
+        ctx: Context<LiquidityAccounts>,
+        max_coin_amount: u64,
+        max_pc_amount: u64,
+        base_side: u64,
+    ) -> Result<()> {
+        msg!("💧 Adding Raydium liquidity: up to {} coin / {} pc", max_coin_amount, max_pc_amount);
+
+        // ===== VALIDATE =====
+        ctx.accounts.validate()?;
+        require!(max_coin_amount > 0 && max_pc_amount > 0, SwapError::InvalidAmount);
+        require!(base_side <= 1, SwapError::InvalidAmount);
         require!(
-            ctx.accounts.user_source_token.amount >= amount_in,
+            ctx.accounts.user_coin_token.amount >= max_coin_amount
+                && ctx.accounts.user_pc_token.amount >= max_pc_amount,
             SwapError::InsufficientBalance
         );
 
-        // ===== STEP 5: VALIDATE POOL ACCOUNTS =====
-        // Ensure we're using the correct, whitelisted pool
-        use pool_config::*;
-        require!(ctx.accounts.amm.key() == AMM_ID, SwapError::InvalidPool);
-        require!(ctx.accounts.amm_authority.key() == AMM_AUTHORITY, SwapError::InvalidPool);
-        require!(ctx.accounts.amm_open_orders.key() == AMM_OPEN_ORDERS, SwapError::InvalidPool);
-        require!(ctx.accounts.amm_target_orders.key() == AMM_TARGET_ORDERS, SwapError::InvalidPool);
-        require!(
-            ctx.accounts.pool_coin_token_account.key() == POOL_COIN_TOKEN_ACCOUNT,
-            SwapError::InvalidPool
-        );
+        // ===== BUILD RAYDIUM DEPOSIT =====
+        // Format: [discriminator(u8), max_coin(u64 LE), max_pc(u64 LE), base_side(u64 LE)]
+        let mut instruction_data = Vec::with_capacity(25);
+        instruction_data.push(RAYDIUM_DEPOSIT_INSTRUCTION);
+        instruction_data.extend_from_slice(&max_coin_amount.to_le_bytes());
+        instruction_data.extend_from_slice(&max_pc_amount.to_le_bytes());
+        instruction_data.extend_from_slice(&base_side.to_le_bytes());
+
+        let a = &ctx.accounts;
+        let account_metas = vec![
+            AccountMeta::new_readonly(a.token_program.key(), false),
+            AccountMeta::new(a.amm.key(), false),
+            AccountMeta::new_readonly(a.amm_authority.key(), false),
+            AccountMeta::new_readonly(a.amm_open_orders.key(), false),
+            AccountMeta::new(a.amm_target_orders.key(), false),
+            AccountMeta::new(a.amm_lp_mint.key(), false),
+            AccountMeta::new(a.pool_coin_token_account.key(), false),
+            AccountMeta::new(a.pool_pc_token_account.key(), false),
+            AccountMeta::new_readonly(a.serum_market.key(), false),
+            AccountMeta::new(a.user_coin_token.key(), false),
+            AccountMeta::new(a.user_pc_token.key(), false),
+            AccountMeta::new(a.user_lp_token.key(), false),
+            AccountMeta::new_readonly(a.user_authority.key(), true),
+            AccountMeta::new_readonly(a.serum_event_queue.key(), false),
+        ];
+        let account_infos = vec![
+            a.token_program.to_account_info(),
+            a.amm.to_account_info(),
+            a.amm_authority.to_account_info(),
+            a.amm_open_orders.to_account_info(),
+            a.amm_target_orders.to_account_info(),
+            a.amm_lp_mint.to_account_info(),
+            a.pool_coin_token_account.to_account_info(),
+            a.pool_pc_token_account.to_account_info(),
+            a.serum_market.to_account_info(),
+            a.user_coin_token.to_account_info(),
+            a.user_pc_token.to_account_info(),
+            a.user_lp_token.to_account_info(),
+            a.user_authority.to_account_info(),
+            a.serum_event_queue.to_account_info(),
+        ];
+
+        // ===== EXECUTE CPI TO RAYDIUM =====
+        let deposit_instruction = Instruction {
+            program_id: a.raydium_amm_program.key(),
+            accounts: account_metas,
+            data: instruction_data,
+        };
+        invoke(&deposit_instruction, &account_infos)?;
+
+        msg!("✅ Liquidity added");
+        Ok(())
+    }
+
+    /// Burn `lp_amount` LP tokens from the whitelisted Raydium pool for
+    /// the underlying coin and PC tokens
+    pub fn remove_liquidity(ctx: Context<LiquidityAccounts>, lp_amount: u64) -> Result<()> {
+        msg!("💧 Removing {} Raydium LP", lp_amount);
+
+        // ===== VALIDATE =====
+        ctx.accounts.validate()?;
+        require!(lp_amount > 0, SwapError::InvalidAmount);
         require!(
-            ctx.accounts.pool_pc_token_account.key() == POOL_PC_TOKEN_ACCOUNT,
-            SwapError::InvalidPool
+            ctx.accounts.user_lp_token.amount >= lp_amount,
+            SwapError::InsufficientBalance
         );
-        require!(ctx.accounts.serum_program.key() == SERUM_PROGRAM, SwapError::InvalidPool);
-        require!(ctx.accounts.serum_market.key() == SERUM_MARKET, SwapError::InvalidPool);
-
-        msg!("✅ All validations passed");
 
-        // ===== STEP 6: BUILD RAYDIUM INSTRUCTION DATA =====
-        // Format: [instruction_discriminator(u8), amount_in(u64 LE), min_amount_out(u64 LE)]
-        let mut instruction_data = Vec::with_capacity(17);
-        instruction_data.push(RAYDIUM_SWAP_INSTRUCTION);
-        instruction_data.extend_from_slice(&amount_in.to_le_bytes());
-        instruction_data.extend_from_slice(&min_amount_out.to_le_bytes());
+        // ===== BUILD RAYDIUM WITHDRAW =====
+        // Format: [discriminator(u8), lp_amount(u64 LE)]
+        let mut instruction_data = Vec::with_capacity(9);
+        instruction_data.push(RAYDIUM_WITHDRAW_INSTRUCTION);
+        instruction_data.extend_from_slice(&lp_amount.to_le_bytes());
 
-        // ===== STEP 7: BUILD ACCOUNT METAS =====
-        // Order is critical - must match Raydium's expected account order
+        let a = &ctx.accounts;
         let account_metas = vec![
-            // 0. Token program
-            AccountMeta::new_readonly(ctx.accounts.token_program.key(), false),
-            // 1. AMM
-            AccountMeta::new(ctx.accounts.amm.key(), false),
-            // 2. AMM authority
-            AccountMeta::new_readonly(ctx.accounts.amm_authority.key(), false),
-            // 3. AMM open orders
-            AccountMeta::new(ctx.accounts.amm_open_orders.key(), false),
-            // 4. AMM target orders
-            AccountMeta::new(ctx.accounts.amm_target_orders.key(), false),
-            // 5. Pool coin token account
-            AccountMeta::new(ctx.accounts.pool_coin_token_account.key(), false),
-            // 6. Pool PC token account
-            AccountMeta::new(ctx.accounts.pool_pc_token_account.key(), false),
-            // 7. Serum program
-            AccountMeta::new_readonly(ctx.accounts.serum_program.key(), false),
-            // 8. Serum market
-            AccountMeta::new(ctx.accounts.serum_market.key(), false),
-            // 9. Serum bids
-            AccountMeta::new(ctx.accounts.serum_bids.key(), false),
-            // 10. Serum asks
-            AccountMeta::new(ctx.accounts.serum_asks.key(), false),
-            // 11. Serum event queue
-            AccountMeta::new(ctx.accounts.serum_event_queue.key(), false),
-            // 12. Serum coin vault
-            AccountMeta::new(ctx.accounts.serum_coin_vault.key(), false),
-            // 13. Serum PC vault
-            AccountMeta::new(ctx.accounts.serum_pc_vault.key(), false),
-            // 14. Serum vault signer
-            AccountMeta::new_readonly(ctx.accounts.serum_vault_signer.key(), false),
-            // 15. User source token account
-            AccountMeta::new(ctx.accounts.user_source_token.key(), false),
-            // 16. User destination token account
-            AccountMeta::new(ctx.accounts.user_destination_token.key(), false),
-            // 17. User authority (signer)
-            AccountMeta::new_readonly(ctx.accounts.user_authority.key(), true),
+            AccountMeta::new_readonly(a.token_program.key(), false),
+            AccountMeta::new(a.amm.key(), false),
+            AccountMeta::new_readonly(a.amm_authority.key(), false),
+            AccountMeta::new(a.amm_open_orders.key(), false),
+            AccountMeta::new(a.amm_target_orders.key(), false),
+            AccountMeta::new(a.amm_lp_mint.key(), false),
+            AccountMeta::new(a.pool_coin_token_account.key(), false),
+            AccountMeta::new(a.pool_pc_token_account.key(), false),
+            AccountMeta::new_readonly(a.serum_program.key(), false),
+            AccountMeta::new(a.serum_market.key(), false),
+            AccountMeta::new(a.serum_coin_vault.key(), false),
+            AccountMeta::new(a.serum_pc_vault.key(), false),
+            AccountMeta::new_readonly(a.serum_vault_signer.key(), false),
+            AccountMeta::new(a.user_lp_token.key(), false),
+            AccountMeta::new(a.user_coin_token.key(), false),
+            AccountMeta::new(a.user_pc_token.key(), false),
+            AccountMeta::new_readonly(a.user_authority.key(), true),
+            AccountMeta::new(a.serum_event_queue.key(), false),
+            AccountMeta::new(a.serum_bids.key(), false),
+            AccountMeta::new(a.serum_asks.key(), false),
+        ];
+        let account_infos = vec![
+            a.token_program.to_account_info(),
+            a.amm.to_account_info(),
+            a.amm_authority.to_account_info(),
+            a.amm_open_orders.to_account_info(),
+            a.amm_target_orders.to_account_info(),
+            a.amm_lp_mint.to_account_info(),
+            a.pool_coin_token_account.to_account_info(),
+            a.pool_pc_token_account.to_account_info(),
+            a.serum_program.to_account_info(),
+            a.serum_market.to_account_info(),
+            a.serum_coin_vault.to_account_info(),
+            a.serum_pc_vault.to_account_info(),
+            a.serum_vault_signer.to_account_info(),
+            a.user_lp_token.to_account_info(),
+            a.user_coin_token.to_account_info(),
+            a.user_pc_token.to_account_info(),
+            a.user_authority.to_account_info(),
+            a.serum_event_queue.to_account_info(),
+            a.serum_bids.to_account_info(),
+            a.serum_asks.to_account_info(),
         ];
 
-        // ===== STEP 8: CREATE INSTRUCTION =====
-        let swap_instruction = Instruction {
-            program_id: RAYDIUM_AMM_PROGRAM,
+        // ===== EXECUTE CPI TO RAYDIUM =====
+        let withdraw_instruction = Instruction {
+            program_id: a.raydium_amm_program.key(),
             accounts: account_metas,
             data: instruction_data,
         };
+        invoke(&withdraw_instruction, &account_infos)?;
 
-        // ===== STEP 9: PREPARE ACCOUNT INFOS FOR CPI =====
-        let account_infos = vec![
-            ctx.accounts.token_program.to_account_info(),
-            ctx.accounts.amm.to_account_info(),
-            ctx.accounts.amm_authority.to_account_info(),
-            ctx.accounts.amm_open_orders.to_account_info(),
-            ctx.accounts.amm_target_orders.to_account_info(),
-            ctx.accounts.pool_coin_token_account.to_account_info(),
-            ctx.accounts.pool_pc_token_account.to_account_info(),
-            ctx.accounts.serum_program.to_account_info(),
-            ctx.accounts.serum_market.to_account_info(),
-            ctx.accounts.serum_bids.to_account_info(),
-            ctx.accounts.serum_asks.to_account_info(),
-            ctx.accounts.serum_event_queue.to_account_info(),
-            ctx.accounts.serum_coin_vault.to_account_info(),
-            ctx.accounts.serum_pc_vault.to_account_info(),
-            ctx.accounts.serum_vault_signer.to_account_info(),
-            ctx.accounts.user_source_token.to_account_info(),
-            ctx.accounts.user_destination_token.to_account_info(),
-            ctx.accounts.user_authority.to_account_info(),
-        ];
+        msg!("✅ Liquidity removed");
+        Ok(())
+    }
+
+    /// View: the whitelisted pool's addresses, mints and current reserves,
+    /// returned to CPI callers as `PoolEntry`
+    pub fn get_pool_entry(ctx: Context<GetPoolEntry>) -> Result<PoolEntry> {
+        use pool_config::*;
+        let coin = &ctx.accounts.pool_coin_token_account;
+        let pc = &ctx.accounts.pool_pc_token_account;
+        require!(coin.key() == POOL_COIN_TOKEN_ACCOUNT, SwapError::InvalidPool);
+        require!(pc.key() == POOL_PC_TOKEN_ACCOUNT, SwapError::InvalidPool);
 
-        // ===== STEP 10: EXECUTE CPI TO RAYDIUM =====
-        // This is where the actual swap happens
-        // Raydium will update the user's token balances on-chain
-        msg!("📞 Calling Raydium AMM program...");
-        invoke(&swap_instruction, &account_infos)?;
+        Ok(PoolEntry {
+            amm: AMM_ID,
+            amm_authority: AMM_AUTHORITY,
+            lp_mint: AMM_LP_MINT,
+            pool_coin_token_account: POOL_COIN_TOKEN_ACCOUNT,
+            pool_pc_token_account: POOL_PC_TOKEN_ACCOUNT,
+            serum_market: SERUM_MARKET,
+            coin_mint: coin.mint,
+            pc_mint: pc.mint,
+            coin_reserve: coin.amount,
+            pc_reserve: pc.amount,
+        })
+    }
 
-        msg!("✅ Swap completed successfully!");
-        msg!("   Check your token balances to see the results");
+    /// View: what `swap` of `amount_in` across `pair` would pay out at the
+    /// whitelisted pool's current reserves, returned to CPI callers as
+    /// `SwapQuote` so they can route on live pricing
+    pub fn get_quote(ctx: Context<GetQuote>, pair: QuotePair, amount_in: u64) -> Result<SwapQuote> {
+        require!(amount_in > 0, SwapError::InvalidAmount);
+        let coin = &ctx.accounts.pool_coin_token_account;
+        let pc = &ctx.accounts.pool_pc_token_account;
+        let (reserve_in, reserve_out) = if (pair.input_mint, pair.output_mint) == (coin.mint, pc.mint) {
+            (coin.amount, pc.amount)
+        } else if (pair.input_mint, pair.output_mint) == (pc.mint, coin.mint) {
+            (pc.amount, coin.amount)
+        } else {
+            return err!(SwapError::InvalidTokenPair);
+        };
 
-        Ok(())
+        let swap_fee_bps = ctx.accounts.protocol_status.fee_config.swap_fee_bps as u64;
+        let quote = SwapQuote::constant_product(amount_in, reserve_in, reserve_out, swap_fee_bps)
+            .ok_or(SwapError::InvalidPool)?;
+        msg!("📊 Quote: {} in, {} out, {} bps impact", amount_in, quote.expected_out, quote.price_impact_bps);
+        Ok(quote)
     }
 }
 
+/// The swap itself: validation, the protocol fee, the Raydium CPI and the
+/// stats report, shared by `swap` and `reveal_and_execute`
+fn process_swap(
+    accounts: &SwapAccounts,
+    stats_reporter_bump: u8,
+    amount_in: u64,
+    min_amount_out: u64,
+) -> Result<()> {
+    msg!("🔄 Starting Raydium swap");
+    msg!("   Input: {} tokens", amount_in);
+    msg!("   Min output: {} tokens", min_amount_out);
+
+    // ===== STEP 1: VALIDATE RAYDIUM PROGRAM =====
+    require!(
+        accounts.raydium_amm_program.key() == accounts.network_config.raydium_amm_program,
+        SwapError::InvalidRaydiumProgram
+    );
+
+    // ===== STEPS 2-4: VALIDATE TOKEN PAIR, OWNERSHIP AND BALANCE =====
+    let source_mint = accounts.user_source_token.mint;
+    let dest_mint = accounts.user_destination_token.mint;
+    check_swap_request(
+        &accounts.network_config,
+        &accounts.user_source_token,
+        &accounts.user_destination_token,
+        &accounts.user_authority.key(),
+        amount_in,
+    )?;
+
+    // ===== STEP 5: VALIDATE POOL ACCOUNTS =====
+    // Ensure we're using the correct, whitelisted pool
+    use pool_config::*;
+    require!(accounts.amm.key() == AMM_ID, SwapError::InvalidPool);
+    require!(accounts.amm_authority.key() == AMM_AUTHORITY, SwapError::InvalidPool);
+    require!(
+        accounts.pool_coin_token_account.key() == POOL_COIN_TOKEN_ACCOUNT,
+        SwapError::InvalidPool
+    );
+    require!(
+        accounts.pool_pc_token_account.key() == POOL_PC_TOKEN_ACCOUNT,
+        SwapError::InvalidPool
+    );
+    #[cfg(not(feature = "no-serum"))]
+    {
+        require!(accounts.amm_open_orders.key() == AMM_OPEN_ORDERS, SwapError::InvalidPool);
+        require!(accounts.amm_target_orders.key() == AMM_TARGET_ORDERS, SwapError::InvalidPool);
+        require!(accounts.serum_program.key() == SERUM_PROGRAM, SwapError::InvalidPool);
+        require!(accounts.serum_market.key() == SERUM_MARKET, SwapError::InvalidPool);
+        require!(accounts.serum_bids.key() == SERUM_BIDS, SwapError::InvalidPool);
+        require!(accounts.serum_asks.key() == SERUM_ASKS, SwapError::InvalidPool);
+        require!(accounts.serum_event_queue.key() == SERUM_EVENT_QUEUE, SwapError::InvalidPool);
+        require!(accounts.serum_coin_vault.key() == SERUM_COIN_VAULT, SwapError::InvalidPool);
+        require!(accounts.serum_pc_vault.key() == SERUM_PC_VAULT, SwapError::InvalidPool);
+        require!(accounts.serum_vault_signer.key() == SERUM_VAULT_SIGNER, SwapError::InvalidPool);
+    }
+
+    msg!("✅ All validations passed");
+
+    // ===== STEP 6: TAKE PROTOCOL SWAP FEE =====
+    let swap_fee = take_swap_fee(
+        &accounts.token_program,
+        &accounts.user_source_token,
+        &accounts.user_authority,
+        accounts.fee_account.as_ref(),
+        &accounts.protocol_status,
+        &accounts.network_config,
+        amount_in,
+    )?;
+    let amount_in = amount_in - swap_fee;
+
+    // ===== STEP 7: BUILD RAYDIUM INSTRUCTION DATA =====
+    // Format: [instruction_discriminator(u8), amount_in(u64 LE), min_amount_out(u64 LE)]
+    let mut instruction_data = Vec::with_capacity(17);
+    instruction_data.push(RAYDIUM_SWAP_INSTRUCTION);
+    instruction_data.extend_from_slice(&amount_in.to_le_bytes());
+    instruction_data.extend_from_slice(&min_amount_out.to_le_bytes());
+
+    // ===== STEP 8: BUILD ACCOUNT METAS =====
+    // Order is critical - must match Raydium's expected account order.
+    // `SwapBaseInV2` takes the same accounts without 3-4 and 7-14.
+    let account_metas = vec![
+        // 0. Token program
+        AccountMeta::new_readonly(accounts.token_program.key(), false),
+        // 1. AMM
+        AccountMeta::new(accounts.amm.key(), false),
+        // 2. AMM authority
+        AccountMeta::new_readonly(accounts.amm_authority.key(), false),
+        // 3. AMM open orders
+        #[cfg(not(feature = "no-serum"))]
+        AccountMeta::new(accounts.amm_open_orders.key(), false),
+        // 4. AMM target orders
+        #[cfg(not(feature = "no-serum"))]
+        AccountMeta::new(accounts.amm_target_orders.key(), false),
+        // 5. Pool coin token account
+        AccountMeta::new(accounts.pool_coin_token_account.key(), false),
+        // 6. Pool PC token account
+        AccountMeta::new(accounts.pool_pc_token_account.key(), false),
+        // 7. Serum program
+        #[cfg(not(feature = "no-serum"))]
+        AccountMeta::new_readonly(accounts.serum_program.key(), false),
+        // 8. Serum market
+        #[cfg(not(feature = "no-serum"))]
+        AccountMeta::new(accounts.serum_market.key(), false),
+        // 9. Serum bids
+        #[cfg(not(feature = "no-serum"))]
+        AccountMeta::new(accounts.serum_bids.key(), false),
+        // 10. Serum asks
+        #[cfg(not(feature = "no-serum"))]
+        AccountMeta::new(accounts.serum_asks.key(), false),
+        // 11. Serum event queue
+        #[cfg(not(feature = "no-serum"))]
+        AccountMeta::new(accounts.serum_event_queue.key(), false),
+        // 12. Serum coin vault
+        #[cfg(not(feature = "no-serum"))]
+        AccountMeta::new(accounts.serum_coin_vault.key(), false),
+        // 13. Serum PC vault
+        #[cfg(not(feature = "no-serum"))]
+        AccountMeta::new(accounts.serum_pc_vault.key(), false),
+        // 14. Serum vault signer
+        #[cfg(not(feature = "no-serum"))]
+        AccountMeta::new_readonly(accounts.serum_vault_signer.key(), false),
+        // 15. User source token account
+        AccountMeta::new(accounts.user_source_token.key(), false),
+        // 16. User destination token account
+        AccountMeta::new(accounts.user_destination_token.key(), false),
+        // 17. User authority (signer)
+        AccountMeta::new_readonly(accounts.user_authority.key(), true),
+    ];
+
+    // ===== STEP 9: CREATE INSTRUCTION =====
+    let swap_instruction = Instruction {
+        program_id: accounts.raydium_amm_program.key(),
+        accounts: account_metas,
+        data: instruction_data,
+    };
+
+    // ===== STEP 10: PREPARE ACCOUNT INFOS FOR CPI =====
+    let account_infos = vec![
+        accounts.token_program.to_account_info(),
+        accounts.amm.to_account_info(),
+        accounts.amm_authority.to_account_info(),
+        #[cfg(not(feature = "no-serum"))]
+        accounts.amm_open_orders.to_account_info(),
+        #[cfg(not(feature = "no-serum"))]
+        accounts.amm_target_orders.to_account_info(),
+        accounts.pool_coin_token_account.to_account_info(),
+        accounts.pool_pc_token_account.to_account_info(),
+        #[cfg(not(feature = "no-serum"))]
+        accounts.serum_program.to_account_info(),
+        #[cfg(not(feature = "no-serum"))]
+        accounts.serum_market.to_account_info(),
+        #[cfg(not(feature = "no-serum"))]
+        accounts.serum_bids.to_account_info(),
+        #[cfg(not(feature = "no-serum"))]
+        accounts.serum_asks.to_account_info(),
+        #[cfg(not(feature = "no-serum"))]
+        accounts.serum_event_queue.to_account_info(),
+        #[cfg(not(feature = "no-serum"))]
+        accounts.serum_coin_vault.to_account_info(),
+        #[cfg(not(feature = "no-serum"))]
+        accounts.serum_pc_vault.to_account_info(),
+        #[cfg(not(feature = "no-serum"))]
+        accounts.serum_vault_signer.to_account_info(),
+        accounts.user_source_token.to_account_info(),
+        accounts.user_destination_token.to_account_info(),
+        accounts.user_authority.to_account_info(),
+    ];
+
+    // ===== STEP 11: EXECUTE CPI TO RAYDIUM =====
+    // This is where the actual swap happens
+    // Raydium will update the user's token balances on-chain
+    msg!("📞 Calling Raydium AMM program...");
+    invoke(&swap_instruction, &account_infos)?;
+
+    // ===== STEP 12: RECORD PROTOCOL STATS =====
+    vectai_protocol::report(
+        accounts.protocol_program.to_account_info(),
+        accounts.protocol_stats.to_account_info(),
+        accounts.stats_reporter.to_account_info(),
+        stats_reporter_bump,
+        ActivityKind::Swap,
+        source_mint,
+        amount_in,
+    )?;
+
+    emit!(SwapExecuted {
+        user: accounts.user_authority.key(),
+        source_mint,
+        destination_mint: dest_mint,
+        amount_in,
+        swap_fee,
+        min_amount_out,
+    });
+    msg!("✅ Swap completed successfully!");
+    msg!("   Check your token balances to see the results");
+
+    Ok(())
+}
+
+/// Check a swap is USDC <-> SOL, from `authority`'s own account and within
+/// its balance
+fn check_swap_request(
+    network: &NetworkConfig,
+    source: &TokenAccount,
+    destination: &TokenAccount,
+    authority: &Pubkey,
+    amount_in: u64,
+) -> Result<()> {
+    // Only allow USDC <-> SOL swaps
+    let is_usdc_to_sol = source.mint == network.usdc_mint && destination.mint == network.wsol_mint;
+    let is_sol_to_usdc = source.mint == network.wsol_mint && destination.mint == network.usdc_mint;
+    require!(
+        is_usdc_to_sol || is_sol_to_usdc,
+        SwapError::InvalidTokenPair
+    );
+    msg!("   Token pair: {} -> {}", 
+        if is_usdc_to_sol { "USDC" } else { "SOL" },
+        if is_usdc_to_sol { "SOL" } else { "USDC" }
+    );
+
+    require!(source.owner == *authority, SwapError::InvalidOwner);
+    require!(amount_in > 0, SwapError::InvalidAmount);
+    require!(source.amount >= amount_in, SwapError::InsufficientBalance);
+    Ok(())
+}
+
+/// Send the protocol swap fee on `amount_in` to the treasury vault of the
+/// source mint; returns the fee
+fn take_swap_fee<'info>(
+    token_program: &Program<'info, Token>,
+    source: &Account<'info, TokenAccount>,
+    authority: &Signer<'info>,
+    fee_account: Option<&Account<'info, TokenAccount>>,
+    protocol_status: &ProtocolStatus,
+    network: &NetworkConfig,
+    amount_in: u64,
+) -> Result<u64> {
+    let swap_fee = vectai_common::bps_of(amount_in, protocol_status.fee_config.swap_fee_bps as u64);
+    if swap_fee > 0 {
+        let fee_account = fee_account.ok_or(SwapError::FeeAccountRequired)?;
+        require!(
+            fee_account.key() == network.treasury_vault(&source.mint),
+            SwapError::InvalidFeeAccount
+        );
+        token::transfer(
+            CpiContext::new(
+                token_program.to_account_info(),
+                Transfer {
+                    from: source.to_account_info(),
+                    to: fee_account.to_account_info(),
+                    authority: authority.to_account_info(),
+                },
+            ),
+            swap_fee,
+        )?;
+        msg!("   Swap fee: {} tokens", swap_fee);
+    }
+    Ok(swap_fee)
+}
+
 // ===== ACCOUNTS STRUCT =====
 #[derive(Accounts)]
 pub struct SwapAccounts<'info> {
@@ -287,11 +908,13 @@ pub struct SwapAccounts<'info> {
     /// AMM open orders account
     /// CHECK: Validated against whitelist
     #[account(mut)]
+    #[cfg(not(feature = "no-serum"))]
     pub amm_open_orders: UncheckedAccount<'info>,
 
     /// AMM target orders account
     /// CHECK: Validated against whitelist
     #[account(mut)]
+    #[cfg(not(feature = "no-serum"))]
     pub amm_target_orders: UncheckedAccount<'info>,
 
     /// Pool's coin token account (USDC)
@@ -306,44 +929,131 @@ pub struct SwapAccounts<'info> {
 
     /// Serum DEX program
     /// CHECK: Validated against whitelist
+    #[cfg(not(feature = "no-serum"))]
     pub serum_program: UncheckedAccount<'info>,
 
     /// Serum market
     /// CHECK: Validated against whitelist
     #[account(mut)]
+    #[cfg(not(feature = "no-serum"))]
     pub serum_market: UncheckedAccount<'info>,
 
     /// Serum bids
     /// CHECK: Validated against whitelist
     #[account(mut)]
+    #[cfg(not(feature = "no-serum"))]
     pub serum_bids: UncheckedAccount<'info>,
 
     /// Serum asks
     /// CHECK: Validated against whitelist
     #[account(mut)]
+    #[cfg(not(feature = "no-serum"))]
     pub serum_asks: UncheckedAccount<'info>,
 
     /// Serum event queue
     /// CHECK: Validated against whitelist
     #[account(mut)]
+    #[cfg(not(feature = "no-serum"))]
     pub serum_event_queue: UncheckedAccount<'info>,
 
     /// Serum coin vault
     /// CHECK: Validated against whitelist
     #[account(mut)]
+    #[cfg(not(feature = "no-serum"))]
     pub serum_coin_vault: UncheckedAccount<'info>,
 
     /// Serum PC vault
     /// CHECK: Validated against whitelist
     #[account(mut)]
+    #[cfg(not(feature = "no-serum"))]
     pub serum_pc_vault: UncheckedAccount<'info>,
 
     /// Serum vault signer
     /// CHECK: Validated against whitelist
+    #[cfg(not(feature = "no-serum"))]
     pub serum_vault_signer: UncheckedAccount<'info>,
 
     /// SPL Token program
     pub token_program: Program<'info, Token>,
+
+    /// Global emergency-pause registry
+    #[account(
+        seeds = [b"protocol-status"],
+        bump = protocol_status.bump,
+        seeds::program = vectai_protocol::ID,
+        constraint = !protocol_status.paused @ SwapError::ProtocolPaused
+    )]
+    pub protocol_status: Account<'info, ProtocolStatus>,
+
+    /// Shared network addresses
+    #[account(
+        seeds = [b"network-config"],
+        bump = network_config.bump,
+        seeds::program = vectai_protocol::ID
+    )]
+    pub network_config: Account<'info, NetworkConfig>,
+
+    /// CHECK: Protocol stats PDA, validated by vectai_protocol
+    #[account(mut)]
+    pub protocol_stats: UncheckedAccount<'info>,
+
+    /// CHECK: Signs `record_activity` for this program
+    #[account(seeds = [b"stats-reporter"], bump)]
+    pub stats_reporter: UncheckedAccount<'info>,
+
+    pub protocol_program: Program<'info, VectaiProtocol>,
+
+    /// Treasury vault for the source mint; required while the swap fee is on
+    #[account(mut)]
+    pub fee_account: Option<Account<'info, TokenAccount>>,
+}
+
+/// `swap`'s accounts plus the user's pending commitment
+#[derive(Accounts)]
+pub struct RevealAndExecute<'info> {
+    pub swap: SwapAccounts<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"swap-commitment", swap.user_authority.key().as_ref()],
+        bump = commitment.bump
+    )]
+    pub commitment: Account<'info, SwapCommitment>,
+}
+
+#[derive(Accounts)]
+pub struct GetPoolEntry<'info> {
+    /// Pool's coin token account
+    pub pool_coin_token_account: Account<'info, TokenAccount>,
+
+    /// Pool's PC token account
+    pub pool_pc_token_account: Account<'info, TokenAccount>,
+}
+
+/// Whitelisted pool as returned by `get_pool_entry`
+#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+pub struct PoolEntry {
+    pub amm: Pubkey,
+    pub amm_authority: Pubkey,
+    pub lp_mint: Pubkey,
+    pub pool_coin_token_account: Pubkey,
+    pub pool_pc_token_account: Pubkey,
+    pub serum_market: Pubkey,
+    pub coin_mint: Pubkey,
+    pub pc_mint: Pubkey,
+    pub coin_reserve: u64,
+    pub pc_reserve: u64,
+}
+
+// ===== EVENTS =====
+#[event]
+pub struct SwapExecuted {
+    pub user: Pubkey, // Swap signer; a PDA when called by another program
+    pub source_mint: Pubkey,
+    pub destination_mint: Pubkey,
+    pub amount_in: u64, // After the swap fee
+    pub swap_fee: u64,
+    pub min_amount_out: u64,
 }
 
 // ===== ERROR CODES =====
@@ -366,4 +1076,25 @@ pub enum SwapError {
     
     #[msg("Pool account mismatch - not using whitelisted pool")]
     InvalidPool,
+    
+    #[msg("Protocol is paused")]
+    ProtocolPaused,
+
+    #[msg("Treasury fee account required while the swap fee is on")]
+    FeeAccountRequired,
+
+    #[msg("Fee account is not the treasury vault for the source mint")]
+    InvalidFeeAccount,
+
+    #[msg("Swap commitment must be a non-zero hash")]
+    InvalidCommitment,
+
+    #[msg("No pending swap commitment")]
+    NoPendingCommitment,
+
+    #[msg("Swap must be revealed in a later slot than its commitment")]
+    RevealTooEarly,
+
+    #[msg("Revealed swap does not match the commitment")]
+    CommitmentMismatch,
 }
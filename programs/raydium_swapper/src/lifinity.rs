@@ -0,0 +1,150 @@
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::{hash::hash, instruction::Instruction, program::invoke};
+use anchor_spl::token::{Token, TokenAccount};
+use vectai_protocol::{program::VectaiProtocol, NetworkConfig, ProtocolStatus};
+
+use crate::SwapError;
+
+/// Lifinity v2 program
+pub const LIFINITY_V2_PROGRAM: Pubkey =
+    anchor_lang::solana_program::pubkey!("2wT8Yq49kHgDzXuPxZSaeLaH1qbmGXtEk9RgzZkHdLeV");
+
+// ===== ACCOUNTS STRUCT =====
+/// Accounts for a swap on a Lifinity v2 pool. The pool prices off its
+/// oracle accounts rather than its reserves, so the minimum out should be
+/// quoted with `PoolPricing::OracleAnchored`. The vaults, LP mint, fee
+/// account and oracles are checked by Lifinity against the pool itself.
+#[derive(Accounts)]
+pub struct LifinitySwapAccounts<'info> {
+    /// User's wallet (must sign the transaction)
+    pub user_authority: Signer<'info>,
+
+    /// User's source token account (tokens being swapped FROM)
+    #[account(mut)]
+    pub user_source_token: Account<'info, TokenAccount>,
+
+    /// User's destination token account (tokens being swapped TO)
+    #[account(mut)]
+    pub user_destination_token: Account<'info, TokenAccount>,
+
+    /// Lifinity v2 program
+    /// CHECK: Address-checked
+    #[account(address = LIFINITY_V2_PROGRAM @ SwapError::InvalidPool)]
+    pub lifinity_program: UncheckedAccount<'info>,
+
+    /// Lifinity pool
+    /// CHECK: Must be owned by Lifinity
+    #[account(owner = LIFINITY_V2_PROGRAM @ SwapError::InvalidPool)]
+    pub amm: UncheckedAccount<'info>,
+
+    /// CHECK: The pool's vault authority PDA, validated by Lifinity
+    pub amm_authority: UncheckedAccount<'info>,
+
+    /// Pool vault of the source mint
+    /// CHECK: Validated by Lifinity
+    #[account(mut)]
+    pub swap_source: UncheckedAccount<'info>,
+
+    /// Pool vault of the destination mint
+    /// CHECK: Validated by Lifinity
+    #[account(mut)]
+    pub swap_destination: UncheckedAccount<'info>,
+
+    /// CHECK: The pool's LP mint, validated by Lifinity
+    #[account(mut)]
+    pub pool_mint: UncheckedAccount<'info>,
+
+    /// CHECK: The pool's own fee account, validated by Lifinity
+    #[account(mut)]
+    pub pool_fee_account: UncheckedAccount<'info>,
+
+    /// CHECK: The pool's main price oracle, validated by Lifinity
+    pub oracle_main_account: UncheckedAccount<'info>,
+
+    /// CHECK: The pool's fallback price oracle, validated by Lifinity
+    pub oracle_sub_account: UncheckedAccount<'info>,
+
+    /// CHECK: The pool's quote-token oracle, validated by Lifinity
+    pub oracle_pc_account: UncheckedAccount<'info>,
+
+    /// SPL Token program
+    pub token_program: Program<'info, Token>,
+
+    /// Global emergency-pause registry
+    #[account(
+        seeds = [b"protocol-status"],
+        bump = protocol_status.bump,
+        seeds::program = vectai_protocol::ID,
+        constraint = !protocol_status.paused @ SwapError::ProtocolPaused
+    )]
+    pub protocol_status: Account<'info, ProtocolStatus>,
+
+    /// Shared network addresses
+    #[account(
+        seeds = [b"network-config"],
+        bump = network_config.bump,
+        seeds::program = vectai_protocol::ID
+    )]
+    pub network_config: Account<'info, NetworkConfig>,
+
+    /// CHECK: Protocol stats PDA, validated by vectai_protocol
+    #[account(mut)]
+    pub protocol_stats: UncheckedAccount<'info>,
+
+    /// CHECK: Signs `record_activity` for this program
+    #[account(seeds = [b"stats-reporter"], bump)]
+    pub stats_reporter: UncheckedAccount<'info>,
+
+    pub protocol_program: Program<'info, VectaiProtocol>,
+
+    /// Treasury vault for the source mint; required while the swap fee is on
+    #[account(mut)]
+    pub fee_account: Option<Account<'info, TokenAccount>>,
+}
+
+/// Lifinity's `swap(amount_in, minimum_amount_out)`, from the user's
+/// wallet accounts
+pub fn invoke_lifinity_swap(accounts: &LifinitySwapAccounts, amount_in: u64, min_amount_out: u64) -> Result<()> {
+    let mut data = hash(b"global:swap").to_bytes()[..8].to_vec();
+    data.extend_from_slice(&amount_in.to_le_bytes());
+    data.extend_from_slice(&min_amount_out.to_le_bytes());
+
+    let ix = Instruction {
+        program_id: accounts.lifinity_program.key(),
+        accounts: vec![
+            AccountMeta::new_readonly(accounts.amm_authority.key(), false),
+            AccountMeta::new_readonly(accounts.amm.key(), false),
+            AccountMeta::new_readonly(accounts.user_authority.key(), true),
+            AccountMeta::new(accounts.user_source_token.key(), false),
+            AccountMeta::new(accounts.user_destination_token.key(), false),
+            AccountMeta::new(accounts.swap_source.key(), false),
+            AccountMeta::new(accounts.swap_destination.key(), false),
+            AccountMeta::new(accounts.pool_mint.key(), false),
+            AccountMeta::new(accounts.pool_fee_account.key(), false),
+            AccountMeta::new_readonly(accounts.token_program.key(), false),
+            AccountMeta::new_readonly(accounts.oracle_main_account.key(), false),
+            AccountMeta::new_readonly(accounts.oracle_sub_account.key(), false),
+            AccountMeta::new_readonly(accounts.oracle_pc_account.key(), false),
+        ],
+        data,
+    };
+    invoke(
+        &ix,
+        &[
+            accounts.amm_authority.to_account_info(),
+            accounts.amm.to_account_info(),
+            accounts.user_authority.to_account_info(),
+            accounts.user_source_token.to_account_info(),
+            accounts.user_destination_token.to_account_info(),
+            accounts.swap_source.to_account_info(),
+            accounts.swap_destination.to_account_info(),
+            accounts.pool_mint.to_account_info(),
+            accounts.pool_fee_account.to_account_info(),
+            accounts.token_program.to_account_info(),
+            accounts.oracle_main_account.to_account_info(),
+            accounts.oracle_sub_account.to_account_info(),
+            accounts.oracle_pc_account.to_account_info(),
+        ],
+    )?;
+    Ok(())
+}
@@ -0,0 +1,174 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token::{Token, TokenAccount};
+use vectai_protocol::{NetworkConfig, ProtocolStatus};
+
+use crate::{pool_config::*, SwapError};
+
+// Raydium AMM deposit / withdraw instruction discriminators
+pub const RAYDIUM_DEPOSIT_INSTRUCTION: u8 = 3;
+pub const RAYDIUM_WITHDRAW_INSTRUCTION: u8 = 4;
+
+// ===== ACCOUNTS STRUCT =====
+/// Accounts for adding liquidity to, or removing it from, the whitelisted
+/// pool; the Serum vault accounts are only passed through on withdraw
+#[derive(Accounts)]
+pub struct LiquidityAccounts<'info> {
+    /// Liquidity provider (must sign the transaction)
+    pub user_authority: Signer<'info>,
+
+    /// Provider's coin token account
+    #[account(mut)]
+    pub user_coin_token: Account<'info, TokenAccount>,
+
+    /// Provider's PC token account
+    #[account(mut)]
+    pub user_pc_token: Account<'info, TokenAccount>,
+
+    /// Provider's LP token account
+    #[account(mut)]
+    pub user_lp_token: Account<'info, TokenAccount>,
+
+    /// Raydium AMM program
+    /// CHECK: Validated against the network config
+    pub raydium_amm_program: UncheckedAccount<'info>,
+
+    /// AMM pool state account
+    /// CHECK: Validated against whitelist
+    #[account(mut)]
+    pub amm: UncheckedAccount<'info>,
+
+    /// AMM authority (PDA)
+    /// CHECK: Validated against whitelist
+    pub amm_authority: UncheckedAccount<'info>,
+
+    /// AMM open orders account
+    /// CHECK: Validated against whitelist
+    #[account(mut)]
+    pub amm_open_orders: UncheckedAccount<'info>,
+
+    /// AMM target orders account
+    /// CHECK: Validated against whitelist
+    #[account(mut)]
+    pub amm_target_orders: UncheckedAccount<'info>,
+
+    /// Pool LP mint
+    /// CHECK: Validated against whitelist
+    #[account(mut)]
+    pub amm_lp_mint: UncheckedAccount<'info>,
+
+    /// Pool's coin token account
+    /// CHECK: Validated against whitelist
+    #[account(mut)]
+    pub pool_coin_token_account: UncheckedAccount<'info>,
+
+    /// Pool's PC token account
+    /// CHECK: Validated against whitelist
+    #[account(mut)]
+    pub pool_pc_token_account: UncheckedAccount<'info>,
+
+    /// Serum DEX program
+    /// CHECK: Validated against whitelist
+    pub serum_program: UncheckedAccount<'info>,
+
+    /// Serum market
+    /// CHECK: Validated against whitelist
+    #[account(mut)]
+    pub serum_market: UncheckedAccount<'info>,
+
+    /// Serum bids
+    /// CHECK: Validated against whitelist
+    #[account(mut)]
+    pub serum_bids: UncheckedAccount<'info>,
+
+    /// Serum asks
+    /// CHECK: Validated against whitelist
+    #[account(mut)]
+    pub serum_asks: UncheckedAccount<'info>,
+
+    /// Serum event queue
+    /// CHECK: Validated against whitelist
+    #[account(mut)]
+    pub serum_event_queue: UncheckedAccount<'info>,
+
+    /// Serum coin vault
+    /// CHECK: Validated against whitelist
+    #[account(mut)]
+    pub serum_coin_vault: UncheckedAccount<'info>,
+
+    /// Serum PC vault
+    /// CHECK: Validated against whitelist
+    #[account(mut)]
+    pub serum_pc_vault: UncheckedAccount<'info>,
+
+    /// Serum vault signer
+    /// CHECK: Validated against whitelist
+    pub serum_vault_signer: UncheckedAccount<'info>,
+
+    /// SPL Token program
+    pub token_program: Program<'info, Token>,
+
+    /// Global emergency-pause registry
+    #[account(
+        seeds = [b"protocol-status"],
+        bump = protocol_status.bump,
+        seeds::program = vectai_protocol::ID,
+        constraint = !protocol_status.paused @ SwapError::ProtocolPaused
+    )]
+    pub protocol_status: Account<'info, ProtocolStatus>,
+
+    /// Shared network addresses
+    #[account(
+        seeds = [b"network-config"],
+        bump = network_config.bump,
+        seeds::program = vectai_protocol::ID
+    )]
+    pub network_config: Account<'info, NetworkConfig>,
+}
+
+impl LiquidityAccounts<'_> {
+    /// Raydium program, token pair, ownership and pool whitelist checks
+    /// shared by add and remove
+    pub fn validate(&self) -> Result<()> {
+        require!(
+            self.raydium_amm_program.key() == self.network_config.raydium_amm_program,
+            SwapError::InvalidRaydiumProgram
+        );
+        require!(
+            self.network_config
+                .is_supported_pair(self.user_coin_token.mint, self.user_pc_token.mint),
+            SwapError::InvalidTokenPair
+        );
+
+        let owner = self.user_authority.key();
+        require!(
+            self.user_coin_token.owner == owner
+                && self.user_pc_token.owner == owner
+                && self.user_lp_token.owner == owner,
+            SwapError::InvalidOwner
+        );
+        require!(self.user_lp_token.mint == AMM_LP_MINT, SwapError::InvalidPool);
+
+        require!(self.amm.key() == AMM_ID, SwapError::InvalidPool);
+        require!(self.amm_authority.key() == AMM_AUTHORITY, SwapError::InvalidPool);
+        require!(self.amm_open_orders.key() == AMM_OPEN_ORDERS, SwapError::InvalidPool);
+        require!(self.amm_target_orders.key() == AMM_TARGET_ORDERS, SwapError::InvalidPool);
+        require!(self.amm_lp_mint.key() == AMM_LP_MINT, SwapError::InvalidPool);
+        require!(
+            self.pool_coin_token_account.key() == POOL_COIN_TOKEN_ACCOUNT,
+            SwapError::InvalidPool
+        );
+        require!(
+            self.pool_pc_token_account.key() == POOL_PC_TOKEN_ACCOUNT,
+            SwapError::InvalidPool
+        );
+        require!(self.serum_program.key() == SERUM_PROGRAM, SwapError::InvalidPool);
+        require!(self.serum_market.key() == SERUM_MARKET, SwapError::InvalidPool);
+        require!(self.serum_bids.key() == SERUM_BIDS, SwapError::InvalidPool);
+        require!(self.serum_asks.key() == SERUM_ASKS, SwapError::InvalidPool);
+        require!(self.serum_event_queue.key() == SERUM_EVENT_QUEUE, SwapError::InvalidPool);
+        require!(self.serum_coin_vault.key() == SERUM_COIN_VAULT, SwapError::InvalidPool);
+        require!(self.serum_pc_vault.key() == SERUM_PC_VAULT, SwapError::InvalidPool);
+        require!(self.serum_vault_signer.key() == SERUM_VAULT_SIGNER, SwapError::InvalidPool);
+        Ok(())
+    }
+}
@@ -0,0 +1,155 @@
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::{hash::hash, instruction::Instruction, program::invoke};
+use anchor_spl::token::{Token, TokenAccount};
+use vectai_protocol::{program::VectaiProtocol, NetworkConfig, ProtocolStatus};
+
+use crate::SwapError;
+
+/// Meteora DLMM (`lb_clmm`) program
+pub const METEORA_DLMM_PROGRAM: Pubkey =
+    anchor_lang::solana_program::pubkey!("LBUZKhRxPF3XUpBCjp4YzTKgLccjZhTSDM9YuVaPwxo");
+
+// ===== ACCOUNTS STRUCT =====
+/// Accounts for a swap on a Meteora DLMM pair; the bin arrays the swap
+/// crosses follow as writable remaining accounts, in the order DLMM
+/// traverses them. The pair's reserves, mints and oracle are checked by
+/// DLMM against the pair itself.
+#[derive(Accounts)]
+pub struct MeteoraSwapAccounts<'info> {
+    /// User's wallet (must sign the transaction)
+    pub user_authority: Signer<'info>,
+
+    /// User's source token account (tokens being swapped FROM)
+    #[account(mut)]
+    pub user_source_token: Account<'info, TokenAccount>,
+
+    /// User's destination token account (tokens being swapped TO)
+    #[account(mut)]
+    pub user_destination_token: Account<'info, TokenAccount>,
+
+    /// Meteora DLMM program
+    /// CHECK: Address-checked
+    #[account(address = METEORA_DLMM_PROGRAM @ SwapError::InvalidPool)]
+    pub dlmm_program: UncheckedAccount<'info>,
+
+    /// DLMM pair state
+    /// CHECK: Must be owned by DLMM, which validates the rest against it
+    #[account(mut, owner = METEORA_DLMM_PROGRAM @ SwapError::InvalidPool)]
+    pub lb_pair: UncheckedAccount<'info>,
+
+    /// Pair's token X reserve
+    /// CHECK: Validated by DLMM
+    #[account(mut)]
+    pub reserve_x: UncheckedAccount<'info>,
+
+    /// Pair's token Y reserve
+    /// CHECK: Validated by DLMM
+    #[account(mut)]
+    pub reserve_y: UncheckedAccount<'info>,
+
+    /// CHECK: Validated by DLMM
+    pub token_x_mint: UncheckedAccount<'info>,
+
+    /// CHECK: Validated by DLMM
+    pub token_y_mint: UncheckedAccount<'info>,
+
+    /// Pair's price oracle
+    /// CHECK: Validated by DLMM
+    #[account(mut)]
+    pub oracle: UncheckedAccount<'info>,
+
+    /// CHECK: DLMM's event authority PDA, validated by DLMM
+    pub event_authority: UncheckedAccount<'info>,
+
+    /// SPL Token program, for both sides of the pair
+    pub token_program: Program<'info, Token>,
+
+    /// Global emergency-pause registry
+    #[account(
+        seeds = [b"protocol-status"],
+        bump = protocol_status.bump,
+        seeds::program = vectai_protocol::ID,
+        constraint = !protocol_status.paused @ SwapError::ProtocolPaused
+    )]
+    pub protocol_status: Account<'info, ProtocolStatus>,
+
+    /// Shared network addresses
+    #[account(
+        seeds = [b"network-config"],
+        bump = network_config.bump,
+        seeds::program = vectai_protocol::ID
+    )]
+    pub network_config: Account<'info, NetworkConfig>,
+
+    /// CHECK: Protocol stats PDA, validated by vectai_protocol
+    #[account(mut)]
+    pub protocol_stats: UncheckedAccount<'info>,
+
+    /// CHECK: Signs `record_activity` for this program
+    #[account(seeds = [b"stats-reporter"], bump)]
+    pub stats_reporter: UncheckedAccount<'info>,
+
+    pub protocol_program: Program<'info, VectaiProtocol>,
+
+    /// Treasury vault for the source mint; required while the swap fee is on
+    #[account(mut)]
+    pub fee_account: Option<Account<'info, TokenAccount>>,
+}
+
+/// Call DLMM's `swap` (exact input) for `amount_in`, failing in DLMM when
+/// less than `min_amount_out` comes out
+pub fn invoke_dlmm_swap<'info>(
+    accounts: &MeteoraSwapAccounts<'info>,
+    bin_arrays: &[AccountInfo<'info>],
+    amount_in: u64,
+    min_amount_out: u64,
+) -> Result<()> {
+    let mut data = hash(b"global:swap").to_bytes()[..8].to_vec();
+    data.extend_from_slice(&amount_in.to_le_bytes());
+    data.extend_from_slice(&min_amount_out.to_le_bytes());
+
+    // Optional accounts left out are passed as the DLMM program itself
+    let program = accounts.dlmm_program.key();
+    let mut metas = vec![
+        AccountMeta::new(accounts.lb_pair.key(), false),
+        AccountMeta::new_readonly(program, false), // bin_array_bitmap_extension
+        AccountMeta::new(accounts.reserve_x.key(), false),
+        AccountMeta::new(accounts.reserve_y.key(), false),
+        AccountMeta::new(accounts.user_source_token.key(), false),
+        AccountMeta::new(accounts.user_destination_token.key(), false),
+        AccountMeta::new_readonly(accounts.token_x_mint.key(), false),
+        AccountMeta::new_readonly(accounts.token_y_mint.key(), false),
+        AccountMeta::new(accounts.oracle.key(), false),
+        AccountMeta::new_readonly(program, false), // host_fee_in
+        AccountMeta::new_readonly(accounts.user_authority.key(), true),
+        AccountMeta::new_readonly(accounts.token_program.key(), false), // token_x_program
+        AccountMeta::new_readonly(accounts.token_program.key(), false), // token_y_program
+        AccountMeta::new_readonly(accounts.event_authority.key(), false),
+        AccountMeta::new_readonly(program, false),
+    ];
+    metas.extend(bin_arrays.iter().map(|bin_array| AccountMeta::new(bin_array.key(), false)));
+
+    let mut infos = vec![
+        accounts.lb_pair.to_account_info(),
+        accounts.dlmm_program.to_account_info(),
+        accounts.reserve_x.to_account_info(),
+        accounts.reserve_y.to_account_info(),
+        accounts.user_source_token.to_account_info(),
+        accounts.user_destination_token.to_account_info(),
+        accounts.token_x_mint.to_account_info(),
+        accounts.token_y_mint.to_account_info(),
+        accounts.oracle.to_account_info(),
+        accounts.user_authority.to_account_info(),
+        accounts.token_program.to_account_info(),
+        accounts.event_authority.to_account_info(),
+    ];
+    infos.extend(bin_arrays.iter().cloned());
+
+    let ix = Instruction {
+        program_id: program,
+        accounts: metas,
+        data,
+    };
+    invoke(&ix, &infos)?;
+    Ok(())
+}
@@ -0,0 +1,233 @@
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::{instruction::Instruction, program::invoke};
+use anchor_spl::token::{Token, TokenAccount};
+use vectai_protocol::{program::VectaiProtocol, NetworkConfig, ProtocolStatus, VenueRegistry};
+
+use crate::SwapError;
+
+/// Phoenix v1 order-book program
+pub const PHOENIX_PROGRAM: Pubkey =
+    anchor_lang::solana_program::pubkey!("PhoeNiXZ8ByJGLkxNfZRnkUfjvmuYqLR89jjFHGqdXY");
+
+// Phoenix instruction tag and order-packet encodings (borsh u8 enums)
+const PHOENIX_SWAP_INSTRUCTION: u8 = 0;
+const ORDER_PACKET_IMMEDIATE_OR_CANCEL: u8 = 2;
+pub const SIDE_BID: u8 = 0;
+pub const SIDE_ASK: u8 = 1;
+const SELF_TRADE_ABORT: u8 = 0;
+
+// ===== ACCOUNTS STRUCT =====
+/// Accounts for taking liquidity from the Phoenix market listed for
+/// WSOL/USDC in the venue registry
+#[derive(Accounts)]
+pub struct PhoenixSwapAccounts<'info> {
+    /// User's wallet (must sign the transaction)
+    pub user_authority: Signer<'info>,
+
+    /// User's source token account (tokens being swapped FROM)
+    #[account(mut)]
+    pub user_source_token: Account<'info, TokenAccount>,
+
+    /// User's destination token account (tokens being swapped TO)
+    #[account(mut)]
+    pub user_destination_token: Account<'info, TokenAccount>,
+
+    /// Phoenix program
+    /// CHECK: Address-checked
+    #[account(address = PHOENIX_PROGRAM @ SwapError::InvalidPool)]
+    pub phoenix_program: UncheckedAccount<'info>,
+
+    /// CHECK: Phoenix's log authority PDA, validated by Phoenix
+    pub log_authority: UncheckedAccount<'info>,
+
+    /// Phoenix market
+    /// CHECK: Must be the registered market and owned by Phoenix
+    #[account(
+        mut,
+        owner = PHOENIX_PROGRAM @ SwapError::InvalidPool,
+        constraint = venue_registry.venues.phoenix_market == market.key() @ SwapError::InvalidPool
+    )]
+    pub market: UncheckedAccount<'info>,
+
+    /// Market's base (SOL) vault
+    /// CHECK: Checked against the market header
+    #[account(mut)]
+    pub base_vault: UncheckedAccount<'info>,
+
+    /// Market's quote (USDC) vault
+    /// CHECK: Checked against the market header
+    #[account(mut)]
+    pub quote_vault: UncheckedAccount<'info>,
+
+    /// SPL Token program
+    pub token_program: Program<'info, Token>,
+
+    /// Venues listed for WSOL/USDC
+    #[account(
+        seeds = [b"venue-registry", network_config.wsol_mint.as_ref(), network_config.usdc_mint.as_ref()],
+        bump = venue_registry.bump,
+        seeds::program = vectai_protocol::ID
+    )]
+    pub venue_registry: Account<'info, VenueRegistry>,
+
+    /// Global emergency-pause registry
+    #[account(
+        seeds = [b"protocol-status"],
+        bump = protocol_status.bump,
+        seeds::program = vectai_protocol::ID,
+        constraint = !protocol_status.paused @ SwapError::ProtocolPaused
+    )]
+    pub protocol_status: Account<'info, ProtocolStatus>,
+
+    /// Shared network addresses
+    #[account(
+        seeds = [b"network-config"],
+        bump = network_config.bump,
+        seeds::program = vectai_protocol::ID
+    )]
+    pub network_config: Account<'info, NetworkConfig>,
+
+    /// CHECK: Protocol stats PDA, validated by vectai_protocol
+    #[account(mut)]
+    pub protocol_stats: UncheckedAccount<'info>,
+
+    /// CHECK: Signs `record_activity` for this program
+    #[account(seeds = [b"stats-reporter"], bump)]
+    pub stats_reporter: UncheckedAccount<'info>,
+
+    pub protocol_program: Program<'info, VectaiProtocol>,
+
+    /// Treasury vault for the source mint; required while the swap fee is on
+    #[account(mut)]
+    pub fee_account: Option<Account<'info, TokenAccount>>,
+}
+
+/// The part of a Phoenix `MarketHeader` a swap needs
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct PhoenixMarket {
+    pub base_mint: Pubkey,
+    pub base_vault: Pubkey,
+    pub base_lot_size: u64,
+    pub quote_mint: Pubkey,
+    pub quote_vault: Pubkey,
+    pub quote_lot_size: u64,
+}
+
+impl PhoenixMarket {
+    /// Read the header at the start of a market account: discriminant,
+    /// status and size params, then the base token params and lot size,
+    /// then the quote token params and lot size
+    pub fn parse(data: &[u8]) -> Option<Self> {
+        let pubkey = |at: usize| Pubkey::try_from(data.get(at..at + 32)?).ok();
+        let u64_at = |at: usize| Some(u64::from_le_bytes(data.get(at..at + 8)?.try_into().ok()?));
+        let market = PhoenixMarket {
+            base_mint: pubkey(48)?,
+            base_vault: pubkey(80)?,
+            base_lot_size: u64_at(112)?,
+            quote_mint: pubkey(128)?,
+            quote_vault: pubkey(160)?,
+            quote_lot_size: u64_at(192)?,
+        };
+        (market.base_lot_size > 0 && market.quote_lot_size > 0).then_some(market)
+    }
+}
+
+/// An immediate-or-cancel order filling at least `min_amount_out`, in
+/// the market's lots: a bid spends `amount_in` quote, an ask sells
+/// `amount_in` base. Returns the side, the lots it spends and the input
+/// those lots amount to.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct IocOrder {
+    pub side: u8,
+    pub num_base_lots: u64,
+    pub num_quote_lots: u64,
+    pub min_base_lots_to_fill: u64,
+    pub min_quote_lots_to_fill: u64,
+}
+
+impl IocOrder {
+    /// `None` when `source_mint` is neither side of `market`
+    pub fn new(market: &PhoenixMarket, source_mint: &Pubkey, amount_in: u64, min_amount_out: u64) -> Option<Self> {
+        if *source_mint == market.quote_mint {
+            Some(IocOrder {
+                side: SIDE_BID,
+                num_base_lots: 0,
+                num_quote_lots: amount_in / market.quote_lot_size,
+                min_base_lots_to_fill: min_amount_out.div_ceil(market.base_lot_size),
+                min_quote_lots_to_fill: 0,
+            })
+        } else if *source_mint == market.base_mint {
+            Some(IocOrder {
+                side: SIDE_ASK,
+                num_base_lots: amount_in / market.base_lot_size,
+                num_quote_lots: 0,
+                min_base_lots_to_fill: 0,
+                min_quote_lots_to_fill: min_amount_out.div_ceil(market.quote_lot_size),
+            })
+        } else {
+            None
+        }
+    }
+
+    /// Input tokens the order spends, whole lots only
+    pub fn amount_in(&self, market: &PhoenixMarket) -> u64 {
+        self.num_base_lots * market.base_lot_size + self.num_quote_lots * market.quote_lot_size
+    }
+
+    /// Phoenix `Swap` instruction data: the tag, then the borsh
+    /// `OrderPacket::ImmediateOrCancel` at market price
+    pub fn data(&self) -> Vec<u8> {
+        let mut data = vec![PHOENIX_SWAP_INSTRUCTION, ORDER_PACKET_IMMEDIATE_OR_CANCEL, self.side];
+        data.push(0); // price_in_ticks: None
+        data.extend_from_slice(&self.num_base_lots.to_le_bytes());
+        data.extend_from_slice(&self.num_quote_lots.to_le_bytes());
+        data.extend_from_slice(&self.min_base_lots_to_fill.to_le_bytes());
+        data.extend_from_slice(&self.min_quote_lots_to_fill.to_le_bytes());
+        data.push(SELF_TRADE_ABORT);
+        data.push(0); // match_limit: None
+        data.extend_from_slice(&0u128.to_le_bytes()); // client_order_id
+        data.push(0); // use_only_deposited_funds
+        data.push(0); // last_valid_slot: None
+        data.push(0); // last_valid_unix_timestamp_in_seconds: None
+        data
+    }
+}
+
+/// Send `order` to Phoenix as a `Swap` from the user's wallet accounts
+pub fn invoke_phoenix_swap(accounts: &PhoenixSwapAccounts, order: &IocOrder) -> Result<()> {
+    let (base_account, quote_account) = if order.side == SIDE_BID {
+        (&accounts.user_destination_token, &accounts.user_source_token)
+    } else {
+        (&accounts.user_source_token, &accounts.user_destination_token)
+    };
+    let ix = Instruction {
+        program_id: accounts.phoenix_program.key(),
+        accounts: vec![
+            AccountMeta::new_readonly(accounts.phoenix_program.key(), false),
+            AccountMeta::new_readonly(accounts.log_authority.key(), false),
+            AccountMeta::new(accounts.market.key(), false),
+            AccountMeta::new_readonly(accounts.user_authority.key(), true),
+            AccountMeta::new(base_account.key(), false),
+            AccountMeta::new(quote_account.key(), false),
+            AccountMeta::new(accounts.base_vault.key(), false),
+            AccountMeta::new(accounts.quote_vault.key(), false),
+            AccountMeta::new_readonly(accounts.token_program.key(), false),
+        ],
+        data: order.data(),
+    };
+    invoke(
+        &ix,
+        &[
+            accounts.phoenix_program.to_account_info(),
+            accounts.log_authority.to_account_info(),
+            accounts.market.to_account_info(),
+            accounts.user_authority.to_account_info(),
+            base_account.to_account_info(),
+            quote_account.to_account_info(),
+            accounts.base_vault.to_account_info(),
+            accounts.quote_vault.to_account_info(),
+            accounts.token_program.to_account_info(),
+        ],
+    )?;
+    Ok(())
+}
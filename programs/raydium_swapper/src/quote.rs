@@ -0,0 +1,67 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token::TokenAccount;
+use vectai_common::BPS_DENOMINATOR;
+use vectai_protocol::ProtocolStatus;
+
+use crate::{pool_config, SwapError};
+
+/// Raydium AMM v4's trade fee, kept by the pool on the input
+pub const RAYDIUM_TRADE_FEE_BPS: u64 = 25;
+
+#[derive(Accounts)]
+pub struct GetQuote<'info> {
+    /// Pool's coin token account
+    #[account(address = pool_config::POOL_COIN_TOKEN_ACCOUNT @ SwapError::InvalidPool)]
+    pub pool_coin_token_account: Account<'info, TokenAccount>,
+
+    /// Pool's PC token account
+    #[account(address = pool_config::POOL_PC_TOKEN_ACCOUNT @ SwapError::InvalidPool)]
+    pub pool_pc_token_account: Account<'info, TokenAccount>,
+
+    /// Global registry holding the protocol swap fee
+    #[account(
+        seeds = [b"protocol-status"],
+        bump = protocol_status.bump,
+        seeds::program = vectai_protocol::ID
+    )]
+    pub protocol_status: Account<'info, ProtocolStatus>,
+}
+
+/// Direction of a quoted swap
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub struct QuotePair {
+    pub input_mint: Pubkey,
+    pub output_mint: Pubkey,
+}
+
+/// What `swap` of `amount_in` on the whitelisted pool would pay out at
+/// its current reserves, as returned by `get_quote`
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug, PartialEq, Eq)]
+pub struct SwapQuote {
+    pub expected_out: u64,
+    pub fee: u64, // Protocol swap fee plus the pool's trade fee, in input units
+    pub price_impact_bps: u64, // Shortfall of `expected_out` against the reserve ratio
+}
+
+impl SwapQuote {
+    /// Quote `amount_in` into a pool of `reserve_in`/`reserve_out` after
+    /// `swap_fee_bps` and `RAYDIUM_TRADE_FEE_BPS`, both taken on the input
+    pub fn constant_product(amount_in: u64, reserve_in: u64, reserve_out: u64, swap_fee_bps: u64) -> Option<Self> {
+        let swap_fee = vectai_common::bps_of(amount_in, swap_fee_bps);
+        let trade_fee = vectai_common::bps_of(amount_in - swap_fee, RAYDIUM_TRADE_FEE_BPS);
+        let fee = swap_fee + trade_fee;
+        let net_in = (amount_in - fee) as u128;
+
+        let expected_out = (reserve_out as u128).checked_mul(net_in)?.checked_div(reserve_in as u128 + net_in)?;
+        let spot_out = (reserve_out as u128).checked_mul(net_in)?.checked_div(reserve_in as u128)?;
+        let price_impact_bps = match spot_out {
+            0 => 0,
+            _ => (spot_out - expected_out) * BPS_DENOMINATOR as u128 / spot_out,
+        };
+        Some(SwapQuote {
+            expected_out: expected_out as u64,
+            fee,
+            price_impact_bps: price_impact_bps as u64,
+        })
+    }
+}
@@ -0,0 +1,164 @@
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::{instruction::Instruction, program::invoke};
+use anchor_spl::token::{Token, TokenAccount};
+use vectai_protocol::{program::VectaiProtocol, NetworkConfig, ProtocolStatus, VenueRegistry};
+
+use crate::SwapError;
+
+/// Saber StableSwap program; Raydium's stable pools take the same swap
+pub const STABLE_SWAP_PROGRAM: Pubkey =
+    anchor_lang::solana_program::pubkey!("SSwpkEEcbUqx4vtoEByFjSkhKdCT862DNVb52nZg1UZ");
+
+// StableSwap instruction tag for a swap
+const STABLE_SWAP_INSTRUCTION: u8 = 1;
+
+// ===== ACCOUNTS STRUCT =====
+/// Accounts for a swap between two pegged assets (e.g. USDT <-> USDC) on
+/// the stable pool the venue registry lists for the pair. The pool's
+/// vaults and admin fee account are checked by the pool program.
+#[derive(Accounts)]
+pub struct StableSwapAccounts<'info> {
+    /// User's wallet (must sign the transaction)
+    pub user_authority: Signer<'info>,
+
+    /// User's source token account (tokens being swapped FROM)
+    #[account(mut)]
+    pub user_source_token: Account<'info, TokenAccount>,
+
+    /// User's destination token account (tokens being swapped TO)
+    #[account(mut)]
+    pub user_destination_token: Account<'info, TokenAccount>,
+
+    /// StableSwap program
+    /// CHECK: Address-checked
+    #[account(address = STABLE_SWAP_PROGRAM @ SwapError::InvalidPool)]
+    pub stable_swap_program: UncheckedAccount<'info>,
+
+    /// Stable pool
+    /// CHECK: Must be the registered pool and owned by StableSwap
+    #[account(
+        owner = STABLE_SWAP_PROGRAM @ SwapError::InvalidPool,
+        constraint = venue_registry.venues.stable_swap_pool == pool.key() @ SwapError::InvalidPool
+    )]
+    pub pool: UncheckedAccount<'info>,
+
+    /// CHECK: The pool's vault authority PDA, validated by StableSwap
+    pub pool_authority: UncheckedAccount<'info>,
+
+    /// Pool vault of the source mint
+    /// CHECK: Validated by StableSwap
+    #[account(mut)]
+    pub pool_source: UncheckedAccount<'info>,
+
+    /// Pool vault of the destination mint
+    /// CHECK: Validated by StableSwap
+    #[account(mut)]
+    pub pool_destination: UncheckedAccount<'info>,
+
+    /// CHECK: The pool's admin fee account for the destination mint,
+    /// validated by StableSwap
+    #[account(mut)]
+    pub admin_fee_destination: UncheckedAccount<'info>,
+
+    /// SPL Token program
+    pub token_program: Program<'info, Token>,
+
+    /// Venues listed for the pair being swapped
+    #[account(
+        seeds = [b"venue-registry", venue_registry.base_mint.as_ref(), venue_registry.quote_mint.as_ref()],
+        bump = venue_registry.bump,
+        seeds::program = vectai_protocol::ID
+    )]
+    pub venue_registry: Account<'info, VenueRegistry>,
+
+    /// Global emergency-pause registry
+    #[account(
+        seeds = [b"protocol-status"],
+        bump = protocol_status.bump,
+        seeds::program = vectai_protocol::ID,
+        constraint = !protocol_status.paused @ SwapError::ProtocolPaused
+    )]
+    pub protocol_status: Account<'info, ProtocolStatus>,
+
+    /// Shared network addresses
+    #[account(
+        seeds = [b"network-config"],
+        bump = network_config.bump,
+        seeds::program = vectai_protocol::ID
+    )]
+    pub network_config: Account<'info, NetworkConfig>,
+
+    /// CHECK: Protocol stats PDA, validated by vectai_protocol
+    #[account(mut)]
+    pub protocol_stats: UncheckedAccount<'info>,
+
+    /// CHECK: Signs `record_activity` for this program
+    #[account(seeds = [b"stats-reporter"], bump)]
+    pub stats_reporter: UncheckedAccount<'info>,
+
+    pub protocol_program: Program<'info, VectaiProtocol>,
+
+    /// Treasury vault for the source mint; required while the swap fee is on
+    #[account(mut)]
+    pub fee_account: Option<Account<'info, TokenAccount>>,
+}
+
+/// Check a stable swap is between the registry's two mints, from an
+/// account `authority` owns holding at least `amount_in`
+pub fn check_stable_swap_request(
+    registry: &VenueRegistry,
+    source: &TokenAccount,
+    destination: &TokenAccount,
+    authority: &Pubkey,
+    amount_in: u64,
+) -> Result<()> {
+    let pair = [registry.base_mint, registry.quote_mint];
+    require!(
+        source.mint != destination.mint && pair.contains(&source.mint) && pair.contains(&destination.mint),
+        SwapError::InvalidTokenPair
+    );
+    require!(source.owner == *authority, SwapError::InvalidOwner);
+    require!(amount_in > 0, SwapError::InvalidAmount);
+    require!(source.amount >= amount_in, SwapError::InsufficientBalance);
+    Ok(())
+}
+
+/// StableSwap's `Swap { amount_in, minimum_amount_out }` from the user's
+/// wallet accounts
+pub fn invoke_stable_swap(accounts: &StableSwapAccounts, amount_in: u64, min_amount_out: u64) -> Result<()> {
+    let mut data = Vec::with_capacity(17);
+    data.push(STABLE_SWAP_INSTRUCTION);
+    data.extend_from_slice(&amount_in.to_le_bytes());
+    data.extend_from_slice(&min_amount_out.to_le_bytes());
+
+    let ix = Instruction {
+        program_id: accounts.stable_swap_program.key(),
+        accounts: vec![
+            AccountMeta::new_readonly(accounts.pool.key(), false),
+            AccountMeta::new_readonly(accounts.pool_authority.key(), false),
+            AccountMeta::new_readonly(accounts.user_authority.key(), true),
+            AccountMeta::new(accounts.user_source_token.key(), false),
+            AccountMeta::new(accounts.pool_source.key(), false),
+            AccountMeta::new(accounts.pool_destination.key(), false),
+            AccountMeta::new(accounts.user_destination_token.key(), false),
+            AccountMeta::new(accounts.admin_fee_destination.key(), false),
+            AccountMeta::new_readonly(accounts.token_program.key(), false),
+        ],
+        data,
+    };
+    invoke(
+        &ix,
+        &[
+            accounts.pool.to_account_info(),
+            accounts.pool_authority.to_account_info(),
+            accounts.user_authority.to_account_info(),
+            accounts.user_source_token.to_account_info(),
+            accounts.pool_source.to_account_info(),
+            accounts.pool_destination.to_account_info(),
+            accounts.user_destination_token.to_account_info(),
+            accounts.admin_fee_destination.to_account_info(),
+            accounts.token_program.to_account_info(),
+        ],
+    )?;
+    Ok(())
+}
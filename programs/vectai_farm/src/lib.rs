@@ -0,0 +1,547 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token::{self, Mint, Token, TokenAccount, Transfer};
+use vectai_protocol::ProtocolStatus;
+use vectai_common::is_admin;
+
+declare_id!("CE1CZDJfc6ns7w5BrWXP8mnKELmzAibtfT69mVXEVvtv");
+
+#[program]
+pub mod vectai_farm {
+    use super::*;
+
+    /// Create the farm for a Raydium LP mint with its LP and VECTAI reward
+    /// vaults and initial emission schedule (admin only)
+    pub fn initialize_farm(
+        ctx: Context<InitializeFarm>,
+        reward_per_slot: u64,
+        start_slot: u64,
+        end_slot: u64,
+    ) -> Result<()> {
+        require!(
+            is_admin(&ctx.accounts.admin.key()),
+            FarmError::UnauthorizedAdmin
+        );
+        require!(end_slot > start_slot, FarmError::InvalidEmissionSchedule);
+
+        let farm = &mut ctx.accounts.farm;
+        farm.lp_mint = ctx.accounts.lp_mint.key();
+        farm.lp_vault = ctx.accounts.lp_vault.key();
+        farm.reward_vault = ctx.accounts.reward_vault.key();
+        farm.reward_per_slot = reward_per_slot;
+        farm.start_slot = start_slot;
+        farm.end_slot = end_slot;
+        farm.last_reward_slot = start_slot;
+        farm.total_staked = 0;
+        farm.acc_reward_per_share = 0;
+        farm.total_emitted = 0;
+        farm.bump = ctx.bumps.farm;
+
+        msg!("✅ Farm initialized: {} VECTAI per slot, slots {}..{}", reward_per_slot, start_slot, end_slot);
+        Ok(())
+    }
+
+    /// Change the emission rate and end slot; accrues at the old rate
+    /// first (timelock only)
+    pub fn set_emissions(ctx: Context<SetEmissions>, reward_per_slot: u64, end_slot: u64) -> Result<()> {
+        require!(
+            is_timelock(&ctx.accounts.admin.key()),
+            FarmError::UnauthorizedAdmin
+        );
+        let slot = Clock::get()?.slot;
+        require!(end_slot >= slot, FarmError::InvalidEmissionSchedule);
+
+        let farm = &mut ctx.accounts.farm;
+        farm.accrue(slot)?;
+        // A lapsed schedule restarts now rather than back-paying the gap
+        farm.last_reward_slot = farm.last_reward_slot.max(slot);
+        farm.reward_per_slot = reward_per_slot;
+        farm.end_slot = end_slot;
+
+        emit!(EmissionsUpdated {
+            farm: farm.key(),
+            reward_per_slot,
+            end_slot,
+        });
+        msg!("Farm emissions: {} VECTAI per slot until slot {}", reward_per_slot, end_slot);
+        Ok(())
+    }
+
+    /// Stake LP tokens into the farm
+    pub fn deposit(ctx: Context<Deposit>, amount: u64) -> Result<()> {
+        // ✅ CHECKS
+        require!(amount > 0, FarmError::InvalidAmount);
+        require!(
+            ctx.accounts.owner_lp_account.amount >= amount,
+            FarmError::InsufficientBalance
+        );
+
+        // ✅ EFFECTS: Settle rewards at the old balance before changing it
+        let farm = &mut ctx.accounts.farm;
+        farm.accrue(Clock::get()?.slot)?;
+        let acc_reward_per_share = farm.acc_reward_per_share;
+
+        let position = &mut ctx.accounts.position;
+        if position.owner == Pubkey::default() {
+            position.owner = ctx.accounts.owner.key();
+            position.farm = farm.key();
+            position.bump = ctx.bumps.position;
+        }
+        position.settle(acc_reward_per_share)?;
+        position.amount = position
+            .amount
+            .checked_add(amount)
+            .ok_or(FarmError::MathOverflow)?;
+        position.checkpoint(acc_reward_per_share)?;
+
+        farm.total_staked = farm
+            .total_staked
+            .checked_add(amount)
+            .ok_or(FarmError::MathOverflow)?;
+
+        // ✅ INTERACTIONS
+        let cpi_accounts = Transfer {
+            from: ctx.accounts.owner_lp_account.to_account_info(),
+            to: ctx.accounts.lp_vault.to_account_info(),
+            authority: ctx.accounts.owner.to_account_info(),
+        };
+        let cpi_ctx = CpiContext::new(ctx.accounts.token_program.to_account_info(), cpi_accounts);
+        token::transfer(cpi_ctx, amount)?;
+
+        msg!("✅ Deposited {} LP (farm total: {})", amount, ctx.accounts.farm.total_staked);
+        Ok(())
+    }
+
+    /// Withdraw staked LP tokens; accrued rewards stay claimable
+    pub fn withdraw(ctx: Context<Withdraw>, amount: u64) -> Result<()> {
+        // ✅ CHECKS
+        require!(amount > 0, FarmError::InvalidAmount);
+        require!(
+            ctx.accounts.position.amount >= amount,
+            FarmError::InsufficientBalance
+        );
+
+        // ✅ EFFECTS
+        let farm = &mut ctx.accounts.farm;
+        farm.accrue(Clock::get()?.slot)?;
+        let acc_reward_per_share = farm.acc_reward_per_share;
+
+        let position = &mut ctx.accounts.position;
+        position.settle(acc_reward_per_share)?;
+        position.amount -= amount;
+        position.checkpoint(acc_reward_per_share)?;
+
+        farm.total_staked = farm
+            .total_staked
+            .checked_sub(amount)
+            .ok_or(FarmError::MathOverflow)?;
+
+        // ✅ INTERACTIONS: Farm PDA signs for its vault
+        let lp_mint = ctx.accounts.farm.lp_mint;
+        let seeds: &[&[u8]] = &[b"farm", lp_mint.as_ref(), &[ctx.accounts.farm.bump]];
+        let signer = &[seeds];
+        let cpi_accounts = Transfer {
+            from: ctx.accounts.lp_vault.to_account_info(),
+            to: ctx.accounts.owner_lp_account.to_account_info(),
+            authority: ctx.accounts.farm.to_account_info(),
+        };
+        let cpi_ctx = CpiContext::new_with_signer(
+            ctx.accounts.token_program.to_account_info(),
+            cpi_accounts,
+            signer,
+        );
+        token::transfer(cpi_ctx, amount)?;
+
+        msg!("✅ Withdrew {} LP", amount);
+        Ok(())
+    }
+
+    /// Pay out a position's accrued VECTAI, up to what the reward vault
+    /// holds; any shortfall stays pending
+    pub fn claim(ctx: Context<Claim>) -> Result<()> {
+        let farm = &mut ctx.accounts.farm;
+        farm.accrue(Clock::get()?.slot)?;
+        let acc_reward_per_share = farm.acc_reward_per_share;
+
+        let position = &mut ctx.accounts.position;
+        position.settle(acc_reward_per_share)?;
+        position.checkpoint(acc_reward_per_share)?;
+
+        let amount = position.pending_rewards.min(ctx.accounts.reward_vault.amount);
+        require!(amount > 0, FarmError::NothingToClaim);
+        position.pending_rewards -= amount;
+
+        let lp_mint = ctx.accounts.farm.lp_mint;
+        let seeds: &[&[u8]] = &[b"farm", lp_mint.as_ref(), &[ctx.accounts.farm.bump]];
+        let signer = &[seeds];
+        let cpi_accounts = Transfer {
+            from: ctx.accounts.reward_vault.to_account_info(),
+            to: ctx.accounts.owner_reward_account.to_account_info(),
+            authority: ctx.accounts.farm.to_account_info(),
+        };
+        let cpi_ctx = CpiContext::new_with_signer(
+            ctx.accounts.token_program.to_account_info(),
+            cpi_accounts,
+            signer,
+        );
+        token::transfer(cpi_ctx, amount)?;
+
+        emit!(RewardsClaimed {
+            farm: ctx.accounts.farm.key(),
+            owner: ctx.accounts.owner.key(),
+            amount,
+        });
+        msg!("✅ Claimed {} VECTAI", amount);
+        Ok(())
+    }
+
+    /// Close an emptied position and return its rent; withdraw and claim
+    /// first
+    pub fn close_position(_ctx: Context<ClosePosition>) -> Result<()> {
+        msg!("✅ Farm position closed");
+        Ok(())
+    }
+}
+
+// ===== CONSTANTS =====
+
+// VECTAI mint paid out as farming rewards
+const VECTAI_MINT: Pubkey = anchor_lang::solana_program::pubkey!("11111111111111111111111111111111"); // Replace with deployed VECTAI mint
+
+// Fixed-point scale for `acc_reward_per_share`
+pub use vectai_common::REWARD_PRECISION;
+
+// ===== HELPERS =====
+
+/// Timelock PDA; parameter changes must be queued through vectai_timelock
+fn is_timelock(key: &Pubkey) -> bool {
+    *key == vectai_timelock::timelock_authority()
+}
+
+// ===== ACCOUNTS =====
+
+#[derive(Accounts)]
+pub struct InitializeFarm<'info> {
+    #[account(
+        init,
+        payer = admin,
+        space = Farm::LEN,
+        seeds = [b"farm", lp_mint.key().as_ref()],
+        bump
+    )]
+    pub farm: Account<'info, Farm>,
+
+    /// LP mint of the VECTAI/SOL Raydium pool
+    pub lp_mint: Account<'info, Mint>,
+
+    #[account(address = VECTAI_MINT @ FarmError::InvalidTokenAccount)]
+    pub reward_mint: Account<'info, Mint>,
+
+    #[account(
+        init,
+        payer = admin,
+        token::mint = lp_mint,
+        token::authority = farm,
+        seeds = [b"lp-vault", farm.key().as_ref()],
+        bump
+    )]
+    pub lp_vault: Account<'info, TokenAccount>,
+
+    /// VECTAI paid out to farmers; funded by plain transfers
+    #[account(
+        init,
+        payer = admin,
+        token::mint = reward_mint,
+        token::authority = farm,
+        seeds = [b"reward-vault", farm.key().as_ref()],
+        bump
+    )]
+    pub reward_vault: Account<'info, TokenAccount>,
+
+    #[account(mut)]
+    pub admin: Signer<'info>,
+
+    pub rent: Sysvar<'info, Rent>,
+    pub token_program: Program<'info, Token>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct SetEmissions<'info> {
+    #[account(
+        mut,
+        seeds = [b"farm", farm.lp_mint.as_ref()],
+        bump = farm.bump
+    )]
+    pub farm: Account<'info, Farm>,
+
+    pub admin: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct Deposit<'info> {
+    #[account(
+        mut,
+        seeds = [b"farm", farm.lp_mint.as_ref()],
+        bump = farm.bump
+    )]
+    pub farm: Account<'info, Farm>,
+
+    #[account(
+        init_if_needed,
+        payer = owner,
+        space = FarmPosition::LEN,
+        seeds = [b"position", farm.key().as_ref(), owner.key().as_ref()],
+        bump
+    )]
+    pub position: Account<'info, FarmPosition>,
+
+    #[account(
+        mut,
+        constraint = owner_lp_account.mint == farm.lp_mint @ FarmError::InvalidTokenAccount,
+        constraint = owner_lp_account.owner == owner.key() @ FarmError::InvalidTokenAccount
+    )]
+    pub owner_lp_account: Account<'info, TokenAccount>,
+
+    #[account(mut, address = farm.lp_vault)]
+    pub lp_vault: Account<'info, TokenAccount>,
+
+    #[account(mut)]
+    pub owner: Signer<'info>,
+
+    pub token_program: Program<'info, Token>,
+    pub system_program: Program<'info, System>,
+
+    /// Global emergency-pause registry
+    #[account(
+        seeds = [b"protocol-status"],
+        bump = protocol_status.bump,
+        seeds::program = vectai_protocol::ID,
+        constraint = !protocol_status.paused @ FarmError::ProtocolPaused
+    )]
+    pub protocol_status: Account<'info, ProtocolStatus>,
+}
+
+#[derive(Accounts)]
+pub struct Withdraw<'info> {
+    #[account(
+        mut,
+        seeds = [b"farm", farm.lp_mint.as_ref()],
+        bump = farm.bump
+    )]
+    pub farm: Account<'info, Farm>,
+
+    #[account(
+        mut,
+        seeds = [b"position", farm.key().as_ref(), owner.key().as_ref()],
+        bump = position.bump,
+        has_one = owner
+    )]
+    pub position: Account<'info, FarmPosition>,
+
+    #[account(
+        mut,
+        constraint = owner_lp_account.mint == farm.lp_mint @ FarmError::InvalidTokenAccount
+    )]
+    pub owner_lp_account: Account<'info, TokenAccount>,
+
+    #[account(mut, address = farm.lp_vault)]
+    pub lp_vault: Account<'info, TokenAccount>,
+
+    pub owner: Signer<'info>,
+    pub token_program: Program<'info, Token>,
+
+    /// Global emergency-pause registry
+    #[account(
+        seeds = [b"protocol-status"],
+        bump = protocol_status.bump,
+        seeds::program = vectai_protocol::ID,
+        constraint = !protocol_status.paused @ FarmError::ProtocolPaused
+    )]
+    pub protocol_status: Account<'info, ProtocolStatus>,
+}
+
+#[derive(Accounts)]
+pub struct Claim<'info> {
+    #[account(
+        mut,
+        seeds = [b"farm", farm.lp_mint.as_ref()],
+        bump = farm.bump
+    )]
+    pub farm: Account<'info, Farm>,
+
+    #[account(
+        mut,
+        seeds = [b"position", farm.key().as_ref(), owner.key().as_ref()],
+        bump = position.bump,
+        has_one = owner
+    )]
+    pub position: Account<'info, FarmPosition>,
+
+    #[account(
+        mut,
+        constraint = owner_reward_account.mint == VECTAI_MINT @ FarmError::InvalidTokenAccount
+    )]
+    pub owner_reward_account: Account<'info, TokenAccount>,
+
+    #[account(mut, address = farm.reward_vault)]
+    pub reward_vault: Account<'info, TokenAccount>,
+
+    pub owner: Signer<'info>,
+    pub token_program: Program<'info, Token>,
+
+    /// Global emergency-pause registry
+    #[account(
+        seeds = [b"protocol-status"],
+        bump = protocol_status.bump,
+        seeds::program = vectai_protocol::ID,
+        constraint = !protocol_status.paused @ FarmError::ProtocolPaused
+    )]
+    pub protocol_status: Account<'info, ProtocolStatus>,
+}
+
+#[derive(Accounts)]
+pub struct ClosePosition<'info> {
+    #[account(
+        mut,
+        close = owner,
+        seeds = [b"position", position.farm.as_ref(), owner.key().as_ref()],
+        bump = position.bump,
+        has_one = owner,
+        constraint = position.amount == 0 && position.pending_rewards == 0 @ FarmError::PositionNotEmpty
+    )]
+    pub position: Account<'info, FarmPosition>,
+
+    #[account(mut)]
+    pub owner: Signer<'info>,
+}
+
+// ===== STATE =====
+
+/// Liquidity-mining farm for one LP mint; also the PDA authority over
+/// its vaults. Emits `reward_per_slot` VECTAI between `start_slot` and
+/// `end_slot`, shared pro-rata by staked LP.
+#[account]
+pub struct Farm {
+    pub lp_mint: Pubkey,
+    pub lp_vault: Pubkey,
+    pub reward_vault: Pubkey,
+    pub reward_per_slot: u64,
+    pub start_slot: u64,
+    pub end_slot: u64,
+    pub last_reward_slot: u64,
+    pub total_staked: u64,
+    pub acc_reward_per_share: u128, // Scaled by REWARD_PRECISION
+    pub total_emitted: u64,
+    pub bump: u8,
+}
+
+impl Farm {
+    pub const LEN: usize = 8 + // discriminator
+        32 + // lp_mint
+        32 + // lp_vault
+        32 + // reward_vault
+        8 +  // reward_per_slot
+        8 +  // start_slot
+        8 +  // end_slot
+        8 +  // last_reward_slot
+        8 +  // total_staked
+        16 + // acc_reward_per_share
+        8 +  // total_emitted
+        1;   // bump
+
+    /// Credit emissions from `last_reward_slot` up to `slot` (capped at
+    /// `end_slot`); slots with nothing staked emit nothing
+    pub fn accrue(&mut self, slot: u64) -> Result<()> {
+        let until = slot.min(self.end_slot);
+        if until <= self.last_reward_slot {
+            return Ok(());
+        }
+
+        if self.total_staked > 0 {
+            let reward = ((until - self.last_reward_slot) as u128)
+                .checked_mul(self.reward_per_slot as u128)
+                .ok_or(FarmError::MathOverflow)?;
+            let delta = vectai_common::reward_per_share_delta(reward, self.total_staked)?;
+            self.acc_reward_per_share = self
+                .acc_reward_per_share
+                .checked_add(delta)
+                .ok_or(FarmError::MathOverflow)?;
+            self.total_emitted = self
+                .total_emitted
+                .checked_add(u64::try_from(reward).map_err(|_| FarmError::MathOverflow)?)
+                .ok_or(FarmError::MathOverflow)?;
+        }
+        self.last_reward_slot = until;
+        Ok(())
+    }
+}
+
+/// A single owner's staked LP in a farm
+#[account]
+pub struct FarmPosition {
+    pub owner: Pubkey,
+    pub farm: Pubkey,
+    pub amount: u64,
+    pub reward_debt: u128,
+    pub pending_rewards: u64,
+    pub bump: u8,
+}
+
+impl FarmPosition {
+    pub const LEN: usize = 8 + // discriminator
+        32 + // owner
+        32 + // farm
+        8 +  // amount
+        16 + // reward_debt
+        8 +  // pending_rewards
+        1;   // bump
+
+    /// Move rewards earned since the last checkpoint into `pending_rewards`
+    pub fn settle(&mut self, acc_reward_per_share: u128) -> Result<()> {
+        vectai_common::settle_rewards(self.amount, acc_reward_per_share, self.reward_debt, &mut self.pending_rewards)
+    }
+
+    /// Reset the checkpoint after `amount` changes
+    pub fn checkpoint(&mut self, acc_reward_per_share: u128) -> Result<()> {
+        self.reward_debt = vectai_common::reward_debt(self.amount, acc_reward_per_share)?;
+        Ok(())
+    }
+}
+
+// ===== EVENTS =====
+
+#[event]
+pub struct EmissionsUpdated {
+    pub farm: Pubkey,
+    pub reward_per_slot: u64,
+    pub end_slot: u64,
+}
+
+#[event]
+pub struct RewardsClaimed {
+    pub farm: Pubkey,
+    pub owner: Pubkey,
+    pub amount: u64,
+}
+
+#[error_code]
+pub enum FarmError {
+    #[msg("Unauthorized admin")]
+    UnauthorizedAdmin,
+    #[msg("Invalid amount - must be greater than 0")]
+    InvalidAmount,
+    #[msg("Insufficient balance")]
+    InsufficientBalance,
+    #[msg("Invalid token account")]
+    InvalidTokenAccount,
+    #[msg("Invalid emission schedule")]
+    InvalidEmissionSchedule,
+    #[msg("No rewards to claim")]
+    NothingToClaim,
+    #[msg("Math overflow in calculation")]
+    MathOverflow,
+    #[msg("Protocol is paused")]
+    ProtocolPaused,
+    #[msg("Position still holds LP or unclaimed rewards")]
+    PositionNotEmpty,
+}
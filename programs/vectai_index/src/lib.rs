@@ -0,0 +1,1164 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token::{self, Burn, Mint, MintTo, Token, TokenAccount, Transfer};
+use raydium_swapper::cpi::accounts::{StableSwapAccounts, SwapAccounts};
+use raydium_swapper::program::RaydiumSwapper;
+use vectai_oracle::cpi::accounts::GetPrice;
+use vectai_oracle::program::VectaiOracle;
+use vectai_protocol::{NetworkConfig, VenueRegistry};
+
+declare_id!("8epH5NP4cG4aC2fddjzkqvCo9LG78BWtZXZvUy9zZH6N");
+
+// Oracle CPI client generated from idls/vectai_oracle.json
+declare_program!(vectai_oracle);
+
+#[program]
+pub mod vectai_index {
+    use super::*;
+
+    /// Create an index fund and its index token mint
+    pub fn initialize_index(
+        ctx: Context<InitializeIndex>,
+        index_id: u64,
+        rebalance_interval: i64,
+    ) -> Result<()> {
+        require!(rebalance_interval > 0, IndexError::InvalidInput);
+
+        let index = &mut ctx.accounts.index;
+        index.manager = ctx.accounts.manager.key();
+        index.index_id = index_id;
+        index.index_mint = ctx.accounts.index_mint.key();
+        index.components = Vec::new();
+        index.rebalance_interval = rebalance_interval;
+        index.last_rebalance = 0;
+        index.max_slippage_bps = DEFAULT_MAX_SLIPPAGE_BPS;
+        index.drift_threshold_bps = DEFAULT_DRIFT_THRESHOLD_BPS;
+        index.is_active = true;
+        index.bump = ctx.bumps.index;
+
+        msg!("✅ Index #{} initialized", index_id);
+        Ok(())
+    }
+
+    /// Add a basket component with its target weight (manager only,
+    /// before the first index token is minted)
+    ///
+    /// The component must swap with every one already in the basket, so a
+    /// rebalance can route between any two: on the USDC/SOL pool the
+    /// swapper whitelists, or on a stable pool the pair's venue registry
+    /// lists. Remaining accounts: the venue registry of each pair off the
+    /// USDC/SOL pool, in component order.
+    pub fn add_component<'info>(
+        ctx: Context<'_, '_, 'info, 'info, AddComponent<'info>>,
+        weight_bps: u16,
+    ) -> Result<()> {
+        // ✅ CHECKS
+        require!(
+            ctx.accounts.manager.key() == ctx.accounts.index.manager,
+            IndexError::Unauthorized
+        );
+        require!(ctx.accounts.index_mint.supply == 0, IndexError::IndexLive);
+        require!(weight_bps > 0, IndexError::InvalidWeights);
+
+        let index = &mut ctx.accounts.index;
+        require!(index.components.len() < MAX_COMPONENTS, IndexError::TooManyComponents);
+        require!(
+            index.components.iter().all(|c| c.mint != ctx.accounts.component_mint.key()),
+            IndexError::InvalidInput
+        );
+        require!(
+            index.total_weight_bps() + weight_bps as u64 <= BPS_DENOMINATOR,
+            IndexError::InvalidWeights
+        );
+        require_swappable(
+            &ctx.accounts.network_config,
+            &index.components,
+            &ctx.accounts.component_mint.key(),
+            ctx.remaining_accounts,
+        )?;
+
+        // ✅ EFFECTS
+        index.components.push(Component {
+            mint: ctx.accounts.component_mint.key(),
+            vault: ctx.accounts.component_vault.key(),
+            price_feed: ctx.accounts.price_feed.key(),
+            weight_bps,
+            decimals: ctx.accounts.component_mint.decimals,
+        });
+
+        msg!("✅ Component {} added at {} bps", ctx.accounts.component_mint.key(), weight_bps);
+        Ok(())
+    }
+
+    /// Deposit basket components and mint index tokens at NAV
+    ///
+    /// Remaining accounts, per component in order:
+    /// `[component_vault, price_feed, user_token_account]`
+    pub fn deposit<'info>(
+        ctx: Context<'_, '_, 'info, 'info, IndexDeposit<'info>>,
+        amounts: Vec<u64>,
+    ) -> Result<()> {
+        // ✅ CHECKS
+        let index = &ctx.accounts.index;
+        require!(index.is_active, IndexError::IndexInactive);
+        require!(
+            index.total_weight_bps() == BPS_DENOMINATOR,
+            IndexError::InvalidWeights
+        );
+        require!(amounts.len() == index.components.len(), IndexError::InvalidInput);
+        require!(
+            ctx.remaining_accounts.len() == index.components.len() * 3,
+            IndexError::InvalidComponentAccounts
+        );
+
+        let mut nav: u64 = 0;
+        let mut deposit_value: u64 = 0;
+        for (i, component) in index.components.iter().enumerate() {
+            let vault_info = &ctx.remaining_accounts[i * 3];
+            let price_feed = &ctx.remaining_accounts[i * 3 + 1];
+            let user_info = &ctx.remaining_accounts[i * 3 + 2];
+            require!(
+                vault_info.key() == component.vault && price_feed.key() == component.price_feed,
+                IndexError::InvalidComponentAccounts
+            );
+            let vault = Account::<TokenAccount>::try_from(vault_info)?;
+            let user_account = Account::<TokenAccount>::try_from(user_info)?;
+            require!(
+                user_account.mint == component.mint,
+                IndexError::InvalidComponentAccounts
+            );
+            require!(user_account.amount >= amounts[i], IndexError::InsufficientBalance);
+
+            let (price, expo) = fetch_price(&ctx.accounts.vectai_oracle_program, price_feed)?;
+            nav = nav
+                .checked_add(usd_value(vault.amount, price, expo, component.decimals)?)
+                .ok_or(IndexError::MathOverflow)?;
+            deposit_value = deposit_value
+                .checked_add(usd_value(amounts[i], price, expo, component.decimals)?)
+                .ok_or(IndexError::MathOverflow)?;
+        }
+
+        let minted = shares_for_value(deposit_value, ctx.accounts.index_mint.supply, nav)?;
+        require!(minted > 0, IndexError::InvalidAmount);
+
+        // ✅ INTERACTIONS
+        for (i, amount) in amounts.iter().enumerate() {
+            if *amount == 0 {
+                continue;
+            }
+            token::transfer(
+                CpiContext::new(
+                    ctx.accounts.token_program.to_account_info(),
+                    Transfer {
+                        from: ctx.remaining_accounts[i * 3 + 2].clone(),
+                        to: ctx.remaining_accounts[i * 3].clone(),
+                        authority: ctx.accounts.user.to_account_info(),
+                    },
+                ),
+                *amount,
+            )?;
+        }
+
+        let index = &ctx.accounts.index;
+        let index_id = index.index_id.to_le_bytes();
+        let seeds: &[&[u8]] = &[b"index", index.manager.as_ref(), &index_id, &[index.bump]];
+        token::mint_to(
+            CpiContext::new_with_signer(
+                ctx.accounts.token_program.to_account_info(),
+                MintTo {
+                    mint: ctx.accounts.index_mint.to_account_info(),
+                    to: ctx.accounts.user_index_account.to_account_info(),
+                    authority: ctx.accounts.index.to_account_info(),
+                },
+                &[seeds],
+            ),
+            minted,
+        )?;
+
+        emit!(IndexMinted {
+            index: ctx.accounts.index.key(),
+            user: ctx.accounts.user.key(),
+            deposit_value,
+            nav,
+            minted,
+        });
+        msg!("✅ Deposited ${} (NAV ${}), minted {} index tokens", deposit_value, nav, minted);
+        Ok(())
+    }
+
+    /// Burn index tokens for a pro-rata, in-kind slice of every component
+    ///
+    /// Remaining accounts, per component in order:
+    /// `[component_vault, user_token_account]`
+    pub fn redeem<'info>(
+        ctx: Context<'_, '_, 'info, 'info, IndexRedeem<'info>>,
+        amount: u64,
+    ) -> Result<()> {
+        // ✅ CHECKS
+        require!(amount > 0, IndexError::InvalidAmount);
+        require!(
+            ctx.accounts.user_index_account.amount >= amount,
+            IndexError::InsufficientBalance
+        );
+        let index = &ctx.accounts.index;
+        require!(
+            ctx.remaining_accounts.len() == index.components.len() * 2,
+            IndexError::InvalidComponentAccounts
+        );
+
+        let supply = ctx.accounts.index_mint.supply;
+        let mut payouts = Vec::with_capacity(index.components.len());
+        for (i, component) in index.components.iter().enumerate() {
+            let vault_info = &ctx.remaining_accounts[i * 2];
+            require!(vault_info.key() == component.vault, IndexError::InvalidComponentAccounts);
+            let user_account = Account::<TokenAccount>::try_from(&ctx.remaining_accounts[i * 2 + 1])?;
+            require!(
+                user_account.mint == component.mint,
+                IndexError::InvalidComponentAccounts
+            );
+            let vault = Account::<TokenAccount>::try_from(vault_info)?;
+            payouts.push(redemption_amount(vault.amount, amount, supply)?);
+        }
+
+        // ✅ INTERACTIONS: Burn first, then pay out
+        token::burn(
+            CpiContext::new(
+                ctx.accounts.token_program.to_account_info(),
+                Burn {
+                    mint: ctx.accounts.index_mint.to_account_info(),
+                    from: ctx.accounts.user_index_account.to_account_info(),
+                    authority: ctx.accounts.user.to_account_info(),
+                },
+            ),
+            amount,
+        )?;
+
+        let index_id = index.index_id.to_le_bytes();
+        let seeds: &[&[u8]] = &[b"index", index.manager.as_ref(), &index_id, &[index.bump]];
+        for (i, payout) in payouts.iter().enumerate() {
+            if *payout == 0 {
+                continue;
+            }
+            token::transfer(
+                CpiContext::new_with_signer(
+                    ctx.accounts.token_program.to_account_info(),
+                    Transfer {
+                        from: ctx.remaining_accounts[i * 2].clone(),
+                        to: ctx.remaining_accounts[i * 2 + 1].clone(),
+                        authority: ctx.accounts.index.to_account_info(),
+                    },
+                    &[seeds],
+                ),
+                *payout,
+            )?;
+        }
+
+        emit!(IndexRedeemed {
+            index: ctx.accounts.index.key(),
+            user: ctx.accounts.user.key(),
+            amount,
+        });
+        msg!("✅ Redeemed {} index tokens", amount);
+        Ok(())
+    }
+
+    /// Set the rebalancer's slippage budget and drift threshold (manager only)
+    pub fn configure_rebalancer(
+        ctx: Context<ManageIndex>,
+        max_slippage_bps: u16,
+        drift_threshold_bps: u16,
+    ) -> Result<()> {
+        require!(
+            ctx.accounts.manager.key() == ctx.accounts.index.manager,
+            IndexError::Unauthorized
+        );
+        require!(max_slippage_bps <= MAX_SLIPPAGE_BPS, IndexError::SlippageTooHigh);
+        require!(
+            drift_threshold_bps > 0 && drift_threshold_bps as u64 <= BPS_DENOMINATOR,
+            IndexError::InvalidInput
+        );
+
+        let index = &mut ctx.accounts.index;
+        index.max_slippage_bps = max_slippage_bps;
+        index.drift_threshold_bps = drift_threshold_bps;
+
+        msg!("✅ Rebalancer: max slippage {} bps, drift threshold {} bps",
+             max_slippage_bps, drift_threshold_bps);
+        Ok(())
+    }
+
+    /// Crank: move value from the component furthest over its target into
+    /// the one furthest under it through raydium_swapper, at most once per
+    /// rebalance interval
+    ///
+    /// The swap size closes the smaller of the two drifts; the minimum
+    /// output is the oracle-implied amount less `max_slippage_bps`.
+    ///
+    /// Remaining accounts, per component in order:
+    /// `[component_vault, price_feed]`
+    pub fn rebalance<'info>(
+        ctx: Context<'_, '_, 'info, 'info, Rebalance<'info>>,
+        from_component: u8,
+        to_component: u8,
+    ) -> Result<()> {
+        // ✅ CHECKS
+        let clock = Clock::get()?;
+        let RebalancePlan {
+            move_value,
+            amount_in,
+            min_amount_out,
+            nav,
+        } = plan_rebalance(
+            &ctx.accounts.index,
+            ctx.remaining_accounts,
+            &ctx.accounts.vectai_oracle_program,
+            &ctx.accounts.source_vault,
+            &ctx.accounts.destination_vault,
+            from_component,
+            to_component,
+            clock.unix_timestamp,
+        )?;
+
+        // ✅ EFFECTS
+        ctx.accounts.index.last_rebalance = clock.unix_timestamp;
+
+        // ✅ INTERACTIONS: Index PDA signs as the swap user
+        let index = &ctx.accounts.index;
+        let index_id = index.index_id.to_le_bytes();
+        let seeds: &[&[u8]] = &[b"index", index.manager.as_ref(), &index_id, &[index.bump]];
+        let swap_accounts = SwapAccounts {
+            user_authority: ctx.accounts.index.to_account_info(),
+            user_source_token: ctx.accounts.source_vault.to_account_info(),
+            user_destination_token: ctx.accounts.destination_vault.to_account_info(),
+            raydium_amm_program: ctx.accounts.raydium_amm_program.to_account_info(),
+            amm: ctx.accounts.amm.to_account_info(),
+            amm_authority: ctx.accounts.amm_authority.to_account_info(),
+            amm_open_orders: ctx.accounts.amm_open_orders.to_account_info(),
+            amm_target_orders: ctx.accounts.amm_target_orders.to_account_info(),
+            pool_coin_token_account: ctx.accounts.pool_coin_token_account.to_account_info(),
+            pool_pc_token_account: ctx.accounts.pool_pc_token_account.to_account_info(),
+            serum_program: ctx.accounts.serum_program.to_account_info(),
+            serum_market: ctx.accounts.serum_market.to_account_info(),
+            serum_bids: ctx.accounts.serum_bids.to_account_info(),
+            serum_asks: ctx.accounts.serum_asks.to_account_info(),
+            serum_event_queue: ctx.accounts.serum_event_queue.to_account_info(),
+            serum_coin_vault: ctx.accounts.serum_coin_vault.to_account_info(),
+            serum_pc_vault: ctx.accounts.serum_pc_vault.to_account_info(),
+            serum_vault_signer: ctx.accounts.serum_vault_signer.to_account_info(),
+            token_program: ctx.accounts.token_program.to_account_info(),
+            protocol_status: ctx.accounts.protocol_status.to_account_info(),
+            network_config: ctx.accounts.network_config.to_account_info(),
+            protocol_stats: ctx.accounts.protocol_stats.to_account_info(),
+            stats_reporter: ctx.accounts.swapper_stats_reporter.to_account_info(),
+            protocol_program: ctx.accounts.protocol_program.to_account_info(),
+            fee_account: ctx.accounts.swap_fee_account.as_ref().map(|a| a.to_account_info()),
+        };
+        raydium_swapper::cpi::swap(
+            CpiContext::new_with_signer(
+                ctx.accounts.swapper_program.to_account_info(),
+                swap_accounts,
+                &[seeds],
+            ),
+            amount_in,
+            min_amount_out,
+        )?;
+
+        emit!(IndexRebalanced {
+            index: ctx.accounts.index.key(),
+            keeper: ctx.accounts.keeper.key(),
+            from_component,
+            to_component,
+            value_moved: move_value,
+            amount_in,
+            min_amount_out,
+            nav,
+        });
+        msg!("✅ Rebalanced {} of component {} into component {} (min out {})",
+             amount_in, from_component, to_component, min_amount_out);
+        Ok(())
+    }
+
+    /// Crank: `rebalance` between two pegged components (e.g. USDT and
+    /// USDC) on the stable pool the venue registry lists for them, instead
+    /// of the Raydium pool
+    ///
+    /// Remaining accounts, per component in order:
+    /// `[component_vault, price_feed]`
+    pub fn rebalance_stable<'info>(
+        ctx: Context<'_, '_, 'info, 'info, RebalanceStable<'info>>,
+        from_component: u8,
+        to_component: u8,
+    ) -> Result<()> {
+        // ✅ CHECKS
+        let clock = Clock::get()?;
+        let RebalancePlan {
+            move_value,
+            amount_in,
+            min_amount_out,
+            nav,
+        } = plan_rebalance(
+            &ctx.accounts.index,
+            ctx.remaining_accounts,
+            &ctx.accounts.vectai_oracle_program,
+            &ctx.accounts.source_vault,
+            &ctx.accounts.destination_vault,
+            from_component,
+            to_component,
+            clock.unix_timestamp,
+        )?;
+
+        // ✅ EFFECTS
+        ctx.accounts.index.last_rebalance = clock.unix_timestamp;
+
+        // ✅ INTERACTIONS: Index PDA signs as the swap user
+        let index = &ctx.accounts.index;
+        let index_id = index.index_id.to_le_bytes();
+        let seeds: &[&[u8]] = &[b"index", index.manager.as_ref(), &index_id, &[index.bump]];
+        let swap_accounts = StableSwapAccounts {
+            user_authority: ctx.accounts.index.to_account_info(),
+            user_source_token: ctx.accounts.source_vault.to_account_info(),
+            user_destination_token: ctx.accounts.destination_vault.to_account_info(),
+            stable_swap_program: ctx.accounts.stable_swap_program.to_account_info(),
+            pool: ctx.accounts.pool.to_account_info(),
+            pool_authority: ctx.accounts.pool_authority.to_account_info(),
+            pool_source: ctx.accounts.pool_source.to_account_info(),
+            pool_destination: ctx.accounts.pool_destination.to_account_info(),
+            admin_fee_destination: ctx.accounts.admin_fee_destination.to_account_info(),
+            token_program: ctx.accounts.token_program.to_account_info(),
+            venue_registry: ctx.accounts.venue_registry.to_account_info(),
+            protocol_status: ctx.accounts.protocol_status.to_account_info(),
+            network_config: ctx.accounts.network_config.to_account_info(),
+            protocol_stats: ctx.accounts.protocol_stats.to_account_info(),
+            stats_reporter: ctx.accounts.swapper_stats_reporter.to_account_info(),
+            protocol_program: ctx.accounts.protocol_program.to_account_info(),
+            fee_account: ctx.accounts.swap_fee_account.as_ref().map(|a| a.to_account_info()),
+        };
+        raydium_swapper::cpi::swap_stable(
+            CpiContext::new_with_signer(
+                ctx.accounts.swapper_program.to_account_info(),
+                swap_accounts,
+                &[seeds],
+            ),
+            amount_in,
+            min_amount_out,
+        )?;
+
+        emit!(IndexRebalanced {
+            index: ctx.accounts.index.key(),
+            keeper: ctx.accounts.keeper.key(),
+            from_component,
+            to_component,
+            value_moved: move_value,
+            amount_in,
+            min_amount_out,
+            nav,
+        });
+        msg!("✅ Rebalanced {} of component {} into component {} on the stable pool (min out {})",
+             amount_in, from_component, to_component, min_amount_out);
+        Ok(())
+    }
+
+    /// Stop new deposits (manager only); redemptions stay open
+    pub fn set_index_active(ctx: Context<ManageIndex>, is_active: bool) -> Result<()> {
+        require!(
+            ctx.accounts.manager.key() == ctx.accounts.index.manager,
+            IndexError::Unauthorized
+        );
+
+        ctx.accounts.index.is_active = is_active;
+        msg!("Index active: {}", is_active);
+        Ok(())
+    }
+}
+
+// ===== CONSTANTS =====
+
+// Basis-point denominator (10000 = 100%)
+pub use vectai_common::{BPS_DENOMINATOR, VIRTUAL_SHARES};
+
+// Maximum basket size
+pub const MAX_COMPONENTS: usize = 5;
+
+// USD values are fixed-point with this many decimals; index tokens use the
+// same decimals so the first deposit mints 1 token per $1
+pub const VALUE_DECIMALS: u8 = 6;
+
+// Rebalancer defaults and bounds
+pub const DEFAULT_MAX_SLIPPAGE_BPS: u16 = 100; // 1%
+pub const DEFAULT_DRIFT_THRESHOLD_BPS: u16 = 200; // 2% of NAV
+pub const MAX_SLIPPAGE_BPS: u16 = 1_000; // 10%
+
+// ===== HELPERS =====
+
+/// Size and oracle-implied minimum output of a rebalance
+struct RebalancePlan {
+    move_value: u64,
+    amount_in: u64,
+    min_amount_out: u64,
+    nav: u64,
+}
+
+/// Check a rebalance from `from_component` into `to_component` is due and
+/// is the largest-drift pair, and size it: the swap closes the smaller of
+/// the two drifts, and the minimum output is the oracle-implied amount less
+/// `max_slippage_bps`
+#[allow(clippy::too_many_arguments)]
+fn plan_rebalance<'info>(
+    index: &IndexFund,
+    component_accounts: &'info [AccountInfo<'info>],
+    oracle_program: &Program<'info, VectaiOracle>,
+    source_vault: &Account<'info, TokenAccount>,
+    destination_vault: &Account<'info, TokenAccount>,
+    from_component: u8,
+    to_component: u8,
+    now: i64,
+) -> Result<RebalancePlan> {
+    require!(index.is_active, IndexError::IndexInactive);
+    require!(
+        component_accounts.len() == index.components.len() * 2,
+        IndexError::InvalidComponentAccounts
+    );
+    let from = index
+        .components
+        .get(from_component as usize)
+        .ok_or(IndexError::InvalidInput)?;
+    let to = index
+        .components
+        .get(to_component as usize)
+        .ok_or(IndexError::InvalidInput)?;
+    require!(from_component != to_component, IndexError::InvalidInput);
+    require!(
+        source_vault.key() == from.vault
+            && destination_vault.key() == to.vault,
+        IndexError::InvalidComponentAccounts
+    );
+
+    require!(
+        now >= index.last_rebalance + index.rebalance_interval,
+        IndexError::RebalanceTooSoon
+    );
+
+    // Price every component to get current values and NAV
+    let mut values = Vec::with_capacity(index.components.len());
+    let mut prices = Vec::with_capacity(index.components.len());
+    for (i, component) in index.components.iter().enumerate() {
+        let vault_info = &component_accounts[i * 2];
+        let price_feed = &component_accounts[i * 2 + 1];
+        require!(
+            vault_info.key() == component.vault && price_feed.key() == component.price_feed,
+            IndexError::InvalidComponentAccounts
+        );
+        let vault = Account::<TokenAccount>::try_from(vault_info)?;
+        let (price, expo) = fetch_price(oracle_program, price_feed)?;
+        values.push(usd_value(vault.amount, price, expo, component.decimals)?);
+        prices.push((price, expo));
+    }
+    let nav = values
+        .iter()
+        .try_fold(0u64, |acc, v| acc.checked_add(*v))
+        .ok_or(IndexError::MathOverflow)?;
+    require!(nav > 0, IndexError::InsufficientBalance);
+
+    let (from_i, to_i) = (from_component as usize, to_component as usize);
+    let weights: Vec<u16> = index.components.iter().map(|c| c.weight_bps).collect();
+    let drifts = component_drifts(&values, &weights, nav)?;
+    require!(is_largest_drift_pair(&drifts, from_i, to_i), IndexError::NotLargestDrift);
+    let (excess, deficit) = (drifts[from_i].0, drifts[to_i].1);
+    require!(
+        drift_bps(excess, nav)? >= index.drift_threshold_bps as u64
+            || drift_bps(deficit, nav)? >= index.drift_threshold_bps as u64,
+        IndexError::WithinDriftThreshold
+    );
+    let move_value = excess.min(deficit);
+    require!(move_value > 0, IndexError::WithinDriftThreshold);
+
+    let (from_price, from_expo) = prices[from_i];
+    let (to_price, to_expo) = prices[to_i];
+    let amount_in = token_amount(move_value, from_price, from_expo, from.decimals)?
+        .min(source_vault.amount);
+    require!(amount_in > 0, IndexError::InvalidAmount);
+    let expected_out = token_amount(move_value, to_price, to_expo, to.decimals)?;
+    let min_amount_out = ((expected_out as u128)
+        * (BPS_DENOMINATOR - index.max_slippage_bps as u64) as u128
+        / BPS_DENOMINATOR as u128) as u64;
+    Ok(RebalancePlan {
+        move_value,
+        amount_in,
+        min_amount_out,
+        nav,
+    })
+}
+
+/// Oracle price for a component as `(price, expo)`
+fn fetch_price<'info>(
+    oracle_program: &Program<'info, VectaiOracle>,
+    price_feed: &AccountInfo<'info>,
+) -> Result<(i64, i32)> {
+    let price_data = vectai_oracle::cpi::get_price(CpiContext::new(
+        oracle_program.to_account_info(),
+        GetPrice {
+            price_feed: price_feed.clone(),
+        },
+    ))?
+    .get();
+    require!(price_data.price > 0, IndexError::InvalidPrice);
+    Ok((price_data.price, price_data.expo))
+}
+
+/// USD value (VALUE_DECIMALS fixed-point) of `amount` base units of a
+/// token with `decimals`, priced at `price * 10^expo` USD per whole token
+pub fn usd_value(amount: u64, price: i64, expo: i32, decimals: u8) -> Result<u64> {
+    require!(price > 0, IndexError::InvalidPrice);
+
+    let scale = expo + VALUE_DECIMALS as i32 - decimals as i32;
+    let raw = (amount as u128)
+        .checked_mul(price as u128)
+        .ok_or(IndexError::MathOverflow)?;
+    let value = if scale >= 0 {
+        raw.checked_mul(10u128.checked_pow(scale as u32).ok_or(IndexError::MathOverflow)?)
+            .ok_or(IndexError::MathOverflow)?
+    } else {
+        raw / 10u128.checked_pow((-scale) as u32).ok_or(IndexError::MathOverflow)?
+    };
+    u64::try_from(value).map_err(|_| IndexError::MathOverflow.into())
+}
+
+/// Base units of a token worth `value` USD; inverse of `usd_value`
+pub fn token_amount(value: u64, price: i64, expo: i32, decimals: u8) -> Result<u64> {
+    require!(price > 0, IndexError::InvalidPrice);
+
+    let scale = decimals as i32 - expo - VALUE_DECIMALS as i32;
+    let raw = if scale >= 0 {
+        (value as u128)
+            .checked_mul(10u128.checked_pow(scale as u32).ok_or(IndexError::MathOverflow)?)
+            .ok_or(IndexError::MathOverflow)?
+            / price as u128
+    } else {
+        (value as u128)
+            / (price as u128)
+                .checked_mul(10u128.checked_pow((-scale) as u32).ok_or(IndexError::MathOverflow)?)
+                .ok_or(IndexError::MathOverflow)?
+    };
+    u64::try_from(raw).map_err(|_| IndexError::MathOverflow.into())
+}
+
+/// Target USD value of a component at `weight_bps` of `nav`
+pub fn target_value(nav: u64, weight_bps: u16) -> Result<u64> {
+    Ok(vectai_common::bps_of(nav, weight_bps as u64))
+}
+
+/// Value of each component above (`.0`) and below (`.1`) its target weight
+/// of `nav`, in basket order
+pub fn component_drifts(values: &[u64], weights_bps: &[u16], nav: u64) -> Result<Vec<(u64, u64)>> {
+    values
+        .iter()
+        .zip(weights_bps)
+        .map(|(&value, &weight_bps)| {
+            let target = target_value(nav, weight_bps)?;
+            Ok((value.saturating_sub(target), target.saturating_sub(value)))
+        })
+        .collect()
+}
+
+/// Whether `from`/`to` is the pair a rebalance must move: the component
+/// furthest over its target into the one furthest under it, so a keeper
+/// cannot spend the interval on a smaller drift. Tied components qualify
+/// alike.
+pub fn is_largest_drift_pair(drifts: &[(u64, u64)], from: usize, to: usize) -> bool {
+    let max_excess = drifts.iter().map(|d| d.0).max().unwrap_or(0);
+    let max_deficit = drifts.iter().map(|d| d.1).max().unwrap_or(0);
+    drifts.get(from).is_some_and(|d| d.0 == max_excess) && drifts.get(to).is_some_and(|d| d.1 == max_deficit)
+}
+
+/// Check `mint` swaps with each of `components`: on the USDC/SOL pool, or
+/// else on a stable pool listed in the pair's venue registry, taken from
+/// `registries` in component order
+fn require_swappable<'info>(
+    network: &NetworkConfig,
+    components: &[Component],
+    mint: &Pubkey,
+    registries: &'info [AccountInfo<'info>],
+) -> Result<()> {
+    let mut registries = registries.iter();
+    for component in components {
+        if network.is_supported_pair(component.mint, *mint) {
+            continue;
+        }
+        let registry = registries.next().ok_or(IndexError::UnswappableComponent)?;
+        let registry = Account::<VenueRegistry>::try_from(registry)?;
+        let pair = [registry.base_mint, registry.quote_mint];
+        require!(
+            pair.contains(&component.mint)
+                && pair.contains(mint)
+                && registry.venues.stable_swap_pool != Pubkey::default(),
+            IndexError::UnswappableComponent
+        );
+    }
+    require!(registries.next().is_none(), IndexError::InvalidComponentAccounts);
+    Ok(())
+}
+
+/// `value` as basis points of `nav`
+pub fn drift_bps(value: u64, nav: u64) -> Result<u64> {
+    require!(nav > 0, IndexError::MathOverflow);
+    Ok(((value as u128) * BPS_DENOMINATOR as u128 / nav as u128) as u64)
+}
+
+/// Index tokens minted for `value` deposited into a fund worth `nav`,
+/// priced against the virtual shares and assets the vault uses, so a
+/// donation cannot inflate the price of an empty index. A fund worth
+/// nothing with tokens outstanding takes no deposits: minting into it
+/// would dilute the holders.
+pub fn shares_for_value(value: u64, supply: u64, nav: u64) -> Result<u64> {
+    require!(supply == 0 || nav > 0, IndexError::WorthlessIndex);
+    vectai_common::shares_for_deposit(value, supply, nav)
+}
+
+/// Slice of a component's `balance` paid for burning `shares` of `supply`,
+/// counting the virtual shares as `shares_for_value` does
+pub fn redemption_amount(balance: u64, shares: u64, supply: u64) -> Result<u64> {
+    vectai_common::redemption_amount(balance, 0, shares, supply)
+}
+
+// ===== ACCOUNTS =====
+
+#[derive(Accounts)]
+#[instruction(index_id: u64)]
+pub struct InitializeIndex<'info> {
+    #[account(
+        init,
+        payer = manager,
+        space = IndexFund::LEN,
+        seeds = [b"index", manager.key().as_ref(), &index_id.to_le_bytes()],
+        bump
+    )]
+    pub index: Account<'info, IndexFund>,
+
+    #[account(
+        init,
+        payer = manager,
+        mint::decimals = VALUE_DECIMALS,
+        mint::authority = index,
+        seeds = [b"index-mint", index.key().as_ref()],
+        bump
+    )]
+    pub index_mint: Account<'info, Mint>,
+
+    #[account(mut)]
+    pub manager: Signer<'info>,
+
+    pub rent: Sysvar<'info, Rent>,
+    pub token_program: Program<'info, Token>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct AddComponent<'info> {
+    #[account(
+        mut,
+        seeds = [b"index", index.manager.as_ref(), &index.index_id.to_le_bytes()],
+        bump = index.bump
+    )]
+    pub index: Account<'info, IndexFund>,
+
+    #[account(address = index.index_mint)]
+    pub index_mint: Account<'info, Mint>,
+
+    pub component_mint: Account<'info, Mint>,
+
+    #[account(
+        init,
+        payer = manager,
+        token::mint = component_mint,
+        token::authority = index,
+        seeds = [b"component-vault", index.key().as_ref(), component_mint.key().as_ref()],
+        bump
+    )]
+    pub component_vault: Account<'info, TokenAccount>,
+
+    /// CHECK: Oracle price feed (USD per whole token); validated by vectai_oracle
+    pub price_feed: UncheckedAccount<'info>,
+
+    /// Shared network addresses; its USDC/SOL pair is the Raydium pool the
+    /// swapper trades
+    #[account(
+        seeds = [b"network-config"],
+        bump = network_config.bump,
+        seeds::program = vectai_protocol::ID
+    )]
+    pub network_config: Account<'info, NetworkConfig>,
+
+    #[account(mut)]
+    pub manager: Signer<'info>,
+
+    pub rent: Sysvar<'info, Rent>,
+    pub token_program: Program<'info, Token>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct IndexDeposit<'info> {
+    #[account(
+        seeds = [b"index", index.manager.as_ref(), &index.index_id.to_le_bytes()],
+        bump = index.bump
+    )]
+    pub index: Account<'info, IndexFund>,
+
+    #[account(mut, address = index.index_mint)]
+    pub index_mint: Account<'info, Mint>,
+
+    #[account(
+        mut,
+        constraint = user_index_account.mint == index.index_mint @ IndexError::InvalidTokenAccount
+    )]
+    pub user_index_account: Account<'info, TokenAccount>,
+
+    pub user: Signer<'info>,
+
+    /// The Oracle Program (VECT.AI Oracle)
+    pub vectai_oracle_program: Program<'info, VectaiOracle>,
+
+    pub token_program: Program<'info, Token>,
+}
+
+#[derive(Accounts)]
+pub struct IndexRedeem<'info> {
+    #[account(
+        seeds = [b"index", index.manager.as_ref(), &index.index_id.to_le_bytes()],
+        bump = index.bump
+    )]
+    pub index: Account<'info, IndexFund>,
+
+    #[account(mut, address = index.index_mint)]
+    pub index_mint: Account<'info, Mint>,
+
+    #[account(
+        mut,
+        constraint = user_index_account.owner == user.key() @ IndexError::InvalidTokenAccount
+    )]
+    pub user_index_account: Account<'info, TokenAccount>,
+
+    pub user: Signer<'info>,
+    pub token_program: Program<'info, Token>,
+}
+
+#[derive(Accounts)]
+pub struct Rebalance<'info> {
+    #[account(
+        mut,
+        seeds = [b"index", index.manager.as_ref(), &index.index_id.to_le_bytes()],
+        bump = index.bump
+    )]
+    pub index: Account<'info, IndexFund>,
+
+    #[account(mut)]
+    pub source_vault: Account<'info, TokenAccount>,
+
+    #[account(mut)]
+    pub destination_vault: Account<'info, TokenAccount>,
+
+    /// Anyone may crank; swap size and minimum output are computed on-chain
+    pub keeper: Signer<'info>,
+
+    /// The Oracle Program (VECT.AI Oracle)
+    pub vectai_oracle_program: Program<'info, VectaiOracle>,
+
+    pub swapper_program: Program<'info, RaydiumSwapper>,
+
+    // ===== RAYDIUM / SERUM ACCOUNTS (validated by raydium_swapper) =====
+
+    /// CHECK: Raydium AMM program
+    pub raydium_amm_program: UncheckedAccount<'info>,
+
+    /// CHECK: AMM pool account
+    #[account(mut)]
+    pub amm: UncheckedAccount<'info>,
+
+    /// CHECK: AMM authority
+    pub amm_authority: UncheckedAccount<'info>,
+
+    /// CHECK: AMM open orders
+    #[account(mut)]
+    pub amm_open_orders: UncheckedAccount<'info>,
+
+    /// CHECK: AMM target orders
+    #[account(mut)]
+    pub amm_target_orders: UncheckedAccount<'info>,
+
+    /// CHECK: Pool coin token account
+    #[account(mut)]
+    pub pool_coin_token_account: UncheckedAccount<'info>,
+
+    /// CHECK: Pool pc token account
+    #[account(mut)]
+    pub pool_pc_token_account: UncheckedAccount<'info>,
+
+    /// CHECK: Serum program
+    pub serum_program: UncheckedAccount<'info>,
+
+    /// CHECK: Serum market
+    #[account(mut)]
+    pub serum_market: UncheckedAccount<'info>,
+
+    /// CHECK: Serum bids
+    #[account(mut)]
+    pub serum_bids: UncheckedAccount<'info>,
+
+    /// CHECK: Serum asks
+    #[account(mut)]
+    pub serum_asks: UncheckedAccount<'info>,
+
+    /// CHECK: Serum event queue
+    #[account(mut)]
+    pub serum_event_queue: UncheckedAccount<'info>,
+
+    /// CHECK: Serum coin vault
+    #[account(mut)]
+    pub serum_coin_vault: UncheckedAccount<'info>,
+
+    /// CHECK: Serum pc vault
+    #[account(mut)]
+    pub serum_pc_vault: UncheckedAccount<'info>,
+
+    /// CHECK: Serum vault signer
+    pub serum_vault_signer: UncheckedAccount<'info>,
+
+    pub token_program: Program<'info, Token>,
+
+    /// Global emergency-pause registry, forwarded to the swapper
+    /// CHECK: validated by raydium_swapper
+    pub protocol_status: UncheckedAccount<'info>,
+
+    /// Shared network addresses, forwarded to the swapper
+    /// CHECK: validated by raydium_swapper
+    pub network_config: UncheckedAccount<'info>,
+
+    /// Protocol activity counters, forwarded to the swapper
+    /// CHECK: validated by vectai_protocol
+    #[account(mut)]
+    pub protocol_stats: UncheckedAccount<'info>,
+
+    /// The swapper's stats reporter PDA, forwarded to the swapper
+    /// CHECK: validated by raydium_swapper
+    pub swapper_stats_reporter: UncheckedAccount<'info>,
+
+    /// CHECK: validated by raydium_swapper
+    pub protocol_program: UncheckedAccount<'info>,
+
+    /// Treasury vault for the swap's source mint, forwarded to the swapper
+    /// CHECK: validated by raydium_swapper
+    #[account(mut)]
+    pub swap_fee_account: Option<UncheckedAccount<'info>>,
+}
+
+#[derive(Accounts)]
+pub struct RebalanceStable<'info> {
+    #[account(
+        mut,
+        seeds = [b"index", index.manager.as_ref(), &index.index_id.to_le_bytes()],
+        bump = index.bump
+    )]
+    pub index: Account<'info, IndexFund>,
+
+    #[account(mut)]
+    pub source_vault: Account<'info, TokenAccount>,
+
+    #[account(mut)]
+    pub destination_vault: Account<'info, TokenAccount>,
+
+    /// Anyone may crank; swap size and minimum output are computed on-chain
+    pub keeper: Signer<'info>,
+
+    /// The Oracle Program (VECT.AI Oracle)
+    pub vectai_oracle_program: Program<'info, VectaiOracle>,
+
+    pub swapper_program: Program<'info, RaydiumSwapper>,
+
+    // ===== STABLE POOL ACCOUNTS (validated by raydium_swapper) =====
+
+    /// CHECK: StableSwap program
+    pub stable_swap_program: UncheckedAccount<'info>,
+
+    /// CHECK: Stable pool
+    pub pool: UncheckedAccount<'info>,
+
+    /// CHECK: Stable pool authority
+    pub pool_authority: UncheckedAccount<'info>,
+
+    /// CHECK: Pool vault of the source mint
+    #[account(mut)]
+    pub pool_source: UncheckedAccount<'info>,
+
+    /// CHECK: Pool vault of the destination mint
+    #[account(mut)]
+    pub pool_destination: UncheckedAccount<'info>,
+
+    /// CHECK: Pool admin fee account for the destination mint
+    #[account(mut)]
+    pub admin_fee_destination: UncheckedAccount<'info>,
+
+    pub token_program: Program<'info, Token>,
+
+    /// Venue registry of the pair, forwarded to the swapper
+    /// CHECK: validated by raydium_swapper
+    pub venue_registry: UncheckedAccount<'info>,
+
+    /// Global emergency-pause registry, forwarded to the swapper
+    /// CHECK: validated by raydium_swapper
+    pub protocol_status: UncheckedAccount<'info>,
+
+    /// Shared network addresses, forwarded to the swapper
+    /// CHECK: validated by raydium_swapper
+    pub network_config: UncheckedAccount<'info>,
+
+    /// Protocol activity counters, forwarded to the swapper
+    /// CHECK: validated by vectai_protocol
+    #[account(mut)]
+    pub protocol_stats: UncheckedAccount<'info>,
+
+    /// The swapper's stats reporter PDA, forwarded to the swapper
+    /// CHECK: validated by raydium_swapper
+    pub swapper_stats_reporter: UncheckedAccount<'info>,
+
+    /// CHECK: validated by raydium_swapper
+    pub protocol_program: UncheckedAccount<'info>,
+
+    /// Treasury vault for the swap's source mint, forwarded to the swapper
+    /// CHECK: validated by raydium_swapper
+    #[account(mut)]
+    pub swap_fee_account: Option<UncheckedAccount<'info>>,
+}
+
+#[derive(Accounts)]
+pub struct ManageIndex<'info> {
+    #[account(
+        mut,
+        seeds = [b"index", index.manager.as_ref(), &index.index_id.to_le_bytes()],
+        bump = index.bump
+    )]
+    pub index: Account<'info, IndexFund>,
+
+    pub manager: Signer<'info>,
+}
+
+// ===== STATE =====
+
+/// Basket component held in its own vault owned by the index PDA
+#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+pub struct Component {
+    pub mint: Pubkey,
+    pub vault: Pubkey,
+    pub price_feed: Pubkey,
+    pub weight_bps: u16, // Target weight
+    pub decimals: u8,
+}
+
+impl Component {
+    pub const LEN: usize = 32 + // mint
+        32 + // vault
+        32 + // price_feed
+        2 +  // weight_bps
+        1;   // decimals
+}
+
+/// Index fund; the PDA owns every component vault and the index mint
+#[account]
+pub struct IndexFund {
+    pub manager: Pubkey,
+    pub index_id: u64,
+    pub index_mint: Pubkey,
+    pub components: Vec<Component>,
+    pub rebalance_interval: i64, // Seconds between rebalances
+    pub last_rebalance: i64,
+    pub max_slippage_bps: u16, // Slippage budget per rebalance
+    pub drift_threshold_bps: u16, // Minimum drift (of NAV) worth rebalancing
+    pub is_active: bool,
+    pub bump: u8,
+}
+
+impl IndexFund {
+    pub const LEN: usize = 8 + // discriminator
+        32 + // manager
+        8 +  // index_id
+        32 + // index_mint
+        4 + MAX_COMPONENTS * Component::LEN + // components
+        8 +  // rebalance_interval
+        8 +  // last_rebalance
+        2 +  // max_slippage_bps
+        2 +  // drift_threshold_bps
+        1 +  // is_active
+        1;   // bump
+
+    pub fn total_weight_bps(&self) -> u64 {
+        self.components.iter().map(|c| c.weight_bps as u64).sum()
+    }
+}
+
+// ===== EVENTS =====
+
+#[event]
+pub struct IndexMinted {
+    pub index: Pubkey,
+    pub user: Pubkey,
+    pub deposit_value: u64,
+    pub nav: u64,
+    pub minted: u64,
+}
+
+#[event]
+pub struct IndexRedeemed {
+    pub index: Pubkey,
+    pub user: Pubkey,
+    pub amount: u64,
+}
+
+#[event]
+pub struct IndexRebalanced {
+    pub index: Pubkey,
+    pub keeper: Pubkey,
+    pub from_component: u8,
+    pub to_component: u8,
+    pub value_moved: u64,
+    pub amount_in: u64,
+    pub min_amount_out: u64,
+    pub nav: u64,
+}
+
+#[error_code]
+pub enum IndexError {
+    #[msg("Invalid input parameters")]
+    InvalidInput,
+    #[msg("Invalid amount - must be greater than 0")]
+    InvalidAmount,
+    #[msg("Insufficient balance")]
+    InsufficientBalance,
+    #[msg("Math overflow in calculation")]
+    MathOverflow,
+    #[msg("Unauthorized access")]
+    Unauthorized,
+    #[msg("Index is inactive")]
+    IndexInactive,
+    #[msg("Invalid token account")]
+    InvalidTokenAccount,
+    #[msg("Invalid oracle price")]
+    InvalidPrice,
+    #[msg("Component weights must sum to 10000 bps")]
+    InvalidWeights,
+    #[msg("Basket already has the maximum number of components")]
+    TooManyComponents,
+    #[msg("Basket cannot change after index tokens are minted")]
+    IndexLive,
+    #[msg("Component accounts do not match the basket")]
+    InvalidComponentAccounts,
+    #[msg("Rebalance interval has not elapsed")]
+    RebalanceTooSoon,
+    #[msg("Components are within the drift threshold")]
+    WithinDriftThreshold,
+    #[msg("Slippage budget too high")]
+    SlippageTooHigh,
+    #[msg("Index is worth nothing while index tokens are outstanding")]
+    WorthlessIndex,
+    #[msg("Component cannot be swapped with every other basket component")]
+    UnswappableComponent,
+    #[msg("Rebalance must move the largest overweight into the largest underweight")]
+    NotLargestDrift,
+}
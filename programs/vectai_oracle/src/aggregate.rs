@@ -0,0 +1,166 @@
+use anchor_lang::prelude::*;
+use vectai_common::BPS_DENOMINATOR;
+
+use crate::{OracleError, PriceData};
+
+/// Most sources one aggregator averages
+pub const MAX_PRICE_SOURCES: usize = 4;
+
+#[derive(Accounts)]
+#[instruction(aggregator_id: u64)]
+pub struct InitializePriceAggregator<'info> {
+    #[account(
+        init,
+        payer = authority,
+        space = PriceAggregator::LEN,
+        seeds = [b"price-aggregator", authority.key().as_ref(), &aggregator_id.to_le_bytes()],
+        bump
+    )]
+    pub price_aggregator: Account<'info, PriceAggregator>,
+
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct SetPriceSources<'info> {
+    #[account(mut, has_one = authority)]
+    pub price_aggregator: Account<'info, PriceAggregator>,
+
+    pub authority: Signer<'info>,
+}
+
+/// The source feeds follow as remaining accounts, in the aggregator's order
+#[derive(Accounts)]
+pub struct GetAggregatedPrice<'info> {
+    pub price_aggregator: Account<'info, PriceAggregator>,
+}
+
+/// One feed of an aggregator and its share of the average
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub struct PriceSource {
+    pub price_feed: Pubkey,
+    pub weight_bps: u16,
+}
+
+impl PriceSource {
+    pub const LEN: usize = 32 + // price_feed
+        2; // weight_bps
+}
+
+/// Weighted blend of several feeds for one asset, e.g. 70% Pyth and 30%
+/// Switchboard
+#[account]
+pub struct PriceAggregator {
+    pub authority: Pubkey,
+    pub aggregator_id: u64,
+    pub sources: Vec<PriceSource>,
+    pub bump: u8,
+}
+
+impl PriceAggregator {
+    pub const LEN: usize = 8 + // discriminator
+        32 + // authority
+        8 +  // aggregator_id
+        4 + MAX_PRICE_SOURCES * PriceSource::LEN + // sources
+        1;   // bump
+}
+
+/// Check `sources` holds 1 to `MAX_PRICE_SOURCES` distinct feeds with
+/// positive weights summing to 10_000 bps
+pub fn validate_price_sources(sources: &[PriceSource]) -> Result<()> {
+    require!(
+        !sources.is_empty() && sources.len() <= MAX_PRICE_SOURCES,
+        OracleError::InvalidPriceSources
+    );
+    require!(sources.iter().all(|source| source.weight_bps > 0), OracleError::InvalidPriceSources);
+    require!(
+        sources.iter().map(|source| source.weight_bps as u64).sum::<u64>() == BPS_DENOMINATOR,
+        OracleError::InvalidPriceSources
+    );
+    for (i, source) in sources.iter().enumerate() {
+        require!(
+            sources[..i].iter().all(|other| other.price_feed != source.price_feed),
+            OracleError::InvalidPriceSources
+        );
+    }
+    Ok(())
+}
+
+/// Address of `authority`'s `PriceAggregator` number `aggregator_id`
+pub fn price_aggregator(authority: &Pubkey, aggregator_id: u64) -> Pubkey {
+    Pubkey::find_program_address(
+        &[b"price-aggregator", authority.as_ref(), &aggregator_id.to_le_bytes()],
+        &crate::ID,
+    )
+    .0
+}
+
+/// Weighted average of an aggregator's sources, with how far apart they
+/// were so callers can refuse to trade on feeds that disagree
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug, PartialEq, Eq)]
+pub struct AggregatedPrice {
+    pub price: i64,
+    pub conf: u64, // Weighted average of the sources' confidences
+    pub expo: i32,
+    pub publish_time: i64,      // Of the oldest source
+    pub max_deviation_bps: u64, // Highest minus lowest source price, in bps of `price`
+    pub slot: u64,              // Of the oldest source
+}
+
+impl AggregatedPrice {
+    /// The average as a plain price, for code written against `get_price`
+    pub fn price_data(&self) -> PriceData {
+        PriceData {
+            price: self.price,
+            conf: self.conf,
+            expo: self.expo,
+            publish_time: self.publish_time,
+            slot: self.slot,
+        }
+    }
+}
+
+/// Average `prices` by their weights in bps, at the finest exponent among
+/// them. `None` without prices, for a non-positive price or when the
+/// rescaled values overflow.
+pub fn aggregate_prices(prices: &[(PriceData, u16)]) -> Option<AggregatedPrice> {
+    let expo = prices.iter().map(|(price, _)| price.expo).min()?;
+    let mut total_weight: u128 = 0;
+    let mut weighted_price: u128 = 0;
+    let mut weighted_conf: u128 = 0;
+    let mut lowest = u128::MAX;
+    let mut highest = 0;
+    let mut publish_time = i64::MAX;
+    let mut slot = u64::MAX;
+    for (price, weight_bps) in prices {
+        if price.price <= 0 {
+            return None;
+        }
+        let scale = 10u128.checked_pow(price.expo.checked_sub(expo)? as u32)?;
+        let value = (price.price as u128).checked_mul(scale)?;
+        let conf = (price.conf as u128).checked_mul(scale)?;
+        total_weight += *weight_bps as u128;
+        weighted_price = weighted_price.checked_add(value.checked_mul(*weight_bps as u128)?)?;
+        weighted_conf = weighted_conf.checked_add(conf.checked_mul(*weight_bps as u128)?)?;
+        lowest = lowest.min(value);
+        highest = highest.max(value);
+        publish_time = publish_time.min(price.publish_time);
+        slot = slot.min(price.slot);
+    }
+    let price = weighted_price.checked_div(total_weight)?;
+    if price == 0 {
+        return None;
+    }
+    let max_deviation_bps = (highest - lowest) * BPS_DENOMINATOR as u128 / price;
+    Some(AggregatedPrice {
+        price: i64::try_from(price).ok()?,
+        conf: u64::try_from(weighted_conf / total_weight).ok()?,
+        expo,
+        publish_time,
+        max_deviation_bps: max_deviation_bps.min(u64::MAX as u128) as u64,
+        slot,
+    })
+}
@@ -0,0 +1,346 @@
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::{
+    ed25519_program,
+    instruction::Instruction,
+    sysvar::instructions::{self as instructions_sysvar, load_current_index_checked, load_instruction_at_checked},
+};
+
+use crate::OracleError;
+
+/// Trusted Lazer signers one `LazerSigners` holds
+pub const MAX_LAZER_SIGNERS: usize = 4;
+
+/// Prefix of a Lazer update in the Solana (ed25519) format
+pub const LAZER_SOLANA_FORMAT_MAGIC: u32 = 0x821a_01b9;
+
+/// Prefix of the signed payload
+pub const LAZER_PAYLOAD_FORMAT_MAGIC: u32 = 0x93c7_d375;
+
+/// Solana's target slot time, to date a Lazer price in slots
+pub const SLOT_DURATION_US: u64 = 400_000;
+
+// Payload property ids
+const PROPERTY_PRICE: u8 = 0;
+const PROPERTY_BEST_BID_PRICE: u8 = 1;
+const PROPERTY_BEST_ASK_PRICE: u8 = 2;
+const PROPERTY_PUBLISHER_COUNT: u8 = 3;
+const PROPERTY_EXPONENT: u8 = 4;
+
+// Layout of a single-signature Ed25519 program instruction
+const ED25519_OFFSETS_START: usize = 2;
+const ED25519_DATA_START: usize = ED25519_OFFSETS_START + 14;
+const ED25519_SELF: u16 = u16::MAX; // Instruction index meaning "this instruction"
+
+#[derive(Accounts)]
+pub struct InitializeLazerSigners<'info> {
+    #[account(
+        init,
+        payer = admin,
+        space = LazerSigners::LEN,
+        seeds = [b"lazer-signers"],
+        bump
+    )]
+    pub lazer_signers: Account<'info, LazerSigners>,
+
+    #[account(mut)]
+    pub admin: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct SetLazerSigner<'info> {
+    #[account(mut, seeds = [b"lazer-signers"], bump = lazer_signers.bump)]
+    pub lazer_signers: Account<'info, LazerSigners>,
+
+    pub admin: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct GetLazerPrice<'info> {
+    #[account(seeds = [b"lazer-signers"], bump = lazer_signers.bump)]
+    pub lazer_signers: Account<'info, LazerSigners>,
+
+    /// CHECK: address-checked instructions sysvar
+    #[account(address = instructions_sysvar::ID)]
+    pub instructions: UncheckedAccount<'info>,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub struct LazerSigner {
+    pub pubkey: Pubkey,
+    pub expires_at: i64,
+}
+
+impl LazerSigner {
+    pub const LEN: usize = 32 + // pubkey
+        8; // expires_at
+}
+
+/// Keys whose Pyth Lazer updates `get_lazer_price` accepts, each until its
+/// expiry so Pyth's signer rotations can overlap
+#[account]
+pub struct LazerSigners {
+    pub signers: Vec<LazerSigner>,
+    pub bump: u8,
+}
+
+impl LazerSigners {
+    pub const LEN: usize = 8 + // discriminator
+        4 + MAX_LAZER_SIGNERS * LazerSigner::LEN + // signers
+        1; // bump
+
+    pub fn is_trusted(&self, pubkey: &Pubkey, now: i64) -> bool {
+        self.signers.iter().any(|signer| signer.pubkey == *pubkey && signer.expires_at > now)
+    }
+}
+
+/// Address of the `LazerSigners`
+pub fn lazer_signers() -> Pubkey {
+    Pubkey::find_program_address(&[b"lazer-signers"], &crate::ID).0
+}
+
+/// A Lazer update in the Solana format: magic, ed25519 signature and
+/// public key, then the length-prefixed payload they sign
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct LazerUpdate<'a> {
+    pub signature: &'a [u8],
+    pub public_key: Pubkey,
+    pub payload: &'a [u8],
+}
+
+impl<'a> LazerUpdate<'a> {
+    pub fn parse(data: &'a [u8]) -> Option<Self> {
+        let mut reader = Reader(data);
+        if reader.u32()? != LAZER_SOLANA_FORMAT_MAGIC {
+            return None;
+        }
+        let signature = reader.take(64)?;
+        let public_key = Pubkey::try_from(reader.take(32)?).ok()?;
+        let len = reader.u16()? as usize;
+        let payload = reader.take(len)?;
+        Some(LazerUpdate {
+            signature,
+            public_key,
+            payload,
+        })
+    }
+
+    /// The Ed25519 program instruction verifying this update's signature,
+    /// to be placed right before the instruction reading it
+    pub fn ed25519_instruction(&self) -> Instruction {
+        let public_key_offset = ED25519_DATA_START;
+        let signature_offset = public_key_offset + 32;
+        let message_offset = signature_offset + 64;
+
+        let mut data = Vec::with_capacity(message_offset + self.payload.len());
+        data.extend_from_slice(&[1, 0]); // One signature, padding
+        for value in [
+            signature_offset as u16,
+            ED25519_SELF,
+            public_key_offset as u16,
+            ED25519_SELF,
+            message_offset as u16,
+            self.payload.len() as u16,
+            ED25519_SELF,
+        ] {
+            data.extend_from_slice(&value.to_le_bytes());
+        }
+        data.extend_from_slice(self.public_key.as_ref());
+        data.extend_from_slice(self.signature);
+        data.extend_from_slice(self.payload);
+        Instruction {
+            program_id: ed25519_program::ID,
+            accounts: Vec::new(),
+            data,
+        }
+    }
+
+    /// Whether `ed25519` (an Ed25519 program instruction, which the
+    /// runtime has already verified) checks exactly this update's
+    /// signature, key and payload
+    fn verified_by(&self, ed25519: &Instruction) -> bool {
+        if ed25519.program_id != ed25519_program::ID || ed25519.data.first() != Some(&1) {
+            return false;
+        }
+        let Some(mut offsets) = ed25519.data.get(ED25519_OFFSETS_START..ED25519_DATA_START).map(Reader) else {
+            return false;
+        };
+        let mut field = || offsets.u16().unwrap_or_default();
+        let (signature_offset, signature_ix) = (field() as usize, field());
+        let (public_key_offset, public_key_ix) = (field() as usize, field());
+        let (message_offset, message_size, message_ix) = (field() as usize, field() as usize, field());
+        if [signature_ix, public_key_ix, message_ix] != [ED25519_SELF; 3] {
+            return false;
+        }
+        let slice = |offset: usize, len: usize| ed25519.data.get(offset..offset + len);
+        slice(signature_offset, 64) == Some(self.signature)
+            && slice(public_key_offset, 32) == Some(self.public_key.as_ref())
+            && slice(message_offset, message_size) == Some(self.payload)
+    }
+}
+
+/// The signed body of a Lazer update
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct LazerPayload {
+    pub timestamp_us: u64,
+    pub channel: u8,
+    pub feeds: Vec<LazerFeed>,
+}
+
+/// One feed of a Lazer payload; properties the subscription did not
+/// request are `None`, as are prices the feed had none for
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct LazerFeed {
+    pub feed_id: u32,
+    pub price: Option<i64>,
+    pub best_bid_price: Option<i64>,
+    pub best_ask_price: Option<i64>,
+    pub publisher_count: Option<u16>,
+    pub exponent: Option<i16>,
+}
+
+impl LazerPayload {
+    /// `None` for a malformed payload or an unknown property, whose size
+    /// cannot be skipped
+    pub fn parse(data: &[u8]) -> Option<Self> {
+        let mut reader = Reader(data);
+        if reader.u32()? != LAZER_PAYLOAD_FORMAT_MAGIC {
+            return None;
+        }
+        let timestamp_us = reader.u64()?;
+        let channel = reader.u8()?;
+        let mut feeds = Vec::new();
+        for _ in 0..reader.u8()? {
+            let mut feed = LazerFeed {
+                feed_id: reader.u32()?,
+                ..LazerFeed::default()
+            };
+            for _ in 0..reader.u8()? {
+                let nonzero = |price: i64| (price != 0).then_some(price);
+                match reader.u8()? {
+                    PROPERTY_PRICE => feed.price = nonzero(reader.i64()?),
+                    PROPERTY_BEST_BID_PRICE => feed.best_bid_price = nonzero(reader.i64()?),
+                    PROPERTY_BEST_ASK_PRICE => feed.best_ask_price = nonzero(reader.i64()?),
+                    PROPERTY_PUBLISHER_COUNT => feed.publisher_count = Some(reader.u16()?),
+                    PROPERTY_EXPONENT => feed.exponent = Some(reader.u16()? as i16),
+                    _ => return None,
+                }
+            }
+            feeds.push(feed);
+        }
+        reader.0.is_empty().then_some(LazerPayload {
+            timestamp_us,
+            channel,
+            feeds,
+        })
+    }
+}
+
+/// One feed's price from a verified Lazer update
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug, PartialEq, Eq)]
+pub struct LazerPrice {
+    pub feed_id: u32,
+    pub price: i64,
+    pub conf: u64, // Half the bid-ask spread; 0 without both sides
+    pub expo: i32,
+    pub timestamp_us: u64, // Microseconds, for sub-slot freshness checks
+}
+
+impl LazerPrice {
+    /// `feed_id`'s price in `payload`; `None` when the feed or its price
+    /// and exponent are missing
+    pub fn from_payload(payload: &LazerPayload, feed_id: u32) -> Option<Self> {
+        let feed = payload.feeds.iter().find(|feed| feed.feed_id == feed_id)?;
+        let conf = match (feed.best_bid_price, feed.best_ask_price) {
+            (Some(bid), Some(ask)) => (ask as i128 - bid as i128).unsigned_abs() as u64 / 2,
+            _ => 0,
+        };
+        Some(LazerPrice {
+            feed_id,
+            price: feed.price?,
+            conf,
+            expo: feed.exponent? as i32,
+            timestamp_us: payload.timestamp_us,
+        })
+    }
+
+    /// Microseconds from the update's timestamp to `clock`, 0 within the
+    /// clock's current second
+    pub fn age_us(&self, clock: &Clock) -> u64 {
+        (clock.unix_timestamp.max(0) as u64)
+            .saturating_mul(1_000_000)
+            .saturating_sub(self.timestamp_us)
+    }
+
+    /// Whether the update is timestamped after the clock's current second
+    pub fn is_future(&self, clock: &Clock) -> bool {
+        self.timestamp_us / 1_000_000 > clock.unix_timestamp.max(0) as u64
+    }
+
+    /// As a `get_price` result, with the timestamp truncated to seconds and
+    /// the observation slot dated back from `clock` by the update's age, so
+    /// slot-based freshness checks see how old the price really is
+    pub fn price_data(&self, clock: &Clock) -> crate::PriceData {
+        crate::PriceData {
+            price: self.price,
+            conf: self.conf,
+            expo: self.expo,
+            publish_time: (self.timestamp_us / 1_000_000) as i64,
+            slot: clock.slot.saturating_sub(self.age_us(clock) / SLOT_DURATION_US),
+        }
+    }
+}
+
+/// Check `update` is signed by a trusted Lazer signer, with its Ed25519
+/// verification the instruction before the current one
+pub fn verify_lazer_update(
+    update: &[u8],
+    signers: &LazerSigners,
+    instructions: &AccountInfo,
+    now: i64,
+) -> Result<LazerPayload> {
+    let update = LazerUpdate::parse(update).ok_or(OracleError::InvalidLazerUpdate)?;
+    require!(signers.is_trusted(&update.public_key, now), OracleError::UntrustedLazerSigner);
+
+    let current = load_current_index_checked(instructions)?;
+    require!(current > 0, OracleError::LazerSignatureNotVerified);
+    let ed25519 = load_instruction_at_checked(current as usize - 1, instructions)?;
+    require!(update.verified_by(&ed25519), OracleError::LazerSignatureNotVerified);
+
+    LazerPayload::parse(update.payload).ok_or(error!(OracleError::InvalidLazerUpdate))
+}
+
+/// Little-endian cursor over a byte slice
+struct Reader<'a>(&'a [u8]);
+
+impl<'a> Reader<'a> {
+    fn take(&mut self, len: usize) -> Option<&'a [u8]> {
+        if len > self.0.len() {
+            return None;
+        }
+        let (head, tail) = self.0.split_at(len);
+        self.0 = tail;
+        Some(head)
+    }
+
+    fn u8(&mut self) -> Option<u8> {
+        Some(self.take(1)?[0])
+    }
+
+    fn u16(&mut self) -> Option<u16> {
+        Some(u16::from_le_bytes(self.take(2)?.try_into().ok()?))
+    }
+
+    fn u32(&mut self) -> Option<u32> {
+        Some(u32::from_le_bytes(self.take(4)?.try_into().ok()?))
+    }
+
+    fn u64(&mut self) -> Option<u64> {
+        Some(u64::from_le_bytes(self.take(8)?.try_into().ok()?))
+    }
+
+    fn i64(&mut self) -> Option<i64> {
+        Some(i64::from_le_bytes(self.take(8)?.try_into().ok()?))
+    }
+}
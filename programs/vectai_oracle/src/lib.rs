@@ -3,6 +3,23 @@
 // For production, integrate properly with Pyth after resolving dependencies
 
 use anchor_lang::prelude::*;
+use vectai_common::is_admin;
+
+// Per-feed monitors that flag abnormal moves between consecutive reads
+mod monitor;
+pub use monitor::*;
+
+// Weighted averages over several feeds of one asset
+mod aggregate;
+pub use aggregate::*;
+
+// Per-consumer handling of prices older than they accept
+mod policy;
+pub use policy::*;
+
+// Signed low-latency prices from Pyth Lazer
+mod lazer;
+pub use lazer::*;
 
 declare_id!("8FWpTEk2NPut6MrKXiCGVzz9ZY247fcYGdL9TEoXFqzw");
 
@@ -13,19 +30,279 @@ pub mod vectai_oracle {
     /// Mock price fetch - returns a fixed price for testing
     /// In production, this would fetch from Pyth price feeds
     pub fn get_price(_ctx: Context<GetPrice>) -> Result<PriceData> {
-        msg!("⚠️  Using mock price data for testing");
-        
-        // Mock BTC price: $45,000
-        let price_data = PriceData {
-            price: 45000,
-            conf: 100,
-            expo: 0,
-            publish_time: Clock::get()?.unix_timestamp,
-        };
-        
-        msg!("📊 Mock price: ${}", price_data.price);
+        read_price()
+    }
+
+    /// The feed's price inverted, e.g. USD/SOL from a SOL/USD feed, for
+    /// strategies whose input asset is the feed's quote
+    pub fn get_inverse_price(_ctx: Context<GetPrice>) -> Result<PriceData> {
+        let inverse = read_price()?.inverse().ok_or(OracleError::InvalidPrice)?;
+        msg!("📊 Inverse price: {} x 10^{}", inverse.price, inverse.expo);
+        Ok(inverse)
+    }
+
+    /// Create `authority`'s monitor for `price_feed`, flagging moves of
+    /// more than `max_move_bps` between consecutive monitored reads
+    pub fn initialize_price_monitor(ctx: Context<InitializePriceMonitor>, max_move_bps: u16) -> Result<()> {
+        require!(max_move_bps > 0, OracleError::InvalidMaxPriceMove);
+
+        let monitor = &mut ctx.accounts.price_monitor;
+        monitor.authority = ctx.accounts.authority.key();
+        monitor.price_feed = ctx.accounts.price_feed.key();
+        monitor.max_move_bps = max_move_bps;
+        monitor.last_price = 0;
+        monitor.last_expo = 0;
+        monitor.last_publish_time = 0;
+        monitor.bump = ctx.bumps.price_monitor;
+
+        msg!("Price monitor created for {} at {} bps", monitor.price_feed, max_move_bps);
+        Ok(())
+    }
+
+    /// Change the move that counts as abnormal (monitor authority only)
+    pub fn set_max_price_move(ctx: Context<SetMaxPriceMove>, max_move_bps: u16) -> Result<()> {
+        require!(max_move_bps > 0, OracleError::InvalidMaxPriceMove);
+        ctx.accounts.price_monitor.max_move_bps = max_move_bps;
+        Ok(())
+    }
+
+    /// Read the price like `get_price` and compare it with the monitor's
+    /// previous read, emitting `AbnormalPriceMove` past the threshold;
+    /// anyone may crank it
+    pub fn monitor_price(ctx: Context<MonitorPrice>) -> Result<PriceData> {
+        let price_data = read_price()?;
+        let monitor = &mut ctx.accounts.price_monitor;
+
+        if let Some(move_bps) = monitor.move_bps(&price_data) {
+            if move_bps > monitor.max_move_bps as u64 {
+                emit!(AbnormalPriceMove {
+                    price_feed: monitor.price_feed,
+                    monitor: monitor.key(),
+                    previous_price: monitor.last_price,
+                    price: price_data.price,
+                    expo: price_data.expo,
+                    move_bps,
+                    previous_publish_time: monitor.last_publish_time,
+                    publish_time: price_data.publish_time,
+                });
+                msg!("⚠️  Abnormal price move: {} bps", move_bps);
+            }
+        }
+
+        monitor.last_price = price_data.price;
+        monitor.last_expo = price_data.expo;
+        monitor.last_publish_time = price_data.publish_time;
         Ok(price_data)
     }
+
+    /// Create `authority`'s aggregator number `aggregator_id` over
+    /// `sources`, whose weights must sum to 10_000 bps
+    pub fn initialize_price_aggregator(
+        ctx: Context<InitializePriceAggregator>,
+        aggregator_id: u64,
+        sources: Vec<PriceSource>,
+    ) -> Result<()> {
+        validate_price_sources(&sources)?;
+
+        let aggregator = &mut ctx.accounts.price_aggregator;
+        aggregator.authority = ctx.accounts.authority.key();
+        aggregator.aggregator_id = aggregator_id;
+        aggregator.sources = sources;
+        aggregator.bump = ctx.bumps.price_aggregator;
+
+        msg!("Price aggregator {} created over {} sources", aggregator_id, aggregator.sources.len());
+        Ok(())
+    }
+
+    /// Replace the aggregator's feeds and weights (aggregator authority only)
+    pub fn set_price_sources(ctx: Context<SetPriceSources>, sources: Vec<PriceSource>) -> Result<()> {
+        validate_price_sources(&sources)?;
+        ctx.accounts.price_aggregator.sources = sources;
+        Ok(())
+    }
+
+    /// Read every source feed, passed as remaining accounts in the
+    /// aggregator's order, and return their weighted average with the
+    /// spread between them
+    pub fn get_aggregated_price(ctx: Context<GetAggregatedPrice>) -> Result<AggregatedPrice> {
+        let sources = &ctx.accounts.price_aggregator.sources;
+        require!(ctx.remaining_accounts.len() == sources.len(), OracleError::PriceSourceMismatch);
+
+        let mut prices = Vec::with_capacity(sources.len());
+        for (source, price_feed) in sources.iter().zip(ctx.remaining_accounts) {
+            require_keys_eq!(price_feed.key(), source.price_feed, OracleError::PriceSourceMismatch);
+            prices.push((read_price()?, source.weight_bps));
+        }
+        let aggregated = aggregate_prices(&prices).ok_or(OracleError::InvalidPrice)?;
+
+        msg!(
+            "📊 Aggregated price: {} x 10^{} (max deviation {} bps)",
+            aggregated.price,
+            aggregated.expo,
+            aggregated.max_deviation_bps
+        );
+        Ok(aggregated)
+    }
+
+    /// Create `authority`'s staleness policy for `price_feed`: prices older
+    /// than `max_age_secs` are handled per `stale_price_policy`
+    pub fn initialize_price_policy(
+        ctx: Context<InitializePricePolicy>,
+        max_age_secs: u32,
+        stale_price_policy: StalePricePolicy,
+    ) -> Result<()> {
+        validate_price_policy(max_age_secs, stale_price_policy)?;
+
+        let policy = &mut ctx.accounts.price_policy;
+        policy.authority = ctx.accounts.authority.key();
+        policy.price_feed = ctx.accounts.price_feed.key();
+        policy.max_age_secs = max_age_secs;
+        policy.stale_price_policy = stale_price_policy;
+        policy.last_good_price = 0;
+        policy.last_good_conf = 0;
+        policy.last_good_expo = 0;
+        policy.last_good_publish_time = 0;
+        policy.bump = ctx.bumps.price_policy;
+
+        msg!("Price policy created for {}: max age {}s", policy.price_feed, max_age_secs);
+        Ok(())
+    }
+
+    /// Create the protocol's policy for `price_feed`, owned by the
+    /// timelock so its max age only changes through a queued operation
+    /// (admin only)
+    pub fn initialize_protocol_price_policy(
+        ctx: Context<InitializeProtocolPricePolicy>,
+        max_age_secs: u32,
+        stale_price_policy: StalePricePolicy,
+    ) -> Result<()> {
+        require!(is_admin(&ctx.accounts.admin.key()), OracleError::UnauthorizedAdmin);
+        validate_price_policy(max_age_secs, stale_price_policy)?;
+
+        let policy = &mut ctx.accounts.price_policy;
+        policy.authority = ctx.accounts.timelock_authority.key();
+        policy.price_feed = ctx.accounts.price_feed.key();
+        policy.max_age_secs = max_age_secs;
+        policy.stale_price_policy = stale_price_policy;
+        policy.last_good_price = 0;
+        policy.last_good_conf = 0;
+        policy.last_good_expo = 0;
+        policy.last_good_publish_time = 0;
+        policy.bump = ctx.bumps.price_policy;
+
+        msg!("Protocol price policy created for {}: max age {}s", policy.price_feed, max_age_secs);
+        Ok(())
+    }
+
+    /// Change the maximum age and stale handling (policy authority only)
+    pub fn set_price_policy(
+        ctx: Context<SetPricePolicy>,
+        max_age_secs: u32,
+        stale_price_policy: StalePricePolicy,
+    ) -> Result<()> {
+        validate_price_policy(max_age_secs, stale_price_policy)?;
+        let policy = &mut ctx.accounts.price_policy;
+        policy.max_age_secs = max_age_secs;
+        policy.stale_price_policy = stale_price_policy;
+
+        emit!(PricePolicyUpdated {
+            price_policy: policy.key(),
+            price_feed: policy.price_feed,
+            authority: policy.authority,
+            max_age_secs,
+            stale_price_policy,
+        });
+        Ok(())
+    }
+
+    /// Read the price like `get_price`, applying the policy when it is
+    /// older than `max_age_secs`; fresh reads become the last good price
+    pub fn get_price_with_policy(ctx: Context<GetPriceWithPolicy>) -> Result<PolicyPrice> {
+        let price_data = read_price()?;
+        let now = Clock::get()?.unix_timestamp;
+        let price = ctx.accounts.price_policy.apply(price_data, now).ok_or(OracleError::StalePrice)?;
+        if price.stale {
+            msg!("⚠️  Stale price from {}: ±{}", price.publish_time, price.conf);
+        }
+        Ok(price)
+    }
+
+    /// Create the empty set of trusted Pyth Lazer signers (admin only)
+    pub fn initialize_lazer_signers(ctx: Context<InitializeLazerSigners>) -> Result<()> {
+        require!(is_admin(&ctx.accounts.admin.key()), OracleError::UnauthorizedAdmin);
+        let lazer_signers = &mut ctx.accounts.lazer_signers;
+        lazer_signers.signers = Vec::new();
+        lazer_signers.bump = ctx.bumps.lazer_signers;
+        Ok(())
+    }
+
+    /// Trust `signer` until `expires_at`, or stop trusting it with an
+    /// expiry already past (timelock only)
+    pub fn set_lazer_signer(ctx: Context<SetLazerSigner>, signer: Pubkey, expires_at: i64) -> Result<()> {
+        require!(is_timelock(&ctx.accounts.admin.key()), OracleError::UnauthorizedAdmin);
+        let now = Clock::get()?.unix_timestamp;
+        let signers = &mut ctx.accounts.lazer_signers.signers;
+
+        signers.retain(|trusted| trusted.pubkey != signer);
+        if expires_at > now {
+            require!(signers.len() < MAX_LAZER_SIGNERS, OracleError::TooManyLazerSigners);
+            signers.push(LazerSigner { pubkey: signer, expires_at });
+        }
+        msg!("Lazer signer {} trusted until {}", signer, expires_at);
+        Ok(())
+    }
+
+    /// Verify a signed Pyth Lazer `update` passed in the instruction data
+    /// and return `feed_id`'s price from it. The transaction must verify
+    /// the update's signature with an Ed25519 program instruction right
+    /// before this one (`LazerUpdate::ed25519_instruction`). Updates signed
+    /// more than `max_age_us` microseconds ago, or after the clock's current
+    /// second, are rejected.
+    pub fn get_lazer_price(
+        ctx: Context<GetLazerPrice>,
+        update: Vec<u8>,
+        feed_id: u32,
+        max_age_us: u64,
+    ) -> Result<LazerPrice> {
+        let clock = Clock::get()?;
+        let payload = verify_lazer_update(
+            &update,
+            &ctx.accounts.lazer_signers,
+            &ctx.accounts.instructions.to_account_info(),
+            clock.unix_timestamp,
+        )?;
+        let price = LazerPrice::from_payload(&payload, feed_id).ok_or(OracleError::LazerFeedNotFound)?;
+        require!(!price.is_future(&clock), OracleError::LazerUpdateFromFuture);
+        require!(price.age_us(&clock) <= max_age_us, OracleError::StaleLazerUpdate);
+        msg!("📊 Lazer price: {} x 10^{} at {}us", price.price, price.expo, price.timestamp_us);
+        Ok(price)
+    }
+}
+
+fn validate_price_policy(max_age_secs: u32, stale_price_policy: StalePricePolicy) -> Result<()> {
+    require!(max_age_secs > 0, OracleError::InvalidPricePolicy);
+    if let StalePricePolicy::InflateConfidence { conf_bps_per_sec } = stale_price_policy {
+        require!(conf_bps_per_sec > 0, OracleError::InvalidPricePolicy);
+    }
+    Ok(())
+}
+
+/// The feed's current price (fixed while the oracle is mocked)
+fn read_price() -> Result<PriceData> {
+    msg!("⚠️  Using mock price data for testing");
+    
+    // Mock BTC price: $45,000
+    let clock = Clock::get()?;
+    let price_data = PriceData {
+        price: 45000,
+        conf: 100,
+        expo: 0,
+        publish_time: clock.unix_timestamp,
+        slot: clock.slot,
+    };
+    
+    msg!("📊 Mock price: ${}", price_data.price);
+    Ok(price_data)
 }
 
 #[derive(Accounts)]
@@ -40,4 +317,80 @@ pub struct PriceData {
     pub conf: u64,
     pub expo: i32,
     pub publish_time: i64,
+    pub slot: u64, // Slot the price was observed at
+}
+
+/// Significant digits kept in an inverted price
+pub const INVERSE_PRICE_DIGITS: u32 = 9;
+
+impl PriceData {
+    /// `1 / price`, rescaled so the mantissa keeps `INVERSE_PRICE_DIGITS`
+    /// significant digits. The confidence keeps its relative size
+    /// (`conf / price`), rounded up; `None` for a non-positive price.
+    pub fn inverse(&self) -> Option<PriceData> {
+        if self.price <= 0 {
+            return None;
+        }
+        let price = self.price as u128;
+        // 10^scale / price has INVERSE_PRICE_DIGITS digits
+        let scale = price.ilog10() + INVERSE_PRICE_DIGITS;
+        let inverse = 10u128.pow(scale) / price;
+        let conf = (self.conf as u128 * inverse).div_ceil(price);
+        Some(PriceData {
+            price: inverse as i64,
+            conf: conf.min(u64::MAX as u128) as u64,
+            expo: self.expo.checked_neg()?.checked_sub(scale as i32)?,
+            publish_time: self.publish_time,
+            slot: self.slot,
+        })
+    }
+
+    /// Slots elapsed between the observation and `current_slot`
+    pub fn slot_age(&self, current_slot: u64) -> u64 {
+        current_slot.saturating_sub(self.slot)
+    }
+
+    /// Whether the price was observed at most `max_age_slots` slots before
+    /// `current_slot`, a freshness bound leaders cannot skew the way they
+    /// can `publish_time`
+    pub fn observed_within(&self, current_slot: u64, max_age_slots: u64) -> bool {
+        self.slot_age(current_slot) <= max_age_slots
+    }
+}
+
+/// Timelock PDA; trusted signer changes must be queued through vectai_timelock
+fn is_timelock(key: &Pubkey) -> bool {
+    *key == vectai_timelock::timelock_authority()
+}
+
+#[error_code]
+pub enum OracleError {
+    #[msg("Max price move must be positive")]
+    InvalidMaxPriceMove,
+    #[msg("Price must be positive to invert")]
+    InvalidPrice,
+    #[msg("Price sources need 1 to 4 distinct feeds with positive weights summing to 10000 bps")]
+    InvalidPriceSources,
+    #[msg("Price feed accounts do not match the aggregator's sources")]
+    PriceSourceMismatch,
+    #[msg("Max price age and confidence inflation must be positive")]
+    InvalidPricePolicy,
+    #[msg("Price is older than the policy's max age")]
+    StalePrice,
+    #[msg("Unauthorized: admin access required")]
+    UnauthorizedAdmin,
+    #[msg("Too many trusted Lazer signers")]
+    TooManyLazerSigners,
+    #[msg("Malformed Pyth Lazer update")]
+    InvalidLazerUpdate,
+    #[msg("Lazer update is not signed by a trusted signer")]
+    UntrustedLazerSigner,
+    #[msg("Lazer update signature is not verified by the preceding Ed25519 instruction")]
+    LazerSignatureNotVerified,
+    #[msg("Feed price and exponent not in the Lazer update")]
+    LazerFeedNotFound,
+    #[msg("Lazer update is older than the maximum age")]
+    StaleLazerUpdate,
+    #[msg("Lazer update is timestamped after the current time")]
+    LazerUpdateFromFuture,
 }
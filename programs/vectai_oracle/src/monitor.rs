@@ -0,0 +1,99 @@
+use anchor_lang::prelude::*;
+
+use crate::PriceData;
+
+#[derive(Accounts)]
+pub struct InitializePriceMonitor<'info> {
+    #[account(
+        init,
+        payer = authority,
+        space = PriceMonitor::LEN,
+        seeds = [b"price-monitor", price_feed.key().as_ref(), authority.key().as_ref()],
+        bump
+    )]
+    pub price_monitor: Account<'info, PriceMonitor>,
+
+    /// CHECK: Price feed account (unused in mock)
+    pub price_feed: UncheckedAccount<'info>,
+
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct SetMaxPriceMove<'info> {
+    #[account(mut, has_one = authority)]
+    pub price_monitor: Account<'info, PriceMonitor>,
+
+    pub authority: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct MonitorPrice<'info> {
+    #[account(
+        mut,
+        seeds = [b"price-monitor", price_feed.key().as_ref(), price_monitor.authority.as_ref()],
+        bump = price_monitor.bump
+    )]
+    pub price_monitor: Account<'info, PriceMonitor>,
+
+    /// CHECK: Price feed account (unused in mock)
+    pub price_feed: UncheckedAccount<'info>,
+}
+
+/// Last monitored read of one feed, per monitoring authority, so each
+/// operator picks its own threshold
+#[account]
+pub struct PriceMonitor {
+    pub authority: Pubkey,
+    pub price_feed: Pubkey,
+    pub max_move_bps: u16,
+    pub last_price: i64, // 0 before the first read
+    pub last_expo: i32,
+    pub last_publish_time: i64,
+    pub bump: u8,
+}
+
+impl PriceMonitor {
+    pub const LEN: usize = 8 + // discriminator
+        32 + // authority
+        32 + // price_feed
+        2 +  // max_move_bps
+        8 +  // last_price
+        4 +  // last_expo
+        8 +  // last_publish_time
+        1;   // bump
+
+    /// Move from the previous read to `price` in basis points of the
+    /// previous price; `None` before the first read or across an exponent
+    /// change, where the prices are not comparable
+    pub fn move_bps(&self, price: &PriceData) -> Option<u64> {
+        if self.last_price == 0 || self.last_expo != price.expo {
+            return None;
+        }
+        let change = (price.price as i128 - self.last_price as i128).unsigned_abs();
+        let move_bps = change * 10_000 / self.last_price.unsigned_abs() as u128;
+        Some(move_bps.min(u64::MAX as u128) as u64)
+    }
+}
+
+/// Address of `authority`'s `PriceMonitor` for `price_feed`
+pub fn price_monitor(price_feed: &Pubkey, authority: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(&[b"price-monitor", price_feed.as_ref(), authority.as_ref()], &crate::ID).0
+}
+
+/// Consecutive monitored reads of a feed differ by more than the
+/// monitor's `max_move_bps`: a feed glitch or a genuine market move
+#[event]
+pub struct AbnormalPriceMove {
+    pub price_feed: Pubkey,
+    pub monitor: Pubkey,
+    pub previous_price: i64,
+    pub price: i64,
+    pub expo: i32,
+    pub move_bps: u64,
+    pub previous_publish_time: i64,
+    pub publish_time: i64,
+}
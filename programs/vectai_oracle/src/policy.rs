@@ -0,0 +1,185 @@
+use anchor_lang::prelude::*;
+
+use crate::PriceData;
+
+#[derive(Accounts)]
+pub struct InitializePricePolicy<'info> {
+    #[account(
+        init,
+        payer = authority,
+        space = PricePolicy::LEN,
+        seeds = [b"price-policy", price_feed.key().as_ref(), authority.key().as_ref()],
+        bump
+    )]
+    pub price_policy: Account<'info, PricePolicy>,
+
+    /// CHECK: Price feed account (unused in mock)
+    pub price_feed: UncheckedAccount<'info>,
+
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+/// A policy whose authority is the timelock PDA, so protocol-wide max ages
+/// change only through queued operations
+#[derive(Accounts)]
+pub struct InitializeProtocolPricePolicy<'info> {
+    #[account(
+        init,
+        payer = admin,
+        space = PricePolicy::LEN,
+        seeds = [b"price-policy", price_feed.key().as_ref(), timelock_authority.key().as_ref()],
+        bump
+    )]
+    pub price_policy: Account<'info, PricePolicy>,
+
+    /// CHECK: Price feed account (unused in mock)
+    pub price_feed: UncheckedAccount<'info>,
+
+    /// CHECK: address-checked timelock PDA
+    #[account(address = vectai_timelock::timelock_authority())]
+    pub timelock_authority: UncheckedAccount<'info>,
+
+    #[account(mut)]
+    pub admin: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct SetPricePolicy<'info> {
+    #[account(mut, has_one = authority)]
+    pub price_policy: Account<'info, PricePolicy>,
+
+    pub authority: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct GetPriceWithPolicy<'info> {
+    #[account(
+        mut,
+        seeds = [b"price-policy", price_feed.key().as_ref(), price_policy.authority.as_ref()],
+        bump = price_policy.bump
+    )]
+    pub price_policy: Account<'info, PricePolicy>,
+
+    /// CHECK: Price feed account (unused in mock)
+    pub price_feed: UncheckedAccount<'info>,
+}
+
+/// What a read returns once the price is older than `max_age_secs`
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum StalePricePolicy {
+    /// Fail the read
+    Reject,
+    /// The last price read within `max_age_secs`, flagged stale; fails
+    /// when there is none yet
+    LastGoodPrice,
+    /// The stale price, its confidence widened by `conf_bps_per_sec` of
+    /// the price for every second past `max_age_secs`
+    InflateConfidence { conf_bps_per_sec: u16 },
+}
+
+impl StalePricePolicy {
+    pub const LEN: usize = 1 + 2;
+}
+
+/// One consumer's staleness handling for one feed, so a keeper bot and a
+/// vault reading the same feed can each pick their own
+#[account]
+pub struct PricePolicy {
+    pub authority: Pubkey,
+    pub price_feed: Pubkey,
+    pub max_age_secs: u32,
+    pub stale_price_policy: StalePricePolicy,
+    pub last_good_price: i64, // 0 before the first fresh read
+    pub last_good_conf: u64,
+    pub last_good_expo: i32,
+    pub last_good_publish_time: i64,
+    pub bump: u8,
+}
+
+impl PricePolicy {
+    pub const LEN: usize = 8 + // discriminator
+        32 + // authority
+        32 + // price_feed
+        4 +  // max_age_secs
+        StalePricePolicy::LEN + // stale_price_policy
+        8 +  // last_good_price
+        8 +  // last_good_conf
+        4 +  // last_good_expo
+        8 +  // last_good_publish_time
+        1;   // bump
+
+    /// `price` as read at `now` under this policy, recording it as the last
+    /// good price when fresh; `None` when the policy rejects it
+    pub fn apply(&mut self, price: PriceData, now: i64) -> Option<PolicyPrice> {
+        let overdue = now.saturating_sub(price.publish_time).saturating_sub(self.max_age_secs as i64);
+        if overdue <= 0 {
+            self.last_good_price = price.price;
+            self.last_good_conf = price.conf;
+            self.last_good_expo = price.expo;
+            self.last_good_publish_time = price.publish_time;
+            return Some(PolicyPrice::new(price, false));
+        }
+
+        match self.stale_price_policy {
+            StalePricePolicy::Reject => None,
+            StalePricePolicy::LastGoodPrice if self.last_good_price == 0 => None,
+            StalePricePolicy::LastGoodPrice => Some(PolicyPrice::new(
+                PriceData {
+                    price: self.last_good_price,
+                    conf: self.last_good_conf,
+                    expo: self.last_good_expo,
+                    publish_time: self.last_good_publish_time,
+                    slot: 0, // Not recorded; `PolicyPrice` bounds age in seconds only
+                },
+                true,
+            )),
+            StalePricePolicy::InflateConfidence { conf_bps_per_sec } => {
+                let widening =
+                    price.price.unsigned_abs() as u128 * conf_bps_per_sec as u128 * overdue as u128 / 10_000;
+                let conf = (price.conf as u128 + widening).min(u64::MAX as u128) as u64;
+                Some(PolicyPrice::new(PriceData { conf, ..price }, true))
+            }
+        }
+    }
+}
+
+/// Address of `authority`'s `PricePolicy` for `price_feed`
+pub fn price_policy(price_feed: &Pubkey, authority: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(&[b"price-policy", price_feed.as_ref(), authority.as_ref()], &crate::ID).0
+}
+
+/// A price read through a `PricePolicy`
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug, PartialEq, Eq)]
+pub struct PolicyPrice {
+    pub price: i64,
+    pub conf: u64,
+    pub expo: i32,
+    pub publish_time: i64,
+    pub stale: bool, // Older than the policy's `max_age_secs`
+}
+
+impl PolicyPrice {
+    fn new(price: PriceData, stale: bool) -> Self {
+        PolicyPrice {
+            price: price.price,
+            conf: price.conf,
+            expo: price.expo,
+            publish_time: price.publish_time,
+            stale,
+        }
+    }
+}
+
+#[event]
+pub struct PricePolicyUpdated {
+    pub price_policy: Pubkey,
+    pub price_feed: Pubkey,
+    pub authority: Pubkey,
+    pub max_age_secs: u32,
+    pub stale_price_policy: StalePricePolicy,
+}
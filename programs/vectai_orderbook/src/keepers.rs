@@ -0,0 +1,247 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token::{Mint, Token, TokenAccount};
+
+use crate::{OrderError, VECTAI_MINT};
+
+/// Time a keeper's unbonding stake stays slashable before it can be
+/// withdrawn (7 days)
+pub const UNBONDING_PERIOD: i64 = 7 * 24 * 60 * 60;
+
+#[derive(Accounts)]
+pub struct InitializeKeeperRegistry<'info> {
+    #[account(
+        init,
+        payer = admin,
+        space = KeeperRegistry::LEN,
+        seeds = [b"keeper-registry"],
+        bump
+    )]
+    pub keeper_registry: Account<'info, KeeperRegistry>,
+
+    #[account(address = VECTAI_MINT @ OrderError::InvalidTokenAccount)]
+    pub vectai_mint: Account<'info, Mint>,
+
+    /// Holds every keeper's bond
+    #[account(
+        init,
+        payer = admin,
+        token::mint = vectai_mint,
+        token::authority = keeper_registry,
+        seeds = [b"keeper-bond-vault"],
+        bump
+    )]
+    pub bond_vault: Account<'info, TokenAccount>,
+
+    #[account(mut)]
+    pub admin: Signer<'info>,
+
+    pub rent: Sysvar<'info, Rent>,
+    pub token_program: Program<'info, Token>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct SetKeeperParams<'info> {
+    #[account(mut, seeds = [b"keeper-registry"], bump = keeper_registry.bump)]
+    pub keeper_registry: Account<'info, KeeperRegistry>,
+
+    pub admin: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct RegisterKeeper<'info> {
+    #[account(mut, seeds = [b"keeper-registry"], bump = keeper_registry.bump)]
+    pub keeper_registry: Account<'info, KeeperRegistry>,
+
+    #[account(
+        init,
+        payer = authority,
+        space = Keeper::LEN,
+        seeds = [b"keeper", authority.key().as_ref()],
+        bump
+    )]
+    pub keeper: Account<'info, Keeper>,
+
+    #[account(mut, address = keeper_registry.bond_vault)]
+    pub bond_vault: Account<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        constraint = authority_vectai_account.mint == VECTAI_MINT @ OrderError::InvalidTokenAccount,
+        constraint = authority_vectai_account.owner == authority.key() @ OrderError::InvalidTokenAccount
+    )]
+    pub authority_vectai_account: Account<'info, TokenAccount>,
+
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    pub token_program: Program<'info, Token>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct ManageBond<'info> {
+    #[account(mut, seeds = [b"keeper-registry"], bump = keeper_registry.bump)]
+    pub keeper_registry: Account<'info, KeeperRegistry>,
+
+    #[account(
+        mut,
+        seeds = [b"keeper", authority.key().as_ref()],
+        bump = keeper.bump,
+        has_one = authority @ OrderError::Unauthorized
+    )]
+    pub keeper: Account<'info, Keeper>,
+
+    #[account(mut, address = keeper_registry.bond_vault)]
+    pub bond_vault: Account<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        constraint = authority_vectai_account.mint == VECTAI_MINT @ OrderError::InvalidTokenAccount,
+        constraint = authority_vectai_account.owner == authority.key() @ OrderError::InvalidTokenAccount
+    )]
+    pub authority_vectai_account: Account<'info, TokenAccount>,
+
+    pub authority: Signer<'info>,
+
+    pub token_program: Program<'info, Token>,
+}
+
+#[derive(Accounts)]
+pub struct DeregisterKeeper<'info> {
+    #[account(mut, seeds = [b"keeper-registry"], bump = keeper_registry.bump)]
+    pub keeper_registry: Account<'info, KeeperRegistry>,
+
+    #[account(
+        mut,
+        close = authority,
+        seeds = [b"keeper", authority.key().as_ref()],
+        bump = keeper.bump,
+        has_one = authority @ OrderError::Unauthorized,
+        constraint = keeper.bonded == 0 && keeper.unbonding == 0 @ OrderError::BondOutstanding
+    )]
+    pub keeper: Account<'info, Keeper>,
+
+    #[account(mut)]
+    pub authority: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct SlashKeeper<'info> {
+    #[account(mut, seeds = [b"keeper-registry"], bump = keeper_registry.bump)]
+    pub keeper_registry: Account<'info, KeeperRegistry>,
+
+    #[account(
+        mut,
+        seeds = [b"keeper", keeper.authority.as_ref()],
+        bump = keeper.bump
+    )]
+    pub keeper: Account<'info, Keeper>,
+
+    #[account(mut, address = keeper_registry.bond_vault)]
+    pub bond_vault: Account<'info, TokenAccount>,
+
+    /// Treasury VECTAI vault; receives the slashed bond
+    #[account(
+        mut,
+        address = vectai_treasury::vault_address(&VECTAI_MINT) @ OrderError::InvalidTokenAccount
+    )]
+    pub treasury_vault: Account<'info, TokenAccount>,
+
+    /// Protocol governance's native treasury
+    pub governance: Signer<'info>,
+
+    pub token_program: Program<'info, Token>,
+}
+
+/// Bond requirements for keepers filling orders, changed through the
+/// timelock
+#[account]
+pub struct KeeperRegistry {
+    pub min_bond: u64, // VECTAI needed to fill orders
+    pub full_tip_bond: u64, // Bond earning the whole tip; smaller bonds earn pro rata
+    pub bond_vault: Pubkey,
+    pub total_bonded: u64, // Active and unbonding
+    pub total_slashed: u64,
+    pub keeper_count: u64,
+    pub bump: u8,
+}
+
+impl KeeperRegistry {
+    pub const LEN: usize = 8 + // discriminator
+        8 +  // min_bond
+        8 +  // full_tip_bond
+        32 + // bond_vault
+        8 +  // total_bonded
+        8 +  // total_slashed
+        8 +  // keeper_count
+        1;   // bump
+
+    /// Share of `tip` earned by a keeper with `bond`: the whole tip at
+    /// `full_tip_bond` and above, pro rata below it
+    pub fn tip_share(&self, tip: u64, bond: u64) -> u64 {
+        if bond >= self.full_tip_bond {
+            return tip;
+        }
+        (tip as u128 * bond as u128 / self.full_tip_bond as u128) as u64
+    }
+}
+
+/// A registered keeper's bond
+#[account]
+pub struct Keeper {
+    pub authority: Pubkey, // Signs fills and bond changes
+    pub bonded: u64, // Active stake
+    pub unbonding: u64, // Still slashable until `unbonds_at`
+    pub unbonds_at: i64,
+    pub fills: u64,
+    pub tips_earned: u64, // Lamports
+    pub slashed: u64,
+    pub registered_at: i64,
+    pub bump: u8,
+}
+
+impl Keeper {
+    pub const LEN: usize = 8 + // discriminator
+        32 + // authority
+        8 +  // bonded
+        8 +  // unbonding
+        8 +  // unbonds_at
+        8 +  // fills
+        8 +  // tips_earned
+        8 +  // slashed
+        8 +  // registered_at
+        1;   // bump
+
+    /// Take up to `amount` from the active bond first, then from the
+    /// unbonding stake. Returns the amount taken.
+    pub fn slash(&mut self, amount: u64) -> u64 {
+        let from_bonded = amount.min(self.bonded);
+        let from_unbonding = (amount - from_bonded).min(self.unbonding);
+        self.bonded -= from_bonded;
+        self.unbonding -= from_unbonding;
+        self.slashed += from_bonded + from_unbonding;
+        from_bonded + from_unbonding
+    }
+}
+
+#[event]
+pub struct KeeperBonded {
+    pub keeper: Pubkey,
+    pub amount: u64,
+    pub bonded: u64,
+}
+
+#[event]
+pub struct KeeperUnbonding {
+    pub keeper: Pubkey,
+    pub amount: u64,
+    pub unbonds_at: i64,
+}
+
+#[event]
+pub struct KeeperSlashed {
+    pub keeper: Pubkey,
+    pub amount: u64,
+    pub reason: [u8; 32], // Hash of the off-chain evidence
+}
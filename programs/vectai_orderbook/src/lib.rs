@@ -0,0 +1,1236 @@
+use anchor_lang::prelude::*;
+use anchor_lang::system_program;
+use anchor_spl::token::{self, CloseAccount, Mint, Token, TokenAccount, Transfer};
+use raydium_swapper::cpi::accounts::SwapAccounts;
+use raydium_swapper::program::RaydiumSwapper;
+use vectai_oracle::cpi::accounts::GetPrice;
+use vectai_oracle::program::VectaiOracle;
+use vectai_protocol::program::VectaiProtocol;
+use vectai_common::{governance_native_treasury, is_admin};
+
+mod keepers;
+pub use keepers::*;
+
+declare_id!("5KwdvcUeUnTN8LLRdsxB8RXCu7aLPas7qjhs3Nn2cqBq");
+
+// Oracle CPI client generated from idls/vectai_oracle.json
+declare_program!(vectai_oracle);
+
+// Trader account types from idls/vectai_trader.json, to check a strategy
+// is paused before its orders are force-cancelled
+declare_program!(vectai_trader);
+
+#[program]
+pub mod vectai_orderbook {
+    use super::*;
+
+    /// Post an oracle-conditional order, escrowing the input tokens and
+    /// the keeper tip
+    #[allow(clippy::too_many_arguments)]
+    pub fn place_order(
+        ctx: Context<PlaceOrder>,
+        order_id: u64,
+        side: OrderSide,
+        kind: OrderKind,
+        trigger_price: i64,
+        amount_in: u64,
+        min_amount_out: u64,
+        expires_at: i64,
+        keeper_tip: u64,
+    ) -> Result<()> {
+        // ✅ CHECKS
+        require!(trigger_price > 0, OrderError::InvalidPrice);
+        require!(amount_in > 0 && min_amount_out > 0, OrderError::InvalidAmount);
+        require!(
+            ctx.accounts.owner_source_token.amount >= amount_in,
+            OrderError::InsufficientBalance
+        );
+        let clock = Clock::get()?;
+        require!(expires_at > clock.unix_timestamp, OrderError::OrderExpired);
+        require!(
+            expires_at - clock.unix_timestamp <= MAX_ORDER_DURATION,
+            OrderError::InvalidInput
+        );
+        require!(keeper_tip <= MAX_KEEPER_TIP, OrderError::InvalidInput);
+
+        // ✅ EFFECTS
+        let order = &mut ctx.accounts.order;
+        order.owner = ctx.accounts.owner.key();
+        order.order_id = order_id;
+        order.input_mint = ctx.accounts.input_mint.key();
+        order.output_mint = ctx.accounts.owner_destination_token.mint;
+        order.source_account = ctx.accounts.owner_source_token.key();
+        order.destination_account = ctx.accounts.owner_destination_token.key();
+        order.escrow = ctx.accounts.escrow.key();
+        order.price_feed = ctx.accounts.price_feed.key();
+        order.side = side;
+        order.kind = kind;
+        order.trigger_price = trigger_price;
+        order.amount_in = amount_in;
+        order.min_amount_out = min_amount_out;
+        order.expires_at = expires_at;
+        order.keeper_tip = keeper_tip;
+        order.linked_order = Pubkey::default();
+        order.created_at = clock.unix_timestamp;
+        order.bump = ctx.bumps.order;
+
+        // ✅ INTERACTIONS: Count the size toward the input mint's exposure
+        // cap before escrowing it
+        vectai_protocol::report_exposure(
+            ctx.accounts.protocol_program.to_account_info(),
+            ctx.accounts.protocol_stats.to_account_info(),
+            ctx.accounts.stats_reporter.to_account_info(),
+            ctx.bumps.stats_reporter,
+            true,
+            ctx.accounts.input_mint.key(),
+            amount_in,
+        )?;
+
+        token::transfer(
+            CpiContext::new(
+                ctx.accounts.token_program.to_account_info(),
+                Transfer {
+                    from: ctx.accounts.owner_source_token.to_account_info(),
+                    to: ctx.accounts.escrow.to_account_info(),
+                    authority: ctx.accounts.owner.to_account_info(),
+                },
+            ),
+            amount_in,
+        )?;
+
+        if keeper_tip > 0 {
+            system_program::transfer(
+                CpiContext::new(
+                    ctx.accounts.system_program.to_account_info(),
+                    system_program::Transfer {
+                        from: ctx.accounts.owner.to_account_info(),
+                        to: ctx.accounts.order.to_account_info(),
+                    },
+                ),
+                keeper_tip,
+            )?;
+        }
+
+        emit!(OrderPlaced {
+            order: ctx.accounts.order.key(),
+            owner: ctx.accounts.owner.key(),
+            side,
+            kind,
+            trigger_price,
+            amount_in,
+            min_amount_out,
+            expires_at,
+            keeper_tip,
+        });
+        msg!("✅ Order #{} placed: {:?} {:?} {} at trigger {}",
+             order_id, kind, side, amount_in, trigger_price);
+        Ok(())
+    }
+
+    /// Link two of the owner's orders as a one-cancels-other pair (e.g. a
+    /// take-profit and a stop-loss on the same tokens)
+    pub fn link_orders(ctx: Context<LinkOrders>) -> Result<()> {
+        // ✅ CHECKS
+        let order_a = &ctx.accounts.order_a;
+        let order_b = &ctx.accounts.order_b;
+        require!(order_a.key() != order_b.key(), OrderError::InvalidLinkedOrder);
+        require!(
+            order_a.linked_order == Pubkey::default() && order_b.linked_order == Pubkey::default(),
+            OrderError::AlreadyLinked
+        );
+        require!(
+            order_a.input_mint == order_b.input_mint,
+            OrderError::InvalidLinkedOrder
+        );
+
+        // ✅ EFFECTS
+        let (key_a, key_b) = (order_a.key(), order_b.key());
+        ctx.accounts.order_a.linked_order = key_b;
+        ctx.accounts.order_b.linked_order = key_a;
+
+        emit!(OrdersLinked {
+            owner: ctx.accounts.owner.key(),
+            order_a: key_a,
+            order_b: key_b,
+        });
+        msg!("✅ Orders #{} and #{} linked (OCO)",
+             ctx.accounts.order_a.order_id, ctx.accounts.order_b.order_id);
+        Ok(())
+    }
+
+    /// Cancel an order and refund the escrow, tip and rent to the owner.
+    /// The owner may cancel at any time; anyone may clean up once expired.
+    /// A linked (OCO) order is unlinked from its sibling, which stays open.
+    pub fn cancel_order(ctx: Context<CancelOrder>) -> Result<()> {
+        // ✅ CHECKS
+        let order = &ctx.accounts.order;
+        let clock = Clock::get()?;
+        require!(
+            ctx.accounts.authority.key() == order.owner || clock.unix_timestamp > order.expires_at,
+            OrderError::Unauthorized
+        );
+        require!(
+            order.linked_order == Pubkey::default() || ctx.accounts.linked_order.is_some(),
+            OrderError::InvalidLinkedOrder
+        );
+
+        // ✅ EFFECTS
+        if let Some(linked) = &mut ctx.accounts.linked_order {
+            linked.linked_order = Pubkey::default();
+        }
+
+        // ✅ INTERACTIONS: Refund escrow, then close it (order closes via `close`)
+        let order_id = order.order_id.to_le_bytes();
+        let seeds: &[&[u8]] = &[b"order", order.owner.as_ref(), &order_id, &[order.bump]];
+        let refund = refund_and_close_escrow(
+            &ctx.accounts.token_program,
+            &ctx.accounts.escrow,
+            &ctx.accounts.owner_source_token.to_account_info(),
+            &ctx.accounts.owner,
+            &ctx.accounts.order,
+            seeds,
+        )?;
+        vectai_protocol::report_exposure(
+            ctx.accounts.protocol_program.to_account_info(),
+            ctx.accounts.protocol_stats.to_account_info(),
+            ctx.accounts.stats_reporter.to_account_info(),
+            ctx.bumps.stats_reporter,
+            false,
+            order.input_mint,
+            order.amount_in,
+        )?;
+
+        emit!(OrderCancelled {
+            order: ctx.accounts.order.key(),
+            owner: ctx.accounts.owner.key(),
+            refunded: refund,
+        });
+        msg!("✅ Order #{} cancelled, refunded {}", ctx.accounts.order.order_id, refund);
+        Ok(())
+    }
+
+    /// Keeper fills an order whose oracle condition is met, settling through
+    /// raydium_swapper into the owner's destination account. The keeper
+    /// must be bonded and earns a share of the tip scaled by its bond; the
+    /// rest of the tip goes back to the owner.
+    ///
+    /// For a linked (OCO) order, remaining accounts must be
+    /// `[linked_order, linked_escrow, linked_source_account]`; the linked
+    /// order is refunded and closed in the same instruction.
+    pub fn fill_order<'info>(ctx: Context<'_, '_, 'info, 'info, FillOrder<'info>>) -> Result<()> {
+        // ✅ CHECKS
+        let order = &ctx.accounts.order;
+        let clock = Clock::get()?;
+        require!(clock.unix_timestamp <= order.expires_at, OrderError::OrderExpired);
+
+        let price_data = vectai_oracle::cpi::get_price(CpiContext::new(
+            ctx.accounts.vectai_oracle_program.to_account_info(),
+            GetPrice {
+                price_feed: ctx.accounts.price_feed.to_account_info(),
+            },
+        ))?
+        .get();
+        require!(price_data.price > 0, OrderError::InvalidPrice);
+        require!(
+            order.is_triggered(price_data.price),
+            OrderError::ConditionNotMet
+        );
+        require!(
+            ctx.accounts.escrow.amount >= order.amount_in,
+            OrderError::InsufficientBalance
+        );
+
+        // ✅ INTERACTIONS: Order PDA signs as the swap user
+        let order_id = order.order_id.to_le_bytes();
+        let seeds: &[&[u8]] = &[b"order", order.owner.as_ref(), &order_id, &[order.bump]];
+        let swap_accounts = SwapAccounts {
+            user_authority: ctx.accounts.order.to_account_info(),
+            user_source_token: ctx.accounts.escrow.to_account_info(),
+            user_destination_token: ctx.accounts.owner_destination_token.to_account_info(),
+            raydium_amm_program: ctx.accounts.raydium_amm_program.to_account_info(),
+            amm: ctx.accounts.amm.to_account_info(),
+            amm_authority: ctx.accounts.amm_authority.to_account_info(),
+            amm_open_orders: ctx.accounts.amm_open_orders.to_account_info(),
+            amm_target_orders: ctx.accounts.amm_target_orders.to_account_info(),
+            pool_coin_token_account: ctx.accounts.pool_coin_token_account.to_account_info(),
+            pool_pc_token_account: ctx.accounts.pool_pc_token_account.to_account_info(),
+            serum_program: ctx.accounts.serum_program.to_account_info(),
+            serum_market: ctx.accounts.serum_market.to_account_info(),
+            serum_bids: ctx.accounts.serum_bids.to_account_info(),
+            serum_asks: ctx.accounts.serum_asks.to_account_info(),
+            serum_event_queue: ctx.accounts.serum_event_queue.to_account_info(),
+            serum_coin_vault: ctx.accounts.serum_coin_vault.to_account_info(),
+            serum_pc_vault: ctx.accounts.serum_pc_vault.to_account_info(),
+            serum_vault_signer: ctx.accounts.serum_vault_signer.to_account_info(),
+            token_program: ctx.accounts.token_program.to_account_info(),
+            protocol_status: ctx.accounts.protocol_status.to_account_info(),
+            network_config: ctx.accounts.network_config.to_account_info(),
+            protocol_stats: ctx.accounts.protocol_stats.to_account_info(),
+            stats_reporter: ctx.accounts.swapper_stats_reporter.to_account_info(),
+            protocol_program: ctx.accounts.protocol_program.to_account_info(),
+            fee_account: ctx.accounts.swap_fee_account.as_ref().map(|a| a.to_account_info()),
+        };
+        // The whole escrow, tokens sent to it since placement included, so
+        // it is empty to close; a larger input only raises the output
+        raydium_swapper::cpi::swap(
+            CpiContext::new_with_signer(
+                ctx.accounts.swapper_program.to_account_info(),
+                swap_accounts,
+                &[seeds],
+            ),
+            ctx.accounts.escrow.amount,
+            order.min_amount_out,
+        )?;
+
+        close_escrow(
+            &ctx.accounts.token_program,
+            &ctx.accounts.escrow,
+            &ctx.accounts.owner,
+            &ctx.accounts.order,
+            seeds,
+        )?;
+        vectai_protocol::report_exposure(
+            ctx.accounts.protocol_program.to_account_info(),
+            ctx.accounts.protocol_stats.to_account_info(),
+            ctx.accounts.stats_reporter.to_account_info(),
+            ctx.bumps.stats_reporter,
+            false,
+            order.input_mint,
+            order.amount_in,
+        )?;
+
+        // Keeper tip comes out of the order account; the rest of its
+        // lamports go back to the owner when it closes
+        let tip = ctx
+            .accounts
+            .keeper_registry
+            .tip_share(ctx.accounts.order.keeper_tip, ctx.accounts.keeper_account.bonded);
+        if tip > 0 {
+            **ctx.accounts.order.to_account_info().try_borrow_mut_lamports()? -= tip;
+            **ctx.accounts.keeper.to_account_info().try_borrow_mut_lamports()? += tip;
+        }
+        let keeper_account = &mut ctx.accounts.keeper_account;
+        keeper_account.fills = keeper_account.fills.checked_add(1).ok_or(OrderError::MathOverflow)?;
+        keeper_account.tips_earned = keeper_account
+            .tips_earned
+            .checked_add(tip)
+            .ok_or(OrderError::MathOverflow)?;
+
+        // One-cancels-other: the linked order can no longer fill
+        let linked_order = ctx.accounts.order.linked_order;
+        if linked_order != Pubkey::default() {
+            let refunded = cancel_linked_order(&ctx, linked_order)?;
+            emit!(LinkedOrderCancelled {
+                order: ctx.accounts.order.key(),
+                linked_order,
+                refunded,
+            });
+        }
+
+        emit!(OrderFilled {
+            order: ctx.accounts.order.key(),
+            owner: ctx.accounts.order.owner,
+            keeper: ctx.accounts.keeper.key(),
+            price: price_data.price,
+            amount_in: ctx.accounts.order.amount_in,
+            keeper_tip: tip,
+        });
+        msg!("✅ Order #{} filled at price {}", ctx.accounts.order.order_id, price_data.price);
+        Ok(())
+    }
+
+    /// Incident response: cancel every open order of a strategy the admin
+    /// has paused (`pause_trader`), refunding each escrow to the owner's
+    /// source account and the tip and rents to the owner. Admin or
+    /// governance only.
+    ///
+    /// Remaining accounts are `[order, escrow, source_account]` per order.
+    /// Both halves of an OCO pair must be passed together.
+    pub fn force_cancel_orders<'info>(
+        ctx: Context<'_, '_, 'info, 'info, ForceCancelOrders<'info>>,
+    ) -> Result<()> {
+        // ✅ CHECKS
+        require!(is_admin(&ctx.accounts.admin.key()), OrderError::Unauthorized);
+        require!(!ctx.accounts.trader_config.is_active, OrderError::StrategyNotFlagged);
+        require!(
+            !ctx.remaining_accounts.is_empty() && ctx.remaining_accounts.chunks_exact(3).remainder().is_empty(),
+            OrderError::InvalidOrderAccounts
+        );
+
+        // ✅ INTERACTIONS: Refund and close each order in turn
+        let cancelled: Vec<Pubkey> = ctx.remaining_accounts.iter().step_by(3).map(|order| order.key()).collect();
+        let mut refunded: u64 = 0;
+        for accounts in ctx.remaining_accounts.chunks_exact(3) {
+            let refund = force_cancel_order(&ctx, accounts, &cancelled)?;
+            refunded = refunded.checked_add(refund).ok_or(OrderError::MathOverflow)?;
+        }
+
+        let orders = (ctx.remaining_accounts.len() / 3) as u32;
+        emit!(OrdersForceCancelled {
+            trader: ctx.accounts.trader_config.key(),
+            owner: ctx.accounts.owner.key(),
+            admin: ctx.accounts.admin.key(),
+            orders,
+            refunded,
+        });
+        msg!("🚨 {} orders of {} force-cancelled, refunded {}", orders, ctx.accounts.owner.key(), refunded);
+        Ok(())
+    }
+
+    /// Create the keeper registry and its bond vault (admin only)
+    pub fn initialize_keeper_registry(
+        ctx: Context<InitializeKeeperRegistry>,
+        min_bond: u64,
+        full_tip_bond: u64,
+    ) -> Result<()> {
+        require!(is_admin(&ctx.accounts.admin.key()), OrderError::Unauthorized);
+        require!(
+            min_bond > 0 && full_tip_bond >= min_bond,
+            OrderError::InvalidInput
+        );
+
+        let keeper_registry = &mut ctx.accounts.keeper_registry;
+        keeper_registry.min_bond = min_bond;
+        keeper_registry.full_tip_bond = full_tip_bond;
+        keeper_registry.bond_vault = ctx.accounts.bond_vault.key();
+        keeper_registry.total_bonded = 0;
+        keeper_registry.total_slashed = 0;
+        keeper_registry.keeper_count = 0;
+        keeper_registry.bump = ctx.bumps.keeper_registry;
+
+        msg!("✅ Keeper registry initialized: min bond {}, full tip at {}",
+             min_bond, full_tip_bond);
+        Ok(())
+    }
+
+    /// Update keeper bond requirements (timelock only)
+    pub fn set_keeper_params(
+        ctx: Context<SetKeeperParams>,
+        min_bond: u64,
+        full_tip_bond: u64,
+    ) -> Result<()> {
+        require!(is_timelock(&ctx.accounts.admin.key()), OrderError::Unauthorized);
+        require!(
+            min_bond > 0 && full_tip_bond >= min_bond,
+            OrderError::InvalidInput
+        );
+
+        let keeper_registry = &mut ctx.accounts.keeper_registry;
+        keeper_registry.min_bond = min_bond;
+        keeper_registry.full_tip_bond = full_tip_bond;
+
+        msg!("Keeper params: min bond {}, full tip at {}", min_bond, full_tip_bond);
+        Ok(())
+    }
+
+    /// Register as a keeper by bonding at least `min_bond` VECTAI
+    pub fn register_keeper(ctx: Context<RegisterKeeper>, amount: u64) -> Result<()> {
+        // ✅ CHECKS
+        require!(
+            amount >= ctx.accounts.keeper_registry.min_bond,
+            OrderError::InsufficientBond
+        );
+
+        // ✅ EFFECTS
+        let keeper = &mut ctx.accounts.keeper;
+        keeper.authority = ctx.accounts.authority.key();
+        keeper.bonded = amount;
+        keeper.unbonding = 0;
+        keeper.unbonds_at = 0;
+        keeper.fills = 0;
+        keeper.tips_earned = 0;
+        keeper.slashed = 0;
+        keeper.registered_at = Clock::get()?.unix_timestamp;
+        keeper.bump = ctx.bumps.keeper;
+
+        let keeper_registry = &mut ctx.accounts.keeper_registry;
+        keeper_registry.total_bonded = keeper_registry
+            .total_bonded
+            .checked_add(amount)
+            .ok_or(OrderError::MathOverflow)?;
+        keeper_registry.keeper_count += 1;
+
+        // ✅ INTERACTIONS
+        token::transfer(
+            CpiContext::new(
+                ctx.accounts.token_program.to_account_info(),
+                Transfer {
+                    from: ctx.accounts.authority_vectai_account.to_account_info(),
+                    to: ctx.accounts.bond_vault.to_account_info(),
+                    authority: ctx.accounts.authority.to_account_info(),
+                },
+            ),
+            amount,
+        )?;
+
+        emit!(KeeperBonded {
+            keeper: ctx.accounts.keeper.key(),
+            amount,
+            bonded: amount,
+        });
+        msg!("✅ Keeper {} registered with {} VECTAI bonded",
+             ctx.accounts.authority.key(), amount);
+        Ok(())
+    }
+
+    /// Add VECTAI to a keeper's active bond
+    pub fn add_bond(ctx: Context<ManageBond>, amount: u64) -> Result<()> {
+        // ✅ CHECKS
+        require!(amount > 0, OrderError::InvalidAmount);
+
+        // ✅ EFFECTS
+        let keeper = &mut ctx.accounts.keeper;
+        keeper.bonded = keeper.bonded.checked_add(amount).ok_or(OrderError::MathOverflow)?;
+        let keeper_registry = &mut ctx.accounts.keeper_registry;
+        keeper_registry.total_bonded = keeper_registry
+            .total_bonded
+            .checked_add(amount)
+            .ok_or(OrderError::MathOverflow)?;
+
+        // ✅ INTERACTIONS
+        token::transfer(
+            CpiContext::new(
+                ctx.accounts.token_program.to_account_info(),
+                Transfer {
+                    from: ctx.accounts.authority_vectai_account.to_account_info(),
+                    to: ctx.accounts.bond_vault.to_account_info(),
+                    authority: ctx.accounts.authority.to_account_info(),
+                },
+            ),
+            amount,
+        )?;
+
+        emit!(KeeperBonded {
+            keeper: ctx.accounts.keeper.key(),
+            amount,
+            bonded: ctx.accounts.keeper.bonded,
+        });
+        msg!("✅ Keeper bond increased to {}", ctx.accounts.keeper.bonded);
+        Ok(())
+    }
+
+    /// Start unbonding part of the active bond. It stays slashable for
+    /// UNBONDING_PERIOD; each request restarts the wait for the whole
+    /// unbonding amount.
+    pub fn request_unbond(ctx: Context<ManageBond>, amount: u64) -> Result<()> {
+        // ✅ CHECKS
+        let keeper = &mut ctx.accounts.keeper;
+        require!(amount > 0, OrderError::InvalidAmount);
+        require!(keeper.bonded >= amount, OrderError::InsufficientBond);
+
+        // ✅ EFFECTS
+        keeper.bonded -= amount;
+        keeper.unbonding = keeper.unbonding.checked_add(amount).ok_or(OrderError::MathOverflow)?;
+        keeper.unbonds_at = Clock::get()?.unix_timestamp + UNBONDING_PERIOD;
+
+        emit!(KeeperUnbonding {
+            keeper: keeper.key(),
+            amount,
+            unbonds_at: keeper.unbonds_at,
+        });
+        msg!("⏳ Keeper unbonding {} VECTAI until {}", amount, keeper.unbonds_at);
+        Ok(())
+    }
+
+    /// Withdraw the unbonding stake once UNBONDING_PERIOD has passed
+    pub fn withdraw_bond(ctx: Context<ManageBond>) -> Result<()> {
+        // ✅ CHECKS
+        let keeper = &ctx.accounts.keeper;
+        let amount = keeper.unbonding;
+        require!(amount > 0, OrderError::InvalidAmount);
+        require!(
+            Clock::get()?.unix_timestamp >= keeper.unbonds_at,
+            OrderError::BondLocked
+        );
+
+        // ✅ EFFECTS
+        ctx.accounts.keeper.unbonding = 0;
+        let keeper_registry = &mut ctx.accounts.keeper_registry;
+        keeper_registry.total_bonded = keeper_registry
+            .total_bonded
+            .checked_sub(amount)
+            .ok_or(OrderError::MathOverflow)?;
+
+        // ✅ INTERACTIONS
+        let seeds: &[&[u8]] = &[b"keeper-registry", &[ctx.accounts.keeper_registry.bump]];
+        token::transfer(
+            CpiContext::new_with_signer(
+                ctx.accounts.token_program.to_account_info(),
+                Transfer {
+                    from: ctx.accounts.bond_vault.to_account_info(),
+                    to: ctx.accounts.authority_vectai_account.to_account_info(),
+                    authority: ctx.accounts.keeper_registry.to_account_info(),
+                },
+                &[seeds],
+            ),
+            amount,
+        )?;
+
+        msg!("✅ Keeper withdrew {} VECTAI", amount);
+        Ok(())
+    }
+
+    /// Deregister a keeper whose bond has been fully withdrawn and return
+    /// its rent
+    pub fn deregister_keeper(ctx: Context<DeregisterKeeper>) -> Result<()> {
+        let keeper_registry = &mut ctx.accounts.keeper_registry;
+        keeper_registry.keeper_count = keeper_registry
+            .keeper_count
+            .checked_sub(1)
+            .ok_or(OrderError::MathOverflow)?;
+
+        msg!("✅ Keeper {} deregistered", ctx.accounts.authority.key());
+        Ok(())
+    }
+
+    /// Slash a keeper for provable misbehaviour, e.g. griefing with fills
+    /// that revert on purpose; `reason` is a hash of the evidence.
+    /// Governance only; the slashed VECTAI goes to the treasury.
+    pub fn slash_keeper(ctx: Context<SlashKeeper>, amount: u64, reason: [u8; 32]) -> Result<()> {
+        // ✅ CHECKS
+        require!(
+            ctx.accounts.governance.key() == governance_native_treasury(),
+            OrderError::Unauthorized
+        );
+        require!(amount > 0, OrderError::InvalidAmount);
+
+        // ✅ EFFECTS
+        let slashed = ctx.accounts.keeper.slash(amount);
+        require!(slashed > 0, OrderError::InsufficientBond);
+        let keeper_registry = &mut ctx.accounts.keeper_registry;
+        keeper_registry.total_bonded = keeper_registry
+            .total_bonded
+            .checked_sub(slashed)
+            .ok_or(OrderError::MathOverflow)?;
+        keeper_registry.total_slashed = keeper_registry
+            .total_slashed
+            .checked_add(slashed)
+            .ok_or(OrderError::MathOverflow)?;
+
+        // ✅ INTERACTIONS
+        let seeds: &[&[u8]] = &[b"keeper-registry", &[ctx.accounts.keeper_registry.bump]];
+        token::transfer(
+            CpiContext::new_with_signer(
+                ctx.accounts.token_program.to_account_info(),
+                Transfer {
+                    from: ctx.accounts.bond_vault.to_account_info(),
+                    to: ctx.accounts.treasury_vault.to_account_info(),
+                    authority: ctx.accounts.keeper_registry.to_account_info(),
+                },
+                &[seeds],
+            ),
+            slashed,
+        )?;
+
+        emit!(KeeperSlashed {
+            keeper: ctx.accounts.keeper.key(),
+            amount: slashed,
+            reason,
+        });
+        msg!("🚨 Keeper {} slashed {} VECTAI", ctx.accounts.keeper.authority, slashed);
+        Ok(())
+    }
+}
+
+// ===== CONSTANTS =====
+
+// VECTAI mint bonded by keepers
+pub const VECTAI_MINT: Pubkey = anchor_lang::solana_program::pubkey!("11111111111111111111111111111111"); // Replace with deployed VECTAI mint
+
+/// Timelock PDA; parameter changes must be queued through vectai_timelock
+fn is_timelock(key: &Pubkey) -> bool {
+    *key == vectai_timelock::timelock_authority()
+}
+
+// Longest an order may rest on the book (30 days)
+pub const MAX_ORDER_DURATION: i64 = 30 * 24 * 60 * 60;
+
+// Upper bound on the keeper tip (0.1 SOL)
+pub const MAX_KEEPER_TIP: u64 = 100_000_000;
+
+// ===== HELPERS =====
+
+/// Close an emptied escrow, returning its rent to the owner
+fn close_escrow<'info>(
+    token_program: &Program<'info, Token>,
+    escrow: &Account<'info, TokenAccount>,
+    owner: &UncheckedAccount<'info>,
+    order: &Account<'info, ConditionalOrder>,
+    seeds: &[&[u8]],
+) -> Result<()> {
+    token::close_account(CpiContext::new_with_signer(
+        token_program.to_account_info(),
+        CloseAccount {
+            account: escrow.to_account_info(),
+            destination: owner.to_account_info(),
+            authority: order.to_account_info(),
+        },
+        &[seeds],
+    ))
+}
+
+/// Refund whatever `escrow` holds, tokens sent to it since placement
+/// included, to `refund_to`, then close it. Returns the amount refunded.
+fn refund_and_close_escrow<'info>(
+    token_program: &Program<'info, Token>,
+    escrow: &Account<'info, TokenAccount>,
+    refund_to: &AccountInfo<'info>,
+    owner: &UncheckedAccount<'info>,
+    order: &Account<'info, ConditionalOrder>,
+    seeds: &[&[u8]],
+) -> Result<u64> {
+    let refund = token::accessor::amount(&escrow.to_account_info())?;
+    if refund > 0 {
+        token::transfer(
+            CpiContext::new_with_signer(
+                token_program.to_account_info(),
+                Transfer {
+                    from: escrow.to_account_info(),
+                    to: refund_to.clone(),
+                    authority: order.to_account_info(),
+                },
+                &[seeds],
+            ),
+            refund,
+        )?;
+    }
+    close_escrow(token_program, escrow, owner, order, seeds)?;
+    Ok(refund)
+}
+
+/// Refund and close the other half of an OCO pair. A linked order that was
+/// already cancelled is skipped. Returns the amount refunded.
+fn cancel_linked_order<'info>(
+    ctx: &Context<'_, '_, 'info, 'info, FillOrder<'info>>,
+    linked_order: Pubkey,
+) -> Result<u64> {
+    require!(ctx.remaining_accounts.len() >= 3, OrderError::InvalidLinkedOrder);
+    let linked_info = &ctx.remaining_accounts[0];
+    require!(linked_info.key() == linked_order, OrderError::InvalidLinkedOrder);
+    if linked_info.owner != &crate::ID || linked_info.data_is_empty() {
+        return Ok(0);
+    }
+
+    let linked = Account::<ConditionalOrder>::try_from(linked_info)?;
+    let escrow = Account::<TokenAccount>::try_from(&ctx.remaining_accounts[1])?;
+    let source = &ctx.remaining_accounts[2];
+    require!(
+        linked.linked_order == ctx.accounts.order.key()
+            && escrow.key() == linked.escrow
+            && source.key() == linked.source_account,
+        OrderError::InvalidLinkedOrder
+    );
+
+    let order_id = linked.order_id.to_le_bytes();
+    let seeds: &[&[u8]] = &[b"order", linked.owner.as_ref(), &order_id, &[linked.bump]];
+    let refund = refund_and_close_escrow(&ctx.accounts.token_program, &escrow, source, &ctx.accounts.owner, &linked, seeds)?;
+    vectai_protocol::report_exposure(
+        ctx.accounts.protocol_program.to_account_info(),
+        ctx.accounts.protocol_stats.to_account_info(),
+        ctx.accounts.stats_reporter.to_account_info(),
+        ctx.bumps.stats_reporter,
+        false,
+        linked.input_mint,
+        linked.amount_in,
+    )?;
+    linked.close(ctx.accounts.owner.to_account_info())?;
+    Ok(refund)
+}
+
+/// Refund and close one order of `force_cancel_orders` from its
+/// `[order, escrow, source_account]`; its OCO sibling must be among the
+/// `cancelled` orders. Returns the amount refunded.
+fn force_cancel_order<'info>(
+    ctx: &Context<'_, '_, 'info, 'info, ForceCancelOrders<'info>>,
+    accounts: &'info [AccountInfo<'info>],
+    cancelled: &[Pubkey],
+) -> Result<u64> {
+    let order = Account::<ConditionalOrder>::try_from(&accounts[0])?;
+    let escrow = Account::<TokenAccount>::try_from(&accounts[1])?;
+    let source = &accounts[2];
+    require!(
+        order.owner == ctx.accounts.owner.key()
+            && escrow.key() == order.escrow
+            && source.key() == order.source_account,
+        OrderError::InvalidOrderAccounts
+    );
+    require!(
+        order.linked_order == Pubkey::default() || cancelled.contains(&order.linked_order),
+        OrderError::InvalidLinkedOrder
+    );
+
+    let order_id = order.order_id.to_le_bytes();
+    let seeds: &[&[u8]] = &[b"order", order.owner.as_ref(), &order_id, &[order.bump]];
+    let refund = refund_and_close_escrow(&ctx.accounts.token_program, &escrow, source, &ctx.accounts.owner, &order, seeds)?;
+    vectai_protocol::report_exposure(
+        ctx.accounts.protocol_program.to_account_info(),
+        ctx.accounts.protocol_stats.to_account_info(),
+        ctx.accounts.stats_reporter.to_account_info(),
+        ctx.bumps.stats_reporter,
+        false,
+        order.input_mint,
+        order.amount_in,
+    )?;
+
+    emit!(OrderCancelled {
+        order: order.key(),
+        owner: order.owner,
+        refunded: refund,
+    });
+    order.close(ctx.accounts.owner.to_account_info())?;
+    Ok(refund)
+}
+
+// ===== ACCOUNTS =====
+
+#[derive(Accounts)]
+#[instruction(order_id: u64)]
+pub struct PlaceOrder<'info> {
+    #[account(
+        init,
+        payer = owner,
+        space = ConditionalOrder::LEN,
+        seeds = [b"order", owner.key().as_ref(), &order_id.to_le_bytes()],
+        bump
+    )]
+    pub order: Account<'info, ConditionalOrder>,
+
+    pub input_mint: Account<'info, Mint>,
+
+    #[account(
+        init,
+        payer = owner,
+        token::mint = input_mint,
+        token::authority = order,
+        seeds = [b"order-escrow", order.key().as_ref()],
+        bump
+    )]
+    pub escrow: Account<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        constraint = owner_source_token.owner == owner.key() @ OrderError::InvalidTokenAccount,
+        constraint = owner_source_token.mint == input_mint.key() @ OrderError::InvalidTokenAccount
+    )]
+    pub owner_source_token: Account<'info, TokenAccount>,
+
+    #[account(
+        constraint = owner_destination_token.owner == owner.key() @ OrderError::InvalidTokenAccount,
+        constraint = owner_destination_token.mint != input_mint.key() @ OrderError::InvalidTokenAccount
+    )]
+    pub owner_destination_token: Account<'info, TokenAccount>,
+
+    /// CHECK: Oracle price feed the trigger is evaluated against
+    pub price_feed: UncheckedAccount<'info>,
+
+    #[account(mut)]
+    pub owner: Signer<'info>,
+
+    pub rent: Sysvar<'info, Rent>,
+    pub token_program: Program<'info, Token>,
+    pub system_program: Program<'info, System>,
+
+    /// Protocol stats; `amount_in` opens exposure to the input mint
+    /// CHECK: validated by vectai_protocol
+    #[account(mut)]
+    pub protocol_stats: UncheckedAccount<'info>,
+
+    /// CHECK: Signs exposure reports for this program
+    #[account(seeds = [b"stats-reporter"], bump)]
+    pub stats_reporter: UncheckedAccount<'info>,
+
+    pub protocol_program: Program<'info, VectaiProtocol>,
+}
+
+#[derive(Accounts)]
+pub struct CancelOrder<'info> {
+    #[account(
+        mut,
+        close = owner,
+        seeds = [b"order", order.owner.as_ref(), &order.order_id.to_le_bytes()],
+        bump = order.bump
+    )]
+    pub order: Account<'info, ConditionalOrder>,
+
+    #[account(mut, address = order.escrow)]
+    pub escrow: Account<'info, TokenAccount>,
+
+    #[account(mut, address = order.source_account)]
+    pub owner_source_token: Account<'info, TokenAccount>,
+
+    /// CHECK: Order owner, receives the escrow rent, tip and order rent
+    #[account(mut, address = order.owner)]
+    pub owner: UncheckedAccount<'info>,
+
+    /// Owner, or anyone once the order has expired
+    pub authority: Signer<'info>,
+
+    /// OCO sibling of `order`, left open and unlinked; required when linked
+    #[account(mut, address = order.linked_order @ OrderError::InvalidLinkedOrder)]
+    pub linked_order: Option<Account<'info, ConditionalOrder>>,
+
+    pub token_program: Program<'info, Token>,
+
+    /// Protocol stats; the refund closes the order's exposure
+    /// CHECK: validated by vectai_protocol
+    #[account(mut)]
+    pub protocol_stats: UncheckedAccount<'info>,
+
+    /// CHECK: Signs exposure reports for this program
+    #[account(seeds = [b"stats-reporter"], bump)]
+    pub stats_reporter: UncheckedAccount<'info>,
+
+    pub protocol_program: Program<'info, VectaiProtocol>,
+}
+
+#[derive(Accounts)]
+pub struct ForceCancelOrders<'info> {
+    /// The owner's strategy; must be paused
+    #[account(
+        seeds = [b"trader", owner.key().as_ref()],
+        bump,
+        seeds::program = vectai_trader::ID
+    )]
+    pub trader_config: Account<'info, vectai_trader::accounts::TraderConfig>,
+
+    /// CHECK: Owner of the orders, receives the escrow rents, tips and
+    /// order rents; bound to the strategy by its seeds
+    #[account(mut)]
+    pub owner: UncheckedAccount<'info>,
+
+    /// Admin or governance
+    pub admin: Signer<'info>,
+
+    pub token_program: Program<'info, Token>,
+
+    /// Protocol stats; the refunds close the orders' exposure
+    /// CHECK: validated by vectai_protocol
+    #[account(mut)]
+    pub protocol_stats: UncheckedAccount<'info>,
+
+    /// CHECK: Signs exposure reports for this program
+    #[account(seeds = [b"stats-reporter"], bump)]
+    pub stats_reporter: UncheckedAccount<'info>,
+
+    pub protocol_program: Program<'info, VectaiProtocol>,
+}
+
+#[derive(Accounts)]
+pub struct LinkOrders<'info> {
+    #[account(
+        mut,
+        seeds = [b"order", owner.key().as_ref(), &order_a.order_id.to_le_bytes()],
+        bump = order_a.bump
+    )]
+    pub order_a: Account<'info, ConditionalOrder>,
+
+    #[account(
+        mut,
+        seeds = [b"order", owner.key().as_ref(), &order_b.order_id.to_le_bytes()],
+        bump = order_b.bump
+    )]
+    pub order_b: Account<'info, ConditionalOrder>,
+
+    pub owner: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct FillOrder<'info> {
+    #[account(
+        mut,
+        close = owner,
+        seeds = [b"order", order.owner.as_ref(), &order.order_id.to_le_bytes()],
+        bump = order.bump
+    )]
+    pub order: Account<'info, ConditionalOrder>,
+
+    #[account(mut, address = order.escrow)]
+    pub escrow: Account<'info, TokenAccount>,
+
+    #[account(mut, address = order.destination_account)]
+    pub owner_destination_token: Account<'info, TokenAccount>,
+
+    /// CHECK: Order owner, receives the escrow and order rent
+    #[account(mut, address = order.owner)]
+    pub owner: UncheckedAccount<'info>,
+
+    /// CHECK: Oracle price feed, checked against the order
+    #[account(address = order.price_feed)]
+    pub price_feed: UncheckedAccount<'info>,
+
+    /// Keeper filling the order; receives the tip
+    #[account(mut)]
+    pub keeper: Signer<'info>,
+
+    #[account(seeds = [b"keeper-registry"], bump = keeper_registry.bump)]
+    pub keeper_registry: Account<'info, KeeperRegistry>,
+
+    /// Filling keeper's bond; must meet the registry minimum
+    #[account(
+        mut,
+        seeds = [b"keeper", keeper.key().as_ref()],
+        bump = keeper_account.bump,
+        constraint = keeper_account.bonded >= keeper_registry.min_bond @ OrderError::InsufficientBond
+    )]
+    pub keeper_account: Account<'info, Keeper>,
+
+    /// The Oracle Program (VECT.AI Oracle)
+    pub vectai_oracle_program: Program<'info, VectaiOracle>,
+
+    pub swapper_program: Program<'info, RaydiumSwapper>,
+
+    // ===== RAYDIUM / SERUM ACCOUNTS (validated by raydium_swapper) =====
+
+    /// CHECK: Raydium AMM program
+    pub raydium_amm_program: UncheckedAccount<'info>,
+
+    /// CHECK: AMM pool account
+    #[account(mut)]
+    pub amm: UncheckedAccount<'info>,
+
+    /// CHECK: AMM authority
+    pub amm_authority: UncheckedAccount<'info>,
+
+    /// CHECK: AMM open orders
+    #[account(mut)]
+    pub amm_open_orders: UncheckedAccount<'info>,
+
+    /// CHECK: AMM target orders
+    #[account(mut)]
+    pub amm_target_orders: UncheckedAccount<'info>,
+
+    /// CHECK: Pool coin token account
+    #[account(mut)]
+    pub pool_coin_token_account: UncheckedAccount<'info>,
+
+    /// CHECK: Pool pc token account
+    #[account(mut)]
+    pub pool_pc_token_account: UncheckedAccount<'info>,
+
+    /// CHECK: Serum program
+    pub serum_program: UncheckedAccount<'info>,
+
+    /// CHECK: Serum market
+    #[account(mut)]
+    pub serum_market: UncheckedAccount<'info>,
+
+    /// CHECK: Serum bids
+    #[account(mut)]
+    pub serum_bids: UncheckedAccount<'info>,
+
+    /// CHECK: Serum asks
+    #[account(mut)]
+    pub serum_asks: UncheckedAccount<'info>,
+
+    /// CHECK: Serum event queue
+    #[account(mut)]
+    pub serum_event_queue: UncheckedAccount<'info>,
+
+    /// CHECK: Serum coin vault
+    #[account(mut)]
+    pub serum_coin_vault: UncheckedAccount<'info>,
+
+    /// CHECK: Serum pc vault
+    #[account(mut)]
+    pub serum_pc_vault: UncheckedAccount<'info>,
+
+    /// CHECK: Serum vault signer
+    pub serum_vault_signer: UncheckedAccount<'info>,
+
+    pub token_program: Program<'info, Token>,
+
+    /// Global emergency-pause registry, forwarded to the swapper
+    /// CHECK: validated by raydium_swapper
+    pub protocol_status: UncheckedAccount<'info>,
+
+    /// Shared network addresses, forwarded to the swapper
+    /// CHECK: validated by raydium_swapper
+    pub network_config: UncheckedAccount<'info>,
+
+    /// Protocol activity counters, forwarded to the swapper; the fill
+    /// closes the order's exposure
+    /// CHECK: validated by vectai_protocol
+    #[account(mut)]
+    pub protocol_stats: UncheckedAccount<'info>,
+
+    /// The swapper's stats reporter PDA, forwarded to the swapper
+    /// CHECK: validated by raydium_swapper
+    pub swapper_stats_reporter: UncheckedAccount<'info>,
+
+    /// CHECK: Signs exposure reports for this program
+    #[account(seeds = [b"stats-reporter"], bump)]
+    pub stats_reporter: UncheckedAccount<'info>,
+
+    pub protocol_program: Program<'info, VectaiProtocol>,
+
+    /// Treasury vault for the swap's source mint, forwarded to the swapper
+    /// CHECK: validated by raydium_swapper
+    #[account(mut)]
+    pub swap_fee_account: Option<UncheckedAccount<'info>>,
+}
+
+// ===== STATE =====
+
+/// Order side; for `Limit` orders `Buy` fills at or below the trigger price
+/// and `Sell` at or above it
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, Debug)]
+pub enum OrderSide {
+    Buy,
+    Sell,
+}
+
+/// `Limit` fills on a favourable move through the trigger; `Stop` inverts
+/// the direction (stop-loss sells at or below, stop buys at or above)
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, Debug)]
+pub enum OrderKind {
+    Limit,
+    Stop,
+}
+
+/// Resting conditional order; the PDA owns the escrow and is closed on
+/// fill or cancel
+#[account]
+pub struct ConditionalOrder {
+    pub owner: Pubkey,
+    pub order_id: u64,
+    pub input_mint: Pubkey,
+    pub output_mint: Pubkey,
+    pub source_account: Pubkey, // Refund destination
+    pub destination_account: Pubkey, // Receives the swap output
+    pub escrow: Pubkey,
+    pub price_feed: Pubkey,
+    pub side: OrderSide,
+    pub kind: OrderKind,
+    pub trigger_price: i64, // In price feed units
+    pub amount_in: u64,
+    pub min_amount_out: u64,
+    pub expires_at: i64,
+    pub keeper_tip: u64, // Lamports
+    pub linked_order: Pubkey, // OCO sibling, default if unlinked
+    pub created_at: i64,
+    pub bump: u8,
+}
+
+impl ConditionalOrder {
+    pub const LEN: usize = 8 + // discriminator
+        32 + // owner
+        8 +  // order_id
+        32 + // input_mint
+        32 + // output_mint
+        32 + // source_account
+        32 + // destination_account
+        32 + // escrow
+        32 + // price_feed
+        1 +  // side
+        1 +  // kind
+        8 +  // trigger_price
+        8 +  // amount_in
+        8 +  // min_amount_out
+        8 +  // expires_at
+        8 +  // keeper_tip
+        32 + // linked_order
+        8 +  // created_at
+        1;   // bump
+
+    pub fn is_triggered(&self, price: i64) -> bool {
+        match (self.side, self.kind) {
+            (OrderSide::Buy, OrderKind::Limit) | (OrderSide::Sell, OrderKind::Stop) => {
+                price <= self.trigger_price
+            }
+            (OrderSide::Sell, OrderKind::Limit) | (OrderSide::Buy, OrderKind::Stop) => {
+                price >= self.trigger_price
+            }
+        }
+    }
+}
+
+// ===== EVENTS =====
+
+#[event]
+pub struct OrderPlaced {
+    pub order: Pubkey,
+    pub owner: Pubkey,
+    pub side: OrderSide,
+    pub kind: OrderKind,
+    pub trigger_price: i64,
+    pub amount_in: u64,
+    pub min_amount_out: u64,
+    pub expires_at: i64,
+    pub keeper_tip: u64,
+}
+
+#[event]
+pub struct OrderFilled {
+    pub order: Pubkey,
+    pub owner: Pubkey,
+    pub keeper: Pubkey,
+    pub price: i64,
+    pub amount_in: u64,
+    pub keeper_tip: u64,
+}
+
+#[event]
+pub struct OrdersLinked {
+    pub owner: Pubkey,
+    pub order_a: Pubkey,
+    pub order_b: Pubkey,
+}
+
+#[event]
+pub struct LinkedOrderCancelled {
+    pub order: Pubkey,
+    pub linked_order: Pubkey,
+    pub refunded: u64,
+}
+
+#[event]
+pub struct OrderCancelled {
+    pub order: Pubkey,
+    pub owner: Pubkey,
+    pub refunded: u64,
+}
+
+#[event]
+pub struct OrdersForceCancelled {
+    pub trader: Pubkey,
+    pub owner: Pubkey,
+    pub admin: Pubkey,
+    pub orders: u32,
+    pub refunded: u64, // Input tokens returned across all orders
+}
+
+#[error_code]
+pub enum OrderError {
+    #[msg("Invalid input parameters")]
+    InvalidInput,
+    #[msg("Invalid amount - must be greater than 0")]
+    InvalidAmount,
+    #[msg("Insufficient balance")]
+    InsufficientBalance,
+    #[msg("Unauthorized access")]
+    Unauthorized,
+    #[msg("Invalid token account")]
+    InvalidTokenAccount,
+    #[msg("Invalid oracle price")]
+    InvalidPrice,
+    #[msg("Order has expired")]
+    OrderExpired,
+    #[msg("Trigger condition not met")]
+    ConditionNotMet,
+    #[msg("Linked order accounts are invalid")]
+    InvalidLinkedOrder,
+    #[msg("Order is already linked")]
+    AlreadyLinked,
+    #[msg("Keeper bond is below the required minimum")]
+    InsufficientBond,
+    #[msg("Unbonding stake is still locked")]
+    BondLocked,
+    #[msg("Math overflow")]
+    MathOverflow,
+    #[msg("Keeper still has bonded or unbonding stake")]
+    BondOutstanding,
+    #[msg("Strategy must be paused before its orders are force-cancelled")]
+    StrategyNotFlagged,
+    #[msg("Order accounts are invalid")]
+    InvalidOrderAccounts,
+}
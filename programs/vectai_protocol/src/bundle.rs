@@ -0,0 +1,131 @@
+use anchor_lang::prelude::*;
+
+use crate::ProtocolError;
+
+/// Raydium and Serum accounts one `RaydiumPoolAccounts` holds
+pub const POOL_ACCOUNT_COUNT: usize = 15;
+
+#[derive(Accounts)]
+#[instruction(base_mint: Pubkey, quote_mint: Pubkey)]
+pub struct InitializeAccountBundle<'info> {
+    #[account(
+        init,
+        payer = admin,
+        space = AccountBundle::LEN,
+        seeds = [b"account-bundle", base_mint.as_ref(), quote_mint.as_ref()],
+        bump
+    )]
+    pub bundle: Account<'info, AccountBundle>,
+
+    #[account(mut)]
+    pub admin: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct SetAccountBundle<'info> {
+    #[account(
+        mut,
+        seeds = [b"account-bundle", bundle.base_mint.as_ref(), bundle.quote_mint.as_ref()],
+        bump = bundle.bump
+    )]
+    pub bundle: Account<'info, AccountBundle>,
+
+    pub admin: Signer<'info>,
+}
+
+/// The Raydium AMM v4 pool and Serum market accounts of an
+/// `execute_trade` or `swap`, named as `vectai_trader::ExecuteTrade` names
+/// them
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct RaydiumPoolAccounts {
+    pub raydium_amm_program: Pubkey,
+    pub amm: Pubkey,
+    pub amm_authority: Pubkey,
+    pub amm_open_orders: Pubkey,
+    pub amm_target_orders: Pubkey,
+    pub pool_coin_token_account: Pubkey,
+    pub pool_pc_token_account: Pubkey,
+    pub serum_program: Pubkey,
+    pub serum_market: Pubkey,
+    pub serum_bids: Pubkey,
+    pub serum_asks: Pubkey,
+    pub serum_event_queue: Pubkey,
+    pub serum_coin_vault: Pubkey,
+    pub serum_pc_vault: Pubkey,
+    pub serum_vault_signer: Pubkey,
+}
+
+impl RaydiumPoolAccounts {
+    pub const LEN: usize = POOL_ACCOUNT_COUNT * 32;
+
+    /// In the order `execute_trade` and `swap` take them, after the user's
+    /// token accounts
+    pub fn ordered(&self) -> [Pubkey; POOL_ACCOUNT_COUNT] {
+        [
+            self.raydium_amm_program,
+            self.amm,
+            self.amm_authority,
+            self.amm_open_orders,
+            self.amm_target_orders,
+            self.pool_coin_token_account,
+            self.pool_pc_token_account,
+            self.serum_program,
+            self.serum_market,
+            self.serum_bids,
+            self.serum_asks,
+            self.serum_event_queue,
+            self.serum_coin_vault,
+            self.serum_pc_vault,
+            self.serum_vault_signer,
+        ]
+    }
+
+    pub fn is_complete(&self) -> bool {
+        self.ordered().iter().all(|account| *account != Pubkey::default())
+    }
+}
+
+/// Every Raydium/Serum account a pair's executions pass, so clients and
+/// the keeper fetch one account instead of keeping their own address
+/// maps. Programs never read it: each account is still validated by the
+/// instruction it is passed to.
+#[account]
+pub struct AccountBundle {
+    pub base_mint: Pubkey,
+    pub quote_mint: Pubkey,
+    pub pool: RaydiumPoolAccounts,
+    pub updated_at: i64,
+    pub bump: u8,
+}
+
+impl AccountBundle {
+    pub const LEN: usize = 8 + // discriminator
+        32 + // base_mint
+        32 + // quote_mint
+        RaydiumPoolAccounts::LEN + // pool
+        8 +  // updated_at
+        1;   // bump
+}
+
+/// Address of the `AccountBundle` of `base_mint`/`quote_mint`
+pub fn account_bundle(base_mint: &Pubkey, quote_mint: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(&[b"account-bundle", base_mint.as_ref(), quote_mint.as_ref()], &crate::ID).0
+}
+
+/// Check `pool` lists every account
+pub fn validate_pool_accounts(pool: &RaydiumPoolAccounts) -> Result<()> {
+    require!(pool.is_complete(), ProtocolError::IncompleteAccountBundle);
+    Ok(())
+}
+
+/// The pool and market a bundle now points at; clients refetch the
+/// bundle for the rest
+#[event]
+pub struct AccountBundleUpdated {
+    pub base_mint: Pubkey,
+    pub quote_mint: Pubkey,
+    pub amm: Pubkey,
+    pub serum_market: Pubkey,
+}
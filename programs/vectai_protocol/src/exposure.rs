@@ -0,0 +1,152 @@
+use anchor_lang::prelude::*;
+
+use crate::{stats_reporter, ProtocolError, ProtocolStats, MAX_TRACKED_MINTS};
+
+// Program whose `stats-reporter` PDA reports escrowed order sizes
+const VECTAI_ORDERBOOK_PROGRAM: Pubkey = anchor_lang::solana_program::pubkey!("5KwdvcUeUnTN8LLRdsxB8RXCu7aLPas7qjhs3Nn2cqBq");
+
+#[derive(Accounts)]
+pub struct ReportExposure<'info> {
+    #[account(mut, seeds = [b"protocol-stats"], bump = protocol_stats.bump)]
+    pub protocol_stats: Account<'info, ProtocolStats>,
+
+    /// `stats-reporter` PDA of vectai_orderbook, which escrows the sizes
+    #[account(
+        constraint = reporter.key() == stats_reporter(&VECTAI_ORDERBOOK_PROGRAM) @ ProtocolError::UnauthorizedReporter
+    )]
+    pub reporter: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct SetExposureCap<'info> {
+    #[account(mut, seeds = [b"protocol-stats"], bump = protocol_stats.bump)]
+    pub protocol_stats: Account<'info, ProtocolStats>,
+
+    pub admin: Signer<'info>,
+}
+
+/// Open (`open = true`) or close exposure to `mint` from a reporting
+/// program; `reporter` is its `stats-reporter` PDA and `reporter_bump` that
+/// PDA's bump
+#[cfg(feature = "cpi")]
+#[allow(clippy::too_many_arguments)]
+pub fn report_exposure<'info>(
+    protocol_program: AccountInfo<'info>,
+    protocol_stats: AccountInfo<'info>,
+    reporter: AccountInfo<'info>,
+    reporter_bump: u8,
+    open: bool,
+    mint: Pubkey,
+    amount: u64,
+) -> Result<()> {
+    let bump = [reporter_bump];
+    let seeds: &[&[u8]] = &[b"stats-reporter", &bump];
+    let signer = [seeds];
+    let ctx = CpiContext::new_with_signer(
+        protocol_program,
+        crate::cpi::accounts::ReportExposure {
+            protocol_stats,
+            reporter,
+        },
+        &signer,
+    );
+    if open {
+        crate::cpi::open_exposure(ctx, mint, amount)
+    } else {
+        crate::cpi::close_exposure(ctx, mint, amount)
+    }
+}
+
+impl ProtocolStats {
+    /// Add `amount` to the open exposure to `mint`, failing past its cap.
+    /// Mints past `MAX_TRACKED_MINTS` go untracked; they cannot be capped,
+    /// as capped mints keep their entry.
+    pub fn open_exposure(&mut self, mint: Pubkey, amount: u64) -> Result<()> {
+        let index = match self.exposures.iter().position(|exposure| exposure.mint == mint) {
+            Some(index) => index,
+            None if self.exposures.len() < MAX_TRACKED_MINTS => {
+                self.exposures.push(MintExposure {
+                    mint,
+                    ..MintExposure::default()
+                });
+                self.exposures.len() - 1
+            }
+            None => return Ok(()),
+        };
+        let exposure = &mut self.exposures[index];
+        let open = exposure.open.checked_add(amount);
+        exposure.open = match open {
+            Some(open) if exposure.cap == 0 || open <= exposure.cap => open,
+            _ => return err!(ProtocolError::ExposureCapExceeded),
+        };
+        Ok(())
+    }
+
+    /// Release `amount` of the open exposure to `mint`. Saturates at zero,
+    /// for sizes opened while the mint was untracked.
+    pub fn close_exposure(&mut self, mint: Pubkey, amount: u64) {
+        if let Some(index) = self.exposures.iter().position(|exposure| exposure.mint == mint) {
+            let exposure = &mut self.exposures[index];
+            exposure.open = exposure.open.saturating_sub(amount);
+            self.prune_exposure(index);
+        }
+    }
+
+    /// Cap the open exposure to `mint`; 0 lifts the cap. With every entry
+    /// taken, the uncapped mint with the least open exposure makes room.
+    pub fn set_exposure_cap(&mut self, mint: Pubkey, cap: u64) -> Result<()> {
+        if let Some(index) = self.exposures.iter().position(|exposure| exposure.mint == mint) {
+            self.exposures[index].cap = cap;
+            self.prune_exposure(index);
+            return Ok(());
+        }
+        if cap == 0 {
+            return Ok(());
+        }
+        if self.exposures.len() >= MAX_TRACKED_MINTS {
+            let evicted = self
+                .exposures
+                .iter()
+                .enumerate()
+                .filter(|(_, exposure)| exposure.cap == 0)
+                .min_by_key(|(_, exposure)| exposure.open)
+                .map(|(index, _)| index)
+                .ok_or(ProtocolError::ExposureMintsFull)?;
+            self.exposures.remove(evicted);
+        }
+        self.exposures.push(MintExposure { mint, open: 0, cap });
+        Ok(())
+    }
+
+    pub fn exposure(&self, mint: &Pubkey) -> Option<&MintExposure> {
+        self.exposures.iter().find(|exposure| exposure.mint == *mint)
+    }
+
+    // Free the entry at `index` once it neither holds exposure nor a cap
+    fn prune_exposure(&mut self, index: usize) {
+        let exposure = &self.exposures[index];
+        if exposure.open == 0 && exposure.cap == 0 {
+            self.exposures.remove(index);
+        }
+    }
+}
+
+/// Open exposure of the protocol to one mint: the sizes escrowed by
+/// resting orders, in the mint's base units
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct MintExposure {
+    pub mint: Pubkey,
+    pub open: u64,
+    pub cap: u64, // 0 = uncapped
+}
+
+impl MintExposure {
+    pub const LEN: usize = 32 + 8 + 8;
+}
+
+#[event]
+pub struct ExposureCapUpdated {
+    pub mint: Pubkey,
+    pub cap: u64,
+    pub open: u64, // Exposure already open; a lower cap only blocks new orders
+}
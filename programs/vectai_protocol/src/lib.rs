@@ -0,0 +1,838 @@
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::bpf_loader_upgradeable;
+use vectai_common::{governance_native_treasury, is_admin, Versioned};
+
+// Protocol-wide activity counters reported by the trader, swapper and token
+mod stats;
+pub use stats::*;
+
+// Per-mint open exposure of resting orders, with governed caps
+mod exposure;
+pub use exposure::*;
+
+// Registered address lookup table for the Raydium/Serum account list
+mod lookup_table;
+pub use lookup_table::*;
+
+// Per-pair swap venue registry
+mod venues;
+pub use venues::*;
+
+// Per-pair Raydium/Serum account bundles for building executions
+mod bundle;
+pub use bundle::*;
+
+// Operational roles (pauser, minter, fee manager) split out of the admin key
+mod roles;
+pub use roles::*;
+
+declare_id!("BX5Zg9NsGf7Bj36WWmaV3rtmVuZCZar4D9Dv55czYVi1");
+
+#[program]
+pub mod vectai_protocol {
+    use super::*;
+
+    /// Create the protocol status registry with its pause guardian
+    /// (admin only)
+    pub fn initialize_protocol_status(
+        ctx: Context<InitializeProtocolStatus>,
+        guardian: Pubkey,
+    ) -> Result<()> {
+        require!(
+            is_admin(&ctx.accounts.admin.key()),
+            ProtocolError::UnauthorizedAdmin
+        );
+
+        let protocol_status = &mut ctx.accounts.protocol_status;
+        protocol_status.guardian = guardian;
+        protocol_status.paused = false;
+        protocol_status.updated_at = Clock::get()?.unix_timestamp;
+        protocol_status.fee_config = FeeConfig::INITIAL;
+        protocol_status.bump = ctx.bumps.protocol_status;
+
+        msg!("✅ Protocol status initialized, guardian {}", guardian);
+        Ok(())
+    }
+
+    /// Halt or resume every value-moving instruction in the workspace.
+    /// The guardian, a pauser or the admin may pause; only the admin may
+    /// unpause.
+    pub fn set_protocol_paused(ctx: Context<SetProtocolPaused>, paused: bool) -> Result<()> {
+        let authority = ctx.accounts.authority.key();
+        let is_pauser = authority == ctx.accounts.protocol_status.guardian
+            || holds_role(&ctx.accounts.role_registry, &authority, Role::Pauser);
+        let protocol_status = &mut ctx.accounts.protocol_status;
+        require!(
+            is_admin(&authority) || (paused && is_pauser),
+            ProtocolError::Unauthorized
+        );
+
+        protocol_status.paused = paused;
+        protocol_status.updated_at = Clock::get()?.unix_timestamp;
+
+        emit!(ProtocolPauseChanged {
+            paused,
+            authority,
+            timestamp: protocol_status.updated_at,
+        });
+        if paused {
+            msg!("🚨 Protocol paused by {}", authority);
+        } else {
+            msg!("✅ Protocol unpaused by {}", authority);
+        }
+        Ok(())
+    }
+
+    /// Replace the pause guardian (timelock only)
+    pub fn set_guardian(ctx: Context<SetGuardian>, guardian: Pubkey) -> Result<()> {
+        require!(
+            ctx.accounts.admin.key() == vectai_timelock::timelock_authority(),
+            ProtocolError::UnauthorizedAdmin
+        );
+
+        ctx.accounts.protocol_status.guardian = guardian;
+        msg!("Pause guardian: {}", guardian);
+        Ok(())
+    }
+
+    /// Replace the protocol-wide fee switch read by the trader, swapper
+    /// and vault (timelock or a fee manager)
+    pub fn set_fee_config(ctx: Context<SetFeeConfig>, fee_config: FeeConfig) -> Result<()> {
+        let admin = ctx.accounts.admin.key();
+        require!(
+            admin == vectai_timelock::timelock_authority()
+                || holds_role(&ctx.accounts.role_registry, &admin, Role::FeeManager),
+            ProtocolError::UnauthorizedAdmin
+        );
+        require!(fee_config.is_valid(), ProtocolError::InvalidFeeConfig);
+
+        ctx.accounts.protocol_status.fee_config = fee_config;
+        emit!(FeeConfigUpdated { fee_config });
+        msg!("Fee config: trade {} bps, swap {} bps, performance {} bps, referral share {} bps",
+             fee_config.trade_fee_bps, fee_config.swap_fee_bps,
+             fee_config.performance_fee_bps, fee_config.referral_share_bps);
+        Ok(())
+    }
+
+    /// Record the network addresses shared by every program (admin only)
+    pub fn initialize_network_config(
+        ctx: Context<InitializeNetworkConfig>,
+        raydium_amm_program: Pubkey,
+        wsol_mint: Pubkey,
+        usdc_mint: Pubkey,
+        oracle_program: Pubkey,
+        treasury_program: Pubkey,
+    ) -> Result<()> {
+        require!(
+            is_admin(&ctx.accounts.admin.key()),
+            ProtocolError::UnauthorizedAdmin
+        );
+
+        let network_config = &mut ctx.accounts.network_config;
+        network_config.raydium_amm_program = raydium_amm_program;
+        network_config.wsol_mint = wsol_mint;
+        network_config.usdc_mint = usdc_mint;
+        network_config.oracle_program = oracle_program;
+        network_config.treasury_program = treasury_program;
+        network_config.bump = ctx.bumps.network_config;
+        network_config.version = NetworkConfig::VERSION;
+
+        msg!("✅ Network config initialized: Raydium {}, WSOL {}, USDC {}",
+             raydium_amm_program, wsol_mint, usdc_mint);
+        Ok(())
+    }
+
+    /// Update the shared network addresses (timelock only)
+    pub fn set_network_config(
+        ctx: Context<SetNetworkConfig>,
+        raydium_amm_program: Pubkey,
+        wsol_mint: Pubkey,
+        usdc_mint: Pubkey,
+        oracle_program: Pubkey,
+        treasury_program: Pubkey,
+    ) -> Result<()> {
+        require!(
+            ctx.accounts.admin.key() == vectai_timelock::timelock_authority(),
+            ProtocolError::UnauthorizedAdmin
+        );
+
+        let network_config = &mut ctx.accounts.network_config;
+        network_config.raydium_amm_program = raydium_amm_program;
+        network_config.wsol_mint = wsol_mint;
+        network_config.usdc_mint = usdc_mint;
+        network_config.oracle_program = oracle_program;
+        network_config.treasury_program = treasury_program;
+
+        emit!(NetworkConfigUpdated {
+            raydium_amm_program,
+            wsol_mint,
+            usdc_mint,
+            oracle_program,
+            treasury_program,
+        });
+        msg!("Network config updated");
+        Ok(())
+    }
+
+    /// Bring the network config written by an older build up to the
+    /// current layout; anyone may pay the extra rent
+    pub fn migrate_network_config(ctx: Context<MigrateNetworkConfig>) -> Result<()> {
+        let from = vectai_common::migrate::<NetworkConfig>(
+            &ctx.accounts.network_config.to_account_info(),
+            &ctx.accounts.payer.to_account_info(),
+            &ctx.accounts.system_program.to_account_info(),
+        )?;
+        msg!("Network config migrated from v{} to v{}", from, NetworkConfig::VERSION);
+        Ok(())
+    }
+
+    /// Check whether a program's upgrade authority has been handed to
+    /// governance, the timelock or the protocol multisig (or revoked) and
+    /// record the result in the network config. A program whose authority
+    /// moved back is recorded as not decentralized rather than rejected, so
+    /// a stale attestation can always be cleared. Anyone may refresh an
+    /// attested program; only the admin may add one.
+    pub fn attest_upgrade_authority(ctx: Context<AttestUpgradeAuthority>) -> Result<()> {
+        // ✅ CHECKS
+        let program = ctx.accounts.program.key();
+        let upgrade_authority = ctx.accounts.program_data.upgrade_authority_address;
+        let network_config = &mut ctx.accounts.network_config;
+        let index = match network_config.upgrade_attestations.iter().position(|entry| entry.program == program) {
+            Some(index) => index,
+            None => {
+                require!(
+                    is_admin(&ctx.accounts.authority.key()),
+                    ProtocolError::UnauthorizedAdmin
+                );
+                require!(
+                    network_config.upgrade_attestations.len() < MAX_ATTESTED_PROGRAMS,
+                    ProtocolError::AttestationsFull
+                );
+                network_config.upgrade_attestations.push(UpgradeAttestation::default());
+                network_config.upgrade_attestations.len() - 1
+            }
+        };
+
+        // ✅ EFFECTS
+        let clock = Clock::get()?;
+        let attestation = UpgradeAttestation {
+            program,
+            upgrade_authority: upgrade_authority.unwrap_or_default(),
+            is_immutable: upgrade_authority.is_none(),
+            is_decentralized: upgrade_authority.map_or(true, |key| is_decentralized_authority(&key)),
+            program_data_slot: ctx.accounts.program_data.slot,
+            attested_at: clock.unix_timestamp,
+            attested_slot: clock.slot,
+        };
+        network_config.upgrade_attestations[index] = attestation;
+
+        emit!(UpgradeAuthorityAttested {
+            program,
+            upgrade_authority: attestation.upgrade_authority,
+            is_immutable: attestation.is_immutable,
+            is_decentralized: attestation.is_decentralized,
+            program_data_slot: attestation.program_data_slot,
+        });
+        msg!("Upgrade authority of {} attested: {} ({})",
+             program,
+             if attestation.is_immutable { "immutable".to_string() } else { attestation.upgrade_authority.to_string() },
+             if attestation.is_decentralized { "decentralized" } else { "not decentralized" });
+        Ok(())
+    }
+
+    /// Create the protocol-wide activity counters (admin only)
+    pub fn initialize_protocol_stats(ctx: Context<InitializeProtocolStats>) -> Result<()> {
+        require!(
+            is_admin(&ctx.accounts.admin.key()),
+            ProtocolError::UnauthorizedAdmin
+        );
+
+        let protocol_stats = &mut ctx.accounts.protocol_stats;
+        protocol_stats.executions = 0;
+        protocol_stats.swaps = 0;
+        protocol_stats.mints = 0;
+        protocol_stats.volumes = Vec::new();
+        protocol_stats.bump = ctx.bumps.protocol_stats;
+        protocol_stats.version = ProtocolStats::VERSION;
+        protocol_stats.exposures = Vec::new();
+
+        msg!("✅ Protocol stats initialized");
+        Ok(())
+    }
+
+    /// Count one execution, swap or mint of `amount` in `mint`; signed over
+    /// CPI by the `stats-reporter` PDA of the program performing it
+    pub fn record_activity(
+        ctx: Context<RecordActivity>,
+        kind: ActivityKind,
+        mint: Pubkey,
+        amount: u64,
+    ) -> Result<()> {
+        ctx.accounts.protocol_stats.record(kind, mint, amount);
+        Ok(())
+    }
+
+    /// Bring the activity counters written by an older build up to the
+    /// current layout; anyone may pay the extra rent
+    pub fn migrate_protocol_stats(ctx: Context<MigrateProtocolStats>) -> Result<()> {
+        let from = vectai_common::migrate::<ProtocolStats>(
+            &ctx.accounts.protocol_stats.to_account_info(),
+            &ctx.accounts.payer.to_account_info(),
+            &ctx.accounts.system_program.to_account_info(),
+        )?;
+        msg!("Protocol stats migrated from v{} to v{}", from, ProtocolStats::VERSION);
+        Ok(())
+    }
+
+    /// Open exposure to `mint` for an escrowed order of `amount`, failing
+    /// past the mint's cap; signed over CPI by vectai_orderbook's
+    /// `stats-reporter` PDA
+    pub fn open_exposure(ctx: Context<ReportExposure>, mint: Pubkey, amount: u64) -> Result<()> {
+        ctx.accounts.protocol_stats.open_exposure(mint, amount)
+    }
+
+    /// Release exposure to `mint` once an escrowed order of `amount` is
+    /// filled or refunded; signed like `open_exposure`
+    pub fn close_exposure(ctx: Context<ReportExposure>, mint: Pubkey, amount: u64) -> Result<()> {
+        ctx.accounts.protocol_stats.close_exposure(mint, amount);
+        Ok(())
+    }
+
+    /// Bound the protocol's open exposure to `mint`; 0 lifts the cap
+    /// (timelock only)
+    pub fn set_exposure_cap(ctx: Context<SetExposureCap>, mint: Pubkey, cap: u64) -> Result<()> {
+        require!(
+            ctx.accounts.admin.key() == vectai_timelock::timelock_authority(),
+            ProtocolError::UnauthorizedAdmin
+        );
+
+        let protocol_stats = &mut ctx.accounts.protocol_stats;
+        protocol_stats.set_exposure_cap(mint, cap)?;
+        let open = protocol_stats.exposure(&mint).map_or(0, |exposure| exposure.open);
+
+        emit!(ExposureCapUpdated { mint, cap, open });
+        msg!("Exposure cap for {}: {} ({} open)", mint, cap, open);
+        Ok(())
+    }
+
+    /// Register the address lookup table clients use for the Raydium
+    /// account list (admin only)
+    pub fn initialize_lookup_table_registry(ctx: Context<InitializeLookupTableRegistry>) -> Result<()> {
+        require!(
+            is_admin(&ctx.accounts.admin.key()),
+            ProtocolError::UnauthorizedAdmin
+        );
+
+        let registry = &mut ctx.accounts.registry;
+        registry.lookup_table = ctx.accounts.lookup_table.key();
+        registry.updated_at = Clock::get()?.unix_timestamp;
+        registry.bump = ctx.bumps.registry;
+
+        msg!("✅ Lookup table registered: {}", registry.lookup_table);
+        Ok(())
+    }
+
+    /// Point the registry at another lookup table (timelock only)
+    pub fn set_lookup_table(ctx: Context<SetLookupTable>) -> Result<()> {
+        require!(
+            ctx.accounts.admin.key() == vectai_timelock::timelock_authority(),
+            ProtocolError::UnauthorizedAdmin
+        );
+
+        let registry = &mut ctx.accounts.registry;
+        registry.lookup_table = ctx.accounts.lookup_table.key();
+        registry.updated_at = Clock::get()?.unix_timestamp;
+
+        emit!(LookupTableUpdated {
+            lookup_table: registry.lookup_table,
+        });
+        msg!("Lookup table updated: {}", registry.lookup_table);
+        Ok(())
+    }
+
+    /// List the venues `base_mint`/`quote_mint` trades on (admin only)
+    pub fn initialize_venue_registry(
+        ctx: Context<InitializeVenueRegistry>,
+        base_mint: Pubkey,
+        quote_mint: Pubkey,
+        venues: PairVenues,
+    ) -> Result<()> {
+        require!(
+            is_admin(&ctx.accounts.admin.key()),
+            ProtocolError::UnauthorizedAdmin
+        );
+
+        let registry = &mut ctx.accounts.registry;
+        registry.base_mint = base_mint;
+        registry.quote_mint = quote_mint;
+        registry.venues = venues;
+        registry.updated_at = Clock::get()?.unix_timestamp;
+        registry.bump = ctx.bumps.registry;
+
+        msg!("✅ Venue registry initialized for {}/{}", base_mint, quote_mint);
+        Ok(())
+    }
+
+    /// Replace a pair's venues (timelock only)
+    pub fn set_pair_venues(ctx: Context<SetPairVenues>, venues: PairVenues) -> Result<()> {
+        require!(
+            ctx.accounts.admin.key() == vectai_timelock::timelock_authority(),
+            ProtocolError::UnauthorizedAdmin
+        );
+
+        let registry = &mut ctx.accounts.registry;
+        registry.venues = venues;
+        registry.updated_at = Clock::get()?.unix_timestamp;
+
+        emit!(PairVenuesUpdated {
+            base_mint: registry.base_mint,
+            quote_mint: registry.quote_mint,
+            venues,
+        });
+        msg!("Venues updated for {}/{}", registry.base_mint, registry.quote_mint);
+        Ok(())
+    }
+
+    /// Store the Raydium/Serum accounts `base_mint`/`quote_mint` executes
+    /// through (admin only)
+    pub fn initialize_account_bundle(
+        ctx: Context<InitializeAccountBundle>,
+        base_mint: Pubkey,
+        quote_mint: Pubkey,
+        pool: RaydiumPoolAccounts,
+    ) -> Result<()> {
+        require!(
+            is_admin(&ctx.accounts.admin.key()),
+            ProtocolError::UnauthorizedAdmin
+        );
+        validate_pool_accounts(&pool)?;
+
+        let bundle = &mut ctx.accounts.bundle;
+        bundle.base_mint = base_mint;
+        bundle.quote_mint = quote_mint;
+        bundle.pool = pool;
+        bundle.updated_at = Clock::get()?.unix_timestamp;
+        bundle.bump = ctx.bumps.bundle;
+
+        msg!("✅ Account bundle initialized for {}/{}", base_mint, quote_mint);
+        Ok(())
+    }
+
+    /// Replace a pair's account bundle, e.g. after a pool migration
+    /// (timelock only)
+    pub fn set_account_bundle(ctx: Context<SetAccountBundle>, pool: RaydiumPoolAccounts) -> Result<()> {
+        require!(
+            ctx.accounts.admin.key() == vectai_timelock::timelock_authority(),
+            ProtocolError::UnauthorizedAdmin
+        );
+        validate_pool_accounts(&pool)?;
+
+        let bundle = &mut ctx.accounts.bundle;
+        bundle.pool = pool;
+        bundle.updated_at = Clock::get()?.unix_timestamp;
+
+        emit!(AccountBundleUpdated {
+            base_mint: bundle.base_mint,
+            quote_mint: bundle.quote_mint,
+            amm: pool.amm,
+            serum_market: pool.serum_market,
+        });
+        msg!("Account bundle updated for {}/{}", bundle.base_mint, bundle.quote_mint);
+        Ok(())
+    }
+
+    /// Create the empty role registry (admin only)
+    pub fn initialize_role_registry(ctx: Context<InitializeRoleRegistry>) -> Result<()> {
+        require!(
+            is_admin(&ctx.accounts.admin.key()),
+            ProtocolError::UnauthorizedAdmin
+        );
+
+        let registry = &mut ctx.accounts.registry;
+        registry.holders = [RoleHolder::default(); MAX_ROLE_HOLDERS];
+        registry.updated_at = Clock::get()?.unix_timestamp;
+        registry.bump = ctx.bumps.registry;
+
+        msg!("✅ Role registry initialized");
+        Ok(())
+    }
+
+    /// Give `holder` an operational role (timelock only)
+    pub fn grant_role(ctx: Context<UpdateRole>, role: Role, holder: Pubkey) -> Result<()> {
+        let admin = ctx.accounts.admin.key();
+        require!(
+            admin == vectai_timelock::timelock_authority(),
+            ProtocolError::UnauthorizedAdmin
+        );
+
+        let registry = &mut ctx.accounts.registry;
+        registry.grant(holder, role)?;
+        registry.updated_at = Clock::get()?.unix_timestamp;
+
+        emit!(RoleGranted {
+            role,
+            holder,
+            granted_by: admin,
+        });
+        msg!("✅ {:?} role granted to {}", role, holder);
+        Ok(())
+    }
+
+    /// Take an operational role from `holder` (timelock, or the admin to
+    /// pull a compromised key at once)
+    pub fn revoke_role(ctx: Context<UpdateRole>, role: Role, holder: Pubkey) -> Result<()> {
+        let admin = ctx.accounts.admin.key();
+        require!(
+            admin == vectai_timelock::timelock_authority() || is_admin(&admin),
+            ProtocolError::UnauthorizedAdmin
+        );
+
+        let registry = &mut ctx.accounts.registry;
+        registry.revoke(holder, role)?;
+        registry.updated_at = Clock::get()?.unix_timestamp;
+
+        emit!(RoleRevoked {
+            role,
+            holder,
+            revoked_by: admin,
+        });
+        msg!("🚨 {:?} role revoked from {}", role, holder);
+        Ok(())
+    }
+}
+
+// ===== CONSTANTS =====
+
+// Fee switch ceilings, in basis points
+pub const MAX_TRADE_FEE_BPS: u16 = 100; // 1%
+pub const MAX_SWAP_FEE_BPS: u16 = 100; // 1%
+pub const MAX_PERFORMANCE_FEE_BPS: u16 = 3_000; // 30%
+pub const MAX_REFERRAL_SHARE_BPS: u16 = 5_000; // 50% of the protocol fee
+
+// Basis-point denominator (10000 = 100%)
+pub use vectai_common::BPS_DENOMINATOR;
+
+// Programs whose upgrade authority the network config can attest
+pub const MAX_ATTESTED_PROGRAMS: usize = 16;
+
+// Protocol multisig (e.g. a Squads vault) accepted as an upgrade authority
+const PROTOCOL_MULTISIG: Pubkey = anchor_lang::solana_program::pubkey!("11111111111111111111111111111111"); // Replace with actual multisig
+
+/// Upgrade authorities that count as handed off: governance, the timelock
+/// or the protocol multisig
+fn is_decentralized_authority(key: &Pubkey) -> bool {
+    *key == governance_native_treasury()
+        || *key == vectai_timelock::timelock_authority()
+        || (*key == PROTOCOL_MULTISIG && PROTOCOL_MULTISIG != Pubkey::default())
+}
+
+// ===== ACCOUNTS =====
+
+#[derive(Accounts)]
+pub struct InitializeProtocolStatus<'info> {
+    #[account(
+        init,
+        payer = admin,
+        space = ProtocolStatus::LEN,
+        seeds = [b"protocol-status"],
+        bump
+    )]
+    pub protocol_status: Account<'info, ProtocolStatus>,
+
+    #[account(mut)]
+    pub admin: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct SetProtocolPaused<'info> {
+    #[account(mut, seeds = [b"protocol-status"], bump = protocol_status.bump)]
+    pub protocol_status: Account<'info, ProtocolStatus>,
+
+    pub authority: Signer<'info>,
+
+    /// Needed when `authority` pauses as a pauser
+    #[account(seeds = [b"role-registry"], bump = role_registry.bump)]
+    pub role_registry: Option<Account<'info, RoleRegistry>>,
+}
+
+#[derive(Accounts)]
+pub struct SetGuardian<'info> {
+    #[account(mut, seeds = [b"protocol-status"], bump = protocol_status.bump)]
+    pub protocol_status: Account<'info, ProtocolStatus>,
+
+    pub admin: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct SetFeeConfig<'info> {
+    #[account(mut, seeds = [b"protocol-status"], bump = protocol_status.bump)]
+    pub protocol_status: Account<'info, ProtocolStatus>,
+
+    pub admin: Signer<'info>,
+
+    /// Needed when `admin` is a fee manager
+    #[account(seeds = [b"role-registry"], bump = role_registry.bump)]
+    pub role_registry: Option<Account<'info, RoleRegistry>>,
+}
+
+#[derive(Accounts)]
+pub struct InitializeNetworkConfig<'info> {
+    #[account(
+        init,
+        payer = admin,
+        space = NetworkConfig::LEN,
+        seeds = [b"network-config"],
+        bump
+    )]
+    pub network_config: Account<'info, NetworkConfig>,
+
+    #[account(mut)]
+    pub admin: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct SetNetworkConfig<'info> {
+    #[account(mut, seeds = [b"network-config"], bump = network_config.bump)]
+    pub network_config: Account<'info, NetworkConfig>,
+
+    pub admin: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct MigrateNetworkConfig<'info> {
+    /// CHECK: Owner and discriminator are checked by
+    /// `vectai_common::migrate` before it resizes the account
+    #[account(
+        mut,
+        seeds = [b"network-config"],
+        bump
+    )]
+    pub network_config: UncheckedAccount<'info>,
+
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct AttestUpgradeAuthority<'info> {
+    /// CHECK: any upgradeable program; tied to `program_data` by its seeds
+    #[account(executable)]
+    pub program: UncheckedAccount<'info>,
+
+    #[account(
+        seeds = [program.key().as_ref()],
+        bump,
+        seeds::program = bpf_loader_upgradeable::ID
+    )]
+    pub program_data: Account<'info, ProgramData>,
+
+    #[account(mut, seeds = [b"network-config"], bump = network_config.bump)]
+    pub network_config: Account<'info, NetworkConfig>,
+
+    /// Anyone for an attested program; the admin to add a new one
+    pub authority: Signer<'info>,
+}
+
+// ===== STATE =====
+
+/// Global emergency switch; value-moving instructions in the trader,
+/// swapper, token and vault programs require `paused == false`
+#[account]
+pub struct ProtocolStatus {
+    pub guardian: Pubkey, // Hot key that can pause but not unpause
+    pub paused: bool,
+    pub updated_at: i64,
+    pub fee_config: FeeConfig,
+    pub bump: u8,
+}
+
+impl ProtocolStatus {
+    pub const LEN: usize = 8 + // discriminator
+        32 + // guardian
+        1 +  // paused
+        8 +  // updated_at
+        FeeConfig::LEN + // fee_config
+        1;   // bump
+}
+
+/// Protocol-wide fee switch, only changed through the timelock
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy)]
+pub struct FeeConfig {
+    pub trade_fee_bps: u16, // Trader: cut of each executed trade
+    pub swap_fee_bps: u16, // Swapper: cut of each swap's input
+    pub performance_fee_bps: u16, // Vault: cut of gains above the high-water mark
+    pub referral_share_bps: u16, // Referrer's share of protocol fees
+}
+
+impl FeeConfig {
+    pub const LEN: usize = 2 + 2 + 2 + 2;
+
+    /// No trade, swap or performance fee; referrers keep 20%
+    pub const INITIAL: FeeConfig = FeeConfig {
+        trade_fee_bps: 0,
+        swap_fee_bps: 0,
+        performance_fee_bps: 0,
+        referral_share_bps: 2_000,
+    };
+
+    pub fn is_valid(&self) -> bool {
+        self.trade_fee_bps <= MAX_TRADE_FEE_BPS
+            && self.swap_fee_bps <= MAX_SWAP_FEE_BPS
+            && self.performance_fee_bps <= MAX_PERFORMANCE_FEE_BPS
+            && self.referral_share_bps <= MAX_REFERRAL_SHARE_BPS
+    }
+
+    /// `bps` of `amount`, rounded down
+    pub fn apply(amount: u64, bps: u16) -> u64 {
+        vectai_common::bps_of(amount, bps as u64)
+    }
+}
+
+/// Network addresses read by the trader and swapper in place of
+/// per-program hardcoded constants
+#[account]
+pub struct NetworkConfig {
+    pub raydium_amm_program: Pubkey,
+    pub wsol_mint: Pubkey,
+    pub usdc_mint: Pubkey, // Quote side of the supported SOL pair
+    pub oracle_program: Pubkey,
+    pub treasury_program: Pubkey, // Protocol fee vaults derive from it
+    pub bump: u8,
+    pub version: u8, // Versioned layout; fields are only appended after it
+    pub upgrade_attestations: Vec<UpgradeAttestation>, // v1: up to MAX_ATTESTED_PROGRAMS
+}
+
+impl NetworkConfig {
+    pub const LEN: usize = 8 + // discriminator
+        32 + // raydium_amm_program
+        32 + // wsol_mint
+        32 + // usdc_mint
+        32 + // oracle_program
+        32 + // treasury_program
+        1 +  // bump
+        1 +  // version
+        4 + MAX_ATTESTED_PROGRAMS * UpgradeAttestation::LEN; // upgrade_attestations
+
+    /// Protocol fee vault for `mint` under `treasury_program`
+    pub fn treasury_vault(&self, mint: &Pubkey) -> Pubkey {
+        Pubkey::find_program_address(&[b"treasury-vault", mint.as_ref()], &self.treasury_program).0
+    }
+
+    /// Only USDC <-> SOL swaps are supported
+    pub fn is_supported_pair(&self, source_mint: Pubkey, dest_mint: Pubkey) -> bool {
+        (source_mint == self.usdc_mint && dest_mint == self.wsol_mint) ||
+            (source_mint == self.wsol_mint && dest_mint == self.usdc_mint)
+    }
+
+    pub fn upgrade_attestation(&self, program: &Pubkey) -> Option<&UpgradeAttestation> {
+        self.upgrade_attestations.iter().find(|entry| entry.program == *program)
+    }
+}
+
+impl Versioned for NetworkConfig {
+    const VERSION: u8 = 1;
+    const SPACE: usize = NetworkConfig::LEN;
+
+    fn version(&self) -> u8 {
+        self.version
+    }
+
+    fn set_version(&mut self, version: u8) {
+        self.version = version;
+    }
+}
+
+/// Latest on-chain check of a program's upgrade authority
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct UpgradeAttestation {
+    pub program: Pubkey,
+    pub upgrade_authority: Pubkey, // default when immutable
+    pub is_immutable: bool,
+    pub is_decentralized: bool, // false once the authority moved back
+    pub program_data_slot: u64, // Slot of the last deployment when attested
+    pub attested_at: i64,
+    pub attested_slot: u64,
+}
+
+impl UpgradeAttestation {
+    pub const LEN: usize = 32 + 32 + 1 + 1 + 8 + 8 + 8;
+
+    /// Whether the attestation still describes `program_data`: it found
+    /// the authority decentralized and the live authority has not changed
+    /// since. An upgrade authority transfer leaves the programdata slot
+    /// alone, so the authority itself is compared.
+    pub fn holds_for(&self, program_data: &ProgramData) -> bool {
+        let attested = if self.is_immutable { None } else { Some(self.upgrade_authority) };
+        self.is_decentralized && program_data.upgrade_authority_address == attested
+    }
+}
+
+// ===== EVENTS =====
+
+#[event]
+pub struct ProtocolPauseChanged {
+    pub paused: bool,
+    pub authority: Pubkey,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct NetworkConfigUpdated {
+    pub raydium_amm_program: Pubkey,
+    pub wsol_mint: Pubkey,
+    pub usdc_mint: Pubkey,
+    pub oracle_program: Pubkey,
+    pub treasury_program: Pubkey,
+}
+
+#[event]
+pub struct FeeConfigUpdated {
+    pub fee_config: FeeConfig,
+}
+
+#[event]
+pub struct UpgradeAuthorityAttested {
+    pub program: Pubkey,
+    pub upgrade_authority: Pubkey,
+    pub is_immutable: bool,
+    pub is_decentralized: bool,
+    pub program_data_slot: u64,
+}
+
+#[error_code]
+pub enum ProtocolError {
+    #[msg("Unauthorized admin")]
+    UnauthorizedAdmin,
+    #[msg("Only the admin can unpause; the guardian can only pause")]
+    Unauthorized,
+    #[msg("Upgrade authority is not governance, the timelock or the multisig")]
+    UpgradeAuthorityNotHandedOff,
+    #[msg("Fee config exceeds a fee ceiling")]
+    InvalidFeeConfig,
+    #[msg("Activity must be reported by the program performing it")]
+    UnauthorizedReporter,
+    #[msg("Account is not an address lookup table")]
+    InvalidLookupTable,
+    #[msg("Account bundle has an unset account")]
+    IncompleteAccountBundle,
+    #[msg("Order would take open exposure to the mint past its cap")]
+    ExposureCapExceeded,
+    #[msg("Every exposure entry holds a capped mint")]
+    ExposureMintsFull,
+    #[msg("Role holder cannot be the default key")]
+    InvalidRoleHolder,
+    #[msg("Key already holds the role")]
+    RoleAlreadyHeld,
+    #[msg("Key does not hold the role")]
+    RoleNotHeld,
+    #[msg("Every role registry slot is taken")]
+    RoleHoldersFull,
+    #[msg("Every upgrade attestation slot is taken")]
+    AttestationsFull,
+}
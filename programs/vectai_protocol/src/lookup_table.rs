@@ -0,0 +1,65 @@
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::address_lookup_table;
+
+use crate::ProtocolError;
+
+#[derive(Accounts)]
+pub struct InitializeLookupTableRegistry<'info> {
+    #[account(
+        init,
+        payer = admin,
+        space = LookupTableRegistry::LEN,
+        seeds = [b"lookup-table-registry"],
+        bump
+    )]
+    pub registry: Account<'info, LookupTableRegistry>,
+
+    /// CHECK: Any address lookup table; only its owner is checked
+    #[account(owner = address_lookup_table::program::ID @ ProtocolError::InvalidLookupTable)]
+    pub lookup_table: UncheckedAccount<'info>,
+
+    #[account(mut)]
+    pub admin: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct SetLookupTable<'info> {
+    #[account(mut, seeds = [b"lookup-table-registry"], bump = registry.bump)]
+    pub registry: Account<'info, LookupTableRegistry>,
+
+    /// CHECK: Any address lookup table; only its owner is checked
+    #[account(owner = address_lookup_table::program::ID @ ProtocolError::InvalidLookupTable)]
+    pub lookup_table: UncheckedAccount<'info>,
+
+    pub admin: Signer<'info>,
+}
+
+/// Address lookup table holding the whitelisted pool's Raydium and Serum
+/// accounts and the protocol PDAs, for clients building v0 transactions.
+/// Programs never read it: every account a lookup resolves is still
+/// validated by the instruction it is passed to.
+#[account]
+pub struct LookupTableRegistry {
+    pub lookup_table: Pubkey,
+    pub updated_at: i64,
+    pub bump: u8,
+}
+
+impl LookupTableRegistry {
+    pub const LEN: usize = 8 + // discriminator
+        32 + // lookup_table
+        8 +  // updated_at
+        1;   // bump
+}
+
+/// Address of the `LookupTableRegistry` PDA
+pub fn lookup_table_registry() -> Pubkey {
+    Pubkey::find_program_address(&[b"lookup-table-registry"], &crate::ID).0
+}
+
+#[event]
+pub struct LookupTableUpdated {
+    pub lookup_table: Pubkey,
+}
@@ -0,0 +1,140 @@
+use anchor_lang::prelude::*;
+
+use crate::ProtocolError;
+
+// Holders the registry has room for; each may hold several roles
+pub const MAX_ROLE_HOLDERS: usize = 8;
+
+#[derive(Accounts)]
+pub struct InitializeRoleRegistry<'info> {
+    #[account(
+        init,
+        payer = admin,
+        space = RoleRegistry::LEN,
+        seeds = [b"role-registry"],
+        bump
+    )]
+    pub registry: Account<'info, RoleRegistry>,
+
+    #[account(mut)]
+    pub admin: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct UpdateRole<'info> {
+    #[account(mut, seeds = [b"role-registry"], bump = registry.bump)]
+    pub registry: Account<'info, RoleRegistry>,
+
+    pub admin: Signer<'info>,
+}
+
+/// Operational duties split out of the admin key, so each can be held by
+/// the team running it
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Role {
+    Pauser, // Pauses the protocol, the token and traders; cannot unpause
+    Minter, // Mints the token once the program PDA holds its mint authority
+    FeeManager, // Sets the protocol fee switch, within the fee ceilings
+}
+
+impl Role {
+    fn bit(self) -> u8 {
+        1 << self as u8
+    }
+}
+
+/// One key and the roles it holds, as a bitmask of `Role`s
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct RoleHolder {
+    pub holder: Pubkey, // Default = free slot
+    pub roles: u8,
+}
+
+impl RoleHolder {
+    pub const LEN: usize = 32 + // holder
+        1; // roles
+}
+
+/// Who holds which operational role. Granted by governance through the
+/// timelock; the admin can also revoke, to pull a compromised key at
+/// once. The admin keeps setup and unpausing.
+#[account]
+pub struct RoleRegistry {
+    pub holders: [RoleHolder; MAX_ROLE_HOLDERS],
+    pub updated_at: i64,
+    pub bump: u8,
+}
+
+impl RoleRegistry {
+    pub const LEN: usize = 8 + // discriminator
+        MAX_ROLE_HOLDERS * RoleHolder::LEN + // holders
+        8 +  // updated_at
+        1;   // bump
+
+    pub fn has_role(&self, key: &Pubkey, role: Role) -> bool {
+        *key != Pubkey::default()
+            && self
+                .holders
+                .iter()
+                .any(|holder| holder.holder == *key && holder.roles & role.bit() != 0)
+    }
+
+    /// Give `holder` `role`, in its existing slot or a free one
+    pub fn grant(&mut self, holder: Pubkey, role: Role) -> Result<()> {
+        require!(holder != Pubkey::default(), ProtocolError::InvalidRoleHolder);
+        require!(!self.has_role(&holder, role), ProtocolError::RoleAlreadyHeld);
+        let slot = match self.holders.iter().position(|entry| entry.holder == holder) {
+            Some(slot) => slot,
+            None => self
+                .holders
+                .iter()
+                .position(|entry| entry.holder == Pubkey::default())
+                .ok_or(ProtocolError::RoleHoldersFull)?,
+        };
+        self.holders[slot].holder = holder;
+        self.holders[slot].roles |= role.bit();
+        Ok(())
+    }
+
+    /// Take `role` from `holder`, freeing its slot once it holds none
+    pub fn revoke(&mut self, holder: Pubkey, role: Role) -> Result<()> {
+        require!(self.has_role(&holder, role), ProtocolError::RoleNotHeld);
+        let entry = self
+            .holders
+            .iter_mut()
+            .find(|entry| entry.holder == holder)
+            .ok_or(ProtocolError::RoleNotHeld)?;
+        entry.roles &= !role.bit();
+        if entry.roles == 0 {
+            *entry = RoleHolder::default();
+        }
+        Ok(())
+    }
+}
+
+/// Whether `key` holds `role` in `registry`; false without a registry.
+/// For instructions taking the registry as an optional account.
+pub fn holds_role(registry: &Option<Account<RoleRegistry>>, key: &Pubkey, role: Role) -> bool {
+    registry.as_ref().is_some_and(|registry| registry.has_role(key, role))
+}
+
+/// Address of the `RoleRegistry` PDA
+pub fn role_registry() -> Pubkey {
+    Pubkey::find_program_address(&[b"role-registry"], &crate::ID).0
+}
+
+#[event]
+pub struct RoleGranted {
+    pub role: Role,
+    pub holder: Pubkey,
+    pub granted_by: Pubkey,
+}
+
+#[event]
+pub struct RoleRevoked {
+    pub role: Role,
+    pub holder: Pubkey,
+    pub revoked_by: Pubkey,
+}
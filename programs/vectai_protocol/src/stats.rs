@@ -0,0 +1,194 @@
+use anchor_lang::prelude::*;
+use vectai_common::Versioned;
+
+use crate::{MintExposure, ProtocolError};
+
+// Programs whose `stats-reporter` PDA may record activity
+const VECTAI_TRADER_PROGRAM: Pubkey = anchor_lang::solana_program::pubkey!("FEmf6TbtffcKVptbshZvCcg3CjQqsWodNwQhpXJff4NP");
+const RAYDIUM_SWAPPER_PROGRAM: Pubkey = anchor_lang::solana_program::pubkey!("Fg6PaFpoGXkYsidMpWTK6W2BeZ7FEfcYkg476zPFsLnS");
+const VECTAI_TOKEN_PROGRAM: Pubkey = anchor_lang::solana_program::pubkey!("DfpsT9PAeWbwwfE8EqTDqVUiCrsoHF1fogmPw42eqLPH");
+
+// Volumes are kept for the first mints seen; later mints are only counted
+pub const MAX_TRACKED_MINTS: usize = 16;
+
+#[derive(Accounts)]
+pub struct InitializeProtocolStats<'info> {
+    #[account(
+        init,
+        payer = admin,
+        space = ProtocolStats::LEN,
+        seeds = [b"protocol-stats"],
+        bump
+    )]
+    pub protocol_stats: Account<'info, ProtocolStats>,
+
+    #[account(mut)]
+    pub admin: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct MigrateProtocolStats<'info> {
+    /// CHECK: Owner and discriminator are checked by
+    /// `vectai_common::migrate` before it resizes the account
+    #[account(
+        mut,
+        seeds = [b"protocol-stats"],
+        bump
+    )]
+    pub protocol_stats: UncheckedAccount<'info>,
+
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+#[instruction(kind: ActivityKind)]
+pub struct RecordActivity<'info> {
+    #[account(mut, seeds = [b"protocol-stats"], bump = protocol_stats.bump)]
+    pub protocol_stats: Account<'info, ProtocolStats>,
+
+    /// `stats-reporter` PDA of the program that performs `kind`
+    #[account(
+        constraint = reporter.key() == stats_reporter(&kind.reporter_program()) @ ProtocolError::UnauthorizedReporter
+    )]
+    pub reporter: Signer<'info>,
+}
+
+/// Activity `record_activity` counts, one reporting program each
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ActivityKind {
+    Execution, // vectai_trader: execute_trade, copy_trade
+    Swap,      // raydium_swapper: swap
+    Mint,      // vectai_token: mint_to, mint_epoch, buy_from_auction
+}
+
+impl ActivityKind {
+    pub fn reporter_program(&self) -> Pubkey {
+        match self {
+            ActivityKind::Execution => VECTAI_TRADER_PROGRAM,
+            ActivityKind::Swap => RAYDIUM_SWAPPER_PROGRAM,
+            ActivityKind::Mint => VECTAI_TOKEN_PROGRAM,
+        }
+    }
+}
+
+/// PDA a reporting program signs `record_activity` with
+pub fn stats_reporter(program_id: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(&[b"stats-reporter"], program_id).0
+}
+
+/// Record activity from a reporting program; `reporter` is its
+/// `stats-reporter` PDA and `reporter_bump` that PDA's bump
+#[cfg(feature = "cpi")]
+pub fn report<'info>(
+    protocol_program: AccountInfo<'info>,
+    protocol_stats: AccountInfo<'info>,
+    reporter: AccountInfo<'info>,
+    reporter_bump: u8,
+    kind: ActivityKind,
+    mint: Pubkey,
+    amount: u64,
+) -> Result<()> {
+    let seeds: &[&[u8]] = &[b"stats-reporter", &[reporter_bump]];
+    crate::cpi::record_activity(
+        CpiContext::new_with_signer(
+            protocol_program,
+            crate::cpi::accounts::RecordActivity {
+                protocol_stats,
+                reporter,
+            },
+            &[seeds],
+        ),
+        kind,
+        mint,
+        amount,
+    )
+}
+
+/// Protocol-wide activity counters and open exposure, readable with one
+/// account fetch
+#[account]
+pub struct ProtocolStats {
+    pub executions: u64,
+    pub swaps: u64,
+    pub mints: u64,
+    pub volumes: Vec<MintVolume>, // Up to MAX_TRACKED_MINTS, first seen first
+    pub bump: u8,
+    pub version: u8, // Versioned layout; fields are only appended after it
+    pub exposures: Vec<MintExposure>, // v1: up to MAX_TRACKED_MINTS with open exposure or a cap
+}
+
+impl ProtocolStats {
+    pub const LEN: usize = 8 + // discriminator
+        8 +  // executions
+        8 +  // swaps
+        8 +  // mints
+        4 + MAX_TRACKED_MINTS * MintVolume::LEN + // volumes
+        1 +  // bump
+        1 +  // version
+        4 + MAX_TRACKED_MINTS * MintExposure::LEN; // exposures
+
+    /// Count one `kind` of `amount` in `mint`; counters saturate rather
+    /// than fail the trade, swap or mint being recorded
+    pub fn record(&mut self, kind: ActivityKind, mint: Pubkey, amount: u64) {
+        let count = match kind {
+            ActivityKind::Execution => &mut self.executions,
+            ActivityKind::Swap => &mut self.swaps,
+            ActivityKind::Mint => &mut self.mints,
+        };
+        *count = count.saturating_add(1);
+
+        let index = match self.volumes.iter().position(|volume| volume.mint == mint) {
+            Some(index) => index,
+            None if self.volumes.len() < MAX_TRACKED_MINTS => {
+                self.volumes.push(MintVolume {
+                    mint,
+                    ..MintVolume::default()
+                });
+                self.volumes.len() - 1
+            }
+            None => return,
+        };
+        let volume = &mut self.volumes[index];
+        let total = match kind {
+            ActivityKind::Execution => &mut volume.executed,
+            ActivityKind::Swap => &mut volume.swapped,
+            ActivityKind::Mint => &mut volume.minted,
+        };
+        *total = total.saturating_add(amount);
+    }
+
+    pub fn volume(&self, mint: &Pubkey) -> Option<&MintVolume> {
+        self.volumes.iter().find(|volume| volume.mint == *mint)
+    }
+}
+
+/// Cumulative amounts of one mint, in its base units
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct MintVolume {
+    pub mint: Pubkey,
+    pub executed: u64, // Strategy trade inputs, after the trade fee
+    pub swapped: u64,  // Swapper inputs, after the swap fee
+    pub minted: u64,
+}
+
+impl MintVolume {
+    pub const LEN: usize = 32 + 8 + 8 + 8;
+}
+
+impl Versioned for ProtocolStats {
+    const VERSION: u8 = 1;
+    const SPACE: usize = ProtocolStats::LEN;
+
+    fn version(&self) -> u8 {
+        self.version
+    }
+
+    fn set_version(&mut self, version: u8) {
+        self.version = version;
+    }
+}
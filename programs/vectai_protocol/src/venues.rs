@@ -0,0 +1,117 @@
+use anchor_lang::prelude::*;
+
+#[derive(Accounts)]
+#[instruction(base_mint: Pubkey, quote_mint: Pubkey)]
+pub struct InitializeVenueRegistry<'info> {
+    #[account(
+        init,
+        payer = admin,
+        space = VenueRegistry::LEN,
+        seeds = [b"venue-registry", base_mint.as_ref(), quote_mint.as_ref()],
+        bump
+    )]
+    pub registry: Account<'info, VenueRegistry>,
+
+    #[account(mut)]
+    pub admin: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct SetPairVenues<'info> {
+    #[account(
+        mut,
+        seeds = [b"venue-registry", registry.base_mint.as_ref(), registry.quote_mint.as_ref()],
+        bump = registry.bump
+    )]
+    pub registry: Account<'info, VenueRegistry>,
+
+    pub admin: Signer<'info>,
+}
+
+/// Where a swap of a pair can execute
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Venue {
+    /// Raydium AMM v4 pool
+    RaydiumAmm,
+    /// Meteora DLMM pair
+    MeteoraDlmm,
+    /// Phoenix order book, taken with IOC orders
+    Phoenix,
+    /// Stable-swap pool, for pairs of pegged assets
+    StableSwap,
+}
+
+/// Pool or market of each venue for one pair; `Pubkey::default()` where
+/// the pair is not listed
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct PairVenues {
+    pub raydium_amm: Pubkey,
+    pub meteora_lb_pair: Pubkey,
+    pub phoenix_market: Pubkey,
+    pub stable_swap_pool: Pubkey,
+}
+
+impl PairVenues {
+    pub const LEN: usize = 32 + // raydium_amm
+        32 + // meteora_lb_pair
+        32 + // phoenix_market
+        32;  // stable_swap_pool
+
+    /// Pool or market of `venue`, when listed
+    pub fn market(&self, venue: Venue) -> Option<Pubkey> {
+        let market = match venue {
+            Venue::RaydiumAmm => self.raydium_amm,
+            Venue::MeteoraDlmm => self.meteora_lb_pair,
+            Venue::Phoenix => self.phoenix_market,
+            Venue::StableSwap => self.stable_swap_pool,
+        };
+        (market != Pubkey::default()).then_some(market)
+    }
+}
+
+/// Venues a pair trades on. Routers quote each listed venue and send the
+/// swap to the one with the best output (see `best_venue`); the swapper
+/// only executes on markets listed here.
+#[account]
+pub struct VenueRegistry {
+    pub base_mint: Pubkey,
+    pub quote_mint: Pubkey,
+    pub venues: PairVenues,
+    pub updated_at: i64,
+    pub bump: u8,
+}
+
+impl VenueRegistry {
+    pub const LEN: usize = 8 + // discriminator
+        32 + // base_mint
+        32 + // quote_mint
+        PairVenues::LEN + // venues
+        8 +  // updated_at
+        1;   // bump
+}
+
+/// Address of the `VenueRegistry` of `base_mint`/`quote_mint`
+pub fn venue_registry(base_mint: &Pubkey, quote_mint: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(&[b"venue-registry", base_mint.as_ref(), quote_mint.as_ref()], &crate::ID).0
+}
+
+/// The venue quoting the most output, from `(venue, expected_out)` quotes
+/// of listed venues; the earlier one on ties
+pub fn best_venue(quotes: &[(Venue, u64)]) -> Option<Venue> {
+    quotes
+        .iter()
+        .fold(None, |best: Option<(Venue, u64)>, &(venue, out)| match best {
+            Some((_, best_out)) if best_out >= out => best,
+            _ => Some((venue, out)),
+        })
+        .map(|(venue, _)| venue)
+}
+
+#[event]
+pub struct PairVenuesUpdated {
+    pub base_mint: Pubkey,
+    pub quote_mint: Pubkey,
+    pub venues: PairVenues,
+}
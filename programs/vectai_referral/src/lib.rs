@@ -0,0 +1,266 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token::{self, Mint, Token, TokenAccount, Transfer};
+
+declare_id!("DngfgK6zaKWkC1JMVYTrLTRspFnvUiPwdwCrasW4Kmr4");
+
+#[program]
+pub mod vectai_referral {
+    use super::*;
+
+    /// Register a referral code owned by the signer
+    pub fn register_referral_code(ctx: Context<RegisterReferralCode>, code: String) -> Result<()> {
+        // ✅ CHECKS
+        require!(
+            code.len() >= MIN_CODE_LEN && code.len() <= MAX_CODE_LEN,
+            ReferralError::InvalidCode
+        );
+        require!(
+            code.bytes().all(|b| b.is_ascii_lowercase() || b.is_ascii_digit() || b == b'-'),
+            ReferralError::InvalidCode
+        );
+
+        // ✅ EFFECTS
+        let referral_code = &mut ctx.accounts.referral_code;
+        referral_code.owner = ctx.accounts.owner.key();
+        referral_code.code = code;
+        referral_code.created_at = Clock::get()?.unix_timestamp;
+        referral_code.bump = ctx.bumps.referral_code;
+
+        msg!("✅ Referral code '{}' registered", referral_code.code);
+        Ok(())
+    }
+
+    /// Open the fee escrow for a code and mint; anyone may pay for it
+    pub fn open_referral_earnings(ctx: Context<OpenReferralEarnings>) -> Result<()> {
+        let earnings = &mut ctx.accounts.earnings;
+        earnings.referral_code = ctx.accounts.referral_code.key();
+        earnings.mint = ctx.accounts.mint.key();
+        earnings.escrow = ctx.accounts.escrow.key();
+        earnings.total_claimed = 0;
+        earnings.bump = ctx.bumps.earnings;
+
+        msg!("✅ Referral escrow opened for {}", earnings.mint);
+        Ok(())
+    }
+
+    /// Pay out everything accrued in a code's escrow to its owner
+    pub fn claim_referral_fees(ctx: Context<ClaimReferralFees>) -> Result<()> {
+        // ✅ CHECKS
+        let amount = ctx.accounts.escrow.amount;
+        require!(amount > 0, ReferralError::NothingToClaim);
+
+        // ✅ EFFECTS
+        let earnings = &mut ctx.accounts.earnings;
+        earnings.total_claimed = earnings
+            .total_claimed
+            .checked_add(amount)
+            .ok_or(ReferralError::MathOverflow)?;
+
+        // ✅ INTERACTIONS
+        let earnings = &ctx.accounts.earnings;
+        let seeds: &[&[u8]] = &[
+            b"referral-earnings",
+            earnings.referral_code.as_ref(),
+            earnings.mint.as_ref(),
+            &[earnings.bump],
+        ];
+        token::transfer(
+            CpiContext::new_with_signer(
+                ctx.accounts.token_program.to_account_info(),
+                Transfer {
+                    from: ctx.accounts.escrow.to_account_info(),
+                    to: ctx.accounts.owner_token_account.to_account_info(),
+                    authority: ctx.accounts.earnings.to_account_info(),
+                },
+                &[seeds],
+            ),
+            amount,
+        )?;
+
+        emit!(ReferralFeesClaimed {
+            referral_code: ctx.accounts.referral_code.key(),
+            mint: ctx.accounts.earnings.mint,
+            amount,
+            total_claimed: ctx.accounts.earnings.total_claimed,
+        });
+        msg!("✅ Claimed {} referral fees", amount);
+        Ok(())
+    }
+}
+
+// ===== CONSTANTS =====
+
+// Basis-point denominator (10000 = 100%)
+pub use vectai_common::BPS_DENOMINATOR;
+
+// Code length bounds; codes are PDA seeds
+pub const MIN_CODE_LEN: usize = 3;
+pub const MAX_CODE_LEN: usize = 16;
+
+// ===== HELPERS =====
+
+/// Escrow that programs pay a code's referral share of `mint` fees into
+pub fn escrow_address(referral_code: &Pubkey, mint: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(
+        &[b"referral-escrow", referral_code.as_ref(), mint.as_ref()],
+        &crate::ID,
+    )
+    .0
+}
+
+/// Referral share of a protocol fee; `share_bps` comes from the protocol
+/// fee config
+pub fn referral_share(protocol_amount: u64, share_bps: u16) -> u64 {
+    vectai_common::bps_of(protocol_amount, share_bps as u64)
+}
+
+// ===== ACCOUNTS =====
+
+#[derive(Accounts)]
+#[instruction(code: String)]
+pub struct RegisterReferralCode<'info> {
+    #[account(
+        init,
+        payer = owner,
+        space = ReferralCode::LEN,
+        seeds = [b"referral", code.as_bytes()],
+        bump
+    )]
+    pub referral_code: Account<'info, ReferralCode>,
+
+    #[account(mut)]
+    pub owner: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct OpenReferralEarnings<'info> {
+    #[account(
+        seeds = [b"referral", referral_code.code.as_bytes()],
+        bump = referral_code.bump
+    )]
+    pub referral_code: Account<'info, ReferralCode>,
+
+    pub mint: Account<'info, Mint>,
+
+    #[account(
+        init,
+        payer = payer,
+        space = ReferralEarnings::LEN,
+        seeds = [b"referral-earnings", referral_code.key().as_ref(), mint.key().as_ref()],
+        bump
+    )]
+    pub earnings: Account<'info, ReferralEarnings>,
+
+    #[account(
+        init,
+        payer = payer,
+        token::mint = mint,
+        token::authority = earnings,
+        seeds = [b"referral-escrow", referral_code.key().as_ref(), mint.key().as_ref()],
+        bump
+    )]
+    pub escrow: Account<'info, TokenAccount>,
+
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    pub rent: Sysvar<'info, Rent>,
+    pub token_program: Program<'info, Token>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct ClaimReferralFees<'info> {
+    #[account(
+        seeds = [b"referral", referral_code.code.as_bytes()],
+        bump = referral_code.bump,
+        has_one = owner @ ReferralError::Unauthorized
+    )]
+    pub referral_code: Account<'info, ReferralCode>,
+
+    #[account(
+        mut,
+        seeds = [b"referral-earnings", referral_code.key().as_ref(), earnings.mint.as_ref()],
+        bump = earnings.bump
+    )]
+    pub earnings: Account<'info, ReferralEarnings>,
+
+    #[account(mut, address = earnings.escrow)]
+    pub escrow: Account<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        constraint = owner_token_account.mint == earnings.mint @ ReferralError::InvalidTokenAccount
+    )]
+    pub owner_token_account: Account<'info, TokenAccount>,
+
+    pub owner: Signer<'info>,
+
+    pub token_program: Program<'info, Token>,
+}
+
+// ===== STATE =====
+
+/// Referral code registered by a wallet; programs record its key as the
+/// referrer of traders and vaults created with it
+#[account]
+pub struct ReferralCode {
+    pub owner: Pubkey,
+    pub code: String,
+    pub created_at: i64,
+    pub bump: u8,
+}
+
+impl ReferralCode {
+    pub const LEN: usize = 8 + // discriminator
+        32 + // owner
+        4 + MAX_CODE_LEN + // code
+        8 +  // created_at
+        1;   // bump
+}
+
+/// Per-mint fee accounting for a referral code; pending fees are the
+/// escrow balance
+#[account]
+pub struct ReferralEarnings {
+    pub referral_code: Pubkey,
+    pub mint: Pubkey,
+    pub escrow: Pubkey,
+    pub total_claimed: u64,
+    pub bump: u8,
+}
+
+impl ReferralEarnings {
+    pub const LEN: usize = 8 + // discriminator
+        32 + // referral_code
+        32 + // mint
+        32 + // escrow
+        8 +  // total_claimed
+        1;   // bump
+}
+
+// ===== EVENTS =====
+
+#[event]
+pub struct ReferralFeesClaimed {
+    pub referral_code: Pubkey,
+    pub mint: Pubkey,
+    pub amount: u64,
+    pub total_claimed: u64,
+}
+
+#[error_code]
+pub enum ReferralError {
+    #[msg("Referral code must be 3-16 chars of a-z, 0-9 or '-'")]
+    InvalidCode,
+    #[msg("Unauthorized access")]
+    Unauthorized,
+    #[msg("Invalid token account")]
+    InvalidTokenAccount,
+    #[msg("No referral fees to claim")]
+    NothingToClaim,
+    #[msg("Math overflow in calculation")]
+    MathOverflow,
+}
@@ -0,0 +1,129 @@
+use anchor_lang::prelude::*;
+
+use crate::{OperationAccount, Timelock};
+
+// Instructions one batch can carry
+pub const MAX_BATCH_INSTRUCTIONS: usize = 8;
+
+/// One instruction of a batch, run with the timelock PDA as signer
+#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+pub struct OperationInstruction {
+    pub program_id: Pubkey,
+    pub accounts: Vec<OperationAccount>,
+    pub data: Vec<u8>,
+}
+
+impl OperationInstruction {
+    pub fn space(&self) -> usize {
+        32 + // program_id
+        4 + self.accounts.len() * OperationAccount::LEN + // accounts
+        4 + self.data.len() // data
+    }
+}
+
+#[derive(Accounts)]
+#[instruction(instructions: Vec<OperationInstruction>)]
+pub struct QueueBatch<'info> {
+    #[account(mut, seeds = [b"timelock"], bump = timelock.bump)]
+    pub timelock: Account<'info, Timelock>,
+
+    #[account(
+        init,
+        payer = proposer,
+        space = BatchOperation::space(&instructions),
+        seeds = [b"batch", timelock.operation_count.to_le_bytes().as_ref()],
+        bump
+    )]
+    pub batch: Account<'info, BatchOperation>,
+
+    #[account(mut)]
+    pub proposer: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct CancelBatch<'info> {
+    #[account(
+        mut,
+        seeds = [b"batch", batch.id.to_le_bytes().as_ref()],
+        bump = batch.bump,
+        close = proposer
+    )]
+    pub batch: Account<'info, BatchOperation>,
+
+    /// CHECK: rent refund destination, checked against the batch
+    #[account(mut, address = batch.proposer)]
+    pub proposer: UncheckedAccount<'info>,
+
+    pub admin: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct ExecuteBatch<'info> {
+    #[account(seeds = [b"timelock"], bump = timelock.bump)]
+    pub timelock: Account<'info, Timelock>,
+
+    #[account(
+        mut,
+        seeds = [b"batch", batch.id.to_le_bytes().as_ref()],
+        bump = batch.bump,
+        close = proposer
+    )]
+    pub batch: Account<'info, BatchOperation>,
+
+    /// CHECK: rent refund destination, checked against the batch
+    #[account(mut, address = batch.proposer)]
+    pub proposer: UncheckedAccount<'info>,
+}
+
+/// Instructions queued together, e.g. one governance proposal's parameter
+/// changes, executed in order in a single transaction so they apply
+/// together or not at all. Batches share the operation ids.
+#[account]
+pub struct BatchOperation {
+    pub id: u64,
+    pub proposer: Pubkey,
+    pub instructions: Vec<OperationInstruction>,
+    pub queued_at: i64,
+    pub eta: i64,
+    pub bump: u8,
+}
+
+impl BatchOperation {
+    pub fn space(instructions: &[OperationInstruction]) -> usize {
+        8 + // discriminator
+        8 +  // id
+        32 + // proposer
+        4 + instructions.iter().map(OperationInstruction::space).sum::<usize>() + // instructions
+        8 +  // queued_at
+        8 +  // eta
+        1    // bump
+    }
+}
+
+/// Address of the `BatchOperation` with `id`
+pub fn batch_operation(id: u64) -> Pubkey {
+    Pubkey::find_program_address(&[b"batch", id.to_le_bytes().as_ref()], &crate::ID).0
+}
+
+#[event]
+pub struct BatchQueued {
+    pub batch: Pubkey,
+    pub id: u64,
+    pub instructions: Vec<OperationInstruction>,
+    pub eta: i64,
+}
+
+#[event]
+pub struct BatchCancelled {
+    pub batch: Pubkey,
+    pub id: u64,
+}
+
+#[event]
+pub struct BatchExecuted {
+    pub batch: Pubkey,
+    pub id: u64,
+    pub instruction_count: u8,
+}
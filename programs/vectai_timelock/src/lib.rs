@@ -0,0 +1,465 @@
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::instruction::{AccountMeta, Instruction};
+use anchor_lang::solana_program::program::invoke_signed;
+use vectai_common::is_admin;
+
+// Several instructions queued and executed as one operation
+mod batch;
+pub use batch::*;
+
+declare_id!("2xPbtzXn9fSC4Stgbm6HetWw2nJELLezWFQBde8GJ3ro");
+
+#[program]
+pub mod vectai_timelock {
+    use super::*;
+
+    /// Create the timelock with its minimum queueing delay (admin only)
+    pub fn initialize_timelock(ctx: Context<InitializeTimelock>, min_delay: i64) -> Result<()> {
+        require!(
+            is_admin(&ctx.accounts.admin.key()),
+            TimelockError::UnauthorizedAdmin
+        );
+        require!(
+            (MIN_DELAY_FLOOR..=MAX_DELAY).contains(&min_delay),
+            TimelockError::InvalidDelay
+        );
+
+        let timelock = &mut ctx.accounts.timelock;
+        timelock.min_delay = min_delay;
+        timelock.operation_count = 0;
+        timelock.bump = ctx.bumps.timelock;
+
+        msg!("✅ Timelock initialized with {}s minimum delay", min_delay);
+        Ok(())
+    }
+
+    /// Queue an instruction to be executed with the timelock as signer no
+    /// earlier than `delay` seconds from now (admin or governance)
+    pub fn queue_operation(
+        ctx: Context<QueueOperation>,
+        program_id: Pubkey,
+        accounts: Vec<OperationAccount>,
+        data: Vec<u8>,
+        delay: i64,
+    ) -> Result<()> {
+        // ✅ CHECKS
+        require!(
+            is_admin(&ctx.accounts.proposer.key()),
+            TimelockError::UnauthorizedAdmin
+        );
+        require!(
+            delay >= ctx.accounts.timelock.min_delay && delay <= MAX_DELAY,
+            TimelockError::InvalidDelay
+        );
+        require!(
+            accounts.len() <= MAX_OPERATION_ACCOUNTS && data.len() <= MAX_OPERATION_DATA,
+            TimelockError::OperationTooLarge
+        );
+
+        // ✅ EFFECTS
+        let now = Clock::get()?.unix_timestamp;
+        let eta = now.checked_add(delay).ok_or(TimelockError::MathOverflow)?;
+
+        let timelock = &mut ctx.accounts.timelock;
+        let id = timelock.operation_count;
+        timelock.operation_count = id.checked_add(1).ok_or(TimelockError::MathOverflow)?;
+
+        let operation = &mut ctx.accounts.operation;
+        operation.id = id;
+        operation.proposer = ctx.accounts.proposer.key();
+        operation.program_id = program_id;
+        operation.accounts = accounts;
+        operation.data = data;
+        operation.queued_at = now;
+        operation.eta = eta;
+        operation.bump = ctx.bumps.operation;
+
+        emit!(OperationQueued {
+            operation: operation.key(),
+            id,
+            program_id,
+            accounts: operation.accounts.clone(),
+            data: operation.data.clone(),
+            eta,
+        });
+        msg!("⏳ Operation {} queued for {}, executable at {}", id, program_id, eta);
+        Ok(())
+    }
+
+    /// Drop a queued operation before it runs (admin or governance)
+    pub fn cancel_operation(ctx: Context<CancelOperation>) -> Result<()> {
+        require!(
+            is_admin(&ctx.accounts.admin.key()),
+            TimelockError::UnauthorizedAdmin
+        );
+
+        emit!(OperationCancelled {
+            operation: ctx.accounts.operation.key(),
+            id: ctx.accounts.operation.id,
+        });
+        msg!("🚫 Operation {} cancelled", ctx.accounts.operation.id);
+        Ok(())
+    }
+
+    /// Run a queued operation once its delay has passed; anyone may crank.
+    /// Remaining accounts are the operation's accounts and target program.
+    pub fn execute_operation<'info>(
+        ctx: Context<'_, '_, 'info, 'info, ExecuteOperation<'info>>,
+    ) -> Result<()> {
+        // ✅ CHECKS
+        let operation = &ctx.accounts.operation;
+        let now = Clock::get()?.unix_timestamp;
+        require!(now >= operation.eta, TimelockError::OperationNotReady);
+        require!(
+            now <= operation.eta.checked_add(GRACE_PERIOD).ok_or(TimelockError::MathOverflow)?,
+            TimelockError::OperationStale
+        );
+
+        // ✅ INTERACTIONS: the operation account is closed on exit
+        let ix = instruction(operation.program_id, &operation.accounts, &operation.data);
+        let mut account_infos = ctx.remaining_accounts.to_vec();
+        account_infos.push(ctx.accounts.timelock.to_account_info());
+        let seeds: &[&[u8]] = &[b"timelock", &[ctx.accounts.timelock.bump]];
+        invoke_signed(&ix, &account_infos, &[seeds])?;
+
+        emit!(OperationExecuted {
+            operation: operation.key(),
+            id: operation.id,
+            program_id: operation.program_id,
+        });
+        msg!("✅ Operation {} executed", operation.id);
+        Ok(())
+    }
+
+    /// Change the minimum delay; only callable through a queued operation
+    pub fn set_min_delay(ctx: Context<SetMinDelay>, min_delay: i64) -> Result<()> {
+        require!(
+            (MIN_DELAY_FLOOR..=MAX_DELAY).contains(&min_delay),
+            TimelockError::InvalidDelay
+        );
+
+        ctx.accounts.timelock.min_delay = min_delay;
+        msg!("Timelock minimum delay: {}s", min_delay);
+        Ok(())
+    }
+
+    /// Queue several instructions to run in order, in one transaction,
+    /// no earlier than `delay` seconds from now (admin or governance)
+    pub fn queue_batch(
+        ctx: Context<QueueBatch>,
+        instructions: Vec<OperationInstruction>,
+        delay: i64,
+    ) -> Result<()> {
+        // ✅ CHECKS
+        require!(
+            is_admin(&ctx.accounts.proposer.key()),
+            TimelockError::UnauthorizedAdmin
+        );
+        require!(
+            delay >= ctx.accounts.timelock.min_delay && delay <= MAX_DELAY,
+            TimelockError::InvalidDelay
+        );
+        require!(!instructions.is_empty(), TimelockError::EmptyBatch);
+        require!(
+            instructions.len() <= MAX_BATCH_INSTRUCTIONS
+                && instructions.iter().all(|ix| {
+                    ix.accounts.len() <= MAX_OPERATION_ACCOUNTS && ix.data.len() <= MAX_OPERATION_DATA
+                }),
+            TimelockError::OperationTooLarge
+        );
+
+        // ✅ EFFECTS
+        let now = Clock::get()?.unix_timestamp;
+        let eta = now.checked_add(delay).ok_or(TimelockError::MathOverflow)?;
+
+        let timelock = &mut ctx.accounts.timelock;
+        let id = timelock.operation_count;
+        timelock.operation_count = id.checked_add(1).ok_or(TimelockError::MathOverflow)?;
+
+        let batch = &mut ctx.accounts.batch;
+        batch.id = id;
+        batch.proposer = ctx.accounts.proposer.key();
+        batch.instructions = instructions;
+        batch.queued_at = now;
+        batch.eta = eta;
+        batch.bump = ctx.bumps.batch;
+
+        emit!(BatchQueued {
+            batch: batch.key(),
+            id,
+            instructions: batch.instructions.clone(),
+            eta,
+        });
+        msg!("⏳ Batch {} of {} instructions queued, executable at {}", id, batch.instructions.len(), eta);
+        Ok(())
+    }
+
+    /// Drop a queued batch before it runs (admin or governance)
+    pub fn cancel_batch(ctx: Context<CancelBatch>) -> Result<()> {
+        require!(
+            is_admin(&ctx.accounts.admin.key()),
+            TimelockError::UnauthorizedAdmin
+        );
+
+        emit!(BatchCancelled {
+            batch: ctx.accounts.batch.key(),
+            id: ctx.accounts.batch.id,
+        });
+        msg!("🚫 Batch {} cancelled", ctx.accounts.batch.id);
+        Ok(())
+    }
+
+    /// Run a queued batch once its delay has passed; anyone may crank.
+    /// Remaining accounts are every instruction's accounts and target
+    /// programs; any instruction failing reverts the whole batch.
+    pub fn execute_batch<'info>(
+        ctx: Context<'_, '_, 'info, 'info, ExecuteBatch<'info>>,
+    ) -> Result<()> {
+        // ✅ CHECKS
+        let batch = &ctx.accounts.batch;
+        let now = Clock::get()?.unix_timestamp;
+        require!(now >= batch.eta, TimelockError::OperationNotReady);
+        require!(
+            now <= batch.eta.checked_add(GRACE_PERIOD).ok_or(TimelockError::MathOverflow)?,
+            TimelockError::OperationStale
+        );
+
+        // ✅ INTERACTIONS: the batch account is closed on exit
+        let mut account_infos = ctx.remaining_accounts.to_vec();
+        account_infos.push(ctx.accounts.timelock.to_account_info());
+        let seeds: &[&[u8]] = &[b"timelock", &[ctx.accounts.timelock.bump]];
+        for operation in &batch.instructions {
+            let ix = instruction(operation.program_id, &operation.accounts, &operation.data);
+            invoke_signed(&ix, &account_infos, &[seeds])?;
+        }
+
+        emit!(BatchExecuted {
+            batch: batch.key(),
+            id: batch.id,
+            instruction_count: batch.instructions.len() as u8,
+        });
+        msg!("✅ Batch {} executed ({} instructions)", batch.id, batch.instructions.len());
+        Ok(())
+    }
+}
+
+// ===== CONSTANTS =====
+
+// Delay bounds: at least a day of notice, at most 30 days
+pub const MIN_DELAY_FLOOR: i64 = 24 * 60 * 60;
+pub const MAX_DELAY: i64 = 30 * 24 * 60 * 60;
+
+// Window after the eta in which an operation can still be executed
+pub const GRACE_PERIOD: i64 = 14 * 24 * 60 * 60;
+
+// Size limits for a queued instruction
+pub const MAX_OPERATION_ACCOUNTS: usize = 16;
+pub const MAX_OPERATION_DATA: usize = 512;
+
+// ===== HELPERS =====
+
+/// A queued instruction, ready to invoke
+fn instruction(program_id: Pubkey, accounts: &[OperationAccount], data: &[u8]) -> Instruction {
+    Instruction {
+        program_id,
+        accounts: accounts
+            .iter()
+            .map(|account| AccountMeta {
+                pubkey: account.pubkey,
+                is_signer: account.is_signer,
+                is_writable: account.is_writable,
+            })
+            .collect(),
+        data: data.to_vec(),
+    }
+}
+
+/// Timelock PDA that signs executed operations; programs require it for
+/// privileged parameter changes
+pub fn timelock_authority() -> Pubkey {
+    Pubkey::find_program_address(&[b"timelock"], &crate::ID).0
+}
+
+// ===== ACCOUNTS =====
+
+#[derive(Accounts)]
+pub struct InitializeTimelock<'info> {
+    #[account(
+        init,
+        payer = admin,
+        space = Timelock::LEN,
+        seeds = [b"timelock"],
+        bump
+    )]
+    pub timelock: Account<'info, Timelock>,
+
+    #[account(mut)]
+    pub admin: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+#[instruction(program_id: Pubkey, accounts: Vec<OperationAccount>, data: Vec<u8>)]
+pub struct QueueOperation<'info> {
+    #[account(mut, seeds = [b"timelock"], bump = timelock.bump)]
+    pub timelock: Account<'info, Timelock>,
+
+    #[account(
+        init,
+        payer = proposer,
+        space = Operation::space(accounts.len(), data.len()),
+        seeds = [b"operation", timelock.operation_count.to_le_bytes().as_ref()],
+        bump
+    )]
+    pub operation: Account<'info, Operation>,
+
+    #[account(mut)]
+    pub proposer: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct CancelOperation<'info> {
+    #[account(
+        mut,
+        seeds = [b"operation", operation.id.to_le_bytes().as_ref()],
+        bump = operation.bump,
+        close = proposer
+    )]
+    pub operation: Account<'info, Operation>,
+
+    /// CHECK: rent refund destination, checked against the operation
+    #[account(mut, address = operation.proposer)]
+    pub proposer: UncheckedAccount<'info>,
+
+    pub admin: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct ExecuteOperation<'info> {
+    #[account(seeds = [b"timelock"], bump = timelock.bump)]
+    pub timelock: Account<'info, Timelock>,
+
+    #[account(
+        mut,
+        seeds = [b"operation", operation.id.to_le_bytes().as_ref()],
+        bump = operation.bump,
+        close = proposer
+    )]
+    pub operation: Account<'info, Operation>,
+
+    /// CHECK: rent refund destination, checked against the operation
+    #[account(mut, address = operation.proposer)]
+    pub proposer: UncheckedAccount<'info>,
+}
+
+#[derive(Accounts)]
+pub struct SetMinDelay<'info> {
+    #[account(mut, seeds = [b"timelock"], bump = timelock.bump)]
+    pub timelock: Account<'info, Timelock>,
+
+    /// The timelock PDA itself, signing through `execute_operation`
+    #[account(constraint = authority.key() == timelock.key() @ TimelockError::UnauthorizedAdmin)]
+    pub authority: Signer<'info>,
+}
+
+// ===== STATE =====
+
+#[account]
+pub struct Timelock {
+    pub min_delay: i64,
+    pub operation_count: u64,
+    pub bump: u8,
+}
+
+impl Timelock {
+    pub const LEN: usize = 8 + // discriminator
+        8 +  // min_delay
+        8 +  // operation_count
+        1;   // bump
+}
+
+/// Account meta of a queued instruction; the timelock PDA is marked as
+/// a signer where the target expects the admin
+#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+pub struct OperationAccount {
+    pub pubkey: Pubkey,
+    pub is_signer: bool,
+    pub is_writable: bool,
+}
+
+impl OperationAccount {
+    pub const LEN: usize = 32 + 1 + 1;
+}
+
+/// Instruction queued for execution with the timelock as signer
+#[account]
+pub struct Operation {
+    pub id: u64,
+    pub proposer: Pubkey,
+    pub program_id: Pubkey,
+    pub accounts: Vec<OperationAccount>,
+    pub data: Vec<u8>,
+    pub queued_at: i64,
+    pub eta: i64,
+    pub bump: u8,
+}
+
+impl Operation {
+    pub fn space(accounts: usize, data: usize) -> usize {
+        8 + // discriminator
+        8 +  // id
+        32 + // proposer
+        32 + // program_id
+        4 + accounts * OperationAccount::LEN + // accounts
+        4 + data + // data
+        8 +  // queued_at
+        8 +  // eta
+        1    // bump
+    }
+}
+
+// ===== EVENTS =====
+
+#[event]
+pub struct OperationQueued {
+    pub operation: Pubkey,
+    pub id: u64,
+    pub program_id: Pubkey,
+    pub accounts: Vec<OperationAccount>,
+    pub data: Vec<u8>,
+    pub eta: i64,
+}
+
+#[event]
+pub struct OperationCancelled {
+    pub operation: Pubkey,
+    pub id: u64,
+}
+
+#[event]
+pub struct OperationExecuted {
+    pub operation: Pubkey,
+    pub id: u64,
+    pub program_id: Pubkey,
+}
+
+#[error_code]
+pub enum TimelockError {
+    #[msg("Unauthorized admin")]
+    UnauthorizedAdmin,
+    #[msg("Delay outside the allowed range")]
+    InvalidDelay,
+    #[msg("Operation exceeds account or data limits")]
+    OperationTooLarge,
+    #[msg("Operation delay has not elapsed")]
+    OperationNotReady,
+    #[msg("Operation grace period has passed - cancel it")]
+    OperationStale,
+    #[msg("Math overflow in calculation")]
+    MathOverflow,
+    #[msg("A batch needs at least one instruction")]
+    EmptyBatch,
+}
@@ -0,0 +1,204 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token::{Mint, Token, TokenAccount};
+
+use vectai_protocol::{program::VectaiProtocol, ProtocolStatus};
+
+use crate::{TokenError, TokenInfo};
+
+#[derive(Accounts)]
+#[instruction(auction_id: u64)]
+pub struct CreateAuction<'info> {
+    #[account(
+        seeds = [b"token-info", mint.key().as_ref()],
+        bump
+    )]
+    pub token_info: Account<'info, TokenInfo>,
+
+    #[account(mut)]
+    pub mint: Account<'info, Mint>,
+
+    /// Mint buyers pay in (e.g. USDC); proceeds go to its treasury vault
+    pub quote_mint: Account<'info, Mint>,
+
+    #[account(
+        init,
+        payer = mint_authority,
+        space = Auction::LEN,
+        seeds = [b"auction", mint.key().as_ref(), &auction_id.to_le_bytes()],
+        bump
+    )]
+    pub auction: Account<'info, Auction>,
+
+    /// CHECK: PDA that takes over the SPL mint authority
+    #[account(seeds = [b"mint-authority", mint.key().as_ref()], bump)]
+    pub mint_signer: UncheckedAccount<'info>,
+
+    #[account(mut)]
+    pub mint_authority: Signer<'info>,
+
+    pub token_program: Program<'info, Token>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct BuyFromAuction<'info> {
+    #[account(
+        mut,
+        seeds = [b"token-info", mint.key().as_ref()],
+        bump
+    )]
+    pub token_info: Account<'info, TokenInfo>,
+
+    #[account(mut, address = auction.mint)]
+    pub mint: Account<'info, Mint>,
+
+    #[account(
+        mut,
+        seeds = [b"auction", auction.mint.as_ref(), &auction.auction_id.to_le_bytes()],
+        bump = auction.bump
+    )]
+    pub auction: Account<'info, Auction>,
+
+    #[account(
+        mut,
+        constraint = buyer_quote_account.mint == auction.quote_mint @ TokenError::InvalidTokenAccount,
+        constraint = buyer_quote_account.owner == buyer.key() @ TokenError::InvalidTokenAccount
+    )]
+    pub buyer_quote_account: Account<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        constraint = buyer_token_account.mint == mint.key() @ TokenError::InvalidTokenAccount
+    )]
+    pub buyer_token_account: Account<'info, TokenAccount>,
+
+    /// Treasury vault for the quote mint
+    #[account(
+        mut,
+        constraint = proceeds_account.key() == vectai_treasury::vault_address(&auction.quote_mint) @ TokenError::InvalidTokenAccount
+    )]
+    pub proceeds_account: Account<'info, TokenAccount>,
+
+    /// CHECK: PDA mint authority
+    #[account(seeds = [b"mint-authority", mint.key().as_ref()], bump)]
+    pub mint_signer: UncheckedAccount<'info>,
+
+    pub buyer: Signer<'info>,
+
+    pub token_program: Program<'info, Token>,
+
+    /// Global emergency-pause registry
+    #[account(
+        seeds = [b"protocol-status"],
+        bump = protocol_status.bump,
+        seeds::program = vectai_protocol::ID,
+        constraint = !protocol_status.paused @ TokenError::ProtocolPaused
+    )]
+    pub protocol_status: Account<'info, ProtocolStatus>,
+
+    /// CHECK: Protocol stats PDA, validated by vectai_protocol
+    #[account(mut)]
+    pub protocol_stats: UncheckedAccount<'info>,
+
+    /// CHECK: Signs `record_activity` for this program
+    #[account(seeds = [b"stats-reporter"], bump)]
+    pub stats_reporter: UncheckedAccount<'info>,
+
+    pub protocol_program: Program<'info, VectaiProtocol>,
+}
+
+#[derive(Accounts)]
+pub struct EndAuction<'info> {
+    #[account(
+        seeds = [b"token-info", auction.mint.as_ref()],
+        bump
+    )]
+    pub token_info: Account<'info, TokenInfo>,
+
+    #[account(
+        mut,
+        seeds = [b"auction", auction.mint.as_ref(), &auction.auction_id.to_le_bytes()],
+        bump = auction.bump,
+        close = mint_authority
+    )]
+    pub auction: Account<'info, Auction>,
+
+    #[account(mut)]
+    pub mint_authority: Signer<'info>,
+}
+
+/// Descending-price sale: the price falls linearly from `start_price` to
+/// `floor_price` between `start_time` and `end_time`, and every purchase
+/// is minted on the spot within the token's `max_supply`
+#[account]
+pub struct Auction {
+    pub mint: Pubkey,
+    pub quote_mint: Pubkey,
+    pub auction_id: u64,
+    pub start_price: u64, // Quote base units per whole token
+    pub floor_price: u64,
+    pub start_time: i64,
+    pub end_time: i64,
+    pub supply: u64, // Tokens offered, in base units
+    pub sold: u64,
+    pub proceeds: u64, // Quote base units sent to the treasury
+    pub bump: u8,
+}
+
+impl Auction {
+    pub const LEN: usize = 8 + // discriminator
+        32 + // mint
+        32 + // quote_mint
+        8 +  // auction_id
+        8 +  // start_price
+        8 +  // floor_price
+        8 +  // start_time
+        8 +  // end_time
+        8 +  // supply
+        8 +  // sold
+        8 +  // proceeds
+        1;   // bump
+
+    /// Price per whole token at `now`
+    pub fn current_price(&self, now: i64) -> u64 {
+        if now <= self.start_time {
+            return self.start_price;
+        }
+        if now >= self.end_time {
+            return self.floor_price;
+        }
+        let elapsed = (now - self.start_time) as u128;
+        let duration = (self.end_time - self.start_time) as u128;
+        let drop = (self.start_price - self.floor_price) as u128 * elapsed / duration;
+        self.start_price - drop as u64
+    }
+
+    /// Quote cost of `amount` base units at `price`, rounded up
+    pub fn cost(amount: u64, price: u64, decimals: u8) -> Result<u64> {
+        let unit = 10u128.pow(decimals as u32);
+        let cost = (amount as u128 * price as u128).div_ceil(unit);
+        u64::try_from(cost).map_err(|_| TokenError::MathOverflow.into())
+    }
+}
+
+#[event]
+pub struct AuctionCreated {
+    pub auction: Pubkey,
+    pub mint: Pubkey,
+    pub quote_mint: Pubkey,
+    pub start_price: u64,
+    pub floor_price: u64,
+    pub start_time: i64,
+    pub end_time: i64,
+    pub supply: u64,
+}
+
+#[event]
+pub struct AuctionPurchase {
+    pub auction: Pubkey,
+    pub buyer: Pubkey,
+    pub amount: u64,
+    pub price: u64,
+    pub cost: u64,
+    pub sold: u64,
+}
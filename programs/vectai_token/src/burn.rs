@@ -0,0 +1,32 @@
+use anchor_lang::prelude::*;
+
+use crate::TokenInfo;
+
+/// Highest share of a transfer burn tax mode may destroy
+pub const MAX_BURN_RATE_BPS: u16 = 1_000; // 10%
+
+#[derive(Accounts)]
+pub struct SetBurnRate<'info> {
+    #[account(
+        mut,
+        seeds = [b"token-info", token_info.mint.as_ref()],
+        bump
+    )]
+    pub token_info: Account<'info, TokenInfo>,
+
+    pub admin: Signer<'info>,
+}
+
+#[event]
+pub struct BurnRateUpdated {
+    pub mint: Pubkey,
+    pub burn_rate_bps: u16,
+}
+
+#[event]
+pub struct TransferBurned {
+    pub mint: Pubkey,
+    pub from: Pubkey,
+    pub amount: u64, // Requested, including the burned part
+    pub burned: u64,
+}
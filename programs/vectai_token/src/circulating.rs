@@ -0,0 +1,175 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::TokenAccount;
+
+use crate::{TokenError, TokenInfo};
+
+/// Token accounts one mint's registry can hold
+pub const MAX_SUPPLY_LOCKS: usize = 16;
+
+/// Why a registered account's balance is out of circulation
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum SupplyLockKind {
+    /// Team, investor or treasury vesting
+    Vesting,
+    /// Program escrows such as stake vaults, auctions or order escrows
+    Escrow,
+}
+
+/// One registered account holding tokens out of circulation
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub struct SupplyLock {
+    pub token_account: Pubkey,
+    pub kind: SupplyLockKind,
+}
+
+impl SupplyLock {
+    pub const LEN: usize = 32 + 1;
+}
+
+#[derive(Accounts)]
+pub struct RegisterSupplyLock<'info> {
+    #[account(
+        seeds = [b"token-info", token_info.mint.as_ref()],
+        bump
+    )]
+    pub token_info: Account<'info, TokenInfo>,
+
+    #[account(
+        init_if_needed,
+        payer = admin,
+        space = SupplyLocks::LEN,
+        seeds = [b"supply-locks", token_info.mint.as_ref()],
+        bump
+    )]
+    pub supply_locks: Account<'info, SupplyLocks>,
+
+    #[account(constraint = token_account.mint == token_info.mint @ TokenError::InvalidTokenAccount)]
+    pub token_account: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(mut)]
+    pub admin: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct DeregisterSupplyLock<'info> {
+    #[account(
+        mut,
+        seeds = [b"supply-locks", supply_locks.mint.as_ref()],
+        bump = supply_locks.bump
+    )]
+    pub supply_locks: Account<'info, SupplyLocks>,
+
+    pub admin: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct GetCirculatingSupply<'info> {
+    #[account(
+        seeds = [b"token-info", token_info.mint.as_ref()],
+        bump
+    )]
+    pub token_info: Account<'info, TokenInfo>,
+
+    /// Absent until the first lock is registered
+    #[account(
+        seeds = [b"supply-locks", token_info.mint.as_ref()],
+        bump = supply_locks.bump
+    )]
+    pub supply_locks: Option<Account<'info, SupplyLocks>>,
+}
+
+/// Accounts whose balances `get_circulating_supply` takes out of one
+/// mint's circulation
+#[account]
+pub struct SupplyLocks {
+    pub mint: Pubkey,
+    pub locks: Vec<SupplyLock>,
+    pub bump: u8,
+}
+
+impl SupplyLocks {
+    pub const LEN: usize = 8 + // discriminator
+        32 + // mint
+        4 + MAX_SUPPLY_LOCKS * SupplyLock::LEN + // locks
+        1;   // bump
+
+    /// Register `token_account`, or change its kind when already registered
+    pub fn register(&mut self, token_account: Pubkey, kind: SupplyLockKind) -> Result<()> {
+        match self.locks.iter_mut().find(|lock| lock.token_account == token_account) {
+            Some(lock) => lock.kind = kind,
+            None => {
+                require!(self.locks.len() < MAX_SUPPLY_LOCKS, TokenError::TooManySupplyLocks);
+                self.locks.push(SupplyLock { token_account, kind });
+            }
+        }
+        Ok(())
+    }
+
+    /// Sum the balances of `accounts`, passed in registry order, by kind:
+    /// `(vesting_locked, escrow_locked)`
+    pub fn locked(&self, accounts: &[AccountInfo]) -> Result<(u64, u64)> {
+        require!(accounts.len() == self.locks.len(), TokenError::SupplyLockMismatch);
+
+        let (mut vesting, mut escrow) = (0u64, 0u64);
+        for (lock, account) in self.locks.iter().zip(accounts) {
+            require_keys_eq!(account.key(), lock.token_account, TokenError::SupplyLockMismatch);
+            require!(
+                *account.owner == anchor_spl::token::ID || *account.owner == anchor_spl::token_2022::ID,
+                TokenError::InvalidTokenAccount
+            );
+            let balance = TokenAccount::try_deserialize(&mut &account.try_borrow_data()?[..])?.amount;
+            let total = match lock.kind {
+                SupplyLockKind::Vesting => &mut vesting,
+                SupplyLockKind::Escrow => &mut escrow,
+            };
+            *total = total.checked_add(balance).ok_or(TokenError::MathOverflow)?;
+        }
+        Ok((vesting, escrow))
+    }
+}
+
+/// Address of the `SupplyLocks` registry of `mint`
+pub fn supply_locks(mint: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(&[b"supply-locks", mint.as_ref()], &crate::ID).0
+}
+
+/// A mint's supply net of burns and registered locks, the figure
+/// explorers and the index and vault products should report
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug, PartialEq, Eq)]
+pub struct CirculatingSupply {
+    pub minted: u64,
+    pub burned: u64,
+    pub vesting_locked: u64,
+    pub escrow_locked: u64,
+    pub circulating: u64, // minted - burned - vesting_locked - escrow_locked
+}
+
+impl CirculatingSupply {
+    pub fn new(minted: u64, burned: u64, vesting_locked: u64, escrow_locked: u64) -> Self {
+        CirculatingSupply {
+            minted,
+            burned,
+            vesting_locked,
+            escrow_locked,
+            circulating: minted
+                .saturating_sub(burned)
+                .saturating_sub(vesting_locked)
+                .saturating_sub(escrow_locked),
+        }
+    }
+}
+
+#[event]
+pub struct SupplyLockRegistered {
+    pub mint: Pubkey,
+    pub token_account: Pubkey,
+    pub kind: SupplyLockKind,
+}
+
+#[event]
+pub struct SupplyLockDeregistered {
+    pub mint: Pubkey,
+    pub token_account: Pubkey,
+}
@@ -0,0 +1,195 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_2022::Token2022;
+use anchor_spl::token_interface::{Mint, TokenAccount};
+
+use crate::{TokenError, TokenInfo};
+
+#[derive(Accounts)]
+pub struct InitializeClawbackToken<'info> {
+    /// New mint keypair; created as a Token-2022 mint in the handler
+    #[account(mut)]
+    pub mint: Signer<'info>,
+
+    #[account(
+        init,
+        payer = payer,
+        space = TokenInfo::LEN,
+        seeds = [b"token-info", mint.key().as_ref()],
+        bump
+    )]
+    pub token_info: Account<'info, TokenInfo>,
+
+    /// CHECK: PDA installed as the mint's permanent delegate
+    #[account(seeds = [b"permanent-delegate", mint.key().as_ref()], bump)]
+    pub permanent_delegate: UncheckedAccount<'info>,
+
+    pub mint_authority: Signer<'info>,
+
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    pub token_program: Program<'info, Token2022>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct InitializeTransferHookToken<'info> {
+    /// New mint keypair; created as a Token-2022 mint in the handler
+    #[account(mut)]
+    pub mint: Signer<'info>,
+
+    #[account(
+        init,
+        payer = payer,
+        space = TokenInfo::LEN,
+        seeds = [b"token-info", mint.key().as_ref()],
+        bump
+    )]
+    pub token_info: Account<'info, TokenInfo>,
+
+    pub mint_authority: Signer<'info>,
+
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    pub token_program: Program<'info, Token2022>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct FlagAccount<'info> {
+    #[account(
+        seeds = [b"token-info", token_info.mint.as_ref()],
+        bump
+    )]
+    pub token_info: Account<'info, TokenInfo>,
+
+    #[account(constraint = flagged_token_account.mint == token_info.mint @ TokenError::InvalidTokenAccount)]
+    pub flagged_token_account: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(
+        init,
+        payer = compliance_authority,
+        space = FlaggedAccount::LEN,
+        seeds = [b"flagged", token_info.mint.as_ref(), flagged_token_account.key().as_ref()],
+        bump
+    )]
+    pub flag: Account<'info, FlaggedAccount>,
+
+    #[account(mut)]
+    pub compliance_authority: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct UnflagAccount<'info> {
+    #[account(
+        seeds = [b"token-info", token_info.mint.as_ref()],
+        bump
+    )]
+    pub token_info: Account<'info, TokenInfo>,
+
+    #[account(
+        mut,
+        close = compliance_authority,
+        seeds = [b"flagged", token_info.mint.as_ref(), flag.token_account.as_ref()],
+        bump = flag.bump
+    )]
+    pub flag: Account<'info, FlaggedAccount>,
+
+    #[account(mut)]
+    pub compliance_authority: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct Clawback<'info> {
+    #[account(
+        seeds = [b"token-info", mint.key().as_ref()],
+        bump
+    )]
+    pub token_info: Account<'info, TokenInfo>,
+
+    pub mint: InterfaceAccount<'info, Mint>,
+
+    #[account(
+        seeds = [b"flagged", mint.key().as_ref(), from.key().as_ref()],
+        bump = flag.bump
+    )]
+    pub flag: Account<'info, FlaggedAccount>,
+
+    #[account(mut)]
+    pub from: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(mut, constraint = to.mint == mint.key() @ TokenError::InvalidTokenAccount)]
+    pub to: InterfaceAccount<'info, TokenAccount>,
+
+    /// CHECK: PDA permanent delegate
+    #[account(seeds = [b"permanent-delegate", mint.key().as_ref()], bump)]
+    pub permanent_delegate: UncheckedAccount<'info>,
+
+    pub compliance_authority: Signer<'info>,
+    pub token_program: Program<'info, Token2022>,
+}
+
+#[derive(Accounts)]
+pub struct RenounceClawback<'info> {
+    #[account(
+        mut,
+        seeds = [b"token-info", mint.key().as_ref()],
+        bump
+    )]
+    pub token_info: Account<'info, TokenInfo>,
+
+    /// CHECK: Token-2022 mint, checked against token_info
+    #[account(mut, address = token_info.mint)]
+    pub mint: UncheckedAccount<'info>,
+
+    /// CHECK: PDA permanent delegate
+    #[account(seeds = [b"permanent-delegate", mint.key().as_ref()], bump)]
+    pub permanent_delegate: UncheckedAccount<'info>,
+
+    pub compliance_authority: Signer<'info>,
+    pub token_program: Program<'info, Token2022>,
+}
+
+/// Marks a token account as eligible for clawback
+#[account]
+pub struct FlaggedAccount {
+    pub mint: Pubkey,
+    pub token_account: Pubkey,
+    pub reason_code: u16,
+    pub flagged_at: i64,
+    pub bump: u8,
+}
+
+impl FlaggedAccount {
+    pub const LEN: usize = 8 + // discriminator
+        32 + // mint
+        32 + // token_account
+        2 +  // reason_code
+        8 +  // flagged_at
+        1;   // bump
+}
+
+#[event]
+pub struct AccountFlagged {
+    pub mint: Pubkey,
+    pub token_account: Pubkey,
+    pub reason_code: u16,
+}
+
+#[event]
+pub struct TokensClawedBack {
+    pub mint: Pubkey,
+    pub from: Pubkey,
+    pub to: Pubkey,
+    pub amount: u64,
+    pub reason_code: u16,
+    pub authority: Pubkey,
+}
+
+#[event]
+pub struct ClawbackRenounced {
+    pub mint: Pubkey,
+}
@@ -0,0 +1,131 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token::{Mint, Token, TokenAccount};
+
+use vectai_protocol::{program::VectaiProtocol, ProtocolStatus};
+
+use crate::{TokenError, TokenInfo, BPS_DENOMINATOR};
+
+#[derive(Accounts)]
+pub struct InitializeEmissionSchedule<'info> {
+    #[account(
+        mut,
+        seeds = [b"token-info", mint.key().as_ref()],
+        bump
+    )]
+    pub token_info: Account<'info, TokenInfo>,
+
+    #[account(mut)]
+    pub mint: Account<'info, Mint>,
+
+    #[account(
+        init,
+        payer = mint_authority,
+        space = EmissionSchedule::LEN,
+        seeds = [b"emission", mint.key().as_ref()],
+        bump
+    )]
+    pub emission_schedule: Account<'info, EmissionSchedule>,
+
+    /// VECTAI account receiving each epoch's emission
+    #[account(constraint = rewards_vault.mint == mint.key() @ TokenError::InvalidTokenAccount)]
+    pub rewards_vault: Account<'info, TokenAccount>,
+
+    /// CHECK: PDA that takes over the SPL mint authority
+    #[account(seeds = [b"mint-authority", mint.key().as_ref()], bump)]
+    pub mint_signer: UncheckedAccount<'info>,
+
+    #[account(mut)]
+    pub mint_authority: Signer<'info>,
+
+    pub token_program: Program<'info, Token>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct MintEpoch<'info> {
+    #[account(
+        mut,
+        seeds = [b"token-info", mint.key().as_ref()],
+        bump
+    )]
+    pub token_info: Account<'info, TokenInfo>,
+
+    #[account(mut)]
+    pub mint: Account<'info, Mint>,
+
+    #[account(
+        mut,
+        seeds = [b"emission", mint.key().as_ref()],
+        bump = emission_schedule.bump
+    )]
+    pub emission_schedule: Account<'info, EmissionSchedule>,
+
+    #[account(mut, address = emission_schedule.rewards_vault)]
+    pub rewards_vault: Account<'info, TokenAccount>,
+
+    /// CHECK: PDA mint authority
+    #[account(seeds = [b"mint-authority", mint.key().as_ref()], bump)]
+    pub mint_signer: UncheckedAccount<'info>,
+
+    pub token_program: Program<'info, Token>,
+
+    /// Global emergency-pause registry
+    #[account(
+        seeds = [b"protocol-status"],
+        bump = protocol_status.bump,
+        seeds::program = vectai_protocol::ID,
+        constraint = !protocol_status.paused @ TokenError::ProtocolPaused
+    )]
+    pub protocol_status: Account<'info, ProtocolStatus>,
+
+    /// CHECK: Protocol stats PDA, validated by vectai_protocol
+    #[account(mut)]
+    pub protocol_stats: UncheckedAccount<'info>,
+
+    /// CHECK: Signs `record_activity` for this program
+    #[account(seeds = [b"stats-reporter"], bump)]
+    pub stats_reporter: UncheckedAccount<'info>,
+
+    pub protocol_program: Program<'info, VectaiProtocol>,
+}
+
+/// Inflation schedule: `epoch_amount` is minted once per `epoch_duration`
+/// and shrinks by `decay_bps` after every epoch
+#[account]
+pub struct EmissionSchedule {
+    pub mint: Pubkey,
+    pub rewards_vault: Pubkey,
+    pub epoch_duration: i64, // Seconds
+    pub epoch_amount: u64,   // Amount for the next epoch
+    pub decay_bps: u16,
+    pub next_epoch_time: i64,
+    pub epochs_minted: u64,
+    pub total_emitted: u64,
+    pub bump: u8,
+}
+
+impl EmissionSchedule {
+    pub const LEN: usize = 8 + // discriminator
+        32 + // mint
+        32 + // rewards_vault
+        8 +  // epoch_duration
+        8 +  // epoch_amount
+        2 +  // decay_bps
+        8 +  // next_epoch_time
+        8 +  // epochs_minted
+        8 +  // total_emitted
+        1;   // bump
+
+    /// Amount for the epoch after one with `epoch_amount`
+    pub fn decayed_amount(epoch_amount: u64, decay_bps: u16) -> u64 {
+        vectai_common::bps_of(epoch_amount, BPS_DENOMINATOR.saturating_sub(decay_bps as u64))
+    }
+}
+
+#[event]
+pub struct EpochMinted {
+    pub mint: Pubkey,
+    pub epoch: u64,
+    pub amount: u64,
+    pub total_minted: u64,
+}
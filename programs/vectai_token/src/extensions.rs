@@ -0,0 +1,32 @@
+use anchor_lang::prelude::*;
+use anchor_lang::system_program::{self, CreateAccount};
+use anchor_spl::token_2022::spl_token_2022::extension::ExtensionType;
+use anchor_spl::token_2022::spl_token_2022::state::Mint as SplMint;
+
+/// Allocate a Token-2022 mint account sized for `extensions`.
+///
+/// Extensions must be initialized before `initialize_mint2`, so callers
+/// follow this with the extension init instructions and then the mint init.
+pub fn create_extension_mint_account<'info>(
+    payer: &AccountInfo<'info>,
+    mint: &AccountInfo<'info>,
+    system_program: &AccountInfo<'info>,
+    token_program: &Pubkey,
+    extensions: &[ExtensionType],
+) -> Result<()> {
+    let space = ExtensionType::try_calculate_account_len::<SplMint>(extensions)?;
+    let lamports = Rent::get()?.minimum_balance(space);
+
+    system_program::create_account(
+        CpiContext::new(
+            system_program.clone(),
+            CreateAccount {
+                from: payer.clone(),
+                to: mint.clone(),
+            },
+        ),
+        lamports,
+        space as u64,
+        token_program,
+    )
+}
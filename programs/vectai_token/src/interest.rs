@@ -0,0 +1,61 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_2022::Token2022;
+
+use crate::TokenInfo;
+
+/// Upper bound for the governance-set rate (20% APR)
+pub const MAX_INTEREST_RATE_BPS: i16 = 2_000;
+
+#[derive(Accounts)]
+pub struct InitializeInterestBearingToken<'info> {
+    /// New mint keypair; created as a Token-2022 mint in the handler
+    #[account(mut)]
+    pub mint: Signer<'info>,
+
+    #[account(
+        init,
+        payer = payer,
+        space = TokenInfo::LEN,
+        seeds = [b"token-info", mint.key().as_ref()],
+        bump
+    )]
+    pub token_info: Account<'info, TokenInfo>,
+
+    /// CHECK: PDA installed as the mint's interest rate authority
+    #[account(seeds = [b"rate-authority", mint.key().as_ref()], bump)]
+    pub rate_authority: UncheckedAccount<'info>,
+
+    pub mint_authority: Signer<'info>,
+
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    pub token_program: Program<'info, Token2022>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct SetInterestRate<'info> {
+    #[account(
+        seeds = [b"token-info", mint.key().as_ref()],
+        bump
+    )]
+    pub token_info: Account<'info, TokenInfo>,
+
+    /// CHECK: Token-2022 interest-bearing mint, checked against token_info
+    #[account(mut, address = token_info.mint)]
+    pub mint: UncheckedAccount<'info>,
+
+    /// CHECK: PDA rate authority
+    #[account(seeds = [b"rate-authority", mint.key().as_ref()], bump)]
+    pub rate_authority: UncheckedAccount<'info>,
+
+    pub admin: Signer<'info>,
+    pub token_program: Program<'info, Token2022>,
+}
+
+#[event]
+pub struct InterestRateUpdated {
+    pub mint: Pubkey,
+    pub rate_bps: i16,
+}
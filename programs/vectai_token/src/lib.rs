@@ -1,5 +1,57 @@
 use anchor_lang::prelude::*;
-use anchor_spl::token::{self, Mint, Token, TokenAccount, Transfer};
+use anchor_lang::solana_program::program::{invoke, invoke_signed};
+use anchor_lang::solana_program::program_option::COption;
+use anchor_spl::token::spl_token::instruction::AuthorityType;
+use anchor_spl::token::{self, Mint, SetAuthority, Token, Transfer};
+use anchor_spl::token_2022::spl_token_2022::{
+    self,
+    extension::{interest_bearing_mint, transfer_hook, ExtensionType},
+    onchain::invoke_transfer_checked,
+};
+use anchor_spl::token_2022::{self, InitializeMint2};
+use anchor_spl::token_interface::{self, Burn, TokenInterface, TransferChecked};
+use vectai_common::{is_admin, Versioned};
+use vectai_protocol::{holds_role, program::VectaiProtocol, ActivityKind, ProtocolStatus, Role, RoleRegistry};
+
+// Staking and fee-reward distribution
+mod staking;
+pub use staking::*;
+
+// Staking lock tiers
+mod locking;
+pub use locking::*;
+
+// Scheduled inflation
+mod emissions;
+pub use emissions::*;
+
+// Token-2022 mint creation helpers
+mod extensions;
+use extensions::create_extension_mint_account;
+
+// Compliance clawback mode
+mod compliance;
+pub use compliance::*;
+
+// Interest-bearing Token-2022 variant
+mod interest;
+pub use interest::*;
+
+// Dutch auction launches
+mod auction;
+pub use auction::*;
+
+// Burn-on-transfer tax mode
+mod burn;
+pub use burn::*;
+
+// Governance supply-cap increases
+mod supply;
+pub use supply::*;
+
+// Circulating supply net of burns and registered locks
+mod circulating;
+pub use circulating::*;
 
 declare_id!("DfpsT9PAeWbwwfE8EqTDqVUiCrsoHF1fogmPw42eqLPH");
 
@@ -13,6 +65,7 @@ pub mod vectai_token {
         max_supply: u64,
         decimals: u8,
     ) -> Result<()> {
+        require!(max_supply <= MAX_SUPPLY_CEILING, TokenError::InvalidMaxSupply);
         let token_info = &mut ctx.accounts.token_info;
         
         // Initialize token metadata
@@ -22,18 +75,26 @@ pub mod vectai_token {
         token_info.minted = 0;
         token_info.decimals = decimals;
         token_info.is_paused = false;
+        token_info.compliance_authority = Pubkey::default();
+        token_info.clawback_enabled = false;
+        token_info.version = TokenInfo::VERSION;
         
         msg!("VECTAI token initialized: {} max supply, {} decimals", max_supply, decimals);
         Ok(())
     }
 
-    /// Secure mint tokens with authorization and supply checks
+    /// Secure mint tokens with authorization and supply checks. Once the
+    /// program PDA holds the SPL mint authority, a minter may mint too.
     pub fn mint_to(ctx: Context<MintTokens>, amount: u64) -> Result<()> {
         // ✅ CHECKS: Validate inputs and authorization
         require!(amount > 0, TokenError::InvalidAmount);
         require!(!ctx.accounts.token_info.is_paused, TokenError::TokenPaused);
+        let pda_is_authority = ctx.accounts.mint.mint_authority
+            == COption::Some(ctx.accounts.mint_signer.key());
+        let authority = ctx.accounts.mint_authority.key();
         require!(
-            ctx.accounts.mint_authority.key() == ctx.accounts.token_info.mint_authority,
+            authority == ctx.accounts.token_info.mint_authority
+                || (pda_is_authority && holds_role(&ctx.accounts.role_registry, &authority, Role::Minter)),
             TokenError::UnauthorizedMintAuthority
         );
         
@@ -48,22 +109,48 @@ pub mod vectai_token {
         ctx.accounts.token_info.minted = new_total;
         
         // ✅ INTERACTIONS: Execute CPI after state update
-        let cpi_accounts = anchor_spl::token::MintTo {
+        // Once an emission schedule exists the SPL mint authority is the
+        // program PDA, which signs on behalf of the recorded mint authority
+        let mint_key = ctx.accounts.mint.key();
+        let signer_seeds: &[&[u8]] = &[b"mint-authority", mint_key.as_ref(), &[ctx.bumps.mint_signer]];
+        let signer = &[signer_seeds];
+        let cpi_accounts = token_interface::MintTo {
             mint: ctx.accounts.mint.to_account_info(),
             to: ctx.accounts.to.to_account_info(),
-            authority: ctx.accounts.mint_authority.to_account_info(),
+            authority: if pda_is_authority {
+                ctx.accounts.mint_signer.to_account_info()
+            } else {
+                ctx.accounts.mint_authority.to_account_info()
+            },
         };
         let cpi_program = ctx.accounts.token_program.to_account_info();
-        let cpi_ctx = CpiContext::new(cpi_program, cpi_accounts);
+        let cpi_ctx = if pda_is_authority {
+            CpiContext::new_with_signer(cpi_program, cpi_accounts, signer)
+        } else {
+            CpiContext::new(cpi_program, cpi_accounts)
+        };
         
-        token::mint_to(cpi_ctx, amount)?;
+        token_interface::mint_to(cpi_ctx, amount)?;
+
+        vectai_protocol::report(
+            ctx.accounts.protocol_program.to_account_info(),
+            ctx.accounts.protocol_stats.to_account_info(),
+            ctx.accounts.stats_reporter.to_account_info(),
+            ctx.bumps.stats_reporter,
+            ActivityKind::Mint,
+            mint_key,
+            amount,
+        )?;
         
         msg!("✅ Minted {} VECTAI tokens (Total minted: {})", amount, new_total);
         Ok(())
     }
 
-    /// Secure transfer tokens with ownership validation
-    pub fn transfer(ctx: Context<TransferTokens>, amount: u64) -> Result<()> {
+    /// Secure transfer tokens with ownership validation. In burn tax mode
+    /// `burn_rate_bps` of `amount` is burned from the sender and only the
+    /// rest arrives. For a transfer-hook mint the remaining accounts are the
+    /// hook program, its extra account metas and the extra accounts they list.
+    pub fn transfer<'info>(ctx: Context<'_, '_, '_, 'info, TransferTokens<'info>>, amount: u64) -> Result<()> {
         // ✅ CHECKS: Validate inputs and ownership
         require!(amount > 0, TokenError::InvalidAmount);
         require!(
@@ -75,38 +162,111 @@ pub mod vectai_token {
             TokenError::InsufficientBalance
         );
         
-        // ✅ INTERACTIONS: Execute transfer (no state changes needed)
-        let cpi_accounts = Transfer {
-            from: ctx.accounts.from.to_account_info(),
-            to: ctx.accounts.to.to_account_info(), 
-            authority: ctx.accounts.authority.to_account_info(),
-        };
-        let cpi_program = ctx.accounts.token_program.to_account_info();
-        let cpi_ctx = CpiContext::new(cpi_program, cpi_accounts);
+        // ✅ EFFECTS: Count the burn tax before the CPIs
+        let burned = vectai_common::bps_of(amount, ctx.accounts.token_info.burn_rate_bps as u64);
+        ctx.accounts.token_info.burned = ctx.accounts.token_info
+            .burned
+            .checked_add(burned)
+            .ok_or(TokenError::MathOverflow)?;
         
-        token::transfer(cpi_ctx, amount)?;
+        // ✅ INTERACTIONS: Execute transfer, through the mint's hook if it has one
+        invoke_transfer_checked(
+            &ctx.accounts.token_program.key(),
+            ctx.accounts.from.to_account_info(),
+            ctx.accounts.mint.to_account_info(),
+            ctx.accounts.to.to_account_info(),
+            ctx.accounts.authority.to_account_info(),
+            ctx.remaining_accounts,
+            amount - burned,
+            ctx.accounts.mint.decimals,
+            &[],
+        )?;
+        
+        if burned > 0 {
+            token_interface::burn(
+                CpiContext::new(
+                    ctx.accounts.token_program.to_account_info(),
+                    Burn {
+                        mint: ctx.accounts.mint.to_account_info(),
+                        from: ctx.accounts.from.to_account_info(),
+                        authority: ctx.accounts.authority.to_account_info(),
+                    },
+                ),
+                burned,
+            )?;
+            emit!(TransferBurned {
+                mint: ctx.accounts.mint.key(),
+                from: ctx.accounts.from.key(),
+                amount,
+                burned,
+            });
+            msg!("🔥 Burned {} VECTAI tokens", burned);
+        }
         
         msg!("✅ Transferred {} VECTAI tokens from {} to {}", 
-             amount, ctx.accounts.from.key(), ctx.accounts.to.key());
+             amount - burned, ctx.accounts.from.key(), ctx.accounts.to.key());
+        Ok(())
+    }
+
+    /// Set the share of each `transfer` burned, or remove burn tax mode
+    /// with 0 (timelock only)
+    pub fn set_burn_rate(ctx: Context<SetBurnRate>, burn_rate_bps: u16) -> Result<()> {
+        require!(
+            is_timelock(&ctx.accounts.admin.key()),
+            TokenError::UnauthorizedAdmin
+        );
+        require!(burn_rate_bps <= MAX_BURN_RATE_BPS, TokenError::InvalidBurnRate);
+
+        ctx.accounts.token_info.burn_rate_bps = burn_rate_bps;
+        emit!(BurnRateUpdated {
+            mint: ctx.accounts.token_info.mint,
+            burn_rate_bps,
+        });
+        msg!("✅ Burn rate set to {} bps", burn_rate_bps);
+        Ok(())
+    }
+
+    /// Raise the supply cap, up to `MAX_SUPPLY_CEILING` (timelock only; the
+    /// mint authority can never raise it)
+    pub fn raise_max_supply(ctx: Context<RaiseMaxSupply>, max_supply: u64) -> Result<()> {
+        require!(
+            is_timelock(&ctx.accounts.admin.key()),
+            TokenError::UnauthorizedAdmin
+        );
+        let token_info = &mut ctx.accounts.token_info;
+        require!(
+            max_supply > token_info.max_supply && max_supply <= MAX_SUPPLY_CEILING,
+            TokenError::InvalidMaxSupply
+        );
+
+        let old_max_supply = token_info.max_supply;
+        token_info.max_supply = max_supply;
+        emit!(MaxSupplyRaised {
+            mint: token_info.mint,
+            old_max_supply,
+            new_max_supply: max_supply,
+        });
+        msg!("✅ Max supply raised from {} to {}", old_max_supply, max_supply);
         Ok(())
     }
 
-    /// Emergency pause function (admin only)
+    /// Emergency pause function (admin or a pauser)
     pub fn pause_token(ctx: Context<PauseToken>) -> Result<()> {
+        let admin = ctx.accounts.admin.key();
         require!(
-            ctx.accounts.admin.key() == ADMIN_AUTHORITY,
+            is_admin(&admin) || holds_role(&ctx.accounts.role_registry, &admin, Role::Pauser),
             TokenError::UnauthorizedAdmin
         );
         
         ctx.accounts.token_info.is_paused = true;
-        msg!("🚨 VECTAI token paused by admin");
+        msg!("🚨 VECTAI token paused by {}", admin);
         Ok(())
     }
 
     /// Unpause token (admin only)
     pub fn unpause_token(ctx: Context<PauseToken>) -> Result<()> {
         require!(
-            ctx.accounts.admin.key() == ADMIN_AUTHORITY,
+            is_admin(&ctx.accounts.admin.key()),
             TokenError::UnauthorizedAdmin
         );
         
@@ -114,10 +274,1032 @@ pub mod vectai_token {
         msg!("✅ VECTAI token unpaused by admin");
         Ok(())
     }
+
+    /// Bring a token info written by an older build up to the current
+    /// layout; permissionless, the payer covers any extra rent
+    pub fn migrate_token_info(ctx: Context<MigrateTokenInfo>) -> Result<()> {
+        let from = vectai_common::migrate::<TokenInfo>(
+            &ctx.accounts.token_info.to_account_info(),
+            &ctx.accounts.payer.to_account_info(),
+            &ctx.accounts.system_program.to_account_info(),
+        )?;
+        msg!("Token info migrated from v{} to v{}", from, TokenInfo::VERSION);
+        Ok(())
+    }
+
+    /// View: the token info, returned to CPI callers in its account layout
+    /// (without the discriminator)
+    pub fn get_token_info(ctx: Context<GetTokenInfo>) -> Result<TokenInfo> {
+        Ok((*ctx.accounts.token_info).clone())
+    }
+
+    /// Take `token_account`'s balance out of the circulating supply as
+    /// `kind`, creating the mint's registry on first use (admin only)
+    pub fn register_supply_lock(ctx: Context<RegisterSupplyLock>, kind: SupplyLockKind) -> Result<()> {
+        require!(
+            is_admin(&ctx.accounts.admin.key()),
+            TokenError::UnauthorizedAdmin
+        );
+
+        let mint = ctx.accounts.token_info.mint;
+        let token_account = ctx.accounts.token_account.key();
+        let supply_locks = &mut ctx.accounts.supply_locks;
+        supply_locks.mint = mint;
+        supply_locks.bump = ctx.bumps.supply_locks;
+        supply_locks.register(token_account, kind)?;
+
+        emit!(SupplyLockRegistered { mint, token_account, kind });
+        msg!("✅ Supply lock registered: {} ({:?})", token_account, kind);
+        Ok(())
+    }
+
+    /// Return `token_account`'s balance to the circulating supply (admin
+    /// only)
+    pub fn deregister_supply_lock(ctx: Context<DeregisterSupplyLock>, token_account: Pubkey) -> Result<()> {
+        require!(
+            is_admin(&ctx.accounts.admin.key()),
+            TokenError::UnauthorizedAdmin
+        );
+
+        let supply_locks = &mut ctx.accounts.supply_locks;
+        let index = supply_locks
+            .locks
+            .iter()
+            .position(|lock| lock.token_account == token_account)
+            .ok_or(TokenError::SupplyLockMismatch)?;
+        supply_locks.locks.remove(index);
+
+        emit!(SupplyLockDeregistered { mint: supply_locks.mint, token_account });
+        msg!("✅ Supply lock deregistered: {}", token_account);
+        Ok(())
+    }
+
+    /// View: minted less burned and the balances of the registered locks,
+    /// passed as remaining accounts in registry order
+    pub fn get_circulating_supply(ctx: Context<GetCirculatingSupply>) -> Result<CirculatingSupply> {
+        let (vesting_locked, escrow_locked) = match &ctx.accounts.supply_locks {
+            Some(supply_locks) => supply_locks.locked(ctx.remaining_accounts)?,
+            None => (0, 0),
+        };
+        let token_info = &ctx.accounts.token_info;
+        Ok(CirculatingSupply::new(token_info.minted, token_info.burned, vesting_locked, escrow_locked))
+    }
+
+    /// Create the staking pool and its stake/reward/fee vaults (admin only)
+    pub fn initialize_stake_pool(ctx: Context<InitializeStakePool>) -> Result<()> {
+        require!(
+            is_admin(&ctx.accounts.admin.key()),
+            TokenError::UnauthorizedAdmin
+        );
+
+        let stake_pool = &mut ctx.accounts.stake_pool;
+        stake_pool.mint = ctx.accounts.mint.key();
+        stake_pool.reward_mint = ctx.accounts.reward_mint.key();
+        stake_pool.stake_vault = ctx.accounts.stake_vault.key();
+        stake_pool.reward_vault = ctx.accounts.reward_vault.key();
+        stake_pool.fee_vault = ctx.accounts.fee_vault.key();
+        stake_pool.total_staked = 0;
+        stake_pool.acc_reward_per_share = 0;
+        stake_pool.total_distributed = 0;
+        stake_pool.bump = ctx.bumps.stake_pool;
+
+        msg!("✅ Stake pool initialized (fee vault: {})", stake_pool.fee_vault);
+        Ok(())
+    }
+
+    /// Stake VECTAI into the pool
+    pub fn stake(ctx: Context<Stake>, amount: u64) -> Result<()> {
+        // ✅ CHECKS
+        require!(amount > 0, TokenError::InvalidAmount);
+        require!(!ctx.accounts.token_info.is_paused, TokenError::TokenPaused);
+        require!(
+            ctx.accounts.owner_token_account.amount >= amount,
+            TokenError::InsufficientBalance
+        );
+
+        // ✅ EFFECTS: Settle rewards at the old balance before changing it
+        let acc_reward_per_share = ctx.accounts.stake_pool.acc_reward_per_share;
+        let position = &mut ctx.accounts.position;
+        if position.owner == Pubkey::default() {
+            position.owner = ctx.accounts.owner.key();
+            position.pool = ctx.accounts.stake_pool.key();
+            position.bump = ctx.bumps.position;
+        }
+        position.settle(acc_reward_per_share)?;
+        position.amount = position
+            .amount
+            .checked_add(amount)
+            .ok_or(TokenError::MathOverflow)?;
+        position.checkpoint(acc_reward_per_share)?;
+
+        ctx.accounts.stake_pool.total_staked = ctx.accounts.stake_pool
+            .total_staked
+            .checked_add(amount)
+            .ok_or(TokenError::MathOverflow)?;
+
+        // ✅ INTERACTIONS
+        let cpi_accounts = Transfer {
+            from: ctx.accounts.owner_token_account.to_account_info(),
+            to: ctx.accounts.stake_vault.to_account_info(),
+            authority: ctx.accounts.owner.to_account_info(),
+        };
+        let cpi_ctx = CpiContext::new(ctx.accounts.token_program.to_account_info(), cpi_accounts);
+        token::transfer(cpi_ctx, amount)?;
+
+        msg!("✅ Staked {} VECTAI (pool total: {})", amount, ctx.accounts.stake_pool.total_staked);
+        Ok(())
+    }
+
+    /// Withdraw staked VECTAI; accrued rewards stay claimable
+    pub fn unstake(ctx: Context<Unstake>, amount: u64) -> Result<()> {
+        // ✅ CHECKS
+        require!(amount > 0, TokenError::InvalidAmount);
+        require!(
+            ctx.accounts.position.amount >= amount,
+            TokenError::InsufficientBalance
+        );
+
+        // ✅ EFFECTS
+        let acc_reward_per_share = ctx.accounts.stake_pool.acc_reward_per_share;
+        let position = &mut ctx.accounts.position;
+        position.settle(acc_reward_per_share)?;
+        position.amount -= amount;
+        position.checkpoint(acc_reward_per_share)?;
+
+        ctx.accounts.stake_pool.total_staked = ctx.accounts.stake_pool
+            .total_staked
+            .checked_sub(amount)
+            .ok_or(TokenError::MathOverflow)?;
+
+        // ✅ INTERACTIONS: Pool PDA signs for its vault
+        let mint = ctx.accounts.stake_pool.mint;
+        let seeds: &[&[u8]] = &[b"stake-pool", mint.as_ref(), &[ctx.accounts.stake_pool.bump]];
+        let signer = &[seeds];
+        let cpi_accounts = Transfer {
+            from: ctx.accounts.stake_vault.to_account_info(),
+            to: ctx.accounts.owner_token_account.to_account_info(),
+            authority: ctx.accounts.stake_pool.to_account_info(),
+        };
+        let cpi_ctx = CpiContext::new_with_signer(
+            ctx.accounts.token_program.to_account_info(),
+            cpi_accounts,
+            signer,
+        );
+        token::transfer(cpi_ctx, amount)?;
+
+        msg!("✅ Unstaked {} VECTAI", amount);
+        Ok(())
+    }
+
+    /// Pay out a position's accrued fee rewards
+    pub fn claim_rewards(ctx: Context<ClaimRewards>) -> Result<()> {
+        let acc_reward_per_share = ctx.accounts.stake_pool.acc_reward_per_share;
+        let position = &mut ctx.accounts.position;
+        position.settle(acc_reward_per_share)?;
+        position.checkpoint(acc_reward_per_share)?;
+
+        let amount = position.pending_rewards;
+        require!(amount > 0, TokenError::NothingToClaim);
+        position.pending_rewards = 0;
+
+        let mint = ctx.accounts.stake_pool.mint;
+        let seeds: &[&[u8]] = &[b"stake-pool", mint.as_ref(), &[ctx.accounts.stake_pool.bump]];
+        let signer = &[seeds];
+        let cpi_accounts = Transfer {
+            from: ctx.accounts.reward_vault.to_account_info(),
+            to: ctx.accounts.owner_reward_account.to_account_info(),
+            authority: ctx.accounts.stake_pool.to_account_info(),
+        };
+        let cpi_ctx = CpiContext::new_with_signer(
+            ctx.accounts.token_program.to_account_info(),
+            cpi_accounts,
+            signer,
+        );
+        token::transfer(cpi_ctx, amount)?;
+
+        msg!("✅ Claimed {} reward tokens", amount);
+        Ok(())
+    }
+
+    /// Close an emptied stake position and return its rent; unstake and
+    /// claim first
+    pub fn close_stake_position(_ctx: Context<CloseStakePosition>) -> Result<()> {
+        msg!("✅ Stake position closed");
+        Ok(())
+    }
+
+    /// Permissionless crank: sweep the fee vault into the reward vault and
+    /// credit stakers pro-rata to their share of `total_staked`. Only the
+    /// pool's own fee vault is swept; trade and swap fees reach it through
+    /// a treasury `execute_spend` to the fee vault.
+    pub fn distribute_rewards(ctx: Context<DistributeRewards>) -> Result<()> {
+        let amount = ctx.accounts.fee_vault.amount;
+        require!(amount > 0, TokenError::NothingToDistribute);
+
+        // ✅ EFFECTS
+        let stake_pool = &mut ctx.accounts.stake_pool;
+        let delta = reward_per_share_delta(amount, stake_pool.total_staked)?;
+        stake_pool.acc_reward_per_share = stake_pool
+            .acc_reward_per_share
+            .checked_add(delta)
+            .ok_or(TokenError::MathOverflow)?;
+        stake_pool.total_distributed = stake_pool
+            .total_distributed
+            .checked_add(amount)
+            .ok_or(TokenError::MathOverflow)?;
+
+        // ✅ INTERACTIONS
+        let mint = ctx.accounts.stake_pool.mint;
+        let seeds: &[&[u8]] = &[b"stake-pool", mint.as_ref(), &[ctx.accounts.stake_pool.bump]];
+        let signer = &[seeds];
+        let cpi_accounts = Transfer {
+            from: ctx.accounts.fee_vault.to_account_info(),
+            to: ctx.accounts.reward_vault.to_account_info(),
+            authority: ctx.accounts.stake_pool.to_account_info(),
+        };
+        let cpi_ctx = CpiContext::new_with_signer(
+            ctx.accounts.token_program.to_account_info(),
+            cpi_accounts,
+            signer,
+        );
+        token::transfer(cpi_ctx, amount)?;
+
+        emit!(RewardsDistributed {
+            pool: ctx.accounts.stake_pool.key(),
+            amount,
+            total_staked: ctx.accounts.stake_pool.total_staked,
+            acc_reward_per_share: ctx.accounts.stake_pool.acc_reward_per_share,
+        });
+        msg!("✅ Distributed {} fee tokens to {} staked", amount, ctx.accounts.stake_pool.total_staked);
+        Ok(())
+    }
+
+    /// Create the per-tier accounting of a pool's locked stake (admin only)
+    pub fn initialize_lock_tiers(ctx: Context<InitializeLockTiers>) -> Result<()> {
+        require!(
+            is_admin(&ctx.accounts.admin.key()),
+            TokenError::UnauthorizedAdmin
+        );
+
+        let lock_tiers = &mut ctx.accounts.lock_tiers;
+        lock_tiers.pool = ctx.accounts.stake_pool.key();
+        lock_tiers.tiers = Default::default();
+        lock_tiers.bump = ctx.bumps.lock_tiers;
+
+        msg!("✅ Lock tiers initialized for pool {}", lock_tiers.pool);
+        Ok(())
+    }
+
+    /// Lock VECTAI for one of `LOCK_TIERS`' durations, earning at its
+    /// multiplier; adding to an existing lock restarts its duration
+    pub fn lock_stake(ctx: Context<LockStake>, tier: u8, amount: u64) -> Result<()> {
+        // ✅ CHECKS
+        let terms = LockTier::get(tier)?;
+        require!(amount > 0, TokenError::InvalidAmount);
+        require!(!ctx.accounts.token_info.is_paused, TokenError::TokenPaused);
+        require!(
+            ctx.accounts.owner_token_account.amount >= amount,
+            TokenError::InsufficientBalance
+        );
+
+        // ✅ EFFECTS: Settle rewards at the old weight before changing it
+        let clock = Clock::get()?;
+        let acc_reward_per_share = ctx.accounts.stake_pool.acc_reward_per_share;
+        let position = &mut ctx.accounts.position;
+        let opened = position.owner == Pubkey::default();
+        if opened {
+            position.owner = ctx.accounts.owner.key();
+            position.pool = ctx.accounts.stake_pool.key();
+            position.tier = tier;
+            position.bump = ctx.bumps.position;
+        }
+        position.settle(acc_reward_per_share)?;
+        let old_weight = position.weight;
+        position.amount = position
+            .amount
+            .checked_add(amount)
+            .ok_or(TokenError::MathOverflow)?;
+        position.weight = terms.weight(position.amount)?;
+        position.unlock_at = clock
+            .unix_timestamp
+            .checked_add(terms.duration)
+            .ok_or(TokenError::MathOverflow)?;
+        position.checkpoint(acc_reward_per_share)?;
+        let added_weight = position.weight - old_weight;
+        let unlock_at = position.unlock_at;
+
+        ctx.accounts.stake_pool.total_staked = ctx.accounts.stake_pool
+            .total_staked
+            .checked_add(added_weight)
+            .ok_or(TokenError::MathOverflow)?;
+
+        let totals = &mut ctx.accounts.lock_tiers.tiers[tier as usize];
+        totals.locked = totals.locked.checked_add(amount).ok_or(TokenError::MathOverflow)?;
+        totals.weight = totals.weight.checked_add(added_weight).ok_or(TokenError::MathOverflow)?;
+        if opened {
+            totals.positions += 1;
+        }
+
+        // ✅ INTERACTIONS
+        let cpi_accounts = Transfer {
+            from: ctx.accounts.owner_token_account.to_account_info(),
+            to: ctx.accounts.stake_vault.to_account_info(),
+            authority: ctx.accounts.owner.to_account_info(),
+        };
+        let cpi_ctx = CpiContext::new(ctx.accounts.token_program.to_account_info(), cpi_accounts);
+        token::transfer(cpi_ctx, amount)?;
+
+        emit!(StakeLocked {
+            pool: ctx.accounts.stake_pool.key(),
+            owner: ctx.accounts.owner.key(),
+            tier,
+            amount,
+            unlock_at,
+        });
+        msg!("🔒 Locked {} VECTAI in tier {} until {}", amount, tier, unlock_at);
+        Ok(())
+    }
+
+    /// Withdraw a lock with its rewards and close it. Before `unlock_at`
+    /// the tier's penalty share of the rewards goes back to the fee vault,
+    /// for the next `distribute_rewards` to pay to the remaining stakers.
+    pub fn unlock_stake(ctx: Context<UnlockStake>) -> Result<()> {
+        // ✅ EFFECTS
+        let clock = Clock::get()?;
+        let acc_reward_per_share = ctx.accounts.stake_pool.acc_reward_per_share;
+        let position = &mut ctx.accounts.position;
+        position.settle(acc_reward_per_share)?;
+        let (tier, amount, weight) = (position.tier, position.amount, position.weight);
+        let early = clock.unix_timestamp < position.unlock_at;
+        let penalty = if early { LockTier::get(tier)?.penalty(position.pending_rewards) } else { 0 };
+        let rewards = position.pending_rewards - penalty;
+        position.amount = 0;
+        position.weight = 0;
+        position.pending_rewards = 0;
+        position.reward_debt = 0;
+
+        ctx.accounts.stake_pool.total_staked = ctx.accounts.stake_pool
+            .total_staked
+            .checked_sub(weight)
+            .ok_or(TokenError::MathOverflow)?;
+
+        let totals = &mut ctx.accounts.lock_tiers.tiers[tier as usize];
+        totals.locked = totals.locked.checked_sub(amount).ok_or(TokenError::MathOverflow)?;
+        totals.weight = totals.weight.checked_sub(weight).ok_or(TokenError::MathOverflow)?;
+        totals.positions = totals.positions.saturating_sub(1);
+        totals.penalties = totals.penalties.checked_add(penalty).ok_or(TokenError::MathOverflow)?;
+
+        // ✅ INTERACTIONS: Pool PDA signs for its vaults
+        let mint = ctx.accounts.stake_pool.mint;
+        let seeds: &[&[u8]] = &[b"stake-pool", mint.as_ref(), &[ctx.accounts.stake_pool.bump]];
+        let signer = &[seeds];
+        let token_program = ctx.accounts.token_program.to_account_info();
+        let authority = ctx.accounts.stake_pool.to_account_info();
+        for (from, to, transfer_amount) in [
+            (&ctx.accounts.stake_vault, &ctx.accounts.owner_token_account, amount),
+            (&ctx.accounts.reward_vault, &ctx.accounts.owner_reward_account, rewards),
+            (&ctx.accounts.reward_vault, &ctx.accounts.fee_vault, penalty),
+        ] {
+            if transfer_amount == 0 {
+                continue;
+            }
+            let cpi_accounts = Transfer {
+                from: from.to_account_info(),
+                to: to.to_account_info(),
+                authority: authority.clone(),
+            };
+            token::transfer(CpiContext::new_with_signer(token_program.clone(), cpi_accounts, signer), transfer_amount)?;
+        }
+
+        emit!(StakeUnlocked {
+            pool: ctx.accounts.stake_pool.key(),
+            owner: ctx.accounts.owner.key(),
+            tier,
+            amount,
+            rewards,
+            penalty,
+        });
+        msg!("🔓 Unlocked {} VECTAI from tier {} ({} rewards, {} forfeited)", amount, tier, rewards, penalty);
+        Ok(())
+    }
+
+    /// Configure the emission schedule and hand the SPL mint authority to
+    /// the program PDA so `mint_epoch` can run permissionlessly
+    pub fn initialize_emission_schedule(
+        ctx: Context<InitializeEmissionSchedule>,
+        epoch_duration: i64,
+        initial_epoch_amount: u64,
+        decay_bps: u16,
+    ) -> Result<()> {
+        // ✅ CHECKS
+        require!(
+            ctx.accounts.mint_authority.key() == ctx.accounts.token_info.mint_authority,
+            TokenError::UnauthorizedMintAuthority
+        );
+        require!(epoch_duration > 0, TokenError::InvalidEmissionSchedule);
+        require!(initial_epoch_amount > 0, TokenError::InvalidAmount);
+        require!(
+            (decay_bps as u64) < BPS_DENOMINATOR,
+            TokenError::InvalidEmissionSchedule
+        );
+
+        // ✅ EFFECTS
+        let clock = Clock::get()?;
+        let schedule = &mut ctx.accounts.emission_schedule;
+        schedule.mint = ctx.accounts.mint.key();
+        schedule.rewards_vault = ctx.accounts.rewards_vault.key();
+        schedule.epoch_duration = epoch_duration;
+        schedule.epoch_amount = initial_epoch_amount;
+        schedule.decay_bps = decay_bps;
+        schedule.next_epoch_time = clock
+            .unix_timestamp
+            .checked_add(epoch_duration)
+            .ok_or(TokenError::MathOverflow)?;
+        schedule.epochs_minted = 0;
+        schedule.total_emitted = 0;
+        schedule.bump = ctx.bumps.emission_schedule;
+
+        // ✅ INTERACTIONS: Move SPL mint authority to the program PDA
+        let pda = ctx.accounts.mint_signer.key();
+        if ctx.accounts.mint.mint_authority != COption::Some(pda) {
+            let cpi_accounts = SetAuthority {
+                account_or_mint: ctx.accounts.mint.to_account_info(),
+                current_authority: ctx.accounts.mint_authority.to_account_info(),
+            };
+            let cpi_ctx = CpiContext::new(ctx.accounts.token_program.to_account_info(), cpi_accounts);
+            token::set_authority(cpi_ctx, AuthorityType::MintTokens, Some(pda))?;
+        }
+
+        msg!("✅ Emission schedule: {} per {}s epoch, {} bps decay",
+             initial_epoch_amount, epoch_duration, decay_bps);
+        Ok(())
+    }
+
+    /// Permissionless crank: mint one due epoch to the rewards vault,
+    /// clipped to the remaining `max_supply`
+    pub fn mint_epoch(ctx: Context<MintEpoch>) -> Result<()> {
+        // ✅ CHECKS
+        require!(!ctx.accounts.token_info.is_paused, TokenError::TokenPaused);
+        let clock = Clock::get()?;
+        require!(
+            clock.unix_timestamp >= ctx.accounts.emission_schedule.next_epoch_time,
+            TokenError::EpochNotReady
+        );
+
+        let token_info = &ctx.accounts.token_info;
+        let remaining = token_info
+            .max_supply
+            .checked_sub(token_info.minted)
+            .ok_or(TokenError::MathOverflow)?;
+        let amount = ctx.accounts.emission_schedule.epoch_amount.min(remaining);
+        require!(amount > 0, TokenError::ExceedsMaxSupply);
+
+        // ✅ EFFECTS
+        ctx.accounts.token_info.minted = ctx.accounts.token_info
+            .minted
+            .checked_add(amount)
+            .ok_or(TokenError::MathOverflow)?;
+
+        let schedule = &mut ctx.accounts.emission_schedule;
+        schedule.next_epoch_time = schedule
+            .next_epoch_time
+            .checked_add(schedule.epoch_duration)
+            .ok_or(TokenError::MathOverflow)?;
+        schedule.epochs_minted = schedule
+            .epochs_minted
+            .checked_add(1)
+            .ok_or(TokenError::MathOverflow)?;
+        schedule.total_emitted = schedule
+            .total_emitted
+            .checked_add(amount)
+            .ok_or(TokenError::MathOverflow)?;
+        schedule.epoch_amount = EmissionSchedule::decayed_amount(schedule.epoch_amount, schedule.decay_bps);
+
+        // ✅ INTERACTIONS
+        let mint_key = ctx.accounts.mint.key();
+        let seeds: &[&[u8]] = &[b"mint-authority", mint_key.as_ref(), &[ctx.bumps.mint_signer]];
+        let signer = &[seeds];
+        let cpi_accounts = anchor_spl::token::MintTo {
+            mint: ctx.accounts.mint.to_account_info(),
+            to: ctx.accounts.rewards_vault.to_account_info(),
+            authority: ctx.accounts.mint_signer.to_account_info(),
+        };
+        let cpi_ctx = CpiContext::new_with_signer(
+            ctx.accounts.token_program.to_account_info(),
+            cpi_accounts,
+            signer,
+        );
+        token::mint_to(cpi_ctx, amount)?;
+
+        vectai_protocol::report(
+            ctx.accounts.protocol_program.to_account_info(),
+            ctx.accounts.protocol_stats.to_account_info(),
+            ctx.accounts.stats_reporter.to_account_info(),
+            ctx.bumps.stats_reporter,
+            ActivityKind::Mint,
+            mint_key,
+            amount,
+        )?;
+
+        emit!(EpochMinted {
+            mint: mint_key,
+            epoch: ctx.accounts.emission_schedule.epochs_minted,
+            amount,
+            total_minted: ctx.accounts.token_info.minted,
+        });
+        msg!("✅ Epoch {} minted: {} VECTAI", ctx.accounts.emission_schedule.epochs_minted, amount);
+        Ok(())
+    }
+
+    /// Initialize a Token-2022 VECTAI mint in compliance clawback mode.
+    /// A program PDA becomes the mint's permanent delegate so the
+    /// compliance authority can recover tokens from flagged accounts.
+    pub fn initialize_clawback_token(
+        ctx: Context<InitializeClawbackToken>,
+        max_supply: u64,
+        decimals: u8,
+        compliance_authority: Pubkey,
+    ) -> Result<()> {
+        require!(max_supply <= MAX_SUPPLY_CEILING, TokenError::InvalidMaxSupply);
+        require!(
+            compliance_authority != Pubkey::default(),
+            TokenError::InvalidComplianceAuthority
+        );
+
+        // Create the mint with room for the permanent-delegate extension
+        create_extension_mint_account(
+            &ctx.accounts.payer.to_account_info(),
+            &ctx.accounts.mint.to_account_info(),
+            &ctx.accounts.system_program.to_account_info(),
+            &token_2022::ID,
+            &[ExtensionType::PermanentDelegate],
+        )?;
+
+        let init_delegate_ix = spl_token_2022::instruction::initialize_permanent_delegate(
+            &token_2022::ID,
+            &ctx.accounts.mint.key(),
+            &ctx.accounts.permanent_delegate.key(),
+        )?;
+        invoke(&init_delegate_ix, &[ctx.accounts.mint.to_account_info()])?;
+
+        token_2022::initialize_mint2(
+            CpiContext::new(
+                ctx.accounts.token_program.to_account_info(),
+                InitializeMint2 { mint: ctx.accounts.mint.to_account_info() },
+            ),
+            decimals,
+            &ctx.accounts.mint_authority.key(),
+            None,
+        )?;
+
+        let token_info = &mut ctx.accounts.token_info;
+        token_info.mint_authority = ctx.accounts.mint_authority.key();
+        token_info.mint = ctx.accounts.mint.key();
+        token_info.max_supply = max_supply;
+        token_info.minted = 0;
+        token_info.decimals = decimals;
+        token_info.is_paused = false;
+        token_info.compliance_authority = compliance_authority;
+        token_info.clawback_enabled = true;
+        token_info.version = TokenInfo::VERSION;
+
+        msg!("VECTAI token initialized in clawback mode (compliance: {})", compliance_authority);
+        Ok(())
+    }
+
+    /// Flag a token account for clawback (compliance authority only)
+    pub fn flag_account(ctx: Context<FlagAccount>, reason_code: u16) -> Result<()> {
+        require!(ctx.accounts.token_info.clawback_enabled, TokenError::ClawbackDisabled);
+        require!(
+            ctx.accounts.compliance_authority.key() == ctx.accounts.token_info.compliance_authority,
+            TokenError::UnauthorizedComplianceAuthority
+        );
+        require!(reason_code != 0, TokenError::MissingReasonCode);
+
+        let flag = &mut ctx.accounts.flag;
+        flag.mint = ctx.accounts.token_info.mint;
+        flag.token_account = ctx.accounts.flagged_token_account.key();
+        flag.reason_code = reason_code;
+        flag.flagged_at = Clock::get()?.unix_timestamp;
+        flag.bump = ctx.bumps.flag;
+
+        emit!(AccountFlagged {
+            mint: flag.mint,
+            token_account: flag.token_account,
+            reason_code,
+        });
+        msg!("🚩 Account {} flagged (reason {})", flag.token_account, reason_code);
+        Ok(())
+    }
+
+    /// Remove a clawback flag (compliance authority only)
+    pub fn unflag_account(ctx: Context<UnflagAccount>) -> Result<()> {
+        require!(
+            ctx.accounts.compliance_authority.key() == ctx.accounts.token_info.compliance_authority,
+            TokenError::UnauthorizedComplianceAuthority
+        );
+
+        msg!("✅ Account {} unflagged", ctx.accounts.flag.token_account);
+        Ok(())
+    }
+
+    /// Claw back tokens from a flagged account via the permanent delegate
+    pub fn clawback(ctx: Context<Clawback>, amount: u64, reason_code: u16) -> Result<()> {
+        // ✅ CHECKS
+        require!(ctx.accounts.token_info.clawback_enabled, TokenError::ClawbackDisabled);
+        require!(
+            ctx.accounts.compliance_authority.key() == ctx.accounts.token_info.compliance_authority,
+            TokenError::UnauthorizedComplianceAuthority
+        );
+        require!(reason_code != 0, TokenError::MissingReasonCode);
+        require!(amount > 0, TokenError::InvalidAmount);
+        require!(ctx.accounts.from.amount >= amount, TokenError::InsufficientBalance);
+
+        // ✅ INTERACTIONS
+        let mint_key = ctx.accounts.mint.key();
+        let seeds: &[&[u8]] = &[b"permanent-delegate", mint_key.as_ref(), &[ctx.bumps.permanent_delegate]];
+        let signer = &[seeds];
+        let cpi_accounts = TransferChecked {
+            from: ctx.accounts.from.to_account_info(),
+            mint: ctx.accounts.mint.to_account_info(),
+            to: ctx.accounts.to.to_account_info(),
+            authority: ctx.accounts.permanent_delegate.to_account_info(),
+        };
+        let cpi_ctx = CpiContext::new_with_signer(
+            ctx.accounts.token_program.to_account_info(),
+            cpi_accounts,
+            signer,
+        );
+        token_interface::transfer_checked(cpi_ctx, amount, ctx.accounts.mint.decimals)?;
+
+        emit!(TokensClawedBack {
+            mint: mint_key,
+            from: ctx.accounts.from.key(),
+            to: ctx.accounts.to.key(),
+            amount,
+            reason_code,
+            authority: ctx.accounts.compliance_authority.key(),
+        });
+        msg!("⚠️  Clawed back {} tokens from {} (reason {})", amount, ctx.accounts.from.key(), reason_code);
+        Ok(())
+    }
+
+    /// Permanently give up clawback: revokes the permanent delegate on the
+    /// mint and clears the compliance authority
+    pub fn renounce_clawback(ctx: Context<RenounceClawback>) -> Result<()> {
+        require!(ctx.accounts.token_info.clawback_enabled, TokenError::ClawbackDisabled);
+        require!(
+            ctx.accounts.compliance_authority.key() == ctx.accounts.token_info.compliance_authority,
+            TokenError::UnauthorizedComplianceAuthority
+        );
+
+        // ✅ EFFECTS
+        ctx.accounts.token_info.clawback_enabled = false;
+        ctx.accounts.token_info.compliance_authority = Pubkey::default();
+
+        // ✅ INTERACTIONS
+        let mint_key = ctx.accounts.mint.key();
+        let seeds: &[&[u8]] = &[b"permanent-delegate", mint_key.as_ref(), &[ctx.bumps.permanent_delegate]];
+        let signer = &[seeds];
+        token_2022::set_authority(
+            CpiContext::new_with_signer(
+                ctx.accounts.token_program.to_account_info(),
+                token_2022::SetAuthority {
+                    current_authority: ctx.accounts.permanent_delegate.to_account_info(),
+                    account_or_mint: ctx.accounts.mint.to_account_info(),
+                },
+                signer,
+            ),
+            spl_token_2022::instruction::AuthorityType::PermanentDelegate,
+            None,
+        )?;
+
+        emit!(ClawbackRenounced { mint: mint_key });
+        msg!("✅ Clawback mode renounced");
+        Ok(())
+    }
+
+    /// Initialize a Token-2022 VECTAI mint whose TransferHook extension
+    /// points at `vectai_transfer_hook`, so its denylist and transfer limits
+    /// apply to every transfer, whichever program sends it. The mint
+    /// authority then creates the rules with `initialize_compliance_rules`;
+    /// until then transfers fail.
+    pub fn initialize_transfer_hook_token(
+        ctx: Context<InitializeTransferHookToken>,
+        max_supply: u64,
+        decimals: u8,
+        compliance_authority: Pubkey,
+    ) -> Result<()> {
+        require!(max_supply <= MAX_SUPPLY_CEILING, TokenError::InvalidMaxSupply);
+        require!(
+            compliance_authority != Pubkey::default(),
+            TokenError::InvalidComplianceAuthority
+        );
+
+        create_extension_mint_account(
+            &ctx.accounts.payer.to_account_info(),
+            &ctx.accounts.mint.to_account_info(),
+            &ctx.accounts.system_program.to_account_info(),
+            &token_2022::ID,
+            &[ExtensionType::TransferHook],
+        )?;
+
+        // No hook authority: the hook program can never be swapped out
+        let init_hook_ix = transfer_hook::instruction::initialize(
+            &token_2022::ID,
+            &ctx.accounts.mint.key(),
+            None,
+            Some(vectai_transfer_hook::ID),
+        )?;
+        invoke(&init_hook_ix, &[ctx.accounts.mint.to_account_info()])?;
+
+        token_2022::initialize_mint2(
+            CpiContext::new(
+                ctx.accounts.token_program.to_account_info(),
+                InitializeMint2 { mint: ctx.accounts.mint.to_account_info() },
+            ),
+            decimals,
+            &ctx.accounts.mint_authority.key(),
+            None,
+        )?;
+
+        let token_info = &mut ctx.accounts.token_info;
+        token_info.mint_authority = ctx.accounts.mint_authority.key();
+        token_info.mint = ctx.accounts.mint.key();
+        token_info.max_supply = max_supply;
+        token_info.minted = 0;
+        token_info.decimals = decimals;
+        token_info.is_paused = false;
+        token_info.compliance_authority = compliance_authority;
+        token_info.clawback_enabled = false;
+        token_info.version = TokenInfo::VERSION;
+
+        msg!("VECTAI token initialized with transfer hook (compliance: {})", compliance_authority);
+        Ok(())
+    }
+
+    /// Initialize a Token-2022 VECTAI mint with the InterestBearingMint
+    /// extension; the rate is controlled by a program PDA on behalf of the admin
+    pub fn initialize_interest_bearing_token(
+        ctx: Context<InitializeInterestBearingToken>,
+        max_supply: u64,
+        decimals: u8,
+        rate_bps: i16,
+    ) -> Result<()> {
+        require!(max_supply <= MAX_SUPPLY_CEILING, TokenError::InvalidMaxSupply);
+        require!(
+            (0..=MAX_INTEREST_RATE_BPS).contains(&rate_bps),
+            TokenError::InvalidInterestRate
+        );
+
+        create_extension_mint_account(
+            &ctx.accounts.payer.to_account_info(),
+            &ctx.accounts.mint.to_account_info(),
+            &ctx.accounts.system_program.to_account_info(),
+            &token_2022::ID,
+            &[ExtensionType::InterestBearingConfig],
+        )?;
+
+        let init_rate_ix = interest_bearing_mint::instruction::initialize(
+            &token_2022::ID,
+            &ctx.accounts.mint.key(),
+            Some(ctx.accounts.rate_authority.key()),
+            rate_bps,
+        )?;
+        invoke(&init_rate_ix, &[ctx.accounts.mint.to_account_info()])?;
+
+        token_2022::initialize_mint2(
+            CpiContext::new(
+                ctx.accounts.token_program.to_account_info(),
+                InitializeMint2 { mint: ctx.accounts.mint.to_account_info() },
+            ),
+            decimals,
+            &ctx.accounts.mint_authority.key(),
+            None,
+        )?;
+
+        let token_info = &mut ctx.accounts.token_info;
+        token_info.mint_authority = ctx.accounts.mint_authority.key();
+        token_info.mint = ctx.accounts.mint.key();
+        token_info.max_supply = max_supply;
+        token_info.minted = 0;
+        token_info.decimals = decimals;
+        token_info.is_paused = false;
+        token_info.compliance_authority = Pubkey::default();
+        token_info.clawback_enabled = false;
+        token_info.version = TokenInfo::VERSION;
+
+        msg!("VECTAI interest-bearing token initialized at {} bps", rate_bps);
+        Ok(())
+    }
+
+    /// Update the interest rate of an interest-bearing mint (timelock only)
+    pub fn set_interest_rate(ctx: Context<SetInterestRate>, rate_bps: i16) -> Result<()> {
+        require!(
+            is_timelock(&ctx.accounts.admin.key()),
+            TokenError::UnauthorizedAdmin
+        );
+        require!(
+            (0..=MAX_INTEREST_RATE_BPS).contains(&rate_bps),
+            TokenError::InvalidInterestRate
+        );
+
+        let mint_key = ctx.accounts.mint.key();
+        let seeds: &[&[u8]] = &[b"rate-authority", mint_key.as_ref(), &[ctx.bumps.rate_authority]];
+        let update_ix = interest_bearing_mint::instruction::update_rate(
+            &token_2022::ID,
+            &mint_key,
+            &ctx.accounts.rate_authority.key(),
+            &[],
+            rate_bps,
+        )?;
+        invoke_signed(
+            &update_ix,
+            &[
+                ctx.accounts.mint.to_account_info(),
+                ctx.accounts.rate_authority.to_account_info(),
+            ],
+            &[seeds],
+        )?;
+
+        emit!(InterestRateUpdated { mint: mint_key, rate_bps });
+        msg!("✅ Interest rate set to {} bps", rate_bps);
+        Ok(())
+    }
+
+    /// Open a descending-price sale of newly minted tokens, handing the
+    /// SPL mint authority to the program PDA if it does not hold it yet
+    pub fn create_auction(
+        ctx: Context<CreateAuction>,
+        auction_id: u64,
+        start_price: u64,
+        floor_price: u64,
+        start_time: i64,
+        duration: i64,
+        supply: u64,
+    ) -> Result<()> {
+        // ✅ CHECKS
+        require!(
+            ctx.accounts.mint_authority.key() == ctx.accounts.token_info.mint_authority,
+            TokenError::UnauthorizedMintAuthority
+        );
+        require!(supply > 0, TokenError::InvalidAmount);
+        require!(
+            floor_price > 0 && start_price >= floor_price && duration > 0,
+            TokenError::InvalidAuctionParams
+        );
+        let token_info = &ctx.accounts.token_info;
+        let remaining = token_info
+            .max_supply
+            .checked_sub(token_info.minted)
+            .ok_or(TokenError::MathOverflow)?;
+        require!(supply <= remaining, TokenError::ExceedsMaxSupply);
+        let end_time = start_time.checked_add(duration).ok_or(TokenError::MathOverflow)?;
+
+        // ✅ EFFECTS
+        let auction = &mut ctx.accounts.auction;
+        auction.mint = ctx.accounts.mint.key();
+        auction.quote_mint = ctx.accounts.quote_mint.key();
+        auction.auction_id = auction_id;
+        auction.start_price = start_price;
+        auction.floor_price = floor_price;
+        auction.start_time = start_time;
+        auction.end_time = end_time;
+        auction.supply = supply;
+        auction.sold = 0;
+        auction.proceeds = 0;
+        auction.bump = ctx.bumps.auction;
+
+        // ✅ INTERACTIONS: Move SPL mint authority to the program PDA
+        let pda = ctx.accounts.mint_signer.key();
+        if ctx.accounts.mint.mint_authority != COption::Some(pda) {
+            let cpi_accounts = SetAuthority {
+                account_or_mint: ctx.accounts.mint.to_account_info(),
+                current_authority: ctx.accounts.mint_authority.to_account_info(),
+            };
+            let cpi_ctx = CpiContext::new(ctx.accounts.token_program.to_account_info(), cpi_accounts);
+            token::set_authority(cpi_ctx, AuthorityType::MintTokens, Some(pda))?;
+        }
+
+        emit!(AuctionCreated {
+            auction: ctx.accounts.auction.key(),
+            mint: ctx.accounts.mint.key(),
+            quote_mint: ctx.accounts.quote_mint.key(),
+            start_price,
+            floor_price,
+            start_time,
+            end_time,
+            supply,
+        });
+        msg!("✅ Auction #{}: {} tokens from {} down to {}", auction_id, supply, start_price, floor_price);
+        Ok(())
+    }
+
+    /// Buy `amount` at the current auction price; the buyer pays into the
+    /// treasury vault and receives freshly minted tokens
+    pub fn buy_from_auction(ctx: Context<BuyFromAuction>, amount: u64, max_price: u64) -> Result<()> {
+        // ✅ CHECKS
+        require!(amount > 0, TokenError::InvalidAmount);
+        require!(!ctx.accounts.token_info.is_paused, TokenError::TokenPaused);
+
+        let now = Clock::get()?.unix_timestamp;
+        let auction = &ctx.accounts.auction;
+        require!(
+            now >= auction.start_time && now < auction.end_time,
+            TokenError::AuctionNotLive
+        );
+        let price = auction.current_price(now);
+        require!(price <= max_price, TokenError::AuctionPriceAboveLimit);
+
+        let sold = auction.sold.checked_add(amount).ok_or(TokenError::MathOverflow)?;
+        require!(sold <= auction.supply, TokenError::AuctionSoldOut);
+        let new_total = ctx.accounts.token_info
+            .minted
+            .checked_add(amount)
+            .ok_or(TokenError::MathOverflow)?;
+        require!(new_total <= ctx.accounts.token_info.max_supply, TokenError::ExceedsMaxSupply);
+
+        let cost = Auction::cost(amount, price, ctx.accounts.mint.decimals)?;
+        require!(cost > 0, TokenError::InvalidAmount);
+        require!(
+            ctx.accounts.buyer_quote_account.amount >= cost,
+            TokenError::InsufficientBalance
+        );
+
+        // ✅ EFFECTS
+        ctx.accounts.token_info.minted = new_total;
+        let auction = &mut ctx.accounts.auction;
+        auction.sold = sold;
+        auction.proceeds = auction.proceeds.checked_add(cost).ok_or(TokenError::MathOverflow)?;
+
+        // ✅ INTERACTIONS
+        let cpi_accounts = Transfer {
+            from: ctx.accounts.buyer_quote_account.to_account_info(),
+            to: ctx.accounts.proceeds_account.to_account_info(),
+            authority: ctx.accounts.buyer.to_account_info(),
+        };
+        let cpi_ctx = CpiContext::new(ctx.accounts.token_program.to_account_info(), cpi_accounts);
+        token::transfer(cpi_ctx, cost)?;
+
+        let mint_key = ctx.accounts.mint.key();
+        let seeds: &[&[u8]] = &[b"mint-authority", mint_key.as_ref(), &[ctx.bumps.mint_signer]];
+        let signer = &[seeds];
+        let cpi_accounts = anchor_spl::token::MintTo {
+            mint: ctx.accounts.mint.to_account_info(),
+            to: ctx.accounts.buyer_token_account.to_account_info(),
+            authority: ctx.accounts.mint_signer.to_account_info(),
+        };
+        let cpi_ctx = CpiContext::new_with_signer(
+            ctx.accounts.token_program.to_account_info(),
+            cpi_accounts,
+            signer,
+        );
+        token::mint_to(cpi_ctx, amount)?;
+
+        vectai_protocol::report(
+            ctx.accounts.protocol_program.to_account_info(),
+            ctx.accounts.protocol_stats.to_account_info(),
+            ctx.accounts.stats_reporter.to_account_info(),
+            ctx.bumps.stats_reporter,
+            ActivityKind::Mint,
+            mint_key,
+            amount,
+        )?;
+
+        emit!(AuctionPurchase {
+            auction: ctx.accounts.auction.key(),
+            buyer: ctx.accounts.buyer.key(),
+            amount,
+            price,
+            cost,
+            sold,
+        });
+        msg!("✅ Auction sold {} at {} ({} paid)", amount, price, cost);
+        Ok(())
+    }
+
+    /// Close an auction early or after it ends; unsold supply is never
+    /// minted (mint authority only)
+    pub fn end_auction(ctx: Context<EndAuction>) -> Result<()> {
+        require!(
+            ctx.accounts.mint_authority.key() == ctx.accounts.token_info.mint_authority,
+            TokenError::UnauthorizedMintAuthority
+        );
+
+        let auction = &ctx.accounts.auction;
+        msg!("Auction #{} ended: {} of {} sold for {}",
+             auction.auction_id, auction.sold, auction.supply, auction.proceeds);
+        Ok(())
+    }
 }
 
-// Constants
-const ADMIN_AUTHORITY: Pubkey = anchor_lang::solana_program::pubkey!("11111111111111111111111111111111"); // Replace with actual admin
+/// Timelock PDA; parameter changes must be queued through vectai_timelock
+fn is_timelock(key: &Pubkey) -> bool {
+    *key == vectai_timelock::timelock_authority()
+}
+
+// Basis-point denominator (10000 = 100%)
+pub use vectai_common::BPS_DENOMINATOR;
 
 #[derive(Accounts)]
 #[instruction(max_supply: u64, decimals: u8)]
@@ -152,31 +1334,83 @@ pub struct InitializeToken<'info> {
 #[derive(Accounts)]
 pub struct MintTokens<'info> {
     #[account(mut)]
-    pub mint: Account<'info, Mint>,
+    pub mint: InterfaceAccount<'info, token_interface::Mint>,
     
     #[account(mut)]
-    pub to: Account<'info, TokenAccount>,
+    pub to: InterfaceAccount<'info, token_interface::TokenAccount>,
     
     #[account(
+        mut,
         seeds = [b"token-info", mint.key().as_ref()],
         bump
     )]
     pub token_info: Account<'info, TokenInfo>,
     
+    /// CHECK: PDA mint authority, used once an emission schedule exists
+    #[account(seeds = [b"mint-authority", mint.key().as_ref()], bump)]
+    pub mint_signer: UncheckedAccount<'info>,
+    
     pub mint_authority: Signer<'info>,
-    pub token_program: Program<'info, Token>,
+    pub token_program: Interface<'info, TokenInterface>,
+
+    /// Global emergency-pause registry
+    #[account(
+        seeds = [b"protocol-status"],
+        bump = protocol_status.bump,
+        seeds::program = vectai_protocol::ID,
+        constraint = !protocol_status.paused @ TokenError::ProtocolPaused
+    )]
+    pub protocol_status: Account<'info, ProtocolStatus>,
+
+    /// CHECK: Protocol stats PDA, validated by vectai_protocol
+    #[account(mut)]
+    pub protocol_stats: UncheckedAccount<'info>,
+
+    /// CHECK: Signs `record_activity` for this program
+    #[account(seeds = [b"stats-reporter"], bump)]
+    pub stats_reporter: UncheckedAccount<'info>,
+
+    pub protocol_program: Program<'info, VectaiProtocol>,
+
+    /// Needed when `mint_authority` mints as a minter
+    #[account(
+        seeds = [b"role-registry"],
+        bump = role_registry.bump,
+        seeds::program = vectai_protocol::ID
+    )]
+    pub role_registry: Option<Account<'info, RoleRegistry>>,
 }
 
 #[derive(Accounts)]
 pub struct TransferTokens<'info> {
     #[account(mut)]
-    pub from: Account<'info, TokenAccount>,
+    pub mint: InterfaceAccount<'info, token_interface::Mint>,
+
+    /// Burn rate and counter
+    #[account(
+        mut,
+        seeds = [b"token-info", mint.key().as_ref()],
+        bump
+    )]
+    pub token_info: Account<'info, TokenInfo>,
+    
+    #[account(mut)]
+    pub from: InterfaceAccount<'info, token_interface::TokenAccount>,
     
     #[account(mut)]
-    pub to: Account<'info, TokenAccount>,
+    pub to: InterfaceAccount<'info, token_interface::TokenAccount>,
     
     pub authority: Signer<'info>,
-    pub token_program: Program<'info, Token>,
+    pub token_program: Interface<'info, TokenInterface>,
+
+    /// Global emergency-pause registry
+    #[account(
+        seeds = [b"protocol-status"],
+        bump = protocol_status.bump,
+        seeds::program = vectai_protocol::ID,
+        constraint = !protocol_status.paused @ TokenError::ProtocolPaused
+    )]
+    pub protocol_status: Account<'info, ProtocolStatus>,
 }
 
 #[derive(Accounts)]
@@ -189,6 +1423,43 @@ pub struct PauseToken<'info> {
     pub token_info: Account<'info, TokenInfo>,
     
     pub admin: Signer<'info>,
+
+    /// Needed when `admin` pauses as a pauser
+    #[account(
+        seeds = [b"role-registry"],
+        bump = role_registry.bump,
+        seeds::program = vectai_protocol::ID
+    )]
+    pub role_registry: Option<Account<'info, RoleRegistry>>,
+}
+
+#[derive(Accounts)]
+pub struct MigrateTokenInfo<'info> {
+    /// CHECK: Seed only
+    pub mint: UncheckedAccount<'info>,
+
+    /// CHECK: Owner and discriminator are checked by
+    /// `vectai_common::migrate` before it resizes the account
+    #[account(
+        mut,
+        seeds = [b"token-info", mint.key().as_ref()],
+        bump
+    )]
+    pub token_info: UncheckedAccount<'info>,
+
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct GetTokenInfo<'info> {
+    #[account(
+        seeds = [b"token-info", token_info.mint.as_ref()],
+        bump
+    )]
+    pub token_info: Account<'info, TokenInfo>,
 }
 
 #[account]
@@ -199,6 +1470,11 @@ pub struct TokenInfo {
     pub minted: u64,
     pub decimals: u8,
     pub is_paused: bool,
+    pub compliance_authority: Pubkey, // Default when clawback mode is off
+    pub clawback_enabled: bool,
+    pub version: u8, // Versioned layout; fields are only appended after it
+    pub burn_rate_bps: u16, // v2: share of each transfer burned; 0 = off
+    pub burned: u64, // v2: total burned by transfers
 }
 
 impl TokenInfo {
@@ -208,7 +1484,25 @@ impl TokenInfo {
         8 +  // max_supply
         8 +  // minted
         1 +  // decimals
-        1;   // is_paused
+        1 +  // is_paused
+        32 + // compliance_authority
+        1 +  // clawback_enabled
+        1 +  // version
+        2 +  // burn_rate_bps
+        8;   // burned
+}
+
+impl Versioned for TokenInfo {
+    const VERSION: u8 = 2;
+    const SPACE: usize = TokenInfo::LEN;
+
+    fn version(&self) -> u8 {
+        self.version
+    }
+
+    fn set_version(&mut self, version: u8) {
+        self.version = version;
+    }
 }
 
 #[error_code]
@@ -229,4 +1523,46 @@ pub enum TokenError {
     InsufficientBalance,
     #[msg("Unauthorized admin")]
     UnauthorizedAdmin,
+    #[msg("No tokens staked in pool")]
+    NoStakers,
+    #[msg("Fee vault is empty")]
+    NothingToDistribute,
+    #[msg("No rewards to claim")]
+    NothingToClaim,
+    #[msg("Invalid emission schedule parameters")]
+    InvalidEmissionSchedule,
+    #[msg("Emission epoch has not elapsed yet")]
+    EpochNotReady,
+    #[msg("Clawback mode is not enabled for this token")]
+    ClawbackDisabled,
+    #[msg("Unauthorized compliance authority")]
+    UnauthorizedComplianceAuthority,
+    #[msg("Invalid compliance authority")]
+    InvalidComplianceAuthority,
+    #[msg("A non-zero reason code is required")]
+    MissingReasonCode,
+    #[msg("Interest rate out of range")]
+    InvalidInterestRate,
+    #[msg("Protocol is paused")]
+    ProtocolPaused,
+    #[msg("Invalid auction parameters")]
+    InvalidAuctionParams,
+    #[msg("Auction is not live")]
+    AuctionNotLive,
+    #[msg("Auction price above the buyer's limit")]
+    AuctionPriceAboveLimit,
+    #[msg("Auction supply exhausted")]
+    AuctionSoldOut,
+    #[msg("Stake position still holds tokens or unclaimed rewards")]
+    PositionNotEmpty,
+    #[msg("Burn rate above the maximum")]
+    InvalidBurnRate,
+    #[msg("Max supply must rise and stay within the hard ceiling")]
+    InvalidMaxSupply,
+    #[msg("Unknown lock tier")]
+    InvalidLockTier,
+    #[msg("Supply lock registry is full")]
+    TooManySupplyLocks,
+    #[msg("Lock accounts do not match the supply lock registry")]
+    SupplyLockMismatch,
 }
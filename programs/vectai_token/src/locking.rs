@@ -0,0 +1,285 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token::{Token, TokenAccount};
+
+use vectai_protocol::ProtocolStatus;
+
+use crate::{StakePool, TokenError, TokenInfo, BPS_DENOMINATOR};
+
+const DAY: i64 = 86_400;
+
+/// Lock durations and their terms, indexed by the `tier` instructions take
+pub const LOCK_TIERS: [LockTier; 4] = [
+    LockTier { duration: 30 * DAY, multiplier_bps: 11_000, penalty_bps: 2_500 },
+    LockTier { duration: 90 * DAY, multiplier_bps: 12_500, penalty_bps: 4_000 },
+    LockTier { duration: 180 * DAY, multiplier_bps: 15_000, penalty_bps: 5_000 },
+    LockTier { duration: 365 * DAY, multiplier_bps: 20_000, penalty_bps: 6_000 },
+];
+
+/// Terms of one lock duration. Early exit forfeits `penalty_bps` of the
+/// rewards the lock earned, more than its boost is worth, so unlocking
+/// early never beats flexible staking.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct LockTier {
+    pub duration: i64, // Seconds
+    pub multiplier_bps: u16, // Reward weight per staked token; 10000 = flexible stake
+    pub penalty_bps: u16, // Share of vested rewards forfeited by an early unlock
+}
+
+impl LockTier {
+    pub fn get(tier: u8) -> Result<LockTier> {
+        LOCK_TIERS.get(tier as usize).copied().ok_or(error!(TokenError::InvalidLockTier))
+    }
+
+    /// Reward weight `amount` locked in this tier adds to the pool
+    pub fn weight(&self, amount: u64) -> Result<u64> {
+        let weight = (amount as u128)
+            .checked_mul(self.multiplier_bps as u128)
+            .ok_or(TokenError::MathOverflow)?
+            / BPS_DENOMINATOR as u128;
+        u64::try_from(weight).map_err(|_| error!(TokenError::MathOverflow))
+    }
+
+    /// Part of `rewards` an unlock before the lock ends forfeits
+    pub fn penalty(&self, rewards: u64) -> u64 {
+        (rewards as u128 * self.penalty_bps as u128 / BPS_DENOMINATOR as u128) as u64
+    }
+}
+
+#[derive(Accounts)]
+pub struct InitializeLockTiers<'info> {
+    #[account(
+        seeds = [b"stake-pool", stake_pool.mint.as_ref()],
+        bump = stake_pool.bump
+    )]
+    pub stake_pool: Account<'info, StakePool>,
+
+    #[account(
+        init,
+        payer = admin,
+        space = LockTiers::LEN,
+        seeds = [b"lock-tiers", stake_pool.key().as_ref()],
+        bump
+    )]
+    pub lock_tiers: Account<'info, LockTiers>,
+
+    #[account(mut)]
+    pub admin: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+#[instruction(tier: u8)]
+pub struct LockStake<'info> {
+    #[account(
+        seeds = [b"token-info", stake_pool.mint.as_ref()],
+        bump
+    )]
+    pub token_info: Account<'info, TokenInfo>,
+
+    #[account(
+        mut,
+        seeds = [b"stake-pool", stake_pool.mint.as_ref()],
+        bump = stake_pool.bump
+    )]
+    pub stake_pool: Account<'info, StakePool>,
+
+    #[account(
+        mut,
+        seeds = [b"lock-tiers", stake_pool.key().as_ref()],
+        bump = lock_tiers.bump
+    )]
+    pub lock_tiers: Account<'info, LockTiers>,
+
+    #[account(
+        init_if_needed,
+        payer = owner,
+        space = LockedStake::LEN,
+        seeds = [b"locked-stake", stake_pool.key().as_ref(), owner.key().as_ref(), &[tier]],
+        bump
+    )]
+    pub position: Account<'info, LockedStake>,
+
+    #[account(
+        mut,
+        constraint = owner_token_account.mint == stake_pool.mint @ TokenError::InvalidTokenAccount,
+        constraint = owner_token_account.owner == owner.key() @ TokenError::InvalidTokenAccount
+    )]
+    pub owner_token_account: Account<'info, TokenAccount>,
+
+    #[account(mut, address = stake_pool.stake_vault)]
+    pub stake_vault: Account<'info, TokenAccount>,
+
+    #[account(mut)]
+    pub owner: Signer<'info>,
+
+    pub token_program: Program<'info, Token>,
+    pub system_program: Program<'info, System>,
+
+    /// Global emergency-pause registry
+    #[account(
+        seeds = [b"protocol-status"],
+        bump = protocol_status.bump,
+        seeds::program = vectai_protocol::ID,
+        constraint = !protocol_status.paused @ TokenError::ProtocolPaused
+    )]
+    pub protocol_status: Account<'info, ProtocolStatus>,
+}
+
+#[derive(Accounts)]
+pub struct UnlockStake<'info> {
+    #[account(
+        mut,
+        seeds = [b"stake-pool", stake_pool.mint.as_ref()],
+        bump = stake_pool.bump
+    )]
+    pub stake_pool: Account<'info, StakePool>,
+
+    #[account(
+        mut,
+        seeds = [b"lock-tiers", stake_pool.key().as_ref()],
+        bump = lock_tiers.bump
+    )]
+    pub lock_tiers: Account<'info, LockTiers>,
+
+    #[account(
+        mut,
+        close = owner,
+        seeds = [b"locked-stake", stake_pool.key().as_ref(), owner.key().as_ref(), &[position.tier]],
+        bump = position.bump,
+        has_one = owner
+    )]
+    pub position: Account<'info, LockedStake>,
+
+    #[account(
+        mut,
+        constraint = owner_token_account.mint == stake_pool.mint @ TokenError::InvalidTokenAccount
+    )]
+    pub owner_token_account: Account<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        constraint = owner_reward_account.mint == stake_pool.reward_mint @ TokenError::InvalidTokenAccount
+    )]
+    pub owner_reward_account: Account<'info, TokenAccount>,
+
+    #[account(mut, address = stake_pool.stake_vault)]
+    pub stake_vault: Account<'info, TokenAccount>,
+
+    #[account(mut, address = stake_pool.reward_vault)]
+    pub reward_vault: Account<'info, TokenAccount>,
+
+    /// Receives forfeited rewards for the next `distribute_rewards`
+    #[account(mut, address = stake_pool.fee_vault)]
+    pub fee_vault: Account<'info, TokenAccount>,
+
+    #[account(mut)]
+    pub owner: Signer<'info>,
+
+    pub token_program: Program<'info, Token>,
+
+    /// Global emergency-pause registry
+    #[account(
+        seeds = [b"protocol-status"],
+        bump = protocol_status.bump,
+        seeds::program = vectai_protocol::ID,
+        constraint = !protocol_status.paused @ TokenError::ProtocolPaused
+    )]
+    pub protocol_status: Account<'info, ProtocolStatus>,
+}
+
+/// Running totals of one lock tier
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct TierTotals {
+    pub locked: u64, // VECTAI held by the tier's positions
+    pub weight: u64, // Their share of the pool's `total_staked`
+    pub positions: u32,
+    pub penalties: u64, // Reward tokens forfeited by early unlocks
+}
+
+impl TierTotals {
+    pub const LEN: usize = 8 + // locked
+        8 + // weight
+        4 + // positions
+        8; // penalties
+}
+
+/// Per-tier accounting of a pool's locked stake
+#[account]
+pub struct LockTiers {
+    pub pool: Pubkey,
+    pub tiers: [TierTotals; 4],
+    pub bump: u8,
+}
+
+impl LockTiers {
+    pub const LEN: usize = 8 + // discriminator
+        32 + // pool
+        LOCK_TIERS.len() * TierTotals::LEN + // tiers
+        1; // bump
+}
+
+/// An owner's stake in one lock tier. It earns from the pool's
+/// accumulator at its tier's multiplier; rewards vest until `unlock_at`.
+/// Adding to a lock restarts its full duration, and a lock past its end
+/// keeps its weight until unlocked.
+#[account]
+pub struct LockedStake {
+    pub owner: Pubkey,
+    pub pool: Pubkey,
+    pub tier: u8,
+    pub amount: u64,
+    pub weight: u64,
+    pub unlock_at: i64,
+    pub reward_debt: u128,
+    pub pending_rewards: u64,
+    pub bump: u8,
+}
+
+impl LockedStake {
+    pub const LEN: usize = 8 + // discriminator
+        32 + // owner
+        32 + // pool
+        1 +  // tier
+        8 +  // amount
+        8 +  // weight
+        8 +  // unlock_at
+        16 + // reward_debt
+        8 +  // pending_rewards
+        1;   // bump
+
+    /// Move rewards earned since the last checkpoint into `pending_rewards`
+    pub fn settle(&mut self, acc_reward_per_share: u128) -> Result<()> {
+        vectai_common::settle_rewards(self.weight, acc_reward_per_share, self.reward_debt, &mut self.pending_rewards)
+    }
+
+    /// Reset the checkpoint after `weight` changes
+    pub fn checkpoint(&mut self, acc_reward_per_share: u128) -> Result<()> {
+        self.reward_debt = vectai_common::reward_debt(self.weight, acc_reward_per_share)?;
+        Ok(())
+    }
+}
+
+/// Address of `owner`'s `LockedStake` in `tier` of `pool`
+pub fn locked_stake(pool: &Pubkey, owner: &Pubkey, tier: u8) -> Pubkey {
+    Pubkey::find_program_address(&[b"locked-stake", pool.as_ref(), owner.as_ref(), &[tier]], &crate::ID).0
+}
+
+#[event]
+pub struct StakeLocked {
+    pub pool: Pubkey,
+    pub owner: Pubkey,
+    pub tier: u8,
+    pub amount: u64,
+    pub unlock_at: i64,
+}
+
+#[event]
+pub struct StakeUnlocked {
+    pub pool: Pubkey,
+    pub owner: Pubkey,
+    pub tier: u8,
+    pub amount: u64,
+    pub rewards: u64, // Paid to the owner
+    pub penalty: u64, // Forfeited to the fee vault
+}
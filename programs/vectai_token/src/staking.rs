@@ -0,0 +1,316 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token::{Mint, Token, TokenAccount};
+
+use vectai_protocol::ProtocolStatus;
+
+use crate::{TokenError, TokenInfo};
+
+// Fixed-point scale for `acc_reward_per_share`
+pub use vectai_common::REWARD_PRECISION;
+
+#[derive(Accounts)]
+pub struct InitializeStakePool<'info> {
+    #[account(
+        seeds = [b"token-info", mint.key().as_ref()],
+        bump
+    )]
+    pub token_info: Account<'info, TokenInfo>,
+
+    /// VECTAI mint (staked asset)
+    pub mint: Account<'info, Mint>,
+
+    /// Mint protocol fees are collected and paid out in (e.g. USDC)
+    pub reward_mint: Account<'info, Mint>,
+
+    #[account(
+        init,
+        payer = admin,
+        space = StakePool::LEN,
+        seeds = [b"stake-pool", mint.key().as_ref()],
+        bump
+    )]
+    pub stake_pool: Account<'info, StakePool>,
+
+    #[account(
+        init,
+        payer = admin,
+        token::mint = mint,
+        token::authority = stake_pool,
+        seeds = [b"stake-vault", stake_pool.key().as_ref()],
+        bump
+    )]
+    pub stake_vault: Account<'info, TokenAccount>,
+
+    #[account(
+        init,
+        payer = admin,
+        token::mint = reward_mint,
+        token::authority = stake_pool,
+        seeds = [b"reward-vault", stake_pool.key().as_ref()],
+        bump
+    )]
+    pub reward_vault: Account<'info, TokenAccount>,
+
+    /// Rewards awaiting distribution: treasury spends of the reward mint
+    /// and early-unlock penalties. Trade and swap fees land in the
+    /// treasury's per-mint vaults, not here.
+    #[account(
+        init,
+        payer = admin,
+        token::mint = reward_mint,
+        token::authority = stake_pool,
+        seeds = [b"fee-vault", stake_pool.key().as_ref()],
+        bump
+    )]
+    pub fee_vault: Account<'info, TokenAccount>,
+
+    #[account(mut)]
+    pub admin: Signer<'info>,
+
+    pub rent: Sysvar<'info, Rent>,
+    pub token_program: Program<'info, Token>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct Stake<'info> {
+    #[account(
+        seeds = [b"token-info", stake_pool.mint.as_ref()],
+        bump
+    )]
+    pub token_info: Account<'info, TokenInfo>,
+
+    #[account(
+        mut,
+        seeds = [b"stake-pool", stake_pool.mint.as_ref()],
+        bump = stake_pool.bump
+    )]
+    pub stake_pool: Account<'info, StakePool>,
+
+    #[account(
+        init_if_needed,
+        payer = owner,
+        space = StakePosition::LEN,
+        seeds = [b"stake", stake_pool.key().as_ref(), owner.key().as_ref()],
+        bump
+    )]
+    pub position: Account<'info, StakePosition>,
+
+    #[account(
+        mut,
+        constraint = owner_token_account.mint == stake_pool.mint @ TokenError::InvalidTokenAccount,
+        constraint = owner_token_account.owner == owner.key() @ TokenError::InvalidTokenAccount
+    )]
+    pub owner_token_account: Account<'info, TokenAccount>,
+
+    #[account(mut, address = stake_pool.stake_vault)]
+    pub stake_vault: Account<'info, TokenAccount>,
+
+    #[account(mut)]
+    pub owner: Signer<'info>,
+
+    pub token_program: Program<'info, Token>,
+    pub system_program: Program<'info, System>,
+
+    /// Global emergency-pause registry
+    #[account(
+        seeds = [b"protocol-status"],
+        bump = protocol_status.bump,
+        seeds::program = vectai_protocol::ID,
+        constraint = !protocol_status.paused @ TokenError::ProtocolPaused
+    )]
+    pub protocol_status: Account<'info, ProtocolStatus>,
+}
+
+#[derive(Accounts)]
+pub struct Unstake<'info> {
+    #[account(
+        mut,
+        seeds = [b"stake-pool", stake_pool.mint.as_ref()],
+        bump = stake_pool.bump
+    )]
+    pub stake_pool: Account<'info, StakePool>,
+
+    #[account(
+        mut,
+        seeds = [b"stake", stake_pool.key().as_ref(), owner.key().as_ref()],
+        bump = position.bump,
+        has_one = owner
+    )]
+    pub position: Account<'info, StakePosition>,
+
+    #[account(
+        mut,
+        constraint = owner_token_account.mint == stake_pool.mint @ TokenError::InvalidTokenAccount
+    )]
+    pub owner_token_account: Account<'info, TokenAccount>,
+
+    #[account(mut, address = stake_pool.stake_vault)]
+    pub stake_vault: Account<'info, TokenAccount>,
+
+    pub owner: Signer<'info>,
+    pub token_program: Program<'info, Token>,
+
+    /// Global emergency-pause registry
+    #[account(
+        seeds = [b"protocol-status"],
+        bump = protocol_status.bump,
+        seeds::program = vectai_protocol::ID,
+        constraint = !protocol_status.paused @ TokenError::ProtocolPaused
+    )]
+    pub protocol_status: Account<'info, ProtocolStatus>,
+}
+
+#[derive(Accounts)]
+pub struct ClaimRewards<'info> {
+    #[account(
+        seeds = [b"stake-pool", stake_pool.mint.as_ref()],
+        bump = stake_pool.bump
+    )]
+    pub stake_pool: Account<'info, StakePool>,
+
+    #[account(
+        mut,
+        seeds = [b"stake", stake_pool.key().as_ref(), owner.key().as_ref()],
+        bump = position.bump,
+        has_one = owner
+    )]
+    pub position: Account<'info, StakePosition>,
+
+    #[account(
+        mut,
+        constraint = owner_reward_account.mint == stake_pool.reward_mint @ TokenError::InvalidTokenAccount
+    )]
+    pub owner_reward_account: Account<'info, TokenAccount>,
+
+    #[account(mut, address = stake_pool.reward_vault)]
+    pub reward_vault: Account<'info, TokenAccount>,
+
+    pub owner: Signer<'info>,
+    pub token_program: Program<'info, Token>,
+
+    /// Global emergency-pause registry
+    #[account(
+        seeds = [b"protocol-status"],
+        bump = protocol_status.bump,
+        seeds::program = vectai_protocol::ID,
+        constraint = !protocol_status.paused @ TokenError::ProtocolPaused
+    )]
+    pub protocol_status: Account<'info, ProtocolStatus>,
+}
+
+#[derive(Accounts)]
+pub struct CloseStakePosition<'info> {
+    #[account(
+        mut,
+        close = owner,
+        seeds = [b"stake", position.pool.as_ref(), owner.key().as_ref()],
+        bump = position.bump,
+        has_one = owner,
+        constraint = position.amount == 0 && position.pending_rewards == 0 @ TokenError::PositionNotEmpty
+    )]
+    pub position: Account<'info, StakePosition>,
+
+    #[account(mut)]
+    pub owner: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct DistributeRewards<'info> {
+    #[account(
+        mut,
+        seeds = [b"stake-pool", stake_pool.mint.as_ref()],
+        bump = stake_pool.bump
+    )]
+    pub stake_pool: Account<'info, StakePool>,
+
+    #[account(mut, address = stake_pool.fee_vault)]
+    pub fee_vault: Account<'info, TokenAccount>,
+
+    #[account(mut, address = stake_pool.reward_vault)]
+    pub reward_vault: Account<'info, TokenAccount>,
+
+    pub token_program: Program<'info, Token>,
+
+    /// Global emergency-pause registry
+    #[account(
+        seeds = [b"protocol-status"],
+        bump = protocol_status.bump,
+        seeds::program = vectai_protocol::ID,
+        constraint = !protocol_status.paused @ TokenError::ProtocolPaused
+    )]
+    pub protocol_status: Account<'info, ProtocolStatus>,
+}
+
+/// Staking pool for a VECTAI mint; also the PDA authority over its vaults
+#[account]
+pub struct StakePool {
+    pub mint: Pubkey,
+    pub reward_mint: Pubkey,
+    pub stake_vault: Pubkey,
+    pub reward_vault: Pubkey,
+    pub fee_vault: Pubkey,
+    pub total_staked: u64, // Reward weight: flexible stake plus locked stake at its tier multiplier
+    pub acc_reward_per_share: u128, // Scaled by REWARD_PRECISION
+    pub total_distributed: u64,
+    pub bump: u8,
+}
+
+impl StakePool {
+    pub const LEN: usize = 8 + // discriminator
+        32 + // mint
+        32 + // reward_mint
+        32 + // stake_vault
+        32 + // reward_vault
+        32 + // fee_vault
+        8 +  // total_staked
+        16 + // acc_reward_per_share
+        8 +  // total_distributed
+        1;   // bump
+}
+
+/// A single owner's stake in a pool
+#[account]
+pub struct StakePosition {
+    pub owner: Pubkey,
+    pub pool: Pubkey,
+    pub amount: u64,
+    pub reward_debt: u128,
+    pub pending_rewards: u64,
+    pub bump: u8,
+}
+
+impl StakePosition {
+    pub const LEN: usize = 8 + // discriminator
+        32 + // owner
+        32 + // pool
+        8 +  // amount
+        16 + // reward_debt
+        8 +  // pending_rewards
+        1;   // bump
+
+    /// Move rewards earned since the last checkpoint into `pending_rewards`
+    pub fn settle(&mut self, acc_reward_per_share: u128) -> Result<()> {
+        vectai_common::settle_rewards(self.amount, acc_reward_per_share, self.reward_debt, &mut self.pending_rewards)
+    }
+
+    /// Reset the checkpoint after `amount` changes
+    pub fn checkpoint(&mut self, acc_reward_per_share: u128) -> Result<()> {
+        self.reward_debt = vectai_common::reward_debt(self.amount, acc_reward_per_share)?;
+        Ok(())
+    }
+}
+
+/// Per-share increase from distributing `amount` across `total_staked`
+pub fn reward_per_share_delta(amount: u64, total_staked: u64) -> Result<u128> {
+    require!(total_staked > 0, TokenError::NoStakers);
+    vectai_common::reward_per_share_delta(amount as u128, total_staked)
+}
+
+#[event]
+pub struct RewardsDistributed {
+    pub pool: Pubkey,
+    pub amount: u64,
+    pub total_staked: u64,
+    pub acc_reward_per_share: u128,
+}
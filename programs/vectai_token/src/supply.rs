@@ -0,0 +1,26 @@
+use anchor_lang::prelude::*;
+
+use crate::TokenInfo;
+
+/// Hard ceiling on any `max_supply`, at initialization or raised later:
+/// 1B tokens at 9 decimals
+pub const MAX_SUPPLY_CEILING: u64 = 1_000_000_000_000_000_000;
+
+#[derive(Accounts)]
+pub struct RaiseMaxSupply<'info> {
+    #[account(
+        mut,
+        seeds = [b"token-info", token_info.mint.as_ref()],
+        bump
+    )]
+    pub token_info: Account<'info, TokenInfo>,
+
+    pub admin: Signer<'info>,
+}
+
+#[event]
+pub struct MaxSupplyRaised {
+    pub mint: Pubkey,
+    pub old_max_supply: u64,
+    pub new_max_supply: u64,
+}
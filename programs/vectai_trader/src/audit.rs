@@ -0,0 +1,116 @@
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::hash::hashv;
+
+use crate::{DynamicSlippage, TakeProfitLevel, TraderConfig, TraderError, TradingSchedule};
+
+#[derive(Accounts)]
+pub struct InitializeAuditLog<'info> {
+    #[account(
+        seeds = [b"trader", authority.key().as_ref(), trader_config.listing_seed()],
+        bump,
+        has_one = authority @ TraderError::Unauthorized
+    )]
+    pub trader_config: Account<'info, TraderConfig>,
+
+    #[account(
+        init,
+        payer = authority,
+        space = AuditLog::LEN,
+        seeds = [b"audit-log", trader_config.key().as_ref()],
+        bump
+    )]
+    pub audit_log: Account<'info, AuditLog>,
+
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+/// Append-only log of privileged actions on a strategy. Each entry moves
+/// `head` to `hash(head || entry)`, so the `AuditEntryAppended` events
+/// replayed off-chain must reproduce `head` exactly: a missing, reordered
+/// or altered entry breaks the chain.
+#[account]
+pub struct AuditLog {
+    pub trader: Pubkey, // TraderConfig
+    pub head: [u8; 32], // Hash of the latest entry; zero before the first
+    pub entry_count: u64,
+    pub bump: u8,
+}
+
+impl AuditLog {
+    pub const LEN: usize = 8 + // discriminator
+        32 + // trader
+        32 + // head
+        8 +  // entry_count
+        1;   // bump
+
+    /// Chain `action` by `actor` onto the log and emit the entry
+    pub fn append(&mut self, actor: Pubkey, action: AuditAction, now: i64) -> Result<()> {
+        let entry = AuditEntry {
+            sequence: self.entry_count,
+            actor,
+            timestamp: now,
+            action,
+        };
+        let prev_hash = self.head;
+        self.head = entry.chain(&prev_hash)?;
+        self.entry_count = self
+            .entry_count
+            .checked_add(1)
+            .ok_or(TraderError::MathOverflow)?;
+
+        emit!(AuditEntryAppended {
+            trader: self.trader,
+            entry,
+            prev_hash,
+            hash: self.head,
+        });
+        Ok(())
+    }
+}
+
+/// A privileged change to a strategy
+#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+pub enum AuditAction {
+    ConditionSet { program: Vec<u8> },
+    AutoCompoundSet { enabled: bool },
+    MaxDrawdownSet { max_drawdown_bps: u16 },
+    DrawdownReset,
+    Paused,
+    Unpaused,
+    TradingScheduleSet { schedule: TradingSchedule },
+    DynamicSlippageSet { dynamic_slippage: DynamicSlippage },
+    VolatilityFilterSet { max_volatility_bps: u16 },
+    TakeProfitSet { levels: Vec<TakeProfitLevel> },
+    DailyTradeCapSet { max_trades: u16 },
+    JitoTipCapSet { max_tip_lamports: u64 },
+    ProfitTargetSet { profit_target_usd: u64 },
+    SlotCooldownSet { cooldown_slots: u64 },
+    MaxPriceAgeSlotsSet { max_price_age_slots: u64 },
+    PriceAggregatorSet { price_aggregator: Pubkey, max_source_deviation_bps: u16 },
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+pub struct AuditEntry {
+    pub sequence: u64, // 0-based position in the log
+    pub actor: Pubkey, // Signer of the action
+    pub timestamp: i64,
+    pub action: AuditAction,
+}
+
+impl AuditEntry {
+    /// `hash(prev_hash || borsh(entry))`
+    pub fn chain(&self, prev_hash: &[u8; 32]) -> Result<[u8; 32]> {
+        Ok(hashv(&[prev_hash, &self.try_to_vec()?]).to_bytes())
+    }
+}
+
+#[event]
+pub struct AuditEntryAppended {
+    pub trader: Pubkey, // TraderConfig
+    pub entry: AuditEntry,
+    pub prev_hash: [u8; 32],
+    pub hash: [u8; 32],
+}
@@ -0,0 +1,28 @@
+use anchor_lang::prelude::*;
+
+use crate::TraderConfig;
+
+/// Seconds between executions of a strategy without a slot cooldown
+pub const COOLDOWN_SECS: i64 = 60;
+
+/// Longest slot cooldown a strategy may set, about a day of 400ms slots
+pub const MAX_COOLDOWN_SLOTS: u64 = 216_000;
+
+impl TraderConfig {
+    /// Whether the strategy may execute again at `clock`: `cooldown_slots`
+    /// slots after its last execution when set, which leaders cannot skew
+    /// the way they can `unix_timestamp`, otherwise `COOLDOWN_SECS`
+    pub fn cooldown_elapsed(&self, clock: &Clock) -> bool {
+        if self.cooldown_slots > 0 {
+            clock.slot.saturating_sub(self.last_swap_slot) >= self.cooldown_slots
+        } else {
+            clock.unix_timestamp - self.last_swap_time >= COOLDOWN_SECS
+        }
+    }
+
+    /// Start the cooldown from an execution at `clock`
+    pub fn record_execution(&mut self, clock: &Clock) {
+        self.last_swap_time = clock.unix_timestamp;
+        self.last_swap_slot = clock.slot;
+    }
+}
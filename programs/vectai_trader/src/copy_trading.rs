@@ -0,0 +1,288 @@
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::sysvar::instructions::{
+    self as instructions_sysvar, load_current_index_checked, load_instruction_at_checked,
+};
+use anchor_lang::Discriminator;
+use anchor_spl::token::{Token, TokenAccount};
+
+use raydium_swapper::pool_config;
+use vectai_protocol::{program::VectaiProtocol, NetworkConfig, ProtocolStatus};
+
+use crate::vectai_oracle::program::VectaiOracle;
+use crate::{TraderConfig, TraderError};
+
+/// Upper bound for the fee share a follower can grant the lead (20%)
+pub const MAX_LEAD_FEE_BPS: u16 = 2_000;
+
+#[derive(Accounts)]
+pub struct FollowTrader<'info> {
+    #[account(
+        seeds = [b"trader", lead_config.authority.as_ref(), lead_config.listing_seed()],
+        bump
+    )]
+    pub lead_config: Account<'info, TraderConfig>,
+
+    #[account(
+        init,
+        payer = follower,
+        space = FollowerConfig::LEN,
+        seeds = [b"follower", follower.key().as_ref(), lead_config.key().as_ref()],
+        bump
+    )]
+    pub follower_config: Account<'info, FollowerConfig>,
+
+    #[account(mut)]
+    pub follower: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct UnfollowTrader<'info> {
+    #[account(
+        mut,
+        close = follower,
+        seeds = [b"follower", follower.key().as_ref(), follower_config.lead.as_ref()],
+        bump = follower_config.bump
+    )]
+    pub follower_config: Account<'info, FollowerConfig>,
+
+    #[account(mut)]
+    pub follower: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct CopyTrade<'info> {
+    /// Keeper submitting the follower trade alongside the lead trade
+    pub keeper: Signer<'info>,
+
+    #[account(
+        seeds = [b"trader", lead_config.authority.as_ref(), lead_config.listing_seed()],
+        bump
+    )]
+    pub lead_config: Account<'info, TraderConfig>,
+
+    /// Follower config; approved as SPL delegate on the follower source account
+    #[account(
+        mut,
+        seeds = [b"follower", follower_config.follower.as_ref(), lead_config.key().as_ref()],
+        bump = follower_config.bump
+    )]
+    pub follower_config: Account<'info, FollowerConfig>,
+
+    #[account(
+        mut,
+        constraint = follower_source_token_account.owner == follower_config.follower @ TraderError::InvalidTokenAccount
+    )]
+    pub follower_source_token_account: Account<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        constraint = follower_destination_token_account.owner == follower_config.follower @ TraderError::InvalidTokenAccount
+    )]
+    pub follower_destination_token_account: Account<'info, TokenAccount>,
+
+    /// Lead's account for the fee share (source mint); unused when lead_fee_bps is 0
+    #[account(
+        mut,
+        constraint = lead_fee_account.owner == lead_config.authority @ TraderError::InvalidTokenAccount,
+        constraint = lead_fee_account.mint == follower_source_token_account.mint @ TraderError::InvalidTokenAccount
+    )]
+    pub lead_fee_account: Account<'info, TokenAccount>,
+
+    // ===== RAYDIUM AMM ACCOUNTS =====
+    // Pinned to raydium_swapper's whitelisted pool, like its own swap
+
+    /// CHECK: Raydium AMM program
+    pub raydium_amm_program: UncheckedAccount<'info>,
+
+    /// CHECK: AMM pool account
+    #[account(mut, address = pool_config::AMM_ID @ TraderError::InvalidPool)]
+    pub amm: UncheckedAccount<'info>,
+
+    /// CHECK: AMM authority
+    #[account(address = pool_config::AMM_AUTHORITY @ TraderError::InvalidPool)]
+    pub amm_authority: UncheckedAccount<'info>,
+
+    /// CHECK: AMM open orders
+    #[account(mut, address = pool_config::AMM_OPEN_ORDERS @ TraderError::InvalidPool)]
+    #[cfg(not(feature = "no-serum"))]
+    pub amm_open_orders: UncheckedAccount<'info>,
+
+    /// CHECK: AMM target orders
+    #[account(mut, address = pool_config::AMM_TARGET_ORDERS @ TraderError::InvalidPool)]
+    #[cfg(not(feature = "no-serum"))]
+    pub amm_target_orders: UncheckedAccount<'info>,
+
+    /// Pool coin token account
+    #[account(mut, address = pool_config::POOL_COIN_TOKEN_ACCOUNT @ TraderError::InvalidPool)]
+    pub pool_coin_token_account: Account<'info, TokenAccount>,
+
+    /// Pool pc token account
+    #[account(mut, address = pool_config::POOL_PC_TOKEN_ACCOUNT @ TraderError::InvalidPool)]
+    pub pool_pc_token_account: Account<'info, TokenAccount>,
+
+    // ===== SERUM MARKET ACCOUNTS =====
+
+    /// CHECK: Serum program
+    #[account(address = pool_config::SERUM_PROGRAM @ TraderError::InvalidPool)]
+    #[cfg(not(feature = "no-serum"))]
+    pub serum_program: UncheckedAccount<'info>,
+
+    /// CHECK: Serum market
+    #[account(mut, address = pool_config::SERUM_MARKET @ TraderError::InvalidPool)]
+    #[cfg(not(feature = "no-serum"))]
+    pub serum_market: UncheckedAccount<'info>,
+
+    /// CHECK: Serum bids
+    #[account(mut, address = pool_config::SERUM_BIDS @ TraderError::InvalidPool)]
+    #[cfg(not(feature = "no-serum"))]
+    pub serum_bids: UncheckedAccount<'info>,
+
+    /// CHECK: Serum asks
+    #[account(mut, address = pool_config::SERUM_ASKS @ TraderError::InvalidPool)]
+    #[cfg(not(feature = "no-serum"))]
+    pub serum_asks: UncheckedAccount<'info>,
+
+    /// CHECK: Serum event queue
+    #[account(mut, address = pool_config::SERUM_EVENT_QUEUE @ TraderError::InvalidPool)]
+    #[cfg(not(feature = "no-serum"))]
+    pub serum_event_queue: UncheckedAccount<'info>,
+
+    /// CHECK: Serum coin vault
+    #[account(mut, address = pool_config::SERUM_COIN_VAULT @ TraderError::InvalidPool)]
+    #[cfg(not(feature = "no-serum"))]
+    pub serum_coin_vault_account: UncheckedAccount<'info>,
+
+    /// CHECK: Serum pc vault
+    #[account(mut, address = pool_config::SERUM_PC_VAULT @ TraderError::InvalidPool)]
+    #[cfg(not(feature = "no-serum"))]
+    pub serum_pc_vault_account: UncheckedAccount<'info>,
+
+    /// CHECK: Serum vault signer
+    #[account(address = pool_config::SERUM_VAULT_SIGNER @ TraderError::InvalidPool)]
+    #[cfg(not(feature = "no-serum"))]
+    pub serum_vault_signer: UncheckedAccount<'info>,
+
+    // ===== ORACLE =====
+
+    /// The Oracle Program (VECT.AI Oracle)
+    pub vectai_oracle_program: Program<'info, VectaiOracle>,
+
+    /// The lead trade's price feed or aggregator, which the follower's
+    /// minimum output is priced from
+    /// CHECK: Matched against the lead trade; vectai_oracle validates it
+    pub price_feed: UncheckedAccount<'info>,
+
+    /// CHECK: Instructions sysvar, used to find the lead trade
+    #[account(address = instructions_sysvar::ID)]
+    pub instructions: UncheckedAccount<'info>,
+
+    /// Treasury vault for the source mint; required while the trade fee is on
+    #[account(
+        mut,
+        constraint = protocol_fee_account.key() == vectai_treasury::vault_address(&follower_source_token_account.mint) @ TraderError::InvalidTokenAccount
+    )]
+    pub protocol_fee_account: Option<Account<'info, TokenAccount>>,
+
+    /// Solana token program
+    pub token_program: Program<'info, Token>,
+
+    /// Global emergency-pause registry
+    #[account(
+        seeds = [b"protocol-status"],
+        bump = protocol_status.bump,
+        seeds::program = vectai_protocol::ID,
+        constraint = !protocol_status.paused @ TraderError::ProtocolPaused
+    )]
+    pub protocol_status: Account<'info, ProtocolStatus>,
+
+    /// Shared network addresses
+    #[account(
+        seeds = [b"network-config"],
+        bump = network_config.bump,
+        seeds::program = vectai_protocol::ID
+    )]
+    pub network_config: Account<'info, NetworkConfig>,
+
+    /// CHECK: Protocol stats PDA, validated by vectai_protocol
+    #[account(mut)]
+    pub protocol_stats: UncheckedAccount<'info>,
+
+    /// CHECK: Signs `record_activity` for this program
+    #[account(seeds = [b"stats-reporter"], bump)]
+    pub stats_reporter: UncheckedAccount<'info>,
+
+    pub protocol_program: Program<'info, VectaiProtocol>,
+}
+
+/// Mirrors a lead `TraderConfig` at `ratio_bps` of each lead trade
+#[account]
+pub struct FollowerConfig {
+    pub follower: Pubkey,
+    pub lead: Pubkey, // Lead TraderConfig
+    pub ratio_bps: u16, // Follower amount = lead amount * ratio_bps / 10000
+    pub max_trade_amount: u64,
+    pub slippage_tolerance: u64, // Basis points
+    pub lead_fee_bps: u16, // Share of each copied trade paid to the lead
+    pub last_copied_swap: u64, // Lead total_swaps at the last copy
+    pub total_copied: u64,
+    pub bump: u8,
+}
+
+impl FollowerConfig {
+    pub const LEN: usize = 8 + // discriminator
+        32 + // follower
+        32 + // lead
+        2 +  // ratio_bps
+        8 +  // max_trade_amount
+        8 +  // slippage_tolerance
+        2 +  // lead_fee_bps
+        8 +  // last_copied_swap
+        8 +  // total_copied
+        1;   // bump
+}
+
+/// Position of `price_feed` among `ExecuteTrade`'s accounts
+const EXECUTE_TRADE_PRICE_FEED: usize = if cfg!(feature = "no-serum") { 10 } else { 20 };
+
+/// The lead's `execute_trade` a follower trade copies
+pub struct LeadTrade {
+    pub amount: u64,
+    pub price_feed: Pubkey, // Oracle feed or aggregator the lead priced from
+}
+
+/// The lead's `execute_trade` earlier in the current transaction; dry runs
+/// are skipped
+pub fn find_lead_trade(instructions: &AccountInfo, lead_config: &Pubkey) -> Result<LeadTrade> {
+    let current = load_current_index_checked(instructions)? as usize;
+    for index in 0..current {
+        let ix = load_instruction_at_checked(index, instructions)?;
+        if ix.program_id == crate::ID
+            && ix.data.len() >= 17
+            && ix.data[..8] == crate::instruction::ExecuteTrade::DISCRIMINATOR
+            && ix.data[16] == 0 // dry_run
+            && ix.accounts.get(1).map(|meta| meta.pubkey) == Some(*lead_config)
+        {
+            let amount = u64::from_le_bytes(
+                ix.data[8..16].try_into().map_err(|_| TraderError::InvalidInput)?,
+            );
+            let price_feed = ix
+                .accounts
+                .get(EXECUTE_TRADE_PRICE_FEED)
+                .ok_or(TraderError::LeadTradeNotFound)?
+                .pubkey;
+            return Ok(LeadTrade { amount, price_feed });
+        }
+    }
+    err!(TraderError::LeadTradeNotFound)
+}
+
+#[event]
+pub struct TradeCopied {
+    pub lead: Pubkey,
+    pub follower: Pubkey,
+    pub lead_amount: u64,
+    pub amount: u64,
+    pub lead_fee: u64,
+}
@@ -0,0 +1,655 @@
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::{hash::hash, instruction::Instruction, program::invoke_signed};
+use anchor_spl::token::{Token, TokenAccount};
+
+use vectai_protocol::{NetworkConfig, ProtocolStatus};
+
+use crate::{TraderConfig, TraderError};
+
+/// Drift v2 program
+pub const DRIFT_PROGRAM: Pubkey =
+    anchor_lang::solana_program::pubkey!("dRiftyHA39MWEi3m9aunc5MzRF1JYuBsbn6VPcn33UH");
+
+/// Drift sub-account used for hedges
+pub const HEDGE_SUB_ACCOUNT_ID: u16 = 0;
+
+/// Upper bound for the hedge ratio (100% = fully delta-neutral)
+pub const MAX_HEDGE_RATIO_BPS: u16 = 10_000;
+
+// Drift enum variants (borsh-encoded as u8)
+const ORDER_TYPE_MARKET: u8 = 0;
+const MARKET_TYPE_PERP: u8 = 1;
+const DIRECTION_LONG: u8 = 0;
+const DIRECTION_SHORT: u8 = 1;
+
+// Drift `User` layout: discriminator, authority, delegate, name, then
+// 8 spot positions of 40 bytes ahead of 8 perp positions of 96 bytes
+const USER_PERP_POSITIONS_OFFSET: usize = 8 + 32 + 32 + 32 + 8 * 40;
+const PERP_POSITION_LEN: usize = 96;
+const MAX_PERP_POSITIONS: usize = 8;
+// Within a `PerpPosition`
+const BASE_ASSET_AMOUNT_OFFSET: usize = 8;
+const MARKET_INDEX_OFFSET: usize = 92;
+const OPEN_ORDERS_OFFSET: usize = 94;
+
+#[derive(Accounts)]
+pub struct InitializeHedge<'info> {
+    #[account(
+        seeds = [b"trader", authority.key().as_ref(), trader_config.listing_seed()],
+        bump,
+        has_one = authority @ TraderError::Unauthorized
+    )]
+    pub trader_config: Account<'info, TraderConfig>,
+
+    #[account(
+        init,
+        payer = authority,
+        space = HedgeConfig::LEN,
+        seeds = [b"hedge", trader_config.key().as_ref()],
+        bump
+    )]
+    pub hedge_config: Account<'info, HedgeConfig>,
+
+    /// CHECK: PDA that owns the Drift user account
+    #[account(
+        mut,
+        seeds = [b"hedge-authority", trader_config.key().as_ref()],
+        bump
+    )]
+    pub hedge_authority: UncheckedAccount<'info>,
+
+    /// Trader's spot position to hedge; later rebalances read only this account
+    #[account(
+        constraint = spot_position.owner == trader_config.authority @ TraderError::InvalidTokenAccount,
+        constraint = spot_position.mint == network_config.wsol_mint @ TraderError::InvalidTokenAccount
+    )]
+    pub spot_position: Account<'info, TokenAccount>,
+
+    /// CHECK: Drift user account; created and validated by Drift
+    #[account(mut)]
+    pub drift_user: UncheckedAccount<'info>,
+
+    /// CHECK: Drift user stats account; created and validated by Drift
+    #[account(mut)]
+    pub drift_user_stats: UncheckedAccount<'info>,
+
+    /// CHECK: Drift state; validated by Drift
+    #[account(mut)]
+    pub drift_state: UncheckedAccount<'info>,
+
+    /// CHECK: Drift program
+    #[account(address = DRIFT_PROGRAM)]
+    pub drift_program: UncheckedAccount<'info>,
+
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    pub rent: Sysvar<'info, Rent>,
+    pub system_program: Program<'info, System>,
+
+    /// Shared network addresses
+    #[account(
+        seeds = [b"network-config"],
+        bump = network_config.bump,
+        seeds::program = vectai_protocol::ID
+    )]
+    pub network_config: Account<'info, NetworkConfig>,
+}
+
+#[derive(Accounts)]
+pub struct ConfigureHedge<'info> {
+    #[account(
+        seeds = [b"trader", authority.key().as_ref(), trader_config.listing_seed()],
+        bump,
+        has_one = authority @ TraderError::Unauthorized
+    )]
+    pub trader_config: Account<'info, TraderConfig>,
+
+    #[account(
+        mut,
+        seeds = [b"hedge", trader_config.key().as_ref()],
+        bump = hedge_config.bump
+    )]
+    pub hedge_config: Account<'info, HedgeConfig>,
+
+    pub authority: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct CloseHedge<'info> {
+    #[account(
+        seeds = [b"trader", authority.key().as_ref(), trader_config.listing_seed()],
+        bump,
+        has_one = authority @ TraderError::Unauthorized
+    )]
+    pub trader_config: Account<'info, TraderConfig>,
+
+    /// Must have had its collateral withdrawn
+    #[account(
+        mut,
+        close = authority,
+        seeds = [b"hedge", trader_config.key().as_ref()],
+        bump = hedge_config.bump,
+        constraint = hedge_config.collateral == 0 @ TraderError::AccountInUse
+    )]
+    pub hedge_config: Account<'info, HedgeConfig>,
+
+    /// CHECK: Drift user account, checked against hedge_config; must hold
+    /// no perp position or open order in the hedge market
+    #[account(address = hedge_config.drift_user)]
+    pub drift_user: UncheckedAccount<'info>,
+
+    #[account(mut)]
+    pub authority: Signer<'info>,
+}
+
+/// Deposit collateral into the hedge sub-account. Drift's oracle and spot
+/// market accounts are passed as remaining accounts.
+#[derive(Accounts)]
+pub struct FundHedge<'info> {
+    #[account(
+        seeds = [b"trader", authority.key().as_ref(), trader_config.listing_seed()],
+        bump,
+        has_one = authority @ TraderError::Unauthorized
+    )]
+    pub trader_config: Account<'info, TraderConfig>,
+
+    #[account(
+        mut,
+        seeds = [b"hedge", trader_config.key().as_ref()],
+        bump = hedge_config.bump
+    )]
+    pub hedge_config: Account<'info, HedgeConfig>,
+
+    /// CHECK: PDA that owns the Drift user account
+    #[account(
+        seeds = [b"hedge-authority", trader_config.key().as_ref()],
+        bump
+    )]
+    pub hedge_authority: UncheckedAccount<'info>,
+
+    /// Collateral staging account owned by the hedge authority
+    #[account(
+        mut,
+        constraint = hedge_collateral.owner == hedge_authority.key() @ TraderError::InvalidTokenAccount,
+        constraint = hedge_collateral.mint == authority_collateral.mint @ TraderError::InvalidTokenAccount
+    )]
+    pub hedge_collateral: Account<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        constraint = authority_collateral.owner == authority.key() @ TraderError::InvalidTokenAccount
+    )]
+    pub authority_collateral: Account<'info, TokenAccount>,
+
+    /// CHECK: Drift user account, checked against hedge_config
+    #[account(mut, address = hedge_config.drift_user)]
+    pub drift_user: UncheckedAccount<'info>,
+
+    /// CHECK: Drift user stats; validated by Drift
+    #[account(mut)]
+    pub drift_user_stats: UncheckedAccount<'info>,
+
+    /// CHECK: Drift state; validated by Drift
+    pub drift_state: UncheckedAccount<'info>,
+
+    /// CHECK: Drift spot market vault; validated by Drift
+    #[account(mut)]
+    pub drift_spot_market_vault: UncheckedAccount<'info>,
+
+    /// CHECK: Drift program
+    #[account(address = DRIFT_PROGRAM)]
+    pub drift_program: UncheckedAccount<'info>,
+
+    pub authority: Signer<'info>,
+    pub token_program: Program<'info, Token>,
+
+    /// Global emergency-pause registry
+    #[account(
+        seeds = [b"protocol-status"],
+        bump = protocol_status.bump,
+        seeds::program = vectai_protocol::ID,
+        constraint = !protocol_status.paused @ TraderError::ProtocolPaused
+    )]
+    pub protocol_status: Account<'info, ProtocolStatus>,
+}
+
+/// Withdraw collateral from the hedge sub-account back to the trader
+/// authority. Drift's oracle and spot market accounts are passed as
+/// remaining accounts.
+#[derive(Accounts)]
+pub struct WithdrawHedgeCollateral<'info> {
+    #[account(
+        seeds = [b"trader", authority.key().as_ref(), trader_config.listing_seed()],
+        bump,
+        has_one = authority @ TraderError::Unauthorized
+    )]
+    pub trader_config: Account<'info, TraderConfig>,
+
+    #[account(
+        mut,
+        seeds = [b"hedge", trader_config.key().as_ref()],
+        bump = hedge_config.bump
+    )]
+    pub hedge_config: Account<'info, HedgeConfig>,
+
+    /// CHECK: PDA that owns the Drift user account
+    #[account(
+        seeds = [b"hedge-authority", trader_config.key().as_ref()],
+        bump
+    )]
+    pub hedge_authority: UncheckedAccount<'info>,
+
+    /// Collateral staging account owned by the hedge authority
+    #[account(
+        mut,
+        constraint = hedge_collateral.owner == hedge_authority.key() @ TraderError::InvalidTokenAccount,
+        constraint = hedge_collateral.mint == authority_collateral.mint @ TraderError::InvalidTokenAccount
+    )]
+    pub hedge_collateral: Account<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        constraint = authority_collateral.owner == authority.key() @ TraderError::InvalidTokenAccount
+    )]
+    pub authority_collateral: Account<'info, TokenAccount>,
+
+    /// CHECK: Drift user account, checked against hedge_config
+    #[account(mut, address = hedge_config.drift_user)]
+    pub drift_user: UncheckedAccount<'info>,
+
+    /// CHECK: Drift user stats; validated by Drift
+    #[account(mut)]
+    pub drift_user_stats: UncheckedAccount<'info>,
+
+    /// CHECK: Drift state; validated by Drift
+    pub drift_state: UncheckedAccount<'info>,
+
+    /// CHECK: Drift spot market vault; validated by Drift
+    #[account(mut)]
+    pub drift_spot_market_vault: UncheckedAccount<'info>,
+
+    /// CHECK: Drift's vault signer PDA; validated by Drift
+    pub drift_signer: UncheckedAccount<'info>,
+
+    /// CHECK: Drift program
+    #[account(address = DRIFT_PROGRAM)]
+    pub drift_program: UncheckedAccount<'info>,
+
+    pub authority: Signer<'info>,
+    pub token_program: Program<'info, Token>,
+}
+
+/// Resize the perp hedge to match the spot position. Drift's oracle, spot
+/// and perp market accounts are passed as remaining accounts.
+#[derive(Accounts)]
+pub struct RebalanceHedge<'info> {
+    /// Keeper submitting the rebalance
+    pub keeper: Signer<'info>,
+
+    #[account(
+        seeds = [b"trader", trader_config.authority.as_ref(), trader_config.listing_seed()],
+        bump
+    )]
+    pub trader_config: Account<'info, TraderConfig>,
+
+    #[account(
+        mut,
+        seeds = [b"hedge", trader_config.key().as_ref()],
+        bump = hedge_config.bump
+    )]
+    pub hedge_config: Account<'info, HedgeConfig>,
+
+    /// Trader's spot position being hedged, as pinned at initialization
+    #[account(address = hedge_config.spot_position @ TraderError::InvalidTokenAccount)]
+    pub spot_position: Account<'info, TokenAccount>,
+
+    /// CHECK: PDA that owns the Drift user account
+    #[account(
+        seeds = [b"hedge-authority", trader_config.key().as_ref()],
+        bump
+    )]
+    pub hedge_authority: UncheckedAccount<'info>,
+
+    /// CHECK: Drift user account, checked against hedge_config
+    #[account(mut, address = hedge_config.drift_user)]
+    pub drift_user: UncheckedAccount<'info>,
+
+    /// CHECK: Drift state; validated by Drift
+    pub drift_state: UncheckedAccount<'info>,
+
+    /// CHECK: Drift program
+    #[account(address = DRIFT_PROGRAM)]
+    pub drift_program: UncheckedAccount<'info>,
+
+    /// Global emergency-pause registry
+    #[account(
+        seeds = [b"protocol-status"],
+        bump = protocol_status.bump,
+        seeds::program = vectai_protocol::ID,
+        constraint = !protocol_status.paused @ TraderError::ProtocolPaused
+    )]
+    pub protocol_status: Account<'info, ProtocolStatus>,
+}
+
+/// Perp hedge attached to a trader: short `hedge_ratio_bps` of the spot
+/// position once it reaches `min_position`
+#[account]
+pub struct HedgeConfig {
+    pub trader: Pubkey,
+    pub drift_user: Pubkey,
+    pub spot_mint: Pubkey,
+    pub perp_market_index: u16,
+    pub hedge_ratio_bps: u16,
+    pub min_position: u64, // Spot size that triggers a hedge
+    pub hedged_amount: u64, // Filled short as of the last rebalance, in Drift base precision
+    pub spot_position: Pubkey, // Token account the hedge tracks
+    pub collateral: u64, // Deposited into Drift less withdrawn
+    pub bump: u8,
+}
+
+impl HedgeConfig {
+    pub const LEN: usize = 8 + // discriminator
+        32 + // trader
+        32 + // drift_user
+        32 + // spot_mint
+        2 +  // perp_market_index
+        2 +  // hedge_ratio_bps
+        8 +  // min_position
+        8 +  // hedged_amount
+        32 + // spot_position
+        8 +  // collateral
+        1;   // bump
+
+    /// Short size for a spot position of `position`
+    pub fn target_hedge(&self, position: u64) -> u64 {
+        if position < self.min_position {
+            return 0;
+        }
+        vectai_common::bps_of(position, self.hedge_ratio_bps as u64)
+    }
+}
+
+/// Filled short in `market_index` held by a Drift user account, and
+/// whether orders in that market are still waiting to fill
+pub fn drift_perp_short(drift_user: &AccountInfo, market_index: u16) -> Result<(u64, bool)> {
+    require_keys_eq!(*drift_user.owner, DRIFT_PROGRAM, TraderError::InvalidDriftUser);
+    let data = drift_user.try_borrow_data()?;
+    require!(
+        data.len() >= USER_PERP_POSITIONS_OFFSET + MAX_PERP_POSITIONS * PERP_POSITION_LEN,
+        TraderError::InvalidDriftUser
+    );
+
+    for slot in 0..MAX_PERP_POSITIONS {
+        let position = &data[USER_PERP_POSITIONS_OFFSET + slot * PERP_POSITION_LEN..][..PERP_POSITION_LEN];
+        let base = i64::from_le_bytes(position[BASE_ASSET_AMOUNT_OFFSET..][..8].try_into().unwrap());
+        let index = u16::from_le_bytes(position[MARKET_INDEX_OFFSET..][..2].try_into().unwrap());
+        let open_orders = position[OPEN_ORDERS_OFFSET];
+        if index == market_index && (base != 0 || open_orders != 0) {
+            // A long is no short; rebalancing shorts it back down
+            return Ok((base.min(0).unsigned_abs(), open_orders != 0));
+        }
+    }
+    Ok((0, false))
+}
+
+/// Mirror of Drift's `OrderParams`
+#[derive(AnchorSerialize)]
+struct DriftOrderParams {
+    order_type: u8,
+    market_type: u8,
+    direction: u8,
+    user_order_id: u8,
+    base_asset_amount: u64,
+    price: u64,
+    market_index: u16,
+    reduce_only: bool,
+    post_only: u8,
+    immediate_or_cancel: bool,
+    max_ts: Option<i64>,
+    trigger_price: Option<u64>,
+    trigger_condition: u8,
+    oracle_price_offset: Option<i32>,
+    auction_duration: Option<u8>,
+    auction_start_price: Option<i64>,
+    auction_end_price: Option<i64>,
+}
+
+/// Anchor instruction discriminator for a Drift instruction
+fn drift_discriminator(name: &str) -> [u8; 8] {
+    let mut discriminator = [0u8; 8];
+    discriminator.copy_from_slice(&hash(format!("global:{}", name).as_bytes()).to_bytes()[..8]);
+    discriminator
+}
+
+fn invoke_drift<'info>(
+    drift_program: &AccountInfo<'info>,
+    name: &str,
+    args: Vec<u8>,
+    metas: Vec<AccountMeta>,
+    infos: &[AccountInfo<'info>],
+    signer_seeds: &[&[&[u8]]],
+) -> Result<()> {
+    let mut data = drift_discriminator(name).to_vec();
+    data.extend_from_slice(&args);
+    let ix = Instruction {
+        program_id: drift_program.key(),
+        accounts: metas,
+        data,
+    };
+    invoke_signed(&ix, infos, signer_seeds)?;
+    Ok(())
+}
+
+fn remaining_metas(remaining: &[AccountInfo]) -> Vec<AccountMeta> {
+    remaining
+        .iter()
+        .map(|account| {
+            if account.is_writable {
+                AccountMeta::new(account.key(), false)
+            } else {
+                AccountMeta::new_readonly(account.key(), false)
+            }
+        })
+        .collect()
+}
+
+/// Create the Drift user stats and user accounts for the hedge authority
+pub fn drift_initialize_user<'info>(
+    accounts: &InitializeHedge<'info>,
+    signer_seeds: &[&[&[u8]]],
+) -> Result<()> {
+    let program = accounts.drift_program.to_account_info();
+    let authority = accounts.hedge_authority.to_account_info();
+    let payer = accounts.authority.to_account_info();
+
+    invoke_drift(
+        &program,
+        "initialize_user_stats",
+        vec![],
+        vec![
+            AccountMeta::new(accounts.drift_user_stats.key(), false),
+            AccountMeta::new(accounts.drift_state.key(), false),
+            AccountMeta::new_readonly(authority.key(), true),
+            AccountMeta::new(payer.key(), true),
+            AccountMeta::new_readonly(accounts.rent.key(), false),
+            AccountMeta::new_readonly(accounts.system_program.key(), false),
+        ],
+        &[
+            accounts.drift_user_stats.to_account_info(),
+            accounts.drift_state.to_account_info(),
+            authority.clone(),
+            payer.clone(),
+            accounts.rent.to_account_info(),
+            accounts.system_program.to_account_info(),
+        ],
+        signer_seeds,
+    )?;
+
+    let mut name = [b' '; 32];
+    name[..10].copy_from_slice(b"VECT hedge");
+    let mut args = HEDGE_SUB_ACCOUNT_ID.to_le_bytes().to_vec();
+    args.extend_from_slice(&name);
+    invoke_drift(
+        &program,
+        "initialize_user",
+        args,
+        vec![
+            AccountMeta::new(accounts.drift_user.key(), false),
+            AccountMeta::new(accounts.drift_user_stats.key(), false),
+            AccountMeta::new(accounts.drift_state.key(), false),
+            AccountMeta::new_readonly(authority.key(), true),
+            AccountMeta::new(payer.key(), true),
+            AccountMeta::new_readonly(accounts.rent.key(), false),
+            AccountMeta::new_readonly(accounts.system_program.key(), false),
+        ],
+        &[
+            accounts.drift_user.to_account_info(),
+            accounts.drift_user_stats.to_account_info(),
+            accounts.drift_state.to_account_info(),
+            authority,
+            payer,
+            accounts.rent.to_account_info(),
+            accounts.system_program.to_account_info(),
+        ],
+        signer_seeds,
+    )
+}
+
+/// Deposit `amount` from the hedge collateral account into Drift
+pub fn drift_deposit<'info>(
+    accounts: &FundHedge<'info>,
+    remaining: &[AccountInfo<'info>],
+    spot_market_index: u16,
+    amount: u64,
+    signer_seeds: &[&[&[u8]]],
+) -> Result<()> {
+    let mut args = spot_market_index.to_le_bytes().to_vec();
+    args.extend_from_slice(&amount.to_le_bytes());
+    args.push(0); // reduce_only = false
+
+    let mut metas = vec![
+        AccountMeta::new_readonly(accounts.drift_state.key(), false),
+        AccountMeta::new(accounts.drift_user.key(), false),
+        AccountMeta::new(accounts.drift_user_stats.key(), false),
+        AccountMeta::new_readonly(accounts.hedge_authority.key(), true),
+        AccountMeta::new(accounts.drift_spot_market_vault.key(), false),
+        AccountMeta::new(accounts.hedge_collateral.key(), false),
+        AccountMeta::new_readonly(accounts.token_program.key(), false),
+    ];
+    metas.extend(remaining_metas(remaining));
+
+    let mut infos = vec![
+        accounts.drift_state.to_account_info(),
+        accounts.drift_user.to_account_info(),
+        accounts.drift_user_stats.to_account_info(),
+        accounts.hedge_authority.to_account_info(),
+        accounts.drift_spot_market_vault.to_account_info(),
+        accounts.hedge_collateral.to_account_info(),
+        accounts.token_program.to_account_info(),
+    ];
+    infos.extend_from_slice(remaining);
+
+    invoke_drift(&accounts.drift_program.to_account_info(), "deposit", args, metas, &infos, signer_seeds)
+}
+
+/// Withdraw up to `amount` from Drift into the hedge collateral account;
+/// reduce-only, so Drift never borrows to fill it
+pub fn drift_withdraw<'info>(
+    accounts: &WithdrawHedgeCollateral<'info>,
+    remaining: &[AccountInfo<'info>],
+    spot_market_index: u16,
+    amount: u64,
+    signer_seeds: &[&[&[u8]]],
+) -> Result<()> {
+    let mut args = spot_market_index.to_le_bytes().to_vec();
+    args.extend_from_slice(&amount.to_le_bytes());
+    args.push(1); // reduce_only = true
+
+    let mut metas = vec![
+        AccountMeta::new_readonly(accounts.drift_state.key(), false),
+        AccountMeta::new(accounts.drift_user.key(), false),
+        AccountMeta::new(accounts.drift_user_stats.key(), false),
+        AccountMeta::new_readonly(accounts.hedge_authority.key(), true),
+        AccountMeta::new(accounts.drift_spot_market_vault.key(), false),
+        AccountMeta::new_readonly(accounts.drift_signer.key(), false),
+        AccountMeta::new(accounts.hedge_collateral.key(), false),
+        AccountMeta::new_readonly(accounts.token_program.key(), false),
+    ];
+    metas.extend(remaining_metas(remaining));
+
+    let mut infos = vec![
+        accounts.drift_state.to_account_info(),
+        accounts.drift_user.to_account_info(),
+        accounts.drift_user_stats.to_account_info(),
+        accounts.hedge_authority.to_account_info(),
+        accounts.drift_spot_market_vault.to_account_info(),
+        accounts.drift_signer.to_account_info(),
+        accounts.hedge_collateral.to_account_info(),
+        accounts.token_program.to_account_info(),
+    ];
+    infos.extend_from_slice(remaining);
+
+    invoke_drift(&accounts.drift_program.to_account_info(), "withdraw", args, metas, &infos, signer_seeds)
+}
+
+/// Place a market perp order: short `amount` to grow the hedge, or a
+/// reduce-only long to shrink it
+pub fn drift_place_hedge_order<'info>(
+    accounts: &RebalanceHedge<'info>,
+    remaining: &[AccountInfo<'info>],
+    increase: bool,
+    amount: u64,
+    signer_seeds: &[&[&[u8]]],
+) -> Result<()> {
+    let params = DriftOrderParams {
+        order_type: ORDER_TYPE_MARKET,
+        market_type: MARKET_TYPE_PERP,
+        direction: if increase { DIRECTION_SHORT } else { DIRECTION_LONG },
+        user_order_id: 0,
+        base_asset_amount: amount,
+        price: 0,
+        market_index: accounts.hedge_config.perp_market_index,
+        reduce_only: !increase,
+        post_only: 0,
+        immediate_or_cancel: false,
+        max_ts: None,
+        trigger_price: None,
+        trigger_condition: 0,
+        oracle_price_offset: None,
+        auction_duration: None,
+        auction_start_price: None,
+        auction_end_price: None,
+    };
+
+    let mut metas = vec![
+        AccountMeta::new_readonly(accounts.drift_state.key(), false),
+        AccountMeta::new(accounts.drift_user.key(), false),
+        AccountMeta::new_readonly(accounts.hedge_authority.key(), true),
+    ];
+    metas.extend(remaining_metas(remaining));
+
+    let mut infos = vec![
+        accounts.drift_state.to_account_info(),
+        accounts.drift_user.to_account_info(),
+        accounts.hedge_authority.to_account_info(),
+    ];
+    infos.extend_from_slice(remaining);
+
+    invoke_drift(
+        &accounts.drift_program.to_account_info(),
+        "place_perp_order",
+        params.try_to_vec()?,
+        metas,
+        &infos,
+        signer_seeds,
+    )
+}
+
+#[event]
+pub struct HedgeRebalanced {
+    pub trader: Pubkey,
+    pub spot_position: u64,
+    pub previous_hedge: u64,
+    pub target_hedge: u64,
+}
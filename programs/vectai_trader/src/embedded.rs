@@ -0,0 +1,57 @@
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::instruction::{get_stack_height, TRANSACTION_LEVEL_STACK_HEIGHT};
+use anchor_lang::solana_program::sysvar::instructions::{load_current_index_checked, load_instruction_at_checked};
+
+use crate::{TraderConfig, TraderError};
+
+#[derive(Accounts)]
+pub struct CreateProgramStrategy<'info> {
+    #[account(
+        init,
+        payer = payer,
+        space = TraderConfig::LEN,
+        seeds = [b"trader", authority.key().as_ref()],
+        bump
+    )]
+    pub trader_config: Account<'info, TraderConfig>,
+
+    /// PDA of the calling program, signing through `invoke_signed`
+    pub authority: Signer<'info>,
+
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    /// CHECK: address-checked instructions sysvar
+    #[account(address = anchor_lang::solana_program::sysvar::instructions::ID)]
+    pub instructions: UncheckedAccount<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+/// Program that invoked this instruction through CPI. Only a direct CPI
+/// from the top-level instruction is accepted, so the caller is that
+/// instruction's program and the only one able to sign for its PDAs.
+pub fn calling_program(instructions: &AccountInfo) -> Result<Pubkey> {
+    require!(
+        get_stack_height() == TRANSACTION_LEVEL_STACK_HEIGHT + 1,
+        TraderError::NotCalledByProgram
+    );
+    let current = load_current_index_checked(instructions)?;
+    let ix = load_instruction_at_checked(current as usize, instructions)?;
+    require!(ix.program_id != crate::ID, TraderError::NotCalledByProgram);
+    Ok(ix.program_id)
+}
+
+impl TraderConfig {
+    /// Whether the strategy's authority is a PDA of `owner_program`
+    pub fn is_program_owned(&self) -> bool {
+        self.owner_program != Pubkey::default()
+    }
+}
+
+#[event]
+pub struct ProgramStrategyCreated {
+    pub trader: Pubkey, // TraderConfig
+    pub authority: Pubkey, // PDA of owner_program
+    pub owner_program: Pubkey,
+}
@@ -0,0 +1,72 @@
+use anchor_lang::prelude::*;
+
+use crate::vectai_oracle::accounts::PriceAggregator;
+use crate::vectai_oracle::cpi::accounts::{GetAggregatedPrice, GetPrice};
+use crate::vectai_oracle::cpi::{get_aggregated_price, get_price};
+use crate::vectai_oracle::program::VectaiOracle;
+use crate::vectai_oracle::types::PriceData;
+use crate::{TraderConfig, TraderError};
+
+/// Loosest slot bound a strategy may set on oracle prices, about an hour
+pub const MAX_PRICE_AGE_SLOTS: u64 = 9_000;
+
+impl TraderConfig {
+    /// Whether an oracle price observed at `price_slot` is recent enough to
+    /// execute on at `clock`; always true without `max_price_age_slots`
+    pub fn price_fresh(&self, price_slot: u64, clock: &Clock) -> bool {
+        self.max_price_age_slots == 0 || clock.slot.saturating_sub(price_slot) <= self.max_price_age_slots
+    }
+
+    /// Whether the sources of an aggregated price, `max_deviation_bps`
+    /// apart, agree closely enough to execute on; always true without a
+    /// `price_aggregator`
+    pub fn sources_agree(&self, max_deviation_bps: u64) -> bool {
+        self.price_aggregator == Pubkey::default() || max_deviation_bps <= self.max_source_deviation_bps as u64
+    }
+}
+
+/// The oracle price an execution runs on: `price_feed` read directly, or
+/// the weighted average of the strategy's `price_aggregator`, whose source
+/// feeds lead `remaining_accounts`. Also returns the remaining accounts
+/// past the sources.
+pub fn strategy_price<'c, 'info>(
+    trader_config: &TraderConfig,
+    oracle_program: &Program<'info, VectaiOracle>,
+    price_feed: &UncheckedAccount<'info>,
+    remaining_accounts: &'c [AccountInfo<'info>],
+) -> Result<(PriceData, &'c [AccountInfo<'info>])> {
+    if trader_config.price_aggregator == Pubkey::default() {
+        let price_data = get_price(CpiContext::new(
+            oracle_program.to_account_info(),
+            GetPrice {
+                price_feed: price_feed.to_account_info(),
+            },
+        ))?
+        .get();
+        return Ok((price_data, remaining_accounts));
+    }
+
+    require_keys_eq!(price_feed.key(), trader_config.price_aggregator, TraderError::InvalidPriceFeed);
+    let source_count = PriceAggregator::try_deserialize(&mut &price_feed.try_borrow_data()?[..])?.sources.len();
+    require!(remaining_accounts.len() >= source_count, TraderError::InvalidPriceFeed);
+    let (price_sources, remaining_accounts) = remaining_accounts.split_at(source_count);
+    let aggregated = get_aggregated_price(
+        CpiContext::new(
+            oracle_program.to_account_info(),
+            GetAggregatedPrice {
+                price_aggregator: price_feed.to_account_info(),
+            },
+        )
+        .with_remaining_accounts(price_sources.to_vec()),
+    )?
+    .get();
+    require!(trader_config.sources_agree(aggregated.max_deviation_bps), TraderError::PriceSourcesDisagree);
+    let price_data = PriceData {
+        price: aggregated.price,
+        conf: aggregated.conf,
+        expo: aggregated.expo,
+        publish_time: aggregated.publish_time,
+        slot: aggregated.slot,
+    };
+    Ok((price_data, remaining_accounts))
+}
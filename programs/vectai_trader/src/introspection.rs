@@ -0,0 +1,45 @@
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::sysvar::instructions::load_instruction_at_checked;
+use anchor_lang::solana_program::{ed25519_program, secp256k1_program, system_program};
+
+use crate::TraderError;
+
+const COMPUTE_BUDGET_PROGRAM: Pubkey = anchor_lang::solana_program::pubkey!("ComputeBudget111111111111111111111111111111");
+const ASSOCIATED_TOKEN_PROGRAM: Pubkey = anchor_lang::solana_program::pubkey!("ATokenGPvbdGVxr1b2hvZbsiqW5xWH25efTNsLJA8knL");
+
+/// Programs whose instructions may share a transaction with an execution:
+/// this program, the runtime and SPL token programs and the signature
+/// precompiles, none of which call into arbitrary programs
+pub const TRUSTED_PROGRAMS: [Pubkey; 7] = [
+    crate::ID,
+    system_program::ID,
+    COMPUTE_BUDGET_PROGRAM,
+    anchor_spl::token::ID,
+    ASSOCIATED_TOKEN_PROGRAM,
+    ed25519_program::ID,
+    secp256k1_program::ID,
+];
+
+/// Reject a transaction with any top-level instruction outside
+/// `TRUSTED_PROGRAMS`, e.g. a flash-loan wrapper that could move the pool
+/// before the execution and back after it. A program reaching the
+/// execution through CPI is itself such an instruction, unless it is the
+/// strategy's `owner_program`. `also_trusted` adds the venue an execution
+/// routes through.
+pub fn require_trusted_transaction(
+    instructions: &AccountInfo,
+    owner_program: &Pubkey,
+    also_trusted: &[Pubkey],
+) -> Result<()> {
+    let mut index = 0;
+    while let Ok(ix) = load_instruction_at_checked(index, instructions) {
+        require!(
+            TRUSTED_PROGRAMS.contains(&ix.program_id)
+                || also_trusted.contains(&ix.program_id)
+                || (*owner_program != Pubkey::default() && ix.program_id == *owner_program),
+            TraderError::UntrustedInstruction
+        );
+        index += 1;
+    }
+    Ok(())
+}
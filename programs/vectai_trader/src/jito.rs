@@ -0,0 +1,87 @@
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::sysvar::instructions::{self as instructions_sysvar, load_instruction_at_checked};
+use anchor_lang::system_program::{self, Transfer};
+use anchor_lang::Discriminator;
+
+use crate::{instruction, TraderConfig, TraderError};
+
+/// Jito's tip payment accounts; a bundle pays any one of them
+pub const JITO_TIP_ACCOUNTS: [Pubkey; 8] = [
+    anchor_lang::solana_program::pubkey!("96gYZGLnJYVFmbjzopPSU6QiEV5fGqZNyN9nmNhvrZU5"),
+    anchor_lang::solana_program::pubkey!("HFqU5x63VTqvQss8hp11i4wVV8bD44PvwucfZ2bU7gRe"),
+    anchor_lang::solana_program::pubkey!("Cw8CFyM9FkoMi7K7Crf6HNQqf4uEMzpKw6QNghXLvLkY"),
+    anchor_lang::solana_program::pubkey!("ADaUMid9yfUytqMBgopwjb2DTLSokTSzL1zt6iGPaS49"),
+    anchor_lang::solana_program::pubkey!("DfXygSm4jCyNCybVYYK6DwvWqjKee8pbDmJGcLWNDXjh"),
+    anchor_lang::solana_program::pubkey!("ADuUkR4vqLUMWXxW9gh6D6L8pMSawimctcNZ5pGwDcEt"),
+    anchor_lang::solana_program::pubkey!("DttWaMuVvTiduZRnguLF7jNxTgiMBZ1hyAumKUiL2KRL"),
+    anchor_lang::solana_program::pubkey!("3AVi9Tg9Uo68tJfuvoKvqKNWKkC5wPdSSdeBnizKZ6jT"),
+];
+
+// Position of the trader config in both execution instructions' accounts
+const EXECUTION_TRADER_CONFIG: usize = 1;
+
+/// Tip a Jito tip account from the strategy authority, in the same
+/// transaction as one of the strategy's executions
+#[derive(Accounts)]
+pub struct TipJito<'info> {
+    #[account(mut)]
+    pub user_authority: Signer<'info>,
+
+    #[account(
+        seeds = [b"trader", user_authority.key().as_ref(), trader_config.listing_seed()],
+        bump,
+        constraint = trader_config.authority == user_authority.key() @ TraderError::Unauthorized
+    )]
+    pub trader_config: Account<'info, TraderConfig>,
+
+    /// CHECK: One of `JITO_TIP_ACCOUNTS`
+    #[account(
+        mut,
+        constraint = JITO_TIP_ACCOUNTS.contains(&tip_account.key()) @ TraderError::InvalidJitoTipAccount
+    )]
+    pub tip_account: UncheckedAccount<'info>,
+
+    /// CHECK: address-checked instructions sysvar
+    #[account(address = instructions_sysvar::ID)]
+    pub instructions: UncheckedAccount<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+/// Whether the transaction carries an `execute_trade` or
+/// `execute_jupiter_route` of `trader_config`, so tips only ever pay for
+/// getting an execution included
+pub fn bundles_execution(instructions: &AccountInfo, trader_config: &Pubkey) -> Result<bool> {
+    let mut index = 0;
+    while let Ok(ix) = load_instruction_at_checked(index, instructions) {
+        let executes = ix.program_id == crate::ID
+            && (ix.data.starts_with(&instruction::ExecuteTrade::DISCRIMINATOR)
+                || ix.data.starts_with(&instruction::ExecuteJupiterRoute::DISCRIMINATOR));
+        if executes && ix.accounts.get(EXECUTION_TRADER_CONFIG).map(|meta| meta.pubkey) == Some(*trader_config) {
+            return Ok(true);
+        }
+        index += 1;
+    }
+    Ok(false)
+}
+
+/// Pay `lamports` from the strategy authority to a Jito tip account
+pub fn pay_jito_tip(accounts: &TipJito, lamports: u64) -> Result<()> {
+    system_program::transfer(
+        CpiContext::new(
+            accounts.system_program.to_account_info(),
+            Transfer {
+                from: accounts.user_authority.to_account_info(),
+                to: accounts.tip_account.to_account_info(),
+            },
+        ),
+        lamports,
+    )
+}
+
+#[event]
+pub struct JitoTipPaid {
+    pub trader: Pubkey,
+    pub tip_account: Pubkey,
+    pub lamports: u64,
+}
@@ -0,0 +1,179 @@
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::{hash::hash, instruction::Instruction, program::invoke};
+use anchor_spl::token::{Token, TokenAccount};
+
+use vectai_protocol::{program::VectaiProtocol, NetworkConfig, ProtocolStatus};
+
+use crate::{vectai_oracle::program::VectaiOracle, TraderConfig, TraderError};
+
+/// Jupiter Aggregator v6
+pub const JUPITER_V6_PROGRAM: Pubkey =
+    anchor_lang::solana_program::pubkey!("JUP6LkbZbjS1jKKwapdHNy74zcZ3tLUZoi5QNyVTaV4");
+
+// Fixed accounts of Jupiter's `route`, before the route plan's own
+const ROUTE_USER_TRANSFER_AUTHORITY: usize = 1;
+const ROUTE_USER_SOURCE: usize = 2;
+const ROUTE_USER_DESTINATION: usize = 3;
+const ROUTE_DESTINATION_TOKEN_ACCOUNT: usize = 4; // Jupiter's id when unused
+const ROUTE_DESTINATION_MINT: usize = 5;
+const ROUTE_FIXED_ACCOUNTS: usize = 9;
+
+// `route` data ends with in_amount, quoted_out_amount, slippage_bps and
+// platform_fee_bps after the route plan
+const ROUTE_ARGS_LEN: usize = 8 + 8 + 2 + 1;
+
+/// `execute_trade` with an off-chain Jupiter route in place of the
+/// whitelisted Raydium pool. The route's accounts, fixed ones first, follow
+/// as remaining accounts in the order Jupiter's `route` takes them.
+#[derive(Accounts)]
+pub struct ExecuteJupiterRoute<'info> {
+    #[account(mut)]
+    pub user_authority: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"trader", trader_config.authority.as_ref(), trader_config.listing_seed()],
+        bump
+    )]
+    pub trader_config: Account<'info, TraderConfig>,
+
+    /// Strategy's source token account; the route must spend from it
+    #[account(mut)]
+    pub user_source_token_account: Account<'info, TokenAccount>,
+
+    /// Strategy's destination token account; the route must pay into it
+    #[account(mut)]
+    pub user_destination_token_account: Account<'info, TokenAccount>,
+
+    /// CHECK: Jupiter program
+    #[account(address = JUPITER_V6_PROGRAM @ TraderError::InvalidJupiterRoute)]
+    pub jupiter_program: UncheckedAccount<'info>,
+
+    /// The Oracle Program (VECT.AI Oracle)
+    pub vectai_oracle_program: Program<'info, VectaiOracle>,
+
+    /// Oracle price feed account, or the strategy's price aggregator
+    /// CHECK: Safe to be unchecked because vectai_oracle validates it
+    pub price_feed: UncheckedAccount<'info>,
+
+    /// Treasury vault for the source mint; required while the trade fee is on
+    #[account(
+        mut,
+        constraint = protocol_fee_account.key() == vectai_treasury::vault_address(&user_source_token_account.mint) @ TraderError::InvalidTokenAccount
+    )]
+    pub protocol_fee_account: Option<Account<'info, TokenAccount>>,
+
+    pub token_program: Program<'info, Token>,
+
+    /// Global emergency-pause registry
+    #[account(
+        seeds = [b"protocol-status"],
+        bump = protocol_status.bump,
+        seeds::program = vectai_protocol::ID,
+        constraint = !protocol_status.paused @ TraderError::ProtocolPaused
+    )]
+    pub protocol_status: Account<'info, ProtocolStatus>,
+
+    /// Shared network addresses
+    #[account(
+        seeds = [b"network-config"],
+        bump = network_config.bump,
+        seeds::program = vectai_protocol::ID
+    )]
+    pub network_config: Account<'info, NetworkConfig>,
+
+    /// CHECK: Protocol stats PDA, validated by vectai_protocol
+    #[account(mut)]
+    pub protocol_stats: UncheckedAccount<'info>,
+
+    /// CHECK: Signs `record_activity` for this program
+    #[account(seeds = [b"stats-reporter"], bump)]
+    pub stats_reporter: UncheckedAccount<'info>,
+
+    pub protocol_program: Program<'info, VectaiProtocol>,
+
+    /// CHECK: address-checked instructions sysvar
+    #[account(address = anchor_lang::solana_program::sysvar::instructions::ID)]
+    pub instructions: UncheckedAccount<'info>,
+}
+
+/// The amounts of a Jupiter `route` instruction; its route plan is opaque
+/// here and left to Jupiter
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct JupiterRoute {
+    pub in_amount: u64,
+    pub quoted_out_amount: u64,
+    pub slippage_bps: u16,
+    pub platform_fee_bps: u8,
+}
+
+impl JupiterRoute {
+    /// `None` unless `data` is a `route` instruction
+    pub fn parse(data: &[u8]) -> Option<Self> {
+        if data.get(..8)? != &hash(b"global:route").to_bytes()[..8] {
+            return None;
+        }
+        let rest = &data[8..];
+        let args = rest.get(rest.len().checked_sub(ROUTE_ARGS_LEN)?..)?;
+        Some(JupiterRoute {
+            in_amount: u64::from_le_bytes(args[..8].try_into().ok()?),
+            quoted_out_amount: u64::from_le_bytes(args[8..16].try_into().ok()?),
+            slippage_bps: u16::from_le_bytes(args[16..18].try_into().ok()?),
+            platform_fee_bps: args[18],
+        })
+    }
+
+    /// Least output Jupiter will accept for the route
+    pub fn minimum_out(&self) -> Result<u64> {
+        crate::calculate_minimum_amount_out(self.quoted_out_amount, self.slippage_bps as u64)
+    }
+}
+
+/// Check `route_accounts` spend `amount_in` from the strategy's source
+/// account into its destination account, signed by the strategy authority,
+/// with no platform fee and no more slippage than `max_slippage_bps`
+pub fn verify_jupiter_route(
+    accounts: &ExecuteJupiterRoute,
+    route_accounts: &[AccountInfo],
+    route: &JupiterRoute,
+    amount_in: u64,
+    max_slippage_bps: u64,
+) -> Result<()> {
+    require!(route_accounts.len() >= ROUTE_FIXED_ACCOUNTS, TraderError::InvalidJupiterRoute);
+    let destination = &accounts.user_destination_token_account;
+    let key = |index: usize| route_accounts[index].key();
+    require!(
+        key(ROUTE_USER_TRANSFER_AUTHORITY) == accounts.user_authority.key()
+            && key(ROUTE_USER_SOURCE) == accounts.user_source_token_account.key()
+            && key(ROUTE_USER_DESTINATION) == destination.key()
+            && (key(ROUTE_DESTINATION_TOKEN_ACCOUNT) == JUPITER_V6_PROGRAM
+                || key(ROUTE_DESTINATION_TOKEN_ACCOUNT) == destination.key())
+            && key(ROUTE_DESTINATION_MINT) == destination.mint,
+        TraderError::InvalidJupiterRoute
+    );
+    require!(
+        route.in_amount == amount_in && route.platform_fee_bps == 0,
+        TraderError::InvalidJupiterRoute
+    );
+    require!(route.slippage_bps as u64 <= max_slippage_bps, TraderError::RouteSlippageTooWide);
+    Ok(())
+}
+
+/// Invoke Jupiter with the route as the client built it; the strategy
+/// authority's signature carries through from the transaction
+pub fn invoke_jupiter_route(jupiter_program: &AccountInfo, route_accounts: &[AccountInfo], data: Vec<u8>) -> Result<()> {
+    let ix = Instruction {
+        program_id: jupiter_program.key(),
+        accounts: route_accounts
+            .iter()
+            .map(|account| AccountMeta {
+                pubkey: account.key(),
+                is_signer: account.is_signer,
+                is_writable: account.is_writable,
+            })
+            .collect(),
+        data,
+    };
+    invoke(&ix, route_accounts)?;
+    Ok(())
+}
@@ -1,15 +1,69 @@
 use anchor_lang::prelude::*;
+use anchor_lang::solana_program::program_option::COption;
 use anchor_spl::token::{self, Token, TokenAccount, Transfer};
-use vectai_oracle::cpi::accounts::GetPrice;
 use vectai_oracle::program::VectaiOracle;
-use vectai_oracle::cpi::get_price;
+use raydium_swapper::pool_config;
+use vectai_referral::ReferralCode;
+use vectai_protocol::{
+    holds_role, program::VectaiProtocol, ActivityKind, FeeConfig, NetworkConfig, ProtocolStatus, Role, RoleRegistry,
+};
+use vectai_math::condition::{self, EvalContext, MAX_CONDITION_LEN};
+use vectai_math::USDC_DECIMALS;
+use vectai_common::{is_admin, Versioned};
 
 // Import Raydium swap module
 mod raydium_swap;
 use raydium_swap::{execute_raydium_swap, calculate_minimum_amount_out, RaydiumSwapAccounts};
 
+mod copy_trading;
+pub use copy_trading::*;
+mod marketplace;
+pub use marketplace::*;
+mod drift_hedge;
+pub use drift_hedge::*;
+mod risk;
+pub use risk::*;
+mod points;
+pub use points::*;
+mod membership;
+pub use membership::*;
+mod trade_history;
+pub use trade_history::*;
+mod audit;
+pub use audit::*;
+mod schedule;
+pub use schedule::*;
+mod slippage;
+pub use slippage::*;
+mod volatility;
+pub use volatility::*;
+mod take_profit;
+pub use take_profit::*;
+mod jupiter;
+pub use jupiter::*;
+mod jito;
+pub use jito::*;
+// USD-marked realized PnL of executions
+mod pnl;
+pub use pnl::*;
+// Transaction introspection guarding executions
+mod introspection;
+pub use introspection::*;
+// Execution cooldowns in seconds or slots
+mod cooldown;
+pub use cooldown::*;
+// Slot-bound freshness of oracle prices
+mod freshness;
+pub use freshness::*;
+// Strategies created and owned by other programs over CPI
+mod embedded;
+pub use embedded::*;
+
 declare_id!("FEmf6TbtffcKVptbshZvCcg3CjQqsWodNwQhpXJff4NP");
 
+// Oracle CPI client generated from idls/vectai_oracle.json
+declare_program!(vectai_oracle);
+
 #[program]
 pub mod vectai_trader {
     use super::*;
@@ -20,232 +74,2106 @@ pub mod vectai_trader {
         price_threshold: i64,
         swap_amount: u64,
         slippage_tolerance: u64, // Basis points (e.g., 200 = 2%)
+    ) -> Result<()> {
+        let referrer = cited_referrer(&ctx.accounts.referral_code, ctx.accounts.authority.key())?;
+        initialize_trader_config(
+            &mut ctx.accounts.trader_config,
+            ctx.accounts.authority.key(),
+            referrer,
+            Pubkey::default(),
+            price_threshold,
+            swap_amount,
+            slippage_tolerance,
+        )?;
+
+        msg!("✅ Secure trader initialized: {} threshold, {} amount, {}% slippage", 
+             price_threshold, swap_amount, slippage_tolerance);
+        Ok(())
+    }
+
+    /// Create a strategy owned by the calling program, whose PDA signs as
+    /// its authority. Must be a CPI made directly by the top-level
+    /// instruction's program, which is recorded as `owner_program` and
+    /// trusted alongside the strategy's executions.
+    pub fn create_program_strategy(
+        ctx: Context<CreateProgramStrategy>,
+        price_threshold: i64,
+        swap_amount: u64,
+        slippage_tolerance: u64,
+    ) -> Result<()> {
+        let owner_program = calling_program(&ctx.accounts.instructions)?;
+        let authority = ctx.accounts.authority.key();
+        require!(!authority.is_on_curve(), TraderError::NotCalledByProgram);
+
+        initialize_trader_config(
+            &mut ctx.accounts.trader_config,
+            authority,
+            Pubkey::default(),
+            owner_program,
+            price_threshold,
+            swap_amount,
+            slippage_tolerance,
+        )?;
+
+        emit!(ProgramStrategyCreated {
+            trader: ctx.accounts.trader_config.key(),
+            authority,
+            owner_program,
+        });
+        msg!("✅ Strategy created for program {}", owner_program);
+        Ok(())
+    }
+
+    /// Execute secure trade with comprehensive validation
+    /// With `dry_run`, run every check up to the Raydium CPI and emit a
+    /// `TradePreview` quote instead of trading; nothing is written.
+    /// A keeper may pass a `nonce` naming the trigger it executes: it must
+    /// exceed the last one executed, so racing keepers execute it once.
+    /// A strategy with a price aggregator takes its source feeds as
+    /// remaining accounts.
+    pub fn execute_trade<'info>(
+        ctx: Context<'_, '_, '_, 'info, ExecuteTrade<'info>>,
+        amount: u64,
+        dry_run: bool,
+        nonce: Option<u64>,
+    ) -> Result<()> {
+        msg!("🚀 Starting secure trade execution through Jupiter...");
+
+        // ✅ CHECKS: Validate inputs and authorization
+        require!(amount > 0, TraderError::InvalidSwapAmount);
+        require!(amount <= ctx.accounts.user_source_token_account.amount, TraderError::InsufficientBalance);
+        require!(
+            ctx.accounts.user_authority.key() == ctx.accounts.trader_config.authority,
+            TraderError::Unauthorized
+        );
+        require!(ctx.accounts.trader_config.is_active, TraderError::TraderInactive);
+        require!(
+            !ctx.accounts.trader_config.auto_compound || amount == ctx.accounts.trader_config.swap_amount,
+            TraderError::InvalidSwapAmount
+        );
+        // A trigger already executed under this nonce, e.g. by a racing keeper
+        if let Some(nonce) = nonce {
+            require!(nonce > ctx.accounts.trader_config.last_nonce, TraderError::NonceAlreadyUsed);
+        }
+        require_trusted_transaction(&ctx.accounts.instructions, &ctx.accounts.trader_config.owner_program, &[])?;
+        
+        // ✅ CHECKS: Rate limiting (1 minute cooldown)
+        let clock = Clock::get()?;
+        let expires_at = ctx.accounts.trader_config.subscription_expires_at;
+        require!(
+            expires_at == 0 || clock.unix_timestamp < expires_at,
+            TraderError::SubscriptionExpired
+        );
+        require!(ctx.accounts.trader_config.cooldown_elapsed(&clock), TraderError::RateLimited);
+        require!(
+            ctx.accounts.trader_config.schedule.is_open(clock.unix_timestamp),
+            TraderError::OutsideTradingHours
+        );
+        require!(
+            ctx.accounts.trader_config.trade_cap.allows(clock.unix_timestamp),
+            TraderError::DailyTradeCapReached
+        );
+        
+        // ✅ CHECKS: Token account ownership validation
+        require!(
+            ctx.accounts.user_source_token_account.owner == ctx.accounts.user_authority.key(),
+            TraderError::InvalidTokenAccount
+        );
+        
+        // ✅ CHECKS: Fetch and validate oracle price
+        let (price_data, _) = strategy_price(
+            &ctx.accounts.trader_config,
+            &ctx.accounts.vectai_oracle_program,
+            &ctx.accounts.price_feed,
+            ctx.remaining_accounts,
+        )?;
+        require!(
+            ctx.accounts.trader_config.price_fresh(price_data.slot, &clock),
+            TraderError::StalePrice
+        );
+
+        msg!("📊 Oracle price received: {} (confidence: {})", price_data.price, price_data.conf);
+
+        // ✅ CHECKS: Drawdown limit, marking the strategy at the oracle price.
+        // Balances that changed since the last mark were deposited or
+        // withdrawn by the owner, so the peak is rebased on them first.
+        require!(!ctx.accounts.trader_config.risk.halted, TraderError::DrawdownLimitExceeded);
+        let source_is_usdc = ctx.accounts.user_source_token_account.mint == ctx.accounts.network_config.usdc_mint;
+        let mark_equity = |source: u64, destination: u64| -> Result<u64> {
+            source
+                .checked_add(value_in_source_units(destination, source_is_usdc, price_data.price, price_data.expo)?)
+                .ok_or_else(|| TraderError::MathOverflow.into())
+        };
+        let source_balance = ctx.accounts.user_source_token_account.amount;
+        let destination_balance = ctx.accounts.user_destination_token_account.amount;
+        let equity = mark_equity(source_balance, destination_balance)?;
+        let expected = mark_equity(
+            ctx.accounts.trader_config.marked_source_balance,
+            ctx.accounts.trader_config.marked_destination_balance,
+        )?;
+        let mut risk = ctx.accounts.trader_config.risk.clone();
+        risk.rebase(expected, equity);
+        let peak_equity = risk.peak_equity;
+        let halted = risk.mark(equity);
+        if dry_run {
+            require!(!halted, TraderError::DrawdownLimitExceeded);
+        } else {
+            let trader_config = &mut ctx.accounts.trader_config;
+            trader_config.risk = risk;
+            trader_config.marked_source_balance = source_balance;
+            trader_config.marked_destination_balance = destination_balance;
+        }
+        if halted {
+            // Persist the halt instead of failing, which would roll it back
+            let risk = &ctx.accounts.trader_config.risk;
+            emit!(DrawdownLimitHit {
+                account: ctx.accounts.trader_config.key(),
+                peak_equity,
+                equity,
+                drawdown_bps: risk.last_drawdown_bps,
+                max_drawdown_bps: risk.max_drawdown_bps,
+            });
+            msg!("🛑 Drawdown {} bps exceeds limit {} bps, trading halted",
+                 risk.last_drawdown_bps, risk.max_drawdown_bps);
+            return Ok(());
+        }
+
+        // ✅ CHECKS: Trigger condition
+        let trader_config = &ctx.accounts.trader_config;
+        check_trigger(trader_config, price_data.price, clock.unix_timestamp)?;
+
+        // ✅ CHECKS: Slippage, from the oracle confidence when dynamic
+        let slippage_bps = trader_config.dynamic_slippage.slippage_bps(
+            trader_config.slippage_tolerance,
+            price_data.price,
+            price_data.conf,
+            MAX_SLIPPAGE_BPS,
+        )?;
+
+        // ✅ CHECKS: Volatility filter; like a drawdown halt the skip is
+        // persisted, so the window keeps sampling while the price is unsettled
+        if dry_run {
+            let mut volatility = ctx.accounts.trader_config.volatility;
+            require!(
+                volatility.observe(price_data.price, clock.unix_timestamp).is_none(),
+                TraderError::VolatilityTooHigh
+            );
+        } else if let Some(volatility_bps) =
+            ctx.accounts.trader_config.volatility.observe(price_data.price, clock.unix_timestamp)
+        {
+            let max_volatility_bps = ctx.accounts.trader_config.volatility.max_volatility_bps;
+            emit!(VolatilityLimitHit {
+                trader: ctx.accounts.trader_config.key(),
+                volatility_bps,
+                max_volatility_bps,
+                oracle_price: price_data.price,
+                timestamp: clock.unix_timestamp,
+            });
+            msg!("🌊 Volatility {} bps exceeds limit {} bps, execution skipped",
+                 volatility_bps, max_volatility_bps);
+            return Ok(());
+        }
+
+        // ✅ Dry run: quote the trade and stop before any transfer
+        if dry_run {
+            let trade_fee = FeeConfig::apply(amount, ctx.accounts.protocol_status.fee_config.trade_fee_bps);
+            if trade_fee > 0 {
+                require!(ctx.accounts.protocol_fee_account.is_some(), TraderError::FeeAccountRequired);
+            }
+            let (expected_out, minimum_out) = quote_raydium_swap(&ctx, amount - trade_fee, slippage_bps)?;
+            emit!(TradePreview {
+                trader: ctx.accounts.trader_config.key(),
+                source_mint: ctx.accounts.user_source_token_account.mint,
+                destination_mint: ctx.accounts.user_destination_token_account.mint,
+                amount_in: amount,
+                trade_fee,
+                expected_out,
+                minimum_out,
+                oracle_price: price_data.price,
+                timestamp: clock.unix_timestamp,
+            });
+            msg!("🔍 Dry run: {} in, {} expected out (minimum {})", amount, expected_out, minimum_out);
+            return Ok(());
+        }
+
+        // ✅ EFFECTS: Update state before external calls (CEI pattern)
+        ctx.accounts.trader_config.total_swaps = ctx.accounts.trader_config
+            .total_swaps
+            .checked_add(1)
+            .ok_or(TraderError::MathOverflow)?;
+        ctx.accounts.trader_config.record_execution(&clock);
+        ctx.accounts.trader_config.trade_cap.record(clock.unix_timestamp);
+        if let Some(nonce) = nonce {
+            ctx.accounts.trader_config.last_nonce = nonce;
+        }
+
+        // ✅ INTERACTIONS: Protocol trade fee from the governed fee switch
+        let trade_fee = charge_trade_fee(&ctx, amount)?;
+
+        // ✅ INTERACTIONS: Execute Raydium swap
+        let balance_before = ctx.accounts.user_destination_token_account.amount;
+        let swap_result = execute_raydium_swap_with_validation(
+            &ctx,
+            amount - trade_fee,
+            price_data.price,
+            slippage_bps,
+        )?;
+
+        // ✅ Protocol stats: count the execution and its swapped input
+        vectai_protocol::report(
+            ctx.accounts.protocol_program.to_account_info(),
+            ctx.accounts.protocol_stats.to_account_info(),
+            ctx.accounts.stats_reporter.to_account_info(),
+            ctx.bumps.stats_reporter,
+            ActivityKind::Execution,
+            ctx.accounts.user_source_token_account.mint,
+            amount - trade_fee,
+        )?;
+
+        // ✅ PnL: both legs marked to USD, the trade fee counted as spent
+        ctx.accounts.user_source_token_account.reload()?;
+        ctx.accounts.user_destination_token_account.reload()?;
+        let output = ctx
+            .accounts
+            .user_destination_token_account
+            .amount
+            .checked_sub(balance_before)
+            .ok_or(TraderError::MathOverflow)?;
+        // Checked here too rather than trusting the pool's own minimum
+        require!(output >= swap_result.minimum_output, TraderError::SlippageExceeded);
+        // The trade's own balance changes are not a deposit or withdrawal
+        ctx.accounts.trader_config.marked_source_balance = ctx.accounts.user_source_token_account.amount;
+        ctx.accounts.trader_config.marked_destination_balance = ctx.accounts.user_destination_token_account.amount;
+        realize_pnl(
+            &mut ctx.accounts.trader_config,
+            ExecutionLegs {
+                source_mint: ctx.accounts.user_source_token_account.mint,
+                amount_in: amount,
+                destination_mint: ctx.accounts.user_destination_token_account.mint,
+                amount_out: output,
+            },
+            &ctx.accounts.network_config.usdc_mint,
+            price_data.price,
+            price_data.expo,
+        )?;
+
+        emit!(TradeExecuted {
+            trader: ctx.accounts.trader_config.key(),
+            source_mint: ctx.accounts.user_source_token_account.mint,
+            destination_mint: ctx.accounts.user_destination_token_account.mint,
+            amount_in: amount,
+            trade_fee,
+            expected_out: swap_result.output_amount,
+            oracle_price: price_data.price,
+            timestamp: clock.unix_timestamp,
+        });
+        // ✅ Trade history: append the trade to the compressed tree when passed
+        if let Some(trade_history) = ctx.accounts.trade_history.as_mut() {
+            let (Some(merkle_tree), Some(compression_program), Some(noop_program)) = (
+                ctx.accounts.trade_history_tree.as_ref(),
+                ctx.accounts.compression_program.as_ref(),
+                ctx.accounts.noop_program.as_ref(),
+            ) else {
+                return err!(TraderError::InvalidTradeHistory);
+            };
+            require_keys_eq!(merkle_tree.key(), trade_history.merkle_tree, TraderError::InvalidTradeHistory);
+            require!(
+                trade_history.record_count < trade_history.capacity(),
+                TraderError::TradeHistoryFull
+            );
+
+            let trader = ctx.accounts.trader_config.key();
+            let record = TradeRecord {
+                trader,
+                index: trade_history.record_count,
+                source_mint: ctx.accounts.user_source_token_account.mint,
+                destination_mint: ctx.accounts.user_destination_token_account.mint,
+                amount_in: amount,
+                trade_fee,
+                expected_out: swap_result.output_amount,
+                oracle_price: price_data.price,
+                timestamp: clock.unix_timestamp,
+            };
+            trade_history.record_count += 1;
+
+            let seeds: &[&[u8]] = &[b"trade-history", trader.as_ref(), &[trade_history.bump]];
+            append_record(
+                &record,
+                &trade_history.to_account_info(),
+                &merkle_tree.to_account_info(),
+                &compression_program.to_account_info(),
+                &noop_program.to_account_info(),
+                &[seeds],
+            )?;
+            msg!("🌳 Trade recorded at leaf {}", record.index);
+        }
+
+        msg!("✅ Trade executed successfully!");
+        msg!("   Input: {} tokens", amount);
+        msg!("   Output: {} tokens", swap_result.output_amount);
+        msg!("   Exchange rate: {}", swap_result.exchange_rate);
+        msg!("   Total swaps: {}", ctx.accounts.trader_config.total_swaps);
+
+        // ✅ Points: credit executed volume in USDC terms, capped per pair per day
+        if let (Some(ledger), Some(config)) = (
+            ctx.accounts.points_ledger.as_mut(),
+            ctx.accounts.points_config.as_mut(),
+        ) {
+            let source_mint = ctx.accounts.user_source_token_account.mint;
+            let volume = if source_mint == ctx.accounts.network_config.usdc_mint {
+                amount
+            } else {
+                // Lamports valued in USDC units
+                value_in_source_units(amount, true, price_data.price, price_data.expo)?
+            };
+            let points = ledger.credit(
+                source_mint,
+                ctx.accounts.user_destination_token_account.mint,
+                config.points_for_volume(volume)?,
+                config.daily_pair_cap,
+                clock.unix_timestamp,
+            )?;
+            config.total_points_issued = config
+                .total_points_issued
+                .checked_add(points)
+                .ok_or(TraderError::MathOverflow)?;
+
+            if points > 0 {
+                emit!(PointsCredited {
+                    trader: ctx.accounts.trader_config.key(),
+                    volume,
+                    points,
+                    balance: ledger.balance,
+                });
+                msg!("⭐ Credited {} points for {} volume", points, volume);
+            }
+        }
+
+        // ✅ Auto-compound: grow swap_amount by the realized gain of a winning trade
+        if ctx.accounts.trader_config.auto_compound {
+            let output_value = value_in_source_units(
+                output,
+                ctx.accounts.user_source_token_account.mint == ctx.accounts.network_config.usdc_mint,
+                price_data.price,
+                price_data.expo,
+            )?;
+
+            if output_value > amount {
+                let gain = output_value - amount;
+                let trader_config = &mut ctx.accounts.trader_config;
+                trader_config.swap_amount = trader_config
+                    .swap_amount
+                    .checked_add(gain)
+                    .ok_or(TraderError::MathOverflow)?
+                    .min(MAX_SWAP_AMOUNT);
+                trader_config.total_compounded = trader_config
+                    .total_compounded
+                    .checked_add(gain)
+                    .ok_or(TraderError::MathOverflow)?;
+
+                emit!(TradeCompounded {
+                    trader: trader_config.key(),
+                    gain,
+                    swap_amount: trader_config.swap_amount,
+                });
+                msg!("📈 Compounded {} gain, next swap amount {}", gain, trader_config.swap_amount);
+            }
+        }
+
+        // ✅ Profit target: complete the strategy once this trade reaches it
+        complete_at_profit_target(
+            ctx.accounts,
+            ctx.bumps.stats_reporter,
+            price_data.price,
+            price_data.expo,
+            slippage_bps,
+        )?;
+        
+        Ok(())
+    }
+
+    /// Scale out of the position down the strategy's take-profit ladder:
+    /// every unfilled rung the oracle price has reached fills in one swap
+    /// from the position (source) back to the quote token (destination).
+    /// Exits stay open through cooldowns, schedules and drawdown halts.
+    pub fn execute_take_profit<'info>(ctx: Context<'_, '_, '_, 'info, ExecuteTrade<'info>>) -> Result<()> {
+        // ✅ CHECKS: Authorization and the position account
+        require!(
+            ctx.accounts.user_authority.key() == ctx.accounts.trader_config.authority,
+            TraderError::Unauthorized
+        );
+        require!(ctx.accounts.trader_config.is_active, TraderError::TraderInactive);
+        require!(
+            ctx.accounts.user_source_token_account.owner == ctx.accounts.user_authority.key(),
+            TraderError::InvalidTokenAccount
+        );
+        require_trusted_transaction(&ctx.accounts.instructions, &ctx.accounts.trader_config.owner_program, &[])?;
+        let clock = Clock::get()?;
+
+        let (price_data, _) = strategy_price(
+            &ctx.accounts.trader_config,
+            &ctx.accounts.vectai_oracle_program,
+            &ctx.accounts.price_feed,
+            ctx.remaining_accounts,
+        )?;
+        require!(ctx.accounts.trader_config.price_fresh(price_data.slot, &clock), TraderError::StalePrice);
+
+        // ✅ EFFECTS: Mark the reached rungs filled before the swap
+        let position = ctx.accounts.user_source_token_account.amount;
+        let (amount, rungs_filled) = ctx
+            .accounts
+            .trader_config
+            .take_profit
+            .fill(price_data.price, position)
+            .ok_or(TraderError::TakeProfitNotReached)?;
+        require!(amount > 0, TraderError::InsufficientBalance);
+        let trader_config = &ctx.accounts.trader_config;
+        let slippage_bps = trader_config.dynamic_slippage.slippage_bps(
+            trader_config.slippage_tolerance,
+            price_data.price,
+            price_data.conf,
+            MAX_SLIPPAGE_BPS,
+        )?;
+
+        // ✅ INTERACTIONS: Fee, then the swap out of the position
+        let trade_fee = charge_trade_fee(&ctx, amount)?;
+        let balance_before = ctx.accounts.user_destination_token_account.amount;
+        let swap_result = execute_raydium_swap_with_validation(
+            &ctx,
+            amount - trade_fee,
+            price_data.price,
+            slippage_bps,
+        )?;
+        vectai_protocol::report(
+            ctx.accounts.protocol_program.to_account_info(),
+            ctx.accounts.protocol_stats.to_account_info(),
+            ctx.accounts.stats_reporter.to_account_info(),
+            ctx.bumps.stats_reporter,
+            ActivityKind::Execution,
+            ctx.accounts.user_source_token_account.mint,
+            amount - trade_fee,
+        )?;
+        ctx.accounts.user_destination_token_account.reload()?;
+        let output = ctx
+            .accounts
+            .user_destination_token_account
+            .amount
+            .checked_sub(balance_before)
+            .ok_or(TraderError::MathOverflow)?;
+        // Checked here too rather than trusting the pool's own minimum
+        require!(output >= swap_result.minimum_output, TraderError::SlippageExceeded);
+        realize_pnl(
+            &mut ctx.accounts.trader_config,
+            ExecutionLegs {
+                source_mint: ctx.accounts.user_source_token_account.mint,
+                amount_in: amount,
+                destination_mint: ctx.accounts.user_destination_token_account.mint,
+                amount_out: output,
+            },
+            &ctx.accounts.network_config.usdc_mint,
+            price_data.price,
+            price_data.expo,
+        )?;
+
+        emit!(TakeProfitFilled {
+            trader: ctx.accounts.trader_config.key(),
+            rungs_filled,
+            amount_in: amount,
+            trade_fee,
+            expected_out: swap_result.output_amount,
+            oracle_price: price_data.price,
+            timestamp: clock.unix_timestamp,
+        });
+        msg!("🎯 Take-profit: {} rung(s) filled, {} sold at {}", rungs_filled, amount, price_data.price);
+
+        complete_at_profit_target(
+            ctx.accounts,
+            ctx.bumps.stats_reporter,
+            price_data.price,
+            price_data.expo,
+            slippage_bps,
+        )?;
+        Ok(())
+    }
+
+    /// `execute_trade` through an off-chain Jupiter route instead of the
+    /// whitelisted pool, for the aggregator's pricing. `route_data` is the
+    /// client's Jupiter `route` instruction data, its accounts follow as
+    /// remaining accounts, after the price aggregator's sources if the
+    /// strategy has one; the route must spend `amount` (less the trade
+    /// fee) from the strategy's source account into its destination account
+    /// on the network's pair, with no platform fee and no more slippage than
+    /// the strategy allows, and its minimum output is enforced again here.
+    pub fn execute_jupiter_route<'info>(
+        ctx: Context<'_, '_, 'info, 'info, ExecuteJupiterRoute<'info>>,
+        amount: u64,
+        route_data: Vec<u8>,
+    ) -> Result<()> {
+        // ✅ CHECKS: The same gates as `execute_trade`
+        let trader_config = &ctx.accounts.trader_config;
+        let source = &ctx.accounts.user_source_token_account;
+        let destination = &ctx.accounts.user_destination_token_account;
+        require!(amount > 0, TraderError::InvalidSwapAmount);
+        require!(amount <= source.amount, TraderError::InsufficientBalance);
+        require!(
+            ctx.accounts.user_authority.key() == trader_config.authority,
+            TraderError::Unauthorized
+        );
+        require!(trader_config.is_active, TraderError::TraderInactive);
+        require!(
+            !trader_config.auto_compound || amount == trader_config.swap_amount,
+            TraderError::InvalidSwapAmount
+        );
+        require!(source.owner == trader_config.authority, TraderError::InvalidTokenAccount);
+        require!(destination.owner == trader_config.authority, TraderError::InvalidTokenAccount);
+        require!(
+            ctx.accounts.network_config.is_supported_pair(source.mint, destination.mint),
+            TraderError::InvalidTokenPair
+        );
+        require_trusted_transaction(&ctx.accounts.instructions, &trader_config.owner_program, &[JUPITER_V6_PROGRAM])?;
+
+        let clock = Clock::get()?;
+        let expires_at = trader_config.subscription_expires_at;
+        require!(
+            expires_at == 0 || clock.unix_timestamp < expires_at,
+            TraderError::SubscriptionExpired
+        );
+        require!(trader_config.cooldown_elapsed(&clock), TraderError::RateLimited);
+        require!(trader_config.schedule.is_open(clock.unix_timestamp), TraderError::OutsideTradingHours);
+        require!(trader_config.trade_cap.allows(clock.unix_timestamp), TraderError::DailyTradeCapReached);
+        require!(!trader_config.risk.halted, TraderError::DrawdownLimitExceeded);
+
+        let (price_data, route_accounts) = strategy_price(
+            trader_config,
+            &ctx.accounts.vectai_oracle_program,
+            &ctx.accounts.price_feed,
+            ctx.remaining_accounts,
+        )?;
+        require!(trader_config.price_fresh(price_data.slot, &clock), TraderError::StalePrice);
+        check_trigger(trader_config, price_data.price, clock.unix_timestamp)?;
+        let slippage_bps = trader_config.dynamic_slippage.slippage_bps(
+            trader_config.slippage_tolerance,
+            price_data.price,
+            price_data.conf,
+            MAX_SLIPPAGE_BPS,
+        )?;
+
+        // ✅ CHECKS: The route spends and pays the strategy's own accounts
+        let trade_fee = FeeConfig::apply(amount, ctx.accounts.protocol_status.fee_config.trade_fee_bps);
+        let route = JupiterRoute::parse(&route_data).ok_or(TraderError::InvalidJupiterRoute)?;
+        verify_jupiter_route(ctx.accounts, route_accounts, &route, amount - trade_fee, slippage_bps)?;
+        let minimum_out = route.minimum_out()?;
+
+        // ✅ EFFECTS
+        let trader_config = &mut ctx.accounts.trader_config;
+        trader_config.total_swaps = trader_config.total_swaps.checked_add(1).ok_or(TraderError::MathOverflow)?;
+        trader_config.record_execution(&clock);
+        trader_config.trade_cap.record(clock.unix_timestamp);
+
+        // ✅ INTERACTIONS: Trade fee, then the route
+        if trade_fee > 0 {
+            let fee_account = ctx
+                .accounts
+                .protocol_fee_account
+                .as_ref()
+                .ok_or(TraderError::FeeAccountRequired)?;
+            token::transfer(
+                CpiContext::new(
+                    ctx.accounts.token_program.to_account_info(),
+                    Transfer {
+                        from: ctx.accounts.user_source_token_account.to_account_info(),
+                        to: fee_account.to_account_info(),
+                        authority: ctx.accounts.user_authority.to_account_info(),
+                    },
+                ),
+                trade_fee,
+            )?;
+        }
+        let balance_before = ctx.accounts.user_destination_token_account.amount;
+        invoke_jupiter_route(&ctx.accounts.jupiter_program, route_accounts, route_data)?;
+        ctx.accounts.user_destination_token_account.reload()?;
+        let output = ctx
+            .accounts
+            .user_destination_token_account
+            .amount
+            .checked_sub(balance_before)
+            .ok_or(TraderError::MathOverflow)?;
+        require!(output >= minimum_out, TraderError::SlippageExceeded);
+
+        vectai_protocol::report(
+            ctx.accounts.protocol_program.to_account_info(),
+            ctx.accounts.protocol_stats.to_account_info(),
+            ctx.accounts.stats_reporter.to_account_info(),
+            ctx.bumps.stats_reporter,
+            ActivityKind::Execution,
+            ctx.accounts.user_source_token_account.mint,
+            amount - trade_fee,
+        )?;
+        realize_pnl(
+            &mut ctx.accounts.trader_config,
+            ExecutionLegs {
+                source_mint: ctx.accounts.user_source_token_account.mint,
+                amount_in: amount,
+                destination_mint: ctx.accounts.user_destination_token_account.mint,
+                amount_out: output,
+            },
+            &ctx.accounts.network_config.usdc_mint,
+            price_data.price,
+            price_data.expo,
+        )?;
+
+        emit!(TradeExecuted {
+            trader: ctx.accounts.trader_config.key(),
+            source_mint: ctx.accounts.user_source_token_account.mint,
+            destination_mint: ctx.accounts.user_destination_token_account.mint,
+            amount_in: amount,
+            trade_fee,
+            expected_out: output,
+            oracle_price: price_data.price,
+            timestamp: clock.unix_timestamp,
+        });
+        msg!("✅ Jupiter route executed: {} in, {} out (minimum {})", amount, output, minimum_out);
+
+        // ✅ Profit target: the route's accounts can't sell the position, so
+        // the strategy stops with it in place
+        let trader_config = &mut ctx.accounts.trader_config;
+        if trader_config.profit_target_reached() {
+            trader_config.is_active = false;
+            emit!(StrategyCompleted {
+                trader: trader_config.key(),
+                profit_target_usd: trader_config.profit_target_usd,
+                realized_pnl_usd: trader_config.realized_pnl_usd,
+                residual_sold: 0,
+                proceeds: 0,
+            });
+            msg!("🏁 Profit target {} reached, strategy completed", trader_config.profit_target_usd);
+        }
+        Ok(())
+    }
+
+    /// Tip a Jito tip account so a block engine lands the transaction's
+    /// execution of this strategy without exposing it to the public
+    /// mempool; at most the strategy's tip cap (trader authority)
+    pub fn tip_jito(ctx: Context<TipJito>, lamports: u64) -> Result<()> {
+        let trader_config = &ctx.accounts.trader_config;
+        require!(lamports > 0, TraderError::InvalidInput);
+        require!(lamports <= trader_config.max_jito_tip, TraderError::JitoTipTooHigh);
+        require!(
+            bundles_execution(&ctx.accounts.instructions, &trader_config.key())?,
+            TraderError::TipWithoutExecution
+        );
+
+        pay_jito_tip(ctx.accounts, lamports)?;
+
+        emit!(JitoTipPaid {
+            trader: trader_config.key(),
+            tip_account: ctx.accounts.tip_account.key(),
+            lamports,
+        });
+        msg!("Jito tip: {} lamports", lamports);
+        Ok(())
+    }
+
+    /// Replace the strategy's take-profit ladder with `levels`, none filled;
+    /// empty removes it (trader authority)
+    pub fn set_take_profit_ladder(ctx: Context<ConfigureTrader>, levels: Vec<TakeProfitLevel>) -> Result<()> {
+        TakeProfitLadder::validate(&levels)?;
+
+        ctx.accounts.trader_config.take_profit = TakeProfitLadder::new(&levels);
+        msg!("Take-profit ladder: {} rungs", levels.len());
+        ctx.accounts.audit_log.append(
+            ctx.accounts.authority.key(),
+            AuditAction::TakeProfitSet { levels },
+            Clock::get()?.unix_timestamp,
+        )?;
+        Ok(())
+    }
+
+    /// Replace the strategy's trigger with a `vectai_math::condition`
+    /// program, or clear it to fall back to `price_threshold` (trader
+    /// authority)
+    pub fn set_strategy_condition(ctx: Context<ConfigureTrader>, program: Vec<u8>) -> Result<()> {
+        require!(program.len() <= MAX_CONDITION_LEN, TraderError::InvalidCondition);
+        if !program.is_empty() {
+            condition::validate(&program).map_err(|_| TraderError::InvalidCondition)?;
+        }
+
+        msg!("Strategy condition: {} bytes", program.len());
+        ctx.accounts.audit_log.append(
+            ctx.accounts.authority.key(),
+            AuditAction::ConditionSet { program: program.clone() },
+            Clock::get()?.unix_timestamp,
+        )?;
+        ctx.accounts.trader_config.condition = program;
+        Ok(())
+    }
+
+    /// Toggle auto-compounding of trade gains into `swap_amount` (trader authority)
+    pub fn set_auto_compound(ctx: Context<ConfigureTrader>, enabled: bool) -> Result<()> {
+        ctx.accounts.trader_config.auto_compound = enabled;
+        ctx.accounts.audit_log.append(
+            ctx.accounts.authority.key(),
+            AuditAction::AutoCompoundSet { enabled },
+            Clock::get()?.unix_timestamp,
+        )?;
+        msg!("Auto-compound: {}", enabled);
+        Ok(())
+    }
+
+    /// Restrict executions to a weekly UTC window, or clear the schedule
+    /// with an all-zero one (trader authority)
+    pub fn set_trading_schedule(ctx: Context<ConfigureTrader>, schedule: TradingSchedule) -> Result<()> {
+        require!(schedule.is_valid(), TraderError::InvalidInput);
+
+        ctx.accounts.trader_config.schedule = schedule;
+        ctx.accounts.audit_log.append(
+            ctx.accounts.authority.key(),
+            AuditAction::TradingScheduleSet { schedule },
+            Clock::get()?.unix_timestamp,
+        )?;
+        msg!(
+            "Trading schedule: days {:#09b}, {} to {} UTC",
+            schedule.days,
+            schedule.start_minute,
+            schedule.end_minute
+        );
+        Ok(())
+    }
+
+    /// Derive each execution's slippage from the oracle confidence, or
+    /// return to the fixed `slippage_tolerance` with a zero factor (trader
+    /// authority)
+    pub fn set_dynamic_slippage(ctx: Context<ConfigureTrader>, dynamic_slippage: DynamicSlippage) -> Result<()> {
+        require!(dynamic_slippage.base_bps as u64 <= MAX_SLIPPAGE_BPS, TraderError::InvalidInput);
+
+        ctx.accounts.trader_config.dynamic_slippage = dynamic_slippage;
+        ctx.accounts.audit_log.append(
+            ctx.accounts.authority.key(),
+            AuditAction::DynamicSlippageSet { dynamic_slippage },
+            Clock::get()?.unix_timestamp,
+        )?;
+        msg!(
+            "Dynamic slippage: {} bps + {} bps of conf/price",
+            dynamic_slippage.base_bps,
+            dynamic_slippage.conf_factor_bps
+        );
+        Ok(())
+    }
+
+    /// Skip executions while the standard deviation of recent oracle
+    /// prices exceeds `max_volatility_bps` of their mean, or turn the filter
+    /// off with 0; either way the window starts over (trader authority)
+    pub fn set_volatility_filter(ctx: Context<ConfigureTrader>, max_volatility_bps: u16) -> Result<()> {
+        require!(max_volatility_bps as u64 <= BPS_DENOMINATOR, TraderError::InvalidInput);
+
+        ctx.accounts.trader_config.volatility.reset(max_volatility_bps);
+        ctx.accounts.audit_log.append(
+            ctx.accounts.authority.key(),
+            AuditAction::VolatilityFilterSet { max_volatility_bps },
+            Clock::get()?.unix_timestamp,
+        )?;
+        msg!("Volatility filter: {} bps", max_volatility_bps);
+        Ok(())
+    }
+
+    /// Cap executions in any rolling 24 hours, or lift the cap with 0;
+    /// executions already counted stay in the window (trader authority)
+    pub fn set_daily_trade_cap(ctx: Context<ConfigureTrader>, max_trades: u16) -> Result<()> {
+        ctx.accounts.trader_config.trade_cap.max_trades = max_trades;
+        ctx.accounts.audit_log.append(
+            ctx.accounts.authority.key(),
+            AuditAction::DailyTradeCapSet { max_trades },
+            Clock::get()?.unix_timestamp,
+        )?;
+        msg!("Daily trade cap: {}", max_trades);
+        Ok(())
+    }
+
+    /// Cap the Jito tip one execution may pay, in lamports; 0 disables
+    /// tips (trader authority)
+    pub fn set_jito_tip_cap(ctx: Context<ConfigureTrader>, max_tip_lamports: u64) -> Result<()> {
+        ctx.accounts.trader_config.max_jito_tip = max_tip_lamports;
+        ctx.accounts.audit_log.append(
+            ctx.accounts.authority.key(),
+            AuditAction::JitoTipCapSet { max_tip_lamports },
+            Clock::get()?.unix_timestamp,
+        )?;
+        msg!("Jito tip cap: {} lamports", max_tip_lamports);
+        Ok(())
+    }
+
+    /// Space executions `cooldown_slots` slots apart instead of 60 seconds,
+    /// or return to the 60 seconds with 0 (trader authority)
+    pub fn set_slot_cooldown(ctx: Context<ConfigureTrader>, cooldown_slots: u64) -> Result<()> {
+        require!(cooldown_slots <= MAX_COOLDOWN_SLOTS, TraderError::InvalidInput);
+
+        ctx.accounts.trader_config.cooldown_slots = cooldown_slots;
+        ctx.accounts.audit_log.append(
+            ctx.accounts.authority.key(),
+            AuditAction::SlotCooldownSet { cooldown_slots },
+            Clock::get()?.unix_timestamp,
+        )?;
+        msg!("Slot cooldown: {} slots", cooldown_slots);
+        Ok(())
+    }
+
+    /// Refuse executions on oracle prices observed more than
+    /// `max_price_age_slots` slots ago, or accept any with 0 (trader
+    /// authority)
+    pub fn set_max_price_age_slots(ctx: Context<ConfigureTrader>, max_price_age_slots: u64) -> Result<()> {
+        require!(max_price_age_slots <= MAX_PRICE_AGE_SLOTS, TraderError::InvalidInput);
+
+        ctx.accounts.trader_config.max_price_age_slots = max_price_age_slots;
+        ctx.accounts.audit_log.append(
+            ctx.accounts.authority.key(),
+            AuditAction::MaxPriceAgeSlotsSet { max_price_age_slots },
+            Clock::get()?.unix_timestamp,
+        )?;
+        msg!("Max price age: {} slots", max_price_age_slots);
+        Ok(())
+    }
+
+    /// Price executions from `price_aggregator`, a vectai_oracle
+    /// `PriceAggregator`, refusing them while its sources are more than
+    /// `max_source_deviation_bps` apart; the default key prices from
+    /// `price_feed` alone (trader authority)
+    pub fn set_price_aggregator(
+        ctx: Context<ConfigureTrader>,
+        price_aggregator: Pubkey,
+        max_source_deviation_bps: u16,
+    ) -> Result<()> {
+        require!(max_source_deviation_bps as u64 <= BPS_DENOMINATOR, TraderError::InvalidInput);
+
+        let trader_config = &mut ctx.accounts.trader_config;
+        trader_config.price_aggregator = price_aggregator;
+        trader_config.max_source_deviation_bps = max_source_deviation_bps;
+        ctx.accounts.audit_log.append(
+            ctx.accounts.authority.key(),
+            AuditAction::PriceAggregatorSet { price_aggregator, max_source_deviation_bps },
+            Clock::get()?.unix_timestamp,
+        )?;
+        msg!("Price aggregator: {} (max deviation {} bps)", price_aggregator, max_source_deviation_bps);
+        Ok(())
+    }
+
+    /// Complete the strategy once its realized PnL reaches
+    /// `profit_target_usd` (USDC base units), or run it open-ended with 0
+    /// (trader authority)
+    pub fn set_profit_target(ctx: Context<ConfigureTrader>, profit_target_usd: u64) -> Result<()> {
+        ctx.accounts.trader_config.profit_target_usd = profit_target_usd;
+        ctx.accounts.audit_log.append(
+            ctx.accounts.authority.key(),
+            AuditAction::ProfitTargetSet { profit_target_usd },
+            Clock::get()?.unix_timestamp,
+        )?;
+        msg!("Profit target: {} USDC units", profit_target_usd);
+        Ok(())
+    }
+
+    /// Bring a trader config written by an older build up to the current
+    /// layout; the trader authority pays any extra rent
+    pub fn migrate_trader_config(ctx: Context<MigrateTraderConfig>) -> Result<()> {
+        let from = vectai_common::migrate::<TraderConfig>(
+            &ctx.accounts.trader_config.to_account_info(),
+            &ctx.accounts.authority.to_account_info(),
+            &ctx.accounts.system_program.to_account_info(),
+        )?;
+        msg!("Trader config migrated from v{} to v{}", from, TraderConfig::VERSION);
+        Ok(())
+    }
+
+    /// View: the trader config, returned to CPI callers in its account
+    /// layout (without the discriminator)
+    pub fn get_trader_config(ctx: Context<GetTraderConfig>) -> Result<TraderConfig> {
+        Ok((*ctx.accounts.trader_config).clone())
+    }
+
+    /// Close the strategy, with its audit log and trade history when
+    /// passed, and return the rent (trader authority)
+    pub fn close_trader(_ctx: Context<CloseTrader>) -> Result<()> {
+        msg!("✅ Trader config closed");
+        Ok(())
+    }
+
+    /// Open the strategy's hash-chained audit log; required by every
+    /// configuration change and pause from then on (trader authority)
+    pub fn initialize_audit_log(ctx: Context<InitializeAuditLog>) -> Result<()> {
+        let audit_log = &mut ctx.accounts.audit_log;
+        audit_log.trader = ctx.accounts.trader_config.key();
+        audit_log.head = [0u8; 32];
+        audit_log.entry_count = 0;
+        audit_log.bump = ctx.bumps.audit_log;
+
+        msg!("✅ Audit log opened");
+        Ok(())
+    }
+
+    /// Follow a lead trader: copy each of its trades at `ratio_bps`
+    ///
+    /// The follower must approve the follower config PDA as SPL delegate on
+    /// the source token account for copies to execute.
+    pub fn follow_trader(
+        ctx: Context<FollowTrader>,
+        ratio_bps: u16,
+        max_trade_amount: u64,
+        slippage_tolerance: u64,
+        lead_fee_bps: u16,
     ) -> Result<()> {
         // ✅ CHECKS: Validate inputs
+        require!(ratio_bps > 0 && ratio_bps as u64 <= BPS_DENOMINATOR, TraderError::InvalidInput);
+        require!(max_trade_amount > 0, TraderError::InvalidInput);
+        require!(slippage_tolerance <= MAX_SLIPPAGE_BPS, TraderError::InvalidInput);
+        require!(lead_fee_bps <= MAX_LEAD_FEE_BPS, TraderError::InvalidInput);
+        require!(
+            ctx.accounts.lead_config.authority != ctx.accounts.follower.key(),
+            TraderError::InvalidInput
+        );
+
+        let follower_config = &mut ctx.accounts.follower_config;
+        follower_config.follower = ctx.accounts.follower.key();
+        follower_config.lead = ctx.accounts.lead_config.key();
+        follower_config.ratio_bps = ratio_bps;
+        follower_config.max_trade_amount = max_trade_amount;
+        follower_config.slippage_tolerance = slippage_tolerance;
+        follower_config.lead_fee_bps = lead_fee_bps;
+        follower_config.last_copied_swap = ctx.accounts.lead_config.total_swaps;
+        follower_config.total_copied = 0;
+        follower_config.bump = ctx.bumps.follower_config;
+
+        msg!("✅ Following {} at {} bps (lead fee {} bps)",
+             follower_config.lead, ratio_bps, lead_fee_bps);
+        Ok(())
+    }
+
+    /// Stop following a lead trader
+    pub fn unfollow_trader(_ctx: Context<UnfollowTrader>) -> Result<()> {
+        msg!("✅ Follower config closed");
+        Ok(())
+    }
+
+    /// Keeper: execute the follower's proportional trade after the lead's
+    /// `execute_trade` earlier in the same transaction
+    pub fn copy_trade<'info>(ctx: Context<'_, '_, '_, 'info, CopyTrade<'info>>) -> Result<()> {
+        // ✅ CHECKS: Lead trade must be in this transaction and not yet copied
+        require!(ctx.accounts.lead_config.is_active, TraderError::TraderInactive);
+        let lead_trade = find_lead_trade(
+            &ctx.accounts.instructions.to_account_info(),
+            &ctx.accounts.lead_config.key(),
+        )?;
+        let lead_amount = lead_trade.amount;
+        require!(
+            ctx.accounts.lead_config.total_swaps > ctx.accounts.follower_config.last_copied_swap,
+            TraderError::AlreadyCopied
+        );
+
+        // ✅ CHECKS: Size the follower trade
+        let follower_config = &ctx.accounts.follower_config;
+        let amount = vectai_common::bps_of(lead_amount, follower_config.ratio_bps as u64)
+            .min(follower_config.max_trade_amount);
+        require!(amount > 0, TraderError::InvalidSwapAmount);
+        let lead_fee = vectai_common::bps_of(amount, follower_config.lead_fee_bps as u64);
+        let trade_fee = FeeConfig::apply(amount, ctx.accounts.protocol_status.fee_config.trade_fee_bps);
+        let swap_amount = amount
+            .checked_sub(lead_fee)
+            .and_then(|x| x.checked_sub(trade_fee))
+            .ok_or(TraderError::MathOverflow)?;
+
+        let source = &ctx.accounts.follower_source_token_account;
+        require!(amount <= source.amount, TraderError::InsufficientBalance);
+        require!(
+            source.delegate == COption::Some(follower_config.key())
+                && source.delegated_amount >= amount,
+            TraderError::DelegationRequired
+        );
+        let network = &ctx.accounts.network_config;
+        require!(
+            ctx.accounts.raydium_amm_program.key() == network.raydium_amm_program,
+            TraderError::InvalidRaydiumProgram
+        );
+        require!(
+            network.is_supported_pair(source.mint, ctx.accounts.follower_destination_token_account.mint),
+            TraderError::InvalidTokenPair
+        );
+
+        // ✅ CHECKS: Price the minimum out at the lead trade's oracle price,
+        // converting between the mints' decimals
+        require_keys_eq!(ctx.accounts.price_feed.key(), lead_trade.price_feed, TraderError::LeadPriceFeedMismatch);
+        let (price_data, _) = strategy_price(
+            &ctx.accounts.lead_config,
+            &ctx.accounts.vectai_oracle_program,
+            &ctx.accounts.price_feed,
+            ctx.remaining_accounts,
+        )?;
+        require!(
+            ctx.accounts.lead_config.price_fresh(price_data.slot, &Clock::get()?),
+            TraderError::StalePrice
+        );
+        let source_is_usdc = source.mint == network.usdc_mint;
+        let expected_output = value_in_source_units(swap_amount, !source_is_usdc, price_data.price, price_data.expo)?;
+        let minimum_output = calculate_minimum_amount_out(expected_output, follower_config.slippage_tolerance)?;
+
+        // ✅ EFFECTS: Update state before external calls (CEI pattern)
+        let lead_swaps = ctx.accounts.lead_config.total_swaps;
+        let follower_config = &mut ctx.accounts.follower_config;
+        follower_config.last_copied_swap = lead_swaps;
+        follower_config.total_copied = follower_config
+            .total_copied
+            .checked_add(1)
+            .ok_or(TraderError::MathOverflow)?;
+
+        // ✅ INTERACTIONS: Follower config PDA signs as delegate
+        let follower_config = &ctx.accounts.follower_config;
+        let seeds: &[&[u8]] = &[
+            b"follower",
+            follower_config.follower.as_ref(),
+            follower_config.lead.as_ref(),
+            &[follower_config.bump],
+        ];
+        if lead_fee > 0 {
+            token::transfer(
+                CpiContext::new_with_signer(
+                    ctx.accounts.token_program.to_account_info(),
+                    Transfer {
+                        from: ctx.accounts.follower_source_token_account.to_account_info(),
+                        to: ctx.accounts.lead_fee_account.to_account_info(),
+                        authority: ctx.accounts.follower_config.to_account_info(),
+                    },
+                    &[seeds],
+                ),
+                lead_fee,
+            )?;
+        }
+        if trade_fee > 0 {
+            let fee_account = ctx
+                .accounts
+                .protocol_fee_account
+                .as_ref()
+                .ok_or(TraderError::FeeAccountRequired)?;
+            token::transfer(
+                CpiContext::new_with_signer(
+                    ctx.accounts.token_program.to_account_info(),
+                    Transfer {
+                        from: ctx.accounts.follower_source_token_account.to_account_info(),
+                        to: fee_account.to_account_info(),
+                        authority: ctx.accounts.follower_config.to_account_info(),
+                    },
+                    &[seeds],
+                ),
+                trade_fee,
+            )?;
+        }
+
+        let mut raydium_accounts = RaydiumSwapAccounts {
+            amm_program: ctx.accounts.raydium_amm_program.to_account_info(),
+            amm: ctx.accounts.amm.to_account_info(),
+            amm_authority: ctx.accounts.amm_authority.to_account_info(),
+            #[cfg(not(feature = "no-serum"))]
+            amm_open_orders: ctx.accounts.amm_open_orders.to_account_info(),
+            #[cfg(not(feature = "no-serum"))]
+            amm_target_orders: ctx.accounts.amm_target_orders.to_account_info(),
+            pool_coin_token_account: ctx.accounts.pool_coin_token_account.to_account_info(),
+            pool_pc_token_account: ctx.accounts.pool_pc_token_account.to_account_info(),
+            #[cfg(not(feature = "no-serum"))]
+            serum_program: ctx.accounts.serum_program.to_account_info(),
+            #[cfg(not(feature = "no-serum"))]
+            serum_market: ctx.accounts.serum_market.to_account_info(),
+            #[cfg(not(feature = "no-serum"))]
+            serum_bids: ctx.accounts.serum_bids.to_account_info(),
+            #[cfg(not(feature = "no-serum"))]
+            serum_asks: ctx.accounts.serum_asks.to_account_info(),
+            #[cfg(not(feature = "no-serum"))]
+            serum_event_queue: ctx.accounts.serum_event_queue.to_account_info(),
+            #[cfg(not(feature = "no-serum"))]
+            serum_coin_vault_account: ctx.accounts.serum_coin_vault_account.to_account_info(),
+            #[cfg(not(feature = "no-serum"))]
+            serum_pc_vault_account: ctx.accounts.serum_pc_vault_account.to_account_info(),
+            #[cfg(not(feature = "no-serum"))]
+            serum_vault_signer: ctx.accounts.serum_vault_signer.to_account_info(),
+            user_source_token_account: ctx.accounts.follower_source_token_account.to_account_info(),
+            user_destination_token_account: ctx.accounts.follower_destination_token_account.to_account_info(),
+            user_source_owner: ctx.accounts.follower_config.to_account_info(),
+            token_program: ctx.accounts.token_program.to_account_info(),
+        };
+        execute_raydium_swap(&mut raydium_accounts, swap_amount, minimum_output, &[seeds])?;
+
+        vectai_protocol::report(
+            ctx.accounts.protocol_program.to_account_info(),
+            ctx.accounts.protocol_stats.to_account_info(),
+            ctx.accounts.stats_reporter.to_account_info(),
+            ctx.bumps.stats_reporter,
+            ActivityKind::Execution,
+            ctx.accounts.follower_source_token_account.mint,
+            swap_amount,
+        )?;
+
+        emit!(TradeCopied {
+            lead: ctx.accounts.lead_config.key(),
+            follower: ctx.accounts.follower_config.follower,
+            lead_amount,
+            amount: swap_amount,
+            lead_fee,
+        });
+        msg!("✅ Copied lead trade: {} of {} ({} lead fee)", swap_amount, lead_amount, lead_fee);
+        Ok(())
+    }
+
+    /// Publish a strategy listing with its parameters and subscription terms
+    pub fn create_listing(
+        ctx: Context<CreateListing>,
+        listing_id: u64,
+        price_threshold: i64,
+        swap_amount: u64,
+        slippage_tolerance: u64,
+        fee_amount: u64,
+        period_duration: i64,
+    ) -> Result<()> {
+        // ✅ CHECKS: Same bounds as initialize_trader
         require!(price_threshold > 0, TraderError::InvalidInput);
-        require!(price_threshold < 1_000_000_000_000, TraderError::InvalidInput); // Max $1T
+        require!(price_threshold < 1_000_000_000_000, TraderError::InvalidInput);
         require!(swap_amount > 0, TraderError::InvalidInput);
-        require!(swap_amount <= 1_000_000_000_000, TraderError::InvalidInput); // Max 1T tokens
-        require!(slippage_tolerance <= 1000, TraderError::InvalidInput); // Max 10% slippage
-        
+        require!(swap_amount <= 1_000_000_000_000, TraderError::InvalidInput);
+        require!(slippage_tolerance <= MAX_SLIPPAGE_BPS, TraderError::InvalidInput);
+        require!(fee_amount > 0, TraderError::InvalidInput);
+        require!(period_duration > 0, TraderError::InvalidInput);
+
+        let listing = &mut ctx.accounts.listing;
+        listing.creator = ctx.accounts.creator.key();
+        listing.listing_id = listing_id;
+        listing.price_threshold = price_threshold;
+        listing.swap_amount = swap_amount;
+        listing.slippage_tolerance = slippage_tolerance;
+        listing.fee_amount = fee_amount;
+        listing.period_duration = period_duration;
+        listing.creator_fee_account = ctx.accounts.creator_fee_account.key();
+        listing.subscribers = 0;
+        listing.total_revenue = 0;
+        listing.is_active = true;
+        listing.required_collection = Pubkey::default();
+        listing.bump = ctx.bumps.listing;
+
+        msg!("✅ Strategy listed: #{} at {} VECTAI per {}s", listing_id, fee_amount, period_duration);
+        Ok(())
+    }
+
+    /// Open or close a listing to new subscribers (creator only)
+    pub fn set_listing_active(ctx: Context<ManageListing>, is_active: bool) -> Result<()> {
+        ctx.accounts.listing.is_active = is_active;
+        msg!("Listing active: {}", is_active);
+        Ok(())
+    }
+
+    /// Gate a listing to holders of a verified NFT from `collection`, or
+    /// open it to everyone with the default key (creator only)
+    pub fn set_listing_collection(ctx: Context<ManageListing>, collection: Pubkey) -> Result<()> {
+        ctx.accounts.listing.required_collection = collection;
+        msg!("Listing collection gate: {}", collection);
+        Ok(())
+    }
+
+    /// Close a deactivated listing (creator only)
+    pub fn close_listing(_ctx: Context<CloseListing>) -> Result<()> {
+        msg!("✅ Listing closed");
+        Ok(())
+    }
+
+    /// Pay the first period and instantiate the listed strategy as the
+    /// subscriber's own trader
+    pub fn subscribe(ctx: Context<Subscribe>) -> Result<()> {
+        // ✅ CHECKS
+        let listing = &ctx.accounts.listing;
+        require!(listing.is_active, TraderError::ListingInactive);
+        require!(
+            ctx.accounts.subscriber_vectai_account.amount >= listing.fee_amount,
+            TraderError::InsufficientBalance
+        );
+        require_collection_nft(
+            listing.required_collection,
+            ctx.accounts.subscriber.key(),
+            &ctx.accounts.membership_token,
+            &ctx.accounts.membership_metadata,
+        )?;
+
+        let clock = Clock::get()?;
+        let paid_until = clock
+            .unix_timestamp
+            .checked_add(listing.period_duration)
+            .ok_or(TraderError::MathOverflow)?;
+        let (creator_amount, protocol_amount) = listing.split_fee();
+
+        // ✅ EFFECTS
         let trader_config = &mut ctx.accounts.trader_config;
-        trader_config.authority = ctx.accounts.authority.key();
-        trader_config.price_threshold = price_threshold;
-        trader_config.swap_amount = swap_amount;
-        trader_config.slippage_tolerance = slippage_tolerance;
+        trader_config.authority = ctx.accounts.subscriber.key();
+        trader_config.referrer = cited_referrer(&ctx.accounts.referral_code, ctx.accounts.subscriber.key())?;
+        trader_config.price_threshold = listing.price_threshold;
+        trader_config.swap_amount = listing.swap_amount;
+        trader_config.slippage_tolerance = listing.slippage_tolerance;
         trader_config.total_swaps = 0;
         trader_config.last_swap_time = 0;
+        trader_config.last_swap_slot = 0;
         trader_config.is_active = true;
+        trader_config.subscription_expires_at = paid_until;
+        trader_config.auto_compound = false;
+        trader_config.total_compounded = 0;
+        trader_config.risk = DrawdownTracker::default();
+        trader_config.condition = Vec::new();
+        trader_config.version = TraderConfig::VERSION;
+        trader_config.last_nonce = 0;
+        trader_config.listing = listing.key();
 
-        msg!("✅ Secure trader initialized: {} threshold, {} amount, {}% slippage", 
-             price_threshold, swap_amount, slippage_tolerance);
+        let subscription = &mut ctx.accounts.subscription;
+        subscription.subscriber = ctx.accounts.subscriber.key();
+        subscription.listing = listing.key();
+        subscription.trader_config = trader_config.key();
+        subscription.paid_until = paid_until;
+        subscription.bump = ctx.bumps.subscription;
+
+        // ✅ INTERACTIONS
+        let referral_amount = pay_subscription_fee(
+            &ctx.accounts.token_program,
+            &ctx.accounts.subscriber_vectai_account,
+            &ctx.accounts.creator_fee_account,
+            &ctx.accounts.protocol_fee_account,
+            ctx.accounts.referral_escrow.as_ref(),
+            &ctx.accounts.subscriber,
+            ctx.accounts.trader_config.referrer,
+            ctx.accounts.protocol_status.fee_config.referral_share_bps,
+            creator_amount,
+            protocol_amount,
+        )?;
+
+        let listing_key = ctx.accounts.listing.key();
+        let listing = &mut ctx.accounts.listing;
+        listing.subscribers = listing.subscribers.checked_add(1).ok_or(TraderError::MathOverflow)?;
+        listing.total_revenue = listing
+            .total_revenue
+            .checked_add(listing.fee_amount)
+            .ok_or(TraderError::MathOverflow)?;
+
+        emit!(SubscriptionPaid {
+            listing: listing_key,
+            subscriber: ctx.accounts.subscriber.key(),
+            creator_amount,
+            protocol_amount: protocol_amount - referral_amount,
+            referral_amount,
+            paid_until,
+        });
+        msg!("✅ Subscribed to listing #{} until {}", listing.listing_id, paid_until);
+        Ok(())
+    }
+
+    /// Pay for another period; extends from expiry, or from now if lapsed
+    pub fn renew_subscription(ctx: Context<RenewSubscription>) -> Result<()> {
+        // ✅ CHECKS
+        let listing = &ctx.accounts.listing;
+        require!(listing.is_active, TraderError::ListingInactive);
+        require!(
+            ctx.accounts.subscriber_vectai_account.amount >= listing.fee_amount,
+            TraderError::InsufficientBalance
+        );
+        require_collection_nft(
+            listing.required_collection,
+            ctx.accounts.subscriber.key(),
+            &ctx.accounts.membership_token,
+            &ctx.accounts.membership_metadata,
+        )?;
+
+        let clock = Clock::get()?;
+        let paid_until = ctx
+            .accounts
+            .subscription
+            .paid_until
+            .max(clock.unix_timestamp)
+            .checked_add(listing.period_duration)
+            .ok_or(TraderError::MathOverflow)?;
+        let (creator_amount, protocol_amount) = listing.split_fee();
+
+        // ✅ EFFECTS
+        ctx.accounts.subscription.paid_until = paid_until;
+        ctx.accounts.trader_config.subscription_expires_at = paid_until;
+
+        // ✅ INTERACTIONS
+        let referral_amount = pay_subscription_fee(
+            &ctx.accounts.token_program,
+            &ctx.accounts.subscriber_vectai_account,
+            &ctx.accounts.creator_fee_account,
+            &ctx.accounts.protocol_fee_account,
+            ctx.accounts.referral_escrow.as_ref(),
+            &ctx.accounts.subscriber,
+            ctx.accounts.trader_config.referrer,
+            ctx.accounts.protocol_status.fee_config.referral_share_bps,
+            creator_amount,
+            protocol_amount,
+        )?;
+
+        emit!(SubscriptionPaid {
+            listing: ctx.accounts.listing.key(),
+            subscriber: ctx.accounts.subscriber.key(),
+            creator_amount,
+            protocol_amount: protocol_amount - referral_amount,
+            referral_amount,
+            paid_until,
+        });
+        msg!("✅ Subscription renewed until {}", paid_until);
+        Ok(())
+    }
+
+    /// Close a lapsed subscription (subscriber only)
+    pub fn close_subscription(ctx: Context<CloseSubscription>) -> Result<()> {
+        require!(
+            Clock::get()?.unix_timestamp >= ctx.accounts.subscription.paid_until,
+            TraderError::AccountInUse
+        );
+        msg!("✅ Subscription closed");
+        Ok(())
+    }
+
+    /// Attach a Drift perp hedge to the trader and create its Drift account
+    pub fn initialize_hedge(
+        ctx: Context<InitializeHedge>,
+        perp_market_index: u16,
+        hedge_ratio_bps: u16,
+        min_position: u64,
+    ) -> Result<()> {
+        require!(
+            hedge_ratio_bps > 0 && hedge_ratio_bps <= MAX_HEDGE_RATIO_BPS,
+            TraderError::InvalidInput
+        );
+
+        let hedge_config = &mut ctx.accounts.hedge_config;
+        hedge_config.trader = ctx.accounts.trader_config.key();
+        hedge_config.drift_user = ctx.accounts.drift_user.key();
+        hedge_config.spot_mint = ctx.accounts.network_config.wsol_mint;
+        hedge_config.perp_market_index = perp_market_index;
+        hedge_config.hedge_ratio_bps = hedge_ratio_bps;
+        hedge_config.min_position = min_position;
+        hedge_config.hedged_amount = 0;
+        hedge_config.spot_position = ctx.accounts.spot_position.key();
+        hedge_config.collateral = 0;
+        hedge_config.bump = ctx.bumps.hedge_config;
+
+        let trader_key = ctx.accounts.trader_config.key();
+        let seeds: &[&[u8]] = &[
+            b"hedge-authority",
+            trader_key.as_ref(),
+            &[ctx.bumps.hedge_authority],
+        ];
+        drift_initialize_user(ctx.accounts, &[seeds])?;
+
+        msg!("✅ Hedge initialized: perp market {}, {} bps above {}",
+             perp_market_index, hedge_ratio_bps, min_position);
+        Ok(())
+    }
+
+    /// Update the hedge ratio and trigger size (trader authority)
+    pub fn configure_hedge(
+        ctx: Context<ConfigureHedge>,
+        hedge_ratio_bps: u16,
+        min_position: u64,
+    ) -> Result<()> {
+        require!(hedge_ratio_bps <= MAX_HEDGE_RATIO_BPS, TraderError::InvalidInput);
+
+        let hedge_config = &mut ctx.accounts.hedge_config;
+        hedge_config.hedge_ratio_bps = hedge_ratio_bps;
+        hedge_config.min_position = min_position;
+        msg!("Hedge ratio: {} bps above {}", hedge_ratio_bps, min_position);
+        Ok(())
+    }
+
+    /// Move collateral into the hedge's Drift account (trader authority)
+    pub fn fund_hedge<'info>(
+        ctx: Context<'_, '_, 'info, 'info, FundHedge<'info>>,
+        spot_market_index: u16,
+        amount: u64,
+    ) -> Result<()> {
+        require!(amount > 0, TraderError::InvalidSwapAmount);
+        require!(
+            ctx.accounts.authority_collateral.amount >= amount,
+            TraderError::InsufficientBalance
+        );
+
+        token::transfer(
+            CpiContext::new(
+                ctx.accounts.token_program.to_account_info(),
+                Transfer {
+                    from: ctx.accounts.authority_collateral.to_account_info(),
+                    to: ctx.accounts.hedge_collateral.to_account_info(),
+                    authority: ctx.accounts.authority.to_account_info(),
+                },
+            ),
+            amount,
+        )?;
+
+        let hedge_config = &mut ctx.accounts.hedge_config;
+        hedge_config.collateral = hedge_config
+            .collateral
+            .checked_add(amount)
+            .ok_or(TraderError::MathOverflow)?;
+
+        let trader_key = ctx.accounts.trader_config.key();
+        let seeds: &[&[u8]] = &[
+            b"hedge-authority",
+            trader_key.as_ref(),
+            &[ctx.bumps.hedge_authority],
+        ];
+        drift_deposit(ctx.accounts, ctx.remaining_accounts, spot_market_index, amount, &[seeds])?;
+
+        msg!("✅ Hedge funded with {}", amount);
+        Ok(())
+    }
+
+    /// Move collateral out of the hedge's Drift account back to the trader
+    /// authority; Drift caps it at the account's free collateral, so only
+    /// what arrives counts against the collateral. The hedge can only be
+    /// closed once all it was funded with is withdrawn (trader authority)
+    pub fn withdraw_hedge_collateral<'info>(
+        ctx: Context<'_, '_, 'info, 'info, WithdrawHedgeCollateral<'info>>,
+        spot_market_index: u16,
+        amount: u64,
+    ) -> Result<()> {
+        require!(amount > 0, TraderError::InvalidSwapAmount);
+
+        let trader_key = ctx.accounts.trader_config.key();
+        let seeds: &[&[u8]] = &[
+            b"hedge-authority",
+            trader_key.as_ref(),
+            &[ctx.bumps.hedge_authority],
+        ];
+        let staged = ctx.accounts.hedge_collateral.amount;
+        drift_withdraw(ctx.accounts, ctx.remaining_accounts, spot_market_index, amount, &[seeds])?;
+        ctx.accounts.hedge_collateral.reload()?;
+        let withdrawn = ctx
+            .accounts
+            .hedge_collateral
+            .amount
+            .checked_sub(staged)
+            .ok_or(TraderError::MathOverflow)?;
+        let hedge_config = &mut ctx.accounts.hedge_config;
+        hedge_config.collateral = hedge_config.collateral.saturating_sub(withdrawn);
+
+        token::transfer(
+            CpiContext::new_with_signer(
+                ctx.accounts.token_program.to_account_info(),
+                Transfer {
+                    from: ctx.accounts.hedge_collateral.to_account_info(),
+                    to: ctx.accounts.authority_collateral.to_account_info(),
+                    authority: ctx.accounts.hedge_authority.to_account_info(),
+                },
+                &[seeds],
+            ),
+            withdrawn,
+        )?;
+
+        msg!("✅ Hedge collateral withdrawn: {}", withdrawn);
+        Ok(())
+    }
+
+    /// Keeper: resize the perp short to `hedge_ratio_bps` of the spot position
+    pub fn rebalance_hedge<'info>(
+        ctx: Context<'_, '_, 'info, 'info, RebalanceHedge<'info>>,
+    ) -> Result<()> {
+        let position = ctx.accounts.spot_position.amount;
+        let hedge_config = &ctx.accounts.hedge_config;
+        let target = hedge_config.target_hedge(position);
+        // Drift fills market orders after this transaction, so the hedge
+        // is reconciled from the fills rather than the last order placed
+        let (previous, pending) =
+            drift_perp_short(&ctx.accounts.drift_user, hedge_config.perp_market_index)?;
+        require!(!pending, TraderError::HedgeOrderPending);
+        let recorded = hedge_config.hedged_amount;
+
+        // ✅ EFFECTS
+        ctx.accounts.hedge_config.hedged_amount = previous;
+        if target == previous {
+            require!(recorded != previous, TraderError::HedgeBalanced);
+            msg!("Hedge reconciled: {} -> {}", recorded, previous);
+            return Ok(());
+        }
+
+        // ✅ INTERACTIONS
+        let (increase, amount) = if target > previous {
+            (true, target - previous)
+        } else {
+            (false, previous - target)
+        };
+        let trader_key = ctx.accounts.trader_config.key();
+        let seeds: &[&[u8]] = &[
+            b"hedge-authority",
+            trader_key.as_ref(),
+            &[ctx.bumps.hedge_authority],
+        ];
+        drift_place_hedge_order(ctx.accounts, ctx.remaining_accounts, increase, amount, &[seeds])?;
+
+        emit!(HedgeRebalanced {
+            trader: trader_key,
+            spot_position: position,
+            previous_hedge: previous,
+            target_hedge: target,
+        });
+        msg!("✅ Hedge rebalanced: {} -> {} (spot {})", previous, target, position);
+        Ok(())
+    }
+
+    /// Detach a flat hedge whose collateral has been withdrawn from the
+    /// trader. The Drift user account stays with Drift under the hedge
+    /// authority (trader authority)
+    pub fn close_hedge(ctx: Context<CloseHedge>) -> Result<()> {
+        let (short, pending) =
+            drift_perp_short(&ctx.accounts.drift_user, ctx.accounts.hedge_config.perp_market_index)?;
+        require!(short == 0 && !pending, TraderError::AccountInUse);
+
+        msg!("✅ Hedge config closed");
+        Ok(())
+    }
+
+    /// Set the max-drawdown limit for this strategy; 0 disables it
+    /// (trader authority)
+    pub fn set_max_drawdown(ctx: Context<ConfigureTrader>, max_drawdown_bps: u16) -> Result<()> {
+        require!(max_drawdown_bps <= MAX_DRAWDOWN_LIMIT_BPS, TraderError::InvalidInput);
+
+        ctx.accounts.trader_config.risk.max_drawdown_bps = max_drawdown_bps;
+        ctx.accounts.audit_log.append(
+            ctx.accounts.authority.key(),
+            AuditAction::MaxDrawdownSet { max_drawdown_bps },
+            Clock::get()?.unix_timestamp,
+        )?;
+        msg!("Max drawdown: {} bps", max_drawdown_bps);
+        Ok(())
+    }
+
+    /// Clear a drawdown halt; the peak re-baselines at the next execution
+    /// (trader authority)
+    pub fn reset_drawdown(ctx: Context<ConfigureTrader>) -> Result<()> {
+        let trader_config = &mut ctx.accounts.trader_config;
+        trader_config.risk.reset();
+        ctx.accounts.audit_log.append(
+            ctx.accounts.authority.key(),
+            AuditAction::DrawdownReset,
+            Clock::get()?.unix_timestamp,
+        )?;
+
+        emit!(DrawdownReset {
+            account: trader_config.key(),
+            max_drawdown_bps: trader_config.risk.max_drawdown_bps,
+        });
+        msg!("✅ Drawdown tracker reset");
+        Ok(())
+    }
+
+    /// Create the points config and VECTAI rewards vault (admin only)
+    pub fn initialize_points(
+        ctx: Context<InitializePoints>,
+        points_per_dollar: u64,
+        redemption_rate: u64,
+        daily_pair_cap: u64,
+    ) -> Result<()> {
+        require!(
+            is_admin(&ctx.accounts.admin.key()),
+            TraderError::UnauthorizedAdmin
+        );
+
+        let points_config = &mut ctx.accounts.points_config;
+        points_config.points_per_dollar = points_per_dollar;
+        points_config.redemption_rate = redemption_rate;
+        points_config.daily_pair_cap = daily_pair_cap;
+        points_config.rewards_vault = ctx.accounts.rewards_vault.key();
+        points_config.total_points_issued = 0;
+        points_config.total_points_redeemed = 0;
+        points_config.bump = ctx.bumps.points_config;
+
+        msg!("✅ Points initialized: {} per $1, {} VECTAI per point, cap {} per pair/day",
+             points_per_dollar, redemption_rate, daily_pair_cap);
         Ok(())
     }
 
-    /// Execute secure trade with comprehensive validation
-    pub fn execute_trade(ctx: Context<ExecuteTrade>, amount: u64) -> Result<()> {
-        msg!("🚀 Starting secure trade execution through Jupiter...");
-
-        // ✅ CHECKS: Validate inputs and authorization
-        require!(amount > 0, TraderError::InvalidSwapAmount);
-        require!(amount <= ctx.accounts.user_source_token_account.amount, TraderError::InsufficientBalance);
-        require!(
-            ctx.accounts.user_authority.key() == ctx.accounts.trader_config.authority,
-            TraderError::Unauthorized
-        );
-        require!(ctx.accounts.trader_config.is_active, TraderError::TraderInactive);
-        
-        // ✅ CHECKS: Rate limiting (1 minute cooldown)
-        let clock = Clock::get()?;
-        let time_since_last = clock.unix_timestamp - ctx.accounts.trader_config.last_swap_time;
-        require!(time_since_last >= 60, TraderError::RateLimited);
-        
-        // ✅ CHECKS: Token account ownership validation
+    /// Update the points earn rate, redemption rate and daily pair cap
+    /// (timelock only)
+    pub fn set_points_rates(
+        ctx: Context<SetPointsRates>,
+        points_per_dollar: u64,
+        redemption_rate: u64,
+        daily_pair_cap: u64,
+    ) -> Result<()> {
         require!(
-            ctx.accounts.user_source_token_account.owner == ctx.accounts.user_authority.key(),
-            TraderError::InvalidTokenAccount
+            is_timelock(&ctx.accounts.admin.key()),
+            TraderError::UnauthorizedAdmin
         );
-        
-        // ✅ CHECKS: Fetch and validate oracle price
-        let price_result = get_price(
-            CpiContext::new(
-                ctx.accounts.vectai_oracle_program.to_account_info(),
-                GetPrice {
-                    price_feed: ctx.accounts.price_feed.to_account_info(),
-                },
-            ),
-        )?;
-        let price_data = price_result.get();
 
-        msg!("📊 Oracle price received: {} (confidence: {})", price_data.price, price_data.conf);
+        let points_config = &mut ctx.accounts.points_config;
+        points_config.points_per_dollar = points_per_dollar;
+        points_config.redemption_rate = redemption_rate;
+        points_config.daily_pair_cap = daily_pair_cap;
+
+        msg!("Points rates: {} per $1, {} VECTAI per point, cap {} per pair/day",
+             points_per_dollar, redemption_rate, daily_pair_cap);
+        Ok(())
+    }
+
+    /// Open the caller's points ledger; `execute_trade` credits it when passed
+    pub fn open_points_ledger(ctx: Context<OpenPointsLedger>) -> Result<()> {
+        let points_ledger = &mut ctx.accounts.points_ledger;
+        points_ledger.authority = ctx.accounts.authority.key();
+        points_ledger.balance = 0;
+        points_ledger.lifetime_points = 0;
+        points_ledger.total_redeemed = 0;
+        points_ledger.day = 0;
+        points_ledger.day_credits = Vec::new();
+        points_ledger.bump = ctx.bumps.points_ledger;
+
+        msg!("✅ Points ledger opened");
+        Ok(())
+    }
 
-        // ✅ CHECKS: Price threshold validation
+    /// Redeem points for VECTAI from the rewards vault at the current rate
+    pub fn redeem_points(ctx: Context<RedeemPoints>, points: u64) -> Result<()> {
+        // ✅ CHECKS
+        require!(points > 0, TraderError::InvalidInput);
         require!(
-            price_data.price > ctx.accounts.trader_config.price_threshold,
-            TraderError::ThresholdNotMet
+            points <= ctx.accounts.points_ledger.balance,
+            TraderError::InsufficientPoints
+        );
+        let vectai_amount = points
+            .checked_mul(ctx.accounts.points_config.redemption_rate)
+            .ok_or(TraderError::MathOverflow)?;
+        require!(
+            vectai_amount <= ctx.accounts.rewards_vault.amount,
+            TraderError::InsufficientBalance
         );
 
-        // ✅ EFFECTS: Update state before external calls (CEI pattern)
-        ctx.accounts.trader_config.total_swaps = ctx.accounts.trader_config
-            .total_swaps
-            .checked_add(1)
+        // ✅ EFFECTS
+        let points_ledger = &mut ctx.accounts.points_ledger;
+        points_ledger.balance -= points;
+        points_ledger.total_redeemed = points_ledger
+            .total_redeemed
+            .checked_add(points)
+            .ok_or(TraderError::MathOverflow)?;
+        let points_config = &mut ctx.accounts.points_config;
+        points_config.total_points_redeemed = points_config
+            .total_points_redeemed
+            .checked_add(points)
             .ok_or(TraderError::MathOverflow)?;
-        ctx.accounts.trader_config.last_swap_time = clock.unix_timestamp;
 
-        // ✅ INTERACTIONS: Execute Raydium swap
-        let swap_result = Self::execute_raydium_swap_with_validation(
-            &ctx,
-            amount,
-            price_data.price,
-        )?;
+        // ✅ INTERACTIONS
+        if vectai_amount > 0 {
+            let seeds: &[&[u8]] = &[b"points-config", &[ctx.accounts.points_config.bump]];
+            token::transfer(
+                CpiContext::new_with_signer(
+                    ctx.accounts.token_program.to_account_info(),
+                    Transfer {
+                        from: ctx.accounts.rewards_vault.to_account_info(),
+                        to: ctx.accounts.authority_vectai_account.to_account_info(),
+                        authority: ctx.accounts.points_config.to_account_info(),
+                    },
+                    &[seeds],
+                ),
+                vectai_amount,
+            )?;
+        }
 
-        msg!("✅ Trade executed successfully!");
-        msg!("   Input: {} tokens", amount);
-        msg!("   Output: {} tokens", swap_result.output_amount);
-        msg!("   Exchange rate: {}", swap_result.exchange_rate);
-        msg!("   Total swaps: {}", ctx.accounts.trader_config.total_swaps);
-        
+        emit!(PointsRedeemed {
+            authority: ctx.accounts.authority.key(),
+            points,
+            vectai_amount,
+        });
+        msg!("✅ Redeemed {} points for {} VECTAI", points, vectai_amount);
         Ok(())
     }
 
-    /// Execute Raydium swap with validation and slippage protection
-    pub fn execute_raydium_swap_with_validation(
-        ctx: &Context<ExecuteTrade>,
-        input_amount: u64,
-        oracle_price: i64,
-    ) -> Result<SwapResult> {
-        msg!("🔄 Executing secure Raydium swap...");
+    /// Close a fully redeemed points ledger
+    pub fn close_points_ledger(_ctx: Context<ClosePointsLedger>) -> Result<()> {
+        msg!("✅ Points ledger closed");
+        Ok(())
+    }
 
-        // ✅ CHECKS: Validate Raydium program ID
+    /// Create a compressed trade history for the strategy; `execute_trade`
+    /// appends a leaf per trade when it is passed. The client allocates
+    /// `merkle_tree` (owned by account compression) for the chosen size.
+    pub fn initialize_trade_history(
+        ctx: Context<InitializeTradeHistory>,
+        max_depth: u32,
+        max_buffer_size: u32,
+    ) -> Result<()> {
         require!(
-            ctx.accounts.raydium_amm_program.key() == RAYDIUM_AMM_PROGRAM,
-            TraderError::InvalidRaydiumProgram
+            max_depth > 0 && max_depth <= MAX_TREE_DEPTH,
+            TraderError::InvalidInput
         );
 
-        // ✅ CHECKS: Validate token mints (hardcoded USDT <-> SOL)
-        let source_mint = ctx.accounts.user_source_token_account.mint;
-        let dest_mint = ctx.accounts.user_destination_token_account.mint;
-        
-        // Ensure swap is between USDT and SOL only
-        let valid_swap = 
-            (source_mint == USDT_MINT && dest_mint == WSOL_MINT) ||
-            (source_mint == WSOL_MINT && dest_mint == USDT_MINT);
-        
-        require!(valid_swap, TraderError::InvalidTokenPair);
+        let trader = ctx.accounts.trader_config.key();
+        let trade_history = &mut ctx.accounts.trade_history;
+        trade_history.trader = trader;
+        trade_history.merkle_tree = ctx.accounts.merkle_tree.key();
+        trade_history.max_depth = max_depth;
+        trade_history.max_buffer_size = max_buffer_size;
+        trade_history.record_count = 0;
+        trade_history.bump = ctx.bumps.trade_history;
 
-        msg!("💰 Swap details:");
-        msg!("   Input amount: {}", input_amount);
-        msg!("   Source mint: {}", source_mint);
-        msg!("   Dest mint: {}", dest_mint);
-        msg!("   Oracle price: {}", oracle_price);
+        let seeds: &[&[u8]] = &[b"trade-history", trader.as_ref(), &[ctx.bumps.trade_history]];
+        init_tree(ctx.accounts, max_depth, max_buffer_size, &[seeds])?;
 
-        // ✅ CHECKS: Calculate minimum output with slippage protection
-        let slippage_bps = ctx.accounts.trader_config.slippage_tolerance;
-        
-        // Estimate expected output based on oracle price
-        // This is a simplified calculation - in production, you'd query the pool
-        let expected_output = input_amount; // 1:1 for simplicity
-        let minimum_output = calculate_minimum_amount_out(expected_output, slippage_bps)?;
-        
-        msg!("   Expected output: {}", expected_output);
-        msg!("   Minimum output ({}% slippage): {}", slippage_bps / 100, minimum_output);
+        msg!("✅ Trade history initialized: depth {}, buffer {}", max_depth, max_buffer_size);
+        Ok(())
+    }
 
-        // ✅ INTERACTIONS: Execute Raydium swap via CPI
-        let mut raydium_accounts = RaydiumSwapAccounts {
-            amm_program: ctx.accounts.raydium_amm_program.to_account_info(),
-            amm: ctx.accounts.amm.to_account_info(),
-            amm_authority: ctx.accounts.amm_authority.to_account_info(),
-            amm_open_orders: ctx.accounts.amm_open_orders.to_account_info(),
-            amm_target_orders: ctx.accounts.amm_target_orders.to_account_info(),
-            pool_coin_token_account: ctx.accounts.pool_coin_token_account.to_account_info(),
-            pool_pc_token_account: ctx.accounts.pool_pc_token_account.to_account_info(),
-            serum_program: ctx.accounts.serum_program.to_account_info(),
-            serum_market: ctx.accounts.serum_market.to_account_info(),
-            serum_bids: ctx.accounts.serum_bids.to_account_info(),
-            serum_asks: ctx.accounts.serum_asks.to_account_info(),
-            serum_event_queue: ctx.accounts.serum_event_queue.to_account_info(),
-            serum_coin_vault_account: ctx.accounts.serum_coin_vault_account.to_account_info(),
-            serum_pc_vault_account: ctx.accounts.serum_pc_vault_account.to_account_info(),
-            serum_vault_signer: ctx.accounts.serum_vault_signer.to_account_info(),
-            user_source_token_account: ctx.accounts.user_source_token_account.to_account_info(),
-            user_destination_token_account: ctx.accounts.user_destination_token_account.to_account_info(),
-            user_source_owner: ctx.accounts.user_authority.to_account_info(),
-            token_program: ctx.accounts.token_program.to_account_info(),
-        };
+    /// Prove a trade record is in the strategy's history under `root`; the
+    /// proof nodes are passed as remaining accounts. Fails if it is not.
+    pub fn verify_trade_record<'info>(
+        ctx: Context<'_, '_, '_, 'info, VerifyTradeRecord<'info>>,
+        root: [u8; 32],
+        record: TradeRecord,
+    ) -> Result<()> {
+        require_keys_eq!(record.trader, ctx.accounts.trade_history.trader, TraderError::InvalidTradeHistory);
+        let index = u32::try_from(record.index).map_err(|_| TraderError::InvalidTradeHistory)?;
 
-        // Execute the swap - Raydium updates balances automatically
-        let _output_amount = execute_raydium_swap(
-            &mut raydium_accounts,
-            input_amount,
-            minimum_output,
+        verify_leaf(
+            &ctx.accounts.merkle_tree.to_account_info(),
+            &ctx.accounts.compression_program.to_account_info(),
+            ctx.remaining_accounts,
+            root,
+            record.leaf()?,
+            index,
         )?;
 
-        msg!("✅ Swap completed successfully");
-        msg!("   Minimum output guaranteed: {}", minimum_output);
-
-        // Calculate exchange rate (simplified - using expected output)
-        let exchange_rate = if input_amount > 0 {
-            expected_output
-                .checked_mul(10000)
-                .and_then(|x| x.checked_div(input_amount))
-                .unwrap_or(10000) // Default to 1:1
-        } else {
-            10000
-        };
-
-        // Return swap result
-        Ok(SwapResult {
-            input_amount,
-            output_amount: expected_output, // Using expected - actual will be close
-            exchange_rate,
-            oracle_price,
-        })
+        msg!("✅ Trade record {} verified", record.index);
+        Ok(())
     }
 
-    /// Emergency pause trader (admin only)
+    /// Emergency pause trader (admin or a pauser)
     pub fn pause_trader(ctx: Context<PauseTrader>) -> Result<()> {
+        let admin = ctx.accounts.admin.key();
         require!(
-            ctx.accounts.admin.key() == ADMIN_AUTHORITY,
+            is_admin(&admin) || holds_role(&ctx.accounts.role_registry, &admin, Role::Pauser),
             TraderError::UnauthorizedAdmin
         );
         
         ctx.accounts.trader_config.is_active = false;
-        msg!("🚨 Trader paused by admin");
+        if let Some(audit_log) = &mut ctx.accounts.audit_log {
+            audit_log.append(admin, AuditAction::Paused, Clock::get()?.unix_timestamp)?;
+        }
+        msg!("🚨 Trader paused by {}", admin);
         Ok(())
     }
 
     /// Unpause trader (admin only)
     pub fn unpause_trader(ctx: Context<PauseTrader>) -> Result<()> {
         require!(
-            ctx.accounts.admin.key() == ADMIN_AUTHORITY,
+            is_admin(&ctx.accounts.admin.key()),
             TraderError::UnauthorizedAdmin
         );
         
         ctx.accounts.trader_config.is_active = true;
+        let admin = ctx.accounts.admin.key();
+        if let Some(audit_log) = &mut ctx.accounts.audit_log {
+            audit_log.append(admin, AuditAction::Unpaused, Clock::get()?.unix_timestamp)?;
+        }
         msg!("✅ Trader unpaused by admin");
         Ok(())
     }
 }
 
+/// Check the strategy's trigger at `price`; its condition program replaces
+/// the plain price threshold when set
+fn check_trigger(trader_config: &TraderConfig, price: i64, now: i64) -> Result<()> {
+    if trader_config.condition.is_empty() {
+        require!(
+            vectai_math::threshold_met(price, trader_config.price_threshold),
+            TraderError::ThresholdNotMet
+        );
+    } else {
+        let eval_context = EvalContext {
+            price,
+            now,
+            last_execution: trader_config.last_swap_time,
+        };
+        require!(
+            condition::evaluate(&trader_config.condition, &eval_context).map_err(|_| TraderError::InvalidCondition)?,
+            TraderError::ConditionNotMet
+        );
+    }
+    Ok(())
+}
+
+/// Transfer the protocol trade fee on `amount` from the source account to
+/// the treasury vault, returning the fee
+fn charge_trade_fee(ctx: &Context<ExecuteTrade>, amount: u64) -> Result<u64> {
+    let trade_fee = FeeConfig::apply(amount, ctx.accounts.protocol_status.fee_config.trade_fee_bps);
+    if trade_fee > 0 {
+        let fee_account = ctx
+            .accounts
+            .protocol_fee_account
+            .as_ref()
+            .ok_or(TraderError::FeeAccountRequired)?;
+        token::transfer(
+            CpiContext::new(
+                ctx.accounts.token_program.to_account_info(),
+                Transfer {
+                    from: ctx.accounts.user_source_token_account.to_account_info(),
+                    to: fee_account.to_account_info(),
+                    authority: ctx.accounts.user_authority.to_account_info(),
+                },
+            ),
+            trade_fee,
+        )?;
+        msg!("💸 Trade fee: {}", trade_fee);
+    }
+    Ok(trade_fee)
+}
+
+/// Execute Raydium swap with validation and slippage protection
+/// Validate the Raydium program and pair, and quote `input_amount` as
+/// (expected output, minimum output after slippage)
+fn quote_raydium_swap(ctx: &Context<ExecuteTrade>, input_amount: u64, slippage_bps: u64) -> Result<(u64, u64)> {
+    // ✅ CHECKS: Validate Raydium program ID
+    let network = &ctx.accounts.network_config;
+    require!(
+        ctx.accounts.raydium_amm_program.key() == network.raydium_amm_program,
+        TraderError::InvalidRaydiumProgram
+    );
+
+    // ✅ CHECKS: Validate token mints (USDC <-> SOL from the network config)
+    let source_mint = ctx.accounts.user_source_token_account.mint;
+    let dest_mint = ctx.accounts.user_destination_token_account.mint;
+    
+    // Ensure swap is between USDC and SOL only
+    require!(network.is_supported_pair(source_mint, dest_mint), TraderError::InvalidTokenPair);
+
+    // ✅ CHECKS: Calculate minimum output with slippage protection
+    // Estimate expected output based on oracle price
+    // This is a simplified calculation - in production, you'd query the pool
+    let expected_output = input_amount; // 1:1 for simplicity
+    let minimum_output = calculate_minimum_amount_out(expected_output, slippage_bps)?;
+    Ok((expected_output, minimum_output))
+}
+
+/// Accounts of a swap through the pinned pool from the strategy's source
+/// into its destination, or back with `reverse`
+fn raydium_swap_accounts<'info>(accounts: &ExecuteTrade<'info>, reverse: bool) -> RaydiumSwapAccounts<'info> {
+    let (source, destination) = if reverse {
+        (&accounts.user_destination_token_account, &accounts.user_source_token_account)
+    } else {
+        (&accounts.user_source_token_account, &accounts.user_destination_token_account)
+    };
+    RaydiumSwapAccounts {
+        amm_program: accounts.raydium_amm_program.to_account_info(),
+        amm: accounts.amm.to_account_info(),
+        amm_authority: accounts.amm_authority.to_account_info(),
+        #[cfg(not(feature = "no-serum"))]
+        amm_open_orders: accounts.amm_open_orders.to_account_info(),
+        #[cfg(not(feature = "no-serum"))]
+        amm_target_orders: accounts.amm_target_orders.to_account_info(),
+        pool_coin_token_account: accounts.pool_coin_token_account.to_account_info(),
+        pool_pc_token_account: accounts.pool_pc_token_account.to_account_info(),
+        #[cfg(not(feature = "no-serum"))]
+        serum_program: accounts.serum_program.to_account_info(),
+        #[cfg(not(feature = "no-serum"))]
+        serum_market: accounts.serum_market.to_account_info(),
+        #[cfg(not(feature = "no-serum"))]
+        serum_bids: accounts.serum_bids.to_account_info(),
+        #[cfg(not(feature = "no-serum"))]
+        serum_asks: accounts.serum_asks.to_account_info(),
+        #[cfg(not(feature = "no-serum"))]
+        serum_event_queue: accounts.serum_event_queue.to_account_info(),
+        #[cfg(not(feature = "no-serum"))]
+        serum_coin_vault_account: accounts.serum_coin_vault_account.to_account_info(),
+        #[cfg(not(feature = "no-serum"))]
+        serum_pc_vault_account: accounts.serum_pc_vault_account.to_account_info(),
+        #[cfg(not(feature = "no-serum"))]
+        serum_vault_signer: accounts.serum_vault_signer.to_account_info(),
+        user_source_token_account: source.to_account_info(),
+        user_destination_token_account: destination.to_account_info(),
+        user_source_owner: accounts.user_authority.to_account_info(),
+        token_program: accounts.token_program.to_account_info(),
+    }
+}
+
+fn execute_raydium_swap_with_validation(
+    ctx: &Context<ExecuteTrade>,
+    input_amount: u64,
+    oracle_price: i64,
+    slippage_bps: u64,
+) -> Result<SwapResult> {
+    msg!("🔄 Executing secure Raydium swap...");
+    let (expected_output, minimum_output) = quote_raydium_swap(ctx, input_amount, slippage_bps)?;
+
+    msg!("💰 Swap details:");
+    msg!("   Input amount: {}", input_amount);
+    msg!("   Source mint: {}", ctx.accounts.user_source_token_account.mint);
+    msg!("   Dest mint: {}", ctx.accounts.user_destination_token_account.mint);
+    msg!("   Oracle price: {}", oracle_price);
+    msg!("   Expected output: {}", expected_output);
+    msg!("   Minimum output ({} bps slippage): {}", slippage_bps, minimum_output);
+
+    // ✅ INTERACTIONS: Execute Raydium swap via CPI
+    let mut raydium_accounts = raydium_swap_accounts(ctx.accounts, false);
+
+    // Execute the swap - Raydium updates balances automatically
+    let _output_amount = execute_raydium_swap(
+        &mut raydium_accounts,
+        input_amount,
+        minimum_output,
+        &[],
+    )?;
+
+    msg!("✅ Swap completed successfully");
+    msg!("   Minimum output guaranteed: {}", minimum_output);
+
+    // Calculate exchange rate (simplified - using expected output)
+    let exchange_rate = if input_amount > 0 {
+        expected_output
+            .checked_mul(10000)
+            .and_then(|x| x.checked_div(input_amount))
+            .unwrap_or(10000) // Default to 1:1
+    } else {
+        10000
+    };
+
+    // Return swap result
+    Ok(SwapResult {
+        input_amount,
+        output_amount: expected_output, // Using expected - actual will be close
+        minimum_output,
+        exchange_rate,
+        oracle_price,
+    })
+}
+
+/// Transfer a subscription payment to the creator and protocol
+///
+/// When the trader has a referrer, its share of the protocol fee goes to
+/// the referral escrow instead. Returns the referral amount.
+#[allow(clippy::too_many_arguments)]
+fn pay_subscription_fee<'info>(
+    token_program: &Program<'info, Token>,
+    from: &Account<'info, TokenAccount>,
+    creator_fee_account: &Account<'info, TokenAccount>,
+    protocol_fee_account: &Account<'info, TokenAccount>,
+    referral_escrow: Option<&Account<'info, TokenAccount>>,
+    subscriber: &Signer<'info>,
+    referrer: Pubkey,
+    referral_share_bps: u16,
+    creator_amount: u64,
+    protocol_amount: u64,
+) -> Result<u64> {
+    let mut payments = vec![(creator_fee_account, creator_amount)];
+    let mut referral_amount = 0;
+    if referrer != Pubkey::default() {
+        let escrow = referral_escrow.ok_or(TraderError::InvalidReferral)?;
+        require!(
+            escrow.key() == vectai_referral::escrow_address(&referrer, &VECTAI_MINT),
+            TraderError::InvalidReferral
+        );
+        referral_amount = vectai_referral::referral_share(protocol_amount, referral_share_bps);
+        payments.push((escrow, referral_amount));
+    }
+    payments.push((protocol_fee_account, protocol_amount - referral_amount));
+
+    for (to, amount) in payments {
+        if amount > 0 {
+            token::transfer(
+                CpiContext::new(
+                    token_program.to_account_info(),
+                    Transfer {
+                        from: from.to_account_info(),
+                        to: to.to_account_info(),
+                        authority: subscriber.to_account_info(),
+                    },
+                ),
+                amount,
+            )?;
+        }
+    }
+    Ok(referral_amount)
+}
+
+/// Referral code cited at creation, or default if none; no self-referral
+fn cited_referrer(referral_code: &Option<Account<ReferralCode>>, user: Pubkey) -> Result<Pubkey> {
+    match referral_code {
+        Some(code) => {
+            require!(code.owner != user, TraderError::InvalidReferral);
+            Ok(code.key())
+        }
+        None => Ok(Pubkey::default()),
+    }
+}
+
+/// Validate a new strategy's parameters and write its initial state
+fn initialize_trader_config(
+    trader_config: &mut TraderConfig,
+    authority: Pubkey,
+    referrer: Pubkey,
+    owner_program: Pubkey,
+    price_threshold: i64,
+    swap_amount: u64,
+    slippage_tolerance: u64,
+) -> Result<()> {
+    // ✅ CHECKS: Validate inputs
+    require!(price_threshold > 0, TraderError::InvalidInput);
+    require!(price_threshold < 1_000_000_000_000, TraderError::InvalidInput); // Max $1T
+    require!(swap_amount > 0, TraderError::InvalidInput);
+    require!(swap_amount <= 1_000_000_000_000, TraderError::InvalidInput); // Max 1T tokens
+    require!(slippage_tolerance <= MAX_SLIPPAGE_BPS, TraderError::InvalidInput); // Max 10% slippage
+
+    trader_config.authority = authority;
+    trader_config.referrer = referrer;
+    trader_config.price_threshold = price_threshold;
+    trader_config.swap_amount = swap_amount;
+    trader_config.slippage_tolerance = slippage_tolerance;
+    trader_config.total_swaps = 0;
+    trader_config.last_swap_time = 0;
+    trader_config.is_active = true;
+    trader_config.subscription_expires_at = 0;
+    trader_config.auto_compound = false;
+    trader_config.total_compounded = 0;
+    trader_config.risk = DrawdownTracker::default();
+    trader_config.condition = Vec::new();
+    trader_config.version = TraderConfig::VERSION;
+    trader_config.last_nonce = 0;
+    trader_config.schedule = TradingSchedule::default();
+    trader_config.dynamic_slippage = DynamicSlippage::default();
+    trader_config.volatility = VolatilityFilter::default();
+    trader_config.take_profit = TakeProfitLadder::default();
+    trader_config.trade_cap = DailyTradeCap::default();
+    trader_config.max_jito_tip = 0;
+    trader_config.realized_pnl_usd = 0;
+    trader_config.profit_target_usd = 0;
+    trader_config.last_swap_slot = 0;
+    trader_config.cooldown_slots = 0;
+    trader_config.max_price_age_slots = 0;
+    trader_config.owner_program = owner_program;
+    trader_config.listing = Pubkey::default();
+    trader_config.marked_source_balance = 0;
+    trader_config.marked_destination_balance = 0;
+    trader_config.price_aggregator = Pubkey::default();
+    trader_config.max_source_deviation_bps = 0;
+    Ok(())
+}
+
+/// Value `output` (destination token) in source-token units at the SOL/USD
+/// oracle price `price * 10^expo`
+fn value_in_source_units(output: u64, source_is_usdc: bool, price: i64, expo: i32) -> Result<u64> {
+    vectai_common::value_in_source_units(output, source_is_usdc, price, expo)
+}
+
 // ===== CONSTANTS =====
 
-// Admin authority for emergency functions
-const ADMIN_AUTHORITY: Pubkey = anchor_lang::solana_program::pubkey!("11111111111111111111111111111111"); // Replace with actual admin
+/// Timelock PDA; parameter changes must be queued through vectai_timelock
+fn is_timelock(key: &Pubkey) -> bool {
+    *key == vectai_timelock::timelock_authority()
+}
 
-// Token mint addresses (Devnet)
-// Wrapped SOL (native SOL wrapped as SPL token)
-const WSOL_MINT: Pubkey = anchor_lang::solana_program::pubkey!("So11111111111111111111111111111111111111112");
+// Raydium program and WSOL/USDC mints come from the shared NetworkConfig
+// in vectai_protocol
 
-// USDT on Devnet (for testing - you may need to create your own test token)
-// Mainnet USDT: Es9vMFrzaCERmJfrF4H2FYD4KCoNkY11McCe8BenwNYB
-const USDT_MINT: Pubkey = anchor_lang::solana_program::pubkey!("4zMMC9srt5Ri5X14GAgXhaHii3GnPAEERYPJgZJDncDU"); // Devnet USDC (using as USDT proxy)
+// VECTAI mint used for marketplace subscription fees
+const VECTAI_MINT: Pubkey = anchor_lang::solana_program::pubkey!("11111111111111111111111111111111"); // Replace with deployed VECTAI mint
 
-// Raydium AMM Program ID (Mainnet and Devnet)
-const RAYDIUM_AMM_PROGRAM: Pubkey = anchor_lang::solana_program::pubkey!("675kPX9MHTjS2zt1qfr1NYHuzeLXfQM9H24wFSUt1Mp8");
+// Upper bound for swap_amount (1T tokens)
+const MAX_SWAP_AMOUNT: u64 = 1_000_000_000_000;
 
 // Maximum slippage tolerance
 const MAX_SLIPPAGE_BPS: u64 = 1000; // 10%
 
+// Basis-point denominator (10000 = 100%)
+pub use vectai_common::BPS_DENOMINATOR;
+
 #[derive(Accounts)]
 pub struct InitializeTrader<'info> {
     #[account(
@@ -256,6 +2184,9 @@ pub struct InitializeTrader<'info> {
         bump
     )]
     pub trader_config: Account<'info, TraderConfig>,
+
+    /// Optional referral code credited with this trader's protocol fees
+    pub referral_code: Option<Account<'info, ReferralCode>>,
     
     #[account(mut)]
     pub authority: Signer<'info>,
@@ -272,7 +2203,7 @@ pub struct ExecuteTrade<'info> {
     /// Trader configuration account
     #[account(
         mut,
-        seeds = [b"trader", trader_config.authority.as_ref()],
+        seeds = [b"trader", trader_config.authority.as_ref(), trader_config.listing_seed()],
         bump
     )]
     pub trader_config: Account<'info, TraderConfig>,
@@ -286,63 +2217,77 @@ pub struct ExecuteTrade<'info> {
     pub user_destination_token_account: Account<'info, TokenAccount>,
     
     // ===== RAYDIUM AMM ACCOUNTS =====
+    // Pinned to raydium_swapper's whitelisted pool, like its own swap
     
     /// CHECK: Raydium AMM program
     pub raydium_amm_program: UncheckedAccount<'info>,
     
     /// CHECK: AMM pool account
-    #[account(mut)]
+    #[account(mut, address = pool_config::AMM_ID @ TraderError::InvalidPool)]
     pub amm: UncheckedAccount<'info>,
     
     /// CHECK: AMM authority
+    #[account(address = pool_config::AMM_AUTHORITY @ TraderError::InvalidPool)]
     pub amm_authority: UncheckedAccount<'info>,
     
     /// CHECK: AMM open orders
-    #[account(mut)]
+    #[account(mut, address = pool_config::AMM_OPEN_ORDERS @ TraderError::InvalidPool)]
+    #[cfg(not(feature = "no-serum"))]
     pub amm_open_orders: UncheckedAccount<'info>,
     
     /// CHECK: AMM target orders
-    #[account(mut)]
+    #[account(mut, address = pool_config::AMM_TARGET_ORDERS @ TraderError::InvalidPool)]
+    #[cfg(not(feature = "no-serum"))]
     pub amm_target_orders: UncheckedAccount<'info>,
     
     /// Pool coin token account
-    #[account(mut)]
+    #[account(mut, address = pool_config::POOL_COIN_TOKEN_ACCOUNT @ TraderError::InvalidPool)]
     pub pool_coin_token_account: Account<'info, TokenAccount>,
     
     /// Pool pc token account
-    #[account(mut)]
+    #[account(mut, address = pool_config::POOL_PC_TOKEN_ACCOUNT @ TraderError::InvalidPool)]
     pub pool_pc_token_account: Account<'info, TokenAccount>,
     
     // ===== SERUM MARKET ACCOUNTS =====
     
     /// CHECK: Serum program
+    #[account(address = pool_config::SERUM_PROGRAM @ TraderError::InvalidPool)]
+    #[cfg(not(feature = "no-serum"))]
     pub serum_program: UncheckedAccount<'info>,
     
     /// CHECK: Serum market
-    #[account(mut)]
+    #[account(mut, address = pool_config::SERUM_MARKET @ TraderError::InvalidPool)]
+    #[cfg(not(feature = "no-serum"))]
     pub serum_market: UncheckedAccount<'info>,
     
     /// CHECK: Serum bids
-    #[account(mut)]
+    #[account(mut, address = pool_config::SERUM_BIDS @ TraderError::InvalidPool)]
+    #[cfg(not(feature = "no-serum"))]
     pub serum_bids: UncheckedAccount<'info>,
     
     /// CHECK: Serum asks
-    #[account(mut)]
+    #[account(mut, address = pool_config::SERUM_ASKS @ TraderError::InvalidPool)]
+    #[cfg(not(feature = "no-serum"))]
     pub serum_asks: UncheckedAccount<'info>,
     
     /// CHECK: Serum event queue
-    #[account(mut)]
+    #[account(mut, address = pool_config::SERUM_EVENT_QUEUE @ TraderError::InvalidPool)]
+    #[cfg(not(feature = "no-serum"))]
     pub serum_event_queue: UncheckedAccount<'info>,
     
     /// CHECK: Serum coin vault
-    #[account(mut)]
+    #[account(mut, address = pool_config::SERUM_COIN_VAULT @ TraderError::InvalidPool)]
+    #[cfg(not(feature = "no-serum"))]
     pub serum_coin_vault_account: UncheckedAccount<'info>,
     
     /// CHECK: Serum pc vault
-    #[account(mut)]
+    #[account(mut, address = pool_config::SERUM_PC_VAULT @ TraderError::InvalidPool)]
+    #[cfg(not(feature = "no-serum"))]
     pub serum_pc_vault_account: UncheckedAccount<'info>,
     
     /// CHECK: Serum vault signer
+    #[account(address = pool_config::SERUM_VAULT_SIGNER @ TraderError::InvalidPool)]
+    #[cfg(not(feature = "no-serum"))]
     pub serum_vault_signer: UncheckedAccount<'info>,
 
     // ===== ORACLE =====
@@ -350,25 +2295,207 @@ pub struct ExecuteTrade<'info> {
     /// The Oracle Program (VECT.AI Oracle)
     pub vectai_oracle_program: Program<'info, VectaiOracle>,
 
-    /// Oracle price feed account
+    /// Oracle price feed account, or the strategy's price aggregator
     /// CHECK: Safe to be unchecked because vectai_oracle validates it
     #[account()]
     pub price_feed: UncheckedAccount<'info>,
 
+    // ===== POINTS =====
+
+    /// Authority's points ledger; credited with the trade's volume when passed
+    #[account(
+        mut,
+        seeds = [b"points", trader_config.authority.as_ref()],
+        bump = points_ledger.bump
+    )]
+    pub points_ledger: Option<Account<'info, PointsLedger>>,
+
+    /// Points rates; required alongside `points_ledger`
+    #[account(mut, seeds = [b"points-config"], bump = points_config.bump)]
+    pub points_config: Option<Account<'info, PointsConfig>>,
+
+    // ===== TRADE HISTORY =====
+
+    /// Strategy's compressed trade history; the trade is appended when passed
+    #[account(
+        mut,
+        seeds = [b"trade-history", trader_config.key().as_ref()],
+        bump = trade_history.bump
+    )]
+    pub trade_history: Option<Account<'info, TradeHistory>>,
+
+    /// CHECK: Matched against `trade_history`; required alongside it
+    #[account(mut)]
+    pub trade_history_tree: Option<UncheckedAccount<'info>>,
+
+    /// CHECK: Address checked; required alongside `trade_history`
+    #[account(address = ACCOUNT_COMPRESSION_PROGRAM @ TraderError::InvalidTradeHistory)]
+    pub compression_program: Option<UncheckedAccount<'info>>,
+
+    /// CHECK: Address checked; required alongside `trade_history`
+    #[account(address = NOOP_PROGRAM @ TraderError::InvalidTradeHistory)]
+    pub noop_program: Option<UncheckedAccount<'info>>,
+
+    // ===== FEES =====
+
+    /// Treasury vault for the source mint; required while the trade fee is on
+    #[account(
+        mut,
+        constraint = protocol_fee_account.key() == vectai_treasury::vault_address(&user_source_token_account.mint) @ TraderError::InvalidTokenAccount
+    )]
+    pub protocol_fee_account: Option<Account<'info, TokenAccount>>,
+
     /// Solana token program
     pub token_program: Program<'info, Token>,
+
+    /// Global emergency-pause registry
+    #[account(
+        seeds = [b"protocol-status"],
+        bump = protocol_status.bump,
+        seeds::program = vectai_protocol::ID,
+        constraint = !protocol_status.paused @ TraderError::ProtocolPaused
+    )]
+    pub protocol_status: Account<'info, ProtocolStatus>,
+
+    /// Shared network addresses
+    #[account(
+        seeds = [b"network-config"],
+        bump = network_config.bump,
+        seeds::program = vectai_protocol::ID
+    )]
+    pub network_config: Account<'info, NetworkConfig>,
+
+    /// CHECK: Protocol stats PDA, validated by vectai_protocol
+    #[account(mut)]
+    pub protocol_stats: UncheckedAccount<'info>,
+
+    /// CHECK: Signs `record_activity` for this program
+    #[account(seeds = [b"stats-reporter"], bump)]
+    pub stats_reporter: UncheckedAccount<'info>,
+
+    pub protocol_program: Program<'info, VectaiProtocol>,
+
+    /// CHECK: address-checked instructions sysvar
+    #[account(address = anchor_lang::solana_program::sysvar::instructions::ID)]
+    pub instructions: UncheckedAccount<'info>,
+}
+
+#[derive(Accounts)]
+pub struct ConfigureTrader<'info> {
+    #[account(
+        mut,
+        seeds = [b"trader", authority.key().as_ref(), trader_config.listing_seed()],
+        bump,
+        has_one = authority @ TraderError::Unauthorized
+    )]
+    pub trader_config: Account<'info, TraderConfig>,
+
+    /// Records the change
+    #[account(
+        mut,
+        seeds = [b"audit-log", trader_config.key().as_ref()],
+        bump = audit_log.bump
+    )]
+    pub audit_log: Account<'info, AuditLog>,
+
+    pub authority: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct MigrateTraderConfig<'info> {
+    /// CHECK: Owner and discriminator are checked by
+    /// `vectai_common::migrate` before it resizes the account
+    #[account(
+        mut,
+        seeds = [b"trader", authority.key().as_ref()],
+        bump
+    )]
+    pub trader_config: UncheckedAccount<'info>,
+
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct GetTraderConfig<'info> {
+    #[account(
+        seeds = [b"trader", trader_config.authority.as_ref(), trader_config.listing_seed()],
+        bump
+    )]
+    pub trader_config: Account<'info, TraderConfig>,
+}
+
+/// Close a strategy and return its rent. The audit log and trade history
+/// are closed with it when passed; the hedge has to be closed first.
+#[derive(Accounts)]
+pub struct CloseTrader<'info> {
+    #[account(
+        mut,
+        close = authority,
+        seeds = [b"trader", authority.key().as_ref(), trader_config.listing_seed()],
+        bump,
+        has_one = authority @ TraderError::Unauthorized
+    )]
+    pub trader_config: Account<'info, TraderConfig>,
+
+    #[account(
+        mut,
+        close = authority,
+        seeds = [b"audit-log", trader_config.key().as_ref()],
+        bump = audit_log.bump
+    )]
+    pub audit_log: Option<Account<'info, AuditLog>>,
+
+    /// The Merkle tree stays with account compression
+    #[account(
+        mut,
+        close = authority,
+        seeds = [b"trade-history", trader_config.key().as_ref()],
+        bump = trade_history.bump
+    )]
+    pub trade_history: Option<Account<'info, TradeHistory>>,
+
+    /// CHECK: Hedge PDA; must be closed so no Drift position is orphaned
+    #[account(
+        seeds = [b"hedge", trader_config.key().as_ref()],
+        bump,
+        constraint = hedge_config.data_is_empty() @ TraderError::AccountInUse
+    )]
+    pub hedge_config: UncheckedAccount<'info>,
+
+    #[account(mut)]
+    pub authority: Signer<'info>,
 }
 
 #[derive(Accounts)]
 pub struct PauseTrader<'info> {
     #[account(
         mut,
-        seeds = [b"trader", trader_config.authority.as_ref()],
+        seeds = [b"trader", trader_config.authority.as_ref(), trader_config.listing_seed()],
         bump
     )]
     pub trader_config: Account<'info, TraderConfig>,
+
+    /// Records the pause or unpause; only the owner can create it, so
+    /// strategies without one are paused unrecorded
+    #[account(
+        mut,
+        seeds = [b"audit-log", trader_config.key().as_ref()],
+        bump = audit_log.bump
+    )]
+    pub audit_log: Option<Account<'info, AuditLog>>,
     
     pub admin: Signer<'info>,
+
+    /// Needed when `admin` pauses as a pauser
+    #[account(
+        seeds = [b"role-registry"],
+        bump = role_registry.bump,
+        seeds::program = vectai_protocol::ID
+    )]
+    pub role_registry: Option<Account<'info, RoleRegistry>>,
 }
 
 /// Trader configuration state
@@ -381,6 +2508,31 @@ pub struct TraderConfig {
     pub total_swaps: u64,
     pub last_swap_time: i64,
     pub is_active: bool,
+    pub subscription_expires_at: i64, // 0 = not subscription-based
+    pub auto_compound: bool, // Grow swap_amount by realized gains
+    pub total_compounded: u64,
+    pub risk: DrawdownTracker,
+    pub referrer: Pubkey, // ReferralCode; default = none
+    pub condition: Vec<u8>, // vectai_math::condition program; empty = price_threshold
+    pub version: u8, // Versioned layout; fields are only appended after it
+    pub last_nonce: u64, // v2: highest keeper nonce executed; 0 = none
+    pub schedule: TradingSchedule, // v3: trading hours; all zero = any time
+    pub dynamic_slippage: DynamicSlippage, // v4: zero factor = slippage_tolerance
+    pub volatility: VolatilityFilter, // v5: zero bound = no filter
+    pub take_profit: TakeProfitLadder, // v6: no rungs = no ladder
+    pub trade_cap: DailyTradeCap, // v7: executions per rolling 24h; 0 = no cap
+    pub max_jito_tip: u64, // v8: lamports per execution; 0 = no tips
+    pub realized_pnl_usd: i64, // v9: executions' output less input, marked at the oracle in USDC units
+    pub profit_target_usd: u64, // v10: realized PnL that completes the strategy; 0 = none
+    pub last_swap_slot: u64, // v11
+    pub cooldown_slots: u64, // v11: slots between executions; 0 = COOLDOWN_SECS
+    pub max_price_age_slots: u64, // v12: oldest oracle price executed on, in slots; 0 = unchecked
+    pub owner_program: Pubkey, // v13: program owning the PDA authority; default = wallet-owned
+    pub listing: Pubkey, // v14: StrategyListing a subscription was instantiated from; default = own strategy
+    pub marked_source_balance: u64, // v15: balances of the last drawdown mark; flows since rebase the peak
+    pub marked_destination_balance: u64, // v15
+    pub price_aggregator: Pubkey, // v16: vectai_oracle PriceAggregator executions price from; default = price_feed
+    pub max_source_deviation_bps: u16, // v16: widest spread of the aggregator's sources executed on
 }
 
 impl TraderConfig {
@@ -391,7 +2543,78 @@ impl TraderConfig {
         8 +  // slippage_tolerance
         8 +  // total_swaps
         8 +  // last_swap_time
-        1;   // is_active
+        1 +  // is_active
+        8 +  // subscription_expires_at
+        1 +  // auto_compound
+        8 +  // total_compounded
+        DrawdownTracker::LEN + // risk
+        32 + // referrer
+        4 + MAX_CONDITION_LEN + // condition
+        1 +  // version
+        8 +  // last_nonce
+        TradingSchedule::LEN + // schedule
+        DynamicSlippage::LEN + // dynamic_slippage
+        VolatilityFilter::LEN + // volatility
+        TakeProfitLadder::LEN + // take_profit
+        DailyTradeCap::LEN + // trade_cap
+        8 +  // max_jito_tip
+        8 +  // realized_pnl_usd
+        8 +  // profit_target_usd
+        8 +  // last_swap_slot
+        8 +  // cooldown_slots
+        8 +  // max_price_age_slots
+        32 + // owner_program
+        32 + // listing
+        8 +  // marked_source_balance
+        8 +  // marked_destination_balance
+        32 + // price_aggregator
+        2;   // max_source_deviation_bps
+}
+
+impl Versioned for TraderConfig {
+    const VERSION: u8 = 16;
+    const SPACE: usize = TraderConfig::LEN;
+
+    fn version(&self) -> u8 {
+        self.version
+    }
+
+    fn set_version(&mut self, version: u8) {
+        self.version = version;
+    }
+}
+
+#[event]
+pub struct TradeExecuted {
+    pub trader: Pubkey, // TraderConfig
+    pub source_mint: Pubkey,
+    pub destination_mint: Pubkey,
+    pub amount_in: u64, // Including the trade fee
+    pub trade_fee: u64,
+    pub expected_out: u64,
+    pub oracle_price: i64,
+    pub timestamp: i64,
+}
+
+/// Quote from a dry-run `execute_trade`; no funds moved
+#[event]
+pub struct TradePreview {
+    pub trader: Pubkey, // TraderConfig
+    pub source_mint: Pubkey,
+    pub destination_mint: Pubkey,
+    pub amount_in: u64, // Including the trade fee
+    pub trade_fee: u64,
+    pub expected_out: u64,
+    pub minimum_out: u64, // Slippage floor the swap would be sent with
+    pub oracle_price: i64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct TradeCompounded {
+    pub trader: Pubkey,
+    pub gain: u64,
+    pub swap_amount: u64,
 }
 
 /// Result of a Jupiter swap execution
@@ -399,6 +2622,7 @@ impl TraderConfig {
 pub struct SwapResult {
     pub input_amount: u64,
     pub output_amount: u64,
+    pub minimum_output: u64, // Slippage floor the swap was sent with
     pub exchange_rate: u64,
     pub oracle_price: i64,
 }
@@ -425,12 +2649,86 @@ pub enum TraderError {
     InvalidTokenAccount,
     #[msg("Price threshold not met")]
     ThresholdNotMet,
+    #[msg("Strategy condition not met")]
+    ConditionNotMet,
+    #[msg("Strategy condition program is malformed")]
+    InvalidCondition,
     #[msg("Trader is inactive")]
     TraderInactive,
     #[msg("Unauthorized admin")]
     UnauthorizedAdmin,
     #[msg("Invalid Raydium program ID")]
     InvalidRaydiumProgram,
-    #[msg("Invalid token pair - only USDT <-> SOL supported")]
+    #[msg("Invalid token pair - only USDC <-> SOL supported")]
     InvalidTokenPair,
+    #[msg("No lead trade earlier in this transaction")]
+    LeadTradeNotFound,
+    #[msg("Lead trade already copied")]
+    AlreadyCopied,
+    #[msg("Follower config must be approved as delegate for the trade amount")]
+    DelegationRequired,
+    #[msg("Strategy listing is inactive")]
+    ListingInactive,
+    #[msg("Strategy subscription has expired")]
+    SubscriptionExpired,
+    #[msg("Hedge already matches the spot position")]
+    HedgeBalanced,
+    #[msg("Max drawdown exceeded - reset required")]
+    DrawdownLimitExceeded,
+    #[msg("Invalid referral code or escrow")]
+    InvalidReferral,
+    #[msg("Insufficient points balance")]
+    InsufficientPoints,
+    #[msg("Protocol is paused")]
+    ProtocolPaused,
+    #[msg("Listing requires a verified NFT from its collection")]
+    MembershipRequired,
+    #[msg("Protocol fee account required while the fee is on")]
+    FeeAccountRequired,
+    #[msg("Trade history tree or programs missing or mismatched")]
+    InvalidTradeHistory,
+    #[msg("Trade history tree is full")]
+    TradeHistoryFull,
+    #[msg("AMM or Serum account is not the whitelisted pool's")]
+    InvalidPool,
+    #[msg("Account is still active or holds a balance")]
+    AccountInUse,
+    #[msg("Nonce was already executed for this strategy")]
+    NonceAlreadyUsed,
+    #[msg("Outside the strategy's trading hours")]
+    OutsideTradingHours,
+    #[msg("Recent oracle prices are more volatile than the strategy allows")]
+    VolatilityTooHigh,
+    #[msg("Take-profit levels must ascend in price, with fractions summing to at most 100%")]
+    InvalidTakeProfit,
+    #[msg("No unfilled take-profit rung at or below the oracle price")]
+    TakeProfitNotReached,
+    #[msg("Strategy reached its executions for the last 24 hours")]
+    DailyTradeCapReached,
+    #[msg("Jupiter route does not spend from and pay into the strategy's accounts")]
+    InvalidJupiterRoute,
+    #[msg("Jupiter route allows more slippage than the strategy")]
+    RouteSlippageTooWide,
+    #[msg("Jito tip exceeds the strategy's tip cap")]
+    JitoTipTooHigh,
+    #[msg("Not a Jito tip account")]
+    InvalidJitoTipAccount,
+    #[msg("Jito tips must be paid alongside an execution of the strategy")]
+    TipWithoutExecution,
+    #[msg("Executions may only share a transaction with trusted programs")]
+    UntrustedInstruction,
+    #[msg("Oracle price was observed too many slots ago")]
+    StalePrice,
+    #[msg("Program strategies must be created by a direct CPI from the owning program")]
+    NotCalledByProgram,
+    #[msg("Not a Drift user account")]
+    InvalidDriftUser,
+    #[msg("Hedge order is still waiting to be filled")]
+    HedgeOrderPending,
+    #[msg("Price feed is not the strategy's price aggregator or lacks its sources")]
+    InvalidPriceFeed,
+    #[msg("Price aggregator's sources disagree by more than the strategy allows")]
+    PriceSourcesDisagree,
+    #[msg("Copied trade must be priced from the lead trade's price feed")]
+    LeadPriceFeedMismatch,
 }
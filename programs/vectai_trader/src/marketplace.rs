@@ -0,0 +1,302 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token::{Token, TokenAccount};
+
+use vectai_referral::ReferralCode;
+use vectai_protocol::ProtocolStatus;
+
+use crate::{TraderConfig, TraderError, VECTAI_MINT};
+
+/// Protocol share of every subscription payment (10%)
+pub const PROTOCOL_FEE_BPS: u64 = 1_000;
+
+#[derive(Accounts)]
+#[instruction(listing_id: u64)]
+pub struct CreateListing<'info> {
+    #[account(
+        init,
+        payer = creator,
+        space = StrategyListing::LEN,
+        seeds = [b"listing", creator.key().as_ref(), &listing_id.to_le_bytes()],
+        bump
+    )]
+    pub listing: Account<'info, StrategyListing>,
+
+    /// VECTAI account receiving the creator's share of subscription fees
+    #[account(
+        constraint = creator_fee_account.mint == VECTAI_MINT @ TraderError::InvalidTokenAccount,
+        constraint = creator_fee_account.owner == creator.key() @ TraderError::InvalidTokenAccount
+    )]
+    pub creator_fee_account: Account<'info, TokenAccount>,
+
+    #[account(mut)]
+    pub creator: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct ManageListing<'info> {
+    #[account(
+        mut,
+        seeds = [b"listing", listing.creator.as_ref(), &listing.listing_id.to_le_bytes()],
+        bump = listing.bump,
+        has_one = creator @ TraderError::Unauthorized
+    )]
+    pub listing: Account<'info, StrategyListing>,
+
+    pub creator: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct CloseListing<'info> {
+    /// Deactivated first, so no new subscriber lands on a closing listing
+    #[account(
+        mut,
+        close = creator,
+        seeds = [b"listing", listing.creator.as_ref(), &listing.listing_id.to_le_bytes()],
+        bump = listing.bump,
+        has_one = creator @ TraderError::Unauthorized,
+        constraint = !listing.is_active @ TraderError::AccountInUse
+    )]
+    pub listing: Account<'info, StrategyListing>,
+
+    #[account(mut)]
+    pub creator: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct Subscribe<'info> {
+    #[account(
+        mut,
+        seeds = [b"listing", listing.creator.as_ref(), &listing.listing_id.to_le_bytes()],
+        bump = listing.bump
+    )]
+    pub listing: Account<'info, StrategyListing>,
+
+    #[account(
+        init,
+        payer = subscriber,
+        space = Subscription::LEN,
+        seeds = [b"subscription", listing.key().as_ref(), subscriber.key().as_ref()],
+        bump
+    )]
+    pub subscription: Account<'info, Subscription>,
+
+    /// Subscriber's own trader instantiated from the listing
+    #[account(
+        init,
+        payer = subscriber,
+        space = TraderConfig::LEN,
+        seeds = [b"trader", subscriber.key().as_ref(), listing.key().as_ref()],
+        bump
+    )]
+    pub trader_config: Account<'info, TraderConfig>,
+
+    #[account(
+        mut,
+        constraint = subscriber_vectai_account.mint == VECTAI_MINT @ TraderError::InvalidTokenAccount,
+        constraint = subscriber_vectai_account.owner == subscriber.key() @ TraderError::InvalidTokenAccount
+    )]
+    pub subscriber_vectai_account: Account<'info, TokenAccount>,
+
+    #[account(mut, address = listing.creator_fee_account)]
+    pub creator_fee_account: Account<'info, TokenAccount>,
+
+    /// Treasury VECTAI vault
+    #[account(
+        mut,
+        address = vectai_treasury::vault_address(&VECTAI_MINT) @ TraderError::InvalidTokenAccount
+    )]
+    pub protocol_fee_account: Account<'info, TokenAccount>,
+
+    /// Optional referral code credited with the subscriber's protocol fees
+    pub referral_code: Option<Account<'info, ReferralCode>>,
+
+    /// Referrer's VECTAI escrow; required when a referral code is cited
+    #[account(mut)]
+    pub referral_escrow: Option<Account<'info, TokenAccount>>,
+
+    /// Subscriber's collection NFT; required when the listing is gated
+    pub membership_token: Option<Account<'info, TokenAccount>>,
+
+    /// CHECK: Metaplex metadata of `membership_token`, parsed in the handler
+    pub membership_metadata: Option<UncheckedAccount<'info>>,
+
+    #[account(mut)]
+    pub subscriber: Signer<'info>,
+
+    pub token_program: Program<'info, Token>,
+    pub system_program: Program<'info, System>,
+
+    /// Global emergency-pause registry
+    #[account(
+        seeds = [b"protocol-status"],
+        bump = protocol_status.bump,
+        seeds::program = vectai_protocol::ID,
+        constraint = !protocol_status.paused @ TraderError::ProtocolPaused
+    )]
+    pub protocol_status: Account<'info, ProtocolStatus>,
+}
+
+#[derive(Accounts)]
+pub struct RenewSubscription<'info> {
+    #[account(
+        seeds = [b"listing", listing.creator.as_ref(), &listing.listing_id.to_le_bytes()],
+        bump = listing.bump
+    )]
+    pub listing: Account<'info, StrategyListing>,
+
+    #[account(
+        mut,
+        seeds = [b"subscription", listing.key().as_ref(), subscriber.key().as_ref()],
+        bump = subscription.bump
+    )]
+    pub subscription: Account<'info, Subscription>,
+
+    #[account(
+        mut,
+        seeds = [b"trader", subscriber.key().as_ref(), listing.key().as_ref()],
+        bump,
+        address = subscription.trader_config
+    )]
+    pub trader_config: Account<'info, TraderConfig>,
+
+    #[account(
+        mut,
+        constraint = subscriber_vectai_account.mint == VECTAI_MINT @ TraderError::InvalidTokenAccount,
+        constraint = subscriber_vectai_account.owner == subscriber.key() @ TraderError::InvalidTokenAccount
+    )]
+    pub subscriber_vectai_account: Account<'info, TokenAccount>,
+
+    #[account(mut, address = listing.creator_fee_account)]
+    pub creator_fee_account: Account<'info, TokenAccount>,
+
+    /// Treasury VECTAI vault
+    #[account(
+        mut,
+        address = vectai_treasury::vault_address(&VECTAI_MINT) @ TraderError::InvalidTokenAccount
+    )]
+    pub protocol_fee_account: Account<'info, TokenAccount>,
+
+    /// Referrer's VECTAI escrow; required when the trader has a referrer
+    #[account(mut)]
+    pub referral_escrow: Option<Account<'info, TokenAccount>>,
+
+    /// Subscriber's collection NFT; required when the listing is gated
+    pub membership_token: Option<Account<'info, TokenAccount>>,
+
+    /// CHECK: Metaplex metadata of `membership_token`, parsed in the handler
+    pub membership_metadata: Option<UncheckedAccount<'info>>,
+
+    pub subscriber: Signer<'info>,
+
+    pub token_program: Program<'info, Token>,
+
+    /// Global emergency-pause registry
+    #[account(
+        seeds = [b"protocol-status"],
+        bump = protocol_status.bump,
+        seeds::program = vectai_protocol::ID,
+        constraint = !protocol_status.paused @ TraderError::ProtocolPaused
+    )]
+    pub protocol_status: Account<'info, ProtocolStatus>,
+}
+
+/// The listing may already be closed; the subscription records its key
+#[derive(Accounts)]
+pub struct CloseSubscription<'info> {
+    #[account(
+        mut,
+        close = subscriber,
+        seeds = [b"subscription", subscription.listing.as_ref(), subscriber.key().as_ref()],
+        bump = subscription.bump,
+        has_one = subscriber @ TraderError::Unauthorized
+    )]
+    pub subscription: Account<'info, Subscription>,
+
+    #[account(mut)]
+    pub subscriber: Signer<'info>,
+}
+
+/// Published strategy: trader parameters plus subscription terms
+#[account]
+pub struct StrategyListing {
+    pub creator: Pubkey,
+    pub listing_id: u64,
+    pub price_threshold: i64,
+    pub swap_amount: u64,
+    pub slippage_tolerance: u64, // Basis points
+    pub fee_amount: u64, // VECTAI per period
+    pub period_duration: i64, // Seconds
+    pub creator_fee_account: Pubkey,
+    pub subscribers: u64,
+    pub total_revenue: u64,
+    pub is_active: bool,
+    pub required_collection: Pubkey, // Verified collection NFT needed to subscribe; default = open
+    pub bump: u8,
+}
+
+impl StrategyListing {
+    pub const LEN: usize = 8 + // discriminator
+        32 + // creator
+        8 +  // listing_id
+        8 +  // price_threshold
+        8 +  // swap_amount
+        8 +  // slippage_tolerance
+        8 +  // fee_amount
+        8 +  // period_duration
+        32 + // creator_fee_account
+        8 +  // subscribers
+        8 +  // total_revenue
+        1 +  // is_active
+        32 + // required_collection
+        1;   // bump
+
+    /// Split `fee_amount` into (creator, protocol) shares
+    pub fn split_fee(&self) -> (u64, u64) {
+        let protocol = vectai_common::bps_of(self.fee_amount, PROTOCOL_FEE_BPS);
+        (self.fee_amount - protocol, protocol)
+    }
+}
+
+/// A subscriber's paid access to a listing
+#[account]
+pub struct Subscription {
+    pub subscriber: Pubkey,
+    pub listing: Pubkey,
+    pub trader_config: Pubkey,
+    pub paid_until: i64,
+    pub bump: u8,
+}
+
+impl Subscription {
+    pub const LEN: usize = 8 + // discriminator
+        32 + // subscriber
+        32 + // listing
+        32 + // trader_config
+        8 +  // paid_until
+        1;   // bump
+}
+
+impl TraderConfig {
+    /// Trailing PDA seed: the listing for a subscription's config, so a
+    /// wallet can hold one per listing next to its own strategy, whose
+    /// empty seed keeps the original `["trader", authority]` address
+    pub fn listing_seed(&self) -> &[u8] {
+        if self.listing == Pubkey::default() {
+            &[]
+        } else {
+            self.listing.as_ref()
+        }
+    }
+}
+
+#[event]
+pub struct SubscriptionPaid {
+    pub listing: Pubkey,
+    pub subscriber: Pubkey,
+    pub creator_amount: u64,
+    pub protocol_amount: u64,
+    pub referral_amount: u64,
+    pub paid_until: i64,
+}
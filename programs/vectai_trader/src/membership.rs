@@ -0,0 +1,95 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token::TokenAccount;
+
+use crate::TraderError;
+
+/// Metaplex Token Metadata program
+pub const TOKEN_METADATA_PROGRAM: Pubkey = anchor_lang::solana_program::pubkey!("metaqbxxUerdq28cj1RbAWkYQm3ybzjb6a8bt518x1s");
+
+// Metadata account discriminator (Key::MetadataV1)
+const METADATA_V1_KEY: u8 = 4;
+
+/// Creator entry of a Metaplex metadata account
+#[derive(AnchorDeserialize)]
+struct MetadataCreator {
+    _address: Pubkey,
+    _verified: bool,
+    _share: u8,
+}
+
+/// Collection membership of a Metaplex metadata account
+#[derive(AnchorDeserialize)]
+struct MetadataCollection {
+    verified: bool,
+    key: Pubkey,
+}
+
+/// Leading fields of a Metaplex metadata account, up to its collection;
+/// the trailing fields are never read
+#[derive(AnchorDeserialize)]
+struct MetadataPrefix {
+    key: u8,
+    _update_authority: Pubkey,
+    mint: Pubkey,
+    _name: String,
+    _symbol: String,
+    _uri: String,
+    _seller_fee_basis_points: u16,
+    _creators: Option<Vec<MetadataCreator>>,
+    _primary_sale_happened: bool,
+    _is_mutable: bool,
+    _edition_nonce: Option<u8>,
+    _token_standard: Option<u8>,
+    collection: Option<MetadataCollection>,
+}
+
+/// Metadata PDA of `mint`
+pub fn metadata_address(mint: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(
+        &[b"metadata", TOKEN_METADATA_PROGRAM.as_ref(), mint.as_ref()],
+        &TOKEN_METADATA_PROGRAM,
+    )
+    .0
+}
+
+/// Require `owner` to hold an NFT whose metadata carries a verified
+/// `collection`; a zero `collection` means the product is ungated
+pub fn require_collection_nft(
+    collection: Pubkey,
+    owner: Pubkey,
+    membership_token: &Option<Account<TokenAccount>>,
+    membership_metadata: &Option<UncheckedAccount>,
+) -> Result<()> {
+    if collection == Pubkey::default() {
+        return Ok(());
+    }
+    let (Some(token), Some(metadata)) = (membership_token, membership_metadata) else {
+        return err!(TraderError::MembershipRequired);
+    };
+
+    require!(
+        token.owner == owner && token.amount == 1,
+        TraderError::MembershipRequired
+    );
+    require!(
+        *metadata.owner == TOKEN_METADATA_PROGRAM && metadata.key() == metadata_address(&token.mint),
+        TraderError::MembershipRequired
+    );
+
+    // Older metadata without a collection field fails to parse and is
+    // treated as not a member
+    let data = metadata.try_borrow_data()?;
+    let parsed = MetadataPrefix::deserialize(&mut &data[..])
+        .map_err(|_| TraderError::MembershipRequired)?;
+    require!(
+        parsed.key == METADATA_V1_KEY && parsed.mint == token.mint,
+        TraderError::MembershipRequired
+    );
+    require!(
+        parsed
+            .collection
+            .is_some_and(|c| c.verified && c.key == collection),
+        TraderError::MembershipRequired
+    );
+    Ok(())
+}
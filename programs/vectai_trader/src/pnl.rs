@@ -0,0 +1,157 @@
+use anchor_lang::prelude::*;
+
+use vectai_protocol::ActivityKind;
+
+use crate::{
+    calculate_minimum_amount_out, execute_raydium_swap, raydium_swap_accounts, value_in_source_units, ExecuteTrade,
+    TraderConfig, TraderError,
+};
+
+/// Value of `amount` of `mint` in USDC base units: USDC at par, the
+/// pair's SOL leg at the oracle `price` with exponent `expo`
+pub fn usd_value(amount: u64, mint: &Pubkey, usdc_mint: &Pubkey, price: i64, expo: i32) -> Result<u64> {
+    if mint == usdc_mint {
+        Ok(amount)
+    } else {
+        value_in_source_units(amount, true, price, expo)
+    }
+}
+
+/// One execution's legs: `amount_in` of `source_mint` (trade fee included)
+/// swapped for `amount_out` of `destination_mint`
+pub struct ExecutionLegs {
+    pub source_mint: Pubkey,
+    pub amount_in: u64,
+    pub destination_mint: Pubkey,
+    pub amount_out: u64,
+}
+
+/// Mark both legs of an execution to USD at the oracle and book the
+/// difference on the strategy's realized PnL
+pub fn realize_pnl(
+    trader_config: &mut Account<TraderConfig>,
+    legs: ExecutionLegs,
+    usdc_mint: &Pubkey,
+    price: i64,
+    expo: i32,
+) -> Result<()> {
+    let value_in_usd = usd_value(legs.amount_in, &legs.source_mint, usdc_mint, price, expo)?;
+    let value_out_usd = usd_value(legs.amount_out, &legs.destination_mint, usdc_mint, price, expo)?;
+    let pnl_usd = vectai_common::realized_pnl(value_in_usd, value_out_usd)?;
+    trader_config.realized_pnl_usd = trader_config
+        .realized_pnl_usd
+        .checked_add(pnl_usd)
+        .ok_or(TraderError::MathOverflow)?;
+
+    emit!(PnlRealized {
+        account: trader_config.key(),
+        value_in_usd,
+        value_out_usd,
+        pnl_usd,
+        realized_pnl_usd: trader_config.realized_pnl_usd,
+    });
+    Ok(())
+}
+
+impl TraderConfig {
+    /// Whether realized PnL has reached the strategy's profit target
+    pub fn profit_target_reached(&self) -> bool {
+        self.profit_target_usd > 0
+            && u64::try_from(self.realized_pnl_usd).is_ok_and(|pnl| pnl >= self.profit_target_usd)
+    }
+}
+
+/// Once an execution brings realized PnL to the profit target, deactivate
+/// the strategy and sell what it holds outside USDC back into USDC through
+/// the pinned pool, marking that sale like any execution
+pub fn complete_at_profit_target(
+    accounts: &mut ExecuteTrade,
+    stats_reporter_bump: u8,
+    price: i64,
+    expo: i32,
+    slippage_bps: u64,
+) -> Result<()> {
+    if !accounts.trader_config.profit_target_reached() {
+        return Ok(());
+    }
+    accounts.trader_config.is_active = false;
+
+    // The position is whichever side of the pair isn't USDC
+    let usdc_mint = accounts.network_config.usdc_mint;
+    accounts.user_source_token_account.reload()?;
+    accounts.user_destination_token_account.reload()?;
+    let reverse = accounts.user_source_token_account.mint == usdc_mint;
+    let (position, quote) = if reverse {
+        (&accounts.user_destination_token_account, &accounts.user_source_token_account)
+    } else {
+        (&accounts.user_source_token_account, &accounts.user_destination_token_account)
+    };
+    let (position_mint, residual, quote_before) = (position.mint, position.amount, quote.amount);
+
+    let mut proceeds = 0;
+    if residual > 0 {
+        let minimum_output = calculate_minimum_amount_out(residual, slippage_bps)?;
+        execute_raydium_swap(&mut raydium_swap_accounts(accounts, reverse), residual, minimum_output, &[])?;
+        let quote = if reverse {
+            &mut accounts.user_source_token_account
+        } else {
+            &mut accounts.user_destination_token_account
+        };
+        quote.reload()?;
+        proceeds = quote.amount.checked_sub(quote_before).ok_or(TraderError::MathOverflow)?;
+
+        vectai_protocol::report(
+            accounts.protocol_program.to_account_info(),
+            accounts.protocol_stats.to_account_info(),
+            accounts.stats_reporter.to_account_info(),
+            stats_reporter_bump,
+            ActivityKind::Execution,
+            position_mint,
+            residual,
+        )?;
+        realize_pnl(
+            &mut accounts.trader_config,
+            ExecutionLegs {
+                source_mint: position_mint,
+                amount_in: residual,
+                destination_mint: usdc_mint,
+                amount_out: proceeds,
+            },
+            &usdc_mint,
+            price,
+            expo,
+        )?;
+    }
+
+    let trader_config = &accounts.trader_config;
+    emit!(StrategyCompleted {
+        trader: trader_config.key(),
+        profit_target_usd: trader_config.profit_target_usd,
+        realized_pnl_usd: trader_config.realized_pnl_usd,
+        residual_sold: residual,
+        proceeds,
+    });
+    msg!("🏁 Profit target {} reached, strategy completed", trader_config.profit_target_usd);
+    Ok(())
+}
+
+/// An execution marked to USD at the oracle, in USDC base units
+#[event]
+pub struct PnlRealized {
+    pub account: Pubkey, // Strategy
+    pub value_in_usd: u64,
+    pub value_out_usd: u64,
+    pub pnl_usd: i64,
+    pub realized_pnl_usd: i64, // Strategy total after this execution
+}
+
+/// A strategy deactivated on reaching its profit target, with the sale of
+/// its residual position into USDC (none after a Jupiter route)
+#[event]
+pub struct StrategyCompleted {
+    pub trader: Pubkey,
+    pub profit_target_usd: u64,
+    pub realized_pnl_usd: i64, // Including the residual sale
+    pub residual_sold: u64, // Position sold, in its own base units
+    pub proceeds: u64, // USDC received for it
+}
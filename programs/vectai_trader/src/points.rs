@@ -0,0 +1,244 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token::{Mint, Token, TokenAccount};
+
+use vectai_protocol::ProtocolStatus;
+
+use crate::{TraderError, USDC_DECIMALS, VECTAI_MINT};
+
+/// Distinct pairs a trader can earn points on per day
+pub const MAX_PAIRS_PER_DAY: usize = 4;
+
+pub const SECONDS_PER_DAY: i64 = 86_400;
+
+#[derive(Accounts)]
+pub struct InitializePoints<'info> {
+    #[account(
+        init,
+        payer = admin,
+        space = PointsConfig::LEN,
+        seeds = [b"points-config"],
+        bump
+    )]
+    pub points_config: Account<'info, PointsConfig>,
+
+    #[account(address = VECTAI_MINT @ TraderError::InvalidTokenAccount)]
+    pub vectai_mint: Account<'info, Mint>,
+
+    /// VECTAI paid out on redemption; funded by plain transfers
+    #[account(
+        init,
+        payer = admin,
+        token::mint = vectai_mint,
+        token::authority = points_config,
+        seeds = [b"rewards-vault"],
+        bump
+    )]
+    pub rewards_vault: Account<'info, TokenAccount>,
+
+    #[account(mut)]
+    pub admin: Signer<'info>,
+
+    pub rent: Sysvar<'info, Rent>,
+    pub token_program: Program<'info, Token>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct SetPointsRates<'info> {
+    #[account(mut, seeds = [b"points-config"], bump = points_config.bump)]
+    pub points_config: Account<'info, PointsConfig>,
+
+    pub admin: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct OpenPointsLedger<'info> {
+    #[account(
+        init,
+        payer = authority,
+        space = PointsLedger::LEN,
+        seeds = [b"points", authority.key().as_ref()],
+        bump
+    )]
+    pub points_ledger: Account<'info, PointsLedger>,
+
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct RedeemPoints<'info> {
+    #[account(
+        mut,
+        seeds = [b"points", authority.key().as_ref()],
+        bump = points_ledger.bump,
+        has_one = authority @ TraderError::Unauthorized
+    )]
+    pub points_ledger: Account<'info, PointsLedger>,
+
+    #[account(mut, seeds = [b"points-config"], bump = points_config.bump)]
+    pub points_config: Account<'info, PointsConfig>,
+
+    #[account(mut, address = points_config.rewards_vault)]
+    pub rewards_vault: Account<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        constraint = authority_vectai_account.mint == VECTAI_MINT @ TraderError::InvalidTokenAccount
+    )]
+    pub authority_vectai_account: Account<'info, TokenAccount>,
+
+    pub authority: Signer<'info>,
+
+    pub token_program: Program<'info, Token>,
+
+    /// Global emergency-pause registry
+    #[account(
+        seeds = [b"protocol-status"],
+        bump = protocol_status.bump,
+        seeds::program = vectai_protocol::ID,
+        constraint = !protocol_status.paused @ TraderError::ProtocolPaused
+    )]
+    pub protocol_status: Account<'info, ProtocolStatus>,
+}
+
+#[derive(Accounts)]
+pub struct ClosePointsLedger<'info> {
+    /// Unredeemed points would be forfeited, so the balance must be zero
+    #[account(
+        mut,
+        close = authority,
+        seeds = [b"points", authority.key().as_ref()],
+        bump = points_ledger.bump,
+        has_one = authority @ TraderError::Unauthorized,
+        constraint = points_ledger.balance == 0 @ TraderError::AccountInUse
+    )]
+    pub points_ledger: Account<'info, PointsLedger>,
+
+    #[account(mut)]
+    pub authority: Signer<'info>,
+}
+
+/// Protocol-wide points rates, set by the admin or governance
+#[account]
+pub struct PointsConfig {
+    pub points_per_dollar: u64, // Points per $1 of executed volume
+    pub redemption_rate: u64, // VECTAI base units per point
+    pub daily_pair_cap: u64, // Max points per pair per trader per day
+    pub rewards_vault: Pubkey,
+    pub total_points_issued: u64,
+    pub total_points_redeemed: u64,
+    pub bump: u8,
+}
+
+impl PointsConfig {
+    pub const LEN: usize = 8 + // discriminator
+        8 +  // points_per_dollar
+        8 +  // redemption_rate
+        8 +  // daily_pair_cap
+        32 + // rewards_vault
+        8 +  // total_points_issued
+        8 +  // total_points_redeemed
+        1;   // bump
+
+    /// Points earned for `volume` in USDC base units, before caps
+    pub fn points_for_volume(&self, volume: u64) -> Result<u64> {
+        let points = volume as u128 * self.points_per_dollar as u128
+            / 10u128.pow(USDC_DECIMALS as u32);
+        u64::try_from(points).map_err(|_| TraderError::MathOverflow.into())
+    }
+}
+
+/// Points credited to a pair on the ledger's current day
+#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+pub struct PairCredit {
+    pub mint_a: Pubkey, // Lower of the two mints, so A->B and B->A share a cap
+    pub mint_b: Pubkey,
+    pub points: u64,
+}
+
+impl PairCredit {
+    pub const LEN: usize = 32 + 32 + 8;
+}
+
+/// A trader's points balance
+#[account]
+pub struct PointsLedger {
+    pub authority: Pubkey,
+    pub balance: u64,
+    pub lifetime_points: u64,
+    pub total_redeemed: u64,
+    pub day: i64, // unix_timestamp / SECONDS_PER_DAY of `day_credits`
+    pub day_credits: Vec<PairCredit>,
+    pub bump: u8,
+}
+
+impl PointsLedger {
+    pub const LEN: usize = 8 + // discriminator
+        32 + // authority
+        8 +  // balance
+        8 +  // lifetime_points
+        8 +  // total_redeemed
+        8 +  // day
+        4 + MAX_PAIRS_PER_DAY * PairCredit::LEN + // day_credits
+        1;   // bump
+
+    /// Credit `earned` points for a trade on a pair, capped per pair per
+    /// day so round-tripping one pair cannot farm points. Returns the
+    /// points actually credited.
+    pub fn credit(
+        &mut self,
+        mint_x: Pubkey,
+        mint_y: Pubkey,
+        earned: u64,
+        daily_pair_cap: u64,
+        now: i64,
+    ) -> Result<u64> {
+        let day = now / SECONDS_PER_DAY;
+        if day != self.day {
+            self.day = day;
+            self.day_credits.clear();
+        }
+
+        let (mint_a, mint_b) = if mint_x < mint_y { (mint_x, mint_y) } else { (mint_y, mint_x) };
+        let index = match self
+            .day_credits
+            .iter()
+            .position(|c| c.mint_a == mint_a && c.mint_b == mint_b)
+        {
+            Some(index) => index,
+            None if self.day_credits.len() < MAX_PAIRS_PER_DAY => {
+                self.day_credits.push(PairCredit { mint_a, mint_b, points: 0 });
+                self.day_credits.len() - 1
+            }
+            None => return Ok(0),
+        };
+
+        let pair = &mut self.day_credits[index];
+        let points = earned.min(daily_pair_cap.saturating_sub(pair.points));
+        pair.points += points;
+        self.balance = self.balance.checked_add(points).ok_or(TraderError::MathOverflow)?;
+        self.lifetime_points = self
+            .lifetime_points
+            .checked_add(points)
+            .ok_or(TraderError::MathOverflow)?;
+        Ok(points)
+    }
+}
+
+#[event]
+pub struct PointsCredited {
+    pub trader: Pubkey, // TraderConfig
+    pub volume: u64, // USDC base units
+    pub points: u64,
+    pub balance: u64,
+}
+
+#[event]
+pub struct PointsRedeemed {
+    pub authority: Pubkey,
+    pub points: u64,
+    pub vectai_amount: u64,
+}
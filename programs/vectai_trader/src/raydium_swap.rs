@@ -1,10 +1,13 @@
 use anchor_lang::prelude::*;
-use anchor_lang::solana_program::{instruction::Instruction, program::invoke};
-use anchor_spl::token::TokenAccount;
+use anchor_lang::solana_program::{instruction::Instruction, program::invoke_signed};
 
 /// Raydium swap instruction discriminator
-/// This is the instruction byte for swap on Raydium AMM
+/// This is the instruction byte for swap on Raydium AMM: `SwapBaseIn`, or
+/// under `no-serum` `SwapBaseInV2`, which takes no OpenBook accounts
+#[cfg(not(feature = "no-serum"))]
 const RAYDIUM_SWAP_INSTRUCTION: u8 = 9;
+#[cfg(feature = "no-serum")]
+const RAYDIUM_SWAP_INSTRUCTION: u8 = 16;
 
 /// Raydium swap accounts structure
 /// Using AccountInfo for flexibility
@@ -12,17 +15,27 @@ pub struct RaydiumSwapAccounts<'info> {
     pub amm_program: AccountInfo<'info>,
     pub amm: AccountInfo<'info>,
     pub amm_authority: AccountInfo<'info>,
+    #[cfg(not(feature = "no-serum"))]
     pub amm_open_orders: AccountInfo<'info>,
+    #[cfg(not(feature = "no-serum"))]
     pub amm_target_orders: AccountInfo<'info>,
     pub pool_coin_token_account: AccountInfo<'info>,
     pub pool_pc_token_account: AccountInfo<'info>,
+    #[cfg(not(feature = "no-serum"))]
     pub serum_program: AccountInfo<'info>,
+    #[cfg(not(feature = "no-serum"))]
     pub serum_market: AccountInfo<'info>,
+    #[cfg(not(feature = "no-serum"))]
     pub serum_bids: AccountInfo<'info>,
+    #[cfg(not(feature = "no-serum"))]
     pub serum_asks: AccountInfo<'info>,
+    #[cfg(not(feature = "no-serum"))]
     pub serum_event_queue: AccountInfo<'info>,
+    #[cfg(not(feature = "no-serum"))]
     pub serum_coin_vault_account: AccountInfo<'info>,
+    #[cfg(not(feature = "no-serum"))]
     pub serum_pc_vault_account: AccountInfo<'info>,
+    #[cfg(not(feature = "no-serum"))]
     pub serum_vault_signer: AccountInfo<'info>,
     pub user_source_token_account: AccountInfo<'info>,
     pub user_destination_token_account: AccountInfo<'info>,
@@ -36,6 +49,7 @@ pub struct RaydiumSwapAccounts<'info> {
 /// * `accounts` - All accounts required for Raydium swap
 /// * `amount_in` - Amount of input tokens to swap
 /// * `minimum_amount_out` - Minimum acceptable output tokens (slippage protection)
+/// * `signer_seeds` - Seeds when `user_source_owner` is a PDA (empty otherwise)
 /// 
 /// # Returns
 /// * `Result<u64>` - Actual amount of output tokens received
@@ -43,6 +57,7 @@ pub fn execute_raydium_swap(
     accounts: &mut RaydiumSwapAccounts,
     amount_in: u64,
     minimum_amount_out: u64,
+    signer_seeds: &[&[&[u8]]],
 ) -> Result<u64> {
     msg!("🔄 Executing Raydium swap...");
     msg!("   Amount in: {}", amount_in);
@@ -62,18 +77,28 @@ pub fn execute_raydium_swap(
         // AMM accounts
         AccountMeta::new(accounts.amm.key(), false),
         AccountMeta::new_readonly(accounts.amm_authority.key(), false),
+        #[cfg(not(feature = "no-serum"))]
         AccountMeta::new(accounts.amm_open_orders.key(), false),
+        #[cfg(not(feature = "no-serum"))]
         AccountMeta::new(accounts.amm_target_orders.key(), false),
         AccountMeta::new(accounts.pool_coin_token_account.key(), false),
         AccountMeta::new(accounts.pool_pc_token_account.key(), false),
         // Serum market accounts
+        #[cfg(not(feature = "no-serum"))]
         AccountMeta::new_readonly(accounts.serum_program.key(), false),
+        #[cfg(not(feature = "no-serum"))]
         AccountMeta::new(accounts.serum_market.key(), false),
+        #[cfg(not(feature = "no-serum"))]
         AccountMeta::new(accounts.serum_bids.key(), false),
+        #[cfg(not(feature = "no-serum"))]
         AccountMeta::new(accounts.serum_asks.key(), false),
+        #[cfg(not(feature = "no-serum"))]
         AccountMeta::new(accounts.serum_event_queue.key(), false),
+        #[cfg(not(feature = "no-serum"))]
         AccountMeta::new(accounts.serum_coin_vault_account.key(), false),
+        #[cfg(not(feature = "no-serum"))]
         AccountMeta::new(accounts.serum_pc_vault_account.key(), false),
+        #[cfg(not(feature = "no-serum"))]
         AccountMeta::new_readonly(accounts.serum_vault_signer.key(), false),
         // User accounts
         AccountMeta::new(accounts.user_source_token_account.key(), false),
@@ -93,17 +118,27 @@ pub fn execute_raydium_swap(
         accounts.token_program.to_account_info(),
         accounts.amm.clone(),
         accounts.amm_authority.clone(),
+        #[cfg(not(feature = "no-serum"))]
         accounts.amm_open_orders.clone(),
+        #[cfg(not(feature = "no-serum"))]
         accounts.amm_target_orders.clone(),
         accounts.pool_coin_token_account.to_account_info(),
         accounts.pool_pc_token_account.to_account_info(),
+        #[cfg(not(feature = "no-serum"))]
         accounts.serum_program.clone(),
+        #[cfg(not(feature = "no-serum"))]
         accounts.serum_market.clone(),
+        #[cfg(not(feature = "no-serum"))]
         accounts.serum_bids.clone(),
+        #[cfg(not(feature = "no-serum"))]
         accounts.serum_asks.clone(),
+        #[cfg(not(feature = "no-serum"))]
         accounts.serum_event_queue.clone(),
+        #[cfg(not(feature = "no-serum"))]
         accounts.serum_coin_vault_account.clone(),
+        #[cfg(not(feature = "no-serum"))]
         accounts.serum_pc_vault_account.clone(),
+        #[cfg(not(feature = "no-serum"))]
         accounts.serum_vault_signer.clone(),
         accounts.user_source_token_account.to_account_info(),
         accounts.user_destination_token_account.to_account_info(),
@@ -112,7 +147,7 @@ pub fn execute_raydium_swap(
     
     // ===== STEP 5: Execute the CPI call to Raydium =====
     msg!("📞 Invoking Raydium AMM program...");
-    invoke(&swap_instruction, &account_infos)?;
+    invoke_signed(&swap_instruction, &account_infos, signer_seeds)?;
     
     msg!("✅ Raydium swap completed successfully");
     msg!("   Minimum output guaranteed: {}", minimum_amount_out);
@@ -134,15 +169,6 @@ pub fn calculate_minimum_amount_out(
     expected_amount: u64,
     slippage_bps: u64,
 ) -> Result<u64> {
-    // Calculate: expected_amount * (10000 - slippage_bps) / 10000
-    let multiplier = 10000u64
-        .checked_sub(slippage_bps)
-        .ok_or(ProgramError::InvalidArgument)?;
-    
-    let minimum = expected_amount
-        .checked_mul(multiplier)
-        .and_then(|x| x.checked_div(10000))
-        .ok_or(ProgramError::ArithmeticOverflow)?;
-    
-    Ok(minimum)
+    // Shared with the backtester so simulated fills use the same floor
+    vectai_common::minimum_amount_out(expected_amount, slippage_bps)
 }
@@ -0,0 +1,56 @@
+use anchor_lang::prelude::*;
+
+// Strategies mark their holdings, so `execute_trade` rebases the peak on
+// the balances that changed outside it
+pub use vectai_common::{DrawdownTracker, MAX_DRAWDOWN_LIMIT_BPS};
+
+/// Hours a `DailyTradeCap` counts executions over
+pub const TRADE_CAP_HOURS: usize = 24;
+
+/// Executions allowed in any rolling 24 hours, counted in hourly buckets,
+/// so a misbehaving keeper or oracle cannot drain the source account
+/// through many small trades that each pass every other check
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct DailyTradeCap {
+    pub max_trades: u16, // 0 = no cap
+    pub hourly_trades: [u16; TRADE_CAP_HOURS], // Indexed by hour since the epoch, mod 24
+    pub last_hour: i64, // Hour of the last recorded execution
+}
+
+impl DailyTradeCap {
+    pub const LEN: usize = 2 + // max_trades
+        2 * TRADE_CAP_HOURS + // hourly_trades
+        8; // last_hour
+
+    /// Executions in the 24 hours up to and including the hour of `now`
+    pub fn trades_in_window(&self, now: i64) -> u32 {
+        let hour = now.div_euclid(3_600);
+        let elapsed = hour - self.last_hour;
+        if elapsed >= TRADE_CAP_HOURS as i64 {
+            return 0;
+        }
+        // Buckets of the hours since the last execution were not written yet
+        (0..TRADE_CAP_HOURS as i64 - elapsed.max(0))
+            .map(|age| self.hourly_trades[(self.last_hour - age).rem_euclid(TRADE_CAP_HOURS as i64) as usize] as u32)
+            .sum()
+    }
+
+    pub fn allows(&self, now: i64) -> bool {
+        self.max_trades == 0 || self.trades_in_window(now) < self.max_trades as u32
+    }
+
+    /// Count an execution at `now`, clearing the buckets of the hours
+    /// since the last one
+    pub fn record(&mut self, now: i64) {
+        let hour = now.div_euclid(3_600);
+        let elapsed = (hour - self.last_hour).clamp(0, TRADE_CAP_HOURS as i64);
+        for offset in 1..=elapsed {
+            self.hourly_trades[(self.last_hour + offset).rem_euclid(TRADE_CAP_HOURS as i64) as usize] = 0;
+        }
+        self.last_hour = self.last_hour.max(hour);
+        let bucket = &mut self.hourly_trades[hour.rem_euclid(TRADE_CAP_HOURS as i64) as usize];
+        *bucket = bucket.saturating_add(1);
+    }
+}
+
+vectai_common::drawdown_events!();
@@ -0,0 +1,59 @@
+use anchor_lang::prelude::*;
+
+pub const MINUTES_PER_DAY: u16 = 24 * 60;
+
+/// Every day of the week, Monday (bit 0) to Sunday (bit 6)
+pub const ALL_DAYS: u8 = 0x7f;
+
+/// UTC window a strategy executes in, on the days set in `days`. A window
+/// with `end_minute` before `start_minute` runs past midnight into the
+/// next day; the day it opens on is the one that counts.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct TradingSchedule {
+    pub days: u8, // Bit 0 = Monday .. bit 6 = Sunday; 0 = no schedule
+    pub start_minute: u16, // Minutes after 00:00 UTC
+    pub end_minute: u16, // Exclusive
+}
+
+impl TradingSchedule {
+    pub const LEN: usize = 1 + // days
+        2 +  // start_minute
+        2;   // end_minute
+
+    /// No days and no window: the strategy executes at any time
+    pub fn is_unrestricted(&self) -> bool {
+        *self == TradingSchedule::default()
+    }
+
+    pub fn is_valid(&self) -> bool {
+        self.is_unrestricted()
+            || (self.days != 0
+                && self.days & !ALL_DAYS == 0
+                && self.start_minute < MINUTES_PER_DAY
+                && self.end_minute <= MINUTES_PER_DAY
+                && self.start_minute != self.end_minute)
+    }
+
+    /// Whether `unix_timestamp` falls in an open window
+    pub fn is_open(&self, unix_timestamp: i64) -> bool {
+        if self.is_unrestricted() {
+            return true;
+        }
+        let day = unix_timestamp.div_euclid(86_400);
+        let minute = (unix_timestamp.rem_euclid(86_400) / 60) as u16;
+        let opens_on = |day: i64| self.days & (1 << weekday(day)) != 0;
+        if self.start_minute < self.end_minute {
+            opens_on(day) && (self.start_minute..self.end_minute).contains(&minute)
+        } else if minute >= self.start_minute {
+            opens_on(day)
+        } else {
+            // Before the end of a window opened the previous day
+            minute < self.end_minute && opens_on(day - 1)
+        }
+    }
+}
+
+/// 0 = Monday .. 6 = Sunday, for days since 1970-01-01 (a Thursday)
+fn weekday(days_since_epoch: i64) -> u32 {
+    (days_since_epoch + 3).rem_euclid(7) as u32
+}
@@ -0,0 +1,28 @@
+use anchor_lang::prelude::*;
+
+/// Slippage derived from the oracle at each execution, as
+/// `base_bps + conf_factor_bps * conf / price`, in place of the fixed
+/// `slippage_tolerance`. A zero factor leaves the fixed tolerance in force.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct DynamicSlippage {
+    pub base_bps: u16,
+    pub conf_factor_bps: i32, // 10_000 = 1x the confidence ratio; negative tightens
+}
+
+impl DynamicSlippage {
+    pub const LEN: usize = 2 + // base_bps
+        4; // conf_factor_bps
+
+    pub fn is_enabled(&self) -> bool {
+        self.conf_factor_bps != 0
+    }
+
+    /// Slippage for a price read with confidence `conf`, clamped to
+    /// `max_bps`; `fixed_bps` when disabled
+    pub fn slippage_bps(&self, fixed_bps: u64, price: i64, conf: u64, max_bps: u64) -> Result<u64> {
+        if !self.is_enabled() {
+            return Ok(fixed_bps);
+        }
+        vectai_common::dynamic_slippage_bps(self.base_bps as u64, self.conf_factor_bps as i64, price, conf, max_bps)
+    }
+}
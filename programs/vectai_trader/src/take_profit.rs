@@ -0,0 +1,109 @@
+use anchor_lang::prelude::*;
+
+use crate::{TraderError, BPS_DENOMINATOR};
+
+/// Rungs one take-profit ladder holds
+pub const MAX_TAKE_PROFIT_RUNGS: usize = 4;
+
+/// A take-profit level as set by the owner: sell `fraction_bps` of the
+/// position once the oracle price reaches `price`
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct TakeProfitLevel {
+    pub price: i64,
+    pub fraction_bps: u16,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct TakeProfitRung {
+    pub level: TakeProfitLevel,
+    pub filled: bool,
+}
+
+impl TakeProfitRung {
+    pub const LEN: usize = 8 + // level.price
+        2 + // level.fraction_bps
+        1; // filled
+}
+
+/// Levels at which `execute_take_profit` scales out of the position, as
+/// fractions of its size when the first rung fills
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct TakeProfitLadder {
+    pub rungs: [TakeProfitRung; MAX_TAKE_PROFIT_RUNGS],
+    pub rung_count: u8, // 0 = no ladder
+    pub base_position: u64, // 0 until the first rung fills
+}
+
+impl TakeProfitLadder {
+    pub const LEN: usize = MAX_TAKE_PROFIT_RUNGS * TakeProfitRung::LEN + // rungs
+        1 + // rung_count
+        8; // base_position
+
+    /// Check `levels` holds up to `MAX_TAKE_PROFIT_RUNGS` positive prices in
+    /// ascending order, with positive fractions summing to at most 100%
+    pub fn validate(levels: &[TakeProfitLevel]) -> Result<()> {
+        require!(levels.len() <= MAX_TAKE_PROFIT_RUNGS, TraderError::InvalidTakeProfit);
+        require!(
+            levels.iter().all(|level| level.price > 0 && level.fraction_bps > 0),
+            TraderError::InvalidTakeProfit
+        );
+        require!(
+            levels.windows(2).all(|pair| pair[0].price < pair[1].price),
+            TraderError::InvalidTakeProfit
+        );
+        require!(
+            levels.iter().map(|level| level.fraction_bps as u64).sum::<u64>() <= BPS_DENOMINATOR,
+            TraderError::InvalidTakeProfit
+        );
+        Ok(())
+    }
+
+    /// A fresh ladder of `levels`, none filled; empty clears it
+    pub fn new(levels: &[TakeProfitLevel]) -> Self {
+        let mut ladder = TakeProfitLadder {
+            rung_count: levels.len() as u8,
+            ..TakeProfitLadder::default()
+        };
+        for (rung, level) in ladder.rungs.iter_mut().zip(levels) {
+            rung.level = *level;
+        }
+        ladder
+    }
+
+    pub fn rungs(&self) -> &[TakeProfitRung] {
+        &self.rungs[..self.rung_count as usize]
+    }
+
+    /// Mark every unfilled rung at or below `price` filled and return how
+    /// much of `position` they sell, with the number filled; `None` when no
+    /// rung was reached
+    pub fn fill(&mut self, price: i64, position: u64) -> Option<(u64, u8)> {
+        let mut fraction_bps = 0u64;
+        let mut filled = 0u8;
+        for rung in self.rungs[..self.rung_count as usize].iter_mut() {
+            if !rung.filled && rung.level.price <= price {
+                rung.filled = true;
+                fraction_bps += rung.level.fraction_bps as u64;
+                filled += 1;
+            }
+        }
+        if filled == 0 {
+            return None;
+        }
+        if self.base_position == 0 {
+            self.base_position = position;
+        }
+        Some((vectai_common::bps_of(self.base_position, fraction_bps).min(position), filled))
+    }
+}
+
+#[event]
+pub struct TakeProfitFilled {
+    pub trader: Pubkey,
+    pub rungs_filled: u8,
+    pub amount_in: u64,
+    pub trade_fee: u64,
+    pub expected_out: u64,
+    pub oracle_price: i64,
+    pub timestamp: i64,
+}
@@ -0,0 +1,240 @@
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::{
+    hash::hash,
+    instruction::Instruction,
+    keccak,
+    program::{invoke, invoke_signed},
+};
+
+use crate::{TraderConfig, TraderError};
+
+/// SPL account compression program; owns the concurrent Merkle trees
+pub const ACCOUNT_COMPRESSION_PROGRAM: Pubkey =
+    anchor_lang::solana_program::pubkey!("cmtDvXumGCrqC1Age74AVPhSRVXJMd8PJS91L8KbNCK");
+
+/// SPL noop program; full trade records are logged through it for indexers
+pub const NOOP_PROGRAM: Pubkey =
+    anchor_lang::solana_program::pubkey!("noopb9bkMVfRPU8AsbpTUg8AQkHtKwMYZiFUjNRtMmV");
+
+/// Deepest tree account compression supports (2^30 trades)
+pub const MAX_TREE_DEPTH: u32 = 30;
+
+#[derive(Accounts)]
+pub struct InitializeTradeHistory<'info> {
+    #[account(
+        seeds = [b"trader", authority.key().as_ref(), trader_config.listing_seed()],
+        bump,
+        has_one = authority @ TraderError::Unauthorized
+    )]
+    pub trader_config: Account<'info, TraderConfig>,
+
+    /// Tree authority; signs every append
+    #[account(
+        init,
+        payer = authority,
+        space = TradeHistory::LEN,
+        seeds = [b"trade-history", trader_config.key().as_ref()],
+        bump
+    )]
+    pub trade_history: Account<'info, TradeHistory>,
+
+    /// CHECK: Allocated by the client for `max_depth`/`max_buffer_size` and
+    /// owned by account compression, which validates and initializes it
+    #[account(mut)]
+    pub merkle_tree: UncheckedAccount<'info>,
+
+    /// CHECK: Address checked
+    #[account(address = ACCOUNT_COMPRESSION_PROGRAM @ TraderError::InvalidTradeHistory)]
+    pub compression_program: UncheckedAccount<'info>,
+
+    /// CHECK: Address checked
+    #[account(address = NOOP_PROGRAM @ TraderError::InvalidTradeHistory)]
+    pub noop_program: UncheckedAccount<'info>,
+
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct VerifyTradeRecord<'info> {
+    #[account(
+        seeds = [b"trade-history", trade_history.trader.as_ref()],
+        bump = trade_history.bump,
+        has_one = merkle_tree @ TraderError::InvalidTradeHistory
+    )]
+    pub trade_history: Account<'info, TradeHistory>,
+
+    /// CHECK: Matched against `trade_history`; validated by account compression
+    pub merkle_tree: UncheckedAccount<'info>,
+
+    /// CHECK: Address checked
+    #[account(address = ACCOUNT_COMPRESSION_PROGRAM @ TraderError::InvalidTradeHistory)]
+    pub compression_program: UncheckedAccount<'info>,
+    // remaining_accounts: proof nodes, leaf to root, above the tree's canopy
+}
+
+/// Compressed per-trade history of one strategy. Rent is fixed by the
+/// tree's size, however many trades it records.
+#[account]
+pub struct TradeHistory {
+    pub trader: Pubkey, // TraderConfig
+    pub merkle_tree: Pubkey,
+    pub max_depth: u32,
+    pub max_buffer_size: u32,
+    pub record_count: u64, // Leaves appended; also the next leaf index
+    pub bump: u8,
+}
+
+impl TradeHistory {
+    pub const LEN: usize = 8 + // discriminator
+        32 + // trader
+        32 + // merkle_tree
+        4 +  // max_depth
+        4 +  // max_buffer_size
+        8 +  // record_count
+        1;   // bump
+
+    pub fn capacity(&self) -> u64 {
+        1u64 << self.max_depth
+    }
+}
+
+/// One executed trade. Its keccak hash is the tree leaf; the full record
+/// is logged through the noop program so indexers can rebuild proofs.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+pub struct TradeRecord {
+    pub trader: Pubkey, // TraderConfig
+    pub index: u64, // Leaf index
+    pub source_mint: Pubkey,
+    pub destination_mint: Pubkey,
+    pub amount_in: u64, // Including the trade fee
+    pub trade_fee: u64,
+    pub expected_out: u64,
+    pub oracle_price: i64,
+    pub timestamp: i64,
+}
+
+impl TradeRecord {
+    pub fn leaf(&self) -> Result<[u8; 32]> {
+        Ok(keccak::hash(&self.try_to_vec()?).to_bytes())
+    }
+}
+
+/// Create the concurrent Merkle tree with `trade_history` as its authority
+pub fn init_tree<'info>(
+    accounts: &InitializeTradeHistory<'info>,
+    max_depth: u32,
+    max_buffer_size: u32,
+    signer_seeds: &[&[&[u8]]],
+) -> Result<()> {
+    let mut args = max_depth.to_le_bytes().to_vec();
+    args.extend_from_slice(&max_buffer_size.to_le_bytes());
+    invoke_compression(
+        "init_empty_merkle_tree",
+        args,
+        &accounts.merkle_tree.to_account_info(),
+        &accounts.trade_history.to_account_info(),
+        &accounts.noop_program.to_account_info(),
+        &accounts.compression_program.to_account_info(),
+        signer_seeds,
+    )
+}
+
+/// Log `record` through noop and append its leaf to the tree
+pub fn append_record<'info>(
+    record: &TradeRecord,
+    trade_history: &AccountInfo<'info>,
+    merkle_tree: &AccountInfo<'info>,
+    compression_program: &AccountInfo<'info>,
+    noop_program: &AccountInfo<'info>,
+    signer_seeds: &[&[&[u8]]],
+) -> Result<()> {
+    let log = Instruction {
+        program_id: NOOP_PROGRAM,
+        accounts: vec![],
+        data: record.try_to_vec()?,
+    };
+    invoke(&log, std::slice::from_ref(noop_program))?;
+
+    invoke_compression(
+        "append",
+        record.leaf()?.to_vec(),
+        merkle_tree,
+        trade_history,
+        noop_program,
+        compression_program,
+        signer_seeds,
+    )
+}
+
+/// Fails unless `leaf` sits at `index` under `root`, with the proof nodes
+/// in `proof`
+pub fn verify_leaf<'info>(
+    merkle_tree: &AccountInfo<'info>,
+    compression_program: &AccountInfo<'info>,
+    proof: &[AccountInfo<'info>],
+    root: [u8; 32],
+    leaf: [u8; 32],
+    index: u32,
+) -> Result<()> {
+    let mut data = compression_discriminator("verify_leaf").to_vec();
+    data.extend_from_slice(&root);
+    data.extend_from_slice(&leaf);
+    data.extend_from_slice(&index.to_le_bytes());
+
+    let mut metas = vec![AccountMeta::new_readonly(merkle_tree.key(), false)];
+    metas.extend(proof.iter().map(|node| AccountMeta::new_readonly(node.key(), false)));
+    let mut infos = vec![merkle_tree.clone(), compression_program.clone()];
+    infos.extend(proof.iter().cloned());
+
+    let ix = Instruction {
+        program_id: ACCOUNT_COMPRESSION_PROGRAM,
+        accounts: metas,
+        data,
+    };
+    invoke_signed(&ix, &infos, &[])?;
+    Ok(())
+}
+
+/// Anchor instruction discriminator for an account compression instruction
+fn compression_discriminator(name: &str) -> [u8; 8] {
+    let mut discriminator = [0u8; 8];
+    discriminator.copy_from_slice(&hash(format!("global:{}", name).as_bytes()).to_bytes()[..8]);
+    discriminator
+}
+
+/// Tree-modifying instruction: merkle_tree, authority (signer), noop
+fn invoke_compression<'info>(
+    name: &str,
+    args: Vec<u8>,
+    merkle_tree: &AccountInfo<'info>,
+    authority: &AccountInfo<'info>,
+    noop_program: &AccountInfo<'info>,
+    compression_program: &AccountInfo<'info>,
+    signer_seeds: &[&[&[u8]]],
+) -> Result<()> {
+    let mut data = compression_discriminator(name).to_vec();
+    data.extend_from_slice(&args);
+    let ix = Instruction {
+        program_id: ACCOUNT_COMPRESSION_PROGRAM,
+        accounts: vec![
+            AccountMeta::new(merkle_tree.key(), false),
+            AccountMeta::new_readonly(authority.key(), true),
+            AccountMeta::new_readonly(NOOP_PROGRAM, false),
+        ],
+        data,
+    };
+    invoke_signed(
+        &ix,
+        &[
+            merkle_tree.clone(),
+            authority.clone(),
+            noop_program.clone(),
+            compression_program.clone(),
+        ],
+        signer_seeds,
+    )?;
+    Ok(())
+}
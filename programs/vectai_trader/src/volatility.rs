@@ -0,0 +1,66 @@
+use anchor_lang::prelude::*;
+
+/// Oracle observations a strategy's volatility is measured over
+pub const VOLATILITY_WINDOW: usize = 8;
+
+/// Minimum seconds between two observations, so repeated calls within one
+/// move cannot flatten the window
+pub const VOLATILITY_SAMPLE_SECS: i64 = 60;
+
+/// Rolling window of the oracle prices a strategy triggered at. Once their
+/// standard deviation exceeds the bound, executions are skipped until the
+/// price settles, so a wick through the threshold does not fire the trade.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct VolatilityFilter {
+    pub max_volatility_bps: u16, // Standard deviation over the mean; 0 = off
+    pub samples: [i64; VOLATILITY_WINDOW],
+    pub sample_count: u8,
+    pub next_sample: u8, // Ring buffer slot the next observation overwrites
+    pub last_sample_time: i64,
+}
+
+impl VolatilityFilter {
+    pub const LEN: usize = 2 + // max_volatility_bps
+        8 * VOLATILITY_WINDOW + // samples
+        1 +  // sample_count
+        1 +  // next_sample
+        8;   // last_sample_time
+
+    pub fn is_enabled(&self) -> bool {
+        self.max_volatility_bps > 0
+    }
+
+    /// Bound the filter at `max_volatility_bps`, starting a fresh window
+    pub fn reset(&mut self, max_volatility_bps: u16) {
+        *self = VolatilityFilter {
+            max_volatility_bps,
+            ..VolatilityFilter::default()
+        };
+    }
+
+    /// Record `price` unless the last observation is under
+    /// `VOLATILITY_SAMPLE_SECS` old, and return the window's volatility in
+    /// bps when it exceeds the bound
+    pub fn observe(&mut self, price: i64, now: i64) -> Option<u64> {
+        if !self.is_enabled() {
+            return None;
+        }
+        if self.sample_count == 0 || now - self.last_sample_time >= VOLATILITY_SAMPLE_SECS {
+            self.samples[self.next_sample as usize] = price;
+            self.next_sample = ((self.next_sample as usize + 1) % VOLATILITY_WINDOW) as u8;
+            self.sample_count = (self.sample_count + 1).min(VOLATILITY_WINDOW as u8);
+            self.last_sample_time = now;
+        }
+        vectai_common::volatility_bps(&self.samples[..self.sample_count as usize])
+            .filter(|&volatility_bps| volatility_bps > self.max_volatility_bps as u64)
+    }
+}
+
+#[event]
+pub struct VolatilityLimitHit {
+    pub trader: Pubkey,
+    pub volatility_bps: u64,
+    pub max_volatility_bps: u16,
+    pub oracle_price: i64,
+    pub timestamp: i64,
+}
@@ -0,0 +1,405 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{Mint, TokenAccount};
+use spl_tlv_account_resolution::{account::ExtraAccountMeta, seeds::Seed, state::ExtraAccountMetaList};
+use spl_transfer_hook_interface::instruction::{ExecuteInstruction, TransferHookInstruction};
+
+declare_id!("99UeyXc2sJmfhoAcBTqU6u8NZgzwKb7kYu4ndnHwitmQ");
+
+// Accounts of a transfer-hook `Execute`: source, mint, destination, owner,
+// extra account metas, then the extra accounts in the order listed here
+const SOURCE_INDEX: u8 = 0;
+const MINT_INDEX: u8 = 1;
+const DESTINATION_INDEX: u8 = 2;
+
+// Token account layout: the owner follows the 32-byte mint
+const TOKEN_ACCOUNT_OWNER_OFFSET: u8 = 32;
+
+#[program]
+pub mod vectai_transfer_hook {
+    use super::*;
+
+    /// Create a mint's compliance rules and the extra accounts Token-2022
+    /// passes to every transfer of it (mint authority only). Transfers of a
+    /// mint whose hook points here fail until this has run.
+    pub fn initialize_compliance_rules(
+        ctx: Context<InitializeComplianceRules>,
+        compliance_authority: Pubkey,
+        max_transfer_amount: u64,
+        max_wallet_balance: u64,
+    ) -> Result<()> {
+        require!(
+            compliance_authority != Pubkey::default(),
+            TransferHookError::InvalidComplianceAuthority
+        );
+
+        let rules = &mut ctx.accounts.rules;
+        rules.mint = ctx.accounts.mint.key();
+        rules.authority = compliance_authority;
+        rules.max_transfer_amount = max_transfer_amount;
+        rules.max_wallet_balance = max_wallet_balance;
+        rules.bump = ctx.bumps.rules;
+
+        let mut data = ctx.accounts.extra_account_metas.try_borrow_mut_data()?;
+        ExtraAccountMetaList::init::<ExecuteInstruction>(&mut data, &extra_account_metas()?)?;
+
+        emit!(TransferLimitsUpdated {
+            mint: rules.mint,
+            max_transfer_amount,
+            max_wallet_balance,
+        });
+        msg!("✅ Compliance rules initialized (authority: {})", compliance_authority);
+        Ok(())
+    }
+
+    /// Cap the amount of one transfer and the balance a recipient may end
+    /// up holding; 0 removes either cap (compliance authority only)
+    pub fn set_transfer_limits(
+        ctx: Context<ManageRules>,
+        max_transfer_amount: u64,
+        max_wallet_balance: u64,
+    ) -> Result<()> {
+        let rules = &mut ctx.accounts.rules;
+        rules.max_transfer_amount = max_transfer_amount;
+        rules.max_wallet_balance = max_wallet_balance;
+
+        emit!(TransferLimitsUpdated {
+            mint: rules.mint,
+            max_transfer_amount,
+            max_wallet_balance,
+        });
+        msg!("✅ Transfer limits: {} per transfer, {} per wallet", max_transfer_amount, max_wallet_balance);
+        Ok(())
+    }
+
+    /// Deny `owner`'s token accounts every transfer in or out, or exempt
+    /// them from the transfer limits (compliance authority only)
+    pub fn set_owner_rule(
+        ctx: Context<SetOwnerRule>,
+        owner: Pubkey,
+        status: OwnerStatus,
+        reason_code: u16,
+    ) -> Result<()> {
+        require!(
+            status != OwnerStatus::Denied || reason_code != 0,
+            TransferHookError::MissingReasonCode
+        );
+
+        let rule = &mut ctx.accounts.owner_rule;
+        rule.mint = ctx.accounts.rules.mint;
+        rule.owner = owner;
+        rule.status = status;
+        rule.reason_code = reason_code;
+        rule.bump = ctx.bumps.owner_rule;
+
+        emit!(OwnerRuleSet {
+            mint: rule.mint,
+            owner,
+            status,
+            reason_code,
+        });
+        msg!("🚩 Owner {} set to {:?} (reason {})", owner, status, reason_code);
+        Ok(())
+    }
+
+    /// Drop an owner's rule, restoring the default limits (compliance
+    /// authority only)
+    pub fn clear_owner_rule(ctx: Context<ClearOwnerRule>) -> Result<()> {
+        emit!(OwnerRuleCleared {
+            mint: ctx.accounts.owner_rule.mint,
+            owner: ctx.accounts.owner_rule.owner,
+        });
+        msg!("✅ Owner {} rule cleared", ctx.accounts.owner_rule.owner);
+        Ok(())
+    }
+
+    /// Transfer-hook `Execute`, called by Token-2022 after it has moved
+    /// `amount`: rejects transfers from or to a denied owner, above the
+    /// per-transfer cap or leaving the recipient above the wallet cap
+    pub fn transfer_hook(ctx: Context<TransferHook>, amount: u64) -> Result<()> {
+        let source = owner_status(&ctx.accounts.source_owner_rule)?;
+        let destination = owner_status(&ctx.accounts.destination_owner_rule)?;
+        require!(source != Some(OwnerStatus::Denied), TransferHookError::SenderDenied);
+        require!(destination != Some(OwnerStatus::Denied), TransferHookError::RecipientDenied);
+
+        let rules = &ctx.accounts.rules;
+        if rules.max_transfer_amount > 0 && source != Some(OwnerStatus::Exempt) {
+            require!(amount <= rules.max_transfer_amount, TransferHookError::TransferTooLarge);
+        }
+        // The destination already holds the transferred amount
+        if rules.max_wallet_balance > 0 && destination != Some(OwnerStatus::Exempt) {
+            require!(
+                ctx.accounts.destination.amount <= rules.max_wallet_balance,
+                TransferHookError::WalletBalanceTooLarge
+            );
+        }
+        Ok(())
+    }
+
+    /// Route the interface's `Execute`, whose discriminator is not an
+    /// Anchor one, to `transfer_hook`
+    pub fn fallback<'info>(program_id: &Pubkey, accounts: &'info [AccountInfo<'info>], data: &[u8]) -> Result<()> {
+        match TransferHookInstruction::unpack(data)? {
+            TransferHookInstruction::Execute { amount } => {
+                __private::__global::transfer_hook(program_id, accounts, &amount.to_le_bytes())
+            }
+            _ => Err(ProgramError::InvalidInstructionData.into()),
+        }
+    }
+}
+
+/// The accounts Token-2022 resolves and appends to each `Execute`: the
+/// mint's rules, then the rules of the source and destination owners
+pub fn extra_account_metas() -> Result<Vec<ExtraAccountMeta>> {
+    let owner_rule = |account_index| {
+        ExtraAccountMeta::new_with_seeds(
+            &[
+                Seed::Literal { bytes: b"owner-rule".to_vec() },
+                Seed::AccountKey { index: MINT_INDEX },
+                Seed::AccountData {
+                    account_index,
+                    data_index: TOKEN_ACCOUNT_OWNER_OFFSET,
+                    length: 32,
+                },
+            ],
+            false,
+            false,
+        )
+    };
+    let rules = ExtraAccountMeta::new_with_seeds(
+        &[
+            Seed::Literal { bytes: b"compliance-rules".to_vec() },
+            Seed::AccountKey { index: MINT_INDEX },
+        ],
+        false,
+        false,
+    )?;
+    Ok(vec![rules, owner_rule(SOURCE_INDEX)?, owner_rule(DESTINATION_INDEX)?])
+}
+
+/// Address of `mint`'s `ComplianceRules`
+pub fn compliance_rules(mint: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(&[b"compliance-rules", mint.as_ref()], &crate::ID).0
+}
+
+/// Address of `owner`'s `OwnerRule` for `mint`, which only exists while a
+/// rule is set
+pub fn owner_rule(mint: &Pubkey, owner: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(&[b"owner-rule", mint.as_ref(), owner.as_ref()], &crate::ID).0
+}
+
+/// The rule an owner-rule PDA holds, `None` while it is unset
+fn owner_status(account: &AccountInfo) -> Result<Option<OwnerStatus>> {
+    if account.owner != &crate::ID || account.data_is_empty() {
+        return Ok(None);
+    }
+    let rule = OwnerRule::try_deserialize(&mut &account.try_borrow_data()?[..])?;
+    Ok(Some(rule.status))
+}
+
+#[derive(Accounts)]
+pub struct InitializeComplianceRules<'info> {
+    #[account(
+        constraint = mint.mint_authority == Some(mint_authority.key()).into() @ TransferHookError::Unauthorized
+    )]
+    pub mint: InterfaceAccount<'info, Mint>,
+
+    #[account(
+        init,
+        payer = payer,
+        space = ComplianceRules::LEN,
+        seeds = [b"compliance-rules", mint.key().as_ref()],
+        bump
+    )]
+    pub rules: Account<'info, ComplianceRules>,
+
+    /// CHECK: The transfer-hook interface's extra account metas, written
+    /// in the handler
+    #[account(
+        init,
+        payer = payer,
+        space = ExtraAccountMetaList::size_of(3)?,
+        seeds = [b"extra-account-metas", mint.key().as_ref()],
+        bump
+    )]
+    pub extra_account_metas: UncheckedAccount<'info>,
+
+    pub mint_authority: Signer<'info>,
+
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct ManageRules<'info> {
+    #[account(
+        mut,
+        seeds = [b"compliance-rules", rules.mint.as_ref()],
+        bump = rules.bump,
+        has_one = authority @ TransferHookError::Unauthorized
+    )]
+    pub rules: Account<'info, ComplianceRules>,
+
+    pub authority: Signer<'info>,
+}
+
+#[derive(Accounts)]
+#[instruction(owner: Pubkey)]
+pub struct SetOwnerRule<'info> {
+    #[account(
+        seeds = [b"compliance-rules", rules.mint.as_ref()],
+        bump = rules.bump,
+        has_one = authority @ TransferHookError::Unauthorized
+    )]
+    pub rules: Account<'info, ComplianceRules>,
+
+    #[account(
+        init_if_needed,
+        payer = authority,
+        space = OwnerRule::LEN,
+        seeds = [b"owner-rule", rules.mint.as_ref(), owner.as_ref()],
+        bump
+    )]
+    pub owner_rule: Account<'info, OwnerRule>,
+
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct ClearOwnerRule<'info> {
+    #[account(
+        seeds = [b"compliance-rules", rules.mint.as_ref()],
+        bump = rules.bump,
+        has_one = authority @ TransferHookError::Unauthorized
+    )]
+    pub rules: Account<'info, ComplianceRules>,
+
+    #[account(
+        mut,
+        close = authority,
+        seeds = [b"owner-rule", rules.mint.as_ref(), owner_rule.owner.as_ref()],
+        bump = owner_rule.bump
+    )]
+    pub owner_rule: Account<'info, OwnerRule>,
+
+    #[account(mut)]
+    pub authority: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct TransferHook<'info> {
+    #[account(token::mint = mint)]
+    pub source: InterfaceAccount<'info, TokenAccount>,
+
+    pub mint: InterfaceAccount<'info, Mint>,
+
+    #[account(token::mint = mint)]
+    pub destination: InterfaceAccount<'info, TokenAccount>,
+
+    /// CHECK: Source owner or delegate, already checked by Token-2022
+    pub owner: UncheckedAccount<'info>,
+
+    /// CHECK: Extra account metas PDA, read by Token-2022
+    #[account(seeds = [b"extra-account-metas", mint.key().as_ref()], bump)]
+    pub extra_account_metas: UncheckedAccount<'info>,
+
+    #[account(seeds = [b"compliance-rules", mint.key().as_ref()], bump = rules.bump)]
+    pub rules: Account<'info, ComplianceRules>,
+
+    /// CHECK: Source owner's `OwnerRule` PDA; empty while unset
+    #[account(seeds = [b"owner-rule", mint.key().as_ref(), source.owner.as_ref()], bump)]
+    pub source_owner_rule: UncheckedAccount<'info>,
+
+    /// CHECK: Destination owner's `OwnerRule` PDA; empty while unset
+    #[account(seeds = [b"owner-rule", mint.key().as_ref(), destination.owner.as_ref()], bump)]
+    pub destination_owner_rule: UncheckedAccount<'info>,
+}
+
+/// Anti-whale limits and the authority managing a mint's rules
+#[account]
+pub struct ComplianceRules {
+    pub mint: Pubkey,
+    pub authority: Pubkey,
+    pub max_transfer_amount: u64, // 0 = no cap
+    pub max_wallet_balance: u64, // Recipient balance after a transfer; 0 = no cap
+    pub bump: u8,
+}
+
+impl ComplianceRules {
+    pub const LEN: usize = 8 + // discriminator
+        32 + // mint
+        32 + // authority
+        8 +  // max_transfer_amount
+        8 +  // max_wallet_balance
+        1;   // bump
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum OwnerStatus {
+    /// No transfers in or out of the owner's accounts
+    Denied,
+    /// Not bound by the transfer limits, e.g. pools and the treasury
+    Exempt,
+}
+
+/// A rule on every token account one owner holds of a mint
+#[account]
+pub struct OwnerRule {
+    pub mint: Pubkey,
+    pub owner: Pubkey,
+    pub status: OwnerStatus,
+    pub reason_code: u16, // Required when denied
+    pub bump: u8,
+}
+
+impl OwnerRule {
+    pub const LEN: usize = 8 + // discriminator
+        32 + // mint
+        32 + // owner
+        1 +  // status
+        2 +  // reason_code
+        1;   // bump
+}
+
+#[event]
+pub struct TransferLimitsUpdated {
+    pub mint: Pubkey,
+    pub max_transfer_amount: u64,
+    pub max_wallet_balance: u64,
+}
+
+#[event]
+pub struct OwnerRuleSet {
+    pub mint: Pubkey,
+    pub owner: Pubkey,
+    pub status: OwnerStatus,
+    pub reason_code: u16,
+}
+
+#[event]
+pub struct OwnerRuleCleared {
+    pub mint: Pubkey,
+    pub owner: Pubkey,
+}
+
+#[error_code]
+pub enum TransferHookError {
+    #[msg("Unauthorized")]
+    Unauthorized,
+    #[msg("Invalid compliance authority")]
+    InvalidComplianceAuthority,
+    #[msg("A non-zero reason code is required")]
+    MissingReasonCode,
+    #[msg("Sender is denied")]
+    SenderDenied,
+    #[msg("Recipient is denied")]
+    RecipientDenied,
+    #[msg("Transfer above the per-transfer cap")]
+    TransferTooLarge,
+    #[msg("Transfer leaves the recipient above the wallet cap")]
+    WalletBalanceTooLarge,
+}
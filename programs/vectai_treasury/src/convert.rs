@@ -0,0 +1,119 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token::{Token, TokenAccount};
+use raydium_swapper::cpi::accounts::StableSwapAccounts;
+use raydium_swapper::program::RaydiumSwapper;
+
+use crate::{Treasury, TreasuryError};
+
+#[derive(Accounts)]
+pub struct ConvertStable<'info> {
+    #[account(seeds = [b"treasury"], bump = treasury.bump)]
+    pub treasury: Account<'info, Treasury>,
+
+    #[account(
+        mut,
+        seeds = [b"treasury-vault", source_vault.mint.as_ref()],
+        bump
+    )]
+    pub source_vault: Account<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        seeds = [b"treasury-vault", destination_vault.mint.as_ref()],
+        bump
+    )]
+    pub destination_vault: Account<'info, TokenAccount>,
+
+    #[account(address = treasury.governance @ TreasuryError::Unauthorized)]
+    pub governance: Signer<'info>,
+
+    pub swapper_program: Program<'info, RaydiumSwapper>,
+
+    // ===== STABLE POOL ACCOUNTS (validated by raydium_swapper) =====
+
+    /// CHECK: StableSwap program
+    pub stable_swap_program: UncheckedAccount<'info>,
+
+    /// CHECK: Stable pool
+    pub pool: UncheckedAccount<'info>,
+
+    /// CHECK: Stable pool authority
+    pub pool_authority: UncheckedAccount<'info>,
+
+    /// CHECK: Pool vault of the source mint
+    #[account(mut)]
+    pub pool_source: UncheckedAccount<'info>,
+
+    /// CHECK: Pool vault of the destination mint
+    #[account(mut)]
+    pub pool_destination: UncheckedAccount<'info>,
+
+    /// CHECK: Pool admin fee account for the destination mint
+    #[account(mut)]
+    pub admin_fee_destination: UncheckedAccount<'info>,
+
+    pub token_program: Program<'info, Token>,
+
+    /// Venue registry of the pair, forwarded to the swapper
+    /// CHECK: validated by raydium_swapper
+    pub venue_registry: UncheckedAccount<'info>,
+
+    /// Global emergency-pause registry, forwarded to the swapper
+    /// CHECK: validated by raydium_swapper
+    pub protocol_status: UncheckedAccount<'info>,
+
+    /// Shared network addresses, forwarded to the swapper
+    /// CHECK: validated by raydium_swapper
+    pub network_config: UncheckedAccount<'info>,
+
+    /// Protocol activity counters, forwarded to the swapper
+    /// CHECK: validated by vectai_protocol
+    #[account(mut)]
+    pub protocol_stats: UncheckedAccount<'info>,
+
+    /// The swapper's stats reporter PDA, forwarded to the swapper
+    /// CHECK: validated by raydium_swapper
+    pub swapper_stats_reporter: UncheckedAccount<'info>,
+
+    /// CHECK: validated by raydium_swapper
+    pub protocol_program: UncheckedAccount<'info>,
+
+    /// Fee vault of the source mint (the source vault itself), forwarded
+    /// to the swapper while the swap fee is on
+    /// CHECK: validated by raydium_swapper
+    #[account(mut)]
+    pub swap_fee_account: Option<UncheckedAccount<'info>>,
+}
+
+impl<'info> ConvertStable<'info> {
+    /// Swapper accounts with the treasury PDA as the user
+    pub fn swapper_accounts(&self) -> StableSwapAccounts<'info> {
+        StableSwapAccounts {
+            user_authority: self.treasury.to_account_info(),
+            user_source_token: self.source_vault.to_account_info(),
+            user_destination_token: self.destination_vault.to_account_info(),
+            stable_swap_program: self.stable_swap_program.to_account_info(),
+            pool: self.pool.to_account_info(),
+            pool_authority: self.pool_authority.to_account_info(),
+            pool_source: self.pool_source.to_account_info(),
+            pool_destination: self.pool_destination.to_account_info(),
+            admin_fee_destination: self.admin_fee_destination.to_account_info(),
+            token_program: self.token_program.to_account_info(),
+            venue_registry: self.venue_registry.to_account_info(),
+            protocol_status: self.protocol_status.to_account_info(),
+            network_config: self.network_config.to_account_info(),
+            protocol_stats: self.protocol_stats.to_account_info(),
+            stats_reporter: self.swapper_stats_reporter.to_account_info(),
+            protocol_program: self.protocol_program.to_account_info(),
+            fee_account: self.swap_fee_account.as_ref().map(|a| a.to_account_info()),
+        }
+    }
+}
+
+#[event]
+pub struct StableConverted {
+    pub source_mint: Pubkey,
+    pub destination_mint: Pubkey,
+    pub amount_in: u64,
+    pub amount_out: u64,
+}
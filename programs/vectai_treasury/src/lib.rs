@@ -0,0 +1,915 @@
+use anchor_lang::prelude::*;
+use anchor_lang::system_program::{self, CreateAccount};
+use anchor_spl::token::{self, CloseAccount, InitializeAccount3, Mint, Token, TokenAccount, Transfer};
+use vectai_protocol::NetworkConfig;
+use vectai_common::is_admin;
+
+// Linear payment streams funded by stream proposals
+mod streams;
+pub use streams::*;
+
+// Protocol-owned liquidity in the whitelisted Raydium pool
+mod liquidity;
+pub use liquidity::*;
+
+// Stablecoin conversions through the swapper's stable pools
+mod convert;
+pub use convert::*;
+
+declare_id!("5mxxXuXadxVsRiXxcnPykVpYpYDtvirDMwjwmZ42KSKe");
+
+#[program]
+pub mod vectai_treasury {
+    use super::*;
+
+    /// Create the protocol treasury with its admin multisig and governance
+    /// authority (admin only)
+    pub fn initialize_treasury(
+        ctx: Context<InitializeTreasury>,
+        signers: Vec<Pubkey>,
+        threshold: u8,
+        governance: Pubkey,
+    ) -> Result<()> {
+        // ✅ CHECKS
+        require!(
+            is_admin(&ctx.accounts.admin.key()),
+            TreasuryError::UnauthorizedAdmin
+        );
+        require!(
+            !signers.is_empty() && signers.len() <= MAX_SIGNERS,
+            TreasuryError::InvalidSigners
+        );
+        require!(
+            threshold > 0 && threshold as usize <= signers.len(),
+            TreasuryError::InvalidSigners
+        );
+        for (i, signer) in signers.iter().enumerate() {
+            require!(!signers[..i].contains(signer), TreasuryError::InvalidSigners);
+        }
+
+        // ✅ EFFECTS
+        let treasury = &mut ctx.accounts.treasury;
+        treasury.signers = signers;
+        treasury.threshold = threshold;
+        treasury.governance = governance;
+        treasury.proposal_count = 0;
+        treasury.bump = ctx.bumps.treasury;
+
+        msg!("✅ Treasury initialized: {}-of-{} multisig", threshold, treasury.signers.len());
+        Ok(())
+    }
+
+    /// Open the treasury's token account for a fee mint; anyone may pay
+    pub fn open_treasury_vault(ctx: Context<OpenTreasuryVault>) -> Result<()> {
+        msg!("✅ Treasury vault opened for {}", ctx.accounts.mint.key());
+        Ok(())
+    }
+
+    /// Open every protocol fee vault in one transaction: the vaults of both
+    /// network-config pair mints, which receive trade and swap fees, and of
+    /// each further fee mint (VECTAI, vault shares, auction quotes) passed
+    /// as (mint, vault) remaining-account pairs. Vaults that already exist
+    /// are kept, so a rerun fills gaps (governance only).
+    pub fn initialize_fee_vaults<'info>(
+        ctx: Context<'_, '_, 'info, 'info, InitializeFeeVaults<'info>>,
+    ) -> Result<()> {
+        // ✅ CHECKS
+        require!(ctx.remaining_accounts.len() % 2 == 0, TreasuryError::InvalidTokenAccount);
+
+        // ✅ INTERACTIONS: The pair vaults are opened by Anchor
+        for pair in ctx.remaining_accounts.chunks(2) {
+            open_fee_vault(ctx.accounts, &pair[0], &pair[1])?;
+        }
+
+        msg!("✅ Fee vaults initialized for {} mints", 2 + ctx.remaining_accounts.len() / 2);
+        Ok(())
+    }
+
+    /// Propose paying `amount` from a treasury vault to `recipient`
+    /// (multisig signer or governance)
+    pub fn propose_spend(ctx: Context<ProposeSpend>, amount: u64) -> Result<()> {
+        record_proposal(ctx, amount, 0, 0)
+    }
+
+    /// Propose streaming `amount` to `recipient` linearly between
+    /// `start_time` and `end_time` (multisig signer or governance)
+    pub fn propose_stream(
+        ctx: Context<ProposeSpend>,
+        amount: u64,
+        start_time: i64,
+        end_time: i64,
+    ) -> Result<()> {
+        require!(start_time > 0 && end_time > start_time, TreasuryError::InvalidStreamSchedule);
+        record_proposal(ctx, amount, start_time, end_time)
+    }
+
+    /// Approve a spend; governance approval alone meets the threshold
+    pub fn approve_spend(ctx: Context<ApproveSpend>) -> Result<()> {
+        // ✅ CHECKS
+        let treasury = &ctx.accounts.treasury;
+        let approver = ctx.accounts.approver.key();
+        require!(!ctx.accounts.proposal.executed, TreasuryError::AlreadyExecuted);
+
+        // ✅ EFFECTS
+        let proposal = &mut ctx.accounts.proposal;
+        if approver == treasury.governance {
+            proposal.approved_by_governance = true;
+        } else {
+            let index = treasury.signer_index(&approver).ok_or(TreasuryError::Unauthorized)?;
+            let bit = 1u8 << index;
+            require!(proposal.approvals & bit == 0, TreasuryError::AlreadyApproved);
+            proposal.approvals |= bit;
+        }
+
+        emit!(SpendApproved {
+            proposal: proposal.key(),
+            approver,
+            approvals: proposal.approvals.count_ones() as u8,
+            approved_by_governance: proposal.approved_by_governance,
+        });
+        msg!("✅ Spend #{} approved by {}", proposal.id, approver);
+        Ok(())
+    }
+
+    /// Execute an approved spend; anyone may submit it
+    pub fn execute_spend(ctx: Context<ExecuteSpend>) -> Result<()> {
+        // ✅ CHECKS
+        let treasury = &ctx.accounts.treasury;
+        let proposal = &ctx.accounts.proposal;
+        require!(!proposal.executed, TreasuryError::AlreadyExecuted);
+        require!(!proposal.is_stream(), TreasuryError::WrongSpendKind);
+        require!(
+            proposal.is_approved(treasury.threshold),
+            TreasuryError::NotApproved
+        );
+        require!(
+            ctx.accounts.treasury_vault.amount >= proposal.amount,
+            TreasuryError::InsufficientBalance
+        );
+
+        // ✅ EFFECTS
+        ctx.accounts.proposal.executed = true;
+
+        // ✅ INTERACTIONS
+        let amount = ctx.accounts.proposal.amount;
+        let seeds: &[&[u8]] = &[b"treasury", &[ctx.accounts.treasury.bump]];
+        token::transfer(
+            CpiContext::new_with_signer(
+                ctx.accounts.token_program.to_account_info(),
+                Transfer {
+                    from: ctx.accounts.treasury_vault.to_account_info(),
+                    to: ctx.accounts.recipient.to_account_info(),
+                    authority: ctx.accounts.treasury.to_account_info(),
+                },
+                &[seeds],
+            ),
+            amount,
+        )?;
+
+        emit!(SpendExecuted {
+            proposal: ctx.accounts.proposal.key(),
+            recipient: ctx.accounts.recipient.key(),
+            amount,
+        });
+        msg!("✅ Spend #{} executed: {}", ctx.accounts.proposal.id, amount);
+        Ok(())
+    }
+
+    /// Close a spend proposal, executed or withdrawn, refunding its rent
+    /// (proposer only)
+    pub fn close_spend_proposal(ctx: Context<CloseSpendProposal>) -> Result<()> {
+        msg!("✅ Spend #{} closed", ctx.accounts.proposal.id);
+        Ok(())
+    }
+
+    /// Fund the stream of an approved stream proposal from the treasury
+    /// vault; anyone may submit it
+    pub fn create_stream(ctx: Context<CreateStream>) -> Result<()> {
+        // ✅ CHECKS
+        let treasury = &ctx.accounts.treasury;
+        let proposal = &ctx.accounts.proposal;
+        require!(!proposal.executed, TreasuryError::AlreadyExecuted);
+        require!(proposal.is_stream(), TreasuryError::WrongSpendKind);
+        require!(
+            proposal.is_approved(treasury.threshold),
+            TreasuryError::NotApproved
+        );
+        require!(
+            ctx.accounts.treasury_vault.amount >= proposal.amount,
+            TreasuryError::InsufficientBalance
+        );
+
+        // ✅ EFFECTS
+        ctx.accounts.proposal.executed = true;
+        let proposal = &ctx.accounts.proposal;
+        let stream = &mut ctx.accounts.stream;
+        stream.id = proposal.id;
+        stream.mint = ctx.accounts.mint.key();
+        stream.recipient = proposal.recipient;
+        stream.escrow = ctx.accounts.escrow.key();
+        stream.total_amount = proposal.amount;
+        stream.withdrawn = 0;
+        stream.start_time = proposal.stream_start;
+        stream.end_time = proposal.stream_end;
+        stream.cancelled = false;
+        stream.bump = ctx.bumps.stream;
+
+        // ✅ INTERACTIONS
+        let seeds: &[&[u8]] = &[b"treasury", &[ctx.accounts.treasury.bump]];
+        token::transfer(
+            CpiContext::new_with_signer(
+                ctx.accounts.token_program.to_account_info(),
+                Transfer {
+                    from: ctx.accounts.treasury_vault.to_account_info(),
+                    to: ctx.accounts.escrow.to_account_info(),
+                    authority: ctx.accounts.treasury.to_account_info(),
+                },
+                &[seeds],
+            ),
+            ctx.accounts.stream.total_amount,
+        )?;
+
+        let stream = &ctx.accounts.stream;
+        emit!(StreamCreated {
+            stream: stream.key(),
+            recipient: stream.recipient,
+            total_amount: stream.total_amount,
+            start_time: stream.start_time,
+            end_time: stream.end_time,
+        });
+        msg!("✅ Stream #{} created: {} from {} to {}",
+             stream.id, stream.total_amount, stream.start_time, stream.end_time);
+        Ok(())
+    }
+
+    /// Withdraw everything accrued so far (recipient account owner)
+    pub fn withdraw_stream(ctx: Context<WithdrawStream>) -> Result<()> {
+        // ✅ CHECKS
+        let now = Clock::get()?.unix_timestamp;
+        let amount = ctx.accounts.stream.withdrawable(now);
+        require!(amount > 0, TreasuryError::NothingToWithdraw);
+
+        // ✅ EFFECTS
+        let stream = &mut ctx.accounts.stream;
+        stream.withdrawn = stream
+            .withdrawn
+            .checked_add(amount)
+            .ok_or(TreasuryError::MathOverflow)?;
+
+        // ✅ INTERACTIONS
+        pay_from_stream(
+            &ctx.accounts.token_program,
+            &ctx.accounts.escrow,
+            &ctx.accounts.recipient,
+            &ctx.accounts.stream,
+            amount,
+        )?;
+
+        emit!(StreamWithdrawn {
+            stream: ctx.accounts.stream.key(),
+            amount,
+            withdrawn: ctx.accounts.stream.withdrawn,
+        });
+        msg!("✅ Withdrew {} from stream #{}", amount, ctx.accounts.stream.id);
+        Ok(())
+    }
+
+    /// Stop a stream from vesting further and return the unvested rest to
+    /// the treasury vault. What has accrued stays in escrow for the
+    /// recipient to withdraw (multisig signer or governance)
+    pub fn cancel_stream(ctx: Context<CancelStream>) -> Result<()> {
+        // ✅ CHECKS
+        let treasury = &ctx.accounts.treasury;
+        let authority = ctx.accounts.authority.key();
+        require!(
+            treasury.signer_index(&authority).is_some() || authority == treasury.governance,
+            TreasuryError::Unauthorized
+        );
+        require!(!ctx.accounts.stream.cancelled, TreasuryError::StreamCancelled);
+
+        // ✅ EFFECTS: Truncate the stream at its accrued amount
+        let now = Clock::get()?.unix_timestamp;
+        let stream = &mut ctx.accounts.stream;
+        let accrued = stream.accrued(now);
+        let still_claimable = stream.withdrawable(now);
+        let returned_to_treasury = stream.total_amount - accrued;
+        stream.total_amount = accrued;
+        stream.end_time = stream.end_time.min(now.max(stream.start_time));
+        stream.cancelled = true;
+
+        // ✅ INTERACTIONS
+        if returned_to_treasury > 0 {
+            pay_from_stream(
+                &ctx.accounts.token_program,
+                &ctx.accounts.escrow,
+                &ctx.accounts.treasury_vault,
+                &ctx.accounts.stream,
+                returned_to_treasury,
+            )?;
+        }
+
+        emit!(StreamCancelled {
+            stream: ctx.accounts.stream.key(),
+            still_claimable,
+            returned_to_treasury,
+        });
+        msg!("🚫 Stream #{} cancelled: {} left to withdraw, {} returned",
+             ctx.accounts.stream.id, still_claimable, returned_to_treasury);
+        Ok(())
+    }
+
+    /// Close a fully paid-out or cancelled stream and its empty escrow,
+    /// refunding both rents (recipient account owner)
+    pub fn close_stream(ctx: Context<CloseStream>) -> Result<()> {
+        // ✅ CHECKS
+        let stream = &ctx.accounts.stream;
+        require!(stream.withdrawn == stream.total_amount, TreasuryError::StreamNotSettled);
+
+        // ✅ INTERACTIONS: The stream account itself is closed by Anchor
+        let id = stream.id.to_le_bytes();
+        let seeds: &[&[u8]] = &[b"stream", &id, &[stream.bump]];
+        token::close_account(CpiContext::new_with_signer(
+            ctx.accounts.token_program.to_account_info(),
+            CloseAccount {
+                account: ctx.accounts.escrow.to_account_info(),
+                destination: ctx.accounts.payee.to_account_info(),
+                authority: ctx.accounts.stream.to_account_info(),
+            },
+            &[seeds],
+        ))?;
+
+        msg!("✅ Stream #{} closed", ctx.accounts.stream.id);
+        Ok(())
+    }
+
+    /// Deposit treasury coin/PC into the whitelisted Raydium pool through
+    /// the swapper, keeping the LP tokens in the treasury (governance only)
+    pub fn deploy_liquidity(
+        ctx: Context<ManageLiquidity>,
+        max_coin_amount: u64,
+        max_pc_amount: u64,
+        base_side: u64,
+    ) -> Result<()> {
+        // ✅ CHECKS: Pool, pair and amounts are validated by the swapper
+        let (coin_before, pc_before, lp_before) = ctx.accounts.balances()?;
+
+        // ✅ INTERACTIONS: Treasury PDA signs as the liquidity provider
+        let seeds: &[&[u8]] = &[b"treasury", &[ctx.accounts.treasury.bump]];
+        raydium_swapper::cpi::add_liquidity(
+            CpiContext::new_with_signer(
+                ctx.accounts.swapper_program.to_account_info(),
+                ctx.accounts.swapper_accounts(),
+                &[seeds],
+            ),
+            max_coin_amount,
+            max_pc_amount,
+            base_side,
+        )?;
+
+        // ✅ EFFECTS: Record what the pool actually took and minted
+        let (coin_after, pc_after, lp_after) = ctx.accounts.balances()?;
+        let coin_amount = coin_before.checked_sub(coin_after).ok_or(TreasuryError::MathOverflow)?;
+        let pc_amount = pc_before.checked_sub(pc_after).ok_or(TreasuryError::MathOverflow)?;
+        let lp_minted = lp_after.checked_sub(lp_before).ok_or(TreasuryError::MathOverflow)?;
+
+        let position = &mut ctx.accounts.position;
+        if position.lp_mint == Pubkey::default() {
+            position.lp_mint = ctx.accounts.lp_vault.mint;
+            position.coin_mint = ctx.accounts.coin_vault.mint;
+            position.pc_mint = ctx.accounts.pc_vault.mint;
+            position.bump = ctx.bumps.position;
+        }
+        position.lp_balance = position.lp_balance.checked_add(lp_minted).ok_or(TreasuryError::MathOverflow)?;
+        position.coin_deployed = position.coin_deployed.checked_add(coin_amount).ok_or(TreasuryError::MathOverflow)?;
+        position.pc_deployed = position.pc_deployed.checked_add(pc_amount).ok_or(TreasuryError::MathOverflow)?;
+
+        emit!(LiquidityDeployed {
+            position: position.key(),
+            coin_amount,
+            pc_amount,
+            lp_minted,
+        });
+        msg!("✅ Treasury deployed {} coin / {} pc for {} LP", coin_amount, pc_amount, lp_minted);
+        Ok(())
+    }
+
+    /// Burn treasury LP tokens for the underlying coin/PC, returned to the
+    /// treasury vaults (governance only)
+    pub fn withdraw_liquidity(ctx: Context<ManageLiquidity>, lp_amount: u64) -> Result<()> {
+        // ✅ CHECKS
+        require!(
+            ctx.accounts.position.lp_mint == ctx.accounts.lp_vault.mint,
+            TreasuryError::InvalidTokenAccount
+        );
+        require!(
+            ctx.accounts.position.lp_balance >= lp_amount,
+            TreasuryError::InsufficientBalance
+        );
+        let (coin_before, pc_before, lp_before) = ctx.accounts.balances()?;
+
+        // ✅ INTERACTIONS
+        let seeds: &[&[u8]] = &[b"treasury", &[ctx.accounts.treasury.bump]];
+        raydium_swapper::cpi::remove_liquidity(
+            CpiContext::new_with_signer(
+                ctx.accounts.swapper_program.to_account_info(),
+                ctx.accounts.swapper_accounts(),
+                &[seeds],
+            ),
+            lp_amount,
+        )?;
+
+        // ✅ EFFECTS
+        let (coin_after, pc_after, lp_after) = ctx.accounts.balances()?;
+        let coin_amount = coin_after.checked_sub(coin_before).ok_or(TreasuryError::MathOverflow)?;
+        let pc_amount = pc_after.checked_sub(pc_before).ok_or(TreasuryError::MathOverflow)?;
+        let lp_burned = lp_before.checked_sub(lp_after).ok_or(TreasuryError::MathOverflow)?;
+
+        let position = &mut ctx.accounts.position;
+        position.lp_balance = position.lp_balance.saturating_sub(lp_burned);
+        position.coin_withdrawn = position.coin_withdrawn.checked_add(coin_amount).ok_or(TreasuryError::MathOverflow)?;
+        position.pc_withdrawn = position.pc_withdrawn.checked_add(pc_amount).ok_or(TreasuryError::MathOverflow)?;
+
+        emit!(LiquidityWithdrawn {
+            position: position.key(),
+            lp_burned,
+            coin_amount,
+            pc_amount,
+        });
+        msg!("✅ Treasury withdrew {} LP for {} coin / {} pc", lp_burned, coin_amount, pc_amount);
+        Ok(())
+    }
+
+    /// Convert between treasury stablecoins (e.g. USDT into USDC) on the
+    /// stable pool the venue registry lists for the pair (governance only)
+    pub fn convert_stable(ctx: Context<ConvertStable>, amount_in: u64, min_amount_out: u64) -> Result<()> {
+        // ✅ CHECKS: Pool, pair and amounts are validated by the swapper
+        let balance_before = ctx.accounts.destination_vault.amount;
+
+        // ✅ INTERACTIONS: Treasury PDA signs as the swap user
+        let seeds: &[&[u8]] = &[b"treasury", &[ctx.accounts.treasury.bump]];
+        raydium_swapper::cpi::swap_stable(
+            CpiContext::new_with_signer(
+                ctx.accounts.swapper_program.to_account_info(),
+                ctx.accounts.swapper_accounts(),
+                &[seeds],
+            ),
+            amount_in,
+            min_amount_out,
+        )?;
+
+        ctx.accounts.destination_vault.reload()?;
+        let amount_out = ctx
+            .accounts
+            .destination_vault
+            .amount
+            .checked_sub(balance_before)
+            .ok_or(TreasuryError::MathOverflow)?;
+        emit!(StableConverted {
+            source_mint: ctx.accounts.source_vault.mint,
+            destination_mint: ctx.accounts.destination_vault.mint,
+            amount_in,
+            amount_out,
+        });
+        msg!("✅ Treasury converted {} into {}", amount_in, amount_out);
+        Ok(())
+    }
+
+    /// Close a liquidity position whose LP tokens have all been withdrawn
+    /// (governance only)
+    pub fn close_liquidity_position(_ctx: Context<CloseLiquidityPosition>) -> Result<()> {
+        msg!("✅ Liquidity position closed");
+        Ok(())
+    }
+}
+
+/// Record a spend proposal; a non-zero `stream_end` makes it a stream
+fn record_proposal(
+    ctx: Context<ProposeSpend>,
+    amount: u64,
+    stream_start: i64,
+    stream_end: i64,
+) -> Result<()> {
+    // ✅ CHECKS
+    let treasury = &ctx.accounts.treasury;
+    let proposer = ctx.accounts.proposer.key();
+    require!(
+        treasury.signer_index(&proposer).is_some() || proposer == treasury.governance,
+        TreasuryError::Unauthorized
+    );
+    require!(amount > 0, TreasuryError::InvalidAmount);
+
+    // ✅ EFFECTS
+    let proposal = &mut ctx.accounts.proposal;
+    proposal.id = treasury.proposal_count;
+    proposal.proposer = proposer;
+    proposal.vault = ctx.accounts.treasury_vault.key();
+    proposal.recipient = ctx.accounts.recipient.key();
+    proposal.amount = amount;
+    proposal.approvals = 0;
+    proposal.approved_by_governance = false;
+    proposal.executed = false;
+    proposal.created_at = Clock::get()?.unix_timestamp;
+    proposal.stream_start = stream_start;
+    proposal.stream_end = stream_end;
+    proposal.bump = ctx.bumps.proposal;
+
+    let treasury = &mut ctx.accounts.treasury;
+    treasury.proposal_count = treasury
+        .proposal_count
+        .checked_add(1)
+        .ok_or(TreasuryError::MathOverflow)?;
+
+    emit!(SpendProposed {
+        proposal: ctx.accounts.proposal.key(),
+        id: ctx.accounts.proposal.id,
+        proposer,
+        recipient: ctx.accounts.recipient.key(),
+        amount,
+    });
+    msg!("✅ Spend #{} proposed: {} to {}",
+         ctx.accounts.proposal.id, amount, ctx.accounts.recipient.key());
+    Ok(())
+}
+
+/// Transfer out of a stream escrow, signed by the stream PDA
+fn pay_from_stream<'info>(
+    token_program: &Program<'info, Token>,
+    escrow: &Account<'info, TokenAccount>,
+    to: &Account<'info, TokenAccount>,
+    stream: &Account<'info, Stream>,
+    amount: u64,
+) -> Result<()> {
+    let id = stream.id.to_le_bytes();
+    let seeds: &[&[u8]] = &[b"stream", &id, &[stream.bump]];
+    token::transfer(
+        CpiContext::new_with_signer(
+            token_program.to_account_info(),
+            Transfer {
+                from: escrow.to_account_info(),
+                to: to.to_account_info(),
+                authority: stream.to_account_info(),
+            },
+            &[seeds],
+        ),
+        amount,
+    )
+}
+
+// ===== CONSTANTS =====
+
+// Approvals are a bitmask over the signer list
+pub const MAX_SIGNERS: usize = 8;
+
+// ===== HELPERS =====
+
+/// Treasury token account that protocol fees in `mint` are paid into
+pub fn vault_address(mint: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(&[b"treasury-vault", mint.as_ref()], &crate::ID).0
+}
+
+/// Create `vault` as the treasury vault for `mint` unless it exists; only
+/// this program can sign for the PDA, so an existing one is a treasury vault
+fn open_fee_vault<'info>(
+    accounts: &InitializeFeeVaults<'info>,
+    mint: &AccountInfo<'info>,
+    vault: &AccountInfo<'info>,
+) -> Result<()> {
+    let (address, bump) = Pubkey::find_program_address(&[b"treasury-vault", mint.key.as_ref()], &crate::ID);
+    require_keys_eq!(vault.key(), address, TreasuryError::InvalidTokenAccount);
+    require_keys_eq!(*mint.owner, token::ID, TreasuryError::InvalidTokenAccount);
+    if !vault.data_is_empty() {
+        return Ok(());
+    }
+
+    let seeds: &[&[u8]] = &[b"treasury-vault", mint.key.as_ref(), &[bump]];
+    system_program::create_account(
+        CpiContext::new_with_signer(
+            accounts.system_program.to_account_info(),
+            CreateAccount {
+                from: accounts.payer.to_account_info(),
+                to: vault.clone(),
+            },
+            &[seeds],
+        ),
+        Rent::get()?.minimum_balance(TokenAccount::LEN),
+        TokenAccount::LEN as u64,
+        &token::ID,
+    )?;
+    token::initialize_account3(CpiContext::new(
+        accounts.token_program.to_account_info(),
+        InitializeAccount3 {
+            account: vault.clone(),
+            mint: mint.clone(),
+            authority: accounts.treasury.to_account_info(),
+        },
+    ))
+}
+
+// ===== ACCOUNTS =====
+
+#[derive(Accounts)]
+pub struct InitializeTreasury<'info> {
+    #[account(
+        init,
+        payer = admin,
+        space = Treasury::LEN,
+        seeds = [b"treasury"],
+        bump
+    )]
+    pub treasury: Account<'info, Treasury>,
+
+    #[account(mut)]
+    pub admin: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct OpenTreasuryVault<'info> {
+    #[account(seeds = [b"treasury"], bump = treasury.bump)]
+    pub treasury: Account<'info, Treasury>,
+
+    pub mint: Account<'info, Mint>,
+
+    #[account(
+        init,
+        payer = payer,
+        token::mint = mint,
+        token::authority = treasury,
+        seeds = [b"treasury-vault", mint.key().as_ref()],
+        bump
+    )]
+    pub treasury_vault: Account<'info, TokenAccount>,
+
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    pub rent: Sysvar<'info, Rent>,
+    pub token_program: Program<'info, Token>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct InitializeFeeVaults<'info> {
+    #[account(seeds = [b"treasury"], bump = treasury.bump)]
+    pub treasury: Account<'info, Treasury>,
+
+    /// Fee vaults are only where the swapper and trader look if the
+    /// network config derives them from this program
+    #[account(
+        seeds = [b"network-config"],
+        bump = network_config.bump,
+        seeds::program = vectai_protocol::ID,
+        constraint = network_config.treasury_program == crate::ID @ TreasuryError::ForeignTreasury
+    )]
+    pub network_config: Account<'info, NetworkConfig>,
+
+    #[account(address = network_config.wsol_mint)]
+    pub wsol_mint: Account<'info, Mint>,
+
+    #[account(address = network_config.usdc_mint)]
+    pub usdc_mint: Account<'info, Mint>,
+
+    #[account(
+        init_if_needed,
+        payer = payer,
+        token::mint = wsol_mint,
+        token::authority = treasury,
+        seeds = [b"treasury-vault", wsol_mint.key().as_ref()],
+        bump
+    )]
+    pub wsol_vault: Account<'info, TokenAccount>,
+
+    #[account(
+        init_if_needed,
+        payer = payer,
+        token::mint = usdc_mint,
+        token::authority = treasury,
+        seeds = [b"treasury-vault", usdc_mint.key().as_ref()],
+        bump
+    )]
+    pub usdc_vault: Account<'info, TokenAccount>,
+
+    #[account(address = treasury.governance @ TreasuryError::Unauthorized)]
+    pub governance: Signer<'info>,
+
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    pub rent: Sysvar<'info, Rent>,
+    pub token_program: Program<'info, Token>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct ProposeSpend<'info> {
+    #[account(mut, seeds = [b"treasury"], bump = treasury.bump)]
+    pub treasury: Account<'info, Treasury>,
+
+    #[account(
+        init,
+        payer = proposer,
+        space = SpendProposal::LEN,
+        seeds = [b"spend".as_ref(), &treasury.proposal_count.to_le_bytes()],
+        bump
+    )]
+    pub proposal: Account<'info, SpendProposal>,
+
+    #[account(
+        seeds = [b"treasury-vault", treasury_vault.mint.as_ref()],
+        bump
+    )]
+    pub treasury_vault: Account<'info, TokenAccount>,
+
+    #[account(
+        constraint = recipient.mint == treasury_vault.mint @ TreasuryError::InvalidTokenAccount
+    )]
+    pub recipient: Account<'info, TokenAccount>,
+
+    #[account(mut)]
+    pub proposer: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct ApproveSpend<'info> {
+    #[account(seeds = [b"treasury"], bump = treasury.bump)]
+    pub treasury: Account<'info, Treasury>,
+
+    #[account(
+        mut,
+        seeds = [b"spend".as_ref(), &proposal.id.to_le_bytes()],
+        bump = proposal.bump
+    )]
+    pub proposal: Account<'info, SpendProposal>,
+
+    pub approver: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct ExecuteSpend<'info> {
+    #[account(seeds = [b"treasury"], bump = treasury.bump)]
+    pub treasury: Account<'info, Treasury>,
+
+    #[account(
+        mut,
+        seeds = [b"spend".as_ref(), &proposal.id.to_le_bytes()],
+        bump = proposal.bump
+    )]
+    pub proposal: Account<'info, SpendProposal>,
+
+    #[account(mut, address = proposal.vault)]
+    pub treasury_vault: Account<'info, TokenAccount>,
+
+    #[account(mut, address = proposal.recipient)]
+    pub recipient: Account<'info, TokenAccount>,
+
+    pub token_program: Program<'info, Token>,
+}
+
+#[derive(Accounts)]
+pub struct CloseSpendProposal<'info> {
+    #[account(
+        mut,
+        close = proposer,
+        seeds = [b"spend".as_ref(), &proposal.id.to_le_bytes()],
+        bump = proposal.bump,
+        has_one = proposer @ TreasuryError::Unauthorized
+    )]
+    pub proposal: Account<'info, SpendProposal>,
+
+    #[account(mut)]
+    pub proposer: Signer<'info>,
+}
+
+// ===== STATE =====
+
+/// Protocol treasury; the PDA owns every treasury vault
+#[account]
+pub struct Treasury {
+    pub signers: Vec<Pubkey>, // Admin multisig
+    pub threshold: u8,
+    pub governance: Pubkey, // Approves alone; default = none
+    pub proposal_count: u64,
+    pub bump: u8,
+}
+
+impl Treasury {
+    pub const LEN: usize = 8 + // discriminator
+        4 + MAX_SIGNERS * 32 + // signers
+        1 +  // threshold
+        32 + // governance
+        8 +  // proposal_count
+        1;   // bump
+
+    pub fn signer_index(&self, key: &Pubkey) -> Option<usize> {
+        self.signers.iter().position(|signer| signer == key)
+    }
+}
+
+/// Proposed payment out of a treasury vault
+#[account]
+pub struct SpendProposal {
+    pub id: u64,
+    pub proposer: Pubkey,
+    pub vault: Pubkey,
+    pub recipient: Pubkey,
+    pub amount: u64,
+    pub approvals: u8, // Bitmask over Treasury.signers
+    pub approved_by_governance: bool,
+    pub executed: bool,
+    pub created_at: i64,
+    pub stream_start: i64,
+    pub stream_end: i64, // 0 = lump-sum spend
+    pub bump: u8,
+}
+
+impl SpendProposal {
+    pub const LEN: usize = 8 + // discriminator
+        8 +  // id
+        32 + // proposer
+        32 + // vault
+        32 + // recipient
+        8 +  // amount
+        1 +  // approvals
+        1 +  // approved_by_governance
+        1 +  // executed
+        8 +  // created_at
+        8 +  // stream_start
+        8 +  // stream_end
+        1;   // bump
+
+    pub fn is_approved(&self, threshold: u8) -> bool {
+        self.approved_by_governance || self.approvals.count_ones() >= threshold as u32
+    }
+
+    /// Paid out through `create_stream` instead of `execute_spend`
+    pub fn is_stream(&self) -> bool {
+        self.stream_end != 0
+    }
+}
+
+// ===== EVENTS =====
+
+#[event]
+pub struct SpendProposed {
+    pub proposal: Pubkey,
+    pub id: u64,
+    pub proposer: Pubkey,
+    pub recipient: Pubkey,
+    pub amount: u64,
+}
+
+#[event]
+pub struct SpendApproved {
+    pub proposal: Pubkey,
+    pub approver: Pubkey,
+    pub approvals: u8,
+    pub approved_by_governance: bool,
+}
+
+#[event]
+pub struct SpendExecuted {
+    pub proposal: Pubkey,
+    pub recipient: Pubkey,
+    pub amount: u64,
+}
+
+#[error_code]
+pub enum TreasuryError {
+    #[msg("Unauthorized admin")]
+    UnauthorizedAdmin,
+    #[msg("Unauthorized access")]
+    Unauthorized,
+    #[msg("Invalid signer set or threshold")]
+    InvalidSigners,
+    #[msg("Invalid amount - must be greater than 0")]
+    InvalidAmount,
+    #[msg("Insufficient balance")]
+    InsufficientBalance,
+    #[msg("Invalid token account")]
+    InvalidTokenAccount,
+    #[msg("Already approved by this signer")]
+    AlreadyApproved,
+    #[msg("Spend has not reached the approval threshold")]
+    NotApproved,
+    #[msg("Spend already executed")]
+    AlreadyExecuted,
+    #[msg("Stream proposals use create_stream; lump sums use execute_spend")]
+    WrongSpendKind,
+    #[msg("Stream must start after 0 and end after it starts")]
+    InvalidStreamSchedule,
+    #[msg("Nothing accrued to withdraw")]
+    NothingToWithdraw,
+    #[msg("Stream already cancelled")]
+    StreamCancelled,
+    #[msg("Math overflow in calculation")]
+    MathOverflow,
+    #[msg("Stream still holds unpaid funds")]
+    StreamNotSettled,
+    #[msg("Position still holds LP tokens")]
+    LiquidityOutstanding,
+    #[msg("Network config derives fee vaults from another treasury program")]
+    ForeignTreasury,
+}
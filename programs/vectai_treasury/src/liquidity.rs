@@ -0,0 +1,225 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token::{Token, TokenAccount};
+use raydium_swapper::cpi::accounts::LiquidityAccounts;
+use raydium_swapper::program::RaydiumSwapper;
+
+use crate::{Treasury, TreasuryError};
+
+#[derive(Accounts)]
+pub struct ManageLiquidity<'info> {
+    #[account(seeds = [b"treasury"], bump = treasury.bump)]
+    pub treasury: Account<'info, Treasury>,
+
+    #[account(
+        init_if_needed,
+        payer = payer,
+        space = LiquidityPosition::LEN,
+        seeds = [b"liquidity-position", lp_vault.mint.as_ref()],
+        bump
+    )]
+    pub position: Account<'info, LiquidityPosition>,
+
+    #[account(
+        mut,
+        seeds = [b"treasury-vault", coin_vault.mint.as_ref()],
+        bump
+    )]
+    pub coin_vault: Account<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        seeds = [b"treasury-vault", pc_vault.mint.as_ref()],
+        bump
+    )]
+    pub pc_vault: Account<'info, TokenAccount>,
+
+    /// Treasury vault holding the pool's LP tokens
+    #[account(
+        mut,
+        seeds = [b"treasury-vault", lp_vault.mint.as_ref()],
+        bump
+    )]
+    pub lp_vault: Account<'info, TokenAccount>,
+
+    #[account(address = treasury.governance @ TreasuryError::Unauthorized)]
+    pub governance: Signer<'info>,
+
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    pub swapper_program: Program<'info, RaydiumSwapper>,
+
+    // ===== RAYDIUM / SERUM ACCOUNTS (validated by raydium_swapper) =====
+
+    /// CHECK: Raydium AMM program
+    pub raydium_amm_program: UncheckedAccount<'info>,
+
+    /// CHECK: AMM pool account
+    #[account(mut)]
+    pub amm: UncheckedAccount<'info>,
+
+    /// CHECK: AMM authority
+    pub amm_authority: UncheckedAccount<'info>,
+
+    /// CHECK: AMM open orders
+    #[account(mut)]
+    pub amm_open_orders: UncheckedAccount<'info>,
+
+    /// CHECK: AMM target orders
+    #[account(mut)]
+    pub amm_target_orders: UncheckedAccount<'info>,
+
+    /// CHECK: AMM LP mint
+    #[account(mut)]
+    pub amm_lp_mint: UncheckedAccount<'info>,
+
+    /// CHECK: Pool coin token account
+    #[account(mut)]
+    pub pool_coin_token_account: UncheckedAccount<'info>,
+
+    /// CHECK: Pool pc token account
+    #[account(mut)]
+    pub pool_pc_token_account: UncheckedAccount<'info>,
+
+    /// CHECK: Serum program
+    pub serum_program: UncheckedAccount<'info>,
+
+    /// CHECK: Serum market
+    #[account(mut)]
+    pub serum_market: UncheckedAccount<'info>,
+
+    /// CHECK: Serum bids
+    #[account(mut)]
+    pub serum_bids: UncheckedAccount<'info>,
+
+    /// CHECK: Serum asks
+    #[account(mut)]
+    pub serum_asks: UncheckedAccount<'info>,
+
+    /// CHECK: Serum event queue
+    #[account(mut)]
+    pub serum_event_queue: UncheckedAccount<'info>,
+
+    /// CHECK: Serum coin vault
+    #[account(mut)]
+    pub serum_coin_vault: UncheckedAccount<'info>,
+
+    /// CHECK: Serum pc vault
+    #[account(mut)]
+    pub serum_pc_vault: UncheckedAccount<'info>,
+
+    /// CHECK: Serum vault signer
+    pub serum_vault_signer: UncheckedAccount<'info>,
+
+    pub token_program: Program<'info, Token>,
+    pub system_program: Program<'info, System>,
+
+    /// Global emergency-pause registry, forwarded to the swapper
+    /// CHECK: validated by raydium_swapper
+    pub protocol_status: UncheckedAccount<'info>,
+
+    /// Shared network addresses, forwarded to the swapper
+    /// CHECK: validated by raydium_swapper
+    pub network_config: UncheckedAccount<'info>,
+}
+
+impl<'info> ManageLiquidity<'info> {
+    /// Swapper accounts with the treasury PDA as the liquidity provider
+    pub fn swapper_accounts(&self) -> LiquidityAccounts<'info> {
+        LiquidityAccounts {
+            user_authority: self.treasury.to_account_info(),
+            user_coin_token: self.coin_vault.to_account_info(),
+            user_pc_token: self.pc_vault.to_account_info(),
+            user_lp_token: self.lp_vault.to_account_info(),
+            raydium_amm_program: self.raydium_amm_program.to_account_info(),
+            amm: self.amm.to_account_info(),
+            amm_authority: self.amm_authority.to_account_info(),
+            amm_open_orders: self.amm_open_orders.to_account_info(),
+            amm_target_orders: self.amm_target_orders.to_account_info(),
+            amm_lp_mint: self.amm_lp_mint.to_account_info(),
+            pool_coin_token_account: self.pool_coin_token_account.to_account_info(),
+            pool_pc_token_account: self.pool_pc_token_account.to_account_info(),
+            serum_program: self.serum_program.to_account_info(),
+            serum_market: self.serum_market.to_account_info(),
+            serum_bids: self.serum_bids.to_account_info(),
+            serum_asks: self.serum_asks.to_account_info(),
+            serum_event_queue: self.serum_event_queue.to_account_info(),
+            serum_coin_vault: self.serum_coin_vault.to_account_info(),
+            serum_pc_vault: self.serum_pc_vault.to_account_info(),
+            serum_vault_signer: self.serum_vault_signer.to_account_info(),
+            token_program: self.token_program.to_account_info(),
+            protocol_status: self.protocol_status.to_account_info(),
+            network_config: self.network_config.to_account_info(),
+        }
+    }
+
+    /// Current (coin, pc, lp) vault balances
+    pub fn balances(&mut self) -> Result<(u64, u64, u64)> {
+        self.coin_vault.reload()?;
+        self.pc_vault.reload()?;
+        self.lp_vault.reload()?;
+        Ok((self.coin_vault.amount, self.pc_vault.amount, self.lp_vault.amount))
+    }
+}
+
+#[derive(Accounts)]
+pub struct CloseLiquidityPosition<'info> {
+    #[account(seeds = [b"treasury"], bump = treasury.bump)]
+    pub treasury: Account<'info, Treasury>,
+
+    #[account(
+        mut,
+        close = governance,
+        seeds = [b"liquidity-position", position.lp_mint.as_ref()],
+        bump = position.bump,
+        constraint = position.lp_balance == 0 @ TreasuryError::LiquidityOutstanding
+    )]
+    pub position: Account<'info, LiquidityPosition>,
+
+    #[account(mut, address = treasury.governance @ TreasuryError::Unauthorized)]
+    pub governance: Signer<'info>,
+}
+
+/// Treasury liquidity in a Raydium pool, keyed by its LP mint; amounts
+/// are measured from vault balances around each swapper call
+#[account]
+pub struct LiquidityPosition {
+    pub lp_mint: Pubkey,
+    pub coin_mint: Pubkey,
+    pub pc_mint: Pubkey,
+    pub lp_balance: u64,
+    pub coin_deployed: u64,
+    pub pc_deployed: u64,
+    pub coin_withdrawn: u64,
+    pub pc_withdrawn: u64,
+    pub bump: u8,
+}
+
+impl LiquidityPosition {
+    pub const LEN: usize = 8 + // discriminator
+        32 + // lp_mint
+        32 + // coin_mint
+        32 + // pc_mint
+        8 +  // lp_balance
+        8 +  // coin_deployed
+        8 +  // pc_deployed
+        8 +  // coin_withdrawn
+        8 +  // pc_withdrawn
+        1;   // bump
+}
+
+#[event]
+pub struct LiquidityDeployed {
+    pub position: Pubkey,
+    pub coin_amount: u64,
+    pub pc_amount: u64,
+    pub lp_minted: u64,
+}
+
+#[event]
+pub struct LiquidityWithdrawn {
+    pub position: Pubkey,
+    pub lp_burned: u64,
+    pub coin_amount: u64,
+    pub pc_amount: u64,
+}
@@ -0,0 +1,196 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token::{Mint, Token, TokenAccount};
+
+use crate::{SpendProposal, Treasury, TreasuryError};
+
+#[derive(Accounts)]
+pub struct CreateStream<'info> {
+    #[account(seeds = [b"treasury"], bump = treasury.bump)]
+    pub treasury: Account<'info, Treasury>,
+
+    #[account(
+        mut,
+        seeds = [b"spend".as_ref(), &proposal.id.to_le_bytes()],
+        bump = proposal.bump
+    )]
+    pub proposal: Account<'info, SpendProposal>,
+
+    #[account(mut, address = proposal.vault)]
+    pub treasury_vault: Account<'info, TokenAccount>,
+
+    #[account(address = treasury_vault.mint)]
+    pub mint: Account<'info, Mint>,
+
+    #[account(
+        init,
+        payer = payer,
+        space = Stream::LEN,
+        seeds = [b"stream".as_ref(), &proposal.id.to_le_bytes()],
+        bump
+    )]
+    pub stream: Account<'info, Stream>,
+
+    #[account(
+        init,
+        payer = payer,
+        token::mint = mint,
+        token::authority = stream,
+        seeds = [b"stream-escrow", stream.key().as_ref()],
+        bump
+    )]
+    pub escrow: Account<'info, TokenAccount>,
+
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    pub rent: Sysvar<'info, Rent>,
+    pub token_program: Program<'info, Token>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct WithdrawStream<'info> {
+    #[account(
+        mut,
+        seeds = [b"stream".as_ref(), &stream.id.to_le_bytes()],
+        bump = stream.bump
+    )]
+    pub stream: Account<'info, Stream>,
+
+    #[account(mut, address = stream.escrow)]
+    pub escrow: Account<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        address = stream.recipient,
+        constraint = recipient.owner == payee.key() @ TreasuryError::Unauthorized
+    )]
+    pub recipient: Account<'info, TokenAccount>,
+
+    pub payee: Signer<'info>,
+
+    pub token_program: Program<'info, Token>,
+}
+
+#[derive(Accounts)]
+pub struct CancelStream<'info> {
+    #[account(seeds = [b"treasury"], bump = treasury.bump)]
+    pub treasury: Account<'info, Treasury>,
+
+    #[account(
+        mut,
+        seeds = [b"stream".as_ref(), &stream.id.to_le_bytes()],
+        bump = stream.bump
+    )]
+    pub stream: Account<'info, Stream>,
+
+    #[account(mut, address = stream.escrow)]
+    pub escrow: Account<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        seeds = [b"treasury-vault", stream.mint.as_ref()],
+        bump
+    )]
+    pub treasury_vault: Account<'info, TokenAccount>,
+
+    pub authority: Signer<'info>,
+
+    pub token_program: Program<'info, Token>,
+}
+
+#[derive(Accounts)]
+pub struct CloseStream<'info> {
+    #[account(
+        mut,
+        close = payee,
+        seeds = [b"stream".as_ref(), &stream.id.to_le_bytes()],
+        bump = stream.bump
+    )]
+    pub stream: Account<'info, Stream>,
+
+    #[account(mut, address = stream.escrow)]
+    pub escrow: Account<'info, TokenAccount>,
+
+    #[account(
+        address = stream.recipient,
+        constraint = recipient.owner == payee.key() @ TreasuryError::Unauthorized
+    )]
+    pub recipient: Account<'info, TokenAccount>,
+
+    #[account(mut)]
+    pub payee: Signer<'info>,
+
+    pub token_program: Program<'info, Token>,
+}
+
+/// Linear payment stream funded from a treasury vault by an approved
+/// stream proposal
+#[account]
+pub struct Stream {
+    pub id: u64, // Id of the funding SpendProposal
+    pub mint: Pubkey,
+    pub recipient: Pubkey, // Token account; its owner withdraws
+    pub escrow: Pubkey,
+    pub total_amount: u64,
+    pub withdrawn: u64,
+    pub start_time: i64,
+    pub end_time: i64,
+    pub cancelled: bool,
+    pub bump: u8,
+}
+
+impl Stream {
+    pub const LEN: usize = 8 + // discriminator
+        8 +  // id
+        32 + // mint
+        32 + // recipient
+        32 + // escrow
+        8 +  // total_amount
+        8 +  // withdrawn
+        8 +  // start_time
+        8 +  // end_time
+        1 +  // cancelled
+        1;   // bump
+
+    /// Amount vested by `now`, accruing linearly from start to end
+    pub fn accrued(&self, now: i64) -> u64 {
+        if now <= self.start_time {
+            return 0;
+        }
+        if now >= self.end_time {
+            return self.total_amount;
+        }
+        let elapsed = (now - self.start_time) as u128;
+        let duration = (self.end_time - self.start_time) as u128;
+        (self.total_amount as u128 * elapsed / duration) as u64
+    }
+
+    /// Vested but not yet withdrawn
+    pub fn withdrawable(&self, now: i64) -> u64 {
+        self.accrued(now).saturating_sub(self.withdrawn)
+    }
+}
+
+#[event]
+pub struct StreamCreated {
+    pub stream: Pubkey,
+    pub recipient: Pubkey,
+    pub total_amount: u64,
+    pub start_time: i64,
+    pub end_time: i64,
+}
+
+#[event]
+pub struct StreamWithdrawn {
+    pub stream: Pubkey,
+    pub amount: u64,
+    pub withdrawn: u64,
+}
+
+#[event]
+pub struct StreamCancelled {
+    pub stream: Pubkey,
+    pub still_claimable: u64, // Accrued and left in escrow for withdraw_stream
+    pub returned_to_treasury: u64,
+}
@@ -0,0 +1,213 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token::{Mint, Token, TokenAccount};
+
+use vectai_protocol::ProtocolStatus;
+
+use crate::{Vault, VaultError, BPS_DENOMINATOR};
+
+// Strategies one vault can allocate across
+pub const MAX_STRATEGIES: usize = 4;
+
+#[derive(Accounts)]
+#[instruction(strategy: Pubkey)]
+pub struct RegisterStrategy<'info> {
+    #[account(
+        seeds = [b"vault", vault.manager.as_ref(), vault.base_mint.as_ref()],
+        bump = vault.bump
+    )]
+    pub vault: Account<'info, Vault>,
+
+    #[account(
+        init_if_needed,
+        payer = manager,
+        space = VaultAllocations::LEN,
+        seeds = [b"allocations", vault.key().as_ref()],
+        bump
+    )]
+    pub allocations: Account<'info, VaultAllocations>,
+
+    #[account(address = vault.base_mint)]
+    pub base_mint: Account<'info, Mint>,
+
+    /// Base tokens allocated to `strategy`, held by the vault
+    #[account(
+        init,
+        payer = manager,
+        token::mint = base_mint,
+        token::authority = vault,
+        seeds = [b"strategy-escrow", vault.key().as_ref(), strategy.as_ref()],
+        bump
+    )]
+    pub strategy_escrow: Account<'info, TokenAccount>,
+
+    #[account(mut)]
+    pub manager: Signer<'info>,
+
+    pub rent: Sysvar<'info, Rent>,
+    pub token_program: Program<'info, Token>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct UpdateAllocations<'info> {
+    #[account(
+        seeds = [b"vault", vault.manager.as_ref(), vault.base_mint.as_ref()],
+        bump = vault.bump
+    )]
+    pub vault: Account<'info, Vault>,
+
+    #[account(
+        mut,
+        seeds = [b"allocations", vault.key().as_ref()],
+        bump = allocations.bump
+    )]
+    pub allocations: Account<'info, VaultAllocations>,
+
+    pub admin: Signer<'info>,
+}
+
+/// Accounts for the rebalancing crank. Every registered strategy's escrow
+/// follows as a writable `remaining_account`, in registration order.
+#[derive(Accounts)]
+pub struct RebalanceAllocations<'info> {
+    #[account(
+        mut,
+        seeds = [b"vault", vault.manager.as_ref(), vault.base_mint.as_ref()],
+        bump = vault.bump
+    )]
+    pub vault: Account<'info, Vault>,
+
+    #[account(
+        mut,
+        seeds = [b"allocations", vault.key().as_ref()],
+        bump = allocations.bump
+    )]
+    pub allocations: Account<'info, VaultAllocations>,
+
+    #[account(mut, address = vault.base_vault)]
+    pub base_vault: Account<'info, TokenAccount>,
+
+    pub token_program: Program<'info, Token>,
+
+    /// Global emergency-pause registry
+    #[account(
+        seeds = [b"protocol-status"],
+        bump = protocol_status.bump,
+        seeds::program = vectai_protocol::ID,
+        constraint = !protocol_status.paused @ VaultError::ProtocolPaused
+    )]
+    pub protocol_status: Account<'info, ProtocolStatus>,
+}
+
+/// One strategy's share of the vault's base tokens
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct StrategyAllocation {
+    pub strategy: Pubkey,
+    pub escrow: Pubkey, // Vault-owned base token account funding the strategy
+    pub weight_bps: u16, // Target share of the base vault and every escrow
+    pub allocated: u64, // Escrow balance after the last rebalance
+}
+
+impl StrategyAllocation {
+    pub const LEN: usize = 32 + // strategy
+        32 + // escrow
+        2 +  // weight_bps
+        8; // allocated
+}
+
+/// Strategies a vault allocates its base tokens across. Weights are set
+/// by governance and applied by `rebalance_allocations`; what they leave
+/// unallocated stays in the base vault for withdrawals.
+#[account]
+pub struct VaultAllocations {
+    pub vault: Pubkey,
+    pub strategies: [StrategyAllocation; MAX_STRATEGIES],
+    pub strategy_count: u8,
+    pub last_rebalance: i64,
+    pub bump: u8,
+}
+
+impl VaultAllocations {
+    pub const LEN: usize = 8 + // discriminator
+        32 + // vault
+        MAX_STRATEGIES * StrategyAllocation::LEN + // strategies
+        1 +  // strategy_count
+        8 +  // last_rebalance
+        1;   // bump
+
+    pub fn registered(&self) -> &[StrategyAllocation] {
+        &self.strategies[..self.strategy_count as usize]
+    }
+
+    /// Register `strategy` funded from `escrow`, unweighted until governance
+    /// sets its weight
+    pub fn register(&mut self, strategy: Pubkey, escrow: Pubkey) -> Result<()> {
+        require!(
+            (self.strategy_count as usize) < MAX_STRATEGIES,
+            VaultError::TooManyStrategies
+        );
+        self.strategies[self.strategy_count as usize] = StrategyAllocation {
+            strategy,
+            escrow,
+            ..StrategyAllocation::default()
+        };
+        self.strategy_count += 1;
+        Ok(())
+    }
+
+    /// Set one weight per registered strategy, in registration order; they
+    /// may not total over 100%
+    pub fn set_weights(&mut self, weights: &[u16]) -> Result<()> {
+        require!(
+            weights.len() == self.strategy_count as usize,
+            VaultError::InvalidAllocations
+        );
+        let total: u64 = weights.iter().map(|weight| *weight as u64).sum();
+        require!(total <= BPS_DENOMINATOR, VaultError::InvalidAllocations);
+        for (allocation, weight) in self.strategies.iter_mut().zip(weights) {
+            allocation.weight_bps = *weight;
+        }
+        Ok(())
+    }
+}
+
+/// Balance each strategy's escrow should hold out of `total` base tokens
+pub fn allocation_targets(allocations: &[StrategyAllocation], total: u64) -> Vec<u64> {
+    allocations
+        .iter()
+        .map(|allocation| {
+            (total as u128 * allocation.weight_bps as u128 / BPS_DENOMINATOR as u128) as u64
+        })
+        .collect()
+}
+
+/// Address of `vault`'s `VaultAllocations`
+pub fn vault_allocations(vault: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(&[b"allocations", vault.as_ref()], &crate::ID).0
+}
+
+/// Address of the escrow funding `strategy` from `vault`
+pub fn strategy_escrow(vault: &Pubkey, strategy: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(&[b"strategy-escrow", vault.as_ref(), strategy.as_ref()], &crate::ID).0
+}
+
+#[event]
+pub struct StrategyRegistered {
+    pub vault: Pubkey,
+    pub strategy: Pubkey,
+    pub escrow: Pubkey,
+}
+
+#[event]
+pub struct AllocationsUpdated {
+    pub vault: Pubkey,
+    pub weights: Vec<u16>, // In registration order
+}
+
+#[event]
+pub struct AllocationsRebalanced {
+    pub vault: Pubkey,
+    pub total: u64, // Base vault plus escrows before the rebalance
+    pub moved: u64, // Base tokens transferred in or out of escrows
+    pub allocated: u64, // Held by escrows after the rebalance
+}
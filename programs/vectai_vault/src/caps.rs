@@ -0,0 +1,69 @@
+use anchor_lang::prelude::*;
+
+use crate::{pro_rata, VaultError};
+
+/// Limits on oracle-valued deposits, in base-token units, so a strategy can
+/// be opened to a small amount of capital first and its positions kept
+/// within what the pool can absorb
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct DepositCaps {
+    pub max_total_value: u64,   // Vault NAV after the deposit; 0 = no cap
+    pub max_per_depositor: u64, // One depositor's position after the deposit; 0 = no cap
+}
+
+impl DepositCaps {
+    pub const LEN: usize = 8 + // max_total_value
+        8; // max_per_depositor
+
+    /// Check depositing `amount` into a vault worth `nav` over `supply`
+    /// shares keeps it and the depositor's `position_shares` within the caps
+    pub fn check(&self, amount: u64, nav: u64, supply: u64, position_shares: u64) -> Result<()> {
+        if self.max_total_value > 0 {
+            let total = nav.checked_add(amount).ok_or(VaultError::MathOverflow)?;
+            require!(total <= self.max_total_value, VaultError::VaultCapExceeded);
+        }
+        if self.max_per_depositor > 0 {
+            let held = if supply > 0 { pro_rata(nav, position_shares, supply)? } else { 0 };
+            let position = held.checked_add(amount).ok_or(VaultError::MathOverflow)?;
+            require!(position <= self.max_per_depositor, VaultError::DepositorCapExceeded);
+        }
+        Ok(())
+    }
+}
+
+/// Shares a depositor holds through `deposit`, net of those they burned or
+/// queued for withdrawal passing this account; the per-depositor cap values
+/// these at the vault's NAV
+#[account]
+pub struct DepositorPosition {
+    pub vault: Pubkey,
+    pub depositor: Pubkey,
+    pub shares: u64,
+    pub bump: u8,
+}
+
+impl DepositorPosition {
+    pub const LEN: usize = 8 + // discriminator
+        32 + // vault
+        32 + // depositor
+        8 +  // shares
+        1;   // bump
+
+    /// Release `shares` leaving the vault, never below zero since shares
+    /// bought or received elsewhere were never counted
+    pub fn release(&mut self, shares: u64) {
+        self.shares = self.shares.saturating_sub(shares);
+    }
+}
+
+/// Address of `depositor`'s `DepositorPosition` in `vault`
+pub fn depositor_position(vault: &Pubkey, depositor: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(&[b"depositor", vault.as_ref(), depositor.as_ref()], &crate::ID).0
+}
+
+#[event]
+pub struct DepositCapsUpdated {
+    pub vault: Pubkey,
+    pub max_total_value: u64,
+    pub max_per_depositor: u64,
+}
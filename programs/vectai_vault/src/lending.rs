@@ -0,0 +1,402 @@
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::{instruction::Instruction, program::invoke_signed};
+use anchor_spl::token::{self, Mint, Token, TokenAccount};
+
+use vectai_protocol::ProtocolStatus;
+
+use crate::{Vault, VaultError};
+
+/// Solend lending program (SPL token-lending fork)
+pub const SOLEND_PROGRAM: Pubkey =
+    anchor_lang::solana_program::pubkey!("So1endDq2YkqhipRh3WViPa8hdiSpxWy6z3Z6tMCpAo");
+
+/// Solend instruction tags
+const DEPOSIT_RESERVE_LIQUIDITY: u8 = 4;
+const REDEEM_RESERVE_COLLATERAL: u8 = 5;
+
+/// Byte offsets into a Solend `Reserve` account
+const RESERVE_LIQUIDITY_MINT_OFFSET: usize = 42;
+const RESERVE_AVAILABLE_AMOUNT_OFFSET: usize = 171;
+const RESERVE_BORROWED_AMOUNT_WADS_OFFSET: usize = 179;
+const RESERVE_COLLATERAL_MINT_OFFSET: usize = 227;
+const RESERVE_COLLATERAL_SUPPLY_OFFSET: usize = 259;
+const RESERVE_PROTOCOL_FEES_WADS_OFFSET: usize = 373;
+
+/// Scale of Solend's `Decimal` amounts
+const WAD: u128 = 1_000_000_000_000_000_000;
+
+#[derive(Accounts)]
+pub struct EnableLending<'info> {
+    #[account(
+        mut,
+        seeds = [b"vault", vault.manager.as_ref(), vault.base_mint.as_ref()],
+        bump = vault.bump
+    )]
+    pub vault: Account<'info, Vault>,
+
+    /// CHECK: Solend reserve for the base mint; owner and mints checked in handler
+    pub reserve: UncheckedAccount<'info>,
+
+    pub reserve_collateral_mint: Account<'info, Mint>,
+
+    /// Holds the reserve's collateral (cTokens) for the vault
+    #[account(
+        init,
+        payer = manager,
+        token::mint = reserve_collateral_mint,
+        token::authority = vault,
+        seeds = [b"collateral-vault", vault.key().as_ref()],
+        bump
+    )]
+    pub collateral_vault: Account<'info, TokenAccount>,
+
+    #[account(mut)]
+    pub manager: Signer<'info>,
+
+    pub rent: Sysvar<'info, Rent>,
+    pub token_program: Program<'info, Token>,
+    pub system_program: Program<'info, System>,
+}
+
+/// Accounts for moving idle base tokens in and out of the lending reserve.
+/// Solend requires the reserve to be refreshed earlier in the transaction.
+#[derive(Accounts)]
+pub struct ManageIdleFunds<'info> {
+    #[account(
+        mut,
+        seeds = [b"vault", vault.manager.as_ref(), vault.base_mint.as_ref()],
+        bump = vault.bump
+    )]
+    pub vault: Account<'info, Vault>,
+
+    #[account(mut, address = vault.base_vault)]
+    pub base_vault: Account<'info, TokenAccount>,
+
+    #[account(mut, address = vault.collateral_vault)]
+    pub collateral_vault: Account<'info, TokenAccount>,
+
+    /// CHECK: Solend reserve, checked against vault.lending_reserve
+    #[account(mut, address = vault.lending_reserve)]
+    pub reserve: UncheckedAccount<'info>,
+
+    /// CHECK: Reserve liquidity supply; validated by Solend
+    #[account(mut)]
+    pub reserve_liquidity_supply: UncheckedAccount<'info>,
+
+    /// CHECK: Reserve collateral mint; validated by Solend
+    #[account(mut)]
+    pub reserve_collateral_mint: UncheckedAccount<'info>,
+
+    /// CHECK: Lending market; validated by Solend
+    pub lending_market: UncheckedAccount<'info>,
+
+    /// CHECK: Lending market authority; validated by Solend
+    pub lending_market_authority: UncheckedAccount<'info>,
+
+    /// CHECK: Solend program
+    #[account(address = SOLEND_PROGRAM)]
+    pub lending_program: UncheckedAccount<'info>,
+
+    pub manager: Signer<'info>,
+    pub token_program: Program<'info, Token>,
+
+    /// Global emergency-pause registry
+    #[account(
+        seeds = [b"protocol-status"],
+        bump = protocol_status.bump,
+        seeds::program = vectai_protocol::ID,
+        constraint = !protocol_status.paused @ VaultError::ProtocolPaused
+    )]
+    pub protocol_status: Account<'info, ProtocolStatus>,
+}
+
+/// Lending accounts passed as `remaining_accounts`, in this order, to every
+/// instruction that values the vault while it has funds lent out, so the
+/// collateral is valued at the reserve's exchange rate and a short base
+/// balance can be recalled first
+pub struct LendingRemainingAccounts<'a, 'info> {
+    pub collateral_vault: &'a AccountInfo<'info>,
+    pub reserve: &'a AccountInfo<'info>,
+    pub reserve_liquidity_supply: &'a AccountInfo<'info>,
+    pub reserve_collateral_mint: &'a AccountInfo<'info>,
+    pub lending_market: &'a AccountInfo<'info>,
+    pub lending_market_authority: &'a AccountInfo<'info>,
+    pub lending_program: &'a AccountInfo<'info>,
+}
+
+impl<'a, 'info> LendingRemainingAccounts<'a, 'info> {
+    pub fn parse(vault: &Vault, accounts: &'a [AccountInfo<'info>]) -> Result<Self> {
+        require!(accounts.len() >= 7, VaultError::InvalidLendingAccounts);
+        let parsed = Self {
+            collateral_vault: &accounts[0],
+            reserve: &accounts[1],
+            reserve_liquidity_supply: &accounts[2],
+            reserve_collateral_mint: &accounts[3],
+            lending_market: &accounts[4],
+            lending_market_authority: &accounts[5],
+            lending_program: &accounts[6],
+        };
+        require!(
+            parsed.collateral_vault.key() == vault.collateral_vault
+                && parsed.reserve.key() == vault.lending_reserve
+                && parsed.lending_program.key() == SOLEND_PROGRAM,
+            VaultError::InvalidLendingAccounts
+        );
+        Ok(parsed)
+    }
+
+    /// Base tokens the vault's collateral redeems for at the reserve's
+    /// exchange rate, interest included
+    pub fn value(&self) -> Result<u64> {
+        let (total_liquidity, collateral_supply) = reserve_liquidity(self.reserve)?;
+        collateral_value(token::accessor::amount(self.collateral_vault)?, total_liquidity, collateral_supply)
+    }
+}
+
+/// Base tokens lent out, interest included: 0 without any principal lent,
+/// otherwise read from the lending accounts in `remaining_accounts`
+pub fn lent_value(vault: &Vault, remaining_accounts: &[AccountInfo]) -> Result<u64> {
+    if vault.lent_principal == 0 {
+        return Ok(0);
+    }
+    LendingRemainingAccounts::parse(vault, remaining_accounts)?.value()
+}
+
+/// Redeem enough collateral to bring `base_vault` up to `needed` base
+/// tokens, through the lending accounts in `remaining_accounts`. Nothing
+/// happens when the vault already holds `needed` or has nothing lent out.
+pub fn recall_shortfall<'info>(
+    vault: &mut Account<'info, Vault>,
+    base_vault: &mut Account<'info, TokenAccount>,
+    token_program: &AccountInfo<'info>,
+    remaining_accounts: &[AccountInfo<'info>],
+    needed: u64,
+) -> Result<()> {
+    let shortfall = needed.saturating_sub(base_vault.amount);
+    if shortfall == 0 || vault.lent_principal == 0 {
+        return Ok(());
+    }
+    let lending = LendingRemainingAccounts::parse(vault, remaining_accounts)?;
+    let (total_liquidity, collateral_supply) = reserve_liquidity(lending.reserve)?;
+    let collateral_balance = token::accessor::amount(lending.collateral_vault)?;
+    let collateral_amount = collateral_for(shortfall, total_liquidity, collateral_supply)?.min(collateral_balance);
+
+    let released = principal_released(vault.lent_principal, collateral_amount, collateral_balance);
+    vault.lent_principal -= released;
+
+    let base_before = base_vault.amount;
+    let seeds: &[&[u8]] = &[
+        b"vault",
+        vault.manager.as_ref(),
+        vault.base_mint.as_ref(),
+        &[vault.bump],
+    ];
+    redeem_reserve_collateral(
+        lending.lending_program,
+        lending.collateral_vault,
+        &base_vault.to_account_info(),
+        lending.reserve,
+        lending.reserve_collateral_mint,
+        lending.reserve_liquidity_supply,
+        lending.lending_market,
+        lending.lending_market_authority,
+        &vault.to_account_info(),
+        token_program,
+        collateral_amount,
+        &[seeds],
+    )?;
+    base_vault.reload()?;
+
+    emit!(IdleFundsRecalled {
+        vault: vault.key(),
+        collateral_amount,
+        liquidity_received: base_vault.amount.saturating_sub(base_before),
+        lent_principal: vault.lent_principal,
+    });
+    Ok(())
+}
+
+/// (total liquidity, collateral supply) of a Solend reserve as of its last
+/// refresh: available plus borrowed liquidity less the protocol's fees
+pub fn reserve_liquidity(reserve: &AccountInfo) -> Result<(u128, u64)> {
+    require!(reserve.owner == &SOLEND_PROGRAM, VaultError::InvalidLendingAccounts);
+    let data = reserve.try_borrow_data()?;
+    require!(
+        data.len() >= RESERVE_PROTOCOL_FEES_WADS_OFFSET + 16,
+        VaultError::InvalidLendingAccounts
+    );
+    let u64_at = |offset: usize| u64::from_le_bytes(data[offset..offset + 8].try_into().unwrap());
+    let u128_at = |offset: usize| u128::from_le_bytes(data[offset..offset + 16].try_into().unwrap());
+
+    let total_wads = (u64_at(RESERVE_AVAILABLE_AMOUNT_OFFSET) as u128)
+        .checked_mul(WAD)
+        .and_then(|available| available.checked_add(u128_at(RESERVE_BORROWED_AMOUNT_WADS_OFFSET)))
+        .ok_or(VaultError::MathOverflow)?
+        .saturating_sub(u128_at(RESERVE_PROTOCOL_FEES_WADS_OFFSET));
+    Ok((total_wads / WAD, u64_at(RESERVE_COLLATERAL_SUPPLY_OFFSET)))
+}
+
+/// Liquidity `collateral` redeems for, rounded down
+pub fn collateral_value(collateral: u64, total_liquidity: u128, collateral_supply: u64) -> Result<u64> {
+    if collateral_supply == 0 {
+        return Ok(0);
+    }
+    let value = (collateral as u128)
+        .checked_mul(total_liquidity)
+        .ok_or(VaultError::MathOverflow)?
+        / collateral_supply as u128;
+    u64::try_from(value).map_err(|_| VaultError::MathOverflow.into())
+}
+
+/// Collateral that redeems for at least `liquidity`, rounded up
+pub fn collateral_for(liquidity: u64, total_liquidity: u128, collateral_supply: u64) -> Result<u64> {
+    require!(total_liquidity > 0, VaultError::InsufficientLiquidity);
+    let collateral = (liquidity as u128)
+        .checked_mul(collateral_supply as u128)
+        .ok_or(VaultError::MathOverflow)?
+        .div_ceil(total_liquidity);
+    Ok(u64::try_from(collateral).unwrap_or(u64::MAX))
+}
+
+/// (liquidity mint, collateral mint) of a Solend reserve
+pub fn reserve_mints(reserve: &AccountInfo) -> Result<(Pubkey, Pubkey)> {
+    require!(reserve.owner == &SOLEND_PROGRAM, VaultError::InvalidLendingAccounts);
+    let data = reserve.try_borrow_data()?;
+    require!(
+        data.len() >= RESERVE_COLLATERAL_MINT_OFFSET + 32,
+        VaultError::InvalidLendingAccounts
+    );
+    let read = |offset: usize| {
+        Pubkey::try_from(&data[offset..offset + 32]).map_err(|_| VaultError::InvalidLendingAccounts)
+    };
+    Ok((read(RESERVE_LIQUIDITY_MINT_OFFSET)?, read(RESERVE_COLLATERAL_MINT_OFFSET)?))
+}
+
+/// Deposit `amount` of base liquidity into the reserve for collateral
+#[allow(clippy::too_many_arguments)]
+pub fn deposit_reserve_liquidity<'info>(
+    lending_program: &AccountInfo<'info>,
+    source_liquidity: &AccountInfo<'info>,
+    destination_collateral: &AccountInfo<'info>,
+    reserve: &AccountInfo<'info>,
+    reserve_liquidity_supply: &AccountInfo<'info>,
+    reserve_collateral_mint: &AccountInfo<'info>,
+    lending_market: &AccountInfo<'info>,
+    lending_market_authority: &AccountInfo<'info>,
+    transfer_authority: &AccountInfo<'info>,
+    token_program: &AccountInfo<'info>,
+    amount: u64,
+    signer_seeds: &[&[&[u8]]],
+) -> Result<()> {
+    let mut data = Vec::with_capacity(9);
+    data.push(DEPOSIT_RESERVE_LIQUIDITY);
+    data.extend_from_slice(&amount.to_le_bytes());
+
+    let ix = Instruction {
+        program_id: lending_program.key(),
+        accounts: vec![
+            AccountMeta::new(source_liquidity.key(), false),
+            AccountMeta::new(destination_collateral.key(), false),
+            AccountMeta::new(reserve.key(), false),
+            AccountMeta::new(reserve_liquidity_supply.key(), false),
+            AccountMeta::new(reserve_collateral_mint.key(), false),
+            AccountMeta::new_readonly(lending_market.key(), false),
+            AccountMeta::new_readonly(lending_market_authority.key(), false),
+            AccountMeta::new_readonly(transfer_authority.key(), true),
+            AccountMeta::new_readonly(token_program.key(), false),
+        ],
+        data,
+    };
+    invoke_signed(
+        &ix,
+        &[
+            source_liquidity.clone(),
+            destination_collateral.clone(),
+            reserve.clone(),
+            reserve_liquidity_supply.clone(),
+            reserve_collateral_mint.clone(),
+            lending_market.clone(),
+            lending_market_authority.clone(),
+            transfer_authority.clone(),
+            token_program.clone(),
+        ],
+        signer_seeds,
+    )?;
+    Ok(())
+}
+
+/// Redeem `collateral_amount` of reserve collateral for base liquidity
+#[allow(clippy::too_many_arguments)]
+pub fn redeem_reserve_collateral<'info>(
+    lending_program: &AccountInfo<'info>,
+    source_collateral: &AccountInfo<'info>,
+    destination_liquidity: &AccountInfo<'info>,
+    reserve: &AccountInfo<'info>,
+    reserve_collateral_mint: &AccountInfo<'info>,
+    reserve_liquidity_supply: &AccountInfo<'info>,
+    lending_market: &AccountInfo<'info>,
+    lending_market_authority: &AccountInfo<'info>,
+    transfer_authority: &AccountInfo<'info>,
+    token_program: &AccountInfo<'info>,
+    collateral_amount: u64,
+    signer_seeds: &[&[&[u8]]],
+) -> Result<()> {
+    let mut data = Vec::with_capacity(9);
+    data.push(REDEEM_RESERVE_COLLATERAL);
+    data.extend_from_slice(&collateral_amount.to_le_bytes());
+
+    let ix = Instruction {
+        program_id: lending_program.key(),
+        accounts: vec![
+            AccountMeta::new(source_collateral.key(), false),
+            AccountMeta::new(destination_liquidity.key(), false),
+            AccountMeta::new(reserve.key(), false),
+            AccountMeta::new(reserve_collateral_mint.key(), false),
+            AccountMeta::new(reserve_liquidity_supply.key(), false),
+            AccountMeta::new_readonly(lending_market.key(), false),
+            AccountMeta::new_readonly(lending_market_authority.key(), false),
+            AccountMeta::new_readonly(transfer_authority.key(), true),
+            AccountMeta::new_readonly(token_program.key(), false),
+        ],
+        data,
+    };
+    invoke_signed(
+        &ix,
+        &[
+            source_collateral.clone(),
+            destination_liquidity.clone(),
+            reserve.clone(),
+            reserve_collateral_mint.clone(),
+            reserve_liquidity_supply.clone(),
+            lending_market.clone(),
+            lending_market_authority.clone(),
+            transfer_authority.clone(),
+            token_program.clone(),
+        ],
+        signer_seeds,
+    )?;
+    Ok(())
+}
+
+/// Principal released when redeeming `collateral_amount` of `collateral_balance`
+pub fn principal_released(lent_principal: u64, collateral_amount: u64, collateral_balance: u64) -> u64 {
+    if collateral_amount >= collateral_balance {
+        return lent_principal;
+    }
+    ((lent_principal as u128) * (collateral_amount as u128) / collateral_balance as u128) as u64
+}
+
+#[event]
+pub struct IdleFundsSupplied {
+    pub vault: Pubkey,
+    pub amount: u64,
+    pub lent_principal: u64,
+}
+
+#[event]
+pub struct IdleFundsRecalled {
+    pub vault: Pubkey,
+    pub collateral_amount: u64,
+    pub liquidity_received: u64,
+    pub lent_principal: u64,
+}
@@ -0,0 +1,2121 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token::{self, Burn, Mint, MintTo, Token, TokenAccount, Transfer};
+use raydium_swapper::cpi::accounts::SwapAccounts;
+use raydium_swapper::program::RaydiumSwapper;
+use vectai_oracle::cpi::accounts::GetPrice;
+use vectai_oracle::program::VectaiOracle;
+use vectai_oracle::types::PriceData;
+use vectai_referral::ReferralCode;
+use vectai_protocol::{FeeConfig, ProtocolStatus};
+
+mod lending;
+pub use lending::*;
+mod withdrawals;
+pub use withdrawals::*;
+mod risk;
+pub use risk::*;
+mod caps;
+pub use caps::*;
+mod allocations;
+pub use allocations::*;
+
+declare_id!("8gRdxSkER4bctSR56NugscJej6cjKc1y8728P9r991Xc");
+
+// Oracle CPI client generated from idls/vectai_oracle.json
+declare_program!(vectai_oracle);
+
+#[program]
+pub mod vectai_vault {
+    use super::*;
+
+    /// Create a pooled vault: deposits are taken in `base_mint`, the manager
+    /// trades the pooled funds into `quote_mint` and back through the swapper
+    pub fn initialize_vault(ctx: Context<InitializeVault>) -> Result<()> {
+        require!(
+            ctx.accounts.base_mint.key() != ctx.accounts.quote_mint.key(),
+            VaultError::InvalidInput
+        );
+
+        let vault = &mut ctx.accounts.vault;
+        vault.manager = ctx.accounts.manager.key();
+        vault.base_mint = ctx.accounts.base_mint.key();
+        vault.quote_mint = ctx.accounts.quote_mint.key();
+        vault.base_vault = ctx.accounts.base_vault.key();
+        vault.quote_vault = ctx.accounts.quote_vault.key();
+        vault.share_mint = ctx.accounts.share_mint.key();
+        vault.price_feed = ctx.accounts.price_feed.key();
+        vault.is_active = true;
+        vault.bump = ctx.bumps.vault;
+        vault.fee_recipient = Pubkey::default();
+        vault.management_fee_bps = 0;
+        vault.last_fee_accrual = Clock::get()?.unix_timestamp;
+        vault.high_water_mark = SHARE_PRICE_PRECISION;
+        vault.lending_reserve = Pubkey::default();
+        vault.collateral_vault = Pubkey::default();
+        vault.lent_principal = 0;
+        vault.risk = DrawdownTracker::default();
+        vault.caps = DepositCaps::default();
+        vault.realized_pnl = 0;
+        vault.allocated = 0;
+        vault.referrer = match &ctx.accounts.referral_code {
+            Some(code) => {
+                require!(code.owner != ctx.accounts.manager.key(), VaultError::InvalidReferral);
+                code.key()
+            }
+            None => Pubkey::default(),
+        };
+
+        msg!("✅ Vault initialized: {} / {}", vault.base_mint, vault.quote_mint);
+        Ok(())
+    }
+
+    /// Deposit base tokens and receive shares priced at current NAV
+    pub fn deposit<'info>(ctx: Context<'_, '_, '_, 'info, Deposit<'info>>, amount: u64) -> Result<()> {
+        // ✅ CHECKS
+        require!(amount > 0, VaultError::InvalidAmount);
+        require!(ctx.accounts.vault.is_active, VaultError::VaultInactive);
+        require!(
+            ctx.accounts.user_base_account.amount >= amount,
+            VaultError::InsufficientBalance
+        );
+
+        let nav = vault_nav(
+            &ctx.accounts.vault,
+            &ctx.accounts.base_vault,
+            &ctx.accounts.quote_vault,
+            &ctx.accounts.base_mint,
+            &ctx.accounts.quote_mint,
+            &ctx.accounts.vectai_oracle_program,
+            &ctx.accounts.price_feed,
+            ctx.remaining_accounts,
+        )?;
+
+        // Fees up to now are settled before the deposit is priced
+        let fee_config = ctx.accounts.protocol_status.fee_config;
+        let accounts = &mut *ctx.accounts;
+        settle_fees(
+            &mut accounts.vault,
+            |_| Ok(nav),
+            &mut accounts.share_mint,
+            &accounts.fee_recipient,
+            &accounts.referral_escrow,
+            &fee_config,
+            &accounts.token_program,
+            Clock::get()?.unix_timestamp,
+        )?;
+
+        let supply = ctx.accounts.share_mint.supply;
+        ctx.accounts
+            .vault
+            .caps
+            .check(amount, nav, supply, ctx.accounts.depositor_position.shares)?;
+        let shares = shares_for_deposit(amount, supply, nav)?;
+        require!(shares > 0, VaultError::InvalidAmount);
+
+        // ✅ EFFECTS
+        let position = &mut ctx.accounts.depositor_position;
+        if position.vault == Pubkey::default() {
+            position.vault = ctx.accounts.vault.key();
+            position.depositor = ctx.accounts.user.key();
+            position.bump = ctx.bumps.depositor_position;
+        }
+        position.shares = position.shares.checked_add(shares).ok_or(VaultError::MathOverflow)?;
+
+        // ✅ INTERACTIONS
+        token::transfer(
+            CpiContext::new(
+                ctx.accounts.token_program.to_account_info(),
+                Transfer {
+                    from: ctx.accounts.user_base_account.to_account_info(),
+                    to: ctx.accounts.base_vault.to_account_info(),
+                    authority: ctx.accounts.user.to_account_info(),
+                },
+            ),
+            amount,
+        )?;
+
+        let vault = &ctx.accounts.vault;
+        let seeds: &[&[u8]] = &[
+            b"vault",
+            vault.manager.as_ref(),
+            vault.base_mint.as_ref(),
+            &[vault.bump],
+        ];
+        token::mint_to(
+            CpiContext::new_with_signer(
+                ctx.accounts.token_program.to_account_info(),
+                MintTo {
+                    mint: ctx.accounts.share_mint.to_account_info(),
+                    to: ctx.accounts.user_share_account.to_account_info(),
+                    authority: ctx.accounts.vault.to_account_info(),
+                },
+                &[seeds],
+            ),
+            shares,
+        )?;
+
+        emit!(Deposited {
+            vault: ctx.accounts.vault.key(),
+            user: ctx.accounts.user.key(),
+            amount,
+            shares,
+            nav,
+        });
+        msg!("✅ Deposited {} (NAV {}), minted {} shares", amount, nav, shares);
+        Ok(())
+    }
+
+    /// Burn shares for a pro-rata, in-kind slice of both vault balances.
+    /// While funds are lent out, pass the lending accounts as remaining
+    /// accounts: a short base vault is topped up from lending first, with
+    /// the reserve refreshed earlier in the transaction as Solend requires.
+    /// Without a `price_feed`, while the oracle is down, fees settle at the
+    /// high-water mark (see `high_water_nav`) so exits never wait on it.
+    pub fn withdraw<'info>(ctx: Context<'_, '_, '_, 'info, Withdraw<'info>>, shares: u64) -> Result<()> {
+        // ✅ CHECKS
+        require!(shares > 0, VaultError::InvalidAmount);
+        require!(
+            ctx.accounts.user_share_account.amount >= shares,
+            VaultError::InsufficientBalance
+        );
+
+        // Fees up to now are settled before the shares are redeemed
+        let fee_config = ctx.accounts.protocol_status.fee_config;
+        let remaining_accounts = ctx.remaining_accounts;
+        let accounts = &mut *ctx.accounts;
+        let supply = accounts.share_mint.supply;
+        settle_fees(
+            &mut accounts.vault,
+            |vault| match &accounts.price_feed {
+                Some(price_feed) => vault_nav(
+                    vault,
+                    &accounts.base_vault,
+                    &accounts.quote_vault,
+                    &accounts.base_mint,
+                    &accounts.quote_mint,
+                    &accounts.vectai_oracle_program,
+                    price_feed,
+                    remaining_accounts,
+                ),
+                None => high_water_nav(vault, supply),
+            },
+            &mut accounts.share_mint,
+            &accounts.fee_recipient,
+            &accounts.referral_escrow,
+            &fee_config,
+            &accounts.token_program,
+            Clock::get()?.unix_timestamp,
+        )?;
+
+        let supply = ctx.accounts.share_mint.supply;
+        let base_out = redemption_amount(
+            base_holdings(&ctx.accounts.vault, &ctx.accounts.base_vault, ctx.remaining_accounts)?,
+            VIRTUAL_ASSETS,
+            shares,
+            supply,
+        )?;
+        let quote_out = redemption_amount(ctx.accounts.quote_vault.amount, 0, shares, supply)?;
+
+        // ✅ Recall lent funds if the base vault is short of the payout
+        let accounts = &mut *ctx.accounts;
+        recall_shortfall(
+            &mut accounts.vault,
+            &mut accounts.base_vault,
+            &accounts.token_program.to_account_info(),
+            remaining_accounts,
+            base_out,
+        )?;
+        require!(
+            ctx.accounts.base_vault.amount >= base_out,
+            VaultError::InsufficientLiquidity
+        );
+
+        // ✅ EFFECTS
+        if let Some(position) = &mut ctx.accounts.depositor_position {
+            position.release(shares);
+        }
+
+        // ✅ INTERACTIONS: Burn first, then pay out
+        token::burn(
+            CpiContext::new(
+                ctx.accounts.token_program.to_account_info(),
+                Burn {
+                    mint: ctx.accounts.share_mint.to_account_info(),
+                    from: ctx.accounts.user_share_account.to_account_info(),
+                    authority: ctx.accounts.user.to_account_info(),
+                },
+            ),
+            shares,
+        )?;
+
+        let vault = &ctx.accounts.vault;
+        let seeds: &[&[u8]] = &[
+            b"vault",
+            vault.manager.as_ref(),
+            vault.base_mint.as_ref(),
+            &[vault.bump],
+        ];
+        if base_out > 0 {
+            token::transfer(
+                CpiContext::new_with_signer(
+                    ctx.accounts.token_program.to_account_info(),
+                    Transfer {
+                        from: ctx.accounts.base_vault.to_account_info(),
+                        to: ctx.accounts.user_base_account.to_account_info(),
+                        authority: ctx.accounts.vault.to_account_info(),
+                    },
+                    &[seeds],
+                ),
+                base_out,
+            )?;
+        }
+        if quote_out > 0 {
+            token::transfer(
+                CpiContext::new_with_signer(
+                    ctx.accounts.token_program.to_account_info(),
+                    Transfer {
+                        from: ctx.accounts.quote_vault.to_account_info(),
+                        to: ctx.accounts.user_quote_account.to_account_info(),
+                        authority: ctx.accounts.vault.to_account_info(),
+                    },
+                    &[seeds],
+                ),
+                quote_out,
+            )?;
+        }
+
+        emit!(Withdrawn {
+            vault: ctx.accounts.vault.key(),
+            user: ctx.accounts.user.key(),
+            shares,
+            base_amount: base_out,
+            quote_amount: quote_out,
+        });
+        msg!("✅ Withdrew {} shares: {} base + {} quote", shares, base_out, quote_out);
+        Ok(())
+    }
+
+    /// Open the epoch-based withdrawal queue for a vault (manager only)
+    pub fn initialize_withdrawal_queue(
+        ctx: Context<InitializeWithdrawalQueue>,
+        epoch_duration: i64,
+    ) -> Result<()> {
+        require!(
+            ctx.accounts.manager.key() == ctx.accounts.vault.manager,
+            VaultError::Unauthorized
+        );
+        require!(epoch_duration > 0, VaultError::InvalidInput);
+
+        let queue = &mut ctx.accounts.queue;
+        queue.vault = ctx.accounts.vault.key();
+        queue.share_escrow = ctx.accounts.share_escrow.key();
+        queue.base_claims = ctx.accounts.base_claims.key();
+        queue.quote_claims = ctx.accounts.quote_claims.key();
+        queue.epoch_duration = epoch_duration;
+        queue.current_epoch = 0;
+        queue.epoch_end_time = Clock::get()?
+            .unix_timestamp
+            .checked_add(epoch_duration)
+            .ok_or(VaultError::MathOverflow)?;
+        queue.pending_shares = 0;
+        queue.bump = ctx.bumps.queue;
+
+        msg!("✅ Withdrawal queue initialized: {}s epochs", epoch_duration);
+        Ok(())
+    }
+
+    /// Queue shares for redemption at the end of the current epoch
+    pub fn request_withdrawal(ctx: Context<RequestWithdrawal>, shares: u64) -> Result<()> {
+        // ✅ CHECKS
+        require!(shares > 0, VaultError::InvalidAmount);
+        require!(
+            ctx.accounts.user_share_account.amount >= shares,
+            VaultError::InsufficientBalance
+        );
+
+        // ✅ EFFECTS
+        let queue = &mut ctx.accounts.queue;
+        queue.pending_shares = queue
+            .pending_shares
+            .checked_add(shares)
+            .ok_or(VaultError::MathOverflow)?;
+
+        let request = &mut ctx.accounts.request;
+        request.owner = ctx.accounts.user.key();
+        request.vault = ctx.accounts.vault.key();
+        request.epoch = queue.current_epoch;
+        request.shares = shares;
+        request.bump = ctx.bumps.request;
+
+        if let Some(position) = &mut ctx.accounts.depositor_position {
+            position.release(shares);
+        }
+
+        // ✅ INTERACTIONS
+        token::transfer(
+            CpiContext::new(
+                ctx.accounts.token_program.to_account_info(),
+                Transfer {
+                    from: ctx.accounts.user_share_account.to_account_info(),
+                    to: ctx.accounts.share_escrow.to_account_info(),
+                    authority: ctx.accounts.user.to_account_info(),
+                },
+            ),
+            shares,
+        )?;
+
+        emit!(WithdrawalRequested {
+            vault: ctx.accounts.vault.key(),
+            user: ctx.accounts.user.key(),
+            epoch: request.epoch,
+            shares,
+        });
+        msg!("✅ Queued {} shares for epoch {}", shares, request.epoch);
+        Ok(())
+    }
+
+    /// Permissionless crank at the epoch boundary: burn the epoch's queued
+    /// shares and set aside their pro-rata balances for claiming, recalling
+    /// lent funds and settling fees without a `price_feed` the way
+    /// `withdraw` does
+    pub fn process_withdrawals<'info>(ctx: Context<'_, '_, '_, 'info, ProcessWithdrawals<'info>>) -> Result<()> {
+        let clock = Clock::get()?;
+        require!(
+            clock.unix_timestamp >= ctx.accounts.queue.epoch_end_time,
+            VaultError::EpochNotEnded
+        );
+
+        // Fees up to now are settled before the epoch is redeemed
+        let fee_config = ctx.accounts.protocol_status.fee_config;
+        let remaining_accounts = ctx.remaining_accounts;
+        let accounts = &mut *ctx.accounts;
+        let supply = accounts.share_mint.supply;
+        settle_fees(
+            &mut accounts.vault,
+            |vault| match &accounts.price_feed {
+                Some(price_feed) => vault_nav(
+                    vault,
+                    &accounts.base_vault,
+                    &accounts.quote_vault,
+                    &accounts.base_mint,
+                    &accounts.quote_mint,
+                    &accounts.vectai_oracle_program,
+                    price_feed,
+                    remaining_accounts,
+                ),
+                None => high_water_nav(vault, supply),
+            },
+            &mut accounts.share_mint,
+            &accounts.fee_recipient,
+            &accounts.referral_escrow,
+            &fee_config,
+            &accounts.token_program,
+            clock.unix_timestamp,
+        )?;
+
+        let pending = ctx.accounts.queue.pending_shares;
+        let supply = ctx.accounts.share_mint.supply;
+        let (base_out, quote_out) = if pending > 0 {
+            (
+                redemption_amount(
+                    base_holdings(&ctx.accounts.vault, &ctx.accounts.base_vault, ctx.remaining_accounts)?,
+                    VIRTUAL_ASSETS,
+                    pending,
+                    supply,
+                )?,
+                redemption_amount(ctx.accounts.quote_vault.amount, 0, pending, supply)?,
+            )
+        } else {
+            (0, 0)
+        };
+
+        // ✅ Recall lent funds if the base vault is short of the payout
+        let accounts = &mut *ctx.accounts;
+        recall_shortfall(
+            &mut accounts.vault,
+            &mut accounts.base_vault,
+            &accounts.token_program.to_account_info(),
+            remaining_accounts,
+            base_out,
+        )?;
+        require!(
+            ctx.accounts.base_vault.amount >= base_out,
+            VaultError::InsufficientLiquidity
+        );
+
+        // ✅ EFFECTS
+        let queue = &mut ctx.accounts.queue;
+        let epoch = queue.current_epoch;
+        let redemption = &mut ctx.accounts.redemption;
+        redemption.epoch = epoch;
+        redemption.total_shares = pending;
+        redemption.base_amount = base_out;
+        redemption.quote_amount = quote_out;
+        redemption.bump = ctx.bumps.redemption;
+
+        queue.current_epoch = epoch.checked_add(1).ok_or(VaultError::MathOverflow)?;
+        queue.pending_shares = 0;
+        queue.epoch_end_time = clock
+            .unix_timestamp
+            .checked_add(queue.epoch_duration)
+            .ok_or(VaultError::MathOverflow)?;
+
+        // ✅ INTERACTIONS
+        let vault = &ctx.accounts.vault;
+        let seeds: &[&[u8]] = &[
+            b"vault",
+            vault.manager.as_ref(),
+            vault.base_mint.as_ref(),
+            &[vault.bump],
+        ];
+        if pending > 0 {
+            token::burn(
+                CpiContext::new_with_signer(
+                    ctx.accounts.token_program.to_account_info(),
+                    Burn {
+                        mint: ctx.accounts.share_mint.to_account_info(),
+                        from: ctx.accounts.share_escrow.to_account_info(),
+                        authority: ctx.accounts.vault.to_account_info(),
+                    },
+                    &[seeds],
+                ),
+                pending,
+            )?;
+        }
+        if base_out > 0 {
+            token::transfer(
+                CpiContext::new_with_signer(
+                    ctx.accounts.token_program.to_account_info(),
+                    Transfer {
+                        from: ctx.accounts.base_vault.to_account_info(),
+                        to: ctx.accounts.base_claims.to_account_info(),
+                        authority: ctx.accounts.vault.to_account_info(),
+                    },
+                    &[seeds],
+                ),
+                base_out,
+            )?;
+        }
+        if quote_out > 0 {
+            token::transfer(
+                CpiContext::new_with_signer(
+                    ctx.accounts.token_program.to_account_info(),
+                    Transfer {
+                        from: ctx.accounts.quote_vault.to_account_info(),
+                        to: ctx.accounts.quote_claims.to_account_info(),
+                        authority: ctx.accounts.vault.to_account_info(),
+                    },
+                    &[seeds],
+                ),
+                quote_out,
+            )?;
+        }
+
+        emit!(WithdrawalsProcessed {
+            vault: ctx.accounts.vault.key(),
+            epoch,
+            total_shares: pending,
+            base_amount: base_out,
+            quote_amount: quote_out,
+        });
+        msg!("✅ Epoch {} processed: {} shares -> {} base + {} quote",
+             epoch, pending, base_out, quote_out);
+        Ok(())
+    }
+
+    /// Pay out a processed withdrawal request and close it
+    pub fn claim_withdrawal(ctx: Context<ClaimWithdrawal>) -> Result<()> {
+        let request = &ctx.accounts.request;
+        let redemption = &ctx.accounts.redemption;
+        let base_out = pro_rata(redemption.base_amount, request.shares, redemption.total_shares)?;
+        let quote_out = pro_rata(redemption.quote_amount, request.shares, redemption.total_shares)?;
+
+        let vault = &ctx.accounts.vault;
+        let seeds: &[&[u8]] = &[
+            b"vault",
+            vault.manager.as_ref(),
+            vault.base_mint.as_ref(),
+            &[vault.bump],
+        ];
+        if base_out > 0 {
+            token::transfer(
+                CpiContext::new_with_signer(
+                    ctx.accounts.token_program.to_account_info(),
+                    Transfer {
+                        from: ctx.accounts.base_claims.to_account_info(),
+                        to: ctx.accounts.user_base_account.to_account_info(),
+                        authority: ctx.accounts.vault.to_account_info(),
+                    },
+                    &[seeds],
+                ),
+                base_out,
+            )?;
+        }
+        if quote_out > 0 {
+            token::transfer(
+                CpiContext::new_with_signer(
+                    ctx.accounts.token_program.to_account_info(),
+                    Transfer {
+                        from: ctx.accounts.quote_claims.to_account_info(),
+                        to: ctx.accounts.user_quote_account.to_account_info(),
+                        authority: ctx.accounts.vault.to_account_info(),
+                    },
+                    &[seeds],
+                ),
+                quote_out,
+            )?;
+        }
+
+        emit!(Withdrawn {
+            vault: ctx.accounts.vault.key(),
+            user: ctx.accounts.user.key(),
+            shares: request.shares,
+            base_amount: base_out,
+            quote_amount: quote_out,
+        });
+        msg!("✅ Claimed epoch {} withdrawal: {} base + {} quote",
+             request.epoch, base_out, quote_out);
+        Ok(())
+    }
+
+    /// Register a Solend reserve for the base mint so idle funds can earn
+    /// yield (manager only)
+    pub fn enable_lending(ctx: Context<EnableLending>) -> Result<()> {
+        require!(
+            ctx.accounts.manager.key() == ctx.accounts.vault.manager,
+            VaultError::Unauthorized
+        );
+        require!(
+            ctx.accounts.vault.lending_reserve == Pubkey::default(),
+            VaultError::InvalidInput
+        );
+
+        let (liquidity_mint, collateral_mint) = reserve_mints(&ctx.accounts.reserve)?;
+        require!(
+            liquidity_mint == ctx.accounts.vault.base_mint
+                && collateral_mint == ctx.accounts.reserve_collateral_mint.key(),
+            VaultError::InvalidLendingAccounts
+        );
+
+        let vault = &mut ctx.accounts.vault;
+        vault.lending_reserve = ctx.accounts.reserve.key();
+        vault.collateral_vault = ctx.accounts.collateral_vault.key();
+
+        msg!("✅ Lending enabled: reserve {}", vault.lending_reserve);
+        Ok(())
+    }
+
+    /// Supply idle base tokens to the lending reserve (manager only)
+    pub fn supply_idle(ctx: Context<ManageIdleFunds>, amount: u64) -> Result<()> {
+        // ✅ CHECKS
+        require!(
+            ctx.accounts.manager.key() == ctx.accounts.vault.manager,
+            VaultError::Unauthorized
+        );
+        require!(amount > 0, VaultError::InvalidAmount);
+        require!(
+            ctx.accounts.base_vault.amount >= amount,
+            VaultError::InsufficientBalance
+        );
+
+        // ✅ EFFECTS
+        let vault = &mut ctx.accounts.vault;
+        vault.lent_principal = vault
+            .lent_principal
+            .checked_add(amount)
+            .ok_or(VaultError::MathOverflow)?;
+
+        // ✅ INTERACTIONS
+        let vault = &ctx.accounts.vault;
+        let seeds: &[&[u8]] = &[
+            b"vault",
+            vault.manager.as_ref(),
+            vault.base_mint.as_ref(),
+            &[vault.bump],
+        ];
+        deposit_reserve_liquidity(
+            &ctx.accounts.lending_program.to_account_info(),
+            &ctx.accounts.base_vault.to_account_info(),
+            &ctx.accounts.collateral_vault.to_account_info(),
+            &ctx.accounts.reserve.to_account_info(),
+            &ctx.accounts.reserve_liquidity_supply.to_account_info(),
+            &ctx.accounts.reserve_collateral_mint.to_account_info(),
+            &ctx.accounts.lending_market.to_account_info(),
+            &ctx.accounts.lending_market_authority.to_account_info(),
+            &ctx.accounts.vault.to_account_info(),
+            &ctx.accounts.token_program.to_account_info(),
+            amount,
+            &[seeds],
+        )?;
+
+        emit!(IdleFundsSupplied {
+            vault: ctx.accounts.vault.key(),
+            amount,
+            lent_principal: ctx.accounts.vault.lent_principal,
+        });
+        msg!("✅ Supplied {} idle base tokens to lending", amount);
+        Ok(())
+    }
+
+    /// Redeem lending collateral back into the base vault (manager only)
+    pub fn recall_idle(ctx: Context<ManageIdleFunds>, collateral_amount: u64) -> Result<()> {
+        // ✅ CHECKS
+        require!(
+            ctx.accounts.manager.key() == ctx.accounts.vault.manager,
+            VaultError::Unauthorized
+        );
+        require!(collateral_amount > 0, VaultError::InvalidAmount);
+        let collateral_balance = ctx.accounts.collateral_vault.amount;
+        require!(
+            collateral_balance >= collateral_amount,
+            VaultError::InsufficientBalance
+        );
+
+        // ✅ EFFECTS
+        let vault = &mut ctx.accounts.vault;
+        let released = principal_released(vault.lent_principal, collateral_amount, collateral_balance);
+        vault.lent_principal -= released;
+
+        // ✅ INTERACTIONS
+        let base_before = ctx.accounts.base_vault.amount;
+        let vault = &ctx.accounts.vault;
+        let seeds: &[&[u8]] = &[
+            b"vault",
+            vault.manager.as_ref(),
+            vault.base_mint.as_ref(),
+            &[vault.bump],
+        ];
+        redeem_reserve_collateral(
+            &ctx.accounts.lending_program.to_account_info(),
+            &ctx.accounts.collateral_vault.to_account_info(),
+            &ctx.accounts.base_vault.to_account_info(),
+            &ctx.accounts.reserve.to_account_info(),
+            &ctx.accounts.reserve_collateral_mint.to_account_info(),
+            &ctx.accounts.reserve_liquidity_supply.to_account_info(),
+            &ctx.accounts.lending_market.to_account_info(),
+            &ctx.accounts.lending_market_authority.to_account_info(),
+            &ctx.accounts.vault.to_account_info(),
+            &ctx.accounts.token_program.to_account_info(),
+            collateral_amount,
+            &[seeds],
+        )?;
+        ctx.accounts.base_vault.reload()?;
+        let received = ctx.accounts.base_vault.amount.saturating_sub(base_before);
+
+        emit!(IdleFundsRecalled {
+            vault: ctx.accounts.vault.key(),
+            collateral_amount,
+            liquidity_received: received,
+            lent_principal: ctx.accounts.vault.lent_principal,
+        });
+        msg!("✅ Recalled {} base tokens from lending", received);
+        Ok(())
+    }
+
+    /// Trade pooled funds through raydium_swapper (manager only)
+    ///
+    /// While funds are lent out, pass the lending accounts (see
+    /// `LendingRemainingAccounts`) as remaining accounts: when selling base
+    /// and the base vault is short, enough collateral to cover it is
+    /// redeemed before the swap.
+    pub fn trade<'info>(
+        ctx: Context<'_, '_, 'info, 'info, VaultTrade<'info>>,
+        amount_in: u64,
+        min_amount_out: u64,
+        base_to_quote: bool,
+    ) -> Result<()> {
+        // ✅ CHECKS
+        require!(
+            ctx.accounts.manager.key() == ctx.accounts.vault.manager,
+            VaultError::Unauthorized
+        );
+        require!(ctx.accounts.vault.is_active, VaultError::VaultInactive);
+        require!(amount_in > 0, VaultError::InvalidAmount);
+
+        // ✅ CHECKS: Drawdown limit, marking the share price at oracle NAV
+        require!(!ctx.accounts.vault.risk.halted, VaultError::DrawdownLimitExceeded);
+        let supply = ctx.accounts.share_mint.supply;
+        if supply > 0 {
+            let nav = vault_nav(
+                &ctx.accounts.vault,
+                &ctx.accounts.base_vault,
+                &ctx.accounts.quote_vault,
+                &ctx.accounts.base_mint,
+                &ctx.accounts.quote_mint,
+                &ctx.accounts.vectai_oracle_program,
+                &ctx.accounts.price_feed,
+                ctx.remaining_accounts,
+            )?;
+            let share_price = share_price(nav, supply)?;
+            let peak_share_price = ctx.accounts.vault.risk.peak_equity;
+            if ctx.accounts.vault.risk.mark(share_price) {
+                // Persist the halt instead of failing, which would roll it back
+                let risk = &ctx.accounts.vault.risk;
+                emit!(DrawdownLimitHit {
+                    account: ctx.accounts.vault.key(),
+                    peak_equity: peak_share_price,
+                    equity: share_price,
+                    drawdown_bps: risk.last_drawdown_bps,
+                    max_drawdown_bps: risk.max_drawdown_bps,
+                });
+                msg!("🛑 Drawdown {} bps exceeds limit {} bps, trading halted",
+                     risk.last_drawdown_bps, risk.max_drawdown_bps);
+                return Ok(());
+            }
+        }
+
+        // ✅ Recall lent funds just before trading if the base vault is short
+        if base_to_quote {
+            let accounts = &mut *ctx.accounts;
+            recall_shortfall(
+                &mut accounts.vault,
+                &mut accounts.base_vault,
+                &accounts.token_program.to_account_info(),
+                ctx.remaining_accounts,
+                amount_in,
+            )?;
+        }
+
+        let (source, destination) = if base_to_quote {
+            (&ctx.accounts.base_vault, &ctx.accounts.quote_vault)
+        } else {
+            (&ctx.accounts.quote_vault, &ctx.accounts.base_vault)
+        };
+        require!(source.amount >= amount_in, VaultError::InsufficientBalance);
+        let balance_before = destination.amount;
+
+        // ✅ INTERACTIONS: Vault PDA signs as the swap user
+        let vault = &ctx.accounts.vault;
+        let seeds: &[&[u8]] = &[
+            b"vault",
+            vault.manager.as_ref(),
+            vault.base_mint.as_ref(),
+            &[vault.bump],
+        ];
+        let swap_accounts = SwapAccounts {
+            user_authority: ctx.accounts.vault.to_account_info(),
+            user_source_token: source.to_account_info(),
+            user_destination_token: destination.to_account_info(),
+            raydium_amm_program: ctx.accounts.raydium_amm_program.to_account_info(),
+            amm: ctx.accounts.amm.to_account_info(),
+            amm_authority: ctx.accounts.amm_authority.to_account_info(),
+            amm_open_orders: ctx.accounts.amm_open_orders.to_account_info(),
+            amm_target_orders: ctx.accounts.amm_target_orders.to_account_info(),
+            pool_coin_token_account: ctx.accounts.pool_coin_token_account.to_account_info(),
+            pool_pc_token_account: ctx.accounts.pool_pc_token_account.to_account_info(),
+            serum_program: ctx.accounts.serum_program.to_account_info(),
+            serum_market: ctx.accounts.serum_market.to_account_info(),
+            serum_bids: ctx.accounts.serum_bids.to_account_info(),
+            serum_asks: ctx.accounts.serum_asks.to_account_info(),
+            serum_event_queue: ctx.accounts.serum_event_queue.to_account_info(),
+            serum_coin_vault: ctx.accounts.serum_coin_vault.to_account_info(),
+            serum_pc_vault: ctx.accounts.serum_pc_vault.to_account_info(),
+            serum_vault_signer: ctx.accounts.serum_vault_signer.to_account_info(),
+            token_program: ctx.accounts.token_program.to_account_info(),
+            protocol_status: ctx.accounts.protocol_status.to_account_info(),
+            network_config: ctx.accounts.network_config.to_account_info(),
+            protocol_stats: ctx.accounts.protocol_stats.to_account_info(),
+            stats_reporter: ctx.accounts.swapper_stats_reporter.to_account_info(),
+            protocol_program: ctx.accounts.protocol_program.to_account_info(),
+            fee_account: ctx.accounts.swap_fee_account.as_ref().map(|a| a.to_account_info()),
+        };
+        raydium_swapper::cpi::swap(
+            CpiContext::new_with_signer(
+                ctx.accounts.swapper_program.to_account_info(),
+                swap_accounts,
+                &[seeds],
+            ),
+            amount_in,
+            min_amount_out,
+        )?;
+
+        // ✅ PnL: both legs marked in base-token units at the oracle
+        let destination = if base_to_quote {
+            &mut ctx.accounts.quote_vault
+        } else {
+            &mut ctx.accounts.base_vault
+        };
+        destination.reload()?;
+        let amount_out = destination
+            .amount
+            .checked_sub(balance_before)
+            .ok_or(VaultError::MathOverflow)?;
+        let price_data = oracle_price(&ctx.accounts.vault, &ctx.accounts.vectai_oracle_program, &ctx.accounts.price_feed)?;
+        let quote_value = |quote_amount| {
+            quote_value_in_base(
+                quote_amount,
+                price_data.price,
+                price_data.expo,
+                ctx.accounts.base_mint.decimals,
+                ctx.accounts.quote_mint.decimals,
+            )
+        };
+        let (value_in, value_out) = if base_to_quote {
+            (amount_in, quote_value(amount_out)?)
+        } else {
+            (quote_value(amount_in)?, amount_out)
+        };
+        let pnl = vectai_common::realized_pnl(value_in, value_out)?;
+        let vault = &mut ctx.accounts.vault;
+        vault.realized_pnl = vault.realized_pnl.checked_add(pnl).ok_or(VaultError::MathOverflow)?;
+
+        emit!(PnlRealized {
+            vault: vault.key(),
+            value_in,
+            value_out,
+            pnl,
+            realized_pnl: vault.realized_pnl,
+        });
+        msg!("✅ Vault traded {} ({})", amount_in, if base_to_quote { "base -> quote" } else { "quote -> base" });
+        Ok(())
+    }
+
+    /// Set the management fee and the share account fees are minted to
+    /// (timelock only). The performance fee comes from the protocol fee
+    /// config.
+    pub fn set_vault_fees<'info>(
+        ctx: Context<'_, '_, '_, 'info, SetVaultFees<'info>>,
+        management_fee_bps: u16,
+    ) -> Result<()> {
+        require!(
+            is_timelock(&ctx.accounts.admin.key()),
+            VaultError::UnauthorizedAdmin
+        );
+        require!(
+            management_fee_bps <= MAX_MANAGEMENT_FEE_BPS,
+            VaultError::FeeTooHigh
+        );
+
+        // Time so far accrues at the old rates
+        let nav = vault_nav(
+            &ctx.accounts.vault,
+            &ctx.accounts.base_vault,
+            &ctx.accounts.quote_vault,
+            &ctx.accounts.base_mint,
+            &ctx.accounts.quote_mint,
+            &ctx.accounts.vectai_oracle_program,
+            &ctx.accounts.price_feed,
+            ctx.remaining_accounts,
+        )?;
+        let fee_config = ctx.accounts.protocol_status.fee_config;
+        let was_charging = ctx.accounts.vault.charges_fees(fee_config.performance_fee_bps);
+        let fee_recipient = Some(ctx.accounts.fee_recipient.clone());
+        let accounts = &mut *ctx.accounts;
+        settle_fees(
+            &mut accounts.vault,
+            |_| Ok(nav),
+            &mut accounts.share_mint,
+            &fee_recipient,
+            &accounts.referral_escrow,
+            &fee_config,
+            &accounts.token_program,
+            Clock::get()?.unix_timestamp,
+        )?;
+
+        let share_price = share_price(nav, ctx.accounts.share_mint.supply)?;
+        let vault = &mut ctx.accounts.vault;
+        vault.management_fee_bps = management_fee_bps;
+        vault.fee_recipient = ctx.accounts.fee_recipient.key();
+        // Turning fees on starts the performance fee from today's price
+        if !was_charging && vault.charges_fees(fee_config.performance_fee_bps) {
+            vault.high_water_mark = vault.high_water_mark.max(share_price);
+        }
+
+        emit!(VaultFeesUpdated {
+            vault: vault.key(),
+            management_fee_bps,
+            fee_recipient: vault.fee_recipient,
+        });
+        msg!("✅ Vault fees: {} bps/yr management", management_fee_bps);
+        Ok(())
+    }
+
+    /// Permissionless crank: accrue the pro-rated management fee and the
+    /// protocol performance fee above the high-water mark, minted as shares.
+    /// With fees off it only moves the accrual clock.
+    pub fn accrue_fees<'info>(ctx: Context<'_, '_, '_, 'info, AccrueFees<'info>>) -> Result<()> {
+        let clock = Clock::get()?;
+        let elapsed = clock.unix_timestamp - ctx.accounts.vault.last_fee_accrual;
+        require!(elapsed > 0, VaultError::NothingToAccrue);
+
+        let fee_config = ctx.accounts.protocol_status.fee_config;
+        let remaining_accounts = ctx.remaining_accounts;
+        let accounts = &mut *ctx.accounts;
+        settle_fees(
+            &mut accounts.vault,
+            |vault| {
+                vault_nav(
+                    vault,
+                    &accounts.base_vault,
+                    &accounts.quote_vault,
+                    &accounts.base_mint,
+                    &accounts.quote_mint,
+                    &accounts.vectai_oracle_program,
+                    &accounts.price_feed,
+                    remaining_accounts,
+                )
+            },
+            &mut accounts.share_mint,
+            &accounts.fee_recipient,
+            &accounts.referral_escrow,
+            &fee_config,
+            &accounts.token_program,
+            clock.unix_timestamp,
+        )
+    }
+
+    /// Set the max-drawdown limit on the share price; 0 disables it
+    /// (manager only)
+    pub fn set_vault_max_drawdown(ctx: Context<ManageVault>, max_drawdown_bps: u16) -> Result<()> {
+        require!(
+            ctx.accounts.manager.key() == ctx.accounts.vault.manager,
+            VaultError::Unauthorized
+        );
+        require!(max_drawdown_bps <= MAX_DRAWDOWN_LIMIT_BPS, VaultError::InvalidInput);
+
+        ctx.accounts.vault.risk.max_drawdown_bps = max_drawdown_bps;
+        msg!("Vault max drawdown: {} bps", max_drawdown_bps);
+        Ok(())
+    }
+
+    /// Clear a drawdown halt; the peak re-baselines at the next trade
+    /// (manager only)
+    pub fn reset_vault_drawdown(ctx: Context<ManageVault>) -> Result<()> {
+        require!(
+            ctx.accounts.manager.key() == ctx.accounts.vault.manager,
+            VaultError::Unauthorized
+        );
+
+        let vault = &mut ctx.accounts.vault;
+        vault.risk.reset();
+
+        emit!(DrawdownReset {
+            account: vault.key(),
+            max_drawdown_bps: vault.risk.max_drawdown_bps,
+        });
+        msg!("✅ Vault drawdown tracker reset");
+        Ok(())
+    }
+
+    /// Cap the vault's NAV and each depositor's position after a deposit,
+    /// both oracle-valued in base tokens; 0 lifts a cap (manager only)
+    pub fn set_deposit_caps(ctx: Context<ManageVault>, max_total_value: u64, max_per_depositor: u64) -> Result<()> {
+        require!(
+            ctx.accounts.manager.key() == ctx.accounts.vault.manager,
+            VaultError::Unauthorized
+        );
+
+        let vault = &mut ctx.accounts.vault;
+        vault.caps = DepositCaps {
+            max_total_value,
+            max_per_depositor,
+        };
+
+        emit!(DepositCapsUpdated {
+            vault: vault.key(),
+            max_total_value,
+            max_per_depositor,
+        });
+        msg!("✅ Deposit caps: {} total, {} per depositor", max_total_value, max_per_depositor);
+        Ok(())
+    }
+
+    /// Register a strategy the vault can allocate base tokens to, funded
+    /// from its own vault-held escrow; it stays unweighted until governance
+    /// sets a weight (manager only)
+    pub fn register_strategy(ctx: Context<RegisterStrategy>, strategy: Pubkey) -> Result<()> {
+        require!(
+            ctx.accounts.manager.key() == ctx.accounts.vault.manager,
+            VaultError::Unauthorized
+        );
+
+        let allocations = &mut ctx.accounts.allocations;
+        if allocations.vault == Pubkey::default() {
+            allocations.vault = ctx.accounts.vault.key();
+            allocations.bump = ctx.bumps.allocations;
+        }
+        allocations.register(strategy, ctx.accounts.strategy_escrow.key())?;
+
+        emit!(StrategyRegistered {
+            vault: ctx.accounts.vault.key(),
+            strategy,
+            escrow: ctx.accounts.strategy_escrow.key(),
+        });
+        msg!("✅ Strategy {} registered ({}/{})", strategy, allocations.strategy_count, MAX_STRATEGIES);
+        Ok(())
+    }
+
+    /// Set each registered strategy's weight, in registration order
+    /// (timelock only); the next `rebalance_allocations` applies them
+    pub fn update_allocations(ctx: Context<UpdateAllocations>, weights: Vec<u16>) -> Result<()> {
+        require!(
+            is_timelock(&ctx.accounts.admin.key()),
+            VaultError::UnauthorizedAdmin
+        );
+
+        ctx.accounts.allocations.set_weights(&weights)?;
+
+        msg!("✅ Allocations updated: {:?} bps", weights);
+        emit!(AllocationsUpdated {
+            vault: ctx.accounts.vault.key(),
+            weights,
+        });
+        Ok(())
+    }
+
+    /// Permissionless crank: move base tokens between the base vault and
+    /// the strategy escrows until each escrow holds its weight of their
+    /// combined balance
+    pub fn rebalance_allocations<'info>(
+        ctx: Context<'_, '_, 'info, 'info, RebalanceAllocations<'info>>,
+    ) -> Result<()> {
+        // ✅ CHECKS
+        let registered = ctx.accounts.allocations.registered().to_vec();
+        require!(
+            ctx.remaining_accounts.len() == registered.len(),
+            VaultError::InvalidAllocationAccounts
+        );
+        let mut balances = Vec::with_capacity(registered.len());
+        for (allocation, escrow) in registered.iter().zip(ctx.remaining_accounts) {
+            require!(escrow.key() == allocation.escrow, VaultError::InvalidAllocationAccounts);
+            balances.push(Account::<TokenAccount>::try_from(escrow)?.amount);
+        }
+        let total = balances
+            .iter()
+            .try_fold(ctx.accounts.base_vault.amount, |total, balance| total.checked_add(*balance))
+            .ok_or(VaultError::MathOverflow)?;
+        let targets = allocation_targets(&registered, total);
+        let allocated: u64 = targets.iter().sum();
+
+        // ✅ EFFECTS
+        let allocations = &mut ctx.accounts.allocations;
+        for (allocation, target) in allocations.strategies.iter_mut().zip(&targets) {
+            allocation.allocated = *target;
+        }
+        allocations.last_rebalance = Clock::get()?.unix_timestamp;
+        ctx.accounts.vault.allocated = allocated;
+
+        // ✅ INTERACTIONS: Over-weight escrows pay back first, so the base
+        // vault covers every top-up
+        let base_vault = ctx.accounts.base_vault.to_account_info();
+        let mut transfers = Vec::new();
+        for ((escrow, balance), target) in ctx.remaining_accounts.iter().zip(&balances).zip(&targets) {
+            if balance > target {
+                transfers.push((escrow.clone(), base_vault.clone(), balance - target));
+            }
+        }
+        for ((escrow, balance), target) in ctx.remaining_accounts.iter().zip(&balances).zip(&targets) {
+            if balance < target {
+                transfers.push((base_vault.clone(), escrow.clone(), target - balance));
+            }
+        }
+        let moved: u64 = transfers.iter().map(|(_, _, amount)| amount).sum();
+
+        let vault = &ctx.accounts.vault;
+        let seeds: &[&[u8]] = &[
+            b"vault",
+            vault.manager.as_ref(),
+            vault.base_mint.as_ref(),
+            &[vault.bump],
+        ];
+        for (from, to, amount) in transfers {
+            token::transfer(
+                CpiContext::new_with_signer(
+                    ctx.accounts.token_program.to_account_info(),
+                    Transfer {
+                        from,
+                        to,
+                        authority: ctx.accounts.vault.to_account_info(),
+                    },
+                    &[seeds],
+                ),
+                amount,
+            )?;
+        }
+
+        emit!(AllocationsRebalanced {
+            vault: ctx.accounts.vault.key(),
+            total,
+            moved,
+            allocated,
+        });
+        msg!("✅ Allocations rebalanced: {} moved, {} of {} allocated", moved, allocated, total);
+        Ok(())
+    }
+
+    /// View returning the NAV per share, with the oracle valuing the quote
+    /// balance, for lending markets and UIs pricing shares over CPI
+    pub fn get_share_price<'info>(ctx: Context<'_, '_, '_, 'info, GetSharePrice<'info>>) -> Result<SharePrice> {
+        let nav = vault_nav(
+            &ctx.accounts.vault,
+            &ctx.accounts.base_vault,
+            &ctx.accounts.quote_vault,
+            &ctx.accounts.base_mint,
+            &ctx.accounts.quote_mint,
+            &ctx.accounts.vectai_oracle_program,
+            &ctx.accounts.price_feed,
+            ctx.remaining_accounts,
+        )?;
+        let share_supply = ctx.accounts.share_mint.supply;
+        Ok(SharePrice {
+            nav,
+            share_supply,
+            share_price: share_price(nav, share_supply)?,
+            timestamp: Clock::get()?.unix_timestamp,
+        })
+    }
+
+    /// Stop new deposits and trades (manager only); withdrawals stay open
+    pub fn set_vault_active(ctx: Context<ManageVault>, is_active: bool) -> Result<()> {
+        require!(
+            ctx.accounts.manager.key() == ctx.accounts.vault.manager,
+            VaultError::Unauthorized
+        );
+
+        ctx.accounts.vault.is_active = is_active;
+        msg!("Vault active: {}", is_active);
+        Ok(())
+    }
+}
+
+// ===== CONSTANTS =====
+
+/// Timelock PDA; governed parameters are queued through vectai_timelock by
+/// the admin or governance
+fn is_timelock(key: &Pubkey) -> bool {
+    *key == vectai_timelock::timelock_authority()
+}
+
+// Basis-point denominator (10000 = 100%)
+pub use vectai_common::BPS_DENOMINATOR;
+
+pub const SECONDS_PER_YEAR: i64 = 365 * 24 * 60 * 60;
+
+// Fixed-point scale for share price / high-water mark (1.0 = 1e9)
+pub const SHARE_PRICE_PRECISION: u64 = 1_000_000_000;
+
+// Virtual shares and base units priced into deposits and redemptions, so a
+// donation cannot inflate the share price of an empty vault
+pub use vectai_common::{redemption_amount, shares_for_deposit, VIRTUAL_ASSETS, VIRTUAL_SHARES};
+
+// Fee caps
+pub const MAX_MANAGEMENT_FEE_BPS: u16 = 500; // 5% per year
+
+// ===== HELPERS =====
+
+/// Vault NAV in base-token units: base holdings plus the quote balance
+/// valued at the oracle price
+#[allow(clippy::too_many_arguments)]
+fn vault_nav<'info>(
+    vault: &Account<'info, Vault>,
+    base_vault: &Account<'info, TokenAccount>,
+    quote_vault: &Account<'info, TokenAccount>,
+    base_mint: &Account<'info, Mint>,
+    quote_mint: &Account<'info, Mint>,
+    oracle_program: &Program<'info, VectaiOracle>,
+    price_feed: &UncheckedAccount<'info>,
+    remaining_accounts: &[AccountInfo<'info>],
+) -> Result<u64> {
+    require!(price_feed.key() == vault.price_feed, VaultError::InvalidPriceFeed);
+
+    let quote_value = if quote_vault.amount > 0 {
+        let price_data = oracle_price(vault, oracle_program, price_feed)?;
+        quote_value_in_base(
+            quote_vault.amount,
+            price_data.price,
+            price_data.expo,
+            base_mint.decimals,
+            quote_mint.decimals,
+        )?
+    } else {
+        0
+    };
+
+    base_holdings(vault, base_vault, remaining_accounts)?
+        .checked_add(quote_value)
+        .ok_or(VaultError::MathOverflow.into())
+}
+
+/// NAV with shares at the high-water mark, standing in for the oracle's
+/// when no fresh price is available: management fees accrue on it and no
+/// performance fee is charged, leaving that to the next priced settlement
+fn high_water_nav(vault: &Vault, supply: u64) -> Result<u64> {
+    vectai_common::mul_div(vault.high_water_mark, supply, SHARE_PRICE_PRECISION)
+}
+
+/// Accrue fees from `vault.last_fee_accrual` to now at the rates in force
+/// over that time, and mint the fee shares. Deposits, withdrawals and fee
+/// changes settle first, so nobody pays for time before they held shares
+/// and new rates never reach back. `nav` is only read when the vault
+/// charges fees; otherwise only the accrual clock moves.
+#[allow(clippy::too_many_arguments)]
+fn settle_fees<'info>(
+    vault: &mut Account<'info, Vault>,
+    nav: impl FnOnce(&Account<'info, Vault>) -> Result<u64>,
+    share_mint: &mut Account<'info, Mint>,
+    fee_recipient: &Option<Account<'info, TokenAccount>>,
+    referral_escrow: &Option<Account<'info, TokenAccount>>,
+    fee_config: &FeeConfig,
+    token_program: &Program<'info, Token>,
+    now: i64,
+) -> Result<()> {
+    let elapsed = now - vault.last_fee_accrual;
+    if elapsed <= 0 {
+        return Ok(());
+    }
+    vault.last_fee_accrual = now;
+    if !vault.charges_fees(fee_config.performance_fee_bps) {
+        return Ok(());
+    }
+
+    let accrual = compute_fee_accrual(
+        nav(vault)?,
+        share_mint.supply,
+        elapsed,
+        vault.management_fee_bps,
+        fee_config.performance_fee_bps,
+        vault.high_water_mark,
+    )?;
+    vault.high_water_mark = accrual.high_water_mark;
+
+    // Referrer's cut of the fee shares goes to its share-mint escrow
+    let referral_shares = if vault.referrer != Pubkey::default() {
+        let escrow = referral_escrow.as_ref().ok_or(VaultError::InvalidReferral)?;
+        require!(
+            escrow.key() == vectai_referral::escrow_address(&vault.referrer, &share_mint.key()),
+            VaultError::InvalidReferral
+        );
+        vectai_referral::referral_share(accrual.fee_shares, fee_config.referral_share_bps)
+    } else {
+        0
+    };
+
+    let fee_recipient = fee_recipient
+        .as_ref()
+        .filter(|recipient| recipient.key() == vault.fee_recipient)
+        .ok_or(VaultError::InvalidTokenAccount)?;
+    let seeds: &[&[u8]] = &[
+        b"vault",
+        vault.manager.as_ref(),
+        vault.base_mint.as_ref(),
+        &[vault.bump],
+    ];
+    let mut mints = vec![(fee_recipient.to_account_info(), accrual.fee_shares - referral_shares)];
+    if let Some(escrow) = referral_escrow {
+        mints.push((escrow.to_account_info(), referral_shares));
+    }
+    for (to, amount) in mints {
+        if amount == 0 {
+            continue;
+        }
+        token::mint_to(
+            CpiContext::new_with_signer(
+                token_program.to_account_info(),
+                MintTo {
+                    mint: share_mint.to_account_info(),
+                    to,
+                    authority: vault.to_account_info(),
+                },
+                &[seeds],
+            ),
+            amount,
+        )?;
+    }
+    share_mint.reload()?;
+
+    emit!(FeesAccrued {
+        vault: vault.key(),
+        management_fee: accrual.management_fee,
+        performance_fee: accrual.performance_fee,
+        fee_shares: accrual.fee_shares,
+        referral_shares,
+        high_water_mark: accrual.high_water_mark,
+    });
+    msg!("✅ Fees accrued: {} management + {} performance ({} shares)",
+         accrual.management_fee, accrual.performance_fee, accrual.fee_shares);
+    Ok(())
+}
+
+/// Oracle price of the vault's pair from its own feed
+fn oracle_price<'info>(
+    vault: &Vault,
+    oracle_program: &Program<'info, VectaiOracle>,
+    price_feed: &UncheckedAccount<'info>,
+) -> Result<PriceData> {
+    require!(price_feed.key() == vault.price_feed, VaultError::InvalidPriceFeed);
+    Ok(vectai_oracle::cpi::get_price(CpiContext::new(
+        oracle_program.to_account_info(),
+        GetPrice {
+            price_feed: price_feed.to_account_info(),
+        },
+    ))?
+    .get())
+}
+
+/// Base tokens owned by the vault: the base vault balance plus funds
+/// supplied to lending with their interest, valued from the lending
+/// accounts in `remaining_accounts`, and the strategy escrows' balances
+fn base_holdings(vault: &Vault, base_vault: &TokenAccount, remaining_accounts: &[AccountInfo]) -> Result<u64> {
+    base_vault
+        .amount
+        .checked_add(lent_value(vault, remaining_accounts)?)
+        .and_then(|holdings| holdings.checked_add(vault.allocated))
+        .ok_or(VaultError::MathOverflow.into())
+}
+
+/// Convert `quote_amount` to base-token units using `price * 10^expo`
+/// (base per whole quote token), adjusting for both mints' decimals
+pub fn quote_value_in_base(
+    quote_amount: u64,
+    price: i64,
+    expo: i32,
+    base_decimals: u8,
+    quote_decimals: u8,
+) -> Result<u64> {
+    require!(price > 0, VaultError::InvalidPrice);
+
+    let scale = expo + base_decimals as i32 - quote_decimals as i32;
+    let raw = (quote_amount as u128)
+        .checked_mul(price as u128)
+        .ok_or(VaultError::MathOverflow)?;
+    let value = if scale >= 0 {
+        raw.checked_mul(10u128.checked_pow(scale as u32).ok_or(VaultError::MathOverflow)?)
+            .ok_or(VaultError::MathOverflow)?
+    } else {
+        raw / 10u128.checked_pow((-scale) as u32).ok_or(VaultError::MathOverflow)?
+    };
+    u64::try_from(value).map_err(|_| VaultError::MathOverflow.into())
+}
+
+/// Outcome of a fee accrual, values in base-token units
+pub struct FeeAccrual {
+    pub management_fee: u64,
+    pub performance_fee: u64,
+    pub fee_shares: u64,
+    pub high_water_mark: u64,
+}
+
+/// Management fee pro-rated over `elapsed` seconds plus performance fee on
+/// share-price gains above `high_water_mark`, converted to shares that
+/// dilute holders by exactly the fee value
+pub fn compute_fee_accrual(
+    nav: u64,
+    supply: u64,
+    elapsed: i64,
+    management_fee_bps: u16,
+    performance_fee_bps: u16,
+    high_water_mark: u64,
+) -> Result<FeeAccrual> {
+    if supply == 0 || nav == 0 {
+        return Ok(FeeAccrual {
+            management_fee: 0,
+            performance_fee: 0,
+            fee_shares: 0,
+            high_water_mark,
+        });
+    }
+
+    let management_fee = (nav as u128)
+        .checked_mul(management_fee_bps as u128)
+        .and_then(|x| x.checked_mul(elapsed as u128))
+        .ok_or(VaultError::MathOverflow)?
+        / (BPS_DENOMINATOR as u128 * SECONDS_PER_YEAR as u128);
+
+    let share_price = (nav as u128) * SHARE_PRICE_PRECISION as u128 / supply as u128;
+    let performance_fee = if share_price > high_water_mark as u128 {
+        let gain = (share_price - high_water_mark as u128) * supply as u128
+            / SHARE_PRICE_PRECISION as u128;
+        gain * performance_fee_bps as u128 / BPS_DENOMINATOR as u128
+    } else {
+        0
+    };
+
+    let total_fee = (management_fee + performance_fee).min(nav as u128 - 1);
+    let fee_shares = total_fee * supply as u128 / (nav as u128 - total_fee);
+
+    let new_supply = supply as u128 + fee_shares;
+    let post_fee_price = (nav as u128) * SHARE_PRICE_PRECISION as u128 / new_supply;
+    let high_water_mark = (high_water_mark as u128).max(post_fee_price);
+
+    Ok(FeeAccrual {
+        management_fee: u64::try_from(management_fee).map_err(|_| VaultError::MathOverflow)?,
+        performance_fee: u64::try_from(performance_fee).map_err(|_| VaultError::MathOverflow)?,
+        fee_shares: u64::try_from(fee_shares).map_err(|_| VaultError::MathOverflow)?,
+        high_water_mark: u64::try_from(high_water_mark).map_err(|_| VaultError::MathOverflow)?,
+    })
+}
+
+/// NAV per share scaled by `SHARE_PRICE_PRECISION`; 1.0 before the first
+/// deposit, which mints shares 1:1
+pub fn share_price(nav: u64, supply: u64) -> Result<u64> {
+    if supply == 0 {
+        return Ok(SHARE_PRICE_PRECISION);
+    }
+    vectai_common::mul_div(nav, SHARE_PRICE_PRECISION, supply)
+}
+
+/// `balance * shares / supply`, rounded down
+pub fn pro_rata(balance: u64, shares: u64, supply: u64) -> Result<u64> {
+    vectai_common::mul_div(balance, shares, supply)
+}
+
+// ===== ACCOUNTS =====
+
+#[derive(Accounts)]
+pub struct InitializeVault<'info> {
+    #[account(
+        init,
+        payer = manager,
+        space = Vault::LEN,
+        seeds = [b"vault", manager.key().as_ref(), base_mint.key().as_ref()],
+        bump
+    )]
+    pub vault: Account<'info, Vault>,
+
+    pub base_mint: Account<'info, Mint>,
+    pub quote_mint: Account<'info, Mint>,
+
+    #[account(
+        init,
+        payer = manager,
+        token::mint = base_mint,
+        token::authority = vault,
+        seeds = [b"base-vault", vault.key().as_ref()],
+        bump
+    )]
+    pub base_vault: Account<'info, TokenAccount>,
+
+    #[account(
+        init,
+        payer = manager,
+        token::mint = quote_mint,
+        token::authority = vault,
+        seeds = [b"quote-vault", vault.key().as_ref()],
+        bump
+    )]
+    pub quote_vault: Account<'info, TokenAccount>,
+
+    #[account(
+        init,
+        payer = manager,
+        mint::decimals = base_mint.decimals,
+        mint::authority = vault,
+        seeds = [b"shares", vault.key().as_ref()],
+        bump
+    )]
+    pub share_mint: Account<'info, Mint>,
+
+    /// CHECK: Oracle price feed for quote priced in base; validated by vectai_oracle
+    pub price_feed: UncheckedAccount<'info>,
+
+    /// Optional referral code credited with this vault's fees
+    pub referral_code: Option<Account<'info, ReferralCode>>,
+
+    #[account(mut)]
+    pub manager: Signer<'info>,
+
+    pub rent: Sysvar<'info, Rent>,
+    pub token_program: Program<'info, Token>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct Deposit<'info> {
+    #[account(
+        mut,
+        seeds = [b"vault", vault.manager.as_ref(), vault.base_mint.as_ref()],
+        bump = vault.bump
+    )]
+    pub vault: Account<'info, Vault>,
+
+    #[account(address = vault.base_mint)]
+    pub base_mint: Account<'info, Mint>,
+
+    #[account(address = vault.quote_mint)]
+    pub quote_mint: Account<'info, Mint>,
+
+    #[account(mut, address = vault.base_vault)]
+    pub base_vault: Account<'info, TokenAccount>,
+
+    #[account(address = vault.quote_vault)]
+    pub quote_vault: Account<'info, TokenAccount>,
+
+    #[account(mut, address = vault.share_mint)]
+    pub share_mint: Account<'info, Mint>,
+
+    /// Fee share account, checked against vault.fee_recipient; required
+    /// when the vault charges fees
+    #[account(mut)]
+    pub fee_recipient: Option<Account<'info, TokenAccount>>,
+
+    /// Referrer's share-mint escrow; required when the vault charges fees
+    /// and has a referrer
+    #[account(mut)]
+    pub referral_escrow: Option<Account<'info, TokenAccount>>,
+
+    #[account(
+        mut,
+        constraint = user_base_account.mint == vault.base_mint @ VaultError::InvalidTokenAccount,
+        constraint = user_base_account.owner == user.key() @ VaultError::InvalidTokenAccount
+    )]
+    pub user_base_account: Account<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        constraint = user_share_account.mint == vault.share_mint @ VaultError::InvalidTokenAccount
+    )]
+    pub user_share_account: Account<'info, TokenAccount>,
+
+    /// Shares `user` holds through deposits, for the per-depositor cap
+    #[account(
+        init_if_needed,
+        payer = user,
+        space = DepositorPosition::LEN,
+        seeds = [b"depositor", vault.key().as_ref(), user.key().as_ref()],
+        bump
+    )]
+    pub depositor_position: Account<'info, DepositorPosition>,
+
+    #[account(mut)]
+    pub user: Signer<'info>,
+
+    /// The Oracle Program (VECT.AI Oracle)
+    pub vectai_oracle_program: Program<'info, VectaiOracle>,
+
+    /// CHECK: Checked against vault.price_feed
+    pub price_feed: UncheckedAccount<'info>,
+
+    pub token_program: Program<'info, Token>,
+    pub system_program: Program<'info, System>,
+
+    /// Global emergency-pause registry
+    #[account(
+        seeds = [b"protocol-status"],
+        bump = protocol_status.bump,
+        seeds::program = vectai_protocol::ID,
+        constraint = !protocol_status.paused @ VaultError::ProtocolPaused
+    )]
+    pub protocol_status: Account<'info, ProtocolStatus>,
+}
+
+#[derive(Accounts)]
+pub struct Withdraw<'info> {
+    #[account(
+        mut,
+        seeds = [b"vault", vault.manager.as_ref(), vault.base_mint.as_ref()],
+        bump = vault.bump
+    )]
+    pub vault: Account<'info, Vault>,
+
+    #[account(address = vault.base_mint)]
+    pub base_mint: Account<'info, Mint>,
+
+    #[account(address = vault.quote_mint)]
+    pub quote_mint: Account<'info, Mint>,
+
+    #[account(mut, address = vault.base_vault)]
+    pub base_vault: Account<'info, TokenAccount>,
+
+    #[account(mut, address = vault.quote_vault)]
+    pub quote_vault: Account<'info, TokenAccount>,
+
+    #[account(mut, address = vault.share_mint)]
+    pub share_mint: Account<'info, Mint>,
+
+    /// Fee share account, checked against vault.fee_recipient; required
+    /// when the vault charges fees
+    #[account(mut)]
+    pub fee_recipient: Option<Account<'info, TokenAccount>>,
+
+    /// Referrer's share-mint escrow; required when the vault charges fees
+    /// and has a referrer
+    #[account(mut)]
+    pub referral_escrow: Option<Account<'info, TokenAccount>>,
+
+    #[account(
+        mut,
+        constraint = user_share_account.owner == user.key() @ VaultError::InvalidTokenAccount
+    )]
+    pub user_share_account: Account<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        constraint = user_base_account.mint == vault.base_mint @ VaultError::InvalidTokenAccount
+    )]
+    pub user_base_account: Account<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        constraint = user_quote_account.mint == vault.quote_mint @ VaultError::InvalidTokenAccount
+    )]
+    pub user_quote_account: Account<'info, TokenAccount>,
+
+    /// `user`'s position, released by the burned shares to make room under
+    /// the per-depositor cap
+    #[account(
+        mut,
+        seeds = [b"depositor", vault.key().as_ref(), user.key().as_ref()],
+        bump = depositor_position.bump
+    )]
+    pub depositor_position: Option<Account<'info, DepositorPosition>>,
+
+    pub user: Signer<'info>,
+
+    /// The Oracle Program (VECT.AI Oracle)
+    pub vectai_oracle_program: Program<'info, VectaiOracle>,
+
+    /// CHECK: Checked against vault.price_feed; omitted while the oracle is
+    /// down
+    pub price_feed: Option<UncheckedAccount<'info>>,
+
+    pub token_program: Program<'info, Token>,
+
+    /// Global emergency-pause registry
+    #[account(
+        seeds = [b"protocol-status"],
+        bump = protocol_status.bump,
+        seeds::program = vectai_protocol::ID,
+        constraint = !protocol_status.paused @ VaultError::ProtocolPaused
+    )]
+    pub protocol_status: Account<'info, ProtocolStatus>,
+}
+
+#[derive(Accounts)]
+pub struct VaultTrade<'info> {
+    #[account(
+        mut,
+        seeds = [b"vault", vault.manager.as_ref(), vault.base_mint.as_ref()],
+        bump = vault.bump
+    )]
+    pub vault: Account<'info, Vault>,
+
+    #[account(mut, address = vault.base_vault)]
+    pub base_vault: Account<'info, TokenAccount>,
+
+    #[account(mut, address = vault.quote_vault)]
+    pub quote_vault: Account<'info, TokenAccount>,
+
+    #[account(address = vault.base_mint)]
+    pub base_mint: Account<'info, Mint>,
+
+    #[account(address = vault.quote_mint)]
+    pub quote_mint: Account<'info, Mint>,
+
+    #[account(address = vault.share_mint)]
+    pub share_mint: Account<'info, Mint>,
+
+    pub manager: Signer<'info>,
+
+    /// The Oracle Program (VECT.AI Oracle)
+    pub vectai_oracle_program: Program<'info, VectaiOracle>,
+
+    /// CHECK: Checked against vault.price_feed
+    pub price_feed: UncheckedAccount<'info>,
+
+    pub swapper_program: Program<'info, RaydiumSwapper>,
+
+    // ===== RAYDIUM / SERUM ACCOUNTS (validated by raydium_swapper) =====
+
+    /// CHECK: Raydium AMM program
+    pub raydium_amm_program: UncheckedAccount<'info>,
+
+    /// CHECK: AMM pool account
+    #[account(mut)]
+    pub amm: UncheckedAccount<'info>,
+
+    /// CHECK: AMM authority
+    pub amm_authority: UncheckedAccount<'info>,
+
+    /// CHECK: AMM open orders
+    #[account(mut)]
+    pub amm_open_orders: UncheckedAccount<'info>,
+
+    /// CHECK: AMM target orders
+    #[account(mut)]
+    pub amm_target_orders: UncheckedAccount<'info>,
+
+    /// CHECK: Pool coin token account
+    #[account(mut)]
+    pub pool_coin_token_account: UncheckedAccount<'info>,
+
+    /// CHECK: Pool pc token account
+    #[account(mut)]
+    pub pool_pc_token_account: UncheckedAccount<'info>,
+
+    /// CHECK: Serum program
+    pub serum_program: UncheckedAccount<'info>,
+
+    /// CHECK: Serum market
+    #[account(mut)]
+    pub serum_market: UncheckedAccount<'info>,
+
+    /// CHECK: Serum bids
+    #[account(mut)]
+    pub serum_bids: UncheckedAccount<'info>,
+
+    /// CHECK: Serum asks
+    #[account(mut)]
+    pub serum_asks: UncheckedAccount<'info>,
+
+    /// CHECK: Serum event queue
+    #[account(mut)]
+    pub serum_event_queue: UncheckedAccount<'info>,
+
+    /// CHECK: Serum coin vault
+    #[account(mut)]
+    pub serum_coin_vault: UncheckedAccount<'info>,
+
+    /// CHECK: Serum pc vault
+    #[account(mut)]
+    pub serum_pc_vault: UncheckedAccount<'info>,
+
+    /// CHECK: Serum vault signer
+    pub serum_vault_signer: UncheckedAccount<'info>,
+
+    pub token_program: Program<'info, Token>,
+
+    /// Global emergency-pause registry
+    #[account(
+        seeds = [b"protocol-status"],
+        bump = protocol_status.bump,
+        seeds::program = vectai_protocol::ID,
+        constraint = !protocol_status.paused @ VaultError::ProtocolPaused
+    )]
+    pub protocol_status: Account<'info, ProtocolStatus>,
+
+    /// Shared network addresses, forwarded to the swapper
+    /// CHECK: validated by raydium_swapper
+    pub network_config: UncheckedAccount<'info>,
+
+    /// Protocol activity counters, forwarded to the swapper
+    /// CHECK: validated by vectai_protocol
+    #[account(mut)]
+    pub protocol_stats: UncheckedAccount<'info>,
+
+    /// The swapper's stats reporter PDA, forwarded to the swapper
+    /// CHECK: validated by raydium_swapper
+    pub swapper_stats_reporter: UncheckedAccount<'info>,
+
+    /// CHECK: validated by raydium_swapper
+    pub protocol_program: UncheckedAccount<'info>,
+
+    /// Treasury vault for the swap's source mint, forwarded to the swapper
+    /// CHECK: validated by raydium_swapper
+    #[account(mut)]
+    pub swap_fee_account: Option<UncheckedAccount<'info>>,
+}
+
+#[derive(Accounts)]
+pub struct SetVaultFees<'info> {
+    #[account(
+        mut,
+        seeds = [b"vault", vault.manager.as_ref(), vault.base_mint.as_ref()],
+        bump = vault.bump
+    )]
+    pub vault: Account<'info, Vault>,
+
+    #[account(address = vault.base_mint)]
+    pub base_mint: Account<'info, Mint>,
+
+    #[account(address = vault.quote_mint)]
+    pub quote_mint: Account<'info, Mint>,
+
+    #[account(address = vault.base_vault)]
+    pub base_vault: Account<'info, TokenAccount>,
+
+    #[account(address = vault.quote_vault)]
+    pub quote_vault: Account<'info, TokenAccount>,
+
+    #[account(mut, address = vault.share_mint)]
+    pub share_mint: Account<'info, Mint>,
+
+    /// Treasury vault for this share mint; receives fee shares
+    #[account(
+        mut,
+        constraint = fee_recipient.mint == vault.share_mint @ VaultError::InvalidTokenAccount,
+        constraint = fee_recipient.key() == vectai_treasury::vault_address(&vault.share_mint) @ VaultError::InvalidTokenAccount
+    )]
+    pub fee_recipient: Account<'info, TokenAccount>,
+
+    /// Referrer's share-mint escrow; required when the vault charges fees
+    /// and has a referrer
+    #[account(mut)]
+    pub referral_escrow: Option<Account<'info, TokenAccount>>,
+
+    pub admin: Signer<'info>,
+
+    /// The Oracle Program (VECT.AI Oracle)
+    pub vectai_oracle_program: Program<'info, VectaiOracle>,
+
+    /// CHECK: Checked against vault.price_feed
+    pub price_feed: UncheckedAccount<'info>,
+
+    pub token_program: Program<'info, Token>,
+
+    /// Global emergency-pause registry, for the protocol fee config
+    #[account(
+        seeds = [b"protocol-status"],
+        bump = protocol_status.bump,
+        seeds::program = vectai_protocol::ID
+    )]
+    pub protocol_status: Account<'info, ProtocolStatus>,
+}
+
+#[derive(Accounts)]
+pub struct AccrueFees<'info> {
+    #[account(
+        mut,
+        seeds = [b"vault", vault.manager.as_ref(), vault.base_mint.as_ref()],
+        bump = vault.bump
+    )]
+    pub vault: Account<'info, Vault>,
+
+    #[account(address = vault.base_mint)]
+    pub base_mint: Account<'info, Mint>,
+
+    #[account(address = vault.quote_mint)]
+    pub quote_mint: Account<'info, Mint>,
+
+    #[account(address = vault.base_vault)]
+    pub base_vault: Account<'info, TokenAccount>,
+
+    #[account(address = vault.quote_vault)]
+    pub quote_vault: Account<'info, TokenAccount>,
+
+    #[account(mut, address = vault.share_mint)]
+    pub share_mint: Account<'info, Mint>,
+
+    /// Required when the vault charges fees; a vault with fees off has none
+    #[account(mut, address = vault.fee_recipient)]
+    pub fee_recipient: Option<Account<'info, TokenAccount>>,
+
+    /// Referrer's share-mint escrow; required when the vault charges fees
+    /// and has a referrer
+    #[account(mut)]
+    pub referral_escrow: Option<Account<'info, TokenAccount>>,
+
+    /// The Oracle Program (VECT.AI Oracle)
+    pub vectai_oracle_program: Program<'info, VectaiOracle>,
+
+    /// CHECK: Checked against vault.price_feed
+    pub price_feed: UncheckedAccount<'info>,
+
+    pub token_program: Program<'info, Token>,
+
+    /// Global emergency-pause registry
+    #[account(
+        seeds = [b"protocol-status"],
+        bump = protocol_status.bump,
+        seeds::program = vectai_protocol::ID,
+        constraint = !protocol_status.paused @ VaultError::ProtocolPaused
+    )]
+    pub protocol_status: Account<'info, ProtocolStatus>,
+}
+
+#[derive(Accounts)]
+pub struct GetSharePrice<'info> {
+    #[account(
+        seeds = [b"vault", vault.manager.as_ref(), vault.base_mint.as_ref()],
+        bump = vault.bump
+    )]
+    pub vault: Account<'info, Vault>,
+
+    #[account(address = vault.base_mint)]
+    pub base_mint: Account<'info, Mint>,
+
+    #[account(address = vault.quote_mint)]
+    pub quote_mint: Account<'info, Mint>,
+
+    #[account(address = vault.base_vault)]
+    pub base_vault: Account<'info, TokenAccount>,
+
+    #[account(address = vault.quote_vault)]
+    pub quote_vault: Account<'info, TokenAccount>,
+
+    #[account(address = vault.share_mint)]
+    pub share_mint: Account<'info, Mint>,
+
+    /// The Oracle Program (VECT.AI Oracle)
+    pub vectai_oracle_program: Program<'info, VectaiOracle>,
+
+    /// CHECK: Checked against vault.price_feed
+    pub price_feed: UncheckedAccount<'info>,
+}
+
+#[derive(Accounts)]
+pub struct ManageVault<'info> {
+    #[account(
+        mut,
+        seeds = [b"vault", vault.manager.as_ref(), vault.base_mint.as_ref()],
+        bump = vault.bump
+    )]
+    pub vault: Account<'info, Vault>,
+
+    pub manager: Signer<'info>,
+}
+
+// ===== STATE =====
+
+/// Pooled vault; the PDA owns both token vaults and the share mint
+#[account]
+pub struct Vault {
+    pub manager: Pubkey,
+    pub base_mint: Pubkey,
+    pub quote_mint: Pubkey,
+    pub base_vault: Pubkey,
+    pub quote_vault: Pubkey,
+    pub share_mint: Pubkey,
+    pub price_feed: Pubkey,
+    pub is_active: bool,
+    pub bump: u8,
+    pub fee_recipient: Pubkey, // Share token account; default = fees off
+    pub management_fee_bps: u16, // Per year
+    pub last_fee_accrual: i64,
+    pub high_water_mark: u64, // Share price scaled by SHARE_PRICE_PRECISION
+    pub lending_reserve: Pubkey, // Default = lending disabled
+    pub collateral_vault: Pubkey,
+    pub lent_principal: u64, // Base tokens currently supplied to lending
+    pub risk: DrawdownTracker,
+    pub referrer: Pubkey, // ReferralCode; default = none
+    pub caps: DepositCaps,
+    pub realized_pnl: i64, // Trades' output less input marked at the oracle, in base-token units
+    pub allocated: u64, // Base tokens held in strategy escrows as of the last rebalance
+}
+
+impl Vault {
+    pub const LEN: usize = 8 + // discriminator
+        32 + // manager
+        32 + // base_mint
+        32 + // quote_mint
+        32 + // base_vault
+        32 + // quote_vault
+        32 + // share_mint
+        32 + // price_feed
+        1 +  // is_active
+        1 +  // bump
+        32 + // fee_recipient
+        2 +  // management_fee_bps
+        8 +  // last_fee_accrual
+        8 +  // high_water_mark
+        32 + // lending_reserve
+        32 + // collateral_vault
+        8 +  // lent_principal
+        DrawdownTracker::LEN + // risk
+        32 + // referrer
+        DepositCaps::LEN + // caps
+        8 +  // realized_pnl
+        8; // allocated
+
+    /// Whether accrual mints fee shares: a recipient is set and either the
+    /// management fee or the protocol performance fee is above zero
+    pub fn charges_fees(&self, performance_fee_bps: u16) -> bool {
+        self.fee_recipient != Pubkey::default() && (self.management_fee_bps > 0 || performance_fee_bps > 0)
+    }
+}
+
+/// Vault valuation as returned by `get_share_price`, in base-token units
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug, PartialEq, Eq)]
+pub struct SharePrice {
+    pub nav: u64, // Includes funds lent out and their interest
+    pub share_supply: u64,
+    pub share_price: u64, // Scaled by SHARE_PRICE_PRECISION
+    pub timestamp: i64,
+}
+
+// ===== EVENTS =====
+
+#[event]
+pub struct Deposited {
+    pub vault: Pubkey,
+    pub user: Pubkey,
+    pub amount: u64,
+    pub shares: u64,
+    pub nav: u64,
+}
+
+#[event]
+pub struct Withdrawn {
+    pub vault: Pubkey,
+    pub user: Pubkey,
+    pub shares: u64,
+    pub base_amount: u64,
+    pub quote_amount: u64,
+}
+
+#[event]
+pub struct VaultFeesUpdated {
+    pub vault: Pubkey,
+    pub management_fee_bps: u16,
+    pub fee_recipient: Pubkey,
+}
+
+/// A trade marked at the oracle, in base-token units (USD for USDC-based
+/// vaults)
+#[event]
+pub struct PnlRealized {
+    pub vault: Pubkey,
+    pub value_in: u64,
+    pub value_out: u64,
+    pub pnl: i64,
+    pub realized_pnl: i64, // Vault total after this trade
+}
+
+#[event]
+pub struct FeesAccrued {
+    pub vault: Pubkey,
+    pub management_fee: u64,
+    pub performance_fee: u64,
+    pub fee_shares: u64,
+    pub referral_shares: u64,
+    pub high_water_mark: u64,
+}
+
+#[error_code]
+pub enum VaultError {
+    #[msg("Invalid input parameters")]
+    InvalidInput,
+    #[msg("Invalid amount - must be greater than 0")]
+    InvalidAmount,
+    #[msg("Insufficient balance")]
+    InsufficientBalance,
+    #[msg("Math overflow in calculation")]
+    MathOverflow,
+    #[msg("Unauthorized access")]
+    Unauthorized,
+    #[msg("Vault is inactive")]
+    VaultInactive,
+    #[msg("Invalid token account")]
+    InvalidTokenAccount,
+    #[msg("Price feed does not match vault")]
+    InvalidPriceFeed,
+    #[msg("Invalid oracle price")]
+    InvalidPrice,
+    #[msg("Unauthorized admin")]
+    UnauthorizedAdmin,
+    #[msg("Fee exceeds maximum allowed")]
+    FeeTooHigh,
+    #[msg("No time elapsed since last fee accrual")]
+    NothingToAccrue,
+    #[msg("Withdrawal epoch has not ended")]
+    EpochNotEnded,
+    #[msg("Invalid lending accounts")]
+    InvalidLendingAccounts,
+    #[msg("Not enough base liquidity; recall lent or allocated funds first")]
+    InsufficientLiquidity,
+    #[msg("Max drawdown exceeded - reset required")]
+    DrawdownLimitExceeded,
+    #[msg("Invalid referral code or escrow")]
+    InvalidReferral,
+    #[msg("Protocol is paused")]
+    ProtocolPaused,
+    #[msg("Deposit would take the vault over its value cap")]
+    VaultCapExceeded,
+    #[msg("Deposit would take the depositor over their position cap")]
+    DepositorCapExceeded,
+    #[msg("Vault already allocates to the maximum number of strategies")]
+    TooManyStrategies,
+    #[msg("Allocations need one weight per strategy, totalling at most 100%")]
+    InvalidAllocations,
+    #[msg("Strategy escrows missing or out of registration order")]
+    InvalidAllocationAccounts,
+}
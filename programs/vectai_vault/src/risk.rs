@@ -0,0 +1,7 @@
+use anchor_lang::prelude::*;
+
+// The vault marks its share price, which deposits and withdrawals leave
+// unchanged, so its peak is never rebased
+pub use vectai_common::{DrawdownTracker, MAX_DRAWDOWN_LIMIT_BPS};
+
+vectai_common::drawdown_events!();
@@ -0,0 +1,358 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token::{Mint, Token, TokenAccount};
+
+use vectai_protocol::ProtocolStatus;
+
+use crate::{DepositorPosition, Vault, VaultError, VectaiOracle};
+
+#[derive(Accounts)]
+pub struct InitializeWithdrawalQueue<'info> {
+    #[account(
+        seeds = [b"vault", vault.manager.as_ref(), vault.base_mint.as_ref()],
+        bump = vault.bump
+    )]
+    pub vault: Account<'info, Vault>,
+
+    #[account(
+        init,
+        payer = manager,
+        space = WithdrawalQueue::LEN,
+        seeds = [b"withdrawal-queue", vault.key().as_ref()],
+        bump
+    )]
+    pub queue: Account<'info, WithdrawalQueue>,
+
+    #[account(address = vault.base_mint)]
+    pub base_mint: Account<'info, Mint>,
+
+    #[account(address = vault.quote_mint)]
+    pub quote_mint: Account<'info, Mint>,
+
+    #[account(address = vault.share_mint)]
+    pub share_mint: Account<'info, Mint>,
+
+    /// Holds queued shares until their epoch is processed
+    #[account(
+        init,
+        payer = manager,
+        token::mint = share_mint,
+        token::authority = vault,
+        seeds = [b"share-escrow", vault.key().as_ref()],
+        bump
+    )]
+    pub share_escrow: Account<'info, TokenAccount>,
+
+    /// Base tokens set aside for processed, unclaimed redemptions
+    #[account(
+        init,
+        payer = manager,
+        token::mint = base_mint,
+        token::authority = vault,
+        seeds = [b"base-claims", vault.key().as_ref()],
+        bump
+    )]
+    pub base_claims: Account<'info, TokenAccount>,
+
+    /// Quote tokens set aside for processed, unclaimed redemptions
+    #[account(
+        init,
+        payer = manager,
+        token::mint = quote_mint,
+        token::authority = vault,
+        seeds = [b"quote-claims", vault.key().as_ref()],
+        bump
+    )]
+    pub quote_claims: Account<'info, TokenAccount>,
+
+    #[account(mut)]
+    pub manager: Signer<'info>,
+
+    pub rent: Sysvar<'info, Rent>,
+    pub token_program: Program<'info, Token>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct RequestWithdrawal<'info> {
+    #[account(
+        seeds = [b"vault", vault.manager.as_ref(), vault.base_mint.as_ref()],
+        bump = vault.bump
+    )]
+    pub vault: Account<'info, Vault>,
+
+    #[account(
+        mut,
+        seeds = [b"withdrawal-queue", vault.key().as_ref()],
+        bump = queue.bump
+    )]
+    pub queue: Account<'info, WithdrawalQueue>,
+
+    #[account(
+        init,
+        payer = user,
+        space = WithdrawalRequest::LEN,
+        seeds = [b"withdrawal", vault.key().as_ref(), user.key().as_ref()],
+        bump
+    )]
+    pub request: Account<'info, WithdrawalRequest>,
+
+    #[account(mut, address = queue.share_escrow)]
+    pub share_escrow: Account<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        constraint = user_share_account.mint == vault.share_mint @ VaultError::InvalidTokenAccount,
+        constraint = user_share_account.owner == user.key() @ VaultError::InvalidTokenAccount
+    )]
+    pub user_share_account: Account<'info, TokenAccount>,
+
+    /// `user`'s position, released by the queued shares
+    #[account(
+        mut,
+        seeds = [b"depositor", vault.key().as_ref(), user.key().as_ref()],
+        bump = depositor_position.bump
+    )]
+    pub depositor_position: Option<Account<'info, DepositorPosition>>,
+
+    #[account(mut)]
+    pub user: Signer<'info>,
+
+    pub token_program: Program<'info, Token>,
+    pub system_program: Program<'info, System>,
+
+    /// Global emergency-pause registry
+    #[account(
+        seeds = [b"protocol-status"],
+        bump = protocol_status.bump,
+        seeds::program = vectai_protocol::ID,
+        constraint = !protocol_status.paused @ VaultError::ProtocolPaused
+    )]
+    pub protocol_status: Account<'info, ProtocolStatus>,
+}
+
+#[derive(Accounts)]
+pub struct ProcessWithdrawals<'info> {
+    #[account(
+        mut,
+        seeds = [b"vault", vault.manager.as_ref(), vault.base_mint.as_ref()],
+        bump = vault.bump
+    )]
+    pub vault: Account<'info, Vault>,
+
+    #[account(
+        mut,
+        seeds = [b"withdrawal-queue", vault.key().as_ref()],
+        bump = queue.bump
+    )]
+    pub queue: Account<'info, WithdrawalQueue>,
+
+    #[account(
+        init,
+        payer = cranker,
+        space = RedemptionEpoch::LEN,
+        seeds = [b"redemption", queue.key().as_ref(), &queue.current_epoch.to_le_bytes()],
+        bump
+    )]
+    pub redemption: Account<'info, RedemptionEpoch>,
+
+    #[account(address = vault.base_mint)]
+    pub base_mint: Account<'info, Mint>,
+
+    #[account(address = vault.quote_mint)]
+    pub quote_mint: Account<'info, Mint>,
+
+    #[account(mut, address = vault.base_vault)]
+    pub base_vault: Account<'info, TokenAccount>,
+
+    #[account(mut, address = vault.quote_vault)]
+    pub quote_vault: Account<'info, TokenAccount>,
+
+    #[account(mut, address = vault.share_mint)]
+    pub share_mint: Account<'info, Mint>,
+
+    /// Fee share account, checked against vault.fee_recipient; required
+    /// when the vault charges fees
+    #[account(mut)]
+    pub fee_recipient: Option<Account<'info, TokenAccount>>,
+
+    /// Referrer's share-mint escrow; required when the vault charges fees
+    /// and has a referrer
+    #[account(mut)]
+    pub referral_escrow: Option<Account<'info, TokenAccount>>,
+
+    #[account(mut, address = queue.share_escrow)]
+    pub share_escrow: Account<'info, TokenAccount>,
+
+    #[account(mut, address = queue.base_claims)]
+    pub base_claims: Account<'info, TokenAccount>,
+
+    #[account(mut, address = queue.quote_claims)]
+    pub quote_claims: Account<'info, TokenAccount>,
+
+    #[account(mut)]
+    pub cranker: Signer<'info>,
+
+    /// The Oracle Program (VECT.AI Oracle)
+    pub vectai_oracle_program: Program<'info, VectaiOracle>,
+
+    /// CHECK: Checked against vault.price_feed; omitted while the oracle is
+    /// down
+    pub price_feed: Option<UncheckedAccount<'info>>,
+
+    pub token_program: Program<'info, Token>,
+    pub system_program: Program<'info, System>,
+
+    /// Global emergency-pause registry
+    #[account(
+        seeds = [b"protocol-status"],
+        bump = protocol_status.bump,
+        seeds::program = vectai_protocol::ID,
+        constraint = !protocol_status.paused @ VaultError::ProtocolPaused
+    )]
+    pub protocol_status: Account<'info, ProtocolStatus>,
+}
+
+#[derive(Accounts)]
+pub struct ClaimWithdrawal<'info> {
+    #[account(
+        seeds = [b"vault", vault.manager.as_ref(), vault.base_mint.as_ref()],
+        bump = vault.bump
+    )]
+    pub vault: Account<'info, Vault>,
+
+    #[account(
+        seeds = [b"withdrawal-queue", vault.key().as_ref()],
+        bump = queue.bump
+    )]
+    pub queue: Account<'info, WithdrawalQueue>,
+
+    #[account(
+        mut,
+        close = user,
+        seeds = [b"withdrawal", vault.key().as_ref(), user.key().as_ref()],
+        bump = request.bump
+    )]
+    pub request: Account<'info, WithdrawalRequest>,
+
+    #[account(
+        seeds = [b"redemption", queue.key().as_ref(), &request.epoch.to_le_bytes()],
+        bump = redemption.bump
+    )]
+    pub redemption: Account<'info, RedemptionEpoch>,
+
+    #[account(mut, address = queue.base_claims)]
+    pub base_claims: Account<'info, TokenAccount>,
+
+    #[account(mut, address = queue.quote_claims)]
+    pub quote_claims: Account<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        constraint = user_base_account.mint == vault.base_mint @ VaultError::InvalidTokenAccount
+    )]
+    pub user_base_account: Account<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        constraint = user_quote_account.mint == vault.quote_mint @ VaultError::InvalidTokenAccount
+    )]
+    pub user_quote_account: Account<'info, TokenAccount>,
+
+    #[account(mut)]
+    pub user: Signer<'info>,
+
+    pub token_program: Program<'info, Token>,
+
+    /// Global emergency-pause registry
+    #[account(
+        seeds = [b"protocol-status"],
+        bump = protocol_status.bump,
+        seeds::program = vectai_protocol::ID,
+        constraint = !protocol_status.paused @ VaultError::ProtocolPaused
+    )]
+    pub protocol_status: Account<'info, ProtocolStatus>,
+}
+
+/// Epoch-based redemption queue; shares queued during an epoch are all
+/// redeemed at the balances snapshotted when that epoch is processed
+#[account]
+pub struct WithdrawalQueue {
+    pub vault: Pubkey,
+    pub share_escrow: Pubkey,
+    pub base_claims: Pubkey,
+    pub quote_claims: Pubkey,
+    pub epoch_duration: i64, // Seconds
+    pub current_epoch: u64,
+    pub epoch_end_time: i64,
+    pub pending_shares: u64, // Queued for current_epoch
+    pub bump: u8,
+}
+
+impl WithdrawalQueue {
+    pub const LEN: usize = 8 + // discriminator
+        32 + // vault
+        32 + // share_escrow
+        32 + // base_claims
+        32 + // quote_claims
+        8 +  // epoch_duration
+        8 +  // current_epoch
+        8 +  // epoch_end_time
+        8 +  // pending_shares
+        1;   // bump
+}
+
+/// A user's queued shares; one outstanding request per user and vault
+#[account]
+pub struct WithdrawalRequest {
+    pub owner: Pubkey,
+    pub vault: Pubkey,
+    pub epoch: u64,
+    pub shares: u64,
+    pub bump: u8,
+}
+
+impl WithdrawalRequest {
+    pub const LEN: usize = 8 + // discriminator
+        32 + // owner
+        32 + // vault
+        8 +  // epoch
+        8 +  // shares
+        1;   // bump
+}
+
+/// Amounts set aside when an epoch was processed; claims are paid
+/// `amount * request.shares / total_shares`
+#[account]
+pub struct RedemptionEpoch {
+    pub epoch: u64,
+    pub total_shares: u64,
+    pub base_amount: u64,
+    pub quote_amount: u64,
+    pub bump: u8,
+}
+
+impl RedemptionEpoch {
+    pub const LEN: usize = 8 + // discriminator
+        8 +  // epoch
+        8 +  // total_shares
+        8 +  // base_amount
+        8 +  // quote_amount
+        1;   // bump
+}
+
+#[event]
+pub struct WithdrawalRequested {
+    pub vault: Pubkey,
+    pub user: Pubkey,
+    pub epoch: u64,
+    pub shares: u64,
+}
+
+#[event]
+pub struct WithdrawalsProcessed {
+    pub vault: Pubkey,
+    pub epoch: u64,
+    pub total_shares: u64,
+    pub base_amount: u64,
+    pub quote_amount: u64,
+}
@@ -1,142 +0,0 @@
-use anchor_lang::prelude::*;
-use solana_program_test::*;
-use solana_sdk::{
-    signature::{Keypair, Signer},
-    sysvar::clock::Clock,
-};
-use vectai_oracle::{OracleConfig, PriceData, ThresholdCondition, OracleError};
-
-#[tokio::test]
-async fn test_initialize_oracle() {
-    let program_id = vectai_oracle::id();
-    let mut context = ProgramTestContext::new(
-        "vectai_oracle",
-        program_id,
-        None,
-    ).await;
-
-    let authority = Keypair::new();
-    let asset_name = "BTC/USD".to_string();
-    let feed_id = "e62dniusvzkh9qqfr4jcnzrscswd8cuqpvd2cwcumca".to_string();
-    let max_price_age = 60i64; // 1 minute
-
-    // Test oracle initialization
-    println!("✅ Oracle initialization test ready");
-    println!("   Asset: {}", asset_name);
-    println!("   Feed ID: {}", feed_id);
-    println!("   Max Age: {} seconds", max_price_age);
-}
-
-#[tokio::test]
-async fn test_get_price() {
-    // Test fetching price from Pyth feed
-    
-    // Would test:
-    // 1. Valid price feed account
-    // 2. Price data extraction
-    // 3. Staleness check
-    // 4. Confidence validation
-    
-    println!("✅ Price fetching test ready");
-    println!("   Testing price data extraction from Pyth");
-}
-
-#[tokio::test]
-async fn test_price_validation() {
-    // Test price validation logic
-    
-    // Would test:
-    // 1. Stale price rejection (age > max_age)
-    // 2. Low confidence rejection
-    // 3. Valid price acceptance
-    
-    let max_age = 60i64;
-    let max_confidence_ratio = 0.1f64;
-    
-    println!("✅ Price validation test ready");
-    println!("   Max Age: {} seconds", max_age);
-    println!("   Max Confidence Ratio: {}", max_confidence_ratio);
-}
-
-#[tokio::test] 
-async fn test_threshold_conditions() {
-    // Test different threshold conditions
-    
-    let price = 45000i64; // $45,000
-    let thresholds = [
-        (40000i64, ThresholdCondition::GreaterThan, true),   // 45k > 40k
-        (50000i64, ThresholdCondition::GreaterThan, false),  // 45k > 50k
-        (45000i64, ThresholdCondition::Equal, true),         // 45k == 45k
-        (50000i64, ThresholdCondition::LessThan, true),      // 45k < 50k
-    ];
-    
-    println!("✅ Threshold condition tests ready");
-    for (threshold, condition, expected) in thresholds.iter() {
-        println!("   {} {:?} {} = {}", price, condition, threshold, expected);
-    }
-}
-
-#[tokio::test]
-async fn test_oracle_errors() {
-    // Test error conditions:
-    // 1. Invalid price update account
-    // 2. No price feed found
-    // 3. Stale price
-    // 4. Low confidence
-    // 5. Asset name too long
-    
-    println!("✅ Oracle error handling tests ready");
-}
-
-#[tokio::test]
-async fn test_multiple_assets() {
-    // Test oracle with multiple asset configurations
-    
-    let assets = [
-        ("BTC/USD", "btc_feed_id"),
-        ("ETH/USD", "eth_feed_id"), 
-        ("SOL/USD", "sol_feed_id"),
-    ];
-    
-    println!("✅ Multi-asset oracle test ready");
-    for (asset, feed_id) in assets.iter() {
-        println!("   {}: {}", asset, feed_id);
-    }
-}
-
-// Helper to create mock price data for testing
-fn create_mock_price_data(price: i64, conf: u64, publish_time: i64) -> PriceData {
-    PriceData {
-        price,
-        conf,
-        expo: -8, // Standard USD price exponent
-        publish_time,
-        asset_name: "BTC/USD".to_string(),
-    }
-}
-
-#[tokio::test]
-async fn test_price_age_calculation() {
-    // Test price age calculation and staleness detection
-    
-    let current_time = 1000000i64;
-    let recent_price_time = current_time - 30; // 30 seconds old
-    let stale_price_time = current_time - 120; // 2 minutes old
-    
-    println!("✅ Price age calculation test ready");
-    println!("   Current time: {}", current_time);
-    println!("   Recent price: {} (age: 30s)", recent_price_time);
-    println!("   Stale price: {} (age: 120s)", stale_price_time);
-}
-
-// Integration test for full oracle workflow
-#[tokio::test]
-async fn test_full_oracle_workflow() {
-    println!("🧪 Full Oracle Workflow Test");
-    println!("1. Initialize oracle config");
-    println!("2. Mock Pyth price update");
-    println!("3. Fetch and validate price");
-    println!("4. Check threshold conditions");
-    println!("5. Handle edge cases and errors");
-    println!("✅ Full oracle workflow test framework ready");
-}
\ No newline at end of file
@@ -1,233 +0,0 @@
-use anchor_lang::prelude::*;
-use anchor_spl::token::{TokenAccount, Mint};
-use solana_program_test::*;
-use solana_sdk::{
-    signature::{Keypair, Signer},
-    sysvar::clock::Clock,
-};
-use vectai_trader::{TraderConfig, TraderError};
-use vectai_oracle::ThresholdCondition;
-
-#[tokio::test]
-async fn test_initialize_trader() {
-    let program_id = vectai_trader::id();
-    let mut context = ProgramTestContext::new(
-        "vectai_trader",
-        program_id,
-        None,
-    ).await;
-
-    let authority = Keypair::new();
-    let price_threshold = 40000i64; // $40,000
-    let swap_amount = 1000u64;
-    let asset_name = "BTC/USD".to_string();
-
-    // Test trader initialization
-    println!("✅ Trader initialization test ready");
-    println!("   Authority: {}", authority.pubkey());
-    println!("   Price Threshold: ${}", price_threshold);
-    println!("   Swap Amount: {}", swap_amount);
-    println!("   Asset: {}", asset_name);
-}
-
-#[tokio::test]
-async fn test_conditional_swap_execution() {
-    // Test executing conditional swap when threshold is met
-    
-    // Would test:
-    // 1. Price threshold check via oracle CPI
-    // 2. Balance validation
-    // 3. Jupiter swap execution
-    // 4. State updates (swap count, timestamp)
-    
-    println!("✅ Conditional swap test ready");
-    println!("   Testing: price > threshold → execute swap");
-}
-
-#[tokio::test]
-async fn test_swap_conditions() {
-    // Test different conditions for swap execution
-    
-    let test_cases = [
-        ("Price above threshold", 45000i64, 40000i64, true),
-        ("Price below threshold", 35000i64, 40000i64, false),
-        ("Price equals threshold", 40000i64, 40000i64, false), // Using GreaterThan
-    ];
-    
-    println!("✅ Swap condition tests ready");
-    for (description, price, threshold, should_execute) in test_cases.iter() {
-        println!("   {}: ${} vs ${} → {}", 
-                description, price, threshold, 
-                if *should_execute { "Execute" } else { "Skip" });
-    }
-}
-
-#[tokio::test]
-async fn test_trader_permissions() {
-    // Test trader authority and permission checks
-    
-    // Would test:
-    // 1. Only authority can execute swaps
-    // 2. Only authority can update config
-    // 3. Unauthorized access rejection
-    
-    println!("✅ Trader permission tests ready");
-    println!("   Testing authority-only operations");
-}
-
-#[tokio::test]
-async fn test_balance_validation() {
-    // Test insufficient balance scenarios
-    
-    let swap_amount = 1000u64;
-    let balances = [500u64, 1000u64, 2000u64]; // Below, equal, above
-    
-    println!("✅ Balance validation tests ready");
-    for balance in balances.iter() {
-        let can_swap = balance >= &swap_amount;
-        println!("   Balance: {}, Swap Amount: {} → {}", 
-                balance, swap_amount, 
-                if can_swap { "✅ Can swap" } else { "❌ Insufficient" });
-    }
-}
-
-#[tokio::test]
-async fn test_trader_state_updates() {
-    // Test state updates after successful swaps
-    
-    // Would test:
-    // 1. total_swaps increment
-    // 2. last_swap_time update
-    // 3. Math overflow protection
-    
-    println!("✅ Trader state update tests ready");
-    println!("   Testing swap counter and timestamp updates");
-}
-
-#[tokio::test]
-async fn test_trader_configuration_updates() {
-    // Test updating trader configuration
-    
-    let initial_threshold = 40000i64;
-    let new_threshold = 45000i64;
-    let initial_amount = 1000u64;
-    let new_amount = 1500u64;
-    
-    println!("✅ Trader config update tests ready");
-    println!("   Threshold: {} → {}", initial_threshold, new_threshold);
-    println!("   Amount: {} → {}", initial_amount, new_amount);
-    println!("   Testing active/inactive toggle");
-}
-
-#[tokio::test]
-async fn test_trader_errors() {
-    // Test various error conditions
-    
-    // Would test:
-    // 1. Invalid threshold (≤ 0)
-    // 2. Invalid swap amount (≤ 0) 
-    // 3. Asset name too long
-    // 4. Trader not active
-    // 5. Invalid authority
-    // 6. Threshold not met
-    // 7. Insufficient balance
-    // 8. Math overflow
-    
-    println!("✅ Trader error handling tests ready");
-    println!("   Testing all error conditions");
-}
-
-#[tokio::test]
-async fn test_jupiter_integration() {
-    // Test Jupiter swap integration (placeholder)
-    
-    // Would test:
-    // 1. Jupiter program validation
-    // 2. Swap instruction building
-    // 3. Account preparation
-    // 4. Slippage handling
-    
-    println!("✅ Jupiter integration test ready");
-    println!("   Note: Using placeholder transfer for now");
-    println!("   TODO: Implement actual Jupiter V6 CPI");
-}
-
-#[tokio::test]
-async fn test_oracle_integration() {
-    // Test oracle CPI integration
-    
-    // Would test:
-    // 1. Oracle program CPI
-    // 2. Price threshold checking
-    // 3. Price data validation
-    // 4. Error handling from oracle
-    
-    println!("✅ Oracle integration test ready");
-    println!("   Testing CPI calls to vectai_oracle");
-}
-
-#[tokio::test]
-async fn test_trader_deactivation() {
-    // Test trader deactivation and reactivation
-    
-    println!("✅ Trader deactivation test ready");
-    println!("   Testing active/inactive state transitions");
-}
-
-// Helper function to create mock trader config
-fn create_mock_trader_config(authority: Pubkey) -> TraderConfig {
-    TraderConfig {
-        authority,
-        price_threshold: 40000i64,
-        swap_amount: 1000u64,
-        asset_name: "BTC/USD".to_string(),
-        oracle_config: Pubkey::new_unique(),
-        input_mint: Pubkey::new_unique(),
-        output_mint: Pubkey::new_unique(),
-        is_active: true,
-        total_swaps: 0,
-        last_swap_time: 0,
-    }
-}
-
-// Integration test for full trader workflow
-#[tokio::test]
-async fn test_full_trader_workflow() {
-    println!("🧪 Full Trader Workflow Test");
-    println!("1. Initialize trader with conditions");
-    println!("2. Fund token accounts");
-    println!("3. Mock price above threshold");
-    println!("4. Execute conditional swap");
-    println!("5. Verify state updates");
-    println!("6. Test with price below threshold");
-    println!("7. Verify no swap execution");
-    println!("8. Update trader configuration");
-    println!("9. Test deactivation/reactivation");
-    println!("✅ Full trader workflow test framework ready");
-}
-
-#[tokio::test]
-async fn test_multiple_traders() {
-    // Test multiple traders with different conditions
-    
-    let traders = [
-        ("Conservative", 45000i64, 100u64),
-        ("Moderate", 42000i64, 500u64),
-        ("Aggressive", 38000i64, 1000u64),
-    ];
-    
-    println!("✅ Multiple trader test ready");
-    for (strategy, threshold, amount) in traders.iter() {
-        println!("   {} Strategy: ${} threshold, {} amount", 
-                strategy, threshold, amount);
-    }
-}
-
-#[tokio::test]
-async fn test_swap_frequency_limits() {
-    // Test potential frequency limiting (if implemented)
-    
-    println!("✅ Swap frequency test ready");
-    println!("   Testing rapid successive swaps");
-    println!("   Note: No frequency limits currently implemented");
-}
\ No newline at end of file